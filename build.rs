@@ -0,0 +1,12 @@
+//! Compiles the gRPC protocol definitions when the `grpc` feature is enabled.
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("cargo:rerun-if-changed=build.rs");
+
+    #[cfg(feature = "grpc")]
+    tonic_build::configure()
+        .build_client(false)
+        .compile_protos(&["proto/luna/v1/record.proto"], &["proto"])?;
+
+    Ok(())
+}
@@ -19,6 +19,41 @@ mod m20260428_000001_create_crawl_tables;
 mod m20260428_000002_collapse_duplicate_links;
 mod m20260509_000001_add_record_date_index;
 mod m20260714_000001_create_crawl_entity_progress;
+mod m20261015_000001_unique_named_entities;
+mod m20261015_000002_add_record_deleted_at;
+mod m20261015_000003_create_audit_log;
+mod m20261015_000004_create_roles;
+mod m20261015_000005_create_api_keys;
+mod m20261015_000006_add_refresh_tokens;
+mod m20261015_000007_add_login_lockout;
+mod m20261015_000008_create_password_reset_tokens;
+mod m20261015_000009_add_totp;
+mod m20261015_000010_add_device_sessions;
+mod m20261015_000011_add_record_version;
+mod m20261015_000012_add_search_vectors;
+mod m20261015_000013_create_record_sync_journal;
+mod m20261015_000014_create_user_record_favorites;
+mod m20261015_000015_create_record_rating;
+mod m20261015_000016_create_record_comments;
+mod m20261015_000017_prepare_tag_tables;
+mod m20261015_000018_create_saved_searches;
+mod m20261015_000019_enable_title_trigrams;
+mod m20261015_000020_add_record_trailer;
+mod m20261015_000021_add_record_cover_index;
+mod m20261015_000022_create_media_files;
+mod m20261015_000023_create_jobs;
+mod m20261015_000024_add_link_health;
+mod m20261015_000025_add_link_priority;
+mod m20261015_000026_add_link_create_time;
+mod m20261015_000027_create_record_revisions;
+mod m20261015_000028_add_idol_profile;
+mod m20261015_000029_add_studio_hierarchy;
+mod m20261015_000030_add_record_order_in_series;
+mod m20261015_000031_create_genre_category;
+mod m20261015_000032_add_name_autocomplete_indexes;
+mod m20261015_000033_create_entity_translation;
+mod m20261015_000034_add_token_generation;
+mod m20261015_000035_stamp_record_sync_seq_at_commit;
 
 pub struct Migrator;
 
@@ -45,6 +80,41 @@ impl MigratorTrait for Migrator {
             Box::new(m20260428_000002_collapse_duplicate_links::Migration),
             Box::new(m20260509_000001_add_record_date_index::Migration),
             Box::new(m20260714_000001_create_crawl_entity_progress::Migration),
+            Box::new(m20261015_000001_unique_named_entities::Migration),
+            Box::new(m20261015_000002_add_record_deleted_at::Migration),
+            Box::new(m20261015_000003_create_audit_log::Migration),
+            Box::new(m20261015_000004_create_roles::Migration),
+            Box::new(m20261015_000005_create_api_keys::Migration),
+            Box::new(m20261015_000006_add_refresh_tokens::Migration),
+            Box::new(m20261015_000007_add_login_lockout::Migration),
+            Box::new(m20261015_000008_create_password_reset_tokens::Migration),
+            Box::new(m20261015_000009_add_totp::Migration),
+            Box::new(m20261015_000010_add_device_sessions::Migration),
+            Box::new(m20261015_000011_add_record_version::Migration),
+            Box::new(m20261015_000012_add_search_vectors::Migration),
+            Box::new(m20261015_000013_create_record_sync_journal::Migration),
+            Box::new(m20261015_000014_create_user_record_favorites::Migration),
+            Box::new(m20261015_000015_create_record_rating::Migration),
+            Box::new(m20261015_000016_create_record_comments::Migration),
+            Box::new(m20261015_000017_prepare_tag_tables::Migration),
+            Box::new(m20261015_000018_create_saved_searches::Migration),
+            Box::new(m20261015_000019_enable_title_trigrams::Migration),
+            Box::new(m20261015_000020_add_record_trailer::Migration),
+            Box::new(m20261015_000021_add_record_cover_index::Migration),
+            Box::new(m20261015_000022_create_media_files::Migration),
+            Box::new(m20261015_000023_create_jobs::Migration),
+            Box::new(m20261015_000024_add_link_health::Migration),
+            Box::new(m20261015_000025_add_link_priority::Migration),
+            Box::new(m20261015_000026_add_link_create_time::Migration),
+            Box::new(m20261015_000027_create_record_revisions::Migration),
+            Box::new(m20261015_000028_add_idol_profile::Migration),
+            Box::new(m20261015_000029_add_studio_hierarchy::Migration),
+            Box::new(m20261015_000030_add_record_order_in_series::Migration),
+            Box::new(m20261015_000031_create_genre_category::Migration),
+            Box::new(m20261015_000032_add_name_autocomplete_indexes::Migration),
+            Box::new(m20261015_000033_create_entity_translation::Migration),
+            Box::new(m20261015_000034_add_token_generation::Migration),
+            Box::new(m20261015_000035_stamp_record_sync_seq_at_commit::Migration),
         ]
     }
 }
@@ -0,0 +1,121 @@
+//! Migration: collapse duplicate named entities and add unique indexes on
+//! `(name, link, manual)` for director, studio, label, series, genre and idol.
+//!
+//! The lowest id of each duplicate group survives. References from `record`
+//! and the junction tables are re-pointed to it before the duplicates are
+//! deleted, so the unique index backs the repositories' find-or-create.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// Entity tables referenced by a `record` foreign-key column.
+const RECORD_FK_TABLES: &[(&str, &str)] = &[
+    ("director", "director_id"),
+    ("studio", "studio_id"),
+    ("label", "label_id"),
+    ("series", "series_id"),
+];
+
+/// Entity tables referenced through a junction table: `(entity, junction, fk)`.
+const JUNCTION_TABLES: &[(&str, &str, &str)] = &[
+    ("genre", "record_genre", "genre_id"),
+    ("idol", "idol_participation", "idol_id"),
+];
+
+/// CTE mapping every row of `table` to the surviving id of its duplicate group.
+fn ranked_cte(table: &str) -> String {
+    format!(
+        "WITH ranked AS (
+            SELECT id, MIN(id) OVER (PARTITION BY name, link, manual) AS keep_id
+            FROM {table}
+        )"
+    )
+}
+
+fn index_name(table: &str) -> String {
+    format!("idx_{table}_name_link_manual_unique")
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let conn = manager.get_connection();
+
+        for (table, fk) in RECORD_FK_TABLES {
+            let ranked = ranked_cte(table);
+            conn.execute_unprepared(&format!(
+                "{ranked}
+                UPDATE record r SET {fk} = ranked.keep_id
+                FROM ranked
+                WHERE r.{fk} = ranked.id AND ranked.id <> ranked.keep_id"
+            ))
+            .await?;
+            conn.execute_unprepared(&format!(
+                "{ranked}
+                DELETE FROM {table} t USING ranked
+                WHERE t.id = ranked.id AND ranked.id <> ranked.keep_id"
+            ))
+            .await?;
+        }
+
+        for (table, junction, fk) in JUNCTION_TABLES {
+            let ranked = ranked_cte(table);
+            // Copy junction rows onto the survivor first; the junction's own
+            // unique index absorbs rows the record already has.
+            conn.execute_unprepared(&format!(
+                "{ranked}
+                INSERT INTO {junction} (record_id, {fk}, manual)
+                SELECT j.record_id, ranked.keep_id, bool_or(j.manual)
+                FROM {junction} j
+                JOIN ranked ON j.{fk} = ranked.id
+                WHERE ranked.id <> ranked.keep_id
+                GROUP BY j.record_id, ranked.keep_id
+                ON CONFLICT (record_id, {fk}) DO NOTHING"
+            ))
+            .await?;
+            conn.execute_unprepared(&format!(
+                "{ranked}
+                DELETE FROM {junction} j USING ranked
+                WHERE j.{fk} = ranked.id AND ranked.id <> ranked.keep_id"
+            ))
+            .await?;
+            conn.execute_unprepared(&format!(
+                "{ranked}
+                DELETE FROM {table} t USING ranked
+                WHERE t.id = ranked.id AND ranked.id <> ranked.keep_id"
+            ))
+            .await?;
+        }
+
+        for table in RECORD_FK_TABLES
+            .iter()
+            .map(|(table, _)| table)
+            .chain(JUNCTION_TABLES.iter().map(|(table, _, _)| table))
+        {
+            conn.execute_unprepared(&format!(
+                "CREATE UNIQUE INDEX IF NOT EXISTS {} ON {table} (name, link, manual)",
+                index_name(table)
+            ))
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let conn = manager.get_connection();
+
+        for table in RECORD_FK_TABLES
+            .iter()
+            .map(|(table, _)| table)
+            .chain(JUNCTION_TABLES.iter().map(|(table, _, _)| table))
+        {
+            conn.execute_unprepared(&format!("DROP INDEX IF EXISTS {}", index_name(table)))
+                .await?;
+        }
+
+        Ok(())
+    }
+}
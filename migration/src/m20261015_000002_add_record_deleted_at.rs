@@ -0,0 +1,55 @@
+//! Migration: add nullable `record.deleted_at` for soft deletes.
+//!
+//! A non-null value moves the record to the trash. The partial index keeps
+//! the trash listing cheap without touching the live-record indexes.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Record::Table)
+                    .add_column(
+                        ColumnDef::new(Record::DeletedAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        let conn = manager.get_connection();
+        conn.execute_unprepared(
+            "CREATE INDEX IF NOT EXISTS idx_record_deleted_at ON record (deleted_at DESC) WHERE deleted_at IS NOT NULL",
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let conn = manager.get_connection();
+        conn.execute_unprepared("DROP INDEX IF EXISTS idx_record_deleted_at")
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Record::Table)
+                    .drop_column(Record::DeletedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Record {
+    Table,
+    DeletedAt,
+}
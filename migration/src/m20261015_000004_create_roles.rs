@@ -0,0 +1,120 @@
+//! Migration: create the `roles` table and assign every user a role.
+//!
+//! Roles are ordered `viewer` < `editor` < `admin`. New users default to
+//! `viewer`; users that already exist keep write access as `editor`, and the
+//! seeded `admin` and `apitest01` accounts become `admin`.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// Seeded roles: `(name, description)`.
+const ROLES: &[(&str, &str)] = &[
+    ("viewer", "Read-only access to cards"),
+    ("editor", "Create, update and delete cards and upload media"),
+    ("admin", "Full access, including user management"),
+];
+
+/// Seeded accounts that are granted the `admin` role.
+const ADMIN_USER_IDS: &[&str] = &[
+    "00000000-0000-0000-0000-000000000000",
+    "00000000-0000-0000-0000-000000000021",
+];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Roles::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Roles::Name)
+                            .string_len(16)
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Roles::Description).string().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        let mut insert = Query::insert()
+            .into_table(Roles::Table)
+            .columns([Roles::Name, Roles::Description])
+            .on_conflict(OnConflict::column(Roles::Name).do_nothing().to_owned())
+            .to_owned();
+        for (name, description) in ROLES {
+            insert.values_panic([(*name).into(), (*description).into()]);
+        }
+        manager.exec_stmt(insert).await?;
+
+        // Existing users keep the write access they had before roles existed.
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .add_column_if_not_exists(
+                        ColumnDef::new(Users::Role)
+                            .string_len(16)
+                            .not_null()
+                            .default("editor"),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .get_connection()
+            .execute_unprepared("ALTER TABLE users ALTER COLUMN role SET DEFAULT 'viewer'")
+            .await?;
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("fk_users_role")
+                    .from(Users::Table, Users::Role)
+                    .to(Roles::Table, Roles::Name)
+                    .on_update(ForeignKeyAction::Cascade)
+                    .on_delete(ForeignKeyAction::Restrict)
+                    .to_owned(),
+            )
+            .await?;
+
+        let promote = Query::update()
+            .table(Users::Table)
+            .value(Users::Role, "admin")
+            .and_where(Expr::col(Users::Id).is_in(ADMIN_USER_IDS.iter().copied()))
+            .to_owned();
+        manager.exec_stmt(promote).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Dropping the column also drops `fk_users_role`.
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .drop_column(Users::Role)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Roles::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Roles {
+    Table,
+    Name,
+    Description,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+    Role,
+}
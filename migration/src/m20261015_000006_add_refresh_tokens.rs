@@ -0,0 +1,87 @@
+//! Migration: store hashed refresh tokens.
+//!
+//! An account-level session keeps its refresh token on `user_auth`; a session
+//! bound to a registered device keeps it on that `devices` row, so revoking a
+//! device (or all of a user's devices) revokes its refresh tokens.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(UserAuth::Table)
+                    .add_column_if_not_exists(
+                        ColumnDef::new(UserAuth::RefreshTokenHash)
+                            .string_len(255)
+                            .null(),
+                    )
+                    .add_column_if_not_exists(
+                        ColumnDef::new(UserAuth::RefreshTokenExpiresAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Devices::Table)
+                    .add_column_if_not_exists(
+                        ColumnDef::new(Devices::RefreshTokenHash)
+                            .string_len(255)
+                            .null(),
+                    )
+                    .add_column_if_not_exists(
+                        ColumnDef::new(Devices::RefreshTokenExpiresAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Devices::Table)
+                    .drop_column(Devices::RefreshTokenHash)
+                    .drop_column(Devices::RefreshTokenExpiresAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(UserAuth::Table)
+                    .drop_column(UserAuth::RefreshTokenHash)
+                    .drop_column(UserAuth::RefreshTokenExpiresAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum UserAuth {
+    Table,
+    RefreshTokenHash,
+    RefreshTokenExpiresAt,
+}
+
+#[derive(DeriveIden)]
+enum Devices {
+    Table,
+    RefreshTokenHash,
+    RefreshTokenExpiresAt,
+}
@@ -0,0 +1,49 @@
+//! Migration: track failed logins on `user_auth` for temporary account lockout.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(UserAuth::Table)
+                    .add_column_if_not_exists(
+                        ColumnDef::new(UserAuth::FailedLoginAttempts)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .add_column_if_not_exists(
+                        ColumnDef::new(UserAuth::LockedUntil)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(UserAuth::Table)
+                    .drop_column(UserAuth::FailedLoginAttempts)
+                    .drop_column(UserAuth::LockedUntil)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum UserAuth {
+    Table,
+    FailedLoginAttempts,
+    LockedUntil,
+}
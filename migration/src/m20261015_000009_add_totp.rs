@@ -0,0 +1,55 @@
+//! Migration: add optional TOTP two-factor authentication to `user_auth`.
+//!
+//! The secret is stored encrypted. It is written on enrollment and only
+//! enforced at login once `totp_enabled` is set by a verified code.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(UserAuth::Table)
+                    .add_column_if_not_exists(ColumnDef::new(UserAuth::TotpSecret).binary().null())
+                    .add_column_if_not_exists(
+                        ColumnDef::new(UserAuth::TotpEnabled)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .add_column_if_not_exists(
+                        ColumnDef::new(UserAuth::TotpLastUsedStep)
+                            .big_integer()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(UserAuth::Table)
+                    .drop_column(UserAuth::TotpSecret)
+                    .drop_column(UserAuth::TotpEnabled)
+                    .drop_column(UserAuth::TotpLastUsedStep)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum UserAuth {
+    Table,
+    TotpSecret,
+    TotpEnabled,
+    TotpLastUsedStep,
+}
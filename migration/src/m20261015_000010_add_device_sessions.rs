@@ -0,0 +1,54 @@
+//! Migration: track session activity on `devices`.
+//!
+//! A device doubles as a login session once tokens are issued for it; these
+//! columns record when and from where it was last used.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Devices::Table)
+                    .add_column_if_not_exists(
+                        ColumnDef::new(Devices::LastSeenAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .add_column_if_not_exists(
+                        ColumnDef::new(Devices::UserAgent).string_len(512).null(),
+                    )
+                    .add_column_if_not_exists(
+                        ColumnDef::new(Devices::IpAddress).string_len(64).null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Devices::Table)
+                    .drop_column(Devices::LastSeenAt)
+                    .drop_column(Devices::UserAgent)
+                    .drop_column(Devices::IpAddress)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Devices {
+    Table,
+    LastSeenAt,
+    UserAgent,
+    IpAddress,
+}
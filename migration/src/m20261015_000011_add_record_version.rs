@@ -0,0 +1,46 @@
+//! Migration: add `record.version` for optimistic concurrency.
+//!
+//! Every edit through the API bumps the version; clients send the version
+//! they last saw in `If-Match` so a stale edit is rejected instead of
+//! silently overwriting a newer one.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Record::Table)
+                    .add_column(
+                        ColumnDef::new(Record::Version)
+                            .integer()
+                            .not_null()
+                            .default(1),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Record::Table)
+                    .drop_column(Record::Version)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Record {
+    Table,
+    Version,
+}
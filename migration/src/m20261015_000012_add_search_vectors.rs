@@ -0,0 +1,75 @@
+//! Migration: add `search_vector` full-text columns for the SQL search fallback.
+//!
+//! `record`, `idol`, `director` and `studio` get a `tsvector` column kept up to
+//! date by a `BEFORE INSERT OR UPDATE` trigger, plus a GIN index. The `simple`
+//! configuration is used since names and titles are not in a single language.
+
+use sea_orm_migration::prelude::*;
+
+/// `(table, text expression the vector is built from)`.
+const SEARCH_VECTOR_SOURCES: [(&str, &str); 4] = [
+    ("record", "coalesce(NEW.title, '') || ' ' || NEW.id"),
+    ("idol", "coalesce(NEW.name, '')"),
+    ("director", "coalesce(NEW.name, '')"),
+    ("studio", "coalesce(NEW.name, '')"),
+];
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let conn = manager.get_connection();
+        for (table, source) in SEARCH_VECTOR_SOURCES {
+            conn.execute_unprepared(&format!(
+                "ALTER TABLE {table} ADD COLUMN IF NOT EXISTS search_vector tsvector"
+            ))
+            .await?;
+            conn.execute_unprepared(&format!(
+                "CREATE OR REPLACE FUNCTION {table}_search_vector_update() RETURNS trigger AS $$
+                 BEGIN
+                     NEW.search_vector := to_tsvector('simple', {source});
+                     RETURN NEW;
+                 END
+                 $$ LANGUAGE plpgsql"
+            ))
+            .await?;
+            conn.execute_unprepared(&format!(
+                "CREATE TRIGGER {table}_search_vector_trigger
+                 BEFORE INSERT OR UPDATE ON {table}
+                 FOR EACH ROW EXECUTE FUNCTION {table}_search_vector_update()"
+            ))
+            .await?;
+            // Backfill existing rows through the trigger
+            conn.execute_unprepared(&format!("UPDATE {table} SET search_vector = NULL"))
+                .await?;
+            conn.execute_unprepared(&format!(
+                "CREATE INDEX IF NOT EXISTS idx_{table}_search_vector ON {table} USING GIN (search_vector)"
+            ))
+            .await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let conn = manager.get_connection();
+        for (table, _) in SEARCH_VECTOR_SOURCES {
+            conn.execute_unprepared(&format!(
+                "DROP TRIGGER IF EXISTS {table}_search_vector_trigger ON {table}"
+            ))
+            .await?;
+            conn.execute_unprepared(&format!(
+                "DROP FUNCTION IF EXISTS {table}_search_vector_update()"
+            ))
+            .await?;
+            conn.execute_unprepared(&format!("DROP INDEX IF EXISTS idx_{table}_search_vector"))
+                .await?;
+            conn.execute_unprepared(&format!(
+                "ALTER TABLE {table} DROP COLUMN IF EXISTS search_vector"
+            ))
+            .await?;
+        }
+        Ok(())
+    }
+}
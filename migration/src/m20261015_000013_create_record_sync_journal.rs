@@ -0,0 +1,130 @@
+//! Migration: change sequence and deletion journal for incremental record sync.
+//!
+//! Every insert or update of a `record` row stamps it with the next value of
+//! `record_sync_seq`, and every hard delete appends a `record_deletion` row
+//! stamped from the same sequence. Sync clients remember the highest value
+//! they have seen and ask only for what changed after it.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let conn = manager.get_connection();
+        conn.execute_unprepared("CREATE SEQUENCE IF NOT EXISTS record_sync_seq")
+            .await?;
+        // The column default numbers existing rows as well as new ones
+        conn.execute_unprepared(
+            "ALTER TABLE record ADD COLUMN IF NOT EXISTS sync_seq BIGINT NOT NULL \
+             DEFAULT nextval('record_sync_seq')",
+        )
+        .await?;
+        conn.execute_unprepared(
+            "CREATE OR REPLACE FUNCTION record_sync_seq_update() RETURNS trigger AS $$
+             BEGIN
+                 NEW.sync_seq := nextval('record_sync_seq');
+                 RETURN NEW;
+             END
+             $$ LANGUAGE plpgsql",
+        )
+        .await?;
+        conn.execute_unprepared(
+            "CREATE TRIGGER record_sync_seq_trigger
+             BEFORE UPDATE ON record
+             FOR EACH ROW EXECUTE FUNCTION record_sync_seq_update()",
+        )
+        .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_record_sync_seq")
+                    .table(Record::Table)
+                    .col(Record::SyncSeq)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(RecordDeletion::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(RecordDeletion::Seq)
+                            .big_integer()
+                            .not_null()
+                            .primary_key()
+                            .default(Expr::cust("nextval('record_sync_seq')")),
+                    )
+                    .col(ColumnDef::new(RecordDeletion::RecordId).string().not_null())
+                    .col(
+                        ColumnDef::new(RecordDeletion::DeletedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        conn.execute_unprepared(
+            "CREATE OR REPLACE FUNCTION record_deletion_journal() RETURNS trigger AS $$
+             BEGIN
+                 INSERT INTO record_deletion (record_id) VALUES (OLD.id);
+                 RETURN OLD;
+             END
+             $$ LANGUAGE plpgsql",
+        )
+        .await?;
+        conn.execute_unprepared(
+            "CREATE TRIGGER record_deletion_trigger
+             AFTER DELETE ON record
+             FOR EACH ROW EXECUTE FUNCTION record_deletion_journal()",
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let conn = manager.get_connection();
+        conn.execute_unprepared("DROP TRIGGER IF EXISTS record_deletion_trigger ON record")
+            .await?;
+        conn.execute_unprepared("DROP FUNCTION IF EXISTS record_deletion_journal()")
+            .await?;
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(RecordDeletion::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await?;
+        conn.execute_unprepared("DROP TRIGGER IF EXISTS record_sync_seq_trigger ON record")
+            .await?;
+        conn.execute_unprepared("DROP FUNCTION IF EXISTS record_sync_seq_update()")
+            .await?;
+        conn.execute_unprepared("DROP INDEX IF EXISTS idx_record_sync_seq")
+            .await?;
+        conn.execute_unprepared("ALTER TABLE record DROP COLUMN IF EXISTS sync_seq")
+            .await?;
+        conn.execute_unprepared("DROP SEQUENCE IF EXISTS record_sync_seq")
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Record {
+    Table,
+    SyncSeq,
+}
+
+#[derive(DeriveIden)]
+enum RecordDeletion {
+    Table,
+    Seq,
+    RecordId,
+    DeletedAt,
+}
@@ -0,0 +1,86 @@
+//! Migration: create user_record_favorites table.
+//!
+//! Each row puts one record on one user's favorites list (watchlist).
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(UserRecordFavorites::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(UserRecordFavorites::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(UserRecordFavorites::UserId)
+                            .string_len(255)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(UserRecordFavorites::RecordId)
+                            .string_len(255)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(UserRecordFavorites::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_user_record_favorites_user_id")
+                            .from(UserRecordFavorites::Table, UserRecordFavorites::UserId)
+                            .to(Alias::new("users"), Alias::new("id"))
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_user_record_favorites_record_id")
+                            .from(UserRecordFavorites::Table, UserRecordFavorites::RecordId)
+                            .to(Alias::new("record"), Alias::new("id"))
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_user_record_favorites_unique")
+                    .table(UserRecordFavorites::Table)
+                    .col(UserRecordFavorites::UserId)
+                    .col(UserRecordFavorites::RecordId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(UserRecordFavorites::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum UserRecordFavorites {
+    Table,
+    Id,
+    UserId,
+    RecordId,
+    CreatedAt,
+}
@@ -0,0 +1,112 @@
+//! Migration: create record_rating table.
+//!
+//! Each user may give each record one score from 1 to 10; the record's
+//! average and count are aggregated from these rows.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(RecordRating::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(RecordRating::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(RecordRating::UserId)
+                            .string_len(255)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(RecordRating::RecordId)
+                            .string_len(255)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(RecordRating::Score)
+                            .integer()
+                            .not_null()
+                            .check(Expr::col(RecordRating::Score).between(1, 10)),
+                    )
+                    .col(
+                        ColumnDef::new(RecordRating::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(RecordRating::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_record_rating_user_id")
+                            .from(RecordRating::Table, RecordRating::UserId)
+                            .to(Alias::new("users"), Alias::new("id"))
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_record_rating_record_id")
+                            .from(RecordRating::Table, RecordRating::RecordId)
+                            .to(Alias::new("record"), Alias::new("id"))
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_record_rating_unique")
+                    .table(RecordRating::Table)
+                    .col(RecordRating::UserId)
+                    .col(RecordRating::RecordId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        // Aggregates are computed per record
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_record_rating_record_id")
+                    .table(RecordRating::Table)
+                    .col(RecordRating::RecordId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(RecordRating::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum RecordRating {
+    Table,
+    Id,
+    UserId,
+    RecordId,
+    Score,
+    CreatedAt,
+    UpdatedAt,
+}
@@ -0,0 +1,94 @@
+//! Migration: create record_comments table.
+//!
+//! Free-text notes users attach to records, listed oldest first per record.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(RecordComments::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(RecordComments::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(RecordComments::RecordId)
+                            .string_len(255)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(RecordComments::UserId)
+                            .string_len(255)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(RecordComments::Body).text().not_null())
+                    .col(
+                        ColumnDef::new(RecordComments::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(RecordComments::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_record_comments_record_id")
+                            .from(RecordComments::Table, RecordComments::RecordId)
+                            .to(Alias::new("record"), Alias::new("id"))
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_record_comments_user_id")
+                            .from(RecordComments::Table, RecordComments::UserId)
+                            .to(Alias::new("users"), Alias::new("id"))
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_record_comments_record_created")
+                    .table(RecordComments::Table)
+                    .col(RecordComments::RecordId)
+                    .col(RecordComments::CreatedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(RecordComments::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum RecordComments {
+    Table,
+    Id,
+    RecordId,
+    UserId,
+    Body,
+    CreatedAt,
+    UpdatedAt,
+}
@@ -0,0 +1,79 @@
+//! Migration: make the tag tables writable through the API.
+//!
+//! The seed data inserts tags and tag categories with explicit IDs, leaving
+//! their sequences at 1; both are moved past the highest ID. Tag names become
+//! unique per category, and `record_tag` gets an index on `tag_id` for tag
+//! filters and usage counts.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let conn = manager.get_connection();
+        for table in ["tag_category", "tag"] {
+            conn.execute_unprepared(&format!(
+                "SELECT setval(pg_get_serial_sequence('{table}', 'id'), \
+                 COALESCE((SELECT MAX(id) FROM {table}), 0) + 1, false)"
+            ))
+            .await?;
+        }
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_tag_category_name_unique")
+                    .table(Tag::Table)
+                    .col(Tag::CategoryId)
+                    .col(Tag::Name)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_record_tag_tag_id")
+                    .table(RecordTag::Table)
+                    .col(RecordTag::TagId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_record_tag_tag_id")
+                    .table(RecordTag::Table)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_tag_category_name_unique")
+                    .table(Tag::Table)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Tag {
+    Table,
+    CategoryId,
+    Name,
+}
+
+#[derive(DeriveIden)]
+enum RecordTag {
+    Table,
+    TagId,
+}
@@ -0,0 +1,34 @@
+//! Migration: enable trigram matching on record titles.
+//!
+//! Installs the `pg_trgm` extension and a GIN trigram index on
+//! `record.title`, used by the duplicate report to compare titles with
+//! `similarity()`.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let conn = manager.get_connection();
+        conn.execute_unprepared("CREATE EXTENSION IF NOT EXISTS pg_trgm")
+            .await?;
+        conn.execute_unprepared(
+            "CREATE INDEX IF NOT EXISTS idx_record_title_trgm ON record USING GIN (title gin_trgm_ops)",
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // The extension stays installed; other objects may have come to
+        // depend on it.
+        manager
+            .get_connection()
+            .execute_unprepared("DROP INDEX IF EXISTS idx_record_title_trgm")
+            .await?;
+        Ok(())
+    }
+}
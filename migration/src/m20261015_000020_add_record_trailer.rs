@@ -0,0 +1,40 @@
+//! Migration: add `record.trailer`.
+//!
+//! Holds the URL of the record's trailer video, set when one is uploaded to
+//! `POST /cards/media/{id}/video`; `NULL` while the record has none.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Record::Table)
+                    .add_column(ColumnDef::new(Record::Trailer).string().null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Record::Table)
+                    .drop_column(Record::Trailer)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Record {
+    Table,
+    Trailer,
+}
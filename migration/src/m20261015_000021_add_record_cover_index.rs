@@ -0,0 +1,41 @@
+//! Migration: add `record.cover_index`.
+//!
+//! Holds the sequence number `n` of the image chosen as the record's cover,
+//! set through `PUT /cards/records/{id}/cover`; `NULL` keeps the main
+//! `{id}` image.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Record::Table)
+                    .add_column(ColumnDef::new(Record::CoverIndex).integer().null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Record::Table)
+                    .drop_column(Record::CoverIndex)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Record {
+    Table,
+    CoverIndex,
+}
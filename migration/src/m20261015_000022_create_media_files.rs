@@ -0,0 +1,80 @@
+//! Migration: create media_files table.
+//!
+//! One row per stored media file, keyed by its storage key, with the SHA-256
+//! of its content so uploads of an already stored image can be recognized.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(MediaFiles::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(MediaFiles::Key)
+                            .string_len(1024)
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(MediaFiles::OwnerType)
+                            .string_len(32)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(MediaFiles::OwnerId)
+                            .string_len(255)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(MediaFiles::Filename)
+                            .string_len(255)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(MediaFiles::Sha256).char_len(64).not_null())
+                    .col(ColumnDef::new(MediaFiles::Size).big_integer().not_null())
+                    .col(
+                        ColumnDef::new(MediaFiles::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_media_files_sha256")
+                    .table(MediaFiles::Table)
+                    .col(MediaFiles::Sha256)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(MediaFiles::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum MediaFiles {
+    Table,
+    Key,
+    OwnerType,
+    OwnerId,
+    Filename,
+    Sha256,
+    Size,
+    CreatedAt,
+}
@@ -0,0 +1,72 @@
+//! Migration: create jobs table.
+//!
+//! One row per run of a background job: which job, what started it, its
+//! status and when it started and finished, with a summary or error.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Jobs::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Jobs::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Jobs::Name).string_len(64).not_null())
+                    .col(ColumnDef::new(Jobs::Trigger).string_len(16).not_null())
+                    .col(ColumnDef::new(Jobs::Status).string_len(16).not_null())
+                    .col(
+                        ColumnDef::new(Jobs::StartedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(ColumnDef::new(Jobs::FinishedAt).timestamp_with_time_zone())
+                    .col(ColumnDef::new(Jobs::Summary).text())
+                    .col(ColumnDef::new(Jobs::Error).text())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_jobs_name_started_at")
+                    .table(Jobs::Table)
+                    .col(Jobs::Name)
+                    .col(Jobs::StartedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Jobs::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Jobs {
+    Table,
+    Id,
+    Name,
+    Trigger,
+    Status,
+    StartedAt,
+    FinishedAt,
+    Summary,
+    Error,
+}
@@ -0,0 +1,76 @@
+//! Migration: add link health columns to `links`.
+//!
+//! The link checker stores the HTTP status of the last check (`NULL` when
+//! the request itself failed), when it ran, and whether the link is dead.
+//! Links never checked have no `last_checked_at`, which the checker visits
+//! first.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Links::Table)
+                    .add_column(
+                        ColumnDef::new(Links::Dead)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .add_column(ColumnDef::new(Links::LastStatus).integer().null())
+                    .add_column(
+                        ColumnDef::new(Links::LastCheckedAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_links_last_checked_at")
+                    .table(Links::Table)
+                    .col(Links::LastCheckedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_links_last_checked_at")
+                    .table(Links::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Links::Table)
+                    .drop_column(Links::Dead)
+                    .drop_column(Links::LastStatus)
+                    .drop_column(Links::LastCheckedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Links {
+    Table,
+    Dead,
+    LastStatus,
+    LastCheckedAt,
+}
@@ -0,0 +1,54 @@
+//! Migration: add `links.create_time`.
+//!
+//! Link statistics count links by the month they were added. New links are
+//! stamped with the current date; existing links take the creation date of
+//! their record, when most of them were added.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Links::Table)
+                    .add_column(
+                        ColumnDef::new(Links::CreateTime)
+                            .date()
+                            .not_null()
+                            .default(Expr::current_date()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "UPDATE links SET create_time = record.create_time \
+                 FROM record WHERE record.id = links.record_id",
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Links::Table)
+                    .drop_column(Links::CreateTime)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Links {
+    Table,
+    CreateTime,
+}
@@ -0,0 +1,93 @@
+//! Migration: create record_revisions table.
+//!
+//! Before each edit of a record its current state is stored as a JSON
+//! snapshot, with the user making the edit, so bad edits can be reviewed and
+//! undone. Revisions are listed newest first per record and go with it when
+//! it is purged.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(RecordRevisions::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(RecordRevisions::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(RecordRevisions::RecordId)
+                            .string_len(255)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(RecordRevisions::Version)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(RecordRevisions::Actor)
+                            .string_len(255)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(RecordRevisions::Snapshot)
+                            .json_binary()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(RecordRevisions::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_record_revisions_record_id")
+                            .from(RecordRevisions::Table, RecordRevisions::RecordId)
+                            .to(Alias::new("record"), Alias::new("id"))
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_record_revisions_record_id")
+                    .table(RecordRevisions::Table)
+                    .col(RecordRevisions::RecordId)
+                    .col(RecordRevisions::Id)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(RecordRevisions::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum RecordRevisions {
+    Table,
+    Id,
+    RecordId,
+    Version,
+    Actor,
+    Snapshot,
+    CreatedAt,
+}
@@ -0,0 +1,117 @@
+//! Migration: add idol profiles and create idol_alias table.
+//!
+//! Idols get an optional birthday, debut date and measurements (height,
+//! bust, waist and hips, in centimeters). Other names an idol is known by
+//! are kept in `idol_alias`, one row per name, so searches find the idol
+//! under any of them; aliases go with their idol.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Idol::Table)
+                    .add_column(ColumnDef::new(Idol::Birthday).date().null())
+                    .add_column(ColumnDef::new(Idol::DebutDate).date().null())
+                    .add_column(ColumnDef::new(Idol::HeightCm).integer().null())
+                    .add_column(ColumnDef::new(Idol::BustCm).integer().null())
+                    .add_column(ColumnDef::new(Idol::WaistCm).integer().null())
+                    .add_column(ColumnDef::new(Idol::HipsCm).integer().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(IdolAlias::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(IdolAlias::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(IdolAlias::IdolId).big_integer().not_null())
+                    .col(ColumnDef::new(IdolAlias::Alias).string_len(255).not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_idol_alias_idol_id")
+                            .from(IdolAlias::Table, IdolAlias::IdolId)
+                            .to(Idol::Table, Idol::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_idol_alias_idol_id_alias")
+                    .table(IdolAlias::Table)
+                    .col(IdolAlias::IdolId)
+                    .col(IdolAlias::Alias)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_idol_alias_alias")
+                    .table(IdolAlias::Table)
+                    .col(IdolAlias::Alias)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(IdolAlias::Table).to_owned())
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Idol::Table)
+                    .drop_column(Idol::Birthday)
+                    .drop_column(Idol::DebutDate)
+                    .drop_column(Idol::HeightCm)
+                    .drop_column(Idol::BustCm)
+                    .drop_column(Idol::WaistCm)
+                    .drop_column(Idol::HipsCm)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Idol {
+    Table,
+    Id,
+    Birthday,
+    DebutDate,
+    HeightCm,
+    BustCm,
+    WaistCm,
+    HipsCm,
+}
+
+#[derive(DeriveIden)]
+enum IdolAlias {
+    Table,
+    Id,
+    IdolId,
+    Alias,
+}
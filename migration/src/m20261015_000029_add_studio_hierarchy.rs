@@ -0,0 +1,104 @@
+//! Migration: add `studio.parent_id` and `label.studio_id`.
+//!
+//! Studios can belong to a parent studio (a studio group) and labels to the
+//! studio that publishes them. Both references are optional; deleting a
+//! studio leaves its child studios and labels at the top level.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Studio::Table)
+                    .add_column(ColumnDef::new(Studio::ParentId).big_integer().null())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("fk_studio_parent_id")
+                    .from(Studio::Table, Studio::ParentId)
+                    .to(Studio::Table, Studio::Id)
+                    .on_delete(ForeignKeyAction::SetNull)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_studio_parent_id")
+                    .table(Studio::Table)
+                    .col(Studio::ParentId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Label::Table)
+                    .add_column(ColumnDef::new(Label::StudioId).big_integer().null())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("fk_label_studio_id")
+                    .from(Label::Table, Label::StudioId)
+                    .to(Studio::Table, Studio::Id)
+                    .on_delete(ForeignKeyAction::SetNull)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_label_studio_id")
+                    .table(Label::Table)
+                    .col(Label::StudioId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Dropping the columns also drops their foreign keys and indexes.
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Label::Table)
+                    .drop_column(Label::StudioId)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Studio::Table)
+                    .drop_column(Studio::ParentId)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Studio {
+    Table,
+    Id,
+    ParentId,
+}
+
+#[derive(DeriveIden)]
+enum Label {
+    Table,
+    StudioId,
+}
@@ -0,0 +1,52 @@
+//! Migration: add `record.order_in_series`.
+//!
+//! The position of a record within its series. Records without one follow
+//! the numbered records of the series by date.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Record::Table)
+                    .add_column(ColumnDef::new(Record::OrderInSeries).integer().null())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_record_series_id_order_in_series")
+                    .table(Record::Table)
+                    .col(Record::SeriesId)
+                    .col(Record::OrderInSeries)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Dropping the column also drops its index.
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Record::Table)
+                    .drop_column(Record::OrderInSeries)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Record {
+    Table,
+    SeriesId,
+    OrderInSeries,
+}
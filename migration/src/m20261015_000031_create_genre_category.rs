@@ -0,0 +1,102 @@
+//! Migration: create genre_category table and add `genre.category_id`.
+//!
+//! Genres can be grouped into categories (such as themes and formats).
+//! Category names are unique; a genre belongs to at most one category, and
+//! deleting a category leaves its genres uncategorized.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(GenreCategory::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(GenreCategory::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(GenreCategory::Name)
+                            .string_len(255)
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(ColumnDef::new(GenreCategory::Description).text().null())
+                    .col(
+                        ColumnDef::new(GenreCategory::CreateTime)
+                            .date()
+                            .not_null()
+                            .default(Expr::current_date()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Genre::Table)
+                    .add_column(ColumnDef::new(Genre::CategoryId).big_integer().null())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("fk_genre_category_id")
+                    .from(Genre::Table, Genre::CategoryId)
+                    .to(GenreCategory::Table, GenreCategory::Id)
+                    .on_delete(ForeignKeyAction::SetNull)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_genre_category_id")
+                    .table(Genre::Table)
+                    .col(Genre::CategoryId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Dropping the column also drops its foreign key and index.
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Genre::Table)
+                    .drop_column(Genre::CategoryId)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_table(Table::drop().table(GenreCategory::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum GenreCategory {
+    Table,
+    Id,
+    Name,
+    Description,
+    CreateTime,
+}
+
+#[derive(DeriveIden)]
+enum Genre {
+    Table,
+    CategoryId,
+}
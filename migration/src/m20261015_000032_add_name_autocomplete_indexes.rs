@@ -0,0 +1,46 @@
+//! Migration: index named entity names for autocomplete.
+//!
+//! Autocomplete matches `lower(name)`: a `text_pattern_ops` index serves
+//! the prefix match and a GIN trigram index (`pg_trgm` is installed by the
+//! title trigram migration) serves the substring match.
+
+use sea_orm_migration::prelude::*;
+
+/// Named entity tables offering autocomplete.
+const TABLES: &[&str] = &["director", "genre", "idol", "label", "series", "studio"];
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let conn = manager.get_connection();
+        for table in TABLES {
+            conn.execute_unprepared(&format!(
+                "CREATE INDEX IF NOT EXISTS idx_{table}_name_lower_prefix \
+                 ON {table} (lower(name) text_pattern_ops)"
+            ))
+            .await?;
+            conn.execute_unprepared(&format!(
+                "CREATE INDEX IF NOT EXISTS idx_{table}_name_lower_trgm \
+                 ON {table} USING GIN (lower(name) gin_trgm_ops)"
+            ))
+            .await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let conn = manager.get_connection();
+        for table in TABLES {
+            conn.execute_unprepared(&format!(
+                "DROP INDEX IF EXISTS idx_{table}_name_lower_prefix"
+            ))
+            .await?;
+            conn.execute_unprepared(&format!("DROP INDEX IF EXISTS idx_{table}_name_lower_trgm"))
+                .await?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,86 @@
+//! Migration: create the `entity_translation` table.
+//!
+//! Holds display names of named card entities (directors, genres, idols,
+//! labels, series and studios) in other languages, at most one per entity
+//! and language. Rows name their entity by type and ID, so there is no
+//! foreign key; translations of deleted entities are never looked up.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(EntityTranslation::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(EntityTranslation::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(EntityTranslation::EntityType)
+                            .string_len(16)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(EntityTranslation::EntityId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(EntityTranslation::Language)
+                            .string_len(8)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(EntityTranslation::Name)
+                            .string_len(255)
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_entity_translation_entity_language")
+                    .table(EntityTranslation::Table)
+                    .col(EntityTranslation::EntityType)
+                    .col(EntityTranslation::EntityId)
+                    .col(EntityTranslation::Language)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(EntityTranslation::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum EntityTranslation {
+    Table,
+    Id,
+    EntityType,
+    EntityId,
+    Language,
+    Name,
+}
@@ -0,0 +1,45 @@
+//! Migration: count token generations on `user_auth`.
+//!
+//! Access tokens carry the generation they were issued in; bumping it signs
+//! out every outstanding token of the user at once.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(UserAuth::Table)
+                    .add_column_if_not_exists(
+                        ColumnDef::new(UserAuth::TokenGeneration)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(UserAuth::Table)
+                    .drop_column(UserAuth::TokenGeneration)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum UserAuth {
+    Table,
+    TokenGeneration,
+}
@@ -0,0 +1,110 @@
+//! Migration: stamp record sync positions when the writing transaction commits.
+//!
+//! `record_sync_seq` used to be drawn as rows were written, but rows become
+//! visible in commit order. A long transaction could draw a low position,
+//! commit after a faster one with a higher position, and be skipped by a
+//! client that had already synced past it.
+//!
+//! Writes and deletions now draw their position from deferred constraint
+//! triggers that run at commit, under a transaction-level advisory lock that
+//! is held until the commit finishes. Positions are therefore handed out in
+//! commit order, and a snapshot that sees a position sees every lower one.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let conn = manager.get_connection();
+        conn.execute_unprepared("DROP TRIGGER IF EXISTS record_sync_seq_trigger ON record")
+            .await?;
+        conn.execute_unprepared("DROP FUNCTION IF EXISTS record_sync_seq_update()")
+            .await?;
+        conn.execute_unprepared("DROP TRIGGER IF EXISTS record_deletion_trigger ON record")
+            .await?;
+
+        conn.execute_unprepared(
+            "CREATE OR REPLACE FUNCTION record_sync_seq_commit() RETURNS trigger AS $$
+             BEGIN
+                 PERFORM pg_advisory_xact_lock(hashtext('record_sync_seq'));
+                 UPDATE record SET sync_seq = nextval('record_sync_seq') WHERE id = NEW.id;
+                 RETURN NULL;
+             END
+             $$ LANGUAGE plpgsql",
+        )
+        .await?;
+        // The depth guard keeps the restamp above from queueing itself again
+        conn.execute_unprepared(
+            "CREATE CONSTRAINT TRIGGER record_sync_seq_commit_trigger
+             AFTER INSERT OR UPDATE ON record
+             DEFERRABLE INITIALLY DEFERRED
+             FOR EACH ROW WHEN (pg_trigger_depth() = 0)
+             EXECUTE FUNCTION record_sync_seq_commit()",
+        )
+        .await?;
+
+        conn.execute_unprepared(
+            "CREATE OR REPLACE FUNCTION record_deletion_journal() RETURNS trigger AS $$
+             BEGIN
+                 PERFORM pg_advisory_xact_lock(hashtext('record_sync_seq'));
+                 INSERT INTO record_deletion (record_id) VALUES (OLD.id);
+                 RETURN NULL;
+             END
+             $$ LANGUAGE plpgsql",
+        )
+        .await?;
+        conn.execute_unprepared(
+            "CREATE CONSTRAINT TRIGGER record_deletion_trigger
+             AFTER DELETE ON record
+             DEFERRABLE INITIALLY DEFERRED
+             FOR EACH ROW EXECUTE FUNCTION record_deletion_journal()",
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let conn = manager.get_connection();
+        conn.execute_unprepared("DROP TRIGGER IF EXISTS record_deletion_trigger ON record")
+            .await?;
+        conn.execute_unprepared("DROP TRIGGER IF EXISTS record_sync_seq_commit_trigger ON record")
+            .await?;
+        conn.execute_unprepared("DROP FUNCTION IF EXISTS record_sync_seq_commit()")
+            .await?;
+
+        conn.execute_unprepared(
+            "CREATE OR REPLACE FUNCTION record_deletion_journal() RETURNS trigger AS $$
+             BEGIN
+                 INSERT INTO record_deletion (record_id) VALUES (OLD.id);
+                 RETURN OLD;
+             END
+             $$ LANGUAGE plpgsql",
+        )
+        .await?;
+        conn.execute_unprepared(
+            "CREATE TRIGGER record_deletion_trigger
+             AFTER DELETE ON record
+             FOR EACH ROW EXECUTE FUNCTION record_deletion_journal()",
+        )
+        .await?;
+        conn.execute_unprepared(
+            "CREATE OR REPLACE FUNCTION record_sync_seq_update() RETURNS trigger AS $$
+             BEGIN
+                 NEW.sync_seq := nextval('record_sync_seq');
+                 RETURN NEW;
+             END
+             $$ LANGUAGE plpgsql",
+        )
+        .await?;
+        conn.execute_unprepared(
+            "CREATE TRIGGER record_sync_seq_trigger
+             BEFORE UPDATE ON record
+             FOR EACH ROW EXECUTE FUNCTION record_sync_seq_update()",
+        )
+        .await?;
+        Ok(())
+    }
+}
@@ -3,29 +3,52 @@ use axum::{
     error_handling::HandleErrorLayer,
     extract::{DefaultBodyLimit, Request},
     http::{
-        header::{AUTHORIZATION, CONTENT_TYPE},
-        HeaderValue, Method, StatusCode,
+        header::{CONTENT_LENGTH, CONTENT_TYPE, ETAG, RETRY_AFTER},
+        Extensions, HeaderMap, HeaderName, HeaderValue, Method, StatusCode, Version,
     },
     middleware::{self, Next},
     response::{IntoResponse, Response},
     Router,
 };
-use http_body_util::BodyExt as _;
+use http_body_util::{BodyExt as _, LengthLimitError, Limited};
 
-use std::time::Duration;
+use std::{sync::Arc, time::Duration};
 use tower::ServiceBuilder;
 use tower_http::normalize_path::NormalizePathLayer;
-use tower_http::{cors::CorsLayer, services::ServeDir, trace::TraceLayer};
+use tower_http::{
+    compression::{
+        predicate::{Predicate, SizeAbove},
+        CompressionLayer,
+    },
+    cors::{AllowHeaders, AllowMethods, AllowOrigin, CorsLayer},
+    services::ServeDir,
+    trace::TraceLayer,
+};
 
 use crate::{
     common::{
+        access_log::access_log,
         app_state::AppState,
+        config::CorsConfig,
         error::{handle_error, AppError},
+        jobs::job_routes,
         jwt,
+        rate_limit::{
+            client_ip, rate_limit, too_many_requests, RateLimiter, RequestRateLimiter, RouteClass,
+        },
+        request_id::{request_id, REQUEST_ID_HEADER},
     },
     domains::{
-        auth::user_auth_routes, crawl::crawl_routes, device::device_routes, file::file_routes,
-        luna::luna_routes, search::search_routes, user::user_routes,
+        audit::{audit_routes, audit_writes},
+        auth::{api_key_routes, password_routes, totp_routes, user_auth_routes},
+        backup::backup_routes,
+        crawl::crawl_routes,
+        device::{device_routes, session_routes},
+        file::file_routes,
+        health::{db_admin_routes, health_routes},
+        luna::luna_routes,
+        search::search_routes,
+        user::user_routes,
     },
 };
 
@@ -34,13 +57,26 @@ use utoipa::OpenApi as _;
 
 #[cfg(feature = "swagger")]
 use crate::domains::{
-    auth::UserAuthApiDoc, crawl::CrawlApiDoc, device::DeviceApiDoc, file::FileApiDoc,
-    luna::LunaApiDoc, search::SearchApiDoc, user::UserApiDoc,
+    audit::AuditApiDoc, auth::UserAuthApiDoc, backup::BackupApiDoc, crawl::CrawlApiDoc,
+    device::DeviceApiDoc, file::FileApiDoc, health::HealthApiDoc, luna::LunaApiDoc,
+    search::SearchApiDoc, user::UserApiDoc,
 };
 
+#[cfg(feature = "swagger")]
+use crate::common::jobs::JobsApiDoc;
+
+#[cfg(all(feature = "swagger", feature = "metadata"))]
+use crate::domains::luna::MetadataApiDoc;
+
 #[cfg(feature = "swagger")]
 use utoipa_swagger_ui::SwaggerUi;
 
+#[cfg(feature = "graphql")]
+use crate::domains::graphql::graphql_routes;
+
+#[cfg(feature = "metrics")]
+use crate::common::metrics::{metrics_routes, track_http};
+
 use once_cell::sync::Lazy;
 use regex::Regex;
 
@@ -56,7 +92,7 @@ pub static FORBIDDEN_PATTERNS: Lazy<Vec<Regex>> = Lazy::new(|| {
 
 #[cfg(feature = "swagger")]
 fn create_swagger_ui() -> SwaggerUi {
-    SwaggerUi::new("/docs")
+    let swagger = SwaggerUi::new("/docs")
         .url(
             "/api-docs/user_auth/openapi.json",
             UserAuthApiDoc::openapi(),
@@ -67,27 +103,19 @@ fn create_swagger_ui() -> SwaggerUi {
         .url("/api-docs/luna/openapi.json", LunaApiDoc::openapi())
         .url("/api-docs/search/openapi.json", SearchApiDoc::openapi())
         .url("/api-docs/crawl/openapi.json", CrawlApiDoc::openapi())
+        .url("/api-docs/audit/openapi.json", AuditApiDoc::openapi())
+        .url("/api-docs/backup/openapi.json", BackupApiDoc::openapi())
+        .url("/api-docs/health/openapi.json", HealthApiDoc::openapi())
+        .url("/api-docs/jobs/openapi.json", JobsApiDoc::openapi());
+
+    #[cfg(feature = "metadata")]
+    let swagger = swagger.url("/api-docs/metadata/openapi.json", MetadataApiDoc::openapi());
+
+    swagger
 }
 
 pub fn create_router(state: AppState) -> Router {
-    // Build a CORS layer from configured origins
-    let cors = if state.config.cors_origins.is_empty() {
-        tracing::warn!("CORS_ORIGINS not configured — all cross-origin requests will be rejected");
-        CorsLayer::new()
-            .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
-            .allow_headers([AUTHORIZATION, CONTENT_TYPE])
-    } else {
-        let origins: Vec<HeaderValue> = state
-            .config
-            .cors_origins
-            .iter()
-            .filter_map(|o| o.parse::<HeaderValue>().ok())
-            .collect();
-        CorsLayer::new()
-            .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
-            .allow_origin(origins)
-            .allow_headers([AUTHORIZATION, CONTENT_TYPE])
-    };
+    let cors = cors_layer(&state.config.cors);
 
     // Create a common middleware stack for error handling, timeouts, and CORS.
     let middleware_stack = ServiceBuilder::new()
@@ -95,39 +123,75 @@ pub fn create_router(state: AppState) -> Router {
         .timeout(Duration::from_secs(60))
         .layer(cors);
 
-    // /auth routes (login, register, refresh, etc.) — no logging here
+    // /auth routes (login, register, refresh, etc.) — no logging here, rate limited per client IP
     let auth_router = Router::new()
         .nest("/auth", user_auth_routes())
-        .layer(middleware::from_fn(make_request_response_inspecter(false)));
+        .layer(middleware::from_fn(make_request_response_inspecter(false)))
+        .layer(middleware::from_fn(make_ip_rate_limiter(
+            Arc::new(RateLimiter::new(state.config.auth_ip_rate_limit)),
+            state.config.trust_forwarded_for,
+        )));
+
+    // Per-user (or per-IP) budgets for reads, writes and uploads
+    let rate_limit_layer = middleware::from_fn_with_state(
+        Arc::new(RequestRateLimiter::from_config(&state.config)),
+        rate_limit,
+    );
+
+    // Record every successful write on the luna and user routes in the audit log
+    let audit_layer = middleware::from_fn_with_state(state.clone(), audit_writes);
 
     // Protected API routes
     let protected_routes = Router::new()
-        .nest("/user", user_routes())
+        .nest("/user", user_routes().layer(audit_layer.clone()))
         .nest("/device", device_routes())
+        .nest("/sessions", session_routes())
         .nest("/file", file_routes())
-        .nest("/cards", luna_routes().merge(search_routes()))
+        .nest(
+            "/cards",
+            luna_routes().layer(audit_layer).merge(search_routes()),
+        )
         .nest("/crawl", crawl_routes())
-        // by default, Multipart limits to 2MB; override with `asset_max_size`
-        // See https://docs.rs/axum/latest/axum/extract/struct.Multipart.html
-        .layer(DefaultBodyLimit::max(state.config.asset_max_size))
-        // enforce JWT authentication
-        .route_layer(middleware::from_fn(jwt::jwt_auth))
+        .nest("/audit", audit_routes())
+        .nest(
+            "/admin",
+            backup_routes().merge(db_admin_routes()).merge(job_routes()),
+        )
+        .nest("/api-keys", api_key_routes())
+        .nest("/auth/password", password_routes())
+        .nest("/auth/totp", totp_routes());
+
+    // GraphQL shares authentication and rate limits with the REST API
+    #[cfg(feature = "graphql")]
+    let protected_routes = protected_routes.merge(graphql_routes());
+
+    let protected_routes = protected_routes
+        // rate limit per user; runs inside authentication so the claims are known
+        .route_layer(rate_limit_layer.clone())
+        // enforce JWT or API key authentication
+        .route_layer(middleware::from_fn_with_state(state.clone(), jwt::jwt_auth))
         // attach inspecter
         .layer(middleware::from_fn(make_request_response_inspecter(true)));
 
     // setup assets routes
-    let public_assets_routes = Router::new().nest_service(
-        state.config.assets_public_url.as_str(),
-        ServeDir::new(state.config.assets_public_path.clone()),
-    );
+    let public_assets_routes = Router::new()
+        .nest_service(
+            state.config.assets_public_url.as_str(),
+            ServeDir::new(state.config.assets_public_path.clone()),
+        )
+        // rate limit per client IP
+        .layer(rate_limit_layer.clone());
 
     let private_assets_routes = Router::new()
         .nest_service(
             state.config.assets_private_url.as_str(),
-            ServeDir::new(state.config.assets_private_path.clone()),
+            ServiceBuilder::new()
+                .layer(middleware::from_fn(hide_luna_private_assets))
+                .service(ServeDir::new(state.config.assets_private_path.clone())),
         )
-        // enforce JWT authentication
-        .route_layer(middleware::from_fn(jwt::jwt_auth));
+        .route_layer(rate_limit_layer)
+        // enforce JWT or API key authentication
+        .route_layer(middleware::from_fn_with_state(state.clone(), jwt::jwt_auth));
     // Note: No heavy middleware for static assets to improve performance
 
     // Create the main router
@@ -136,43 +200,139 @@ pub fn create_router(state: AppState) -> Router {
     // and add the state
     let router = Router::new()
         .route("/health", axum::routing::get(health_check))
+        // Kubernetes probes: unauthenticated and not rate limited
+        .merge(health_routes())
         .merge(auth_router)
         .merge(protected_routes)
         .merge(public_assets_routes)
         .merge(private_assets_routes);
 
+    // Prometheus scrape endpoint: unauthenticated, like the probes
+    #[cfg(feature = "metrics")]
+    let router = router
+        .merge(metrics_routes())
+        .layer(middleware::from_fn(track_http));
+
     // Conditionally add Swagger UI only if the feature is enabled
     #[cfg(feature = "swagger")]
     let router = router.merge(create_swagger_ui());
 
+    let router = if state.config.response_compression {
+        router.layer(json_compression_layer())
+    } else {
+        router
+    };
+
     router
+        // body limits are chosen per request by `make_body_limiter`, replacing axum's 2MB default
+        .layer(DefaultBodyLimit::disable())
+        .layer(middleware::from_fn(make_body_limiter(
+            state.config.json_body_limit,
+            state.config.upload_body_limit,
+            state.config.video_max_size,
+        )))
         .layer(NormalizePathLayer::trim_trailing_slash())
         .layer(
             TraceLayer::new_for_http()
                 .make_span_with(|req: &axum::http::Request<_>| {
+                    let request_id = req
+                        .headers()
+                        .get(REQUEST_ID_HEADER)
+                        .and_then(|v| v.to_str().ok())
+                        .unwrap_or_default();
                     tracing::info_span!(
                         "request",
                         method = %req.method(),
                         uri = %req.uri(),
+                        request_id = %request_id,
                     )
                 })
-                .on_response(
-                    |response: &axum::http::Response<_>,
-                     latency: std::time::Duration,
-                     _span: &tracing::Span| {
-                        tracing::info!(
-                            "request completed: status = {status}, latency = {latency:?}",
-                            status = response.status(),
-                            latency = latency
-                        );
-                    },
-                ),
+                // completed requests are logged by `access_log`
+                .on_response(()),
         )
         .fallback(fallback)
+        // after the fallback so unmatched requests are logged too
+        .layer(middleware::from_fn_with_state(
+            state.config.access_log_format,
+            access_log,
+        ))
         .layer(middleware_stack)
+        // outermost, so every log line and error response carries the ID
+        .layer(middleware::from_fn(request_id))
         .with_state(state)
 }
 
+/// Builds the CORS layer from the configured origins, methods, headers and
+/// credentials policy.
+///
+/// Entries that do not parse are skipped with a warning. Credentials are
+/// dropped when any list is a `*` wildcard, since browsers reject that pairing.
+pub fn cors_layer(cors: &CorsConfig) -> CorsLayer {
+    let is_wildcard = |values: &[String]| values.iter().any(|v| v == "*");
+    let any_origin = is_wildcard(&cors.allowed_origins);
+    let any_method = is_wildcard(&cors.allowed_methods);
+    let any_header = is_wildcard(&cors.allowed_headers);
+
+    if cors.allowed_origins.is_empty() {
+        tracing::warn!("CORS_ORIGINS not configured — all cross-origin requests will be rejected");
+    }
+
+    let allow_credentials = cors.allow_credentials && !(any_origin || any_method || any_header);
+    if cors.allow_credentials && !allow_credentials {
+        tracing::warn!("CORS credentials cannot be combined with a `*` wildcard — disabling them");
+    }
+
+    let origins = if any_origin {
+        AllowOrigin::any()
+    } else {
+        AllowOrigin::list(parse_cors_values::<HeaderValue>(
+            "origin",
+            &cors.allowed_origins,
+        ))
+    };
+    let methods = if any_method {
+        AllowMethods::any()
+    } else {
+        let methods: Vec<String> = cors
+            .allowed_methods
+            .iter()
+            .map(|m| m.to_uppercase())
+            .collect();
+        AllowMethods::list(parse_cors_values::<Method>("method", &methods))
+    };
+    let headers = if any_header {
+        AllowHeaders::any()
+    } else {
+        AllowHeaders::list(parse_cors_values::<HeaderName>(
+            "header",
+            &cors.allowed_headers,
+        ))
+    };
+
+    CorsLayer::new()
+        .allow_origin(origins)
+        .allow_methods(methods)
+        .allow_headers(headers)
+        .allow_credentials(allow_credentials)
+        .expose_headers([ETAG, RETRY_AFTER, REQUEST_ID_HEADER])
+}
+
+/// Parses configured CORS entries, logging and skipping the invalid ones.
+fn parse_cors_values<T>(kind: &str, values: &[String]) -> Vec<T>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    values
+        .iter()
+        .filter_map(|v| {
+            v.parse::<T>()
+                .map_err(|err| tracing::warn!("Ignoring invalid CORS {kind} {v:?}: {err}"))
+                .ok()
+        })
+        .collect()
+}
+
 async fn health_check() -> &'static str {
     "OK\n"
 }
@@ -183,6 +343,112 @@ pub async fn fallback() -> Result<impl IntoResponse, AppError> {
     Ok((StatusCode::NOT_FOUND, "Not Found"))
 }
 
+/// Directories under the private assets path that are not served as static
+/// files: record media goes through the permission-checked `/cards/media`
+/// handlers, and upload staging files are never served.
+const HIDDEN_PRIVATE_ASSETS: [&[&str]; 4] = [
+    &["images", "record"],
+    &["videos", "record"],
+    &["thumbnails", "record"],
+    &[".uploads"],
+];
+
+/// Answers 404 for private assets under [`HIDDEN_PRIVATE_ASSETS`]. The path
+/// is decoded and split the way `ServeDir` resolves it, so encoded or padded
+/// paths cannot reach those directories either.
+async fn hide_luna_private_assets(req: Request, next: Next) -> Response {
+    let path = percent_encoding::percent_decode_str(req.uri().path()).decode_utf8_lossy();
+    let segments: Vec<&str> = path
+        .split(['/', '\\'])
+        .filter(|segment| !segment.is_empty() && *segment != ".")
+        .collect();
+    let hidden = HIDDEN_PRIVATE_ASSETS.iter().any(|prefix| {
+        segments.len() >= prefix.len()
+            && prefix
+                .iter()
+                .zip(&segments)
+                .all(|(dir, segment)| dir.eq_ignore_ascii_case(segment))
+    });
+    if hidden {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+    next.run(req).await
+}
+
+/// Compresses JSON responses with gzip or brotli when the client accepts it.
+/// Media, assets and event streams are left alone since they are already
+/// compressed or must be streamed as-is.
+fn json_compression_layer() -> CompressionLayer<impl Predicate> {
+    let is_json = |_: StatusCode, _: Version, headers: &HeaderMap, _: &Extensions| {
+        headers
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|ct| ct.starts_with("application/json"))
+    };
+    CompressionLayer::new()
+        .gzip(true)
+        .br(true)
+        .compress_when(SizeAbove::default().and(is_json))
+}
+
+// Type alias for the boxed future returned by the body limiter middleware
+type BodyLimiterFuture = std::pin::Pin<Box<dyn std::future::Future<Output = Response> + Send>>;
+
+/// Middleware that caps request bodies at `upload_limit` for multipart uploads,
+/// `video_limit` for multipart uploads to a `/video` route, and `json_limit`
+/// for everything else.
+/// A declared `Content-Length` over the limit is rejected up front with 413;
+/// otherwise the body is wrapped so extractors fail with 413 once it overruns.
+fn make_body_limiter(
+    json_limit: usize,
+    upload_limit: usize,
+    video_limit: usize,
+) -> impl Fn(Request<Body>, Next) -> BodyLimiterFuture + Clone + Send + Sync + 'static {
+    move |req, next| {
+        Box::pin(async move {
+            let limit = match RouteClass::of(&req) {
+                RouteClass::Upload if req.uri().path().ends_with("/video") => video_limit,
+                RouteClass::Upload => upload_limit,
+                RouteClass::Read | RouteClass::Write => json_limit,
+            };
+            let declared = req
+                .headers()
+                .get(CONTENT_LENGTH)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.parse::<usize>().ok());
+            if declared.is_some_and(|len| len > limit) {
+                return AppError::PayloadTooLarge.into_response();
+            }
+            let req = req.map(|body| Body::new(Limited::new(body, limit)));
+            next.run(req).await
+        })
+    }
+}
+
+// Type alias for the boxed future returned by the IP rate limiter middleware
+type RateLimiterFuture = std::pin::Pin<Box<dyn std::future::Future<Output = Response> + Send>>;
+
+/// Middleware that rejects requests with 429 Too Many Requests once the client IP
+/// exhausts its token bucket.
+/// Requests without a known IP (e.g. in-process test requests) are not limited.
+fn make_ip_rate_limiter(
+    limiter: Arc<RateLimiter>,
+    trust_forwarded_for: bool,
+) -> impl Fn(Request<Body>, Next) -> RateLimiterFuture + Clone + Send + Sync + 'static {
+    move |req, next| {
+        let limiter = Arc::clone(&limiter);
+        Box::pin(async move {
+            if let Some(client_ip) = client_ip(&req, trust_forwarded_for) {
+                if let Err(retry_after) = limiter.try_acquire(&client_ip) {
+                    tracing::warn!("Auth rate limit exceeded for {client_ip}");
+                    return too_many_requests(retry_after);
+                }
+            }
+            next.run(req).await
+        })
+    }
+}
+
 // Type alias for the boxed future returned by the request/response inspector middleware
 type InspectorFuture = std::pin::Pin<
     Box<dyn std::future::Future<Output = Result<Response, (StatusCode, String)>> + Send>,
@@ -250,10 +516,16 @@ async fn request_inspect_print<B>(
 ) -> Result<Bytes, (StatusCode, String)>
 where
     B: axum::body::HttpBody<Data = Bytes>,
-    B::Error: std::fmt::Display,
+    B::Error: std::error::Error + 'static,
 {
     let bytes = match body.collect().await {
         Ok(collected) => collected.to_bytes(),
+        Err(err) if is_length_limit_error(&err) => {
+            return Err((
+                StatusCode::PAYLOAD_TOO_LARGE,
+                AppError::PayloadTooLarge.to_string(),
+            ));
+        }
         Err(err) => {
             return Err((
                 StatusCode::BAD_REQUEST,
@@ -276,6 +548,18 @@ where
     Ok(bytes)
 }
 
+/// Whether `err`, or one of its sources, is a body overrunning its size limit.
+fn is_length_limit_error(err: &(dyn std::error::Error + 'static)) -> bool {
+    let mut source = Some(err);
+    while let Some(err) = source {
+        if err.is::<LengthLimitError>() {
+            return true;
+        }
+        source = err.source();
+    }
+    false
+}
+
 async fn response_print<B>(direction: &str, body: B) -> Result<Bytes, (StatusCode, String)>
 where
     B: axum::body::HttpBody<Data = Bytes>,
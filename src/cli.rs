@@ -0,0 +1,308 @@
+//! Command line interface: the API server plus routine admin tasks, so
+//! operators need neither direct database access nor separate binaries.
+//!
+//! Without a subcommand the server starts, as before the CLI existed.
+
+use std::{error::Error, path::PathBuf};
+
+use clap::{Parser, Subcommand};
+use futures::StreamExt as _;
+use migration::{Migrator, MigratorTrait as _};
+use serde::de::DeserializeOwned;
+use tokio::io::{AsyncWrite, AsyncWriteExt as _};
+use validator::Validate as _;
+
+use crate::{
+    common::{
+        config::{connect_database, setup_database, Config},
+        jwt::Role,
+    },
+    domains::{
+        auth::{dto::auth_dto::RegisterDto, AuthService, AuthServiceTrait as _},
+        device::{DeviceService, DeviceServiceTrait as _},
+        file::{FileService, FileServiceTrait as _},
+        luna::{
+            dto::{ExportEntity, ExportFormat},
+            LunaService, LunaServiceTrait as _, RecordPermission,
+        },
+        search::{SearchService, SearchServiceTrait as _},
+        user::{UserService, UserServiceTrait as _},
+    },
+};
+
+/// Result of an admin command.
+pub type CliResult = Result<(), Box<dyn Error + Send + Sync>>;
+
+#[derive(Debug, Parser)]
+#[command(version, about = "Lunirelust media-catalog API server and admin tools")]
+pub struct Cli {
+    /// TOML or YAML config file, layered under environment variables.
+    #[arg(long, global = true, env = "CONFIG_FILE")]
+    pub config: Option<PathBuf>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+impl Cli {
+    /// Subcommand to run: the server when none was given.
+    pub fn into_command(self) -> Command {
+        self.command.unwrap_or(Command::Serve)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Subcommand)]
+pub enum Command {
+    /// Run the API server (the default).
+    Serve,
+    /// Apply, revert or list schema migrations.
+    Migrate {
+        #[command(subcommand)]
+        action: MigrateAction,
+    },
+    /// Create a user with the admin role.
+    CreateAdmin {
+        #[arg(long)]
+        username: String,
+        #[arg(long)]
+        email: String,
+        /// Read from `ADMIN_PASSWORD` when not given, to keep it out of the shell history.
+        #[arg(long, env = "ADMIN_PASSWORD", hide_env_values = true)]
+        password: String,
+    },
+    /// Stream every record, or one entity table, to stdout or a file.
+    Export {
+        /// `csv` or `jsonl`.
+        #[arg(long, default_value = "jsonl", value_parser = parse_variant::<ExportFormat>)]
+        format: ExportFormat,
+        /// Entity table to export instead of the records, e.g. `idols`.
+        #[arg(long, value_parser = parse_variant::<ExportEntity>)]
+        entity: Option<ExportEntity>,
+        /// File to write; stdout when omitted.
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Rebuild the search index from the database and wait for it to finish.
+    ReindexSearch,
+}
+
+#[derive(Debug, PartialEq, Eq, Subcommand)]
+pub enum MigrateAction {
+    /// Apply every pending migration.
+    Up,
+    /// Revert the latest migrations.
+    Down {
+        #[arg(long, default_value_t = 1)]
+        steps: u32,
+    },
+    /// List applied and pending migrations.
+    Status,
+}
+
+/// Parses a `snake_case` enum argument the way the API parses it.
+fn parse_variant<T: DeserializeOwned>(value: &str) -> Result<T, String> {
+    serde_json::from_value(serde_json::Value::String(value.to_owned())).map_err(|e| e.to_string())
+}
+
+/// Applies, reverts or lists migrations without starting the server.
+pub async fn migrate(config: &Config, action: MigrateAction) -> CliResult {
+    let pool = connect_database(config).await?;
+    match action {
+        MigrateAction::Up => Migrator::up(&pool, None).await?,
+        MigrateAction::Down { steps } => Migrator::down(&pool, Some(steps)).await?,
+        MigrateAction::Status => {
+            for migration in Migrator::get_applied_migrations(&pool).await? {
+                println!("applied  {}", migration.name());
+            }
+            for migration in Migrator::get_pending_migrations(&pool).await? {
+                println!("pending  {}", migration.name());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Creates an admin account and prints its user ID.
+pub async fn create_admin(config: Config, dto: RegisterDto) -> CliResult {
+    dto.validate()?;
+    let pool = setup_database(&config).await?;
+    let file_service = FileService::create_service(config.clone(), pool.clone());
+    let user_service = UserService::create_service(pool.clone(), file_service);
+    let device_service = DeviceService::create_service(pool.clone());
+    let auth_service = AuthService::create_service(&config, pool, user_service, device_service);
+
+    let user_id = auth_service.create_admin(dto).await?;
+    println!("{user_id}");
+    Ok(())
+}
+
+/// Streams an export with full clearance to `output`, or stdout.
+pub async fn export(
+    config: Config,
+    format: ExportFormat,
+    entity: Option<ExportEntity>,
+    output: Option<PathBuf>,
+) -> CliResult {
+    let pool = setup_database(&config).await?;
+    let luna_service = LunaService::create_service(config, pool);
+    let exports = luna_service.export_service();
+    let mut stream = match entity {
+        Some(entity) => exports.export_entities(entity, format),
+        None => exports.export_records(format, RecordPermission::clearance(Role::Admin)),
+    };
+
+    let mut out: Box<dyn AsyncWrite + Send + Unpin> = match output {
+        Some(path) => Box::new(tokio::fs::File::create(path).await?),
+        None => Box::new(tokio::io::stdout()),
+    };
+    while let Some(chunk) = stream.next().await {
+        out.write_all(chunk?.as_bytes()).await?;
+    }
+    out.flush().await?;
+    Ok(())
+}
+
+/// Rebuilds the search index in the foreground.
+pub async fn reindex_search(config: Config) -> CliResult {
+    let pool = setup_database(&config).await?;
+    SearchService::create_service(config, pool)
+        .reindex()
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use clap::{error::ErrorKind, CommandFactory as _, Parser as _};
+
+    use super::{Cli, Command, MigrateAction};
+    use crate::domains::luna::dto::{ExportEntity, ExportFormat};
+
+    fn parse(args: &[&str]) -> Result<Cli, clap::Error> {
+        Cli::try_parse_from(std::iter::once("lunirelust").chain(args.iter().copied()))
+    }
+
+    #[test]
+    fn definition_is_consistent() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn no_subcommand_runs_the_server() {
+        let cli = parse(&[]).expect("parses");
+        assert_eq!(cli.into_command(), Command::Serve);
+        assert_eq!(
+            parse(&["serve"]).expect("parses").into_command(),
+            Command::Serve
+        );
+    }
+
+    #[test]
+    fn config_is_accepted_before_and_after_the_subcommand() {
+        for args in [
+            &["--config", "app.toml", "reindex-search"][..],
+            &["reindex-search", "--config", "app.toml"][..],
+        ] {
+            let cli = parse(args).expect("parses");
+            assert_eq!(cli.config, Some(PathBuf::from("app.toml")));
+            assert_eq!(cli.into_command(), Command::ReindexSearch);
+        }
+    }
+
+    #[test]
+    fn migrate_parses_each_action() {
+        let action = |args: &[&str]| match parse(args).expect("parses").into_command() {
+            Command::Migrate { action } => action,
+            other => panic!("Expected migrate, got {other:?}"),
+        };
+        assert_eq!(action(&["migrate", "up"]), MigrateAction::Up);
+        assert_eq!(action(&["migrate", "status"]), MigrateAction::Status);
+        assert_eq!(
+            action(&["migrate", "down"]),
+            MigrateAction::Down { steps: 1 }
+        );
+        assert_eq!(
+            action(&["migrate", "down", "--steps", "3"]),
+            MigrateAction::Down { steps: 3 }
+        );
+
+        assert!(parse(&["migrate"]).is_err(), "An action is required");
+        let invalid = parse(&["migrate", "down", "--steps", "all"]).expect_err("steps is a count");
+        assert_eq!(invalid.kind(), ErrorKind::ValueValidation);
+    }
+
+    #[test]
+    fn create_admin_takes_the_account_fields() {
+        let cli = parse(&[
+            "create-admin",
+            "--username",
+            "root",
+            "--email",
+            "root@example.com",
+            "--password",
+            "hunter22",
+        ])
+        .expect("parses");
+        assert_eq!(
+            cli.into_command(),
+            Command::CreateAdmin {
+                username: "root".to_owned(),
+                email: "root@example.com".to_owned(),
+                password: "hunter22".to_owned(),
+            }
+        );
+
+        let missing = parse(&["create-admin", "--username", "root", "--password", "x"])
+            .expect_err("email is required");
+        assert_eq!(missing.kind(), ErrorKind::MissingRequiredArgument);
+    }
+
+    #[test]
+    fn export_defaults_to_every_record_as_jsonl_on_stdout() {
+        assert_eq!(
+            parse(&["export"]).expect("parses").into_command(),
+            Command::Export {
+                format: ExportFormat::Jsonl,
+                entity: None,
+                output: None,
+            }
+        );
+        assert_eq!(
+            parse(&[
+                "export",
+                "--format",
+                "csv",
+                "--entity",
+                "idols",
+                "-o",
+                "idols.csv"
+            ])
+            .expect("parses")
+            .into_command(),
+            Command::Export {
+                format: ExportFormat::Csv,
+                entity: Some(ExportEntity::Idols),
+                output: Some(PathBuf::from("idols.csv")),
+            }
+        );
+    }
+
+    #[test]
+    fn export_rejects_unknown_variants() {
+        for args in [
+            &["export", "--format", "xml"][..],
+            &["export", "--entity", "records"][..],
+        ] {
+            let err = parse(args).expect_err("unknown variant");
+            assert_eq!(err.kind(), ErrorKind::ValueValidation);
+        }
+    }
+
+    #[test]
+    fn unknown_subcommands_are_rejected() {
+        let err = parse(&["frobnicate"]).expect_err("unknown subcommand");
+        assert_eq!(err.kind(), ErrorKind::InvalidSubcommand);
+    }
+}
@@ -1,13 +1,23 @@
+pub mod access_log;
 pub mod app_state;
 pub mod bootstrap;
+pub mod cache;
 pub mod config;
+pub mod crypto;
 pub mod dto;
 pub mod error;
+pub mod etag;
 pub mod hash_util;
+pub mod jobs;
 pub mod jwt;
+#[cfg(feature = "metrics")]
+pub mod metrics;
 pub mod multipart_helper;
 pub mod openapi;
 #[cfg(feature = "opentelemetry")]
 pub mod opentelemetry;
 pub mod pagination;
+pub mod range;
+pub mod rate_limit;
+pub mod request_id;
 pub mod ts_format;
@@ -0,0 +1,140 @@
+//! Access logging: one structured line per request.
+//!
+//! Each line carries the method, the route template (or the raw path when no
+//! route matched), the status, the latency, the authenticated user and the
+//! request ID. `ACCESS_LOG_FORMAT` picks between a JSON object per line, for
+//! log shippers, and tracing fields, for humans reading a terminal.
+
+use std::time::Instant;
+
+use axum::{
+    extract::{MatchedPath, Request, State},
+    middleware::Next,
+    response::Response,
+};
+use serde::Serialize;
+
+use crate::common::{jwt::Claims, request_id::REQUEST_ID_HEADER};
+
+/// Output format of the access log.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AccessLogFormat {
+    /// One JSON object per line.
+    Json,
+    /// Tracing fields, formatted by the subscriber.
+    #[default]
+    Pretty,
+}
+
+impl AccessLogFormat {
+    /// Parses `json` or `pretty`, case-insensitively.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "json" => Some(Self::Json),
+            "pretty" => Some(Self::Pretty),
+            _ => None,
+        }
+    }
+}
+
+/// One access log line.
+#[derive(Debug, Serialize)]
+struct AccessLogEntry<'a> {
+    method: &'a str,
+    path: &'a str,
+    status: u16,
+    latency_ms: f64,
+    user_id: Option<&'a str>,
+    request_id: Option<&'a str>,
+}
+
+impl AccessLogEntry<'_> {
+    fn emit(&self, format: AccessLogFormat) {
+        match format {
+            AccessLogFormat::Json => match serde_json::to_string(self) {
+                Ok(line) => tracing::info!(target: "access_log", "{line}"),
+                Err(err) => tracing::warn!("Failed to serialize access log entry: {err}"),
+            },
+            AccessLogFormat::Pretty => tracing::info!(
+                target: "access_log",
+                method = self.method,
+                path = self.path,
+                status = self.status,
+                latency_ms = self.latency_ms,
+                user_id = self.user_id.unwrap_or("-"),
+                request_id = self.request_id.unwrap_or("-"),
+                "request completed"
+            ),
+        }
+    }
+}
+
+/// Middleware that logs every request once its response is ready.
+///
+/// The user is read from the [`Claims`] that [`crate::common::jwt::jwt_auth`]
+/// leaves on the response, so unauthenticated routes log no user.
+pub async fn access_log(
+    State(format): State<AccessLogFormat>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let method = req.method().clone();
+    let path = req.extensions().get::<MatchedPath>().map_or_else(
+        || req.uri().path().to_owned(),
+        |matched| matched.as_str().to_owned(),
+    );
+    let request_id = req
+        .headers()
+        .get(&REQUEST_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(ToOwned::to_owned);
+    let started = Instant::now();
+
+    let response = next.run(req).await;
+
+    AccessLogEntry {
+        method: method.as_str(),
+        path: &path,
+        status: response.status().as_u16(),
+        latency_ms: started.elapsed().as_secs_f64() * 1000.0,
+        user_id: response
+            .extensions()
+            .get::<Claims>()
+            .map(|claims| claims.sub.as_str()),
+        request_id: request_id.as_deref(),
+    }
+    .emit(format);
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AccessLogEntry, AccessLogFormat};
+
+    #[test]
+    fn formats_parse_case_insensitively() {
+        assert_eq!(AccessLogFormat::parse("JSON"), Some(AccessLogFormat::Json));
+        assert_eq!(
+            AccessLogFormat::parse("pretty"),
+            Some(AccessLogFormat::Pretty)
+        );
+        assert_eq!(AccessLogFormat::parse("xml"), None);
+    }
+
+    #[test]
+    fn json_entries_keep_every_field() {
+        let entry = AccessLogEntry {
+            method: "GET",
+            path: "/cards/records/{id}",
+            status: 200,
+            latency_ms: 1.5,
+            user_id: Some("user-1"),
+            request_id: None,
+        };
+        let line = serde_json::to_value(&entry).expect("entry serializes");
+        assert_eq!(line["path"], "/cards/records/{id}");
+        assert_eq!(line["status"], 200);
+        assert_eq!(line["user_id"], "user-1");
+        assert!(line["request_id"].is_null(), "missing ID should be null");
+    }
+}
@@ -1,12 +1,13 @@
 use std::sync::Arc;
 
 use crate::domains::{
-    auth::AuthServiceTrait, crawl::CrawlServiceTrait, device::DeviceServiceTrait,
-    file::FileServiceTrait, luna::LunaServiceTrait, search::SearchServiceTrait,
+    audit::AuditServiceTrait, auth::AuthServiceTrait, backup::BackupServiceTrait,
+    crawl::CrawlServiceTrait, device::DeviceServiceTrait, file::FileServiceTrait,
+    health::HealthServiceTrait, luna::LunaServiceTrait, search::SearchServiceTrait,
     user::UserServiceTrait,
 };
 
-use super::config::Config;
+use super::{config::Config, jobs::JobRunner};
 
 /// `AppState` is a struct that holds the application-wide shared state.
 /// It is passed to request handlers via Axum's extension mechanism.
@@ -28,6 +29,14 @@ pub struct AppState {
     pub search_service: Arc<dyn SearchServiceTrait>,
     /// Service handling crawl-related logic.
     pub crawl_service: Arc<dyn CrawlServiceTrait>,
+    /// Service handling the audit log.
+    pub audit_service: Arc<dyn AuditServiceTrait>,
+    /// Service handling whole-database backup and restore.
+    pub backup_service: Arc<dyn BackupServiceTrait>,
+    /// Service checking dependencies for the readiness probe.
+    pub health_service: Arc<dyn HealthServiceTrait>,
+    /// Runner of the background jobs.
+    pub job_runner: Arc<JobRunner>,
 }
 
 impl AppState {
@@ -42,6 +51,10 @@ impl AppState {
         luna_service: Arc<dyn LunaServiceTrait>,
         search_service: Arc<dyn SearchServiceTrait>,
         crawl_service: Arc<dyn CrawlServiceTrait>,
+        audit_service: Arc<dyn AuditServiceTrait>,
+        backup_service: Arc<dyn BackupServiceTrait>,
+        health_service: Arc<dyn HealthServiceTrait>,
+        job_runner: Arc<JobRunner>,
     ) -> Self {
         Self {
             config,
@@ -52,6 +65,10 @@ impl AppState {
             luna_service,
             search_service,
             crawl_service,
+            audit_service,
+            backup_service,
+            health_service,
+            job_runner,
         }
     }
 }
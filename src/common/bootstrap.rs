@@ -5,14 +5,18 @@ use sea_orm::DatabaseConnection;
 use tokio::sync::{broadcast, Mutex};
 
 use crate::common::config::Config;
+use crate::common::jobs::JobRunner;
+use crate::domains::audit::{AuditService, AuditServiceTrait};
 use crate::domains::auth::{AuthService, AuthServiceTrait};
+use crate::domains::backup::{BackupService, BackupServiceTrait};
 use crate::domains::crawl::infra::crawler::RunnerCommand;
 use crate::domains::crawl::{CrawlRepo, CrawlService, CrawlServiceTrait, CrawlTaskManager};
 use crate::domains::device::{DeviceService, DeviceServiceTrait};
 use crate::domains::file::{FileService, FileServiceTrait};
+use crate::domains::health::{HealthService, HealthServiceTrait};
 use crate::domains::luna::{
-    infra::impl_service::file::FileService as LunaFileService, infra::RecordRepo, LunaService,
-    LunaServiceTrait,
+    infra::impl_service::file::FileService as LunaFileService, infra::RecordRepo, LinkCheckJob,
+    LunaService, LunaServiceTrait, MediaGcJob,
 };
 use crate::domains::search::{SearchService, SearchServiceTrait};
 use crate::domains::user::{InteractionRepo, InteractionRepository, UserServiceTrait};
@@ -27,13 +31,22 @@ pub fn build_app_state(pool: &DatabaseConnection, config: Config) -> AppState {
         FileService::create_service(config.clone(), pool.clone());
     let user_service: Arc<dyn UserServiceTrait> =
         UserService::create_service(pool.clone(), Arc::clone(&file_service));
-    let auth_service: Arc<dyn AuthServiceTrait> =
-        AuthService::create_service(pool.clone(), Arc::clone(&user_service));
     let device_service: Arc<dyn DeviceServiceTrait> = DeviceService::create_service(pool.clone());
+    let auth_service: Arc<dyn AuthServiceTrait> = AuthService::create_service(
+        &config,
+        pool.clone(),
+        Arc::clone(&user_service),
+        Arc::clone(&device_service),
+    );
     let luna_service: Arc<dyn LunaServiceTrait> =
         LunaService::create_service(config.clone(), pool.clone());
     let search_service: Arc<dyn SearchServiceTrait> =
         SearchService::create_service(config.clone(), pool.clone());
+    let audit_service: Arc<dyn AuditServiceTrait> = AuditService::create_service(pool.clone());
+    let backup_service: Arc<dyn BackupServiceTrait> =
+        BackupService::create_service(pool.clone(), config.clone());
+    let health_service: Arc<dyn HealthServiceTrait> =
+        HealthService::create_service(pool.clone(), config.clone());
 
     // Crawl service wiring
     let interaction_repo: Arc<dyn InteractionRepository + Send + Sync> = Arc::new(InteractionRepo);
@@ -45,7 +58,7 @@ pub fn build_app_state(pool: &DatabaseConnection, config: Config) -> AppState {
         Arc::new(CrawlRepo);
 
     let luna_file_service: Arc<dyn crate::domains::luna::FileServiceTrait + Send + Sync> =
-        Arc::new(LunaFileService::new(config.clone()));
+        Arc::new(LunaFileService::new(config.clone(), pool.clone()));
 
     let (broadcast_tx, _) = broadcast::channel(1024);
     let (runner_tx, runner_rx) = std::sync::mpsc::channel::<RunnerCommand>();
@@ -131,6 +144,14 @@ pub fn build_app_state(pool: &DatabaseConnection, config: Config) -> AppState {
         })
         .expect("Failed to spawn crawl-runner thread");
 
+    let job_runner = Arc::new(JobRunner::new(
+        pool.clone(),
+        vec![
+            Arc::new(MediaGcJob::new(Arc::clone(&luna_service), &config)),
+            Arc::new(LinkCheckJob::new(Arc::clone(&luna_service), &config)),
+        ],
+    ));
+
     AppState::new(
         config,
         auth_service,
@@ -140,6 +161,10 @@ pub fn build_app_state(pool: &DatabaseConnection, config: Config) -> AppState {
         luna_service,
         search_service,
         crawl_service_trait,
+        audit_service,
+        backup_service,
+        health_service,
+        job_runner,
     )
 }
 
@@ -0,0 +1,255 @@
+//! Read-through cache for hot lookups.
+//!
+//! Values are stored as JSON under string keys, in process with moka or, with
+//! the `redis` feature and `REDIS_URL` set, in Redis so several instances share
+//! one cache. Callers invalidate the keys a write affects once it commits;
+//! `CACHE_TTL_SECS` bounds how long a value missed by invalidation can live,
+//! and `0` disables caching. A failing backend only costs the cache: lookups
+//! fall through to the loader.
+
+use std::{future::Future, time::Duration};
+
+use moka::future::Cache;
+use serde::{de::DeserializeOwned, Serialize};
+
+use super::{config::Config, error::AppError};
+
+/// Prefix of every Redis key, so the cache can share a Redis database.
+#[cfg(feature = "redis")]
+const REDIS_KEY_PREFIX: &str = "lunirelust:";
+
+enum Backend {
+    Disabled,
+    Memory(Cache<String, String>),
+    #[cfg(feature = "redis")]
+    Redis {
+        client: redis::Client,
+        /// Connected on first use, so startup does not wait for Redis.
+        conn: tokio::sync::OnceCell<redis::aio::MultiplexedConnection>,
+        ttl: Duration,
+    },
+}
+
+/// Shared cache in front of the database.
+pub struct CacheService {
+    backend: Backend,
+}
+
+impl CacheService {
+    /// Builds the backend `config` selects.
+    pub fn new(config: &Config) -> Self {
+        if config.cache_ttl_secs == 0 {
+            return Self::disabled();
+        }
+        let ttl = Duration::from_secs(config.cache_ttl_secs);
+
+        #[cfg(feature = "redis")]
+        if let Some(url) = &config.redis_url {
+            match redis::Client::open(url.as_str()) {
+                Ok(client) => {
+                    return Self {
+                        backend: Backend::Redis {
+                            client,
+                            conn: tokio::sync::OnceCell::new(),
+                            ttl,
+                        },
+                    }
+                }
+                Err(err) => {
+                    tracing::warn!("Invalid REDIS_URL, caching in process instead: {err}");
+                }
+            }
+        }
+
+        Self::memory(ttl, config.cache_max_entries)
+    }
+
+    /// In-process cache holding at most `max_entries` values for `ttl`.
+    pub fn memory(ttl: Duration, max_entries: u64) -> Self {
+        Self {
+            backend: Backend::Memory(
+                Cache::builder()
+                    .max_capacity(max_entries)
+                    .time_to_live(ttl)
+                    .build(),
+            ),
+        }
+    }
+
+    /// A cache that stores nothing.
+    pub fn disabled() -> Self {
+        Self {
+            backend: Backend::Disabled,
+        }
+    }
+
+    /// Returns the value cached under `key`, or loads, caches and returns it.
+    ///
+    /// Errors from `load` are returned as-is and never cached.
+    pub async fn get_or_load<T, F, Fut>(&self, key: &str, load: F) -> Result<T, AppError>
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T, AppError>>,
+    {
+        if let Some(json) = self.get(key).await {
+            match serde_json::from_str(&json) {
+                Ok(value) => return Ok(value),
+                Err(err) => tracing::warn!("Discarding unreadable cache entry {key}: {err}"),
+            }
+        }
+
+        let value = load().await?;
+        match serde_json::to_string(&value) {
+            Ok(json) => self.set(key, json).await,
+            Err(err) => tracing::warn!("Failed to serialize cache entry {key}: {err}"),
+        }
+        Ok(value)
+    }
+
+    /// Drops `keys`, so the next lookups reload them.
+    pub async fn invalidate(&self, keys: &[String]) {
+        match &self.backend {
+            Backend::Disabled => {}
+            Backend::Memory(cache) => {
+                for key in keys {
+                    cache.invalidate(key).await;
+                }
+            }
+            #[cfg(feature = "redis")]
+            Backend::Redis { .. } => {
+                use redis::AsyncCommands as _;
+
+                if keys.is_empty() {
+                    return;
+                }
+                let Some(mut conn) = self.redis_connection().await else {
+                    return;
+                };
+                let keys: Vec<String> = keys
+                    .iter()
+                    .map(|key| format!("{REDIS_KEY_PREFIX}{key}"))
+                    .collect();
+                let deleted: redis::RedisResult<()> = conn.del(keys).await;
+                if let Err(err) = deleted {
+                    tracing::warn!("Failed to invalidate Redis cache entries: {err}");
+                }
+            }
+        }
+    }
+
+    async fn get(&self, key: &str) -> Option<String> {
+        match &self.backend {
+            Backend::Disabled => None,
+            Backend::Memory(cache) => cache.get(key).await,
+            #[cfg(feature = "redis")]
+            Backend::Redis { .. } => {
+                use redis::AsyncCommands as _;
+
+                let mut conn = self.redis_connection().await?;
+                conn.get(format!("{REDIS_KEY_PREFIX}{key}"))
+                    .await
+                    .inspect_err(|err| tracing::warn!("Failed to read Redis cache: {err}"))
+                    .ok()
+                    .flatten()
+            }
+        }
+    }
+
+    async fn set(&self, key: &str, json: String) {
+        match &self.backend {
+            Backend::Disabled => {}
+            Backend::Memory(cache) => cache.insert(key.to_owned(), json).await,
+            #[cfg(feature = "redis")]
+            Backend::Redis { ttl, .. } => {
+                use redis::AsyncCommands as _;
+
+                let Some(mut conn) = self.redis_connection().await else {
+                    return;
+                };
+                let written: redis::RedisResult<()> = conn
+                    .set_ex(format!("{REDIS_KEY_PREFIX}{key}"), json, ttl.as_secs())
+                    .await;
+                if let Err(err) = written {
+                    tracing::warn!("Failed to write Redis cache: {err}");
+                }
+            }
+        }
+    }
+
+    /// The shared Redis connection, opened on first use; `None` while Redis
+    /// is unreachable.
+    #[cfg(feature = "redis")]
+    async fn redis_connection(&self) -> Option<redis::aio::MultiplexedConnection> {
+        let Backend::Redis { client, conn, .. } = &self.backend else {
+            return None;
+        };
+        conn.get_or_try_init(|| client.get_multiplexed_async_connection())
+            .await
+            .inspect_err(|err| tracing::warn!("Failed to connect to Redis cache: {err}"))
+            .ok()
+            .cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::CacheService;
+    use crate::common::error::AppError;
+
+    #[tokio::test]
+    async fn memory_cache_serves_hits_until_invalidated() {
+        let cache = CacheService::memory(Duration::from_secs(60), 100);
+        let key = "director:1".to_owned();
+
+        let first: u32 = cache
+            .get_or_load(&key, || async { Ok(1) })
+            .await
+            .expect("load succeeds");
+        let hit: u32 = cache
+            .get_or_load(&key, || async { Ok(2) })
+            .await
+            .expect("load succeeds");
+        assert_eq!((first, hit), (1, 1));
+
+        cache.invalidate(std::slice::from_ref(&key)).await;
+        let reloaded: u32 = cache
+            .get_or_load(&key, || async { Ok(3) })
+            .await
+            .expect("load succeeds");
+        assert_eq!(reloaded, 3);
+    }
+
+    #[tokio::test]
+    async fn errors_are_not_cached() {
+        let cache = CacheService::memory(Duration::from_secs(60), 100);
+        let failed: Result<u32, _> = cache
+            .get_or_load("genre:all", || async {
+                Err(AppError::NotFound("Genre not found".into()))
+            })
+            .await;
+        assert!(failed.is_err());
+
+        let loaded: u32 = cache
+            .get_or_load("genre:all", || async { Ok(7) })
+            .await
+            .expect("load succeeds");
+        assert_eq!(loaded, 7);
+    }
+
+    #[tokio::test]
+    async fn disabled_cache_always_loads() {
+        let cache = CacheService::disabled();
+        let _first: u32 = cache
+            .get_or_load("k", || async { Ok(1) })
+            .await
+            .expect("load succeeds");
+        let second: u32 = cache
+            .get_or_load("k", || async { Ok(2) })
+            .await
+            .expect("load succeeds");
+        assert_eq!(second, 2);
+    }
+}
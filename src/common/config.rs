@@ -1,10 +1,17 @@
 use migration::{Migrator, MigratorTrait as _};
 use regex::Regex;
 use sea_orm::{ConnectOptions, DatabaseConnection};
+use std::collections::HashMap;
 use std::env;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 use std::time::Duration;
+use thiserror::Error;
 use tokio::time::sleep;
 
+use super::{access_log::AccessLogFormat, rate_limit::RateLimit};
+
 /// Default page size for all paginated list endpoints.
 /// Used when no `limit` query parameter is provided.
 pub const DEFAULT_PAGE_SIZE: u64 = 20;
@@ -12,9 +19,121 @@ pub const DEFAULT_PAGE_SIZE: u64 = 20;
 /// Default maximum file upload size (50 MB).
 pub const DEFAULT_MAX_FILE_SIZE: usize = 50 * 1024 * 1024;
 
-/// Database connection/acquire timeout in seconds.
+/// Default maximum video upload size (2 GB).
+pub const DEFAULT_MAX_VIDEO_SIZE: usize = 2 * 1024 * 1024 * 1024;
+
+/// Default thumbnail widths and heights, in pixels. Requested sizes are
+/// rounded up to one of these so the thumbnail cache stays bounded.
+const DEFAULT_THUMBNAIL_SIZES: &[&str] = &["160", "320", "640"];
+
+/// Default thumbnail width linked from list views such as the feeds.
+const DEFAULT_THUMBNAIL_LIST_SIZE: u32 = 320;
+
+/// Default lifetime of media in browser and CDN caches, in seconds.
+const DEFAULT_MEDIA_CACHE_MAX_AGE_SECS: u64 = 86_400;
+
+/// Default number of media uploads handled at once.
+const DEFAULT_UPLOAD_MAX_CONCURRENT: usize = 4;
+
+/// Default lifetime of presigned media URLs, in seconds.
+const DEFAULT_MEDIA_PRESIGN_EXPIRY_SECS: u64 = 900;
+
+/// Default region of the S3 media bucket.
+const DEFAULT_S3_REGION: &str = "us-east-1";
+
+/// Default timeout of a request to a metadata provider, in seconds.
+const DEFAULT_METADATA_TIMEOUT_SECS: u64 = 10;
+
+/// Default number of links the link checker requests at once.
+const DEFAULT_LINK_CHECK_CONCURRENCY: usize = 8;
+
+/// Default time a checked link has to answer, in seconds.
+const DEFAULT_LINK_CHECK_TIMEOUT_SECS: u64 = 10;
+
+/// Default number of links one scheduled check visits.
+const DEFAULT_LINK_CHECK_BATCH_SIZE: u64 = 500;
+
+/// Default request body limit for non-multipart requests (1 MB).
+pub const DEFAULT_JSON_BODY_LIMIT: usize = 1024 * 1024;
+
+/// Default port of the gRPC server.
+const DEFAULT_GRPC_PORT: &str = "50051";
+
+/// Default per-IP limit on `/auth` requests.
+const DEFAULT_AUTH_IP_RATE_LIMIT: RateLimit = RateLimit {
+    burst: 20,
+    per_minute: 10,
+};
+
+/// Default per-account limit on login attempts.
+const DEFAULT_AUTH_ACCOUNT_RATE_LIMIT: RateLimit = RateLimit {
+    burst: 10,
+    per_minute: 5,
+};
+
+/// Default per-user (or per-IP) limit on read requests.
+const DEFAULT_RATE_LIMIT_READ: RateLimit = RateLimit {
+    burst: 300,
+    per_minute: 600,
+};
+
+/// Default per-user (or per-IP) limit on write requests.
+const DEFAULT_RATE_LIMIT_WRITE: RateLimit = RateLimit {
+    burst: 120,
+    per_minute: 240,
+};
+
+/// Default per-user (or per-IP) limit on multipart uploads.
+const DEFAULT_RATE_LIMIT_UPLOAD: RateLimit = RateLimit {
+    burst: 20,
+    per_minute: 30,
+};
+
+/// Default number of consecutive failed logins that locks an account.
+const DEFAULT_AUTH_MAX_FAILED_LOGINS: u32 = 5;
+
+/// Default account lockout duration in minutes.
+const DEFAULT_AUTH_LOCKOUT_MINUTES: i64 = 15;
+
+/// Default methods allowed on cross-origin requests.
+const DEFAULT_CORS_METHODS: &[&str] = &["GET", "POST", "PUT", "PATCH", "DELETE"];
+
+/// Default request headers allowed on cross-origin requests.
+const DEFAULT_CORS_HEADERS: &[&str] = &[
+    "authorization",
+    "content-type",
+    "x-api-key",
+    "if-match",
+    "if-none-match",
+];
+
+/// Database connection timeout in seconds.
 const DB_CONNECT_TIMEOUT_SECS: u64 = 30;
 
+/// Default time to wait for a free pooled connection, in seconds.
+const DEFAULT_DB_ACQUIRE_TIMEOUT_SECS: u64 = 30;
+
+/// Default threshold above which a query is logged as slow, in milliseconds.
+const DEFAULT_DB_SLOW_QUERY_MS: u64 = 1000;
+
+/// Default lifetime of cached lookups in seconds.
+const DEFAULT_CACHE_TTL_SECS: u64 = 300;
+
+/// Default number of values kept by the in-process cache.
+const DEFAULT_CACHE_MAX_ENTRIES: u64 = 10_000;
+
+/// Default similar-records score per shared idol.
+const DEFAULT_SIMILAR_IDOL_WEIGHT: f64 = 3.0;
+
+/// Default similar-records score per shared genre.
+const DEFAULT_SIMILAR_GENRE_WEIGHT: f64 = 1.0;
+
+/// Default similar-records score for a shared series.
+const DEFAULT_SIMILAR_SERIES_WEIGHT: f64 = 4.0;
+
+/// Default similar-records score for a shared studio.
+const DEFAULT_SIMILAR_STUDIO_WEIGHT: f64 = 2.0;
+
 /// Database idle timeout in seconds.
 const DB_IDLE_TIMEOUT_SECS: u64 = 600;
 
@@ -27,9 +146,17 @@ pub struct Config {
     pub database_url: String,
     pub database_max_connections: u32,
     pub database_min_connections: u32,
+    /// How long a request waits for a free pooled connection.
+    pub database_acquire_timeout_secs: u64,
+    /// Server-side `statement_timeout` of every connection; 0 disables it.
+    pub database_statement_timeout_ms: u64,
+    /// Queries slower than this are logged as warnings; 0 disables the log.
+    pub database_slow_query_ms: u64,
 
     pub service_host: String,
     pub service_port: String,
+    /// Port of the gRPC server started with the `grpc` feature.
+    pub grpc_port: String,
 
     pub assets_public_path: String,
     pub assets_public_url: String,
@@ -39,9 +166,50 @@ pub struct Config {
 
     pub asset_allowed_extensions_pattern: Regex,
     pub asset_allowed_extensions: Vec<String>,
+    /// Largest single uploaded file, in bytes.
     pub asset_max_size: usize,
+    /// Largest uploaded video, in bytes; also the request body limit of
+    /// video uploads.
+    pub video_max_size: usize,
+
+    /// Sizes thumbnails are generated at, ascending; requested widths and
+    /// heights round up to the next one.
+    pub thumbnail_sizes: Vec<u32>,
+    /// Thumbnail width linked from list views, and generated on upload with
+    /// the `thumbnails` feature.
+    pub thumbnail_list_size: u32,
+    /// `max-age` of served media; caches revalidate with `ETag` and
+    /// `Last-Modified` afterwards.
+    pub media_cache_max_age_secs: u64,
+    /// Backend the media files are kept in.
+    pub media_storage: MediaStorageConfig,
+    /// Seconds between scheduled orphaned-media collections; 0 disables them.
+    pub media_gc_interval_secs: u64,
+    /// Let scheduled collections delete what they find instead of only
+    /// logging it.
+    pub media_gc_delete: bool,
+    /// Seconds between scheduled link health checks; 0 disables them.
+    pub link_check_interval_secs: u64,
+    /// Links requested at once by a health check.
+    pub link_check_concurrency: usize,
+    /// Seconds a link has to answer before it counts as dead.
+    pub link_check_timeout_secs: u64,
+    /// Most links one scheduled check visits, least recently checked first.
+    pub link_check_batch_size: u64,
+
+    /// Request body limit for JSON and other non-multipart requests.
+    pub json_body_limit: usize,
+    /// Request body limit for multipart uploads; defaults to `asset_max_size`.
+    pub upload_body_limit: usize,
+    /// Media uploads streamed at once; further uploads get 429.
+    pub upload_max_concurrent: usize,
+    /// Re-encode uploaded images to drop EXIF and other metadata; needs the
+    /// `thumbnails` feature and is ignored without it.
+    pub upload_strip_metadata: bool,
+    /// Compress JSON responses with gzip or brotli when the client accepts it.
+    pub response_compression: bool,
 
-    pub cors_origins: Vec<String>,
+    pub cors: CorsConfig,
 
     // MeiliSearch configuration
     pub meili_url: String,
@@ -51,84 +219,611 @@ pub struct Config {
     pub vllm_embedding_url: String,
     pub vllm_embedding_model: String,
     pub vllm_embedding_timeout_secs: u64,
+
+    // Auth rate limiting and lockout
+    pub auth_ip_rate_limit: RateLimit,
+    pub auth_account_rate_limit: RateLimit,
+    pub auth_max_failed_logins: u32,
+    pub auth_lockout_minutes: i64,
+    /// Key material for encrypting TOTP secrets; falls back to `JWT_SECRET_KEY`.
+    pub totp_encryption_key: String,
+
+    // Request rate limiting, by user or client IP
+    pub rate_limit_read: RateLimit,
+    pub rate_limit_write: RateLimit,
+    pub rate_limit_upload: RateLimit,
+    /// Take the client IP from `X-Forwarded-For`; only enable behind a trusted proxy.
+    pub trust_forwarded_for: bool,
+
+    /// Format of the per-request access log lines.
+    pub access_log_format: AccessLogFormat,
+
+    /// Lifetime of cached lookups in seconds; 0 disables the cache.
+    pub cache_ttl_secs: u64,
+    /// Number of values kept by the in-process cache.
+    pub cache_max_entries: u64,
+    /// Redis server shared by all instances, used with the `redis` feature
+    /// instead of the in-process cache.
+    pub redis_url: Option<String>,
+
+    /// Scoring of `GET /cards/records/{id}/similar`.
+    pub similarity_weights: SimilarityWeights,
+
+    /// External sources of record metadata, used with the `metadata` feature.
+    pub metadata: MetadataConfig,
+}
+
+/// Cross-origin resource sharing settings applied by the router.
+#[derive(Clone, Debug, Default)]
+pub struct CorsConfig {
+    /// Origins allowed to make cross-origin requests; `*` allows any origin.
+    /// When empty, all cross-origin requests are rejected.
+    pub allowed_origins: Vec<String>,
+    /// Methods allowed on cross-origin requests; `*` allows any method.
+    pub allowed_methods: Vec<String>,
+    /// Request headers allowed on cross-origin requests; `*` allows any header.
+    pub allowed_headers: Vec<String>,
+    /// Whether browsers may send cookies and `Authorization` credentials.
+    /// Cannot be combined with a `*` origin, method or header.
+    pub allow_credentials: bool,
+}
+
+impl CorsConfig {
+    /// Reads `CORS_ORIGINS`, `CORS_ALLOWED_METHODS`, `CORS_ALLOWED_HEADERS` and
+    /// `CORS_ALLOW_CREDENTIALS`, each list being comma separated.
+    pub fn from_source(source: &ConfigSource) -> Result<Self, ConfigError> {
+        Ok(Self {
+            allowed_origins: source.list("CORS_ORIGINS", &[]),
+            allowed_methods: source.list("CORS_ALLOWED_METHODS", DEFAULT_CORS_METHODS),
+            allowed_headers: source.list("CORS_ALLOWED_HEADERS", DEFAULT_CORS_HEADERS),
+            allow_credentials: source.flag("CORS_ALLOW_CREDENTIALS", false)?,
+        })
+    }
+}
+
+/// Backend of [`MediaStorageConfig`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MediaStorageKind {
+    /// Files under `assets_private_path`.
+    #[default]
+    Local,
+    /// Objects in an S3-compatible bucket; needs the `s3` feature.
+    #[cfg(feature = "s3")]
+    S3,
+}
+
+/// Where media files are kept and how clients fetch them.
+#[derive(Clone, Debug, Default)]
+pub struct MediaStorageConfig {
+    pub kind: MediaStorageKind,
+    pub s3_bucket: String,
+    pub s3_region: String,
+    /// Endpoint of an S3-compatible service such as MinIO; AWS when unset.
+    pub s3_endpoint: Option<String>,
+    pub s3_access_key_id: String,
+    pub s3_secret_access_key: String,
+    /// Prefix of every object key, so the bucket can be shared.
+    pub s3_prefix: String,
+    /// Address the bucket as `endpoint/bucket`, as MinIO expects.
+    pub s3_force_path_style: bool,
+    /// Redirect media requests to presigned URLs instead of proxying the
+    /// bytes, when the backend supports them.
+    pub presigned_urls: bool,
+    /// Lifetime of presigned URLs.
+    pub presign_expiry_secs: u64,
+}
+
+impl MediaStorageConfig {
+    /// Reads `MEDIA_STORAGE` (`local` or `s3`), `MEDIA_PRESIGNED_URLS`,
+    /// `MEDIA_PRESIGN_EXPIRY_SECS` and, for `s3`, `S3_BUCKET`, `S3_REGION`,
+    /// `S3_ENDPOINT`, `S3_ACCESS_KEY_ID`, `S3_SECRET_ACCESS_KEY`, `S3_PREFIX`
+    /// and `S3_FORCE_PATH_STYLE`.
+    pub fn from_source(source: &ConfigSource) -> Result<Self, ConfigError> {
+        let kind = match source.get("MEDIA_STORAGE") {
+            None => MediaStorageKind::Local,
+            Some(value) => match value.trim().to_ascii_lowercase().as_str() {
+                "local" => MediaStorageKind::Local,
+                #[cfg(feature = "s3")]
+                "s3" => MediaStorageKind::S3,
+                #[cfg(not(feature = "s3"))]
+                "s3" => {
+                    return Err(ConfigError::Invalid {
+                        key: "MEDIA_STORAGE".to_owned(),
+                        reason: "the s3 backend needs the `s3` feature".to_owned(),
+                        value,
+                    })
+                }
+                _ => {
+                    return Err(ConfigError::Invalid {
+                        key: "MEDIA_STORAGE".to_owned(),
+                        reason: "expected local or s3".to_owned(),
+                        value,
+                    })
+                }
+            },
+        };
+        let s3 = kind != MediaStorageKind::Local;
+        let s3_setting = |key: &str| {
+            if s3 {
+                source.required(key)
+            } else {
+                Ok(source.get(key).unwrap_or_default())
+            }
+        };
+        Ok(Self {
+            kind,
+            s3_bucket: s3_setting("S3_BUCKET")?,
+            s3_region: source.string_or("S3_REGION", DEFAULT_S3_REGION),
+            s3_endpoint: source.get("S3_ENDPOINT"),
+            s3_access_key_id: s3_setting("S3_ACCESS_KEY_ID")?,
+            s3_secret_access_key: s3_setting("S3_SECRET_ACCESS_KEY")?,
+            s3_prefix: source.string_or("S3_PREFIX", ""),
+            s3_force_path_style: source.flag("S3_FORCE_PATH_STYLE", false)?,
+            presigned_urls: source.flag("MEDIA_PRESIGNED_URLS", false)?,
+            presign_expiry_secs: source.parse_or(
+                "MEDIA_PRESIGN_EXPIRY_SECS",
+                DEFAULT_MEDIA_PRESIGN_EXPIRY_SECS,
+            )?,
+        })
+    }
+}
+
+/// Score a record earns towards being similar to another for each kind of
+/// metadata they share.
+#[derive(Clone, Copy, Debug)]
+pub struct SimilarityWeights {
+    /// Per idol featured in both records.
+    pub idol: f64,
+    /// Per genre tagged on both records.
+    pub genre: f64,
+    /// When both records belong to the same series.
+    pub series: f64,
+    /// When both records come from the same studio.
+    pub studio: f64,
+}
+
+impl Default for SimilarityWeights {
+    fn default() -> Self {
+        Self {
+            idol: DEFAULT_SIMILAR_IDOL_WEIGHT,
+            genre: DEFAULT_SIMILAR_GENRE_WEIGHT,
+            series: DEFAULT_SIMILAR_SERIES_WEIGHT,
+            studio: DEFAULT_SIMILAR_STUDIO_WEIGHT,
+        }
+    }
+}
+
+impl SimilarityWeights {
+    /// Reads `SIMILAR_IDOL_WEIGHT`, `SIMILAR_GENRE_WEIGHT`,
+    /// `SIMILAR_SERIES_WEIGHT` and `SIMILAR_STUDIO_WEIGHT`; 0 ignores that
+    /// kind of metadata.
+    pub fn from_source(source: &ConfigSource) -> Result<Self, ConfigError> {
+        Ok(Self {
+            idol: source.parse_or("SIMILAR_IDOL_WEIGHT", DEFAULT_SIMILAR_IDOL_WEIGHT)?,
+            genre: source.parse_or("SIMILAR_GENRE_WEIGHT", DEFAULT_SIMILAR_GENRE_WEIGHT)?,
+            series: source.parse_or("SIMILAR_SERIES_WEIGHT", DEFAULT_SIMILAR_SERIES_WEIGHT)?,
+            studio: source.parse_or("SIMILAR_STUDIO_WEIGHT", DEFAULT_SIMILAR_STUDIO_WEIGHT)?,
+        })
+    }
+}
+
+/// An external source of record metadata.
+#[derive(Clone, Debug)]
+pub struct MetadataProviderConfig {
+    /// Name reported with the metadata it supplies.
+    pub name: String,
+    /// URL fetched for a record, with `{id}` replaced by the record ID.
+    pub url: String,
+    /// Record IDs this provider knows about; others are not sent to it.
+    pub id_pattern: Regex,
+}
+
+/// Where `POST /cards/records/{id}/refresh-metadata` looks up records.
+#[derive(Clone, Debug, Default)]
+pub struct MetadataConfig {
+    /// Providers in the order they are asked; earlier ones win per field.
+    pub providers: Vec<MetadataProviderConfig>,
+    /// Timeout of each provider request.
+    pub timeout_secs: u64,
+}
+
+impl MetadataConfig {
+    /// Reads the provider names from `METADATA_PROVIDERS` (comma separated)
+    /// and, for each name, `METADATA_PROVIDER_<NAME>_URL` and the optional
+    /// `METADATA_PROVIDER_<NAME>_ID_PATTERN` regex, plus
+    /// `METADATA_TIMEOUT_SECS`.
+    pub fn from_source(source: &ConfigSource) -> Result<Self, ConfigError> {
+        let providers = source
+            .list("METADATA_PROVIDERS", &[])
+            .into_iter()
+            .map(|name| {
+                let prefix = format!("METADATA_PROVIDER_{}", name.to_uppercase());
+                let pattern_key = format!("{prefix}_ID_PATTERN");
+                let pattern = source.string_or(&pattern_key, ".*");
+                let id_pattern = Regex::new(&format!("^(?:{pattern})$")).map_err(|err| {
+                    ConfigError::Invalid {
+                        key: pattern_key,
+                        reason: err.to_string(),
+                        value: pattern,
+                    }
+                })?;
+                Ok(MetadataProviderConfig {
+                    url: source.required(&format!("{prefix}_URL"))?,
+                    name,
+                    id_pattern,
+                })
+            })
+            .collect::<Result<Vec<_>, ConfigError>>()?;
+        Ok(Self {
+            providers,
+            timeout_secs: source
+                .parse_or("METADATA_TIMEOUT_SECS", DEFAULT_METADATA_TIMEOUT_SECS)?,
+        })
+    }
+}
+
+/// Error raised when the configuration cannot be loaded.
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("Failed to read config file {path}: {reason}")]
+    File { path: String, reason: String },
+
+    #[error("Missing required setting {0}")]
+    Missing(String),
+
+    #[error("Invalid value {value:?} for {key}: {reason}")]
+    Invalid {
+        key: String,
+        value: String,
+        reason: String,
+    },
+}
+
+/// Settings layered from the environment over an optional config file.
+///
+/// Keys are environment variable names. A TOML or YAML file provides the
+/// same settings in lower case, and nested tables join their keys with `_`,
+/// so `[rate_limit] read_burst = 300` sets `RATE_LIMIT_READ_BURST`. Arrays
+/// stand for comma separated lists. Environment variables override the file,
+/// which overrides the built-in defaults.
+#[derive(Clone, Debug, Default)]
+pub struct ConfigSource {
+    file: HashMap<String, String>,
+}
+
+impl ConfigSource {
+    /// Loads the config file at `path`, or at `CONFIG_FILE` when `path` is
+    /// `None`. Without either, only the environment is read.
+    pub fn load(path: Option<&Path>) -> Result<Self, ConfigError> {
+        let path = match path {
+            Some(path) => Some(path.to_path_buf()),
+            None => env::var_os("CONFIG_FILE").map(PathBuf::from),
+        };
+        let Some(path) = path else {
+            return Ok(Self::default());
+        };
+
+        let file_error = |reason: String| ConfigError::File {
+            path: path.display().to_string(),
+            reason,
+        };
+        let contents = std::fs::read_to_string(&path).map_err(|e| file_error(e.to_string()))?;
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_ascii_lowercase);
+        let root: serde_json::Value = match extension.as_deref() {
+            Some("toml") => toml::from_str(&contents).map_err(|e| file_error(e.to_string()))?,
+            Some("yaml" | "yml") => {
+                serde_yaml::from_str(&contents).map_err(|e| file_error(e.to_string()))?
+            }
+            _ => {
+                return Err(file_error(
+                    "expected a .toml, .yaml or .yml file".to_owned(),
+                ))
+            }
+        };
+
+        let mut file = HashMap::new();
+        flatten_into(&mut file, "", &root);
+        Ok(Self { file })
+    }
+
+    /// The value of `key`, from the environment first and the file second.
+    pub fn get(&self, key: &str) -> Option<String> {
+        env::var(key).ok().or_else(|| self.file.get(key).cloned())
+    }
+
+    fn required(&self, key: &str) -> Result<String, ConfigError> {
+        self.get(key)
+            .ok_or_else(|| ConfigError::Missing(key.to_owned()))
+    }
+
+    fn string_or(&self, key: &str, default: &str) -> String {
+        self.get(key).unwrap_or_else(|| default.to_owned())
+    }
+
+    /// Parses `key`, falling back to `default` when unset and failing with
+    /// the key's name when the value does not parse.
+    fn parse_or<T>(&self, key: &str, default: T) -> Result<T, ConfigError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        self.get(key).map_or(Ok(default), |value| {
+            value
+                .trim()
+                .parse()
+                .map_err(|err: T::Err| ConfigError::Invalid {
+                    key: key.to_owned(),
+                    reason: err.to_string(),
+                    value,
+                })
+        })
+    }
+
+    fn flag(&self, key: &str, default: bool) -> Result<bool, ConfigError> {
+        self.get(key).map_or(Ok(default), |value| {
+            match value.trim().to_ascii_lowercase().as_str() {
+                "true" => Ok(true),
+                "false" => Ok(false),
+                _ => Err(ConfigError::Invalid {
+                    key: key.to_owned(),
+                    reason: "expected true or false".to_owned(),
+                    value,
+                }),
+            }
+        })
+    }
+
+    /// Reads a comma separated list from `key`, falling back to `default` when unset.
+    fn list(&self, key: &str, default: &[&str]) -> Vec<String> {
+        self.get(key).map_or_else(
+            || default.iter().map(|&v| v.to_owned()).collect(),
+            |s| {
+                s.split(',')
+                    .map(str::trim)
+                    .filter(|v| !v.is_empty())
+                    .map(str::to_owned)
+                    .collect()
+            },
+        )
+    }
+
+    /// Reads a comma separated list of positive numbers from `key`, sorted
+    /// and deduplicated, falling back to `default` when unset.
+    fn sizes(&self, key: &str, default: &[&str]) -> Result<Vec<u32>, ConfigError> {
+        let mut sizes = self
+            .list(key, default)
+            .into_iter()
+            .map(|value| match value.parse::<u32>() {
+                Ok(size) if size > 0 => Ok(size),
+                _ => Err(ConfigError::Invalid {
+                    key: key.to_owned(),
+                    reason: "expected positive integers".to_owned(),
+                    value,
+                }),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if sizes.is_empty() {
+            return Err(ConfigError::Invalid {
+                key: key.to_owned(),
+                value: String::new(),
+                reason: "expected at least one size".to_owned(),
+            });
+        }
+        sizes.sort_unstable();
+        sizes.dedup();
+        Ok(sizes)
+    }
+
+    /// Reads a [`RateLimit`] from `<prefix>_BURST` and `<prefix>_PER_MINUTE`,
+    /// falling back to `default` for missing values.
+    fn rate_limit(&self, prefix: &str, default: RateLimit) -> Result<RateLimit, ConfigError> {
+        Ok(RateLimit {
+            burst: self.parse_or(&format!("{prefix}_BURST"), default.burst)?,
+            per_minute: self.parse_or(&format!("{prefix}_PER_MINUTE"), default.per_minute)?,
+        })
+    }
+}
+
+/// Flattens a parsed config file into upper case, `_` joined keys.
+fn flatten_into(out: &mut HashMap<String, String>, prefix: &str, value: &serde_json::Value) {
+    use serde_json::Value;
+
+    let scalar = |value: &Value| match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    match value {
+        Value::Object(table) => {
+            for (key, value) in table {
+                let key = if prefix.is_empty() {
+                    key.to_uppercase()
+                } else {
+                    format!("{prefix}_{}", key.to_uppercase())
+                };
+                flatten_into(out, &key, value);
+            }
+        }
+        Value::Array(items) => {
+            let items: Vec<String> = items.iter().map(scalar).collect();
+            out.insert(prefix.to_owned(), items.join(","));
+        }
+        Value::Null => {}
+        other => {
+            out.insert(prefix.to_owned(), scalar(other));
+        }
+    }
 }
 
-/// `from_env` reads the environment variables and returns a Config struct.
-/// It uses the dotenv crate to load environment variables from a .env file if it exists.
-/// It returns a Result with the Config struct or an error if any of the environment variables are missing.
 impl Config {
-    pub fn from_env() -> Result<Self, env::VarError> {
+    /// Loads the configuration from the environment, layered over the file
+    /// named by `CONFIG_FILE` if set.
+    /// It uses the dotenv crate to load environment variables from a .env file if it exists.
+    pub fn from_env() -> Result<Self, ConfigError> {
+        Self::load(None)
+    }
+
+    /// Loads the configuration from built-in defaults, then the config file
+    /// at `path` (or `CONFIG_FILE`), then environment variables.
+    ///
+    /// Fails with the name of the first setting that is missing or invalid.
+    pub fn load(path: Option<&Path>) -> Result<Self, ConfigError> {
         dotenvy::dotenv().ok();
+        Self::from_source(&ConfigSource::load(path)?)
+    }
 
-        let ext_val = env::var("ASSET_ALLOWED_EXTENSIONS")?;
+    /// Builds the configuration from an already loaded [`ConfigSource`].
+    pub fn from_source(source: &ConfigSource) -> Result<Self, ConfigError> {
+        // Extensions may be separated by `|` (the historical form) or `,`.
+        let asset_allowed_extensions: Vec<String> = source
+            .required("ASSET_ALLOWED_EXTENSIONS")?
+            .split(['|', ','])
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::to_lowercase)
+            .collect();
+        let ext_val = asset_allowed_extensions.join("|");
+        let asset_allowed_extensions_pattern = Regex::new(&format!(r"(?i)^.*\.({ext_val})$"))
+            .map_err(|err| ConfigError::Invalid {
+                key: "ASSET_ALLOWED_EXTENSIONS".to_owned(),
+                value: ext_val.clone(),
+                reason: err.to_string(),
+            })?;
 
-        let asset_allowed_extensions: Vec<String> =
-            ext_val.split('|').map(|s| s.to_lowercase()).collect();
+        let asset_max_size = source.parse_or("ASSET_MAX_SIZE", DEFAULT_MAX_FILE_SIZE)?;
 
         Ok(Self {
-            database_url: env::var("DATABASE_URL")?,
+            database_url: source.required("DATABASE_URL")?,
 
-            database_max_connections: env::var("DATABASE_MAX_CONNECTIONS")
-                .map(|s| s.parse::<u32>().unwrap_or(20))
-                .unwrap_or(20),
-            database_min_connections: env::var("DATABASE_MIN_CONNECTIONS")
-                .map(|s| s.parse::<u32>().unwrap_or(5))
-                .unwrap_or(5),
+            database_max_connections: source.parse_or("DATABASE_MAX_CONNECTIONS", 20)?,
+            database_min_connections: source.parse_or("DATABASE_MIN_CONNECTIONS", 5)?,
+            database_acquire_timeout_secs: source
+                .parse_or("DATABASE_ACQUIRE_TIMEOUT", DEFAULT_DB_ACQUIRE_TIMEOUT_SECS)?,
+            database_statement_timeout_ms: source.parse_or("DATABASE_STATEMENT_TIMEOUT_MS", 0)?,
+            database_slow_query_ms: source
+                .parse_or("DATABASE_SLOW_QUERY_MS", DEFAULT_DB_SLOW_QUERY_MS)?,
 
-            service_host: env::var("SERVICE_HOST")?,
-            service_port: env::var("SERVICE_PORT")?,
+            service_host: source.required("SERVICE_HOST")?,
+            service_port: source.required("SERVICE_PORT")?,
+            grpc_port: source.string_or("GRPC_PORT", DEFAULT_GRPC_PORT),
 
-            assets_public_path: env::var("ASSETS_PUBLIC_PATH")?,
-            assets_public_url: env::var("ASSETS_PUBLIC_URL")?,
+            assets_public_path: source.required("ASSETS_PUBLIC_PATH")?,
+            assets_public_url: source.required("ASSETS_PUBLIC_URL")?,
 
-            assets_private_path: env::var("ASSETS_PRIVATE_PATH")?,
-            assets_private_url: env::var("ASSETS_PRIVATE_URL")?,
+            assets_private_path: source.required("ASSETS_PRIVATE_PATH")?,
+            assets_private_url: source.required("ASSETS_PRIVATE_URL")?,
 
-            asset_allowed_extensions_pattern: Regex::new(&format!(r"(?i)^.*\.({ext_val})$"))
-                .unwrap_or_else(|_| {
-                    eprintln!("Invalid ASSET_ALLOWED_EXTENSIONS regex pattern: {ext_val}");
-                    Regex::new(r"(?i)^.*\.(jpg|jpeg|png|gif|webp)$")
-                        .expect("Failed to compile default asset extensions regex")
-                }),
+            asset_allowed_extensions_pattern,
 
             asset_allowed_extensions,
 
-            asset_max_size: env::var("ASSET_MAX_SIZE")
-                .map(|s| s.parse::<usize>().unwrap_or(DEFAULT_MAX_FILE_SIZE))?,
+            asset_max_size,
+            video_max_size: source.parse_or("VIDEO_MAX_SIZE", DEFAULT_MAX_VIDEO_SIZE)?,
+
+            thumbnail_sizes: source.sizes("THUMBNAIL_SIZES", DEFAULT_THUMBNAIL_SIZES)?,
+            thumbnail_list_size: source
+                .parse_or("THUMBNAIL_LIST_SIZE", DEFAULT_THUMBNAIL_LIST_SIZE)?,
+            media_cache_max_age_secs: source
+                .parse_or("MEDIA_CACHE_MAX_AGE_SECS", DEFAULT_MEDIA_CACHE_MAX_AGE_SECS)?,
+            media_storage: MediaStorageConfig::from_source(source)?,
+            media_gc_interval_secs: source.parse_or("MEDIA_GC_INTERVAL_SECS", 0)?,
+            media_gc_delete: source.flag("MEDIA_GC_DELETE", false)?,
+            link_check_interval_secs: source.parse_or("LINK_CHECK_INTERVAL_SECS", 0)?,
+            link_check_concurrency: source
+                .parse_or("LINK_CHECK_CONCURRENCY", DEFAULT_LINK_CHECK_CONCURRENCY)?,
+            link_check_timeout_secs: source
+                .parse_or("LINK_CHECK_TIMEOUT_SECS", DEFAULT_LINK_CHECK_TIMEOUT_SECS)?,
+            link_check_batch_size: source
+                .parse_or("LINK_CHECK_BATCH_SIZE", DEFAULT_LINK_CHECK_BATCH_SIZE)?,
 
-            cors_origins: env::var("CORS_ORIGINS")
-                .map(|s| s.split(',').map(|o| o.trim().to_owned()).collect())
+            json_body_limit: source.parse_or("JSON_BODY_LIMIT", DEFAULT_JSON_BODY_LIMIT)?,
+            upload_body_limit: source.parse_or("UPLOAD_BODY_LIMIT", asset_max_size)?,
+            upload_max_concurrent: source
+                .parse_or("UPLOAD_MAX_CONCURRENT", DEFAULT_UPLOAD_MAX_CONCURRENT)?,
+            upload_strip_metadata: source.flag("UPLOAD_STRIP_METADATA", false)?,
+            response_compression: source.flag("RESPONSE_COMPRESSION", true)?,
+
+            cors: CorsConfig::from_source(source)?,
+
+            meili_url: source.string_or("MEILI_URL", "http://localhost:7700"),
+            meili_master_key: source.string_or("MEILI_MASTER_KEY", "meili_master_key_dev"),
+            vllm_embedding_url: source.string_or("VLLM_EMBEDDING_URL", "http://localhost:8000"),
+            vllm_embedding_model: source.string_or("VLLM_EMBEDDING_MODEL", "BAAI/bge-m3"),
+            vllm_embedding_timeout_secs: source.parse_or("VLLM_EMBEDDING_TIMEOUT", 5)?,
+
+            auth_ip_rate_limit: source
+                .rate_limit("AUTH_IP_RATE_LIMIT", DEFAULT_AUTH_IP_RATE_LIMIT)?,
+            auth_account_rate_limit: source
+                .rate_limit("AUTH_ACCOUNT_RATE_LIMIT", DEFAULT_AUTH_ACCOUNT_RATE_LIMIT)?,
+            auth_max_failed_logins: source
+                .parse_or("AUTH_MAX_FAILED_LOGINS", DEFAULT_AUTH_MAX_FAILED_LOGINS)?,
+            auth_lockout_minutes: source
+                .parse_or("AUTH_LOCKOUT_MINUTES", DEFAULT_AUTH_LOCKOUT_MINUTES)?,
+            totp_encryption_key: source
+                .get("TOTP_ENCRYPTION_KEY")
+                .map_or_else(|| source.required("JWT_SECRET_KEY"), Ok)?,
+            rate_limit_read: source.rate_limit("RATE_LIMIT_READ", DEFAULT_RATE_LIMIT_READ)?,
+            rate_limit_write: source.rate_limit("RATE_LIMIT_WRITE", DEFAULT_RATE_LIMIT_WRITE)?,
+            rate_limit_upload: source.rate_limit("RATE_LIMIT_UPLOAD", DEFAULT_RATE_LIMIT_UPLOAD)?,
+            trust_forwarded_for: source.flag("TRUST_FORWARDED_FOR", false)?,
+            access_log_format: source
+                .get("ACCESS_LOG_FORMAT")
+                .map(|value| {
+                    AccessLogFormat::parse(&value).ok_or_else(|| ConfigError::Invalid {
+                        key: "ACCESS_LOG_FORMAT".to_owned(),
+                        reason: "expected json or pretty".to_owned(),
+                        value,
+                    })
+                })
+                .transpose()?
                 .unwrap_or_default(),
 
-            meili_url: env::var("MEILI_URL").unwrap_or_else(|_| "http://localhost:7700".to_owned()),
-            meili_master_key: env::var("MEILI_MASTER_KEY")
-                .unwrap_or_else(|_| "meili_master_key_dev".to_owned()),
-            vllm_embedding_url: env::var("VLLM_EMBEDDING_URL")
-                .unwrap_or_else(|_| "http://localhost:8000".to_owned()),
-            vllm_embedding_model: env::var("VLLM_EMBEDDING_MODEL")
-                .unwrap_or_else(|_| "BAAI/bge-m3".to_owned()),
-            vllm_embedding_timeout_secs: env::var("VLLM_EMBEDDING_TIMEOUT")
-                .map(|s| s.parse::<u64>().unwrap_or(5))
-                .unwrap_or(5),
+            cache_ttl_secs: source.parse_or("CACHE_TTL_SECS", DEFAULT_CACHE_TTL_SECS)?,
+            cache_max_entries: source.parse_or("CACHE_MAX_ENTRIES", DEFAULT_CACHE_MAX_ENTRIES)?,
+            redis_url: source.get("REDIS_URL"),
+
+            similarity_weights: SimilarityWeights::from_source(source)?,
+
+            metadata: MetadataConfig::from_source(source)?,
         })
     }
 }
 
-/// `setup_database` initializes the database connection pool.
+/// `setup_database` initializes the database connection pool and applies
+/// pending migrations.
 pub async fn setup_database(config: &Config) -> Result<DatabaseConnection, sea_orm::DbErr> {
+    let pool = connect_database(config).await?;
+
+    // Run pending migrations
+    Migrator::up(&pool, None).await?;
+
+    Ok(pool)
+}
+
+/// `connect_database` initializes the database connection pool without
+/// touching the schema.
+pub async fn connect_database(config: &Config) -> Result<DatabaseConnection, sea_orm::DbErr> {
     // Attempt to connect repeatedly, with a small delay, until success (or a max number of tries)
     let mut attempts = 0;
     let mut opt = ConnectOptions::new(&config.database_url);
     opt.min_connections(config.database_min_connections)
         .max_connections(config.database_max_connections)
         .connect_timeout(Duration::from_secs(DB_CONNECT_TIMEOUT_SECS))
-        .acquire_timeout(Duration::from_secs(DB_CONNECT_TIMEOUT_SECS))
+        .acquire_timeout(Duration::from_secs(config.database_acquire_timeout_secs))
         .idle_timeout(Duration::from_secs(DB_IDLE_TIMEOUT_SECS))
         .max_lifetime(Duration::from_secs(DB_MAX_LIFETIME_SECS));
+    if config.database_statement_timeout_ms > 0 {
+        let statement_timeout = config.database_statement_timeout_ms;
+        opt.map_sqlx_postgres_opts(move |pg| {
+            pg.options([("statement_timeout", statement_timeout)])
+        });
+    }
     // .sqlx_logging(true)
     // .sqlx_logging_level(tracing::Level::INFO);
 
-    let pool = loop {
+    let mut pool = loop {
         attempts += 1;
         match sea_orm::Database::connect(opt.clone()).await {
             Ok(pool) => break pool,
@@ -145,8 +840,129 @@ pub async fn setup_database(config: &Config) -> Result<DatabaseConnection, sea_o
         }
     };
 
-    // Run pending migrations
-    Migrator::up(&pool, None).await?;
+    let slow_query = Duration::from_millis(config.database_slow_query_ms);
+    pool.set_metric_callback(move |info| {
+        if !slow_query.is_zero() && info.elapsed >= slow_query {
+            tracing::warn!(
+                elapsed_ms = u64::try_from(info.elapsed.as_millis()).unwrap_or(u64::MAX),
+                failed = info.failed,
+                "Slow query: {}",
+                info.statement
+            );
+        }
+        #[cfg(feature = "metrics")]
+        super::metrics::observe_query(info);
+    });
+    #[cfg(feature = "metrics")]
+    super::metrics::register_pool(&pool);
 
     Ok(pool)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{flatten_into, ConfigError, ConfigSource, MetadataConfig};
+
+    #[test]
+    fn nested_tables_and_arrays_flatten_to_env_names() {
+        let root: serde_json::Value = toml::from_str(
+            r#"
+            database_url = "postgres://db"
+            [cors]
+            origins = ["https://a.example", "https://b.example"]
+            [rate_limit]
+            read_burst = 300
+            "#,
+        )
+        .expect("valid TOML");
+        let mut file = HashMap::new();
+        flatten_into(&mut file, "", &root);
+
+        assert_eq!(file["DATABASE_URL"], "postgres://db");
+        assert_eq!(file["CORS_ORIGINS"], "https://a.example,https://b.example");
+        assert_eq!(file["RATE_LIMIT_READ_BURST"], "300");
+    }
+
+    #[test]
+    fn invalid_values_name_their_key() {
+        let source = ConfigSource {
+            file: HashMap::from([("LUNIRELUST_TEST_LIMIT".to_owned(), "lots".to_owned())]),
+        };
+        let err = source
+            .parse_or::<u32>("LUNIRELUST_TEST_LIMIT", 1)
+            .expect_err("`lots` is not a number");
+        assert!(
+            matches!(&err, ConfigError::Invalid { key, .. } if key == "LUNIRELUST_TEST_LIMIT"),
+            "unexpected error {err}"
+        );
+        assert_eq!(
+            source.parse_or("LUNIRELUST_TEST_UNSET", 7_u32).ok(),
+            Some(7)
+        );
+    }
+
+    #[test]
+    fn metadata_providers_are_read_by_name() {
+        let source = ConfigSource {
+            file: HashMap::from([
+                ("METADATA_PROVIDERS".to_owned(), "alpha, beta".to_owned()),
+                (
+                    "METADATA_PROVIDER_ALPHA_URL".to_owned(),
+                    "http://alpha.example/{id}".to_owned(),
+                ),
+                (
+                    "METADATA_PROVIDER_ALPHA_ID_PATTERN".to_owned(),
+                    "[A-Z]+-[0-9]+".to_owned(),
+                ),
+                (
+                    "METADATA_PROVIDER_BETA_URL".to_owned(),
+                    "http://beta.example/{id}".to_owned(),
+                ),
+            ]),
+        };
+        let metadata = MetadataConfig::from_source(&source).expect("valid providers");
+        let names: Vec<&str> = metadata.providers.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["alpha", "beta"]);
+        assert!(metadata.providers[0].id_pattern.is_match("ABC-123"));
+        assert!(
+            !metadata.providers[0].id_pattern.is_match("xABC-123"),
+            "patterns match whole IDs"
+        );
+        assert!(metadata.providers[1].id_pattern.is_match("anything"));
+
+        let missing_url = ConfigSource {
+            file: HashMap::from([("METADATA_PROVIDERS".to_owned(), "gamma".to_owned())]),
+        };
+        assert!(matches!(
+            MetadataConfig::from_source(&missing_url),
+            Err(ConfigError::Missing(key)) if key == "METADATA_PROVIDER_GAMMA_URL"
+        ));
+    }
+
+    #[test]
+    fn sizes_are_sorted_deduplicated_and_positive() {
+        let source = ConfigSource {
+            file: HashMap::from([
+                (
+                    "LUNIRELUST_TEST_SIZES".to_owned(),
+                    "640, 160,320,160".to_owned(),
+                ),
+                ("LUNIRELUST_TEST_ZERO".to_owned(), "0,320".to_owned()),
+            ]),
+        };
+        assert_eq!(
+            source.sizes("LUNIRELUST_TEST_SIZES", &[]).ok(),
+            Some(vec![160, 320, 640])
+        );
+        assert!(
+            source.sizes("LUNIRELUST_TEST_ZERO", &[]).is_err(),
+            "0 is not a size"
+        );
+        assert_eq!(
+            source.sizes("LUNIRELUST_TEST_UNSET", &["80"]).ok(),
+            Some(vec![80])
+        );
+    }
+}
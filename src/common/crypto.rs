@@ -0,0 +1,100 @@
+//! Symmetric encryption for secrets that must be stored in the database but
+//! read back later, such as TOTP secrets.
+//!
+//! Uses AES-256-GCM with a random nonce per value. A sealed value is the nonce
+//! followed by the ciphertext and authentication tag.
+
+use rand::Rng as _;
+use ring::{
+    aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN},
+    digest,
+};
+
+use super::error::AppError;
+
+/// AES-256-GCM cipher keyed by a configured secret.
+pub struct SecretCipher {
+    key: LessSafeKey,
+}
+
+impl SecretCipher {
+    /// Creates a cipher whose key is the SHA-256 digest of `key_material`.
+    pub fn new(key_material: &str) -> Self {
+        let digest = digest::digest(&digest::SHA256, key_material.as_bytes());
+        let key = UnboundKey::new(&AES_256_GCM, digest.as_ref())
+            .expect("SHA-256 digest is a valid AES-256 key");
+        Self {
+            key: LessSafeKey::new(key),
+        }
+    }
+
+    /// Encrypts `plaintext` under a fresh random nonce.
+    pub fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>, AppError> {
+        let mut nonce = [0_u8; NONCE_LEN];
+        rand::rng().fill(&mut nonce);
+
+        let mut in_out = plaintext.to_vec();
+        self.key
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::empty(),
+                &mut in_out,
+            )
+            .map_err(|err| {
+                AppError::InternalErrorWithMessage(format!("Encryption failed: {err}"))
+            })?;
+
+        let mut sealed = nonce.to_vec();
+        sealed.extend_from_slice(&in_out);
+        Ok(sealed)
+    }
+
+    /// Decrypts a value produced by [`Self::encrypt`]. Fails if it was
+    /// tampered with or sealed under a different key.
+    pub fn decrypt(&self, sealed: &[u8]) -> Result<Vec<u8>, AppError> {
+        let decryption_failed =
+            |err| AppError::InternalErrorWithMessage(format!("Decryption failed: {err}"));
+
+        if sealed.len() < NONCE_LEN {
+            return Err(AppError::InternalErrorWithMessage(
+                "Decryption failed: sealed value is too short".to_owned(),
+            ));
+        }
+        let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+        let nonce = Nonce::try_assume_unique_for_key(nonce).map_err(decryption_failed)?;
+
+        let mut in_out = ciphertext.to_vec();
+        let plaintext = self
+            .key
+            .open_in_place(nonce, Aad::empty(), &mut in_out)
+            .map_err(decryption_failed)?;
+        Ok(plaintext.to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SecretCipher;
+
+    #[test]
+    fn round_trips_with_fresh_nonces() {
+        let cipher = SecretCipher::new("test key");
+        let first = cipher.encrypt(b"secret").expect("encrypts");
+        let second = cipher.encrypt(b"secret").expect("encrypts");
+        assert_ne!(first, second);
+        assert_eq!(cipher.decrypt(&first).expect("decrypts"), b"secret");
+        assert_eq!(cipher.decrypt(&second).expect("decrypts"), b"secret");
+    }
+
+    #[test]
+    fn rejects_tampered_values_and_other_keys() {
+        let cipher = SecretCipher::new("test key");
+        let mut sealed = cipher.encrypt(b"secret").expect("encrypts");
+        assert!(SecretCipher::new("other key").decrypt(&sealed).is_err());
+
+        let last = sealed.len() - 1;
+        sealed[last] ^= 1;
+        assert!(cipher.decrypt(&sealed).is_err());
+        assert!(cipher.decrypt(&[0_u8; 4]).is_err());
+    }
+}
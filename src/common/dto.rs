@@ -1,15 +1,54 @@
 use axum::response::{IntoResponse, Response};
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::{error::ErrorCode, request_id::current_request_id};
 
 /// A standardized API response format.
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct ApiResponse<T>
 where
     T: Serialize,
 {
+    /// HTTP status code, repeated from the response line.
     pub status: u16,
+    /// Human-readable outcome, `success` unless the handler says otherwise.
     pub message: String,
+    /// The payload; `null` on failures without details.
     pub data: Option<T>,
+    /// The `X-Request-Id` of the request, to quote when reporting a problem.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+}
+
+/// Body of every error response.
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct ErrorResponse {
+    /// HTTP status code, repeated from the response line.
+    pub status: u16,
+    /// Stable machine-readable error code.
+    pub code: ErrorCode,
+    /// Human-readable description; its wording may change.
+    pub message: String,
+    /// Per-field failures on `VALIDATION_FAILED`, the blocking record IDs on
+    /// `STILL_REFERENCED`, `{ "version": n }` on `VERSION_MISMATCH`, `null`
+    /// otherwise.
+    pub details: Option<serde_json::Value>,
+    /// The `X-Request-Id` of the request, to quote when reporting a problem.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+}
+
+/// One failed `validator` rule, listed in the `details` of a
+/// `VALIDATION_FAILED` response.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, ToSchema)]
+pub struct ValidationErrorDetail {
+    /// Path of the offending field, e.g. `title` or `links[0].link`.
+    pub field: String,
+    /// Rule that failed, e.g. `length` or `url`.
+    pub code: String,
+    /// Explanation attached to the rule, if it has one.
+    pub message: Option<String>,
 }
 
 /// A standardized API response format for successful and failed responses.
@@ -28,6 +67,7 @@ where
             status: 200,
             message: "success".to_owned(),
             data: Some(data),
+            request_id: None,
         }
     }
 
@@ -37,6 +77,7 @@ where
             status: 200,
             message: message.into(),
             data: Some(data),
+            request_id: None,
         }
     }
 
@@ -46,6 +87,7 @@ where
             status,
             message: message.into(),
             data: None,
+            request_id: None,
         }
     }
 }
@@ -80,6 +122,8 @@ impl<T: Serialize> RestApiResponse<T> {
 
 impl<T: Serialize> IntoResponse for RestApiResponse<T> {
     fn into_response(self) -> Response {
-        axum::Json(self.0).into_response()
+        let mut body = self.0;
+        body.request_id = current_request_id();
+        axum::Json(body).into_response()
     }
 }
@@ -4,13 +4,17 @@ use axum::{
     BoxError,
 };
 
-use sea_orm::DbErr as DbError;
+use sea_orm::{DbErr as DbError, SqlErr};
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use tracing::error;
+use utoipa::ToSchema;
+use validator::{ValidationErrors, ValidationErrorsKind};
 
-use crate::common::dto::RestApiResponse;
-
-use super::dto::ApiResponse;
+use super::{
+    dto::{ErrorResponse, ValidationErrorDetail},
+    request_id::current_request_id,
+};
 
 /// `AppError` is an enum that represents various types of errors that can occur in the application.
 /// It implements the `std::error::Error` trait and the `axum::response::IntoResponse` trait.
@@ -31,6 +35,11 @@ pub enum AppError {
     #[error("Validation error: {0}")]
     ValidationError(String),
 
+    /// A request body failed its `validator` rules. Each failed rule is
+    /// returned as a [`ValidationErrorDetail`] in the response `data`.
+    #[error("Validation error: Invalid input: {0}")]
+    InvalidInput(ValidationErrors),
+
     /// Semantically invalid input that is well-formed (e.g. an unknown enum
     /// value or an out-of-range parameter). Maps to 422 Unprocessable Entity.
     #[error("Unprocessable entity: {0}")]
@@ -39,6 +48,16 @@ pub enum AppError {
     #[error("Conflict: {0}")]
     Conflict(String),
 
+    /// The target is still referenced by records. The blocking record IDs are
+    /// returned as the response `data`. Maps to 409 Conflict.
+    #[error("Conflict: {0}")]
+    ConflictWithRecords(String, Vec<String>),
+
+    /// The client's `If-Match` version is stale. The current version is
+    /// returned as the response `data`. Maps to 412 Precondition Failed.
+    #[error("Precondition failed: the resource is now at version {0}")]
+    PreconditionFailed(i32),
+
     #[error("Forbidden Request")]
     Forbidden,
 
@@ -49,6 +68,10 @@ pub enum AppError {
     #[error("File size exceeded")]
     FileSizeExceeded,
 
+    /// The request body is larger than the route allows. Maps to 413 Payload Too Large.
+    #[error("Request body too large")]
+    PayloadTooLarge,
+
     #[error("Invalid file name")]
     InvalidFileName,
 
@@ -66,44 +89,224 @@ pub enum AppError {
     TokenCreation,
     #[error("User not found")]
     UserNotFound,
+    /// Rate limit exceeded. Maps to 429 Too Many Requests.
+    #[error("Too many requests")]
+    TooManyRequests,
+    /// Too many failed logins; the account is temporarily locked. Maps to 423 Locked.
+    #[error("Account temporarily locked")]
+    AccountLocked,
 }
 
-/// Converts the `AppError` enum into an HTTP response.
-/// It maps the error to an appropriate HTTP status code and constructs a JSON response body.
-impl IntoResponse for AppError {
-    fn into_response(self) -> Response {
-        let status = match self {
+/// Stable, machine-readable error codes reported in [`ErrorResponse::code`].
+/// Clients should branch on these rather than on the message wording.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    /// The request body failed its validation rules; `details` lists them.
+    ValidationFailed,
+    /// The request is malformed (bad query parameter, cursor, ID list, ...).
+    BadRequest,
+    UnprocessableEntity,
+    PayloadTooLarge,
+    InvalidFileData,
+    FileSizeExceeded,
+    InvalidFileName,
+    UnsupportedFileExtension,
+    MissingCredentials,
+    WrongCredentials,
+    InvalidToken,
+    Forbidden,
+    AccountLocked,
+    RateLimited,
+    RecordNotFound,
+    DirectorNotFound,
+    StudioNotFound,
+    LabelNotFound,
+    SeriesNotFound,
+    GenreNotFound,
+    IdolNotFound,
+    UserNotFound,
+    DeviceNotFound,
+    ApiKeyNotFound,
+    TaskNotFound,
+    FileNotFound,
+    MediaNotFound,
+    /// Something not covered by a more specific `*_NOT_FOUND` code.
+    NotFound,
+    Conflict,
+    /// A unique column already holds the submitted value.
+    AlreadyExists,
+    /// The target is still referenced; `details` lists the blocking record IDs.
+    StillReferenced,
+    /// The `If-Match` version is stale; `details.version` is the current one.
+    VersionMismatch,
+    RequestTimeout,
+    DatabaseError,
+    InternalError,
+}
+
+impl ErrorCode {
+    /// The `*_NOT_FOUND` code for a `"<Resource> not found..."` message.
+    fn not_found(message: &str) -> Self {
+        let resource = message
+            .split(" not found")
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        match resource.as_str() {
+            "record" => Self::RecordNotFound,
+            "director" => Self::DirectorNotFound,
+            "studio" => Self::StudioNotFound,
+            "label" => Self::LabelNotFound,
+            "series" => Self::SeriesNotFound,
+            "genre" => Self::GenreNotFound,
+            "idol" => Self::IdolNotFound,
+            "user" => Self::UserNotFound,
+            "device" | "active device" => Self::DeviceNotFound,
+            "api key" => Self::ApiKeyNotFound,
+            "task" => Self::TaskNotFound,
+            "file" => Self::FileNotFound,
+            "media" => Self::MediaNotFound,
+            _ => Self::NotFound,
+        }
+    }
+}
+
+impl AppError {
+    /// Whether this is a database error caused by a unique constraint.
+    fn is_unique_violation(&self) -> bool {
+        matches!(
+            self,
+            Self::DatabaseError(err)
+                if matches!(err.sql_err(), Some(SqlErr::UniqueConstraintViolation(_)))
+        )
+    }
+
+    /// HTTP status this error is reported with.
+    pub fn status_code(&self) -> StatusCode {
+        match self {
             Self::ValidationError(_)
+            | Self::InvalidInput(_)
             | Self::InvalidFileData
             | Self::FileSizeExceeded
             | Self::InvalidFileName
             | Self::UnsupportedFileExtension
             | Self::MissingCredentials => StatusCode::BAD_REQUEST,
             Self::UnprocessableEntity(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            Self::PayloadTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+            Self::DatabaseError(_) if self.is_unique_violation() => StatusCode::CONFLICT,
             Self::DatabaseError(_)
             | Self::InternalError
             | Self::InternalErrorWithMessage(_)
             | Self::TokenCreation => StatusCode::INTERNAL_SERVER_ERROR,
             Self::NotFound(_) | Self::UserNotFound => StatusCode::NOT_FOUND,
             Self::Forbidden => StatusCode::FORBIDDEN,
-            Self::Conflict(_) => StatusCode::CONFLICT,
+            Self::Conflict(_) | Self::ConflictWithRecords(..) => StatusCode::CONFLICT,
+            Self::PreconditionFailed(_) => StatusCode::PRECONDITION_FAILED,
             Self::WrongCredentials | Self::InvalidToken => StatusCode::UNAUTHORIZED,
-        };
+            Self::TooManyRequests => StatusCode::TOO_MANY_REQUESTS,
+            Self::AccountLocked => StatusCode::LOCKED,
+        }
+    }
+
+    /// Machine-readable code this error is reported with.
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Self::DatabaseError(_) if self.is_unique_violation() => ErrorCode::AlreadyExists,
+            Self::DatabaseError(_) => ErrorCode::DatabaseError,
+            Self::NotFound(message) => ErrorCode::not_found(message),
+            Self::InternalError | Self::InternalErrorWithMessage(_) | Self::TokenCreation => {
+                ErrorCode::InternalError
+            }
+            Self::ValidationError(_) => ErrorCode::BadRequest,
+            Self::InvalidInput(_) => ErrorCode::ValidationFailed,
+            Self::UnprocessableEntity(_) => ErrorCode::UnprocessableEntity,
+            Self::Conflict(_) => ErrorCode::Conflict,
+            Self::ConflictWithRecords(..) => ErrorCode::StillReferenced,
+            Self::PreconditionFailed(_) => ErrorCode::VersionMismatch,
+            Self::Forbidden => ErrorCode::Forbidden,
+            Self::InvalidFileData => ErrorCode::InvalidFileData,
+            Self::FileSizeExceeded => ErrorCode::FileSizeExceeded,
+            Self::PayloadTooLarge => ErrorCode::PayloadTooLarge,
+            Self::InvalidFileName => ErrorCode::InvalidFileName,
+            Self::UnsupportedFileExtension => ErrorCode::UnsupportedFileExtension,
+            Self::WrongCredentials => ErrorCode::WrongCredentials,
+            Self::MissingCredentials => ErrorCode::MissingCredentials,
+            Self::InvalidToken => ErrorCode::InvalidToken,
+            Self::UserNotFound => ErrorCode::UserNotFound,
+            Self::TooManyRequests => ErrorCode::RateLimited,
+            Self::AccountLocked => ErrorCode::AccountLocked,
+        }
+    }
+
+    /// Error-specific details returned alongside the code, if any.
+    fn details(&self) -> Option<serde_json::Value> {
+        match self {
+            Self::InvalidInput(errors) => serde_json::to_value(validation_details(errors)).ok(),
+            Self::ConflictWithRecords(_, record_ids) => Some(serde_json::json!(record_ids)),
+            Self::PreconditionFailed(version) => Some(serde_json::json!({ "version": version })),
+            _ => None,
+        }
+    }
+}
+
+/// Converts the `AppError` enum into an HTTP response.
+/// It maps the error to an appropriate HTTP status code and constructs a JSON
+/// [`ErrorResponse`] body.
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        let status = self.status_code();
 
         if status.is_server_error() {
             error!(?status, %self, "Server error");
         }
 
-        let body = axum::Json(ApiResponse::<()> {
+        let body = axum::Json(ErrorResponse {
             status: status.as_u16(),
+            code: self.code(),
             message: self.to_string(),
-            data: None,
+            details: self.details(),
+            request_id: current_request_id(),
         });
 
         (status, body).into_response()
     }
 }
 
+/// Flatten `errors` into one entry per failed rule, with nested struct and
+/// list fields addressed as `parent.child` and `parent[index]`.
+fn validation_details(errors: &ValidationErrors) -> Vec<ValidationErrorDetail> {
+    fn collect(errors: &ValidationErrors, prefix: &str, out: &mut Vec<ValidationErrorDetail>) {
+        for (field, kind) in errors.errors() {
+            let path = if prefix.is_empty() {
+                field.to_string()
+            } else {
+                format!("{prefix}.{field}")
+            };
+            match kind {
+                ValidationErrorsKind::Field(field_errors) => {
+                    out.extend(field_errors.iter().map(|err| ValidationErrorDetail {
+                        field: path.clone(),
+                        code: err.code.to_string(),
+                        message: err.message.as_ref().map(ToString::to_string),
+                    }));
+                }
+                ValidationErrorsKind::Struct(nested) => collect(nested, &path, out),
+                ValidationErrorsKind::List(items) => {
+                    for (index, nested) in items {
+                        collect(nested, &format!("{path}[{index}]"), out);
+                    }
+                }
+            }
+        }
+    }
+
+    let mut details = Vec::new();
+    collect(errors, "", &mut details);
+    details.sort_by(|a, b| a.field.cmp(&b.field).then_with(|| a.code.cmp(&b.code)));
+    details
+}
+
 /// `handle_error` is a function that middlewares the error handling in the application.
 ///
 /// It takes a `BoxError` as input and returns an HTTP response.
@@ -120,7 +323,88 @@ pub async fn handle_error(error: BoxError) -> impl IntoResponse {
     let message = error.to_string();
     error!(?status, %message, "Request failed");
 
-    let body = RestApiResponse::<()>::failure(status.as_u16(), message);
+    let code = if status == StatusCode::REQUEST_TIMEOUT {
+        ErrorCode::RequestTimeout
+    } else {
+        ErrorCode::InternalError
+    };
+    let body = axum::Json(ErrorResponse {
+        status: status.as_u16(),
+        code,
+        message,
+        details: None,
+        request_id: current_request_id(),
+    });
 
     (status, body)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{validation_details, AppError, ErrorCode};
+    use validator::Validate;
+
+    #[test]
+    fn not_found_codes_name_the_resource() {
+        let cases = [
+            ("Record not found in trash", ErrorCode::RecordNotFound),
+            ("Series not found", ErrorCode::SeriesNotFound),
+            ("Active device not found: abc", ErrorCode::DeviceNotFound),
+            ("API key not found: abc", ErrorCode::ApiKeyNotFound),
+            ("Widget not found", ErrorCode::NotFound),
+        ];
+        for (message, code) in cases {
+            assert_eq!(AppError::NotFound(message.into()).code(), code, "{message}");
+        }
+    }
+
+    #[test]
+    fn codes_serialize_in_screaming_snake_case() {
+        let value =
+            serde_json::to_value(AppError::PreconditionFailed(2).code()).expect("code serializes");
+        assert_eq!(value, "VERSION_MISMATCH");
+    }
+
+    #[derive(Validate)]
+    struct LinkInput {
+        #[validate(url)]
+        link: String,
+    }
+
+    #[derive(Validate)]
+    struct RecordInput {
+        #[validate(length(max = 3, message = "too long"))]
+        title: String,
+        #[validate(nested)]
+        links: Vec<LinkInput>,
+    }
+
+    #[test]
+    fn validation_details_address_nested_fields() {
+        let input = RecordInput {
+            title: "Too long".to_owned(),
+            links: vec![
+                LinkInput {
+                    link: "https://example.com".to_owned(),
+                },
+                LinkInput {
+                    link: "not a url".to_owned(),
+                },
+            ],
+        };
+        let errors = input.validate().expect_err("input is invalid");
+
+        let details = validation_details(&errors);
+        let fields: Vec<_> = details
+            .iter()
+            .map(|d| (d.field.as_str(), d.code.as_str(), d.message.as_deref()))
+            .collect();
+        assert_eq!(
+            fields,
+            [
+                ("links[1].link", "url", None),
+                ("title", "length", Some("too long"))
+            ]
+        );
+    }
+}
@@ -0,0 +1,299 @@
+//! Conditional GET support.
+//!
+//! Responses carry a weak `ETag` derived from a SHA-256 digest of the
+//! serialized JSON body, so any change to the payload (including per-user
+//! fields such as `liked`) yields a new tag. Requests whose `If-None-Match`
+//! already names the current tag get an empty `304 Not Modified`.
+//!
+//! Edits use the resource's integer version instead: clients send the version
+//! they last saw in `If-Match`, and a stale one is rejected with 412. Tags of
+//! versioned resources lead with the version, e.g. `W/"3-…"`, so the `ETag` of
+//! a GET can be sent back in `If-Match` as is.
+//!
+//! Files are tagged from their size and modification time instead, so their
+//! validators cost a `stat` rather than a read.
+
+use axum::{
+    http::{
+        header::{
+            CACHE_CONTROL, CONTENT_TYPE, ETAG, IF_MATCH, IF_MODIFIED_SINCE, IF_NONE_MATCH,
+            IF_RANGE, VARY,
+        },
+        HeaderMap, HeaderValue, StatusCode,
+    },
+    response::{IntoResponse as _, Response},
+};
+use chrono::{DateTime, Utc};
+use ring::digest;
+use serde::Serialize;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::{
+    dto::{ApiResponse, RestApiResponse},
+    error::AppError,
+    request_id::current_request_id,
+};
+
+/// Number of digest bytes kept in the tag.
+const ETAG_DIGEST_BYTES: usize = 16;
+
+/// Hex of the leading digest bytes of a serialized response body.
+fn body_digest(body: &[u8]) -> String {
+    let digest = digest::digest(&digest::SHA256, body);
+    digest
+        .as_ref()
+        .iter()
+        .take(ETAG_DIGEST_BYTES)
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+/// Returns the weak `ETag` for a serialized response body.
+pub fn weak_etag(body: &[u8]) -> String {
+    format!("W/\"{}\"", body_digest(body))
+}
+
+/// Returns the weak `ETag` for a serialized response body of a resource at
+/// `version`.
+pub fn versioned_etag(version: i32, body: &[u8]) -> String {
+    format!("W/\"{version}-{}\"", body_digest(body))
+}
+
+/// Whether an `If-None-Match` header value matches `etag` under the weak
+/// comparison used for GET requests.
+pub fn if_none_match(header: &str, etag: &str) -> bool {
+    let opaque = |tag: &str| tag.trim().trim_start_matches("W/").to_owned();
+    let etag = opaque(etag);
+    header
+        .split(',')
+        .any(|candidate| candidate.trim() == "*" || opaque(candidate) == etag)
+}
+
+/// Returns the strong `ETag` of a file of `len` bytes last modified at
+/// `modified`.
+pub fn file_etag(len: u64, modified: Option<SystemTime>) -> String {
+    let nanos = modified
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |elapsed| elapsed.as_nanos());
+    format!("\"{len:x}-{nanos:x}\"")
+}
+
+/// Formats `time` as an HTTP date, e.g. `Sun, 06 Nov 1994 08:49:37 GMT`.
+pub fn http_date(time: SystemTime) -> String {
+    DateTime::<Utc>::from(time)
+        .format("%a, %d %b %Y %H:%M:%S GMT")
+        .to_string()
+}
+
+/// Whether `time` is no later than the HTTP date `header`, to the second.
+/// Dates that do not parse never match.
+fn not_after_http_date(header: &str, time: SystemTime) -> bool {
+    DateTime::parse_from_rfc2822(header.trim())
+        .is_ok_and(|date| DateTime::<Utc>::from(time).timestamp() <= date.timestamp())
+}
+
+/// Whether a file tagged `etag` and last modified at `modified` is unchanged
+/// for the client, under `If-None-Match` or, when that is absent,
+/// `If-Modified-Since`.
+pub fn file_not_modified(headers: &HeaderMap, etag: &str, modified: Option<SystemTime>) -> bool {
+    if let Some(value) = headers.get(IF_NONE_MATCH) {
+        return value.to_str().is_ok_and(|v| if_none_match(v, etag));
+    }
+    let since = headers.get(IF_MODIFIED_SINCE).and_then(|v| v.to_str().ok());
+    match (since, modified) {
+        (Some(since), Some(modified)) => not_after_http_date(since, modified),
+        _ => false,
+    }
+}
+
+/// Whether a `Range` request may be answered with part of a file tagged
+/// `etag` and last modified at `modified`: true without `If-Range`, or when it
+/// names the strong tag or the exact modification date.
+pub fn if_range(headers: &HeaderMap, etag: &str, modified: Option<SystemTime>) -> bool {
+    let Some(value) = headers.get(IF_RANGE) else {
+        return true;
+    };
+    let Ok(value) = value.to_str().map(str::trim) else {
+        return false;
+    };
+    if value.starts_with('"') {
+        return value == etag;
+    }
+    modified.is_some_and(|modified| {
+        DateTime::parse_from_rfc2822(value)
+            .is_ok_and(|date| DateTime::<Utc>::from(modified).timestamp() == date.timestamp())
+    })
+}
+
+/// Reads the version a client expects from `If-Match`, as a bare or quoted
+/// integer or a tag from [`versioned_etag`]. A missing header or `*` accepts
+/// any version.
+pub fn if_match_version(headers: &HeaderMap) -> Result<Option<i32>, AppError> {
+    let Some(value) = headers.get(IF_MATCH) else {
+        return Ok(None);
+    };
+    let invalid = || AppError::ValidationError("If-Match must be a record version".into());
+    let value = value.to_str().map_err(|_| invalid())?.trim();
+    if value == "*" {
+        return Ok(None);
+    }
+    let opaque = value.strip_prefix("W/").unwrap_or(value).trim_matches('"');
+    let version = opaque
+        .split_once('-')
+        .map_or(opaque, |(version, _)| version);
+    version.parse::<i32>().map(Some).map_err(|_| invalid())
+}
+
+impl<T: Serialize> RestApiResponse<T> {
+    /// Serializes the response with a weak `ETag`, answering `304 Not Modified`
+    /// when the request's `If-None-Match` already matches it.
+    pub fn into_conditional_response(self, headers: &HeaderMap) -> Result<Response, AppError> {
+        self.conditional_response(headers, None)
+    }
+
+    /// Like [`Self::into_conditional_response`], for a resource at `version`
+    /// whose `ETag` is sent back in `If-Match` when editing it.
+    pub fn into_versioned_conditional_response(
+        self,
+        headers: &HeaderMap,
+        version: i32,
+    ) -> Result<Response, AppError> {
+        self.conditional_response(headers, Some(version))
+    }
+
+    fn conditional_response(
+        self,
+        headers: &HeaderMap,
+        version: Option<i32>,
+    ) -> Result<Response, AppError> {
+        let serialize = |payload: &ApiResponse<T>| {
+            serde_json::to_vec(payload).map_err(|err| {
+                tracing::error!("Failed to serialize response: {err}");
+                AppError::InternalError
+            })
+        };
+        // The tag covers the payload only; the request ID differs every time
+        let body = serialize(&self.0)?;
+        let etag = version.map_or_else(|| weak_etag(&body), |v| versioned_etag(v, &body));
+        let etag_value = HeaderValue::from_str(&etag).map_err(|_| AppError::InternalError)?;
+
+        let not_modified = headers
+            .get(IF_NONE_MATCH)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| if_none_match(v, &etag));
+
+        let mut response = if not_modified {
+            StatusCode::NOT_MODIFIED.into_response()
+        } else {
+            let mut payload = self.0;
+            payload.request_id = current_request_id();
+            let mut response = serialize(&payload)?.into_response();
+            response
+                .headers_mut()
+                .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+            response
+        };
+        let response_headers = response.headers_mut();
+        response_headers.insert(ETAG, etag_value);
+        // Bodies can depend on the caller, so shared caches must not reuse them
+        response_headers.insert(CACHE_CONTROL, HeaderValue::from_static("private, no-cache"));
+        // Entity names follow the caller's preferred language
+        response_headers.insert(VARY, HeaderValue::from_static("Accept-Language"));
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_weak_etag_is_stable_and_content_sensitive() {
+        let etag = weak_etag(br#"{"id":1}"#);
+        assert!(etag.starts_with("W/\"") && etag.ends_with('"'));
+        assert_eq!(etag.len(), 4 + ETAG_DIGEST_BYTES * 2 + 1);
+        assert_eq!(etag, weak_etag(br#"{"id":1}"#));
+        assert_ne!(etag, weak_etag(br#"{"id":2}"#));
+    }
+
+    #[test]
+    fn test_if_match_version_parsing() {
+        let with_if_match = |value: &str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(
+                IF_MATCH,
+                HeaderValue::from_str(value).expect("header value"),
+            );
+            headers
+        };
+
+        assert_eq!(if_match_version(&HeaderMap::new()).ok(), Some(None));
+        assert_eq!(if_match_version(&with_if_match("*")).ok(), Some(None));
+        assert_eq!(if_match_version(&with_if_match("3")).ok(), Some(Some(3)));
+        assert_eq!(
+            if_match_version(&with_if_match("\"7\"")).ok(),
+            Some(Some(7))
+        );
+        assert_eq!(
+            if_match_version(&with_if_match(&versioned_etag(4, b"body"))).ok(),
+            Some(Some(4))
+        );
+        assert!(if_match_version(&with_if_match("W/\"abc\"")).is_err());
+        assert!(if_match_version(&with_if_match(&weak_etag(b"body"))).is_err());
+    }
+
+    #[test]
+    fn test_if_none_match_uses_weak_comparison() {
+        let etag = weak_etag(b"body");
+        let opaque = etag.trim_start_matches("W/");
+
+        assert!(if_none_match(&etag, &etag));
+        assert!(if_none_match(opaque, &etag));
+        assert!(if_none_match(&format!("\"other\", {etag}"), &etag));
+        assert!(if_none_match("*", &etag));
+        assert!(!if_none_match("W/\"other\"", &etag));
+        assert!(!if_none_match("", &etag));
+    }
+
+    #[test]
+    fn test_file_validators() {
+        let modified = UNIX_EPOCH + std::time::Duration::from_secs(784_111_777);
+        let etag = file_etag(42, Some(modified));
+        assert_eq!(http_date(modified), "Sun, 06 Nov 1994 08:49:37 GMT");
+        let with = |name, value: &str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(name, HeaderValue::from_str(value).expect("header value"));
+            headers
+        };
+
+        assert!(file_not_modified(
+            &with(IF_NONE_MATCH, &etag),
+            &etag,
+            Some(modified)
+        ));
+        assert!(file_not_modified(
+            &with(IF_MODIFIED_SINCE, "Sun, 06 Nov 1994 08:49:37 GMT"),
+            &etag,
+            Some(modified)
+        ));
+        assert!(!file_not_modified(
+            &with(IF_MODIFIED_SINCE, "Sun, 06 Nov 1994 08:49:36 GMT"),
+            &etag,
+            Some(modified)
+        ));
+        assert!(!file_not_modified(&HeaderMap::new(), &etag, Some(modified)));
+
+        assert!(if_range(&HeaderMap::new(), &etag, Some(modified)));
+        assert!(if_range(&with(IF_RANGE, &etag), &etag, Some(modified)));
+        assert!(!if_range(
+            &with(IF_RANGE, &format!("W/{etag}")),
+            &etag,
+            Some(modified)
+        ));
+        assert!(if_range(
+            &with(IF_RANGE, "Sun, 06 Nov 1994 08:49:37 GMT"),
+            &etag,
+            Some(modified)
+        ));
+    }
+}
@@ -4,6 +4,7 @@ use argon2::{
     },
     Argon2,
 };
+use ring::digest;
 
 /// Hash the provided password using Argon2.
 pub fn hash_password(password: &str) -> Result<String, argon2::Error> {
@@ -37,6 +38,29 @@ pub fn verify_password(password_hash: &str, password: &str) -> bool {
         .is_ok()
 }
 
+/// Hex SHA-256 digest of a random, high-entropy token such as an API key.
+/// Such tokens need no salt or key stretching, so checking one stays cheap
+/// enough to do on every request.
+pub fn token_digest(token: &str) -> String {
+    digest::digest(&digest::SHA256, token.as_bytes())
+        .as_ref()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+/// Verify that a token matches the provided [`token_digest`], comparing in
+/// constant time.
+pub fn verify_token_digest(expected_digest: &str, token: &str) -> bool {
+    let digest = token_digest(token);
+    digest.len() == expected_digest.len()
+        && digest
+            .bytes()
+            .zip(expected_digest.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -52,6 +76,17 @@ mod tests {
         assert!(!verify_password(&hash, "wrong_password"));
     }
 
+    #[test]
+    fn test_token_digest_and_verify() {
+        let token = "lrk_abcdefgh_secret";
+        let digest = token_digest(token);
+
+        assert_eq!(digest.len(), 64);
+        assert!(verify_token_digest(&digest, token));
+        assert!(!verify_token_digest(&digest, "lrk_abcdefgh_other"));
+        assert!(!verify_token_digest(&digest[..63], token));
+    }
+
     #[test]
     fn test_argon2_jvm_verify() {
         let password = "mySecretPassword";
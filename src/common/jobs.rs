@@ -0,0 +1,501 @@
+//! Background jobs.
+//!
+//! A [`Job`] is registered with the [`JobRunner`] under a unique name and,
+//! optionally, an interval it runs on. Each run is a tokio task recorded as a
+//! row of the `jobs` table, so the history survives restarts; runs a restart
+//! cut short are marked failed when the runner starts. A job never runs twice
+//! at once: triggering a running job gets `409` and a scheduled run is
+//! skipped.
+//!
+//! Admins list the jobs at `GET /admin/jobs`, start one with
+//! `POST /admin/jobs/{name}/run` and follow its runs at
+//! `GET /admin/jobs/{name}/runs` and `GET /admin/job-runs/{id}`.
+
+use crate::common::{
+    app_state::AppState,
+    dto::{ApiResponse, RestApiResponse},
+    error::AppError,
+    jwt::{with_role, Role},
+    openapi::{ErrorResponsesAddon, SecurityAddon},
+};
+use crate::entities::{jobs, JobsEntity};
+use async_trait::async_trait;
+use axum::{
+    extract::{Path, Query, State},
+    response::IntoResponse,
+    routing::{get, post},
+    Router,
+};
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    ActiveModelTrait as _, ActiveValue::Set, ColumnTrait as _, DatabaseConnection, DbErr,
+    EntityTrait as _, QueryFilter as _, QueryOrder as _, QuerySelect as _,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+use tokio::time::MissedTickBehavior;
+use utoipa::{IntoParams, OpenApi, ToSchema};
+
+/// Default number of runs listed per job.
+pub const DEFAULT_JOB_RUNS: u64 = 20;
+
+/// Most runs listed per job.
+pub const MAX_JOB_RUNS: u64 = 200;
+
+/// Work the [`JobRunner`] runs in the background.
+#[async_trait]
+pub trait Job: Send + Sync {
+    /// Unique name the job is listed and triggered by.
+    fn name(&self) -> &'static str;
+
+    /// Time between scheduled runs, or `None` when it only runs on demand.
+    fn interval(&self) -> Option<Duration>;
+
+    /// Does the work once and describes what was done.
+    async fn run(&self) -> Result<String, AppError>;
+}
+
+/// What started a job run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum JobTrigger {
+    Schedule,
+    Manual,
+}
+
+impl JobTrigger {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Schedule => "schedule",
+            Self::Manual => "manual",
+        }
+    }
+
+    fn parse(value: &str) -> Self {
+        match value {
+            "schedule" => Self::Schedule,
+            _ => Self::Manual,
+        }
+    }
+}
+
+/// State of a job run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum JobRunStatus {
+    Running,
+    Succeeded,
+    Failed,
+}
+
+impl JobRunStatus {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Running => "running",
+            Self::Succeeded => "succeeded",
+            Self::Failed => "failed",
+        }
+    }
+
+    fn parse(value: &str) -> Self {
+        match value {
+            "running" => Self::Running,
+            "succeeded" => Self::Succeeded,
+            _ => Self::Failed,
+        }
+    }
+}
+
+/// One run of a job.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct JobRunDto {
+    pub id: i64,
+    /// Name of the job.
+    pub name: String,
+    pub trigger: JobTrigger,
+    pub status: JobRunStatus,
+    pub started_at: DateTime<Utc>,
+    /// Unset while the run is going.
+    pub finished_at: Option<DateTime<Utc>>,
+    /// What a successful run did.
+    pub summary: Option<String>,
+    /// Why a failed run failed.
+    pub error: Option<String>,
+}
+
+impl From<jobs::Model> for JobRunDto {
+    fn from(model: jobs::Model) -> Self {
+        Self {
+            id: model.id,
+            name: model.name,
+            trigger: JobTrigger::parse(&model.trigger),
+            status: JobRunStatus::parse(&model.status),
+            started_at: model.started_at,
+            finished_at: model.finished_at,
+            summary: model.summary,
+            error: model.error,
+        }
+    }
+}
+
+/// A registered job and its latest run.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct JobDto {
+    pub name: String,
+    /// Seconds between scheduled runs; unset for jobs that only run on
+    /// demand.
+    pub interval_secs: Option<u64>,
+    /// Whether a run is going.
+    pub running: bool,
+    pub last_run: Option<JobRunDto>,
+}
+
+/// Query parameters of `GET /admin/jobs/{name}/runs`.
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct JobRunsQuery {
+    /// Number of runs, newest first (default [`DEFAULT_JOB_RUNS`], at most
+    /// [`MAX_JOB_RUNS`])
+    pub limit: Option<u64>,
+}
+
+/// Runs the registered jobs on their schedule and on demand, recording each
+/// run in the `jobs` table.
+pub struct JobRunner {
+    db: DatabaseConnection,
+    jobs: Vec<Arc<dyn Job>>,
+    /// Names of the jobs with a run going.
+    running: Arc<Mutex<HashSet<&'static str>>>,
+}
+
+impl JobRunner {
+    /// Runner of `jobs`, whose names must be unique.
+    pub fn new(db: DatabaseConnection, jobs: Vec<Arc<dyn Job>>) -> Self {
+        Self {
+            db,
+            jobs,
+            running: Arc::new(Mutex::new(HashSet::new())),
+        }
+    }
+
+    /// Marks the runs a restart cut short as failed and starts the schedule
+    /// of every job with an interval. A job's first scheduled run comes one
+    /// interval after startup.
+    pub fn start(self: &Arc<Self>) {
+        let runner = Arc::clone(self);
+        tokio::spawn(async move {
+            if let Err(e) = runner.fail_interrupted_runs().await {
+                tracing::warn!("Failed to mark interrupted job runs: {e}");
+            }
+            for job in &runner.jobs {
+                let Some(period) = job.interval() else {
+                    continue;
+                };
+                let runner = Arc::clone(&runner);
+                let job = Arc::clone(job);
+                tokio::spawn(async move {
+                    let mut ticker = tokio::time::interval(period);
+                    ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+                    // The first tick completes at once
+                    ticker.tick().await;
+                    loop {
+                        ticker.tick().await;
+                        match runner.start_run(&job, JobTrigger::Schedule).await {
+                            Ok(_) => {}
+                            Err(AppError::Conflict(_)) => {
+                                tracing::info!("Job {} still running, skipping", job.name());
+                            }
+                            Err(e) => tracing::warn!("Failed to start job {}: {e}", job.name()),
+                        }
+                    }
+                });
+            }
+        });
+    }
+
+    /// Registered jobs by name, with their latest run.
+    pub async fn list(&self) -> Result<Vec<JobDto>, AppError> {
+        let mut jobs = Vec::with_capacity(self.jobs.len());
+        for job in &self.jobs {
+            let last_run = JobsEntity::find()
+                .filter(jobs::Column::Name.eq(job.name()))
+                .order_by_desc(jobs::Column::Id)
+                .one(&self.db)
+                .await
+                .map_err(AppError::DatabaseError)?;
+            jobs.push(JobDto {
+                name: job.name().to_owned(),
+                interval_secs: job.interval().map(|interval| interval.as_secs()),
+                running: self.is_running(job.name()),
+                last_run: last_run.map(JobRunDto::from),
+            });
+        }
+        jobs.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(jobs)
+    }
+
+    /// Starts a run of the job `name` now and returns it as recorded.
+    pub async fn trigger(&self, name: &str) -> Result<JobRunDto, AppError> {
+        let job = Arc::clone(self.job(name)?);
+        self.start_run(&job, JobTrigger::Manual).await
+    }
+
+    /// Runs of the job `name`, newest first.
+    pub async fn runs(&self, name: &str, query: JobRunsQuery) -> Result<Vec<JobRunDto>, AppError> {
+        let job = self.job(name)?;
+        let limit = query.limit.unwrap_or(DEFAULT_JOB_RUNS);
+        if limit == 0 {
+            return Err(AppError::ValidationError("limit must be > 0".into()));
+        }
+        let runs = JobsEntity::find()
+            .filter(jobs::Column::Name.eq(job.name()))
+            .order_by_desc(jobs::Column::Id)
+            .limit(limit.min(MAX_JOB_RUNS))
+            .all(&self.db)
+            .await
+            .map_err(AppError::DatabaseError)?;
+        Ok(runs.into_iter().map(JobRunDto::from).collect())
+    }
+
+    /// The run `id`.
+    pub async fn run(&self, id: i64) -> Result<JobRunDto, AppError> {
+        JobsEntity::find_by_id(id)
+            .one(&self.db)
+            .await
+            .map_err(AppError::DatabaseError)?
+            .map(JobRunDto::from)
+            .ok_or_else(|| AppError::NotFound(format!("Job run {id} not found")))
+    }
+
+    fn job(&self, name: &str) -> Result<&Arc<dyn Job>, AppError> {
+        self.jobs
+            .iter()
+            .find(|job| job.name() == name)
+            .ok_or_else(|| AppError::NotFound(format!("Job '{name}' not found")))
+    }
+
+    fn is_running(&self, name: &str) -> bool {
+        self.running
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .contains(name)
+    }
+
+    /// Records a run of `job` and spawns it, unless one is going already.
+    async fn start_run(
+        &self,
+        job: &Arc<dyn Job>,
+        trigger: JobTrigger,
+    ) -> Result<JobRunDto, AppError> {
+        let claim = RunClaim::acquire(&self.running, job.name()).ok_or_else(|| {
+            AppError::Conflict(format!("Job '{}' is already running", job.name()))
+        })?;
+        let row = jobs::ActiveModel {
+            name: Set(job.name().to_owned()),
+            trigger: Set(trigger.as_str().to_owned()),
+            status: Set(JobRunStatus::Running.as_str().to_owned()),
+            started_at: Set(Utc::now()),
+            ..Default::default()
+        }
+        .insert(&self.db)
+        .await
+        .map_err(AppError::DatabaseError)?;
+
+        let db = self.db.clone();
+        let job = Arc::clone(job);
+        let id = row.id;
+        tokio::spawn(async move {
+            let _claim = claim;
+            // Run in a task of its own so a panic is recorded as a failure
+            let outcome = match tokio::spawn(async move { job.run().await }).await {
+                Ok(outcome) => outcome.map_err(|e| e.to_string()),
+                Err(e) => Err(format!("Job task failed: {e}")),
+            };
+            if let Err(e) = finish_run(&db, id, outcome).await {
+                tracing::error!("Failed to record the end of job run {id}: {e}");
+            }
+        });
+        Ok(row.into())
+    }
+
+    async fn fail_interrupted_runs(&self) -> Result<(), DbErr> {
+        JobsEntity::update_many()
+            .col_expr(
+                jobs::Column::Status,
+                JobRunStatus::Failed.as_str().to_owned().into(),
+            )
+            .col_expr(jobs::Column::FinishedAt, Utc::now().into())
+            .col_expr(
+                jobs::Column::Error,
+                Some("Interrupted by a restart".to_owned()).into(),
+            )
+            .filter(jobs::Column::Status.eq(JobRunStatus::Running.as_str()))
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+}
+
+/// A job's place in the set of running jobs, given up on drop.
+struct RunClaim {
+    running: Arc<Mutex<HashSet<&'static str>>>,
+    name: &'static str,
+}
+
+impl RunClaim {
+    fn acquire(running: &Arc<Mutex<HashSet<&'static str>>>, name: &'static str) -> Option<Self> {
+        let claimed = running
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(name);
+        claimed.then(|| Self {
+            running: Arc::clone(running),
+            name,
+        })
+    }
+}
+
+impl Drop for RunClaim {
+    fn drop(&mut self) {
+        self.running
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(self.name);
+    }
+}
+
+/// Records how run `id` ended.
+async fn finish_run(
+    db: &DatabaseConnection,
+    id: i64,
+    outcome: Result<String, String>,
+) -> Result<(), DbErr> {
+    let (status, summary, error) = match outcome {
+        Ok(summary) => (JobRunStatus::Succeeded, Some(summary), None),
+        Err(error) => {
+            tracing::warn!("Job run {id} failed: {error}");
+            (JobRunStatus::Failed, None, Some(error))
+        }
+    };
+    jobs::ActiveModel {
+        id: Set(id),
+        status: Set(status.as_str().to_owned()),
+        finished_at: Set(Some(Utc::now())),
+        summary: Set(summary),
+        error: Set(error),
+        ..Default::default()
+    }
+    .update(db)
+    .await?;
+    Ok(())
+}
+
+/// Lists the background jobs with their latest run.
+#[utoipa::path(
+    get,
+    path = "/admin/jobs",
+    responses(
+        (status = 200, description = "Registered jobs by name", body = ApiResponse<Vec<JobDto>>),
+        (status = 403, description = "Caller is not an admin")
+    ),
+    tag = "Jobs"
+)]
+pub async fn list_jobs(State(state): State<AppState>) -> Result<impl IntoResponse, AppError> {
+    let jobs = state.job_runner.list().await?;
+    Ok(RestApiResponse::success(jobs))
+}
+
+/// Starts a run of a job now.
+#[utoipa::path(
+    post,
+    path = "/admin/jobs/{name}/run",
+    params(("name" = String, Path, description = "Job name")),
+    responses(
+        (status = 200, description = "The run, as started", body = ApiResponse<JobRunDto>),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 404, description = "No job of that name"),
+        (status = 409, description = "The job is already running")
+    ),
+    tag = "Jobs"
+)]
+pub async fn trigger_job(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let run = state.job_runner.trigger(&name).await?;
+    Ok(RestApiResponse::success(run))
+}
+
+/// Lists the runs of a job, newest first.
+#[utoipa::path(
+    get,
+    path = "/admin/jobs/{name}/runs",
+    params(("name" = String, Path, description = "Job name"), JobRunsQuery),
+    responses(
+        (status = 200, description = "Runs of the job, newest first", body = ApiResponse<Vec<JobRunDto>>),
+        (status = 400, description = "limit is 0"),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 404, description = "No job of that name")
+    ),
+    tag = "Jobs"
+)]
+pub async fn list_job_runs(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Query(query): Query<JobRunsQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let runs = state.job_runner.runs(&name, query).await?;
+    Ok(RestApiResponse::success(runs))
+}
+
+/// Gets one job run.
+#[utoipa::path(
+    get,
+    path = "/admin/job-runs/{id}",
+    params(("id" = i64, Path, description = "Job run ID")),
+    responses(
+        (status = 200, description = "The job run", body = ApiResponse<JobRunDto>),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 404, description = "No run with that ID")
+    ),
+    tag = "Jobs"
+)]
+pub async fn get_job_run(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, AppError> {
+    let run = state.job_runner.run(id).await?;
+    Ok(RestApiResponse::success(run))
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(list_jobs, trigger_job, list_job_runs, get_job_run),
+    components(schemas(JobDto, JobRunDto, JobTrigger, JobRunStatus)),
+    tags(
+        (name = "Jobs", description = "Background jobs and their runs")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    modifiers(&SecurityAddon, &ErrorResponsesAddon)
+)]
+/// This struct is used to generate `OpenAPI` documentation for the job routes.
+pub struct JobsApiDoc;
+
+/// This function creates a router for the admin job routes.
+pub fn job_routes() -> Router<AppState> {
+    Router::new()
+        .route("/jobs", with_role(Role::Admin, get(list_jobs)))
+        .route(
+            "/jobs/{name}/run",
+            with_role(Role::Admin, post(trigger_job)),
+        )
+        .route(
+            "/jobs/{name}/runs",
+            with_role(Role::Admin, get(list_job_runs)),
+        )
+        .route("/job-runs/{id}", with_role(Role::Admin, get(get_job_run)))
+}
@@ -1,7 +1,9 @@
 use axum::{
-    extract::Request,
-    middleware::Next,
+    extract::{Request, State},
+    http::{header::USER_AGENT, HeaderMap},
+    middleware::{self, Next},
     response::{IntoResponse as _, Response},
+    routing::MethodRouter,
 };
 
 use chrono::{Duration, Utc};
@@ -11,7 +13,7 @@ use std::sync::LazyLock;
 use std::{env, fmt::Display};
 use utoipa::ToSchema;
 
-use super::error::AppError;
+use super::{app_state::AppState, error::AppError, rate_limit::client_ip};
 
 /// `JWT_SECRET_KEY` is the environment variable that holds the secret key for JWT encoding and decoding.
 ///
@@ -24,6 +26,18 @@ pub static KEYS: LazyLock<Keys> = LazyLock::new(|| {
     Keys::new(secret.as_bytes())
 });
 
+/// Keys for TOTP login challenge tokens. They are derived from `JWT_SECRET_KEY`
+/// but differ from [`KEYS`], so a challenge token is never accepted as an access token.
+static CHALLENGE_KEYS: LazyLock<Keys> = LazyLock::new(|| {
+    dotenvy::dotenv().ok();
+
+    let secret = env::var("JWT_SECRET_KEY").expect("JWT_SECRET_KEY must be set");
+    Keys::new(format!("{secret}:totp-challenge").as_bytes())
+});
+
+/// How long a TOTP login challenge token stays valid.
+pub const CHALLENGE_TOKEN_TTL_MINUTES: i64 = 5;
+
 /// Keys is a struct that holds the encoding and decoding keys for JWT.
 pub struct Keys {
     pub encoding: EncodingKey,
@@ -40,9 +54,51 @@ impl Keys {
     }
 }
 
+/// Access role of a user, ordered from least to most privileged.
+///
+/// A route guarded by [`require_role`] admits its minimum role and every
+/// role above it.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    /// Read-only access.
+    #[default]
+    Viewer,
+    /// May create, update and delete cards and upload media.
+    Editor,
+    /// Full access, including user management.
+    Admin,
+}
+
+impl Display for Role {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Viewer => "viewer",
+            Self::Editor => "editor",
+            Self::Admin => "admin",
+        };
+        write!(f, "{s}")
+    }
+}
+
+impl std::str::FromStr for Role {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "viewer" => Ok(Self::Viewer),
+            "editor" => Ok(Self::Editor),
+            "admin" => Ok(Self::Admin),
+            _ => Err(AppError::ValidationError(format!("Unknown role: {s}"))),
+        }
+    }
+}
+
 /// Claims is a struct that represents the claims in the JWT token.
 ///
-/// It contains the subject (user ID), expiration time, and issued at time.
+/// It contains the subject (user ID), expiration time, issued at time and role.
 /// The `sub` field is the user ID, `exp` is the expiration time, and `iat` is the issued at time.
 /// The `Claims` struct is used to encode and decode the JWT tokens.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -50,6 +106,17 @@ pub struct Claims {
     pub sub: String,
     pub exp: usize,
     pub iat: usize,
+    /// Role of the user at login; tokens issued before roles existed read as `viewer`.
+    #[serde(default)]
+    pub role: Role,
+    /// Device session the token was issued for. [`jwt_auth`] rejects the token
+    /// once that device is revoked.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub device_id: Option<String>,
+    /// Token generation of the user at issue. [`jwt_auth`] rejects the token
+    /// once the user's generation has moved on, e.g. after a password change.
+    #[serde(default)]
+    pub generation: i32,
 }
 
 /// The Claims struct implements the `Display` trait for easy printing.
@@ -72,6 +139,9 @@ impl Default for Claims {
             sub: String::new(),
             exp,
             iat,
+            role: Role::default(),
+            device_id: None,
+            generation: 0,
         }
     }
 }
@@ -81,15 +151,20 @@ impl Default for Claims {
 pub struct AuthBody {
     pub access_token: String,
     pub token_type: String,
+    /// Long-lived token exchanged at `/auth/refresh` for a new access token.
+    /// Each use rotates it, so only the most recently issued one is valid.
+    #[serde(default)]
+    pub refresh_token: String,
 }
 
 /// The `AuthBody` struct is used to create a new instance of the authentication body.
-/// It takes an access token as a parameter and sets the token type to "Bearer".
+/// It takes an access token and a refresh token as parameters and sets the token type to "Bearer".
 impl AuthBody {
-    pub fn new(access_token: String) -> Self {
+    pub fn new(access_token: String, refresh_token: String) -> Self {
         Self {
             access_token,
             token_type: "Bearer".to_owned(),
+            refresh_token,
         }
     }
 }
@@ -103,10 +178,19 @@ pub struct AuthPayload {
 }
 
 /// `make_jwt_token` is a function that creates a JWT token.
-/// It takes a user ID as a parameter and returns a Result with the JWT token or an error.
-pub fn make_jwt_token(user_id: &str) -> Result<String, AppError> {
+/// It takes a user ID, role, optional device session and the user's token
+/// generation and returns a Result with the JWT token or an error.
+pub fn make_jwt_token(
+    user_id: &str,
+    role: Role,
+    device_id: Option<String>,
+    generation: i32,
+) -> Result<String, AppError> {
     let claims = Claims {
         sub: user_id.to_owned(),
+        role,
+        device_id,
+        generation,
         ..Default::default()
     };
     encode(&Header::default(), &claims, &KEYS.encoding).map_err(|err| {
@@ -115,30 +199,174 @@ pub fn make_jwt_token(user_id: &str) -> Result<String, AppError> {
     })
 }
 
-/// Middleware to validate JWT tokens.
-/// If the token is valid, the request proceeds; otherwise, a 401 Unauthorized is returned.
-pub async fn jwt_auth<B>(mut req: Request<B>, next: Next) -> Result<Response, Response>
-where
-    B: Send + Into<axum::body::Body>,
-{
+/// Claims of a TOTP login challenge token, issued after the password check
+/// of an account with two-factor authentication.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChallengeClaims {
+    pub sub: String,
+    pub exp: usize,
+    pub iat: usize,
+    /// Device the login was for, carried over to the issued refresh token.
+    pub device_id: Option<String>,
+}
+
+/// Creates a challenge token for a user whose password was verified.
+pub fn make_challenge_token(user_id: &str, device_id: Option<String>) -> Result<String, AppError> {
+    let now = Utc::now();
+    let claims = ChallengeClaims {
+        sub: user_id.to_owned(),
+        exp: (now + Duration::minutes(CHALLENGE_TOKEN_TTL_MINUTES)).timestamp() as usize,
+        iat: now.timestamp() as usize,
+        device_id,
+    };
+    encode(&Header::default(), &claims, &CHALLENGE_KEYS.encoding).map_err(|err| {
+        tracing::error!("Error encoding challenge token: {:?}", err);
+        AppError::TokenCreation
+    })
+}
+
+/// Validates a challenge token and returns its claims.
+pub fn decode_challenge_token(token: &str) -> Result<ChallengeClaims, AppError> {
+    decode::<ChallengeClaims>(token, &CHALLENGE_KEYS.decoding, &Validation::default())
+        .map(|data| data.claims)
+        .map_err(|err| {
+            tracing::error!("Error decoding challenge token: {:?}", err);
+            AppError::InvalidToken
+        })
+}
+
+/// Header carrying an API key, accepted by [`jwt_auth`] in place of a Bearer token.
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// Resolves the caller's claims from an `X-Api-Key` header or, failing that,
+/// an `Authorization: Bearer` header.
+/// Tokens are only accepted while their generation is the user's current one.
+/// Tokens bound to a device are only accepted while that device is active, and
+/// `client_ip` is recorded as the session's latest address.
+pub async fn authenticate(
+    state: &AppState,
+    headers: &HeaderMap,
+    client_ip: Option<String>,
+) -> Result<Claims, AppError> {
+    let api_key = headers
+        .get(API_KEY_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(|k| k.trim())
+        .filter(|k| !k.is_empty());
+
+    if let Some(api_key) = api_key {
+        return state.auth_service.authenticate_api_key(api_key).await;
+    }
+
     // Try to extract and trim the token in one go.
-    let token = req
-        .headers()
+    let token = headers
         .get("Authorization")
         .and_then(|v| v.to_str().ok())
         .and_then(|header| header.strip_prefix("Bearer "))
         .map(|t| t.trim())
         .filter(|t| !t.is_empty())
-        .ok_or_else(|| AppError::InvalidToken.into_response())?;
+        .ok_or(AppError::InvalidToken)?;
 
     // Validate and decode the token.
-    let token_data =
-        decode::<Claims>(token, &KEYS.decoding, &Validation::default()).map_err(|err| {
+    let claims = decode::<Claims>(token, &KEYS.decoding, &Validation::default())
+        .map_err(|err| {
             tracing::error!("Error decoding token: {:?}", err);
-            AppError::InvalidToken.into_response()
-        })?;
+            AppError::InvalidToken
+        })?
+        .claims;
+
+    // Reject tokens issued before the user's sessions were last revoked.
+    state.auth_service.check_token_generation(&claims).await?;
+
+    // Reject tokens of revoked devices and record the session's activity.
+    if let Some(device_id) = &claims.device_id {
+        let user_agent = headers
+            .get(USER_AGENT)
+            .and_then(|v| v.to_str().ok())
+            .map(ToOwned::to_owned);
+        state
+            .device_service
+            .touch_session(&claims.sub, device_id, user_agent, client_ip)
+            .await?;
+    }
+    Ok(claims)
+}
+
+/// Middleware to authenticate requests by JWT token or API key (see [`authenticate`]).
+/// If the credential is valid, the request proceeds; otherwise, a 401 Unauthorized is returned.
+pub async fn jwt_auth(
+    State(state): State<AppState>,
+    mut req: Request,
+    next: Next,
+) -> Result<Response, Response> {
+    let ip_address = client_ip(&req, state.config.trust_forwarded_for);
+    let claims = authenticate(&state, req.headers(), ip_address)
+        .await
+        .map_err(|err| err.into_response())?;
+
+    // Insert the resolved claims into the request extensions, and into the
+    // response's so the access log can name the user.
+    req.extensions_mut().insert(claims.clone());
+    let mut response = next.run(req).await;
+    response.extensions_mut().insert(claims);
+    Ok(response)
+}
+
+// Type alias for the boxed future returned by the role guard middleware
+type RoleGuardFuture = std::pin::Pin<Box<dyn std::future::Future<Output = Response> + Send>>;
 
-    // Insert the decoded claims into the request extensions.
-    req.extensions_mut().insert(token_data.claims);
-    Ok(next.run(req.map(Into::into)).await)
+/// Middleware factory that admits only callers whose role is at least `min`.
+///
+/// Must run inside [`jwt_auth`] so the request carries [`Claims`]; use it as
+/// `middleware::from_fn(require_role(Role::Editor))`. Returns 403 Forbidden
+/// for lower roles.
+pub fn require_role(
+    min: Role,
+) -> impl Fn(Request, Next) -> RoleGuardFuture + Clone + Send + Sync + 'static {
+    move |req, next| {
+        Box::pin(async move {
+            let allowed = req
+                .extensions()
+                .get::<Claims>()
+                .is_some_and(|claims| claims.role >= min);
+            if !allowed {
+                return AppError::Forbidden.into_response();
+            }
+            next.run(req).await
+        })
+    }
+}
+
+/// Restricts a method router to callers whose role is at least `min`.
+pub fn with_role<S>(min: Role, route: MethodRouter<S>) -> MethodRouter<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    route.route_layer(middleware::from_fn(require_role(min)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Claims, Role};
+
+    #[test]
+    fn roles_are_ordered_by_privilege() {
+        assert!(Role::Viewer < Role::Editor);
+        assert!(Role::Editor < Role::Admin);
+    }
+
+    #[test]
+    fn claims_without_role_default_to_viewer() {
+        let claims: Claims =
+            serde_json::from_str(r#"{"sub":"u","exp":1,"iat":1}"#).expect("valid claims");
+        assert_eq!(claims.role, Role::Viewer);
+    }
+
+    #[test]
+    fn role_round_trips_through_str() {
+        for role in [Role::Viewer, Role::Editor, Role::Admin] {
+            assert_eq!(role.to_string().parse::<Role>().ok(), Some(role));
+        }
+        assert!("owner".parse::<Role>().is_err());
+    }
 }
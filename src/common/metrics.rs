@@ -0,0 +1,208 @@
+//! Prometheus metrics, enabled with the `metrics` feature.
+//!
+//! `GET /metrics` exports, in the Prometheus text format:
+//! - request counts and latencies per method, route template and status,
+//! - database query latencies and the connection pool's size,
+//! - bytes of media served,
+//! - business counters such as records created and logins.
+//!
+//! Routes are labelled by their template (`/cards/records/{id}`), never by the
+//! raw path, so the number of series stays bounded.
+
+use std::{sync::OnceLock, time::Instant};
+
+use axum::{
+    extract::{MatchedPath, Request},
+    http::{header::CONTENT_TYPE, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    routing::get,
+    Router,
+};
+use once_cell::sync::Lazy;
+use prometheus::{
+    core::Collector, Encoder as _, HistogramOpts, HistogramVec, IntCounter, IntCounterVec,
+    IntGauge, Opts, Registry, TextEncoder,
+};
+use sea_orm::{metric::Info, DatabaseConnection};
+
+use crate::common::app_state::AppState;
+
+/// Route label for requests that matched no route.
+const UNMATCHED_ROUTE: &str = "unmatched";
+
+/// Latency buckets in seconds, from a fast cache hit to a slow export.
+const LATENCY_BUCKETS: &[f64] = &[
+    0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// Every metric the service exports, registered in one registry.
+struct Metrics {
+    registry: Registry,
+    http_requests: IntCounterVec,
+    http_duration: HistogramVec,
+    db_queries: HistogramVec,
+    db_pool_connections: IntGauge,
+    db_pool_idle: IntGauge,
+    media_bytes: IntCounter,
+    records_created: IntCounter,
+    logins: IntCounterVec,
+}
+
+static METRICS: Lazy<Metrics> =
+    Lazy::new(|| Metrics::new().expect("Failed to register Prometheus metrics"));
+
+/// Pool whose statistics are sampled on every scrape.
+static POOL: OnceLock<DatabaseConnection> = OnceLock::new();
+
+impl Metrics {
+    fn new() -> prometheus::Result<Self> {
+        let registry = Registry::new_custom(Some("lunirelust".to_owned()), None)?;
+        let metrics = Self {
+            http_requests: IntCounterVec::new(
+                Opts::new("http_requests_total", "HTTP requests handled"),
+                &["method", "route", "status"],
+            )?,
+            http_duration: HistogramVec::new(
+                HistogramOpts::new(
+                    "http_request_duration_seconds",
+                    "Time taken to handle an HTTP request",
+                )
+                .buckets(LATENCY_BUCKETS.to_vec()),
+                &["method", "route"],
+            )?,
+            db_queries: HistogramVec::new(
+                HistogramOpts::new(
+                    "db_query_duration_seconds",
+                    "Time taken by database queries",
+                )
+                .buckets(LATENCY_BUCKETS.to_vec()),
+                &["outcome"],
+            )?,
+            db_pool_connections: IntGauge::new(
+                "db_pool_connections",
+                "Open connections in the database pool",
+            )?,
+            db_pool_idle: IntGauge::new(
+                "db_pool_idle_connections",
+                "Idle connections in the database pool",
+            )?,
+            media_bytes: IntCounter::new("media_bytes_served_total", "Bytes of media served")?,
+            records_created: IntCounter::new("records_created_total", "Records created")?,
+            logins: IntCounterVec::new(Opts::new("logins_total", "Login attempts"), &["outcome"])?,
+            registry,
+        };
+        let collectors: [Box<dyn Collector>; 8] = [
+            Box::new(metrics.http_requests.clone()),
+            Box::new(metrics.http_duration.clone()),
+            Box::new(metrics.db_queries.clone()),
+            Box::new(metrics.db_pool_connections.clone()),
+            Box::new(metrics.db_pool_idle.clone()),
+            Box::new(metrics.media_bytes.clone()),
+            Box::new(metrics.records_created.clone()),
+            Box::new(metrics.logins.clone()),
+        ];
+        for collector in collectors {
+            metrics.registry.register(collector)?;
+        }
+        Ok(metrics)
+    }
+}
+
+/// Middleware that counts and times every request by its route template.
+pub async fn track_http(req: Request, next: Next) -> Response {
+    let route = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map_or(UNMATCHED_ROUTE, MatchedPath::as_str)
+        .to_owned();
+    let method = req.method().as_str().to_owned();
+    let started = Instant::now();
+
+    let response = next.run(req).await;
+
+    let status = response.status().as_u16().to_string();
+    METRICS
+        .http_requests
+        .with_label_values(&[method.as_str(), route.as_str(), status.as_str()])
+        .inc();
+    METRICS
+        .http_duration
+        .with_label_values(&[method.as_str(), route.as_str()])
+        .observe(started.elapsed().as_secs_f64());
+    response
+}
+
+/// Samples the statistics of `db`'s pool on each scrape.
+pub fn register_pool(db: &DatabaseConnection) {
+    // Only the first pool is sampled; the server creates exactly one.
+    let _result: Result<(), _> = POOL.set(db.clone());
+}
+
+/// Times a query; called from the pool's metric callback.
+pub fn observe_query(info: &Info<'_>) {
+    let outcome = if info.failed { "error" } else { "ok" };
+    METRICS
+        .db_queries
+        .with_label_values(&[outcome])
+        .observe(info.elapsed.as_secs_f64());
+}
+
+/// Counts `bytes` of media sent to a client.
+pub fn media_served(bytes: usize) {
+    METRICS
+        .media_bytes
+        .inc_by(u64::try_from(bytes).unwrap_or(u64::MAX));
+}
+
+/// Counts a newly created record.
+pub fn record_created() {
+    METRICS.records_created.inc();
+}
+
+/// Counts a login attempt, successful or not.
+pub fn login_attempt(success: bool) {
+    let outcome = if success { "success" } else { "failure" };
+    METRICS.logins.with_label_values(&[outcome]).inc();
+}
+
+/// Renders every metric in the Prometheus text format.
+async fn export_metrics() -> Response {
+    if let Some(db) = POOL.get() {
+        let pool = db.get_postgres_connection_pool();
+        METRICS.db_pool_connections.set(i64::from(pool.size()));
+        METRICS
+            .db_pool_idle
+            .set(i64::try_from(pool.num_idle()).unwrap_or(i64::MAX));
+    }
+
+    let encoder = TextEncoder::new();
+    let mut buffer = Vec::new();
+    if let Err(err) = encoder.encode(&METRICS.registry.gather(), &mut buffer) {
+        tracing::error!("Failed to encode metrics: {err}");
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    }
+    ([(CONTENT_TYPE, encoder.format_type().to_owned())], buffer).into_response()
+}
+
+/// This function creates a router for the unauthenticated scrape endpoint.
+pub fn metrics_routes() -> Router<AppState> {
+    Router::new().route("/metrics", get(export_metrics))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{record_created, METRICS};
+
+    #[test]
+    fn counters_are_exported() {
+        record_created();
+        let families = METRICS.registry.gather();
+        assert!(
+            families
+                .iter()
+                .any(|f| f.get_name() == "lunirelust_records_created_total"),
+            "records counter missing from the registry"
+        );
+    }
+}
@@ -1,9 +1,20 @@
 use utoipa::{
-    openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme},
-    Modify,
+    openapi::{
+        path::Operation,
+        security::{
+            ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityRequirement, SecurityScheme,
+        },
+        Components, ContentBuilder, OpenApi, Ref, RefOr, Response,
+    },
+    Modify, PartialSchema, ToSchema,
 };
 
-/// Shared `OpenAPI` security addon that adds JWT Bearer authentication scheme.
+use super::{
+    dto::{ErrorResponse, ValidationErrorDetail},
+    error::ErrorCode,
+};
+
+/// Shared `OpenAPI` security addon that adds the JWT Bearer and `X-Api-Key` authentication schemes.
 pub struct SecurityAddon;
 
 impl Modify for SecurityAddon {
@@ -22,5 +33,198 @@ impl Modify for SecurityAddon {
                     .build(),
             ),
         );
+        components.add_security_scheme(
+            "api_key",
+            SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new("X-Api-Key"))),
+        );
+    }
+}
+
+/// Body of 400 responses: an [`ErrorResponse`] whose `details` lists every
+/// failed rule when the code is `VALIDATION_FAILED`. Only used for the schema.
+#[derive(ToSchema)]
+pub struct ValidationErrorResponse {
+    /// Always 400.
+    pub status: u16,
+    /// `VALIDATION_FAILED`, or `BAD_REQUEST` for malformed parameters.
+    pub code: ErrorCode,
+    /// Summary of what was rejected.
+    pub message: String,
+    /// One entry per failed rule on `VALIDATION_FAILED`, `null` otherwise.
+    pub details: Option<Vec<ValidationErrorDetail>>,
+}
+
+/// Shared `OpenAPI` addon documenting error bodies. Every 4xx/5xx response
+/// without a body gets [`ErrorResponse`] (or [`ValidationErrorResponse`] for
+/// 400), and each operation gains the errors any handler can return: 400 when
+/// it takes a body, 401 when it needs credentials and 500.
+pub struct ErrorResponsesAddon;
+
+impl Modify for ErrorResponsesAddon {
+    fn modify(&self, openapi: &mut OpenApi) {
+        openapi
+            .components
+            .get_or_insert_with(Components::new)
+            .schemas
+            .extend([
+                (ErrorResponse::name().into_owned(), ErrorResponse::schema()),
+                (
+                    ValidationErrorResponse::name().into_owned(),
+                    ValidationErrorResponse::schema(),
+                ),
+                (
+                    ValidationErrorDetail::name().into_owned(),
+                    ValidationErrorDetail::schema(),
+                ),
+                (ErrorCode::name().into_owned(), ErrorCode::schema()),
+            ]);
+
+        let secured_by_default = openapi
+            .security
+            .as_ref()
+            .is_some_and(|requirements| !requirements.is_empty());
+        for item in openapi.paths.paths.values_mut() {
+            let operations = [
+                &mut item.get,
+                &mut item.put,
+                &mut item.post,
+                &mut item.delete,
+                &mut item.options,
+                &mut item.head,
+                &mut item.patch,
+                &mut item.trace,
+            ];
+            for operation in operations.into_iter().flatten() {
+                document_errors(operation, secured_by_default);
+            }
+        }
+    }
+}
+
+fn document_errors(operation: &mut Operation, secured_by_default: bool) {
+    // `security(())` marks a public operation with one empty requirement.
+    let secured = operation
+        .security
+        .as_ref()
+        .map_or(secured_by_default, |requirements| {
+            requirements
+                .iter()
+                .any(|requirement| *requirement != SecurityRequirement::default())
+        });
+
+    let responses = &mut operation.responses.responses;
+    if operation.request_body.is_some() {
+        responses
+            .entry("400".to_owned())
+            .or_insert_with(|| Response::new("Validation error").into());
+    }
+    if secured {
+        responses
+            .entry("401".to_owned())
+            .or_insert_with(|| Response::new("Unauthorized").into());
+    }
+    responses
+        .entry("500".to_owned())
+        .or_insert_with(|| Response::new("Internal server error").into());
+
+    for (status, response) in responses.iter_mut() {
+        let RefOr::T(response) = response else {
+            continue;
+        };
+        if !(status.starts_with('4') || status.starts_with('5')) || !response.content.is_empty() {
+            continue;
+        }
+        let schema = if status == "400" {
+            ValidationErrorResponse::name()
+        } else {
+            ErrorResponse::name()
+        };
+        response.content.insert(
+            "application/json".to_owned(),
+            ContentBuilder::new()
+                .schema(Some(Ref::from_schema_name(schema)))
+                .build(),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{document_errors, ErrorResponsesAddon};
+    use utoipa::{
+        openapi::{
+            path::{HttpMethod, OperationBuilder, PathItem, PathsBuilder},
+            request_body::RequestBodyBuilder,
+            security::SecurityRequirement,
+            OpenApiBuilder, RefOr, ResponseBuilder,
+        },
+        Modify,
+    };
+
+    fn content_ref(operation: &utoipa::openapi::path::Operation, status: &str) -> Option<String> {
+        let RefOr::T(response) = operation.responses.responses.get(status)? else {
+            return None;
+        };
+        let schema = response.content.get("application/json")?.schema.as_ref()?;
+        match schema {
+            RefOr::Ref(reference) => Some(reference.ref_location.clone()),
+            RefOr::T(_) => None,
+        }
+    }
+
+    #[test]
+    fn errors_get_bodies_and_default_responses() {
+        let mut operation = OperationBuilder::new()
+            .request_body(Some(RequestBodyBuilder::new().build()))
+            .response(
+                "404",
+                ResponseBuilder::new().description("Not found").build(),
+            )
+            .build();
+        document_errors(&mut operation, true);
+
+        assert_eq!(
+            content_ref(&operation, "400").as_deref(),
+            Some("#/components/schemas/ValidationErrorResponse")
+        );
+        for status in ["401", "404", "500"] {
+            assert_eq!(
+                content_ref(&operation, status).as_deref(),
+                Some("#/components/schemas/ErrorResponse"),
+                "{status}"
+            );
+        }
+    }
+
+    #[test]
+    fn public_operations_get_no_unauthorized_response() {
+        let mut operation = OperationBuilder::new()
+            .security(SecurityRequirement::default())
+            .build();
+        document_errors(&mut operation, true);
+
+        let statuses: Vec<_> = operation.responses.responses.keys().collect();
+        assert_eq!(statuses, ["500"]);
+    }
+
+    #[test]
+    fn addon_registers_error_schemas() {
+        let mut openapi = OpenApiBuilder::new()
+            .paths(PathsBuilder::new().path(
+                "/ping",
+                PathItem::new(HttpMethod::Get, OperationBuilder::new().build()),
+            ))
+            .build();
+        ErrorResponsesAddon.modify(&mut openapi);
+
+        let schemas = &openapi.components.expect("components are added").schemas;
+        for name in [
+            "ErrorResponse",
+            "ValidationErrorResponse",
+            "ValidationErrorDetail",
+            "ErrorCode",
+        ] {
+            assert!(schemas.contains_key(name), "{name}");
+        }
     }
 }
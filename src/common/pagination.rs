@@ -1,4 +1,5 @@
 use crate::common::config::DEFAULT_PAGE_SIZE;
+use crate::common::error::AppError;
 use crate::domains::luna::dto::{PaginatedResponse, PaginationQuery};
 
 /// Generic pagination helper for in-memory slices.
@@ -43,6 +44,88 @@ pub fn paginate<T, U>(
         count: total_count as i64,
         next,
         previous,
+        next_cursor: None,
         results,
     }
 }
+
+/// One `field` / direction pair parsed from an `ordering` query value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SortKey {
+    pub field: String,
+    pub descending: bool,
+}
+
+/// Parse an `ordering` value such as `-date,title` against an allowlist.
+///
+/// Keys are comma separated; a leading `-` sorts that key descending. Empty
+/// segments are ignored. Unknown fields are rejected with a validation error
+/// listing the allowed names, so a typo surfaces as a 400 instead of being
+/// silently ignored.
+pub fn parse_ordering(raw: &str, allowed: &[&str]) -> Result<Vec<SortKey>, AppError> {
+    let mut keys = Vec::new();
+    for segment in raw.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        let (field, descending) = match segment.strip_prefix('-') {
+            Some(field) => (field, true),
+            None => (segment, false),
+        };
+        if !allowed.contains(&field) {
+            return Err(AppError::ValidationError(format!(
+                "Unsupported ordering field '{field}'; allowed: {}",
+                allowed.join(", ")
+            )));
+        }
+        keys.push(SortKey {
+            field: field.to_string(),
+            descending,
+        });
+    }
+    Ok(keys)
+}
+
+/// Validate the `ordering` of a pagination query against an allowlist.
+///
+/// Returns an empty list when no ordering was requested, meaning the caller
+/// should fall back to its default order.
+pub fn resolve_ordering(
+    pagination: &PaginationQuery,
+    allowed: &[&str],
+) -> Result<Vec<SortKey>, AppError> {
+    pagination
+        .ordering
+        .as_deref()
+        .map_or(Ok(Vec::new()), |raw| parse_ordering(raw, allowed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALLOWED: &[&str] = &["date", "title", "id"];
+
+    #[test]
+    fn parse_ordering_reads_direction_prefixes() {
+        let keys = parse_ordering("-date, title,", ALLOWED).unwrap();
+        assert_eq!(
+            keys,
+            vec![
+                SortKey {
+                    field: "date".to_string(),
+                    descending: true
+                },
+                SortKey {
+                    field: "title".to_string(),
+                    descending: false
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_ordering_rejects_unknown_fields() {
+        assert!(matches!(
+            parse_ordering("-password", ALLOWED),
+            Err(AppError::ValidationError(_))
+        ));
+    }
+}
@@ -0,0 +1,113 @@
+//! Byte-range requests.
+//!
+//! Only a single `bytes` range is honoured. Other units, multiple ranges and
+//! malformed values are ignored and the whole body is served, which RFC 9110
+//! allows.
+
+/// An inclusive range of bytes within a body.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ByteRange {
+    pub start: u64,
+    pub end: u64,
+}
+
+impl ByteRange {
+    /// Number of bytes in the range.
+    pub fn byte_count(self) -> u64 {
+        self.end - self.start + 1
+    }
+
+    /// `Content-Range` value of this range of a `total`-byte body.
+    pub fn content_range(self, total: u64) -> String {
+        format!("bytes {}-{}/{total}", self.start, self.end)
+    }
+}
+
+/// How a `Range` header applies to a body of known length.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RangeRequest {
+    /// Serve the whole body.
+    Full,
+    /// Serve this part of the body with `206 Partial Content`.
+    Partial(ByteRange),
+    /// The range lies past the end of the body; answer
+    /// `416 Range Not Satisfiable`.
+    Unsatisfiable,
+}
+
+/// Applies the `Range` header value `header` to a body of `len` bytes.
+pub fn parse_range(header: &str, len: u64) -> RangeRequest {
+    let Some(spec) = header.trim().strip_prefix("bytes=") else {
+        return RangeRequest::Full;
+    };
+    if spec.contains(',') {
+        return RangeRequest::Full;
+    }
+    let Some((start, end)) = spec.split_once('-') else {
+        return RangeRequest::Full;
+    };
+    let (start, end) = (start.trim(), end.trim());
+
+    if start.is_empty() {
+        // `bytes=-N` asks for the last N bytes
+        let Ok(suffix) = end.parse::<u64>() else {
+            return RangeRequest::Full;
+        };
+        if suffix == 0 || len == 0 {
+            return RangeRequest::Unsatisfiable;
+        }
+        return RangeRequest::Partial(ByteRange {
+            start: len.saturating_sub(suffix),
+            end: len - 1,
+        });
+    }
+
+    let Ok(start) = start.parse::<u64>() else {
+        return RangeRequest::Full;
+    };
+    let end = if end.is_empty() {
+        u64::MAX
+    } else {
+        match end.parse::<u64>() {
+            Ok(end) if end >= start => end,
+            _ => return RangeRequest::Full,
+        }
+    };
+    if start >= len {
+        return RangeRequest::Unsatisfiable;
+    }
+    RangeRequest::Partial(ByteRange {
+        start,
+        end: end.min(len - 1),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn partial(start: u64, end: u64) -> RangeRequest {
+        RangeRequest::Partial(ByteRange { start, end })
+    }
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(parse_range("bytes=0-9", 100), partial(0, 9));
+        assert_eq!(parse_range("bytes=90-", 100), partial(90, 99));
+        assert_eq!(parse_range("bytes=90-500", 100), partial(90, 99));
+        assert_eq!(parse_range("bytes=-10", 100), partial(90, 99));
+        assert_eq!(parse_range("bytes=-500", 100), partial(0, 99));
+        assert_eq!(parse_range("bytes=100-", 100), RangeRequest::Unsatisfiable);
+        assert_eq!(parse_range("bytes=-0", 100), RangeRequest::Unsatisfiable);
+        assert_eq!(parse_range("bytes=0-1,5-6", 100), RangeRequest::Full);
+        assert_eq!(parse_range("bytes=9-0", 100), RangeRequest::Full);
+        assert_eq!(parse_range("items=0-9", 100), RangeRequest::Full);
+    }
+
+    #[test]
+    fn test_content_range() {
+        let range = ByteRange { start: 90, end: 99 };
+        assert_eq!(range.byte_count(), 10);
+        assert_eq!(range.content_range(100), "bytes 90-99/100");
+    }
+}
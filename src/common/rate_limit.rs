@@ -0,0 +1,310 @@
+//! In-memory token-bucket rate limiting.
+//!
+//! Each key (a client IP, an account name, ...) gets its own bucket holding up
+//! to `burst` tokens that refill at `per_minute` tokens per minute. A request
+//! is admitted if it can take a token. Buckets live in process memory, so
+//! limits are per instance and reset on restart.
+//!
+//! [`rate_limit`] is the request middleware built on top: it keys buckets by
+//! authenticated user or client IP and budgets reads, writes and uploads
+//! separately.
+
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::{
+        header::{CONTENT_TYPE, RETRY_AFTER},
+        HeaderValue, Method,
+    },
+    middleware::Next,
+    response::{IntoResponse as _, Response},
+};
+
+use super::{config::Config, error::AppError, jwt::Claims};
+
+/// Number of tracked keys above which full buckets are dropped.
+const PRUNE_THRESHOLD: usize = 10_000;
+
+/// Wait reported when a bucket never refills (`per_minute` of 0).
+const NO_REFILL_RETRY_AFTER: Duration = Duration::from_secs(60);
+
+/// Bucket size and refill rate of a [`RateLimiter`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RateLimit {
+    /// Requests allowed back-to-back before throttling starts; 0 disables the limit.
+    pub burst: u32,
+    /// Sustained requests allowed per minute.
+    pub per_minute: u32,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+/// Token-bucket limiter keyed by string.
+#[derive(Debug)]
+pub struct RateLimiter {
+    limit: RateLimit,
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+impl RateLimiter {
+    pub fn new(limit: RateLimit) -> Self {
+        Self {
+            limit,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Takes a token from `key`'s bucket. If the bucket is empty, returns how
+    /// long until the next token is available.
+    pub fn try_acquire(&self, key: &str) -> Result<(), Duration> {
+        self.try_acquire_at(key, Instant::now())
+    }
+
+    fn try_acquire_at(&self, key: &str, now: Instant) -> Result<(), Duration> {
+        if self.limit.burst == 0 {
+            return Ok(());
+        }
+
+        let burst = f64::from(self.limit.burst);
+        let mut buckets = self
+            .buckets
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+
+        if buckets.len() > PRUNE_THRESHOLD {
+            buckets.retain(|_, bucket| self.refilled(bucket, now) < burst);
+        }
+
+        let bucket = buckets.entry(key.to_owned()).or_insert(Bucket {
+            tokens: burst,
+            updated_at: now,
+        });
+        bucket.tokens = self.refilled(bucket, now);
+        bucket.updated_at = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            return Ok(());
+        }
+
+        if self.limit.per_minute == 0 {
+            return Err(NO_REFILL_RETRY_AFTER);
+        }
+        let missing = 1.0 - bucket.tokens;
+        Err(Duration::from_secs_f64(
+            missing * 60.0 / f64::from(self.limit.per_minute),
+        ))
+    }
+
+    /// Token count of `bucket` after refilling up to `now`, capped at the burst size.
+    fn refilled(&self, bucket: &Bucket, now: Instant) -> f64 {
+        let elapsed = now
+            .checked_duration_since(bucket.updated_at)
+            .unwrap_or(Duration::ZERO);
+        let refill = elapsed.as_secs_f64() * f64::from(self.limit.per_minute) / 60.0;
+        (bucket.tokens + refill).min(f64::from(self.limit.burst))
+    }
+}
+
+/// Returns the client IP of a request: the first `X-Forwarded-For` entry when
+/// `trust_forwarded_for` is set, otherwise the peer address if it is known.
+pub fn client_ip(req: &Request, trust_forwarded_for: bool) -> Option<String> {
+    let forwarded = if trust_forwarded_for {
+        req.headers()
+            .get("X-Forwarded-For")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.split(',').next())
+            .map(|ip| ip.trim().to_owned())
+    } else {
+        None
+    };
+    forwarded.or_else(|| {
+        req.extensions()
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip().to_string())
+    })
+}
+
+/// Builds a 429 Too Many Requests response with a `Retry-After` header in whole seconds.
+pub fn too_many_requests(retry_after: Duration) -> Response {
+    let secs = retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0);
+    let mut response = AppError::TooManyRequests.into_response();
+    response
+        .headers_mut()
+        .insert(RETRY_AFTER, HeaderValue::from(secs.max(1)));
+    response
+}
+
+/// Budget class of a request.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RouteClass {
+    /// `GET`, `HEAD` and `OPTIONS` requests.
+    Read,
+    /// Other requests without a multipart body.
+    Write,
+    /// Multipart requests, i.e. file and image uploads.
+    Upload,
+}
+
+impl RouteClass {
+    pub fn of(req: &Request) -> Self {
+        if matches!(*req.method(), Method::GET | Method::HEAD | Method::OPTIONS) {
+            return Self::Read;
+        }
+        let is_multipart = req
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|s| s.starts_with("multipart/form-data"));
+        if is_multipart {
+            Self::Upload
+        } else {
+            Self::Write
+        }
+    }
+}
+
+/// Per-class limiters used by the [`rate_limit`] middleware.
+#[derive(Debug)]
+pub struct RequestRateLimiter {
+    read: RateLimiter,
+    write: RateLimiter,
+    upload: RateLimiter,
+    trust_forwarded_for: bool,
+}
+
+impl RequestRateLimiter {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            read: RateLimiter::new(config.rate_limit_read),
+            write: RateLimiter::new(config.rate_limit_write),
+            upload: RateLimiter::new(config.rate_limit_upload),
+            trust_forwarded_for: config.trust_forwarded_for,
+        }
+    }
+
+    fn limiter(&self, class: RouteClass) -> &RateLimiter {
+        match class {
+            RouteClass::Read => &self.read,
+            RouteClass::Write => &self.write,
+            RouteClass::Upload => &self.upload,
+        }
+    }
+}
+
+/// Middleware that enforces the read/write/upload budgets.
+///
+/// Requests carrying [`Claims`] (i.e. running inside `jwt_auth`) are counted
+/// against the user; others against the client IP. Requests with neither are
+/// not limited. Exceeding the budget returns 429 with `Retry-After`.
+pub async fn rate_limit(
+    State(limiter): State<Arc<RequestRateLimiter>>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let key = match req.extensions().get::<Claims>() {
+        Some(claims) => Some(format!("user:{}", claims.sub)),
+        None => client_ip(&req, limiter.trust_forwarded_for).map(|ip| format!("ip:{ip}")),
+    };
+
+    if let Some(key) = key {
+        let class = RouteClass::of(&req);
+        if let Err(retry_after) = limiter.limiter(class).try_acquire(&key) {
+            tracing::warn!("Rate limit exceeded for {key} ({class:?})");
+            return too_many_requests(retry_after);
+        }
+    }
+    next.run(req).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RateLimit, RateLimiter, RouteClass};
+    use axum::{body::Body, extract::Request, http::header::CONTENT_TYPE};
+    use std::time::{Duration, Instant};
+
+    fn limiter() -> RateLimiter {
+        RateLimiter::new(RateLimit {
+            burst: 2,
+            per_minute: 60,
+        })
+    }
+
+    #[test]
+    fn admits_burst_then_throttles() {
+        let limiter = limiter();
+        let now = Instant::now();
+        assert!(limiter.try_acquire_at("a", now).is_ok());
+        assert!(limiter.try_acquire_at("a", now).is_ok());
+        assert_eq!(
+            limiter.try_acquire_at("a", now),
+            Err(Duration::from_secs(1))
+        );
+    }
+
+    #[test]
+    fn keys_have_separate_buckets() {
+        let limiter = limiter();
+        let now = Instant::now();
+        assert!(limiter.try_acquire_at("a", now).is_ok());
+        assert!(limiter.try_acquire_at("a", now).is_ok());
+        assert!(limiter.try_acquire_at("b", now).is_ok());
+    }
+
+    #[test]
+    fn tokens_refill_over_time() {
+        let limiter = limiter();
+        let now = Instant::now();
+        assert!(limiter.try_acquire_at("a", now).is_ok());
+        assert!(limiter.try_acquire_at("a", now).is_ok());
+        assert!(limiter.try_acquire_at("a", now).is_err());
+        assert!(limiter
+            .try_acquire_at("a", now + Duration::from_secs(1))
+            .is_ok());
+    }
+
+    #[test]
+    fn zero_burst_disables_limit() {
+        let limiter = RateLimiter::new(RateLimit {
+            burst: 0,
+            per_minute: 0,
+        });
+        let now = Instant::now();
+        for _ in 0..100 {
+            assert!(limiter.try_acquire_at("a", now).is_ok());
+        }
+    }
+
+    #[test]
+    fn classifies_requests_by_method_and_body() {
+        let request = |method: &str, content_type: &str| {
+            Request::builder()
+                .method(method)
+                .header(CONTENT_TYPE, content_type)
+                .body(Body::empty())
+                .expect("valid request")
+        };
+        assert_eq!(
+            RouteClass::of(&request("GET", "application/json")),
+            RouteClass::Read
+        );
+        assert_eq!(
+            RouteClass::of(&request("PUT", "application/json")),
+            RouteClass::Write
+        );
+        assert_eq!(
+            RouteClass::of(&request("POST", "multipart/form-data; boundary=x")),
+            RouteClass::Upload
+        );
+    }
+}
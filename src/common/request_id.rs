@@ -0,0 +1,83 @@
+//! Request correlation IDs.
+//!
+//! Every request gets an `X-Request-Id`: the client's own when it sends a
+//! usable one, a fresh UUID otherwise. The ID is recorded on the request's
+//! tracing span, echoed in the response headers and included in JSON response
+//! bodies, so a user can quote it in a bug report and it can be found in the
+//! logs.
+
+use axum::{
+    extract::Request,
+    http::{HeaderName, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use uuid::Uuid;
+
+/// Header carrying the request ID in both directions.
+pub const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Longest client-supplied ID that is kept; longer ones are replaced.
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// The ID of the request being handled, if called within [`request_id`].
+pub fn current_request_id() -> Option<String> {
+    REQUEST_ID.try_with(Clone::clone).ok()
+}
+
+/// Whether a client-supplied ID is short and plain enough to log and echo.
+fn is_acceptable(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= MAX_REQUEST_ID_LEN
+        && id
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.' | b':'))
+}
+
+/// Middleware that assigns the request ID, exposes it to the rest of the
+/// stack through the request header and [`current_request_id`], and returns it
+/// in the response header.
+pub async fn request_id(mut req: Request, next: Next) -> Response {
+    let id = req
+        .headers()
+        .get(&REQUEST_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .filter(|id| is_acceptable(id))
+        .map_or_else(|| Uuid::new_v4().to_string(), ToOwned::to_owned);
+    // Acceptable IDs and UUIDs are plain ASCII, so they are valid header values
+    let Ok(header) = HeaderValue::from_str(&id) else {
+        return next.run(req).await;
+    };
+    req.headers_mut().insert(REQUEST_ID_HEADER, header.clone());
+
+    let mut response = REQUEST_ID.scope(id, next.run(req)).await;
+    response.headers_mut().insert(REQUEST_ID_HEADER, header);
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{current_request_id, is_acceptable, REQUEST_ID};
+
+    #[test]
+    fn plain_client_ids_are_kept() {
+        assert!(is_acceptable("3f2b-41ac_trace.1:a"));
+        assert!(!is_acceptable(""));
+        assert!(!is_acceptable("has space"));
+        assert!(!is_acceptable("line\nbreak"));
+        assert!(!is_acceptable(&"a".repeat(129)));
+    }
+
+    #[tokio::test]
+    async fn current_id_is_scoped_to_the_request() {
+        assert_eq!(current_request_id(), None);
+        let seen = REQUEST_ID
+            .scope("req-1".to_owned(), async { current_request_id() })
+            .await;
+        assert_eq!(seen.as_deref(), Some("req-1"));
+    }
+}
@@ -1,7 +1,14 @@
+pub mod audit;
 pub mod auth;
+pub mod backup;
 pub mod crawl;
 pub mod device;
 pub mod file;
+#[cfg(feature = "graphql")]
+pub mod graphql;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+pub mod health;
 pub mod luna;
 pub mod search;
 pub mod user;
@@ -0,0 +1,32 @@
+//! Audit domain: an append-only log of who changed what.
+//!
+//! Writes are captured by the [`audit_writes`] middleware layered onto the
+//! luna and user routers; `GET /audit` queries the history.
+
+mod api {
+    mod handlers;
+    pub mod middleware;
+    pub mod routes;
+}
+
+mod domain {
+    pub mod model;
+    pub mod repository;
+    pub mod service;
+}
+
+pub mod dto {
+    pub mod audit_dto;
+}
+
+mod infra {
+    mod impl_repository;
+    pub mod impl_service;
+}
+
+// Re-export commonly used items for convenience
+pub use api::middleware::audit_writes;
+pub use api::routes::{audit_routes, AuditApiDoc};
+pub use domain::model::NewAuditEntry;
+pub use domain::service::AuditServiceTrait;
+pub use infra::impl_service::AuditService;
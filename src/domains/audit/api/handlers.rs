@@ -0,0 +1,23 @@
+use crate::common::dto::{ApiResponse, RestApiResponse};
+use crate::common::{app_state::AppState, error::AppError};
+
+use crate::domains::audit::dto::audit_dto::{AuditLogDto, AuditQueryDto};
+use crate::domains::luna::dto::PaginatedResponse;
+use axum::{extract::State, response::IntoResponse};
+
+/// Lists audit log entries, newest first.
+/// Filter by `entity` and `id` for the history of one entity, or by `actor`.
+#[utoipa::path(
+    get,
+    path = "/audit",
+    params(AuditQueryDto),
+    responses((status = 200, description = "Audit log entries", body = ApiResponse<PaginatedResponse<AuditLogDto>>)),
+    tag = "Audit"
+)]
+pub async fn get_audit_logs(
+    State(state): State<AppState>,
+    axum::extract::Query(query): axum::extract::Query<AuditQueryDto>,
+) -> Result<impl IntoResponse, AppError> {
+    let logs = state.audit_service.get_audit_logs(query).await?;
+    Ok(RestApiResponse::success(logs))
+}
@@ -0,0 +1,210 @@
+//! Middleware that appends an audit log entry for every successful write.
+//!
+//! It is layered onto the luna and user routers (after routing, so the
+//! matched route and path parameters are available) and runs inside JWT
+//! authentication, so the acting user comes from the request's [`Claims`].
+
+use axum::{
+    body::{to_bytes, Body},
+    extract::{FromRequestParts as _, MatchedPath, RawPathParams, Request, State},
+    http::header::CONTENT_TYPE,
+    middleware::Next,
+    response::Response,
+};
+use serde_json::{json, Value as JsonValue};
+
+use crate::{
+    common::{app_state::AppState, jwt::Claims},
+    domains::audit::domain::model::NewAuditEntry,
+};
+
+/// Maps a write request to the `(entity_type, action)` it performs.
+///
+/// `route` is the matched route template, e.g. `/cards/records/{id}`.
+/// Returns `None` for reads and for writes that are not entity changes
+/// (existence checks, interactions, media uploads).
+pub(crate) fn classify_write(method: &str, route: &str) -> Option<(&'static str, &'static str)> {
+    let segments: Vec<&str> = route
+        .trim_matches('/')
+        .split('/')
+        .skip_while(|s| *s == "cards")
+        .collect();
+    let (collection, rest) = segments.split_first()?;
+
+    let entity_type = match *collection {
+        "records" => "record",
+        "directors" => "director",
+        "genres" => "genre",
+        "labels" => "label",
+        "studios" => "studio",
+        "series" => "series",
+        "idols" => "idol",
+        "user" => "user",
+        _ => return None,
+    };
+
+    let action = match (method, rest) {
+        ("POST", [] | ["bulk"]) => "create",
+        ("PUT" | "PATCH", ["{id}"] | ["{id}", "full"] | ["links", "{id}"]) => "update",
+        ("DELETE", [] | ["{id}"]) => "delete",
+        ("POST", ["{id}", "merge"]) => "merge",
+        ("POST", ["{id}", "restore"]) => "restore",
+        ("DELETE", ["{id}", "purge"]) => "purge",
+        ("POST", ["{id}", "unlock"]) => "unlock",
+        _ => return None,
+    };
+
+    Some((entity_type, action))
+}
+
+/// Records an audit entry for successful create/update/delete requests.
+///
+/// The diff holds the submitted JSON body as `changes` and the response's
+/// `data` as `result`. Creates take the entity id from `result.id`. A
+/// failure to write the entry is logged and never fails the request.
+pub async fn audit_writes(State(state): State<AppState>, req: Request, next: Next) -> Response {
+    let route = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|p| p.as_str().to_owned());
+    let classified = route
+        .as_deref()
+        .and_then(|route| classify_write(req.method().as_str(), route));
+    let (Some((entity_type, action)), Some(claims)) =
+        (classified, req.extensions().get::<Claims>().cloned())
+    else {
+        return next.run(req).await;
+    };
+
+    let (mut parts, body) = req.into_parts();
+    let path_id = RawPathParams::from_request_parts(&mut parts, &())
+        .await
+        .ok()
+        .and_then(|params| {
+            params
+                .iter()
+                .find(|(key, _)| *key == "id")
+                .map(|(_, value)| value.to_owned())
+        });
+
+    // Multipart bodies (user create/update) are streamed through untouched.
+    let is_multipart = parts
+        .headers
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|s| s.starts_with("multipart/form-data"));
+    let (body, changes) = if is_multipart {
+        (body, JsonValue::Null)
+    } else {
+        let Ok(bytes) = to_bytes(body, usize::MAX).await else {
+            return next.run(Request::from_parts(parts, Body::empty())).await;
+        };
+        let changes = serde_json::from_slice(&bytes).unwrap_or(JsonValue::Null);
+        (Body::from(bytes), changes)
+    };
+
+    let res = next.run(Request::from_parts(parts, body)).await;
+    if !res.status().is_success() {
+        return res;
+    }
+
+    let (res_parts, res_body) = res.into_parts();
+    let Ok(bytes) = to_bytes(res_body, usize::MAX).await else {
+        tracing::warn!("audit: failed to read response body for {entity_type} {action}");
+        return Response::from_parts(res_parts, Body::empty());
+    };
+    let result = serde_json::from_slice::<JsonValue>(&bytes)
+        .ok()
+        .and_then(|mut v| v.get_mut("data").map(JsonValue::take))
+        .unwrap_or(JsonValue::Null);
+
+    let entity_id = path_id.or_else(|| match result.get("id") {
+        Some(JsonValue::String(id)) => Some(id.clone()),
+        Some(JsonValue::Number(id)) => Some(id.to_string()),
+        _ => None,
+    });
+
+    let entry = NewAuditEntry {
+        entity_type: entity_type.to_owned(),
+        entity_id,
+        action: action.to_owned(),
+        actor: claims.sub,
+        diff: Some(json!({ "changes": changes, "result": result })),
+    };
+    if let Err(e) = state.audit_service.record(entry).await {
+        tracing::warn!("audit: failed to record {entity_type} {action}: {e}");
+    }
+
+    Response::from_parts(res_parts, Body::from(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::classify_write;
+
+    #[test]
+    fn classifies_luna_writes() {
+        assert_eq!(
+            classify_write("POST", "/cards/records"),
+            Some(("record", "create"))
+        );
+        assert_eq!(
+            classify_write("POST", "/cards/records/bulk"),
+            Some(("record", "create"))
+        );
+        assert_eq!(
+            classify_write("PATCH", "/cards/directors/{id}"),
+            Some(("director", "update"))
+        );
+        assert_eq!(
+            classify_write("PUT", "/cards/records/{id}/full"),
+            Some(("record", "update"))
+        );
+        assert_eq!(
+            classify_write("PATCH", "/cards/records/links/{id}"),
+            Some(("record", "update"))
+        );
+        assert_eq!(
+            classify_write("DELETE", "/cards/series/{id}"),
+            Some(("series", "delete"))
+        );
+        assert_eq!(
+            classify_write("POST", "/cards/idols/{id}/merge"),
+            Some(("idol", "merge"))
+        );
+        assert_eq!(
+            classify_write("DELETE", "/cards/records/{id}/purge"),
+            Some(("record", "purge"))
+        );
+    }
+
+    #[test]
+    fn classifies_user_writes() {
+        assert_eq!(classify_write("POST", "/user"), Some(("user", "create")));
+        assert_eq!(
+            classify_write("PUT", "/user/{id}"),
+            Some(("user", "update"))
+        );
+        assert_eq!(
+            classify_write("DELETE", "/user/{id}"),
+            Some(("user", "delete"))
+        );
+        assert_eq!(
+            classify_write("POST", "/user/{id}/unlock"),
+            Some(("user", "unlock"))
+        );
+    }
+
+    #[test]
+    fn ignores_reads_and_non_entity_writes() {
+        assert_eq!(classify_write("GET", "/cards/records/{id}"), None);
+        assert_eq!(classify_write("POST", "/cards/records/exists"), None);
+        assert_eq!(classify_write("POST", "/cards/records/user/status"), None);
+        assert_eq!(
+            classify_write("POST", "/cards/records/user/{record_id}/like"),
+            None
+        );
+        assert_eq!(classify_write("POST", "/cards/media/upload"), None);
+        assert_eq!(classify_write("POST", "/user/list"), None);
+    }
+}
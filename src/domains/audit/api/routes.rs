@@ -0,0 +1,30 @@
+use super::handlers::{__path_get_audit_logs, get_audit_logs};
+use crate::{
+    common::app_state::AppState,
+    domains::{audit::dto::audit_dto::AuditLogDto, luna::dto::PaginatedResponse},
+};
+use axum::{routing::get, Router};
+
+use utoipa::OpenApi;
+
+use crate::common::openapi::{ErrorResponsesAddon, SecurityAddon};
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(get_audit_logs),
+    components(schemas(AuditLogDto, PaginatedResponse<AuditLogDto>)),
+    tags(
+        (name = "Audit", description = "Audit log of entity changes")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    modifiers(&SecurityAddon, &ErrorResponsesAddon)
+)]
+/// This struct is used to generate `OpenAPI` documentation for the audit routes.
+pub struct AuditApiDoc;
+
+/// This function creates a router for the audit routes.
+pub fn audit_routes() -> Router<AppState> {
+    Router::new().route("/", get(get_audit_logs))
+}
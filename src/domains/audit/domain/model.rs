@@ -0,0 +1,31 @@
+//! Domain model definitions for audit log entries.
+
+use chrono::{DateTime, Utc};
+use serde_json::Value as JsonValue;
+
+/// A change to be appended to the audit log.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewAuditEntry {
+    /// Entity type label, e.g. "record", "director" or "user".
+    pub entity_type: String,
+    /// Primary key of the affected entity, when the operation has one.
+    pub entity_id: Option<String>,
+    /// Operation performed, e.g. "create", "update" or "delete".
+    pub action: String,
+    /// User ID of the caller, taken from the JWT `sub` claim.
+    pub actor: String,
+    /// JSON change payload.
+    pub diff: Option<JsonValue>,
+}
+
+/// A persisted audit log entry.
+#[derive(Debug, Clone)]
+pub struct AuditLog {
+    pub id: i64,
+    pub entity_type: String,
+    pub entity_id: Option<String>,
+    pub action: String,
+    pub actor: String,
+    pub diff: Option<JsonValue>,
+    pub created_at: DateTime<Utc>,
+}
@@ -0,0 +1,26 @@
+// This module defines the `AuditLogRepository` trait, which abstracts
+// the database operations related to the audit log.
+
+use crate::domains::audit::dto::audit_dto::AuditQueryDto;
+
+use super::model::{AuditLog, NewAuditEntry};
+
+use async_trait::async_trait;
+use sea_orm::{DatabaseConnection, DbErr};
+
+#[async_trait]
+/// Trait representing repository-level operations for audit log entries.
+pub trait AuditLogRepository: Send + Sync {
+    /// Appends an entry to the audit log.
+    async fn insert(&self, db: &DatabaseConnection, entry: NewAuditEntry) -> Result<(), DbErr>;
+
+    /// Returns one page of entries matching the query, newest first,
+    /// together with the total number of matching entries.
+    async fn find_paginated(
+        &self,
+        db: &DatabaseConnection,
+        query: &AuditQueryDto,
+        limit: u64,
+        offset: u64,
+    ) -> Result<(Vec<AuditLog>, u64), DbErr>;
+}
@@ -0,0 +1,34 @@
+//! This module defines the `AuditServiceTrait` which records and queries
+//! the audit log.
+
+use std::sync::Arc;
+
+use sea_orm::DatabaseConnection;
+
+use crate::{
+    common::error::AppError,
+    domains::{
+        audit::dto::audit_dto::{AuditLogDto, AuditQueryDto},
+        luna::dto::PaginatedResponse,
+    },
+};
+
+use super::model::NewAuditEntry;
+
+#[async_trait::async_trait]
+/// Trait defining the contract for audit log operations.
+pub trait AuditServiceTrait: Send + Sync {
+    /// constructor for the service.
+    fn create_service(db: DatabaseConnection) -> Arc<dyn AuditServiceTrait>
+    where
+        Self: Sized;
+
+    /// Appends an entry to the audit log.
+    async fn record(&self, entry: NewAuditEntry) -> Result<(), AppError>;
+
+    /// Lists audit entries matching the query, newest first.
+    async fn get_audit_logs(
+        &self,
+        query: AuditQueryDto,
+    ) -> Result<PaginatedResponse<AuditLogDto>, AppError>;
+}
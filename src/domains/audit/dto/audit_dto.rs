@@ -0,0 +1,50 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::domains::audit::domain::model::AuditLog;
+
+/// Query parameters of `GET /audit`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, IntoParams)]
+pub struct AuditQueryDto {
+    /// Entity type to filter by, e.g. `record`, `director` or `user`.
+    pub entity: Option<String>,
+    /// Entity primary key to filter by.
+    pub id: Option<String>,
+    /// Only return changes made by this user ID.
+    pub actor: Option<String>,
+    /// Maximum number of entries per page.
+    pub limit: Option<i64>,
+    /// Number of entries to skip.
+    pub offset: Option<i64>,
+}
+
+/// A single audit log entry.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AuditLogDto {
+    pub id: i64,
+    pub entity_type: String,
+    pub entity_id: Option<String>,
+    /// One of `create`, `update`, `delete`, `merge`, `restore`, `purge` or `unlock`.
+    pub action: String,
+    /// User ID of the caller that made the change.
+    pub actor: String,
+    /// Submitted `changes` and the resulting entity state (`result`), when available.
+    pub diff: Option<JsonValue>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<AuditLog> for AuditLogDto {
+    fn from(log: AuditLog) -> Self {
+        Self {
+            id: log.id,
+            entity_type: log.entity_type,
+            entity_id: log.entity_id,
+            action: log.action,
+            actor: log.actor,
+            diff: log.diff,
+            created_at: log.created_at,
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use async_trait::async_trait;
+use sea_orm::{
+    ActiveModelTrait as _, ColumnTrait as _, DatabaseConnection, DbErr, EntityTrait as _, NotSet,
+    Order, PaginatorTrait as _, QueryFilter as _, QueryOrder as _, QuerySelect as _, Set,
+};
+
+use crate::domains::audit::domain::model::{AuditLog, NewAuditEntry};
+use crate::domains::audit::domain::repository::AuditLogRepository;
+use crate::domains::audit::dto::audit_dto::AuditQueryDto;
+use crate::entities::audit_log;
+
+pub struct AuditLogRepo;
+
+impl AuditLogRepo {
+    fn entity_to_model(entity: audit_log::Model) -> AuditLog {
+        AuditLog {
+            id: entity.id,
+            entity_type: entity.entity_type,
+            entity_id: entity.entity_id,
+            action: entity.action,
+            actor: entity.actor,
+            diff: entity.diff,
+            created_at: entity.created_at.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl AuditLogRepository for AuditLogRepo {
+    async fn insert(&self, db: &DatabaseConnection, entry: NewAuditEntry) -> Result<(), DbErr> {
+        audit_log::ActiveModel {
+            id: NotSet,
+            entity_type: Set(entry.entity_type),
+            entity_id: Set(entry.entity_id),
+            action: Set(entry.action),
+            actor: Set(entry.actor),
+            diff: Set(entry.diff),
+            created_at: Set(chrono::Utc::now().into()),
+        }
+        .insert(db)
+        .await?;
+        Ok(())
+    }
+
+    async fn find_paginated(
+        &self,
+        db: &DatabaseConnection,
+        query: &AuditQueryDto,
+        limit: u64,
+        offset: u64,
+    ) -> Result<(Vec<AuditLog>, u64), DbErr> {
+        let mut select = audit_log::Entity::find();
+        if let Some(entity) = &query.entity {
+            select = select.filter(audit_log::Column::EntityType.eq(entity.as_str()));
+        }
+        if let Some(id) = &query.id {
+            select = select.filter(audit_log::Column::EntityId.eq(id.as_str()));
+        }
+        if let Some(actor) = &query.actor {
+            select = select.filter(audit_log::Column::Actor.eq(actor.as_str()));
+        }
+
+        let total = select.clone().count(db).await?;
+        let entries = select
+            .order_by(audit_log::Column::CreatedAt, Order::Desc)
+            .order_by(audit_log::Column::Id, Order::Desc)
+            .offset(offset)
+            .limit(limit)
+            .all(db)
+            .await?
+            .into_iter()
+            .map(Self::entity_to_model)
+            .collect();
+
+        Ok((entries, total))
+    }
+}
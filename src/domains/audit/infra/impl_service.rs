@@ -0,0 +1,77 @@
+use crate::{
+    common::{config::DEFAULT_PAGE_SIZE, error::AppError},
+    domains::{
+        audit::{
+            domain::{
+                model::NewAuditEntry, repository::AuditLogRepository, service::AuditServiceTrait,
+            },
+            dto::audit_dto::{AuditLogDto, AuditQueryDto},
+            infra::impl_repository::AuditLogRepo,
+        },
+        luna::dto::PaginatedResponse,
+    },
+};
+
+use async_trait::async_trait;
+use sea_orm::DatabaseConnection;
+use std::sync::Arc;
+
+/// Service struct for appending to and querying the audit log.
+#[derive(Clone)]
+pub struct AuditService {
+    db: DatabaseConnection,
+    repo: Arc<dyn AuditLogRepository + Send + Sync>,
+}
+
+#[async_trait]
+impl AuditServiceTrait for AuditService {
+    fn create_service(db: DatabaseConnection) -> Arc<dyn AuditServiceTrait> {
+        Arc::new(Self {
+            db,
+            repo: Arc::new(AuditLogRepo),
+        })
+    }
+
+    async fn record(&self, entry: NewAuditEntry) -> Result<(), AppError> {
+        self.repo
+            .insert(&self.db, entry)
+            .await
+            .map_err(AppError::DatabaseError)
+    }
+
+    async fn get_audit_logs(
+        &self,
+        query: AuditQueryDto,
+    ) -> Result<PaginatedResponse<AuditLogDto>, AppError> {
+        if query.id.is_some() && query.entity.is_none() {
+            return Err(AppError::ValidationError(
+                "'id' requires 'entity' to be set".into(),
+            ));
+        }
+
+        let limit = query
+            .limit
+            .filter(|&l| l > 0)
+            .map_or(DEFAULT_PAGE_SIZE, |l| l as u64);
+        let offset = query.offset.unwrap_or(0).max(0) as u64;
+
+        let (entries, total) = self
+            .repo
+            .find_paginated(&self.db, &query, limit, offset)
+            .await
+            .map_err(AppError::DatabaseError)?;
+
+        let next =
+            (offset + limit < total).then(|| format!("?limit={limit}&offset={}", offset + limit));
+        let previous =
+            (offset > 0).then(|| format!("?limit={limit}&offset={}", offset.saturating_sub(limit)));
+
+        Ok(PaginatedResponse {
+            count: total as i64,
+            next,
+            previous,
+            next_cursor: None,
+            results: entries.into_iter().map(AuditLogDto::from).collect(),
+        })
+    }
+}
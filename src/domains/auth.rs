@@ -7,10 +7,13 @@ mod domain {
     pub mod model;
     pub mod repository;
     pub mod service;
+    pub mod totp;
 }
 
 pub mod dto {
+    pub mod api_key_dto;
     pub mod auth_dto;
+    pub mod totp_dto;
 }
 
 mod infra {
@@ -19,6 +22,9 @@ mod infra {
 }
 
 // Re-export commonly used items for convenience
-pub use api::routes::{user_auth_routes, UserAuthApiDoc};
+pub use api::routes::{
+    api_key_routes, password_routes, totp_routes, user_auth_routes, UserAuthApiDoc,
+};
 pub use domain::service::AuthServiceTrait;
+pub use domain::totp;
 pub use infra::impl_service::AuthService;
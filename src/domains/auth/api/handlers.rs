@@ -1,14 +1,22 @@
 use crate::{
     common::{
         app_state::AppState,
-        dto::RestApiResponse,
+        dto::{ApiResponse, RestApiResponse},
         error::AppError,
-        jwt::{AuthBody, AuthPayload},
+        jwt::{AuthBody, AuthPayload, Claims},
+    },
+    domains::auth::dto::{
+        api_key_dto::{ApiKeyCreatedDto, ApiKeyDto, CreateApiKeyDto},
+        auth_dto::{
+            ChangePasswordDto, LoginQuery, PasswordResetConfirmDto, PasswordResetRequestDto,
+            RefreshTokenDto, RegisterDto,
+        },
+        totp_dto::{LoginResponse, TotpCodeDto, TotpEnrollmentDto, TotpLoginDto},
     },
-    domains::auth::dto::auth_dto::RegisterDto,
 };
 use axum::extract::State;
-use axum::{response::IntoResponse, Json};
+use axum::{response::IntoResponse, Extension, Json};
+use validator::Validate as _;
 
 /// this function creates a router for creating user authentication registration
 /// it will create a new user in the database
@@ -16,7 +24,7 @@ use axum::{response::IntoResponse, Json};
     post,
     path = "/auth/register",
     request_body = RegisterDto,
-    responses((status = 200, description = "Create user authentication", body = RegisterDto)),
+    responses((status = 200, description = "Create user authentication", body = ApiResponse<RegisterDto>)),
     tag = "UserAuth"
 )]
 pub async fn create_user_auth(
@@ -29,18 +37,318 @@ pub async fn create_user_auth(
 }
 
 /// this function creates a router for login user
-/// it will return a JWT token if the user is authenticated
+/// it will return a JWT token and a refresh token if the user is authenticated,
+/// or a challenge to complete at `/auth/login/totp` if the account uses TOTP
 #[utoipa::path(
     post,
     path = "/auth/login",
+    params(LoginQuery),
     request_body = AuthPayload,
-    responses((status = 200, description = "Login user", body = AuthBody)),
+    responses((status = 200, description = "Login user", body = ApiResponse<LoginResponse>)),
     tag = "UserAuth"
 )]
 pub async fn login_user(
     State(state): State<AppState>,
+    axum::extract::Query(query): axum::extract::Query<LoginQuery>,
     Json(payload): Json<AuthPayload>,
 ) -> Result<impl IntoResponse, AppError> {
-    let auth_body = state.auth_service.login_user(payload).await?;
+    let auth_body = state
+        .auth_service
+        .login_user(payload, query.device_id)
+        .await;
+    #[cfg(feature = "metrics")]
+    crate::common::metrics::login_attempt(auth_body.is_ok());
+    Ok(RestApiResponse::success(auth_body?))
+}
+
+/// Completes a login challenge with a TOTP code.
+#[utoipa::path(
+    post,
+    path = "/auth/login/totp",
+    request_body = TotpLoginDto,
+    responses(
+        (status = 200, description = "Logged in", body = ApiResponse<AuthBody>),
+        (status = 401, description = "Challenge token invalid or expired, or wrong code"),
+        (status = 423, description = "Account temporarily locked"),
+    ),
+    tag = "UserAuth"
+)]
+pub async fn login_totp(
+    State(state): State<AppState>,
+    Json(payload): Json<TotpLoginDto>,
+) -> Result<impl IntoResponse, AppError> {
+    let auth_body = state.auth_service.login_totp(payload).await?;
+    Ok(RestApiResponse::success(auth_body))
+}
+
+/// Exchanges a refresh token for a new access token.
+/// The refresh token is rotated: the response carries a new one and the old one is revoked.
+#[utoipa::path(
+    post,
+    path = "/auth/refresh",
+    request_body = RefreshTokenDto,
+    responses(
+        (status = 200, description = "Tokens refreshed", body = ApiResponse<AuthBody>),
+        (status = 401, description = "Refresh token invalid, expired, revoked or already used"),
+    ),
+    tag = "UserAuth"
+)]
+pub async fn refresh_token(
+    State(state): State<AppState>,
+    Json(payload): Json<RefreshTokenDto>,
+) -> Result<impl IntoResponse, AppError> {
+    let auth_body = state
+        .auth_service
+        .refresh_token(&payload.refresh_token)
+        .await?;
+    Ok(RestApiResponse::success(auth_body))
+}
+
+/// Revokes the session the refresh token belongs to. Outstanding access
+/// tokens of the user stop working; other sessions get new ones by refreshing.
+#[utoipa::path(
+    post,
+    path = "/auth/logout",
+    request_body = RefreshTokenDto,
+    responses(
+        (status = 200, description = "Logged out"),
+        (status = 401, description = "Refresh token invalid"),
+    ),
+    tag = "UserAuth"
+)]
+pub async fn logout(
+    State(state): State<AppState>,
+    Json(payload): Json<RefreshTokenDto>,
+) -> Result<impl IntoResponse, AppError> {
+    state.auth_service.logout(&payload.refresh_token).await?;
+    Ok(RestApiResponse::success(()))
+}
+
+/// Revokes every session of the refresh token's user, on all devices, along
+/// with their outstanding access tokens.
+#[utoipa::path(
+    post,
+    path = "/auth/logout_all",
+    request_body = RefreshTokenDto,
+    responses(
+        (status = 200, description = "Logged out everywhere"),
+        (status = 401, description = "Refresh token invalid"),
+    ),
+    tag = "UserAuth"
+)]
+pub async fn logout_all(
+    State(state): State<AppState>,
+    Json(payload): Json<RefreshTokenDto>,
+) -> Result<impl IntoResponse, AppError> {
+    state
+        .auth_service
+        .logout_all(&payload.refresh_token)
+        .await?;
+    Ok(RestApiResponse::success(()))
+}
+
+/// Changes the caller's password. Every other session is signed out;
+/// the response carries tokens for a new session.
+#[utoipa::path(
+    post,
+    path = "/auth/password/change",
+    request_body = ChangePasswordDto,
+    responses(
+        (status = 200, description = "Password changed", body = ApiResponse<AuthBody>),
+        (status = 401, description = "Old password is wrong"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = [])),
+    tag = "UserAuth"
+)]
+pub async fn change_password(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(body): Json<ChangePasswordDto>,
+) -> Result<impl IntoResponse, AppError> {
+    body.validate().map_err(|err| {
+        tracing::error!("Validation error: {err}");
+        AppError::InvalidInput(err)
+    })?;
+
+    let auth_body = state.auth_service.change_password(&claims, body).await?;
     Ok(RestApiResponse::success(auth_body))
 }
+
+/// Emails a password reset token to the accounts registered with the address.
+/// Always succeeds, so it can't be used to find out which emails are registered.
+#[utoipa::path(
+    post,
+    path = "/auth/password/reset/request",
+    request_body = PasswordResetRequestDto,
+    responses((status = 200, description = "Reset token sent if the email is registered")),
+    tag = "UserAuth"
+)]
+pub async fn request_password_reset(
+    State(state): State<AppState>,
+    Json(body): Json<PasswordResetRequestDto>,
+) -> Result<impl IntoResponse, AppError> {
+    body.validate().map_err(|err| {
+        tracing::error!("Validation error: {err}");
+        AppError::InvalidInput(err)
+    })?;
+
+    state
+        .auth_service
+        .request_password_reset(&body.email)
+        .await?;
+    Ok(RestApiResponse::success(()))
+}
+
+/// Sets a new password with a reset token. The token can only be used once,
+/// and every session of the account is signed out.
+#[utoipa::path(
+    post,
+    path = "/auth/password/reset/confirm",
+    request_body = PasswordResetConfirmDto,
+    responses(
+        (status = 200, description = "Password reset"),
+        (status = 401, description = "Reset token invalid, expired or already used"),
+    ),
+    tag = "UserAuth"
+)]
+pub async fn confirm_password_reset(
+    State(state): State<AppState>,
+    Json(body): Json<PasswordResetConfirmDto>,
+) -> Result<impl IntoResponse, AppError> {
+    body.validate().map_err(|err| {
+        tracing::error!("Validation error: {err}");
+        AppError::InvalidInput(err)
+    })?;
+
+    state.auth_service.confirm_password_reset(body).await?;
+    Ok(RestApiResponse::success(()))
+}
+
+/// Starts TOTP enrollment with a new secret. TOTP is not required at login
+/// until a code is confirmed at `/auth/totp/verify`.
+#[utoipa::path(
+    post,
+    path = "/auth/totp/enroll",
+    responses(
+        (status = 200, description = "Secret and provisioning URI", body = ApiResponse<TotpEnrollmentDto>),
+        (status = 409, description = "TOTP is already enabled"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = [])),
+    tag = "UserAuth"
+)]
+pub async fn enroll_totp(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<impl IntoResponse, AppError> {
+    let enrollment = state.auth_service.enroll_totp(&claims).await?;
+    Ok(RestApiResponse::success(enrollment))
+}
+
+/// Confirms TOTP enrollment with a code from the authenticator app and enables TOTP.
+#[utoipa::path(
+    post,
+    path = "/auth/totp/verify",
+    request_body = TotpCodeDto,
+    responses(
+        (status = 200, description = "TOTP enabled"),
+        (status = 400, description = "Invalid code"),
+        (status = 409, description = "TOTP already enabled, or enrollment not started"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = [])),
+    tag = "UserAuth"
+)]
+pub async fn verify_totp(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(body): Json<TotpCodeDto>,
+) -> Result<impl IntoResponse, AppError> {
+    state.auth_service.verify_totp(&claims, &body.code).await?;
+    Ok(RestApiResponse::success(()))
+}
+
+/// Disables TOTP for the caller.
+#[utoipa::path(
+    post,
+    path = "/auth/totp/disable",
+    request_body = TotpCodeDto,
+    responses(
+        (status = 200, description = "TOTP disabled"),
+        (status = 400, description = "Invalid code"),
+        (status = 409, description = "TOTP is not enabled"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = [])),
+    tag = "UserAuth"
+)]
+pub async fn disable_totp(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(body): Json<TotpCodeDto>,
+) -> Result<impl IntoResponse, AppError> {
+    state.auth_service.disable_totp(&claims, &body.code).await?;
+    Ok(RestApiResponse::success(()))
+}
+
+/// Issues an API key. The full key is only returned in this response;
+/// send it as the `X-Api-Key` header instead of a Bearer token.
+#[utoipa::path(
+    post,
+    path = "/api-keys",
+    request_body = CreateApiKeyDto,
+    responses(
+        (status = 200, description = "API key issued", body = ApiResponse<ApiKeyCreatedDto>),
+        (status = 403, description = "Role exceeds the owner's role, or issuing for another user without admin role"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = [])),
+    tag = "ApiKeys"
+)]
+pub async fn create_api_key(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(body): Json<CreateApiKeyDto>,
+) -> Result<impl IntoResponse, AppError> {
+    body.validate().map_err(|err| {
+        tracing::error!("Validation error: {err}");
+        AppError::InvalidInput(err)
+    })?;
+
+    let api_key = state.auth_service.create_api_key(&claims, body).await?;
+    Ok(RestApiResponse::success(api_key))
+}
+
+/// Lists the caller's API keys, or every key for admins.
+#[utoipa::path(
+    get,
+    path = "/api-keys",
+    responses((status = 200, description = "API keys", body = ApiResponse<Vec<ApiKeyDto>>)),
+    security(("bearer_auth" = []), ("api_key" = [])),
+    tag = "ApiKeys"
+)]
+pub async fn get_api_keys(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<impl IntoResponse, AppError> {
+    let api_keys = state.auth_service.list_api_keys(&claims).await?;
+    Ok(RestApiResponse::success(api_keys))
+}
+
+/// Revokes an API key. Revoked keys stay listed but no longer authenticate.
+#[utoipa::path(
+    delete,
+    path = "/api-keys/{id}",
+    params(("id" = String, Path, description = "API key ID")),
+    responses(
+        (status = 200, description = "API key revoked", body = ApiResponse<ApiKeyDto>),
+        (status = 403, description = "Key belongs to another user"),
+        (status = 404, description = "API key not found"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = [])),
+    tag = "ApiKeys"
+)]
+pub async fn revoke_api_key(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    axum::extract::Path(id): axum::extract::Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let api_key = state.auth_service.revoke_api_key(&claims, &id).await?;
+    Ok(RestApiResponse::success(api_key))
+}
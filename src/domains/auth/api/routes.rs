@@ -1,5 +1,11 @@
-use crate::common::app_state::AppState;
-use axum::{routing::post, Router};
+use crate::common::{
+    app_state::AppState,
+    openapi::{ErrorResponsesAddon, SecurityAddon},
+};
+use axum::{
+    routing::{delete, get, post},
+    Router,
+};
 
 use super::handlers;
 
@@ -10,16 +16,44 @@ use utoipa::OpenApi;
 #[openapi(
     paths(
         super::handlers::login_user,
+        super::handlers::login_totp,
         super::handlers::create_user_auth,
+        super::handlers::refresh_token,
+        super::handlers::logout,
+        super::handlers::logout_all,
+        super::handlers::change_password,
+        super::handlers::request_password_reset,
+        super::handlers::confirm_password_reset,
+        super::handlers::enroll_totp,
+        super::handlers::verify_totp,
+        super::handlers::disable_totp,
+        super::handlers::create_api_key,
+        super::handlers::get_api_keys,
+        super::handlers::revoke_api_key,
     ),
     components(schemas(
         crate::domains::auth::dto::auth_dto::RegisterDto,
+        crate::domains::auth::dto::auth_dto::RefreshTokenDto,
+        crate::domains::auth::dto::auth_dto::ChangePasswordDto,
+        crate::domains::auth::dto::auth_dto::PasswordResetRequestDto,
+        crate::domains::auth::dto::auth_dto::PasswordResetConfirmDto,
+        crate::domains::auth::dto::totp_dto::TotpEnrollmentDto,
+        crate::domains::auth::dto::totp_dto::TotpCodeDto,
+        crate::domains::auth::dto::totp_dto::TotpLoginDto,
+        crate::domains::auth::dto::totp_dto::TotpChallengeDto,
+        crate::domains::auth::dto::totp_dto::LoginResponse,
+        crate::domains::auth::dto::api_key_dto::CreateApiKeyDto,
+        crate::domains::auth::dto::api_key_dto::ApiKeyDto,
+        crate::domains::auth::dto::api_key_dto::ApiKeyCreatedDto,
         crate::common::jwt::AuthPayload,
         crate::common::jwt::AuthBody,
+        crate::common::jwt::Role,
     )),
     tags(
-        (name = "UserAuth", description = "User authentication endpoints")
-    )
+        (name = "UserAuth", description = "User authentication endpoints"),
+        (name = "ApiKeys", description = "API keys for machine clients")
+    ),
+    modifiers(&SecurityAddon, &ErrorResponsesAddon)
 )]
 /// This struct is used to generate `OpenAPI` documentation for the user authentication routes.
 pub struct UserAuthApiDoc;
@@ -29,5 +63,42 @@ pub struct UserAuthApiDoc;
 pub fn user_auth_routes() -> Router<AppState> {
     Router::new()
         .route("/login", post(handlers::login_user))
+        .route("/login/totp", post(handlers::login_totp))
         .route("/register", post(handlers::create_user_auth))
+        .route("/refresh", post(handlers::refresh_token))
+        .route("/logout", post(handlers::logout))
+        .route("/logout_all", post(handlers::logout_all))
+        .route(
+            "/password/reset/request",
+            post(handlers::request_password_reset),
+        )
+        .route(
+            "/password/reset/confirm",
+            post(handlers::confirm_password_reset),
+        )
+}
+
+/// This function creates a router for changing the caller's password.
+/// It is nested under `/auth/password` but, unlike the other `/auth` routes,
+/// requires an authenticated caller.
+pub fn password_routes() -> Router<AppState> {
+    Router::new().route("/change", post(handlers::change_password))
+}
+
+/// This function creates a router for managing API keys.
+/// Unlike the login routes, these require an authenticated caller.
+pub fn api_key_routes() -> Router<AppState> {
+    Router::new()
+        .route("/", post(handlers::create_api_key))
+        .route("/", get(handlers::get_api_keys))
+        .route("/{id}", delete(handlers::revoke_api_key))
+}
+
+/// This function creates a router for managing the caller's TOTP two-factor
+/// authentication. Like [`password_routes`], it requires an authenticated caller.
+pub fn totp_routes() -> Router<AppState> {
+    Router::new()
+        .route("/enroll", post(handlers::enroll_totp))
+        .route("/verify", post(handlers::verify_totp))
+        .route("/disable", post(handlers::disable_totp))
 }
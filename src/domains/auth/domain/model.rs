@@ -1,6 +1,7 @@
-//! This module defines the `UserAuth` model used for representing
-//! authentication data tied to a user.
+//! This module defines the `UserAuth`, `ApiKey` and `PasswordResetToken` models
+//! used for representing authentication data tied to a user.
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 /// Represents a user's authentication information, including hashed password.
@@ -8,4 +9,154 @@ use serde::{Deserialize, Serialize};
 pub struct UserAuth {
     pub user_id: String,
     pub password_hash: String,
+    /// Consecutive failed logins since the last successful one.
+    pub failed_login_attempts: i32,
+    /// Logins are rejected until this time after too many failures.
+    pub locked_until: Option<DateTime<Utc>>,
+    /// Encrypted TOTP secret; present once enrollment has started.
+    pub totp_secret: Option<Vec<u8>>,
+    /// Whether logins require a TOTP code.
+    pub totp_enabled: bool,
+    /// Time step of the last accepted TOTP code.
+    pub totp_last_used_step: Option<i64>,
+}
+
+/// Marker that starts every API key, so leaked keys are easy to recognise.
+pub const API_KEY_MARKER: &str = "lrk";
+
+/// Length of the non-secret lookup prefix inside an API key.
+pub const API_KEY_PREFIX_LEN: usize = 8;
+
+/// Represents an issued API key. The key itself is never stored, only its hash.
+#[derive(Debug, Clone)]
+pub struct ApiKey {
+    pub id: String,
+    pub user_id: String,
+    pub name: String,
+    pub prefix: String,
+    pub key_hash: String,
+    pub role: String,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+/// Builds the full key presented by clients: `lrk_<prefix>_<secret>`.
+pub fn format_api_key(prefix: &str, secret: &str) -> String {
+    format!("{API_KEY_MARKER}_{prefix}_{secret}")
+}
+
+/// Extracts the lookup prefix from a presented key, or `None` if it is malformed.
+pub fn api_key_prefix(key: &str) -> Option<&str> {
+    let rest = key.strip_prefix(API_KEY_MARKER)?.strip_prefix('_')?;
+    let (prefix, secret) = rest.split_once('_')?;
+    (prefix.len() == API_KEY_PREFIX_LEN && !secret.is_empty()).then_some(prefix)
+}
+
+/// How long a refresh token stays valid if it is not rotated.
+pub const REFRESH_TOKEN_TTL_DAYS: i64 = 30;
+
+/// The session a refresh token belongs to: the account itself, or one of the
+/// user's registered devices.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RefreshSession {
+    pub user_id: String,
+    pub device_id: Option<String>,
+}
+
+/// Builds a refresh token: `<user_id>.<device_id>.<secret>`, with an empty
+/// device segment for account-level sessions.
+pub fn format_refresh_token(session: &RefreshSession, secret: &str) -> String {
+    let device_id = session.device_id.as_deref().unwrap_or_default();
+    format!("{}.{device_id}.{secret}", session.user_id)
+}
+
+/// Extracts the session from a presented refresh token, or `None` if it is malformed.
+pub fn parse_refresh_token(token: &str) -> Option<RefreshSession> {
+    let mut parts = token.splitn(3, '.');
+    let user_id = parts.next().filter(|s| !s.is_empty())?;
+    let device_id = parts.next()?;
+    parts.next().filter(|s| !s.is_empty())?;
+    Some(RefreshSession {
+        user_id: user_id.to_owned(),
+        device_id: (!device_id.is_empty()).then(|| device_id.to_owned()),
+    })
+}
+
+/// How long an emailed password reset token stays valid.
+pub const PASSWORD_RESET_TTL_MINUTES: i64 = 30;
+
+/// Represents an issued password reset token. Only its hash is stored.
+#[derive(Debug, Clone)]
+pub struct PasswordResetToken {
+    pub id: String,
+    pub user_id: String,
+    pub token_hash: String,
+    pub expires_at: DateTime<Utc>,
+    pub used_at: Option<DateTime<Utc>>,
+}
+
+/// Builds a password reset token: `<token_id>.<secret>`.
+pub fn format_reset_token(id: &str, secret: &str) -> String {
+    format!("{id}.{secret}")
+}
+
+/// Extracts the token ID from a presented reset token, or `None` if it is malformed.
+pub fn reset_token_id(token: &str) -> Option<&str> {
+    let (id, secret) = token.split_once('.')?;
+    (!id.is_empty() && !secret.is_empty()).then_some(id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        api_key_prefix, format_api_key, format_refresh_token, format_reset_token,
+        parse_refresh_token, reset_token_id, RefreshSession,
+    };
+
+    #[test]
+    fn prefix_round_trips_through_formatted_key() {
+        let key = format_api_key("abcd1234", "s3cret");
+        assert_eq!(api_key_prefix(&key), Some("abcd1234"));
+    }
+
+    #[test]
+    fn malformed_keys_have_no_prefix() {
+        assert_eq!(api_key_prefix("abcd1234_s3cret"), None);
+        assert_eq!(api_key_prefix("lrk_abcd_s3cret"), None);
+        assert_eq!(api_key_prefix("lrk_abcd1234_"), None);
+        assert_eq!(api_key_prefix("lrk_abcd1234"), None);
+    }
+
+    #[test]
+    fn refresh_session_round_trips_through_token() {
+        for device_id in [None, Some("device-1".to_owned())] {
+            let session = RefreshSession {
+                user_id: "user-1".to_owned(),
+                device_id,
+            };
+            let token = format_refresh_token(&session, "s3cret");
+            assert_eq!(parse_refresh_token(&token), Some(session));
+        }
+    }
+
+    #[test]
+    fn malformed_refresh_tokens_have_no_session() {
+        assert_eq!(parse_refresh_token("user-1.device-1."), None);
+        assert_eq!(parse_refresh_token(".device-1.s3cret"), None);
+        assert_eq!(parse_refresh_token("user-1"), None);
+    }
+
+    #[test]
+    fn reset_token_id_round_trips() {
+        let token = format_reset_token("token-1", "s3cret");
+        assert_eq!(reset_token_id(&token), Some("token-1"));
+    }
+
+    #[test]
+    fn malformed_reset_tokens_have_no_id() {
+        assert_eq!(reset_token_id("token-1"), None);
+        assert_eq!(reset_token_id(".s3cret"), None);
+        assert_eq!(reset_token_id("token-1."), None);
+    }
 }
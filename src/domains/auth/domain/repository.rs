@@ -1,7 +1,9 @@
 //! This module defines the `UserAuthRepository` trait, which provides an abstraction
 //! over database operations related to user authentication records.
 
-use super::model::UserAuth;
+use super::model::{ApiKey, PasswordResetToken, RefreshSession, UserAuth};
+
+use chrono::{DateTime, Utc};
 
 use async_trait::async_trait;
 use sea_orm::{DatabaseConnection, DatabaseTransaction, DbErr};
@@ -18,6 +20,183 @@ pub trait UserAuthRepository: Send + Sync {
         user_name: String,
     ) -> Result<Option<UserAuth>, DbErr>;
 
+    /// Finds a user authentication record by user ID.
+    async fn find_by_user_id(
+        &self,
+        db: &DatabaseConnection,
+        user_id: &str,
+    ) -> Result<Option<UserAuth>, DbErr>;
+
+    /// Returns the IDs of the users registered with an email address.
+    async fn find_user_ids_by_email(
+        &self,
+        db: &DatabaseConnection,
+        email: &str,
+    ) -> Result<Vec<String>, DbErr>;
+
+    /// Returns the role name assigned to the user, or `None` if no such user exists.
+    async fn find_role(
+        &self,
+        db: &DatabaseConnection,
+        user_id: &str,
+    ) -> Result<Option<String>, DbErr>;
+
     /// Inserts a new user authentication record into the database using a transaction.
     async fn create(&self, tx: &DatabaseTransaction, user_auth: UserAuth) -> Result<(), DbErr>;
+
+    /// Assigns the role named `role` to the user.
+    async fn set_role(
+        &self,
+        tx: &DatabaseTransaction,
+        user_id: &str,
+        role: &str,
+    ) -> Result<(), DbErr>;
+
+    /// Replaces the user's password hash and lifts any lockout.
+    /// Returns whether the user exists.
+    async fn update_password(
+        &self,
+        tx: &DatabaseTransaction,
+        user_id: &str,
+        password_hash: String,
+    ) -> Result<bool, DbErr>;
+
+    /// Stores a new encrypted TOTP secret (or removes it with `None`).
+    /// TOTP stays disabled until [`Self::enable_totp`] is called.
+    async fn set_totp_secret(
+        &self,
+        db: &DatabaseConnection,
+        user_id: &str,
+        secret: Option<Vec<u8>>,
+    ) -> Result<(), DbErr>;
+
+    /// Requires a TOTP code at login, if a secret is stored.
+    async fn enable_totp(&self, db: &DatabaseConnection, user_id: &str) -> Result<(), DbErr>;
+
+    /// Records that the code of `step` was used. Returns `false` if that step
+    /// or a later one was already used, so each code is accepted only once.
+    async fn use_totp_step(
+        &self,
+        db: &DatabaseConnection,
+        user_id: &str,
+        step: i64,
+    ) -> Result<bool, DbErr>;
+
+    /// Counts a failed login. Reaching `max_attempts` consecutive failures locks
+    /// the account until `lock_until` and starts the count again.
+    async fn record_failed_login(
+        &self,
+        db: &DatabaseConnection,
+        user_id: &str,
+        max_attempts: i32,
+        lock_until: DateTime<Utc>,
+    ) -> Result<(), DbErr>;
+
+    /// Clears the failed login count and any lockout. Returns whether the user exists.
+    async fn reset_failed_logins(
+        &self,
+        db: &DatabaseConnection,
+        user_id: &str,
+    ) -> Result<bool, DbErr>;
+
+    /// Returns the stored refresh token hash and its expiry for a session.
+    /// Device sessions are only found while the device is `active`.
+    async fn find_refresh_token(
+        &self,
+        db: &DatabaseConnection,
+        session: &RefreshSession,
+    ) -> Result<Option<(String, DateTime<Utc>)>, DbErr>;
+
+    /// Stores a new refresh token hash for a session. When `expected_hash` is set,
+    /// the write only succeeds if it is still the stored hash, so a token can be
+    /// rotated at most once. Returns whether the session was updated.
+    async fn store_refresh_token(
+        &self,
+        db: &DatabaseConnection,
+        session: &RefreshSession,
+        expected_hash: Option<&str>,
+        hash: String,
+        expires_at: DateTime<Utc>,
+    ) -> Result<bool, DbErr>;
+
+    /// Revokes the refresh token of a single session.
+    async fn clear_refresh_token(
+        &self,
+        db: &DatabaseConnection,
+        session: &RefreshSession,
+    ) -> Result<(), DbErr>;
+
+    /// Revokes the refresh tokens of the account and every device of the user.
+    async fn clear_all_refresh_tokens(
+        &self,
+        db: &DatabaseConnection,
+        user_id: &str,
+    ) -> Result<(), DbErr>;
+
+    /// Returns the user's current token generation, or `None` if no such user exists.
+    async fn find_token_generation(
+        &self,
+        db: &DatabaseConnection,
+        user_id: &str,
+    ) -> Result<Option<i32>, DbErr>;
+
+    /// Starts a new token generation, invalidating every access token issued
+    /// to the user so far.
+    async fn bump_token_generation(
+        &self,
+        db: &DatabaseConnection,
+        user_id: &str,
+    ) -> Result<(), DbErr>;
+}
+
+#[async_trait]
+/// Trait representing the repository contract for API keys.
+pub trait ApiKeyRepository: Send + Sync {
+    /// Inserts a newly issued API key.
+    async fn create(&self, db: &DatabaseConnection, api_key: ApiKey) -> Result<ApiKey, DbErr>;
+
+    /// Finds an API key by ID, including revoked keys.
+    async fn find_by_id(&self, db: &DatabaseConnection, id: &str) -> Result<Option<ApiKey>, DbErr>;
+
+    /// Finds a non-revoked API key by its lookup prefix.
+    async fn find_active_by_prefix(
+        &self,
+        db: &DatabaseConnection,
+        prefix: &str,
+    ) -> Result<Option<ApiKey>, DbErr>;
+
+    /// Lists API keys, newest first; restricted to one user when `user_id` is set.
+    async fn find_all(
+        &self,
+        db: &DatabaseConnection,
+        user_id: Option<&str>,
+    ) -> Result<Vec<ApiKey>, DbErr>;
+
+    /// Marks an API key as revoked. Revoking an already revoked key is a no-op.
+    async fn revoke(&self, db: &DatabaseConnection, id: &str) -> Result<(), DbErr>;
+
+    /// Records that an API key was just used to authenticate.
+    async fn touch(&self, db: &DatabaseConnection, id: &str) -> Result<(), DbErr>;
+}
+
+#[async_trait]
+/// Trait representing the repository contract for password reset tokens.
+pub trait PasswordResetRepository: Send + Sync {
+    /// Inserts a newly issued reset token.
+    async fn create(&self, db: &DatabaseConnection, token: PasswordResetToken)
+        -> Result<(), DbErr>;
+
+    /// Finds a reset token by ID, including used and expired tokens.
+    async fn find_by_id(
+        &self,
+        db: &DatabaseConnection,
+        id: &str,
+    ) -> Result<Option<PasswordResetToken>, DbErr>;
+
+    /// Marks a token as used. Returns `false` if it had already been used,
+    /// so a token can be redeemed at most once.
+    async fn mark_used(&self, tx: &DatabaseTransaction, id: &str) -> Result<bool, DbErr>;
+
+    /// Marks every unused token of a user as used, e.g. after a password change.
+    async fn invalidate_all(&self, tx: &DatabaseTransaction, user_id: &str) -> Result<(), DbErr>;
 }
@@ -7,10 +7,19 @@ use sea_orm::DatabaseConnection;
 
 use crate::{
     common::{
+        config::Config,
         error::AppError,
-        jwt::{AuthBody, AuthPayload},
+        jwt::{AuthBody, AuthPayload, Claims},
+    },
+    domains::{
+        auth::dto::{
+            api_key_dto::{ApiKeyCreatedDto, ApiKeyDto, CreateApiKeyDto},
+            auth_dto::{ChangePasswordDto, PasswordResetConfirmDto, RegisterDto},
+            totp_dto::{LoginResponse, TotpEnrollmentDto, TotpLoginDto},
+        },
+        device::DeviceServiceTrait,
+        user::UserServiceTrait,
     },
-    domains::{auth::dto::auth_dto::RegisterDto, user::UserServiceTrait},
 };
 
 #[async_trait::async_trait]
@@ -19,8 +28,10 @@ use crate::{
 pub trait AuthServiceTrait: Send + Sync {
     /// constructor for the service.
     fn create_service(
+        config: &Config,
         pool: DatabaseConnection,
         user_service: Arc<dyn UserServiceTrait>,
+        device_service: Arc<dyn DeviceServiceTrait>,
     ) -> Arc<dyn AuthServiceTrait>
     where
         Self: Sized;
@@ -28,6 +39,90 @@ pub trait AuthServiceTrait: Send + Sync {
     /// Registers a new user authentication entry.
     async fn create_user_auth(&self, register_dto: RegisterDto) -> Result<(), AppError>;
 
+    /// Registers a new user with the admin role and returns its ID.
+    async fn create_admin(&self, register_dto: RegisterDto) -> Result<String, AppError>;
+
     /// Authenticates a user and returns a JWT token payload on success.
-    async fn login_user(&self, auth_payload: AuthPayload) -> Result<AuthBody, AppError>;
+    /// Attempts are rate limited per account, and repeated failures lock the account.
+    /// The refresh token is bound to `device_id` when given, otherwise to the account.
+    /// Accounts with TOTP enabled get a challenge to complete with [`Self::login_totp`].
+    async fn login_user(
+        &self,
+        auth_payload: AuthPayload,
+        device_id: Option<String>,
+    ) -> Result<LoginResponse, AppError>;
+
+    /// Completes a login challenge with a TOTP code and returns the tokens.
+    /// Wrong codes count as failed logins.
+    async fn login_totp(&self, dto: TotpLoginDto) -> Result<AuthBody, AppError>;
+
+    /// Starts TOTP enrollment for the caller with a new secret, replacing any
+    /// unverified one. Fails if TOTP is already enabled.
+    async fn enroll_totp(&self, claims: &Claims) -> Result<TotpEnrollmentDto, AppError>;
+
+    /// Enables TOTP once the caller proves their app produces valid codes.
+    async fn verify_totp(&self, claims: &Claims, code: &str) -> Result<(), AppError>;
+
+    /// Disables TOTP for the caller; requires a current code.
+    async fn disable_totp(&self, claims: &Claims, code: &str) -> Result<(), AppError>;
+
+    /// Exchanges a refresh token for a new token pair, rotating the refresh token.
+    async fn refresh_token(&self, refresh_token: &str) -> Result<AuthBody, AppError>;
+
+    /// Clears a user's failed login count and lifts any lockout.
+    async fn unlock_account(&self, user_id: &str) -> Result<(), AppError>;
+
+    /// Revokes the refresh token's session. Every access token of the user is
+    /// invalidated, since they do not name their session; the user's other
+    /// sessions keep their refresh tokens and get new access tokens with them.
+    async fn logout(&self, refresh_token: &str) -> Result<(), AppError>;
+
+    /// Revokes every session of the refresh token's user, on all devices, and
+    /// every access token issued to the user.
+    async fn logout_all(&self, refresh_token: &str) -> Result<(), AppError>;
+
+    /// Changes the caller's password after checking the old one. Every other
+    /// session is signed out; the returned tokens continue the caller's
+    /// session, on its device when it has one.
+    async fn change_password(
+        &self,
+        claims: &Claims,
+        dto: ChangePasswordDto,
+    ) -> Result<AuthBody, AppError>;
+
+    /// Sends a password reset token to every account registered with `email`.
+    /// Succeeds whether or not such an account exists.
+    async fn request_password_reset(&self, email: &str) -> Result<(), AppError>;
+
+    /// Sets a new password using an emailed reset token, lifts any lockout
+    /// and signs out every session.
+    async fn confirm_password_reset(&self, dto: PasswordResetConfirmDto) -> Result<(), AppError>;
+
+    /// Issues an API key for the caller, or for `user_id` when the caller is an admin.
+    async fn create_api_key(
+        &self,
+        claims: &Claims,
+        dto: CreateApiKeyDto,
+    ) -> Result<ApiKeyCreatedDto, AppError>;
+
+    /// Lists the caller's API keys; admins see every key.
+    async fn list_api_keys(&self, claims: &Claims) -> Result<Vec<ApiKeyDto>, AppError>;
+
+    /// Revokes an API key owned by the caller (or any key, for admins).
+    async fn revoke_api_key(&self, claims: &Claims, id: &str) -> Result<ApiKeyDto, AppError>;
+
+    /// Resolves a presented API key to the claims of its owner, with the key's role.
+    async fn authenticate_api_key(&self, api_key: &str) -> Result<Claims, AppError>;
+
+    /// Fails with `InvalidToken` unless the access token's generation is still
+    /// its user's current one.
+    async fn check_token_generation(&self, claims: &Claims) -> Result<(), AppError>;
+}
+
+#[async_trait::async_trait]
+/// Delivers password reset tokens to users, e.g. by email.
+pub trait PasswordResetNotifier: Send + Sync {
+    /// Sends `token` to `email`. Delivery failures are reported but do not
+    /// fail the reset request.
+    async fn send_reset_token(&self, email: &str, token: &str) -> Result<(), AppError>;
 }
@@ -0,0 +1,56 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::{common::jwt::Role, domains::auth::domain::model::ApiKey};
+
+/// Request body for issuing an API key.
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+pub struct CreateApiKeyDto {
+    /// Label for the key, e.g. the name of the client using it.
+    #[validate(length(min = 1, max = 64, message = "Name must be 1-64 characters"))]
+    pub name: String,
+    /// Role granted to the key; defaults to the owner's role and may not exceed it.
+    pub role: Option<Role>,
+    /// Owner of the key; defaults to the caller. Only admins may issue keys for other users.
+    pub user_id: Option<String>,
+}
+
+/// An issued API key, without its secret.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ApiKeyDto {
+    pub id: String,
+    pub user_id: String,
+    pub name: String,
+    /// Non-secret prefix that identifies the key in logs and listings.
+    pub prefix: String,
+    pub role: String,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+/// A newly issued API key. `api_key` is only ever returned here and cannot be recovered later.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ApiKeyCreatedDto {
+    /// The full key, to be sent as the `X-Api-Key` header.
+    pub api_key: String,
+    #[serde(flatten)]
+    pub key: ApiKeyDto,
+}
+
+impl From<ApiKey> for ApiKeyDto {
+    fn from(key: ApiKey) -> Self {
+        Self {
+            id: key.id,
+            user_id: key.user_id,
+            name: key.name,
+            prefix: key.prefix,
+            role: key.role,
+            created_at: key.created_at,
+            last_used_at: key.last_used_at,
+            revoked_at: key.revoked_at,
+        }
+    }
+}
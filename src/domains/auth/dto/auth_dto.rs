@@ -1,5 +1,5 @@
-use serde::Deserialize;
-use utoipa::ToSchema;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 use validator::Validate;
 
 #[derive(Debug, Deserialize, ToSchema, Validate)]
@@ -11,3 +11,41 @@ pub struct RegisterDto {
     #[validate(length(min = 8, message = "Password must be at least 8 characters long"))]
     pub password: String,
 }
+
+/// Query parameters for `/auth/login`.
+#[derive(Debug, Default, Deserialize, IntoParams)]
+pub struct LoginQuery {
+    /// Registered device to bind the refresh token to. Without it the refresh
+    /// token belongs to the account, and a new login replaces it.
+    pub device_id: Option<String>,
+}
+
+/// Request body carrying a refresh token.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RefreshTokenDto {
+    pub refresh_token: String,
+}
+
+/// Request body for `/auth/password/change`.
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+pub struct ChangePasswordDto {
+    pub old_password: String,
+    #[validate(length(min = 8, message = "Password must be at least 8 characters long"))]
+    pub new_password: String,
+}
+
+/// Request body for `/auth/password/reset/request`.
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+pub struct PasswordResetRequestDto {
+    #[validate(email(message = "Email is invalid"))]
+    pub email: String,
+}
+
+/// Request body for `/auth/password/reset/confirm`.
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+pub struct PasswordResetConfirmDto {
+    /// Token received by email.
+    pub token: String,
+    #[validate(length(min = 8, message = "Password must be at least 8 characters long"))]
+    pub new_password: String,
+}
@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::common::jwt::AuthBody;
+
+/// Returned when TOTP enrollment starts. Show `provisioning_uri` as a QR code,
+/// or let the user type `secret` into their authenticator app.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TotpEnrollmentDto {
+    /// Base32-encoded secret.
+    pub secret: String,
+    /// `otpauth://` URI for authenticator apps.
+    pub provisioning_uri: String,
+}
+
+/// Request body carrying a TOTP code from the user's authenticator app.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TotpCodeDto {
+    pub code: String,
+}
+
+/// Request body for `/auth/login/totp`, the second step of a login with TOTP.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TotpLoginDto {
+    /// Token from the first login step.
+    pub challenge_token: String,
+    pub code: String,
+}
+
+/// Returned by `/auth/login` instead of tokens when the account requires a
+/// TOTP code. Exchange it with the code at `/auth/login/totp`.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TotpChallengeDto {
+    /// Always `true`; lets clients tell this response apart from [`AuthBody`].
+    pub totp_required: bool,
+    /// Short-lived token proving the password was verified.
+    pub challenge_token: String,
+}
+
+/// Result of the password step of a login.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(untagged)]
+pub enum LoginResponse {
+    /// Logged in.
+    Tokens(AuthBody),
+    /// A TOTP code is required to finish logging in.
+    TotpRequired(TotpChallengeDto),
+}
@@ -1,12 +1,19 @@
 use async_trait::async_trait;
 use sea_orm::{
-    ActiveModelTrait as _, ColumnTrait as _, DatabaseConnection, DatabaseTransaction, DbErr,
-    EntityTrait as _, JoinType, QueryFilter as _, QuerySelect as _, RelationTrait as _, Set,
+    sea_query::{Expr, SimpleExpr},
+    ActiveModelTrait as _, ColumnTrait as _, Condition, DatabaseConnection, DatabaseTransaction,
+    DbErr, EntityTrait as _, JoinType, NotSet, Order, QueryFilter as _, QueryOrder as _,
+    QuerySelect as _, RelationTrait as _, Set, TransactionTrait as _,
 };
 
-use crate::domains::auth::domain::model::UserAuth;
-use crate::domains::auth::domain::repository::UserAuthRepository;
-use crate::entities::{user_auth, users};
+use crate::domains::auth::domain::model::{ApiKey, PasswordResetToken, RefreshSession, UserAuth};
+use crate::domains::auth::domain::repository::{
+    ApiKeyRepository, PasswordResetRepository, UserAuthRepository,
+};
+use crate::entities::{api_keys, devices, password_reset_tokens, user_auth, users};
+
+/// Device status whose sessions may use refresh tokens.
+const ACTIVE_DEVICE_STATUS: &str = "active";
 
 pub struct UserAuthRepo;
 
@@ -15,6 +22,11 @@ impl UserAuthRepo {
         UserAuth {
             user_id: entity.user_id,
             password_hash: entity.password_hash,
+            failed_login_attempts: entity.failed_login_attempts,
+            locked_until: entity.locked_until,
+            totp_secret: entity.totp_secret,
+            totp_enabled: entity.totp_enabled,
+            totp_last_used_step: entity.totp_last_used_step,
         }
     }
 }
@@ -36,15 +48,548 @@ impl UserAuthRepository for UserAuthRepo {
         Ok(result)
     }
 
+    async fn find_by_user_id(
+        &self,
+        db: &DatabaseConnection,
+        user_id: &str,
+    ) -> Result<Option<UserAuth>, DbErr> {
+        let result = user_auth::Entity::find_by_id(user_id)
+            .one(db)
+            .await?
+            .map(Self::entity_to_model);
+
+        Ok(result)
+    }
+
+    async fn find_user_ids_by_email(
+        &self,
+        db: &DatabaseConnection,
+        email: &str,
+    ) -> Result<Vec<String>, DbErr> {
+        user_auth::Entity::find()
+            .join(JoinType::InnerJoin, user_auth::Relation::Users.def())
+            .filter(users::Column::Email.eq(email))
+            .select_only()
+            .column(user_auth::Column::UserId)
+            .into_tuple()
+            .all(db)
+            .await
+    }
+
+    async fn find_role(
+        &self,
+        db: &DatabaseConnection,
+        user_id: &str,
+    ) -> Result<Option<String>, DbErr> {
+        users::Entity::find_by_id(user_id)
+            .select_only()
+            .column(users::Column::Role)
+            .into_tuple()
+            .one(db)
+            .await
+    }
+
     async fn create(&self, tx: &DatabaseTransaction, user_auth: UserAuth) -> Result<(), DbErr> {
         let active_user_auth = user_auth::ActiveModel {
             user_id: Set(user_auth.user_id),
             password_hash: Set(user_auth.password_hash),
             created_at: Set(Some(chrono::Utc::now())),
             modified_at: Set(Some(chrono::Utc::now())),
+            refresh_token_hash: NotSet,
+            refresh_token_expires_at: NotSet,
+            failed_login_attempts: Set(user_auth.failed_login_attempts),
+            locked_until: Set(user_auth.locked_until),
+            totp_secret: Set(user_auth.totp_secret),
+            totp_enabled: Set(user_auth.totp_enabled),
+            totp_last_used_step: Set(user_auth.totp_last_used_step),
+            token_generation: NotSet,
         };
 
         active_user_auth.insert(tx).await?;
         Ok(())
     }
+
+    async fn set_role(
+        &self,
+        tx: &DatabaseTransaction,
+        user_id: &str,
+        role: &str,
+    ) -> Result<(), DbErr> {
+        users::Entity::update_many()
+            .col_expr(users::Column::Role, Expr::value(role))
+            .filter(users::Column::Id.eq(user_id))
+            .exec(tx)
+            .await?;
+        Ok(())
+    }
+
+    async fn update_password(
+        &self,
+        tx: &DatabaseTransaction,
+        user_id: &str,
+        password_hash: String,
+    ) -> Result<bool, DbErr> {
+        let result = user_auth::Entity::update_many()
+            .col_expr(user_auth::Column::PasswordHash, Expr::value(password_hash))
+            .col_expr(user_auth::Column::FailedLoginAttempts, Expr::value(0))
+            .col_expr(
+                user_auth::Column::LockedUntil,
+                Expr::value(None::<chrono::DateTime<chrono::Utc>>),
+            )
+            .col_expr(
+                user_auth::Column::ModifiedAt,
+                Expr::value(chrono::Utc::now()),
+            )
+            .filter(user_auth::Column::UserId.eq(user_id))
+            .exec(tx)
+            .await?;
+        Ok(result.rows_affected > 0)
+    }
+
+    async fn set_totp_secret(
+        &self,
+        db: &DatabaseConnection,
+        user_id: &str,
+        secret: Option<Vec<u8>>,
+    ) -> Result<(), DbErr> {
+        user_auth::Entity::update_many()
+            .col_expr(user_auth::Column::TotpSecret, Expr::value(secret))
+            .col_expr(user_auth::Column::TotpEnabled, Expr::value(false))
+            .col_expr(
+                user_auth::Column::TotpLastUsedStep,
+                Expr::value(None::<i64>),
+            )
+            .filter(user_auth::Column::UserId.eq(user_id))
+            .exec(db)
+            .await?;
+        Ok(())
+    }
+
+    async fn enable_totp(&self, db: &DatabaseConnection, user_id: &str) -> Result<(), DbErr> {
+        user_auth::Entity::update_many()
+            .col_expr(user_auth::Column::TotpEnabled, Expr::value(true))
+            .filter(user_auth::Column::UserId.eq(user_id))
+            .filter(user_auth::Column::TotpSecret.is_not_null())
+            .exec(db)
+            .await?;
+        Ok(())
+    }
+
+    async fn use_totp_step(
+        &self,
+        db: &DatabaseConnection,
+        user_id: &str,
+        step: i64,
+    ) -> Result<bool, DbErr> {
+        let result = user_auth::Entity::update_many()
+            .col_expr(user_auth::Column::TotpLastUsedStep, Expr::value(step))
+            .filter(user_auth::Column::UserId.eq(user_id))
+            .filter(
+                Condition::any()
+                    .add(user_auth::Column::TotpLastUsedStep.is_null())
+                    .add(user_auth::Column::TotpLastUsedStep.lt(step)),
+            )
+            .exec(db)
+            .await?;
+        Ok(result.rows_affected > 0)
+    }
+
+    async fn record_failed_login(
+        &self,
+        db: &DatabaseConnection,
+        user_id: &str,
+        max_attempts: i32,
+        lock_until: chrono::DateTime<chrono::Utc>,
+    ) -> Result<(), DbErr> {
+        // Both expressions read the pre-update counter.
+        let reaches_limit =
+            Expr::expr(Expr::col(user_auth::Column::FailedLoginAttempts).add(1)).gte(max_attempts);
+        user_auth::Entity::update_many()
+            .col_expr(
+                user_auth::Column::FailedLoginAttempts,
+                SimpleExpr::from(
+                    Expr::case(reaches_limit.clone(), 0)
+                        .finally(Expr::col(user_auth::Column::FailedLoginAttempts).add(1)),
+                ),
+            )
+            .col_expr(
+                user_auth::Column::LockedUntil,
+                SimpleExpr::from(
+                    Expr::case(reaches_limit, Expr::value(lock_until))
+                        .finally(Expr::col(user_auth::Column::LockedUntil)),
+                ),
+            )
+            .filter(user_auth::Column::UserId.eq(user_id))
+            .exec(db)
+            .await?;
+        Ok(())
+    }
+
+    async fn reset_failed_logins(
+        &self,
+        db: &DatabaseConnection,
+        user_id: &str,
+    ) -> Result<bool, DbErr> {
+        let result = user_auth::Entity::update_many()
+            .col_expr(user_auth::Column::FailedLoginAttempts, Expr::value(0))
+            .col_expr(
+                user_auth::Column::LockedUntil,
+                Expr::value(None::<chrono::DateTime<chrono::Utc>>),
+            )
+            .filter(user_auth::Column::UserId.eq(user_id))
+            .exec(db)
+            .await?;
+        Ok(result.rows_affected > 0)
+    }
+
+    async fn find_refresh_token(
+        &self,
+        db: &DatabaseConnection,
+        session: &RefreshSession,
+    ) -> Result<Option<(String, chrono::DateTime<chrono::Utc>)>, DbErr> {
+        let stored: Option<(Option<String>, Option<chrono::DateTime<chrono::Utc>>)> =
+            match &session.device_id {
+                Some(device_id) => {
+                    devices::Entity::find_by_id(device_id.as_str())
+                        .filter(devices::Column::UserId.eq(session.user_id.as_str()))
+                        .filter(devices::Column::Status.eq(ACTIVE_DEVICE_STATUS))
+                        .select_only()
+                        .column(devices::Column::RefreshTokenHash)
+                        .column(devices::Column::RefreshTokenExpiresAt)
+                        .into_tuple()
+                        .one(db)
+                        .await?
+                }
+                None => {
+                    user_auth::Entity::find_by_id(session.user_id.as_str())
+                        .select_only()
+                        .column(user_auth::Column::RefreshTokenHash)
+                        .column(user_auth::Column::RefreshTokenExpiresAt)
+                        .into_tuple()
+                        .one(db)
+                        .await?
+                }
+            };
+
+        Ok(stored.and_then(|(hash, expires_at)| hash.zip(expires_at)))
+    }
+
+    async fn store_refresh_token(
+        &self,
+        db: &DatabaseConnection,
+        session: &RefreshSession,
+        expected_hash: Option<&str>,
+        hash: String,
+        expires_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<bool, DbErr> {
+        let result = match &session.device_id {
+            Some(device_id) => {
+                let mut update = devices::Entity::update_many()
+                    .col_expr(devices::Column::RefreshTokenHash, Expr::value(hash))
+                    .col_expr(
+                        devices::Column::RefreshTokenExpiresAt,
+                        Expr::value(expires_at),
+                    )
+                    .filter(devices::Column::Id.eq(device_id.as_str()))
+                    .filter(devices::Column::UserId.eq(session.user_id.as_str()))
+                    .filter(devices::Column::Status.eq(ACTIVE_DEVICE_STATUS));
+                if let Some(expected_hash) = expected_hash {
+                    update = update.filter(devices::Column::RefreshTokenHash.eq(expected_hash));
+                }
+                update.exec(db).await?
+            }
+            None => {
+                let mut update = user_auth::Entity::update_many()
+                    .col_expr(user_auth::Column::RefreshTokenHash, Expr::value(hash))
+                    .col_expr(
+                        user_auth::Column::RefreshTokenExpiresAt,
+                        Expr::value(expires_at),
+                    )
+                    .filter(user_auth::Column::UserId.eq(session.user_id.as_str()));
+                if let Some(expected_hash) = expected_hash {
+                    update = update.filter(user_auth::Column::RefreshTokenHash.eq(expected_hash));
+                }
+                update.exec(db).await?
+            }
+        };
+
+        Ok(result.rows_affected > 0)
+    }
+
+    async fn clear_refresh_token(
+        &self,
+        db: &DatabaseConnection,
+        session: &RefreshSession,
+    ) -> Result<(), DbErr> {
+        match &session.device_id {
+            Some(device_id) => {
+                devices::Entity::update_many()
+                    .col_expr(
+                        devices::Column::RefreshTokenHash,
+                        Expr::value(None::<String>),
+                    )
+                    .col_expr(
+                        devices::Column::RefreshTokenExpiresAt,
+                        Expr::value(None::<chrono::DateTime<chrono::Utc>>),
+                    )
+                    .filter(devices::Column::Id.eq(device_id.as_str()))
+                    .filter(devices::Column::UserId.eq(session.user_id.as_str()))
+                    .exec(db)
+                    .await?;
+            }
+            None => {
+                user_auth::Entity::update_many()
+                    .col_expr(
+                        user_auth::Column::RefreshTokenHash,
+                        Expr::value(None::<String>),
+                    )
+                    .col_expr(
+                        user_auth::Column::RefreshTokenExpiresAt,
+                        Expr::value(None::<chrono::DateTime<chrono::Utc>>),
+                    )
+                    .filter(user_auth::Column::UserId.eq(session.user_id.as_str()))
+                    .exec(db)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn clear_all_refresh_tokens(
+        &self,
+        db: &DatabaseConnection,
+        user_id: &str,
+    ) -> Result<(), DbErr> {
+        let txn = db.begin().await?;
+        user_auth::Entity::update_many()
+            .col_expr(
+                user_auth::Column::RefreshTokenHash,
+                Expr::value(None::<String>),
+            )
+            .col_expr(
+                user_auth::Column::RefreshTokenExpiresAt,
+                Expr::value(None::<chrono::DateTime<chrono::Utc>>),
+            )
+            .filter(user_auth::Column::UserId.eq(user_id))
+            .exec(&txn)
+            .await?;
+        devices::Entity::update_many()
+            .col_expr(
+                devices::Column::RefreshTokenHash,
+                Expr::value(None::<String>),
+            )
+            .col_expr(
+                devices::Column::RefreshTokenExpiresAt,
+                Expr::value(None::<chrono::DateTime<chrono::Utc>>),
+            )
+            .filter(devices::Column::UserId.eq(user_id))
+            .exec(&txn)
+            .await?;
+        txn.commit().await
+    }
+
+    async fn find_token_generation(
+        &self,
+        db: &DatabaseConnection,
+        user_id: &str,
+    ) -> Result<Option<i32>, DbErr> {
+        user_auth::Entity::find_by_id(user_id)
+            .select_only()
+            .column(user_auth::Column::TokenGeneration)
+            .into_tuple()
+            .one(db)
+            .await
+    }
+
+    async fn bump_token_generation(
+        &self,
+        db: &DatabaseConnection,
+        user_id: &str,
+    ) -> Result<(), DbErr> {
+        user_auth::Entity::update_many()
+            .col_expr(
+                user_auth::Column::TokenGeneration,
+                Expr::col(user_auth::Column::TokenGeneration).add(1),
+            )
+            .filter(user_auth::Column::UserId.eq(user_id))
+            .exec(db)
+            .await?;
+        Ok(())
+    }
+}
+
+pub struct ApiKeyRepo;
+
+impl ApiKeyRepo {
+    fn entity_to_model(entity: api_keys::Model) -> ApiKey {
+        ApiKey {
+            id: entity.id,
+            user_id: entity.user_id,
+            name: entity.name,
+            prefix: entity.prefix,
+            key_hash: entity.key_hash,
+            role: entity.role,
+            created_at: entity.created_at.into(),
+            last_used_at: entity.last_used_at.map(Into::into),
+            revoked_at: entity.revoked_at.map(Into::into),
+        }
+    }
+}
+
+#[async_trait]
+impl ApiKeyRepository for ApiKeyRepo {
+    async fn create(&self, db: &DatabaseConnection, api_key: ApiKey) -> Result<ApiKey, DbErr> {
+        let inserted = api_keys::ActiveModel {
+            id: Set(api_key.id),
+            user_id: Set(api_key.user_id),
+            name: Set(api_key.name),
+            prefix: Set(api_key.prefix),
+            key_hash: Set(api_key.key_hash),
+            role: Set(api_key.role),
+            created_at: Set(api_key.created_at.into()),
+            last_used_at: Set(None),
+            revoked_at: Set(None),
+        }
+        .insert(db)
+        .await?;
+
+        Ok(Self::entity_to_model(inserted))
+    }
+
+    async fn find_by_id(&self, db: &DatabaseConnection, id: &str) -> Result<Option<ApiKey>, DbErr> {
+        let result = api_keys::Entity::find_by_id(id)
+            .one(db)
+            .await?
+            .map(Self::entity_to_model);
+        Ok(result)
+    }
+
+    async fn find_active_by_prefix(
+        &self,
+        db: &DatabaseConnection,
+        prefix: &str,
+    ) -> Result<Option<ApiKey>, DbErr> {
+        let result = api_keys::Entity::find()
+            .filter(api_keys::Column::Prefix.eq(prefix))
+            .filter(api_keys::Column::RevokedAt.is_null())
+            .one(db)
+            .await?
+            .map(Self::entity_to_model);
+        Ok(result)
+    }
+
+    async fn find_all(
+        &self,
+        db: &DatabaseConnection,
+        user_id: Option<&str>,
+    ) -> Result<Vec<ApiKey>, DbErr> {
+        let mut select = api_keys::Entity::find();
+        if let Some(user_id) = user_id {
+            select = select.filter(api_keys::Column::UserId.eq(user_id));
+        }
+
+        let keys = select
+            .order_by(api_keys::Column::CreatedAt, Order::Desc)
+            .all(db)
+            .await?
+            .into_iter()
+            .map(Self::entity_to_model)
+            .collect();
+        Ok(keys)
+    }
+
+    async fn revoke(&self, db: &DatabaseConnection, id: &str) -> Result<(), DbErr> {
+        api_keys::Entity::update_many()
+            .col_expr(api_keys::Column::RevokedAt, Expr::value(chrono::Utc::now()))
+            .filter(api_keys::Column::Id.eq(id))
+            .filter(api_keys::Column::RevokedAt.is_null())
+            .exec(db)
+            .await?;
+        Ok(())
+    }
+
+    async fn touch(&self, db: &DatabaseConnection, id: &str) -> Result<(), DbErr> {
+        api_keys::Entity::update_many()
+            .col_expr(
+                api_keys::Column::LastUsedAt,
+                Expr::value(chrono::Utc::now()),
+            )
+            .filter(api_keys::Column::Id.eq(id))
+            .exec(db)
+            .await?;
+        Ok(())
+    }
+}
+
+pub struct PasswordResetRepo;
+
+impl PasswordResetRepo {
+    fn entity_to_model(entity: password_reset_tokens::Model) -> PasswordResetToken {
+        PasswordResetToken {
+            id: entity.id,
+            user_id: entity.user_id,
+            token_hash: entity.token_hash,
+            expires_at: entity.expires_at.into(),
+            used_at: entity.used_at.map(Into::into),
+        }
+    }
+}
+
+#[async_trait]
+impl PasswordResetRepository for PasswordResetRepo {
+    async fn create(
+        &self,
+        db: &DatabaseConnection,
+        token: PasswordResetToken,
+    ) -> Result<(), DbErr> {
+        password_reset_tokens::ActiveModel {
+            id: Set(token.id),
+            user_id: Set(token.user_id),
+            token_hash: Set(token.token_hash),
+            expires_at: Set(token.expires_at.into()),
+            used_at: Set(token.used_at.map(Into::into)),
+            created_at: Set(chrono::Utc::now().into()),
+        }
+        .insert(db)
+        .await?;
+        Ok(())
+    }
+
+    async fn find_by_id(
+        &self,
+        db: &DatabaseConnection,
+        id: &str,
+    ) -> Result<Option<PasswordResetToken>, DbErr> {
+        let result = password_reset_tokens::Entity::find_by_id(id)
+            .one(db)
+            .await?
+            .map(Self::entity_to_model);
+        Ok(result)
+    }
+
+    async fn mark_used(&self, tx: &DatabaseTransaction, id: &str) -> Result<bool, DbErr> {
+        let result = password_reset_tokens::Entity::update_many()
+            .col_expr(
+                password_reset_tokens::Column::UsedAt,
+                Expr::value(chrono::Utc::now()),
+            )
+            .filter(password_reset_tokens::Column::Id.eq(id))
+            .filter(password_reset_tokens::Column::UsedAt.is_null())
+            .exec(tx)
+            .await?;
+        Ok(result.rows_affected > 0)
+    }
+
+    async fn invalidate_all(&self, tx: &DatabaseTransaction, user_id: &str) -> Result<(), DbErr> {
+        password_reset_tokens::Entity::update_many()
+            .col_expr(
+                password_reset_tokens::Column::UsedAt,
+                Expr::value(chrono::Utc::now()),
+            )
+            .filter(password_reset_tokens::Column::UserId.eq(user_id))
+            .filter(password_reset_tokens::Column::UsedAt.is_null())
+            .exec(tx)
+            .await?;
+        Ok(())
+    }
 }
@@ -2,21 +2,65 @@ use std::sync::Arc;
 
 use crate::{
     common::{
+        config::Config,
+        crypto::SecretCipher,
         error::AppError,
         hash_util,
-        jwt::{make_jwt_token, AuthBody, AuthPayload},
+        jwt::{
+            decode_challenge_token, make_challenge_token, make_jwt_token, AuthBody, AuthPayload,
+            Claims, Role,
+        },
+        rate_limit::RateLimiter,
     },
     domains::{
         auth::{
-            domain::{model::UserAuth, repository::UserAuthRepository, service::AuthServiceTrait},
-            dto::auth_dto::RegisterDto,
-            infra::impl_repository::UserAuthRepo,
+            domain::{
+                model::{
+                    api_key_prefix, format_api_key, format_refresh_token, format_reset_token,
+                    parse_refresh_token, reset_token_id, ApiKey, PasswordResetToken,
+                    RefreshSession, UserAuth, API_KEY_PREFIX_LEN, PASSWORD_RESET_TTL_MINUTES,
+                    REFRESH_TOKEN_TTL_DAYS,
+                },
+                repository::{ApiKeyRepository, PasswordResetRepository, UserAuthRepository},
+                service::{AuthServiceTrait, PasswordResetNotifier},
+                totp,
+            },
+            dto::{
+                api_key_dto::{ApiKeyCreatedDto, ApiKeyDto, CreateApiKeyDto},
+                auth_dto::{ChangePasswordDto, PasswordResetConfirmDto, RegisterDto},
+                totp_dto::{LoginResponse, TotpChallengeDto, TotpEnrollmentDto, TotpLoginDto},
+            },
+            infra::impl_repository::{ApiKeyRepo, PasswordResetRepo, UserAuthRepo},
         },
+        device::DeviceServiceTrait,
         user::{dto::user_dto::CreateUserMultipartDto, UserServiceTrait},
     },
 };
 
-use sea_orm::{DatabaseConnection, TransactionTrait as _};
+use rand::{distr::Alphanumeric, Rng as _};
+use sea_orm::{DatabaseConnection, DbErr, TransactionTrait as _};
+
+/// Length of the random secret part of an API key.
+const API_KEY_SECRET_LEN: usize = 32;
+
+/// Length of the random secret part of a refresh token.
+const REFRESH_TOKEN_SECRET_LEN: usize = 48;
+
+/// Length of the random secret part of a password reset token.
+const RESET_TOKEN_SECRET_LEN: usize = 32;
+
+/// Default [`PasswordResetNotifier`] for deployments without a mail sender:
+/// writes the token to the debug log so an operator can pass it on.
+pub struct LogPasswordResetNotifier;
+
+#[async_trait::async_trait]
+impl PasswordResetNotifier for LogPasswordResetNotifier {
+    async fn send_reset_token(&self, email: &str, token: &str) -> Result<(), AppError> {
+        tracing::info!("Password reset requested for {email}");
+        tracing::debug!("Password reset token for {email}: {token}");
+        Ok(())
+    }
+}
 
 /// Service for handling user authentication
 /// and authorization logic.
@@ -24,26 +68,38 @@ use sea_orm::{DatabaseConnection, TransactionTrait as _};
 pub struct AuthService {
     db: DatabaseConnection,
     repo: Arc<dyn UserAuthRepository + Send + Sync>,
+    api_key_repo: Arc<dyn ApiKeyRepository + Send + Sync>,
+    reset_repo: Arc<dyn PasswordResetRepository + Send + Sync>,
+    reset_notifier: Arc<dyn PasswordResetNotifier>,
     user_service: Arc<dyn UserServiceTrait>,
+    device_service: Arc<dyn DeviceServiceTrait>,
+    /// Per-account limit on login attempts, keyed by lower-cased username.
+    login_limiter: Arc<RateLimiter>,
+    /// Consecutive failed logins that lock an account; 0 disables lockout.
+    max_failed_logins: u32,
+    lockout: chrono::Duration,
+    /// Encrypts TOTP secrets at rest.
+    totp_cipher: Arc<SecretCipher>,
 }
 
-/// Implementation of the `AuthService`
-#[async_trait::async_trait]
-impl AuthServiceTrait for AuthService {
-    /// constructor for the service.
-    fn create_service(
-        db: DatabaseConnection,
-        user_service: Arc<dyn UserServiceTrait>,
-    ) -> Arc<dyn AuthServiceTrait> {
-        Arc::new(Self {
-            db,
-            repo: Arc::new(UserAuthRepo {}),
-            user_service,
-        })
+impl AuthService {
+    /// Returns the current role of a user.
+    async fn user_role(&self, user_id: &str) -> Result<Role, AppError> {
+        self.repo
+            .find_role(&self.db, user_id)
+            .await
+            .map_err(AppError::DatabaseError)?
+            .ok_or(AppError::UserNotFound)?
+            .parse::<Role>()
     }
 
-    /// It hashes the password and stores it in the database.
-    async fn create_user_auth(&self, register_dto: RegisterDto) -> Result<(), AppError> {
+    /// Creates the user and its credentials, assigning `role` when given
+    /// instead of the column default. Returns the new user's ID.
+    async fn register(
+        &self,
+        register_dto: RegisterDto,
+        role: Option<Role>,
+    ) -> Result<String, AppError> {
         let tx = self.db.begin().await?;
 
         let username = register_dto.username.clone();
@@ -67,12 +123,26 @@ impl AuthServiceTrait for AuthService {
         let user_auth = UserAuth {
             user_id: user_dto.id,
             password_hash,
+            failed_login_attempts: 0,
+            locked_until: None,
+            totp_secret: None,
+            totp_enabled: false,
+            totp_last_used_step: None,
         };
 
-        match self.repo.create(&tx, user_auth).await {
+        let user_id = user_auth.user_id.clone();
+        let created = async {
+            self.repo.create(&tx, user_auth).await?;
+            if let Some(role) = role {
+                self.repo.set_role(&tx, &user_id, &role.to_string()).await?;
+            }
+            Ok::<_, DbErr>(())
+        }
+        .await;
+        match created {
             Ok(()) => {
                 tx.commit().await?;
-                Ok(())
+                Ok(user_id)
             }
             Err(err) => {
                 tracing::error!("Error creating user auth: {err}");
@@ -82,15 +152,232 @@ impl AuthServiceTrait for AuthService {
         }
     }
 
+    /// Issues an access token and a fresh refresh token for a session.
+    /// `expected_hash` is the hash of the refresh token being rotated, if any.
+    async fn issue_tokens(
+        &self,
+        session: &RefreshSession,
+        expected_hash: Option<&str>,
+    ) -> Result<AuthBody, AppError> {
+        let role = self.user_role(&session.user_id).await?;
+        let generation = self
+            .repo
+            .find_token_generation(&self.db, &session.user_id)
+            .await?
+            .ok_or(AppError::UserNotFound)?;
+        let access_token = make_jwt_token(
+            &session.user_id,
+            role,
+            session.device_id.clone(),
+            generation,
+        )
+        .map_err(|e| AppError::InternalErrorWithMessage(e.to_string()))?;
+
+        let refresh_token =
+            format_refresh_token(session, &random_alphanumeric(REFRESH_TOKEN_SECRET_LEN));
+        let hash = hash_util::hash_password(&refresh_token)
+            .map_err(|e| AppError::InternalErrorWithMessage(e.to_string()))?;
+        let expires_at = chrono::Utc::now() + chrono::Duration::days(REFRESH_TOKEN_TTL_DAYS);
+
+        let stored = self
+            .repo
+            .store_refresh_token(&self.db, session, expected_hash, hash, expires_at)
+            .await?;
+        if !stored {
+            return Err(match (&session.device_id, expected_hash) {
+                (_, Some(_)) => AppError::InvalidToken,
+                (Some(device_id), None) => {
+                    AppError::NotFound(format!("Active device not found: {device_id}"))
+                }
+                (None, None) => AppError::UserNotFound,
+            });
+        }
+
+        Ok(AuthBody::new(access_token, refresh_token))
+    }
+
+    /// Checks a presented refresh token against its session's stored hash and
+    /// expiry. Returns the session and the stored hash.
+    async fn verify_refresh_token(
+        &self,
+        refresh_token: &str,
+    ) -> Result<(RefreshSession, String), AppError> {
+        let session = parse_refresh_token(refresh_token).ok_or(AppError::InvalidToken)?;
+        let (hash, expires_at) = self
+            .repo
+            .find_refresh_token(&self.db, &session)
+            .await?
+            .ok_or(AppError::InvalidToken)?;
+
+        if expires_at <= chrono::Utc::now() || !hash_util::verify_password(&hash, refresh_token) {
+            return Err(AppError::InvalidToken);
+        }
+        Ok((session, hash))
+    }
+
+    /// Stores a new password for a user, consumes the reset token `reset_token_id`
+    /// if given, and invalidates every other outstanding reset token.
+    async fn set_password(
+        &self,
+        user_id: &str,
+        new_password: &str,
+        reset_token_id: Option<&str>,
+    ) -> Result<(), AppError> {
+        let password_hash = hash_util::hash_password(new_password)
+            .map_err(|e| AppError::InternalErrorWithMessage(e.to_string()))?;
+
+        let tx = self.db.begin().await?;
+        if let Some(id) = reset_token_id {
+            if !self.reset_repo.mark_used(&tx, id).await? {
+                tx.rollback().await?;
+                return Err(AppError::InvalidToken);
+            }
+        }
+        if !self
+            .repo
+            .update_password(&tx, user_id, password_hash)
+            .await?
+        {
+            tx.rollback().await?;
+            return Err(AppError::UserNotFound);
+        }
+        self.reset_repo.invalidate_all(&tx, user_id).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Takes a token from the per-account attempt limit of a known user.
+    fn acquire_user_attempt(&self, user_id: &str) -> Result<(), AppError> {
+        self.login_limiter
+            .try_acquire(&format!("user:{user_id}"))
+            .map_err(|_retry_after| AppError::TooManyRequests)
+    }
+
+    /// Counts a failed login or TOTP attempt towards the account lockout.
+    async fn record_failed_login(&self, user_id: &str) -> Result<(), AppError> {
+        if self.max_failed_logins > 0 {
+            let max_attempts = i32::try_from(self.max_failed_logins).unwrap_or(i32::MAX);
+            self.repo
+                .record_failed_login(
+                    &self.db,
+                    user_id,
+                    max_attempts,
+                    chrono::Utc::now() + self.lockout,
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Loads the authentication record of a user.
+    async fn user_auth(&self, user_id: &str) -> Result<UserAuth, AppError> {
+        self.repo
+            .find_by_user_id(&self.db, user_id)
+            .await?
+            .ok_or(AppError::UserNotFound)
+    }
+
+    /// Checks a TOTP code against the user's stored secret and consumes it.
+    /// Returns `false` for wrong, stale or already used codes.
+    async fn check_totp_code(&self, user_auth: &UserAuth, code: &str) -> Result<bool, AppError> {
+        let Some(sealed) = &user_auth.totp_secret else {
+            return Ok(false);
+        };
+        let secret = self.totp_cipher.decrypt(sealed)?;
+
+        let Some(step) = totp::verify_code(
+            &secret,
+            code,
+            chrono::Utc::now(),
+            user_auth.totp_last_used_step,
+        ) else {
+            return Ok(false);
+        };
+        Ok(self
+            .repo
+            .use_totp_step(&self.db, &user_auth.user_id, step)
+            .await?)
+    }
+
+    /// Loads an API key the caller is allowed to manage.
+    async fn find_manageable_api_key(&self, claims: &Claims, id: &str) -> Result<ApiKey, AppError> {
+        let api_key = self
+            .api_key_repo
+            .find_by_id(&self.db, id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("API key not found: {id}")))?;
+
+        if api_key.user_id != claims.sub && claims.role < Role::Admin {
+            return Err(AppError::Forbidden);
+        }
+        Ok(api_key)
+    }
+}
+
+fn random_alphanumeric(len: usize) -> String {
+    rand::rng()
+        .sample_iter(Alphanumeric)
+        .take(len)
+        .map(char::from)
+        .collect()
+}
+
+/// Implementation of the `AuthService`
+#[async_trait::async_trait]
+impl AuthServiceTrait for AuthService {
+    /// constructor for the service.
+    fn create_service(
+        config: &Config,
+        db: DatabaseConnection,
+        user_service: Arc<dyn UserServiceTrait>,
+        device_service: Arc<dyn DeviceServiceTrait>,
+    ) -> Arc<dyn AuthServiceTrait> {
+        Arc::new(Self {
+            db,
+            repo: Arc::new(UserAuthRepo {}),
+            api_key_repo: Arc::new(ApiKeyRepo {}),
+            reset_repo: Arc::new(PasswordResetRepo {}),
+            reset_notifier: Arc::new(LogPasswordResetNotifier),
+            user_service,
+            device_service,
+            login_limiter: Arc::new(RateLimiter::new(config.auth_account_rate_limit)),
+            max_failed_logins: config.auth_max_failed_logins,
+            lockout: chrono::Duration::minutes(config.auth_lockout_minutes),
+            totp_cipher: Arc::new(SecretCipher::new(&config.totp_encryption_key)),
+        })
+    }
+
+    /// It hashes the password and stores it in the database.
+    async fn create_user_auth(&self, register_dto: RegisterDto) -> Result<(), AppError> {
+        self.register(register_dto, None).await.map(|_| ())
+    }
+
+    async fn create_admin(&self, register_dto: RegisterDto) -> Result<String, AppError> {
+        self.register(register_dto, Some(Role::Admin)).await
+    }
+
     /// Authenticates a user by checking the provided credentials
     /// against the stored credentials in the database.
-    /// If the credentials are valid, it generates a JWT token for the user.
+    /// If the credentials are valid, it generates a JWT token and a refresh token
+    /// for the account, or for `device_id` when given.
     /// If the credentials are invalid, it returns an error.
-    async fn login_user(&self, auth_payload: AuthPayload) -> Result<AuthBody, AppError> {
+    async fn login_user(
+        &self,
+        auth_payload: AuthPayload,
+        device_id: Option<String>,
+    ) -> Result<LoginResponse, AppError> {
         if auth_payload.client_id.is_empty() || auth_payload.client_secret.is_empty() {
             return Err(AppError::MissingCredentials);
         }
 
+        if self
+            .login_limiter
+            .try_acquire(&auth_payload.client_id.to_lowercase())
+            .is_err()
+        {
+            return Err(AppError::TooManyRequests);
+        }
+
         let user_auth = self
             .repo
             .find_by_user_name(&self.db, auth_payload.client_id.clone())
@@ -99,13 +386,366 @@ impl AuthServiceTrait for AuthService {
 
         let user_auth = user_auth.ok_or(AppError::UserNotFound)?;
 
+        let now = chrono::Utc::now();
+        if user_auth.locked_until.is_some_and(|until| until > now) {
+            return Err(AppError::AccountLocked);
+        }
+
         if !hash_util::verify_password(&user_auth.password_hash, &auth_payload.client_secret) {
+            self.record_failed_login(&user_auth.user_id).await?;
             return Err(AppError::WrongCredentials);
         }
 
-        let token = make_jwt_token(&user_auth.user_id)
-            .map_err(|e| AppError::InternalErrorWithMessage(e.to_string()))?;
+        // The failure count is only reset once the TOTP step succeeds too.
+        if user_auth.totp_enabled {
+            let challenge_token = make_challenge_token(&user_auth.user_id, device_id)?;
+            return Ok(LoginResponse::TotpRequired(TotpChallengeDto {
+                totp_required: true,
+                challenge_token,
+            }));
+        }
+
+        if user_auth.failed_login_attempts > 0 || user_auth.locked_until.is_some() {
+            self.repo
+                .reset_failed_logins(&self.db, &user_auth.user_id)
+                .await?;
+        }
 
-        Ok(AuthBody::new(token))
+        let session = RefreshSession {
+            user_id: user_auth.user_id,
+            device_id,
+        };
+        self.issue_tokens(&session, None)
+            .await
+            .map(LoginResponse::Tokens)
+    }
+
+    async fn login_totp(&self, dto: TotpLoginDto) -> Result<AuthBody, AppError> {
+        let challenge = decode_challenge_token(&dto.challenge_token)?;
+        self.acquire_user_attempt(&challenge.sub)?;
+
+        let user_auth = self
+            .repo
+            .find_by_user_id(&self.db, &challenge.sub)
+            .await?
+            .filter(|user_auth| user_auth.totp_enabled)
+            .ok_or(AppError::InvalidToken)?;
+
+        if user_auth
+            .locked_until
+            .is_some_and(|until| until > chrono::Utc::now())
+        {
+            return Err(AppError::AccountLocked);
+        }
+
+        if !self.check_totp_code(&user_auth, &dto.code).await? {
+            self.record_failed_login(&user_auth.user_id).await?;
+            return Err(AppError::WrongCredentials);
+        }
+
+        if user_auth.failed_login_attempts > 0 || user_auth.locked_until.is_some() {
+            self.repo
+                .reset_failed_logins(&self.db, &user_auth.user_id)
+                .await?;
+        }
+
+        let session = RefreshSession {
+            user_id: user_auth.user_id,
+            device_id: challenge.device_id,
+        };
+        self.issue_tokens(&session, None).await
+    }
+
+    async fn enroll_totp(&self, claims: &Claims) -> Result<TotpEnrollmentDto, AppError> {
+        let user_auth = self.user_auth(&claims.sub).await?;
+        if user_auth.totp_enabled {
+            return Err(AppError::Conflict("TOTP is already enabled".to_owned()));
+        }
+
+        let secret = totp::generate_secret();
+        let sealed = self.totp_cipher.encrypt(&secret)?;
+        self.repo
+            .set_totp_secret(&self.db, &claims.sub, Some(sealed))
+            .await?;
+
+        let user = self.user_service.get_user_by_id(claims.sub.clone()).await?;
+        Ok(TotpEnrollmentDto {
+            secret: totp::base32_encode(&secret),
+            provisioning_uri: totp::provisioning_uri(&user.username, &secret),
+        })
+    }
+
+    async fn verify_totp(&self, claims: &Claims, code: &str) -> Result<(), AppError> {
+        self.acquire_user_attempt(&claims.sub)?;
+        let user_auth = self.user_auth(&claims.sub).await?;
+        if user_auth.totp_enabled {
+            return Err(AppError::Conflict("TOTP is already enabled".to_owned()));
+        }
+        if user_auth.totp_secret.is_none() {
+            return Err(AppError::Conflict(
+                "TOTP enrollment has not been started".to_owned(),
+            ));
+        }
+
+        if !self.check_totp_code(&user_auth, code).await? {
+            return Err(AppError::ValidationError("Invalid TOTP code".to_owned()));
+        }
+        self.repo.enable_totp(&self.db, &claims.sub).await?;
+        Ok(())
+    }
+
+    async fn disable_totp(&self, claims: &Claims, code: &str) -> Result<(), AppError> {
+        self.acquire_user_attempt(&claims.sub)?;
+        let user_auth = self.user_auth(&claims.sub).await?;
+        if !user_auth.totp_enabled {
+            return Err(AppError::Conflict("TOTP is not enabled".to_owned()));
+        }
+
+        if !self.check_totp_code(&user_auth, code).await? {
+            return Err(AppError::ValidationError("Invalid TOTP code".to_owned()));
+        }
+        self.repo
+            .set_totp_secret(&self.db, &claims.sub, None)
+            .await?;
+        Ok(())
+    }
+
+    /// Exchanges a refresh token for a new access token and rotates the refresh
+    /// token; the presented one stops working.
+    async fn refresh_token(&self, refresh_token: &str) -> Result<AuthBody, AppError> {
+        let (session, hash) = self.verify_refresh_token(refresh_token).await?;
+        self.issue_tokens(&session, Some(&hash)).await
+    }
+
+    async fn unlock_account(&self, user_id: &str) -> Result<(), AppError> {
+        if self.repo.reset_failed_logins(&self.db, user_id).await? {
+            Ok(())
+        } else {
+            Err(AppError::UserNotFound)
+        }
+    }
+
+    async fn logout(&self, refresh_token: &str) -> Result<(), AppError> {
+        let (session, _) = self.verify_refresh_token(refresh_token).await?;
+        self.repo.clear_refresh_token(&self.db, &session).await?;
+        self.repo
+            .bump_token_generation(&self.db, &session.user_id)
+            .await?;
+        Ok(())
+    }
+
+    async fn logout_all(&self, refresh_token: &str) -> Result<(), AppError> {
+        let (session, _) = self.verify_refresh_token(refresh_token).await?;
+        self.repo
+            .clear_all_refresh_tokens(&self.db, &session.user_id)
+            .await?;
+        self.repo
+            .bump_token_generation(&self.db, &session.user_id)
+            .await?;
+        Ok(())
+    }
+
+    /// Password attempts share the per-account login limit. Every other
+    /// device session is revoked and every access token issued so far stops
+    /// working; the caller's own device stays signed in with the new tokens.
+    async fn change_password(
+        &self,
+        claims: &Claims,
+        dto: ChangePasswordDto,
+    ) -> Result<AuthBody, AppError> {
+        self.acquire_user_attempt(&claims.sub)?;
+
+        let user_auth = self.user_auth(&claims.sub).await?;
+        if !hash_util::verify_password(&user_auth.password_hash, &dto.old_password) {
+            return Err(AppError::WrongCredentials);
+        }
+
+        self.set_password(&claims.sub, &dto.new_password, None)
+            .await?;
+        self.repo
+            .clear_all_refresh_tokens(&self.db, &claims.sub)
+            .await?;
+        self.repo
+            .bump_token_generation(&self.db, &claims.sub)
+            .await?;
+        self.device_service
+            .revoke_other_sessions(&claims.sub, claims.device_id.as_deref())
+            .await?;
+
+        let session = RefreshSession {
+            user_id: claims.sub.clone(),
+            device_id: claims.device_id.clone(),
+        };
+        self.issue_tokens(&session, None).await
+    }
+
+    /// Issues one token per matching account and hands it to the notifier.
+    /// Unknown emails are only logged, so the response does not reveal
+    /// which addresses are registered.
+    async fn request_password_reset(&self, email: &str) -> Result<(), AppError> {
+        let user_ids = self.repo.find_user_ids_by_email(&self.db, email).await?;
+        if user_ids.is_empty() {
+            tracing::info!("Password reset requested for unknown email {email}");
+            return Ok(());
+        }
+
+        let expires_at = chrono::Utc::now() + chrono::Duration::minutes(PASSWORD_RESET_TTL_MINUTES);
+        for user_id in user_ids {
+            let id = uuid::Uuid::new_v4().to_string();
+            let token = format_reset_token(&id, &random_alphanumeric(RESET_TOKEN_SECRET_LEN));
+            let token_hash = hash_util::hash_password(&token)
+                .map_err(|e| AppError::InternalErrorWithMessage(e.to_string()))?;
+
+            self.reset_repo
+                .create(
+                    &self.db,
+                    PasswordResetToken {
+                        id,
+                        user_id,
+                        token_hash,
+                        expires_at,
+                        used_at: None,
+                    },
+                )
+                .await?;
+
+            if let Err(err) = self.reset_notifier.send_reset_token(email, &token).await {
+                tracing::error!("Failed to send password reset token to {email}: {err}");
+            }
+        }
+        Ok(())
+    }
+
+    /// Signs the account out everywhere: every device session is revoked and
+    /// every access token issued so far stops working.
+    async fn confirm_password_reset(&self, dto: PasswordResetConfirmDto) -> Result<(), AppError> {
+        let id = reset_token_id(&dto.token).ok_or(AppError::InvalidToken)?;
+        let stored = self
+            .reset_repo
+            .find_by_id(&self.db, id)
+            .await?
+            .ok_or(AppError::InvalidToken)?;
+
+        if stored.used_at.is_some()
+            || stored.expires_at <= chrono::Utc::now()
+            || !hash_util::verify_password(&stored.token_hash, &dto.token)
+        {
+            return Err(AppError::InvalidToken);
+        }
+
+        self.set_password(&stored.user_id, &dto.new_password, Some(&stored.id))
+            .await?;
+        self.repo
+            .clear_all_refresh_tokens(&self.db, &stored.user_id)
+            .await?;
+        self.repo
+            .bump_token_generation(&self.db, &stored.user_id)
+            .await?;
+        self.device_service
+            .revoke_other_sessions(&stored.user_id, None)
+            .await?;
+        Ok(())
+    }
+
+    /// Generates a random key, stores only its hash and returns the key once.
+    /// The key's role defaults to, and may not exceed, the lower of the
+    /// owner's role and the caller's, so a scoped key cannot mint a broader one.
+    async fn create_api_key(
+        &self,
+        claims: &Claims,
+        dto: CreateApiKeyDto,
+    ) -> Result<ApiKeyCreatedDto, AppError> {
+        let user_id = dto.user_id.unwrap_or_else(|| claims.sub.clone());
+        if user_id != claims.sub && claims.role < Role::Admin {
+            return Err(AppError::Forbidden);
+        }
+
+        let max_role = self.user_role(&user_id).await?.min(claims.role);
+        let role = dto.role.unwrap_or(max_role);
+        if role > max_role {
+            return Err(AppError::Forbidden);
+        }
+
+        let prefix = random_alphanumeric(API_KEY_PREFIX_LEN);
+        let key = format_api_key(&prefix, &random_alphanumeric(API_KEY_SECRET_LEN));
+        let key_hash = hash_util::token_digest(&key);
+
+        let api_key = self
+            .api_key_repo
+            .create(
+                &self.db,
+                ApiKey {
+                    id: uuid::Uuid::new_v4().to_string(),
+                    user_id,
+                    name: dto.name,
+                    prefix,
+                    key_hash,
+                    role: role.to_string(),
+                    created_at: chrono::Utc::now(),
+                    last_used_at: None,
+                    revoked_at: None,
+                },
+            )
+            .await?;
+
+        Ok(ApiKeyCreatedDto {
+            api_key: key,
+            key: api_key.into(),
+        })
+    }
+
+    async fn list_api_keys(&self, claims: &Claims) -> Result<Vec<ApiKeyDto>, AppError> {
+        let user_id = (claims.role < Role::Admin).then_some(claims.sub.as_str());
+        let keys = self.api_key_repo.find_all(&self.db, user_id).await?;
+        Ok(keys.into_iter().map(Into::into).collect())
+    }
+
+    async fn revoke_api_key(&self, claims: &Claims, id: &str) -> Result<ApiKeyDto, AppError> {
+        let mut api_key = self.find_manageable_api_key(claims, id).await?;
+        self.api_key_repo.revoke(&self.db, id).await?;
+        api_key.revoked_at.get_or_insert_with(chrono::Utc::now);
+        Ok(api_key.into())
+    }
+
+    /// Verifies the key against its stored digest. The resulting role is the
+    /// lower of the key's role and the owner's current role, so demoting a
+    /// user also limits their existing keys.
+    async fn authenticate_api_key(&self, api_key: &str) -> Result<Claims, AppError> {
+        let prefix = api_key_prefix(api_key).ok_or(AppError::InvalidToken)?;
+        let stored = self
+            .api_key_repo
+            .find_active_by_prefix(&self.db, prefix)
+            .await?
+            .ok_or(AppError::InvalidToken)?;
+
+        if !hash_util::verify_token_digest(&stored.key_hash, api_key) {
+            return Err(AppError::InvalidToken);
+        }
+
+        let key_role = stored.role.parse::<Role>()?;
+        let owner_role = self.user_role(&stored.user_id).await?;
+
+        if let Err(err) = self.api_key_repo.touch(&self.db, &stored.id).await {
+            tracing::warn!("Failed to record API key use: {err}");
+        }
+
+        Ok(Claims {
+            sub: stored.user_id,
+            role: key_role.min(owner_role),
+            ..Default::default()
+        })
+    }
+
+    async fn check_token_generation(&self, claims: &Claims) -> Result<(), AppError> {
+        let current = self
+            .repo
+            .find_token_generation(&self.db, &claims.sub)
+            .await?
+            .ok_or(AppError::InvalidToken)?;
+        if claims.generation == current {
+            Ok(())
+        } else {
+            Err(AppError::InvalidToken)
+        }
     }
 }
@@ -0,0 +1,32 @@
+//! Backup domain: portable whole-database archives for moving a catalog
+//! between Postgres instances.
+//!
+//! `POST /admin/backup` streams every table as JSONL from one snapshot,
+//! followed by a manifest of the private media files. `POST /admin/restore`
+//! replays such an archive into an empty catalog in one transaction. Media
+//! files themselves are copied separately; the restore report lists any the
+//! manifest names that are missing.
+
+mod api {
+    mod handlers;
+    pub mod routes;
+}
+
+mod domain {
+    pub mod repository;
+    pub mod service;
+}
+
+pub mod dto {
+    pub mod backup_dto;
+}
+
+mod infra {
+    mod impl_repository;
+    pub mod impl_service;
+}
+
+// Re-export commonly used items for convenience
+pub use api::routes::{backup_routes, BackupApiDoc};
+pub use domain::service::{BackupServiceTrait, BackupStream};
+pub use infra::impl_service::BackupService;
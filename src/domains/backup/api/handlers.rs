@@ -0,0 +1,96 @@
+use crate::common::dto::{ApiResponse, RestApiResponse};
+use crate::common::{app_state::AppState, error::AppError};
+use crate::domains::backup::dto::backup_dto::RestoreReport;
+use axum::{
+    body::Body,
+    extract::{Multipart, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+};
+
+/// Streams a backup archive of every table and the media manifest.
+///
+/// The archive is JSONL: a header line, one line per table row, then one
+/// line per file under the private assets directory. Media files are not
+/// included and must be copied separately.
+#[utoipa::path(
+    post,
+    path = "/admin/backup",
+    responses(
+        (status = 200, description = "Backup archive", content_type = "application/x-ndjson"),
+        (status = 403, description = "Caller is not an admin")
+    ),
+    tag = "Backup"
+)]
+pub async fn create_backup(State(state): State<AppState>) -> Result<Response, AppError> {
+    let stream = state.backup_service.backup().await?;
+    let file_name = format!(
+        "lunirelust-backup-{}.jsonl",
+        chrono::Utc::now().format("%Y%m%dT%H%M%SZ")
+    );
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "application/x-ndjson")
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{file_name}\""),
+        )
+        .body(Body::from_stream(stream))
+        .map_err(|err| {
+            tracing::error!("Error building response: {}", err);
+            AppError::InternalError
+        })
+}
+
+/// Restores a backup archive into a database without records.
+///
+/// Every archived table is emptied and refilled in one transaction, and a
+/// search reindex is started afterwards.
+#[utoipa::path(
+    post,
+    path = "/admin/restore",
+    request_body(
+        content = String,
+        description = "Multipart form data with a 'file' field holding the archive",
+        content_type = "multipart/form-data"
+    ),
+    responses(
+        (status = 200, description = "Rows restored per table", body = ApiResponse<RestoreReport>),
+        (status = 400, description = "Unreadable archive or schema version mismatch"),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 409, description = "The database already holds records")
+    ),
+    tag = "Backup"
+)]
+pub async fn restore_backup(
+    State(state): State<AppState>,
+    mut multipart: Multipart,
+) -> Result<impl IntoResponse, AppError> {
+    let mut archive: Option<String> = None;
+    while let Some(field) = multipart.next_field().await.map_err(|e| {
+        AppError::ValidationError(format!("Failed to parse multipart form data: {e}"))
+    })? {
+        if field.name() != Some("file") {
+            continue;
+        }
+        let data = field
+            .bytes()
+            .await
+            .map_err(|e| AppError::ValidationError(format!("Failed to read file data: {e}")))?;
+        archive = Some(
+            String::from_utf8(data.to_vec())
+                .map_err(|e| AppError::ValidationError(format!("Invalid UTF-8 in file: {e}")))?,
+        );
+    }
+    let archive = archive
+        .ok_or_else(|| AppError::ValidationError("Missing 'file' field in form data".to_owned()))?;
+
+    let report = state.backup_service.restore(&archive).await?;
+
+    // The search index still describes the previous contents
+    if let Err(err) = state.search_service.trigger_reindex() {
+        tracing::warn!("Search reindex after restore not started: {err}");
+    }
+    Ok(RestApiResponse::success(report))
+}
@@ -0,0 +1,35 @@
+use super::handlers::{__path_create_backup, __path_restore_backup, create_backup, restore_backup};
+use crate::{
+    common::{
+        app_state::AppState,
+        jwt::{with_role, Role},
+    },
+    domains::backup::dto::backup_dto::{RestoreReport, RestoredTable},
+};
+use axum::{routing::post, Router};
+
+use utoipa::OpenApi;
+
+use crate::common::openapi::{ErrorResponsesAddon, SecurityAddon};
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(create_backup, restore_backup),
+    components(schemas(RestoreReport, RestoredTable)),
+    tags(
+        (name = "Backup", description = "Whole-database backup and restore")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    modifiers(&SecurityAddon, &ErrorResponsesAddon)
+)]
+/// This struct is used to generate `OpenAPI` documentation for the backup routes.
+pub struct BackupApiDoc;
+
+/// This function creates a router for the admin backup routes.
+pub fn backup_routes() -> Router<AppState> {
+    Router::new()
+        .route("/backup", with_role(Role::Admin, post(create_backup)))
+        .route("/restore", with_role(Role::Admin, post(restore_backup)))
+}
@@ -0,0 +1,46 @@
+// This module defines the `BackupRepository` trait, which abstracts the
+// schema-agnostic table reads and writes behind backup and restore.
+
+use async_trait::async_trait;
+use sea_orm::{DatabaseTransaction, DbErr};
+
+#[async_trait]
+/// Trait representing repository-level operations for whole-database archives.
+///
+/// Every method runs inside the caller's transaction so a backup reads one
+/// snapshot and a restore applies atomically.
+pub trait BackupRepository: Send + Sync {
+    /// Name of the latest applied migration, if any.
+    async fn schema_version(&self, txn: &DatabaseTransaction) -> Result<Option<String>, DbErr>;
+
+    /// Application tables in the current schema, each listed after the
+    /// tables its foreign keys reference.
+    async fn tables(&self, txn: &DatabaseTransaction) -> Result<Vec<String>, DbErr>;
+
+    /// Opens the backup cursor over the rows of `table`.
+    async fn open_cursor(&self, txn: &DatabaseTransaction, table: &str) -> Result<(), DbErr>;
+
+    /// Fetches up to `limit` rows from the open cursor, each as JSON text.
+    async fn fetch_rows(&self, txn: &DatabaseTransaction, limit: u64)
+        -> Result<Vec<String>, DbErr>;
+
+    /// Closes the backup cursor.
+    async fn close_cursor(&self, txn: &DatabaseTransaction) -> Result<(), DbErr>;
+
+    /// Whether the catalog holds any record, trashed or not.
+    async fn has_records(&self, txn: &DatabaseTransaction) -> Result<bool, DbErr>;
+
+    /// Empties `tables`, including seed rows.
+    async fn truncate(&self, txn: &DatabaseTransaction, tables: &[String]) -> Result<(), DbErr>;
+
+    /// Inserts rows given as `to_jsonb` objects into `table`.
+    async fn insert_rows(
+        &self,
+        txn: &DatabaseTransaction,
+        table: &str,
+        rows: Vec<serde_json::Value>,
+    ) -> Result<u64, DbErr>;
+
+    /// Moves every column-default sequence past the highest value in use.
+    async fn reset_sequences(&self, txn: &DatabaseTransaction) -> Result<(), DbErr>;
+}
@@ -0,0 +1,33 @@
+//! This module defines the `BackupServiceTrait` which produces and replays
+//! whole-database archives.
+
+use std::sync::Arc;
+
+use futures::stream::BoxStream;
+use sea_orm::DatabaseConnection;
+
+use crate::{
+    common::{config::Config, error::AppError},
+    domains::backup::dto::backup_dto::RestoreReport,
+};
+
+/// Body chunks of a backup archive, produced lazily from one snapshot.
+pub type BackupStream = BoxStream<'static, Result<String, AppError>>;
+
+#[async_trait::async_trait]
+/// Trait defining the contract for backup and restore operations.
+pub trait BackupServiceTrait: Send + Sync {
+    /// constructor for the service.
+    fn create_service(db: DatabaseConnection, config: Config) -> Arc<dyn BackupServiceTrait>
+    where
+        Self: Sized;
+
+    /// Opens a read-only snapshot and streams every table, then the media
+    /// manifest, as archive lines.
+    async fn backup(&self) -> Result<BackupStream, AppError>;
+
+    /// Replays `archive` into this database in one transaction. Fails with
+    /// `Conflict` unless the catalog is empty, and with `ValidationError`
+    /// when the archive was taken at a different schema version.
+    async fn restore(&self, archive: &str) -> Result<RestoreReport, AppError>;
+}
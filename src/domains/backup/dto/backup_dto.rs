@@ -0,0 +1,50 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Value of `format` in the archive header.
+pub const BACKUP_FORMAT: &str = "lunirelust-backup";
+
+/// Archive layout version written by this build.
+pub const BACKUP_VERSION: u32 = 1;
+
+/// One line of a backup archive. The header comes first, then every row of
+/// every table (referenced tables before the tables referencing them), then
+/// the media manifest.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum BackupLine {
+    /// Identifies the archive and the schema it was taken from.
+    Header {
+        format: String,
+        version: u32,
+        /// Latest migration applied to the source database.
+        schema_version: String,
+        created_at: DateTime<Utc>,
+    },
+    /// One table row as produced by Postgres `to_jsonb`.
+    Row {
+        table: String,
+        data: serde_json::Value,
+    },
+    /// One file under the private assets directory.
+    Media { path: String, size: u64 },
+}
+
+/// Rows restored into one table.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RestoredTable {
+    pub table: String,
+    pub rows: u64,
+}
+
+/// Response body of `POST /admin/restore`.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RestoreReport {
+    pub schema_version: String,
+    pub tables: Vec<RestoredTable>,
+    /// Files listed in the media manifest.
+    pub media_files: usize,
+    /// Manifest entries not present under the private assets directory.
+    pub missing_media: Vec<String>,
+}
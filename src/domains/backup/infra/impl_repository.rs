@@ -0,0 +1,278 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use async_trait::async_trait;
+use sea_orm::{
+    ConnectionTrait as _, DatabaseBackend, DatabaseTransaction, DbErr, FromQueryResult, Statement,
+};
+
+use crate::domains::backup::domain::repository::BackupRepository;
+
+/// Bookkeeping table of the migrator; the target database has its own.
+const MIGRATIONS_TABLE: &str = "seaql_migrations";
+
+/// Name of the server-side cursor used while streaming a table.
+const BACKUP_CURSOR: &str = "backup_rows";
+
+pub struct BackupRepo;
+
+#[derive(FromQueryResult)]
+struct NameRow {
+    name: String,
+}
+
+#[derive(FromQueryResult)]
+struct ForeignKeyRow {
+    child: String,
+    parent: String,
+}
+
+#[derive(FromQueryResult)]
+struct DataRow {
+    data: String,
+}
+
+#[derive(FromQueryResult)]
+struct SequenceColumnRow {
+    seq: String,
+    tbl: String,
+    col: String,
+}
+
+/// Double-quote an SQL identifier.
+fn quote_ident(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// Order `tables` so each comes after the tables it references through
+/// `edges` (`(child, parent)` pairs). Ties and cycles fall back to name order.
+fn dependency_order(tables: &[String], edges: &[(String, String)]) -> Vec<String> {
+    let mut parents: BTreeMap<&str, BTreeSet<&str>> = tables
+        .iter()
+        .map(|t| (t.as_str(), BTreeSet::new()))
+        .collect();
+    for (child, parent) in edges {
+        if child != parent && parents.contains_key(parent.as_str()) {
+            if let Some(set) = parents.get_mut(child.as_str()) {
+                set.insert(parent.as_str());
+            }
+        }
+    }
+
+    let mut ordered = Vec::with_capacity(tables.len());
+    while !parents.is_empty() {
+        let ready: Vec<&str> = parents
+            .iter()
+            .filter(|(_, deps)| deps.is_empty())
+            .map(|(table, _)| *table)
+            .collect();
+        // A reference cycle: release the first remaining table by name
+        let ready = if ready.is_empty() {
+            parents.keys().take(1).copied().collect()
+        } else {
+            ready
+        };
+        for table in ready {
+            parents.remove(table);
+            for deps in parents.values_mut() {
+                deps.remove(table);
+            }
+            ordered.push(table.to_owned());
+        }
+    }
+    ordered
+}
+
+async fn execute(txn: &DatabaseTransaction, sql: String) -> Result<u64, DbErr> {
+    Ok(txn
+        .execute(Statement::from_string(DatabaseBackend::Postgres, sql))
+        .await?
+        .rows_affected())
+}
+
+#[async_trait]
+impl BackupRepository for BackupRepo {
+    async fn schema_version(&self, txn: &DatabaseTransaction) -> Result<Option<String>, DbErr> {
+        let row = NameRow::find_by_statement(Statement::from_string(
+            DatabaseBackend::Postgres,
+            format!("SELECT version AS name FROM {MIGRATIONS_TABLE} ORDER BY version DESC LIMIT 1"),
+        ))
+        .one(txn)
+        .await?;
+        Ok(row.map(|r| r.name))
+    }
+
+    async fn tables(&self, txn: &DatabaseTransaction) -> Result<Vec<String>, DbErr> {
+        let tables: Vec<String> = NameRow::find_by_statement(Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            "SELECT tablename::text AS name FROM pg_tables \
+             WHERE schemaname = current_schema() AND tablename <> $1 ORDER BY tablename",
+            [MIGRATIONS_TABLE.into()],
+        ))
+        .all(txn)
+        .await?
+        .into_iter()
+        .map(|r| r.name)
+        .collect();
+
+        let edges: Vec<(String, String)> =
+            ForeignKeyRow::find_by_statement(Statement::from_string(
+                DatabaseBackend::Postgres,
+                "SELECT child.relname::text AS child, parent.relname::text AS parent \
+                 FROM pg_constraint c \
+                 JOIN pg_class child ON child.oid = c.conrelid \
+                 JOIN pg_class parent ON parent.oid = c.confrelid \
+                 JOIN pg_namespace n ON n.oid = child.relnamespace \
+                 WHERE c.contype = 'f' AND n.nspname = current_schema()",
+            ))
+            .all(txn)
+            .await?
+            .into_iter()
+            .map(|r| (r.child, r.parent))
+            .collect();
+
+        Ok(dependency_order(&tables, &edges))
+    }
+
+    async fn open_cursor(&self, txn: &DatabaseTransaction, table: &str) -> Result<(), DbErr> {
+        execute(
+            txn,
+            format!(
+                "DECLARE {BACKUP_CURSOR} NO SCROLL CURSOR FOR SELECT to_jsonb(t)::text AS data FROM {} t",
+                quote_ident(table)
+            ),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn fetch_rows(
+        &self,
+        txn: &DatabaseTransaction,
+        limit: u64,
+    ) -> Result<Vec<String>, DbErr> {
+        Ok(DataRow::find_by_statement(Statement::from_string(
+            DatabaseBackend::Postgres,
+            format!("FETCH {limit} FROM {BACKUP_CURSOR}"),
+        ))
+        .all(txn)
+        .await?
+        .into_iter()
+        .map(|r| r.data)
+        .collect())
+    }
+
+    async fn close_cursor(&self, txn: &DatabaseTransaction) -> Result<(), DbErr> {
+        execute(txn, format!("CLOSE {BACKUP_CURSOR}")).await?;
+        Ok(())
+    }
+
+    async fn has_records(&self, txn: &DatabaseTransaction) -> Result<bool, DbErr> {
+        let row = NameRow::find_by_statement(Statement::from_string(
+            DatabaseBackend::Postgres,
+            "SELECT EXISTS (SELECT 1 FROM record)::text AS name",
+        ))
+        .one(txn)
+        .await?;
+        Ok(row.is_some_and(|r| r.name == "true"))
+    }
+
+    async fn truncate(&self, txn: &DatabaseTransaction, tables: &[String]) -> Result<(), DbErr> {
+        if tables.is_empty() {
+            return Ok(());
+        }
+        let list = tables
+            .iter()
+            .map(|t| quote_ident(t))
+            .collect::<Vec<_>>()
+            .join(", ");
+        execute(txn, format!("TRUNCATE {list} RESTART IDENTITY CASCADE")).await?;
+        Ok(())
+    }
+
+    async fn insert_rows(
+        &self,
+        txn: &DatabaseTransaction,
+        table: &str,
+        rows: Vec<serde_json::Value>,
+    ) -> Result<u64, DbErr> {
+        let table = quote_ident(table);
+        let result = txn
+            .execute(Statement::from_sql_and_values(
+                DatabaseBackend::Postgres,
+                format!("INSERT INTO {table} SELECT * FROM jsonb_populate_recordset(NULL::{table}, $1::jsonb)"),
+                [serde_json::Value::Array(rows).into()],
+            ))
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    async fn reset_sequences(&self, txn: &DatabaseTransaction) -> Result<(), DbErr> {
+        let columns = SequenceColumnRow::find_by_statement(Statement::from_string(
+            DatabaseBackend::Postgres,
+            "SELECT s.relname::text AS seq, c.relname::text AS tbl, a.attname::text AS col \
+             FROM pg_attrdef d \
+             JOIN pg_class c ON c.oid = d.adrelid \
+             JOIN pg_attribute a ON a.attrelid = d.adrelid AND a.attnum = d.adnum \
+             JOIN pg_namespace n ON n.oid = c.relnamespace \
+             JOIN pg_class s ON s.relkind = 'S' AND s.relnamespace = n.oid \
+               AND pg_get_expr(d.adbin, d.adrelid) = 'nextval(''' || s.relname || '''::regclass)' \
+             WHERE n.nspname = current_schema()",
+        ))
+        .all(txn)
+        .await?;
+
+        // A sequence may number several columns (the record sync sequence does)
+        let mut by_sequence: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for column in columns {
+            by_sequence.entry(column.seq).or_default().push(format!(
+                "(SELECT MAX({}) FROM {})",
+                quote_ident(&column.col),
+                quote_ident(&column.tbl)
+            ));
+        }
+        for (sequence, maxima) in by_sequence {
+            let sequence = quote_ident(&sequence).replace('\'', "''");
+            execute(
+                txn,
+                format!(
+                    "SELECT setval('{sequence}', COALESCE(GREATEST({}), 0) + 1, false)",
+                    maxima.join(", ")
+                ),
+            )
+            .await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dependency_order_puts_referenced_tables_first() {
+        let tables: Vec<String> = ["record_genre", "record", "genre", "director", "tree"]
+            .iter()
+            .map(|t| (*t).to_owned())
+            .collect();
+        let edge = |child: &str, parent: &str| (child.to_owned(), parent.to_owned());
+        let edges = vec![
+            edge("record_genre", "record"),
+            edge("record_genre", "genre"),
+            edge("record", "director"),
+            edge("tree", "tree"),
+            edge("record", "elsewhere"),
+        ];
+
+        assert_eq!(
+            dependency_order(&tables, &edges),
+            ["director", "genre", "tree", "record", "record_genre"]
+        );
+    }
+
+    #[test]
+    fn quote_ident_escapes_quotes() {
+        assert_eq!(quote_ident("record"), "\"record\"");
+        assert_eq!(quote_ident("a\"b"), "\"a\"\"b\"");
+    }
+}
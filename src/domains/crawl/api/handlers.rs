@@ -13,7 +13,7 @@ use tokio_stream::wrappers::BroadcastStream;
 use tokio_stream::StreamExt as _;
 
 use crate::common::app_state::AppState;
-use crate::common::dto::RestApiResponse;
+use crate::common::dto::{ApiResponse, RestApiResponse};
 use crate::common::error::AppError;
 use crate::common::jwt::Claims;
 use crate::domains::crawl::domain::model::CrawlTask;
@@ -35,7 +35,7 @@ use validator::Validate as _;
     path = "/crawl/batch",
     request_body = StartBatchRequest,
     responses(
-        (status = 202, description = "Batch crawl task created", body = TaskResponse),
+        (status = 202, description = "Batch crawl task created", body = ApiResponse<TaskResponse>),
         (status = 400, description = "Validation error"),
         (status = 401, description = "Unauthorized"),
     ),
@@ -75,7 +75,7 @@ pub async fn start_batch(
     path = "/crawl/auto",
     request_body = StartAutoRequest,
     responses(
-        (status = 202, description = "Auto crawl task created", body = TaskResponse),
+        (status = 202, description = "Auto crawl task created", body = ApiResponse<TaskResponse>),
         (status = 400, description = "Validation error"),
         (status = 401, description = "Unauthorized"),
     ),
@@ -117,7 +117,7 @@ pub async fn start_auto(
     path = "/crawl/update",
     request_body = StartUpdateRequest,
     responses(
-        (status = 202, description = "Update crawl task created", body = TaskResponse),
+        (status = 202, description = "Update crawl task created", body = ApiResponse<TaskResponse>),
         (status = 400, description = "Validation error"),
         (status = 401, description = "Unauthorized"),
     ),
@@ -158,7 +158,7 @@ pub async fn start_update(
     path = "/crawl/idol",
     request_body = StartIdolRequest,
     responses(
-        (status = 202, description = "Idol image crawl task created", body = TaskResponse),
+        (status = 202, description = "Idol image crawl task created", body = ApiResponse<TaskResponse>),
         (status = 400, description = "Validation error"),
         (status = 401, description = "Unauthorized"),
     ),
@@ -189,7 +189,7 @@ pub async fn start_idol(
     path = "/crawl/entity-auto-crawl",
     request_body = StartEntityAutoCrawlRequest,
     responses(
-        (status = 202, description = "Entity auto crawl tasks created", body = EntityAutoCrawlTaskResponse),
+        (status = 202, description = "Entity auto crawl tasks created", body = ApiResponse<EntityAutoCrawlTaskResponse>),
         (status = 422, description = "Validation error (invalid entity_type, scope, or count)"),
         (status = 401, description = "Unauthorized"),
     ),
@@ -231,7 +231,7 @@ pub async fn start_entity_auto_crawl(
         ("page_size" = Option<u64>, Query, description = "Page size (default 20)"),
     ),
     responses(
-        (status = 200, description = "Paginated entity progress", body = EntityProgressListResponse),
+        (status = 200, description = "Paginated entity progress", body = ApiResponse<EntityProgressListResponse>),
         (status = 400, description = "Validation error"),
         (status = 401, description = "Unauthorized"),
     ),
@@ -268,7 +268,7 @@ pub async fn list_entity_progress(
         ("entity_type" = String, Query, description = "Entity kind: idol/director/label/series/studio/genre"),
     ),
     responses(
-        (status = 200, description = "Current-round coverage summary", body = EntityProgressSummary),
+        (status = 200, description = "Current-round coverage summary", body = ApiResponse<EntityProgressSummary>),
         (status = 400, description = "Validation error"),
         (status = 401, description = "Unauthorized"),
     ),
@@ -328,7 +328,7 @@ pub async fn cancel_task(
         ("page_size" = Option<u64>, Query, description = "Page size (default 20)"),
     ),
     responses(
-        (status = 200, description = "List of crawl tasks", body = TaskListResponse),
+        (status = 200, description = "List of crawl tasks", body = ApiResponse<TaskListResponse>),
         (status = 400, description = "Validation error (e.g. page=0)"),
         (status = 401, description = "Unauthorized"),
     ),
@@ -388,7 +388,7 @@ pub async fn list_tasks(
     path = "/crawl/tasks/{id}",
     params(("id" = i64, Path, description = "Task ID")),
     responses(
-        (status = 200, description = "Task detail with results", body = TaskDetailResponse),
+        (status = 200, description = "Task detail with results", body = ApiResponse<TaskDetailResponse>),
         (status = 401, description = "Unauthorized"),
         (status = 404, description = "Task not found"),
     ),
@@ -664,7 +664,7 @@ pub async fn stream_task(
     post,
     path = "/crawl/initialize",
     responses(
-        (status = 200, description = "Crawler initialized", body = CrawlerStatusResponse),
+        (status = 200, description = "Crawler initialized", body = ApiResponse<CrawlerStatusResponse>),
         (status = 401, description = "Unauthorized"),
         (status = 409, description = "Crawler is busy"),
     ),
@@ -688,7 +688,7 @@ pub async fn initialize_crawler(
     get,
     path = "/crawl/health",
     responses(
-        (status = 200, description = "Crawler status", body = CrawlerStatusResponse),
+        (status = 200, description = "Crawler status", body = ApiResponse<CrawlerStatusResponse>),
         (status = 401, description = "Unauthorized"),
     ),
     security(("bearer_auth" = [])),
@@ -2,7 +2,7 @@ use axum::routing::{get, post};
 use axum::Router;
 
 use crate::common::app_state::AppState;
-use crate::common::openapi::SecurityAddon;
+use crate::common::openapi::{ErrorResponsesAddon, SecurityAddon};
 use crate::domains::crawl::dto::task_dto::{
     CodeResultResponse, CrawlerStatusResponse, EntityAutoCrawlDetail, EntityAutoCrawlTaskItem,
     EntityAutoCrawlTaskResponse, EntityProgressItem, EntityProgressListResponse,
@@ -54,6 +54,6 @@ pub fn crawl_routes() -> Router<AppState> {
     )),
     tags((name = "Crawl", description = "Crawl task management and progress endpoints")),
     security(("bearer_auth" = [])),
-    modifiers(&SecurityAddon)
+    modifiers(&SecurityAddon, &ErrorResponsesAddon)
 )]
 pub struct CrawlApiDoc;
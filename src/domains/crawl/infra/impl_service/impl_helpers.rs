@@ -279,19 +279,25 @@ impl CrawlService {
             links,
             permission: 0,
             local_img_count: 0,
-            creator: "crawl".to_owned(),
-            modified_by: "crawl".to_owned(),
         };
 
         let txn = self.db.begin().await.map_err(AppError::DatabaseError)?;
 
-        let (id, nested) = match self.record_repo.create(&txn, create_dto).await {
+        let (id, nested) = match self.record_repo.create(&txn, create_dto, "crawl").await {
             Ok(result) => result,
             Err(e) => {
                 let _ = txn.rollback().await;
                 return Err(AppError::DatabaseError(e));
             }
         };
+        for resolved in &nested.resolved_idols {
+            tracing::info!(
+                "Crawled idol {} of record {id} resolved to {} ({})",
+                resolved.name,
+                resolved.idol_name,
+                resolved.idol_id
+            );
+        }
 
         if let Err(e) = self.insert_nested_outbox_events(&txn, &nested).await {
             let _ = txn.rollback().await;
@@ -338,19 +344,19 @@ impl CrawlService {
             return Ok(false);
         }
         let txn = self.db.begin().await.map_err(AppError::DatabaseError)?;
-        let changed = match self
+        let result = match self
             .record_repo
             .update_record_links(&txn, record_id.to_owned(), new_links.to_vec())
             .await
         {
-            Ok(c) => c,
+            Ok(r) => r,
             Err(e) => {
                 txn.rollback().await.ok();
                 return Err(AppError::DatabaseError(e));
             }
         };
         txn.commit().await.map_err(AppError::DatabaseError)?;
-        Ok(changed > 0)
+        Ok(result.changed())
     }
 
     pub(super) async fn save_images(&self, record_id: &str, images: &[ImageData]) -> i32 {
@@ -10,7 +10,11 @@ use regex::Regex;
 use sea_orm::{DatabaseConnection, DbErr};
 use tokio_util::sync::CancellationToken;
 
-use crate::common::config::Config;
+use crate::common::access_log::AccessLogFormat;
+use crate::common::config::{
+    Config, CorsConfig, MediaStorageConfig, MetadataConfig, SimilarityWeights,
+};
+use crate::common::rate_limit::RateLimit;
 use crate::domains::crawl::domain::model::{
     CodeResultStatus, CrawlCodeResult, CrawlPageResult, CrawlTask, CrawlTaskDetail,
     EntityAutoCrawlScope, EntityAutoCrawlTaskInput, EntityAutoCrawlType, PageResultStatus,
@@ -275,13 +279,23 @@ impl CrawlTaskRepository for RecordingCrawlTaskRepo {
     }
 }
 
+/// Rate limit that never throttles.
+const UNLIMITED: RateLimit = RateLimit {
+    burst: 0,
+    per_minute: 0,
+};
+
 fn test_config() -> Config {
     Config {
         database_url: "postgres://example.invalid/test".to_owned(),
         database_max_connections: 1,
         database_min_connections: 0,
+        database_acquire_timeout_secs: 30,
+        database_statement_timeout_ms: 0,
+        database_slow_query_ms: 0,
         service_host: "127.0.0.1".to_owned(),
         service_port: "3000".to_owned(),
+        grpc_port: "50051".to_owned(),
         assets_public_path: "/tmp".to_owned(),
         assets_public_url: "http://localhost/public".to_owned(),
         assets_private_path: "/tmp".to_owned(),
@@ -289,12 +303,43 @@ fn test_config() -> Config {
         asset_allowed_extensions_pattern: Regex::new(r".*").expect("regex"),
         asset_allowed_extensions: vec!["jpg".to_owned()],
         asset_max_size: 1024,
-        cors_origins: vec![],
+        video_max_size: 1024,
+        thumbnail_sizes: vec![160, 320, 640],
+        thumbnail_list_size: 320,
+        media_cache_max_age_secs: 0,
+        media_storage: MediaStorageConfig::default(),
+        media_gc_interval_secs: 0,
+        media_gc_delete: false,
+        link_check_interval_secs: 0,
+        link_check_concurrency: 8,
+        link_check_timeout_secs: 10,
+        link_check_batch_size: 500,
+        json_body_limit: 1024,
+        upload_body_limit: 1024,
+        upload_max_concurrent: 1,
+        upload_strip_metadata: false,
+        response_compression: false,
+        cors: CorsConfig::default(),
         meili_url: "http://localhost:7700".to_owned(),
         meili_master_key: "test".to_owned(),
         vllm_embedding_url: "http://localhost:8000".to_owned(),
         vllm_embedding_model: "test".to_owned(),
         vllm_embedding_timeout_secs: 5,
+        auth_ip_rate_limit: UNLIMITED,
+        auth_account_rate_limit: UNLIMITED,
+        auth_max_failed_logins: 5,
+        auth_lockout_minutes: 15,
+        totp_encryption_key: "test".to_owned(),
+        rate_limit_read: UNLIMITED,
+        rate_limit_write: UNLIMITED,
+        rate_limit_upload: UNLIMITED,
+        trust_forwarded_for: false,
+        access_log_format: AccessLogFormat::Pretty,
+        cache_ttl_secs: 0,
+        cache_max_entries: 0,
+        redis_url: None,
+        similarity_weights: SimilarityWeights::default(),
+        metadata: MetadataConfig::default(),
     }
 }
 
@@ -427,6 +472,7 @@ impl crate::domains::luna::RecordRepository for MockRecordRepo {
         _search_dto: crate::domains::luna::dto::SearchRecordDto,
         _pagination: crate::domains::luna::dto::PaginationQuery,
         _user_filter: Option<crate::domains::luna::dto::UserFilter>,
+        _relations: crate::domains::luna::dto::RecordRelations,
     ) -> Result<crate::domains::luna::dto::PaginatedResponse<crate::domains::luna::Record>, DbErr>
     {
         unreachable!()
@@ -435,6 +481,7 @@ impl crate::domains::luna::RecordRepository for MockRecordRepo {
         &self,
         _txn: &sea_orm::DatabaseTransaction,
         _record: crate::domains::luna::dto::CreateRecordDto,
+        _actor: &str,
     ) -> Result<(String, crate::domains::luna::CreatedNestedEntities), DbErr> {
         unreachable!()
     }
@@ -443,9 +490,53 @@ impl crate::domains::luna::RecordRepository for MockRecordRepo {
         _txn: &sea_orm::DatabaseTransaction,
         _id: String,
         _record: crate::domains::luna::dto::UpdateRecordDto,
+        _actor: &str,
+    ) -> Result<Option<crate::domains::luna::Record>, DbErr> {
+        unreachable!()
+    }
+    async fn replace(
+        &self,
+        _txn: &sea_orm::DatabaseTransaction,
+        _record: crate::domains::luna::dto::CreateRecordDto,
+        _override_manual: bool,
+        _actor: &str,
+    ) -> Result<Option<(bool, crate::domains::luna::CreatedNestedEntities)>, DbErr> {
+        unreachable!()
+    }
+    async fn patch(
+        &self,
+        _txn: &sea_orm::DatabaseTransaction,
+        _id: String,
+        _patch: crate::domains::luna::dto::PatchRecordDto,
+        _actor: &str,
     ) -> Result<Option<crate::domains::luna::Record>, DbErr> {
         unreachable!()
     }
+    async fn merge_metadata(
+        &self,
+        _txn: &sea_orm::DatabaseTransaction,
+        _id: String,
+        _metadata: &crate::domains::luna::dto::FetchedMetadata,
+        _overwrite: bool,
+        _actor: &str,
+    ) -> Result<Option<(Vec<String>, crate::domains::luna::CreatedNestedEntities)>, DbErr> {
+        unreachable!()
+    }
+    async fn bump_version(
+        &self,
+        _txn: &sea_orm::DatabaseTransaction,
+        _id: String,
+        _expected: Option<i32>,
+    ) -> Result<Option<i32>, DbErr> {
+        unreachable!()
+    }
+    async fn find_version(
+        &self,
+        _txn: &sea_orm::DatabaseTransaction,
+        _id: String,
+    ) -> Result<Option<i32>, DbErr> {
+        unreachable!()
+    }
     async fn delete(
         &self,
         _txn: &sea_orm::DatabaseTransaction,
@@ -453,12 +544,101 @@ impl crate::domains::luna::RecordRepository for MockRecordRepo {
     ) -> Result<bool, DbErr> {
         unreachable!()
     }
+    async fn soft_delete(
+        &self,
+        _txn: &sea_orm::DatabaseTransaction,
+        _id: String,
+    ) -> Result<bool, DbErr> {
+        unreachable!()
+    }
+    async fn restore(
+        &self,
+        _txn: &sea_orm::DatabaseTransaction,
+        _id: String,
+    ) -> Result<bool, DbErr> {
+        unreachable!()
+    }
+    async fn purge(&self, _txn: &sea_orm::DatabaseTransaction, _id: String) -> Result<bool, DbErr> {
+        unreachable!()
+    }
+    async fn find_trash_paginated(
+        &self,
+        _db: &DatabaseConnection,
+        _pagination: crate::domains::luna::dto::PaginationQuery,
+        _max_permission: i32,
+    ) -> Result<crate::domains::luna::dto::PaginatedResponse<crate::domains::luna::Record>, DbErr>
+    {
+        unreachable!()
+    }
+    async fn soft_delete_many(
+        &self,
+        _txn: &sea_orm::DatabaseTransaction,
+        _ids: Vec<String>,
+    ) -> Result<Vec<String>, DbErr> {
+        unreachable!()
+    }
+    async fn delete_many(
+        &self,
+        _txn: &sea_orm::DatabaseTransaction,
+        _ids: Vec<String>,
+    ) -> Result<crate::domains::luna::DeletedRecordRows, DbErr> {
+        unreachable!()
+    }
+    async fn add_local_images(
+        &self,
+        _txn: &sea_orm::DatabaseTransaction,
+        _id: String,
+        _added: i32,
+    ) -> Result<Option<i32>, DbErr> {
+        unreachable!()
+    }
+    async fn set_trailer(
+        &self,
+        _txn: &sea_orm::DatabaseTransaction,
+        _id: String,
+        _trailer: Option<String>,
+    ) -> Result<bool, DbErr> {
+        unreachable!()
+    }
+    async fn set_cover_index(
+        &self,
+        _txn: &sea_orm::DatabaseTransaction,
+        _id: String,
+        _cover_index: Option<i32>,
+    ) -> Result<bool, DbErr> {
+        unreachable!()
+    }
+    async fn set_order_in_series(
+        &self,
+        _txn: &sea_orm::DatabaseTransaction,
+        _id: String,
+        _order_in_series: Option<i32>,
+    ) -> Result<bool, DbErr> {
+        unreachable!()
+    }
+    async fn merge_into(
+        &self,
+        _txn: &sea_orm::DatabaseTransaction,
+        _target_id: String,
+        _source_id: String,
+        _actor: &str,
+    ) -> Result<Option<crate::domains::luna::Record>, DbErr> {
+        unreachable!()
+    }
     async fn update_record_links(
         &self,
         _txn: &sea_orm::DatabaseTransaction,
         _record_id: String,
         _new_links: Vec<crate::domains::luna::dto::CreateLinkDto>,
-    ) -> Result<i32, DbErr> {
+    ) -> Result<crate::domains::luna::dto::LinkUpdateResultDto, DbErr> {
+        unreachable!()
+    }
+    async fn replace_record_links(
+        &self,
+        _txn: &sea_orm::DatabaseTransaction,
+        _record_id: String,
+        _links: Vec<crate::domains::luna::dto::CreateLinkDto>,
+    ) -> Result<crate::domains::luna::dto::LinkUpdateResultDto, DbErr> {
         unreachable!()
     }
     async fn find_all_slim(
@@ -475,6 +655,21 @@ impl crate::domains::luna::RecordRepository for MockRecordRepo {
     ) -> Result<Vec<String>, DbErr> {
         unreachable!()
     }
+    async fn find_existing_ids(
+        &self,
+        _db: &DatabaseConnection,
+        _ids: Vec<String>,
+        _max_permission: i32,
+    ) -> Result<Vec<String>, DbErr> {
+        unreachable!()
+    }
+    async fn find_permission(
+        &self,
+        _db: &DatabaseConnection,
+        _id: &str,
+    ) -> Result<Option<i32>, DbErr> {
+        unreachable!()
+    }
     async fn find_ids_paginated(
         &self,
         _db: &DatabaseConnection,
@@ -486,6 +681,7 @@ impl crate::domains::luna::RecordRepository for MockRecordRepo {
     async fn find_all_slim_paginated(
         &self,
         _db: &DatabaseConnection,
+        _search_dto: crate::domains::luna::dto::SearchRecordDto,
         _pagination: crate::domains::luna::dto::PaginationQuery,
         _user_filter: Option<crate::domains::luna::dto::UserFilter>,
     ) -> Result<crate::domains::luna::dto::PaginatedResponse<crate::domains::luna::Record>, DbErr>
@@ -499,30 +695,86 @@ impl crate::domains::luna::RecordRepository for MockRecordRepo {
     ) -> Result<Vec<crate::domains::luna::Record>, DbErr> {
         unreachable!()
     }
-    async fn find_by_genre_id_paginated(
+    async fn find_by_idol_id(
         &self,
         _db: &DatabaseConnection,
-        _genre_id: i64,
-        _pagination: crate::domains::luna::dto::PaginationQuery,
-        _user_filter: Option<crate::domains::luna::dto::UserFilter>,
-    ) -> Result<crate::domains::luna::dto::PaginatedResponse<crate::domains::luna::Record>, DbErr>
-    {
+        _idol_id: i64,
+    ) -> Result<Vec<crate::domains::luna::Record>, DbErr> {
         unreachable!()
     }
-    async fn find_by_idol_id(
+    async fn find_changes_since(
         &self,
         _db: &DatabaseConnection,
-        _idol_id: i64,
+        _since: i64,
+        _limit: u64,
+        _max_permission: i32,
+    ) -> Result<crate::domains::luna::RecordChanges, DbErr> {
+        unreachable!()
+    }
+    async fn find_recent(
+        &self,
+        _db: &DatabaseConnection,
+        _limit: u64,
+        _max_permission: i32,
     ) -> Result<Vec<crate::domains::luna::Record>, DbErr> {
         unreachable!()
     }
-    async fn find_by_idol_id_paginated(
+    async fn find_similar(
         &self,
         _db: &DatabaseConnection,
-        _idol_id: i64,
+        _id: &str,
+        _weights: SimilarityWeights,
+        _limit: u64,
+        _max_permission: i32,
+    ) -> Result<Vec<(crate::domains::luna::Record, f64)>, DbErr> {
+        unreachable!()
+    }
+    async fn find_series_neighbors(
+        &self,
+        _db: &DatabaseConnection,
+        _id: &str,
+        _max_permission: i32,
+    ) -> Result<
+        (
+            Option<crate::domains::luna::dto::SeriesNeighborDto>,
+            Option<crate::domains::luna::dto::SeriesNeighborDto>,
+        ),
+        DbErr,
+    > {
+        unreachable!()
+    }
+    async fn find_random(
+        &self,
+        _db: &DatabaseConnection,
+        _count: u64,
+        _genre_id: Option<i64>,
+        _idol_id: Option<i64>,
+        _max_permission: i32,
+    ) -> Result<Vec<crate::domains::luna::Record>, DbErr> {
+        unreachable!()
+    }
+    async fn find_seen_paginated(
+        &self,
+        _db: &DatabaseConnection,
+        _user_id: &str,
         _pagination: crate::domains::luna::dto::PaginationQuery,
-        _user_filter: Option<crate::domains::luna::dto::UserFilter>,
-    ) -> Result<crate::domains::luna::dto::PaginatedResponse<crate::domains::luna::Record>, DbErr>
+        _max_permission: i32,
+    ) -> Result<
+        crate::domains::luna::dto::PaginatedResponse<(
+            crate::domains::luna::Record,
+            chrono::DateTime<Utc>,
+        )>,
+        DbErr,
+    > {
+        unreachable!()
+    }
+
+    async fn find_relations(
+        &self,
+        _db: &DatabaseConnection,
+        _record_ids: Vec<String>,
+        _relations: crate::domains::luna::dto::RecordRelations,
+    ) -> Result<std::collections::HashMap<String, crate::domains::luna::RecordRelationRows>, DbErr>
     {
         unreachable!()
     }
@@ -545,13 +797,13 @@ fn test_service(
     let created_code_results = Arc::clone(&repo.created_code_results);
     let finalized = Arc::clone(&repo.finalized);
     let service = CrawlService::new(
-        db,
+        db.clone(),
         config.clone(),
         Arc::new(repo),
         Arc::new(NoopEntityProgressRepo),
         Arc::new(InteractionRepo),
         record_repo,
-        Arc::new(FileService::new(config)),
+        Arc::new(FileService::new(config, db)),
         manager,
     );
     (service, rx, created_code_results, finalized)
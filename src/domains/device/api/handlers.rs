@@ -1,8 +1,8 @@
-use crate::common::dto::RestApiResponse;
+use crate::common::dto::{ApiResponse, RestApiResponse};
 use crate::common::{app_state::AppState, error::AppError, jwt::Claims};
 
 use crate::domains::device::dto::device_dto::{
-    CreateDeviceDto, DeviceDto, UpdateDeviceDto, UpdateManyDevicesDto,
+    CreateDeviceDto, DeviceDto, SessionDto, UpdateDeviceDto, UpdateManyDevicesDto,
 };
 use axum::{
     extract::{Path, State},
@@ -15,7 +15,7 @@ use axum::{
 #[utoipa::path(
     get,
     path = "/device/{id}",
-    responses((status = 200, description = "Get device by ID", body = DeviceDto)),
+    responses((status = 200, description = "Get device by ID", body = ApiResponse<DeviceDto>)),
     tag = "Devices"
 )]
 pub async fn get_device_by_id(
@@ -31,7 +31,7 @@ pub async fn get_device_by_id(
 #[utoipa::path(
     get,
     path = "/device",
-    responses((status = 200, description = "List all devices", body = [DeviceDto])),
+    responses((status = 200, description = "List all devices", body = ApiResponse<Vec<DeviceDto>>)),
     tag = "Devices"
 )]
 pub async fn get_devices(State(state): State<AppState>) -> Result<impl IntoResponse, AppError> {
@@ -46,7 +46,7 @@ pub async fn get_devices(State(state): State<AppState>) -> Result<impl IntoRespo
     post,
     path = "/device",
     request_body = CreateDeviceDto,
-    responses((status = 200, description = "Create a new device", body = DeviceDto)),
+    responses((status = 200, description = "Create a new device", body = ApiResponse<DeviceDto>)),
     tag = "Devices"
 )]
 pub async fn create_device(
@@ -69,7 +69,7 @@ pub async fn create_device(
     put,
     path = "/device/{id}",
     request_body = UpdateDeviceDto,
-    responses((status = 200, description = "Update device", body = DeviceDto)),
+    responses((status = 200, description = "Update device", body = ApiResponse<DeviceDto>)),
     tag = "Devices"
 )]
 pub async fn update_device(
@@ -129,3 +129,65 @@ pub async fn update_many_devices(
 
     Ok(RestApiResponse::success_with_message(message, ()))
 }
+
+/// Lists the caller's active devices as sessions, with when and from where
+/// each was last used. The session making the request is flagged `current`.
+#[utoipa::path(
+    get,
+    path = "/sessions",
+    responses((status = 200, description = "Active sessions", body = ApiResponse<Vec<SessionDto>>)),
+    tag = "Sessions"
+)]
+pub async fn get_sessions(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<impl IntoResponse, AppError> {
+    let sessions = state
+        .device_service
+        .list_sessions(&claims.sub, claims.device_id.as_deref())
+        .await?;
+    Ok(RestApiResponse::success(sessions))
+}
+
+/// Revokes one of the caller's devices. Its refresh token is revoked and its
+/// access tokens are rejected from the next request on.
+#[utoipa::path(
+    delete,
+    path = "/sessions/{id}",
+    params(("id" = String, Path, description = "Device ID")),
+    responses(
+        (status = 200, description = "Session revoked"),
+        (status = 404, description = "No active device of the caller with this ID"),
+    ),
+    tag = "Sessions"
+)]
+pub async fn revoke_session(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    state
+        .device_service
+        .revoke_session(&claims.sub, &id)
+        .await?;
+    Ok(RestApiResponse::success(()))
+}
+
+/// Revokes every session of the caller except the one making the request.
+/// Returns the number of revoked devices.
+#[utoipa::path(
+    post,
+    path = "/sessions/revoke_others",
+    responses((status = 200, description = "Other sessions revoked", body = ApiResponse<u64>)),
+    tag = "Sessions"
+)]
+pub async fn revoke_other_sessions(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<impl IntoResponse, AppError> {
+    let revoked = state
+        .device_service
+        .revoke_other_sessions(&claims.sub, claims.device_id.as_deref())
+        .await?;
+    Ok(RestApiResponse::success(revoked))
+}
@@ -1,11 +1,12 @@
 use super::handlers::{
     __path_create_device, __path_delete_device, __path_get_device_by_id, __path_get_devices,
-    __path_update_device, __path_update_many_devices, create_device, delete_device,
-    get_device_by_id, get_devices, update_device, update_many_devices,
+    __path_get_sessions, __path_revoke_other_sessions, __path_revoke_session, __path_update_device,
+    __path_update_many_devices, create_device, delete_device, get_device_by_id, get_devices,
+    get_sessions, revoke_other_sessions, revoke_session, update_device, update_many_devices,
 };
 use crate::{
     common::app_state::AppState,
-    domains::device::dto::device_dto::{CreateDeviceDto, DeviceDto, UpdateDeviceDto},
+    domains::device::dto::device_dto::{CreateDeviceDto, DeviceDto, SessionDto, UpdateDeviceDto},
 };
 use axum::{
     routing::{delete, get, post, put},
@@ -14,7 +15,7 @@ use axum::{
 
 use utoipa::OpenApi;
 
-use crate::common::openapi::SecurityAddon;
+use crate::common::openapi::{ErrorResponsesAddon, SecurityAddon};
 
 #[derive(OpenApi)]
 #[openapi(
@@ -25,15 +26,19 @@ use crate::common::openapi::SecurityAddon;
         update_device,
         update_many_devices,
         delete_device,
+        get_sessions,
+        revoke_session,
+        revoke_other_sessions,
     ),
-    components(schemas(DeviceDto, CreateDeviceDto, UpdateDeviceDto)),
+    components(schemas(DeviceDto, CreateDeviceDto, UpdateDeviceDto, SessionDto)),
     tags(
-        (name = "Device", description = "Device management endpoints")
+        (name = "Device", description = "Device management endpoints"),
+        (name = "Sessions", description = "The caller's device sessions")
     ),
     security(
         ("bearer_auth" = [])
     ),
-    modifiers(&SecurityAddon)
+    modifiers(&SecurityAddon, &ErrorResponsesAddon)
 )]
 /// This struct is used to generate `OpenAPI` documentation for the device routes.
 pub struct DeviceApiDoc;
@@ -49,3 +54,11 @@ pub fn device_routes() -> Router<AppState> {
         .route("/{id}", delete(delete_device))
         .route("/batch/{user_id}", put(update_many_devices))
 }
+
+/// This function creates a router for the caller's device sessions.
+pub fn session_routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(get_sessions))
+        .route("/revoke_others", post(revoke_other_sessions))
+        .route("/{id}", delete(revoke_session))
+}
@@ -117,4 +117,7 @@ pub struct Device {
     pub created_at: Option<DateTime<Utc>>,
     pub modified_by: Option<String>,
     pub modified_at: Option<DateTime<Utc>>,
+    pub last_seen_at: Option<DateTime<Utc>>,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
 }
@@ -50,4 +50,43 @@ pub trait DeviceRepository: Send + Sync {
 
     /// Deletes a device record by its ID.
     async fn delete(&self, txn: &DatabaseTransaction, id: String) -> Result<bool, DbErr>;
+
+    /// Lists a user's active devices, most recently seen first.
+    async fn find_active_by_user(
+        &self,
+        db: &DatabaseConnection,
+        user_id: &str,
+    ) -> Result<Vec<Device>, DbErr>;
+
+    /// Finds one of a user's devices if it is still active.
+    async fn find_active_session(
+        &self,
+        db: &DatabaseConnection,
+        user_id: &str,
+        id: &str,
+    ) -> Result<Option<Device>, DbErr>;
+
+    /// Records an authenticated request made with the device's tokens.
+    async fn record_activity(
+        &self,
+        db: &DatabaseConnection,
+        id: &str,
+        user_agent: Option<String>,
+        ip_address: Option<String>,
+    ) -> Result<(), DbErr>;
+
+    /// Revokes one of a user's active devices: marks it inactive and clears its
+    /// refresh token. Returns whether such a device existed.
+    async fn revoke(&self, db: &DatabaseConnection, user_id: &str, id: &str)
+        -> Result<bool, DbErr>;
+
+    /// Revokes every active device of a user except `keep`. When `keep` is a
+    /// device, the account-level refresh token is revoked too.
+    /// Returns the number of revoked devices.
+    async fn revoke_all_except(
+        &self,
+        db: &DatabaseConnection,
+        user_id: &str,
+        keep: Option<&str>,
+    ) -> Result<u64, DbErr>;
 }
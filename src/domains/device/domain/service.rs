@@ -8,7 +8,7 @@ use sea_orm::DatabaseConnection;
 use crate::{
     common::error::AppError,
     domains::device::dto::device_dto::{
-        CreateDeviceDto, DeviceDto, UpdateDeviceDto, UpdateManyDevicesDto,
+        CreateDeviceDto, DeviceDto, SessionDto, UpdateDeviceDto, UpdateManyDevicesDto,
     },
 };
 
@@ -48,4 +48,32 @@ pub trait DeviceServiceTrait: Send + Sync {
         modified_by: String,
         payload: UpdateManyDevicesDto,
     ) -> Result<String, AppError>;
+
+    /// Lists the user's active devices as sessions, flagging `current_device_id`.
+    async fn list_sessions(
+        &self,
+        user_id: &str,
+        current_device_id: Option<&str>,
+    ) -> Result<Vec<SessionDto>, AppError>;
+
+    /// Revokes one of the user's devices; its tokens stop working.
+    async fn revoke_session(&self, user_id: &str, device_id: &str) -> Result<(), AppError>;
+
+    /// Revokes every session of the user except `current_device_id`.
+    /// Returns the number of revoked devices.
+    async fn revoke_other_sessions(
+        &self,
+        user_id: &str,
+        current_device_id: Option<&str>,
+    ) -> Result<u64, AppError>;
+
+    /// Checks that a device session is still active and records the request.
+    /// Fails with `InvalidToken` once the device has been revoked.
+    async fn touch_session(
+        &self,
+        user_id: &str,
+        device_id: &str,
+        user_agent: Option<String>,
+        ip_address: Option<String>,
+    ) -> Result<(), AppError>;
 }
@@ -20,6 +20,11 @@ pub struct DeviceDto {
     pub modified_by: Option<String>,
     #[serde(with = "crate::common::ts_format::option")]
     pub modified_at: Option<DateTime<Utc>>,
+    /// Last authenticated request made with the device's tokens.
+    #[serde(with = "crate::common::ts_format::option")]
+    pub last_seen_at: Option<DateTime<Utc>>,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
 }
 
 impl From<Device> for DeviceDto {
@@ -35,10 +40,22 @@ impl From<Device> for DeviceDto {
             created_at: device.created_at,
             modified_by: device.modified_by,
             modified_at: device.modified_at,
+            last_seen_at: device.last_seen_at,
+            user_agent: device.user_agent,
+            ip_address: device.ip_address,
         }
     }
 }
 
+/// A device the caller is signed in on.
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct SessionDto {
+    #[serde(flatten)]
+    pub device: DeviceDto,
+    /// Whether this is the session making the request.
+    pub current: bool,
+}
+
 #[derive(PartialEq, Eq, Debug, Deserialize, serde::Serialize, ToSchema)]
 pub struct CreateDeviceDto {
     pub name: String,
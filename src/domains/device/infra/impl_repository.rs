@@ -1,6 +1,8 @@
 use async_trait::async_trait;
 use sea_orm::{
-    ActiveModelTrait as _, DatabaseConnection, DatabaseTransaction, DbErr, EntityTrait as _, Set,
+    sea_query::{Expr, NullOrdering},
+    ActiveModelTrait as _, ColumnTrait as _, DatabaseConnection, DatabaseTransaction, DbErr,
+    EntityTrait as _, NotSet, Order, QueryFilter as _, QueryOrder as _, Set, TransactionTrait as _,
 };
 use std::str::FromStr as _;
 use uuid::Uuid;
@@ -10,11 +12,35 @@ use crate::domains::device::domain::repository::DeviceRepository;
 use crate::domains::device::dto::device_dto::{
     CreateDeviceDto, UpdateDeviceDto, UpdateManyDevicesDto,
 };
-use crate::entities::devices;
+use crate::entities::{devices, user_auth};
 
 pub struct DeviceRepo;
 
+/// Status of devices whose tokens are accepted.
+const ACTIVE_STATUS: &str = "active";
+
+/// Status given to revoked devices.
+const REVOKED_STATUS: &str = "inactive";
+
 impl DeviceRepo {
+    /// Update that revokes the active devices of a user; narrow it with more filters.
+    fn revoke_query(user_id: &str) -> sea_orm::UpdateMany<devices::Entity> {
+        devices::Entity::update_many()
+            .col_expr(devices::Column::Status, Expr::value(REVOKED_STATUS))
+            .col_expr(
+                devices::Column::RefreshTokenHash,
+                Expr::value(None::<String>),
+            )
+            .col_expr(
+                devices::Column::RefreshTokenExpiresAt,
+                Expr::value(None::<chrono::DateTime<chrono::Utc>>),
+            )
+            .col_expr(devices::Column::ModifiedBy, Expr::value(user_id))
+            .col_expr(devices::Column::ModifiedAt, Expr::value(chrono::Utc::now()))
+            .filter(devices::Column::UserId.eq(user_id))
+            .filter(devices::Column::Status.eq(ACTIVE_STATUS))
+    }
+
     fn entity_to_model(entity: devices::Model) -> Result<Device, DbErr> {
         Ok(Device {
             id: entity.id,
@@ -29,6 +55,9 @@ impl DeviceRepo {
             created_at: entity.created_at,
             modified_by: entity.modified_by,
             modified_at: entity.modified_at,
+            last_seen_at: entity.last_seen_at,
+            user_agent: entity.user_agent,
+            ip_address: entity.ip_address,
         })
     }
 }
@@ -79,6 +108,11 @@ impl DeviceRepository for DeviceRepo {
             created_at: Set(Some(now)),
             modified_by: Set(Some(device.modified_by)),
             modified_at: Set(Some(now)),
+            refresh_token_hash: NotSet,
+            refresh_token_expires_at: NotSet,
+            last_seen_at: NotSet,
+            user_agent: NotSet,
+            ip_address: NotSet,
         };
 
         let inserted = active_device.insert(tx).await?;
@@ -147,6 +181,11 @@ impl DeviceRepository for DeviceRepo {
                     created_at: Set(Some(now)),
                     modified_by: Set(Some(modified_by.clone())),
                     modified_at: Set(Some(now)),
+                    refresh_token_hash: NotSet,
+                    refresh_token_expires_at: NotSet,
+                    last_seen_at: NotSet,
+                    user_agent: NotSet,
+                    ip_address: NotSet,
                 }
             })
             .collect();
@@ -175,4 +214,97 @@ impl DeviceRepository for DeviceRepo {
 
         Ok(result.rows_affected > 0)
     }
+
+    async fn find_active_by_user(
+        &self,
+        db: &DatabaseConnection,
+        user_id: &str,
+    ) -> Result<Vec<Device>, DbErr> {
+        devices::Entity::find()
+            .filter(devices::Column::UserId.eq(user_id))
+            .filter(devices::Column::Status.eq(ACTIVE_STATUS))
+            .order_by_with_nulls(devices::Column::LastSeenAt, Order::Desc, NullOrdering::Last)
+            .order_by_desc(devices::Column::CreatedAt)
+            .all(db)
+            .await?
+            .into_iter()
+            .map(Self::entity_to_model)
+            .collect()
+    }
+
+    async fn find_active_session(
+        &self,
+        db: &DatabaseConnection,
+        user_id: &str,
+        id: &str,
+    ) -> Result<Option<Device>, DbErr> {
+        devices::Entity::find_by_id(id)
+            .filter(devices::Column::UserId.eq(user_id))
+            .filter(devices::Column::Status.eq(ACTIVE_STATUS))
+            .one(db)
+            .await?
+            .map(Self::entity_to_model)
+            .transpose()
+    }
+
+    async fn record_activity(
+        &self,
+        db: &DatabaseConnection,
+        id: &str,
+        user_agent: Option<String>,
+        ip_address: Option<String>,
+    ) -> Result<(), DbErr> {
+        devices::Entity::update_many()
+            .col_expr(devices::Column::LastSeenAt, Expr::value(chrono::Utc::now()))
+            .col_expr(devices::Column::UserAgent, Expr::value(user_agent))
+            .col_expr(devices::Column::IpAddress, Expr::value(ip_address))
+            .filter(devices::Column::Id.eq(id))
+            .exec(db)
+            .await?;
+        Ok(())
+    }
+
+    async fn revoke(
+        &self,
+        db: &DatabaseConnection,
+        user_id: &str,
+        id: &str,
+    ) -> Result<bool, DbErr> {
+        let result = Self::revoke_query(user_id)
+            .filter(devices::Column::Id.eq(id))
+            .exec(db)
+            .await?;
+        Ok(result.rows_affected > 0)
+    }
+
+    async fn revoke_all_except(
+        &self,
+        db: &DatabaseConnection,
+        user_id: &str,
+        keep: Option<&str>,
+    ) -> Result<u64, DbErr> {
+        let txn = db.begin().await?;
+
+        let mut revoke = Self::revoke_query(user_id);
+        if let Some(keep) = keep {
+            revoke = revoke.filter(devices::Column::Id.ne(keep));
+
+            user_auth::Entity::update_many()
+                .col_expr(
+                    user_auth::Column::RefreshTokenHash,
+                    Expr::value(None::<String>),
+                )
+                .col_expr(
+                    user_auth::Column::RefreshTokenExpiresAt,
+                    Expr::value(None::<chrono::DateTime<chrono::Utc>>),
+                )
+                .filter(user_auth::Column::UserId.eq(user_id))
+                .exec(&txn)
+                .await?;
+        }
+        let result = revoke.exec(&txn).await?;
+
+        txn.commit().await?;
+        Ok(result.rows_affected)
+    }
 }
@@ -2,7 +2,9 @@ use crate::{
     common::error::AppError,
     domains::device::{
         domain::{repository::DeviceRepository, service::DeviceServiceTrait},
-        dto::device_dto::{CreateDeviceDto, DeviceDto, UpdateDeviceDto, UpdateManyDevicesDto},
+        dto::device_dto::{
+            CreateDeviceDto, DeviceDto, SessionDto, UpdateDeviceDto, UpdateManyDevicesDto,
+        },
         infra::impl_repository::DeviceRepo,
     },
 };
@@ -11,6 +13,13 @@ use async_trait::async_trait;
 use sea_orm::{DatabaseConnection, TransactionTrait as _};
 use std::sync::Arc;
 
+/// Minimum time between two activity updates of the same session, so busy
+/// clients don't cause a write per request.
+const SESSION_TOUCH_INTERVAL_SECS: i64 = 60;
+
+/// Length of the `devices.user_agent` column; longer values are truncated.
+const MAX_USER_AGENT_LEN: usize = 512;
+
 /// Service struct for handling device-related operations
 /// such as creating, updating, deleting, and fetching devices.
 /// It uses a repository pattern to abstract the data access layer.
@@ -114,4 +123,72 @@ impl DeviceServiceTrait for DeviceService {
         tx.commit().await?;
         Ok("Devices updated".into())
     }
+
+    async fn list_sessions(
+        &self,
+        user_id: &str,
+        current_device_id: Option<&str>,
+    ) -> Result<Vec<SessionDto>, AppError> {
+        let devices = self.repo.find_active_by_user(&self.db, user_id).await?;
+        Ok(devices
+            .into_iter()
+            .map(|device| SessionDto {
+                current: current_device_id == Some(device.id.as_str()),
+                device: device.into(),
+            })
+            .collect())
+    }
+
+    async fn revoke_session(&self, user_id: &str, device_id: &str) -> Result<(), AppError> {
+        if self.repo.revoke(&self.db, user_id, device_id).await? {
+            Ok(())
+        } else {
+            Err(AppError::NotFound(format!(
+                "Active device not found: {device_id}"
+            )))
+        }
+    }
+
+    async fn revoke_other_sessions(
+        &self,
+        user_id: &str,
+        current_device_id: Option<&str>,
+    ) -> Result<u64, AppError> {
+        Ok(self
+            .repo
+            .revoke_all_except(&self.db, user_id, current_device_id)
+            .await?)
+    }
+
+    async fn touch_session(
+        &self,
+        user_id: &str,
+        device_id: &str,
+        user_agent: Option<String>,
+        ip_address: Option<String>,
+    ) -> Result<(), AppError> {
+        let user_agent = user_agent.map(|ua| ua.chars().take(MAX_USER_AGENT_LEN).collect());
+        let device = self
+            .repo
+            .find_active_session(&self.db, user_id, device_id)
+            .await?
+            .ok_or(AppError::InvalidToken)?;
+
+        let recently_seen = device.last_seen_at.is_some_and(|seen| {
+            chrono::Utc::now() - seen < chrono::Duration::seconds(SESSION_TOUCH_INTERVAL_SECS)
+        });
+        let unchanged = device.user_agent == user_agent && device.ip_address == ip_address;
+        if recently_seen && unchanged {
+            return Ok(());
+        }
+
+        if let Err(err) = self
+            .repo
+            .record_activity(&self.db, device_id, user_agent, ip_address)
+            .await
+        {
+            tracing::warn!("Failed to record session activity: {err}");
+        }
+        Ok(())
+    }
 }
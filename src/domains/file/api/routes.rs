@@ -9,7 +9,7 @@ use axum::{
 
 use utoipa::OpenApi;
 
-use crate::common::openapi::SecurityAddon;
+use crate::common::openapi::{ErrorResponsesAddon, SecurityAddon};
 
 #[derive(OpenApi)]
 #[openapi(
@@ -24,7 +24,7 @@ use crate::common::openapi::SecurityAddon;
     security(
         ("bearer_auth" = [])
     ),
-    modifiers(&SecurityAddon)
+    modifiers(&SecurityAddon, &ErrorResponsesAddon)
 )]
 /// `FileApiDoc` is used to generate `OpenAPI` documentation for the file API.
 pub struct FileApiDoc;
@@ -0,0 +1,19 @@
+//! GraphQL API over the luna catalog at `POST /graphql`, built with the
+//! `graphql` feature. Resolvers go through the same services as the REST
+//! routes, so permissions, validation and change events behave identically.
+
+mod api {
+    mod handlers;
+    pub mod routes;
+}
+
+mod error;
+mod loaders;
+mod mutation;
+mod query;
+mod schema;
+mod types;
+
+// Re-export commonly used items for convenience
+pub use api::routes::graphql_routes;
+pub use schema::{build_schema, LunaSchema};
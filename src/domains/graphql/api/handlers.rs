@@ -0,0 +1,34 @@
+use crate::{
+    common::{app_state::AppState, jwt::Claims},
+    domains::graphql::{
+        loaders::{InteractionLoader, RelationLoader},
+        LunaSchema,
+    },
+};
+use async_graphql::dataloader::DataLoader;
+use async_graphql_axum::{GraphQLRequest, GraphQLResponse};
+use axum::{extract::State, Extension};
+
+/// Execute a GraphQL operation on behalf of the authenticated caller.
+///
+/// Dataloaders are created per request so batching and caching never span
+/// callers with different permissions.
+pub async fn execute_graphql(
+    State(state): State<AppState>,
+    Extension(schema): Extension<LunaSchema>,
+    Extension(claims): Extension<Claims>,
+    request: GraphQLRequest,
+) -> GraphQLResponse {
+    let relations = DataLoader::new(RelationLoader::new(state.clone()), tokio::spawn);
+    let interactions = DataLoader::new(
+        InteractionLoader::new(state.clone(), claims.sub.clone()),
+        tokio::spawn,
+    );
+    let request = request
+        .into_inner()
+        .data(relations)
+        .data(interactions)
+        .data(state)
+        .data(claims);
+    schema.execute(request).await.into()
+}
@@ -0,0 +1,9 @@
+use super::handlers::execute_graphql;
+use crate::{common::app_state::AppState, domains::graphql::build_schema};
+use axum::{routing::post, Extension, Router};
+
+pub fn graphql_routes() -> Router<AppState> {
+    Router::new()
+        .route("/graphql", post(execute_graphql))
+        .layer(Extension(build_schema()))
+}
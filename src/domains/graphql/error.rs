@@ -0,0 +1,48 @@
+use crate::common::{
+    error::AppError,
+    jwt::{Claims, Role},
+};
+use async_graphql::{Context, Error, ErrorExtensions as _};
+use validator::Validate;
+
+/// Convert a service error into a GraphQL error. `extensions.status` and
+/// `extensions.code` hold the HTTP status and error code the REST API answers
+/// with, and a stale `expectedVersion` also reports the current one in
+/// `extensions.version`.
+pub(super) fn gql_error(err: AppError) -> Error {
+    let status = err.status_code();
+    let code = err.code();
+    if status.is_server_error() {
+        tracing::error!(?status, %err, "Server error");
+    }
+    let version = match err {
+        AppError::PreconditionFailed(version) => Some(version),
+        _ => None,
+    };
+    Error::new(err.to_string()).extend_with(|_, ext| {
+        ext.set("status", status.as_u16());
+        if let Ok(code) = async_graphql::to_value(code) {
+            ext.set("code", code);
+        }
+        if let Some(version) = version {
+            ext.set("version", version);
+        }
+    })
+}
+
+/// Run the input's `validator` rules, reporting failures like the REST handlers.
+pub(super) fn validate_input(input: &impl Validate) -> Result<(), Error> {
+    input.validate().map_err(|err| {
+        tracing::error!("Validation error: {err}");
+        gql_error(AppError::InvalidInput(err))
+    })
+}
+
+/// The caller's claims, rejected unless their role is at least `min`.
+pub(super) fn require_role<'a>(ctx: &Context<'a>, min: Role) -> Result<&'a Claims, Error> {
+    let claims = ctx.data::<Claims>()?;
+    if claims.role < min {
+        return Err(gql_error(AppError::Forbidden));
+    }
+    Ok(claims)
+}
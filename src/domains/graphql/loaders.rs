@@ -0,0 +1,146 @@
+//! Dataloaders that batch the per-record lookups of one GraphQL response.
+
+use super::error::gql_error;
+use crate::{
+    common::app_state::AppState,
+    domains::{
+        luna::dto::{
+            IdolParticipationDto, LinkDto, RecordGenreDto, RecordRelations, RecordRelationsDto,
+        },
+        user::dto::interaction_dto::InteractionStatusDto,
+    },
+};
+use async_graphql::{dataloader::Loader, Error};
+use std::collections::HashMap;
+
+/// Genres of a record.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(super) struct GenresOf(pub String);
+
+/// Idol participations of a record.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(super) struct IdolsOf(pub String);
+
+/// Download links of a record.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(super) struct LinksOf(pub String);
+
+/// Loads record relations with one query per relation for all records
+/// resolved in the same response, instead of one per record.
+pub(super) struct RelationLoader {
+    state: AppState,
+}
+
+impl RelationLoader {
+    pub(super) fn new(state: AppState) -> Self {
+        Self { state }
+    }
+
+    async fn load_relations(
+        &self,
+        record_ids: Vec<String>,
+        relations: RecordRelations,
+    ) -> Result<HashMap<String, RecordRelationsDto>, Error> {
+        self.state
+            .luna_service
+            .record_service()
+            .get_record_relations(&record_ids, relations)
+            .await
+            .map_err(gql_error)
+    }
+}
+
+impl Loader<GenresOf> for RelationLoader {
+    type Value = Vec<RecordGenreDto>;
+    type Error = Error;
+
+    async fn load(&self, keys: &[GenresOf]) -> Result<HashMap<GenresOf, Self::Value>, Error> {
+        let ids = keys.iter().map(|key| key.0.clone()).collect();
+        let relations = RecordRelations {
+            genres: true,
+            idols: false,
+            links: false,
+        };
+        let rows = self.load_relations(ids, relations).await?;
+        Ok(rows
+            .into_iter()
+            .map(|(id, dto)| (GenresOf(id), dto.genres))
+            .collect())
+    }
+}
+
+impl Loader<IdolsOf> for RelationLoader {
+    type Value = Vec<IdolParticipationDto>;
+    type Error = Error;
+
+    async fn load(&self, keys: &[IdolsOf]) -> Result<HashMap<IdolsOf, Self::Value>, Error> {
+        let ids = keys.iter().map(|key| key.0.clone()).collect();
+        let relations = RecordRelations {
+            genres: false,
+            idols: true,
+            links: false,
+        };
+        let rows = self.load_relations(ids, relations).await?;
+        Ok(rows
+            .into_iter()
+            .map(|(id, dto)| (IdolsOf(id), dto.idols))
+            .collect())
+    }
+}
+
+impl Loader<LinksOf> for RelationLoader {
+    type Value = Vec<LinkDto>;
+    type Error = Error;
+
+    async fn load(&self, keys: &[LinksOf]) -> Result<HashMap<LinksOf, Self::Value>, Error> {
+        let ids = keys.iter().map(|key| key.0.clone()).collect();
+        let relations = RecordRelations {
+            genres: false,
+            idols: false,
+            links: true,
+        };
+        let rows = self.load_relations(ids, relations).await?;
+        Ok(rows
+            .into_iter()
+            .map(|(id, dto)| (LinksOf(id), dto.links))
+            .collect())
+    }
+}
+
+/// Loads the caller's liked/viewed/favorite status for all records in a response.
+pub(super) struct InteractionLoader {
+    state: AppState,
+    user_id: String,
+}
+
+impl InteractionLoader {
+    pub(super) fn new(state: AppState, user_id: String) -> Self {
+        Self { state, user_id }
+    }
+}
+
+impl Loader<String> for InteractionLoader {
+    type Value = InteractionStatusDto;
+    type Error = Error;
+
+    async fn load(&self, keys: &[String]) -> Result<HashMap<String, Self::Value>, Error> {
+        let statuses = self
+            .state
+            .user_service
+            .interaction_service()
+            .batch_get_status(&self.user_id, keys)
+            .await
+            .map_err(gql_error)?;
+        Ok(statuses
+            .into_iter()
+            .map(|(id, status)| {
+                let dto = InteractionStatusDto {
+                    liked: status.liked,
+                    viewed: status.viewed,
+                    is_favorite: status.favorite,
+                };
+                (id, dto)
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,64 @@
+use super::{
+    error::{gql_error, require_role, validate_input},
+    types::Record,
+};
+use crate::{
+    common::{app_state::AppState, jwt::Role},
+    domains::luna::dto::{CreateRecordDto, PatchRecordDto},
+};
+use async_graphql::{Context, Object, Result};
+
+/// Record writes; each requires the `editor` role, like the REST routes.
+pub struct MutationRoot;
+
+#[Object]
+impl MutationRoot {
+    /// Create a record, creating any nested director, studio, label, series,
+    /// genres and idols that do not exist yet.
+    async fn create_record(&self, ctx: &Context<'_>, input: CreateRecordDto) -> Result<Record> {
+        let claims = require_role(ctx, Role::Editor)?;
+        let state = ctx.data::<AppState>()?;
+        validate_input(&input)?;
+        let record = state
+            .luna_service
+            .record_service()
+            .create_record(input, &claims.sub)
+            .await
+            .map_err(gql_error)?;
+        Ok(Record::hydrated(record))
+    }
+
+    /// Update the fields present in `input`. With `expectedVersion`, a record
+    /// changed since that version is rejected with status 412.
+    async fn patch_record(
+        &self,
+        ctx: &Context<'_>,
+        id: String,
+        input: PatchRecordDto,
+        expected_version: Option<i32>,
+    ) -> Result<Record> {
+        let claims = require_role(ctx, Role::Editor)?;
+        let state = ctx.data::<AppState>()?;
+        validate_input(&input)?;
+        let record = state
+            .luna_service
+            .record_service()
+            .patch_record(&id, input, expected_version, &claims.sub)
+            .await
+            .map_err(gql_error)?;
+        Ok(Record::hydrated(record))
+    }
+
+    /// Move a record to the trash and return its ID.
+    async fn delete_record(&self, ctx: &Context<'_>, id: String) -> Result<String> {
+        require_role(ctx, Role::Editor)?;
+        let state = ctx.data::<AppState>()?;
+        state
+            .luna_service
+            .record_service()
+            .delete_record(&id)
+            .await
+            .map_err(gql_error)?;
+        Ok(id)
+    }
+}
@@ -0,0 +1,154 @@
+use super::{
+    error::gql_error,
+    types::{Record, RecordFilter, RecordPage},
+};
+use crate::{
+    common::{app_state::AppState, error::AppError, jwt::Claims},
+    domains::luna::{
+        dto::{
+            DirectorDto, GenreDto, IdolDto, LabelDto, PaginationQuery, RecordRelations,
+            SearchRecordDto, SeriesDto, StudioDto, UserFilter,
+        },
+        RecordPermission,
+    },
+};
+use async_graphql::{Context, Object, Result};
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// A record by ID, or `null` when it does not exist. Records above the
+    /// caller's permission level are rejected with status 403.
+    async fn record(&self, ctx: &Context<'_>, id: String) -> Result<Option<Record>> {
+        let state = ctx.data::<AppState>()?;
+        let claims = ctx.data::<Claims>()?;
+        let record = match state
+            .luna_service
+            .record_service()
+            .get_record_by_id(&id)
+            .await
+        {
+            Ok(record) => record,
+            Err(AppError::NotFound(_)) => return Ok(None),
+            Err(err) => return Err(gql_error(err)),
+        };
+        if record.permission > RecordPermission::clearance(claims.role) {
+            return Err(gql_error(AppError::Forbidden));
+        }
+        Ok(Some(Record::hydrated(record)))
+    }
+
+    /// Records matching `filter`, paged like `GET /cards/records`. Pass
+    /// `cursor` (empty for the first page) to switch to keyset paging.
+    async fn records(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<RecordFilter>,
+        limit: Option<i64>,
+        offset: Option<i64>,
+        #[graphql(desc = "Sort keys, e.g. `-date,title`; prefix `-` for descending.")]
+        ordering: Option<String>,
+        cursor: Option<String>,
+    ) -> Result<RecordPage> {
+        let state = ctx.data::<AppState>()?;
+        let claims = ctx.data::<Claims>()?;
+        let filter = filter.unwrap_or_default();
+        let (liked_only, viewed_only) = (filter.liked_only, filter.viewed_only);
+        let search_dto = SearchRecordDto::from(filter);
+        search_dto
+            .validate_date_range()
+            .and_then(|()| search_dto.validate_id_lists())
+            .map_err(|err| gql_error(AppError::ValidationError(err)))?;
+        let pagination = PaginationQuery {
+            limit,
+            offset,
+            liked_only: Some(liked_only),
+            viewed_only: Some(viewed_only),
+            seen: None,
+            ordering,
+            cursor,
+        };
+        let user_filter = UserFilter {
+            user_id: claims.sub.clone(),
+            liked_only,
+            viewed_only,
+            favorites_only: false,
+            unseen_only: false,
+            max_permission: RecordPermission::clearance(claims.role),
+        };
+        // Genres, idols and links are only fetched, in batch, when selected.
+        let relations = RecordRelations {
+            genres: false,
+            idols: false,
+            links: false,
+        };
+        let page = state
+            .luna_service
+            .record_service()
+            .get_record_list_paginated(search_dto, pagination, Some(user_filter), relations)
+            .await
+            .map_err(gql_error)?;
+        Ok(RecordPage::from(page))
+    }
+
+    async fn directors(&self, ctx: &Context<'_>) -> Result<Vec<DirectorDto>> {
+        let state = ctx.data::<AppState>()?;
+        state
+            .luna_service
+            .director_service()
+            .get_directors()
+            .await
+            .map_err(gql_error)
+    }
+
+    async fn studios(&self, ctx: &Context<'_>) -> Result<Vec<StudioDto>> {
+        let state = ctx.data::<AppState>()?;
+        state
+            .luna_service
+            .studio_service()
+            .get_studios()
+            .await
+            .map_err(gql_error)
+    }
+
+    async fn labels(&self, ctx: &Context<'_>) -> Result<Vec<LabelDto>> {
+        let state = ctx.data::<AppState>()?;
+        state
+            .luna_service
+            .label_service()
+            .get_labels()
+            .await
+            .map_err(gql_error)
+    }
+
+    async fn series(&self, ctx: &Context<'_>) -> Result<Vec<SeriesDto>> {
+        let state = ctx.data::<AppState>()?;
+        state
+            .luna_service
+            .series_service()
+            .get_series()
+            .await
+            .map_err(gql_error)
+    }
+
+    async fn genres(&self, ctx: &Context<'_>) -> Result<Vec<GenreDto>> {
+        let state = ctx.data::<AppState>()?;
+        state
+            .luna_service
+            .genre_service()
+            .get_genres()
+            .await
+            .map_err(gql_error)
+    }
+
+    async fn idols(&self, ctx: &Context<'_>) -> Result<Vec<IdolDto>> {
+        let state = ctx.data::<AppState>()?;
+        state
+            .luna_service
+            .idol_service()
+            .get_idols()
+            .await
+            .map_err(gql_error)
+    }
+}
@@ -0,0 +1,41 @@
+use super::{mutation::MutationRoot, query::QueryRoot};
+use async_graphql::{EmptySubscription, Schema};
+
+/// Deepest selection accepted, enough for `records { results { idols { idol { name } } } }`
+/// with room for fragments.
+const MAX_QUERY_DEPTH: usize = 10;
+/// Most fields a single operation may select.
+const MAX_QUERY_COMPLEXITY: usize = 500;
+
+/// Schema served at `POST /graphql`.
+pub type LunaSchema = Schema<QueryRoot, MutationRoot, EmptySubscription>;
+
+/// Build the schema. Per-request data (state, claims and dataloaders) is
+/// attached by the handler.
+pub fn build_schema() -> LunaSchema {
+    Schema::build(QueryRoot, MutationRoot, EmptySubscription)
+        .limit_depth(MAX_QUERY_DEPTH)
+        .limit_complexity(MAX_QUERY_COMPLEXITY)
+        .finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::build_schema;
+
+    #[test]
+    fn schema_exposes_records_and_mutations() {
+        let sdl = build_schema().sdl();
+        for needle in [
+            "records(",
+            "createRecord(input: RecordInput!)",
+            "patchRecord(",
+            "deleteRecord(id: String!)",
+            "type Director",
+            "input LinkInput",
+            "enum MatchMode",
+        ] {
+            assert!(sdl.contains(needle), "schema is missing `{needle}`");
+        }
+    }
+}
@@ -0,0 +1,244 @@
+use super::loaders::{GenresOf, IdolsOf, InteractionLoader, LinksOf, RelationLoader};
+use crate::domains::luna::dto::{
+    DirectorDto, IdolParticipationDto, LabelDto, LinkDto, MatchMode, PaginatedResponse, RecordDto,
+    RecordGenreDto, SearchRecordDto, SeriesDto, StudioDto,
+};
+use async_graphql::{dataloader::DataLoader, Context, InputObject, Object, Result, SimpleObject};
+use sea_orm::prelude::Date;
+
+/// A record. Director, studio, label and series come with the record; genres,
+/// idols, links and interaction status are batched across the response.
+pub struct Record {
+    dto: RecordDto,
+    /// Whether `dto` already carries genres, idols and links.
+    hydrated: bool,
+}
+
+impl Record {
+    /// A record whose relations were loaded with it.
+    pub(super) fn hydrated(dto: RecordDto) -> Self {
+        Self {
+            dto,
+            hydrated: true,
+        }
+    }
+
+    /// A record whose relations are loaded on demand.
+    pub(super) fn lazy(dto: RecordDto) -> Self {
+        Self {
+            dto,
+            hydrated: false,
+        }
+    }
+}
+
+#[Object]
+impl Record {
+    async fn id(&self) -> &str {
+        &self.dto.id
+    }
+
+    async fn title(&self) -> &str {
+        &self.dto.title
+    }
+
+    async fn date(&self) -> Date {
+        self.dto.date
+    }
+
+    async fn duration(&self) -> i32 {
+        self.dto.duration
+    }
+
+    async fn director(&self) -> &DirectorDto {
+        &self.dto.director
+    }
+
+    async fn studio(&self) -> &StudioDto {
+        &self.dto.studio
+    }
+
+    async fn label(&self) -> &LabelDto {
+        &self.dto.label
+    }
+
+    async fn series(&self) -> &SeriesDto {
+        &self.dto.series
+    }
+
+    async fn genres(&self, ctx: &Context<'_>) -> Result<Vec<RecordGenreDto>> {
+        if self.hydrated {
+            return Ok(self.dto.genres.clone());
+        }
+        let loader = ctx.data::<DataLoader<RelationLoader>>()?;
+        let genres = loader.load_one(GenresOf(self.dto.id.clone())).await?;
+        Ok(genres.unwrap_or_default())
+    }
+
+    async fn idols(&self, ctx: &Context<'_>) -> Result<Vec<IdolParticipationDto>> {
+        if self.hydrated {
+            return Ok(self.dto.idols.clone());
+        }
+        let loader = ctx.data::<DataLoader<RelationLoader>>()?;
+        let idols = loader.load_one(IdolsOf(self.dto.id.clone())).await?;
+        Ok(idols.unwrap_or_default())
+    }
+
+    async fn has_links(&self) -> bool {
+        self.dto.has_links
+    }
+
+    async fn links(&self, ctx: &Context<'_>) -> Result<Vec<LinkDto>> {
+        if self.hydrated {
+            return Ok(self.dto.links.clone());
+        }
+        let loader = ctx.data::<DataLoader<RelationLoader>>()?;
+        let links = loader.load_one(LinksOf(self.dto.id.clone())).await?;
+        Ok(links.unwrap_or_default())
+    }
+
+    async fn permission(&self) -> i32 {
+        self.dto.permission
+    }
+
+    async fn local_img_count(&self) -> i32 {
+        self.dto.local_img_count
+    }
+
+    async fn create_time(&self) -> Date {
+        self.dto.create_time
+    }
+
+    async fn update_time(&self) -> Date {
+        self.dto.update_time
+    }
+
+    async fn creator(&self) -> &str {
+        &self.dto.creator
+    }
+
+    async fn modified_by(&self) -> &str {
+        &self.dto.modified_by
+    }
+
+    /// Optimistic-concurrency version; pass it as `expectedVersion` when patching.
+    async fn version(&self) -> i32 {
+        self.dto.version
+    }
+
+    /// Whether the caller has liked this record.
+    async fn liked(&self, ctx: &Context<'_>) -> Result<bool> {
+        let loader = ctx.data::<DataLoader<InteractionLoader>>()?;
+        let status = loader.load_one(self.dto.id.clone()).await?;
+        Ok(status.is_some_and(|s| s.liked))
+    }
+
+    /// Whether the caller has viewed this record.
+    async fn viewed(&self, ctx: &Context<'_>) -> Result<bool> {
+        let loader = ctx.data::<DataLoader<InteractionLoader>>()?;
+        let status = loader.load_one(self.dto.id.clone()).await?;
+        Ok(status.is_some_and(|s| s.viewed))
+    }
+
+    /// Whether this record is on the caller's favorites list.
+    async fn is_favorite(&self, ctx: &Context<'_>) -> Result<bool> {
+        let loader = ctx.data::<DataLoader<InteractionLoader>>()?;
+        let status = loader.load_one(self.dto.id.clone()).await?;
+        Ok(status.is_some_and(|s| s.is_favorite))
+    }
+}
+
+/// One page of records.
+#[derive(SimpleObject)]
+pub struct RecordPage {
+    /// Total number of matching records.
+    pub count: i64,
+    /// Cursor for the next page in keyset mode; `null` in offset mode or on
+    /// the last page.
+    pub next_cursor: Option<String>,
+    pub results: Vec<Record>,
+}
+
+impl From<PaginatedResponse<RecordDto>> for RecordPage {
+    fn from(page: PaginatedResponse<RecordDto>) -> Self {
+        Self {
+            count: page.count,
+            next_cursor: page.next_cursor,
+            results: page.results.into_iter().map(Record::lazy).collect(),
+        }
+    }
+}
+
+/// Record filters, as accepted by `GET /cards/records`.
+#[derive(Default, InputObject)]
+pub struct RecordFilter {
+    pub id: Option<String>,
+    pub title: Option<String>,
+    pub director_id: Option<i64>,
+    pub studio_id: Option<i64>,
+    pub label_id: Option<i64>,
+    pub series_id: Option<i64>,
+    /// Records linked to these genres, combined per `matchMode`.
+    pub genre_ids: Option<Vec<i64>>,
+    /// Records featuring these idols, combined per `matchMode`.
+    pub idol_ids: Option<Vec<i64>>,
+    pub match_mode: Option<MatchMode>,
+    /// Free-text search term.
+    pub search: Option<String>,
+    pub date_from: Option<Date>,
+    pub date_to: Option<Date>,
+    pub modified_since: Option<Date>,
+    /// Only records the caller has liked.
+    #[graphql(default)]
+    pub liked_only: bool,
+    /// Only records the caller has viewed.
+    #[graphql(default)]
+    pub viewed_only: bool,
+}
+
+/// Render an ID list the way the REST query string carries it.
+fn join_ids(ids: Option<Vec<i64>>) -> Option<String> {
+    ids.map(|ids| ids.iter().map(i64::to_string).collect::<Vec<_>>().join(","))
+}
+
+impl From<RecordFilter> for SearchRecordDto {
+    fn from(filter: RecordFilter) -> Self {
+        Self {
+            id: filter.id,
+            title: filter.title,
+            director_id: filter.director_id,
+            studio_id: filter.studio_id,
+            label_id: filter.label_id,
+            series_id: filter.series_id,
+            genre_id: None,
+            genre_category_id: None,
+            idol_id: None,
+            genre_ids: join_ids(filter.genre_ids),
+            idol_ids: join_ids(filter.idol_ids),
+            tag_ids: None,
+            match_mode: filter.match_mode,
+            search: filter.search,
+            date_from: filter.date_from,
+            date_to: filter.date_to,
+            modified_since: filter.modified_since,
+            min_rating: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RecordFilter, SearchRecordDto};
+
+    #[test]
+    fn filter_id_lists_become_comma_separated() {
+        let search = SearchRecordDto::from(RecordFilter {
+            genre_ids: Some(vec![3, 1]),
+            idol_ids: Some(vec![]),
+            ..RecordFilter::default()
+        });
+        assert_eq!(search.genre_ids.as_deref(), Some("3,1"));
+        assert_eq!(search.idol_ids.as_deref(), Some(""));
+        assert_eq!(search.genre_id_list(), Ok(vec![3, 1]));
+    }
+}
@@ -0,0 +1,20 @@
+//! gRPC record catalog service (`proto/luna/v1/record.proto`), built with the
+//! `grpc` feature and served on `GRPC_PORT` beside the REST API. Calls go
+//! through the same services, so permissions, validation and change events
+//! behave identically.
+
+mod proto {
+    #![allow(clippy::allow_attributes)]
+    #![allow(clippy::all, clippy::pedantic, clippy::nursery, clippy::restriction)]
+    #![allow(rust_2018_idioms, unused_qualifications)]
+
+    tonic::include_proto!("lunirelust.luna.v1");
+}
+
+mod convert;
+mod error;
+mod server;
+mod service;
+
+// Re-export commonly used items for convenience
+pub use server::serve_grpc;
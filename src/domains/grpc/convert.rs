@@ -0,0 +1,280 @@
+//! Conversions between protobuf messages and the luna DTOs.
+
+use super::proto;
+use crate::{
+    common::error::AppError,
+    domains::luna::dto::{
+        default_link_name, CreateDirectorDto, CreateGenreDto, CreateIdolDto, CreateLabelDto,
+        CreateLinkDto, CreateRecordDto, CreateSeriesDto, CreateStudioDto, DirectorDto, GenreDto,
+        IdolDto, IdolParticipationDto, LabelDto, LinkDto, MatchMode, PatchRecordDto, RecordDto,
+        RecordGenreDto, SearchRecordDto, SeriesDto, StudioDto,
+    },
+};
+use sea_orm::prelude::{Date, Decimal};
+
+/// Parse a `YYYY-MM-DD` field.
+fn parse_date(field: &str, value: &str) -> Result<Date, AppError> {
+    Date::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| AppError::ValidationError(format!("{field} must be a YYYY-MM-DD date")))
+}
+
+fn parse_optional_date(field: &str, value: Option<String>) -> Result<Option<Date>, AppError> {
+    value.map(|v| parse_date(field, &v)).transpose()
+}
+
+/// Render an ID list the way the REST query string carries it.
+fn join_ids(ids: &[i64]) -> Option<String> {
+    (!ids.is_empty()).then(|| ids.iter().map(i64::to_string).collect::<Vec<_>>().join(","))
+}
+
+macro_rules! named_entity_from {
+    ($($dto:ty),+) => {
+        $(
+            impl From<$dto> for proto::NamedEntity {
+                fn from(dto: $dto) -> Self {
+                    Self {
+                        id: dto.id,
+                        name: dto.name,
+                        link: dto.link,
+                        manual: dto.manual,
+                    }
+                }
+            }
+        )+
+    };
+}
+
+named_entity_from!(
+    DirectorDto,
+    StudioDto,
+    LabelDto,
+    SeriesDto,
+    GenreDto,
+    IdolDto
+);
+
+macro_rules! create_dto_from_input {
+    ($($dto:ident),+) => {
+        $(
+            impl From<proto::NamedEntityInput> for $dto {
+                fn from(input: proto::NamedEntityInput) -> Self {
+                    Self {
+                        name: input.name,
+                        link: input.link,
+                        manual: input.manual,
+                    }
+                }
+            }
+        )+
+    };
+}
+
+create_dto_from_input!(
+    CreateDirectorDto,
+    CreateStudioDto,
+    CreateLabelDto,
+    CreateSeriesDto,
+    CreateGenreDto,
+    CreateIdolDto
+);
+
+impl From<LinkDto> for proto::Link {
+    fn from(dto: LinkDto) -> Self {
+        Self {
+            id: dto.id,
+            name: dto.name,
+            size: dto.size.to_string(),
+            date: dto.date.to_string(),
+            link: dto.link,
+            star: dto.star,
+        }
+    }
+}
+
+impl From<RecordGenreDto> for proto::RecordGenre {
+    fn from(dto: RecordGenreDto) -> Self {
+        Self {
+            genre: Some(dto.genre.into()),
+            manual: dto.manual,
+        }
+    }
+}
+
+impl From<IdolParticipationDto> for proto::IdolParticipation {
+    fn from(dto: IdolParticipationDto) -> Self {
+        Self {
+            idol: Some(dto.idol.into()),
+            manual: dto.manual,
+        }
+    }
+}
+
+impl From<RecordDto> for proto::Record {
+    fn from(dto: RecordDto) -> Self {
+        Self {
+            id: dto.id,
+            title: dto.title,
+            date: dto.date.to_string(),
+            duration: dto.duration,
+            director: Some(dto.director.into()),
+            studio: Some(dto.studio.into()),
+            label: Some(dto.label.into()),
+            series: Some(dto.series.into()),
+            genres: dto.genres.into_iter().map(Into::into).collect(),
+            idols: dto.idols.into_iter().map(Into::into).collect(),
+            has_links: dto.has_links,
+            links: dto.links.into_iter().map(Into::into).collect(),
+            permission: dto.permission,
+            local_img_count: dto.local_img_count,
+            create_time: dto.create_time.to_string(),
+            update_time: dto.update_time.to_string(),
+            creator: dto.creator,
+            modified_by: dto.modified_by,
+            version: dto.version,
+        }
+    }
+}
+
+impl TryFrom<proto::LinkInput> for CreateLinkDto {
+    type Error = AppError;
+
+    fn try_from(input: proto::LinkInput) -> Result<Self, AppError> {
+        let size = input
+            .size
+            .map(|size| {
+                size.parse::<Decimal>().map_err(|_| {
+                    AppError::ValidationError("link size must be a decimal number".into())
+                })
+            })
+            .transpose()?;
+        Ok(Self {
+            name: input.name.unwrap_or_else(default_link_name),
+            size,
+            date: parse_optional_date("link date", input.date)?,
+            link: input.link,
+            star: input.star,
+        })
+    }
+}
+
+impl TryFrom<proto::RecordInput> for CreateRecordDto {
+    type Error = AppError;
+
+    fn try_from(input: proto::RecordInput) -> Result<Self, AppError> {
+        Ok(Self {
+            date: parse_date("date", &input.date)?,
+            id: input.id,
+            title: input.title,
+            duration: input.duration,
+            director: input.director.map(Into::into),
+            studio: input.studio.map(Into::into),
+            label: input.label.map(Into::into),
+            series: input.series.map(Into::into),
+            genres: input.genres.into_iter().map(Into::into).collect(),
+            idols: input.idols.into_iter().map(Into::into).collect(),
+            has_links: input.has_links,
+            links: input
+                .links
+                .into_iter()
+                .map(CreateLinkDto::try_from)
+                .collect::<Result<_, _>>()?,
+            permission: input.permission,
+            local_img_count: input.local_img_count,
+        })
+    }
+}
+
+impl TryFrom<proto::RecordPatch> for PatchRecordDto {
+    type Error = AppError;
+
+    fn try_from(patch: proto::RecordPatch) -> Result<Self, AppError> {
+        Ok(Self {
+            date: parse_optional_date("date", patch.date)?,
+            title: patch.title,
+            duration: patch.duration,
+            director_id: patch.director_id,
+            studio_id: patch.studio_id,
+            label_id: patch.label_id,
+            series_id: patch.series_id,
+            has_links: patch.has_links,
+            permission: patch.permission,
+            local_img_count: patch.local_img_count,
+        })
+    }
+}
+
+impl TryFrom<proto::RecordFilter> for SearchRecordDto {
+    type Error = AppError;
+
+    fn try_from(filter: proto::RecordFilter) -> Result<Self, AppError> {
+        Ok(Self {
+            id: filter.id,
+            title: filter.title,
+            director_id: filter.director_id,
+            studio_id: filter.studio_id,
+            label_id: filter.label_id,
+            series_id: filter.series_id,
+            genre_id: None,
+            genre_category_id: None,
+            idol_id: None,
+            genre_ids: join_ids(&filter.genre_ids),
+            idol_ids: join_ids(&filter.idol_ids),
+            tag_ids: None,
+            match_mode: filter.match_all.then_some(MatchMode::All),
+            search: filter.search,
+            date_from: parse_optional_date("date_from", filter.date_from)?,
+            date_to: parse_optional_date("date_to", filter.date_to)?,
+            modified_since: parse_optional_date("modified_since", filter.modified_since)?,
+            min_rating: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{proto, AppError, CreateLinkDto, SearchRecordDto};
+
+    #[test]
+    fn filter_converts_id_lists_and_dates() {
+        let search = SearchRecordDto::try_from(proto::RecordFilter {
+            genre_ids: vec![4, 2],
+            match_all: true,
+            date_from: Some("2024-01-31".to_owned()),
+            ..proto::RecordFilter::default()
+        })
+        .expect("filter converts");
+        assert_eq!(search.genre_ids.as_deref(), Some("4,2"));
+        assert_eq!(search.idol_ids, None);
+        assert_eq!(
+            search.date_from.map(|d| d.to_string()).as_deref(),
+            Some("2024-01-31")
+        );
+    }
+
+    #[test]
+    fn malformed_dates_are_rejected() {
+        let err = SearchRecordDto::try_from(proto::RecordFilter {
+            date_to: Some("31/01/2024".to_owned()),
+            ..proto::RecordFilter::default()
+        })
+        .expect_err("date is not YYYY-MM-DD");
+        assert!(matches!(err, AppError::ValidationError(_)));
+    }
+
+    #[test]
+    fn link_input_defaults_missing_name() {
+        let link = CreateLinkDto::try_from(proto::LinkInput {
+            size: Some("1.25".to_owned()),
+            link: "https://example.com/a".to_owned(),
+            ..proto::LinkInput::default()
+        })
+        .expect("link converts");
+        assert_eq!(link.name, "None");
+        assert_eq!(link.size.map(|s| s.to_string()).as_deref(), Some("1.25"));
+        assert!(CreateLinkDto::try_from(proto::LinkInput {
+            size: Some("big".to_owned()),
+            ..proto::LinkInput::default()
+        })
+        .is_err());
+    }
+}
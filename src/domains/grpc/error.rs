@@ -0,0 +1,61 @@
+use crate::common::error::AppError;
+use axum::http::StatusCode;
+use tonic::{Code, Status};
+
+/// The gRPC code closest to the HTTP status the REST API answers with.
+fn code_for(status: StatusCode) -> Code {
+    match status {
+        StatusCode::BAD_REQUEST | StatusCode::UNPROCESSABLE_ENTITY => Code::InvalidArgument,
+        StatusCode::PAYLOAD_TOO_LARGE => Code::OutOfRange,
+        StatusCode::UNAUTHORIZED => Code::Unauthenticated,
+        StatusCode::FORBIDDEN | StatusCode::LOCKED => Code::PermissionDenied,
+        StatusCode::NOT_FOUND => Code::NotFound,
+        StatusCode::CONFLICT => Code::AlreadyExists,
+        StatusCode::PRECONDITION_FAILED => Code::FailedPrecondition,
+        StatusCode::TOO_MANY_REQUESTS => Code::ResourceExhausted,
+        _ => Code::Internal,
+    }
+}
+
+/// Convert a service error into a gRPC status carrying its message.
+pub(super) fn to_status(err: AppError) -> Status {
+    let status = err.status_code();
+    if status.is_server_error() {
+        tracing::error!(?status, %err, "Server error");
+    }
+    Status::new(code_for(status), err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{to_status, AppError};
+    use tonic::Code;
+
+    #[test]
+    fn errors_map_to_matching_codes() {
+        let cases = [
+            (
+                AppError::ValidationError("bad".into()),
+                Code::InvalidArgument,
+            ),
+            (AppError::InvalidToken, Code::Unauthenticated),
+            (AppError::Forbidden, Code::PermissionDenied),
+            (
+                AppError::NotFound("Record not found".into()),
+                Code::NotFound,
+            ),
+            (AppError::Conflict("exists".into()), Code::AlreadyExists),
+            (AppError::PreconditionFailed(3), Code::FailedPrecondition),
+            (AppError::InternalError, Code::Internal),
+        ];
+        for (err, code) in cases {
+            assert_eq!(to_status(err).code(), code);
+        }
+    }
+
+    #[test]
+    fn status_keeps_the_error_message() {
+        let status = to_status(AppError::NotFound("Record not found".into()));
+        assert_eq!(status.message(), "Not found: Record not found");
+    }
+}
@@ -0,0 +1,21 @@
+use super::{proto::record_service_server::RecordServiceServer, service::RecordGrpcService};
+use crate::common::app_state::AppState;
+use std::future::Future;
+use tokio::net::TcpListener;
+use tokio_stream::wrappers::TcpListenerStream;
+use tonic::transport::Server;
+
+/// Serve the gRPC API on `listener` until `shutdown` resolves.
+///
+/// # Errors
+/// Returns an error if the server fails while accepting or serving connections.
+pub async fn serve_grpc(
+    state: AppState,
+    listener: TcpListener,
+    shutdown: impl Future<Output = ()>,
+) -> Result<(), tonic::transport::Error> {
+    Server::builder()
+        .add_service(RecordServiceServer::new(RecordGrpcService::new(state)))
+        .serve_with_incoming_shutdown(TcpListenerStream::new(listener), shutdown)
+        .await
+}
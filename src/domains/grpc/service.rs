@@ -0,0 +1,262 @@
+use super::{
+    error::to_status,
+    proto::{
+        self, record_service_server::RecordService, CreateRecordRequest, DeleteRecordRequest,
+        DeleteRecordResponse, GetRecordRequest, ListRecordsRequest, ListRecordsResponse,
+        StreamRecordsRequest, UpdateRecordRequest,
+    },
+};
+use crate::{
+    common::{
+        app_state::AppState,
+        config::DEFAULT_PAGE_SIZE,
+        error::AppError,
+        jwt::{authenticate, Claims, Role},
+    },
+    domains::luna::{
+        dto::{
+            CreateRecordDto, PaginationQuery, PatchRecordDto, RecordRelations, SearchRecordDto,
+            UserFilter,
+        },
+        RecordPermission,
+    },
+};
+use futures::stream::Stream;
+use std::pin::Pin;
+use tonic::{Request, Response, Status};
+use validator::Validate;
+
+/// Most records `StreamRecords` fetches per database round trip.
+const MAX_STREAM_BATCH_SIZE: i64 = 500;
+
+type RecordStream = Pin<Box<dyn Stream<Item = Result<proto::Record, Status>> + Send>>;
+
+/// `RecordService` backed by the luna record service.
+pub(super) struct RecordGrpcService {
+    state: AppState,
+}
+
+impl RecordGrpcService {
+    pub(super) fn new(state: AppState) -> Self {
+        Self { state }
+    }
+
+    /// Authenticate the call from its metadata, rejecting callers below `min`.
+    async fn authorize<T>(&self, request: &Request<T>, min: Role) -> Result<Claims, Status> {
+        let headers = request.metadata().clone().into_headers();
+        let client_ip = request.remote_addr().map(|addr| addr.ip().to_string());
+        let claims = authenticate(&self.state, &headers, client_ip)
+            .await
+            .map_err(to_status)?;
+        if claims.role < min {
+            return Err(to_status(AppError::Forbidden));
+        }
+        Ok(claims)
+    }
+}
+
+/// The list filters of `filter`, validated like `GET /cards/records` does.
+fn search_filter(filter: Option<proto::RecordFilter>) -> Result<SearchRecordDto, AppError> {
+    let search_dto = SearchRecordDto::try_from(filter.unwrap_or_default())?;
+    search_dto
+        .validate_date_range()
+        .map_err(AppError::ValidationError)?;
+    search_dto
+        .validate_id_lists()
+        .map_err(AppError::ValidationError)?;
+    Ok(search_dto)
+}
+
+/// Restrict listings to the records the caller may see.
+fn user_filter(claims: &Claims) -> UserFilter {
+    UserFilter {
+        user_id: claims.sub.clone(),
+        liked_only: false,
+        viewed_only: false,
+        favorites_only: false,
+        unseen_only: false,
+        max_permission: RecordPermission::clearance(claims.role),
+    }
+}
+
+/// Run the input's `validator` rules, reporting failures like the REST handlers.
+fn validate_input(input: &impl Validate) -> Result<(), Status> {
+    input.validate().map_err(|err| {
+        tracing::error!("Validation error: {err}");
+        to_status(AppError::InvalidInput(err))
+    })
+}
+
+#[tonic::async_trait]
+impl RecordService for RecordGrpcService {
+    type StreamRecordsStream = RecordStream;
+
+    async fn get_record(
+        &self,
+        request: Request<GetRecordRequest>,
+    ) -> Result<Response<proto::Record>, Status> {
+        let claims = self.authorize(&request, Role::Viewer).await?;
+        let record = self
+            .state
+            .luna_service
+            .record_service()
+            .get_record_by_id(&request.into_inner().id)
+            .await
+            .map_err(to_status)?;
+        if record.permission > RecordPermission::clearance(claims.role) {
+            return Err(to_status(AppError::Forbidden));
+        }
+        Ok(Response::new(record.into()))
+    }
+
+    async fn list_records(
+        &self,
+        request: Request<ListRecordsRequest>,
+    ) -> Result<Response<ListRecordsResponse>, Status> {
+        let claims = self.authorize(&request, Role::Viewer).await?;
+        let request = request.into_inner();
+        let search_dto = search_filter(request.filter).map_err(to_status)?;
+        let pagination = PaginationQuery {
+            limit: request.limit,
+            offset: request.offset,
+            liked_only: None,
+            viewed_only: None,
+            seen: None,
+            ordering: request.ordering,
+            cursor: request.cursor,
+        };
+        let page = self
+            .state
+            .luna_service
+            .record_service()
+            .get_record_list_paginated(
+                search_dto,
+                pagination,
+                Some(user_filter(&claims)),
+                RecordRelations::ALL,
+            )
+            .await
+            .map_err(to_status)?;
+        Ok(Response::new(ListRecordsResponse {
+            count: page.count,
+            next_cursor: page.next_cursor,
+            records: page.results.into_iter().map(Into::into).collect(),
+        }))
+    }
+
+    async fn stream_records(
+        &self,
+        request: Request<StreamRecordsRequest>,
+    ) -> Result<Response<Self::StreamRecordsStream>, Status> {
+        let claims = self.authorize(&request, Role::Viewer).await?;
+        let request = request.into_inner();
+        let search_dto = search_filter(request.filter).map_err(to_status)?;
+        let batch_size = request
+            .batch_size
+            .filter(|&size| size > 0)
+            .unwrap_or(DEFAULT_PAGE_SIZE as i64)
+            .min(MAX_STREAM_BATCH_SIZE);
+        let user_filter = user_filter(&claims);
+        let state = self.state.clone();
+
+        // Walk the keyset pages so each batch is one indexed seek, however
+        // far into the catalog the stream gets.
+        let stream = async_stream::try_stream! {
+            let mut cursor = String::new();
+            loop {
+                let pagination = PaginationQuery {
+                    limit: Some(batch_size),
+                    offset: None,
+                    liked_only: None,
+                    viewed_only: None,
+                    seen: None,
+                    ordering: None,
+                    cursor: Some(cursor),
+                };
+                let page = state
+                    .luna_service
+                    .record_service()
+                    .get_record_list_paginated(
+                        search_dto.clone(),
+                        pagination,
+                        Some(user_filter.clone()),
+                        RecordRelations::ALL,
+                    )
+                    .await
+                    .map_err(to_status)?;
+                for record in page.results {
+                    yield proto::Record::from(record);
+                }
+                match page.next_cursor {
+                    Some(next) => cursor = next,
+                    None => break,
+                }
+            }
+        };
+        Ok(Response::new(Box::pin(stream) as RecordStream))
+    }
+
+    async fn create_record(
+        &self,
+        request: Request<CreateRecordRequest>,
+    ) -> Result<Response<proto::Record>, Status> {
+        let claims = self.authorize(&request, Role::Editor).await?;
+        let input = request
+            .into_inner()
+            .record
+            .ok_or_else(|| Status::invalid_argument("record is required"))?;
+        let create_dto = CreateRecordDto::try_from(input).map_err(to_status)?;
+        validate_input(&create_dto)?;
+        let record = self
+            .state
+            .luna_service
+            .record_service()
+            .create_record(create_dto, &claims.sub)
+            .await
+            .map_err(to_status)?;
+        Ok(Response::new(record.into()))
+    }
+
+    async fn update_record(
+        &self,
+        request: Request<UpdateRecordRequest>,
+    ) -> Result<Response<proto::Record>, Status> {
+        let claims = self.authorize(&request, Role::Editor).await?;
+        let request = request.into_inner();
+        let patch_dto = request
+            .patch
+            .map(PatchRecordDto::try_from)
+            .transpose()
+            .map_err(to_status)?
+            .unwrap_or_default();
+        validate_input(&patch_dto)?;
+        let record = self
+            .state
+            .luna_service
+            .record_service()
+            .patch_record(
+                &request.id,
+                patch_dto,
+                request.expected_version,
+                &claims.sub,
+            )
+            .await
+            .map_err(to_status)?;
+        Ok(Response::new(record.into()))
+    }
+
+    async fn delete_record(
+        &self,
+        request: Request<DeleteRecordRequest>,
+    ) -> Result<Response<DeleteRecordResponse>, Status> {
+        self.authorize(&request, Role::Editor).await?;
+        let id = request.into_inner().id;
+        self.state
+            .luna_service
+            .record_service()
+            .delete_record(&id)
+            .await
+            .map_err(to_status)?;
+        Ok(Response::new(DeleteRecordResponse { id }))
+    }
+}
@@ -0,0 +1,30 @@
+//! Health domain: probes for orchestrators such as Kubernetes.
+//!
+//! `GET /healthz` only reports that the process is serving requests.
+//! `GET /readyz` checks the dependencies a request needs — the database, a
+//! writable private assets directory and an up-to-date schema — and answers
+//! 503 while any of them is down.
+//!
+//! `GET /admin/db/stats` reports the connection pool's live usage to admins.
+
+mod api {
+    mod handlers;
+    pub mod routes;
+}
+
+mod domain {
+    pub mod service;
+}
+
+pub mod dto {
+    pub mod health_dto;
+}
+
+mod infra {
+    pub mod impl_service;
+}
+
+// Re-export commonly used items for convenience
+pub use api::routes::{db_admin_routes, health_routes, HealthApiDoc};
+pub use domain::service::HealthServiceTrait;
+pub use infra::impl_service::HealthService;
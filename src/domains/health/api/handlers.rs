@@ -0,0 +1,64 @@
+use crate::common::{
+    app_state::AppState,
+    dto::{ApiResponse, RestApiResponse},
+};
+use crate::domains::health::dto::health_dto::{
+    DbPoolStats, HealthStatus, LivenessReport, ReadinessReport,
+};
+use axum::{extract::State, http::StatusCode, response::IntoResponse};
+
+/// Liveness probe: answers as long as the process serves requests.
+#[utoipa::path(
+    get,
+    path = "/healthz",
+    responses(
+        (status = 200, description = "The process is serving requests", body = ApiResponse<LivenessReport>)
+    ),
+    security(()),
+    tag = "Health"
+)]
+pub async fn liveness() -> impl IntoResponse {
+    RestApiResponse::success(LivenessReport {
+        status: HealthStatus::Up,
+    })
+}
+
+/// Readiness probe: checks the database, the private assets directory and
+/// the schema migrations, and reports each of them.
+#[utoipa::path(
+    get,
+    path = "/readyz",
+    responses(
+        (status = 200, description = "Every dependency is up", body = ApiResponse<ReadinessReport>),
+        (status = 503, description = "A dependency is down; the report says which", body = ApiResponse<ReadinessReport>)
+    ),
+    security(()),
+    tag = "Health"
+)]
+pub async fn readiness(State(state): State<AppState>) -> impl IntoResponse {
+    let report = state.health_service.readiness().await;
+    if report.status == HealthStatus::Up {
+        return (StatusCode::OK, RestApiResponse::success(report));
+    }
+    let status = StatusCode::SERVICE_UNAVAILABLE;
+    let mut response = RestApiResponse::success_with_message("not ready", report);
+    response.0.status = status.as_u16();
+    (status, response)
+}
+
+/// Reports live database pool usage next to the pool settings.
+#[utoipa::path(
+    get,
+    path = "/admin/db/stats",
+    responses(
+        (status = 200, description = "Pool usage and settings", body = ApiResponse<DbPoolStats>),
+        (status = 403, description = "Caller is not an admin")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Health"
+)]
+pub async fn db_pool_stats(State(state): State<AppState>) -> impl IntoResponse {
+    RestApiResponse::success(state.health_service.db_pool_stats())
+}
@@ -0,0 +1,49 @@
+use super::handlers::{
+    __path_db_pool_stats, __path_liveness, __path_readiness, db_pool_stats, liveness, readiness,
+};
+use crate::{
+    common::{
+        app_state::AppState,
+        jwt::{with_role, Role},
+    },
+    domains::health::dto::health_dto::{
+        ComponentHealth, DbPoolStats, HealthStatus, LivenessReport, MigrationHealth,
+        ReadinessReport,
+    },
+};
+use axum::{routing::get, Router};
+
+use utoipa::OpenApi;
+
+use crate::common::openapi::{ErrorResponsesAddon, SecurityAddon};
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(liveness, readiness, db_pool_stats),
+    components(schemas(
+        HealthStatus,
+        LivenessReport,
+        ComponentHealth,
+        MigrationHealth,
+        ReadinessReport,
+        DbPoolStats
+    )),
+    tags(
+        (name = "Health", description = "Liveness and readiness probes")
+    ),
+    modifiers(&SecurityAddon, &ErrorResponsesAddon)
+)]
+/// This struct is used to generate `OpenAPI` documentation for the health routes.
+pub struct HealthApiDoc;
+
+/// This function creates a router for the unauthenticated probe routes.
+pub fn health_routes() -> Router<AppState> {
+    Router::new()
+        .route("/healthz", get(liveness))
+        .route("/readyz", get(readiness))
+}
+
+/// This function creates a router for the admin database routes.
+pub fn db_admin_routes() -> Router<AppState> {
+    Router::new().route("/db/stats", with_role(Role::Admin, get(db_pool_stats)))
+}
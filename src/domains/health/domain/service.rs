@@ -0,0 +1,27 @@
+//! This module defines the `HealthServiceTrait` which checks the service's
+//! dependencies for the readiness probe.
+
+use std::sync::Arc;
+
+use sea_orm::DatabaseConnection;
+
+use crate::{
+    common::config::Config,
+    domains::health::dto::health_dto::{DbPoolStats, ReadinessReport},
+};
+
+#[async_trait::async_trait]
+/// Trait defining the contract for dependency checks.
+pub trait HealthServiceTrait: Send + Sync {
+    /// constructor for the service.
+    fn create_service(db: DatabaseConnection, config: Config) -> Arc<dyn HealthServiceTrait>
+    where
+        Self: Sized;
+
+    /// Checks every dependency; each check gives up after a timeout so a
+    /// hanging dependency cannot stall the probe.
+    async fn readiness(&self) -> ReadinessReport;
+
+    /// Reports the connection pool's current usage next to its settings.
+    fn db_pool_stats(&self) -> DbPoolStats;
+}
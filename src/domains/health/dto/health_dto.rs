@@ -0,0 +1,73 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// State of the service or one of its dependencies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthStatus {
+    Up,
+    Down,
+}
+
+/// Response of `GET /healthz`.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct LivenessReport {
+    pub status: HealthStatus,
+}
+
+/// Outcome of one dependency check.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ComponentHealth {
+    pub status: HealthStatus,
+    /// How long the check took, in milliseconds.
+    pub latency_ms: u64,
+    /// Why the check failed; absent when it passed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Schema migration state, as recorded in `seaql_migrations`.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct MigrationHealth {
+    /// `down` while migrations are pending or their state cannot be read.
+    pub status: HealthStatus,
+    /// Number of applied migrations.
+    pub applied: usize,
+    /// Names of the migrations not applied yet.
+    pub pending: Vec<String>,
+    /// Why the state could not be read; absent when it was.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Response of `GET /readyz`.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ReadinessReport {
+    /// `up` only when every component is up.
+    pub status: HealthStatus,
+    /// Database connectivity.
+    pub database: ComponentHealth,
+    /// Whether the private assets directory accepts writes.
+    pub assets: ComponentHealth,
+    pub migrations: MigrationHealth,
+}
+
+/// Response of `GET /admin/db/stats`: live usage and settings of the
+/// database connection pool.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DbPoolStats {
+    /// Open connections, idle or in use.
+    pub size: u32,
+    /// Open connections waiting for a query.
+    pub idle: u32,
+    /// Connections currently checked out by requests.
+    pub in_use: u32,
+    pub max_connections: u32,
+    pub min_connections: u32,
+    /// How long a request waits for a free connection, in seconds.
+    pub acquire_timeout_secs: u64,
+    /// Server-side statement timeout in milliseconds; 0 when disabled.
+    pub statement_timeout_ms: u64,
+    /// Queries slower than this are logged, in milliseconds; 0 when disabled.
+    pub slow_query_ms: u64,
+}
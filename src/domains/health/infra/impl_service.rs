@@ -0,0 +1,170 @@
+use std::{
+    fmt::Display,
+    future::Future,
+    path::Path,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use migration::{Migrator, MigratorTrait as _};
+use sea_orm::{DatabaseConnection, DbErr};
+use uuid::Uuid;
+
+use crate::{
+    common::config::Config,
+    domains::health::{
+        domain::service::HealthServiceTrait,
+        dto::health_dto::{
+            ComponentHealth, DbPoolStats, HealthStatus, MigrationHealth, ReadinessReport,
+        },
+    },
+};
+
+/// Longest a single dependency check may take before it counts as down.
+const CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Service struct for checking the service's dependencies.
+#[derive(Clone)]
+pub struct HealthService {
+    db: DatabaseConnection,
+    config: Config,
+}
+
+#[async_trait]
+impl HealthServiceTrait for HealthService {
+    fn create_service(db: DatabaseConnection, config: Config) -> Arc<dyn HealthServiceTrait> {
+        Arc::new(Self { db, config })
+    }
+
+    async fn readiness(&self) -> ReadinessReport {
+        let (database, assets, migrations) = tokio::join!(
+            timed(self.db.ping()),
+            timed(self.probe_assets()),
+            self.check_migrations(),
+        );
+        let status = if [database.status, assets.status, migrations.status]
+            .iter()
+            .all(|status| *status == HealthStatus::Up)
+        {
+            HealthStatus::Up
+        } else {
+            tracing::warn!(
+                ?database.error,
+                ?assets.error,
+                pending = ?migrations.pending,
+                "Readiness check failed"
+            );
+            HealthStatus::Down
+        };
+        ReadinessReport {
+            status,
+            database,
+            assets,
+            migrations,
+        }
+    }
+
+    fn db_pool_stats(&self) -> DbPoolStats {
+        let pool = self.db.get_postgres_connection_pool();
+        let size = pool.size();
+        let idle = u32::try_from(pool.num_idle()).unwrap_or(u32::MAX);
+        DbPoolStats {
+            size,
+            idle,
+            in_use: size.saturating_sub(idle),
+            max_connections: self.config.database_max_connections,
+            min_connections: self.config.database_min_connections,
+            acquire_timeout_secs: self.config.database_acquire_timeout_secs,
+            statement_timeout_ms: self.config.database_statement_timeout_ms,
+            slow_query_ms: self.config.database_slow_query_ms,
+        }
+    }
+}
+
+impl HealthService {
+    /// Creates and removes an empty file in the private assets directory.
+    async fn probe_assets(&self) -> std::io::Result<()> {
+        let probe =
+            Path::new(&self.config.assets_private_path).join(format!(".readyz-{}", Uuid::new_v4()));
+        tokio::fs::write(&probe, b"").await?;
+        tokio::fs::remove_file(&probe).await
+    }
+
+    async fn check_migrations(&self) -> MigrationHealth {
+        let state = tokio::time::timeout(CHECK_TIMEOUT, async {
+            let applied = Migrator::get_applied_migrations(&self.db).await?.len();
+            let pending = Migrator::get_pending_migrations(&self.db)
+                .await?
+                .iter()
+                .map(|m| m.name().to_owned())
+                .collect::<Vec<_>>();
+            Ok::<_, DbErr>((applied, pending))
+        })
+        .await;
+        match state {
+            Ok(Ok((applied, pending))) => MigrationHealth {
+                status: if pending.is_empty() {
+                    HealthStatus::Up
+                } else {
+                    HealthStatus::Down
+                },
+                applied,
+                pending,
+                error: None,
+            },
+            Ok(Err(err)) => migrations_unknown(err.to_string()),
+            Err(_) => migrations_unknown(timeout_message()),
+        }
+    }
+}
+
+fn migrations_unknown(error: String) -> MigrationHealth {
+    MigrationHealth {
+        status: HealthStatus::Down,
+        applied: 0,
+        pending: Vec::new(),
+        error: Some(error),
+    }
+}
+
+fn timeout_message() -> String {
+    format!("timed out after {}s", CHECK_TIMEOUT.as_secs())
+}
+
+/// Runs `check` under [`CHECK_TIMEOUT`], recording how long it took.
+async fn timed<E: Display>(check: impl Future<Output = Result<(), E>>) -> ComponentHealth {
+    let started = Instant::now();
+    let outcome = tokio::time::timeout(CHECK_TIMEOUT, check).await;
+    let latency_ms = u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX);
+    let error = match outcome {
+        Ok(Ok(())) => None,
+        Ok(Err(err)) => Some(err.to_string()),
+        Err(_) => Some(timeout_message()),
+    };
+    ComponentHealth {
+        status: if error.is_none() {
+            HealthStatus::Up
+        } else {
+            HealthStatus::Down
+        },
+        latency_ms,
+        error,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{timed, HealthStatus};
+
+    #[tokio::test]
+    async fn failing_checks_report_their_error() {
+        let health = timed(async { Err::<(), _>("connection refused") }).await;
+        assert_eq!(health.status, HealthStatus::Down);
+        assert_eq!(health.error.as_deref(), Some("connection refused"));
+
+        let health = timed(async { Ok::<(), String>(()) }).await;
+        assert_eq!(health.status, HealthStatus::Up);
+        assert_eq!(health.error, None);
+    }
+}
@@ -1,26 +1,52 @@
 mod api {
     mod handlers {
+        mod comment;
         mod director;
+        mod duplicate;
+        mod events;
+        mod export;
+        mod feed;
         mod genre;
         mod idol;
+        mod integrity;
         mod interaction_handlers;
         mod label;
+        mod link;
         mod media;
+        #[cfg(feature = "metadata")]
+        mod metadata;
         mod record;
+        mod revision;
+        mod saved_search;
         mod series;
         mod statistics;
         mod studio;
+        mod tag;
+        mod translation;
 
+        pub use comment::*;
         pub use director::*;
+        pub use duplicate::*;
+        pub use events::*;
+        pub use export::*;
+        pub use feed::*;
         pub use genre::*;
         pub use idol::*;
+        pub use integrity::*;
         pub use interaction_handlers::*;
         pub use label::*;
+        pub use link::*;
         pub use media::*;
+        #[cfg(feature = "metadata")]
+        pub use metadata::*;
         pub use record::*;
+        pub use revision::*;
+        pub use saved_search::*;
         pub use series::*;
         pub use statistics::*;
         pub use studio::*;
+        pub use tag::*;
+        pub use translation::*;
     }
     pub mod routes;
 }
@@ -39,13 +65,25 @@ mod domain {
     mod repository {
         //! This module defines repository traits for luna (cards) domain entities,
         //! which abstract the database operations.
+        pub(super) mod comment;
         pub(super) mod director;
+        pub(super) mod duplicate;
+        pub(super) mod export;
         pub(super) mod genre;
         pub(super) mod idol;
+        pub(super) mod integrity;
         pub(super) mod label;
+        pub(super) mod link;
+        pub(super) mod media_file;
+        pub(super) mod merge;
         pub(super) mod record;
+        pub(super) mod revision;
+        pub(super) mod saved_search;
         pub(super) mod series;
+        pub(super) mod statistics;
         pub(super) mod studio;
+        pub(super) mod tag;
+        pub(super) mod translation;
     }
 
     mod service;
@@ -54,76 +92,145 @@ mod domain {
         director::*, genre::*, idol::*, label::*, links::*, record::*, series::*, studio::*,
     };
     pub use service::{
-        director::DirectorServiceTrait, file::FileServiceTrait, genre::GenreServiceTrait,
-        idol::IdolServiceTrait, label::LabelServiceTrait, record::RecordServiceTrait,
-        series::SeriesServiceTrait, studio::StudioServiceTrait, LunaServiceTrait,
+        comment::CommentServiceTrait, director::DirectorServiceTrait,
+        duplicate::DuplicateServiceTrait, export::ExportServiceTrait, export::ExportStream,
+        file::FileServiceTrait, genre::GenreServiceTrait, idol::IdolServiceTrait,
+        integrity::IntegrityServiceTrait, label::LabelServiceTrait, link::LinkServiceTrait,
+        record::RecordServiceTrait, saved_search::SavedSearchServiceTrait,
+        series::SeriesServiceTrait, statistics::StatisticsServiceTrait, studio::StudioServiceTrait,
+        tag::TagServiceTrait, translation::TranslationServiceTrait, LunaServiceTrait,
     };
 
     pub use repository::{
-        director::DirectorAffinityRepository, director::DirectorRepository,
-        genre::GenreAffinityRepository, genre::GenreRepository, idol::IdolAffinityRepository,
-        idol::IdolRepository, label::LabelAffinityRepository, label::LabelRepository,
-        record::CreatedNestedEntities, record::RecordRepository, series::SeriesAffinityRepository,
-        series::SeriesRepository, studio::StudioAffinityRepository, studio::StudioRepository,
+        comment::CommentRepository, director::DirectorAffinityRepository,
+        director::DirectorRepository, duplicate::DuplicateRepository, export::ExportRepository,
+        export::NamedEntityRow, genre::GenreAffinityRepository, genre::GenreCategoryRepository,
+        genre::GenreRepository, idol::IdolAffinityRepository, idol::IdolProfileRepository,
+        idol::IdolRepository, integrity::IntegrityRepository, label::LabelAffinityRepository,
+        label::LabelRepository, link::LinkRepository, media_file::MediaFileRepository,
+        media_file::StoredMediaFile, merge::NamedEntityMergeRepository,
+        record::CreatedNestedEntities, record::DeletedRecordRows, record::RecordChanges,
+        record::RecordRelationRows, record::RecordRepository, revision::RevisionRepository,
+        saved_search::SavedSearchRepository, series::SeriesAffinityRepository,
+        series::SeriesRepository, statistics::StatisticsRepository,
+        studio::StudioAffinityRepository, studio::StudioHierarchyRepository,
+        studio::StudioRepository, tag::TagRepository, translation::TranslationRepository,
     };
 }
 
 pub mod dto {
+    mod autocomplete;
+    mod batch;
+    mod comment;
     mod director;
+    mod duplicate;
+    mod events;
+    mod export;
+    mod feed;
     mod genre;
     mod idol;
     mod image;
+    mod import;
+    mod integrity;
     mod label;
     mod link;
     mod media;
+    mod merge;
+    mod metadata;
     mod pagination;
     mod record;
+    mod revision;
+    mod saved_search;
     mod series;
     mod statistics;
     mod studio;
+    mod sync;
+    mod tag;
+    mod translation;
 
+    pub use autocomplete::*;
+    pub use batch::*;
+    pub use comment::*;
     pub use director::*;
+    pub use duplicate::*;
+    pub use events::*;
+    pub use export::*;
+    pub use feed::*;
     pub use genre::*;
     pub use idol::*;
     pub use image::*;
+    pub use import::*;
+    pub use integrity::*;
     pub use label::*;
     pub use link::*;
     pub use media::*;
+    pub use merge::*;
+    pub use metadata::*;
     pub use pagination::*;
     pub use record::*;
+    pub use revision::*;
+    pub use saved_search::*;
     pub use series::*;
     pub use statistics::*;
     pub use studio::*;
+    pub use sync::*;
+    pub use tag::*;
+    pub use translation::*;
 }
 
 pub(crate) mod infra {
     mod impl_repository {
         #[macro_use]
         mod entity_repo_macro;
+        pub(super) mod comment;
         pub(super) mod director;
+        pub(super) mod duplicate;
+        pub(super) mod export;
         pub(super) mod genre;
         pub(super) mod idol;
+        pub(super) mod integrity;
         pub(super) mod label;
+        pub(super) mod link;
+        pub(super) mod media_file;
         pub(super) mod record;
         pub(super) mod record_loader;
+        pub(super) mod revision;
+        pub(super) mod saved_search;
         pub(super) mod series;
+        pub(super) mod statistics;
         pub(super) mod studio;
+        pub(super) mod tag;
+        pub(super) mod translation;
     }
     pub use impl_repository::{
-        director::*, genre::*, idol::*, label::*, record::*, series::*, studio::*,
+        comment::*, director::*, duplicate::*, export::*, genre::*, idol::*, integrity::*,
+        label::*, link::*, media_file::*, record::*, revision::*, saved_search::*, series::*,
+        statistics::*, studio::*, tag::*, translation::*,
     };
 
+    pub mod catalog_cache;
+    pub mod catalog_events;
     pub mod impl_service;
+    pub mod jobs;
+    pub mod media_storage;
+    #[cfg(feature = "metadata")]
+    pub mod metadata;
     pub mod search_outbox;
 }
 
 // Re-export commonly used items for convenience
+#[cfg(feature = "metadata")]
+pub use api::routes::MetadataApiDoc;
 pub use api::routes::{luna_routes, LunaApiDoc};
 pub use domain::{
-    CreatedNestedEntities, DirectorAffinityRepository, FileServiceTrait, GenreAffinityRepository,
-    IdolAffinityRepository, LabelAffinityRepository, LunaServiceTrait, Record, RecordRepository,
-    RecordServiceTrait, SeriesAffinityRepository, StudioAffinityRepository,
+    CreatedNestedEntities, DeletedRecordRows, DirectorAffinityRepository, ExportStream,
+    FileServiceTrait, GenreAffinityRepository, IdolAffinityRepository, IdolRepository,
+    LabelAffinityRepository, LunaServiceTrait, Record, RecordChanges, RecordPermission,
+    RecordRelationRows, RecordRepository, RecordServiceTrait, SeriesAffinityRepository,
+    StudioAffinityRepository,
 };
+pub use infra::catalog_events::CatalogEvents;
 pub use infra::impl_service::LunaService;
+pub use infra::jobs::{LinkCheckJob, MediaGcJob};
 pub use infra::search_outbox::outbox_entity_upsert;
 pub use infra::IdolRepo;
@@ -0,0 +1,166 @@
+use crate::{
+    common::{
+        app_state::AppState,
+        dto::{ApiResponse, RestApiResponse},
+        error::AppError,
+        jwt::Claims,
+    },
+    domains::luna::{
+        dto::{CommentDto, CreateCommentDto, PaginatedResponse, PaginationQuery, UpdateCommentDto},
+        RecordPermission,
+    },
+};
+
+use axum::{
+    extract::{Path, Query, State},
+    response::IntoResponse,
+    Extension, Json,
+};
+
+use validator::Validate as _;
+
+/// Reject requests about records that do not exist or sit above the
+/// caller's clearance.
+pub(super) async fn ensure_record_visible(
+    state: &AppState,
+    claims: &Claims,
+    record_id: &str,
+) -> Result<(), AppError> {
+    let permission = state
+        .luna_service
+        .record_service()
+        .get_record_permission(record_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Record not found".into()))?;
+    if permission > RecordPermission::clearance(claims.role) {
+        return Err(AppError::Forbidden);
+    }
+    Ok(())
+}
+
+#[utoipa::path(
+    get,
+    path = "/cards/records/{id}/comments",
+    params(
+        ("id" = String, Path, description = "Record ID"),
+        ("limit" = Option<i64>, Query, description = "Limit for pagination"),
+        ("offset" = Option<i64>, Query, description = "Offset for pagination")
+    ),
+    responses(
+        (status = 200, description = "Comments on the record, oldest first", body = ApiResponse<PaginatedResponse<CommentDto>>),
+        (status = 403, description = "Record is above the caller's permission level"),
+        (status = 404, description = "Record not found")
+    ),
+    tag = "Records"
+)]
+pub async fn get_record_comments(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<String>,
+    Query(pagination): Query<PaginationQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_record_visible(&state, &claims, &id).await?;
+    let comments = state
+        .luna_service
+        .comment_service()
+        .list_comments(&id, pagination)
+        .await?;
+    Ok(RestApiResponse::success(comments))
+}
+
+#[utoipa::path(
+    post,
+    path = "/cards/records/{id}/comments",
+    params(("id" = String, Path, description = "Record ID")),
+    request_body = CreateCommentDto,
+    responses(
+        (status = 201, description = "Comment added", body = ApiResponse<CommentDto>),
+        (status = 400, description = "Empty or overlong comment"),
+        (status = 403, description = "Record is above the caller's permission level"),
+        (status = 404, description = "Record not found")
+    ),
+    tag = "Records"
+)]
+pub async fn create_record_comment(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<String>,
+    Json(payload): Json<CreateCommentDto>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate().map_err(|err| {
+        tracing::error!("Validation error: {err}");
+        AppError::InvalidInput(err)
+    })?;
+    ensure_record_visible(&state, &claims, &id).await?;
+
+    let comment = state
+        .luna_service
+        .comment_service()
+        .create_comment(&id, &claims.sub, payload)
+        .await?;
+    Ok(RestApiResponse::success(comment))
+}
+
+#[utoipa::path(
+    put,
+    path = "/cards/records/{id}/comments/{comment_id}",
+    params(
+        ("id" = String, Path, description = "Record ID"),
+        ("comment_id" = i64, Path, description = "Comment ID")
+    ),
+    request_body = UpdateCommentDto,
+    responses(
+        (status = 200, description = "Comment edited", body = ApiResponse<CommentDto>),
+        (status = 400, description = "Empty or overlong comment"),
+        (status = 403, description = "Not the author, or record is above the caller's permission level"),
+        (status = 404, description = "Record or comment not found")
+    ),
+    tag = "Records"
+)]
+pub async fn update_record_comment(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path((id, comment_id)): Path<(String, i64)>,
+    Json(payload): Json<UpdateCommentDto>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate().map_err(|err| {
+        tracing::error!("Validation error: {err}");
+        AppError::InvalidInput(err)
+    })?;
+    ensure_record_visible(&state, &claims, &id).await?;
+
+    let comment = state
+        .luna_service
+        .comment_service()
+        .update_comment(&id, comment_id, &claims.sub, payload)
+        .await?;
+    Ok(RestApiResponse::success(comment))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/cards/records/{id}/comments/{comment_id}",
+    params(
+        ("id" = String, Path, description = "Record ID"),
+        ("comment_id" = i64, Path, description = "Comment ID")
+    ),
+    responses(
+        (status = 204, description = "Comment deleted"),
+        (status = 403, description = "Not the author, or record is above the caller's permission level"),
+        (status = 404, description = "Record or comment not found")
+    ),
+    tag = "Records"
+)]
+pub async fn delete_record_comment(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path((id, comment_id)): Path<(String, i64)>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_record_visible(&state, &claims, &id).await?;
+    state
+        .luna_service
+        .comment_service()
+        .delete_comment(&id, comment_id, &claims.sub)
+        .await?;
+    Ok(RestApiResponse::success(()))
+}
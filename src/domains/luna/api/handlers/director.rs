@@ -1,63 +1,138 @@
 use crate::{
-    common::{app_state::AppState, dto::RestApiResponse, error::AppError, jwt::Claims},
+    common::{
+        app_state::AppState,
+        dto::{ApiResponse, RestApiResponse},
+        error::AppError,
+        jwt::Claims,
+    },
     domains::luna::dto::{
-        CreateDirectorDto, DirectorDto, PaginationQuery, SearchDirectorDto, UpdateDirectorDto,
+        AutocompleteQuery, BatchLookupDto, CreateDirectorDto, DirectorDto, MergeEntityDto,
+        MergeEntityResponse, NameSuggestionDto, PaginatedResponse, PaginationQuery,
+        PatchDirectorDto, SearchDirectorDto, UpdateDirectorDto,
     },
 };
 
-use axum::{extract::State, response::IntoResponse, Extension, Json};
+use axum::{extract::State, http::HeaderMap, response::IntoResponse, Extension, Json};
 
 use validator::Validate as _;
 
+use super::translation::localize_names;
+
 // Director handlers
 #[utoipa::path(
     get,
     path = "/cards/directors/{id}",
-    responses((status = 200, description = "Get director by ID", body = DirectorDto)),
+    responses(
+        (status = 200, description = "Get director by ID", body = ApiResponse<DirectorDto>),
+        (status = 304, description = "Not modified since the `If-None-Match` ETag")
+    ),
     tag = "Directors"
 )]
 pub async fn get_director_by_id(
     State(state): State<AppState>,
+    headers: HeaderMap,
     axum::extract::Path(id): axum::extract::Path<i64>,
 ) -> Result<impl IntoResponse, AppError> {
-    let director = state
+    let mut director = state
         .luna_service
         .director_service()
         .get_director_by_id(id)
         .await?;
-    Ok(RestApiResponse::success(director))
+    localize_names(&state, &headers, &mut director).await?;
+    RestApiResponse::success(director).into_conditional_response(&headers)
+}
+
+/// Looks up many directors in one request, in the order of the IDs sent.
+/// Unknown IDs are left out.
+#[utoipa::path(
+    post,
+    path = "/cards/directors/batch",
+    request_body = BatchLookupDto,
+    responses(
+        (status = 200, description = "The directors found", body = ApiResponse<Vec<DirectorDto>>),
+        (status = 400, description = "Empty or oversized ID list")
+    ),
+    tag = "Directors"
+)]
+pub async fn get_directors_by_ids(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<BatchLookupDto>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate().map_err(|err| {
+        tracing::error!("Validation error: {err}");
+        AppError::InvalidInput(err)
+    })?;
+
+    let mut directors = state
+        .luna_service
+        .director_service()
+        .get_directors_by_ids(&payload.ids)
+        .await?;
+    localize_names(&state, &headers, &mut directors).await?;
+    Ok(RestApiResponse::success(directors))
+}
+
+/// Suggests directors for the text typed so far: names starting with `q` first,
+/// then names containing it, each group by record count.
+#[utoipa::path(
+    get,
+    path = "/cards/directors/autocomplete",
+    params(AutocompleteQuery),
+    responses(
+        (status = 200, description = "Matching directors, best first", body = ApiResponse<Vec<NameSuggestionDto>>),
+        (status = 400, description = "Missing or blank `q`")
+    ),
+    tag = "Directors"
+)]
+pub async fn autocomplete_directors(
+    State(state): State<AppState>,
+    axum::extract::Query(query): axum::extract::Query<AutocompleteQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let suggestions = state
+        .luna_service
+        .director_service()
+        .autocomplete_directors(query)
+        .await?;
+    Ok(RestApiResponse::success(suggestions))
 }
 
 #[utoipa::path(
     get,
     path = "/cards/directors",
-    responses((status = 200, description = "List all directors", body = [DirectorDto])),
+    params(
+        SearchDirectorDto,
+        ("limit" = Option<i64>, Query, description = "Limit for pagination"),
+        ("offset" = Option<i64>, Query, description = "Offset for pagination"),
+        ("ordering" = Option<String>, Query, description = "Sort keys from `id`, `name`, `link`; prefix `-` for descending. Defaults to affinity order")
+    ),
+    responses(
+        (status = 200, description = "List all directors", body = ApiResponse<PaginatedResponse<DirectorDto>>),
+        (status = 304, description = "Not modified since the `If-None-Match` ETag")
+    ),
     tag = "Directors"
 )]
 pub async fn get_directors(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Extension(claims): Extension<Claims>,
     axum::extract::Query(pagination): axum::extract::Query<PaginationQuery>,
+    axum::extract::Query(search_dto): axum::extract::Query<SearchDirectorDto>,
 ) -> Result<impl IntoResponse, AppError> {
-    let search_dto = SearchDirectorDto {
-        id: None,
-        name: None,
-        link: None,
-    };
-
-    let paginated_result = state
+    let mut paginated_result = state
         .luna_service
         .director_service()
         .get_director_list_by_affinity(search_dto, pagination, claims.sub)
         .await?;
-    Ok(RestApiResponse::success(paginated_result))
+    localize_names(&state, &headers, &mut paginated_result).await?;
+    RestApiResponse::success(paginated_result).into_conditional_response(&headers)
 }
 
 #[utoipa::path(
     post,
     path = "/cards/directors",
     request_body = CreateDirectorDto,
-    responses((status = 201, description = "Create a new director", body = DirectorDto)),
+    responses((status = 201, description = "Create a new director", body = ApiResponse<DirectorDto>)),
     tag = "Directors"
 )]
 pub async fn create_director(
@@ -67,7 +142,7 @@ pub async fn create_director(
 ) -> Result<impl IntoResponse, AppError> {
     payload.validate().map_err(|err| {
         tracing::error!("Validation error: {err}");
-        AppError::ValidationError(format!("Invalid input: {err}"))
+        AppError::InvalidInput(err)
     })?;
 
     let director = state
@@ -82,7 +157,7 @@ pub async fn create_director(
     put,
     path = "/cards/directors/{id}",
     request_body = UpdateDirectorDto,
-    responses((status = 200, description = "Update director", body = DirectorDto)),
+    responses((status = 200, description = "Update director", body = ApiResponse<DirectorDto>)),
     tag = "Directors"
 )]
 pub async fn update_director(
@@ -93,7 +168,7 @@ pub async fn update_director(
 ) -> Result<impl IntoResponse, AppError> {
     payload.validate().map_err(|err| {
         tracing::error!("Validation error: {err}");
-        AppError::ValidationError(format!("Invalid input: {err}"))
+        AppError::InvalidInput(err)
     })?;
 
     let director = state
@@ -107,25 +182,25 @@ pub async fn update_director(
 #[utoipa::path(
     patch,
     path = "/cards/directors/{id}",
-    request_body = UpdateDirectorDto,
-    responses((status = 200, description = "Partially update director", body = DirectorDto)),
+    request_body = PatchDirectorDto,
+    responses((status = 200, description = "Partially update director", body = ApiResponse<DirectorDto>)),
     tag = "Directors"
 )]
 pub async fn patch_director(
     State(state): State<AppState>,
     Extension(_claims): Extension<Claims>,
     axum::extract::Path(id): axum::extract::Path<i64>,
-    Json(payload): Json<UpdateDirectorDto>,
+    Json(payload): Json<PatchDirectorDto>,
 ) -> Result<impl IntoResponse, AppError> {
     payload.validate().map_err(|err| {
         tracing::error!("Validation error: {err}");
-        AppError::ValidationError(format!("Invalid input: {err}"))
+        AppError::InvalidInput(err)
     })?;
 
     let director = state
         .luna_service
         .director_service()
-        .update_director(id, payload)
+        .update_director(id, payload.into_update(id))
         .await?;
     Ok(RestApiResponse::success(director))
 }
@@ -133,18 +208,44 @@ pub async fn patch_director(
 #[utoipa::path(
     delete,
     path = "/cards/directors/{id}",
-    responses((status = 204, description = "Director deleted")),
+    params(DeleteEntityQuery),
+    responses(
+        (status = 204, description = "Director deleted"),
+        (status = 409, description = "Still referenced by records; data lists their IDs", body = ApiResponse<Vec<String>>)
+    ),
     tag = "Directors"
 )]
 pub async fn delete_director(
     State(state): State<AppState>,
     Extension(_claims): Extension<Claims>,
     axum::extract::Path(id): axum::extract::Path<i64>,
+    axum::extract::Query(query): axum::extract::Query<DeleteEntityQuery>,
 ) -> Result<impl IntoResponse, AppError> {
     let message = state
         .luna_service
         .director_service()
-        .delete_director(id)
+        .delete_director(id, query.reassign_to)
         .await?;
     Ok(RestApiResponse::success_with_message(message, ()))
 }
+
+#[utoipa::path(
+    post,
+    path = "/cards/directors/{id}/merge",
+    request_body = MergeEntityDto,
+    responses((status = 200, description = "Duplicate director merged into this one", body = ApiResponse<MergeEntityResponse>)),
+    tag = "Directors"
+)]
+pub async fn merge_director(
+    State(state): State<AppState>,
+    Extension(_claims): Extension<Claims>,
+    axum::extract::Path(id): axum::extract::Path<i64>,
+    Json(body): Json<MergeEntityDto>,
+) -> Result<impl IntoResponse, AppError> {
+    let merged = state
+        .luna_service
+        .director_service()
+        .merge_director(id, body.source_id)
+        .await?;
+    Ok(RestApiResponse::success(merged))
+}
@@ -0,0 +1,61 @@
+use crate::{
+    common::{
+        app_state::AppState,
+        dto::{ApiResponse, RestApiResponse},
+        error::AppError,
+    },
+    domains::luna::dto::{
+        DuplicateGroupDto, DuplicateImageGroupDto, DuplicateImageQuery, DuplicateQuery,
+    },
+};
+
+use axum::{
+    extract::{Query, State},
+    response::IntoResponse,
+};
+
+#[utoipa::path(
+    get,
+    path = "/cards/admin/duplicates",
+    params(DuplicateQuery),
+    responses(
+        (status = 200, description = "Groups of probable duplicate records, most confident first", body = ApiResponse<Vec<DuplicateGroupDto>>),
+        (status = 400, description = "min_similarity outside (0, 1] or limit is 0"),
+        (status = 403, description = "Caller is not an admin")
+    ),
+    tag = "Admin"
+)]
+pub async fn get_duplicate_records(
+    State(state): State<AppState>,
+    Query(query): Query<DuplicateQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let groups = state
+        .luna_service
+        .duplicate_service()
+        .find_duplicates(query)
+        .await?;
+    Ok(RestApiResponse::success(groups))
+}
+
+#[utoipa::path(
+    get,
+    path = "/cards/admin/duplicate-images",
+    params(DuplicateImageQuery),
+    responses(
+        (status = 200, description = "Groups of stored images with identical content, most copies first", body = ApiResponse<Vec<DuplicateImageGroupDto>>),
+        (status = 400, description = "limit is 0"),
+        (status = 403, description = "Caller is not an admin")
+    ),
+    tag = "Admin"
+)]
+pub async fn get_duplicate_images(
+    State(state): State<AppState>,
+    Query(query): Query<DuplicateImageQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let groups = state
+        .luna_service
+        .duplicate_service()
+        .find_duplicate_images(query)
+        .await?;
+    Ok(RestApiResponse::success(groups))
+}
@@ -0,0 +1,96 @@
+use crate::{
+    common::{app_state::AppState, jwt::Claims},
+    domains::luna::{
+        dto::CATALOG_RESET_EVENT, infra::catalog_events::SequencedEvent, RecordPermission,
+    },
+};
+use axum::{
+    extract::State,
+    http::HeaderMap,
+    response::sse::{Event, KeepAlive, Sse},
+    Extension,
+};
+use futures::stream::Stream;
+use std::{convert::Infallible, pin::Pin, time::Duration};
+use tokio_stream::{
+    wrappers::{errors::BroadcastStreamRecvError, BroadcastStream},
+    StreamExt as _,
+};
+
+/// Interval between heartbeat comments on an idle stream.
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(15);
+/// Reconnection delay suggested to clients through the SSE `retry` field.
+const RECONNECT_DELAY: Duration = Duration::from_secs(3);
+
+type CatalogEventStream = Pin<Box<dyn Stream<Item = Result<Event, Infallible>> + Send>>;
+
+/// Render a catalog event as the caller with `clearance` sees it.
+fn sse_event(sequenced: SequencedEvent, clearance: i32) -> Event {
+    let event = sequenced.event.for_clearance(clearance);
+    let data = serde_json::to_string(&event).unwrap_or_default();
+    Event::default()
+        .id(sequenced.id)
+        .event(event.name())
+        .data(data)
+}
+
+/// Tell the client it missed events that cannot be replayed.
+fn reset_event(head: Option<String>) -> Event {
+    let event = Event::default().event(CATALOG_RESET_EVENT).data("{}");
+    match head {
+        Some(id) => event.id(id),
+        None => event,
+    }
+}
+
+/// Stream create, update and delete events for records and card entities
+///
+/// Each event is named `<entity>.<action>` (e.g. `record.updated`) and carries
+/// `{entity, id, action}`. A reconnecting client sends `Last-Event-ID` to
+/// replay what it missed; when that is no longer possible the stream opens
+/// with a `reset` event and the client should resync via `/cards/sync/records`.
+#[utoipa::path(
+    get,
+    path = "/cards/events",
+    params(
+        ("Last-Event-ID" = Option<String>, Header, description = "ID of the last event received")
+    ),
+    responses(
+        (status = 200, description = "SSE stream of catalog change events", content_type = "text/event-stream"),
+        (status = 401, description = "Unauthorized")
+    ),
+    tag = "Events"
+)]
+pub async fn stream_catalog_events(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    headers: HeaderMap,
+) -> Sse<CatalogEventStream> {
+    let clearance = RecordPermission::clearance(claims.role);
+    let last_event_id = headers.get("Last-Event-ID").and_then(|v| v.to_str().ok());
+    let subscription = state.luna_service.catalog_events().subscribe(last_event_id);
+    let head = subscription.head;
+    let replay = subscription.replay;
+    let mut live = BroadcastStream::new(subscription.receiver);
+
+    let stream = async_stream::stream! {
+        yield Ok(Event::default().comment("connected").retry(RECONNECT_DELAY));
+        match replay {
+            Some(missed) => {
+                for event in missed {
+                    yield Ok(sse_event(event, clearance));
+                }
+            }
+            None => yield Ok(reset_event(head)),
+        }
+        while let Some(item) = live.next().await {
+            match item {
+                Ok(event) => yield Ok(sse_event(event, clearance)),
+                Err(BroadcastStreamRecvError::Lagged(_)) => yield Ok(reset_event(None)),
+            }
+        }
+    };
+
+    Sse::new(Box::pin(stream) as CatalogEventStream)
+        .keep_alive(KeepAlive::new().interval(HEARTBEAT_INTERVAL))
+}
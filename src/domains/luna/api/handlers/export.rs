@@ -0,0 +1,149 @@
+use crate::{
+    common::{
+        app_state::AppState,
+        dto::{ApiResponse, RestApiResponse},
+        error::AppError,
+        jwt::Claims,
+    },
+    domains::luna::{
+        dto::{parse_import, ExportEntity, ExportFormat, ExportQuery, ImportQuery, ImportResponse},
+        ExportStream, RecordPermission,
+    },
+};
+use axum::{
+    body::Body,
+    extract::{Multipart, Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Extension,
+};
+
+/// Wrap an export stream in a download response named `{name}.{ext}`.
+fn export_response(
+    stream: ExportStream,
+    name: &str,
+    format: ExportFormat,
+) -> Result<Response, AppError> {
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, format.content_type())
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{name}.{}\"", format.extension()),
+        )
+        .body(Body::from_stream(stream))
+        .map_err(|err| {
+            tracing::error!("Error building response: {}", err);
+            AppError::InternalError
+        })
+}
+
+/// Stream every record visible to the caller as CSV or JSONL, ordered by ID.
+#[utoipa::path(
+    get,
+    path = "/cards/export/records",
+    params(ExportQuery),
+    responses(
+        (status = 200, description = "Record dump", content_type = ["text/csv", "application/x-ndjson"]),
+        (status = 400, description = "Unknown format")
+    ),
+    tag = "Export"
+)]
+pub async fn export_records(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Query(query): Query<ExportQuery>,
+) -> Result<Response, AppError> {
+    let format = query.format.unwrap_or_default();
+    let stream = state
+        .luna_service
+        .export_service()
+        .export_records(format, RecordPermission::clearance(claims.role));
+    export_response(stream, "records", format)
+}
+
+/// Stream every row of a named card entity as CSV or JSONL, ordered by ID.
+#[utoipa::path(
+    get,
+    path = "/cards/export/{entity}",
+    params(
+        ("entity" = ExportEntity, Path, description = "Entity to export"),
+        ExportQuery
+    ),
+    responses(
+        (status = 200, description = "Entity dump", content_type = ["text/csv", "application/x-ndjson"]),
+        (status = 400, description = "Unknown entity or format")
+    ),
+    tag = "Export"
+)]
+pub async fn export_entities(
+    State(state): State<AppState>,
+    Path(entity): Path<ExportEntity>,
+    Query(query): Query<ExportQuery>,
+) -> Result<Response, AppError> {
+    let format = query.format.unwrap_or_default();
+    let stream = state
+        .luna_service
+        .export_service()
+        .export_entities(entity, format);
+    export_response(stream, entity.name(), format)
+}
+
+/// Import a CSV or JSONL dump as produced by `/cards/export/records`
+///
+/// The multipart `file` field holds the dump. JSONL lines may also use the
+/// `POST /cards/records` body shape. Each row is created, updated, skipped or
+/// reported as an error independently of the others.
+#[utoipa::path(
+    post,
+    path = "/cards/import",
+    params(ImportQuery),
+    request_body(
+        content = String,
+        description = "Multipart form data with a 'file' field",
+        content_type = "multipart/form-data"
+    ),
+    responses(
+        (status = 200, description = "Per-row import report", body = ApiResponse<ImportResponse>),
+        (status = 400, description = "Missing file, unreadable dump or too many rows")
+    ),
+    tag = "Export"
+)]
+pub async fn import_records(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Query(query): Query<ImportQuery>,
+    mut multipart: Multipart,
+) -> Result<impl IntoResponse, AppError> {
+    let mut upload: Option<(Option<String>, String)> = None;
+    while let Some(field) = multipart.next_field().await.map_err(|e| {
+        AppError::ValidationError(format!("Failed to parse multipart form data: {e}"))
+    })? {
+        if field.name() != Some("file") {
+            continue;
+        }
+        let file_name = field.file_name().map(str::to_owned);
+        let data = field
+            .bytes()
+            .await
+            .map_err(|e| AppError::ValidationError(format!("Failed to read file data: {e}")))?;
+        let text = String::from_utf8(data.to_vec())
+            .map_err(|e| AppError::ValidationError(format!("Invalid UTF-8 in file: {e}")))?;
+        upload = Some((file_name, text));
+    }
+    let (file_name, text) = upload
+        .ok_or_else(|| AppError::ValidationError("Missing 'file' field in form data".to_owned()))?;
+
+    let format = query
+        .format
+        .or_else(|| file_name.as_deref().and_then(ExportFormat::from_file_name))
+        .unwrap_or_default();
+    let rows = parse_import(format, &text).map_err(AppError::ValidationError)?;
+
+    let report = state
+        .luna_service
+        .record_service()
+        .import_records(rows, query.on_conflict, &claims.sub)
+        .await?;
+    Ok(RestApiResponse::success(report))
+}
@@ -0,0 +1,154 @@
+use crate::{
+    common::{
+        app_state::AppState,
+        error::AppError,
+        etag::{if_none_match, weak_etag},
+        jwt::Claims,
+    },
+    domains::luna::{
+        dto::{FeedQuery, JsonFeed},
+        RecordPermission,
+    },
+};
+use axum::{
+    extract::{OriginalUri, Query, State},
+    http::{
+        header::{CACHE_CONTROL, CONTENT_TYPE, ETAG, HOST, IF_NONE_MATCH},
+        HeaderMap, HeaderValue, StatusCode,
+    },
+    response::{IntoResponse as _, Response},
+    Extension,
+};
+
+/// Scheme and host the client reached us at, for absolute feed URLs.
+/// `X-Forwarded-Proto`/`-Host` are honoured only behind a trusted proxy.
+fn base_url(state: &AppState, headers: &HeaderMap) -> String {
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+    let forwarded = |name: &str| {
+        state
+            .config
+            .trust_forwarded_for
+            .then(|| header(name))
+            .flatten()
+            .and_then(|v| v.split(',').next())
+            .map(str::trim)
+    };
+    let scheme = forwarded("X-Forwarded-Proto").unwrap_or("http");
+    let host = forwarded("X-Forwarded-Host")
+        .or_else(|| header(HOST.as_str()))
+        .map_or_else(
+            || {
+                format!(
+                    "{}:{}",
+                    state.config.service_host, state.config.service_port
+                )
+            },
+            str::to_owned,
+        );
+    format!("{scheme}://{host}")
+}
+
+/// Build the feed of the most recent records visible to the caller.
+async fn recent_feed(
+    state: &AppState,
+    claims: &Claims,
+    headers: &HeaderMap,
+    uri: &OriginalUri,
+    query: FeedQuery,
+) -> Result<JsonFeed, AppError> {
+    let records = state
+        .luna_service
+        .record_service()
+        .get_recent_records(query.limit, RecordPermission::clearance(claims.role))
+        .await?;
+    let base = base_url(state, headers);
+    Ok(JsonFeed::from_records(
+        records,
+        &base,
+        format!("{base}{}", uri.0),
+        state.config.thumbnail_list_size,
+    ))
+}
+
+/// Serve a rendered feed with a weak `ETag`, answering `304 Not Modified`
+/// when the client's copy is current.
+fn feed_response(
+    headers: &HeaderMap,
+    body: Vec<u8>,
+    content_type: &'static str,
+) -> Result<Response, AppError> {
+    let etag = weak_etag(&body);
+    let etag_value = HeaderValue::from_str(&etag).map_err(|_| AppError::InternalError)?;
+    let not_modified = headers
+        .get(IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| if_none_match(v, &etag));
+
+    let mut response = if not_modified {
+        StatusCode::NOT_MODIFIED.into_response()
+    } else {
+        let mut response = body.into_response();
+        response
+            .headers_mut()
+            .insert(CONTENT_TYPE, HeaderValue::from_static(content_type));
+        response
+    };
+    let response_headers = response.headers_mut();
+    response_headers.insert(ETAG, etag_value);
+    response_headers.insert(CACHE_CONTROL, HeaderValue::from_static("private, no-cache"));
+    Ok(response)
+}
+
+/// Atom feed of the most recently created or updated records
+#[utoipa::path(
+    get,
+    path = "/cards/feeds/recent.atom",
+    params(FeedQuery),
+    responses(
+        (status = 200, description = "Atom feed", content_type = "application/atom+xml"),
+        (status = 304, description = "Feed unchanged since the `If-None-Match` tag"),
+        (status = 400, description = "Invalid limit")
+    ),
+    tag = "Feeds"
+)]
+pub async fn recent_atom_feed(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    headers: HeaderMap,
+    uri: OriginalUri,
+    Query(query): Query<FeedQuery>,
+) -> Result<Response, AppError> {
+    let feed = recent_feed(&state, &claims, &headers, &uri, query).await?;
+    feed_response(
+        &headers,
+        feed.to_atom().into_bytes(),
+        "application/atom+xml; charset=utf-8",
+    )
+}
+
+/// JSON Feed of the most recently created or updated records
+#[utoipa::path(
+    get,
+    path = "/cards/feeds/recent.json",
+    params(FeedQuery),
+    responses(
+        (status = 200, description = "JSON Feed 1.1 document", body = JsonFeed, content_type = "application/feed+json"),
+        (status = 304, description = "Feed unchanged since the `If-None-Match` tag"),
+        (status = 400, description = "Invalid limit")
+    ),
+    tag = "Feeds"
+)]
+pub async fn recent_json_feed(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    headers: HeaderMap,
+    uri: OriginalUri,
+    Query(query): Query<FeedQuery>,
+) -> Result<Response, AppError> {
+    let feed = recent_feed(&state, &claims, &headers, &uri, query).await?;
+    let body = serde_json::to_vec(&feed).map_err(|err| {
+        tracing::error!("Failed to serialize feed: {err}");
+        AppError::InternalError
+    })?;
+    feed_response(&headers, body, "application/feed+json")
+}
@@ -1,63 +1,139 @@
 use crate::{
-    common::{app_state::AppState, dto::RestApiResponse, error::AppError, jwt::Claims},
+    common::{
+        app_state::AppState,
+        dto::{ApiResponse, RestApiResponse},
+        error::AppError,
+        jwt::Claims,
+    },
     domains::luna::dto::{
-        CreateGenreDto, GenreDto, PaginationQuery, SearchGenreDto, UpdateGenreDto,
+        AutocompleteQuery, BatchLookupDto, CreateGenreCategoryDto, CreateGenreDto,
+        GenreCategoryAssignmentDto, GenreCategoryDto, GenreDto, MergeEntityDto,
+        MergeEntityResponse, NameSuggestionDto, PaginatedResponse, PaginationQuery, PatchGenreDto,
+        SearchGenreDto, UpdateGenreDto,
     },
 };
 
-use axum::{extract::State, response::IntoResponse, Extension, Json};
+use axum::{extract::State, http::HeaderMap, response::IntoResponse, Extension, Json};
 
 use validator::Validate as _;
 
+use super::translation::localize_names;
+
 // Genre handlers
 #[utoipa::path(
     get,
     path = "/cards/genres/{id}",
-    responses((status = 200, description = "Get genre by ID", body = GenreDto)),
+    responses(
+        (status = 200, description = "Get genre by ID", body = ApiResponse<GenreDto>),
+        (status = 304, description = "Not modified since the `If-None-Match` ETag")
+    ),
     tag = "Genres"
 )]
 pub async fn get_genre_by_id(
     State(state): State<AppState>,
+    headers: HeaderMap,
     axum::extract::Path(id): axum::extract::Path<i64>,
 ) -> Result<impl IntoResponse, AppError> {
-    let genre = state
+    let mut genre = state
         .luna_service
         .genre_service()
         .get_genre_by_id(id)
         .await?;
-    Ok(RestApiResponse::success(genre))
+    localize_names(&state, &headers, &mut genre).await?;
+    RestApiResponse::success(genre).into_conditional_response(&headers)
+}
+
+/// Looks up many genres in one request, in the order of the IDs sent.
+/// Unknown IDs are left out.
+#[utoipa::path(
+    post,
+    path = "/cards/genres/batch",
+    request_body = BatchLookupDto,
+    responses(
+        (status = 200, description = "The genres found", body = ApiResponse<Vec<GenreDto>>),
+        (status = 400, description = "Empty or oversized ID list")
+    ),
+    tag = "Genres"
+)]
+pub async fn get_genres_by_ids(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<BatchLookupDto>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate().map_err(|err| {
+        tracing::error!("Validation error: {err}");
+        AppError::InvalidInput(err)
+    })?;
+
+    let mut genres = state
+        .luna_service
+        .genre_service()
+        .get_genres_by_ids(&payload.ids)
+        .await?;
+    localize_names(&state, &headers, &mut genres).await?;
+    Ok(RestApiResponse::success(genres))
+}
+
+/// Suggests genres for the text typed so far: names starting with `q` first,
+/// then names containing it, each group by record count.
+#[utoipa::path(
+    get,
+    path = "/cards/genres/autocomplete",
+    params(AutocompleteQuery),
+    responses(
+        (status = 200, description = "Matching genres, best first", body = ApiResponse<Vec<NameSuggestionDto>>),
+        (status = 400, description = "Missing or blank `q`")
+    ),
+    tag = "Genres"
+)]
+pub async fn autocomplete_genres(
+    State(state): State<AppState>,
+    axum::extract::Query(query): axum::extract::Query<AutocompleteQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let suggestions = state
+        .luna_service
+        .genre_service()
+        .autocomplete_genres(query)
+        .await?;
+    Ok(RestApiResponse::success(suggestions))
 }
 
 #[utoipa::path(
     get,
     path = "/cards/genres",
-    responses((status = 200, description = "List all genres", body = [GenreDto])),
+    params(
+        SearchGenreDto,
+        ("limit" = Option<i64>, Query, description = "Limit for pagination"),
+        ("offset" = Option<i64>, Query, description = "Offset for pagination"),
+        ("ordering" = Option<String>, Query, description = "Sort keys from `id`, `name`, `link`; prefix `-` for descending. Defaults to affinity order")
+    ),
+    responses(
+        (status = 200, description = "List all genres", body = ApiResponse<PaginatedResponse<GenreDto>>),
+        (status = 304, description = "Not modified since the `If-None-Match` ETag")
+    ),
     tag = "Genres"
 )]
 pub async fn get_genres(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Extension(claims): Extension<Claims>,
     axum::extract::Query(pagination): axum::extract::Query<PaginationQuery>,
+    axum::extract::Query(search_dto): axum::extract::Query<SearchGenreDto>,
 ) -> Result<impl IntoResponse, AppError> {
-    let search_dto = SearchGenreDto {
-        id: None,
-        name: None,
-        link: None,
-    };
-
-    let paginated_result = state
+    let mut paginated_result = state
         .luna_service
         .genre_service()
         .get_genre_list_by_affinity(search_dto, pagination, claims.sub)
         .await?;
-    Ok(RestApiResponse::success(paginated_result))
+    localize_names(&state, &headers, &mut paginated_result).await?;
+    RestApiResponse::success(paginated_result).into_conditional_response(&headers)
 }
 
 #[utoipa::path(
     post,
     path = "/cards/genres",
     request_body = CreateGenreDto,
-    responses((status = 201, description = "Create a new genre", body = GenreDto)),
+    responses((status = 201, description = "Create a new genre", body = ApiResponse<GenreDto>)),
     tag = "Genres"
 )]
 pub async fn create_genre(
@@ -67,7 +143,7 @@ pub async fn create_genre(
 ) -> Result<impl IntoResponse, AppError> {
     payload.validate().map_err(|err| {
         tracing::error!("Validation error: {err}");
-        AppError::ValidationError(format!("Invalid input: {err}"))
+        AppError::InvalidInput(err)
     })?;
 
     let genre = state
@@ -82,7 +158,7 @@ pub async fn create_genre(
     put,
     path = "/cards/genres/{id}",
     request_body = UpdateGenreDto,
-    responses((status = 200, description = "Update genre", body = GenreDto)),
+    responses((status = 200, description = "Update genre", body = ApiResponse<GenreDto>)),
     tag = "Genres"
 )]
 pub async fn update_genre(
@@ -93,7 +169,7 @@ pub async fn update_genre(
 ) -> Result<impl IntoResponse, AppError> {
     payload.validate().map_err(|err| {
         tracing::error!("Validation error: {err}");
-        AppError::ValidationError(format!("Invalid input: {err}"))
+        AppError::InvalidInput(err)
     })?;
 
     let genre = state
@@ -107,25 +183,25 @@ pub async fn update_genre(
 #[utoipa::path(
     patch,
     path = "/cards/genres/{id}",
-    request_body = UpdateGenreDto,
-    responses((status = 200, description = "Partially update genre", body = GenreDto)),
+    request_body = PatchGenreDto,
+    responses((status = 200, description = "Partially update genre", body = ApiResponse<GenreDto>)),
     tag = "Genres"
 )]
 pub async fn patch_genre(
     State(state): State<AppState>,
     Extension(_claims): Extension<Claims>,
     axum::extract::Path(id): axum::extract::Path<i64>,
-    Json(payload): Json<UpdateGenreDto>,
+    Json(payload): Json<PatchGenreDto>,
 ) -> Result<impl IntoResponse, AppError> {
     payload.validate().map_err(|err| {
         tracing::error!("Validation error: {err}");
-        AppError::ValidationError(format!("Invalid input: {err}"))
+        AppError::InvalidInput(err)
     })?;
 
     let genre = state
         .luna_service
         .genre_service()
-        .update_genre(id, payload)
+        .update_genre(id, payload.into_update(id))
         .await?;
     Ok(RestApiResponse::success(genre))
 }
@@ -144,3 +220,174 @@ pub async fn delete_genre(
     let message = state.luna_service.genre_service().delete_genre(id).await?;
     Ok(RestApiResponse::success_with_message(message, ()))
 }
+
+#[utoipa::path(
+    post,
+    path = "/cards/genres/{id}/merge",
+    request_body = MergeEntityDto,
+    responses((status = 200, description = "Duplicate genre merged into this one", body = ApiResponse<MergeEntityResponse>)),
+    tag = "Genres"
+)]
+pub async fn merge_genre(
+    State(state): State<AppState>,
+    Extension(_claims): Extension<Claims>,
+    axum::extract::Path(id): axum::extract::Path<i64>,
+    Json(body): Json<MergeEntityDto>,
+) -> Result<impl IntoResponse, AppError> {
+    let merged = state
+        .luna_service
+        .genre_service()
+        .merge_genre(id, body.source_id)
+        .await?;
+    Ok(RestApiResponse::success(merged))
+}
+
+/// Puts a genre in a genre category, or leaves it uncategorized.
+#[utoipa::path(
+    put,
+    path = "/cards/genres/{id}/category",
+    request_body = GenreCategoryAssignmentDto,
+    params(("id" = i64, Path, description = "Genre ID")),
+    responses(
+        (status = 200, description = "Genre category set", body = ApiResponse<GenreDto>),
+        (status = 400, description = "Unknown genre category"),
+        (status = 403, description = "Caller is not an editor"),
+        (status = 404, description = "Genre not found")
+    ),
+    tag = "Genres"
+)]
+pub async fn set_genre_category(
+    State(state): State<AppState>,
+    axum::extract::Path(id): axum::extract::Path<i64>,
+    Json(body): Json<GenreCategoryAssignmentDto>,
+) -> Result<impl IntoResponse, AppError> {
+    let genre = state
+        .luna_service
+        .genre_service()
+        .set_genre_category(id, body.category_id)
+        .await?;
+    Ok(RestApiResponse::success(genre))
+}
+
+// Genre category handlers
+#[utoipa::path(
+    get,
+    path = "/cards/genres/categories",
+    responses((status = 200, description = "List genre categories by name", body = ApiResponse<Vec<GenreCategoryDto>>)),
+    tag = "Genres"
+)]
+pub async fn get_genre_categories(
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, AppError> {
+    let categories = state
+        .luna_service
+        .genre_service()
+        .list_genre_categories()
+        .await?;
+    Ok(RestApiResponse::success(categories))
+}
+
+#[utoipa::path(
+    get,
+    path = "/cards/genres/categories/{id}",
+    params(("id" = i64, Path, description = "Genre category ID")),
+    responses(
+        (status = 200, description = "Get genre category by ID", body = ApiResponse<GenreCategoryDto>),
+        (status = 404, description = "Genre category not found")
+    ),
+    tag = "Genres"
+)]
+pub async fn get_genre_category_by_id(
+    State(state): State<AppState>,
+    axum::extract::Path(id): axum::extract::Path<i64>,
+) -> Result<impl IntoResponse, AppError> {
+    let category = state
+        .luna_service
+        .genre_service()
+        .get_genre_category(id)
+        .await?;
+    Ok(RestApiResponse::success(category))
+}
+
+#[utoipa::path(
+    post,
+    path = "/cards/genres/categories",
+    request_body = CreateGenreCategoryDto,
+    responses(
+        (status = 201, description = "Create a new genre category", body = ApiResponse<GenreCategoryDto>),
+        (status = 403, description = "Caller is not an editor"),
+        (status = 409, description = "A genre category with this name exists")
+    ),
+    tag = "Genres"
+)]
+pub async fn create_genre_category(
+    State(state): State<AppState>,
+    Json(payload): Json<CreateGenreCategoryDto>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate().map_err(|err| {
+        tracing::error!("Validation error: {err}");
+        AppError::InvalidInput(err)
+    })?;
+
+    let category = state
+        .luna_service
+        .genre_service()
+        .create_genre_category(payload)
+        .await?;
+    Ok(RestApiResponse::success(category))
+}
+
+#[utoipa::path(
+    put,
+    path = "/cards/genres/categories/{id}",
+    request_body = CreateGenreCategoryDto,
+    params(("id" = i64, Path, description = "Genre category ID")),
+    responses(
+        (status = 200, description = "Update genre category", body = ApiResponse<GenreCategoryDto>),
+        (status = 403, description = "Caller is not an editor"),
+        (status = 404, description = "Genre category not found"),
+        (status = 409, description = "A genre category with this name exists")
+    ),
+    tag = "Genres"
+)]
+pub async fn update_genre_category(
+    State(state): State<AppState>,
+    axum::extract::Path(id): axum::extract::Path<i64>,
+    Json(payload): Json<CreateGenreCategoryDto>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate().map_err(|err| {
+        tracing::error!("Validation error: {err}");
+        AppError::InvalidInput(err)
+    })?;
+
+    let category = state
+        .luna_service
+        .genre_service()
+        .update_genre_category(id, payload)
+        .await?;
+    Ok(RestApiResponse::success(category))
+}
+
+/// Deletes a genre category. Its genres are kept, uncategorized.
+#[utoipa::path(
+    delete,
+    path = "/cards/genres/categories/{id}",
+    params(("id" = i64, Path, description = "Genre category ID")),
+    responses(
+        (status = 204, description = "Genre category deleted"),
+        (status = 403, description = "Caller is not an editor"),
+        (status = 404, description = "Genre category not found")
+    ),
+    tag = "Genres"
+)]
+pub async fn delete_genre_category(
+    State(state): State<AppState>,
+    axum::extract::Path(id): axum::extract::Path<i64>,
+) -> Result<impl IntoResponse, AppError> {
+    state
+        .luna_service
+        .genre_service()
+        .delete_genre_category(id)
+        .await?;
+    Ok(RestApiResponse::success(()))
+}
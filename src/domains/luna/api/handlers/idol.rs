@@ -1,60 +1,134 @@
 use crate::{
-    common::{app_state::AppState, dto::RestApiResponse, error::AppError, jwt::Claims},
+    common::{
+        app_state::AppState,
+        dto::{ApiResponse, RestApiResponse},
+        error::AppError,
+        jwt::Claims,
+    },
     domains::luna::dto::{
-        CreateIdolDto, IdolDto, IdolWithoutImageDto, PaginationQuery, SearchIdolDto, UpdateIdolDto,
+        AutocompleteQuery, BatchLookupDto, CreateIdolDto, IdolDto, IdolProfileDto,
+        IdolWithoutImageDto, MergeEntityDto, MergeEntityResponse, NameSuggestionDto,
+        PaginatedResponse, PaginationQuery, PatchIdolDto, SearchIdolDto, UpdateIdolDto,
     },
 };
 
-use axum::{extract::State, response::IntoResponse, Extension, Json};
+use axum::{extract::State, http::HeaderMap, response::IntoResponse, Extension, Json};
 
 use validator::Validate as _;
 
+use super::translation::localize_names;
+
 // Idol handlers
 #[utoipa::path(
     get,
     path = "/cards/idols/{id}",
-    responses((status = 200, description = "Get idol by ID", body = IdolDto)),
+    responses(
+        (status = 200, description = "Get idol by ID", body = ApiResponse<IdolDto>),
+        (status = 304, description = "Not modified since the `If-None-Match` ETag")
+    ),
     tag = "Idols"
 )]
 pub async fn get_idol_by_id(
     State(state): State<AppState>,
+    headers: HeaderMap,
     axum::extract::Path(id): axum::extract::Path<i64>,
 ) -> Result<impl IntoResponse, AppError> {
-    let idol = state.luna_service.idol_service().get_idol_by_id(id).await?;
-    Ok(RestApiResponse::success(idol))
+    let mut idol = state.luna_service.idol_service().get_idol_by_id(id).await?;
+    localize_names(&state, &headers, &mut idol).await?;
+    RestApiResponse::success(idol).into_conditional_response(&headers)
+}
+
+/// Looks up many idols in one request, in the order of the IDs sent.
+/// Unknown IDs are left out.
+#[utoipa::path(
+    post,
+    path = "/cards/idols/batch",
+    request_body = BatchLookupDto,
+    responses(
+        (status = 200, description = "The idols found", body = ApiResponse<Vec<IdolDto>>),
+        (status = 400, description = "Empty or oversized ID list")
+    ),
+    tag = "Idols"
+)]
+pub async fn get_idols_by_ids(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<BatchLookupDto>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate().map_err(|err| {
+        tracing::error!("Validation error: {err}");
+        AppError::InvalidInput(err)
+    })?;
+
+    let mut idols = state
+        .luna_service
+        .idol_service()
+        .get_idols_by_ids(&payload.ids)
+        .await?;
+    localize_names(&state, &headers, &mut idols).await?;
+    Ok(RestApiResponse::success(idols))
+}
+
+/// Suggests idols for the text typed so far: names starting with `q` first,
+/// then names containing it, each group by record count.
+#[utoipa::path(
+    get,
+    path = "/cards/idols/autocomplete",
+    params(AutocompleteQuery),
+    responses(
+        (status = 200, description = "Matching idols, best first", body = ApiResponse<Vec<NameSuggestionDto>>),
+        (status = 400, description = "Missing or blank `q`")
+    ),
+    tag = "Idols"
+)]
+pub async fn autocomplete_idols(
+    State(state): State<AppState>,
+    axum::extract::Query(query): axum::extract::Query<AutocompleteQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let suggestions = state
+        .luna_service
+        .idol_service()
+        .autocomplete_idols(query)
+        .await?;
+    Ok(RestApiResponse::success(suggestions))
 }
 
 #[utoipa::path(
     get,
     path = "/cards/idols",
-    responses((status = 200, description = "List all idols")),
+    params(
+        SearchIdolDto,
+        ("limit" = Option<i64>, Query, description = "Limit for pagination"),
+        ("offset" = Option<i64>, Query, description = "Offset for pagination"),
+        ("ordering" = Option<String>, Query, description = "Sort keys from `id`, `name`, `link`; prefix `-` for descending. Defaults to affinity order")
+    ),
+    responses(
+        (status = 200, description = "List all idols", body = ApiResponse<PaginatedResponse<IdolDto>>),
+        (status = 304, description = "Not modified since the `If-None-Match` ETag")
+    ),
     tag = "Idols"
 )]
 pub async fn get_idols(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Extension(claims): Extension<Claims>,
     axum::extract::Query(pagination): axum::extract::Query<PaginationQuery>,
+    axum::extract::Query(search_dto): axum::extract::Query<SearchIdolDto>,
 ) -> Result<impl IntoResponse, AppError> {
-    let search_dto = SearchIdolDto {
-        id: None,
-        name: None,
-        link: None,
-        search: None,
-    };
-
-    let paginated_result = state
+    let mut paginated_result = state
         .luna_service
         .idol_service()
         .get_idol_list_by_affinity(search_dto, pagination, claims.sub)
         .await?;
-    Ok(RestApiResponse::success(paginated_result))
+    localize_names(&state, &headers, &mut paginated_result).await?;
+    RestApiResponse::success(paginated_result).into_conditional_response(&headers)
 }
 
 #[utoipa::path(
     post,
     path = "/cards/idols",
     request_body = CreateIdolDto,
-    responses((status = 201, description = "Idol created", body = IdolDto)),
+    responses((status = 201, description = "Idol created", body = ApiResponse<IdolDto>)),
     tag = "Idols"
 )]
 pub async fn create_idol(
@@ -64,7 +138,7 @@ pub async fn create_idol(
 ) -> Result<impl IntoResponse, AppError> {
     body.validate().map_err(|err| {
         tracing::error!("Validation error: {err}");
-        AppError::ValidationError(format!("Invalid input: {err}"))
+        AppError::InvalidInput(err)
     })?;
 
     let idol = state.luna_service.idol_service().create_idol(body).await?;
@@ -75,7 +149,7 @@ pub async fn create_idol(
     put,
     path = "/cards/idols/{id}",
     request_body = UpdateIdolDto,
-    responses((status = 200, description = "Idol updated", body = IdolDto)),
+    responses((status = 200, description = "Idol updated", body = ApiResponse<IdolDto>)),
     tag = "Idols"
 )]
 pub async fn update_idol(
@@ -86,7 +160,7 @@ pub async fn update_idol(
 ) -> Result<impl IntoResponse, AppError> {
     body.validate().map_err(|err| {
         tracing::error!("Validation error: {err}");
-        AppError::ValidationError(format!("Invalid input: {err}"))
+        AppError::InvalidInput(err)
     })?;
 
     let idol = state
@@ -100,24 +174,85 @@ pub async fn update_idol(
 #[utoipa::path(
     patch,
     path = "/cards/idols/{id}",
-    request_body = UpdateIdolDto,
-    responses((status = 200, description = "Idol partially updated", body = IdolDto)),
+    request_body = PatchIdolDto,
+    responses((status = 200, description = "Idol partially updated", body = ApiResponse<IdolDto>)),
     tag = "Idols"
 )]
 pub async fn patch_idol(
     State(state): State<AppState>,
     Extension(_claims): Extension<Claims>,
     axum::extract::Path(id): axum::extract::Path<i64>,
-    Json(body): Json<UpdateIdolDto>,
+    Json(payload): Json<PatchIdolDto>,
 ) -> Result<impl IntoResponse, AppError> {
+    payload.validate().map_err(|err| {
+        tracing::error!("Validation error: {err}");
+        AppError::InvalidInput(err)
+    })?;
+
     let idol = state
         .luna_service
         .idol_service()
-        .update_idol(id, body)
+        .update_idol(id, payload.into_update(id))
         .await?;
     Ok(RestApiResponse::success(idol))
 }
 
+/// Gets the profile of an idol: birthday, debut date, measurements and
+/// aliases.
+#[utoipa::path(
+    get,
+    path = "/cards/idols/{id}/profile",
+    params(("id" = i64, Path, description = "Idol ID")),
+    responses(
+        (status = 200, description = "Profile of the idol", body = ApiResponse<IdolProfileDto>),
+        (status = 404, description = "Idol not found")
+    ),
+    tag = "Idols"
+)]
+pub async fn get_idol_profile(
+    State(state): State<AppState>,
+    axum::extract::Path(id): axum::extract::Path<i64>,
+) -> Result<impl IntoResponse, AppError> {
+    let profile = state
+        .luna_service
+        .idol_service()
+        .get_idol_profile(id)
+        .await?;
+    Ok(RestApiResponse::success(profile))
+}
+
+/// Replaces the profile of an idol; omitted fields are cleared.
+#[utoipa::path(
+    put,
+    path = "/cards/idols/{id}/profile",
+    request_body = IdolProfileDto,
+    params(("id" = i64, Path, description = "Idol ID")),
+    responses(
+        (status = 200, description = "Profile replaced", body = ApiResponse<IdolProfileDto>),
+        (status = 400, description = "Measurement out of range, debut before the birthday, or blank alias"),
+        (status = 403, description = "Caller is not an editor"),
+        (status = 404, description = "Idol not found")
+    ),
+    tag = "Idols"
+)]
+pub async fn replace_idol_profile(
+    State(state): State<AppState>,
+    axum::extract::Path(id): axum::extract::Path<i64>,
+    Json(payload): Json<IdolProfileDto>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate().map_err(|err| {
+        tracing::error!("Validation error: {err}");
+        AppError::InvalidInput(err)
+    })?;
+
+    let profile = state
+        .luna_service
+        .idol_service()
+        .replace_idol_profile(id, payload)
+        .await?;
+    Ok(RestApiResponse::success(profile))
+}
+
 #[utoipa::path(
     delete,
     path = "/cards/idols/{id}",
@@ -133,6 +268,27 @@ pub async fn delete_idol(
     Ok(RestApiResponse::success_with_message(message, ()))
 }
 
+#[utoipa::path(
+    post,
+    path = "/cards/idols/{id}/merge",
+    request_body = MergeEntityDto,
+    responses((status = 200, description = "Duplicate idol merged into this one", body = ApiResponse<MergeEntityResponse>)),
+    tag = "Idols"
+)]
+pub async fn merge_idol(
+    State(state): State<AppState>,
+    Extension(_claims): Extension<Claims>,
+    axum::extract::Path(id): axum::extract::Path<i64>,
+    Json(body): Json<MergeEntityDto>,
+) -> Result<impl IntoResponse, AppError> {
+    let merged = state
+        .luna_service
+        .idol_service()
+        .merge_idol(id, body.source_id)
+        .await?;
+    Ok(RestApiResponse::success(merged))
+}
+
 /// Get idols without images
 ///
 /// This endpoint returns a list of idols that don't have any images
@@ -140,7 +296,7 @@ pub async fn delete_idol(
 #[utoipa::path(
     get,
     path = "/cards/idols/without-images",
-    responses((status = 200, description = "Get idols without images", body = Vec<IdolWithoutImageDto>)),
+    responses((status = 200, description = "Get idols without images", body = ApiResponse<Vec<IdolWithoutImageDto>>)),
     tag = "Idols"
 )]
 pub async fn get_idols_without_images(
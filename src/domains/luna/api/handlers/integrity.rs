@@ -0,0 +1,84 @@
+use crate::{
+    common::{
+        app_state::AppState,
+        dto::{ApiResponse, RestApiResponse},
+        error::AppError,
+    },
+    domains::luna::dto::{
+        IntegrityQuery, IntegrityReportDto, MediaGcQuery, MediaGcReportDto, ReconcileImagesResponse,
+    },
+};
+
+use axum::{
+    extract::{Query, State},
+    response::IntoResponse,
+};
+
+#[utoipa::path(
+    get,
+    path = "/cards/admin/integrity",
+    params(IntegrityQuery),
+    responses(
+        (status = 200, description = "Data-quality checks with counts and sample IDs", body = ApiResponse<IntegrityReportDto>),
+        (status = 400, description = "sample is 0"),
+        (status = 403, description = "Caller is not an admin")
+    ),
+    tag = "Admin"
+)]
+pub async fn get_integrity_report(
+    State(state): State<AppState>,
+    Query(query): Query<IntegrityQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let report = state
+        .luna_service
+        .integrity_service()
+        .integrity_report(query)
+        .await?;
+    Ok(RestApiResponse::success(report))
+}
+
+/// Recounts every live record's media directory and corrects the
+/// `local_img_count` values that drifted.
+#[utoipa::path(
+    post,
+    path = "/cards/admin/integrity/reconcile-images",
+    responses(
+        (status = 200, description = "Records scanned and image counts corrected", body = ApiResponse<ReconcileImagesResponse>),
+        (status = 403, description = "Caller is not an admin")
+    ),
+    tag = "Admin"
+)]
+pub async fn reconcile_image_counts(
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, AppError> {
+    let reconciled = state
+        .luna_service
+        .integrity_service()
+        .reconcile_image_counts()
+        .await?;
+    Ok(RestApiResponse::success(reconciled))
+}
+
+/// Finds media directories whose record or idol no longer exists and
+/// stored-file rows whose file is gone, deleting both with `dry_run=false`.
+#[utoipa::path(
+    post,
+    path = "/cards/admin/media-gc",
+    params(MediaGcQuery),
+    responses(
+        (status = 200, description = "Orphaned media found and what was deleted", body = ApiResponse<MediaGcReportDto>),
+        (status = 403, description = "Caller is not an admin")
+    ),
+    tag = "Admin"
+)]
+pub async fn collect_orphaned_media(
+    State(state): State<AppState>,
+    Query(query): Query<MediaGcQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let report = state
+        .luna_service
+        .integrity_service()
+        .collect_orphaned_media(query.dry_run.unwrap_or(true))
+        .await?;
+    Ok(RestApiResponse::success(report))
+}
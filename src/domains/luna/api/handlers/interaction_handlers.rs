@@ -1,21 +1,50 @@
 use crate::{
-    common::{app_state::AppState, dto::RestApiResponse, error::AppError, jwt::Claims},
+    common::{
+        app_state::AppState,
+        dto::{ApiResponse, RestApiResponse},
+        error::AppError,
+        jwt::Claims,
+    },
     domains::{
-        luna::dto::{PaginatedResponse, PaginationQuery},
+        luna::{
+            dto::{PaginatedResponse, PaginationQuery},
+            RecordPermission,
+        },
         user::dto::interaction_dto::{
-            BatchStatusRequestDto, InteractionStatusDto, MarkViewedResponse, ToggleLikeResponse,
+            BatchStatusRequestDto, FavoriteResponse, InteractionStatusDto, MarkSeenDto,
+            MarkViewedResponse, RateRecordDto, RatingResponse, SeenResponse, ToggleLikeResponse,
         },
     },
 };
 
 use axum::{extract::State, response::IntoResponse, Extension, Json};
+use chrono::Utc;
 use std::collections::HashMap;
+use validator::Validate as _;
+
+/// Reject a record the caller may not see before recording an interaction
+/// with it; `NotFound` when it does not exist.
+async fn ensure_record_visible(
+    state: &AppState,
+    claims: &Claims,
+    record_id: &str,
+) -> Result<(), AppError> {
+    let record = state
+        .luna_service
+        .record_service()
+        .get_record_by_id(record_id)
+        .await?;
+    if record.permission > RecordPermission::clearance(claims.role) {
+        return Err(AppError::Forbidden);
+    }
+    Ok(())
+}
 
 #[utoipa::path(
     post,
     path = "/cards/records/user/{record_id}/like",
     responses(
-        (status = 200, description = "Like toggled", body = ToggleLikeResponse)
+        (status = 200, description = "Like toggled", body = ApiResponse<ToggleLikeResponse>)
     ),
     security(
         ("bearer_auth" = [])
@@ -39,7 +68,7 @@ pub async fn toggle_like(
     post,
     path = "/cards/records/user/{record_id}/viewed",
     responses(
-        (status = 200, description = "Record marked as viewed", body = MarkViewedResponse)
+        (status = 200, description = "Record marked as viewed", body = ApiResponse<MarkViewedResponse>)
     ),
     security(
         ("bearer_auth" = [])
@@ -61,12 +90,194 @@ pub async fn mark_viewed(
     }))
 }
 
+#[utoipa::path(
+    put,
+    path = "/cards/records/user/{record_id}/seen",
+    request_body = MarkSeenDto,
+    responses(
+        (status = 200, description = "Record marked as seen by the caller", body = ApiResponse<SeenResponse>),
+        (status = 400, description = "`seen_at` is in the future"),
+        (status = 403, description = "Record is above the caller's permission level"),
+        (status = 404, description = "Record not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Records"
+)]
+pub async fn mark_seen(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    axum::extract::Path(record_id): axum::extract::Path<String>,
+    Json(body): Json<MarkSeenDto>,
+) -> Result<impl IntoResponse, AppError> {
+    let now = Utc::now();
+    let seen_at = body.seen_at.unwrap_or(now);
+    if seen_at > now {
+        return Err(AppError::ValidationError(
+            "seen_at must not be in the future".into(),
+        ));
+    }
+    ensure_record_visible(&state, &claims, &record_id).await?;
+    state
+        .user_service
+        .interaction_service()
+        .mark_seen(&claims.sub, &record_id, seen_at)
+        .await?;
+    Ok(RestApiResponse::success(SeenResponse {
+        seen: true,
+        seen_at: Some(seen_at),
+    }))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/cards/records/user/{record_id}/seen",
+    responses(
+        (status = 200, description = "Record marked as unseen by the caller", body = ApiResponse<SeenResponse>)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Records"
+)]
+pub async fn mark_unseen(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    axum::extract::Path(record_id): axum::extract::Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    state
+        .user_service
+        .interaction_service()
+        .mark_unseen(&claims.sub, &record_id)
+        .await?;
+    Ok(RestApiResponse::success(SeenResponse {
+        seen: false,
+        seen_at: None,
+    }))
+}
+
+#[utoipa::path(
+    put,
+    path = "/cards/records/user/{record_id}/favorite",
+    responses(
+        (status = 200, description = "Record is on the caller's favorites", body = ApiResponse<FavoriteResponse>),
+        (status = 403, description = "Record is above the caller's permission level"),
+        (status = 404, description = "Record not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Records"
+)]
+pub async fn favorite_record(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    axum::extract::Path(record_id): axum::extract::Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_record_visible(&state, &claims, &record_id).await?;
+    state
+        .user_service
+        .interaction_service()
+        .add_favorite(&claims.sub, &record_id)
+        .await?;
+    Ok(RestApiResponse::success(FavoriteResponse {
+        is_favorite: true,
+    }))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/cards/records/user/{record_id}/favorite",
+    responses(
+        (status = 200, description = "Record is off the caller's favorites", body = ApiResponse<FavoriteResponse>)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Records"
+)]
+pub async fn unfavorite_record(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    axum::extract::Path(record_id): axum::extract::Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    state
+        .user_service
+        .interaction_service()
+        .remove_favorite(&claims.sub, &record_id)
+        .await?;
+    Ok(RestApiResponse::success(FavoriteResponse {
+        is_favorite: false,
+    }))
+}
+
+#[utoipa::path(
+    put,
+    path = "/cards/records/user/{record_id}/rating",
+    request_body = RateRecordDto,
+    responses(
+        (status = 200, description = "Caller's score recorded", body = ApiResponse<RatingResponse>),
+        (status = 400, description = "Score is not between 1 and 10"),
+        (status = 403, description = "Record is above the caller's permission level"),
+        (status = 404, description = "Record not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Records"
+)]
+pub async fn rate_record(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    axum::extract::Path(record_id): axum::extract::Path<String>,
+    Json(body): Json<RateRecordDto>,
+) -> Result<impl IntoResponse, AppError> {
+    body.validate().map_err(|err| {
+        tracing::error!("Validation error: {err}");
+        AppError::InvalidInput(err)
+    })?;
+    ensure_record_visible(&state, &claims, &record_id).await?;
+    state
+        .user_service
+        .interaction_service()
+        .rate(&claims.sub, &record_id, body.score)
+        .await?;
+    Ok(RestApiResponse::success(RatingResponse {
+        score: Some(body.score),
+    }))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/cards/records/user/{record_id}/rating",
+    responses(
+        (status = 200, description = "Caller's score withdrawn", body = ApiResponse<RatingResponse>)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Records"
+)]
+pub async fn unrate_record(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    axum::extract::Path(record_id): axum::extract::Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    state
+        .user_service
+        .interaction_service()
+        .remove_rating(&claims.sub, &record_id)
+        .await?;
+    Ok(RestApiResponse::success(RatingResponse { score: None }))
+}
+
 #[utoipa::path(
     post,
     path = "/cards/records/user/status",
     request_body = BatchStatusRequestDto,
     responses(
-        (status = 200, description = "Batch interaction status", body = HashMap<String, InteractionStatusDto>)
+        (status = 200, description = "Batch interaction status", body = ApiResponse<HashMap<String, InteractionStatusDto>>)
     ),
     security(
         ("bearer_auth" = [])
@@ -94,6 +305,7 @@ pub async fn batch_status(
                 InteractionStatusDto {
                     liked: status.liked,
                     viewed: status.viewed,
+                    is_favorite: status.favorite,
                 },
             )
         })
@@ -109,7 +321,7 @@ pub async fn batch_status(
         ("limit" = Option<i64>, Query, description = "Limit for pagination"),
         ("offset" = Option<i64>, Query, description = "Offset for pagination")
     ),
-    responses((status = 200, description = "Viewed record IDs", body = PaginatedResponse<String>)),
+    responses((status = 200, description = "Viewed record IDs", body = ApiResponse<PaginatedResponse<String>>)),
     security(
         ("bearer_auth" = [])
     ),
@@ -1,63 +1,138 @@
 use crate::{
-    common::{app_state::AppState, dto::RestApiResponse, error::AppError, jwt::Claims},
+    common::{
+        app_state::AppState,
+        dto::{ApiResponse, RestApiResponse},
+        error::AppError,
+        jwt::Claims,
+    },
     domains::luna::dto::{
-        CreateLabelDto, LabelDto, PaginationQuery, SearchLabelDto, UpdateLabelDto,
+        AutocompleteQuery, BatchLookupDto, CreateLabelDto, LabelDto, LabelStudioDto,
+        MergeEntityDto, MergeEntityResponse, NameSuggestionDto, PaginatedResponse, PaginationQuery,
+        PatchLabelDto, SearchLabelDto, UpdateLabelDto,
     },
 };
 
-use axum::{extract::State, response::IntoResponse, Extension, Json};
+use axum::{extract::State, http::HeaderMap, response::IntoResponse, Extension, Json};
 
 use validator::Validate as _;
 
+use super::translation::localize_names;
+
 // Label handlers
 #[utoipa::path(
     get,
     path = "/cards/labels/{id}",
-    responses((status = 200, description = "Get label by ID", body = LabelDto)),
+    responses(
+        (status = 200, description = "Get label by ID", body = ApiResponse<LabelDto>),
+        (status = 304, description = "Not modified since the `If-None-Match` ETag")
+    ),
     tag = "Labels"
 )]
 pub async fn get_label_by_id(
     State(state): State<AppState>,
+    headers: HeaderMap,
     axum::extract::Path(id): axum::extract::Path<i64>,
 ) -> Result<impl IntoResponse, AppError> {
-    let label = state
+    let mut label = state
         .luna_service
         .label_service()
         .get_label_by_id(id)
         .await?;
-    Ok(RestApiResponse::success(label))
+    localize_names(&state, &headers, &mut label).await?;
+    RestApiResponse::success(label).into_conditional_response(&headers)
+}
+
+/// Looks up many labels in one request, in the order of the IDs sent.
+/// Unknown IDs are left out.
+#[utoipa::path(
+    post,
+    path = "/cards/labels/batch",
+    request_body = BatchLookupDto,
+    responses(
+        (status = 200, description = "The labels found", body = ApiResponse<Vec<LabelDto>>),
+        (status = 400, description = "Empty or oversized ID list")
+    ),
+    tag = "Labels"
+)]
+pub async fn get_labels_by_ids(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<BatchLookupDto>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate().map_err(|err| {
+        tracing::error!("Validation error: {err}");
+        AppError::InvalidInput(err)
+    })?;
+
+    let mut labels = state
+        .luna_service
+        .label_service()
+        .get_labels_by_ids(&payload.ids)
+        .await?;
+    localize_names(&state, &headers, &mut labels).await?;
+    Ok(RestApiResponse::success(labels))
+}
+
+/// Suggests labels for the text typed so far: names starting with `q` first,
+/// then names containing it, each group by record count.
+#[utoipa::path(
+    get,
+    path = "/cards/labels/autocomplete",
+    params(AutocompleteQuery),
+    responses(
+        (status = 200, description = "Matching labels, best first", body = ApiResponse<Vec<NameSuggestionDto>>),
+        (status = 400, description = "Missing or blank `q`")
+    ),
+    tag = "Labels"
+)]
+pub async fn autocomplete_labels(
+    State(state): State<AppState>,
+    axum::extract::Query(query): axum::extract::Query<AutocompleteQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let suggestions = state
+        .luna_service
+        .label_service()
+        .autocomplete_labels(query)
+        .await?;
+    Ok(RestApiResponse::success(suggestions))
 }
 
 #[utoipa::path(
     get,
     path = "/cards/labels",
-    responses((status = 200, description = "List all labels", body = [LabelDto])),
+    params(
+        SearchLabelDto,
+        ("limit" = Option<i64>, Query, description = "Limit for pagination"),
+        ("offset" = Option<i64>, Query, description = "Offset for pagination"),
+        ("ordering" = Option<String>, Query, description = "Sort keys from `id`, `name`, `link`; prefix `-` for descending. Defaults to affinity order")
+    ),
+    responses(
+        (status = 200, description = "List all labels", body = ApiResponse<PaginatedResponse<LabelDto>>),
+        (status = 304, description = "Not modified since the `If-None-Match` ETag")
+    ),
     tag = "Labels"
 )]
 pub async fn get_labels(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Extension(claims): Extension<Claims>,
     axum::extract::Query(pagination): axum::extract::Query<PaginationQuery>,
+    axum::extract::Query(search_dto): axum::extract::Query<SearchLabelDto>,
 ) -> Result<impl IntoResponse, AppError> {
-    let search_dto = SearchLabelDto {
-        id: None,
-        name: None,
-        link: None,
-    };
-
-    let paginated_result = state
+    let mut paginated_result = state
         .luna_service
         .label_service()
         .get_label_list_by_affinity(search_dto, pagination, claims.sub)
         .await?;
-    Ok(RestApiResponse::success(paginated_result))
+    localize_names(&state, &headers, &mut paginated_result).await?;
+    RestApiResponse::success(paginated_result).into_conditional_response(&headers)
 }
 
 #[utoipa::path(
     post,
     path = "/cards/labels",
     request_body = CreateLabelDto,
-    responses((status = 201, description = "Create a new label", body = LabelDto)),
+    responses((status = 201, description = "Create a new label", body = ApiResponse<LabelDto>)),
     tag = "Labels"
 )]
 pub async fn create_label(
@@ -67,7 +142,7 @@ pub async fn create_label(
 ) -> Result<impl IntoResponse, AppError> {
     payload.validate().map_err(|err| {
         tracing::error!("Validation error: {err}");
-        AppError::ValidationError(format!("Invalid input: {err}"))
+        AppError::InvalidInput(err)
     })?;
 
     let label = state
@@ -82,7 +157,7 @@ pub async fn create_label(
     put,
     path = "/cards/labels/{id}",
     request_body = UpdateLabelDto,
-    responses((status = 200, description = "Update label", body = LabelDto)),
+    responses((status = 200, description = "Update label", body = ApiResponse<LabelDto>)),
     tag = "Labels"
 )]
 pub async fn update_label(
@@ -93,7 +168,7 @@ pub async fn update_label(
 ) -> Result<impl IntoResponse, AppError> {
     payload.validate().map_err(|err| {
         tracing::error!("Validation error: {err}");
-        AppError::ValidationError(format!("Invalid input: {err}"))
+        AppError::InvalidInput(err)
     })?;
 
     let label = state
@@ -107,25 +182,25 @@ pub async fn update_label(
 #[utoipa::path(
     patch,
     path = "/cards/labels/{id}",
-    request_body = UpdateLabelDto,
-    responses((status = 200, description = "Partially update label", body = LabelDto)),
+    request_body = PatchLabelDto,
+    responses((status = 200, description = "Partially update label", body = ApiResponse<LabelDto>)),
     tag = "Labels"
 )]
 pub async fn patch_label(
     State(state): State<AppState>,
     Extension(_claims): Extension<Claims>,
     axum::extract::Path(id): axum::extract::Path<i64>,
-    Json(payload): Json<UpdateLabelDto>,
+    Json(payload): Json<PatchLabelDto>,
 ) -> Result<impl IntoResponse, AppError> {
     payload.validate().map_err(|err| {
         tracing::error!("Validation error: {err}");
-        AppError::ValidationError(format!("Invalid input: {err}"))
+        AppError::InvalidInput(err)
     })?;
 
     let label = state
         .luna_service
         .label_service()
-        .update_label(id, payload)
+        .update_label(id, payload.into_update(id))
         .await?;
     Ok(RestApiResponse::success(label))
 }
@@ -133,14 +208,71 @@ pub async fn patch_label(
 #[utoipa::path(
     delete,
     path = "/cards/labels/{id}",
-    responses((status = 204, description = "Label deleted")),
+    params(DeleteEntityQuery),
+    responses(
+        (status = 204, description = "Label deleted"),
+        (status = 409, description = "Still referenced by records; data lists their IDs", body = ApiResponse<Vec<String>>)
+    ),
     tag = "Labels"
 )]
 pub async fn delete_label(
     State(state): State<AppState>,
     Extension(_claims): Extension<Claims>,
     axum::extract::Path(id): axum::extract::Path<i64>,
+    axum::extract::Query(query): axum::extract::Query<DeleteEntityQuery>,
 ) -> Result<impl IntoResponse, AppError> {
-    let message = state.luna_service.label_service().delete_label(id).await?;
+    let message = state
+        .luna_service
+        .label_service()
+        .delete_label(id, query.reassign_to)
+        .await?;
     Ok(RestApiResponse::success_with_message(message, ()))
 }
+
+#[utoipa::path(
+    post,
+    path = "/cards/labels/{id}/merge",
+    request_body = MergeEntityDto,
+    responses((status = 200, description = "Duplicate label merged into this one", body = ApiResponse<MergeEntityResponse>)),
+    tag = "Labels"
+)]
+pub async fn merge_label(
+    State(state): State<AppState>,
+    Extension(_claims): Extension<Claims>,
+    axum::extract::Path(id): axum::extract::Path<i64>,
+    Json(body): Json<MergeEntityDto>,
+) -> Result<impl IntoResponse, AppError> {
+    let merged = state
+        .luna_service
+        .label_service()
+        .merge_label(id, body.source_id)
+        .await?;
+    Ok(RestApiResponse::success(merged))
+}
+
+/// Attaches a label to the studio publishing it, or detaches it.
+#[utoipa::path(
+    put,
+    path = "/cards/labels/{id}/studio",
+    request_body = LabelStudioDto,
+    params(("id" = i64, Path, description = "Label ID")),
+    responses(
+        (status = 200, description = "Label attached or detached", body = ApiResponse<LabelDto>),
+        (status = 400, description = "A placeholder label or studio is involved"),
+        (status = 403, description = "Caller is not an editor"),
+        (status = 404, description = "Label or studio not found")
+    ),
+    tag = "Labels"
+)]
+pub async fn set_label_studio(
+    State(state): State<AppState>,
+    axum::extract::Path(id): axum::extract::Path<i64>,
+    Json(body): Json<LabelStudioDto>,
+) -> Result<impl IntoResponse, AppError> {
+    let label = state
+        .luna_service
+        .label_service()
+        .set_label_studio(id, body.studio_id)
+        .await?;
+    Ok(RestApiResponse::success(label))
+}
@@ -0,0 +1,222 @@
+use crate::{
+    common::{
+        app_state::AppState,
+        dto::{ApiResponse, RestApiResponse},
+        error::AppError,
+        jwt::Claims,
+    },
+    domains::luna::{
+        dto::{
+            LinkDto, LinkSearchQuery, PaginatedResponse, PaginationQuery, PatchLinkDto, StarLinkDto,
+        },
+        RecordPermission,
+    },
+};
+
+use axum::{
+    extract::{Path, Query, State},
+    response::IntoResponse,
+    Extension, Json,
+};
+use validator::Validate as _;
+
+use super::comment::ensure_record_visible;
+
+/// Searches the links of all live records the caller may see, newest first.
+#[utoipa::path(
+    get,
+    path = "/cards/links",
+    params(LinkSearchQuery),
+    responses(
+        (status = 200, description = "Matching links", body = ApiResponse<PaginatedResponse<LinkDto>>),
+        (status = 400, description = "Malformed size, date or flag")
+    ),
+    tag = "Links"
+)]
+pub async fn search_links(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Query(query): Query<LinkSearchQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let links = state
+        .luna_service
+        .link_service()
+        .search_links(query, RecordPermission::clearance(claims.role))
+        .await?;
+    Ok(RestApiResponse::success(links))
+}
+
+/// Lists the links of live records the last health check found dead.
+#[utoipa::path(
+    get,
+    path = "/cards/links/dead",
+    params(
+        ("limit" = Option<i64>, Query, description = "Limit for pagination"),
+        ("offset" = Option<i64>, Query, description = "Offset for pagination")
+    ),
+    responses(
+        (status = 200, description = "Dead links, most recently checked first", body = ApiResponse<PaginatedResponse<LinkDto>>),
+        (status = 403, description = "Caller is not an editor")
+    ),
+    tag = "Links"
+)]
+pub async fn get_dead_links(
+    State(state): State<AppState>,
+    Query(pagination): Query<PaginationQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let links = state
+        .luna_service
+        .link_service()
+        .dead_links(pagination)
+        .await?;
+    Ok(RestApiResponse::success(links))
+}
+
+/// Checks one link now.
+#[utoipa::path(
+    post,
+    path = "/cards/links/{id}/check",
+    params(("id" = i64, Path, description = "Link ID")),
+    responses(
+        (status = 200, description = "The link with the outcome of the check", body = ApiResponse<LinkDto>),
+        (status = 400, description = "Not an http or https link"),
+        (status = 403, description = "Caller is not an editor"),
+        (status = 404, description = "Link not found")
+    ),
+    tag = "Links"
+)]
+pub async fn check_link(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, AppError> {
+    let link = state.luna_service.link_service().check_link(id).await?;
+    Ok(RestApiResponse::success(link))
+}
+
+/// Checks every http and https link of a record now.
+#[utoipa::path(
+    post,
+    path = "/cards/records/{id}/links/check",
+    params(("id" = String, Path, description = "Record ID")),
+    responses(
+        (status = 200, description = "All links of the record, by ID, with the outcome of the checks", body = ApiResponse<Vec<LinkDto>>),
+        (status = 403, description = "Caller is not an editor"),
+        (status = 404, description = "Record not found")
+    ),
+    tag = "Links"
+)]
+pub async fn check_record_links(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_record_visible(&state, &claims, &id).await?;
+    let links = state
+        .luna_service
+        .link_service()
+        .check_record_links(&id)
+        .await?;
+    Ok(RestApiResponse::success(links))
+}
+
+/// Edits one link of a record.
+#[utoipa::path(
+    patch,
+    path = "/cards/records/{id}/links/{link_id}",
+    request_body = PatchLinkDto,
+    params(
+        ("id" = String, Path, description = "Record ID"),
+        ("link_id" = i64, Path, description = "Link ID")
+    ),
+    responses(
+        (status = 200, description = "Link updated", body = ApiResponse<LinkDto>),
+        (status = 403, description = "Caller is not an editor"),
+        (status = 404, description = "Record not found or link not on the record"),
+        (status = 409, description = "The record already has a link with the new URL")
+    ),
+    tag = "Links"
+)]
+pub async fn update_link(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path((id, link_id)): Path<(String, i64)>,
+    Json(payload): Json<PatchLinkDto>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate().map_err(|err| {
+        tracing::error!("Validation error: {err}");
+        AppError::InvalidInput(err)
+    })?;
+
+    ensure_record_visible(&state, &claims, &id).await?;
+    let link = state
+        .luna_service
+        .link_service()
+        .update_link(&id, link_id, payload, &claims.sub)
+        .await?;
+    Ok(RestApiResponse::success(link))
+}
+
+/// Stars or unstars one link of a record. Starred links come first in the
+/// record's link list.
+#[utoipa::path(
+    post,
+    path = "/cards/records/{id}/links/{link_id}/star",
+    request_body = StarLinkDto,
+    params(
+        ("id" = String, Path, description = "Record ID"),
+        ("link_id" = i64, Path, description = "Link ID")
+    ),
+    responses(
+        (status = 200, description = "Link starred or unstarred", body = ApiResponse<LinkDto>),
+        (status = 403, description = "Caller is not an editor"),
+        (status = 404, description = "Record not found or link not on the record")
+    ),
+    tag = "Links"
+)]
+pub async fn star_link(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path((id, link_id)): Path<(String, i64)>,
+    Json(payload): Json<StarLinkDto>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_record_visible(&state, &claims, &id).await?;
+    let patch = PatchLinkDto {
+        star: Some(payload.star),
+        ..PatchLinkDto::default()
+    };
+    let link = state
+        .luna_service
+        .link_service()
+        .update_link(&id, link_id, patch, &claims.sub)
+        .await?;
+    Ok(RestApiResponse::success(link))
+}
+
+/// Removes one link from a record.
+#[utoipa::path(
+    delete,
+    path = "/cards/records/{id}/links/{link_id}",
+    params(
+        ("id" = String, Path, description = "Record ID"),
+        ("link_id" = i64, Path, description = "Link ID")
+    ),
+    responses(
+        (status = 204, description = "Link deleted"),
+        (status = 403, description = "Caller is not an editor"),
+        (status = 404, description = "Record not found or link not on the record")
+    ),
+    tag = "Links"
+)]
+pub async fn delete_link(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path((id, link_id)): Path<(String, i64)>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_record_visible(&state, &claims, &id).await?;
+    state
+        .luna_service
+        .link_service()
+        .delete_link(&id, link_id, &claims.sub)
+        .await?;
+    Ok(RestApiResponse::success(()))
+}
@@ -1,11 +1,16 @@
-use crate::common::dto::RestApiResponse;
-use crate::common::{app_state::AppState, error::AppError};
-use crate::domains::luna::dto::{ImageData, MediaAccessDto, MediaType, UploadImageDto};
+use crate::common::dto::{ApiResponse, RestApiResponse};
+use crate::common::{app_state::AppState, error::AppError, jwt::Claims};
+use crate::domains::luna::dto::{
+    ImageData, MediaAccessDto, MediaFileDto, MediaType, ThumbnailFit, ThumbnailSpec, UploadImageDto,
+};
+use crate::domains::luna::RecordPermission;
 use axum::extract::Multipart;
+use axum::http::HeaderMap;
 use axum::response::IntoResponse;
 use axum::{
     extract::{Path, Query, State},
     response::Response,
+    Extension,
 };
 use serde::Deserialize;
 use utoipa::IntoParams;
@@ -20,6 +25,46 @@ pub struct MediaPathParams {
 pub struct MediaQueryParams {
     /// Optional sequence number for the media file
     pub n: Option<u32>,
+    /// Thumbnail width in pixels, rounded up to a configured size
+    pub w: Option<u32>,
+    /// Thumbnail height in pixels, rounded up to a configured size
+    pub h: Option<u32>,
+    /// How the thumbnail fills `w`×`h` (default `contain`)
+    pub fit: Option<ThumbnailFit>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ThumbnailQueryParams {
+    /// Thumbnail width in pixels, rounded up to a configured size
+    pub w: Option<u32>,
+    /// Thumbnail height in pixels, rounded up to a configured size
+    pub h: Option<u32>,
+    /// How the thumbnail fills `w`×`h` (default `contain`)
+    pub fit: Option<ThumbnailFit>,
+}
+
+impl ThumbnailQueryParams {
+    fn spec(&self) -> Option<ThumbnailSpec> {
+        ThumbnailSpec::from_query(self.w, self.h, self.fit)
+    }
+}
+
+/// Reject media of a record above the caller's clearance. Media without a
+/// matching record is served as before.
+async fn ensure_record_media_visible(
+    state: &AppState,
+    id: &str,
+    claims: &Claims,
+) -> Result<(), AppError> {
+    let permission = state
+        .luna_service
+        .record_service()
+        .get_record_permission(id)
+        .await?;
+    if permission.is_some_and(|p| p > RecordPermission::clearance(claims.role)) {
+        return Err(AppError::Forbidden);
+    }
+    Ok(())
 }
 
 /// Serves media files (images) for luna cards
@@ -28,6 +73,16 @@ pub struct MediaQueryParams {
 /// - If `n` is provided, it returns `{id}_{n}.jpg`
 /// - If `n` is not provided, it returns `{id}.jpg`
 ///
+/// With `w` and/or `h`, a JPEG thumbnail no larger than the original is
+/// served instead, generated once and cached (requires the `thumbnails`
+/// feature; otherwise the original is served).
+///
+/// Responses carry `ETag`, `Last-Modified` and `Cache-Control`, answer
+/// `If-None-Match` and `If-Modified-Since` with `304 Not Modified`, and
+/// serve single `Range` requests with `206 Partial Content`. With
+/// `MEDIA_PRESIGNED_URLS` and a backend that supports them, originals are
+/// answered with a redirect to a presigned URL instead.
+///
 /// Files are looked up in the configured private assets directory under the subdirectory named by the ID.
 #[utoipa::path(
     get,
@@ -38,22 +93,95 @@ pub struct MediaQueryParams {
     ),
     responses(
         (status = 200, description = "Media file served successfully", content_type = "image/jpg"),
+        (status = 206, description = "Requested byte range of the media file", content_type = "image/jpg"),
+        (status = 304, description = "Media file unchanged since the client's copy"),
+        (status = 307, description = "Redirect to a presigned URL of the media file (`MEDIA_PRESIGNED_URLS`)"),
+        (status = 400, description = "`w` or `h` is 0"),
+        (status = 403, description = "Record is above the caller's permission level"),
         (status = 404, description = "Media file or directory not found"),
+        (status = 416, description = "Requested range lies past the end of the file"),
         (status = 500, description = "Internal server error")
     ),
     tag = "Media"
 )]
 pub async fn serve_media(
     State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
     Path(path_params): Path<MediaPathParams>,
     Query(query_params): Query<MediaQueryParams>,
+    headers: HeaderMap,
 ) -> Result<Response, AppError> {
-    let media_dto = MediaAccessDto::new(path_params.id, MediaType::RecordImage, query_params.n);
+    ensure_record_media_visible(&state, &path_params.id, &claims).await?;
+    let thumbnail = ThumbnailSpec::from_query(query_params.w, query_params.h, query_params.fit);
+    let media_dto = MediaAccessDto::new(path_params.id, MediaType::RecordImage, query_params.n)
+        .with_thumbnail(thumbnail);
+
+    state
+        .luna_service
+        .file_service()
+        .serve_media_file(media_dto, &headers)
+        .await
+}
+
+/// Lists the images of a record
+///
+/// Returns the main image and the numbered images served by
+/// `/cards/media/{id}`, with their size, dimensions (when the `thumbnails`
+/// feature can read them) and URL, main image first. A record without a
+/// media directory lists nothing.
+#[utoipa::path(
+    get,
+    path = "/cards/media/{id}/list",
+    params(MediaPathParams),
+    responses(
+        (status = 200, description = "Images available for the record", body = ApiResponse<Vec<MediaFileDto>>),
+        (status = 403, description = "Record is above the caller's permission level"),
+        (status = 404, description = "Invalid media ID"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Media"
+)]
+pub async fn list_media(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(path_params): Path<MediaPathParams>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_record_media_visible(&state, &path_params.id, &claims).await?;
+    let url = format!("/cards/media/{}", path_params.id);
+    let files = state
+        .luna_service
+        .file_service()
+        .list_media(MediaType::RecordImage, &path_params.id, &url)
+        .await?;
+    Ok(RestApiResponse::success(files))
+}
 
+/// Downloads the images of a record as a ZIP archive
+///
+/// The archive is written while it is sent, one stored entry per image file,
+/// so its size is not known up front and memory use stays bounded.
+#[utoipa::path(
+    get,
+    path = "/cards/media/{id}/archive",
+    params(MediaPathParams),
+    responses(
+        (status = 200, description = "ZIP of the record's images", content_type = "application/zip"),
+        (status = 403, description = "Record is above the caller's permission level"),
+        (status = 404, description = "Record has no images"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Media"
+)]
+pub async fn archive_media(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(path_params): Path<MediaPathParams>,
+) -> Result<Response, AppError> {
+    ensure_record_media_visible(&state, &path_params.id, &claims).await?;
     state
         .luna_service
         .file_service()
-        .serve_media_file(media_dto)
+        .archive_media(MediaType::RecordImage, &path_params.id)
         .await
 }
 
@@ -61,14 +189,19 @@ pub async fn serve_media(
 /// This endpoint uses two separate path parameters for cleaner URL structure
 pub async fn serve_media_with_number(
     State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
     Path((id, n)): Path<(String, u32)>,
+    Query(thumbnail): Query<ThumbnailQueryParams>,
+    headers: HeaderMap,
 ) -> Result<Response, AppError> {
-    let media_dto = MediaAccessDto::new(id, MediaType::RecordImage, Some(n));
+    ensure_record_media_visible(&state, &id, &claims).await?;
+    let media_dto =
+        MediaAccessDto::new(id, MediaType::RecordImage, Some(n)).with_thumbnail(thumbnail.spec());
 
     state
         .luna_service
         .file_service()
-        .serve_media_file(media_dto)
+        .serve_media_file(media_dto, &headers)
         .await
 }
 
@@ -76,7 +209,15 @@ pub async fn serve_media_with_number(
 ///
 /// This endpoint accepts multipart form data with image files and uploads them
 /// to the private assets directory under the subdirectory named by the ID.
-/// Only uploads files that don't already exist (no overwriting).
+/// Only uploads files that don't already exist (no overwriting), and adds
+/// the files written to the record's `local_img_count`.
+///
+/// Files are streamed to disk as they arrive and renamed into place once the
+/// form is read. Each file may be at most `ASSET_MAX_SIZE` bytes, and at most
+/// `UPLOAD_MAX_CONCURRENT` uploads run at once; further ones get 429.
+///
+/// Files must be JPEG, PNG or WebP, judged by their magic bytes, and a declared
+/// content type must agree; they are stored with the sniffed format's extension.
 #[utoipa::path(
     post,
     path = "/cards/media/upload",
@@ -86,8 +227,10 @@ pub async fn serve_media_with_number(
         content_type = "multipart/form-data"
     ),
     responses(
-        (status = 200, description = "Images uploaded successfully", body = String),
+        (status = 200, description = "Images uploaded successfully", body = ApiResponse<String>),
         (status = 400, description = "Bad request - invalid data or record ID not found"),
+        (status = 413, description = "File larger than `ASSET_MAX_SIZE`"),
+        (status = 429, description = "Too many uploads in progress"),
         (status = 500, description = "Internal server error")
     ),
     tag = "Media"
@@ -96,6 +239,8 @@ pub async fn upload_images(
     State(state): State<AppState>,
     mut multipart: Multipart,
 ) -> Result<impl IntoResponse, AppError> {
+    let file_service = state.luna_service.file_service();
+    let _upload_slot = file_service.try_acquire_upload_slot()?;
     let mut id: Option<String> = None;
     let mut images: Vec<ImageData> = Vec::new();
 
@@ -119,10 +264,7 @@ pub async fn upload_images(
                 .content_type()
                 .unwrap_or("application/octet-stream")
                 .to_owned();
-            let data = field
-                .bytes()
-                .await
-                .map_err(|e| AppError::ValidationError(format!("Failed to read file data: {e}")))?;
+            let staged = file_service.stage_upload(field).await?;
 
             // Extract filename without extension for the name
             let name = filename.split('.').next().unwrap_or(&filename).to_owned();
@@ -130,7 +272,7 @@ pub async fn upload_images(
             images.push(ImageData {
                 name,
                 mime: content_type,
-                bytes: data.to_vec(),
+                staged,
             });
         }
     }
@@ -160,19 +302,138 @@ pub async fn upload_images(
         Err(e) => return Err(e), // Other database errors
     }
 
-    let upload_dto = UploadImageDto { id, files: images };
+    let upload_dto = UploadImageDto {
+        id: id.clone(),
+        files: images,
+    };
 
-    let uploaded_count = state
-        .luna_service
-        .file_service()
+    let uploaded_count = file_service
         .upload_images(MediaType::RecordImage, upload_dto)
         .await?;
 
+    // Files that already existed were skipped and are not counted again.
+    if uploaded_count > 0 {
+        state
+            .luna_service
+            .record_service()
+            .add_local_images(&id, uploaded_count as i32)
+            .await?;
+    }
+
     Ok(RestApiResponse::success(format!(
         "Successfully uploaded {uploaded_count} image(s)"
     )))
 }
 
+/// Serves the trailer video of a record
+///
+/// Streams `{id}.mp4` or `{id}.webm` from the record's video directory with
+/// the same validators and caching headers as images. Single `Range`
+/// requests are answered with `206 Partial Content`, so players can seek.
+#[utoipa::path(
+    get,
+    path = "/cards/media/{id}/video",
+    params(MediaPathParams),
+    responses(
+        (status = 200, description = "Trailer video served successfully", content_type = "video/mp4"),
+        (status = 206, description = "Requested byte range of the video", content_type = "video/mp4"),
+        (status = 304, description = "Video unchanged since the client's copy"),
+        (status = 307, description = "Redirect to a presigned URL of the video (`MEDIA_PRESIGNED_URLS`)"),
+        (status = 403, description = "Record is above the caller's permission level"),
+        (status = 404, description = "Record has no trailer video"),
+        (status = 416, description = "Requested range lies past the end of the file"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Media"
+)]
+pub async fn serve_record_video(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(path_params): Path<MediaPathParams>,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    ensure_record_media_visible(&state, &path_params.id, &claims).await?;
+    let media_dto = MediaAccessDto::new(path_params.id, MediaType::RecordVideo, None);
+
+    state
+        .luna_service
+        .file_service()
+        .serve_media_file(media_dto, &headers)
+        .await
+}
+
+/// Upload the trailer video of a record
+///
+/// Accepts multipart form data with a single `file` field holding an MP4 or
+/// WebM video, judged by its magic bytes, of at most `VIDEO_MAX_SIZE` bytes.
+/// The video is stored next to the record's images and the record's
+/// `trailer` is set to its URL. A record keeps one trailer; uploading a
+/// second one fails with 409.
+#[utoipa::path(
+    post,
+    path = "/cards/media/{id}/video",
+    params(MediaPathParams),
+    request_body(
+        content = String,
+        description = "Multipart form data with a 'file' field holding the video",
+        content_type = "multipart/form-data"
+    ),
+    responses(
+        (status = 200, description = "Video uploaded; returns its URL", body = ApiResponse<String>),
+        (status = 400, description = "Missing or unsupported video file"),
+        (status = 404, description = "Record not found"),
+        (status = 409, description = "Record already has a trailer video"),
+        (status = 413, description = "Video larger than `VIDEO_MAX_SIZE`"),
+        (status = 429, description = "Too many uploads in progress"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Media"
+)]
+pub async fn upload_record_video(
+    State(state): State<AppState>,
+    Path(path_params): Path<MediaPathParams>,
+    mut multipart: Multipart,
+) -> Result<impl IntoResponse, AppError> {
+    let file_service = state.luna_service.file_service();
+    let _upload_slot = file_service.try_acquire_upload_slot()?;
+    let id = path_params.id;
+
+    // Fail before streaming a large body for a record that does not exist
+    state
+        .luna_service
+        .record_service()
+        .get_record_by_id(&id)
+        .await?;
+
+    let mut video = None;
+    while let Some(field) = multipart.next_field().await.map_err(|e| {
+        AppError::ValidationError(format!("Failed to parse multipart form data: {e}"))
+    })? {
+        if field.name() == Some("file") {
+            let content_type = field
+                .content_type()
+                .unwrap_or("application/octet-stream")
+                .to_owned();
+            video = Some((file_service.stage_video_upload(field).await?, content_type));
+        }
+    }
+    let (staged, content_type) =
+        video.ok_or_else(|| AppError::ValidationError("No video file provided".to_owned()))?;
+
+    file_service
+        .upload_video(MediaType::RecordVideo, &id, staged, &content_type)
+        .await?;
+
+    let url = format!("/cards/media/{id}/video");
+    state
+        .luna_service
+        .record_service()
+        .set_trailer(&id, Some(url.clone()))
+        .await?;
+
+    Ok(RestApiResponse::success(url))
+}
+
 /// Serves idol media files by idol ID
 ///
 /// This endpoint serves jpg images for idols based on the provided ID.
@@ -183,6 +444,7 @@ pub async fn upload_images(
     path = "/records/media/idol/id/{idol_id}",
     params(
         ("idol_id" = i64, Path, description = "The unique identifier for the idol"),
+        MediaQueryParams,
     ),
     responses(
         (status = 200, description = "Idol media file served successfully", content_type = "image/*"),
@@ -194,6 +456,8 @@ pub async fn upload_images(
 pub async fn serve_idol_media_by_id(
     State(state): State<AppState>,
     Path(idol_id): Path<i64>,
+    Query(query_params): Query<MediaQueryParams>,
+    headers: HeaderMap,
 ) -> Result<Response, AppError> {
     // First check if the idol exists in the database
     let idol = state
@@ -203,12 +467,84 @@ pub async fn serve_idol_media_by_id(
         .await?;
 
     // Use the idol's name as the media ID
-    let media_dto = MediaAccessDto::new(idol.name, MediaType::IdolImage, None);
+    let thumbnail = ThumbnailSpec::from_query(query_params.w, query_params.h, query_params.fit);
+    let media_dto = MediaAccessDto::new(idol.name, MediaType::IdolImage, query_params.n)
+        .with_thumbnail(thumbnail);
+
+    state
+        .luna_service
+        .file_service()
+        .serve_media_file(media_dto, &headers)
+        .await
+}
+
+/// Lists the images of an idol
+///
+/// Returns the images stored under the idol's name, main image first, with
+/// URLs under `/cards/media/idol/id/{idol_id}`.
+#[utoipa::path(
+    get,
+    path = "/cards/media/idol/id/{idol_id}/list",
+    params(
+        ("idol_id" = i64, Path, description = "The unique identifier for the idol"),
+    ),
+    responses(
+        (status = 200, description = "Images available for the idol", body = ApiResponse<Vec<MediaFileDto>>),
+        (status = 404, description = "Idol not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Media"
+)]
+pub async fn list_idol_media_by_id(
+    State(state): State<AppState>,
+    Path(idol_id): Path<i64>,
+) -> Result<impl IntoResponse, AppError> {
+    let idol = state
+        .luna_service
+        .idol_service()
+        .get_idol_by_id(idol_id)
+        .await?;
+
+    let url = format!("/cards/media/idol/id/{idol_id}");
+    let files = state
+        .luna_service
+        .file_service()
+        .list_media(MediaType::IdolImage, &idol.name, &url)
+        .await?;
+    Ok(RestApiResponse::success(files))
+}
+
+/// Downloads the images of an idol as a ZIP archive
+///
+/// Like `/cards/media/{id}/archive`, for the images stored under the idol's
+/// name.
+#[utoipa::path(
+    get,
+    path = "/cards/media/idol/id/{idol_id}/archive",
+    params(
+        ("idol_id" = i64, Path, description = "The unique identifier for the idol"),
+    ),
+    responses(
+        (status = 200, description = "ZIP of the idol's images", content_type = "application/zip"),
+        (status = 404, description = "Idol not found or has no images"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Media"
+)]
+pub async fn archive_idol_media_by_id(
+    State(state): State<AppState>,
+    Path(idol_id): Path<i64>,
+) -> Result<Response, AppError> {
+    let idol = state
+        .luna_service
+        .idol_service()
+        .get_idol_by_id(idol_id)
+        .await?;
 
     state
         .luna_service
         .file_service()
-        .serve_media_file(media_dto)
+        .archive_media(MediaType::IdolImage, &idol.name)
         .await
 }
 
@@ -222,6 +558,7 @@ pub async fn serve_idol_media_by_id(
     path = "/records/media/idol/name/{idol_name}",
     params(
         ("idol_name" = String, Path, description = "The name of the idol"),
+        ThumbnailQueryParams,
     ),
     responses(
         (status = 200, description = "Idol media file served successfully", content_type = "image/*"),
@@ -233,6 +570,8 @@ pub async fn serve_idol_media_by_id(
 pub async fn serve_idol_media_by_name(
     State(state): State<AppState>,
     Path(idol_name): Path<String>,
+    Query(thumbnail): Query<ThumbnailQueryParams>,
+    headers: HeaderMap,
 ) -> Result<Response, AppError> {
     // First check if the idol exists in the database by searching by name
     use crate::domains::luna::dto::SearchIdolDto;
@@ -256,12 +595,13 @@ pub async fn serve_idol_media_by_name(
     }
 
     // Use the idol's name as the media ID
-    let media_dto = MediaAccessDto::new(idol_name, MediaType::IdolImage, None);
+    let media_dto =
+        MediaAccessDto::new(idol_name, MediaType::IdolImage, None).with_thumbnail(thumbnail.spec());
 
     state
         .luna_service
         .file_service()
-        .serve_media_file(media_dto)
+        .serve_media_file(media_dto, &headers)
         .await
 }
 
@@ -282,8 +622,10 @@ pub async fn serve_idol_media_by_name(
         content_type = "multipart/form-data"
     ),
     responses(
-        (status = 200, description = "Images already exist", body = String),
+        (status = 200, description = "Images already exist", body = ApiResponse<String>),
         (status = 400, description = "Bad request - invalid data or idol ID not found"),
+        (status = 413, description = "File larger than `ASSET_MAX_SIZE`"),
+        (status = 429, description = "Too many uploads in progress"),
         (status = 500, description = "Internal server error")
     ),
     tag = "Media"
@@ -300,6 +642,8 @@ pub async fn upload_idol_images_by_id(
         .get_idol_by_id(idol_id)
         .await?;
 
+    let file_service = state.luna_service.file_service();
+    let _upload_slot = file_service.try_acquire_upload_slot()?;
     let mut images: Vec<ImageData> = Vec::new();
 
     // Parse multipart form data
@@ -313,16 +657,13 @@ pub async fn upload_idol_images_by_id(
                 .content_type()
                 .unwrap_or("application/octet-stream")
                 .to_owned();
-            let data = field
-                .bytes()
-                .await
-                .map_err(|e| AppError::ValidationError(format!("Failed to read file data: {e}")))?;
+            let staged = file_service.stage_upload(field).await?;
 
             // Use the idol's name as the filename (without extension)
             images.push(ImageData {
                 name: idol.name.clone(),
                 mime: content_type,
-                bytes: data.to_vec(),
+                staged,
             });
         }
     }
@@ -338,9 +679,7 @@ pub async fn upload_idol_images_by_id(
         files: images,
     };
 
-    let uploaded_count = state
-        .luna_service
-        .file_service()
+    let uploaded_count = file_service
         .upload_images(MediaType::IdolImage, upload_dto)
         .await?;
 
@@ -374,8 +713,10 @@ pub async fn upload_idol_images_by_id(
         content_type = "multipart/form-data"
     ),
     responses(
-        (status = 200, description = "Images already exist", body = String),
+        (status = 200, description = "Images already exist", body = ApiResponse<String>),
         (status = 400, description = "Bad request - invalid data or idol name not found"),
+        (status = 413, description = "File larger than `ASSET_MAX_SIZE`"),
+        (status = 429, description = "Too many uploads in progress"),
         (status = 500, description = "Internal server error")
     ),
     tag = "Media"
@@ -406,6 +747,8 @@ pub async fn upload_idol_images_by_name(
         )));
     }
 
+    let file_service = state.luna_service.file_service();
+    let _upload_slot = file_service.try_acquire_upload_slot()?;
     let mut images: Vec<ImageData> = Vec::new();
 
     // Parse multipart form data
@@ -420,16 +763,13 @@ pub async fn upload_idol_images_by_name(
                 .content_type()
                 .unwrap_or("application/octet-stream")
                 .to_owned();
-            let data = field
-                .bytes()
-                .await
-                .map_err(|e| AppError::ValidationError(format!("Failed to read file data: {e}")))?;
+            let staged = file_service.stage_upload(field).await?;
 
             // Use the idol's name as the filename (without extension)
             images.push(ImageData {
                 name: idol_name.clone(),
                 mime: content_type,
-                bytes: data.to_vec(),
+                staged,
             });
         }
     }
@@ -445,9 +785,7 @@ pub async fn upload_idol_images_by_name(
         files: images,
     };
 
-    let uploaded_count = state
-        .luna_service
-        .file_service()
+    let uploaded_count = file_service
         .upload_images(MediaType::IdolImage, upload_dto)
         .await?;
 
@@ -0,0 +1,48 @@
+use crate::{
+    common::{
+        app_state::AppState,
+        dto::{ApiResponse, RestApiResponse},
+        error::AppError,
+        etag::if_match_version,
+        jwt::Claims,
+    },
+    domains::luna::dto::{RefreshMetadataQuery, RefreshMetadataResponse},
+};
+
+use axum::{
+    extract::{Query, State},
+    http::HeaderMap,
+    response::IntoResponse,
+    Extension,
+};
+
+#[utoipa::path(
+    post,
+    path = "/cards/records/{id}/refresh-metadata",
+    params(
+        ("id" = String, Path, description = "Record ID"),
+        ("If-Match" = Option<String>, Header, description = "Record version the refresh is based on"),
+        RefreshMetadataQuery
+    ),
+    responses(
+        (status = 200, description = "Fetched metadata merged into the record", body = ApiResponse<RefreshMetadataResponse>),
+        (status = 404, description = "Record not found, or no provider knows it"),
+        (status = 412, description = "`If-Match` version is stale; `data.version` is the current one"),
+        (status = 500, description = "Every provider asked failed")
+    ),
+    tag = "Metadata"
+)]
+pub async fn refresh_record_metadata(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(claims): Extension<Claims>,
+    axum::extract::Path(id): axum::extract::Path<String>,
+    Query(query): Query<RefreshMetadataQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let refreshed = state
+        .luna_service
+        .record_service()
+        .refresh_metadata(&id, query, if_match_version(&headers)?, &claims.sub)
+        .await?;
+    Ok(RestApiResponse::success(refreshed))
+}
@@ -1,28 +1,62 @@
 use crate::{
-    common::{app_state::AppState, dto::RestApiResponse, error::AppError, jwt::Claims},
-    domains::luna::dto::{
-        CreateLinkDto, CreateRecordDto, PaginatedResponse, PaginationQuery, RecordDto,
-        RecordSlimDto, SearchRecordDto, UpdateRecordDto, UserFilter,
+    common::{
+        app_state::AppState,
+        dto::{ApiResponse, RestApiResponse},
+        error::AppError,
+        etag::if_match_version,
+        jwt::Claims,
+    },
+    domains::luna::{
+        dto::{
+            BulkCreateQuery, BulkCreateResponse, BulkDeleteRecordsDto, BulkDeleteRecordsResponse,
+            CreateLinkDto, CreateRecordDto, LinkUpdateMode, LinkUpdateResultDto, MediaType,
+            MergeRecordDto, PaginatedResponse, PaginationQuery, PatchRecordDto, RandomRecordsQuery,
+            RecordDto, RecordExistsDto, RecordExistsResponse, RecordFields, RecordFieldsQuery,
+            RecordRelations, RecordSlimDto, RecordSyncQuery, RecordSyncResponse,
+            ReplaceRecordQuery, SearchRecordDto, SeenRecordDto, SetRecordCoverDto,
+            SetSeriesOrderDto, SimilarRecordDto, SimilarRecordsQuery, UpdateRecordDto,
+            UpdateRecordLinksQuery, UserFilter,
+        },
+        RecordPermission,
     },
 };
 
-use axum::{extract::State, http::StatusCode, response::IntoResponse, Extension, Json};
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    Extension, Json,
+};
 
 use validator::Validate as _;
 
+use super::translation::localize_names;
+
 /// Build a `UserFilter` from query params and claims.
-fn build_user_filter(pagination: &PaginationQuery, claims: &Claims) -> Option<UserFilter> {
-    let liked = pagination.liked_only.unwrap_or(false);
-    let viewed = pagination.viewed_only.unwrap_or(false);
-    (liked || viewed).then_some(UserFilter {
+pub(super) fn build_user_filter(
+    pagination: &PaginationQuery,
+    claims: &Claims,
+) -> Option<UserFilter> {
+    Some(UserFilter {
         user_id: claims.sub.clone(),
-        liked_only: liked,
-        viewed_only: viewed,
+        liked_only: pagination.liked_only.unwrap_or(false),
+        viewed_only: pagination.viewed_only.unwrap_or(false) || pagination.seen == Some(true),
+        favorites_only: false,
+        unseen_only: pagination.seen == Some(false),
+        max_permission: RecordPermission::clearance(claims.role),
     })
 }
 
+/// Reject a record the caller's role may not see.
+fn ensure_visible(record: &RecordDto, claims: &Claims) -> Result<(), AppError> {
+    if record.permission > RecordPermission::clearance(claims.role) {
+        return Err(AppError::Forbidden);
+    }
+    Ok(())
+}
+
 /// Attach interaction status to a list of `RecordDto`.
-async fn attach_interaction_status(
+pub(super) async fn attach_interaction_status(
     state: &AppState,
     user_id: &str,
     dtos: &mut [RecordDto],
@@ -40,6 +74,7 @@ async fn attach_interaction_status(
         if let Some(status) = status_map.get(&dto.id) {
             dto.liked = status.liked;
             dto.viewed = status.viewed;
+            dto.is_favorite = status.favorite;
         }
     }
     Ok(())
@@ -64,6 +99,7 @@ async fn attach_interaction_status_slim(
         if let Some(status) = status_map.get(&dto.id) {
             dto.liked = status.liked;
             dto.viewed = status.viewed;
+            dto.is_favorite = status.favorite;
         }
     }
     Ok(())
@@ -73,132 +109,463 @@ async fn attach_interaction_status_slim(
 #[utoipa::path(
     get,
     path = "/cards/records/{id}",
-    responses((status = 200, description = "Get record by ID", body = RecordDto)),
+    responses(
+        (status = 200, description = "Get record by ID", body = ApiResponse<RecordDto>),
+        (status = 304, description = "Not modified since the `If-None-Match` ETag"),
+        (status = 403, description = "Record is above the caller's permission level")
+    ),
     tag = "Records"
 )]
 pub async fn get_record_by_id(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Extension(claims): Extension<Claims>,
     axum::extract::Path(id): axum::extract::Path<String>,
 ) -> Result<impl IntoResponse, AppError> {
-    let record = state
+    let mut record = state
         .luna_service
         .record_service()
         .get_record_by_id(&id)
         .await?;
+    ensure_visible(&record, &claims)?;
+    if record.series.id != 0 {
+        (record.series_previous, record.series_next) = state
+            .luna_service
+            .record_service()
+            .get_series_neighbors(&id, RecordPermission::clearance(claims.role))
+            .await?;
+    }
     let mut records = vec![record];
     attach_interaction_status(&state, &claims.sub, &mut records).await?;
-    Ok(RestApiResponse::success(
-        records.into_iter().next().expect("vec has one element"),
-    ))
+    localize_names(&state, &headers, &mut records).await?;
+    let record = records.into_iter().next().expect("vec has one element");
+    let version = record.version;
+    RestApiResponse::success(record).into_versioned_conditional_response(&headers, version)
+}
+
+#[utoipa::path(
+    head,
+    path = "/cards/records/{id}",
+    responses(
+        (status = 200, description = "Record exists"),
+        (status = 404, description = "Record not found or not visible to the caller")
+    ),
+    tag = "Records"
+)]
+pub async fn head_record(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    axum::extract::Path(id): axum::extract::Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let exists = state
+        .luna_service
+        .record_service()
+        .record_exists(&id, RecordPermission::clearance(claims.role))
+        .await?;
+
+    if exists {
+        Ok(StatusCode::OK)
+    } else {
+        Ok(StatusCode::NOT_FOUND)
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/cards/records/exists",
+    request_body = RecordExistsDto,
+    responses((status = 200, description = "Subset of the given IDs that exist and the caller may see", body = ApiResponse<RecordExistsResponse>)),
+    tag = "Records"
+)]
+pub async fn records_exist(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(body): Json<RecordExistsDto>,
+) -> Result<impl IntoResponse, AppError> {
+    body.validate().map_err(|err| {
+        tracing::error!("Validation error: {err}");
+        AppError::InvalidInput(err)
+    })?;
+
+    let existing = state
+        .luna_service
+        .record_service()
+        .find_existing_record_ids(body.ids, RecordPermission::clearance(claims.role))
+        .await?;
+    Ok(RestApiResponse::success(RecordExistsResponse { existing }))
+}
+
+/// Records sharing idols, genres, series or studio with record `id`, ranked
+/// by the configured similarity weights.
+#[utoipa::path(
+    get,
+    path = "/cards/records/{id}/similar",
+    params(("id" = String, Path, description = "Record ID"), SimilarRecordsQuery),
+    responses(
+        (status = 200, description = "Similar records, most similar first", body = ApiResponse<Vec<SimilarRecordDto>>),
+        (status = 400, description = "`limit` is 0"),
+        (status = 403, description = "Record is above the caller's permission level"),
+        (status = 404, description = "Record not found")
+    ),
+    tag = "Records"
+)]
+pub async fn get_similar_records(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    axum::extract::Path(id): axum::extract::Path<String>,
+    axum::extract::Query(query): axum::extract::Query<SimilarRecordsQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let similar = state
+        .luna_service
+        .record_service()
+        .get_similar_records(&id, query.limit, RecordPermission::clearance(claims.role))
+        .await?;
+    let (mut records, scores): (Vec<RecordSlimDto>, Vec<f64>) = similar
+        .into_iter()
+        .map(|similar| (similar.record, similar.score))
+        .unzip();
+    attach_interaction_status_slim(&state, &claims.sub, &mut records).await?;
+    let similar: Vec<SimilarRecordDto> = records
+        .into_iter()
+        .zip(scores)
+        .map(|(record, score)| SimilarRecordDto { record, score })
+        .collect();
+    Ok(RestApiResponse::success(similar))
+}
+
+/// A random sample of the records the caller may see, optionally limited to
+/// one genre or idol, for "surprise me" picks.
+#[utoipa::path(
+    get,
+    path = "/cards/records/random",
+    params(RandomRecordsQuery),
+    responses(
+        (status = 200, description = "Random records matching the filters", body = ApiResponse<Vec<RecordDto>>),
+        (status = 400, description = "`count` is 0")
+    ),
+    tag = "Records"
+)]
+pub async fn get_random_records(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    axum::extract::Query(query): axum::extract::Query<RandomRecordsQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let mut records = state
+        .luna_service
+        .record_service()
+        .get_random_records(query, RecordPermission::clearance(claims.role))
+        .await?;
+    attach_interaction_status(&state, &claims.sub, &mut records).await?;
+    Ok(RestApiResponse::success(records))
 }
 
 #[utoipa::path(
     get,
     path = "/cards/records",
     params(
+        SearchRecordDto,
+        RecordFieldsQuery,
+        ("limit" = Option<i64>, Query, description = "Limit for pagination"),
+        ("offset" = Option<i64>, Query, description = "Offset for pagination"),
         ("liked_only" = Option<bool>, Query, description = "Filter to liked records only"),
-        ("viewed_only" = Option<bool>, Query, description = "Filter to viewed records only")
+        ("viewed_only" = Option<bool>, Query, description = "Filter to viewed records only"),
+        ("seen" = Option<bool>, Query, description = "`true` for records the caller has seen, `false` for those they have not"),
+        ("ordering" = Option<String>, Query, description = "Sort keys, e.g. `-date,title`; prefix `-` for descending"),
+        ("cursor" = Option<String>, Query, description = "Keyset cursor from `next_cursor`; empty starts keyset mode")
+    ),
+    responses(
+        (status = 200, description = "List records matching the filters; with `fields`, each result holds only the requested keys", body = ApiResponse<PaginatedResponse<RecordDto>>),
+        (status = 304, description = "Not modified since the `If-None-Match` ETag")
     ),
-    responses((status = 200, description = "List all records")),
     tag = "Records"
 )]
 pub async fn get_records(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Extension(claims): Extension<Claims>,
     axum::extract::Query(pagination): axum::extract::Query<PaginationQuery>,
+    axum::extract::Query(search_dto): axum::extract::Query<SearchRecordDto>,
+    axum::extract::Query(fields): axum::extract::Query<RecordFieldsQuery>,
 ) -> Result<impl IntoResponse, AppError> {
+    search_dto
+        .validate_date_range()
+        .map_err(AppError::ValidationError)?;
+    search_dto
+        .validate_id_lists()
+        .map_err(AppError::ValidationError)?;
+    let fields =
+        RecordFields::parse(fields.fields.as_deref()).map_err(AppError::ValidationError)?;
     let user_filter = build_user_filter(&pagination, &claims);
-    let search_dto = SearchRecordDto {
-        id: None,
-        title: None,
-        director_id: None,
-        studio_id: None,
-        label_id: None,
-        series_id: None,
-        search: None,
-    };
 
     let mut paginated_result = state
         .luna_service
         .record_service()
-        .get_record_list_paginated(search_dto, pagination, user_filter)
+        .get_record_list_paginated(search_dto, pagination, user_filter, fields.relations())
         .await?;
     attach_interaction_status(&state, &claims.sub, &mut paginated_result.results).await?;
-    Ok(RestApiResponse::success(paginated_result))
+    localize_names(&state, &headers, &mut paginated_result).await?;
+    if fields.is_full() {
+        return RestApiResponse::success(paginated_result).into_conditional_response(&headers);
+    }
+    let projected = PaginatedResponse {
+        count: paginated_result.count,
+        next: paginated_result.next,
+        previous: paginated_result.previous,
+        next_cursor: paginated_result.next_cursor,
+        results: paginated_result
+            .results
+            .iter()
+            .map(|record| fields.project(record))
+            .collect::<Vec<_>>(),
+    };
+    RestApiResponse::success(projected).into_conditional_response(&headers)
+}
+
+/// The caller's favorite records, narrowed by the usual record filters.
+#[utoipa::path(
+    get,
+    path = "/cards/records/user/favorites",
+    params(
+        SearchRecordDto,
+        ("limit" = Option<i64>, Query, description = "Limit for pagination"),
+        ("offset" = Option<i64>, Query, description = "Offset for pagination"),
+        ("liked_only" = Option<bool>, Query, description = "Filter to liked records only"),
+        ("viewed_only" = Option<bool>, Query, description = "Filter to viewed records only"),
+        ("seen" = Option<bool>, Query, description = "`true` for records the caller has seen, `false` for those they have not"),
+        ("ordering" = Option<String>, Query, description = "Sort keys, e.g. `-date,title`; prefix `-` for descending"),
+        ("cursor" = Option<String>, Query, description = "Keyset cursor from `next_cursor`; empty starts keyset mode")
+    ),
+    responses((status = 200, description = "Favorite records matching the filters", body = ApiResponse<PaginatedResponse<RecordDto>>)),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Records"
+)]
+pub async fn get_favorite_records(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    axum::extract::Query(pagination): axum::extract::Query<PaginationQuery>,
+    axum::extract::Query(search_dto): axum::extract::Query<SearchRecordDto>,
+) -> Result<impl IntoResponse, AppError> {
+    search_dto
+        .validate_date_range()
+        .map_err(AppError::ValidationError)?;
+    search_dto
+        .validate_id_lists()
+        .map_err(AppError::ValidationError)?;
+    let user_filter = build_user_filter(&pagination, &claims).map(|filter| UserFilter {
+        favorites_only: true,
+        ..filter
+    });
+
+    let mut records = state
+        .luna_service
+        .record_service()
+        .get_record_list_paginated(search_dto, pagination, user_filter, RecordRelations::ALL)
+        .await?;
+    attach_interaction_status(&state, &claims.sub, &mut records.results).await?;
+    Ok(RestApiResponse::success(records))
+}
+
+/// The records the caller has seen, most recently seen first.
+#[utoipa::path(
+    get,
+    path = "/cards/users/me/history",
+    params(
+        ("limit" = Option<i64>, Query, description = "Limit for pagination"),
+        ("offset" = Option<i64>, Query, description = "Offset for pagination")
+    ),
+    responses((status = 200, description = "Recently seen records", body = ApiResponse<PaginatedResponse<SeenRecordDto>>)),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Records"
+)]
+pub async fn get_seen_history(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    axum::extract::Query(pagination): axum::extract::Query<PaginationQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let history = state
+        .luna_service
+        .record_service()
+        .get_seen_history(
+            &claims.sub,
+            pagination,
+            RecordPermission::clearance(claims.role),
+        )
+        .await?;
+    let (mut records, seen_at): (Vec<RecordDto>, Vec<_>) = history
+        .results
+        .into_iter()
+        .map(|entry| (entry.record, entry.seen_at))
+        .unzip();
+    attach_interaction_status(&state, &claims.sub, &mut records).await?;
+    Ok(RestApiResponse::success(PaginatedResponse {
+        count: history.count,
+        next: history.next,
+        previous: history.previous,
+        next_cursor: history.next_cursor,
+        results: records
+            .into_iter()
+            .zip(seen_at)
+            .map(|(record, seen_at)| SeenRecordDto { record, seen_at })
+            .collect(),
+    }))
 }
 
 #[utoipa::path(
     post,
     path = "/cards/records",
     request_body = CreateRecordDto,
-    responses((status = 201, description = "Record created", body = RecordDto)),
+    responses((status = 201, description = "Record created", body = ApiResponse<RecordDto>)),
     tag = "Records"
 )]
 pub async fn create_record(
     State(state): State<AppState>,
-    Extension(_claims): Extension<Claims>,
+    Extension(claims): Extension<Claims>,
     Json(body): Json<CreateRecordDto>,
 ) -> Result<impl IntoResponse, AppError> {
     body.validate().map_err(|err| {
         tracing::error!("Validation error: {err}");
-        AppError::ValidationError(format!("Invalid input: {err}"))
+        AppError::InvalidInput(err)
     })?;
 
     let record = state
         .luna_service
         .record_service()
-        .create_record(body)
+        .create_record(body, &claims.sub)
         .await?;
     Ok(RestApiResponse::success(record))
 }
 
+#[utoipa::path(
+    post,
+    path = "/cards/records/bulk",
+    params(BulkCreateQuery),
+    request_body = Vec<CreateRecordDto>,
+    responses(
+        (status = 200, description = "Per-item results of the bulk create", body = ApiResponse<BulkCreateResponse>),
+        (status = 400, description = "Empty or oversized batch")
+    ),
+    tag = "Records"
+)]
+pub async fn create_records_bulk(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    axum::extract::Query(query): axum::extract::Query<BulkCreateQuery>,
+    Json(body): Json<Vec<CreateRecordDto>>,
+) -> Result<impl IntoResponse, AppError> {
+    let response = state
+        .luna_service
+        .record_service()
+        .create_records_bulk(body, query.mode, &claims.sub)
+        .await?;
+    Ok(RestApiResponse::success(response))
+}
+
 #[utoipa::path(
     put,
     path = "/cards/records/{id}",
     request_body = Vec<CreateLinkDto>,
     responses(
-        (status = 200, description = "No new links added", body = i32),
-        (status = 201, description = "New links added successfully", body = i32)
+        (status = 200, description = "No new links added", body = ApiResponse<LinkUpdateResultDto>),
+        (status = 201, description = "New links added successfully", body = ApiResponse<LinkUpdateResultDto>)
     ),
     tag = "Records"
 )]
 pub async fn update_record(
     State(state): State<AppState>,
-    Extension(_claims): Extension<Claims>,
+    Extension(claims): Extension<Claims>,
     axum::extract::Path(id): axum::extract::Path<String>,
     Json(body): Json<Vec<CreateLinkDto>>,
 ) -> Result<impl IntoResponse, AppError> {
-    let added_count = state
+    let result = state
         .luna_service
         .record_service()
-        .update_record_links(&id, body)
+        .update_record_links(&id, body, LinkUpdateMode::Append, &claims.sub)
         .await?;
 
-    if added_count > 0 {
-        Ok((StatusCode::CREATED, RestApiResponse::success(added_count)))
+    if result.inserted.is_empty() {
+        Ok((StatusCode::OK, RestApiResponse::success(result)))
     } else {
-        Ok((StatusCode::OK, RestApiResponse::success(added_count)))
+        Ok((StatusCode::CREATED, RestApiResponse::success(result)))
     }
 }
 
 #[utoipa::path(
     patch,
     path = "/cards/records/{id}",
-    request_body = UpdateRecordDto,
-    responses((status = 200, description = "Record partially updated", body = RecordDto)),
+    request_body = PatchRecordDto,
+    params(("If-Match" = Option<String>, Header, description = "Record version the edit is based on")),
+    responses(
+        (status = 200, description = "Record partially updated", body = ApiResponse<RecordDto>),
+        (status = 412, description = "`If-Match` version is stale; `data.version` is the current one")
+    ),
     tag = "Records"
 )]
 pub async fn patch_record(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Extension(claims): Extension<Claims>,
     axum::extract::Path(id): axum::extract::Path<String>,
-    Json(body): Json<UpdateRecordDto>,
+    Json(body): Json<PatchRecordDto>,
 ) -> Result<impl IntoResponse, AppError> {
+    body.validate().map_err(|err| {
+        tracing::error!("Validation error: {err}");
+        AppError::InvalidInput(err)
+    })?;
+
     let record = state
         .luna_service
         .record_service()
-        .update_record(&id, body)
+        .patch_record(&id, body, if_match_version(&headers)?, &claims.sub)
+        .await?;
+    let mut records = vec![record];
+    attach_interaction_status(&state, &claims.sub, &mut records).await?;
+    Ok(RestApiResponse::success(
+        records.into_iter().next().expect("vec has one element"),
+    ))
+}
+
+#[utoipa::path(
+    put,
+    path = "/cards/records/{id}/full",
+    request_body = CreateRecordDto,
+    params(
+        ("If-Match" = Option<String>, Header, description = "Record version the edit is based on"),
+        ReplaceRecordQuery
+    ),
+    responses(
+        (status = 200, description = "Record and its relations replaced", body = ApiResponse<RecordDto>),
+        (status = 400, description = "Invalid input or body id does not match path id"),
+        (status = 412, description = "`If-Match` version is stale; `data.version` is the current one")
+    ),
+    tag = "Records"
+)]
+pub async fn replace_record_full(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(claims): Extension<Claims>,
+    axum::extract::Path(id): axum::extract::Path<String>,
+    axum::extract::Query(query): axum::extract::Query<ReplaceRecordQuery>,
+    Json(body): Json<CreateRecordDto>,
+) -> Result<impl IntoResponse, AppError> {
+    body.validate().map_err(|err| {
+        tracing::error!("Validation error: {err}");
+        AppError::InvalidInput(err)
+    })?;
+
+    let record = state
+        .luna_service
+        .record_service()
+        .replace_record(
+            &id,
+            body,
+            query.override_manual,
+            if_match_version(&headers)?,
+            &claims.sub,
+        )
         .await?;
     let mut records = vec![record];
     attach_interaction_status(&state, &claims.sub, &mut records).await?;
@@ -211,27 +578,121 @@ pub async fn patch_record(
     patch,
     path = "/cards/records/links/{id}",
     request_body = Vec<CreateLinkDto>,
-    responses((status = 200, description = "Record links updated", body = i32)),
+    params(UpdateRecordLinksQuery),
+    responses((status = 200, description = "Record links updated; lists the links inserted, updated, removed and skipped", body = ApiResponse<LinkUpdateResultDto>)),
     tag = "Records"
 )]
 pub async fn update_record_links(
     State(state): State<AppState>,
-    Extension(_claims): Extension<Claims>,
+    Extension(claims): Extension<Claims>,
     axum::extract::Path(id): axum::extract::Path<String>,
+    axum::extract::Query(query): axum::extract::Query<UpdateRecordLinksQuery>,
     Json(body): Json<Vec<CreateLinkDto>>,
 ) -> Result<impl IntoResponse, AppError> {
-    let added_count = state
+    let result = state
         .luna_service
         .record_service()
-        .update_record_links(&id, body)
+        .update_record_links(&id, body, query.mode, &claims.sub)
+        .await?;
+    Ok(RestApiResponse::success(result))
+}
+
+#[utoipa::path(
+    put,
+    path = "/cards/records/{id}/cover",
+    request_body = SetRecordCoverDto,
+    responses(
+        (status = 200, description = "Cover image chosen; returns the record with its new `cover_url`", body = ApiResponse<RecordDto>),
+        (status = 400, description = "The record has no uploaded image `n`"),
+        (status = 404, description = "Record not found")
+    ),
+    tag = "Records"
+)]
+pub async fn set_record_cover(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    axum::extract::Path(id): axum::extract::Path<String>,
+    Json(body): Json<SetRecordCoverDto>,
+) -> Result<impl IntoResponse, AppError> {
+    let record_service = state.luna_service.record_service();
+    record_service.get_record_by_id(&id).await?;
+
+    // Only an image the media route actually serves can be the cover
+    let url = format!("/cards/media/{id}");
+    let images = state
+        .luna_service
+        .file_service()
+        .list_media(MediaType::RecordImage, &id, &url)
+        .await?;
+    if !images.iter().any(|image| image.n == body.n) {
+        return Err(AppError::ValidationError(match body.n {
+            Some(n) => format!("Record '{id}' has no image {n}"),
+            None => format!("Record '{id}' has no main image"),
+        }));
+    }
+    let cover_index = body
+        .n
+        .map(|n| {
+            i32::try_from(n).map_err(|_| AppError::ValidationError("n is too large".to_owned()))
+        })
+        .transpose()?;
+
+    record_service.set_cover_index(&id, cover_index).await?;
+    let record = record_service.get_record_by_id(&id).await?;
+    let mut records = vec![record];
+    attach_interaction_status(&state, &claims.sub, &mut records).await?;
+    Ok(RestApiResponse::success(
+        records.into_iter().next().expect("vec has one element"),
+    ))
+}
+
+/// Sets the position of a record within its series, used by
+/// `ordering=series_order` and the `series_previous`/`series_next` links.
+#[utoipa::path(
+    put,
+    path = "/cards/records/{id}/series-order",
+    request_body = SetSeriesOrderDto,
+    responses(
+        (status = 200, description = "Series order set; returns the record", body = ApiResponse<RecordDto>),
+        (status = 400, description = "The record is not in a series, or the order is below 1"),
+        (status = 404, description = "Record not found")
+    ),
+    tag = "Records"
+)]
+pub async fn set_record_series_order(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    axum::extract::Path(id): axum::extract::Path<String>,
+    Json(body): Json<SetSeriesOrderDto>,
+) -> Result<impl IntoResponse, AppError> {
+    body.validate().map_err(|err| {
+        tracing::error!("Validation error: {err}");
+        AppError::InvalidInput(err)
+    })?;
+
+    let record_service = state.luna_service.record_service();
+    let record = record_service.get_record_by_id(&id).await?;
+    if record.series.id == 0 {
+        return Err(AppError::ValidationError(format!(
+            "Record '{id}' is not in a series"
+        )));
+    }
+
+    record_service
+        .set_order_in_series(&id, body.order_in_series)
         .await?;
-    Ok(RestApiResponse::success(added_count))
+    let record = record_service.get_record_by_id(&id).await?;
+    let mut records = vec![record];
+    attach_interaction_status(&state, &claims.sub, &mut records).await?;
+    Ok(RestApiResponse::success(
+        records.into_iter().next().expect("vec has one element"),
+    ))
 }
 
 #[utoipa::path(
     delete,
     path = "/cards/records/{id}",
-    responses((status = 204, description = "Record deleted")),
+    responses((status = 204, description = "Record moved to trash")),
     tag = "Records"
 )]
 pub async fn delete_record(
@@ -247,6 +708,145 @@ pub async fn delete_record(
     Ok(RestApiResponse::success_with_message(message, ()))
 }
 
+#[utoipa::path(
+    get,
+    path = "/cards/records/trash",
+    params(
+        ("limit" = Option<i64>, Query, description = "Limit for pagination"),
+        ("offset" = Option<i64>, Query, description = "Offset for pagination")
+    ),
+    responses(
+        (status = 200, description = "Trashed records the caller may see, most recently deleted first", body = ApiResponse<PaginatedResponse<RecordDto>>),
+        (status = 403, description = "Caller is not an editor")
+    ),
+    tag = "Records"
+)]
+pub async fn get_record_trash(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    axum::extract::Query(pagination): axum::extract::Query<PaginationQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let result = state
+        .luna_service
+        .record_service()
+        .get_trash_paginated(pagination, RecordPermission::clearance(claims.role))
+        .await?;
+    Ok(RestApiResponse::success(result))
+}
+
+#[utoipa::path(
+    post,
+    path = "/cards/records/{id}/restore",
+    responses(
+        (status = 200, description = "Record restored from trash", body = ApiResponse<RecordDto>),
+        (status = 404, description = "No trashed record with this ID")
+    ),
+    tag = "Records"
+)]
+pub async fn restore_record(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    axum::extract::Path(id): axum::extract::Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let record = state
+        .luna_service
+        .record_service()
+        .restore_record(&id)
+        .await?;
+    let mut records = vec![record];
+    attach_interaction_status(&state, &claims.sub, &mut records).await?;
+    Ok(RestApiResponse::success(
+        records.into_iter().next().expect("vec has one element"),
+    ))
+}
+
+#[utoipa::path(
+    post,
+    path = "/cards/records/{id}/merge",
+    request_body = MergeRecordDto,
+    params(("If-Match" = Option<String>, Header, description = "Version of the surviving record the merge is based on")),
+    responses(
+        (status = 200, description = "Duplicate record merged into this one and deleted", body = ApiResponse<RecordDto>),
+        (status = 400, description = "Record merged into itself"),
+        (status = 404, description = "Either record not found or in the trash"),
+        (status = 412, description = "`If-Match` version is stale; `data.version` is the current one")
+    ),
+    tag = "Records"
+)]
+pub async fn merge_record(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(claims): Extension<Claims>,
+    axum::extract::Path(id): axum::extract::Path<String>,
+    Json(body): Json<MergeRecordDto>,
+) -> Result<impl IntoResponse, AppError> {
+    let record = state
+        .luna_service
+        .record_service()
+        .merge_records(
+            &id,
+            &body.source_id,
+            if_match_version(&headers)?,
+            &claims.sub,
+        )
+        .await?;
+    let mut records = vec![record];
+    attach_interaction_status(&state, &claims.sub, &mut records).await?;
+    Ok(RestApiResponse::success(
+        records.into_iter().next().expect("vec has one element"),
+    ))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/cards/records/{id}/purge",
+    responses(
+        (status = 204, description = "Trashed record permanently deleted"),
+        (status = 404, description = "No trashed record with this ID")
+    ),
+    tag = "Records"
+)]
+pub async fn purge_record(
+    State(state): State<AppState>,
+    Extension(_claims): Extension<Claims>,
+    axum::extract::Path(id): axum::extract::Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let message = state
+        .luna_service
+        .record_service()
+        .purge_record(&id)
+        .await?;
+    Ok(RestApiResponse::success_with_message(message, ()))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/cards/records",
+    request_body = BulkDeleteRecordsDto,
+    responses(
+        (status = 200, description = "Counts of trashed or purged rows", body = ApiResponse<BulkDeleteRecordsResponse>),
+        (status = 400, description = "Empty or oversized ID list, or `delete_media` without `purge`")
+    ),
+    tag = "Records"
+)]
+pub async fn delete_records_bulk(
+    State(state): State<AppState>,
+    Extension(_claims): Extension<Claims>,
+    Json(body): Json<BulkDeleteRecordsDto>,
+) -> Result<impl IntoResponse, AppError> {
+    body.validate().map_err(|err| {
+        tracing::error!("Validation error: {err}");
+        AppError::InvalidInput(err)
+    })?;
+
+    let counts = state
+        .luna_service
+        .record_service()
+        .delete_records_bulk(body)
+        .await?;
+    Ok(RestApiResponse::success(counts))
+}
+
 // Records by entity handlers
 #[utoipa::path(
     get,
@@ -256,9 +856,12 @@ pub async fn delete_record(
         ("limit" = Option<i64>, Query, description = "Limit for pagination"),
         ("offset" = Option<i64>, Query, description = "Offset for pagination"),
         ("liked_only" = Option<bool>, Query, description = "Filter to liked records only"),
-        ("viewed_only" = Option<bool>, Query, description = "Filter to viewed records only")
+        ("viewed_only" = Option<bool>, Query, description = "Filter to viewed records only"),
+        ("seen" = Option<bool>, Query, description = "`true` for records the caller has seen, `false` for those they have not"),
+        ("ordering" = Option<String>, Query, description = "Sort keys, e.g. `-date,title`; prefix `-` for descending"),
+        ("cursor" = Option<String>, Query, description = "Keyset cursor from `next_cursor`; empty starts keyset mode")
     ),
-    responses((status = 200, description = "Get records by director", body = PaginatedResponse<RecordDto>)),
+    responses((status = 200, description = "Get records by director", body = ApiResponse<PaginatedResponse<RecordDto>>)),
     tag = "Records"
 )]
 pub async fn get_records_by_director(
@@ -275,12 +878,16 @@ pub async fn get_records_by_director(
     let viewed_only = params
         .get("viewed_only")
         .and_then(|s| s.parse::<bool>().ok());
+    let seen = params.get("seen").and_then(|s| s.parse::<bool>().ok());
 
     let pagination = PaginationQuery {
         limit,
         offset,
         liked_only,
         viewed_only,
+        seen,
+        ordering: params.get("ordering").cloned(),
+        cursor: params.get("cursor").cloned(),
     };
     let user_filter = build_user_filter(&pagination, &claims);
 
@@ -301,9 +908,12 @@ pub async fn get_records_by_director(
         ("limit" = Option<i64>, Query, description = "Limit for pagination"),
         ("offset" = Option<i64>, Query, description = "Offset for pagination"),
         ("liked_only" = Option<bool>, Query, description = "Filter to liked records only"),
-        ("viewed_only" = Option<bool>, Query, description = "Filter to viewed records only")
+        ("viewed_only" = Option<bool>, Query, description = "Filter to viewed records only"),
+        ("seen" = Option<bool>, Query, description = "`true` for records the caller has seen, `false` for those they have not"),
+        ("ordering" = Option<String>, Query, description = "Sort keys, e.g. `-date,title`; prefix `-` for descending"),
+        ("cursor" = Option<String>, Query, description = "Keyset cursor from `next_cursor`; empty starts keyset mode")
     ),
-    responses((status = 200, description = "Get records by studio", body = PaginatedResponse<RecordDto>)),
+    responses((status = 200, description = "Get records by studio", body = ApiResponse<PaginatedResponse<RecordDto>>)),
     tag = "Records"
 )]
 pub async fn get_records_by_studio(
@@ -320,12 +930,16 @@ pub async fn get_records_by_studio(
     let viewed_only = params
         .get("viewed_only")
         .and_then(|s| s.parse::<bool>().ok());
+    let seen = params.get("seen").and_then(|s| s.parse::<bool>().ok());
 
     let pagination = PaginationQuery {
         limit,
         offset,
         liked_only,
         viewed_only,
+        seen,
+        ordering: params.get("ordering").cloned(),
+        cursor: params.get("cursor").cloned(),
     };
     let user_filter = build_user_filter(&pagination, &claims);
 
@@ -346,9 +960,12 @@ pub async fn get_records_by_studio(
         ("limit" = Option<i64>, Query, description = "Limit for pagination"),
         ("offset" = Option<i64>, Query, description = "Offset for pagination"),
         ("liked_only" = Option<bool>, Query, description = "Filter to liked records only"),
-        ("viewed_only" = Option<bool>, Query, description = "Filter to viewed records only")
+        ("viewed_only" = Option<bool>, Query, description = "Filter to viewed records only"),
+        ("seen" = Option<bool>, Query, description = "`true` for records the caller has seen, `false` for those they have not"),
+        ("ordering" = Option<String>, Query, description = "Sort keys, e.g. `-date,title`; prefix `-` for descending"),
+        ("cursor" = Option<String>, Query, description = "Keyset cursor from `next_cursor`; empty starts keyset mode")
     ),
-    responses((status = 200, description = "Get records by label", body = PaginatedResponse<RecordDto>)),
+    responses((status = 200, description = "Get records by label", body = ApiResponse<PaginatedResponse<RecordDto>>)),
     tag = "Records"
 )]
 pub async fn get_records_by_label(
@@ -365,12 +982,16 @@ pub async fn get_records_by_label(
     let viewed_only = params
         .get("viewed_only")
         .and_then(|s| s.parse::<bool>().ok());
+    let seen = params.get("seen").and_then(|s| s.parse::<bool>().ok());
 
     let pagination = PaginationQuery {
         limit,
         offset,
         liked_only,
         viewed_only,
+        seen,
+        ordering: params.get("ordering").cloned(),
+        cursor: params.get("cursor").cloned(),
     };
     let user_filter = build_user_filter(&pagination, &claims);
 
@@ -391,9 +1012,16 @@ pub async fn get_records_by_label(
         ("limit" = Option<i64>, Query, description = "Limit for pagination"),
         ("offset" = Option<i64>, Query, description = "Offset for pagination"),
         ("liked_only" = Option<bool>, Query, description = "Filter to liked records only"),
-        ("viewed_only" = Option<bool>, Query, description = "Filter to viewed records only")
+        ("viewed_only" = Option<bool>, Query, description = "Filter to viewed records only"),
+        ("seen" = Option<bool>, Query, description = "`true` for records the caller has seen, `false` for those they have not"),
+        ("ordering" = Option<String>, Query, description = "Sort keys, e.g. `-date,title`; prefix `-` for descending"),
+        ("ordered" = Option<bool>, Query, description = "`true` to list the records in series order, i.e. `ordering=series_order`"),
+        ("cursor" = Option<String>, Query, description = "Keyset cursor from `next_cursor`; empty starts keyset mode")
+    ),
+    responses(
+        (status = 200, description = "Get records by series", body = ApiResponse<PaginatedResponse<RecordDto>>),
+        (status = 400, description = "`ordered` combined with `ordering` or `cursor`")
     ),
-    responses((status = 200, description = "Get records by series", body = PaginatedResponse<RecordDto>)),
     tag = "Records"
 )]
 pub async fn get_records_by_series(
@@ -410,12 +1038,30 @@ pub async fn get_records_by_series(
     let viewed_only = params
         .get("viewed_only")
         .and_then(|s| s.parse::<bool>().ok());
+    let seen = params.get("seen").and_then(|s| s.parse::<bool>().ok());
+    let ordered = params
+        .get("ordered")
+        .and_then(|s| s.parse::<bool>().ok())
+        .unwrap_or(false);
+
+    let mut ordering = params.get("ordering").cloned();
+    if ordered {
+        if ordering.is_some() || params.contains_key("cursor") {
+            return Err(AppError::ValidationError(
+                "`ordered` cannot be combined with `ordering` or `cursor`".to_string(),
+            ));
+        }
+        ordering = Some("series_order".to_string());
+    }
 
     let pagination = PaginationQuery {
         limit,
         offset,
         liked_only,
         viewed_only,
+        seen,
+        ordering,
+        cursor: params.get("cursor").cloned(),
     };
     let user_filter = build_user_filter(&pagination, &claims);
 
@@ -436,9 +1082,12 @@ pub async fn get_records_by_series(
         ("limit" = Option<i64>, Query, description = "Limit for pagination"),
         ("offset" = Option<i64>, Query, description = "Offset for pagination"),
         ("liked_only" = Option<bool>, Query, description = "Filter to liked records only"),
-        ("viewed_only" = Option<bool>, Query, description = "Filter to viewed records only")
+        ("viewed_only" = Option<bool>, Query, description = "Filter to viewed records only"),
+        ("seen" = Option<bool>, Query, description = "`true` for records the caller has seen, `false` for those they have not"),
+        ("ordering" = Option<String>, Query, description = "Sort keys, e.g. `-date,title`; prefix `-` for descending"),
+        ("cursor" = Option<String>, Query, description = "Keyset cursor from `next_cursor`; empty starts keyset mode")
     ),
-    responses((status = 200, description = "Get records by genre", body = PaginatedResponse<RecordDto>)),
+    responses((status = 200, description = "Get records by genre", body = ApiResponse<PaginatedResponse<RecordDto>>)),
     tag = "Records"
 )]
 pub async fn get_records_by_genre(
@@ -455,12 +1104,16 @@ pub async fn get_records_by_genre(
     let viewed_only = params
         .get("viewed_only")
         .and_then(|s| s.parse::<bool>().ok());
+    let seen = params.get("seen").and_then(|s| s.parse::<bool>().ok());
 
     let pagination = PaginationQuery {
         limit,
         offset,
         liked_only,
         viewed_only,
+        seen,
+        ordering: params.get("ordering").cloned(),
+        cursor: params.get("cursor").cloned(),
     };
     let user_filter = build_user_filter(&pagination, &claims);
 
@@ -481,9 +1134,12 @@ pub async fn get_records_by_genre(
         ("limit" = Option<i64>, Query, description = "Limit for pagination"),
         ("offset" = Option<i64>, Query, description = "Offset for pagination"),
         ("liked_only" = Option<bool>, Query, description = "Filter to liked records only"),
-        ("viewed_only" = Option<bool>, Query, description = "Filter to viewed records only")
+        ("viewed_only" = Option<bool>, Query, description = "Filter to viewed records only"),
+        ("seen" = Option<bool>, Query, description = "`true` for records the caller has seen, `false` for those they have not"),
+        ("ordering" = Option<String>, Query, description = "Sort keys, e.g. `-date,title`; prefix `-` for descending"),
+        ("cursor" = Option<String>, Query, description = "Keyset cursor from `next_cursor`; empty starts keyset mode")
     ),
-    responses((status = 200, description = "Get records by idol", body = PaginatedResponse<RecordDto>)),
+    responses((status = 200, description = "Get records by idol", body = ApiResponse<PaginatedResponse<RecordDto>>)),
     tag = "Records"
 )]
 pub async fn get_records_by_idol(
@@ -500,12 +1156,16 @@ pub async fn get_records_by_idol(
     let viewed_only = params
         .get("viewed_only")
         .and_then(|s| s.parse::<bool>().ok());
+    let seen = params.get("seen").and_then(|s| s.parse::<bool>().ok());
 
     let pagination = PaginationQuery {
         limit,
         offset,
         liked_only,
         viewed_only,
+        seen,
+        ordering: params.get("ordering").cloned(),
+        cursor: params.get("cursor").cloned(),
     };
     let user_filter = build_user_filter(&pagination, &claims);
 
@@ -525,7 +1185,7 @@ pub async fn get_records_by_idol(
         ("liked_only" = Option<bool>, Query, description = "Filter to liked records only"),
         ("viewed_only" = Option<bool>, Query, description = "Filter to viewed records only")
     ),
-    responses((status = 200, description = "Get all record slim data", body = Vec<RecordSlimDto>)),
+    responses((status = 200, description = "Get all record slim data", body = ApiResponse<Vec<RecordSlimDto>>)),
     tag = "Records"
 )]
 pub async fn get_all_record_slim_all(
@@ -547,24 +1207,34 @@ pub async fn get_all_record_slim_all(
     get,
     path = "/cards/records/slim",
     params(
+        SearchRecordDto,
         ("limit" = Option<i64>, Query, description = "Limit for pagination"),
         ("offset" = Option<i64>, Query, description = "Offset for pagination"),
         ("liked_only" = Option<bool>, Query, description = "Filter to liked records only"),
-        ("viewed_only" = Option<bool>, Query, description = "Filter to viewed records only")
+        ("viewed_only" = Option<bool>, Query, description = "Filter to viewed records only"),
+        ("seen" = Option<bool>, Query, description = "`true` for records the caller has seen, `false` for those they have not"),
+        ("ordering" = Option<String>, Query, description = "Sort keys, e.g. `-date,title`; prefix `-` for descending")
     ),
-    responses((status = 200, description = "Get slim records with pagination", body = PaginatedResponse<RecordSlimDto>)),
+    responses((status = 200, description = "Get slim records with pagination", body = ApiResponse<PaginatedResponse<RecordSlimDto>>)),
     tag = "Records"
 )]
 pub async fn get_record_slim_paginated(
     State(state): State<AppState>,
     Extension(claims): Extension<Claims>,
     axum::extract::Query(pagination): axum::extract::Query<PaginationQuery>,
+    axum::extract::Query(search_dto): axum::extract::Query<SearchRecordDto>,
 ) -> Result<impl IntoResponse, AppError> {
+    search_dto
+        .validate_date_range()
+        .map_err(AppError::ValidationError)?;
+    search_dto
+        .validate_id_lists()
+        .map_err(AppError::ValidationError)?;
     let user_filter = build_user_filter(&pagination, &claims);
     let mut result = state
         .luna_service
         .record_service()
-        .get_record_slim_paginated(pagination, user_filter)
+        .get_record_slim_paginated(search_dto, pagination, user_filter)
         .await?;
     attach_interaction_status_slim(&state, &claims.sub, &mut result.results).await?;
     Ok(RestApiResponse::success(result))
@@ -577,7 +1247,7 @@ pub async fn get_record_slim_paginated(
         ("liked_only" = Option<bool>, Query, description = "Filter to liked records only"),
         ("viewed_only" = Option<bool>, Query, description = "Filter to viewed records only")
     ),
-    responses((status = 200, description = "Get all record IDs", body = Vec<String>)),
+    responses((status = 200, description = "Get all record IDs", body = ApiResponse<Vec<String>>)),
     tag = "Records"
 )]
 pub async fn get_all_record_ids_all(
@@ -601,9 +1271,11 @@ pub async fn get_all_record_ids_all(
         ("limit" = Option<i64>, Query, description = "Limit for pagination"),
         ("offset" = Option<i64>, Query, description = "Offset for pagination"),
         ("liked_only" = Option<bool>, Query, description = "Filter to liked records only"),
-        ("viewed_only" = Option<bool>, Query, description = "Filter to viewed records only")
+        ("viewed_only" = Option<bool>, Query, description = "Filter to viewed records only"),
+        ("seen" = Option<bool>, Query, description = "`true` for records the caller has seen, `false` for those they have not"),
+        ("ordering" = Option<String>, Query, description = "Sort keys, e.g. `-date,title`; prefix `-` for descending")
     ),
-    responses((status = 200, description = "Get record IDs with pagination", body = PaginatedResponse<String>)),
+    responses((status = 200, description = "Get record IDs with pagination", body = ApiResponse<PaginatedResponse<String>>)),
     tag = "Records"
 )]
 pub async fn get_record_ids_paginated(
@@ -619,3 +1291,30 @@ pub async fn get_record_ids_paginated(
         .await?;
     Ok(RestApiResponse::success(result))
 }
+
+#[utoipa::path(
+    get,
+    path = "/cards/sync/records",
+    params(RecordSyncQuery),
+    responses(
+        (status = 200, description = "Records changed and deleted after `since`", body = ApiResponse<RecordSyncResponse>),
+        (status = 400, description = "Invalid limit")
+    ),
+    tag = "Records"
+)]
+pub async fn sync_records(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    axum::extract::Query(query): axum::extract::Query<RecordSyncQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let changes = state
+        .luna_service
+        .record_service()
+        .sync_records(
+            query.since.unwrap_or(0),
+            query.limit,
+            RecordPermission::clearance(claims.role),
+        )
+        .await?;
+    Ok(RestApiResponse::success(changes))
+}
@@ -0,0 +1,89 @@
+use crate::{
+    common::{
+        app_state::AppState,
+        dto::{ApiResponse, RestApiResponse},
+        error::AppError,
+        etag::if_match_version,
+        jwt::Claims,
+    },
+    domains::luna::dto::{PaginatedResponse, PaginationQuery, RecordDto, RecordRevisionDto},
+};
+
+use axum::{
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    response::IntoResponse,
+    Extension,
+};
+
+use super::comment::ensure_record_visible;
+use super::record::attach_interaction_status;
+
+/// Lists the revisions of a record: its state before each edit and who made
+/// the edit.
+#[utoipa::path(
+    get,
+    path = "/cards/records/{id}/history",
+    params(
+        ("id" = String, Path, description = "Record ID"),
+        ("limit" = Option<i64>, Query, description = "Limit for pagination"),
+        ("offset" = Option<i64>, Query, description = "Offset for pagination")
+    ),
+    responses(
+        (status = 200, description = "Revisions of the record, newest first", body = ApiResponse<PaginatedResponse<RecordRevisionDto>>),
+        (status = 403, description = "Record is above the caller's permission level"),
+        (status = 404, description = "Record not found")
+    ),
+    tag = "Records"
+)]
+pub async fn get_record_history(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<String>,
+    Query(pagination): Query<PaginationQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_record_visible(&state, &claims, &id).await?;
+    let history = state
+        .luna_service
+        .record_service()
+        .get_record_history(&id, pagination)
+        .await?;
+    Ok(RestApiResponse::success(history))
+}
+
+/// Restores a record to the state stored in one of its revisions, undoing
+/// the edits made since.
+#[utoipa::path(
+    post,
+    path = "/cards/records/{id}/revert/{revision}",
+    params(
+        ("id" = String, Path, description = "Record ID"),
+        ("revision" = i64, Path, description = "Revision ID"),
+        ("If-Match" = Option<String>, Header, description = "Record version the revert is based on")
+    ),
+    responses(
+        (status = 200, description = "Record restored", body = ApiResponse<RecordDto>),
+        (status = 403, description = "Caller is not an editor or the record is above their permission level"),
+        (status = 404, description = "Record not found or revision not of the record"),
+        (status = 412, description = "`If-Match` version is stale; `data.version` is the current one")
+    ),
+    tag = "Records"
+)]
+pub async fn revert_record(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(claims): Extension<Claims>,
+    Path((id, revision)): Path<(String, i64)>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_record_visible(&state, &claims, &id).await?;
+    let record = state
+        .luna_service
+        .record_service()
+        .revert_record(&id, revision, if_match_version(&headers)?, &claims.sub)
+        .await?;
+    let mut records = vec![record];
+    attach_interaction_status(&state, &claims.sub, &mut records).await?;
+    Ok(RestApiResponse::success(
+        records.into_iter().next().expect("vec has one element"),
+    ))
+}
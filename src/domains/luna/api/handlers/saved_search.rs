@@ -0,0 +1,227 @@
+use crate::{
+    common::{
+        app_state::AppState,
+        dto::{ApiResponse, RestApiResponse},
+        error::AppError,
+        jwt::Claims,
+    },
+    domains::luna::dto::{
+        CreateSavedSearchDto, PaginatedResponse, PaginationQuery, RecordDto, RecordRelations,
+        SavedSearchDto,
+    },
+};
+
+use axum::{
+    extract::{Path, Query, State},
+    response::IntoResponse,
+    Extension, Json,
+};
+
+use validator::Validate as _;
+
+use super::record::{attach_interaction_status, build_user_filter};
+
+#[utoipa::path(
+    get,
+    path = "/cards/saved-searches",
+    params(
+        ("limit" = Option<i64>, Query, description = "Limit for pagination"),
+        ("offset" = Option<i64>, Query, description = "Offset for pagination")
+    ),
+    responses((status = 200, description = "The caller's saved searches, by name", body = ApiResponse<PaginatedResponse<SavedSearchDto>>)),
+    tag = "Saved Searches"
+)]
+pub async fn get_saved_searches(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Query(pagination): Query<PaginationQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let searches = state
+        .luna_service
+        .saved_search_service()
+        .list_own(&claims.sub, pagination)
+        .await?;
+    Ok(RestApiResponse::success(searches))
+}
+
+#[utoipa::path(
+    get,
+    path = "/cards/saved-searches/public",
+    params(
+        ("limit" = Option<i64>, Query, description = "Limit for pagination"),
+        ("offset" = Option<i64>, Query, description = "Offset for pagination")
+    ),
+    responses((status = 200, description = "Searches shared by every user, newest first", body = ApiResponse<PaginatedResponse<SavedSearchDto>>)),
+    tag = "Saved Searches"
+)]
+pub async fn get_public_saved_searches(
+    State(state): State<AppState>,
+    Query(pagination): Query<PaginationQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let searches = state
+        .luna_service
+        .saved_search_service()
+        .list_public(pagination)
+        .await?;
+    Ok(RestApiResponse::success(searches))
+}
+
+#[utoipa::path(
+    post,
+    path = "/cards/saved-searches",
+    request_body = CreateSavedSearchDto,
+    responses(
+        (status = 201, description = "Search saved", body = ApiResponse<SavedSearchDto>),
+        (status = 400, description = "Invalid name, filters or ordering"),
+        (status = 409, description = "The caller already has a search with this name")
+    ),
+    tag = "Saved Searches"
+)]
+pub async fn create_saved_search(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<CreateSavedSearchDto>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate().map_err(|err| {
+        tracing::error!("Validation error: {err}");
+        AppError::InvalidInput(err)
+    })?;
+
+    let search = state
+        .luna_service
+        .saved_search_service()
+        .create_saved_search(&claims.sub, payload)
+        .await?;
+    Ok(RestApiResponse::success(search))
+}
+
+#[utoipa::path(
+    get,
+    path = "/cards/saved-searches/{id}",
+    params(("id" = i64, Path, description = "Saved search ID")),
+    responses(
+        (status = 200, description = "Get a saved search the caller owns or that is public", body = ApiResponse<SavedSearchDto>),
+        (status = 404, description = "Saved search not found, or private to another user")
+    ),
+    tag = "Saved Searches"
+)]
+pub async fn get_saved_search_by_id(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, AppError> {
+    let search = state
+        .luna_service
+        .saved_search_service()
+        .get_saved_search(id, &claims.sub)
+        .await?;
+    Ok(RestApiResponse::success(search))
+}
+
+#[utoipa::path(
+    put,
+    path = "/cards/saved-searches/{id}",
+    params(("id" = i64, Path, description = "Saved search ID")),
+    request_body = CreateSavedSearchDto,
+    responses(
+        (status = 200, description = "Saved search replaced", body = ApiResponse<SavedSearchDto>),
+        (status = 400, description = "Invalid name, filters or ordering"),
+        (status = 403, description = "Public search owned by another user"),
+        (status = 404, description = "Saved search not found, or private to another user"),
+        (status = 409, description = "The caller already has a search with this name")
+    ),
+    tag = "Saved Searches"
+)]
+pub async fn update_saved_search(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<i64>,
+    Json(payload): Json<CreateSavedSearchDto>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate().map_err(|err| {
+        tracing::error!("Validation error: {err}");
+        AppError::InvalidInput(err)
+    })?;
+
+    let search = state
+        .luna_service
+        .saved_search_service()
+        .update_saved_search(id, &claims.sub, payload)
+        .await?;
+    Ok(RestApiResponse::success(search))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/cards/saved-searches/{id}",
+    params(("id" = i64, Path, description = "Saved search ID")),
+    responses(
+        (status = 204, description = "Saved search deleted"),
+        (status = 403, description = "Public search owned by another user"),
+        (status = 404, description = "Saved search not found, or private to another user")
+    ),
+    tag = "Saved Searches"
+)]
+pub async fn delete_saved_search(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, AppError> {
+    state
+        .luna_service
+        .saved_search_service()
+        .delete_saved_search(id, &claims.sub)
+        .await?;
+    Ok(RestApiResponse::success(()))
+}
+
+/// Runs a saved search as the caller: records above the caller's clearance
+/// stay hidden even when the search belongs to someone else.
+#[utoipa::path(
+    get,
+    path = "/cards/saved-searches/{id}/results",
+    params(
+        ("id" = i64, Path, description = "Saved search ID"),
+        ("limit" = Option<i64>, Query, description = "Limit for pagination"),
+        ("offset" = Option<i64>, Query, description = "Offset for pagination"),
+        ("liked_only" = Option<bool>, Query, description = "Filter to liked records only"),
+        ("viewed_only" = Option<bool>, Query, description = "Filter to viewed records only"),
+        ("seen" = Option<bool>, Query, description = "`true` for records the caller has seen, `false` for those they have not"),
+        ("ordering" = Option<String>, Query, description = "Sort keys overriding the saved ordering, e.g. `-date,title`"),
+        ("cursor" = Option<String>, Query, description = "Keyset cursor from `next_cursor`; only for searches without an ordering")
+    ),
+    responses(
+        (status = 200, description = "Records matching the saved filters", body = ApiResponse<PaginatedResponse<RecordDto>>),
+        (status = 404, description = "Saved search not found, or private to another user")
+    ),
+    tag = "Saved Searches"
+)]
+pub async fn get_saved_search_results(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<i64>,
+    Query(mut pagination): Query<PaginationQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let search = state
+        .luna_service
+        .saved_search_service()
+        .get_saved_search(id, &claims.sub)
+        .await?;
+    if pagination.ordering.is_none() {
+        pagination.ordering = search.ordering;
+    }
+    let user_filter = build_user_filter(&pagination, &claims);
+
+    let mut paginated_result = state
+        .luna_service
+        .record_service()
+        .get_record_list_paginated(
+            search.filters,
+            pagination,
+            user_filter,
+            RecordRelations::ALL,
+        )
+        .await?;
+    attach_interaction_status(&state, &claims.sub, &mut paginated_result.results).await?;
+    Ok(RestApiResponse::success(paginated_result))
+}
@@ -1,63 +1,138 @@
 use crate::{
-    common::{app_state::AppState, dto::RestApiResponse, error::AppError, jwt::Claims},
+    common::{
+        app_state::AppState,
+        dto::{ApiResponse, RestApiResponse},
+        error::AppError,
+        jwt::Claims,
+    },
     domains::luna::dto::{
-        CreateSeriesDto, PaginationQuery, SearchSeriesDto, SeriesDto, UpdateSeriesDto,
+        AutocompleteQuery, BatchLookupDto, CreateSeriesDto, MergeEntityDto, MergeEntityResponse,
+        NameSuggestionDto, PaginatedResponse, PaginationQuery, PatchSeriesDto, SearchSeriesDto,
+        SeriesDto, UpdateSeriesDto,
     },
 };
 
-use axum::{extract::State, response::IntoResponse, Extension, Json};
+use axum::{extract::State, http::HeaderMap, response::IntoResponse, Extension, Json};
 
 use validator::Validate as _;
 
+use super::translation::localize_names;
+
 // Series handlers
 #[utoipa::path(
     get,
     path = "/cards/series/{id}",
-    responses((status = 200, description = "Get series by ID", body = SeriesDto)),
+    responses(
+        (status = 200, description = "Get series by ID", body = ApiResponse<SeriesDto>),
+        (status = 304, description = "Not modified since the `If-None-Match` ETag")
+    ),
     tag = "Series"
 )]
 pub async fn get_series_by_id(
     State(state): State<AppState>,
+    headers: HeaderMap,
     axum::extract::Path(id): axum::extract::Path<i64>,
 ) -> Result<impl IntoResponse, AppError> {
-    let series = state
+    let mut series = state
         .luna_service
         .series_service()
         .get_series_by_id(id)
         .await?;
+    localize_names(&state, &headers, &mut series).await?;
+    RestApiResponse::success(series).into_conditional_response(&headers)
+}
+
+/// Looks up many series in one request, in the order of the IDs sent.
+/// Unknown IDs are left out.
+#[utoipa::path(
+    post,
+    path = "/cards/series/batch",
+    request_body = BatchLookupDto,
+    responses(
+        (status = 200, description = "The series found", body = ApiResponse<Vec<SeriesDto>>),
+        (status = 400, description = "Empty or oversized ID list")
+    ),
+    tag = "Series"
+)]
+pub async fn get_series_by_ids(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<BatchLookupDto>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate().map_err(|err| {
+        tracing::error!("Validation error: {err}");
+        AppError::InvalidInput(err)
+    })?;
+
+    let mut series = state
+        .luna_service
+        .series_service()
+        .get_series_by_ids(&payload.ids)
+        .await?;
+    localize_names(&state, &headers, &mut series).await?;
     Ok(RestApiResponse::success(series))
 }
 
+/// Suggests series for the text typed so far: names starting with `q` first,
+/// then names containing it, each group by record count.
+#[utoipa::path(
+    get,
+    path = "/cards/series/autocomplete",
+    params(AutocompleteQuery),
+    responses(
+        (status = 200, description = "Matching series, best first", body = ApiResponse<Vec<NameSuggestionDto>>),
+        (status = 400, description = "Missing or blank `q`")
+    ),
+    tag = "Series"
+)]
+pub async fn autocomplete_series(
+    State(state): State<AppState>,
+    axum::extract::Query(query): axum::extract::Query<AutocompleteQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let suggestions = state
+        .luna_service
+        .series_service()
+        .autocomplete_series(query)
+        .await?;
+    Ok(RestApiResponse::success(suggestions))
+}
+
 #[utoipa::path(
     get,
     path = "/cards/series",
-    responses((status = 200, description = "List all series")),
+    params(
+        SearchSeriesDto,
+        ("limit" = Option<i64>, Query, description = "Limit for pagination"),
+        ("offset" = Option<i64>, Query, description = "Offset for pagination"),
+        ("ordering" = Option<String>, Query, description = "Sort keys from `id`, `name`, `link`; prefix `-` for descending. Defaults to affinity order")
+    ),
+    responses(
+        (status = 200, description = "List all series", body = ApiResponse<PaginatedResponse<SeriesDto>>),
+        (status = 304, description = "Not modified since the `If-None-Match` ETag")
+    ),
     tag = "Series"
 )]
 pub async fn get_series(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Extension(claims): Extension<Claims>,
     axum::extract::Query(pagination): axum::extract::Query<PaginationQuery>,
+    axum::extract::Query(search_dto): axum::extract::Query<SearchSeriesDto>,
 ) -> Result<impl IntoResponse, AppError> {
-    let search_dto = SearchSeriesDto {
-        id: None,
-        name: None,
-        link: None,
-    };
-
-    let paginated_result = state
+    let mut paginated_result = state
         .luna_service
         .series_service()
         .get_series_list_by_affinity(search_dto, pagination, claims.sub)
         .await?;
-    Ok(RestApiResponse::success(paginated_result))
+    localize_names(&state, &headers, &mut paginated_result).await?;
+    RestApiResponse::success(paginated_result).into_conditional_response(&headers)
 }
 
 #[utoipa::path(
     post,
     path = "/cards/series",
     request_body = CreateSeriesDto,
-    responses((status = 201, description = "Series created", body = SeriesDto)),
+    responses((status = 201, description = "Series created", body = ApiResponse<SeriesDto>)),
     tag = "Series"
 )]
 pub async fn create_series(
@@ -67,7 +142,7 @@ pub async fn create_series(
 ) -> Result<impl IntoResponse, AppError> {
     body.validate().map_err(|err| {
         tracing::error!("Validation error: {err}");
-        AppError::ValidationError(format!("Invalid input: {err}"))
+        AppError::InvalidInput(err)
     })?;
 
     let series = state
@@ -82,7 +157,7 @@ pub async fn create_series(
     put,
     path = "/cards/series/{id}",
     request_body = UpdateSeriesDto,
-    responses((status = 200, description = "Series updated", body = SeriesDto)),
+    responses((status = 200, description = "Series updated", body = ApiResponse<SeriesDto>)),
     tag = "Series"
 )]
 pub async fn update_series(
@@ -93,7 +168,7 @@ pub async fn update_series(
 ) -> Result<impl IntoResponse, AppError> {
     body.validate().map_err(|err| {
         tracing::error!("Validation error: {err}");
-        AppError::ValidationError(format!("Invalid input: {err}"))
+        AppError::InvalidInput(err)
     })?;
 
     let series = state
@@ -107,20 +182,25 @@ pub async fn update_series(
 #[utoipa::path(
     patch,
     path = "/cards/series/{id}",
-    request_body = UpdateSeriesDto,
-    responses((status = 200, description = "Series partially updated", body = SeriesDto)),
+    request_body = PatchSeriesDto,
+    responses((status = 200, description = "Series partially updated", body = ApiResponse<SeriesDto>)),
     tag = "Series"
 )]
 pub async fn patch_series(
     State(state): State<AppState>,
     Extension(_claims): Extension<Claims>,
     axum::extract::Path(id): axum::extract::Path<i64>,
-    Json(body): Json<UpdateSeriesDto>,
+    Json(payload): Json<PatchSeriesDto>,
 ) -> Result<impl IntoResponse, AppError> {
+    payload.validate().map_err(|err| {
+        tracing::error!("Validation error: {err}");
+        AppError::InvalidInput(err)
+    })?;
+
     let series = state
         .luna_service
         .series_service()
-        .update_series(id, body)
+        .update_series(id, payload.into_update(id))
         .await?;
     Ok(RestApiResponse::success(series))
 }
@@ -128,18 +208,44 @@ pub async fn patch_series(
 #[utoipa::path(
     delete,
     path = "/cards/series/{id}",
-    responses((status = 204, description = "Series deleted")),
+    params(DeleteEntityQuery),
+    responses(
+        (status = 204, description = "Series deleted"),
+        (status = 409, description = "Still referenced by records; data lists their IDs", body = ApiResponse<Vec<String>>)
+    ),
     tag = "Series"
 )]
 pub async fn delete_series(
     State(state): State<AppState>,
     Extension(_claims): Extension<Claims>,
     axum::extract::Path(id): axum::extract::Path<i64>,
+    axum::extract::Query(query): axum::extract::Query<DeleteEntityQuery>,
 ) -> Result<impl IntoResponse, AppError> {
     let message = state
         .luna_service
         .series_service()
-        .delete_series(id)
+        .delete_series(id, query.reassign_to)
         .await?;
     Ok(RestApiResponse::success_with_message(message, ()))
 }
+
+#[utoipa::path(
+    post,
+    path = "/cards/series/{id}/merge",
+    request_body = MergeEntityDto,
+    responses((status = 200, description = "Duplicate series merged into this one", body = ApiResponse<MergeEntityResponse>)),
+    tag = "Series"
+)]
+pub async fn merge_series(
+    State(state): State<AppState>,
+    Extension(_claims): Extension<Claims>,
+    axum::extract::Path(id): axum::extract::Path<i64>,
+    Json(body): Json<MergeEntityDto>,
+) -> Result<impl IntoResponse, AppError> {
+    let merged = state
+        .luna_service
+        .series_service()
+        .merge_series(id, body.source_id)
+        .await?;
+    Ok(RestApiResponse::success(merged))
+}
@@ -1,24 +1,42 @@
 use crate::{
-    common::{app_state::AppState, dto::RestApiResponse, error::AppError},
-    domains::luna::dto::EntityCountDto,
+    common::{
+        app_state::AppState,
+        dto::{ApiResponse, RestApiResponse},
+        error::AppError,
+        jwt::Claims,
+    },
+    domains::luna::{
+        dto::{
+            CoOccurrenceQuery, DateCountDto, EntityCountDto, GenreCategoryCountDto,
+            LinkStatisticsDto, PaginatedResponse, RecordCountQuery, RecordsByDateQuery,
+            StatisticsOverviewDto,
+        },
+        RecordPermission,
+    },
 };
 
-use axum::{extract::State, response::IntoResponse};
+use axum::{
+    extract::{Path, Query, State},
+    response::IntoResponse,
+    Extension,
+};
 
-// Count handlers
+// Count handlers: entities ranked by record count, one page at a time.
 #[utoipa::path(
     get,
     path = "/cards/director-records-count",
-    responses((status = 200, description = "Get director record counts", body = [EntityCountDto])),
+    params(RecordCountQuery),
+    responses((status = 200, description = "Get director record counts", body = ApiResponse<PaginatedResponse<EntityCountDto>>)),
     tag = "Statistics"
 )]
 pub async fn get_director_records_count(
     State(state): State<AppState>,
+    Query(query): Query<RecordCountQuery>,
 ) -> Result<impl IntoResponse, AppError> {
     let counts = state
         .luna_service
         .director_service()
-        .get_director_record_counts()
+        .get_director_record_counts(query)
         .await?;
     Ok(RestApiResponse::success(counts))
 }
@@ -26,16 +44,37 @@ pub async fn get_director_records_count(
 #[utoipa::path(
     get,
     path = "/cards/genre-records-count",
-    responses((status = 200, description = "Get genre record counts", body = [EntityCountDto])),
+    params(RecordCountQuery),
+    responses((status = 200, description = "Get genre record counts", body = ApiResponse<PaginatedResponse<EntityCountDto>>)),
     tag = "Statistics"
 )]
 pub async fn get_genre_records_count(
     State(state): State<AppState>,
+    Query(query): Query<RecordCountQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let counts = state
+        .luna_service
+        .genre_service()
+        .get_genre_record_counts(query)
+        .await?;
+    Ok(RestApiResponse::success(counts))
+}
+
+/// Genre record counts grouped by genre category. Categories are ranked by
+/// the records they cover; uncategorized genres come last.
+#[utoipa::path(
+    get,
+    path = "/cards/genre-records-count/by-category",
+    responses((status = 200, description = "Get genre record counts by genre category", body = ApiResponse<Vec<GenreCategoryCountDto>>)),
+    tag = "Statistics"
+)]
+pub async fn get_genre_category_records_count(
+    State(state): State<AppState>,
 ) -> Result<impl IntoResponse, AppError> {
     let counts = state
         .luna_service
         .genre_service()
-        .get_genre_record_counts()
+        .get_genre_category_record_counts()
         .await?;
     Ok(RestApiResponse::success(counts))
 }
@@ -43,16 +82,18 @@ pub async fn get_genre_records_count(
 #[utoipa::path(
     get,
     path = "/cards/label-records-count",
-    responses((status = 200, description = "Get label record counts", body = [EntityCountDto])),
+    params(RecordCountQuery),
+    responses((status = 200, description = "Get label record counts", body = ApiResponse<PaginatedResponse<EntityCountDto>>)),
     tag = "Statistics"
 )]
 pub async fn get_label_records_count(
     State(state): State<AppState>,
+    Query(query): Query<RecordCountQuery>,
 ) -> Result<impl IntoResponse, AppError> {
     let counts = state
         .luna_service
         .label_service()
-        .get_label_record_counts()
+        .get_label_record_counts(query)
         .await?;
     Ok(RestApiResponse::success(counts))
 }
@@ -60,16 +101,18 @@ pub async fn get_label_records_count(
 #[utoipa::path(
     get,
     path = "/cards/studio-records-count",
-    responses((status = 200, description = "Get studio record counts", body = [EntityCountDto])),
+    params(RecordCountQuery),
+    responses((status = 200, description = "Get studio record counts", body = ApiResponse<PaginatedResponse<EntityCountDto>>)),
     tag = "Statistics"
 )]
 pub async fn get_studio_records_count(
     State(state): State<AppState>,
+    Query(query): Query<RecordCountQuery>,
 ) -> Result<impl IntoResponse, AppError> {
     let counts = state
         .luna_service
         .studio_service()
-        .get_studio_record_counts()
+        .get_studio_record_counts(query)
         .await?;
     Ok(RestApiResponse::success(counts))
 }
@@ -77,16 +120,18 @@ pub async fn get_studio_records_count(
 #[utoipa::path(
     get,
     path = "/cards/series-records-count",
-    responses((status = 200, description = "Get series record counts", body = [EntityCountDto])),
+    params(RecordCountQuery),
+    responses((status = 200, description = "Get series record counts", body = ApiResponse<PaginatedResponse<EntityCountDto>>)),
     tag = "Statistics"
 )]
 pub async fn get_series_records_count(
     State(state): State<AppState>,
+    Query(query): Query<RecordCountQuery>,
 ) -> Result<impl IntoResponse, AppError> {
     let counts = state
         .luna_service
         .series_service()
-        .get_series_record_counts()
+        .get_series_record_counts(query)
         .await?;
     Ok(RestApiResponse::success(counts))
 }
@@ -94,16 +139,127 @@ pub async fn get_series_records_count(
 #[utoipa::path(
     get,
     path = "/cards/idol-records-count",
-    responses((status = 200, description = "Get idol record counts", body = [EntityCountDto])),
+    params(RecordCountQuery),
+    responses((status = 200, description = "Get idol record counts", body = ApiResponse<PaginatedResponse<EntityCountDto>>)),
     tag = "Statistics"
 )]
 pub async fn get_idol_records_count(
     State(state): State<AppState>,
+    Query(query): Query<RecordCountQuery>,
 ) -> Result<impl IntoResponse, AppError> {
     let counts = state
         .luna_service
         .idol_service()
-        .get_idol_record_counts()
+        .get_idol_record_counts(query)
+        .await?;
+    Ok(RestApiResponse::success(counts))
+}
+
+/// Catalog overview for dashboards: totals, monthly growth, top genres, idols
+/// and studios, and the average duration.
+#[utoipa::path(
+    get,
+    path = "/cards/statistics/overview",
+    responses((status = 200, description = "Get catalog statistics overview", body = ApiResponse<StatisticsOverviewDto>)),
+    tag = "Statistics"
+)]
+pub async fn get_statistics_overview(
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, AppError> {
+    let overview = state.luna_service.statistics_service().overview().await?;
+    Ok(RestApiResponse::success(overview))
+}
+
+/// Link count, combined size and monthly additions, for storage planning.
+#[utoipa::path(
+    get,
+    path = "/cards/statistics/links",
+    responses((status = 200, description = "Get link statistics", body = ApiResponse<LinkStatisticsDto>)),
+    tag = "Statistics"
+)]
+pub async fn get_link_statistics(
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, AppError> {
+    let statistics = state
+        .luna_service
+        .statistics_service()
+        .link_statistics()
+        .await?;
+    Ok(RestApiResponse::success(statistics))
+}
+
+/// Live records bucketed by release month or year, for charting catalog
+/// growth and release distribution.
+#[utoipa::path(
+    get,
+    path = "/cards/statistics/records-by-date",
+    params(RecordsByDateQuery),
+    responses(
+        (status = 200, description = "Get record counts per release period", body = ApiResponse<Vec<DateCountDto>>),
+        (status = 400, description = "Unknown granularity, malformed date or `from` after `to`")
+    ),
+    tag = "Statistics"
+)]
+pub async fn get_records_by_date(
+    State(state): State<AppState>,
+    Query(query): Query<RecordsByDateQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let counts = state
+        .luna_service
+        .statistics_service()
+        .records_by_date(query)
         .await?;
     Ok(RestApiResponse::success(counts))
 }
+
+/// Idols appearing alongside idol `id`, ranked by shared live records the
+/// caller may see.
+#[utoipa::path(
+    get,
+    path = "/cards/idols/{id}/co-stars",
+    params(("id" = i64, Path, description = "Idol ID"), CoOccurrenceQuery),
+    responses(
+        (status = 200, description = "Get idols sharing records with the idol", body = ApiResponse<Vec<EntityCountDto>>),
+        (status = 404, description = "Idol not found")
+    ),
+    tag = "Statistics"
+)]
+pub async fn get_idol_co_stars(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<i64>,
+    Query(query): Query<CoOccurrenceQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let co_stars = state
+        .luna_service
+        .statistics_service()
+        .co_stars(id, query, RecordPermission::clearance(claims.role))
+        .await?;
+    Ok(RestApiResponse::success(co_stars))
+}
+
+/// Genres tagged together with genre `id`, ranked by shared live records the
+/// caller may see.
+#[utoipa::path(
+    get,
+    path = "/cards/genres/{id}/related",
+    params(("id" = i64, Path, description = "Genre ID"), CoOccurrenceQuery),
+    responses(
+        (status = 200, description = "Get genres sharing records with the genre", body = ApiResponse<Vec<EntityCountDto>>),
+        (status = 404, description = "Genre not found")
+    ),
+    tag = "Statistics"
+)]
+pub async fn get_related_genres(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<i64>,
+    Query(query): Query<CoOccurrenceQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let related = state
+        .luna_service
+        .statistics_service()
+        .related_genres(id, query, RecordPermission::clearance(claims.role))
+        .await?;
+    Ok(RestApiResponse::success(related))
+}
@@ -1,63 +1,138 @@
 use crate::{
-    common::{app_state::AppState, dto::RestApiResponse, error::AppError, jwt::Claims},
+    common::{
+        app_state::AppState,
+        dto::{ApiResponse, RestApiResponse},
+        error::AppError,
+        jwt::Claims,
+    },
     domains::luna::dto::{
-        CreateStudioDto, PaginationQuery, SearchStudioDto, StudioDto, UpdateStudioDto,
+        AutocompleteQuery, BatchLookupDto, CreateStudioDto, MergeEntityDto, MergeEntityResponse,
+        NameSuggestionDto, PaginatedResponse, PaginationQuery, PatchStudioDto, SearchStudioDto,
+        StudioChildrenDto, StudioDto, StudioParentDto, StudioRollupDto, UpdateStudioDto,
     },
 };
 
-use axum::{extract::State, response::IntoResponse, Extension, Json};
+use axum::{extract::State, http::HeaderMap, response::IntoResponse, Extension, Json};
 
 use validator::Validate as _;
 
+use super::translation::localize_names;
+
 // Studio handlers
 #[utoipa::path(
     get,
     path = "/cards/studios/{id}",
-    responses((status = 200, description = "Get studio by ID", body = StudioDto)),
+    responses(
+        (status = 200, description = "Get studio by ID", body = ApiResponse<StudioDto>),
+        (status = 304, description = "Not modified since the `If-None-Match` ETag")
+    ),
     tag = "Studios"
 )]
 pub async fn get_studio_by_id(
     State(state): State<AppState>,
+    headers: HeaderMap,
     axum::extract::Path(id): axum::extract::Path<i64>,
 ) -> Result<impl IntoResponse, AppError> {
-    let studio = state
+    let mut studio = state
         .luna_service
         .studio_service()
         .get_studio_by_id(id)
         .await?;
-    Ok(RestApiResponse::success(studio))
+    localize_names(&state, &headers, &mut studio).await?;
+    RestApiResponse::success(studio).into_conditional_response(&headers)
+}
+
+/// Looks up many studios in one request, in the order of the IDs sent.
+/// Unknown IDs are left out.
+#[utoipa::path(
+    post,
+    path = "/cards/studios/batch",
+    request_body = BatchLookupDto,
+    responses(
+        (status = 200, description = "The studios found", body = ApiResponse<Vec<StudioDto>>),
+        (status = 400, description = "Empty or oversized ID list")
+    ),
+    tag = "Studios"
+)]
+pub async fn get_studios_by_ids(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<BatchLookupDto>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate().map_err(|err| {
+        tracing::error!("Validation error: {err}");
+        AppError::InvalidInput(err)
+    })?;
+
+    let mut studios = state
+        .luna_service
+        .studio_service()
+        .get_studios_by_ids(&payload.ids)
+        .await?;
+    localize_names(&state, &headers, &mut studios).await?;
+    Ok(RestApiResponse::success(studios))
+}
+
+/// Suggests studios for the text typed so far: names starting with `q` first,
+/// then names containing it, each group by record count.
+#[utoipa::path(
+    get,
+    path = "/cards/studios/autocomplete",
+    params(AutocompleteQuery),
+    responses(
+        (status = 200, description = "Matching studios, best first", body = ApiResponse<Vec<NameSuggestionDto>>),
+        (status = 400, description = "Missing or blank `q`")
+    ),
+    tag = "Studios"
+)]
+pub async fn autocomplete_studios(
+    State(state): State<AppState>,
+    axum::extract::Query(query): axum::extract::Query<AutocompleteQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let suggestions = state
+        .luna_service
+        .studio_service()
+        .autocomplete_studios(query)
+        .await?;
+    Ok(RestApiResponse::success(suggestions))
 }
 
 #[utoipa::path(
     get,
     path = "/cards/studios",
-    responses((status = 200, description = "List all studios")),
+    params(
+        SearchStudioDto,
+        ("limit" = Option<i64>, Query, description = "Limit for pagination"),
+        ("offset" = Option<i64>, Query, description = "Offset for pagination"),
+        ("ordering" = Option<String>, Query, description = "Sort keys from `id`, `name`, `link`; prefix `-` for descending. Defaults to affinity order")
+    ),
+    responses(
+        (status = 200, description = "List all studios", body = ApiResponse<PaginatedResponse<StudioDto>>),
+        (status = 304, description = "Not modified since the `If-None-Match` ETag")
+    ),
     tag = "Studios"
 )]
 pub async fn get_studios(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Extension(claims): Extension<Claims>,
     axum::extract::Query(pagination): axum::extract::Query<PaginationQuery>,
+    axum::extract::Query(search_dto): axum::extract::Query<SearchStudioDto>,
 ) -> Result<impl IntoResponse, AppError> {
-    let search_dto = SearchStudioDto {
-        id: None,
-        name: None,
-        link: None,
-    };
-
-    let paginated_result = state
+    let mut paginated_result = state
         .luna_service
         .studio_service()
         .get_studio_list_by_affinity(search_dto, pagination, claims.sub)
         .await?;
-    Ok(RestApiResponse::success(paginated_result))
+    localize_names(&state, &headers, &mut paginated_result).await?;
+    RestApiResponse::success(paginated_result).into_conditional_response(&headers)
 }
 
 #[utoipa::path(
     post,
     path = "/cards/studios",
     request_body = CreateStudioDto,
-    responses((status = 201, description = "Studio created", body = StudioDto)),
+    responses((status = 201, description = "Studio created", body = ApiResponse<StudioDto>)),
     tag = "Studios"
 )]
 pub async fn create_studio(
@@ -67,7 +142,7 @@ pub async fn create_studio(
 ) -> Result<impl IntoResponse, AppError> {
     body.validate().map_err(|err| {
         tracing::error!("Validation error: {err}");
-        AppError::ValidationError(format!("Invalid input: {err}"))
+        AppError::InvalidInput(err)
     })?;
 
     let studio = state
@@ -82,7 +157,7 @@ pub async fn create_studio(
     put,
     path = "/cards/studios/{id}",
     request_body = UpdateStudioDto,
-    responses((status = 200, description = "Studio updated", body = StudioDto)),
+    responses((status = 200, description = "Studio updated", body = ApiResponse<StudioDto>)),
     tag = "Studios"
 )]
 pub async fn update_studio(
@@ -93,7 +168,7 @@ pub async fn update_studio(
 ) -> Result<impl IntoResponse, AppError> {
     body.validate().map_err(|err| {
         tracing::error!("Validation error: {err}");
-        AppError::ValidationError(format!("Invalid input: {err}"))
+        AppError::InvalidInput(err)
     })?;
 
     let studio = state
@@ -107,20 +182,25 @@ pub async fn update_studio(
 #[utoipa::path(
     patch,
     path = "/cards/studios/{id}",
-    request_body = UpdateStudioDto,
-    responses((status = 200, description = "Studio partially updated", body = StudioDto)),
+    request_body = PatchStudioDto,
+    responses((status = 200, description = "Studio partially updated", body = ApiResponse<StudioDto>)),
     tag = "Studios"
 )]
 pub async fn patch_studio(
     State(state): State<AppState>,
     Extension(_claims): Extension<Claims>,
     axum::extract::Path(id): axum::extract::Path<i64>,
-    Json(body): Json<UpdateStudioDto>,
+    Json(payload): Json<PatchStudioDto>,
 ) -> Result<impl IntoResponse, AppError> {
+    payload.validate().map_err(|err| {
+        tracing::error!("Validation error: {err}");
+        AppError::InvalidInput(err)
+    })?;
+
     let studio = state
         .luna_service
         .studio_service()
-        .update_studio(id, body)
+        .update_studio(id, payload.into_update(id))
         .await?;
     Ok(RestApiResponse::success(studio))
 }
@@ -128,18 +208,118 @@ pub async fn patch_studio(
 #[utoipa::path(
     delete,
     path = "/cards/studios/{id}",
-    responses((status = 204, description = "Studio deleted")),
+    params(DeleteEntityQuery),
+    responses(
+        (status = 204, description = "Studio deleted"),
+        (status = 409, description = "Still referenced by records; data lists their IDs", body = ApiResponse<Vec<String>>)
+    ),
     tag = "Studios"
 )]
 pub async fn delete_studio(
     State(state): State<AppState>,
     Extension(_claims): Extension<Claims>,
     axum::extract::Path(id): axum::extract::Path<i64>,
+    axum::extract::Query(query): axum::extract::Query<DeleteEntityQuery>,
 ) -> Result<impl IntoResponse, AppError> {
     let message = state
         .luna_service
         .studio_service()
-        .delete_studio(id)
+        .delete_studio(id, query.reassign_to)
         .await?;
     Ok(RestApiResponse::success_with_message(message, ()))
 }
+
+#[utoipa::path(
+    post,
+    path = "/cards/studios/{id}/merge",
+    request_body = MergeEntityDto,
+    responses((status = 200, description = "Duplicate studio merged into this one", body = ApiResponse<MergeEntityResponse>)),
+    tag = "Studios"
+)]
+pub async fn merge_studio(
+    State(state): State<AppState>,
+    Extension(_claims): Extension<Claims>,
+    axum::extract::Path(id): axum::extract::Path<i64>,
+    Json(body): Json<MergeEntityDto>,
+) -> Result<impl IntoResponse, AppError> {
+    let merged = state
+        .luna_service
+        .studio_service()
+        .merge_studio(id, body.source_id)
+        .await?;
+    Ok(RestApiResponse::success(merged))
+}
+
+/// Moves a studio under a studio group, or to the top level.
+#[utoipa::path(
+    put,
+    path = "/cards/studios/{id}/parent",
+    request_body = StudioParentDto,
+    params(("id" = i64, Path, description = "Studio ID")),
+    responses(
+        (status = 200, description = "Studio moved", body = ApiResponse<StudioDto>),
+        (status = 400, description = "The parent is the studio or below it, or a placeholder is involved"),
+        (status = 403, description = "Caller is not an editor"),
+        (status = 404, description = "Studio or parent studio not found")
+    ),
+    tag = "Studios"
+)]
+pub async fn set_studio_parent(
+    State(state): State<AppState>,
+    axum::extract::Path(id): axum::extract::Path<i64>,
+    Json(body): Json<StudioParentDto>,
+) -> Result<impl IntoResponse, AppError> {
+    let studio = state
+        .luna_service
+        .studio_service()
+        .set_studio_parent(id, body.parent_id)
+        .await?;
+    Ok(RestApiResponse::success(studio))
+}
+
+/// Lists the studios directly below a studio and the labels it publishes.
+#[utoipa::path(
+    get,
+    path = "/cards/studios/{id}/children",
+    params(("id" = i64, Path, description = "Studio ID")),
+    responses(
+        (status = 200, description = "Child studios and labels", body = ApiResponse<StudioChildrenDto>),
+        (status = 404, description = "Studio not found")
+    ),
+    tag = "Studios"
+)]
+pub async fn get_studio_children(
+    State(state): State<AppState>,
+    axum::extract::Path(id): axum::extract::Path<i64>,
+) -> Result<impl IntoResponse, AppError> {
+    let children = state
+        .luna_service
+        .studio_service()
+        .get_studio_children(id)
+        .await?;
+    Ok(RestApiResponse::success(children))
+}
+
+/// Counts the live records of a studio and of every studio and label below
+/// it, as a tree.
+#[utoipa::path(
+    get,
+    path = "/cards/studios/{id}/rollup",
+    params(("id" = i64, Path, description = "Studio ID")),
+    responses(
+        (status = 200, description = "Record counts across the studio's hierarchy", body = ApiResponse<StudioRollupDto>),
+        (status = 404, description = "Studio not found")
+    ),
+    tag = "Studios"
+)]
+pub async fn get_studio_rollup(
+    State(state): State<AppState>,
+    axum::extract::Path(id): axum::extract::Path<i64>,
+) -> Result<impl IntoResponse, AppError> {
+    let rollup = state
+        .luna_service
+        .studio_service()
+        .get_studio_rollup(id)
+        .await?;
+    Ok(RestApiResponse::success(rollup))
+}
@@ -0,0 +1,251 @@
+use crate::{
+    common::{
+        app_state::AppState,
+        dto::{ApiResponse, RestApiResponse},
+        error::AppError,
+        jwt::Claims,
+    },
+    domains::luna::{
+        dto::{
+            AttachTagsDto, CreateTagDto, SearchTagDto, TagCategoryDto, TagCloudQuery, TagCountDto,
+            TagDto,
+        },
+        RecordPermission,
+    },
+};
+
+use axum::{
+    extract::{Path, Query, State},
+    response::IntoResponse,
+    Extension, Json,
+};
+
+use validator::Validate as _;
+
+use super::comment::ensure_record_visible;
+
+// Tag handlers
+#[utoipa::path(
+    get,
+    path = "/cards/tags",
+    params(SearchTagDto),
+    responses((status = 200, description = "List tags by category then name", body = ApiResponse<Vec<TagDto>>)),
+    tag = "Tags"
+)]
+pub async fn get_tags(
+    State(state): State<AppState>,
+    Query(search): Query<SearchTagDto>,
+) -> Result<impl IntoResponse, AppError> {
+    let tags = state.luna_service.tag_service().list_tags(search).await?;
+    Ok(RestApiResponse::success(tags))
+}
+
+#[utoipa::path(
+    get,
+    path = "/cards/tags/{id}",
+    params(("id" = i64, Path, description = "Tag ID")),
+    responses(
+        (status = 200, description = "Get tag by ID", body = ApiResponse<TagDto>),
+        (status = 404, description = "Tag not found")
+    ),
+    tag = "Tags"
+)]
+pub async fn get_tag_by_id(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, AppError> {
+    let tag = state.luna_service.tag_service().get_tag(id).await?;
+    Ok(RestApiResponse::success(tag))
+}
+
+#[utoipa::path(
+    post,
+    path = "/cards/tags",
+    request_body = CreateTagDto,
+    responses(
+        (status = 201, description = "Create a new tag", body = ApiResponse<TagDto>),
+        (status = 400, description = "Invalid name or unknown category"),
+        (status = 409, description = "Category already has a tag with this name")
+    ),
+    tag = "Tags"
+)]
+pub async fn create_tag(
+    State(state): State<AppState>,
+    Json(payload): Json<CreateTagDto>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate().map_err(|err| {
+        tracing::error!("Validation error: {err}");
+        AppError::InvalidInput(err)
+    })?;
+
+    let tag = state.luna_service.tag_service().create_tag(payload).await?;
+    Ok(RestApiResponse::success(tag))
+}
+
+#[utoipa::path(
+    put,
+    path = "/cards/tags/{id}",
+    params(("id" = i64, Path, description = "Tag ID")),
+    request_body = CreateTagDto,
+    responses(
+        (status = 200, description = "Update tag", body = ApiResponse<TagDto>),
+        (status = 400, description = "Invalid name or unknown category"),
+        (status = 404, description = "Tag not found"),
+        (status = 409, description = "Category already has a tag with this name")
+    ),
+    tag = "Tags"
+)]
+pub async fn update_tag(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    Json(payload): Json<CreateTagDto>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate().map_err(|err| {
+        tracing::error!("Validation error: {err}");
+        AppError::InvalidInput(err)
+    })?;
+
+    let tag = state
+        .luna_service
+        .tag_service()
+        .update_tag(id, payload)
+        .await?;
+    Ok(RestApiResponse::success(tag))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/cards/tags/{id}",
+    params(("id" = i64, Path, description = "Tag ID")),
+    responses(
+        (status = 204, description = "Tag deleted and detached from every record"),
+        (status = 404, description = "Tag not found")
+    ),
+    tag = "Tags"
+)]
+pub async fn delete_tag(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, AppError> {
+    state.luna_service.tag_service().delete_tag(id).await?;
+    Ok(RestApiResponse::success(()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/cards/tags/categories",
+    responses((status = 200, description = "List tag categories", body = ApiResponse<Vec<TagCategoryDto>>)),
+    tag = "Tags"
+)]
+pub async fn get_tag_categories(
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, AppError> {
+    let categories = state.luna_service.tag_service().list_categories().await?;
+    Ok(RestApiResponse::success(categories))
+}
+
+#[utoipa::path(
+    get,
+    path = "/cards/tags/cloud",
+    params(TagCloudQuery),
+    responses(
+        (status = 200, description = "Tags with the number of visible records carrying each, most used first", body = ApiResponse<Vec<TagCountDto>>),
+        (status = 400, description = "limit is 0")
+    ),
+    tag = "Tags"
+)]
+pub async fn get_tag_cloud(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Query(query): Query<TagCloudQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let cloud = state
+        .luna_service
+        .tag_service()
+        .tag_cloud(query.limit, RecordPermission::clearance(claims.role))
+        .await?;
+    Ok(RestApiResponse::success(cloud))
+}
+
+// Record tagging handlers
+#[utoipa::path(
+    get,
+    path = "/cards/records/{id}/tags",
+    params(("id" = String, Path, description = "Record ID")),
+    responses(
+        (status = 200, description = "Tags attached to the record", body = ApiResponse<Vec<TagDto>>),
+        (status = 403, description = "Record is above the caller's permission level"),
+        (status = 404, description = "Record not found")
+    ),
+    tag = "Tags"
+)]
+pub async fn get_record_tags(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_record_visible(&state, &claims, &id).await?;
+    let tags = state.luna_service.tag_service().record_tags(&id).await?;
+    Ok(RestApiResponse::success(tags))
+}
+
+#[utoipa::path(
+    post,
+    path = "/cards/records/{id}/tags",
+    params(("id" = String, Path, description = "Record ID")),
+    request_body = AttachTagsDto,
+    responses(
+        (status = 200, description = "Tags attached; returns every tag on the record", body = ApiResponse<Vec<TagDto>>),
+        (status = 400, description = "Empty tag list or unknown tag IDs"),
+        (status = 403, description = "Record is above the caller's permission level"),
+        (status = 404, description = "Record not found")
+    ),
+    tag = "Tags"
+)]
+pub async fn attach_record_tags(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<String>,
+    Json(payload): Json<AttachTagsDto>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate().map_err(|err| {
+        tracing::error!("Validation error: {err}");
+        AppError::InvalidInput(err)
+    })?;
+    ensure_record_visible(&state, &claims, &id).await?;
+
+    let tags = state
+        .luna_service
+        .tag_service()
+        .attach_tags(&id, payload)
+        .await?;
+    Ok(RestApiResponse::success(tags))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/cards/records/{id}/tags/{tag_id}",
+    params(
+        ("id" = String, Path, description = "Record ID"),
+        ("tag_id" = i64, Path, description = "Tag ID")
+    ),
+    responses(
+        (status = 204, description = "Tag detached from the record"),
+        (status = 403, description = "Record is above the caller's permission level"),
+        (status = 404, description = "Record not found or tag not attached")
+    ),
+    tag = "Tags"
+)]
+pub async fn detach_record_tag(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path((id, tag_id)): Path<(String, i64)>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_record_visible(&state, &claims, &id).await?;
+    state
+        .luna_service
+        .tag_service()
+        .detach_tag(&id, tag_id)
+        .await?;
+    Ok(RestApiResponse::success(()))
+}
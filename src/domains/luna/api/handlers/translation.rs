@@ -0,0 +1,137 @@
+use crate::{
+    common::{
+        app_state::AppState,
+        dto::{ApiResponse, RestApiResponse},
+        error::AppError,
+    },
+    domains::luna::dto::{
+        Language, LocalizedNames, SetTranslationDto, TranslatedEntity, TranslationDto,
+    },
+};
+
+use axum::{
+    extract::{Path, State},
+    http::{header::ACCEPT_LANGUAGE, HeaderMap},
+    response::IntoResponse,
+    Json,
+};
+use validator::Validate as _;
+
+/// Replaces the entity names in `response` with their translations into the
+/// language the caller prefers in `Accept-Language`. Names without a
+/// translation, and every name when no supported language is accepted, are
+/// left as stored.
+pub(super) async fn localize_names<T: LocalizedNames>(
+    state: &AppState,
+    headers: &HeaderMap,
+    response: &mut T,
+) -> Result<(), AppError> {
+    let Some(language) = headers
+        .get(ACCEPT_LANGUAGE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(Language::from_accept_language)
+    else {
+        return Ok(());
+    };
+    let mut keys = Vec::new();
+    response.name_keys(&mut keys);
+    let names = state
+        .luna_service
+        .translation_service()
+        .names(language, &keys)
+        .await?;
+    response.apply_names(&names);
+    Ok(())
+}
+
+/// Lists the translated names of one entity.
+#[utoipa::path(
+    get,
+    path = "/cards/admin/translations/{entity}/{id}",
+    params(
+        ("entity" = TranslatedEntity, Path, description = "Entity type"),
+        ("id" = i64, Path, description = "Entity ID")
+    ),
+    responses(
+        (status = 200, description = "Translations of the entity, by language", body = ApiResponse<Vec<TranslationDto>>),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 404, description = "Entity not found")
+    ),
+    tag = "Admin"
+)]
+pub async fn get_translations(
+    State(state): State<AppState>,
+    Path((entity, id)): Path<(TranslatedEntity, i64)>,
+) -> Result<impl IntoResponse, AppError> {
+    let translations = state
+        .luna_service
+        .translation_service()
+        .list_translations(entity, id)
+        .await?;
+    Ok(RestApiResponse::success(translations))
+}
+
+/// Sets the name of one entity in one language, replacing any earlier
+/// translation.
+#[utoipa::path(
+    put,
+    path = "/cards/admin/translations/{entity}/{id}/{language}",
+    request_body = SetTranslationDto,
+    params(
+        ("entity" = TranslatedEntity, Path, description = "Entity type"),
+        ("id" = i64, Path, description = "Entity ID"),
+        ("language" = Language, Path, description = "Language of the name")
+    ),
+    responses(
+        (status = 200, description = "Translation stored", body = ApiResponse<TranslationDto>),
+        (status = 400, description = "Blank or overlong name"),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 404, description = "Entity not found")
+    ),
+    tag = "Admin"
+)]
+pub async fn set_translation(
+    State(state): State<AppState>,
+    Path((entity, id, language)): Path<(TranslatedEntity, i64, Language)>,
+    Json(payload): Json<SetTranslationDto>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate().map_err(|err| {
+        tracing::error!("Validation error: {err}");
+        AppError::InvalidInput(err)
+    })?;
+
+    let translation = state
+        .luna_service
+        .translation_service()
+        .set_translation(entity, id, language, &payload.name)
+        .await?;
+    Ok(RestApiResponse::success(translation))
+}
+
+/// Removes the name of one entity in one language.
+#[utoipa::path(
+    delete,
+    path = "/cards/admin/translations/{entity}/{id}/{language}",
+    params(
+        ("entity" = TranslatedEntity, Path, description = "Entity type"),
+        ("id" = i64, Path, description = "Entity ID"),
+        ("language" = Language, Path, description = "Language of the name")
+    ),
+    responses(
+        (status = 204, description = "Translation deleted"),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 404, description = "No translation in that language")
+    ),
+    tag = "Admin"
+)]
+pub async fn delete_translation(
+    State(state): State<AppState>,
+    Path((entity, id, language)): Path<(TranslatedEntity, i64, Language)>,
+) -> Result<impl IntoResponse, AppError> {
+    state
+        .luna_service
+        .translation_service()
+        .delete_translation(entity, id, language)
+        .await?;
+    Ok(RestApiResponse::success(()))
+}
@@ -1,62 +1,142 @@
 use super::handlers::{
+    __path_archive_idol_media_by_id,
+    __path_archive_media,
+    __path_attach_record_tags,
+    __path_autocomplete_directors,
+    __path_autocomplete_genres,
+    __path_autocomplete_idols,
+    __path_autocomplete_labels,
+    __path_autocomplete_series,
+    __path_autocomplete_studios,
     __path_batch_status,
+    __path_check_link,
+    __path_check_record_links,
+    __path_collect_orphaned_media,
     // Director handlers
     __path_create_director,
     // Genre handlers
     __path_create_genre,
+    __path_create_genre_category,
     // Idol handlers
     __path_create_idol,
     // Label handlers
     __path_create_label,
     // Record handlers
     __path_create_record,
+    __path_create_record_comment,
+    __path_create_records_bulk,
+    __path_create_saved_search,
     // Series handlers
     __path_create_series,
     // Studio handlers
     __path_create_studio,
+    __path_create_tag,
     __path_delete_director,
     __path_delete_genre,
+    __path_delete_genre_category,
     __path_delete_idol,
     __path_delete_label,
+    __path_delete_link,
     __path_delete_record,
+    __path_delete_record_comment,
+    __path_delete_records_bulk,
+    __path_delete_saved_search,
     __path_delete_series,
     __path_delete_studio,
+    __path_delete_tag,
+    __path_delete_translation,
+    __path_detach_record_tag,
+    // Export handlers
+    __path_export_entities,
+    __path_export_records,
+    __path_favorite_record,
     // New record ID/slim handlers
     __path_get_all_record_ids_all,
     __path_get_all_record_slim_all,
+    __path_get_dead_links,
     __path_get_director_by_id,
     // Auto-generated paths for count handlers
     __path_get_director_records_count,
     __path_get_directors,
+    __path_get_directors_by_ids,
+    __path_get_duplicate_images,
+    __path_get_duplicate_records,
+    __path_get_favorite_records,
     __path_get_genre_by_id,
+    __path_get_genre_categories,
+    __path_get_genre_category_by_id,
+    __path_get_genre_category_records_count,
     __path_get_genre_records_count,
     __path_get_genres,
+    __path_get_genres_by_ids,
     __path_get_idol_by_id,
+    __path_get_idol_co_stars,
+    __path_get_idol_profile,
     __path_get_idol_records_count,
     __path_get_idols,
+    __path_get_idols_by_ids,
     __path_get_idols_without_images,
+    __path_get_integrity_report,
     __path_get_label_by_id,
     __path_get_label_records_count,
     __path_get_labels,
+    __path_get_labels_by_ids,
+    __path_get_link_statistics,
+    __path_get_public_saved_searches,
+    __path_get_random_records,
     __path_get_record_by_id,
+    __path_get_record_comments,
+    __path_get_record_history,
     __path_get_record_ids_paginated,
     __path_get_record_slim_paginated,
+    __path_get_record_tags,
+    __path_get_record_trash,
     __path_get_records,
     // Auto-generated paths for records by entity handlers
+    __path_get_records_by_date,
     __path_get_records_by_director,
     __path_get_records_by_genre,
     __path_get_records_by_idol,
     __path_get_records_by_label,
     __path_get_records_by_series,
     __path_get_records_by_studio,
+    __path_get_related_genres,
+    __path_get_saved_search_by_id,
+    __path_get_saved_search_results,
+    __path_get_saved_searches,
+    __path_get_seen_history,
     __path_get_series,
     __path_get_series_by_id,
+    __path_get_series_by_ids,
     __path_get_series_records_count,
+    __path_get_similar_records,
+    __path_get_statistics_overview,
     __path_get_studio_by_id,
+    __path_get_studio_children,
     __path_get_studio_records_count,
+    __path_get_studio_rollup,
     __path_get_studios,
+    __path_get_studios_by_ids,
+    __path_get_tag_by_id,
+    __path_get_tag_categories,
+    __path_get_tag_cloud,
+    __path_get_tags,
+    __path_get_translations,
     __path_get_viewed_record_ids,
+    __path_head_record,
+    __path_import_records,
+    __path_list_idol_media_by_id,
+    __path_list_media,
+    __path_mark_seen,
+    __path_mark_unseen,
     __path_mark_viewed,
+    __path_merge_director,
+    __path_merge_genre,
+    __path_merge_idol,
+    __path_merge_label,
+    __path_merge_record,
+    __path_merge_series,
+    __path_merge_studio,
     __path_patch_director,
     __path_patch_genre,
     __path_patch_idol,
@@ -64,73 +144,182 @@ use super::handlers::{
     __path_patch_record,
     __path_patch_series,
     __path_patch_studio,
+    __path_purge_record,
+    __path_rate_record,
+    // Feed handlers
+    __path_recent_atom_feed,
+    __path_recent_json_feed,
+    __path_reconcile_image_counts,
+    __path_records_exist,
+    __path_replace_idol_profile,
+    __path_replace_record_full,
+    __path_restore_record,
+    __path_revert_record,
+    __path_search_links,
     __path_serve_idol_media_by_id,
     __path_serve_idol_media_by_name,
     __path_serve_media,
+    __path_serve_record_video,
+    __path_set_genre_category,
+    __path_set_label_studio,
+    __path_set_record_cover,
+    __path_set_record_series_order,
+    __path_set_studio_parent,
+    __path_set_translation,
+    __path_star_link,
+    __path_stream_catalog_events,
     // Interaction handlers (moved from user domain)
+    __path_sync_records,
     __path_toggle_like,
+    __path_unfavorite_record,
+    __path_unrate_record,
     __path_update_director,
     __path_update_genre,
+    __path_update_genre_category,
     __path_update_idol,
     __path_update_label,
+    __path_update_link,
     __path_update_record,
+    __path_update_record_comment,
     __path_update_record_links,
+    __path_update_saved_search,
     __path_update_series,
     __path_update_studio,
+    __path_update_tag,
     __path_upload_idol_images_by_id,
     __path_upload_idol_images_by_name,
     __path_upload_images,
+    __path_upload_record_video,
+    archive_idol_media_by_id,
+    archive_media,
+    attach_record_tags,
+    autocomplete_directors,
+    autocomplete_genres,
+    autocomplete_idols,
+    autocomplete_labels,
+    autocomplete_series,
+    autocomplete_studios,
     batch_status,
+    check_link,
+    check_record_links,
+    collect_orphaned_media,
     create_director,
     create_genre,
+    create_genre_category,
     create_idol,
     create_label,
     create_record,
+    create_record_comment,
+    create_records_bulk,
+    create_saved_search,
     create_series,
     create_studio,
+    create_tag,
     delete_director,
     delete_genre,
+    delete_genre_category,
     delete_idol,
     delete_label,
+    delete_link,
     delete_record,
+    delete_record_comment,
+    delete_records_bulk,
+    delete_saved_search,
     delete_series,
     delete_studio,
+    delete_tag,
+    delete_translation,
+    detach_record_tag,
+    export_entities,
+    export_records,
+    favorite_record,
     // New record ID/slim handlers
     get_all_record_ids_all,
     get_all_record_slim_all,
+    get_dead_links,
     get_director_by_id,
     // Count handlers
     get_director_records_count,
     get_directors,
+    get_directors_by_ids,
+    get_duplicate_images,
+    get_duplicate_records,
+    get_favorite_records,
     get_genre_by_id,
+    get_genre_categories,
+    get_genre_category_by_id,
+    get_genre_category_records_count,
     get_genre_records_count,
     get_genres,
+    get_genres_by_ids,
     get_idol_by_id,
+    get_idol_co_stars,
+    get_idol_profile,
     get_idol_records_count,
     get_idols,
+    get_idols_by_ids,
     get_idols_without_images,
+    get_integrity_report,
     get_label_by_id,
     get_label_records_count,
     get_labels,
+    get_labels_by_ids,
+    get_link_statistics,
+    get_public_saved_searches,
+    get_random_records,
     get_record_by_id,
+    get_record_comments,
+    get_record_history,
     get_record_ids_paginated,
     get_record_slim_paginated,
+    get_record_tags,
+    get_record_trash,
     get_records,
     // Records by entity handlers
+    get_records_by_date,
     get_records_by_director,
     get_records_by_genre,
     get_records_by_idol,
     get_records_by_label,
     get_records_by_series,
     get_records_by_studio,
+    get_related_genres,
+    get_saved_search_by_id,
+    get_saved_search_results,
+    get_saved_searches,
+    get_seen_history,
     get_series,
     get_series_by_id,
+    get_series_by_ids,
     get_series_records_count,
+    get_similar_records,
+    get_statistics_overview,
     get_studio_by_id,
+    get_studio_children,
     get_studio_records_count,
+    get_studio_rollup,
     get_studios,
+    get_studios_by_ids,
+    get_tag_by_id,
+    get_tag_categories,
+    get_tag_cloud,
+    get_tags,
+    get_translations,
     get_viewed_record_ids,
+    head_record,
+    import_records,
+    list_idol_media_by_id,
+    list_media,
+    mark_seen,
+    mark_unseen,
     mark_viewed,
+    merge_director,
+    merge_genre,
+    merge_idol,
+    merge_label,
+    merge_record,
+    merge_series,
+    merge_studio,
     patch_director,
     patch_genre,
     patch_idol,
@@ -138,50 +327,99 @@ use super::handlers::{
     patch_record,
     patch_series,
     patch_studio,
+    purge_record,
+    rate_record,
+    recent_atom_feed,
+    recent_json_feed,
+    reconcile_image_counts,
+    records_exist,
+    replace_idol_profile,
+    replace_record_full,
+    restore_record,
+    revert_record,
+    search_links,
     serve_idol_media_by_id,
     serve_idol_media_by_name,
     // Media handlers
     serve_media,
     serve_media_with_number,
+    serve_record_video,
+    set_genre_category,
+    set_label_studio,
+    set_record_cover,
+    set_record_series_order,
+    set_studio_parent,
+    set_translation,
+    star_link,
+    stream_catalog_events,
+    sync_records,
     // Interaction handlers (moved from user domain)
     toggle_like,
+    unfavorite_record,
+    unrate_record,
     update_director,
     update_genre,
+    update_genre_category,
     update_idol,
     update_label,
+    update_link,
     update_record,
+    update_record_comment,
     update_record_links,
+    update_saved_search,
     update_series,
     update_studio,
+    update_tag,
     upload_idol_images_by_id,
     upload_idol_images_by_name,
     upload_images,
+    upload_record_video,
 };
 
 use crate::{
     common::app_state::AppState,
     domains::{
         luna::dto::{
-            CreateDirectorDto, CreateGenreDto, CreateIdolDto, CreateLabelDto, CreateRecordDto,
-            CreateSeriesDto, CreateStudioDto, DirectorDto, GenreDto, IdolDto, LabelDto,
-            MediaAccessDto, PaginatedResponse, RecordDto, RecordSlimDto, SeriesDto, StudioDto,
-            UpdateDirectorDto, UpdateGenreDto, UpdateIdolDto, UpdateLabelDto, UpdateRecordDto,
-            UpdateSeriesDto, UpdateStudioDto,
+            AttachTagsDto, BatchLookupDto, BulkCreateMode, BulkCreateResponse,
+            BulkDeleteRecordsDto, BulkDeleteRecordsResponse, BulkItemResult, CatalogAction,
+            CatalogEvent, CommentAuthorDto, CommentDto, CreateCommentDto, CreateDirectorDto,
+            CreateGenreCategoryDto, CreateGenreDto, CreateIdolDto, CreateLabelDto, CreateRecordDto,
+            CreateSavedSearchDto, CreateSeriesDto, CreateStudioDto, CreateTagDto, DirectorDto,
+            DuplicateCandidateDto, DuplicateGroupDto, DuplicateImageFileDto,
+            DuplicateImageGroupDto, DuplicateReason, ExportEntity, ExportFormat,
+            GenreCategoryAssignmentDto, GenreCategoryCountDto, GenreCategoryDto, GenreDto, IdolDto,
+            IdolProfileDto, ImportConflictMode, ImportResponse, ImportRowResult, ImportRowStatus,
+            IntegrityReportDto, JsonFeed, JsonFeedAttachment, JsonFeedItem, LabelDto,
+            LabelStudioDto, Language, LinkDto, LinkSkipReason, LinkUpdateMode, LinkUpdateResultDto,
+            MediaAccessDto, MediaFileDto, MediaGcReportDto, MergeEntityDto, MergeEntityResponse,
+            MergeRecordDto, NameSuggestionDto, OrphanedJunctionRowsDto, OrphanedMediaDirDto,
+            OrphanedRowsDto, PaginatedResponse, PatchDirectorDto, PatchGenreDto, PatchIdolDto,
+            PatchLabelDto, PatchLinkDto, PatchRecordDto, PatchSeriesDto, PatchStudioDto,
+            PlaceholderReferencesDto, ReconcileImagesResponse, RecordDto, RecordExistsDto,
+            RecordExistsResponse, RecordIssueDto, RecordRevisionDto, RecordSlimDto,
+            RecordSyncResponse, ResolvedIdolDto, SavedSearchDto, SeenRecordDto, SeriesDto,
+            SeriesNeighborDto, SetRecordCoverDto, SetSeriesOrderDto, SetTranslationDto,
+            SkippedLinkDto, StarLinkDto, StudioChildrenDto, StudioDto, StudioParentDto,
+            StudioRollupDto, TagCategoryDto, TagCountDto, TagDto, ThumbnailFit, ThumbnailSpec,
+            TranslatedEntity, TranslationDto, UpdateCommentDto, UpdateDirectorDto, UpdateGenreDto,
+            UpdateIdolDto, UpdateLabelDto, UpdateRecordDto, UpdateSeriesDto, UpdateStudioDto,
         },
         user::dto::interaction_dto::{
-            BatchStatusRequestDto, InteractionStatusDto, MarkViewedResponse, ToggleLikeResponse,
+            BatchStatusRequestDto, FavoriteResponse, InteractionStatusDto, MarkSeenDto,
+            MarkViewedResponse, RateRecordDto, RatingResponse, SeenResponse, ToggleLikeResponse,
         },
     },
 };
 
 use axum::{
-    routing::{delete, get, patch, post, put},
+    routing::{delete, get, head, patch, post, put, MethodRouter},
     Router,
 };
 
 use utoipa::OpenApi;
 
-use crate::common::openapi::SecurityAddon;
+use crate::common::jwt::{with_role, Role};
+use crate::common::openapi::{ErrorResponsesAddon, SecurityAddon};
 
 #[derive(OpenApi)]
 #[openapi(
@@ -189,60 +427,152 @@ use crate::common::openapi::SecurityAddon;
         // Director endpoints
         get_director_by_id,
         get_directors,
+        get_directors_by_ids,
+        autocomplete_directors,
         create_director,
         update_director,
         patch_director,
         delete_director,
+        merge_director,
         // Genre endpoints
         get_genre_by_id,
         get_genres,
+        get_genres_by_ids,
+        autocomplete_genres,
         create_genre,
         update_genre,
         patch_genre,
         delete_genre,
+        merge_genre,
+        set_genre_category,
+        get_genre_categories,
+        get_genre_category_by_id,
+        create_genre_category,
+        update_genre_category,
+        delete_genre_category,
         // Label endpoints
         get_label_by_id,
         get_labels,
+        get_labels_by_ids,
+        autocomplete_labels,
         create_label,
         update_label,
         patch_label,
         delete_label,
+        merge_label,
+        set_label_studio,
         // Studio endpoints
         get_studio_by_id,
         get_studios,
+        get_studios_by_ids,
+        autocomplete_studios,
         create_studio,
         update_studio,
         patch_studio,
         delete_studio,
+        merge_studio,
+        set_studio_parent,
+        get_studio_children,
+        get_studio_rollup,
         // Series endpoints
         get_series_by_id,
         get_series,
+        get_series_by_ids,
+        autocomplete_series,
         create_series,
         update_series,
         patch_series,
         delete_series,
+        merge_series,
         // Idol endpoints
         get_idol_by_id,
         get_idols,
+        get_idols_by_ids,
+        autocomplete_idols,
         create_idol,
         update_idol,
         patch_idol,
         delete_idol,
+        merge_idol,
+        get_idol_profile,
+        replace_idol_profile,
         // Record endpoints
         get_record_by_id,
         get_records,
         create_record,
+        create_records_bulk,
+        head_record,
+        records_exist,
+        get_random_records,
+        get_similar_records,
         update_record,
         patch_record,
+        replace_record_full,
+        set_record_cover,
+        set_record_series_order,
         update_record_links,
         delete_record,
+        purge_record,
+        restore_record,
+        merge_record,
+        get_record_history,
+        revert_record,
+        get_record_trash,
+        delete_records_bulk,
+        get_record_comments,
+        create_record_comment,
+        update_record_comment,
+        delete_record_comment,
+        // Tag endpoints
+        get_tags,
+        create_tag,
+        get_tag_by_id,
+        update_tag,
+        delete_tag,
+        get_tag_categories,
+        get_tag_cloud,
+        get_record_tags,
+        attach_record_tags,
+        detach_record_tag,
+        // Saved search endpoints
+        get_saved_searches,
+        get_public_saved_searches,
+        create_saved_search,
+        get_saved_search_by_id,
+        update_saved_search,
+        delete_saved_search,
+        get_saved_search_results,
+        // Admin reports
+        get_duplicate_records,
+        get_duplicate_images,
+        get_integrity_report,
+        reconcile_image_counts,
+        collect_orphaned_media,
+        // Entity name translations
+        get_translations,
+        set_translation,
+        delete_translation,
+        // Link health endpoints
+        search_links,
+        get_dead_links,
+        check_link,
+        check_record_links,
+        update_link,
+        star_link,
+        delete_link,
         // Count endpoints
         get_director_records_count,
         get_genre_records_count,
+        get_genre_category_records_count,
         get_label_records_count,
         get_studio_records_count,
         get_series_records_count,
         get_idol_records_count,
+        get_statistics_overview,
+        get_records_by_date,
+        get_link_statistics,
+        get_idol_co_stars,
+        get_related_genres,
         // Records by entity endpoints
         get_records_by_director,
         get_records_by_studio,
@@ -254,34 +584,97 @@ use crate::common::openapi::SecurityAddon;
         get_record_ids_paginated,
         get_all_record_slim_all,
         get_record_slim_paginated,
+        sync_records,
+        // Export endpoints
+        export_records,
+        export_entities,
+        import_records,
+        // Feed endpoints
+        recent_atom_feed,
+        recent_json_feed,
+        stream_catalog_events,
         // Interaction endpoints (moved from user domain)
         toggle_like,
         mark_viewed,
         batch_status,
         get_viewed_record_ids,
+        favorite_record,
+        unfavorite_record,
+        get_favorite_records,
+        rate_record,
+        unrate_record,
+        mark_seen,
+        mark_unseen,
+        get_seen_history,
         get_idols_without_images,
         // media
         serve_media,
+        list_media,
+        archive_media,
+        serve_record_video,
+        upload_record_video,
         serve_idol_media_by_id,
         serve_idol_media_by_name,
+        list_idol_media_by_id,
+        archive_idol_media_by_id,
         upload_images,
         upload_idol_images_by_id,
         upload_idol_images_by_name,
     ),
     components(schemas(
-        DirectorDto, CreateDirectorDto, UpdateDirectorDto,
-        GenreDto, CreateGenreDto, UpdateGenreDto,
-        LabelDto, CreateLabelDto, UpdateLabelDto,
-        StudioDto, CreateStudioDto, UpdateStudioDto,
-        SeriesDto, CreateSeriesDto, UpdateSeriesDto,
-        IdolDto, CreateIdolDto, UpdateIdolDto,
-        RecordDto, RecordSlimDto, CreateRecordDto, UpdateRecordDto,
-        MediaAccessDto,
+        DirectorDto, CreateDirectorDto, UpdateDirectorDto, PatchDirectorDto,
+        GenreDto, CreateGenreDto, UpdateGenreDto, PatchGenreDto,
+        GenreCategoryDto, CreateGenreCategoryDto, GenreCategoryAssignmentDto, GenreCategoryCountDto,
+        LabelDto, CreateLabelDto, UpdateLabelDto, PatchLabelDto, LabelStudioDto,
+        StudioDto, CreateStudioDto, UpdateStudioDto, PatchStudioDto,
+        StudioParentDto, StudioChildrenDto, StudioRollupDto,
+        SeriesDto, CreateSeriesDto, UpdateSeriesDto, PatchSeriesDto,
+        IdolDto, CreateIdolDto, UpdateIdolDto, PatchIdolDto, IdolProfileDto,
+        RecordDto, RecordSlimDto, CreateRecordDto, UpdateRecordDto, PatchRecordDto,
+        BulkCreateMode, BulkItemResult, BulkCreateResponse, ResolvedIdolDto,
+        BulkDeleteRecordsDto, BulkDeleteRecordsResponse,
+        RecordExistsDto, RecordExistsResponse,
+        MergeEntityDto, MergeEntityResponse, MergeRecordDto, SetRecordCoverDto, BatchLookupDto,
+        NameSuggestionDto,
+        Language, TranslatedEntity, TranslationDto, SetTranslationDto,
+        SetSeriesOrderDto, SeriesNeighborDto,
+        MediaAccessDto, MediaFileDto, ThumbnailSpec, ThumbnailFit,
+        CommentDto, CommentAuthorDto, CreateCommentDto, UpdateCommentDto,
+        TagDto, TagCategoryDto, CreateTagDto, AttachTagsDto, TagCountDto,
+        SavedSearchDto, CreateSavedSearchDto,
+        PaginatedResponse<SavedSearchDto>,
+        DuplicateReason, DuplicateCandidateDto, DuplicateGroupDto,
+        DuplicateImageFileDto, DuplicateImageGroupDto,
+        IntegrityReportDto, PlaceholderReferencesDto, RecordIssueDto, OrphanedJunctionRowsDto,
+        OrphanedRowsDto, ReconcileImagesResponse, MediaGcReportDto, OrphanedMediaDirDto,
+        LinkDto, PatchLinkDto, LinkUpdateMode, PaginatedResponse<LinkDto>,
+        LinkUpdateResultDto, SkippedLinkDto, LinkSkipReason, StarLinkDto,
+        RecordRevisionDto, PaginatedResponse<RecordRevisionDto>,
         PaginatedResponse<RecordDto>,
         PaginatedResponse<RecordSlimDto>,
         PaginatedResponse<String>,
+        PaginatedResponse<CommentDto>,
+        PaginatedResponse<SeenRecordDto>,
+        SeenRecordDto,
+        RecordSyncResponse,
+        ExportFormat,
+        ExportEntity,
+        ImportConflictMode,
+        ImportRowStatus,
+        ImportRowResult,
+        ImportResponse,
+        CatalogAction,
+        CatalogEvent,
+        JsonFeed,
+        JsonFeedItem,
+        JsonFeedAttachment,
         ToggleLikeResponse,
         MarkViewedResponse,
+        FavoriteResponse,
+        RateRecordDto,
+        RatingResponse,
+        MarkSeenDto,
+        SeenResponse,
         BatchStatusRequestDto,
         InteractionStatusDto
     )),
@@ -293,79 +686,266 @@ use crate::common::openapi::SecurityAddon;
         (name = "Series", description = "Series management endpoints"),
         (name = "Idols", description = "Idol management endpoints"),
         (name = "Records", description = "Record management endpoints"),
+        (name = "Tags", description = "User-defined tags and record tagging endpoints"),
+        (name = "Saved Searches", description = "Named record searches users save, share and re-run"),
+        (name = "Admin", description = "Catalog maintenance reports and entity name translations for admins"),
+        (name = "Links", description = "Record link health checks"),
         (name = "Statistics", description = "Statistics and count endpoints"),
-        (name = "Media", description = "Media file serving endpoints")
+        (name = "Media", description = "Media file serving endpoints"),
+        (name = "Export", description = "CSV and JSONL catalog dumps and imports"),
+        (name = "Feeds", description = "Atom and JSON feeds of recently changed records"),
+        (name = "Events", description = "Server-sent catalog change events")
     ),
     security(
         ("bearer_auth" = [])
     ),
-    modifiers(&SecurityAddon)
+    modifiers(&SecurityAddon, &ErrorResponsesAddon)
 )]
 /// This struct is used to generate `OpenAPI` documentation for the luna routes.
 pub struct LunaApiDoc;
 
+#[cfg(feature = "metadata")]
+#[derive(OpenApi)]
+#[openapi(
+    paths(super::handlers::refresh_record_metadata),
+    components(schemas(
+        crate::domains::luna::dto::FetchedMetadata,
+        crate::domains::luna::dto::RefreshMetadataResponse
+    )),
+    tags(
+        (name = "Metadata", description = "Record metadata fetched from external providers")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    modifiers(&SecurityAddon, &ErrorResponsesAddon)
+)]
+/// `OpenAPI` documentation of the metadata refresh route, built with the
+/// `metadata` feature.
+pub struct MetadataApiDoc;
+
+/// Card writes and media uploads require the editor role; reads and the
+/// caller's own like/viewed/seen/favorite/rating interactions, history,
+/// comments and saved searches are open to every role.
+fn editor(route: MethodRouter<AppState>) -> MethodRouter<AppState> {
+    with_role(Role::Editor, route)
+}
+
+/// Catalog maintenance reports require the admin role.
+fn admin(route: MethodRouter<AppState>) -> MethodRouter<AppState> {
+    with_role(Role::Admin, route)
+}
+
 pub fn luna_routes() -> Router<AppState> {
-    Router::new()
+    let router = Router::new()
         // Director routes
         .route("/directors", get(get_directors))
-        .route("/directors", post(create_director))
+        .route("/directors", editor(post(create_director)))
+        .route("/directors/batch", post(get_directors_by_ids))
+        .route("/directors/autocomplete", get(autocomplete_directors))
         .route("/directors/{id}", get(get_director_by_id))
-        .route("/directors/{id}", put(update_director))
-        .route("/directors/{id}", patch(patch_director))
-        .route("/directors/{id}", delete(delete_director))
+        .route("/directors/{id}", editor(put(update_director)))
+        .route("/directors/{id}", editor(patch(patch_director)))
+        .route("/directors/{id}", editor(delete(delete_director)))
+        .route("/directors/{id}/merge", editor(post(merge_director)))
         // Genre routes
         .route("/genres", get(get_genres))
-        .route("/genres", post(create_genre))
+        .route("/genres", editor(post(create_genre)))
+        .route("/genres/batch", post(get_genres_by_ids))
+        .route("/genres/autocomplete", get(autocomplete_genres))
         .route("/genres/{id}", get(get_genre_by_id))
-        .route("/genres/{id}", put(update_genre))
-        .route("/genres/{id}", patch(patch_genre))
-        .route("/genres/{id}", delete(delete_genre))
+        .route("/genres/{id}", editor(put(update_genre)))
+        .route("/genres/{id}", editor(patch(patch_genre)))
+        .route("/genres/{id}", editor(delete(delete_genre)))
+        .route("/genres/{id}/merge", editor(post(merge_genre)))
+        .route("/genres/{id}/related", get(get_related_genres))
+        .route("/genres/{id}/category", editor(put(set_genre_category)))
+        .route("/genres/categories", get(get_genre_categories))
+        .route("/genres/categories", editor(post(create_genre_category)))
+        .route("/genres/categories/{id}", get(get_genre_category_by_id))
+        .route(
+            "/genres/categories/{id}",
+            editor(put(update_genre_category)),
+        )
+        .route(
+            "/genres/categories/{id}",
+            editor(delete(delete_genre_category)),
+        )
         // Label routes
         .route("/labels", get(get_labels))
-        .route("/labels", post(create_label))
+        .route("/labels", editor(post(create_label)))
+        .route("/labels/batch", post(get_labels_by_ids))
+        .route("/labels/autocomplete", get(autocomplete_labels))
         .route("/labels/{id}", get(get_label_by_id))
-        .route("/labels/{id}", put(update_label))
-        .route("/labels/{id}", patch(patch_label))
-        .route("/labels/{id}", delete(delete_label))
+        .route("/labels/{id}", editor(put(update_label)))
+        .route("/labels/{id}", editor(patch(patch_label)))
+        .route("/labels/{id}", editor(delete(delete_label)))
+        .route("/labels/{id}/merge", editor(post(merge_label)))
+        .route("/labels/{id}/studio", editor(put(set_label_studio)))
         // Studio routes
         .route("/studios", get(get_studios))
-        .route("/studios", post(create_studio))
+        .route("/studios", editor(post(create_studio)))
+        .route("/studios/batch", post(get_studios_by_ids))
+        .route("/studios/autocomplete", get(autocomplete_studios))
         .route("/studios/{id}", get(get_studio_by_id))
-        .route("/studios/{id}", put(update_studio))
-        .route("/studios/{id}", patch(patch_studio))
-        .route("/studios/{id}", delete(delete_studio))
+        .route("/studios/{id}", editor(put(update_studio)))
+        .route("/studios/{id}", editor(patch(patch_studio)))
+        .route("/studios/{id}", editor(delete(delete_studio)))
+        .route("/studios/{id}/merge", editor(post(merge_studio)))
+        .route("/studios/{id}/parent", editor(put(set_studio_parent)))
+        .route("/studios/{id}/children", get(get_studio_children))
+        .route("/studios/{id}/rollup", get(get_studio_rollup))
         // Series routes
         .route("/series", get(get_series))
-        .route("/series", post(create_series))
+        .route("/series", editor(post(create_series)))
+        .route("/series/batch", post(get_series_by_ids))
+        .route("/series/autocomplete", get(autocomplete_series))
         .route("/series/{id}", get(get_series_by_id))
-        .route("/series/{id}", put(update_series))
-        .route("/series/{id}", patch(patch_series))
-        .route("/series/{id}", delete(delete_series))
+        .route("/series/{id}", editor(put(update_series)))
+        .route("/series/{id}", editor(patch(patch_series)))
+        .route("/series/{id}", editor(delete(delete_series)))
+        .route("/series/{id}/merge", editor(post(merge_series)))
         // Idol routes
         .route("/idols", get(get_idols))
         .route("/idols/without-images", get(get_idols_without_images))
-        .route("/idols", post(create_idol))
+        .route("/idols", editor(post(create_idol)))
+        .route("/idols/batch", post(get_idols_by_ids))
+        .route("/idols/autocomplete", get(autocomplete_idols))
         .route("/idols/{id}", get(get_idol_by_id))
-        .route("/idols/{id}", put(update_idol))
-        .route("/idols/{id}", patch(patch_idol))
-        .route("/idols/{id}", delete(delete_idol))
+        .route("/idols/{id}", editor(put(update_idol)))
+        .route("/idols/{id}", editor(patch(patch_idol)))
+        .route("/idols/{id}", editor(delete(delete_idol)))
+        .route("/idols/{id}/merge", editor(post(merge_idol)))
+        .route("/idols/{id}/profile", get(get_idol_profile))
+        .route("/idols/{id}/profile", editor(put(replace_idol_profile)))
+        .route("/idols/{id}/co-stars", get(get_idol_co_stars))
+        // Tag routes
+        .route("/tags", get(get_tags))
+        .route("/tags", editor(post(create_tag)))
+        .route("/tags/categories", get(get_tag_categories))
+        .route("/tags/cloud", get(get_tag_cloud))
+        .route("/tags/{id}", get(get_tag_by_id))
+        .route("/tags/{id}", editor(put(update_tag)))
+        .route("/tags/{id}", editor(delete(delete_tag)))
+        // Admin report routes
+        .route("/admin/duplicates", admin(get(get_duplicate_records)))
+        .route("/admin/duplicate-images", admin(get(get_duplicate_images)))
+        .route("/admin/integrity", admin(get(get_integrity_report)))
+        .route(
+            "/admin/integrity/reconcile-images",
+            admin(post(reconcile_image_counts)),
+        )
+        .route("/admin/media-gc", admin(post(collect_orphaned_media)))
+        // Entity name translation routes
+        .route(
+            "/admin/translations/{entity}/{id}",
+            admin(get(get_translations)),
+        )
+        .route(
+            "/admin/translations/{entity}/{id}/{language}",
+            admin(put(set_translation)),
+        )
+        .route(
+            "/admin/translations/{entity}/{id}/{language}",
+            admin(delete(delete_translation)),
+        )
+        // Link health routes
+        .route("/links", get(search_links))
+        .route("/links/dead", editor(get(get_dead_links)))
+        .route("/links/{id}/check", editor(post(check_link)))
+        .route(
+            "/records/{id}/links/check",
+            editor(post(check_record_links)),
+        )
+        .route("/records/{id}/links/{link_id}", editor(patch(update_link)))
+        .route("/records/{id}/links/{link_id}", editor(delete(delete_link)))
+        .route(
+            "/records/{id}/links/{link_id}/star",
+            editor(post(star_link)),
+        )
+        // Saved search routes
+        .route("/saved-searches", get(get_saved_searches))
+        .route("/saved-searches", post(create_saved_search))
+        .route("/saved-searches/public", get(get_public_saved_searches))
+        .route("/saved-searches/{id}", get(get_saved_search_by_id))
+        .route("/saved-searches/{id}", put(update_saved_search))
+        .route("/saved-searches/{id}", delete(delete_saved_search))
+        .route(
+            "/saved-searches/{id}/results",
+            get(get_saved_search_results),
+        )
         // Record routes
         .route("/records", get(get_records))
-        .route("/records", post(create_record))
+        .route("/records", editor(post(create_record)))
+        .route("/records", editor(delete(delete_records_bulk)))
+        .route("/records/bulk", editor(post(create_records_bulk)))
+        .route("/records/exists", post(records_exist))
+        .route("/records/random", get(get_random_records))
         .route("/records/{id}", get(get_record_by_id))
-        .route("/records/{id}", put(update_record))
-        .route("/records/{id}", patch(patch_record))
-        .route("/records/links/{id}", patch(update_record_links))
-        .route("/records/{id}", delete(delete_record))
+        .route("/records/{id}", head(head_record))
+        .route("/records/{id}", editor(put(update_record)))
+        .route("/records/{id}", editor(patch(patch_record)))
+        .route("/records/{id}/full", editor(put(replace_record_full)))
+        .route("/records/{id}/cover", editor(put(set_record_cover)))
+        .route(
+            "/records/{id}/series-order",
+            editor(put(set_record_series_order)),
+        )
+        .route("/records/{id}/similar", get(get_similar_records))
+        .route("/records/{id}/comments", get(get_record_comments))
+        .route("/records/{id}/comments", post(create_record_comment))
+        .route(
+            "/records/{id}/comments/{comment_id}",
+            put(update_record_comment),
+        )
+        .route(
+            "/records/{id}/comments/{comment_id}",
+            delete(delete_record_comment),
+        )
+        .route("/records/{id}/tags", get(get_record_tags))
+        .route("/records/{id}/tags", editor(post(attach_record_tags)))
+        .route(
+            "/records/{id}/tags/{tag_id}",
+            editor(delete(detach_record_tag)),
+        )
+        .route("/records/links/{id}", editor(patch(update_record_links)))
+        .route("/records/{id}", editor(delete(delete_record)))
+        .route("/records/trash", editor(get(get_record_trash)))
+        .route("/records/{id}/restore", editor(post(restore_record)))
+        .route("/records/{id}/purge", editor(delete(purge_record)))
+        .route("/records/{id}/merge", editor(post(merge_record)))
+        .route("/records/{id}/history", get(get_record_history))
+        .route(
+            "/records/{id}/revert/{revision}",
+            editor(post(revert_record)),
+        )
         .route("/records/ids", get(get_record_ids_paginated))
         .route("/records/ids/all", get(get_all_record_ids_all))
         .route("/records/slim", get(get_record_slim_paginated))
         .route("/records/slim/all", get(get_all_record_slim_all))
+        .route("/sync/records", get(sync_records))
+        .route("/export/records", get(export_records))
+        .route("/export/{entity}", get(export_entities))
+        .route("/import", editor(post(import_records)))
+        .route("/feeds/recent.atom", get(recent_atom_feed))
+        .route("/feeds/recent.json", get(recent_json_feed))
+        .route("/events", get(stream_catalog_events))
         // User interaction routes (moved from /user domain)
         .route("/records/user/{record_id}/like", post(toggle_like))
         .route("/records/user/{record_id}/viewed", post(mark_viewed))
         .route("/records/user/status", post(batch_status))
         .route("/records/user/viewed", get(get_viewed_record_ids))
+        .route("/records/user/{record_id}/favorite", put(favorite_record))
+        .route(
+            "/records/user/{record_id}/favorite",
+            delete(unfavorite_record),
+        )
+        .route("/records/user/favorites", get(get_favorite_records))
+        .route("/records/user/{record_id}/rating", put(rate_record))
+        .route("/records/user/{record_id}/rating", delete(unrate_record))
+        .route("/records/user/{record_id}/seen", put(mark_seen))
+        .route("/records/user/{record_id}/seen", delete(mark_unseen))
+        .route("/users/me/history", get(get_seen_history))
         // Records by entity endpoints
         .route("/director/{id}/records", get(get_records_by_director))
         .route("/studio/{id}/records", get(get_records_by_studio))
@@ -376,23 +956,44 @@ pub fn luna_routes() -> Router<AppState> {
         // Count routes
         .route("/director-records-count", get(get_director_records_count))
         .route("/genre-records-count", get(get_genre_records_count))
+        .route(
+            "/genre-records-count/by-category",
+            get(get_genre_category_records_count),
+        )
         .route("/label-records-count", get(get_label_records_count))
         .route("/studio-records-count", get(get_studio_records_count))
         .route("/series-records-count", get(get_series_records_count))
         .route("/idol-records-count", get(get_idol_records_count))
+        .route("/statistics/overview", get(get_statistics_overview))
+        .route("/statistics/records-by-date", get(get_records_by_date))
+        .route("/statistics/links", get(get_link_statistics))
         // Media routes
         .route("/media/{id}", get(serve_media))
+        .route("/media/{id}/list", get(list_media))
+        .route("/media/{id}/archive", get(archive_media))
+        .route("/media/{id}/video", get(serve_record_video))
+        .route("/media/{id}/video", editor(post(upload_record_video)))
         .route("/media/{id}/{n}", get(serve_media_with_number))
-        .route("/media/upload", post(upload_images))
+        .route("/media/upload", editor(post(upload_images)))
         // Idol media routes
         .route("/media/idol/id/{id}", get(serve_idol_media_by_id))
+        .route("/media/idol/id/{id}/list", get(list_idol_media_by_id))
+        .route("/media/idol/id/{id}/archive", get(archive_idol_media_by_id))
         .route("/media/idol/name/{name}", get(serve_idol_media_by_name))
         .route(
             "/media/upload_idol_by_id/{id}",
-            post(upload_idol_images_by_id),
+            editor(post(upload_idol_images_by_id)),
         )
         .route(
             "/media/upload_idol_by_name/{name}",
-            post(upload_idol_images_by_name),
-        )
+            editor(post(upload_idol_images_by_name)),
+        );
+
+    #[cfg(feature = "metadata")]
+    let router = router.route(
+        "/records/{id}/refresh-metadata",
+        editor(post(super::handlers::refresh_record_metadata)),
+    );
+
+    router
 }
@@ -7,6 +7,8 @@ pub struct Genre {
     pub name: String,
     pub link: String,
     pub manual: bool,
+    /// Category the genre is grouped under.
+    pub category_id: Option<i64>,
 }
 
 impl From<genre::Model> for Genre {
@@ -16,6 +18,7 @@ impl From<genre::Model> for Genre {
             name: genre.name,
             link: genre.link,
             manual: genre.manual,
+            category_id: genre.category_id,
         }
     }
 }
@@ -1,4 +1,5 @@
 use crate::entities::idol;
+use chrono::{Datelike as _, NaiveDate};
 
 /// Domain model representing an idol in the application.
 #[derive(Debug, Clone)]
@@ -7,6 +8,22 @@ pub struct Idol {
     pub name: String,
     pub link: String,
     pub manual: bool,
+    pub birthday: Option<NaiveDate>,
+    pub debut_date: Option<NaiveDate>,
+    pub height_cm: Option<i32>,
+    pub bust_cm: Option<i32>,
+    pub waist_cm: Option<i32>,
+    pub hips_cm: Option<i32>,
+}
+
+impl Idol {
+    /// Age in whole years on `date`, or `None` when the birthday is unknown
+    /// or after `date`.
+    pub fn age_on(&self, date: NaiveDate) -> Option<i32> {
+        let birthday = self.birthday.filter(|&birthday| birthday <= date)?;
+        let had_birthday = (date.month(), date.day()) >= (birthday.month(), birthday.day());
+        Some(date.year() - birthday.year() - i32::from(!had_birthday))
+    }
 }
 
 impl From<idol::Model> for Idol {
@@ -16,6 +33,12 @@ impl From<idol::Model> for Idol {
             name: idol.name,
             link: idol.link,
             manual: idol.manual,
+            birthday: idol.birthday,
+            debut_date: idol.debut_date,
+            height_cm: idol.height_cm,
+            bust_cm: idol.bust_cm,
+            waist_cm: idol.waist_cm,
+            hips_cm: idol.hips_cm,
         }
     }
 }
@@ -25,4 +48,46 @@ impl From<idol::Model> for Idol {
 pub struct IdolParticipation {
     pub idol: Idol,
     pub manual: bool,
+    /// Age of the idol on the record's release date, when the birthday is
+    /// known.
+    pub age_at_record: Option<i32>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Idol;
+    use chrono::NaiveDate;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).expect("valid date")
+    }
+
+    fn idol(birthday: Option<NaiveDate>) -> Idol {
+        Idol {
+            id: 1,
+            name: "Aoi".to_owned(),
+            link: String::new(),
+            manual: false,
+            birthday,
+            debut_date: None,
+            height_cm: None,
+            bust_cm: None,
+            waist_cm: None,
+            hips_cm: None,
+        }
+    }
+
+    #[test]
+    fn age_counts_whole_years() {
+        let aoi = idol(Some(date(2000, 6, 15)));
+        assert_eq!(aoi.age_on(date(2020, 6, 14)), Some(19));
+        assert_eq!(aoi.age_on(date(2020, 6, 15)), Some(20));
+        assert_eq!(aoi.age_on(date(2000, 6, 15)), Some(0));
+        assert_eq!(aoi.age_on(date(1999, 1, 1)), None, "before birth");
+        assert_eq!(idol(None).age_on(date(2020, 1, 1)), None);
+
+        let leap = idol(Some(date(2000, 2, 29)));
+        assert_eq!(leap.age_on(date(2021, 2, 28)), Some(20));
+        assert_eq!(leap.age_on(date(2021, 3, 1)), Some(21));
+    }
 }
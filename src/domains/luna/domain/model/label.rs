@@ -7,6 +7,8 @@ pub struct Label {
     pub name: String,
     pub link: String,
     pub manual: bool,
+    /// Studio that publishes the label.
+    pub studio_id: Option<i64>,
 }
 
 impl From<label::Model> for Label {
@@ -16,6 +18,7 @@ impl From<label::Model> for Label {
             name: label.name,
             link: label.link,
             manual: label.manual,
+            studio_id: label.studio_id,
         }
     }
 }
@@ -1,4 +1,5 @@
 use crate::entities::links;
+use chrono::{DateTime, Utc};
 use sea_orm::entity::prelude::*;
 
 /// Domain model representing a link in the application.
@@ -11,6 +12,11 @@ pub struct Link {
     pub date: Date,
     pub link: String,
     pub star: bool,
+    pub priority: i32,
+    pub dead: bool,
+    pub last_status: Option<i32>,
+    pub last_checked_at: Option<DateTime<Utc>>,
+    pub create_time: Date,
 }
 
 impl From<links::Model> for Link {
@@ -23,6 +29,11 @@ impl From<links::Model> for Link {
             date: link.date,
             link: link.link,
             star: link.star,
+            priority: link.priority,
+            dead: link.dead,
+            last_status: link.last_status,
+            last_checked_at: link.last_checked_at,
+            create_time: link.create_time,
         }
     }
 }
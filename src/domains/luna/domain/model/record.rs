@@ -1,5 +1,7 @@
 use sea_orm::entity::prelude::*;
 
+use crate::common::jwt::Role;
+
 /// Domain model representing a record in the application.
 #[derive(Debug, Clone)]
 pub struct Record {
@@ -21,4 +23,92 @@ pub struct Record {
     pub update_time: Date,
     pub creator: String,
     pub modified_by: String,
+    pub version: i32,
+    /// URL of the trailer video; `None` until one is uploaded.
+    pub trailer: Option<String>,
+    /// Sequence number of the cover image; `None` for the main `{id}` image.
+    pub cover_index: Option<i32>,
+    /// Position within the series; `None` when not numbered.
+    pub order_in_series: Option<i32>,
+    /// Average of the users' 1-10 scores; `None` while unrated.
+    pub rating_average: Option<f64>,
+    pub rating_count: i64,
+    pub comment_count: i64,
+}
+
+/// Visibility levels stored in `record.permission`.
+///
+/// A caller sees a record when its `permission` is at most the caller's
+/// [`clearance`](Self::clearance). Values above `AdminOnly` are treated as
+/// admin-only.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RecordPermission {
+    /// Visible without authentication.
+    Public,
+    /// Visible to every authenticated user.
+    Authenticated,
+    /// Visible to admins only.
+    AdminOnly,
+}
+
+impl RecordPermission {
+    /// The value stored in `record.permission` for this level.
+    pub const fn level(self) -> i32 {
+        match self {
+            Self::Public => 0,
+            Self::Authenticated => 1,
+            Self::AdminOnly => 2,
+        }
+    }
+
+    /// Highest `record.permission` an authenticated caller with `role` may see.
+    pub const fn clearance(role: Role) -> i32 {
+        match role {
+            Role::Admin => i32::MAX,
+            Role::Editor | Role::Viewer => Self::Authenticated.level(),
+        }
+    }
+}
+
+/// Whether record `id` names a single directory under a media root, so it
+/// can be joined onto a media path. Rejects empty IDs, `.`, `..` anywhere,
+/// path separators and NUL.
+pub fn is_safe_media_id(id: &str) -> bool {
+    !id.is_empty()
+        && id != "."
+        && !id.contains("..")
+        && !id.contains('/')
+        && !id.contains('\\')
+        && !id.contains('\0')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_safe_media_id, RecordPermission, Role};
+
+    #[test]
+    fn media_ids_name_one_directory() {
+        assert!(is_safe_media_id("ABC-123"));
+        assert!(is_safe_media_id("v1.2"));
+        for id in ["", ".", "..", "a/../b", "a/b", "a\\b", "a\0b"] {
+            assert!(!is_safe_media_id(id), "{id:?}");
+        }
+    }
+
+    #[test]
+    fn admins_see_every_level() {
+        let clearance = RecordPermission::clearance(Role::Admin);
+        assert!(RecordPermission::AdminOnly.level() <= clearance);
+        assert!(i32::MAX <= clearance);
+    }
+
+    #[test]
+    fn non_admins_see_up_to_authenticated() {
+        for role in [Role::Viewer, Role::Editor] {
+            let clearance = RecordPermission::clearance(role);
+            assert!(RecordPermission::Public.level() <= clearance);
+            assert!(RecordPermission::Authenticated.level() <= clearance);
+            assert!(RecordPermission::AdminOnly.level() > clearance);
+        }
+    }
 }
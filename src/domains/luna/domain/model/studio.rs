@@ -7,6 +7,8 @@ pub struct Studio {
     pub name: String,
     pub link: String,
     pub manual: bool,
+    /// Studio group the studio belongs to.
+    pub parent_id: Option<i64>,
 }
 
 impl From<studio::Model> for Studio {
@@ -16,6 +18,7 @@ impl From<studio::Model> for Studio {
             name: studio.name,
             link: studio.link,
             manual: studio.manual,
+            parent_id: studio.parent_id,
         }
     }
 }
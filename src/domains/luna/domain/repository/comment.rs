@@ -0,0 +1,42 @@
+use crate::domains::luna::dto::CommentDto;
+use async_trait::async_trait;
+use sea_orm::{DatabaseConnection, DbErr};
+
+#[async_trait]
+/// Persistence of the comments users attach to records. Comments are read
+/// together with their author.
+pub trait CommentRepository: Send + Sync {
+    /// A page of the comments on `record_id`, oldest first, and the total
+    /// number of comments on it.
+    async fn find_by_record_paginated(
+        &self,
+        db: &DatabaseConnection,
+        record_id: &str,
+        limit: u64,
+        offset: u64,
+    ) -> Result<(Vec<CommentDto>, u64), DbErr>;
+
+    /// Comment `id` on `record_id`, or `None` if there is no such comment on
+    /// that record.
+    async fn find_by_id(
+        &self,
+        db: &DatabaseConnection,
+        record_id: &str,
+        id: i64,
+    ) -> Result<Option<CommentDto>, DbErr>;
+
+    /// Stores a new comment and returns its ID.
+    async fn create(
+        &self,
+        db: &DatabaseConnection,
+        record_id: &str,
+        user_id: &str,
+        body: &str,
+    ) -> Result<i64, DbErr>;
+
+    /// Replaces the body of comment `id` and stamps `updated_at`.
+    async fn update_body(&self, db: &DatabaseConnection, id: i64, body: &str) -> Result<(), DbErr>;
+
+    /// Deletes comment `id`.
+    async fn delete(&self, db: &DatabaseConnection, id: i64) -> Result<(), DbErr>;
+}
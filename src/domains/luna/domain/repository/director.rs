@@ -1,8 +1,8 @@
 use crate::domains::luna::{
     domain::Director,
     dto::{
-        CreateDirectorDto, EntityCountDto, PaginatedResponse, PaginationQuery, SearchDirectorDto,
-        UpdateDirectorDto,
+        CreateDirectorDto, EntityCountDto, NameSuggestionDto, PaginatedResponse, PaginationQuery,
+        RecordCountQuery, SearchDirectorDto, UpdateDirectorDto,
     },
 };
 use async_trait::async_trait;
@@ -18,6 +18,23 @@ pub trait DirectorRepository: Send + Sync {
     async fn find_by_id(&self, db: &DatabaseConnection, id: i64)
         -> Result<Option<Director>, DbErr>;
 
+    /// Finds the directors with the given identifiers, in the order of `ids`.
+    /// Unknown identifiers are skipped.
+    async fn find_by_ids(
+        &self,
+        db: &DatabaseConnection,
+        ids: &[i64],
+    ) -> Result<Vec<Director>, DbErr>;
+
+    /// Up to `limit` directors whose name contains `term` (lowercased), those whose
+    /// name starts with it first, then the ones with the most records.
+    async fn autocomplete(
+        &self,
+        db: &DatabaseConnection,
+        term: &str,
+        limit: u64,
+    ) -> Result<Vec<NameSuggestionDto>, DbErr>;
+
     /// Finds director list by condition
     async fn find_list(
         &self,
@@ -51,11 +68,12 @@ pub trait DirectorRepository: Send + Sync {
     /// Deletes a director by their unique identifier within an active transaction.
     async fn delete(&self, txn: &DatabaseTransaction, id: i64) -> Result<bool, DbErr>;
 
-    /// Gets record counts grouped by directors.
+    /// Gets one page of record counts grouped by directors, filtered by `query`.
     async fn get_director_record_counts(
         &self,
         db: &DatabaseConnection,
-    ) -> Result<Vec<EntityCountDto>, DbErr>;
+        query: &RecordCountQuery,
+    ) -> Result<PaginatedResponse<EntityCountDto>, DbErr>;
 }
 
 #[async_trait]
@@ -87,6 +105,9 @@ pub trait DirectorAffinityRepository: Send + Sync {
     /// The score is a relative ordering key and is NOT bounded to `[0, 1]`; a
     /// director with `total = 0` scores `0` and sorts last. Hyper-parameters
     /// live as constants in the repository implementation.
+    ///
+    /// A non-empty `pagination.ordering` (see `ENTITY_ORDERING_FIELDS`)
+    /// replaces the score as the sort key; filters and paging are unchanged.
     async fn find_list_paginated_by_affinity(
         &self,
         db: &DatabaseConnection,
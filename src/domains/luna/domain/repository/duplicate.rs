@@ -0,0 +1,35 @@
+use crate::domains::luna::dto::DuplicateCandidateDto;
+use async_trait::async_trait;
+use sea_orm::{DatabaseConnection, DbErr};
+
+#[async_trait]
+/// Reads backing the duplicate-record report. Trashed records are left out.
+pub trait DuplicateRepository: Send + Sync {
+    /// Up to `limit` sets of record IDs sharing a title (ignoring case and
+    /// surrounding spaces) and release date, largest sets first. IDs are
+    /// sorted within each set.
+    async fn same_title_and_date(
+        &self,
+        db: &DatabaseConnection,
+        limit: u64,
+    ) -> Result<Vec<Vec<String>>, DbErr>;
+
+    /// Up to `limit` pairs of records with the same non-empty set of idols
+    /// whose titles have a trigram similarity of at least `min_similarity`,
+    /// as `(lower ID, higher ID, similarity)`, most similar first. Pairs
+    /// that also share title and date are left to
+    /// [`same_title_and_date`](Self::same_title_and_date).
+    async fn similar_titles_with_same_idols(
+        &self,
+        db: &DatabaseConnection,
+        min_similarity: f64,
+        limit: u64,
+    ) -> Result<Vec<(String, String, f64)>, DbErr>;
+
+    /// ID, title and date of the records in `ids`.
+    async fn find_candidates(
+        &self,
+        db: &DatabaseConnection,
+        ids: &[String],
+    ) -> Result<Vec<DuplicateCandidateDto>, DbErr>;
+}
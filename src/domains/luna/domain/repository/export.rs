@@ -0,0 +1,43 @@
+use crate::domains::luna::{domain::Record, dto::ExportEntity};
+use async_trait::async_trait;
+use sea_orm::{DatabaseConnection, DbErr};
+
+/// Columns shared by every named card entity table.
+#[derive(Debug, Clone)]
+pub struct NamedEntityRow {
+    pub id: i64,
+    pub name: String,
+    pub link: String,
+    pub manual: bool,
+}
+
+#[async_trait]
+/// Reads backing the export and import endpoints.
+pub trait ExportRepository: Send + Sync {
+    /// Next batch of live records visible at `max_permission`, ordered by ID
+    /// and starting after `after` (from the beginning when `None`).
+    async fn record_batch(
+        &self,
+        db: &DatabaseConnection,
+        after: Option<String>,
+        limit: u64,
+        max_permission: i32,
+    ) -> Result<Vec<Record>, DbErr>;
+
+    /// Next batch of `entity` rows ordered by ID, starting after `after`.
+    async fn entity_batch(
+        &self,
+        db: &DatabaseConnection,
+        entity: ExportEntity,
+        after: i64,
+        limit: u64,
+    ) -> Result<Vec<NamedEntityRow>, DbErr>;
+
+    /// The lowest-ID `entity` row named exactly `name`, if any.
+    async fn entity_by_name(
+        &self,
+        db: &DatabaseConnection,
+        entity: ExportEntity,
+        name: &str,
+    ) -> Result<Option<NamedEntityRow>, DbErr>;
+}
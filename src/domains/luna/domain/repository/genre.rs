@@ -1,8 +1,9 @@
 use crate::domains::luna::{
     domain::Genre,
     dto::{
-        CreateGenreDto, EntityCountDto, PaginatedResponse, PaginationQuery, SearchGenreDto,
-        UpdateGenreDto,
+        CreateGenreCategoryDto, CreateGenreDto, EntityCountDto, GenreCategoryCountDto,
+        GenreCategoryDto, NameSuggestionDto, PaginatedResponse, PaginationQuery, RecordCountQuery,
+        SearchGenreDto, UpdateGenreDto,
     },
 };
 
@@ -18,6 +19,19 @@ pub trait GenreRepository: Send + Sync {
     /// Finds a genre by their unique identifier.
     async fn find_by_id(&self, db: &DatabaseConnection, id: i64) -> Result<Option<Genre>, DbErr>;
 
+    /// Finds the genres with the given identifiers, in the order of `ids`.
+    /// Unknown identifiers are skipped.
+    async fn find_by_ids(&self, db: &DatabaseConnection, ids: &[i64]) -> Result<Vec<Genre>, DbErr>;
+
+    /// Up to `limit` genres whose name contains `term` (lowercased), those whose
+    /// name starts with it first, then the ones with the most records.
+    async fn autocomplete(
+        &self,
+        db: &DatabaseConnection,
+        term: &str,
+        limit: u64,
+    ) -> Result<Vec<NameSuggestionDto>, DbErr>;
+
     /// Finds genre list by condition
     async fn find_list(
         &self,
@@ -51,11 +65,12 @@ pub trait GenreRepository: Send + Sync {
     /// Deletes a genre by their unique identifier within an active transaction.
     async fn delete(&self, txn: &DatabaseTransaction, id: i64) -> Result<bool, DbErr>;
 
-    /// Gets record counts grouped by genres.
+    /// Gets one page of record counts grouped by genres, filtered by `query`.
     async fn get_genre_record_counts(
         &self,
         db: &DatabaseConnection,
-    ) -> Result<Vec<EntityCountDto>, DbErr>;
+        query: &RecordCountQuery,
+    ) -> Result<PaginatedResponse<EntityCountDto>, DbErr>;
 }
 
 #[async_trait]
@@ -86,6 +101,9 @@ pub trait GenreAffinityRepository: Send + Sync {
     /// The score is a relative ordering key and is NOT bounded to `[0, 1]`; a
     /// genre with `total = 0` scores `0` and sorts last. Hyper-parameters live
     /// as constants in the repository implementation.
+    ///
+    /// A non-empty `pagination.ordering` (see `ENTITY_ORDERING_FIELDS`)
+    /// replaces the score as the sort key; filters and paging are unchanged.
     async fn find_list_paginated_by_affinity(
         &self,
         db: &DatabaseConnection,
@@ -94,3 +112,62 @@ pub trait GenreAffinityRepository: Send + Sync {
         user_id: &str,
     ) -> Result<PaginatedResponse<Genre>, DbErr>;
 }
+
+#[async_trait]
+/// Repository trait for genre categories and the category of each genre.
+/// Kept apart from [`GenreRepository`] for the same reason as
+/// [`GenreAffinityRepository`]: the other named entities have no categories.
+pub trait GenreCategoryRepository: Send + Sync {
+    /// Every genre category, by name.
+    async fn find_categories(
+        &self,
+        db: &DatabaseConnection,
+    ) -> Result<Vec<GenreCategoryDto>, DbErr>;
+
+    /// Genre category `id`, or `None` if it does not exist.
+    async fn find_category(
+        &self,
+        db: &DatabaseConnection,
+        id: i64,
+    ) -> Result<Option<GenreCategoryDto>, DbErr>;
+
+    /// Stores a new genre category and returns its ID.
+    async fn create_category(
+        &self,
+        db: &DatabaseConnection,
+        dto: CreateGenreCategoryDto,
+    ) -> Result<i64, DbErr>;
+
+    /// Replaces the name and description of genre category `id`. Returns
+    /// `false` when it does not exist.
+    async fn update_category(
+        &self,
+        db: &DatabaseConnection,
+        id: i64,
+        dto: CreateGenreCategoryDto,
+    ) -> Result<bool, DbErr>;
+
+    /// Deletes genre category `id`, leaving its genres uncategorized. Returns
+    /// the IDs of those genres, or `None` when the category does not exist.
+    async fn delete_category(
+        &self,
+        txn: &DatabaseTransaction,
+        id: i64,
+    ) -> Result<Option<Vec<i64>>, DbErr>;
+
+    /// Puts genre `id` in `category_id`, or leaves it uncategorized. Returns
+    /// the updated genre, or `None` when it does not exist.
+    async fn set_genre_category(
+        &self,
+        txn: &DatabaseTransaction,
+        id: i64,
+        category_id: Option<i64>,
+    ) -> Result<Option<Genre>, DbErr>;
+
+    /// Genre record counts grouped by category: every category, even
+    /// without genres, by record count, then the uncategorized genres.
+    async fn category_record_counts(
+        &self,
+        db: &DatabaseConnection,
+    ) -> Result<Vec<GenreCategoryCountDto>, DbErr>;
+}
@@ -4,8 +4,8 @@ use sea_orm::{DatabaseConnection, DatabaseTransaction, DbErr};
 use crate::domains::luna::{
     domain::Idol,
     dto::{
-        CreateIdolDto, EntityCountDto, PaginatedResponse, PaginationQuery, SearchIdolDto,
-        UpdateIdolDto,
+        CreateIdolDto, EntityCountDto, IdolProfileDto, NameSuggestionDto, PaginatedResponse,
+        PaginationQuery, RecordCountQuery, SearchIdolDto, UpdateIdolDto,
     },
 };
 
@@ -18,6 +18,19 @@ pub trait IdolRepository: Send + Sync {
     /// Finds an idol by their unique identifier.
     async fn find_by_id(&self, db: &DatabaseConnection, id: i64) -> Result<Option<Idol>, DbErr>;
 
+    /// Finds the idols with the given identifiers, in the order of `ids`.
+    /// Unknown identifiers are skipped.
+    async fn find_by_ids(&self, db: &DatabaseConnection, ids: &[i64]) -> Result<Vec<Idol>, DbErr>;
+
+    /// Up to `limit` idols whose name contains `term` (lowercased), those whose
+    /// name starts with it first, then the ones with the most records.
+    async fn autocomplete(
+        &self,
+        db: &DatabaseConnection,
+        term: &str,
+        limit: u64,
+    ) -> Result<Vec<NameSuggestionDto>, DbErr>;
+
     /// Finds idol list by condition with search support
     async fn find_list(
         &self,
@@ -51,11 +64,12 @@ pub trait IdolRepository: Send + Sync {
     /// Deletes an idol by their unique identifier within an active transaction.
     async fn delete(&self, txn: &DatabaseTransaction, id: i64) -> Result<bool, DbErr>;
 
-    /// Gets record counts grouped by idols.
+    /// Gets one page of record counts grouped by idols, filtered by `query`.
     async fn get_idol_record_counts(
         &self,
         db: &DatabaseConnection,
-    ) -> Result<Vec<EntityCountDto>, DbErr>;
+        query: &RecordCountQuery,
+    ) -> Result<PaginatedResponse<EntityCountDto>, DbErr>;
 }
 
 #[async_trait]
@@ -86,6 +100,9 @@ pub trait IdolAffinityRepository: Send + Sync {
     /// The score is a relative ordering key and is NOT bounded to `[0, 1]`; an
     /// idol with `total = 0` scores `0` and sorts last. Hyper-parameters live
     /// as constants in the repository implementation.
+    ///
+    /// A non-empty `pagination.ordering` (see `ENTITY_ORDERING_FIELDS`)
+    /// replaces the score as the sort key; filters and paging are unchanged.
     async fn find_list_paginated_by_affinity(
         &self,
         db: &DatabaseConnection,
@@ -94,3 +111,22 @@ pub trait IdolAffinityRepository: Send + Sync {
         user_id: &str,
     ) -> Result<PaginatedResponse<Idol>, DbErr>;
 }
+
+#[async_trait]
+/// Repository trait for idol profiles and aliases, kept apart from
+/// [`IdolRepository`] for the same reason as [`IdolAffinityRepository`]: the
+/// other named entities have neither.
+pub trait IdolProfileRepository: Send + Sync {
+    /// Aliases of idol `id`, sorted.
+    async fn find_aliases(&self, db: &DatabaseConnection, id: i64) -> Result<Vec<String>, DbErr>;
+
+    /// Replaces the profile and the aliases of idol `id` with `profile`,
+    /// whose aliases are already trimmed and unique. Returns the updated
+    /// idol, or `None` when it does not exist.
+    async fn replace_profile(
+        &self,
+        txn: &DatabaseTransaction,
+        id: i64,
+        profile: &IdolProfileDto,
+    ) -> Result<Option<Idol>, DbErr>;
+}
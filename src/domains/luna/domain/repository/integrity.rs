@@ -0,0 +1,50 @@
+use crate::domains::luna::dto::{
+    OrphanedJunctionRowsDto, PlaceholderReferencesDto, RecordIssueDto,
+};
+use async_trait::async_trait;
+use sea_orm::{DatabaseConnection, DbErr};
+
+#[async_trait]
+/// Reads backing the data-quality report. Each check returns its total and
+/// up to `sample` IDs, lowest first. Trashed records are left out.
+pub trait IntegrityRepository: Send + Sync {
+    /// Records whose director, studio, label or series is the placeholder
+    /// entity with ID 0.
+    async fn placeholder_references(
+        &self,
+        db: &DatabaseConnection,
+        sample: u64,
+    ) -> Result<PlaceholderReferencesDto, DbErr>;
+
+    /// Records with `has_links` set but no rows in `links`.
+    async fn has_links_without_links(
+        &self,
+        db: &DatabaseConnection,
+        sample: u64,
+    ) -> Result<RecordIssueDto, DbErr>;
+
+    /// Junction and link rows pointing at a record or entity that does not
+    /// exist, trashed records included.
+    async fn orphaned_rows(
+        &self,
+        db: &DatabaseConnection,
+        sample: u64,
+    ) -> Result<OrphanedJunctionRowsDto, DbErr>;
+
+    /// `(id, local_img_count)` of every record, by ID.
+    async fn image_counts(&self, db: &DatabaseConnection) -> Result<Vec<(String, i32)>, DbErr>;
+
+    /// IDs of every record, trashed ones included.
+    async fn record_ids(&self, db: &DatabaseConnection) -> Result<Vec<String>, DbErr>;
+
+    /// Names of every idol, which their media directories are named by.
+    async fn idol_names(&self, db: &DatabaseConnection) -> Result<Vec<String>, DbErr>;
+
+    /// Sets `local_img_count` of each `(id, count)` in one statement.
+    /// Returns the number of records whose count changed.
+    async fn update_image_counts(
+        &self,
+        db: &DatabaseConnection,
+        counts: Vec<(String, i32)>,
+    ) -> Result<u64, DbErr>;
+}
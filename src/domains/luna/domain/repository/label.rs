@@ -1,8 +1,8 @@
 use crate::domains::luna::{
     domain::Label,
     dto::{
-        CreateLabelDto, EntityCountDto, PaginatedResponse, PaginationQuery, SearchLabelDto,
-        UpdateLabelDto,
+        CreateLabelDto, EntityCountDto, NameSuggestionDto, PaginatedResponse, PaginationQuery,
+        RecordCountQuery, SearchLabelDto, UpdateLabelDto,
     },
 };
 use async_trait::async_trait;
@@ -17,6 +17,19 @@ pub trait LabelRepository: Send + Sync {
     /// Finds a label by their unique identifier.
     async fn find_by_id(&self, db: &DatabaseConnection, id: i64) -> Result<Option<Label>, DbErr>;
 
+    /// Finds the labels with the given identifiers, in the order of `ids`.
+    /// Unknown identifiers are skipped.
+    async fn find_by_ids(&self, db: &DatabaseConnection, ids: &[i64]) -> Result<Vec<Label>, DbErr>;
+
+    /// Up to `limit` labels whose name contains `term` (lowercased), those whose
+    /// name starts with it first, then the ones with the most records.
+    async fn autocomplete(
+        &self,
+        db: &DatabaseConnection,
+        term: &str,
+        limit: u64,
+    ) -> Result<Vec<NameSuggestionDto>, DbErr>;
+
     /// Finds label list by condition
     async fn find_list(
         &self,
@@ -50,11 +63,12 @@ pub trait LabelRepository: Send + Sync {
     /// Deletes a label by their unique identifier within an active transaction.
     async fn delete(&self, txn: &DatabaseTransaction, id: i64) -> Result<bool, DbErr>;
 
-    /// Gets record counts grouped by labels.
+    /// Gets one page of record counts grouped by labels, filtered by `query`.
     async fn get_label_record_counts(
         &self,
         db: &DatabaseConnection,
-    ) -> Result<Vec<EntityCountDto>, DbErr>;
+        query: &RecordCountQuery,
+    ) -> Result<PaginatedResponse<EntityCountDto>, DbErr>;
 }
 
 #[async_trait]
@@ -86,6 +100,9 @@ pub trait LabelAffinityRepository: Send + Sync {
     /// The score is a relative ordering key and is NOT bounded to `[0, 1]`; a
     /// label with `total = 0` scores `0` and sorts last. Hyper-parameters live
     /// as constants in the repository implementation.
+    ///
+    /// A non-empty `pagination.ordering` (see `ENTITY_ORDERING_FIELDS`)
+    /// replaces the score as the sort key; filters and paging are unchanged.
     async fn find_list_paginated_by_affinity(
         &self,
         db: &DatabaseConnection,
@@ -0,0 +1,79 @@
+use crate::domains::luna::{
+    domain::Link,
+    dto::{LinkSearchQuery, PatchLinkDto},
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sea_orm::{DatabaseConnection, DatabaseTransaction, DbErr};
+
+#[async_trait]
+/// Persistence of record links and their health. Links of trashed records
+/// are left out of the listings.
+pub trait LinkRepository: Send + Sync {
+    /// Link `id`, whatever the state of its record.
+    async fn find_by_id(&self, db: &DatabaseConnection, id: i64) -> Result<Option<Link>, DbErr>;
+
+    /// Links of `record_id`, by ID.
+    async fn find_by_record(
+        &self,
+        db: &DatabaseConnection,
+        record_id: &str,
+    ) -> Result<Vec<Link>, DbErr>;
+
+    /// A page of the links the last check found dead, most recently checked
+    /// first, and their total.
+    async fn find_dead_paginated(
+        &self,
+        db: &DatabaseConnection,
+        limit: u64,
+        offset: u64,
+    ) -> Result<(Vec<Link>, u64), DbErr>;
+
+    /// A page of the links of live records at or below `max_permission`
+    /// matching `query`, newest first, and their total.
+    async fn search(
+        &self,
+        db: &DatabaseConnection,
+        query: &LinkSearchQuery,
+        max_permission: i32,
+        limit: u64,
+        offset: u64,
+    ) -> Result<(Vec<Link>, u64), DbErr>;
+
+    /// Up to `limit` HTTP(S) links, never-checked ones first and then the
+    /// least recently checked.
+    async fn find_least_recently_checked(
+        &self,
+        db: &DatabaseConnection,
+        limit: u64,
+    ) -> Result<Vec<Link>, DbErr>;
+
+    /// Stores the outcome of checking link `id` at `checked_at`.
+    async fn record_check(
+        &self,
+        db: &DatabaseConnection,
+        id: i64,
+        status: Option<i32>,
+        dead: bool,
+        checked_at: DateTime<Utc>,
+    ) -> Result<(), DbErr>;
+
+    /// Writes the fields present in `patch` to link `id` of `record_id`.
+    /// Returns `None` when the record has no such link.
+    async fn update(
+        &self,
+        txn: &DatabaseTransaction,
+        record_id: &str,
+        id: i64,
+        patch: PatchLinkDto,
+    ) -> Result<Option<Link>, DbErr>;
+
+    /// Deletes link `id` of `record_id` and clears the record's `has_links`
+    /// when it was the last one. Returns whether the link existed.
+    async fn delete(
+        &self,
+        txn: &DatabaseTransaction,
+        record_id: &str,
+        id: i64,
+    ) -> Result<bool, DbErr>;
+}
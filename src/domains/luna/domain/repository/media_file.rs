@@ -0,0 +1,65 @@
+use crate::domains::luna::dto::DuplicateImageGroupDto;
+use async_trait::async_trait;
+use sea_orm::{DatabaseConnection, DbErr};
+
+/// A stored media file and the SHA-256 of its content.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredMediaFile {
+    /// Storage key of the file.
+    pub key: String,
+    /// Media directory the file is in, `record` or `idol`.
+    pub owner_type: String,
+    /// Record ID or idol name the file belongs to.
+    pub owner_id: String,
+    pub filename: String,
+    /// Lowercase hex SHA-256 of the content.
+    pub sha256: String,
+    pub size: u64,
+}
+
+#[async_trait]
+/// Content hashes of stored media files.
+pub trait MediaFileRepository: Send + Sync {
+    /// Stored files whose content hashes to `sha256`, by key.
+    async fn find_by_sha256(
+        &self,
+        db: &DatabaseConnection,
+        sha256: &str,
+    ) -> Result<Vec<StoredMediaFile>, DbErr>;
+
+    /// Records `file`, replacing an earlier row for the same key.
+    async fn upsert(&self, db: &DatabaseConnection, file: &StoredMediaFile) -> Result<(), DbErr>;
+
+    /// Keys of every stored file, by key.
+    async fn keys(&self, db: &DatabaseConnection) -> Result<Vec<String>, DbErr>;
+
+    /// Forgets the files stored at `keys`.
+    async fn delete_keys(&self, db: &DatabaseConnection, keys: &[String]) -> Result<u64, DbErr>;
+
+    /// Points the row of the file stored at `key` at the file `filename` of
+    /// `owner_id` stored at `new_key`, where it was moved.
+    async fn rename_key(
+        &self,
+        db: &DatabaseConnection,
+        key: &str,
+        new_key: &str,
+        owner_id: &str,
+        filename: &str,
+    ) -> Result<(), DbErr>;
+
+    /// Forgets the files of the `owner_type` owners `owner_ids`, whose
+    /// media directories were removed.
+    async fn delete_by_owners(
+        &self,
+        db: &DatabaseConnection,
+        owner_type: &str,
+        owner_ids: &[String],
+    ) -> Result<u64, DbErr>;
+
+    /// Up to `limit` groups of files sharing a hash, most copies first.
+    async fn duplicate_groups(
+        &self,
+        db: &DatabaseConnection,
+        limit: u64,
+    ) -> Result<Vec<DuplicateImageGroupDto>, DbErr>;
+}
@@ -0,0 +1,20 @@
+use async_trait::async_trait;
+use sea_orm::{DatabaseTransaction, DbErr};
+
+#[async_trait]
+/// Repository trait for folding a duplicate named entity into another.
+///
+/// Implemented for every named entity repo by `impl_entity_merge_repo!`. Kept
+/// separate from the per-entity repository traits so one service helper can
+/// drive the merge for all six entity types.
+pub trait NamedEntityMergeRepository: Send + Sync {
+    /// Re-points every reference to `source_id` at `target_id`, then deletes
+    /// `source_id`. Returns the number of records re-pointed and the surviving
+    /// entity's name, or `None` when either entity does not exist.
+    async fn merge(
+        &self,
+        txn: &DatabaseTransaction,
+        target_id: i64,
+        source_id: i64,
+    ) -> Result<Option<(u64, String)>, DbErr>;
+}
@@ -1,12 +1,16 @@
+use crate::common::config::SimilarityWeights;
 use crate::domains::luna::{
-    domain::Record,
+    domain::{IdolParticipation, Link, Record, RecordGenre},
     dto::{
-        CreateLinkDto, CreateRecordDto, PaginatedResponse, PaginationQuery, SearchRecordDto,
-        UpdateRecordDto, UserFilter,
+        CreateLinkDto, CreateRecordDto, FetchedMetadata, LinkUpdateResultDto, PaginatedResponse,
+        PaginationQuery, PatchRecordDto, RecordRelations, ResolvedIdolDto, SearchRecordDto,
+        SeriesNeighborDto, UpdateRecordDto, UserFilter,
     },
 };
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use sea_orm::{DatabaseConnection, DatabaseTransaction, DbErr};
+use std::collections::HashMap;
 
 /// Tracks nested named entities created during a record creation.
 #[derive(Debug, Default)]
@@ -17,6 +21,40 @@ pub struct CreatedNestedEntities {
     pub series: Option<(i64, String)>,
     pub genres: Vec<(i64, String)>,
     pub idols: Vec<(i64, String)>,
+    /// Idol names that were aliases and the idols they resolved to.
+    pub resolved_idols: Vec<ResolvedIdolDto>,
+}
+
+/// Row counts removed by a bulk record delete.
+#[derive(Debug, Default)]
+pub struct DeletedRecordRows {
+    /// IDs of the records that existed and were deleted.
+    pub record_ids: Vec<String>,
+    pub record_genres: u64,
+    pub idol_participations: u64,
+    pub links: u64,
+}
+
+/// Genres, idols and links of one record, as loaded by
+/// [`RecordRepository::find_relations`].
+#[derive(Debug, Clone, Default)]
+pub struct RecordRelationRows {
+    pub genres: Vec<RecordGenre>,
+    pub idols: Vec<IdolParticipation>,
+    pub links: Vec<Link>,
+}
+
+/// One page of record changes after a sync position.
+#[derive(Debug, Default)]
+pub struct RecordChanges {
+    /// Records created, updated or restored, in change order.
+    pub records: Vec<Record>,
+    /// IDs of records deleted, trashed or above the caller's clearance.
+    pub deleted: Vec<String>,
+    /// Sync position of the last change in the page.
+    pub next_since: i64,
+    /// Whether further changes follow the page.
+    pub has_more: bool,
 }
 
 #[async_trait]
@@ -40,41 +78,213 @@ pub trait RecordRepository: Send + Sync {
     ) -> Result<Vec<Record>, DbErr>;
 
     /// Finds record list with database-level pagination using LIMIT/OFFSET.
+    ///
+    /// All `search_dto` filters, including the genre/idol junction filters,
+    /// are applied in SQL so both the page and the total count are computed
+    /// by the database. When `pagination.cursor` is set, paging switches to
+    /// keyset mode on `(date DESC, id ASC)` and the response carries
+    /// `next_cursor` instead of offset-based `previous` links. Only the
+    /// junction relations selected in `relations` are loaded.
     async fn find_list_paginated(
         &self,
         db: &DatabaseConnection,
         search_dto: SearchRecordDto,
         pagination: PaginationQuery,
         user_filter: Option<UserFilter>,
+        relations: RecordRelations,
     ) -> Result<PaginatedResponse<Record>, DbErr>;
 
-    /// Creates a new record within an active transaction.
+    /// Creates a new record within an active transaction, with `actor` as
+    /// its creator and last modifier.
     /// Returns the record ID and info about any nested named entities created.
     async fn create(
         &self,
         txn: &DatabaseTransaction,
         record: CreateRecordDto,
+        actor: &str,
     ) -> Result<(String, CreatedNestedEntities), DbErr>;
 
-    /// Updates an existing record.
+    /// Updates an existing record, recording `actor` as its last modifier.
     async fn update(
         &self,
         txn: &DatabaseTransaction,
         id: String,
         record: UpdateRecordDto,
+        actor: &str,
     ) -> Result<Option<Record>, DbErr>;
 
-    /// Deletes a record by their unique identifier within an active transaction.
+    /// Creates the record, or replaces its scalar fields, genre set, idol set
+    /// and links. Junction and link rows are diffed against the stored rows,
+    /// so unchanged rows are kept. Genre and idol rows marked manual are
+    /// kept as well, unless `override_manual` is set. Returns whether the
+    /// record was created and the nested entities resolved along the way,
+    /// or `None` when the record is in the trash. `actor` becomes the last
+    /// modifier, and the creator of a newly created record.
+    async fn replace(
+        &self,
+        txn: &DatabaseTransaction,
+        record: CreateRecordDto,
+        override_manual: bool,
+        actor: &str,
+    ) -> Result<Option<(bool, CreatedNestedEntities)>, DbErr>;
+
+    /// Updates only the fields present in `patch`, leaving the rest as stored,
+    /// and records `actor` as the last modifier.
+    async fn patch(
+        &self,
+        txn: &DatabaseTransaction,
+        id: String,
+        patch: PatchRecordDto,
+        actor: &str,
+    ) -> Result<Option<Record>, DbErr>;
+
+    /// Merges fetched `metadata` into a live record, recording `actor` as the
+    /// last modifier when anything changed. The title and duration are only
+    /// filled in while blank and the date is kept, unless `overwrite` is set.
+    /// Genres and idols named by the metadata replace the automatic
+    /// associations; those marked manual are never removed. Returns the names
+    /// of the changed fields and the nested entities created, or `None` when
+    /// the record is missing.
+    async fn merge_metadata(
+        &self,
+        txn: &DatabaseTransaction,
+        id: String,
+        metadata: &FetchedMetadata,
+        overwrite: bool,
+        actor: &str,
+    ) -> Result<Option<(Vec<String>, CreatedNestedEntities)>, DbErr>;
+
+    /// Increments the version of a live record, provided it still equals
+    /// `expected` (any version when `None`). The row stays locked until
+    /// the transaction ends, so concurrent editors are serialized. Returns the
+    /// new version, or `None` when the record is missing, trashed or has moved
+    /// on.
+    async fn bump_version(
+        &self,
+        txn: &DatabaseTransaction,
+        id: String,
+        expected: Option<i32>,
+    ) -> Result<Option<i32>, DbErr>;
+
+    /// Returns the current version of a record, trashed or not.
+    async fn find_version(
+        &self,
+        txn: &DatabaseTransaction,
+        id: String,
+    ) -> Result<Option<i32>, DbErr>;
+
+    /// Moves a live record to the trash by setting `deleted_at`.
+    /// Returns `false` when no live record has this ID.
+    async fn soft_delete(&self, txn: &DatabaseTransaction, id: String) -> Result<bool, DbErr>;
+
+    /// Takes a record out of the trash. Returns `false` when no trashed
+    /// record has this ID.
+    async fn restore(&self, txn: &DatabaseTransaction, id: String) -> Result<bool, DbErr>;
+
+    /// Permanently deletes a trashed record. Returns `false` when no trashed
+    /// record has this ID.
+    async fn purge(&self, txn: &DatabaseTransaction, id: String) -> Result<bool, DbErr>;
+
+    /// Lists trashed records visible at `max_permission`, most recently
+    /// deleted first.
+    async fn find_trash_paginated(
+        &self,
+        db: &DatabaseConnection,
+        pagination: PaginationQuery,
+        max_permission: i32,
+    ) -> Result<PaginatedResponse<Record>, DbErr>;
+
+    /// Permanently deletes a record, trashed or not, within an active transaction.
     async fn delete(&self, txn: &DatabaseTransaction, id: String) -> Result<bool, DbErr>;
 
-    /// Update record links only - add new links that don't already exist
-    /// Returns the number of new links added
+    /// Moves the given live records to the trash within an active
+    /// transaction. Returns the IDs that were live; the others are ignored.
+    async fn soft_delete_many(
+        &self,
+        txn: &DatabaseTransaction,
+        ids: Vec<String>,
+    ) -> Result<Vec<String>, DbErr>;
+
+    /// Permanently deletes the given records, trashed or not, and their
+    /// junction and link rows within an active transaction. IDs that do not
+    /// exist are ignored.
+    async fn delete_many(
+        &self,
+        txn: &DatabaseTransaction,
+        ids: Vec<String>,
+    ) -> Result<DeletedRecordRows, DbErr>;
+
+    /// Folds live record `source_id` into live record `target_id` within an
+    /// active transaction, then deletes the source. The target gains the
+    /// source's genres, idols, tags, links (by URL), comments and crawl
+    /// results, plus the favorites, ratings and interactions of users who had
+    /// none on the target. When the source was updated later, its scalar
+    /// fields replace the target's; `local_img_count` becomes the sum of both.
+    /// `actor` becomes the last modifier. Returns the merged record, or `None`
+    /// when either record is not live.
+    async fn merge_into(
+        &self,
+        txn: &DatabaseTransaction,
+        target_id: String,
+        source_id: String,
+        actor: &str,
+    ) -> Result<Option<Record>, DbErr>;
+
+    /// Adds `added` to the `local_img_count` of a live record in one
+    /// statement, so concurrent uploads do not lose counts. Returns the new
+    /// count, or `None` when no live record has this ID.
+    async fn add_local_images(
+        &self,
+        txn: &DatabaseTransaction,
+        id: String,
+        added: i32,
+    ) -> Result<Option<i32>, DbErr>;
+
+    /// Sets the `trailer` of a live record. Returns `false` when no live
+    /// record has this ID.
+    async fn set_trailer(
+        &self,
+        txn: &DatabaseTransaction,
+        id: String,
+        trailer: Option<String>,
+    ) -> Result<bool, DbErr>;
+
+    /// Sets the `cover_index` of a live record. Returns `false` when no live
+    /// record has this ID.
+    async fn set_cover_index(
+        &self,
+        txn: &DatabaseTransaction,
+        id: String,
+        cover_index: Option<i32>,
+    ) -> Result<bool, DbErr>;
+
+    /// Sets the `order_in_series` of a live record. Returns `false` when no
+    /// live record has this ID.
+    async fn set_order_in_series(
+        &self,
+        txn: &DatabaseTransaction,
+        id: String,
+        order_in_series: Option<i32>,
+    ) -> Result<bool, DbErr>;
+
+    /// Update record links only: add links whose normalized URL (ignoring
+    /// case) and name and size are both new, and fill in placeholder fields
+    /// of known URLs. Reports what was inserted, updated and skipped.
     async fn update_record_links(
         &self,
         txn: &DatabaseTransaction,
         record_id: String,
         new_links: Vec<CreateLinkDto>,
-    ) -> Result<i32, DbErr>;
+    ) -> Result<LinkUpdateResultDto, DbErr>;
+
+    /// Makes the links of `record_id` exactly `links`, keyed by normalized
+    /// URL, and updates `has_links`. Blank and repeated links are skipped.
+    async fn replace_record_links(
+        &self,
+        txn: &DatabaseTransaction,
+        record_id: String,
+        links: Vec<CreateLinkDto>,
+    ) -> Result<LinkUpdateResultDto, DbErr>;
 
     /// Retrieves all record slim data from the database, optionally filtered by user interaction.
     async fn find_all_slim(
@@ -90,6 +300,22 @@ pub trait RecordRepository: Send + Sync {
         user_filter: Option<UserFilter>,
     ) -> Result<Vec<String>, DbErr>;
 
+    /// Returns the subset of `ids` that exist as live records visible at
+    /// `max_permission`, without loading relations.
+    async fn find_existing_ids(
+        &self,
+        db: &DatabaseConnection,
+        ids: Vec<String>,
+        max_permission: i32,
+    ) -> Result<Vec<String>, DbErr>;
+
+    /// Returns the `permission` level of a live record, or `None` if it does not exist.
+    async fn find_permission(
+        &self,
+        db: &DatabaseConnection,
+        id: &str,
+    ) -> Result<Option<i32>, DbErr>;
+
     /// Retrieves record IDs with database-level pagination and optional user filtering.
     async fn find_ids_paginated(
         &self,
@@ -98,10 +324,12 @@ pub trait RecordRepository: Send + Sync {
         user_filter: Option<UserFilter>,
     ) -> Result<PaginatedResponse<String>, DbErr>;
 
-    /// Retrieves slim records with database-level pagination and optional user filtering.
+    /// Retrieves slim records matching `search_dto` with database-level
+    /// pagination and optional user filtering.
     async fn find_all_slim_paginated(
         &self,
         db: &DatabaseConnection,
+        search_dto: SearchRecordDto,
         pagination: PaginationQuery,
         user_filter: Option<UserFilter>,
     ) -> Result<PaginatedResponse<Record>, DbErr>;
@@ -113,15 +341,6 @@ pub trait RecordRepository: Send + Sync {
         genre_id: i64,
     ) -> Result<Vec<Record>, DbErr>;
 
-    /// Finds records filtered by genre with database-level pagination.
-    async fn find_by_genre_id_paginated(
-        &self,
-        db: &DatabaseConnection,
-        genre_id: i64,
-        pagination: PaginationQuery,
-        user_filter: Option<UserFilter>,
-    ) -> Result<PaginatedResponse<Record>, DbErr>;
-
     /// Finds records filtered by idol via JOIN on `idol_participation` table.
     async fn find_by_idol_id(
         &self,
@@ -129,12 +348,76 @@ pub trait RecordRepository: Send + Sync {
         idol_id: i64,
     ) -> Result<Vec<Record>, DbErr>;
 
-    /// Finds records filtered by idol with database-level pagination.
-    async fn find_by_idol_id_paginated(
+    /// Returns up to `limit` changes with a sync position after `since`,
+    /// merging record writes with the deletion journal. Only the latest
+    /// change of each record is kept; records above `max_permission` are
+    /// reported as deleted.
+    async fn find_changes_since(
         &self,
         db: &DatabaseConnection,
-        idol_id: i64,
+        since: i64,
+        limit: u64,
+        max_permission: i32,
+    ) -> Result<RecordChanges, DbErr>;
+
+    /// Returns the `limit` most recently created or updated live records
+    /// visible at `max_permission`, newest first.
+    async fn find_recent(
+        &self,
+        db: &DatabaseConnection,
+        limit: u64,
+        max_permission: i32,
+    ) -> Result<Vec<Record>, DbErr>;
+
+    /// Returns up to `limit` slim live records visible at `max_permission`
+    /// that share metadata with record `id`, with their score under
+    /// `weights`, highest first. Records scoring 0 are left out.
+    async fn find_similar(
+        &self,
+        db: &DatabaseConnection,
+        id: &str,
+        weights: SimilarityWeights,
+        limit: u64,
+        max_permission: i32,
+    ) -> Result<Vec<(Record, f64)>, DbErr>;
+
+    /// Returns the live records visible at `max_permission` just before and
+    /// just after record `id` in its series, by `order_in_series`, then date.
+    /// Both are `None` for records outside any series.
+    async fn find_series_neighbors(
+        &self,
+        db: &DatabaseConnection,
+        id: &str,
+        max_permission: i32,
+    ) -> Result<(Option<SeriesNeighborDto>, Option<SeriesNeighborDto>), DbErr>;
+
+    /// Returns up to `count` live records visible at `max_permission`, drawn
+    /// at random from those tagged with `genre_id` and featuring `idol_id`.
+    async fn find_random(
+        &self,
+        db: &DatabaseConnection,
+        count: u64,
+        genre_id: Option<i64>,
+        idol_id: Option<i64>,
+        max_permission: i32,
+    ) -> Result<Vec<Record>, DbErr>;
+
+    /// Lists the live records visible at `max_permission` that user
+    /// `user_id` has seen, with when they saw each, most recently seen first.
+    async fn find_seen_paginated(
+        &self,
+        db: &DatabaseConnection,
+        user_id: &str,
         pagination: PaginationQuery,
-        user_filter: Option<UserFilter>,
-    ) -> Result<PaginatedResponse<Record>, DbErr>;
+        max_permission: i32,
+    ) -> Result<PaginatedResponse<(Record, DateTime<Utc>)>, DbErr>;
+
+    /// Batch-loads the junction relations selected in `relations` for
+    /// `record_ids`, keyed by record ID. Records without rows are absent.
+    async fn find_relations(
+        &self,
+        db: &DatabaseConnection,
+        record_ids: Vec<String>,
+        relations: RecordRelations,
+    ) -> Result<HashMap<String, RecordRelationRows>, DbErr>;
 }
@@ -0,0 +1,37 @@
+use crate::domains::luna::dto::RecordRevisionDto;
+use async_trait::async_trait;
+use sea_orm::{DatabaseConnection, DatabaseTransaction, DbErr};
+
+#[async_trait]
+/// Persistence of record revisions: snapshots of a record taken before each
+/// edit, with the user making the edit.
+pub trait RevisionRepository: Send + Sync {
+    /// Locks live record `record_id` for the rest of `txn` and stores its
+    /// current state as a revision by `actor`, who is about to edit it.
+    /// Returns `false` when there is no such record.
+    async fn snapshot(
+        &self,
+        txn: &DatabaseTransaction,
+        record_id: &str,
+        actor: &str,
+    ) -> Result<bool, DbErr>;
+
+    /// A page of the revisions of `record_id`, newest first, and the total
+    /// number of revisions of it.
+    async fn find_by_record_paginated(
+        &self,
+        db: &DatabaseConnection,
+        record_id: &str,
+        limit: u64,
+        offset: u64,
+    ) -> Result<(Vec<RecordRevisionDto>, u64), DbErr>;
+
+    /// Revision `id` of `record_id`, or `None` if the record has no such
+    /// revision.
+    async fn find_by_id(
+        &self,
+        db: &DatabaseConnection,
+        record_id: &str,
+        id: i64,
+    ) -> Result<Option<RecordRevisionDto>, DbErr>;
+}
@@ -0,0 +1,51 @@
+use crate::domains::luna::dto::{CreateSavedSearchDto, SavedSearchDto};
+use async_trait::async_trait;
+use sea_orm::{DatabaseConnection, DbErr};
+
+#[async_trait]
+/// Persistence of saved record searches.
+pub trait SavedSearchRepository: Send + Sync {
+    /// A page of the searches saved by `user_id`, by name, and the total.
+    async fn find_by_owner_paginated(
+        &self,
+        db: &DatabaseConnection,
+        user_id: &str,
+        limit: u64,
+        offset: u64,
+    ) -> Result<(Vec<SavedSearchDto>, u64), DbErr>;
+
+    /// A page of the public searches of every user, newest first, and the
+    /// total.
+    async fn find_public_paginated(
+        &self,
+        db: &DatabaseConnection,
+        limit: u64,
+        offset: u64,
+    ) -> Result<(Vec<SavedSearchDto>, u64), DbErr>;
+
+    /// Saved search `id`, or `None` if it does not exist.
+    async fn find_by_id(
+        &self,
+        db: &DatabaseConnection,
+        id: i64,
+    ) -> Result<Option<SavedSearchDto>, DbErr>;
+
+    /// Stores a search for `user_id` and returns its ID.
+    async fn create(
+        &self,
+        db: &DatabaseConnection,
+        user_id: &str,
+        dto: CreateSavedSearchDto,
+    ) -> Result<i64, DbErr>;
+
+    /// Replaces the definition of search `id` and bumps `updated_at`.
+    async fn update(
+        &self,
+        db: &DatabaseConnection,
+        id: i64,
+        dto: CreateSavedSearchDto,
+    ) -> Result<(), DbErr>;
+
+    /// Deletes search `id`.
+    async fn delete(&self, db: &DatabaseConnection, id: i64) -> Result<(), DbErr>;
+}
@@ -1,8 +1,8 @@
 use crate::domains::luna::{
     domain::Series,
     dto::{
-        CreateSeriesDto, EntityCountDto, PaginatedResponse, PaginationQuery, SearchSeriesDto,
-        UpdateSeriesDto,
+        CreateSeriesDto, EntityCountDto, NameSuggestionDto, PaginatedResponse, PaginationQuery,
+        RecordCountQuery, SearchSeriesDto, UpdateSeriesDto,
     },
 };
 use async_trait::async_trait;
@@ -17,6 +17,20 @@ pub trait SeriesRepository: Send + Sync {
     /// Finds a series by their unique identifier.
     async fn find_by_id(&self, db: &DatabaseConnection, id: i64) -> Result<Option<Series>, DbErr>;
 
+    /// Finds the series with the given identifiers, in the order of `ids`.
+    /// Unknown identifiers are skipped.
+    async fn find_by_ids(&self, db: &DatabaseConnection, ids: &[i64])
+        -> Result<Vec<Series>, DbErr>;
+
+    /// Up to `limit` series whose name contains `term` (lowercased), those whose
+    /// name starts with it first, then the ones with the most records.
+    async fn autocomplete(
+        &self,
+        db: &DatabaseConnection,
+        term: &str,
+        limit: u64,
+    ) -> Result<Vec<NameSuggestionDto>, DbErr>;
+
     /// Finds series list by condition
     async fn find_list(
         &self,
@@ -50,11 +64,12 @@ pub trait SeriesRepository: Send + Sync {
     /// Deletes a series by their unique identifier within an active transaction.
     async fn delete(&self, txn: &DatabaseTransaction, id: i64) -> Result<bool, DbErr>;
 
-    /// Gets record counts grouped by series.
+    /// Gets one page of record counts grouped by series, filtered by `query`.
     async fn get_series_record_counts(
         &self,
         db: &DatabaseConnection,
-    ) -> Result<Vec<EntityCountDto>, DbErr>;
+        query: &RecordCountQuery,
+    ) -> Result<PaginatedResponse<EntityCountDto>, DbErr>;
 }
 
 #[async_trait]
@@ -86,6 +101,9 @@ pub trait SeriesAffinityRepository: Send + Sync {
     /// The score is a relative ordering key and is NOT bounded to `[0, 1]`; a
     /// series with `total = 0` scores `0` and sorts last. Hyper-parameters live
     /// as constants in the repository implementation.
+    ///
+    /// A non-empty `pagination.ordering` (see `ENTITY_ORDERING_FIELDS`)
+    /// replaces the score as the sort key; filters and paging are unchanged.
     async fn find_list_paginated_by_affinity(
         &self,
         db: &DatabaseConnection,
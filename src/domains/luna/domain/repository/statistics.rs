@@ -0,0 +1,76 @@
+use crate::domains::luna::dto::{CatalogTotalsDto, DateGranularity, EntityCountDto, ExportEntity};
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use sea_orm::{prelude::Decimal, DatabaseConnection, DbErr};
+
+#[async_trait]
+/// Aggregate reads backing the statistics endpoints. Trashed records and the
+/// placeholder entities (ID 0) are left out.
+pub trait StatisticsRepository: Send + Sync {
+    /// Row counts across the catalog.
+    async fn catalog_totals(&self, db: &DatabaseConnection) -> Result<CatalogTotalsDto, DbErr>;
+
+    /// Records created per month since `since`, as `(first day of month,
+    /// count)` pairs in month order. Months without records are absent.
+    async fn records_added_per_month(
+        &self,
+        db: &DatabaseConnection,
+        since: NaiveDate,
+    ) -> Result<Vec<(NaiveDate, i64)>, DbErr>;
+
+    /// Number and combined size of the links of live records. Placeholder
+    /// sizes (negative) are not summed.
+    async fn link_totals(&self, db: &DatabaseConnection) -> Result<(i64, Decimal), DbErr>;
+
+    /// Links of live records added per month since `since`, as `(first day
+    /// of month, count, size)` in month order. Months without links are
+    /// absent.
+    async fn links_added_per_month(
+        &self,
+        db: &DatabaseConnection,
+        since: NaiveDate,
+    ) -> Result<Vec<(NaiveDate, i64, Decimal)>, DbErr>;
+
+    /// Live records per release-date bucket between `from` and `to`
+    /// (inclusive, unbounded when `None`), as `(first day of bucket, count)`
+    /// pairs in date order. Empty buckets are absent.
+    async fn records_by_release_date(
+        &self,
+        db: &DatabaseConnection,
+        granularity: DateGranularity,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> Result<Vec<(NaiveDate, i64)>, DbErr>;
+
+    /// The `limit` `entity` rows with the most records, most first.
+    async fn top_entities(
+        &self,
+        db: &DatabaseConnection,
+        entity: ExportEntity,
+        limit: u64,
+    ) -> Result<Vec<EntityCountDto>, DbErr>;
+
+    /// Whether the `entity` row `id` exists.
+    async fn entity_exists(
+        &self,
+        db: &DatabaseConnection,
+        entity: ExportEntity,
+        id: i64,
+    ) -> Result<bool, DbErr>;
+
+    /// The `limit` `entity` rows sharing the most live records visible at
+    /// `max_permission` with row `id`, most first, counted by joining the
+    /// entity's junction table with itself. Only idols and genres have a
+    /// junction table.
+    async fn co_occurring(
+        &self,
+        db: &DatabaseConnection,
+        entity: ExportEntity,
+        id: i64,
+        limit: u64,
+        max_permission: i32,
+    ) -> Result<Vec<EntityCountDto>, DbErr>;
+
+    /// Mean duration of records with a non-zero duration.
+    async fn average_duration(&self, db: &DatabaseConnection) -> Result<Option<f64>, DbErr>;
+}
@@ -1,8 +1,8 @@
 use crate::domains::luna::{
-    domain::Studio,
+    domain::{Label, Studio},
     dto::{
-        CreateStudioDto, EntityCountDto, PaginatedResponse, PaginationQuery, SearchStudioDto,
-        UpdateStudioDto,
+        CreateStudioDto, EntityCountDto, NameSuggestionDto, PaginatedResponse, PaginationQuery,
+        RecordCountQuery, SearchStudioDto, StudioRollupDto, UpdateStudioDto,
     },
 };
 use async_trait::async_trait;
@@ -17,6 +17,20 @@ pub trait StudioRepository: Send + Sync {
     /// Finds a studio by their unique identifier.
     async fn find_by_id(&self, db: &DatabaseConnection, id: i64) -> Result<Option<Studio>, DbErr>;
 
+    /// Finds the studios with the given identifiers, in the order of `ids`.
+    /// Unknown identifiers are skipped.
+    async fn find_by_ids(&self, db: &DatabaseConnection, ids: &[i64])
+        -> Result<Vec<Studio>, DbErr>;
+
+    /// Up to `limit` studios whose name contains `term` (lowercased), those whose
+    /// name starts with it first, then the ones with the most records.
+    async fn autocomplete(
+        &self,
+        db: &DatabaseConnection,
+        term: &str,
+        limit: u64,
+    ) -> Result<Vec<NameSuggestionDto>, DbErr>;
+
     /// Finds studio list by condition
     async fn find_list(
         &self,
@@ -50,11 +64,12 @@ pub trait StudioRepository: Send + Sync {
     /// Deletes a studio by their unique identifier within an active transaction.
     async fn delete(&self, txn: &DatabaseTransaction, id: i64) -> Result<bool, DbErr>;
 
-    /// Gets record counts grouped by studios.
+    /// Gets one page of record counts grouped by studios, filtered by `query`.
     async fn get_studio_record_counts(
         &self,
         db: &DatabaseConnection,
-    ) -> Result<Vec<EntityCountDto>, DbErr>;
+        query: &RecordCountQuery,
+    ) -> Result<PaginatedResponse<EntityCountDto>, DbErr>;
 }
 
 #[async_trait]
@@ -86,6 +101,9 @@ pub trait StudioAffinityRepository: Send + Sync {
     /// The score is a relative ordering key and is NOT bounded to `[0, 1]`; a
     /// studio with `total = 0` scores `0` and sorts last. Hyper-parameters live
     /// as constants in the repository implementation.
+    ///
+    /// A non-empty `pagination.ordering` (see `ENTITY_ORDERING_FIELDS`)
+    /// replaces the score as the sort key; filters and paging are unchanged.
     async fn find_list_paginated_by_affinity(
         &self,
         db: &DatabaseConnection,
@@ -94,3 +112,51 @@ pub trait StudioAffinityRepository: Send + Sync {
         user_id: &str,
     ) -> Result<PaginatedResponse<Studio>, DbErr>;
 }
+
+#[async_trait]
+/// Repository trait for the studio hierarchy: the parent of each studio and
+/// the studio of each label. Kept apart from [`StudioRepository`] for the
+/// same reason as [`StudioAffinityRepository`]: the other named entities
+/// have no hierarchy.
+pub trait StudioHierarchyRepository: Send + Sync {
+    /// IDs of studio `id` and its ancestors, nearest first; empty when the
+    /// studio does not exist.
+    ///
+    /// Takes a lock held until `txn` ends, so a cycle check made with it
+    /// cannot race another hierarchy change.
+    async fn find_lineage(&self, txn: &DatabaseTransaction, id: i64) -> Result<Vec<i64>, DbErr>;
+
+    /// Moves studio `id` under `parent_id`, or to the top level. Returns the
+    /// updated studio, or `None` when it does not exist.
+    async fn set_parent(
+        &self,
+        txn: &DatabaseTransaction,
+        id: i64,
+        parent_id: Option<i64>,
+    ) -> Result<Option<Studio>, DbErr>;
+
+    /// Attaches label `id` to `studio_id`, or detaches it. Returns the
+    /// updated label, or `None` when it does not exist.
+    async fn set_label_studio(
+        &self,
+        txn: &DatabaseTransaction,
+        id: i64,
+        studio_id: Option<i64>,
+    ) -> Result<Option<Label>, DbErr>;
+
+    /// Studios whose parent is studio `id` and the labels it publishes, both
+    /// by name.
+    async fn find_children(
+        &self,
+        db: &DatabaseConnection,
+        id: i64,
+    ) -> Result<(Vec<Studio>, Vec<Label>), DbErr>;
+
+    /// Live record counts of studio `id` and everything below it, or `None`
+    /// when the studio does not exist.
+    async fn rollup(
+        &self,
+        db: &DatabaseConnection,
+        id: i64,
+    ) -> Result<Option<StudioRollupDto>, DbErr>;
+}
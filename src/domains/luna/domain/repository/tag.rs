@@ -0,0 +1,81 @@
+use crate::domains::luna::dto::{CreateTagDto, SearchTagDto, TagCategoryDto, TagCountDto, TagDto};
+use async_trait::async_trait;
+use sea_orm::{DatabaseConnection, DbErr};
+
+#[async_trait]
+/// Persistence of user-defined tags, their categories and the tags attached
+/// to records. Tags are read together with their category.
+pub trait TagRepository: Send + Sync {
+    /// Every tag matching `search`, by category then name.
+    async fn find_all(
+        &self,
+        db: &DatabaseConnection,
+        search: SearchTagDto,
+    ) -> Result<Vec<TagDto>, DbErr>;
+
+    /// Tag `id`, or `None` if it does not exist.
+    async fn find_by_id(&self, db: &DatabaseConnection, id: i64) -> Result<Option<TagDto>, DbErr>;
+
+    /// Every tag category, by ID.
+    async fn find_categories(&self, db: &DatabaseConnection) -> Result<Vec<TagCategoryDto>, DbErr>;
+
+    /// Whether tag category `id` exists.
+    async fn category_exists(&self, db: &DatabaseConnection, id: i64) -> Result<bool, DbErr>;
+
+    /// The subset of `ids` naming existing tags.
+    async fn find_existing_ids(
+        &self,
+        db: &DatabaseConnection,
+        ids: &[i64],
+    ) -> Result<Vec<i64>, DbErr>;
+
+    /// Stores a new tag and returns its ID.
+    async fn create(&self, db: &DatabaseConnection, dto: CreateTagDto) -> Result<i64, DbErr>;
+
+    /// Replaces the name, description and category of tag `id`. Returns
+    /// `false` when it does not exist.
+    async fn update(
+        &self,
+        db: &DatabaseConnection,
+        id: i64,
+        dto: CreateTagDto,
+    ) -> Result<bool, DbErr>;
+
+    /// Deletes tag `id` and detaches it from every record. Returns `false`
+    /// when it does not exist.
+    async fn delete(&self, db: &DatabaseConnection, id: i64) -> Result<bool, DbErr>;
+
+    /// The tags attached to `record_id`, by category then name.
+    async fn find_by_record(
+        &self,
+        db: &DatabaseConnection,
+        record_id: &str,
+    ) -> Result<Vec<TagDto>, DbErr>;
+
+    /// Attaches `tag_ids` to `record_id`; tags already attached are kept.
+    async fn attach(
+        &self,
+        db: &DatabaseConnection,
+        record_id: &str,
+        tag_ids: &[i64],
+    ) -> Result<(), DbErr>;
+
+    /// Detaches tag `tag_id` from `record_id`. Returns `false` when it was
+    /// not attached.
+    async fn detach(
+        &self,
+        db: &DatabaseConnection,
+        record_id: &str,
+        tag_id: i64,
+    ) -> Result<bool, DbErr>;
+
+    /// Up to `limit` tags with the number of live records visible at
+    /// `max_permission` carrying each, most used first. Unused tags are
+    /// included with a count of 0.
+    async fn usage_counts(
+        &self,
+        db: &DatabaseConnection,
+        limit: u64,
+        max_permission: i32,
+    ) -> Result<Vec<TagCountDto>, DbErr>;
+}
@@ -0,0 +1,51 @@
+use crate::domains::luna::dto::{Language, TranslatedEntity, TranslatedNames, TranslationDto};
+use async_trait::async_trait;
+use sea_orm::{DatabaseConnection, DbErr};
+
+#[async_trait]
+/// Persistence of translated entity names.
+pub trait TranslationRepository: Send + Sync {
+    /// Names in `language` of the entities in `keys` that have one.
+    async fn find_names(
+        &self,
+        db: &DatabaseConnection,
+        language: Language,
+        keys: &[(TranslatedEntity, i64)],
+    ) -> Result<TranslatedNames, DbErr>;
+
+    /// Every translation of one entity, by language.
+    async fn find_by_entity(
+        &self,
+        db: &DatabaseConnection,
+        entity: TranslatedEntity,
+        id: i64,
+    ) -> Result<Vec<TranslationDto>, DbErr>;
+
+    /// Stores the name of one entity in `language`, replacing any earlier one.
+    async fn upsert(
+        &self,
+        db: &DatabaseConnection,
+        entity: TranslatedEntity,
+        id: i64,
+        language: Language,
+        name: &str,
+    ) -> Result<(), DbErr>;
+
+    /// Deletes the name of one entity in `language`. Returns whether there
+    /// was one.
+    async fn delete(
+        &self,
+        db: &DatabaseConnection,
+        entity: TranslatedEntity,
+        id: i64,
+        language: Language,
+    ) -> Result<bool, DbErr>;
+
+    /// Whether entity `id` of type `entity` exists.
+    async fn entity_exists(
+        &self,
+        db: &DatabaseConnection,
+        entity: TranslatedEntity,
+        id: i64,
+    ) -> Result<bool, DbErr>;
+}
@@ -2,18 +2,28 @@
 //! responsible for business logic operations.
 
 use crate::common::config::Config;
+use crate::domains::luna::infra::catalog_events::CatalogEvents;
 use async_trait::async_trait;
 use sea_orm::DatabaseConnection;
 use std::sync::Arc;
 
+pub(super) mod comment;
 pub(super) mod director;
+pub(super) mod duplicate;
+pub(super) mod export;
 pub(super) mod file;
 pub(super) mod genre;
 pub(super) mod idol;
+pub(super) mod integrity;
 pub(super) mod label;
+pub(super) mod link;
 pub(super) mod record;
+pub(super) mod saved_search;
 pub(super) mod series;
+pub(super) mod statistics;
 pub(super) mod studio;
+pub(super) mod tag;
+pub(super) mod translation;
 
 #[async_trait]
 /// Combined service trait that includes all luna domain services.
@@ -46,4 +56,34 @@ pub trait LunaServiceTrait: Send + Sync {
 
     /// Get file service
     fn file_service(&self) -> &dyn file::FileServiceTrait;
+
+    /// Get export service
+    fn export_service(&self) -> &dyn export::ExportServiceTrait;
+
+    /// Get statistics service
+    fn statistics_service(&self) -> &dyn statistics::StatisticsServiceTrait;
+
+    /// Get comment service
+    fn comment_service(&self) -> &dyn comment::CommentServiceTrait;
+
+    /// Get tag service
+    fn tag_service(&self) -> &dyn tag::TagServiceTrait;
+
+    /// Get saved search service
+    fn saved_search_service(&self) -> &dyn saved_search::SavedSearchServiceTrait;
+
+    /// Get duplicate report service
+    fn duplicate_service(&self) -> &dyn duplicate::DuplicateServiceTrait;
+
+    /// Get data-quality report service
+    fn integrity_service(&self) -> &dyn integrity::IntegrityServiceTrait;
+
+    /// Get link health service
+    fn link_service(&self) -> &dyn link::LinkServiceTrait;
+
+    /// Get entity name translation service
+    fn translation_service(&self) -> &dyn translation::TranslationServiceTrait;
+
+    /// Get the catalog change event channel
+    fn catalog_events(&self) -> &CatalogEvents;
 }
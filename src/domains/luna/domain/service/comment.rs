@@ -0,0 +1,40 @@
+use crate::{
+    common::error::AppError,
+    domains::luna::dto::{
+        CommentDto, CreateCommentDto, PaginatedResponse, PaginationQuery, UpdateCommentDto,
+    },
+};
+use async_trait::async_trait;
+
+#[async_trait]
+/// Service trait for the comments users attach to records. Callers check
+/// that the record exists and is visible before calling in.
+pub trait CommentServiceTrait: Send + Sync {
+    /// A page of the comments on `record_id`, oldest first.
+    async fn list_comments(
+        &self,
+        record_id: &str,
+        pagination: PaginationQuery,
+    ) -> Result<PaginatedResponse<CommentDto>, AppError>;
+
+    /// Adds a comment by `user_id` to `record_id`.
+    async fn create_comment(
+        &self,
+        record_id: &str,
+        user_id: &str,
+        dto: CreateCommentDto,
+    ) -> Result<CommentDto, AppError>;
+
+    /// Edits comment `id` on `record_id`; only its author may.
+    async fn update_comment(
+        &self,
+        record_id: &str,
+        id: i64,
+        user_id: &str,
+        dto: UpdateCommentDto,
+    ) -> Result<CommentDto, AppError>;
+
+    /// Deletes comment `id` on `record_id`; only its author may.
+    async fn delete_comment(&self, record_id: &str, id: i64, user_id: &str)
+        -> Result<(), AppError>;
+}
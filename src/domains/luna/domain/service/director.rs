@@ -1,8 +1,12 @@
 use crate::{
     common::error::AppError,
-    domains::luna::dto::{
-        CreateDirectorDto, DirectorDto, EntityCountDto, PaginatedResponse, PaginationQuery,
-        SearchDirectorDto, UpdateDirectorDto,
+    domains::luna::{
+        dto::{
+            AutocompleteQuery, CreateDirectorDto, DirectorDto, EntityCountDto, MergeEntityResponse,
+            NameSuggestionDto, PaginatedResponse, PaginationQuery, RecordCountQuery,
+            SearchDirectorDto, UpdateDirectorDto,
+        },
+        infra::{catalog_cache::CatalogCache, catalog_events::CatalogEvents},
     },
 };
 
@@ -14,13 +18,27 @@ use std::sync::Arc;
 /// Trait defining business operations for director management.
 pub trait DirectorServiceTrait: Send + Sync {
     /// Constructor for the service.
-    fn create_service(db: DatabaseConnection) -> Arc<dyn DirectorServiceTrait>
+    fn create_service(
+        db: DatabaseConnection,
+        events: Arc<CatalogEvents>,
+        cache: Arc<CatalogCache>,
+    ) -> Arc<dyn DirectorServiceTrait>
     where
         Self: Sized;
 
     /// Retrieves a director by their unique identifier.
     async fn get_director_by_id(&self, id: i64) -> Result<DirectorDto, AppError>;
 
+    /// Retrieves the directors with the given identifiers, in the order requested.
+    /// Unknown identifiers are skipped.
+    async fn get_directors_by_ids(&self, ids: &[i64]) -> Result<Vec<DirectorDto>, AppError>;
+
+    /// Suggests directors for the text typed so far, for typeahead inputs.
+    async fn autocomplete_directors(
+        &self,
+        query: AutocompleteQuery,
+    ) -> Result<Vec<NameSuggestionDto>, AppError>;
+
     /// Retrieves director list by condition
     async fn get_director_list(
         &self,
@@ -57,8 +75,22 @@ pub trait DirectorServiceTrait: Send + Sync {
     ) -> Result<DirectorDto, AppError>;
 
     /// Deletes a director by their unique identifier.
-    async fn delete_director(&self, id: i64) -> Result<String, AppError>;
+    ///
+    /// Fails with a conflict listing the blocking record IDs while records
+    /// still reference it, unless `reassign_to` names a director to re-point them
+    /// to first.
+    async fn delete_director(&self, id: i64, reassign_to: Option<i64>) -> Result<String, AppError>;
 
-    /// Gets record counts grouped by directors.
-    async fn get_director_record_counts(&self) -> Result<Vec<EntityCountDto>, AppError>;
+    /// Merges the duplicate director `source_id` into `id`, re-pointing its records.
+    async fn merge_director(
+        &self,
+        id: i64,
+        source_id: i64,
+    ) -> Result<MergeEntityResponse, AppError>;
+
+    /// Gets one page of record counts grouped by directors, filtered by `query`.
+    async fn get_director_record_counts(
+        &self,
+        query: RecordCountQuery,
+    ) -> Result<PaginatedResponse<EntityCountDto>, AppError>;
 }
@@ -0,0 +1,25 @@
+use crate::{
+    common::error::AppError,
+    domains::luna::dto::{
+        DuplicateGroupDto, DuplicateImageGroupDto, DuplicateImageQuery, DuplicateQuery,
+    },
+};
+use async_trait::async_trait;
+
+#[async_trait]
+/// Service trait for the duplicate-record report.
+pub trait DuplicateServiceTrait: Send + Sync {
+    /// Groups of live records that probably describe the same release, most
+    /// confident first: exact title and date matches, then records with the
+    /// same idols and similar titles.
+    async fn find_duplicates(
+        &self,
+        query: DuplicateQuery,
+    ) -> Result<Vec<DuplicateGroupDto>, AppError>;
+
+    /// Groups of stored images with identical content, most copies first.
+    async fn find_duplicate_images(
+        &self,
+        query: DuplicateImageQuery,
+    ) -> Result<Vec<DuplicateImageGroupDto>, AppError>;
+}
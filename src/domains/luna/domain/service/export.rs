@@ -0,0 +1,15 @@
+use crate::common::error::AppError;
+use crate::domains::luna::dto::{ExportEntity, ExportFormat};
+use futures::stream::BoxStream;
+
+/// Body chunks of an export, produced lazily one batch at a time.
+pub type ExportStream = BoxStream<'static, Result<String, AppError>>;
+
+/// Service trait for streaming catalog exports.
+pub trait ExportServiceTrait: Send + Sync {
+    /// Stream every live record the caller's clearance allows, ordered by ID.
+    fn export_records(&self, format: ExportFormat, max_permission: i32) -> ExportStream;
+
+    /// Stream every row of a named entity table, ordered by ID.
+    fn export_entities(&self, entity: ExportEntity, format: ExportFormat) -> ExportStream;
+}
@@ -1,20 +1,66 @@
 use crate::common::error::AppError;
-use crate::domains::luna::dto::{MediaAccessDto, MediaType, UploadImageDto};
+use crate::domains::luna::dto::{
+    MediaAccessDto, MediaFileDto, MediaType, StagedFile, UploadImageDto,
+};
 use async_trait::async_trait;
-use axum::response::Response;
+use axum::{extract::multipart::Field, http::HeaderMap, response::Response};
+use tokio::sync::OwnedSemaphorePermit;
 
 /// Service trait for handling file-related operations in luna domain
 #[async_trait]
 pub trait FileServiceTrait: Send + Sync {
     /// Serves a media file based on the provided media access parameters
-    /// Returns the file content as a response or an error if the file is not found
-    async fn serve_media_file(&self, media_dto: MediaAccessDto) -> Result<Response, AppError>;
+    /// Returns the file content as a response or an error if the file is not found.
+    /// Honours `Range`, `If-Range`, `If-None-Match` and `If-Modified-Since`
+    /// in `headers`.
+    async fn serve_media_file(
+        &self,
+        media_dto: MediaAccessDto,
+        headers: &HeaderMap,
+    ) -> Result<Response, AppError>;
+
+    /// Lists the images served for `id`: the main image and the numbered
+    /// ones, lowest first, each in the extension the media route picks.
+    /// Their URLs are `url` and `url?n=N`. A missing directory lists nothing.
+    async fn list_media(
+        &self,
+        media_type: MediaType,
+        id: &str,
+        url: &str,
+    ) -> Result<Vec<MediaFileDto>, AppError>;
+
+    /// Streams a ZIP of the files of `id` with an allowed extension, written
+    /// while it is sent. Fails with `NotFound` when there is none.
+    async fn archive_media(&self, media_type: MediaType, id: &str) -> Result<Response, AppError>;
+
+    /// Reserves one of the configured concurrent upload slots until the
+    /// permit is dropped. Fails with `TooManyRequests` when all are taken.
+    fn try_acquire_upload_slot(&self) -> Result<OwnedSemaphorePermit, AppError>;
 
-    /// Uploads image files to the specified directory
+    /// Streams a multipart file field to a temporary file next to the media
+    /// directories, failing with `PayloadTooLarge` past `asset_max_size`.
+    async fn stage_upload(&self, field: Field<'_>) -> Result<StagedFile, AppError>;
+
+    /// Streams a multipart video field like [`Self::stage_upload`], failing
+    /// with `PayloadTooLarge` past `video_max_size`.
+    async fn stage_video_upload(&self, field: Field<'_>) -> Result<StagedFile, AppError>;
+
+    /// Moves staged image files into the specified directory
     /// Returns the number of successfully uploaded files
     async fn upload_images(
         &self,
         ty: MediaType,
         upload_dto: UploadImageDto,
     ) -> Result<usize, AppError>;
+
+    /// Moves a staged MP4 or WebM video, recognized by its magic bytes, into
+    /// the `ty` directory of `id`. Fails with `Conflict` when `id` already
+    /// has a video; it is never overwritten.
+    async fn upload_video(
+        &self,
+        ty: MediaType,
+        id: &str,
+        staged: StagedFile,
+        mime: &str,
+    ) -> Result<(), AppError>;
 }
@@ -1,8 +1,13 @@
 use crate::{
     common::error::AppError,
-    domains::luna::dto::{
-        CreateGenreDto, EntityCountDto, GenreDto, PaginatedResponse, PaginationQuery,
-        SearchGenreDto, UpdateGenreDto,
+    domains::luna::{
+        dto::{
+            AutocompleteQuery, CreateGenreCategoryDto, CreateGenreDto, EntityCountDto,
+            GenreCategoryCountDto, GenreCategoryDto, GenreDto, MergeEntityResponse,
+            NameSuggestionDto, PaginatedResponse, PaginationQuery, RecordCountQuery,
+            SearchGenreDto, UpdateGenreDto,
+        },
+        infra::{catalog_cache::CatalogCache, catalog_events::CatalogEvents},
     },
 };
 
@@ -14,13 +19,27 @@ use std::sync::Arc;
 /// Trait defining business operations for genre management.
 pub trait GenreServiceTrait: Send + Sync {
     /// Constructor for the service.
-    fn create_service(db: DatabaseConnection) -> Arc<dyn GenreServiceTrait>
+    fn create_service(
+        db: DatabaseConnection,
+        events: Arc<CatalogEvents>,
+        cache: Arc<CatalogCache>,
+    ) -> Arc<dyn GenreServiceTrait>
     where
         Self: Sized;
 
     /// Retrieves a genre by their unique identifier.
     async fn get_genre_by_id(&self, id: i64) -> Result<GenreDto, AppError>;
 
+    /// Retrieves the genres with the given identifiers, in the order requested.
+    /// Unknown identifiers are skipped.
+    async fn get_genres_by_ids(&self, ids: &[i64]) -> Result<Vec<GenreDto>, AppError>;
+
+    /// Suggests genres for the text typed so far, for typeahead inputs.
+    async fn autocomplete_genres(
+        &self,
+        query: AutocompleteQuery,
+    ) -> Result<Vec<NameSuggestionDto>, AppError>;
+
     /// Retrieves genre list by condition
     async fn get_genre_list(&self, search_dto: SearchGenreDto) -> Result<Vec<GenreDto>, AppError>;
 
@@ -51,6 +70,47 @@ pub trait GenreServiceTrait: Send + Sync {
     /// Deletes a genre by their unique identifier.
     async fn delete_genre(&self, id: i64) -> Result<String, AppError>;
 
-    /// Gets record counts grouped by genres.
-    async fn get_genre_record_counts(&self) -> Result<Vec<EntityCountDto>, AppError>;
+    /// Merges the duplicate genre `source_id` into `id`, re-pointing its records.
+    async fn merge_genre(&self, id: i64, source_id: i64) -> Result<MergeEntityResponse, AppError>;
+
+    /// Gets one page of record counts grouped by genres, filtered by `query`.
+    async fn get_genre_record_counts(
+        &self,
+        query: RecordCountQuery,
+    ) -> Result<PaginatedResponse<EntityCountDto>, AppError>;
+
+    /// Gets the genre record counts grouped by genre category.
+    async fn get_genre_category_record_counts(
+        &self,
+    ) -> Result<Vec<GenreCategoryCountDto>, AppError>;
+
+    /// Every genre category.
+    async fn list_genre_categories(&self) -> Result<Vec<GenreCategoryDto>, AppError>;
+
+    /// Genre category `id`.
+    async fn get_genre_category(&self, id: i64) -> Result<GenreCategoryDto, AppError>;
+
+    /// Creates a genre category.
+    async fn create_genre_category(
+        &self,
+        dto: CreateGenreCategoryDto,
+    ) -> Result<GenreCategoryDto, AppError>;
+
+    /// Replaces genre category `id`.
+    async fn update_genre_category(
+        &self,
+        id: i64,
+        dto: CreateGenreCategoryDto,
+    ) -> Result<GenreCategoryDto, AppError>;
+
+    /// Deletes genre category `id`, leaving its genres uncategorized.
+    async fn delete_genre_category(&self, id: i64) -> Result<(), AppError>;
+
+    /// Puts genre `id` in a category, or leaves it uncategorized. Fails with
+    /// `ValidationError` when the category does not exist.
+    async fn set_genre_category(
+        &self,
+        id: i64,
+        category_id: Option<i64>,
+    ) -> Result<GenreDto, AppError>;
 }
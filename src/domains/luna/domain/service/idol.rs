@@ -1,8 +1,12 @@
 use crate::{
     common::{config::Config, error::AppError},
-    domains::luna::dto::{
-        CreateIdolDto, EntityCountDto, IdolDto, IdolWithoutImageDto, PaginatedResponse,
-        PaginationQuery, SearchIdolDto, UpdateIdolDto,
+    domains::luna::{
+        dto::{
+            AutocompleteQuery, CreateIdolDto, EntityCountDto, IdolDto, IdolProfileDto,
+            IdolWithoutImageDto, MergeEntityResponse, NameSuggestionDto, PaginatedResponse,
+            PaginationQuery, RecordCountQuery, SearchIdolDto, UpdateIdolDto,
+        },
+        infra::{catalog_cache::CatalogCache, catalog_events::CatalogEvents},
     },
 };
 
@@ -14,13 +18,28 @@ use std::sync::Arc;
 /// Service trait for idol-related business logic operations.
 pub trait IdolServiceTrait: Send + Sync {
     /// Constructor for the service.
-    fn create_service(db: DatabaseConnection, config: Config) -> Arc<dyn IdolServiceTrait>
+    fn create_service(
+        db: DatabaseConnection,
+        config: Config,
+        events: Arc<CatalogEvents>,
+        cache: Arc<CatalogCache>,
+    ) -> Arc<dyn IdolServiceTrait>
     where
         Self: Sized;
 
     /// Retrieves an idol by their unique identifier.
     async fn get_idol_by_id(&self, id: i64) -> Result<IdolDto, AppError>;
 
+    /// Retrieves the idols with the given identifiers, in the order requested.
+    /// Unknown identifiers are skipped.
+    async fn get_idols_by_ids(&self, ids: &[i64]) -> Result<Vec<IdolDto>, AppError>;
+
+    /// Suggests idols for the text typed so far, for typeahead inputs.
+    async fn autocomplete_idols(
+        &self,
+        query: AutocompleteQuery,
+    ) -> Result<Vec<NameSuggestionDto>, AppError>;
+
     /// Retrieves idol list by condition
     async fn get_idol_list(&self, search_dto: SearchIdolDto) -> Result<Vec<IdolDto>, AppError>;
 
@@ -48,11 +67,29 @@ pub trait IdolServiceTrait: Send + Sync {
     /// Updates an existing idol.
     async fn update_idol(&self, id: i64, update_dto: UpdateIdolDto) -> Result<IdolDto, AppError>;
 
+    /// Retrieves the profile of an idol, aliases included.
+    async fn get_idol_profile(&self, id: i64) -> Result<IdolProfileDto, AppError>;
+
+    /// Replaces the profile and aliases of an idol. Fails with
+    /// `ValidationError` when the debut is before the birthday or an alias is
+    /// blank or too long.
+    async fn replace_idol_profile(
+        &self,
+        id: i64,
+        profile: IdolProfileDto,
+    ) -> Result<IdolProfileDto, AppError>;
+
     /// Deletes an idol by their unique identifier.
     async fn delete_idol(&self, id: i64) -> Result<String, AppError>;
 
-    /// Gets record counts grouped by idols.
-    async fn get_idol_record_counts(&self) -> Result<Vec<EntityCountDto>, AppError>;
+    /// Merges the duplicate idol `source_id` into `id`, re-pointing its records.
+    async fn merge_idol(&self, id: i64, source_id: i64) -> Result<MergeEntityResponse, AppError>;
+
+    /// Gets one page of record counts grouped by idols, filtered by `query`.
+    async fn get_idol_record_counts(
+        &self,
+        query: RecordCountQuery,
+    ) -> Result<PaginatedResponse<EntityCountDto>, AppError>;
 
     /// Gets idols that don't have any images in the media directory.
     async fn get_idols_without_images(
@@ -0,0 +1,28 @@
+use crate::{
+    common::error::AppError,
+    domains::luna::dto::{
+        IntegrityQuery, IntegrityReportDto, MediaGcReportDto, ReconcileImagesResponse,
+    },
+};
+use async_trait::async_trait;
+
+#[async_trait]
+/// Service trait for the data-quality report and the repairs it drives.
+pub trait IntegrityServiceTrait: Send + Sync {
+    /// Counts and sample IDs of live records pointing at placeholder
+    /// entities, flagged with links they do not have, or with a
+    /// `local_img_count` that disagrees with their media directory, and of
+    /// orphaned junction and link rows.
+    async fn integrity_report(&self, query: IntegrityQuery)
+        -> Result<IntegrityReportDto, AppError>;
+
+    /// Recounts the media directory of every live record and writes the
+    /// counts that differ from `local_img_count` back in one statement.
+    async fn reconcile_image_counts(&self) -> Result<ReconcileImagesResponse, AppError>;
+
+    /// Finds media directories on the local disk whose record or idol no
+    /// longer exists, and stored-file rows whose file is gone. Unless
+    /// `dry_run`, removes the directories, their cached thumbnails and the
+    /// rows.
+    async fn collect_orphaned_media(&self, dry_run: bool) -> Result<MediaGcReportDto, AppError>;
+}
@@ -1,8 +1,12 @@
 use crate::{
     common::error::AppError,
-    domains::luna::dto::{
-        CreateLabelDto, EntityCountDto, LabelDto, PaginatedResponse, PaginationQuery,
-        SearchLabelDto, UpdateLabelDto,
+    domains::luna::{
+        dto::{
+            AutocompleteQuery, CreateLabelDto, EntityCountDto, LabelDto, MergeEntityResponse,
+            NameSuggestionDto, PaginatedResponse, PaginationQuery, RecordCountQuery,
+            SearchLabelDto, UpdateLabelDto,
+        },
+        infra::{catalog_cache::CatalogCache, catalog_events::CatalogEvents},
     },
 };
 
@@ -14,13 +18,27 @@ use std::sync::Arc;
 /// Trait defining business operations for label management.
 pub trait LabelServiceTrait: Send + Sync {
     /// Constructor for the service.
-    fn create_service(db: DatabaseConnection) -> Arc<dyn LabelServiceTrait>
+    fn create_service(
+        db: DatabaseConnection,
+        events: Arc<CatalogEvents>,
+        cache: Arc<CatalogCache>,
+    ) -> Arc<dyn LabelServiceTrait>
     where
         Self: Sized;
 
     /// Retrieves a label by their unique identifier.
     async fn get_label_by_id(&self, id: i64) -> Result<LabelDto, AppError>;
 
+    /// Retrieves the labels with the given identifiers, in the order requested.
+    /// Unknown identifiers are skipped.
+    async fn get_labels_by_ids(&self, ids: &[i64]) -> Result<Vec<LabelDto>, AppError>;
+
+    /// Suggests labels for the text typed so far, for typeahead inputs.
+    async fn autocomplete_labels(
+        &self,
+        query: AutocompleteQuery,
+    ) -> Result<Vec<NameSuggestionDto>, AppError>;
+
     /// Retrieves label list by condition
     async fn get_label_list(&self, search_dto: SearchLabelDto) -> Result<Vec<LabelDto>, AppError>;
 
@@ -48,9 +66,25 @@ pub trait LabelServiceTrait: Send + Sync {
     /// Updates an existing label.
     async fn update_label(&self, id: i64, payload: UpdateLabelDto) -> Result<LabelDto, AppError>;
 
+    /// Attaches a label to the studio publishing it, or detaches it. Fails
+    /// with `ValidationError` when the placeholder label or studio is
+    /// involved.
+    async fn set_label_studio(&self, id: i64, studio_id: Option<i64>)
+        -> Result<LabelDto, AppError>;
+
     /// Deletes a label by their unique identifier.
-    async fn delete_label(&self, id: i64) -> Result<String, AppError>;
+    ///
+    /// Fails with a conflict listing the blocking record IDs while records
+    /// still reference it, unless `reassign_to` names a label to re-point them
+    /// to first.
+    async fn delete_label(&self, id: i64, reassign_to: Option<i64>) -> Result<String, AppError>;
+
+    /// Merges the duplicate label `source_id` into `id`, re-pointing its records.
+    async fn merge_label(&self, id: i64, source_id: i64) -> Result<MergeEntityResponse, AppError>;
 
-    /// Gets record counts grouped by labels.
-    async fn get_label_record_counts(&self) -> Result<Vec<EntityCountDto>, AppError>;
+    /// Gets one page of record counts grouped by labels, filtered by `query`.
+    async fn get_label_record_counts(
+        &self,
+        query: RecordCountQuery,
+    ) -> Result<PaginatedResponse<EntityCountDto>, AppError>;
 }
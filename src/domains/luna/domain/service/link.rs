@@ -0,0 +1,54 @@
+use crate::{
+    common::error::AppError,
+    domains::luna::dto::{
+        LinkCheckReportDto, LinkDto, LinkSearchQuery, PaginatedResponse, PaginationQuery,
+        PatchLinkDto,
+    },
+};
+use async_trait::async_trait;
+
+#[async_trait]
+/// Service trait for record links and their health checks. A check requests
+/// the link with `HEAD` (falling back to `GET` where `HEAD` is not allowed)
+/// and counts it dead when the request fails or answers with an error
+/// status other than `429`. Only HTTP(S) links are checked.
+pub trait LinkServiceTrait: Send + Sync {
+    /// A page of the links of live records the last check found dead, most
+    /// recently checked first.
+    async fn dead_links(
+        &self,
+        pagination: PaginationQuery,
+    ) -> Result<PaginatedResponse<LinkDto>, AppError>;
+
+    /// A page of the links of live records at or below `max_permission`
+    /// matching `query`, whatever their record, newest first.
+    async fn search_links(
+        &self,
+        query: LinkSearchQuery,
+        max_permission: i32,
+    ) -> Result<PaginatedResponse<LinkDto>, AppError>;
+
+    /// Checks link `id` now and returns it with the outcome.
+    async fn check_link(&self, id: i64) -> Result<LinkDto, AppError>;
+
+    /// Checks the HTTP(S) links of `record_id` now and returns all of its
+    /// links. Callers check that the record exists.
+    async fn check_record_links(&self, record_id: &str) -> Result<Vec<LinkDto>, AppError>;
+
+    /// Checks up to `link_check_batch_size` links, never-checked ones first
+    /// and then the least recently checked.
+    async fn check_stale_links(&self) -> Result<LinkCheckReportDto, AppError>;
+
+    /// Writes the fields present in `patch` to link `id` of `record_id`.
+    /// Fails with `Conflict` when the record already has the new URL.
+    async fn update_link(
+        &self,
+        record_id: &str,
+        id: i64,
+        patch: PatchLinkDto,
+        actor: &str,
+    ) -> Result<LinkDto, AppError>;
+
+    /// Deletes link `id` of `record_id`; the record's `has_links` follows.
+    async fn delete_link(&self, record_id: &str, id: i64, actor: &str) -> Result<(), AppError>;
+}
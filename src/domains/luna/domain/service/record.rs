@@ -1,20 +1,35 @@
+#[cfg(feature = "metadata")]
+use crate::domains::luna::dto::{RefreshMetadataQuery, RefreshMetadataResponse};
 use crate::{
-    common::error::AppError,
-    domains::luna::dto::{
-        CreateLinkDto, CreateRecordDto, PaginatedResponse, PaginationQuery, RecordDto,
-        RecordSlimDto, SearchRecordDto, UpdateRecordDto, UserFilter,
+    common::{config::Config, error::AppError},
+    domains::luna::{
+        dto::{
+            BulkCreateMode, BulkCreateResponse, BulkDeleteRecordsDto, BulkDeleteRecordsResponse,
+            CreateLinkDto, CreateRecordDto, ImportConflictMode, ImportResponse, ImportRow,
+            LinkUpdateMode, LinkUpdateResultDto, PaginatedResponse, PaginationQuery,
+            PatchRecordDto, RandomRecordsQuery, RecordDto, RecordRelations, RecordRelationsDto,
+            RecordRevisionDto, RecordSlimDto, RecordSyncResponse, SearchRecordDto, SeenRecordDto,
+            SeriesNeighborDto, SimilarRecordDto, UpdateRecordDto, UserFilter,
+        },
+        infra::{catalog_cache::CatalogCache, catalog_events::CatalogEvents},
     },
 };
 
 use async_trait::async_trait;
 use sea_orm::DatabaseConnection;
+use std::collections::HashMap;
 use std::sync::Arc;
 
 #[async_trait]
 /// Service trait for record-related business logic operations.
 pub trait RecordServiceTrait: Send + Sync {
     /// Constructor for the service.
-    fn create_service(db: DatabaseConnection) -> Arc<dyn RecordServiceTrait>
+    fn create_service(
+        db: DatabaseConnection,
+        config: Config,
+        events: Arc<CatalogEvents>,
+        cache: Arc<CatalogCache>,
+    ) -> Arc<dyn RecordServiceTrait>
     where
         Self: Sized;
 
@@ -27,6 +42,20 @@ pub trait RecordServiceTrait: Send + Sync {
         user_filter: Option<UserFilter>,
     ) -> Result<Vec<String>, AppError>;
 
+    /// Returns whether a record with `id` exists and is visible at `max_permission`.
+    async fn record_exists(&self, id: &str, max_permission: i32) -> Result<bool, AppError>;
+
+    /// Returns the subset of `ids` that already exist and are visible at
+    /// `max_permission`, in request order.
+    async fn find_existing_record_ids(
+        &self,
+        ids: Vec<String>,
+        max_permission: i32,
+    ) -> Result<Vec<String>, AppError>;
+
+    /// Returns the `permission` level of a record, or `None` if it does not exist.
+    async fn get_record_permission(&self, id: &str) -> Result<Option<i32>, AppError>;
+
     /// Retrieves all records in a slim format, optionally filtered by user interaction.
     async fn get_all_record_slim(
         &self,
@@ -40,9 +69,11 @@ pub trait RecordServiceTrait: Send + Sync {
         user_filter: Option<UserFilter>,
     ) -> Result<PaginatedResponse<String>, AppError>;
 
-    /// Retrieves slim records with database-level pagination and optional user filtering.
+    /// Retrieves slim records matching `search_dto` with database-level
+    /// pagination and optional user filtering.
     async fn get_record_slim_paginated(
         &self,
+        search_dto: SearchRecordDto,
         pagination: PaginationQuery,
         user_filter: Option<UserFilter>,
     ) -> Result<PaginatedResponse<RecordSlimDto>, AppError>;
@@ -53,30 +84,183 @@ pub trait RecordServiceTrait: Send + Sync {
         search_dto: SearchRecordDto,
     ) -> Result<Vec<RecordDto>, AppError>;
 
-    /// Retrieves record list with pagination
+    /// Retrieves record list with pagination, loading only the junction
+    /// relations selected in `relations`.
     async fn get_record_list_paginated(
         &self,
         search_dto: SearchRecordDto,
         pagination: PaginationQuery,
         user_filter: Option<UserFilter>,
+        relations: RecordRelations,
     ) -> Result<PaginatedResponse<RecordDto>, AppError>;
 
     /// Retrieves all records.
     async fn get_records(&self) -> Result<Vec<RecordDto>, AppError>;
 
-    /// Creates a new record.
-    async fn create_record(&self, create_dto: CreateRecordDto) -> Result<RecordDto, AppError>;
+    /// Creates a new record, with `actor` (the caller's user ID) as its
+    /// creator and last modifier.
+    async fn create_record(
+        &self,
+        create_dto: CreateRecordDto,
+        actor: &str,
+    ) -> Result<RecordDto, AppError>;
 
-    /// Updates an existing record.
+    /// Creates many records in one transaction, reporting a result per item.
+    ///
+    /// In [`BulkCreateMode::Atomic`] the first failure rolls back the whole
+    /// batch; in [`BulkCreateMode::Partial`] failed items are skipped and the
+    /// rest are committed.
+    async fn create_records_bulk(
+        &self,
+        items: Vec<CreateRecordDto>,
+        mode: BulkCreateMode,
+        actor: &str,
+    ) -> Result<BulkCreateResponse, AppError>;
+
+    /// Imports parsed dump rows in one transaction, each row in its own
+    /// savepoint, reporting a result per row. Name-only references are
+    /// matched to an existing entity of the same name before a new one is
+    /// created; existing record IDs are skipped or replaced per `on_conflict`.
+    async fn import_records(
+        &self,
+        rows: Vec<ImportRow>,
+        on_conflict: ImportConflictMode,
+        actor: &str,
+    ) -> Result<ImportResponse, AppError>;
+
+    /// Updates an existing record, recording `actor` as its last modifier.
+    /// Fails with `PreconditionFailed` when `expected_version` is stale.
     async fn update_record(
         &self,
         id: &str,
         update_dto: UpdateRecordDto,
+        expected_version: Option<i32>,
+        actor: &str,
+    ) -> Result<RecordDto, AppError>;
+
+    /// Partially updates a record, writing only the fields present in `patch_dto`.
+    /// Fails with `PreconditionFailed` when `expected_version` is stale.
+    async fn patch_record(
+        &self,
+        id: &str,
+        patch_dto: PatchRecordDto,
+        expected_version: Option<i32>,
+        actor: &str,
+    ) -> Result<RecordDto, AppError>;
+
+    /// Creates or fully replaces a record, including its genre set, idol set
+    /// and links, in one transaction. Returns the hydrated record.
+    /// Manual genre and idol associations survive unless `override_manual`
+    /// is set. With `expected_version`, the record must exist at that version.
+    async fn replace_record(
+        &self,
+        id: &str,
+        replace_dto: CreateRecordDto,
+        override_manual: bool,
+        expected_version: Option<i32>,
+        actor: &str,
     ) -> Result<RecordDto, AppError>;
 
-    /// Deletes a record by their unique identifier.
+    /// Looks the record up with the configured metadata providers and merges
+    /// what they report into it, never removing manual genre and idol
+    /// associations. Fails with `PreconditionFailed` when `expected_version`
+    /// is stale.
+    #[cfg(feature = "metadata")]
+    async fn refresh_metadata(
+        &self,
+        id: &str,
+        query: RefreshMetadataQuery,
+        expected_version: Option<i32>,
+        actor: &str,
+    ) -> Result<RefreshMetadataResponse, AppError>;
+
+    /// A page of the revisions of record `id`, newest first. Each revision
+    /// holds the state of the record before one edit and who made the edit.
+    async fn get_record_history(
+        &self,
+        id: &str,
+        pagination: PaginationQuery,
+    ) -> Result<PaginatedResponse<RecordRevisionDto>, AppError>;
+
+    /// Restores record `id` to the state stored in revision `revision`,
+    /// genres, idols and links included, recording `actor` as its last
+    /// modifier. The current state becomes a revision first, so a revert can
+    /// be reverted too. The image count is kept, and re-added genre and idol
+    /// associations are not marked manual. Fails with `PreconditionFailed`
+    /// when `expected_version` is stale.
+    async fn revert_record(
+        &self,
+        id: &str,
+        revision: i64,
+        expected_version: Option<i32>,
+        actor: &str,
+    ) -> Result<RecordDto, AppError>;
+
+    /// Moves a record to the trash. It disappears from lists and search until
+    /// restored or purged.
     async fn delete_record(&self, id: &str) -> Result<String, AppError>;
 
+    /// Lists trashed records visible at `max_permission`, most recently
+    /// deleted first.
+    async fn get_trash_paginated(
+        &self,
+        pagination: PaginationQuery,
+        max_permission: i32,
+    ) -> Result<PaginatedResponse<RecordDto>, AppError>;
+
+    /// Takes a record out of the trash and reindexes it.
+    async fn restore_record(&self, id: &str) -> Result<RecordDto, AppError>;
+
+    /// Permanently deletes a trashed record.
+    async fn purge_record(&self, id: &str) -> Result<String, AppError>;
+
+    /// Moves many records to the trash in one transaction. With `purge` they
+    /// are permanently deleted instead, with their junction and link rows,
+    /// optionally removing their media directories afterwards.
+    async fn delete_records_bulk(
+        &self,
+        delete_dto: BulkDeleteRecordsDto,
+    ) -> Result<BulkDeleteRecordsResponse, AppError>;
+
+    /// Adds `added` freshly uploaded images to the `local_img_count` of
+    /// record `id`. Returns the new count.
+    async fn add_local_images(&self, id: &str, added: i32) -> Result<i32, AppError>;
+
+    /// Points the `trailer` of record `id` at `trailer`, or clears it.
+    async fn set_trailer(&self, id: &str, trailer: Option<String>) -> Result<(), AppError>;
+
+    /// Makes image `cover_index` of record `id` its cover, or the main image
+    /// when `None`. Does not check that the image exists.
+    async fn set_cover_index(&self, id: &str, cover_index: Option<i32>) -> Result<(), AppError>;
+
+    /// Sets the position of record `id` within its series, or clears it so
+    /// the record is ordered by date.
+    async fn set_order_in_series(
+        &self,
+        id: &str,
+        order_in_series: Option<i32>,
+    ) -> Result<(), AppError>;
+
+    /// Returns the records visible at `max_permission` before and after
+    /// record `id` in its series.
+    async fn get_series_neighbors(
+        &self,
+        id: &str,
+        max_permission: i32,
+    ) -> Result<(Option<SeriesNeighborDto>, Option<SeriesNeighborDto>), AppError>;
+
+    /// Folds record `source_id` into record `id` in one transaction and
+    /// deletes the source, then moves the source's media into the record's
+    /// directory. Fails with `PreconditionFailed` when `expected_version` of
+    /// the record `id` is stale.
+    async fn merge_records(
+        &self,
+        id: &str,
+        source_id: &str,
+        expected_version: Option<i32>,
+        actor: &str,
+    ) -> Result<RecordDto, AppError>;
+
     /// Get records by director ID with pagination
     async fn get_records_by_director(
         &self,
@@ -125,11 +309,73 @@ pub trait RecordServiceTrait: Send + Sync {
         user_filter: Option<UserFilter>,
     ) -> Result<PaginatedResponse<RecordDto>, AppError>;
 
-    /// Update record links only - add new links that don't already exist
-    /// Returns the number of new links added
+    /// Update record links only. In `Append` mode new links are added and
+    /// known ones backfilled; in `Replace` mode the links become exactly
+    /// `new_links`. URLs are normalized and duplicates, by URL or by name
+    /// and size, are skipped and reported. The links before the change are
+    /// kept in a revision by `actor`.
     async fn update_record_links(
         &self,
         id: &str,
         new_links: Vec<CreateLinkDto>,
-    ) -> Result<i32, AppError>;
+        mode: LinkUpdateMode,
+        actor: &str,
+    ) -> Result<LinkUpdateResultDto, AppError>;
+
+    /// Returns the record changes after sync position `since`, capped at
+    /// `limit` (see [`MAX_SYNC_LIMIT`](crate::domains::luna::dto::MAX_SYNC_LIMIT)).
+    async fn sync_records(
+        &self,
+        since: i64,
+        limit: Option<u64>,
+        max_permission: i32,
+    ) -> Result<RecordSyncResponse, AppError>;
+
+    /// Returns up to `limit` (see
+    /// [`MAX_FEED_LIMIT`](crate::domains::luna::dto::MAX_FEED_LIMIT)) of the
+    /// most recently created or updated records, newest first.
+    async fn get_recent_records(
+        &self,
+        limit: Option<u64>,
+        max_permission: i32,
+    ) -> Result<Vec<RecordDto>, AppError>;
+
+    /// Returns up to `limit` (see
+    /// [`MAX_SIMILAR_RECORDS`](crate::domains::luna::dto::MAX_SIMILAR_RECORDS))
+    /// records visible at `max_permission` that share idols, genres, series
+    /// or studio with record `id`, most similar first.
+    async fn get_similar_records(
+        &self,
+        id: &str,
+        limit: Option<u64>,
+        max_permission: i32,
+    ) -> Result<Vec<SimilarRecordDto>, AppError>;
+
+    /// Returns up to `query.count` (see
+    /// [`MAX_RANDOM_RECORDS`](crate::domains::luna::dto::MAX_RANDOM_RECORDS))
+    /// random records visible at `max_permission`, matching the query's genre
+    /// and idol filters.
+    async fn get_random_records(
+        &self,
+        query: RandomRecordsQuery,
+        max_permission: i32,
+    ) -> Result<Vec<RecordDto>, AppError>;
+
+    /// Lists the records visible at `max_permission` that user `user_id` has
+    /// seen, most recently seen first.
+    async fn get_seen_history(
+        &self,
+        user_id: &str,
+        pagination: PaginationQuery,
+        max_permission: i32,
+    ) -> Result<PaginatedResponse<SeenRecordDto>, AppError>;
+
+    /// Batch-loads the relations selected in `relations` for `record_ids`.
+    /// Every requested ID is present in the result, with empty lists when
+    /// it has no rows.
+    async fn get_record_relations(
+        &self,
+        record_ids: &[String],
+        relations: RecordRelations,
+    ) -> Result<HashMap<String, RecordRelationsDto>, AppError>;
 }
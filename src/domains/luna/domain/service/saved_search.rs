@@ -0,0 +1,46 @@
+use crate::{
+    common::error::AppError,
+    domains::luna::dto::{
+        CreateSavedSearchDto, PaginatedResponse, PaginationQuery, SavedSearchDto,
+    },
+};
+use async_trait::async_trait;
+
+#[async_trait]
+/// Service trait for saved record searches. A search is visible to its owner
+/// and, once public, to every user; only the owner may change it.
+pub trait SavedSearchServiceTrait: Send + Sync {
+    /// A page of the searches saved by `user_id`, by name.
+    async fn list_own(
+        &self,
+        user_id: &str,
+        pagination: PaginationQuery,
+    ) -> Result<PaginatedResponse<SavedSearchDto>, AppError>;
+
+    /// A page of every user's public searches, newest first.
+    async fn list_public(
+        &self,
+        pagination: PaginationQuery,
+    ) -> Result<PaginatedResponse<SavedSearchDto>, AppError>;
+
+    /// Search `id`, if `user_id` owns it or it is public.
+    async fn get_saved_search(&self, id: i64, user_id: &str) -> Result<SavedSearchDto, AppError>;
+
+    /// Validates and stores a search for `user_id`.
+    async fn create_saved_search(
+        &self,
+        user_id: &str,
+        dto: CreateSavedSearchDto,
+    ) -> Result<SavedSearchDto, AppError>;
+
+    /// Replaces the definition of search `id`; only its owner may.
+    async fn update_saved_search(
+        &self,
+        id: i64,
+        user_id: &str,
+        dto: CreateSavedSearchDto,
+    ) -> Result<SavedSearchDto, AppError>;
+
+    /// Deletes search `id`; only its owner may.
+    async fn delete_saved_search(&self, id: i64, user_id: &str) -> Result<(), AppError>;
+}
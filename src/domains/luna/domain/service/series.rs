@@ -1,8 +1,12 @@
 use crate::{
     common::error::AppError,
-    domains::luna::dto::{
-        CreateSeriesDto, EntityCountDto, PaginatedResponse, PaginationQuery, SearchSeriesDto,
-        SeriesDto, UpdateSeriesDto,
+    domains::luna::{
+        dto::{
+            AutocompleteQuery, CreateSeriesDto, EntityCountDto, MergeEntityResponse,
+            NameSuggestionDto, PaginatedResponse, PaginationQuery, RecordCountQuery,
+            SearchSeriesDto, SeriesDto, UpdateSeriesDto,
+        },
+        infra::{catalog_cache::CatalogCache, catalog_events::CatalogEvents},
     },
 };
 
@@ -14,13 +18,27 @@ use std::sync::Arc;
 /// Service trait for series-related business logic operations.
 pub trait SeriesServiceTrait: Send + Sync {
     /// Constructor for the service.
-    fn create_service(db: DatabaseConnection) -> Arc<dyn SeriesServiceTrait>
+    fn create_service(
+        db: DatabaseConnection,
+        events: Arc<CatalogEvents>,
+        cache: Arc<CatalogCache>,
+    ) -> Arc<dyn SeriesServiceTrait>
     where
         Self: Sized;
 
     /// Retrieves a series by their unique identifier.
     async fn get_series_by_id(&self, id: i64) -> Result<SeriesDto, AppError>;
 
+    /// Retrieves the series with the given identifiers, in the order requested.
+    /// Unknown identifiers are skipped.
+    async fn get_series_by_ids(&self, ids: &[i64]) -> Result<Vec<SeriesDto>, AppError>;
+
+    /// Suggests series for the text typed so far, for typeahead inputs.
+    async fn autocomplete_series(
+        &self,
+        query: AutocompleteQuery,
+    ) -> Result<Vec<NameSuggestionDto>, AppError>;
+
     /// Retrieves series list by condition
     async fn get_series_list(
         &self,
@@ -56,8 +74,18 @@ pub trait SeriesServiceTrait: Send + Sync {
     ) -> Result<SeriesDto, AppError>;
 
     /// Deletes a series by their unique identifier.
-    async fn delete_series(&self, id: i64) -> Result<String, AppError>;
+    ///
+    /// Fails with a conflict listing the blocking record IDs while records
+    /// still reference it, unless `reassign_to` names a series to re-point them
+    /// to first.
+    async fn delete_series(&self, id: i64, reassign_to: Option<i64>) -> Result<String, AppError>;
 
-    /// Gets record counts grouped by series.
-    async fn get_series_record_counts(&self) -> Result<Vec<EntityCountDto>, AppError>;
+    /// Merges the duplicate series `source_id` into `id`, re-pointing its records.
+    async fn merge_series(&self, id: i64, source_id: i64) -> Result<MergeEntityResponse, AppError>;
+
+    /// Gets one page of record counts grouped by series, filtered by `query`.
+    async fn get_series_record_counts(
+        &self,
+        query: RecordCountQuery,
+    ) -> Result<PaginatedResponse<EntityCountDto>, AppError>;
 }
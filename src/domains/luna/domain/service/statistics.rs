@@ -0,0 +1,45 @@
+use crate::{
+    common::error::AppError,
+    domains::luna::dto::{
+        CoOccurrenceQuery, DateCountDto, EntityCountDto, LinkStatisticsDto, RecordsByDateQuery,
+        StatisticsOverviewDto,
+    },
+};
+use async_trait::async_trait;
+
+#[async_trait]
+/// Service trait for catalog-wide statistics.
+pub trait StatisticsServiceTrait: Send + Sync {
+    /// Totals, monthly growth, top entities and average duration, served
+    /// from the cache while no catalog write has invalidated it.
+    async fn overview(&self) -> Result<StatisticsOverviewDto, AppError>;
+
+    /// Number and combined size of the links of live records, and the links
+    /// added per month over the same window as the overview.
+    async fn link_statistics(&self) -> Result<LinkStatisticsDto, AppError>;
+
+    /// Live records per release month or year, oldest first. Empty buckets
+    /// between the first and last release are included as zero.
+    async fn records_by_date(
+        &self,
+        query: RecordsByDateQuery,
+    ) -> Result<Vec<DateCountDto>, AppError>;
+
+    /// Idols sharing the most live records visible at `max_permission` with
+    /// idol `id`, most first, with the number of shared records.
+    async fn co_stars(
+        &self,
+        id: i64,
+        query: CoOccurrenceQuery,
+        max_permission: i32,
+    ) -> Result<Vec<EntityCountDto>, AppError>;
+
+    /// Genres tagged on the most live records visible at `max_permission`
+    /// alongside genre `id`, most first, with the number of shared records.
+    async fn related_genres(
+        &self,
+        id: i64,
+        query: CoOccurrenceQuery,
+        max_permission: i32,
+    ) -> Result<Vec<EntityCountDto>, AppError>;
+}
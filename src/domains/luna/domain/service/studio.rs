@@ -1,8 +1,12 @@
 use crate::{
     common::error::AppError,
-    domains::luna::dto::{
-        CreateStudioDto, EntityCountDto, PaginatedResponse, PaginationQuery, SearchStudioDto,
-        StudioDto, UpdateStudioDto,
+    domains::luna::{
+        dto::{
+            AutocompleteQuery, CreateStudioDto, EntityCountDto, MergeEntityResponse,
+            NameSuggestionDto, PaginatedResponse, PaginationQuery, RecordCountQuery,
+            SearchStudioDto, StudioChildrenDto, StudioDto, StudioRollupDto, UpdateStudioDto,
+        },
+        infra::{catalog_cache::CatalogCache, catalog_events::CatalogEvents},
     },
 };
 
@@ -14,13 +18,27 @@ use std::sync::Arc;
 /// Service trait for studio-related business logic operations.
 pub trait StudioServiceTrait: Send + Sync {
     /// Constructor for the service.
-    fn create_service(db: DatabaseConnection) -> Arc<dyn StudioServiceTrait>
+    fn create_service(
+        db: DatabaseConnection,
+        events: Arc<CatalogEvents>,
+        cache: Arc<CatalogCache>,
+    ) -> Arc<dyn StudioServiceTrait>
     where
         Self: Sized;
 
     /// Retrieves a studio by their unique identifier.
     async fn get_studio_by_id(&self, id: i64) -> Result<StudioDto, AppError>;
 
+    /// Retrieves the studios with the given identifiers, in the order requested.
+    /// Unknown identifiers are skipped.
+    async fn get_studios_by_ids(&self, ids: &[i64]) -> Result<Vec<StudioDto>, AppError>;
+
+    /// Suggests studios for the text typed so far, for typeahead inputs.
+    async fn autocomplete_studios(
+        &self,
+        query: AutocompleteQuery,
+    ) -> Result<Vec<NameSuggestionDto>, AppError>;
+
     /// Retrieves studio list by condition
     async fn get_studio_list(
         &self,
@@ -55,9 +73,35 @@ pub trait StudioServiceTrait: Send + Sync {
         update_dto: UpdateStudioDto,
     ) -> Result<StudioDto, AppError>;
 
+    /// Moves a studio under `parent_id`, or to the top level. Fails with
+    /// `ValidationError` when that would put the studio below itself or
+    /// involves the placeholder studio.
+    async fn set_studio_parent(
+        &self,
+        id: i64,
+        parent_id: Option<i64>,
+    ) -> Result<StudioDto, AppError>;
+
+    /// Retrieves the studios whose parent is a studio and the labels it
+    /// publishes.
+    async fn get_studio_children(&self, id: i64) -> Result<StudioChildrenDto, AppError>;
+
+    /// Retrieves the live record counts of a studio and everything below it.
+    async fn get_studio_rollup(&self, id: i64) -> Result<StudioRollupDto, AppError>;
+
     /// Deletes a studio by their unique identifier.
-    async fn delete_studio(&self, id: i64) -> Result<String, AppError>;
+    ///
+    /// Fails with a conflict listing the blocking record IDs while records
+    /// still reference it, unless `reassign_to` names a studio to re-point them
+    /// to first.
+    async fn delete_studio(&self, id: i64, reassign_to: Option<i64>) -> Result<String, AppError>;
 
-    /// Gets record counts grouped by studios.
-    async fn get_studio_record_counts(&self) -> Result<Vec<EntityCountDto>, AppError>;
+    /// Merges the duplicate studio `source_id` into `id`, re-pointing its records.
+    async fn merge_studio(&self, id: i64, source_id: i64) -> Result<MergeEntityResponse, AppError>;
+
+    /// Gets one page of record counts grouped by studios, filtered by `query`.
+    async fn get_studio_record_counts(
+        &self,
+        query: RecordCountQuery,
+    ) -> Result<PaginatedResponse<EntityCountDto>, AppError>;
 }
@@ -0,0 +1,52 @@
+use crate::{
+    common::error::AppError,
+    domains::luna::dto::{
+        AttachTagsDto, CreateTagDto, SearchTagDto, TagCategoryDto, TagCountDto, TagDto,
+    },
+};
+use async_trait::async_trait;
+
+#[async_trait]
+/// Service trait for user-defined tags and the tags attached to records.
+/// Callers check that a record exists and is visible before tagging it.
+pub trait TagServiceTrait: Send + Sync {
+    /// Every tag matching `search`.
+    async fn list_tags(&self, search: SearchTagDto) -> Result<Vec<TagDto>, AppError>;
+
+    /// Tag `id`.
+    async fn get_tag(&self, id: i64) -> Result<TagDto, AppError>;
+
+    /// Every tag category.
+    async fn list_categories(&self) -> Result<Vec<TagCategoryDto>, AppError>;
+
+    /// Creates a tag in an existing category.
+    async fn create_tag(&self, dto: CreateTagDto) -> Result<TagDto, AppError>;
+
+    /// Replaces tag `id`.
+    async fn update_tag(&self, id: i64, dto: CreateTagDto) -> Result<TagDto, AppError>;
+
+    /// Deletes tag `id`, detaching it from every record.
+    async fn delete_tag(&self, id: i64) -> Result<(), AppError>;
+
+    /// The tags attached to `record_id`.
+    async fn record_tags(&self, record_id: &str) -> Result<Vec<TagDto>, AppError>;
+
+    /// Attaches existing tags to `record_id` and returns its tags.
+    async fn attach_tags(
+        &self,
+        record_id: &str,
+        dto: AttachTagsDto,
+    ) -> Result<Vec<TagDto>, AppError>;
+
+    /// Detaches tag `tag_id` from `record_id`.
+    async fn detach_tag(&self, record_id: &str, tag_id: i64) -> Result<(), AppError>;
+
+    /// Up to `limit` tags (clamped to
+    /// [`MAX_TAG_CLOUD_LIMIT`](crate::domains::luna::dto::MAX_TAG_CLOUD_LIMIT))
+    /// with their usage among live records visible at `max_permission`.
+    async fn tag_cloud(
+        &self,
+        limit: Option<u64>,
+        max_permission: i32,
+    ) -> Result<Vec<TagCountDto>, AppError>;
+}
@@ -0,0 +1,40 @@
+use crate::{
+    common::error::AppError,
+    domains::luna::dto::{Language, TranslatedEntity, TranslatedNames, TranslationDto},
+};
+use async_trait::async_trait;
+
+#[async_trait]
+/// Service trait for translated entity names.
+pub trait TranslationServiceTrait: Send + Sync {
+    /// Names in `language` of the entities in `keys` that have one.
+    async fn names(
+        &self,
+        language: Language,
+        keys: &[(TranslatedEntity, i64)],
+    ) -> Result<TranslatedNames, AppError>;
+
+    /// Every translation of one entity, by language.
+    async fn list_translations(
+        &self,
+        entity: TranslatedEntity,
+        id: i64,
+    ) -> Result<Vec<TranslationDto>, AppError>;
+
+    /// Sets the name of one entity in `language`.
+    async fn set_translation(
+        &self,
+        entity: TranslatedEntity,
+        id: i64,
+        language: Language,
+        name: &str,
+    ) -> Result<TranslationDto, AppError>;
+
+    /// Removes the name of one entity in `language`.
+    async fn delete_translation(
+        &self,
+        entity: TranslatedEntity,
+        id: i64,
+        language: Language,
+    ) -> Result<(), AppError>;
+}
@@ -0,0 +1,71 @@
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+/// Suggestions returned when `limit` is omitted.
+pub const DEFAULT_AUTOCOMPLETE_LIMIT: u64 = 10;
+/// Most suggestions one request returns.
+pub const MAX_AUTOCOMPLETE_LIMIT: u64 = 50;
+
+/// Query parameters of the `/cards/{entities}/autocomplete` endpoints.
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AutocompleteQuery {
+    /// Typed text, matched anywhere in the name and ignoring case. Names
+    /// starting with it come first.
+    pub q: String,
+    /// Maximum number of suggestions (default 10, at most 50).
+    pub limit: Option<u64>,
+}
+
+impl AutocompleteQuery {
+    /// `q` trimmed and lowercased, or `None` when it is blank.
+    pub fn term(&self) -> Option<String> {
+        let term = self.q.trim();
+        (!term.is_empty()).then(|| term.to_lowercase())
+    }
+
+    /// `limit` with the default applied, kept within `1..=MAX_AUTOCOMPLETE_LIMIT`.
+    pub fn limit(&self) -> u64 {
+        self.limit
+            .unwrap_or(DEFAULT_AUTOCOMPLETE_LIMIT)
+            .clamp(1, MAX_AUTOCOMPLETE_LIMIT)
+    }
+}
+
+/// One autocomplete suggestion.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct NameSuggestionDto {
+    pub id: i64,
+    pub name: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AutocompleteQuery, MAX_AUTOCOMPLETE_LIMIT};
+
+    #[test]
+    fn term_is_trimmed_and_lowercased() {
+        let query = AutocompleteQuery {
+            q: "  Ami ".to_string(),
+            limit: None,
+        };
+        assert_eq!(query.term().as_deref(), Some("ami"));
+        assert_eq!(query.limit(), 10);
+
+        let blank = AutocompleteQuery {
+            q: "   ".to_string(),
+            limit: Some(0),
+        };
+        assert_eq!(blank.term(), None);
+        assert_eq!(blank.limit(), 1);
+    }
+
+    #[test]
+    fn limit_is_capped() {
+        let query = AutocompleteQuery {
+            q: "a".to_string(),
+            limit: Some(1_000),
+        };
+        assert_eq!(query.limit(), MAX_AUTOCOMPLETE_LIMIT);
+    }
+}
@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+/// Request body of `POST /cards/{entities}/batch`.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, Validate)]
+pub struct BatchLookupDto {
+    /// Entities to look up. The found ones are returned in this order,
+    /// each once; unknown IDs are left out.
+    #[validate(length(min = 1, max = 200, message = "Between 1 and 200 IDs are required"))]
+    pub ids: Vec<i64>,
+}
@@ -0,0 +1,47 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+/// Longest comment body accepted, in characters.
+pub const MAX_COMMENT_LENGTH: u64 = 4000;
+
+/// Request body of `POST /cards/records/{id}/comments`.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, Validate)]
+pub struct CreateCommentDto {
+    #[validate(length(
+        min = 1,
+        max = MAX_COMMENT_LENGTH,
+        message = "Comment must be between 1 and 4000 characters"
+    ))]
+    pub body: String,
+}
+
+/// Request body of `PUT /cards/records/{id}/comments/{comment_id}`.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, Validate)]
+pub struct UpdateCommentDto {
+    #[validate(length(
+        min = 1,
+        max = MAX_COMMENT_LENGTH,
+        message = "Comment must be between 1 and 4000 characters"
+    ))]
+    pub body: String,
+}
+
+/// The user who wrote a comment.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CommentAuthorDto {
+    pub id: String,
+    pub username: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CommentDto {
+    pub id: i64,
+    pub record_id: String,
+    pub author: CommentAuthorDto,
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+    /// Equal to `created_at` until the body is edited.
+    pub updated_at: DateTime<Utc>,
+}
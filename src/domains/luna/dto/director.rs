@@ -1,11 +1,16 @@
 use serde::{Deserialize, Serialize};
-use utoipa::ToSchema;
+use utoipa::{IntoParams, ToSchema};
 use validator::Validate;
 
 use crate::domains::luna::domain::Director;
 
 // Director DTOs
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[cfg_attr(
+    feature = "graphql",
+    derive(async_graphql::SimpleObject),
+    graphql(name = "Director")
+)]
 pub struct DirectorDto {
     pub id: i64,
     pub name: String,
@@ -24,14 +29,24 @@ impl From<Director> for DirectorDto {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+/// Director list filters, extracted from the query string of `GET /cards/directors`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct SearchDirectorDto {
+    /// Exact director ID
     pub id: Option<i64>,
+    /// Substring match on the director name
     pub name: Option<String>,
+    /// Substring match on the director link
     pub link: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+#[cfg_attr(
+    feature = "graphql",
+    derive(async_graphql::InputObject),
+    graphql(name = "DirectorInput")
+)]
 pub struct CreateDirectorDto {
     #[validate(length(min = 1, message = "Name cannot be empty"))]
     pub name: String,
@@ -48,3 +63,25 @@ pub struct UpdateDirectorDto {
     pub link: Option<String>,
     pub manual: Option<bool>,
 }
+
+/// Partial update for `PATCH /cards/directors/{id}`. The ID comes from the
+/// path; omitted fields keep their stored values.
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema, Validate)]
+pub struct PatchDirectorDto {
+    #[validate(length(min = 1, message = "Name cannot be empty"))]
+    pub name: Option<String>,
+    pub link: Option<String>,
+    pub manual: Option<bool>,
+}
+
+impl PatchDirectorDto {
+    /// Build the shared update payload for the entity at `id`.
+    pub fn into_update(self, id: i64) -> UpdateDirectorDto {
+        UpdateDirectorDto {
+            id,
+            name: self.name,
+            link: self.link,
+            manual: self.manual,
+        }
+    }
+}
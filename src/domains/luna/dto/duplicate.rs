@@ -0,0 +1,83 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+/// Default trigram similarity two titles need to be reported as duplicates.
+pub const DEFAULT_DUPLICATE_SIMILARITY: f64 = 0.6;
+
+/// Default number of duplicate groups reported.
+pub const DEFAULT_DUPLICATE_GROUPS: u64 = 50;
+
+/// Most duplicate groups reported.
+pub const MAX_DUPLICATE_GROUPS: u64 = 500;
+
+/// Query parameters of `GET /cards/admin/duplicates`.
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DuplicateQuery {
+    /// Trigram similarity (0-1] records with the same idols need between
+    /// their titles (default [`DEFAULT_DUPLICATE_SIMILARITY`])
+    pub min_similarity: Option<f64>,
+    /// Number of groups, most confident first (default
+    /// [`DEFAULT_DUPLICATE_GROUPS`], at most [`MAX_DUPLICATE_GROUPS`])
+    pub limit: Option<u64>,
+}
+
+/// Query parameters of `GET /cards/admin/duplicate-images`.
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DuplicateImageQuery {
+    /// Number of groups, most copies first (default
+    /// [`DEFAULT_DUPLICATE_GROUPS`], at most [`MAX_DUPLICATE_GROUPS`])
+    pub limit: Option<u64>,
+}
+
+/// Why records were grouped as probable duplicates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicateReason {
+    /// Same title (ignoring case and surrounding spaces) and release date.
+    SameTitleAndDate,
+    /// Same set of idols and titles at least `min_similarity` alike.
+    SameIdolsSimilarTitle,
+}
+
+/// One record in a duplicate group.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DuplicateCandidateDto {
+    pub id: String,
+    pub title: String,
+    pub date: NaiveDate,
+}
+
+/// Records that probably describe the same release.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DuplicateGroupDto {
+    pub reason: DuplicateReason,
+    /// 1.0 for exact title and date matches; otherwise the lowest title
+    /// similarity among the pairs linking the group.
+    pub confidence: f64,
+    /// Candidates by ID.
+    pub records: Vec<DuplicateCandidateDto>,
+}
+
+/// One stored copy in a duplicate-image group.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DuplicateImageFileDto {
+    /// `record` or `idol`.
+    pub owner_type: String,
+    /// Record ID or idol name the copy belongs to.
+    pub owner_id: String,
+    pub filename: String,
+}
+
+/// Stored images with identical content.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DuplicateImageGroupDto {
+    /// Hex SHA-256 of the content.
+    pub sha256: String,
+    /// Size of each copy in bytes.
+    pub size: u64,
+    /// Copies by owner and file name.
+    pub files: Vec<DuplicateImageFileDto>,
+}
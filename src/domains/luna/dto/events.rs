@@ -0,0 +1,99 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::domains::search::SearchEntityType;
+
+/// SSE event name sent when events since `Last-Event-ID` cannot be replayed;
+/// the client should resync through `GET /cards/sync/records`.
+pub const CATALOG_RESET_EVENT: &str = "reset";
+
+/// What happened to the entity named in a [`CatalogEvent`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CatalogAction {
+    Created,
+    Updated,
+    Deleted,
+}
+
+impl CatalogAction {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Created => "created",
+            Self::Updated => "updated",
+            Self::Deleted => "deleted",
+        }
+    }
+}
+
+/// One catalog change, as delivered by `GET /cards/events`.
+///
+/// Events carry identifiers only; clients fetch the current state through the
+/// regular endpoints.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct CatalogEvent {
+    /// Kind of entity that changed.
+    pub entity: SearchEntityType,
+    /// Record ID, or the numeric ID of a card entity as a string.
+    pub id: String,
+    pub action: CatalogAction,
+    /// `record.permission` of a created or updated record.
+    #[serde(skip)]
+    pub permission: Option<i32>,
+}
+
+impl CatalogEvent {
+    /// SSE event name, e.g. `record.updated`.
+    pub fn name(&self) -> String {
+        format!("{}.{}", self.entity.as_str(), self.action.as_str())
+    }
+
+    /// The event as a caller with `clearance` sees it: a record above the
+    /// clearance is reported as deleted, as `GET /cards/sync/records` does.
+    pub fn for_clearance(mut self, clearance: i32) -> Self {
+        if self.permission.is_some_and(|p| p > clearance) {
+            self.action = CatalogAction::Deleted;
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CatalogAction, CatalogEvent, SearchEntityType};
+
+    fn record_event(permission: i32) -> CatalogEvent {
+        CatalogEvent {
+            entity: SearchEntityType::Record,
+            id: "ABC-001".to_owned(),
+            action: CatalogAction::Updated,
+            permission: Some(permission),
+        }
+    }
+
+    #[test]
+    fn name_joins_entity_and_action() {
+        assert_eq!(record_event(0).name(), "record.updated");
+    }
+
+    #[test]
+    fn records_above_clearance_read_as_deleted() {
+        assert_eq!(
+            record_event(2).for_clearance(1).action,
+            CatalogAction::Deleted
+        );
+        assert_eq!(
+            record_event(1).for_clearance(1).action,
+            CatalogAction::Updated
+        );
+    }
+
+    #[test]
+    fn permission_is_not_serialized() {
+        let json = serde_json::to_value(record_event(2)).expect("event serializes");
+        assert_eq!(
+            json,
+            serde_json::json!({"entity": "record", "id": "ABC-001", "action": "updated"})
+        );
+    }
+}
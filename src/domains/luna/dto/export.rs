@@ -0,0 +1,151 @@
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+/// Output format of the export endpoints.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    /// Comma-separated values with a header row.
+    #[default]
+    Csv,
+    /// One JSON object per line.
+    Jsonl,
+}
+
+impl ExportFormat {
+    /// `Content-Type` of the response body.
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Csv => "text/csv; charset=utf-8",
+            Self::Jsonl => "application/x-ndjson",
+        }
+    }
+
+    /// Format implied by an uploaded file's extension, if recognized.
+    pub fn from_file_name(name: &str) -> Option<Self> {
+        let extension = name.rsplit_once('.')?.1.to_ascii_lowercase();
+        match extension.as_str() {
+            "csv" => Some(Self::Csv),
+            "jsonl" | "ndjson" => Some(Self::Jsonl),
+            _ => None,
+        }
+    }
+
+    /// File extension used in the download file name.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Jsonl => "jsonl",
+        }
+    }
+}
+
+/// Query parameters for the `/cards/export/*` endpoints.
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ExportQuery {
+    /// `csv` (default) or `jsonl`.
+    pub format: Option<ExportFormat>,
+}
+
+/// Named card entities that can be exported, addressed by their plural path segment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportEntity {
+    Directors,
+    Genres,
+    Labels,
+    Studios,
+    Series,
+    Idols,
+}
+
+impl ExportEntity {
+    /// Database table holding the entity.
+    pub fn table(self) -> &'static str {
+        match self {
+            Self::Directors => "director",
+            Self::Genres => "genre",
+            Self::Labels => "label",
+            Self::Studios => "studio",
+            Self::Series => "series",
+            Self::Idols => "idol",
+        }
+    }
+
+    /// Path segment, also used as the download file name.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Directors => "directors",
+            Self::Genres => "genres",
+            Self::Labels => "labels",
+            Self::Studios => "studios",
+            Self::Series => "series",
+            Self::Idols => "idols",
+        }
+    }
+}
+
+/// Column order of record CSV exports. Multi-valued columns join their
+/// values with `|`.
+pub const RECORD_EXPORT_COLUMNS: &[&str] = &[
+    "id",
+    "title",
+    "date",
+    "duration",
+    "director",
+    "studio",
+    "label",
+    "series",
+    "genres",
+    "idols",
+    "links",
+    "permission",
+    "local_img_count",
+    "create_time",
+    "update_time",
+    "creator",
+    "modified_by",
+    "version",
+];
+
+/// Column order of named-entity CSV exports.
+pub const ENTITY_EXPORT_COLUMNS: &[&str] = &["id", "name", "link", "manual"];
+
+/// Quote a CSV field when it contains a delimiter, quote or line break
+/// (RFC 4180).
+pub fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_owned()
+    }
+}
+
+/// Join already-stringified fields into one CSV line, including the newline.
+pub fn csv_line<I, S>(fields: I) -> String
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut line = fields
+        .into_iter()
+        .map(|f| csv_field(f.as_ref()))
+        .collect::<Vec<_>>()
+        .join(",");
+    line.push('\n');
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_fields_are_quoted_only_when_needed() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_line(["1", "x\ny"]), "1,\"x\ny\"\n");
+    }
+}
@@ -0,0 +1,258 @@
+use chrono::{NaiveDate, NaiveTime, SecondsFormat};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use super::record::RecordDto;
+
+/// Entries in a feed when `limit` is omitted.
+pub const DEFAULT_FEED_LIMIT: u64 = 50;
+/// Most entries a feed returns.
+pub const MAX_FEED_LIMIT: u64 = 200;
+
+/// Value of `version` in JSON Feed documents.
+const JSON_FEED_VERSION: &str = "https://jsonfeed.org/version/1.1";
+/// Feed title shared by the Atom and JSON renderings.
+const FEED_TITLE: &str = "Lunirelust: recent records";
+/// Media type given to record links, whose targets are not known.
+const LINK_MIME_TYPE: &str = "application/octet-stream";
+
+/// Query parameters for the `/cards/feeds/recent.*` endpoints.
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct FeedQuery {
+    /// Maximum number of entries (default 50, at most 200).
+    pub limit: Option<u64>,
+}
+
+/// A JSON Feed 1.1 document; also the source of the Atom rendering.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct JsonFeed {
+    pub version: String,
+    pub title: String,
+    pub home_page_url: String,
+    pub feed_url: String,
+    pub items: Vec<JsonFeedItem>,
+}
+
+/// One record in a [`JsonFeed`].
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct JsonFeedItem {
+    pub id: String,
+    pub url: String,
+    pub title: String,
+    pub content_text: String,
+    /// Cover image, when the record has local images.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+    pub date_published: String,
+    pub date_modified: String,
+    /// Genre names.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// The record's links.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<JsonFeedAttachment>,
+}
+
+/// One record link in a [`JsonFeedItem`].
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct JsonFeedAttachment {
+    pub url: String,
+    pub mime_type: String,
+    pub title: String,
+}
+
+/// RFC 3339 timestamp for the start of `date` in UTC.
+fn rfc3339(date: NaiveDate) -> String {
+    date.and_time(NaiveTime::MIN)
+        .and_utc()
+        .to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+impl JsonFeed {
+    /// Build the feed of `records` with URLs rooted at `base_url` (scheme and
+    /// host, no trailing slash); `feed_url` is the URL the feed was fetched from.
+    /// Images link to thumbnails `thumbnail_width` pixels wide.
+    pub fn from_records(
+        records: Vec<RecordDto>,
+        base_url: &str,
+        feed_url: String,
+        thumbnail_width: u32,
+    ) -> Self {
+        let items = records
+            .into_iter()
+            .map(|record| {
+                let url = format!("{base_url}/cards/records/{}", record.id);
+                let genres: Vec<String> = record.genres.into_iter().map(|g| g.genre.name).collect();
+                let idols: Vec<String> = record.idols.into_iter().map(|i| i.idol.name).collect();
+                let mut content_text = format!(
+                    "Date: {}\nDirector: {}\nStudio: {}",
+                    record.date, record.director.name, record.studio.name
+                );
+                if !idols.is_empty() {
+                    content_text.push_str(&format!("\nIdols: {}", idols.join(", ")));
+                }
+                if !genres.is_empty() {
+                    content_text.push_str(&format!("\nGenres: {}", genres.join(", ")));
+                }
+
+                JsonFeedItem {
+                    title: format!("{} {}", record.id, record.title).trim().to_owned(),
+                    image: (record.local_img_count > 0).then(|| {
+                        format!("{base_url}/cards/media/{}?w={thumbnail_width}", record.id)
+                    }),
+                    date_published: rfc3339(record.create_time),
+                    date_modified: rfc3339(record.update_time),
+                    tags: genres,
+                    attachments: record
+                        .links
+                        .into_iter()
+                        .map(|link| JsonFeedAttachment {
+                            url: link.link,
+                            mime_type: LINK_MIME_TYPE.to_owned(),
+                            title: link.name,
+                        })
+                        .collect(),
+                    id: record.id,
+                    url,
+                    content_text,
+                }
+            })
+            .collect();
+
+        Self {
+            version: JSON_FEED_VERSION.to_owned(),
+            title: FEED_TITLE.to_owned(),
+            home_page_url: format!("{base_url}/cards/records"),
+            feed_url,
+            items,
+        }
+    }
+
+    /// Render the feed as an Atom (RFC 4287) document.
+    pub fn to_atom(&self) -> String {
+        // RFC 3339 strings in one format compare chronologically
+        let updated = self
+            .items
+            .iter()
+            .map(|item| item.date_modified.clone())
+            .max()
+            .unwrap_or_else(|| rfc3339(chrono::Utc::now().date_naive()));
+
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+        xml.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+        push_element(&mut xml, 1, "id", &self.feed_url);
+        push_element(&mut xml, 1, "title", &self.title);
+        push_element(&mut xml, 1, "updated", &updated);
+        xml.push_str("  <author><name>lunirelust</name></author>\n");
+        push_link(&mut xml, 1, "self", &self.feed_url, None);
+        push_link(&mut xml, 1, "alternate", &self.home_page_url, None);
+
+        for item in &self.items {
+            xml.push_str("  <entry>\n");
+            push_element(&mut xml, 2, "id", &item.url);
+            push_element(&mut xml, 2, "title", &item.title);
+            push_element(&mut xml, 2, "published", &item.date_published);
+            push_element(&mut xml, 2, "updated", &item.date_modified);
+            push_link(&mut xml, 2, "alternate", &item.url, None);
+            if let Some(image) = &item.image {
+                xml.push_str(&format!(
+                    "    <link rel=\"enclosure\" type=\"image/jpeg\" href=\"{}\"/>\n",
+                    escape_xml(image)
+                ));
+            }
+            for attachment in &item.attachments {
+                push_link(
+                    &mut xml,
+                    2,
+                    "related",
+                    &attachment.url,
+                    Some(&attachment.title),
+                );
+            }
+            for tag in &item.tags {
+                xml.push_str(&format!("    <category term=\"{}\"/>\n", escape_xml(tag)));
+            }
+            push_element(&mut xml, 2, "summary", &item.content_text);
+            xml.push_str("  </entry>\n");
+        }
+        xml.push_str("</feed>\n");
+        xml
+    }
+}
+
+fn push_element(xml: &mut String, depth: usize, name: &str, text: &str) {
+    xml.push_str(&format!(
+        "{}<{name}>{}</{name}>\n",
+        "  ".repeat(depth),
+        escape_xml(text)
+    ));
+}
+
+fn push_link(xml: &mut String, depth: usize, rel: &str, href: &str, title: Option<&str>) {
+    let title = title.map_or_else(String::new, |t| format!(" title=\"{}\"", escape_xml(t)));
+    xml.push_str(&format!(
+        "{}<link rel=\"{rel}\" href=\"{}\"{title}/>\n",
+        "  ".repeat(depth),
+        escape_xml(href)
+    ));
+}
+
+/// Escape text for XML content and attribute values.
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn atom_escapes_text_and_uses_latest_update() {
+        let item = |id: &str, modified: &str| JsonFeedItem {
+            id: id.to_owned(),
+            url: format!("http://host/cards/records/{id}"),
+            title: format!("{id} <Tom & Jerry>"),
+            content_text: String::new(),
+            image: None,
+            date_published: "2024-01-01T00:00:00Z".to_owned(),
+            date_modified: modified.to_owned(),
+            tags: vec!["a\"b".to_owned()],
+            attachments: Vec::new(),
+        };
+        let feed = JsonFeed {
+            version: JSON_FEED_VERSION.to_owned(),
+            title: FEED_TITLE.to_owned(),
+            home_page_url: "http://host/cards/records".to_owned(),
+            feed_url: "http://host/cards/feeds/recent.atom?limit=2&x=1".to_owned(),
+            items: vec![
+                item("a", "2024-03-01T00:00:00Z"),
+                item("b", "2024-05-01T00:00:00Z"),
+            ],
+        };
+
+        let atom = feed.to_atom();
+        assert!(atom.contains("<title>a &lt;Tom &amp; Jerry&gt;</title>"));
+        assert!(atom.contains("<category term=\"a&quot;b\"/>"));
+        assert!(atom.contains("href=\"http://host/cards/feeds/recent.atom?limit=2&amp;x=1\""));
+        assert!(atom.contains("  <updated>2024-05-01T00:00:00Z</updated>\n  <author>"));
+        assert_eq!(atom.matches("<entry>").count(), 2);
+    }
+
+    #[test]
+    fn rfc3339_is_midnight_utc() {
+        let date = NaiveDate::from_ymd_opt(2024, 2, 3).expect("valid date");
+        assert_eq!(rfc3339(date), "2024-02-03T00:00:00Z");
+    }
+}
@@ -1,16 +1,24 @@
 use serde::{Deserialize, Serialize};
-use utoipa::ToSchema;
+use utoipa::{IntoParams, ToSchema};
 use validator::Validate;
 
+use super::EntityCountDto;
 use crate::domains::luna::domain::{Genre, RecordGenre};
 
 // Genre DTOs
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[cfg_attr(
+    feature = "graphql",
+    derive(async_graphql::SimpleObject),
+    graphql(name = "Genre")
+)]
 pub struct GenreDto {
     pub id: i64,
     pub name: String,
     pub link: String,
     pub manual: bool,
+    /// Category the genre is grouped under.
+    pub category_id: Option<i64>,
 }
 
 impl From<Genre> for GenreDto {
@@ -20,18 +28,29 @@ impl From<Genre> for GenreDto {
             name: genre.name,
             link: genre.link,
             manual: genre.manual,
+            category_id: genre.category_id,
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+/// Genre list filters, extracted from the query string of `GET /cards/genres`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct SearchGenreDto {
+    /// Exact genre ID
     pub id: Option<i64>,
+    /// Substring match on the genre name
     pub name: Option<String>,
+    /// Substring match on the genre link
     pub link: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+#[cfg_attr(
+    feature = "graphql",
+    derive(async_graphql::InputObject),
+    graphql(name = "GenreInput")
+)]
 pub struct CreateGenreDto {
     #[validate(length(
         min = 1,
@@ -56,8 +75,82 @@ pub struct UpdateGenreDto {
     pub manual: Option<bool>,
 }
 
+/// Partial update for `PATCH /cards/genres/{id}`. The ID comes from the
+/// path; omitted fields keep their stored values.
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema, Validate)]
+pub struct PatchGenreDto {
+    #[validate(length(
+        min = 1,
+        max = 255,
+        message = "Name must be between 1 and 255 characters"
+    ))]
+    pub name: Option<String>,
+    pub link: Option<String>,
+    pub manual: Option<bool>,
+}
+
+impl PatchGenreDto {
+    /// Build the shared update payload for the entity at `id`.
+    pub fn into_update(self, id: i64) -> UpdateGenreDto {
+        UpdateGenreDto {
+            id,
+            name: self.name,
+            link: self.link,
+            manual: self.manual,
+        }
+    }
+}
+
+/// A group of genres, such as themes or formats.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct GenreCategoryDto {
+    pub id: i64,
+    pub name: String,
+    pub description: Option<String>,
+}
+
+/// Request body of `POST /cards/genres/categories` and
+/// `PUT /cards/genres/categories/{id}`.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, Validate)]
+pub struct CreateGenreCategoryDto {
+    /// Category name; names are unique.
+    #[validate(length(
+        min = 1,
+        max = 255,
+        message = "Name must be between 1 and 255 characters"
+    ))]
+    pub name: String,
+    pub description: Option<String>,
+}
+
+/// Body of `PUT /cards/genres/{id}/category`.
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct GenreCategoryAssignmentDto {
+    /// Category to put the genre in; `null` leaves it uncategorized.
+    pub category_id: Option<i64>,
+}
+
+/// Genre record counts of one category, from
+/// `GET /cards/genre-records-count/by-category`.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct GenreCategoryCountDto {
+    /// Category ID; `None` for the genres without a category.
+    pub id: Option<i64>,
+    /// Category name; `None` for the genres without a category.
+    pub name: Option<String>,
+    /// Records carrying any genre of the category, each counted once.
+    pub count: i64,
+    /// Genres of the category with their record counts, highest first.
+    pub genres: Vec<EntityCountDto>,
+}
+
 // Record related DTOs
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[cfg_attr(
+    feature = "graphql",
+    derive(async_graphql::SimpleObject),
+    graphql(name = "RecordGenre")
+)]
 pub struct RecordGenreDto {
     pub genre: GenreDto,
     pub manual: bool,
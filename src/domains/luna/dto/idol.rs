@@ -1,16 +1,28 @@
+use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
-use utoipa::ToSchema;
+use utoipa::{IntoParams, ToSchema};
 use validator::Validate;
 
 use crate::domains::luna::domain::{Idol, IdolParticipation};
 
 // Idol DTOs
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[cfg_attr(
+    feature = "graphql",
+    derive(async_graphql::SimpleObject),
+    graphql(name = "Idol")
+)]
 pub struct IdolDto {
     pub id: i64,
     pub name: String,
     pub link: String,
     pub manual: bool,
+    pub birthday: Option<NaiveDate>,
+    pub debut_date: Option<NaiveDate>,
+    pub height_cm: Option<i32>,
+    pub bust_cm: Option<i32>,
+    pub waist_cm: Option<i32>,
+    pub hips_cm: Option<i32>,
 }
 
 impl From<Idol> for IdolDto {
@@ -20,19 +32,36 @@ impl From<Idol> for IdolDto {
             name: idol.name,
             link: idol.link,
             manual: idol.manual,
+            birthday: idol.birthday,
+            debut_date: idol.debut_date,
+            height_cm: idol.height_cm,
+            bust_cm: idol.bust_cm,
+            waist_cm: idol.waist_cm,
+            hips_cm: idol.hips_cm,
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+/// Idol list filters, extracted from the query string of `GET /cards/idols`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct SearchIdolDto {
+    /// Exact idol ID
     pub id: Option<i64>,
+    /// Substring match on the idol name
     pub name: Option<String>,
+    /// Substring match on the idol link
     pub link: Option<String>,
-    pub search: Option<String>, // For search term parameter
+    /// Free-text search term, matched against the name, link and aliases
+    pub search: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+#[cfg_attr(
+    feature = "graphql",
+    derive(async_graphql::InputObject),
+    graphql(name = "IdolInput")
+)]
 pub struct CreateIdolDto {
     #[validate(length(
         min = 1,
@@ -57,10 +86,44 @@ pub struct UpdateIdolDto {
     pub manual: Option<bool>,
 }
 
+/// Partial update for `PATCH /cards/idols/{id}`. The ID comes from the
+/// path; omitted fields keep their stored values.
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema, Validate)]
+pub struct PatchIdolDto {
+    #[validate(length(
+        min = 1,
+        max = 255,
+        message = "Name must be between 1 and 255 characters"
+    ))]
+    pub name: Option<String>,
+    pub link: Option<String>,
+    pub manual: Option<bool>,
+}
+
+impl PatchIdolDto {
+    /// Build the shared update payload for the entity at `id`.
+    pub fn into_update(self, id: i64) -> UpdateIdolDto {
+        UpdateIdolDto {
+            id,
+            name: self.name,
+            link: self.link,
+            manual: self.manual,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[cfg_attr(
+    feature = "graphql",
+    derive(async_graphql::SimpleObject),
+    graphql(name = "IdolParticipation")
+)]
 pub struct IdolParticipationDto {
     pub idol: IdolDto,
     pub manual: bool,
+    /// Age of the idol on the record's release date, when the birthday is
+    /// known.
+    pub age_at_record: Option<i32>,
 }
 
 impl From<IdolParticipation> for IdolParticipationDto {
@@ -68,10 +131,34 @@ impl From<IdolParticipation> for IdolParticipationDto {
         Self {
             idol: IdolDto::from(idol_participation.idol),
             manual: idol_participation.manual,
+            age_at_record: idol_participation.age_at_record,
         }
     }
 }
 
+/// Profile of an idol for `GET` and `PUT /cards/idols/{id}/profile`. A `PUT`
+/// replaces the whole profile: omitted fields are cleared.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema, Validate)]
+pub struct IdolProfileDto {
+    pub birthday: Option<NaiveDate>,
+    /// Cannot be before the birthday.
+    pub debut_date: Option<NaiveDate>,
+    #[validate(range(min = 50, max = 250, message = "Height must be between 50 and 250 cm"))]
+    pub height_cm: Option<i32>,
+    #[validate(range(min = 30, max = 200, message = "Bust must be between 30 and 200 cm"))]
+    pub bust_cm: Option<i32>,
+    #[validate(range(min = 30, max = 200, message = "Waist must be between 30 and 200 cm"))]
+    pub waist_cm: Option<i32>,
+    #[validate(range(min = 30, max = 200, message = "Hips must be between 30 and 200 cm"))]
+    pub hips_cm: Option<i32>,
+    /// Other names the idol is known by, each up to 255 characters. Searches
+    /// find the idol under any of them. Stored trimmed and without
+    /// duplicates, sorted.
+    #[serde(default)]
+    #[validate(length(max = 50, message = "An idol can have at most 50 aliases"))]
+    pub aliases: Vec<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
 pub struct CreateIdolParticipationDto {
     pub idol_id: i64,
@@ -1,15 +1,17 @@
+use std::path::{Path, PathBuf};
+
 /// Image data structure for storing images
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug)]
 pub struct ImageData {
     /// Name of the image
     pub name: String,
     /// MIME type of the image
     pub mime: String,
-    /// Raw image bytes
-    pub bytes: Vec<u8>,
+    /// Image bytes, streamed to a temporary file
+    pub staged: StagedFile,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub struct UploadImageDto {
     /// The name of this image
     pub id: String,
@@ -17,3 +19,98 @@ pub struct UploadImageDto {
     /// The image files to upload
     pub files: Vec<ImageData>,
 }
+
+/// Image formats accepted for upload, recognized by their magic bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageKind {
+    Jpeg,
+    Png,
+    Webp,
+}
+
+impl ImageKind {
+    /// Bytes needed from the start of a file to recognize its format.
+    pub const SNIFF_LEN: usize = 12;
+
+    /// Recognizes the format from the first bytes of a file.
+    pub fn sniff(head: &[u8]) -> Option<Self> {
+        if head.starts_with(&[0xFF, 0xD8, 0xFF]) {
+            Some(Self::Jpeg)
+        } else if head.starts_with(b"\x89PNG\r\n\x1a\n") {
+            Some(Self::Png)
+        } else if head.len() >= Self::SNIFF_LEN
+            && head.starts_with(b"RIFF")
+            && &head[8..12] == b"WEBP"
+        {
+            Some(Self::Webp)
+        } else {
+            None
+        }
+    }
+
+    /// MIME type of the format.
+    pub fn mime(self) -> &'static str {
+        match self {
+            Self::Jpeg => "image/jpeg",
+            Self::Png => "image/png",
+            Self::Webp => "image/webp",
+        }
+    }
+
+    /// Extension files of this format are stored with.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Jpeg => "jpg",
+            Self::Png => "png",
+            Self::Webp => "webp",
+        }
+    }
+
+    /// Whether a declared `Content-Type` agrees with the format. A missing
+    /// type, sent as `application/octet-stream`, agrees with any format.
+    pub fn matches_mime(self, mime: &str) -> bool {
+        let mime = mime.trim().to_ascii_lowercase();
+        mime == "application/octet-stream"
+            || mime == self.mime()
+            || (self == Self::Jpeg && mime == "image/jpg")
+    }
+}
+
+/// An upload streamed to a temporary file. The file is deleted when this is
+/// dropped, unless [`StagedFile::persist`] moved it into place.
+#[derive(Debug)]
+pub struct StagedFile {
+    path: PathBuf,
+    persisted: bool,
+}
+
+impl StagedFile {
+    /// Takes ownership of the temporary file at `path`.
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            persisted: false,
+        }
+    }
+
+    /// Path of the temporary file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Renames the file to `target` in one step, so readers never see a
+    /// partial file. `target` must be on the same filesystem.
+    pub async fn persist(mut self, target: &Path) -> std::io::Result<()> {
+        tokio::fs::rename(&self.path, target).await?;
+        self.persisted = true;
+        Ok(())
+    }
+}
+
+impl Drop for StagedFile {
+    fn drop(&mut self) {
+        if !self.persisted {
+            drop(std::fs::remove_file(&self.path));
+        }
+    }
+}
@@ -0,0 +1,407 @@
+use super::{
+    CreateDirectorDto, CreateGenreDto, CreateIdolDto, CreateLabelDto, CreateLinkDto,
+    CreateRecordDto, CreateSeriesDto, CreateStudioDto, ExportFormat, RecordDto,
+    RECORD_EXPORT_COLUMNS,
+};
+use sea_orm::prelude::Date;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+/// Maximum number of data rows accepted by one import.
+pub const MAX_IMPORT_ROWS: usize = 10_000;
+
+/// How `POST /cards/import` treats rows whose record ID already exists.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportConflictMode {
+    /// Leave the stored record untouched and report the row as skipped.
+    #[default]
+    Skip,
+    /// Fully replace the stored record, including genres, idols and links.
+    Update,
+}
+
+/// Query parameters for `POST /cards/import`.
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ImportQuery {
+    /// `csv` or `jsonl`; inferred from the file name when omitted, else `csv`.
+    pub format: Option<ExportFormat>,
+    /// `skip` (default) or `update`.
+    #[serde(default)]
+    #[param(value_type = Option<ImportConflictMode>)]
+    pub on_conflict: ImportConflictMode,
+}
+
+/// Outcome of one imported row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportRowStatus {
+    Created,
+    Updated,
+    Skipped,
+    Error,
+}
+
+/// Report entry for one data row of the uploaded file.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ImportRowResult {
+    /// 1-based data row (CSV, excluding the header) or line (JSONL).
+    pub row: usize,
+    /// Record ID, when the row got far enough to name one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub status: ImportRowStatus,
+    /// Failure or skip reason.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// Response body of `POST /cards/import`.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ImportResponse {
+    pub created: usize,
+    pub updated: usize,
+    pub skipped: usize,
+    pub failed: usize,
+    pub rows: Vec<ImportRowResult>,
+}
+
+/// One parsed data row: its position and the record, or why it could not be read.
+#[derive(Debug)]
+pub struct ImportRow {
+    pub row: usize,
+    pub record: Result<CreateRecordDto, String>,
+}
+
+/// Parse an uploaded dump into rows. File-level problems (bad header,
+/// unterminated quote, too many rows) fail the whole import; row-level
+/// problems are reported per row.
+pub fn parse_import(format: ExportFormat, data: &str) -> Result<Vec<ImportRow>, String> {
+    let rows = match format {
+        ExportFormat::Csv => parse_csv_import(data)?,
+        ExportFormat::Jsonl => parse_jsonl_import(data),
+    };
+    if rows.is_empty() {
+        return Err("Import file contains no rows".to_owned());
+    }
+    if rows.len() > MAX_IMPORT_ROWS {
+        return Err(format!("Import cannot exceed {MAX_IMPORT_ROWS} rows"));
+    }
+    Ok(rows)
+}
+
+fn parse_jsonl_import(data: &str) -> Vec<ImportRow> {
+    data.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| ImportRow {
+            row: index + 1,
+            record: parse_jsonl_record(line),
+        })
+        .collect()
+}
+
+/// Accept both the export shape (`RecordDto`) and the create shape
+/// (`CreateRecordDto`) used by `POST /cards/records`.
+fn parse_jsonl_record(line: &str) -> Result<CreateRecordDto, String> {
+    if let Ok(record) = serde_json::from_str::<RecordDto>(line) {
+        return Ok(record.into());
+    }
+    serde_json::from_str::<CreateRecordDto>(line).map_err(|err| format!("Invalid record: {err}"))
+}
+
+fn parse_csv_import(data: &str) -> Result<Vec<ImportRow>, String> {
+    let mut lines = parse_csv(data)?.into_iter();
+    let header = lines.next().ok_or("Import file is empty")?;
+    for column in &header {
+        if !RECORD_EXPORT_COLUMNS.contains(&column.as_str()) {
+            return Err(format!("Unknown column '{column}'"));
+        }
+    }
+    for required in ["id", "title", "date"] {
+        if !header.iter().any(|c| c == required) {
+            return Err(format!("Missing required column '{required}'"));
+        }
+    }
+
+    Ok(lines
+        .enumerate()
+        .map(|(index, fields)| ImportRow {
+            row: index + 1,
+            record: csv_record(&header, &fields),
+        })
+        .collect())
+}
+
+fn csv_record(header: &[String], fields: &[String]) -> Result<CreateRecordDto, String> {
+    if fields.len() != header.len() {
+        return Err(format!(
+            "Expected {} fields, found {}",
+            header.len(),
+            fields.len()
+        ));
+    }
+    let get = |column: &str| {
+        header
+            .iter()
+            .position(|c| c == column)
+            .map_or("", |i| fields[i].as_str())
+    };
+    let number = |column: &str| -> Result<i32, String> {
+        let value = get(column).trim();
+        if value.is_empty() {
+            return Ok(0);
+        }
+        value
+            .parse()
+            .map_err(|_| format!("Invalid {column} '{value}'"))
+    };
+    let name = |column: &str| {
+        let value = get(column).trim();
+        (!value.is_empty()).then(|| value.to_owned())
+    };
+
+    let id = get("id").trim().to_owned();
+    if id.is_empty() {
+        return Err("Missing id".to_owned());
+    }
+    let date = get("date").trim();
+    let date = date
+        .parse::<Date>()
+        .map_err(|_| format!("Invalid date '{date}'"))?;
+    let links: Vec<CreateLinkDto> = split_multi(get("links"))
+        .into_iter()
+        .map(|link| CreateLinkDto {
+            name: String::new(),
+            size: None,
+            date: None,
+            link,
+            star: None,
+        })
+        .collect();
+
+    Ok(CreateRecordDto {
+        id,
+        title: get("title").to_owned(),
+        date,
+        duration: number("duration")?,
+        director: name("director").map(|name| CreateDirectorDto {
+            name,
+            link: None,
+            manual: None,
+        }),
+        studio: name("studio").map(|name| CreateStudioDto {
+            name,
+            link: None,
+            manual: None,
+        }),
+        label: name("label").map(|name| CreateLabelDto {
+            name,
+            link: None,
+            manual: None,
+        }),
+        series: name("series").map(|name| CreateSeriesDto {
+            name,
+            link: None,
+            manual: None,
+        }),
+        genres: split_multi(get("genres"))
+            .into_iter()
+            .map(|name| CreateGenreDto {
+                name,
+                link: None,
+                manual: None,
+            })
+            .collect(),
+        idols: split_multi(get("idols"))
+            .into_iter()
+            .map(|name| CreateIdolDto {
+                name,
+                link: None,
+                manual: None,
+            })
+            .collect(),
+        has_links: !links.is_empty(),
+        links,
+        permission: number("permission")?,
+        local_img_count: number("local_img_count")?,
+    })
+}
+
+/// Split a `|`-joined column into trimmed, non-empty, de-duplicated values.
+fn split_multi(value: &str) -> Vec<String> {
+    let mut values: Vec<String> = Vec::new();
+    for part in value.split('|').map(str::trim).filter(|p| !p.is_empty()) {
+        if !values.iter().any(|v| v == part) {
+            values.push(part.to_owned());
+        }
+    }
+    values
+}
+
+/// Split RFC 4180 text into lines of fields. Quoted fields may contain
+/// delimiters, doubled quotes and line breaks; blank lines are dropped.
+pub fn parse_csv(data: &str) -> Result<Vec<Vec<String>>, String> {
+    let mut lines = Vec::new();
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = data.trim_start_matches('\u{feff}').chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                _ => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' if field.is_empty() => in_quotes = true,
+            ',' => fields.push(std::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                fields.push(std::mem::take(&mut field));
+                if fields.len() > 1 || !fields[0].is_empty() {
+                    lines.push(std::mem::take(&mut fields));
+                } else {
+                    fields.clear();
+                }
+            }
+            _ => field.push(c),
+        }
+    }
+    if in_quotes {
+        return Err("Unterminated quoted field".to_owned());
+    }
+    if !field.is_empty() || !fields.is_empty() {
+        fields.push(field);
+        lines.push(fields);
+    }
+    Ok(lines)
+}
+
+impl From<RecordDto> for CreateRecordDto {
+    /// Rebuild the create payload of an exported record. References to the
+    /// `0` ("unknown") placeholder rows become `None`.
+    fn from(record: RecordDto) -> Self {
+        let link = |link: String| (!link.is_empty()).then_some(link);
+        Self {
+            id: record.id,
+            title: record.title,
+            date: record.date,
+            duration: record.duration,
+            director: (record.director.id != 0).then(|| CreateDirectorDto {
+                name: record.director.name,
+                link: link(record.director.link),
+                manual: Some(record.director.manual),
+            }),
+            studio: (record.studio.id != 0).then(|| CreateStudioDto {
+                name: record.studio.name,
+                link: link(record.studio.link),
+                manual: Some(record.studio.manual),
+            }),
+            label: (record.label.id != 0).then(|| CreateLabelDto {
+                name: record.label.name,
+                link: link(record.label.link),
+                manual: Some(record.label.manual),
+            }),
+            series: (record.series.id != 0).then(|| CreateSeriesDto {
+                name: record.series.name,
+                link: link(record.series.link),
+                manual: Some(record.series.manual),
+            }),
+            genres: record
+                .genres
+                .into_iter()
+                .map(|g| CreateGenreDto {
+                    name: g.genre.name,
+                    link: link(g.genre.link),
+                    manual: Some(g.manual),
+                })
+                .collect(),
+            idols: record
+                .idols
+                .into_iter()
+                .map(|i| CreateIdolDto {
+                    name: i.idol.name,
+                    link: link(i.idol.link),
+                    manual: Some(i.manual),
+                })
+                .collect(),
+            has_links: record.has_links,
+            links: record
+                .links
+                .into_iter()
+                .map(|l| CreateLinkDto {
+                    name: l.name,
+                    size: Some(l.size),
+                    date: Some(l.date),
+                    link: l.link,
+                    star: Some(l.star),
+                })
+                .collect(),
+            permission: record.permission,
+            local_img_count: record.local_img_count,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domains::luna::dto::csv_line;
+
+    #[test]
+    fn parse_csv_handles_quotes_and_line_breaks() {
+        let data = "a,b\r\n\"x, y\",\"say \"\"hi\"\"\nthere\"\n\n1,\n";
+        let lines = parse_csv(data).expect("valid csv");
+        assert_eq!(
+            lines,
+            vec![
+                vec!["a".to_owned(), "b".to_owned()],
+                vec!["x, y".to_owned(), "say \"hi\"\nthere".to_owned()],
+                vec!["1".to_owned(), String::new()],
+            ]
+        );
+        assert!(parse_csv("\"open").is_err());
+    }
+
+    #[test]
+    fn parse_csv_import_reads_exported_rows() {
+        let mut data = csv_line(["id", "title", "date", "genres", "links", "version"]);
+        data.push_str(&csv_line([
+            "r-1",
+            "A, B",
+            "2024-01-02",
+            "x|y|x",
+            "http://l",
+            "3",
+        ]));
+        data.push_str(&csv_line(["r-2", "C", "not a date", "", "", "1"]));
+
+        let rows = parse_import(ExportFormat::Csv, &data).expect("valid import");
+        assert_eq!(rows.len(), 2);
+        let first = rows[0].record.as_ref().expect("first row parses");
+        assert_eq!(first.title, "A, B");
+        assert_eq!(
+            first
+                .genres
+                .iter()
+                .map(|g| g.name.as_str())
+                .collect::<Vec<_>>(),
+            ["x", "y"]
+        );
+        assert!(first.has_links && first.director.is_none());
+        assert_eq!(rows[1].row, 2);
+        assert!(rows[1].record.is_err());
+
+        assert!(parse_import(ExportFormat::Csv, "id,title,date,bogus\n").is_err());
+        assert!(parse_import(ExportFormat::Csv, "id,title\nr,t\n").is_err());
+    }
+}
@@ -0,0 +1,107 @@
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+/// Default number of sample IDs reported per check.
+pub const DEFAULT_INTEGRITY_SAMPLE: u64 = 10;
+
+/// Most sample IDs reported per check.
+pub const MAX_INTEGRITY_SAMPLE: u64 = 100;
+
+/// Query parameters of `GET /cards/admin/integrity`.
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct IntegrityQuery {
+    /// Sample IDs per check, lowest first (default
+    /// [`DEFAULT_INTEGRITY_SAMPLE`], at most [`MAX_INTEGRITY_SAMPLE`])
+    pub sample: Option<u64>,
+}
+
+/// Live records failing one check.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct RecordIssueDto {
+    pub count: u64,
+    /// IDs of some of the records, lowest first.
+    pub sample_ids: Vec<String>,
+}
+
+/// Junction or link rows whose record or entity no longer exists.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct OrphanedRowsDto {
+    pub count: u64,
+    /// Row IDs of some of the orphans, lowest first.
+    pub sample_ids: Vec<i64>,
+}
+
+/// Live records still pointing at a placeholder entity (ID 0).
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct PlaceholderReferencesDto {
+    pub director: RecordIssueDto,
+    pub studio: RecordIssueDto,
+    pub label: RecordIssueDto,
+    pub series: RecordIssueDto,
+}
+
+/// Orphaned rows per table.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct OrphanedJunctionRowsDto {
+    pub record_genre: OrphanedRowsDto,
+    pub idol_participation: OrphanedRowsDto,
+    pub record_tag: OrphanedRowsDto,
+    pub links: OrphanedRowsDto,
+}
+
+/// Result of `POST /cards/admin/integrity/reconcile-images`.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ReconcileImagesResponse {
+    /// Live records whose media directory was counted.
+    pub scanned: u64,
+    /// Records whose `local_img_count` was corrected.
+    pub updated: u64,
+}
+
+/// Result of `GET /cards/admin/integrity`.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct IntegrityReportDto {
+    pub placeholder_references: PlaceholderReferencesDto,
+    /// Live records with `has_links` set but no link rows.
+    pub has_links_without_links: RecordIssueDto,
+    /// Live records whose `local_img_count` differs from the number of files
+    /// in their media directory.
+    pub local_img_count_mismatch: RecordIssueDto,
+    pub orphaned_rows: OrphanedJunctionRowsDto,
+}
+
+/// Query parameters of `POST /cards/admin/media-gc`.
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct MediaGcQuery {
+    /// Only report what would be removed (default `true`)
+    pub dry_run: Option<bool>,
+}
+
+/// Media directory whose record or idol no longer exists.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct OrphanedMediaDirDto {
+    /// Directory key, e.g. `images/record/ABC-123`.
+    pub key: String,
+    /// Regular files in the directory.
+    pub files: u64,
+    /// Total size of those files in bytes.
+    pub bytes: u64,
+}
+
+/// Result of `POST /cards/admin/media-gc`.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct MediaGcReportDto {
+    /// Whether this run only reported.
+    pub dry_run: bool,
+    /// Media directories with no record (trashed included) or idol of
+    /// their name, by key.
+    pub orphaned_dirs: Vec<OrphanedMediaDirDto>,
+    /// Keys of stored-file rows whose file is gone, by key.
+    pub missing_files: Vec<String>,
+    /// Directories removed; always `0` on a dry run.
+    pub deleted_dirs: u64,
+    /// Stored-file rows removed; always `0` on a dry run.
+    pub deleted_rows: u64,
+}
@@ -1,16 +1,23 @@
 use serde::{Deserialize, Serialize};
-use utoipa::ToSchema;
+use utoipa::{IntoParams, ToSchema};
 use validator::Validate;
 
 use crate::domains::luna::domain::Label;
 
 // Label DTOs
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[cfg_attr(
+    feature = "graphql",
+    derive(async_graphql::SimpleObject),
+    graphql(name = "Label")
+)]
 pub struct LabelDto {
     pub id: i64,
     pub name: String,
     pub link: String,
     pub manual: bool,
+    /// Studio that publishes the label.
+    pub studio_id: Option<i64>,
 }
 
 impl From<Label> for LabelDto {
@@ -20,18 +27,29 @@ impl From<Label> for LabelDto {
             name: label.name,
             link: label.link,
             manual: label.manual,
+            studio_id: label.studio_id,
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+/// Label list filters, extracted from the query string of `GET /cards/labels`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct SearchLabelDto {
+    /// Exact label ID
     pub id: Option<i64>,
+    /// Substring match on the label name
     pub name: Option<String>,
+    /// Substring match on the label link
     pub link: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+#[cfg_attr(
+    feature = "graphql",
+    derive(async_graphql::InputObject),
+    graphql(name = "LabelInput")
+)]
 pub struct CreateLabelDto {
     #[validate(length(min = 1, message = "Name cannot be empty"))]
     pub name: String,
@@ -47,3 +65,32 @@ pub struct UpdateLabelDto {
     pub link: Option<String>,
     pub manual: Option<bool>,
 }
+
+/// Partial update for `PATCH /cards/labels/{id}`. The ID comes from the
+/// path; omitted fields keep their stored values.
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema, Validate)]
+pub struct PatchLabelDto {
+    #[validate(length(min = 1, message = "Name cannot be empty"))]
+    pub name: Option<String>,
+    pub link: Option<String>,
+    pub manual: Option<bool>,
+}
+
+impl PatchLabelDto {
+    /// Build the shared update payload for the entity at `id`.
+    pub fn into_update(self, id: i64) -> UpdateLabelDto {
+        UpdateLabelDto {
+            id,
+            name: self.name,
+            link: self.link,
+            manual: self.manual,
+        }
+    }
+}
+
+/// Body of `PUT /cards/labels/{id}/studio`.
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct LabelStudioDto {
+    /// Studio publishing the label; `null` detaches it.
+    pub studio_id: Option<i64>,
+}
@@ -1,19 +1,70 @@
-use chrono::NaiveDate;
+use chrono::{DateTime, NaiveDate, Utc};
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
-use utoipa::ToSchema;
+use utoipa::{IntoParams, ToSchema};
 use validator::Validate;
 
 use crate::domains::luna::domain::Link;
 
+/// Name given to links created without one.
 // Keep deserialization aligned with the link-placeholder contract so omitted
 // names enter the system as the same sentinel used by update-mode backfill.
-fn default_link_name() -> String {
+pub fn default_link_name() -> String {
     "None".to_owned()
 }
 
+/// Query parameters dropped from http(s) link URLs besides `utm_*`: they
+/// only say where a visitor came from.
+const TRACKING_PARAMS: &[&str] = &[
+    "fbclid", "gclid", "dclid", "msclkid", "yclid", "igshid", "mc_cid", "mc_eid", "_ga",
+];
+
+/// Canonical form of a link URL: trimmed and, for http(s) URLs, without
+/// tracking query parameters. Other schemes (magnet links) only get trimmed.
+pub fn normalize_link_url(url: &str) -> String {
+    let url = url.trim();
+    let lower = url.to_ascii_lowercase();
+    if !lower.starts_with("http://") && !lower.starts_with("https://") {
+        return url.to_owned();
+    }
+    let (rest, fragment) = match url.split_once('#') {
+        Some((rest, fragment)) => (rest, Some(fragment)),
+        None => (url, None),
+    };
+    let Some((base, query)) = rest.split_once('?') else {
+        return url.to_owned();
+    };
+    let kept: Vec<&str> = query
+        .split('&')
+        .filter(|pair| !pair.is_empty() && !is_tracking_param(pair))
+        .collect();
+    let mut normalized = base.to_owned();
+    if !kept.is_empty() {
+        normalized.push('?');
+        normalized.push_str(&kept.join("&"));
+    }
+    if let Some(fragment) = fragment {
+        normalized.push('#');
+        normalized.push_str(fragment);
+    }
+    normalized
+}
+
+fn is_tracking_param(pair: &str) -> bool {
+    let key = pair
+        .split_once('=')
+        .map_or(pair, |(key, _)| key)
+        .to_ascii_lowercase();
+    key.starts_with("utm_") || TRACKING_PARAMS.contains(&key.as_str())
+}
+
 // Link DTOs
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[cfg_attr(
+    feature = "graphql",
+    derive(async_graphql::SimpleObject),
+    graphql(name = "Link")
+)]
 pub struct LinkDto {
     pub id: i64,
     pub record_id: String,
@@ -23,6 +74,17 @@ pub struct LinkDto {
     pub date: Date,
     pub link: String,
     pub star: bool,
+    /// Rank among the links of the record; higher comes first.
+    pub priority: i32,
+    /// Whether the last health check found the link unreachable.
+    pub dead: bool,
+    /// HTTP status of the last health check; unset when the request failed
+    /// or the link was never checked.
+    pub last_status: Option<i32>,
+    /// When the link was last checked.
+    pub last_checked_at: Option<DateTime<Utc>>,
+    /// Day the link was added to its record.
+    pub create_time: Date,
 }
 
 impl From<Link> for LinkDto {
@@ -35,13 +97,24 @@ impl From<Link> for LinkDto {
             date: link.date,
             link: link.link,
             star: link.star,
+            priority: link.priority,
+            dead: link.dead,
+            last_status: link.last_status,
+            last_checked_at: link.last_checked_at,
+            create_time: link.create_time,
         }
     }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema, Validate)]
+#[cfg_attr(
+    feature = "graphql",
+    derive(async_graphql::InputObject),
+    graphql(name = "LinkInput")
+)]
 pub struct CreateLinkDto {
     #[serde(default = "default_link_name")]
+    #[cfg_attr(feature = "graphql", graphql(default_with = "default_link_name()"))]
     #[validate(length(max = 255, message = "Name cannot exceed 255 characters"))]
     pub name: String,
     #[schema(value_type = String)]
@@ -53,6 +126,101 @@ pub struct CreateLinkDto {
     pub star: Option<bool>,
 }
 
+/// Partial link update for `PATCH /cards/records/{id}/links/{link_id}`.
+///
+/// Only fields present in the body are written. A new URL clears the
+/// health-check state of the link.
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema, Validate)]
+pub struct PatchLinkDto {
+    #[validate(length(max = 255, message = "Name cannot exceed 255 characters"))]
+    pub name: Option<String>,
+    #[schema(value_type = Option<String>)]
+    pub size: Option<Decimal>,
+    pub date: Option<Date>,
+    #[validate(length(min = 1, message = "Link URL cannot be empty"))]
+    pub link: Option<String>,
+    pub star: Option<bool>,
+    pub priority: Option<i32>,
+}
+
+/// Request body of `POST /cards/records/{id}/links/{link_id}/star`.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct StarLinkDto {
+    pub star: bool,
+}
+
+/// Why a link sent to `PATCH /cards/records/links/{id}` was not stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkSkipReason {
+    /// The URL is empty.
+    Blank,
+    /// The record or the request already has the URL, ignoring case.
+    DuplicateUrl,
+    /// The record or the request already has a link with the same name,
+    /// ignoring case, and size.
+    DuplicateNameSize,
+}
+
+/// A link that was not stored.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SkippedLinkDto {
+    /// The URL as sent.
+    pub link: String,
+    pub reason: LinkSkipReason,
+}
+
+/// Outcome of updating the links of a record, listing normalized URLs.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct LinkUpdateResultDto {
+    /// Links added.
+    pub inserted: Vec<String>,
+    /// Known links whose fields were filled in or changed.
+    pub updated: Vec<String>,
+    /// Links dropped because the request left them out (`replace` mode).
+    pub removed: Vec<String>,
+    /// Links sent but not stored.
+    pub skipped: Vec<SkippedLinkDto>,
+}
+
+impl LinkUpdateResultDto {
+    /// Whether any link was added, changed or removed.
+    pub fn changed(&self) -> bool {
+        !self.inserted.is_empty() || !self.updated.is_empty() || !self.removed.is_empty()
+    }
+}
+
+/// Outcome of a batch of link health checks.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct LinkCheckReportDto {
+    /// Links requested.
+    pub checked: u64,
+    /// Links found dead.
+    pub dead: u64,
+    /// Links that were dead before and answer again.
+    pub revived: u64,
+}
+
+/// Query parameters of `GET /cards/links`. Filters combine; links of trashed
+/// records and of records above the caller's permission level are left out.
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct LinkSearchQuery {
+    /// Only links whose name contains this text.
+    pub name: Option<String>,
+    /// Only links at least this large.
+    #[param(value_type = Option<String>)]
+    pub min_size: Option<Decimal>,
+    /// Only starred (`true`) or unstarred (`false`) links.
+    pub star: Option<bool>,
+    /// Only links dated on or after this day.
+    pub date_from: Option<NaiveDate>,
+    /// Links per page. Defaults to [`DEFAULT_PAGE_SIZE`](crate::common::config::DEFAULT_PAGE_SIZE).
+    pub limit: Option<u64>,
+    /// Number of matching links to skip. Defaults to 0.
+    pub offset: Option<u64>,
+}
+
 /// Deserialize optional date string into Option<Date>.
 /// - Missing field -> None
 /// - Empty string -> None
@@ -80,7 +248,28 @@ where
 
 #[cfg(test)]
 mod tests {
-    use super::CreateLinkDto;
+    use super::{normalize_link_url, CreateLinkDto};
+
+    #[test]
+    fn normalize_link_url_strips_tracking_params() {
+        assert_eq!(
+            normalize_link_url("  https://example.com/file?utm_source=x&id=1&FBCLID=y#part "),
+            "https://example.com/file?id=1#part"
+        );
+        assert_eq!(
+            normalize_link_url("https://example.com/file?utm_medium=feed"),
+            "https://example.com/file"
+        );
+        assert_eq!(
+            normalize_link_url("https://example.com/file"),
+            "https://example.com/file"
+        );
+        // Magnet parameters are data, not tracking
+        assert_eq!(
+            normalize_link_url(" magnet:?xt=urn:btih:abc&utm_source=x "),
+            "magnet:?xt=urn:btih:abc&utm_source=x"
+        );
+    }
 
     #[test]
     fn create_link_dto_defaults_missing_name_to_none_sentinel() {
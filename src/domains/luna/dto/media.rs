@@ -5,15 +5,71 @@ use utoipa::ToSchema;
 pub enum MediaType {
     RecordImage,
     IdolImage,
+    RecordVideo,
 }
 
 impl MediaType {
     pub fn get_sub_dir_name(&self) -> String {
         match self {
-            Self::RecordImage => "record".to_owned(),
+            Self::RecordImage | Self::RecordVideo => "record".to_owned(),
             Self::IdolImage => "idol".to_owned(),
         }
     }
+
+    /// Top-level media directory files of this type are kept under.
+    pub fn get_root_dir_name(&self) -> &'static str {
+        match self {
+            Self::RecordImage | Self::IdolImage => "images",
+            Self::RecordVideo => "videos",
+        }
+    }
+}
+
+/// Video formats accepted for upload, recognized by their magic bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VideoKind {
+    Mp4,
+    Webm,
+}
+
+impl VideoKind {
+    /// Extensions video files are stored with.
+    pub const EXTENSIONS: &'static [&'static str] = &["mp4", "webm"];
+
+    /// Recognizes the format from the first bytes of a file: an ISO media
+    /// `ftyp` box for MP4, the EBML header for WebM.
+    pub fn sniff(head: &[u8]) -> Option<Self> {
+        if head.len() >= 8 && &head[4..8] == b"ftyp" {
+            Some(Self::Mp4)
+        } else if head.starts_with(&[0x1A, 0x45, 0xDF, 0xA3]) {
+            Some(Self::Webm)
+        } else {
+            None
+        }
+    }
+
+    /// MIME type of the format.
+    pub fn mime(self) -> &'static str {
+        match self {
+            Self::Mp4 => "video/mp4",
+            Self::Webm => "video/webm",
+        }
+    }
+
+    /// Extension files of this format are stored with.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Mp4 => "mp4",
+            Self::Webm => "webm",
+        }
+    }
+
+    /// Whether a declared `Content-Type` agrees with the format. A missing
+    /// type, sent as `application/octet-stream`, agrees with any format.
+    pub fn matches_mime(self, mime: &str) -> bool {
+        let mime = mime.trim().to_ascii_lowercase();
+        mime == "application/octet-stream" || mime == self.mime()
+    }
 }
 
 /// DTO for media access request parameters
@@ -26,12 +82,26 @@ pub struct MediaAccessDto {
     /// Optional sequence number for the media file (e.g., 1, 2, 3...)
     /// If not provided, returns the default image (id.jpg)
     pub n: Option<u32>,
+    /// Serve a resized copy instead of the original file
+    #[serde(default)]
+    pub thumbnail: Option<ThumbnailSpec>,
 }
 
 impl MediaAccessDto {
     /// Creates a new `MediaAccessDto`
     pub fn new(id: String, media_type: MediaType, n: Option<u32>) -> Self {
-        Self { id, media_type, n }
+        Self {
+            id,
+            media_type,
+            n,
+            thumbnail: None,
+        }
+    }
+
+    /// Requests a thumbnail of the file instead of the original
+    pub fn with_thumbnail(mut self, thumbnail: Option<ThumbnailSpec>) -> Self {
+        self.thumbnail = thumbnail;
+        self
     }
 
     /// Generates the expected filename based on id and optional sequence number
@@ -43,3 +113,65 @@ impl MediaAccessDto {
         }
     }
 }
+
+/// One image available in a media directory.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct MediaFileDto {
+    /// File name, e.g. `ABC-123_2.jpg`
+    pub filename: String,
+    /// Sequence number to request the image with; `None` for the main image
+    pub n: Option<u32>,
+    /// File size in bytes
+    pub size: u64,
+    /// Width in pixels, read with the `thumbnails` feature
+    pub width: Option<u32>,
+    /// Height in pixels, read with the `thumbnails` feature
+    pub height: Option<u32>,
+    /// URL the image is served at
+    pub url: String,
+}
+
+/// How a thumbnail fills the box given by the requested width and height.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ThumbnailFit {
+    /// Scale to fit inside the box, keeping the aspect ratio.
+    #[default]
+    Contain,
+    /// Scale to cover the box, keeping the aspect ratio, and crop the overflow.
+    Cover,
+    /// Stretch to exactly the box.
+    Fill,
+}
+
+impl ThumbnailFit {
+    /// Name used in cached thumbnail file names.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Contain => "contain",
+            Self::Cover => "cover",
+            Self::Fill => "fill",
+        }
+    }
+}
+
+/// Requested thumbnail box. A missing side follows the aspect ratio of the
+/// original, so `cover` and `fill` only differ from `contain` when both are set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ThumbnailSpec {
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub fit: ThumbnailFit,
+}
+
+impl ThumbnailSpec {
+    /// Builds the spec from the `w`, `h` and `fit` query parameters; `None`
+    /// when neither side is given, which serves the original.
+    pub fn from_query(w: Option<u32>, h: Option<u32>, fit: Option<ThumbnailFit>) -> Option<Self> {
+        (w.is_some() || h.is_some()).then(|| Self {
+            width: w,
+            height: h,
+            fit: fit.unwrap_or_default(),
+        })
+    }
+}
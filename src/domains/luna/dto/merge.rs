@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+/// Request body of `POST /cards/{entities}/{id}/merge`.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct MergeEntityDto {
+    /// Duplicate entity folded into the path entity and then deleted.
+    pub source_id: i64,
+}
+
+/// Result of merging a duplicate named entity.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct MergeEntityResponse {
+    /// Surviving entity.
+    pub id: i64,
+    /// Deleted duplicate.
+    pub merged_id: i64,
+    /// Records re-pointed from the duplicate to the surviving entity.
+    pub records: u64,
+}
+
+/// Request body of `POST /cards/records/{id}/merge`.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct MergeRecordDto {
+    /// Duplicate record folded into the path record and then deleted.
+    pub source_id: String,
+}
+
+/// Query parameters of `DELETE /cards/{entities}/{id}`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, IntoParams)]
+pub struct DeleteEntityQuery {
+    /// Re-point the entity's records to this entity before deleting it.
+    /// Without it, deleting an entity that records still reference fails
+    /// with 409 and the blocking record IDs.
+    pub reassign_to: Option<i64>,
+}
@@ -0,0 +1,70 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use super::RecordDto;
+
+/// Record metadata as a provider reports it. Every field is optional, and
+/// empty lists mean the provider does not know the genres or idols.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct FetchedMetadata {
+    pub title: Option<String>,
+    pub date: Option<NaiveDate>,
+    /// Length in minutes.
+    pub duration: Option<i32>,
+    #[serde(default)]
+    pub genres: Vec<String>,
+    #[serde(default)]
+    pub idols: Vec<String>,
+    /// URL of the cover image.
+    pub cover: Option<String>,
+}
+
+impl FetchedMetadata {
+    /// Whether no field is known.
+    pub fn is_empty(&self) -> bool {
+        self.title.is_none()
+            && self.date.is_none()
+            && self.duration.is_none()
+            && self.genres.is_empty()
+            && self.idols.is_empty()
+            && self.cover.is_none()
+    }
+
+    /// Fills the fields still unknown from `other`.
+    pub fn fill_from(&mut self, other: Self) {
+        self.title = self.title.take().or(other.title);
+        self.date = self.date.or(other.date);
+        self.duration = self.duration.or(other.duration);
+        if self.genres.is_empty() {
+            self.genres = other.genres;
+        }
+        if self.idols.is_empty() {
+            self.idols = other.idols;
+        }
+        self.cover = self.cover.take().or(other.cover);
+    }
+}
+
+/// Query parameters of `POST /cards/records/{id}/refresh-metadata`.
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct RefreshMetadataQuery {
+    /// Replace the stored title, date and duration too; by default only a
+    /// blank title and a zero duration are filled in.
+    #[serde(default)]
+    pub overwrite: bool,
+}
+
+/// Response of `POST /cards/records/{id}/refresh-metadata`.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RefreshMetadataResponse {
+    /// The record after the merge.
+    pub record: RecordDto,
+    /// Providers that supplied metadata, in the order they were asked.
+    pub sources: Vec<String>,
+    /// Metadata combined from the providers.
+    pub fetched: FetchedMetadata,
+    /// Record fields the merge changed, e.g. `title` or `genres`.
+    pub updated: Vec<String>,
+}
@@ -27,13 +27,39 @@ pub struct PaginationQuery {
     /// When true, only return records the authenticated user has viewed.
     #[serde(default)]
     pub viewed_only: Option<bool>,
+
+    /// `true` keeps only records the authenticated user has seen, `false`
+    /// only those they have not.
+    #[serde(default)]
+    pub seen: Option<bool>,
+
+    /// Comma-separated sort keys, e.g. `-date,title`. A leading `-` sorts that
+    /// key descending. Allowed fields depend on the endpoint.
+    #[serde(default)]
+    pub ordering: Option<String>,
+
+    /// Opaque keyset cursor from a previous page's `next_cursor`. When present
+    /// (an empty value starts from the first page), record listing switches to
+    /// keyset pagination on `(date, id)` and `offset` is ignored. Only the
+    /// first keyset page reports `count`.
+    #[serde(default)]
+    pub cursor: Option<String>,
 }
 
+/// Fields accepted by `ordering` on named-entity list endpoints.
+pub const ENTITY_ORDERING_FIELDS: &[&str] = &["id", "name", "link"];
+
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct PaginatedResponse<T> {
+    /// Total number of matching items; `-1` on keyset pages after the first,
+    /// which skip counting.
     pub count: i64,
     pub next: Option<String>,
     pub previous: Option<String>,
+    /// Cursor for the next page in keyset mode; absent in offset mode or on
+    /// the last page.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
     pub results: Vec<T>,
 }
 
@@ -45,11 +71,18 @@ pub struct SearchQuery {
     pub pagination: PaginationQuery,
 }
 
-/// User context for filtering records by interaction status.
-/// Passed through service/repo layers when a user wants liked/viewed-only results.
+/// Caller context for filtering records by visibility and interaction status.
+/// Handlers always pass one; `None` (internal callers) skips both filters.
 #[derive(Debug, Clone)]
 pub struct UserFilter {
     pub user_id: String,
     pub liked_only: bool,
     pub viewed_only: bool,
+    /// Only records on the user's favorites list.
+    pub favorites_only: bool,
+    /// Only records the user has not seen.
+    pub unseen_only: bool,
+    /// Highest `record.permission` the caller may see
+    /// (see [`RecordPermission::clearance`](crate::domains::luna::RecordPermission::clearance)).
+    pub max_permission: i32,
 }
@@ -1,9 +1,9 @@
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
-use utoipa::ToSchema;
+use utoipa::{IntoParams, ToSchema};
 use validator::Validate;
 
-use crate::domains::luna::domain::Record;
+use crate::domains::luna::domain::{is_safe_media_id, Record, RecordRelationRows};
 
 use super::{
     director::DirectorDto,
@@ -30,10 +30,16 @@ pub struct RecordSlimDto {
     pub idols: Vec<String>,
     pub has_links: bool,
     pub links: Vec<LinkDto>,
+    /// URL of the cover image.
+    #[serde(default)]
+    pub cover_url: String,
     #[serde(default)]
     pub liked: bool,
     #[serde(default)]
     pub viewed: bool,
+    /// Whether the record is on the caller's favorites list.
+    #[serde(default)]
+    pub is_favorite: bool,
 }
 
 // Record DTOs
@@ -57,14 +63,189 @@ pub struct RecordDto {
     pub update_time: Date,
     pub creator: String,
     pub modified_by: String,
+    /// Current edit version; send it back in `If-Match` when updating.
+    pub version: i32,
+    /// URL of the trailer video, served with `Range` support; `None` until
+    /// one is uploaded.
+    #[serde(default)]
+    pub trailer: Option<String>,
+    /// Sequence number `n` of the image chosen as cover; `None` for the
+    /// main image.
+    #[serde(default)]
+    pub cover_index: Option<i32>,
+    /// URL of the cover image.
+    #[serde(default)]
+    pub cover_url: String,
+    /// Position within the series; `None` when not numbered.
+    #[serde(default)]
+    pub order_in_series: Option<i32>,
+    /// The record before this one in its series, as ordered by
+    /// `ordering=series_order`. Only set on `GET /cards/records/{id}`.
+    #[serde(default)]
+    pub series_previous: Option<SeriesNeighborDto>,
+    /// The record after this one in its series. Only set on
+    /// `GET /cards/records/{id}`.
+    #[serde(default)]
+    pub series_next: Option<SeriesNeighborDto>,
+    /// Average of the users' 1-10 scores; absent while unrated.
+    #[serde(default)]
+    pub rating_average: Option<f64>,
+    /// Number of users who rated the record.
+    #[serde(default)]
+    pub rating_count: i64,
+    /// Number of user comments on the record.
+    #[serde(default)]
+    pub comment_count: i64,
     #[serde(default)]
     pub liked: bool,
     #[serde(default)]
     pub viewed: bool,
+    /// Whether the record is on the caller's favorites list.
+    #[serde(default)]
+    pub is_favorite: bool,
+}
+
+/// Top-level `RecordDto` keys accepted by `fields` on record list endpoints.
+pub const RECORD_FIELDS: &[&str] = &[
+    "id",
+    "title",
+    "date",
+    "duration",
+    "director",
+    "studio",
+    "label",
+    "series",
+    "genres",
+    "idols",
+    "has_links",
+    "links",
+    "permission",
+    "local_img_count",
+    "create_time",
+    "update_time",
+    "creator",
+    "modified_by",
+    "version",
+    "trailer",
+    "cover_index",
+    "cover_url",
+    "order_in_series",
+    "rating_average",
+    "rating_count",
+    "comment_count",
+    "liked",
+    "viewed",
+    "is_favorite",
+];
+
+/// Which junction relations a record listing hydrates. Relations left out
+/// are not queried and come back as empty lists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecordRelations {
+    pub genres: bool,
+    pub idols: bool,
+    pub links: bool,
+}
+
+impl RecordRelations {
+    /// Load every relation.
+    pub const ALL: Self = Self {
+        genres: true,
+        idols: true,
+        links: true,
+    };
+}
+
+/// Genres, idols and links of one record, loaded apart from the record.
+#[derive(Debug, Clone, Default)]
+pub struct RecordRelationsDto {
+    pub genres: Vec<RecordGenreDto>,
+    pub idols: Vec<IdolParticipationDto>,
+    pub links: Vec<LinkDto>,
+}
+
+impl From<RecordRelationRows> for RecordRelationsDto {
+    fn from(rows: RecordRelationRows) -> Self {
+        Self {
+            genres: rows.genres.into_iter().map(RecordGenreDto::from).collect(),
+            idols: rows
+                .idols
+                .into_iter()
+                .map(IdolParticipationDto::from)
+                .collect(),
+            links: rows.links.into_iter().map(LinkDto::from).collect(),
+        }
+    }
+}
+
+/// `fields` projection for record list endpoints, extracted from the query string.
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct RecordFieldsQuery {
+    /// Comma-separated `RecordDto` keys to return, e.g. `id,title,date`.
+    /// `id` is always included. Omit for the full record.
+    pub fields: Option<String>,
+}
+
+/// Parsed `fields` projection. `None` keeps every field.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RecordFields(Option<Vec<String>>);
+
+impl RecordFields {
+    /// Parse and validate a `fields` value against [`RECORD_FIELDS`].
+    pub fn parse(raw: Option<&str>) -> Result<Self, String> {
+        let Some(raw) = raw else {
+            return Ok(Self(None));
+        };
+        let mut fields = vec!["id".to_owned()];
+        for field in raw.split(',').map(str::trim).filter(|f| !f.is_empty()) {
+            if !RECORD_FIELDS.contains(&field) {
+                return Err(format!(
+                    "Unknown field `{field}`; allowed: {}",
+                    RECORD_FIELDS.join(", ")
+                ));
+            }
+            if !fields.iter().any(|f| f == field) {
+                fields.push(field.to_owned());
+            }
+        }
+        Ok(Self(Some(fields)))
+    }
+
+    /// Whether `field` is part of the projection.
+    pub fn includes(&self, field: &str) -> bool {
+        self.0
+            .as_ref()
+            .is_none_or(|fields| fields.iter().any(|f| f == field))
+    }
+
+    /// The relations the projection needs loaded.
+    pub fn relations(&self) -> RecordRelations {
+        RecordRelations {
+            genres: self.includes("genres"),
+            idols: self.includes("idols"),
+            links: self.includes("links"),
+        }
+    }
+
+    /// Serialize `record`, keeping only the projected keys.
+    pub fn project(&self, record: &RecordDto) -> serde_json::Value {
+        let mut value = serde_json::to_value(record).unwrap_or_default();
+        if let (Some(fields), Some(object)) = (&self.0, value.as_object_mut()) {
+            object.retain(|key, _| fields.iter().any(|f| f == key));
+        }
+        value
+    }
+
+    /// Whether every field is kept.
+    pub fn is_full(&self) -> bool {
+        self.0.is_none()
+    }
 }
 
 impl From<Record> for RecordDto {
     fn from(record: Record) -> Self {
+        let cover = cover_url(&record.id, record.cover_index);
         Self {
             id: record.id,
             title: record.title,
@@ -92,14 +273,26 @@ impl From<Record> for RecordDto {
             update_time: record.update_time,
             creator: record.creator,
             modified_by: record.modified_by,
+            version: record.version,
+            trailer: record.trailer,
+            cover_url: cover,
+            cover_index: record.cover_index,
+            order_in_series: record.order_in_series,
+            series_previous: None,
+            series_next: None,
+            rating_average: record.rating_average,
+            rating_count: record.rating_count,
+            comment_count: record.comment_count,
             liked: false,
             viewed: false,
+            is_favorite: false,
         }
     }
 }
 
 impl From<Record> for RecordSlimDto {
     fn from(record: Record) -> Self {
+        let cover = cover_url(&record.id, record.cover_index);
         Self {
             id: record.id,
             title: record.title,
@@ -113,30 +306,233 @@ impl From<Record> for RecordSlimDto {
             idols: record.idols.into_iter().map(|ip| ip.idol.name).collect(),
             has_links: record.has_links,
             links: record.links.into_iter().map(LinkDto::from).collect(),
+            cover_url: cover,
             liked: false,
             viewed: false,
+            is_favorite: false,
         }
     }
 }
 
-#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+/// URL of the image chosen as cover of record `id`: the main image, or
+/// image `n` when `cover_index` is set.
+pub fn cover_url(id: &str, cover_index: Option<i32>) -> String {
+    match cover_index {
+        Some(n) => format!("/cards/media/{id}?n={n}"),
+        None => format!("/cards/media/{id}"),
+    }
+}
+
+/// Request body of `PUT /cards/records/{id}/cover`.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SetRecordCoverDto {
+    /// Sequence number of the uploaded image to use as cover; `null` for the
+    /// main image.
+    pub n: Option<u32>,
+}
+
+/// Request body of `PUT /cards/records/{id}/series-order`.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, Validate)]
+pub struct SetSeriesOrderDto {
+    /// Position of the record within its series; `null` to order it by date
+    /// after the numbered records.
+    #[validate(range(min = 1, message = "Series order must be at least 1"))]
+    pub order_in_series: Option<i32>,
+}
+
+/// A record next to another in their series.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct SeriesNeighborDto {
+    pub id: String,
+    pub title: String,
+}
+
+/// Keyset position for cursor pagination over records ordered by
+/// `(date DESC, id ASC)`: the last record of the previous page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordCursor {
+    pub date: Date,
+    pub id: String,
+}
+
+impl RecordCursor {
+    /// Encode as an opaque, URL-safe token (hex of `date|id`).
+    pub fn encode(&self) -> String {
+        format!("{}|{}", self.date.format("%Y-%m-%d"), self.id)
+            .bytes()
+            .map(|b| format!("{b:02x}"))
+            .collect()
+    }
+
+    /// Decode a token produced by [`RecordCursor::encode`]. Returns `None`
+    /// for anything malformed.
+    pub fn decode(raw: &str) -> Option<Self> {
+        if raw.len() % 2 != 0 {
+            return None;
+        }
+        let bytes = (0..raw.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(raw.get(i..i + 2)?, 16).ok())
+            .collect::<Option<Vec<u8>>>()?;
+        let text = String::from_utf8(bytes).ok()?;
+        let (date, id) = text.split_once('|')?;
+        let date = Date::parse_from_str(date, "%Y-%m-%d").ok()?;
+        Some(Self {
+            date,
+            id: id.to_string(),
+        })
+    }
+}
+
+/// Fields accepted by `ordering` on record list endpoints.
+pub const RECORD_ORDERING_FIELDS: &[&str] = &[
+    "id",
+    "title",
+    "date",
+    "duration",
+    "create_time",
+    "update_time",
+    "rating",
+    "series_order",
+];
+
+/// How a multi-valued junction filter (`genre_ids`, `idol_ids`, `tag_ids`)
+/// combines its IDs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[cfg_attr(feature = "graphql", derive(async_graphql::Enum))]
+#[serde(rename_all = "snake_case")]
+pub enum MatchMode {
+    /// Records linked to at least one of the IDs.
+    #[default]
+    Any,
+    /// Records linked to every one of the IDs.
+    All,
+}
+
+/// Parse a comma-separated ID list, skipping empty entries and duplicates.
+fn parse_id_list(name: &str, raw: Option<&str>) -> Result<Vec<i64>, String> {
+    let mut ids = Vec::new();
+    for part in raw.unwrap_or_default().split(',').map(str::trim) {
+        if part.is_empty() {
+            continue;
+        }
+        let id = part
+            .parse::<i64>()
+            .map_err(|_| format!("{name} must be a comma-separated list of IDs, got `{part}`"))?;
+        if !ids.contains(&id) {
+            ids.push(id);
+        }
+    }
+    Ok(ids)
+}
+
+/// Record list filters, extracted from the query string of `GET /cards/records`.
+///
+/// Every field is optional; filters that are present are combined with `AND`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct SearchRecordDto {
+    /// Substring match on the record ID
     pub id: Option<String>,
+    /// Substring match on the record title
     pub title: Option<String>,
+    /// Exact director ID
     pub director_id: Option<i64>,
+    /// Exact studio ID
     pub studio_id: Option<i64>,
+    /// Exact label ID
     pub label_id: Option<i64>,
+    /// Exact series ID
     pub series_id: Option<i64>,
-    pub search: Option<String>, // For search term parameter
+    /// Restrict to records tagged with this genre (via `record_genre`).
+    pub genre_id: Option<i64>,
+    /// Restrict to records with a genre of this genre category.
+    pub genre_category_id: Option<i64>,
+    /// Restrict to records featuring this idol (via `idol_participation`).
+    pub idol_id: Option<i64>,
+    /// Comma-separated genre IDs, combined according to `match`.
+    pub genre_ids: Option<String>,
+    /// Comma-separated idol IDs, combined according to `match`.
+    pub idol_ids: Option<String>,
+    /// Comma-separated tag IDs, combined according to `match`.
+    pub tag_ids: Option<String>,
+    /// `any` (default) or `all`: whether `genre_ids` / `idol_ids` / `tag_ids`
+    /// require one or every listed ID.
+    #[serde(rename = "match")]
+    pub match_mode: Option<MatchMode>,
+    /// Free-text search term
+    pub search: Option<String>,
+    /// Release date range start (inclusive, `YYYY-MM-DD`)
+    pub date_from: Option<Date>,
+    /// Release date range end (inclusive, `YYYY-MM-DD`)
+    pub date_to: Option<Date>,
+    /// Only records last updated on or after this day (`YYYY-MM-DD`), for
+    /// incremental sync
+    pub modified_since: Option<Date>,
+    /// Only records whose average rating is at least this (1-10)
+    pub min_rating: Option<f64>,
+}
+
+impl SearchRecordDto {
+    /// Rejects an inverted release date range.
+    pub fn validate_date_range(&self) -> Result<(), String> {
+        match (self.date_from, self.date_to) {
+            (Some(from), Some(to)) if from > to => Err(format!(
+                "date_from ({from}) must not be after date_to ({to})"
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Parsed `genre_ids`.
+    pub fn genre_id_list(&self) -> Result<Vec<i64>, String> {
+        parse_id_list("genre_ids", self.genre_ids.as_deref())
+    }
+
+    /// Parsed `idol_ids`.
+    pub fn idol_id_list(&self) -> Result<Vec<i64>, String> {
+        parse_id_list("idol_ids", self.idol_ids.as_deref())
+    }
+
+    /// Parsed `tag_ids`.
+    pub fn tag_id_list(&self) -> Result<Vec<i64>, String> {
+        parse_id_list("tag_ids", self.tag_ids.as_deref())
+    }
+
+    /// Rejects malformed `genre_ids` / `idol_ids` / `tag_ids` lists.
+    pub fn validate_id_lists(&self) -> Result<(), String> {
+        self.genre_id_list()?;
+        self.idol_id_list()?;
+        self.tag_id_list()?;
+        Ok(())
+    }
+}
+
+/// Rejects record IDs that cannot name their media directory, such as `.`.
+fn validate_record_id(id: &str) -> Result<(), validator::ValidationError> {
+    if is_safe_media_id(id) {
+        Ok(())
+    } else {
+        Err(validator::ValidationError::new("record_id")
+            .with_message("ID cannot be '.' or contain '..', '/', '\\' or NUL".into()))
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+#[cfg_attr(
+    feature = "graphql",
+    derive(async_graphql::InputObject),
+    graphql(name = "RecordInput")
+)]
 pub struct CreateRecordDto {
-    #[validate(length(
-        min = 1,
-        max = 255,
-        message = "ID must be between 1 and 255 characters"
-    ))]
+    #[validate(
+        length(
+            min = 1,
+            max = 255,
+            message = "ID must be between 1 and 255 characters"
+        ),
+        custom(function = "validate_record_id")
+    )]
     pub id: String,
     #[validate(length(max = 1024, message = "Title cannot exceed 1024 characters"))]
     pub title: String,
@@ -148,12 +544,13 @@ pub struct CreateRecordDto {
     pub series: Option<CreateSeriesDto>,
     pub genres: Vec<CreateGenreDto>,
     pub idols: Vec<CreateIdolDto>,
+    /// Ignored: the flag follows whether the record has links.
+    #[serde(default)]
+    #[cfg_attr(feature = "graphql", graphql(default))]
     pub has_links: bool,
     pub links: Vec<CreateLinkDto>,
     pub permission: i32,
     pub local_img_count: i32,
-    pub creator: String,
-    pub modified_by: String,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
@@ -168,14 +565,293 @@ pub struct UpdateRecordDto {
     pub series_id: i64,
     pub genres: Vec<UpdateGenreDto>,
     pub idols: Vec<CreateIdolParticipationDto>,
+    /// Ignored: the flag follows whether the record has links.
+    #[serde(default)]
     pub has_links: bool,
     pub links: Vec<CreateLinkDto>,
     pub permission: i32,
     pub local_img_count: i32,
-    pub modified_by: String,
+}
+
+/// Partial record update for `PATCH /cards/records/{id}`.
+///
+/// Only fields present in the body are written; omitted fields keep their
+/// stored values. Genres, idols and links are not touched.
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema, Validate)]
+#[cfg_attr(
+    feature = "graphql",
+    derive(async_graphql::InputObject),
+    graphql(name = "RecordPatch")
+)]
+pub struct PatchRecordDto {
+    #[validate(length(max = 1024, message = "Title cannot exceed 1024 characters"))]
+    pub title: Option<String>,
+    pub date: Option<Date>,
+    pub duration: Option<i32>,
+    pub director_id: Option<i64>,
+    pub studio_id: Option<i64>,
+    pub label_id: Option<i64>,
+    pub series_id: Option<i64>,
+    /// Ignored: the flag follows whether the record has links.
+    pub has_links: Option<bool>,
+    pub permission: Option<i32>,
+    pub local_img_count: Option<i32>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
 pub struct UpdateRecordLinksDto {
     pub links: Vec<CreateLinkDto>,
 }
+
+/// Maximum number of records accepted by one bulk request.
+pub const MAX_BULK_RECORDS: usize = 500;
+
+/// Failure handling for `POST /cards/records/bulk`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BulkCreateMode {
+    /// Any failing item rolls back the whole batch.
+    #[default]
+    Atomic,
+    /// Each item runs in its own savepoint; failures are reported and the
+    /// successful items are still committed.
+    Partial,
+}
+
+/// Query parameters for `POST /cards/records/bulk`.
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct BulkCreateQuery {
+    /// `atomic` (default) or `partial`.
+    #[serde(default)]
+    #[param(value_type = Option<BulkCreateMode>)]
+    pub mode: BulkCreateMode,
+}
+
+/// How `PATCH /cards/records/links/{id}` applies the links it is sent.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkUpdateMode {
+    /// New URLs are added and placeholder fields of known ones filled in.
+    #[default]
+    Append,
+    /// The links become exactly the ones sent, keyed by URL: others are
+    /// removed and known ones updated in place.
+    Replace,
+}
+
+/// Query parameters for `PATCH /cards/records/links/{id}`.
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct UpdateRecordLinksQuery {
+    /// `append` (default) or `replace`.
+    #[serde(default)]
+    #[param(value_type = Option<LinkUpdateMode>)]
+    pub mode: LinkUpdateMode,
+}
+
+/// Query parameters for `PUT /cards/records/{id}/full`.
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ReplaceRecordQuery {
+    /// Also drop genre and idol associations marked manual that the body
+    /// leaves out; by default they are kept.
+    #[serde(default)]
+    pub override_manual: bool,
+}
+
+/// Outcome of a single item in a bulk request.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct BulkItemResult {
+    /// Position of the item in the request body.
+    pub index: usize,
+    pub id: String,
+    pub success: bool,
+    /// Failure reason; absent on success.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Idol names of the item that were aliases of known idols; absent when
+    /// none were.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub resolved_idols: Vec<ResolvedIdolDto>,
+}
+
+/// An idol name in a record body that no idol has but one idol is known by
+/// as an alias, and the idol the record was linked to instead.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ResolvedIdolDto {
+    /// The name as submitted.
+    pub name: String,
+    pub idol_id: i64,
+    /// The name of the idol it resolved to.
+    pub idol_name: String,
+}
+
+/// Response body of `POST /cards/records/bulk`.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct BulkCreateResponse {
+    pub mode: BulkCreateMode,
+    pub created: usize,
+    pub failed: usize,
+    pub results: Vec<BulkItemResult>,
+}
+
+/// Request body of `DELETE /cards/records`. Records are moved to the trash
+/// unless `purge` is set.
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+pub struct BulkDeleteRecordsDto {
+    #[validate(length(
+        min = 1,
+        max = 500,
+        message = "Between 1 and 500 record IDs are required"
+    ))]
+    pub ids: Vec<String>,
+    /// Permanently delete the records, trashed or not, instead of trashing
+    /// them. Defaults to `false`.
+    #[serde(default)]
+    pub purge: bool,
+    /// Also remove each record's images, trailer and cached thumbnails;
+    /// requires `purge`.
+    /// Defaults to `false`.
+    #[serde(default)]
+    pub delete_media: bool,
+}
+
+/// Row counts removed by `DELETE /cards/records`.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct BulkDeleteRecordsResponse {
+    /// Records moved to the trash, or permanently deleted with `purge`.
+    pub records: u64,
+    /// Junction and link rows below are only removed by `purge`.
+    pub record_genres: u64,
+    pub idol_participations: u64,
+    pub links: u64,
+    /// Image and video directories removed; always `0` unless `delete_media`
+    /// was set.
+    pub media_dirs: u64,
+    /// Requested IDs that did not exist, or were already trashed when not
+    /// purging.
+    pub not_found: Vec<String>,
+}
+
+/// Request body of `POST /cards/records/exists`.
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+pub struct RecordExistsDto {
+    #[validate(length(
+        min = 1,
+        max = 500,
+        message = "Between 1 and 500 record IDs are required"
+    ))]
+    pub ids: Vec<String>,
+}
+
+/// Response of `POST /cards/records/exists`.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RecordExistsResponse {
+    /// Requested IDs that already exist, in request order.
+    pub existing: Vec<String>,
+}
+
+/// Records returned by `GET /cards/records/random` when `count` is omitted.
+pub const DEFAULT_RANDOM_RECORDS: u64 = 1;
+/// Most records `GET /cards/records/random` returns.
+pub const MAX_RANDOM_RECORDS: u64 = 50;
+
+/// Query parameters of `GET /cards/records/random`.
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct RandomRecordsQuery {
+    /// Number of records (default 1, at most 50).
+    pub count: Option<u64>,
+    /// Only records tagged with this genre.
+    pub genre_id: Option<i64>,
+    /// Only records featuring this idol.
+    pub idol_id: Option<i64>,
+}
+
+/// Records returned by `GET /cards/records/{id}/similar` when `limit` is omitted.
+pub const DEFAULT_SIMILAR_RECORDS: u64 = 10;
+/// Most records `GET /cards/records/{id}/similar` returns.
+pub const MAX_SIMILAR_RECORDS: u64 = 50;
+
+/// Query parameters of `GET /cards/records/{id}/similar`.
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SimilarRecordsQuery {
+    /// Number of records (default 10, at most 50).
+    pub limit: Option<u64>,
+}
+
+/// A record similar to the requested one, with its similarity score.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SimilarRecordDto {
+    #[serde(flatten)]
+    pub record: RecordSlimDto,
+    /// Sum of the configured weights of the idols, genres, series and studio
+    /// both records share.
+    pub score: f64,
+}
+
+/// A record in the caller's seen history.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SeenRecordDto {
+    #[serde(flatten)]
+    pub record: RecordDto,
+    /// When the caller last marked the record seen.
+    pub seen_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_cursor_round_trips() {
+        let cursor = RecordCursor {
+            date: Date::from_ymd_opt(2025, 8, 11).unwrap(),
+            id: "ABC-123|x".to_string(),
+        };
+        assert_eq!(RecordCursor::decode(&cursor.encode()), Some(cursor));
+    }
+
+    #[test]
+    fn record_cursor_rejects_malformed_tokens() {
+        assert_eq!(RecordCursor::decode("zz"), None);
+        assert_eq!(RecordCursor::decode("abc"), None);
+        assert_eq!(RecordCursor::decode("6e6f2d736570617261746f72"), None);
+    }
+
+    #[test]
+    fn id_lists_parse_and_dedupe() {
+        let dto = SearchRecordDto {
+            genre_ids: Some("3, 1,,3".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(dto.genre_id_list(), Ok(vec![3, 1]));
+        assert_eq!(dto.idol_id_list(), Ok(vec![]));
+        assert_eq!(dto.tag_id_list(), Ok(vec![]));
+
+        let bad = SearchRecordDto {
+            idol_ids: Some("1,two".to_string()),
+            ..Default::default()
+        };
+        assert!(bad.validate_id_lists().is_err());
+    }
+
+    #[test]
+    fn record_fields_parse_and_project() {
+        assert!(RecordFields::parse(None).unwrap().is_full());
+        assert!(RecordFields::parse(Some("id,nope")).is_err());
+
+        let fields = RecordFields::parse(Some("title, genres")).unwrap();
+        assert!(fields.includes("id"));
+        assert_eq!(
+            fields.relations(),
+            RecordRelations {
+                genres: true,
+                idols: false,
+                links: false,
+            }
+        );
+    }
+}
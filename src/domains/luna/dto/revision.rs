@@ -0,0 +1,22 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::RecordDto;
+
+/// One entry of `GET /cards/records/{id}/history`: the state of the record
+/// before an edit, and who made the edit.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RecordRevisionDto {
+    pub id: i64,
+    pub record_id: String,
+    /// Version of the record the snapshot shows.
+    pub version: i32,
+    /// User ID of the caller whose edit replaced this state.
+    pub actor: String,
+    /// When the edit was made.
+    pub created_at: DateTime<Utc>,
+    /// The record as it was before the edit. Per-user fields (ratings,
+    /// favorites, interactions) are not kept.
+    pub snapshot: RecordDto,
+}
@@ -0,0 +1,40 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use super::SearchRecordDto;
+
+/// Request body of `POST /cards/saved-searches` and
+/// `PUT /cards/saved-searches/{id}`.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, Validate)]
+pub struct CreateSavedSearchDto {
+    #[validate(length(
+        min = 1,
+        max = 255,
+        message = "Name must be between 1 and 255 characters"
+    ))]
+    pub name: String,
+    /// Record filters, as accepted in the query string of `GET /cards/records`.
+    #[serde(default)]
+    pub filters: SearchRecordDto,
+    /// Sort keys, e.g. `-date,title`; the default record order when absent.
+    #[serde(default)]
+    pub ordering: Option<String>,
+    /// Whether every user may see and run the search.
+    #[serde(default)]
+    pub is_public: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SavedSearchDto {
+    pub id: i64,
+    /// ID of the user who saved the search; only they may change it.
+    pub owner_id: String,
+    pub name: String,
+    pub filters: SearchRecordDto,
+    pub ordering: Option<String>,
+    pub is_public: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
@@ -1,11 +1,16 @@
 use serde::{Deserialize, Serialize};
-use utoipa::ToSchema;
+use utoipa::{IntoParams, ToSchema};
 use validator::Validate;
 
 use crate::domains::luna::domain::Series;
 
 // Series DTOs
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[cfg_attr(
+    feature = "graphql",
+    derive(async_graphql::SimpleObject),
+    graphql(name = "Series")
+)]
 pub struct SeriesDto {
     pub id: i64,
     pub name: String,
@@ -24,14 +29,24 @@ impl From<Series> for SeriesDto {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+/// Series list filters, extracted from the query string of `GET /cards/series`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct SearchSeriesDto {
+    /// Exact series ID
     pub id: Option<i64>,
+    /// Substring match on the series name
     pub name: Option<String>,
+    /// Substring match on the series link
     pub link: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+#[cfg_attr(
+    feature = "graphql",
+    derive(async_graphql::InputObject),
+    graphql(name = "SeriesInput")
+)]
 pub struct CreateSeriesDto {
     #[validate(length(min = 1, message = "Name cannot be empty"))]
     pub name: String,
@@ -47,3 +62,25 @@ pub struct UpdateSeriesDto {
     pub link: Option<String>,
     pub manual: Option<bool>,
 }
+
+/// Partial update for `PATCH /cards/series/{id}`. The ID comes from the
+/// path; omitted fields keep their stored values.
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema, Validate)]
+pub struct PatchSeriesDto {
+    #[validate(length(min = 1, message = "Name cannot be empty"))]
+    pub name: Option<String>,
+    pub link: Option<String>,
+    pub manual: Option<bool>,
+}
+
+impl PatchSeriesDto {
+    /// Build the shared update payload for the entity at `id`.
+    pub fn into_update(self, id: i64) -> UpdateSeriesDto {
+        UpdateSeriesDto {
+            id,
+            name: self.name,
+            link: self.link,
+            manual: self.manual,
+        }
+    }
+}
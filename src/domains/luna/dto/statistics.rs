@@ -1,5 +1,8 @@
+use crate::common::config::DEFAULT_PAGE_SIZE;
+use chrono::NaiveDate;
+use sea_orm::prelude::Decimal;
 use serde::{Deserialize, Serialize};
-use utoipa::ToSchema;
+use utoipa::{IntoParams, ToSchema};
 
 // Count DTOs for statistics
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -8,3 +11,157 @@ pub struct EntityCountDto {
     pub name: String,
     pub count: i64,
 }
+
+/// Query parameters of the `*-records-count` endpoints.
+///
+/// Filtering and paging run in the counting query, so only the requested page
+/// of entities is loaded. Entities are ranked by count, highest first.
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct RecordCountQuery {
+    /// Entities per page. Defaults to [`DEFAULT_PAGE_SIZE`](crate::common::config::DEFAULT_PAGE_SIZE).
+    pub limit: Option<u64>,
+    /// Number of ranked entities to skip. Defaults to 0.
+    pub offset: Option<u64>,
+    /// Only entities with at least this many records.
+    pub min_count: Option<i64>,
+    /// Only entities whose name contains this text.
+    pub name: Option<String>,
+}
+
+impl RecordCountQuery {
+    /// Effective page size.
+    pub fn page_size(&self) -> u64 {
+        self.limit
+            .filter(|&limit| limit > 0)
+            .unwrap_or(DEFAULT_PAGE_SIZE)
+    }
+
+    /// Whether this asks for the unfiltered first page, the only page cached.
+    pub fn is_first_page(&self) -> bool {
+        self.page_size() == DEFAULT_PAGE_SIZE
+            && self.offset.unwrap_or(0) == 0
+            && self.min_count.is_none()
+            && self
+                .name
+                .as_deref()
+                .is_none_or(|name| name.trim().is_empty())
+    }
+}
+
+/// Query parameters of the co-occurrence endpoints
+/// (`/cards/idols/{id}/co-stars`, `/cards/genres/{id}/related`).
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CoOccurrenceQuery {
+    /// Entities returned. Defaults to [`DEFAULT_PAGE_SIZE`](crate::common::config::DEFAULT_PAGE_SIZE).
+    pub limit: Option<u64>,
+}
+
+/// Catalog-wide totals, excluding trashed records and placeholder entities.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CatalogTotalsDto {
+    pub records: i64,
+    pub idols: i64,
+    pub directors: i64,
+    pub studios: i64,
+    /// Download links attached to live records.
+    pub links: i64,
+    /// Images stored locally for live records.
+    pub local_images: i64,
+}
+
+/// Records added in one calendar month.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct MonthCountDto {
+    /// `YYYY-MM`.
+    pub month: String,
+    pub count: i64,
+}
+
+/// Response of `GET /cards/statistics/overview`.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct StatisticsOverviewDto {
+    pub totals: CatalogTotalsDto,
+    /// Records added per month over the last 24 months, oldest first, with
+    /// empty months included.
+    pub records_per_month: Vec<MonthCountDto>,
+    pub top_genres: Vec<EntityCountDto>,
+    pub top_idols: Vec<EntityCountDto>,
+    pub top_studios: Vec<EntityCountDto>,
+    /// Mean `duration` of live records with a non-zero duration; absent when
+    /// there are none.
+    pub average_duration: Option<f64>,
+}
+
+/// Bucket width of `GET /cards/statistics/records-by-date`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DateGranularity {
+    #[default]
+    Month,
+    Year,
+}
+
+impl DateGranularity {
+    /// Postgres `date_trunc` field.
+    pub fn trunc_field(self) -> &'static str {
+        match self {
+            Self::Month => "month",
+            Self::Year => "year",
+        }
+    }
+
+    /// `chrono` format of a bucket label.
+    pub fn label_format(self) -> &'static str {
+        match self {
+            Self::Month => "%Y-%m",
+            Self::Year => "%Y",
+        }
+    }
+}
+
+/// Query parameters of `GET /cards/statistics/records-by-date`.
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct RecordsByDateQuery {
+    /// `month` (default) or `year`.
+    pub granularity: Option<DateGranularity>,
+    /// Earliest release date counted, inclusive.
+    pub from: Option<NaiveDate>,
+    /// Latest release date counted, inclusive.
+    pub to: Option<NaiveDate>,
+}
+
+/// Records released in one bucket.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DateCountDto {
+    /// `YYYY-MM` for months, `YYYY` for years.
+    pub period: String,
+    pub count: i64,
+}
+
+/// Links added in one calendar month.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct LinkMonthDto {
+    /// `YYYY-MM`.
+    pub month: String,
+    pub count: i64,
+    /// Combined size of the links with a known size.
+    #[schema(value_type = String)]
+    pub size: Decimal,
+}
+
+/// Response of `GET /cards/statistics/links`. Links of trashed records are
+/// left out.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct LinkStatisticsDto {
+    pub total_count: i64,
+    /// Combined size of the links with a known size; placeholder sizes are
+    /// not counted.
+    #[schema(value_type = String)]
+    pub total_size: Decimal,
+    /// Links added per month over the last 24 months, oldest first, with
+    /// empty months included.
+    pub links_per_month: Vec<LinkMonthDto>,
+}
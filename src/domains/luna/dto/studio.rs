@@ -1,16 +1,24 @@
 use serde::{Deserialize, Serialize};
-use utoipa::ToSchema;
+use utoipa::{IntoParams, ToSchema};
 use validator::Validate;
 
+use super::{EntityCountDto, LabelDto};
 use crate::domains::luna::domain::Studio;
 
 // Studio DTOs
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[cfg_attr(
+    feature = "graphql",
+    derive(async_graphql::SimpleObject),
+    graphql(name = "Studio")
+)]
 pub struct StudioDto {
     pub id: i64,
     pub name: String,
     pub link: String,
     pub manual: bool,
+    /// Studio group the studio belongs to.
+    pub parent_id: Option<i64>,
 }
 
 impl From<Studio> for StudioDto {
@@ -20,18 +28,29 @@ impl From<Studio> for StudioDto {
             name: studio.name,
             link: studio.link,
             manual: studio.manual,
+            parent_id: studio.parent_id,
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+/// Studio list filters, extracted from the query string of `GET /cards/studios`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct SearchStudioDto {
+    /// Exact studio ID
     pub id: Option<i64>,
+    /// Substring match on the studio name
     pub name: Option<String>,
+    /// Substring match on the studio link
     pub link: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+#[cfg_attr(
+    feature = "graphql",
+    derive(async_graphql::InputObject),
+    graphql(name = "StudioInput")
+)]
 pub struct CreateStudioDto {
     #[validate(length(min = 1, message = "Name cannot be empty"))]
     pub name: String,
@@ -47,3 +66,59 @@ pub struct UpdateStudioDto {
     pub link: Option<String>,
     pub manual: Option<bool>,
 }
+
+/// Partial update for `PATCH /cards/studios/{id}`. The ID comes from the
+/// path; omitted fields keep their stored values.
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema, Validate)]
+pub struct PatchStudioDto {
+    #[validate(length(min = 1, message = "Name cannot be empty"))]
+    pub name: Option<String>,
+    pub link: Option<String>,
+    pub manual: Option<bool>,
+}
+
+impl PatchStudioDto {
+    /// Build the shared update payload for the entity at `id`.
+    pub fn into_update(self, id: i64) -> UpdateStudioDto {
+        UpdateStudioDto {
+            id,
+            name: self.name,
+            link: self.link,
+            manual: self.manual,
+        }
+    }
+}
+
+/// Body of `PUT /cards/studios/{id}/parent`.
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct StudioParentDto {
+    /// Studio group to move the studio under; `null` makes it top-level.
+    pub parent_id: Option<i64>,
+}
+
+/// Direct children of a studio, from `GET /cards/studios/{id}/children`.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct StudioChildrenDto {
+    /// Studios whose parent is the studio, by name.
+    pub studios: Vec<StudioDto>,
+    /// Labels the studio publishes, by name.
+    pub labels: Vec<LabelDto>,
+}
+
+/// Record counts of a studio and everything below it, from
+/// `GET /cards/studios/{id}/rollup`. Only live records are counted.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct StudioRollupDto {
+    pub id: i64,
+    pub name: String,
+    /// Records of the studio itself.
+    pub record_count: i64,
+    /// Records of the studio, its labels, and every studio below it and
+    /// their labels, each record counted once.
+    pub total_record_count: i64,
+    /// Labels the studio publishes with their record counts, by name.
+    pub labels: Vec<EntityCountDto>,
+    /// Roll-ups of the studios whose parent is the studio, by name.
+    #[schema(no_recursion)]
+    pub children: Vec<StudioRollupDto>,
+}
@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use super::record::RecordDto;
+
+/// Page size used by `GET /cards/sync/records` when `limit` is omitted.
+pub const DEFAULT_SYNC_LIMIT: u64 = 200;
+/// Largest page `GET /cards/sync/records` returns.
+pub const MAX_SYNC_LIMIT: u64 = 1000;
+
+/// Query parameters for `GET /cards/sync/records`.
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct RecordSyncQuery {
+    /// `next_since` from the previous sync; omit (or send 0) to start over.
+    pub since: Option<i64>,
+    /// Maximum number of changes per page (default 200, at most 1000).
+    pub limit: Option<u64>,
+}
+
+/// One page of catalog changes for incremental sync.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RecordSyncResponse {
+    /// Records created, updated or restored since `since`, in change order.
+    pub records: Vec<RecordDto>,
+    /// IDs of records deleted, moved to the trash, or no longer visible to
+    /// the caller since `since`.
+    pub deleted: Vec<String>,
+    /// Send as `since` on the next call.
+    pub next_since: i64,
+    /// More changes are waiting; call again with `next_since` right away.
+    pub has_more: bool,
+}
@@ -0,0 +1,75 @@
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+/// Default number of tags in the tag cloud.
+pub const DEFAULT_TAG_CLOUD_LIMIT: u64 = 100;
+
+/// Most tags the tag cloud returns.
+pub const MAX_TAG_CLOUD_LIMIT: u64 = 1000;
+
+// Tag DTOs
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TagCategoryDto {
+    pub id: i64,
+    pub name: String,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TagDto {
+    pub id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    pub category: TagCategoryDto,
+}
+
+/// Tag list filters, extracted from the query string of `GET /cards/tags`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SearchTagDto {
+    /// Exact tag category ID
+    pub category_id: Option<i64>,
+    /// Substring match on the tag name
+    pub name: Option<String>,
+}
+
+/// Request body of `POST /cards/tags` and `PUT /cards/tags/{id}`.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, Validate)]
+pub struct CreateTagDto {
+    #[validate(length(
+        min = 1,
+        max = 255,
+        message = "Name must be between 1 and 255 characters"
+    ))]
+    pub name: String,
+    pub description: Option<String>,
+    /// Tag category ID; names are unique within a category.
+    pub category_id: i64,
+}
+
+/// Request body of `POST /cards/records/{id}/tags`.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, Validate)]
+pub struct AttachTagsDto {
+    #[validate(length(min = 1, message = "At least one tag ID is required"))]
+    pub tag_ids: Vec<i64>,
+}
+
+/// Query parameters of `GET /cards/tags/cloud`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct TagCloudQuery {
+    /// Number of tags, most used first (default
+    /// [`DEFAULT_TAG_CLOUD_LIMIT`], at most [`MAX_TAG_CLOUD_LIMIT`])
+    pub limit: Option<u64>,
+}
+
+/// A tag with the number of records carrying it.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TagCountDto {
+    pub id: i64,
+    pub name: String,
+    pub category_id: i64,
+    /// Live records the caller may see that carry the tag.
+    pub count: i64,
+}
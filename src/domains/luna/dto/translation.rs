@@ -0,0 +1,229 @@
+use super::{
+    DirectorDto, GenreDto, IdolDto, LabelDto, PaginatedResponse, RecordDto, SeriesDto, StudioDto,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use utoipa::ToSchema;
+use validator::Validate;
+
+/// Languages entity names can be translated into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    Ja,
+    En,
+    Zh,
+}
+
+impl Language {
+    pub const ALL: [Self; 3] = [Self::Ja, Self::En, Self::Zh];
+
+    /// ISO 639-1 code, as stored in `entity_translation.language`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Ja => "ja",
+            Self::En => "en",
+            Self::Zh => "zh",
+        }
+    }
+
+    /// Language of a tag such as `en-US` or `zh-Hant`, read from its primary
+    /// subtag.
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let primary = tag.split(['-', '_']).next()?.trim();
+        Self::ALL
+            .into_iter()
+            .find(|language| language.as_str().eq_ignore_ascii_case(primary))
+    }
+
+    /// Preferred supported language of an `Accept-Language` header: the
+    /// one with the highest `q`, the first listed on ties. Languages with
+    /// `q=0`, `*` and unsupported languages are skipped.
+    pub fn from_accept_language(header: &str) -> Option<Self> {
+        let mut best: Option<(Self, f32)> = None;
+        for item in header.split(',') {
+            let mut params = item.split(';');
+            let tag = params.next().unwrap_or_default();
+            let q = match params.find_map(|param| param.trim().strip_prefix("q=")) {
+                Some(q) => q.trim().parse::<f32>().ok(),
+                None => Some(1.0),
+            };
+            let (Some(language), Some(q)) = (Self::from_tag(tag), q) else {
+                continue;
+            };
+            if q > 0.0 && !matches!(best, Some((_, best_q)) if best_q >= q) {
+                best = Some((language, q));
+            }
+        }
+        best.map(|(language, _)| language)
+    }
+}
+
+/// Named card entities whose names can be translated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum TranslatedEntity {
+    Director,
+    Genre,
+    Idol,
+    Label,
+    Series,
+    Studio,
+}
+
+impl TranslatedEntity {
+    /// Value stored in `entity_translation.entity_type`, which is also the
+    /// entity's table.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Director => "director",
+            Self::Genre => "genre",
+            Self::Idol => "idol",
+            Self::Label => "label",
+            Self::Series => "series",
+            Self::Studio => "studio",
+        }
+    }
+}
+
+/// One translated name of an entity.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TranslationDto {
+    pub language: Language,
+    pub name: String,
+}
+
+/// Request body of `PUT /cards/admin/translations/{entity}/{id}/{language}`.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, Validate)]
+pub struct SetTranslationDto {
+    #[validate(length(
+        min = 1,
+        max = 255,
+        message = "Name must be between 1 and 255 characters"
+    ))]
+    pub name: String,
+}
+
+/// Translated names in one language, by entity type and ID.
+pub type TranslatedNames = HashMap<(TranslatedEntity, i64), String>;
+
+/// Responses showing entity names that translations can replace.
+pub trait LocalizedNames {
+    /// Adds the entities named in the response to `keys`.
+    fn name_keys(&self, keys: &mut Vec<(TranslatedEntity, i64)>);
+
+    /// Replaces every name that has a translation in `names`.
+    fn apply_names(&mut self, names: &TranslatedNames);
+}
+
+macro_rules! impl_localized_names {
+    ($dto:ty, $entity:ident) => {
+        impl LocalizedNames for $dto {
+            fn name_keys(&self, keys: &mut Vec<(TranslatedEntity, i64)>) {
+                keys.push((TranslatedEntity::$entity, self.id));
+            }
+
+            fn apply_names(&mut self, names: &TranslatedNames) {
+                if let Some(name) = names.get(&(TranslatedEntity::$entity, self.id)) {
+                    self.name.clone_from(name);
+                }
+            }
+        }
+    };
+}
+
+impl_localized_names!(DirectorDto, Director);
+impl_localized_names!(GenreDto, Genre);
+impl_localized_names!(IdolDto, Idol);
+impl_localized_names!(LabelDto, Label);
+impl_localized_names!(SeriesDto, Series);
+impl_localized_names!(StudioDto, Studio);
+
+impl LocalizedNames for RecordDto {
+    fn name_keys(&self, keys: &mut Vec<(TranslatedEntity, i64)>) {
+        self.director.name_keys(keys);
+        self.studio.name_keys(keys);
+        self.label.name_keys(keys);
+        self.series.name_keys(keys);
+        for genre in &self.genres {
+            genre.genre.name_keys(keys);
+        }
+        for idol in &self.idols {
+            idol.idol.name_keys(keys);
+        }
+    }
+
+    fn apply_names(&mut self, names: &TranslatedNames) {
+        self.director.apply_names(names);
+        self.studio.apply_names(names);
+        self.label.apply_names(names);
+        self.series.apply_names(names);
+        for genre in &mut self.genres {
+            genre.genre.apply_names(names);
+        }
+        for idol in &mut self.idols {
+            idol.idol.apply_names(names);
+        }
+    }
+}
+
+impl<T: LocalizedNames> LocalizedNames for Vec<T> {
+    fn name_keys(&self, keys: &mut Vec<(TranslatedEntity, i64)>) {
+        for item in self {
+            item.name_keys(keys);
+        }
+    }
+
+    fn apply_names(&mut self, names: &TranslatedNames) {
+        for item in self {
+            item.apply_names(names);
+        }
+    }
+}
+
+impl<T: LocalizedNames> LocalizedNames for PaginatedResponse<T> {
+    fn name_keys(&self, keys: &mut Vec<(TranslatedEntity, i64)>) {
+        self.results.name_keys(keys);
+    }
+
+    fn apply_names(&mut self, names: &TranslatedNames) {
+        self.results.apply_names(names);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Language;
+
+    #[test]
+    fn tags_match_on_the_primary_subtag() {
+        assert_eq!(Language::from_tag("ja"), Some(Language::Ja));
+        assert_eq!(Language::from_tag("en-US"), Some(Language::En));
+        assert_eq!(Language::from_tag("ZH-Hant"), Some(Language::Zh));
+        assert_eq!(Language::from_tag("fr"), None);
+        assert_eq!(Language::from_tag("*"), None);
+    }
+
+    #[test]
+    fn accept_language_prefers_the_highest_weight() {
+        assert_eq!(
+            Language::from_accept_language("fr-CH, fr;q=0.9, en;q=0.8, ja;q=0.7"),
+            Some(Language::En)
+        );
+        assert_eq!(
+            Language::from_accept_language("en;q=0.5, zh-CN;q=0.9"),
+            Some(Language::Zh)
+        );
+        assert_eq!(Language::from_accept_language("ja, en"), Some(Language::Ja));
+    }
+
+    #[test]
+    fn accept_language_skips_refused_and_unsupported_languages() {
+        assert_eq!(
+            Language::from_accept_language("ja;q=0, en;q=0.1"),
+            Some(Language::En)
+        );
+        assert_eq!(Language::from_accept_language("*, de"), None);
+        assert_eq!(Language::from_accept_language(""), None);
+    }
+}
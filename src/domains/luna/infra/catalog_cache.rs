@@ -0,0 +1,168 @@
+//! Cached card-entity lookups and statistics, and the keys each
+//! committed catalog write invalidates.
+
+use crate::common::{cache::CacheService, error::AppError};
+use crate::domains::luna::dto::{
+    EntityCountDto, NameSuggestionDto, PaginatedResponse, StatisticsOverviewDto,
+};
+use crate::domains::search::SearchEntityType;
+use serde::{de::DeserializeOwned, Serialize};
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Card entity types whose lookups are cached.
+const CARD_ENTITY_TYPES: &[SearchEntityType] = &[
+    SearchEntityType::Idol,
+    SearchEntityType::Director,
+    SearchEntityType::Genre,
+    SearchEntityType::Label,
+    SearchEntityType::Studio,
+    SearchEntityType::Series,
+];
+
+/// Key of the statistics overview, which every catalog write invalidates.
+const OVERVIEW_KEY: &str = "statistics:overview";
+
+/// How long autocomplete suggestions are kept.
+const SUGGESTIONS_TTL: Duration = Duration::from_secs(60);
+/// Most autocomplete answers kept at once.
+const SUGGESTIONS_CAPACITY: u64 = 1_000;
+
+fn entity_key(entity: SearchEntityType, id: i64) -> String {
+    format!("{}:{id}", entity.as_str())
+}
+
+fn entity_list_key(entity: SearchEntityType) -> String {
+    format!("{}:all", entity.as_str())
+}
+
+fn record_counts_key(entity: SearchEntityType) -> String {
+    format!("{}:record_counts", entity.as_str())
+}
+
+fn suggestions_key(entity: SearchEntityType, generation: u64, term: &str, limit: u64) -> String {
+    format!(
+        "{}:autocomplete:{generation}:{limit}:{term}",
+        entity.as_str()
+    )
+}
+
+/// Catalog view of the shared [`CacheService`], owned by the luna services.
+pub struct CatalogCache {
+    cache: CacheService,
+    /// Autocomplete answers. There is one per typed prefix, too many to
+    /// invalidate by key, so they stay in process for a short while and are
+    /// keyed by `suggestions_generation`, which every catalog write bumps.
+    suggestions: CacheService,
+    suggestions_generation: AtomicU64,
+}
+
+impl CatalogCache {
+    pub fn new(cache: CacheService) -> Self {
+        Self {
+            cache,
+            suggestions: CacheService::memory(SUGGESTIONS_TTL, SUGGESTIONS_CAPACITY),
+            suggestions_generation: AtomicU64::new(0),
+        }
+    }
+
+    /// Entity `id` of type `entity`, loaded by `load` on a miss.
+    pub async fn entity<T, F, Fut>(
+        &self,
+        entity: SearchEntityType,
+        id: i64,
+        load: F,
+    ) -> Result<T, AppError>
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T, AppError>>,
+    {
+        self.cache.get_or_load(&entity_key(entity, id), load).await
+    }
+
+    /// Every entity of type `entity`, loaded by `load` on a miss.
+    pub async fn entity_list<T, F, Fut>(
+        &self,
+        entity: SearchEntityType,
+        load: F,
+    ) -> Result<Vec<T>, AppError>
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<Vec<T>, AppError>>,
+    {
+        self.cache.get_or_load(&entity_list_key(entity), load).await
+    }
+
+    /// First unfiltered page of record counts per entity of type `entity`,
+    /// loaded by `load` on a miss.
+    pub async fn record_counts<F, Fut>(
+        &self,
+        entity: SearchEntityType,
+        load: F,
+    ) -> Result<PaginatedResponse<EntityCountDto>, AppError>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<PaginatedResponse<EntityCountDto>, AppError>>,
+    {
+        self.cache
+            .get_or_load(&record_counts_key(entity), load)
+            .await
+    }
+
+    /// The statistics overview, loaded by `load` on a miss.
+    pub async fn overview<F, Fut>(&self, load: F) -> Result<StatisticsOverviewDto, AppError>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<StatisticsOverviewDto, AppError>>,
+    {
+        self.cache.get_or_load(OVERVIEW_KEY, load).await
+    }
+
+    /// Autocomplete suggestions of type `entity` for `term`, loaded by `load`
+    /// on a miss.
+    pub async fn suggestions<F, Fut>(
+        &self,
+        entity: SearchEntityType,
+        term: &str,
+        limit: u64,
+        load: F,
+    ) -> Result<Vec<NameSuggestionDto>, AppError>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<Vec<NameSuggestionDto>, AppError>>,
+    {
+        let generation = self.suggestions_generation.load(Ordering::Acquire);
+        self.suggestions
+            .get_or_load(&suggestions_key(entity, generation, term, limit), load)
+            .await
+    }
+
+    /// Forget entities `ids` of type `entity` after they were created, updated,
+    /// merged or deleted, along with that type's list and record counts, the
+    /// statistics overview and every autocomplete answer.
+    pub async fn invalidate_entities(&self, entity: SearchEntityType, ids: &[i64]) {
+        let mut keys: Vec<String> = ids.iter().map(|&id| entity_key(entity, id)).collect();
+        keys.push(entity_list_key(entity));
+        keys.push(record_counts_key(entity));
+        keys.push(OVERVIEW_KEY.to_owned());
+        self.cache.invalidate(&keys).await;
+        self.suggestions_generation.fetch_add(1, Ordering::AcqRel);
+    }
+
+    /// Forget every list, record count, autocomplete answer and the overview
+    /// after a record write.
+    /// Record writes move counts and may create entities by name, but never
+    /// change an existing entity.
+    pub async fn invalidate_records(&self) {
+        let mut keys: Vec<String> = CARD_ENTITY_TYPES
+            .iter()
+            .flat_map(|&entity| [entity_list_key(entity), record_counts_key(entity)])
+            .collect();
+        keys.push(OVERVIEW_KEY.to_owned());
+        self.cache.invalidate(&keys).await;
+        self.suggestions_generation.fetch_add(1, Ordering::AcqRel);
+    }
+}
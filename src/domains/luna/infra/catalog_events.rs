@@ -0,0 +1,212 @@
+//! In-process fan-out of catalog change events to `GET /cards/events` subscribers.
+
+use crate::domains::luna::dto::{CatalogAction, CatalogEvent};
+use crate::domains::search::SearchEntityType;
+use chrono::Utc;
+use std::collections::VecDeque;
+use std::sync::{Mutex, PoisonError};
+use tokio::sync::broadcast;
+
+/// Events kept for `Last-Event-ID` replay, and the broadcast buffer per subscriber.
+pub const CATALOG_EVENT_BACKLOG: usize = 1024;
+
+/// A published event and its position in this process's sequence.
+#[derive(Clone, Debug)]
+pub struct SequencedEvent {
+    /// SSE `id`, `<epoch>-<seq>`.
+    pub id: String,
+    pub seq: u64,
+    pub event: CatalogEvent,
+}
+
+/// What a new subscriber receives: the events it missed, then the live feed.
+pub struct CatalogSubscription {
+    /// Events after `Last-Event-ID`, or `None` when they are no longer
+    /// available and the client must resync.
+    pub replay: Option<Vec<SequencedEvent>>,
+    /// ID of the latest published event, if any.
+    pub head: Option<String>,
+    pub receiver: broadcast::Receiver<SequencedEvent>,
+}
+
+struct Backlog {
+    next_seq: u64,
+    events: VecDeque<SequencedEvent>,
+}
+
+/// Broadcast channel of catalog changes, populated by the luna services after
+/// their transactions commit.
+///
+/// Event IDs are `<epoch>-<seq>`. The epoch changes on every start, so an ID
+/// handed out before a restart is recognised as stale instead of replaying
+/// from the wrong position.
+pub struct CatalogEvents {
+    epoch: i64,
+    capacity: usize,
+    tx: broadcast::Sender<SequencedEvent>,
+    backlog: Mutex<Backlog>,
+}
+
+impl CatalogEvents {
+    pub fn new(capacity: usize) -> Self {
+        let (tx, _) = broadcast::channel(capacity);
+        Self {
+            epoch: Utc::now().timestamp_millis(),
+            capacity,
+            tx,
+            backlog: Mutex::new(Backlog {
+                next_seq: 1,
+                events: VecDeque::with_capacity(capacity),
+            }),
+        }
+    }
+
+    /// Publish one change. `permission` is the record's level for record
+    /// creates and updates, and `None` otherwise.
+    pub fn publish(
+        &self,
+        entity: SearchEntityType,
+        id: impl Into<String>,
+        action: CatalogAction,
+        permission: Option<i32>,
+    ) {
+        let mut backlog = self.backlog.lock().unwrap_or_else(PoisonError::into_inner);
+        let event = SequencedEvent {
+            id: self.event_id(backlog.next_seq),
+            seq: backlog.next_seq,
+            event: CatalogEvent {
+                entity,
+                id: id.into(),
+                action,
+                permission,
+            },
+        };
+        backlog.next_seq += 1;
+        if backlog.events.len() == self.capacity {
+            backlog.events.pop_front();
+        }
+        backlog.events.push_back(event.clone());
+        // Sending under the lock keeps the channel in sequence order and lets
+        // `subscribe` split replay from live events without a gap.
+        drop(self.tx.send(event));
+    }
+
+    /// Publish a change to the card entity `id`.
+    pub fn publish_entity(&self, entity: SearchEntityType, id: i64, action: CatalogAction) {
+        self.publish(entity, id.to_string(), action, None);
+    }
+
+    /// Subscribe to live events, replaying those after `last_event_id`.
+    pub fn subscribe(&self, last_event_id: Option<&str>) -> CatalogSubscription {
+        let backlog = self.backlog.lock().unwrap_or_else(PoisonError::into_inner);
+        let receiver = self.tx.subscribe();
+        let latest = backlog.next_seq - 1;
+        let replay = match last_event_id {
+            None => Some(Vec::new()),
+            Some(id) => self
+                .parse_event_id(id)
+                .filter(|&seq| seq <= latest)
+                .filter(|&seq| {
+                    let oldest = backlog.events.front().map_or(latest + 1, |e| e.seq);
+                    seq + 1 >= oldest
+                })
+                .map(|seq| {
+                    backlog
+                        .events
+                        .iter()
+                        .filter(|e| e.seq > seq)
+                        .cloned()
+                        .collect()
+                }),
+        };
+        CatalogSubscription {
+            replay,
+            head: (latest > 0).then(|| self.event_id(latest)),
+            receiver,
+        }
+    }
+
+    /// SSE `id` of the event at `seq`.
+    pub fn event_id(&self, seq: u64) -> String {
+        format!("{}-{seq}", self.epoch)
+    }
+
+    /// Sequence number of an ID issued by this process.
+    fn parse_event_id(&self, id: &str) -> Option<u64> {
+        let (epoch, seq) = id.trim().split_once('-')?;
+        (epoch.parse::<i64>().ok()? == self.epoch)
+            .then(|| seq.parse().ok())
+            .flatten()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CatalogAction, CatalogEvents, SearchEntityType};
+
+    fn publish(events: &CatalogEvents, id: &str) {
+        events.publish(
+            SearchEntityType::Record,
+            id,
+            CatalogAction::Created,
+            Some(0),
+        );
+    }
+
+    fn replayed_ids(events: &CatalogEvents, last_event_id: Option<&str>) -> Option<Vec<String>> {
+        events
+            .subscribe(last_event_id)
+            .replay
+            .map(|replay| replay.into_iter().map(|e| e.event.id).collect())
+    }
+
+    #[test]
+    fn fresh_subscribers_replay_nothing() {
+        let events = CatalogEvents::new(4);
+        publish(&events, "a");
+        assert_eq!(replayed_ids(&events, None), Some(vec![]));
+    }
+
+    #[test]
+    fn replays_events_after_last_event_id() {
+        let events = CatalogEvents::new(4);
+        publish(&events, "a");
+        publish(&events, "b");
+        publish(&events, "c");
+        let last = events.event_id(1);
+        assert_eq!(
+            replayed_ids(&events, Some(&last)),
+            Some(vec!["b".to_owned(), "c".to_owned()])
+        );
+        let head = events.event_id(3);
+        assert_eq!(replayed_ids(&events, Some(&head)), Some(vec![]));
+    }
+
+    #[test]
+    fn evicted_or_foreign_ids_require_a_resync() {
+        let events = CatalogEvents::new(2);
+        for id in ["a", "b", "c", "d"] {
+            publish(&events, id);
+        }
+        // Event 2 was evicted, so a client that last saw event 1 cannot catch up.
+        assert_eq!(replayed_ids(&events, Some(&events.event_id(1))), None);
+        assert_eq!(
+            replayed_ids(&events, Some(&events.event_id(2))),
+            Some(vec!["c".to_owned(), "d".to_owned()])
+        );
+        assert_eq!(replayed_ids(&events, Some("0-1")), None);
+        assert_eq!(replayed_ids(&events, Some(&events.event_id(9))), None);
+        assert_eq!(replayed_ids(&events, Some("garbage")), None);
+    }
+
+    #[tokio::test]
+    async fn live_events_follow_the_replay() {
+        let events = CatalogEvents::new(4);
+        publish(&events, "a");
+        let mut subscription = events.subscribe(Some(&events.event_id(0)));
+        assert_eq!(subscription.head, Some(events.event_id(1)));
+        publish(&events, "b");
+        let live = subscription.receiver.recv().await.expect("live event");
+        assert_eq!((live.seq, live.event.id.as_str()), (2, "b"));
+    }
+}
@@ -0,0 +1,106 @@
+use crate::domains::luna::{
+    domain::CommentRepository,
+    dto::{CommentAuthorDto, CommentDto},
+};
+use crate::entities::{record_comments, users, RecordCommentsEntity, UsersEntity};
+use async_trait::async_trait;
+use chrono::Utc;
+use sea_orm::{
+    ActiveValue::Set, ColumnTrait as _, DatabaseConnection, DbErr, EntityTrait as _,
+    PaginatorTrait as _, QueryFilter as _, QueryOrder as _, QuerySelect as _,
+};
+
+/// Pair a comment row with its author. Authors are never missing, as
+/// deleting a user deletes their comments.
+fn to_dto((comment, author): (record_comments::Model, Option<users::Model>)) -> CommentDto {
+    let (author_id, username) = author
+        .map(|user| (user.id, user.username))
+        .unwrap_or_else(|| (comment.user_id.clone(), String::new()));
+    CommentDto {
+        id: comment.id,
+        record_id: comment.record_id,
+        author: CommentAuthorDto {
+            id: author_id,
+            username,
+        },
+        body: comment.body,
+        created_at: comment.created_at,
+        updated_at: comment.updated_at,
+    }
+}
+
+pub struct CommentRepo;
+
+#[async_trait]
+impl CommentRepository for CommentRepo {
+    async fn find_by_record_paginated(
+        &self,
+        db: &DatabaseConnection,
+        record_id: &str,
+        limit: u64,
+        offset: u64,
+    ) -> Result<(Vec<CommentDto>, u64), DbErr> {
+        let query =
+            RecordCommentsEntity::find().filter(record_comments::Column::RecordId.eq(record_id));
+        let total = query.clone().count(db).await?;
+        let rows = query
+            .find_also_related(UsersEntity)
+            .order_by_asc(record_comments::Column::CreatedAt)
+            .order_by_asc(record_comments::Column::Id)
+            .offset(offset)
+            .limit(limit)
+            .all(db)
+            .await?;
+        Ok((rows.into_iter().map(to_dto).collect(), total))
+    }
+
+    async fn find_by_id(
+        &self,
+        db: &DatabaseConnection,
+        record_id: &str,
+        id: i64,
+    ) -> Result<Option<CommentDto>, DbErr> {
+        let row = RecordCommentsEntity::find_by_id(id)
+            .filter(record_comments::Column::RecordId.eq(record_id))
+            .find_also_related(UsersEntity)
+            .one(db)
+            .await?;
+        Ok(row.map(to_dto))
+    }
+
+    async fn create(
+        &self,
+        db: &DatabaseConnection,
+        record_id: &str,
+        user_id: &str,
+        body: &str,
+    ) -> Result<i64, DbErr> {
+        let now = Utc::now();
+        let active = record_comments::ActiveModel {
+            record_id: Set(record_id.to_owned()),
+            user_id: Set(user_id.to_owned()),
+            body: Set(body.to_owned()),
+            created_at: Set(now),
+            updated_at: Set(now),
+            ..Default::default()
+        };
+        let inserted = RecordCommentsEntity::insert(active).exec(db).await?;
+        Ok(inserted.last_insert_id)
+    }
+
+    async fn update_body(&self, db: &DatabaseConnection, id: i64, body: &str) -> Result<(), DbErr> {
+        let active = record_comments::ActiveModel {
+            id: Set(id),
+            body: Set(body.to_owned()),
+            updated_at: Set(Utc::now()),
+            ..Default::default()
+        };
+        RecordCommentsEntity::update(active).exec(db).await?;
+        Ok(())
+    }
+
+    async fn delete(&self, db: &DatabaseConnection, id: i64) -> Result<(), DbErr> {
+        RecordCommentsEntity::delete_by_id(id).exec(db).await?;
+        Ok(())
+    }
+}
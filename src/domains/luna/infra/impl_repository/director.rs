@@ -1,3 +1,4 @@
+use super::entity_repo_macro::affinity_order_by;
 use crate::domains::luna::{
     domain::{Director, DirectorAffinityRepository, DirectorRepository},
     dto::{
@@ -19,6 +20,11 @@ impl_named_entity_repo!(
     get_director_record_counts, RecordEntity, record, DirectorId
 );
 
+impl_entity_merge_repo!(
+    record_fk;
+    DirectorRepo, DirectorEntity, DirectorId
+);
+
 // Tunable hyper-parameters for the affinity score. These are fixed constants
 // (not user input), interpolated into the SQL as literals. Two main knobs:
 // `AFFINITY_M_V` controls how hard small samples are pulled toward the prior
@@ -173,10 +179,11 @@ impl DirectorAffinityRepository for DirectorRepo {
                FROM record r \
                LEFT JOIN user_record_interaction uri \
                       ON uri.record_id = r.id AND uri.user_id = $1 \
+               WHERE r.deleted_at IS NULL \
                GROUP BY r.director_id \
              ) agg ON agg.entity_id = d.id\
              {where_clause} \
-             ORDER BY score DESC, d.id ASC \
+             ORDER BY {order_by} \
              LIMIT ${limit_param} OFFSET ${offset_param}",
             W_V = AFFINITY_W_V,
             W_L = AFFINITY_W_L,
@@ -189,6 +196,7 @@ impl DirectorAffinityRepository for DirectorRepo {
             N0 = AFFINITY_N0,
             limit_param = 2 + filter_binds.len(),
             offset_param = 3 + filter_binds.len(),
+            order_by = affinity_order_by(&pagination, "d"),
         );
 
         let mut select_binds: Vec<Value> = Vec::with_capacity(filter_binds.len() + 3);
@@ -216,9 +224,14 @@ impl DirectorAffinityRepository for DirectorRepo {
         let total_items = count_row.map_or(0, |r| r.cnt);
         let total_pages = (total_items as u64).div_ceil(page_size);
 
+        let ordering_param = pagination
+            .ordering
+            .as_deref()
+            .map(|o| format!("&ordering={o}"))
+            .unwrap_or_default();
         let next = if page_num + 1 < total_pages {
             Some(format!(
-                "?limit={page_size}&offset={}",
+                "?limit={page_size}&offset={}{ordering_param}",
                 (page_num + 1) * page_size
             ))
         } else {
@@ -226,7 +239,7 @@ impl DirectorAffinityRepository for DirectorRepo {
         };
         let previous = if page_num > 0 {
             Some(format!(
-                "?limit={page_size}&offset={}",
+                "?limit={page_size}&offset={}{ordering_param}",
                 (page_num - 1) * page_size
             ))
         } else {
@@ -237,6 +250,7 @@ impl DirectorAffinityRepository for DirectorRepo {
             count: total_items,
             next,
             previous,
+            next_cursor: None,
             results,
         })
     }
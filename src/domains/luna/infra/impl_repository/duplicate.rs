@@ -0,0 +1,99 @@
+use crate::domains::luna::{domain::DuplicateRepository, dto::DuplicateCandidateDto};
+use crate::entities::{record, RecordEntity};
+use async_trait::async_trait;
+use sea_orm::{
+    ColumnTrait as _, DatabaseBackend, DatabaseConnection, DbErr, EntityTrait as _,
+    FromQueryResult, QueryFilter as _, QueryOrder as _, Statement,
+};
+
+#[derive(FromQueryResult)]
+struct IdSetRow {
+    ids: Vec<String>,
+}
+
+#[derive(FromQueryResult)]
+struct PairRow {
+    left_id: String,
+    right_id: String,
+    score: f64,
+}
+
+pub struct DuplicateRepo;
+
+#[async_trait]
+impl DuplicateRepository for DuplicateRepo {
+    async fn same_title_and_date(
+        &self,
+        db: &DatabaseConnection,
+        limit: u64,
+    ) -> Result<Vec<Vec<String>>, DbErr> {
+        let rows = IdSetRow::find_by_statement(Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            "SELECT array_agg(id ORDER BY id) AS ids \
+             FROM record \
+             WHERE deleted_at IS NULL AND btrim(title) <> '' \
+             GROUP BY lower(btrim(title)), date \
+             HAVING COUNT(*) > 1 \
+             ORDER BY COUNT(*) DESC, MIN(id) \
+             LIMIT $1",
+            [(limit as i64).into()],
+        ))
+        .all(db)
+        .await?;
+        Ok(rows.into_iter().map(|row| row.ids).collect())
+    }
+
+    async fn similar_titles_with_same_idols(
+        &self,
+        db: &DatabaseConnection,
+        min_similarity: f64,
+        limit: u64,
+    ) -> Result<Vec<(String, String, f64)>, DbErr> {
+        // Idol sets are compared as sorted ID lists so the self-join is a
+        // plain equality join; similarity() only runs on matching sets.
+        let rows = PairRow::find_by_statement(Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            "WITH idol_sets AS ( \
+                SELECT r.id, r.title, r.date, \
+                       string_agg(p.idol_id::TEXT, ',' ORDER BY p.idol_id) AS idols \
+                FROM record r JOIN idol_participation p ON p.record_id = r.id \
+                WHERE r.deleted_at IS NULL AND p.idol_id <> 0 \
+                GROUP BY r.id, r.title, r.date \
+             ) \
+             SELECT a.id AS left_id, b.id AS right_id, \
+                    similarity(a.title, b.title)::DOUBLE PRECISION AS score \
+             FROM idol_sets a JOIN idol_sets b ON b.idols = a.idols AND a.id < b.id \
+             WHERE similarity(a.title, b.title) >= $1 \
+               AND NOT (lower(btrim(a.title)) = lower(btrim(b.title)) AND a.date = b.date) \
+             ORDER BY score DESC, a.id, b.id \
+             LIMIT $2",
+            [min_similarity.into(), (limit as i64).into()],
+        ))
+        .all(db)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.left_id, row.right_id, row.score))
+            .collect())
+    }
+
+    async fn find_candidates(
+        &self,
+        db: &DatabaseConnection,
+        ids: &[String],
+    ) -> Result<Vec<DuplicateCandidateDto>, DbErr> {
+        let rows = RecordEntity::find()
+            .filter(record::Column::Id.is_in(ids.iter().cloned()))
+            .order_by_asc(record::Column::Id)
+            .all(db)
+            .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| DuplicateCandidateDto {
+                id: row.id,
+                title: row.title,
+                date: row.date,
+            })
+            .collect())
+    }
+}
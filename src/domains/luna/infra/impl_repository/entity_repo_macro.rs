@@ -47,6 +47,82 @@ macro_rules! impl_named_entity_repo {
                 Ok(item.map(<$domain>::from))
             }
 
+            async fn find_by_ids(
+                &self,
+                db: &sea_orm::DatabaseConnection,
+                ids: &[i64],
+            ) -> Result<Vec<$domain>, sea_orm::DbErr> {
+                use sea_orm::{ColumnTrait as _, QueryFilter as _};
+                let items = $entity_struct::find()
+                    .filter($entity_mod::Column::Id.is_in(ids.iter().copied()))
+                    .all(db)
+                    .await?;
+                Ok(super::entity_repo_macro::in_request_order(
+                    ids,
+                    items.into_iter().map(<$domain>::from),
+                    |item| item.id,
+                ))
+            }
+
+            /// Names containing `term` (already lowercased), those starting
+            /// with it first, then by record count. Both conditions compare
+            /// `lower(name)`, which the prefix and trigram indexes cover.
+            async fn autocomplete(
+                &self,
+                db: &sea_orm::DatabaseConnection,
+                term: &str,
+                limit: u64,
+            ) -> Result<Vec<crate::domains::luna::dto::NameSuggestionDto>, sea_orm::DbErr> {
+                use sea_orm::sea_query::{Expr, Func, SimpleExpr};
+                use sea_orm::{
+                    ColumnTrait as _, FromQueryResult, JoinType, Order, QueryFilter as _,
+                    QueryOrder as _, QuerySelect as _,
+                };
+
+                #[derive(FromQueryResult)]
+                struct SuggestionRow {
+                    id: i64,
+                    name: String,
+                }
+
+                let pattern = super::record::escape_like_pattern(term);
+                let lower_name = || -> SimpleExpr {
+                    Func::lower(Expr::col(($entity_mod::Entity, $entity_mod::Column::Name))).into()
+                };
+                let records = $count_entity_struct::belongs_to($entity_struct)
+                    .from($count_entity_mod::Column::$count_fk_column)
+                    .to($entity_mod::Column::Id)
+                    .into();
+                let rows = $entity_struct::find()
+                    .select_only()
+                    .column($entity_mod::Column::Id)
+                    .column($entity_mod::Column::Name)
+                    .join_rev(JoinType::LeftJoin, records)
+                    // The ID 0 placeholder is never suggested.
+                    .filter($entity_mod::Column::Id.ne(0))
+                    .filter(Expr::expr(lower_name()).like(format!("%{pattern}%")))
+                    .group_by($entity_mod::Column::Id)
+                    .group_by($entity_mod::Column::Name)
+                    .order_by(
+                        Expr::expr(lower_name()).like(format!("{pattern}%")),
+                        Order::Desc,
+                    )
+                    .order_by($count_entity_mod::Column::Id.count(), Order::Desc)
+                    .order_by_asc($entity_mod::Column::Name)
+                    .order_by_asc($entity_mod::Column::Id)
+                    .limit(limit)
+                    .into_model::<SuggestionRow>()
+                    .all(db)
+                    .await?;
+                Ok(rows
+                    .into_iter()
+                    .map(|row| crate::domains::luna::dto::NameSuggestionDto {
+                        id: row.id,
+                        name: row.name,
+                    })
+                    .collect())
+            }
+
             async fn find_list(
                 &self,
                 db: &sea_orm::DatabaseConnection,
@@ -68,8 +144,12 @@ macro_rules! impl_named_entity_repo {
 
             /// Finds entities with pagination using database-level `LIMIT`/`OFFSET`.
             ///
-            /// **Note:** `next`/`previous` links in the response contain only `limit` and
-            /// `offset` parameters — search filter fields (`id`, `name`, `link`) from
+            /// Results follow `pagination.ordering` (allowlisted by
+            /// `ENTITY_ORDERING_FIELDS`, validated by the service), with `id` as the
+            /// default order and tiebreaker.
+            ///
+            /// **Note:** `next`/`previous` links in the response contain only `limit`,
+            /// `offset` and `ordering` parameters — search filter fields (`id`, `name`, `link`) from
             /// `search_dto` are not preserved in the links. Route handlers should document
             /// this or reconstruct filter params when building client-facing URLs.
             async fn find_list_paginated(
@@ -88,6 +168,36 @@ macro_rules! impl_named_entity_repo {
                 if let Some(link) = search_dto.link.as_deref().filter(|s| !s.trim().is_empty()) {
                     query = query.filter($entity_mod::Column::Link.contains(link));
                 }
+                {
+                    use sea_orm::QueryOrder as _;
+                    let keys = pagination
+                        .ordering
+                        .as_deref()
+                        .and_then(|raw| {
+                            crate::common::pagination::parse_ordering(
+                                raw,
+                                crate::domains::luna::dto::ENTITY_ORDERING_FIELDS,
+                            )
+                            .ok()
+                        })
+                        .unwrap_or_default();
+                    for key in &keys {
+                        let column = match key.field.as_str() {
+                            "name" => $entity_mod::Column::Name,
+                            "link" => $entity_mod::Column::Link,
+                            _ => $entity_mod::Column::Id,
+                        };
+                        let order = if key.descending {
+                            sea_orm::Order::Desc
+                        } else {
+                            sea_orm::Order::Asc
+                        };
+                        query = query.order_by(column, order);
+                    }
+                    if !keys.iter().any(|k| k.field == "id") {
+                        query = query.order_by($entity_mod::Column::Id, sea_orm::Order::Asc);
+                    }
+                }
 
                 let page_size = pagination
                     .limit
@@ -102,9 +212,14 @@ macro_rules! impl_named_entity_repo {
                 let items = paginator.fetch_page(page_num).await?;
                 let results: Vec<$domain> = items.into_iter().map(<$domain>::from).collect();
 
+                let ordering_param = pagination
+                    .ordering
+                    .as_deref()
+                    .map(|o| format!("&ordering={o}"))
+                    .unwrap_or_default();
                 let next = if page_num + 1 < total_pages {
                     Some(format!(
-                        "?limit={page_size}&offset={}",
+                        "?limit={page_size}&offset={}{ordering_param}",
                         (page_num + 1) * page_size
                     ))
                 } else {
@@ -112,7 +227,7 @@ macro_rules! impl_named_entity_repo {
                 };
                 let previous = if page_num > 0 {
                     Some(format!(
-                        "?limit={page_size}&offset={}",
+                        "?limit={page_size}&offset={}{ordering_param}",
                         (page_num - 1) * page_size
                     ))
                 } else {
@@ -123,6 +238,7 @@ macro_rules! impl_named_entity_repo {
                     count: total_items as i64,
                     next,
                     previous,
+                    next_cursor: None,
                     results,
                 })
             }
@@ -146,14 +262,43 @@ macro_rules! impl_named_entity_repo {
                     return Ok((e.id, false));
                 }
 
+                // A concurrent writer may insert the same row between the lookup
+                // above and this insert; the unique index on (name, link, manual)
+                // turns that into a no-op, and the winner's row is returned.
                 let active_model = $entity_mod::ActiveModel {
-                    name: sea_orm::Set(name),
-                    link: sea_orm::Set(link),
+                    name: sea_orm::Set(name.clone()),
+                    link: sea_orm::Set(link.clone()),
                     manual: sea_orm::Set(manual),
                     ..Default::default()
                 };
-                let inserted = active_model.insert(txn).await?;
-                Ok((inserted.id, true))
+                let on_conflict = sea_orm::sea_query::OnConflict::columns([
+                    $entity_mod::Column::Name,
+                    $entity_mod::Column::Link,
+                    $entity_mod::Column::Manual,
+                ])
+                .do_nothing()
+                .to_owned();
+                match $entity_struct::insert(active_model)
+                    .on_conflict(on_conflict)
+                    .exec(txn)
+                    .await
+                {
+                    Ok(res) => Ok((res.last_insert_id, true)),
+                    Err(sea_orm::DbErr::RecordNotInserted) => {
+                        let winner = $entity_struct::find()
+                            .filter($entity_mod::Column::Name.eq(&name))
+                            .filter($entity_mod::Column::Link.eq(&link))
+                            .filter($entity_mod::Column::Manual.eq(manual))
+                            .one(txn)
+                            .await?
+                            .ok_or(sea_orm::DbErr::RecordNotFound(format!(
+                                "{} conflicted on insert but was not found",
+                                stringify!($entity_mod)
+                            )))?;
+                        Ok((winner.id, false))
+                    }
+                    Err(e) => Err(e),
+                }
             }
 
             async fn update(
@@ -205,44 +350,86 @@ macro_rules! impl_named_entity_repo {
                 Ok(result.rows_affected > 0)
             }
 
+            /// Ranks entities by record count with `name` and `min_count`
+            /// applied as `WHERE`/`HAVING` and the page cut by `LIMIT`/`OFFSET`.
+            ///
+            /// **Note:** `next`/`previous` links contain only `limit` and
+            /// `offset`; the `name` and `min_count` filters are not repeated.
             async fn $count_method(
                 &self,
                 db: &sea_orm::DatabaseConnection,
-            ) -> Result<Vec<EntityCountDto>, sea_orm::DbErr> {
-                use sea_orm::{FromQueryResult, QuerySelect as _};
-                use std::collections::HashMap;
+                query: &crate::domains::luna::dto::RecordCountQuery,
+            ) -> Result<PaginatedResponse<EntityCountDto>, sea_orm::DbErr> {
+                use sea_orm::sea_query::{Alias, Expr};
+                use sea_orm::{
+                    FromQueryResult, JoinType, Order, PaginatorTrait as _, QueryOrder as _,
+                    QuerySelect as _,
+                };
 
                 #[derive(FromQueryResult)]
                 struct CountRow {
-                    entity_id: i64,
+                    id: i64,
+                    name: String,
                     count: i64,
                 }
 
-                let counts: Vec<CountRow> = $count_entity_struct::find()
+                // One `LEFT JOIN ... GROUP BY`, so entities without records
+                // still appear with a zero count.
+                let records = $count_entity_struct::belongs_to($entity_struct)
+                    .from($count_entity_mod::Column::$count_fk_column)
+                    .to($entity_mod::Column::Id)
+                    .into();
+                let mut select = $entity_struct::find()
                     .select_only()
-                    .column_as($count_entity_mod::Column::$count_fk_column, "entity_id")
+                    .column($entity_mod::Column::Id)
+                    .column($entity_mod::Column::Name)
                     .column_as($count_entity_mod::Column::Id.count(), "count")
-                    .group_by($count_entity_mod::Column::$count_fk_column)
+                    .join_rev(JoinType::LeftJoin, records)
+                    .group_by($entity_mod::Column::Id)
+                    .group_by($entity_mod::Column::Name);
+                if let Some(name) = query.name.as_deref().filter(|s| !s.trim().is_empty()) {
+                    select = select.filter($entity_mod::Column::Name.contains(name));
+                }
+                if let Some(min_count) = query.min_count {
+                    select = select
+                        .having(Expr::expr($count_entity_mod::Column::Id.count()).gte(min_count));
+                }
+
+                let total = select.clone().count(db).await?;
+                let page_size = query.page_size();
+                let offset = query.offset.unwrap_or(0);
+                let results = select
+                    .order_by(Expr::col(Alias::new("count")), Order::Desc)
+                    .order_by_asc($entity_mod::Column::Id)
+                    .limit(page_size)
+                    .offset(offset)
                     .into_model::<CountRow>()
                     .all(db)
-                    .await?;
-
-                let count_map: HashMap<i64, i64> =
-                    counts.into_iter().map(|c| (c.entity_id, c.count)).collect();
-
-                let entities = $entity_struct::find().all(db).await?;
-                let mut result = Vec::new();
-                for entity in entities {
-                    let count = count_map.get(&entity.id).copied().unwrap_or(0);
-                    result.push(EntityCountDto {
-                        id: entity.id,
-                        name: entity.name,
-                        count,
-                    });
-                }
+                    .await?
+                    .into_iter()
+                    .map(|row| EntityCountDto {
+                        id: row.id,
+                        name: row.name,
+                        count: row.count,
+                    })
+                    .collect();
+
+                let next = (offset + page_size < total)
+                    .then(|| format!("?limit={page_size}&offset={}", offset + page_size));
+                let previous = (offset > 0).then(|| {
+                    format!(
+                        "?limit={page_size}&offset={}",
+                        offset.saturating_sub(page_size)
+                    )
+                });
 
-                result.sort_by(|a, b| b.count.cmp(&a.count));
-                Ok(result)
+                Ok(PaginatedResponse {
+                    count: total as i64,
+                    next,
+                    previous,
+                    next_cursor: None,
+                    results,
+                })
             }
         }
     };
@@ -275,6 +462,82 @@ macro_rules! impl_named_entity_repo {
                 Ok(item.map(<$domain>::from))
             }
 
+            async fn find_by_ids(
+                &self,
+                db: &sea_orm::DatabaseConnection,
+                ids: &[i64],
+            ) -> Result<Vec<$domain>, sea_orm::DbErr> {
+                use sea_orm::{ColumnTrait as _, QueryFilter as _};
+                let items = $entity_struct::find()
+                    .filter($entity_mod::Column::Id.is_in(ids.iter().copied()))
+                    .all(db)
+                    .await?;
+                Ok(super::entity_repo_macro::in_request_order(
+                    ids,
+                    items.into_iter().map(<$domain>::from),
+                    |item| item.id,
+                ))
+            }
+
+            /// Names containing `term` (already lowercased), those starting
+            /// with it first, then by record count. Both conditions compare
+            /// `lower(name)`, which the prefix and trigram indexes cover.
+            async fn autocomplete(
+                &self,
+                db: &sea_orm::DatabaseConnection,
+                term: &str,
+                limit: u64,
+            ) -> Result<Vec<crate::domains::luna::dto::NameSuggestionDto>, sea_orm::DbErr> {
+                use sea_orm::sea_query::{Expr, Func, SimpleExpr};
+                use sea_orm::{
+                    ColumnTrait as _, FromQueryResult, JoinType, Order, QueryFilter as _,
+                    QueryOrder as _, QuerySelect as _,
+                };
+
+                #[derive(FromQueryResult)]
+                struct SuggestionRow {
+                    id: i64,
+                    name: String,
+                }
+
+                let pattern = super::record::escape_like_pattern(term);
+                let lower_name = || -> SimpleExpr {
+                    Func::lower(Expr::col(($entity_mod::Entity, $entity_mod::Column::Name))).into()
+                };
+                let records = $count_entity_struct::belongs_to($entity_struct)
+                    .from($count_entity_mod::Column::$count_fk_column)
+                    .to($entity_mod::Column::Id)
+                    .into();
+                let rows = $entity_struct::find()
+                    .select_only()
+                    .column($entity_mod::Column::Id)
+                    .column($entity_mod::Column::Name)
+                    .join_rev(JoinType::LeftJoin, records)
+                    // The ID 0 placeholder is never suggested.
+                    .filter($entity_mod::Column::Id.ne(0))
+                    .filter(Expr::expr(lower_name()).like(format!("%{pattern}%")))
+                    .group_by($entity_mod::Column::Id)
+                    .group_by($entity_mod::Column::Name)
+                    .order_by(
+                        Expr::expr(lower_name()).like(format!("{pattern}%")),
+                        Order::Desc,
+                    )
+                    .order_by($count_entity_mod::Column::Id.count(), Order::Desc)
+                    .order_by_asc($entity_mod::Column::Name)
+                    .order_by_asc($entity_mod::Column::Id)
+                    .limit(limit)
+                    .into_model::<SuggestionRow>()
+                    .all(db)
+                    .await?;
+                Ok(rows
+                    .into_iter()
+                    .map(|row| crate::domains::luna::dto::NameSuggestionDto {
+                        id: row.id,
+                        name: row.name,
+                    })
+                    .collect())
+            }
+
             async fn find_list(
                 &self,
                 db: &sea_orm::DatabaseConnection,
@@ -313,14 +576,43 @@ macro_rules! impl_named_entity_repo {
                     return Ok((e.id, false));
                 }
 
+                // A concurrent writer may insert the same row between the lookup
+                // above and this insert; the unique index on (name, link, manual)
+                // turns that into a no-op, and the winner's row is returned.
                 let active_model = $entity_mod::ActiveModel {
-                    name: sea_orm::Set(name),
-                    link: sea_orm::Set(link),
+                    name: sea_orm::Set(name.clone()),
+                    link: sea_orm::Set(link.clone()),
                     manual: sea_orm::Set(manual),
                     ..Default::default()
                 };
-                let inserted = active_model.insert(txn).await?;
-                Ok((inserted.id, true))
+                let on_conflict = sea_orm::sea_query::OnConflict::columns([
+                    $entity_mod::Column::Name,
+                    $entity_mod::Column::Link,
+                    $entity_mod::Column::Manual,
+                ])
+                .do_nothing()
+                .to_owned();
+                match $entity_struct::insert(active_model)
+                    .on_conflict(on_conflict)
+                    .exec(txn)
+                    .await
+                {
+                    Ok(res) => Ok((res.last_insert_id, true)),
+                    Err(sea_orm::DbErr::RecordNotInserted) => {
+                        let winner = $entity_struct::find()
+                            .filter($entity_mod::Column::Name.eq(&name))
+                            .filter($entity_mod::Column::Link.eq(&link))
+                            .filter($entity_mod::Column::Manual.eq(manual))
+                            .one(txn)
+                            .await?
+                            .ok_or(sea_orm::DbErr::RecordNotFound(format!(
+                                "{} conflicted on insert but was not found",
+                                stringify!($entity_mod)
+                            )))?;
+                        Ok((winner.id, false))
+                    }
+                    Err(e) => Err(e),
+                }
             }
 
             async fn update(
@@ -372,45 +664,256 @@ macro_rules! impl_named_entity_repo {
                 Ok(result.rows_affected > 0)
             }
 
+            /// Ranks entities by record count with `name` and `min_count`
+            /// applied as `WHERE`/`HAVING` and the page cut by `LIMIT`/`OFFSET`.
+            ///
+            /// **Note:** `next`/`previous` links contain only `limit` and
+            /// `offset`; the `name` and `min_count` filters are not repeated.
             async fn $count_method(
                 &self,
                 db: &sea_orm::DatabaseConnection,
-            ) -> Result<Vec<EntityCountDto>, sea_orm::DbErr> {
-                use sea_orm::{FromQueryResult, QuerySelect as _};
-                use std::collections::HashMap;
+                query: &crate::domains::luna::dto::RecordCountQuery,
+            ) -> Result<PaginatedResponse<EntityCountDto>, sea_orm::DbErr> {
+                use sea_orm::sea_query::{Alias, Expr};
+                use sea_orm::{
+                    FromQueryResult, JoinType, Order, PaginatorTrait as _, QueryOrder as _,
+                    QuerySelect as _,
+                };
 
                 #[derive(FromQueryResult)]
                 struct CountRow {
-                    entity_id: i64,
+                    id: i64,
+                    name: String,
                     count: i64,
                 }
 
-                let counts: Vec<CountRow> = $count_entity_struct::find()
+                // One `LEFT JOIN ... GROUP BY`, so entities without records
+                // still appear with a zero count.
+                let records = $count_entity_struct::belongs_to($entity_struct)
+                    .from($count_entity_mod::Column::$count_fk_column)
+                    .to($entity_mod::Column::Id)
+                    .into();
+                let mut select = $entity_struct::find()
                     .select_only()
-                    .column_as($count_entity_mod::Column::$count_fk_column, "entity_id")
+                    .column($entity_mod::Column::Id)
+                    .column($entity_mod::Column::Name)
                     .column_as($count_entity_mod::Column::Id.count(), "count")
-                    .group_by($count_entity_mod::Column::$count_fk_column)
+                    .join_rev(JoinType::LeftJoin, records)
+                    .group_by($entity_mod::Column::Id)
+                    .group_by($entity_mod::Column::Name);
+                if let Some(name) = query.name.as_deref().filter(|s| !s.trim().is_empty()) {
+                    select = select.filter($entity_mod::Column::Name.contains(name));
+                }
+                if let Some(min_count) = query.min_count {
+                    select = select
+                        .having(Expr::expr($count_entity_mod::Column::Id.count()).gte(min_count));
+                }
+
+                let total = select.clone().count(db).await?;
+                let page_size = query.page_size();
+                let offset = query.offset.unwrap_or(0);
+                let results = select
+                    .order_by(Expr::col(Alias::new("count")), Order::Desc)
+                    .order_by_asc($entity_mod::Column::Id)
+                    .limit(page_size)
+                    .offset(offset)
                     .into_model::<CountRow>()
                     .all(db)
+                    .await?
+                    .into_iter()
+                    .map(|row| EntityCountDto {
+                        id: row.id,
+                        name: row.name,
+                        count: row.count,
+                    })
+                    .collect();
+
+                let next = (offset + page_size < total)
+                    .then(|| format!("?limit={page_size}&offset={}", offset + page_size));
+                let previous = (offset > 0).then(|| {
+                    format!(
+                        "?limit={page_size}&offset={}",
+                        offset.saturating_sub(page_size)
+                    )
+                });
+
+                Ok(PaginatedResponse {
+                    count: total as i64,
+                    next,
+                    previous,
+                    next_cursor: None,
+                    results,
+                })
+            }
+        }
+    };
+}
+
+/// Macro to implement `NamedEntityMergeRepository` for a named entity repo.
+///
+/// # Variants
+/// - `record_fk;` the entity is referenced by a `record` column (Director,
+///   Studio, Label, Series); `$fk_column` is that column (e.g. `DirectorId`).
+/// - `junction;` the entity is referenced through a junction table (Genre,
+///   Idol); `$junction_struct`/`$junction_mod` name it and `$fk_column` is its
+///   entity column (e.g. `GenreId`).
+macro_rules! impl_entity_merge_repo {
+    (
+        record_fk;
+        $repo:ident, $entity_struct:ident, $fk_column:ident
+    ) => {
+        #[async_trait::async_trait]
+        impl crate::domains::luna::domain::NamedEntityMergeRepository for $repo {
+            async fn merge(
+                &self,
+                txn: &sea_orm::DatabaseTransaction,
+                target_id: i64,
+                source_id: i64,
+            ) -> Result<Option<(u64, String)>, sea_orm::DbErr> {
+                use crate::entities::{record, RecordEntity};
+                use sea_orm::sea_query::Expr;
+
+                let Some(target) = $entity_struct::find_by_id(target_id).one(txn).await? else {
+                    return Ok(None);
+                };
+                if $entity_struct::find_by_id(source_id)
+                    .one(txn)
+                    .await?
+                    .is_none()
+                {
+                    return Ok(None);
+                }
+
+                let repointed = RecordEntity::update_many()
+                    .col_expr(record::Column::$fk_column, Expr::value(target_id))
+                    .filter(record::Column::$fk_column.eq(source_id))
+                    .exec(txn)
                     .await?;
+                $entity_struct::delete_by_id(source_id).exec(txn).await?;
+                Ok(Some((repointed.rows_affected, target.name)))
+            }
+        }
+    };
 
-                let count_map: HashMap<i64, i64> =
-                    counts.into_iter().map(|c| (c.entity_id, c.count)).collect();
+    (
+        junction;
+        $repo:ident, $entity_struct:ident, $junction_struct:ident, $junction_mod:ident,
+        $fk_column:ident
+    ) => {
+        #[async_trait::async_trait]
+        impl crate::domains::luna::domain::NamedEntityMergeRepository for $repo {
+            async fn merge(
+                &self,
+                txn: &sea_orm::DatabaseTransaction,
+                target_id: i64,
+                source_id: i64,
+            ) -> Result<Option<(u64, String)>, sea_orm::DbErr> {
+                use sea_orm::sea_query::{Expr, Query};
+                use sea_orm::QuerySelect as _;
 
-                let entities = $entity_struct::find().all(db).await?;
-                let mut result = Vec::new();
-                for entity in entities {
-                    let count = count_map.get(&entity.id).copied().unwrap_or(0);
-                    result.push(EntityCountDto {
-                        id: entity.id,
-                        name: entity.name,
-                        count,
-                    });
+                let Some(target) = $entity_struct::find_by_id(target_id).one(txn).await? else {
+                    return Ok(None);
+                };
+                if $entity_struct::find_by_id(source_id)
+                    .one(txn)
+                    .await?
+                    .is_none()
+                {
+                    return Ok(None);
                 }
 
-                result.sort_by(|a, b| b.count.cmp(&a.count));
-                Ok(result)
+                let affected = $junction_struct::find()
+                    .select_only()
+                    .column($junction_mod::Column::RecordId)
+                    .filter($junction_mod::Column::$fk_column.eq(source_id))
+                    .into_tuple::<String>()
+                    .all(txn)
+                    .await?
+                    .len() as u64;
+
+                // Records already linked to the target keep that row; re-pointing
+                // their source row would violate the junction's unique index.
+                $junction_struct::delete_many()
+                    .filter($junction_mod::Column::$fk_column.eq(source_id))
+                    .filter(
+                        $junction_mod::Column::RecordId.in_subquery(
+                            Query::select()
+                                .column($junction_mod::Column::RecordId)
+                                .from($junction_struct)
+                                .and_where($junction_mod::Column::$fk_column.eq(target_id))
+                                .to_owned(),
+                        ),
+                    )
+                    .exec(txn)
+                    .await?;
+                $junction_struct::update_many()
+                    .col_expr($junction_mod::Column::$fk_column, Expr::value(target_id))
+                    .filter($junction_mod::Column::$fk_column.eq(source_id))
+                    .exec(txn)
+                    .await?;
+                $entity_struct::delete_by_id(source_id).exec(txn).await?;
+                Ok(Some((affected, target.name)))
             }
         }
     };
 }
+
+/// Build the `ORDER BY` list for a raw affinity query.
+///
+/// With no `ordering` the affinity score wins (`score DESC`); otherwise the
+/// allowlisted keys are emitted against the table `alias`. `id` is always the
+/// final tiebreaker. Field names come from `ENTITY_ORDERING_FIELDS`, never
+/// from raw input, so interpolating them is safe.
+pub(super) fn affinity_order_by(
+    pagination: &crate::domains::luna::dto::PaginationQuery,
+    alias: &str,
+) -> String {
+    let keys = pagination
+        .ordering
+        .as_deref()
+        .and_then(|raw| {
+            crate::common::pagination::parse_ordering(
+                raw,
+                crate::domains::luna::dto::ENTITY_ORDERING_FIELDS,
+            )
+            .ok()
+        })
+        .unwrap_or_default();
+    let mut parts: Vec<String> = if keys.is_empty() {
+        vec!["score DESC".to_string()]
+    } else {
+        keys.iter()
+            .map(|k| {
+                let dir = if k.descending { "DESC" } else { "ASC" };
+                format!("{alias}.{} {dir}", k.field)
+            })
+            .collect()
+    };
+    if !keys.iter().any(|k| k.field == "id") {
+        parts.push(format!("{alias}.id ASC"));
+    }
+    parts.join(", ")
+}
+
+/// Arrange `items` in the order of `ids`, each once. IDs without an item are
+/// skipped.
+pub(super) fn in_request_order<T>(
+    ids: &[i64],
+    items: impl IntoIterator<Item = T>,
+    id_of: impl Fn(&T) -> i64,
+) -> Vec<T> {
+    let mut by_id: std::collections::HashMap<i64, T> =
+        items.into_iter().map(|item| (id_of(&item), item)).collect();
+    ids.iter().filter_map(|id| by_id.remove(id)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::in_request_order;
+
+    #[test]
+    fn items_follow_the_requested_ids_once_each() {
+        let found = in_request_order(&[3, 9, 1, 3], [1, 3], |&id| id);
+        assert_eq!(found, [3, 1]);
+    }
+}
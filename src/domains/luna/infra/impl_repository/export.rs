@@ -0,0 +1,99 @@
+use super::record_loader::load_records_batch;
+use crate::domains::luna::{
+    domain::{ExportRepository, NamedEntityRow, Record},
+    dto::ExportEntity,
+};
+use crate::entities::{record, RecordEntity};
+use async_trait::async_trait;
+use sea_orm::{
+    ColumnTrait as _, DatabaseBackend, DatabaseConnection, DbErr, EntityTrait as _,
+    FromQueryResult, Order, QueryFilter as _, QueryOrder as _, QuerySelect as _, Statement,
+};
+
+#[derive(FromQueryResult)]
+struct NamedRow {
+    id: i64,
+    name: String,
+    link: String,
+    manual: bool,
+}
+
+pub struct ExportRepo;
+
+#[async_trait]
+impl ExportRepository for ExportRepo {
+    async fn record_batch(
+        &self,
+        db: &DatabaseConnection,
+        after: Option<String>,
+        limit: u64,
+        max_permission: i32,
+    ) -> Result<Vec<Record>, DbErr> {
+        let mut query = RecordEntity::find()
+            .filter(record::Column::DeletedAt.is_null())
+            .filter(record::Column::Permission.lte(max_permission));
+        if let Some(after) = after {
+            query = query.filter(record::Column::Id.gt(after));
+        }
+        let record_models = query
+            .order_by(record::Column::Id, Order::Asc)
+            .limit(limit)
+            .all(db)
+            .await?;
+        load_records_batch(db, record_models).await
+    }
+
+    async fn entity_batch(
+        &self,
+        db: &DatabaseConnection,
+        entity: ExportEntity,
+        after: i64,
+        limit: u64,
+    ) -> Result<Vec<NamedEntityRow>, DbErr> {
+        // All named entity tables share `(id, name, link, manual)`; the table
+        // name comes from a fixed list, never from user input.
+        let sql = format!(
+            "SELECT id, name, link, manual FROM {} WHERE id > $1 ORDER BY id LIMIT $2",
+            entity.table()
+        );
+        let rows = NamedRow::find_by_statement(Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            &sql,
+            [after.into(), (limit as i64).into()],
+        ))
+        .all(db)
+        .await?;
+        Ok(rows.into_iter().map(NamedEntityRow::from).collect())
+    }
+
+    async fn entity_by_name(
+        &self,
+        db: &DatabaseConnection,
+        entity: ExportEntity,
+        name: &str,
+    ) -> Result<Option<NamedEntityRow>, DbErr> {
+        let sql = format!(
+            "SELECT id, name, link, manual FROM {} WHERE name = $1 ORDER BY id LIMIT 1",
+            entity.table()
+        );
+        let row = NamedRow::find_by_statement(Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            &sql,
+            [name.into()],
+        ))
+        .one(db)
+        .await?;
+        Ok(row.map(NamedEntityRow::from))
+    }
+}
+
+impl From<NamedRow> for NamedEntityRow {
+    fn from(row: NamedRow) -> Self {
+        Self {
+            id: row.id,
+            name: row.name,
+            link: row.link,
+            manual: row.manual,
+        }
+    }
+}
@@ -1,16 +1,22 @@
+use super::entity_repo_macro::affinity_order_by;
 use crate::domains::luna::{
-    domain::{Genre, GenreAffinityRepository, GenreRepository},
+    domain::{Genre, GenreAffinityRepository, GenreCategoryRepository, GenreRepository},
     dto::{
-        CreateGenreDto, EntityCountDto, PaginatedResponse, PaginationQuery, SearchGenreDto,
-        UpdateGenreDto,
+        CreateGenreCategoryDto, CreateGenreDto, EntityCountDto, GenreCategoryCountDto,
+        GenreCategoryDto, PaginatedResponse, PaginationQuery, SearchGenreDto, UpdateGenreDto,
     },
 };
-use crate::entities::{genre, record_genre, GenreEntity, RecordGenreEntity};
+use crate::entities::{
+    genre, genre_category, record_genre, GenreCategoryEntity, GenreEntity, RecordGenreEntity,
+};
 use async_trait::async_trait;
+use chrono::Utc;
 use sea_orm::{
-    ActiveModelTrait as _, ColumnTrait as _, DatabaseBackend, DatabaseConnection, DbErr,
-    EntityTrait as _, FromQueryResult, PaginatorTrait as _, QueryFilter as _, Statement, Value,
+    ActiveModelTrait as _, ActiveValue::Set, ColumnTrait as _, DatabaseBackend, DatabaseConnection,
+    DatabaseTransaction, DbErr, EntityTrait as _, FromQueryResult, PaginatorTrait as _,
+    QueryFilter as _, QueryOrder as _, QuerySelect as _, Statement, Value,
 };
+use std::collections::HashMap;
 
 impl_named_entity_repo!(
     paginated;
@@ -19,6 +25,11 @@ impl_named_entity_repo!(
     get_genre_record_counts, RecordGenreEntity, record_genre, GenreId
 );
 
+impl_entity_merge_repo!(
+    junction;
+    GenreRepo, GenreEntity, RecordGenreEntity, record_genre, GenreId
+);
+
 // Tunable hyper-parameters for the affinity score. These are fixed constants
 // (not user input), interpolated into the SQL as literals. Two main knobs:
 // `AFFINITY_M_V` controls how hard small samples are pulled toward the prior
@@ -54,6 +65,7 @@ struct AffinityGenreRow {
     name: String,
     link: String,
     manual: bool,
+    category_id: Option<i64>,
 }
 
 impl From<AffinityGenreRow> for Genre {
@@ -63,6 +75,7 @@ impl From<AffinityGenreRow> for Genre {
             name: row.name,
             link: row.link,
             manual: row.manual,
+            category_id: row.category_id,
         }
     }
 }
@@ -152,7 +165,7 @@ impl GenreAffinityRepository for GenreRepo {
         // Genres relate to records many-to-many via record_genre, so the
         // aggregate groups the junction rows (like idol_participation).
         let select_sql = format!(
-            "SELECT g.id, g.name, g.link, g.manual, \
+            "SELECT g.id, g.name, g.link, g.manual, g.category_id, \
              COALESCE( \
                CASE WHEN agg.total > 0 \
                     THEN ( {W_V} * ((agg.viewed::float8 + {M_V} * {C_V}) / (agg.total::float8 + {M_V})) \
@@ -170,10 +183,11 @@ impl GenreAffinityRepository for GenreRepo {
                FROM record_genre rg \
                LEFT JOIN user_record_interaction uri \
                       ON uri.record_id = rg.record_id AND uri.user_id = $1 \
+               JOIN record r ON r.id = rg.record_id AND r.deleted_at IS NULL \
                GROUP BY rg.genre_id \
              ) agg ON agg.genre_id = g.id\
              {where_clause} \
-             ORDER BY score DESC, g.id ASC \
+             ORDER BY {order_by} \
              LIMIT ${limit_param} OFFSET ${offset_param}",
             W_V = AFFINITY_W_V,
             W_L = AFFINITY_W_L,
@@ -186,6 +200,7 @@ impl GenreAffinityRepository for GenreRepo {
             N0 = AFFINITY_N0,
             limit_param = 2 + filter_binds.len(),
             offset_param = 3 + filter_binds.len(),
+            order_by = affinity_order_by(&pagination, "g"),
         );
 
         let mut select_binds: Vec<Value> = Vec::with_capacity(filter_binds.len() + 3);
@@ -213,9 +228,14 @@ impl GenreAffinityRepository for GenreRepo {
         let total_items = count_row.map_or(0, |r| r.cnt);
         let total_pages = (total_items as u64).div_ceil(page_size);
 
+        let ordering_param = pagination
+            .ordering
+            .as_deref()
+            .map(|o| format!("&ordering={o}"))
+            .unwrap_or_default();
         let next = if page_num + 1 < total_pages {
             Some(format!(
-                "?limit={page_size}&offset={}",
+                "?limit={page_size}&offset={}{ordering_param}",
                 (page_num + 1) * page_size
             ))
         } else {
@@ -223,7 +243,7 @@ impl GenreAffinityRepository for GenreRepo {
         };
         let previous = if page_num > 0 {
             Some(format!(
-                "?limit={page_size}&offset={}",
+                "?limit={page_size}&offset={}{ordering_param}",
                 (page_num - 1) * page_size
             ))
         } else {
@@ -234,6 +254,7 @@ impl GenreAffinityRepository for GenreRepo {
             count: total_items,
             next,
             previous,
+            next_cursor: None,
             results,
         })
     }
@@ -244,3 +265,268 @@ impl GenreAffinityRepository for GenreRepo {
 struct CountRow {
     cnt: i64,
 }
+
+fn category_to_dto(category: genre_category::Model) -> GenreCategoryDto {
+    GenreCategoryDto {
+        id: category.id,
+        name: category.name,
+        description: category.description,
+    }
+}
+
+/// A genre with its record count, for the per-category counts.
+#[derive(Debug, FromQueryResult)]
+struct CategoryGenreCountRow {
+    id: i64,
+    name: String,
+    category_id: Option<i64>,
+    count: i64,
+}
+
+/// Records carrying any genre of a category; `category_id` is `None` for
+/// the uncategorized genres.
+#[derive(Debug, FromQueryResult)]
+struct CategoryTotalRow {
+    category_id: Option<i64>,
+    count: i64,
+}
+
+/// Group `genres` (highest count first) under `categories`, ranking the
+/// categories by their `totals`, then by ID. Uncategorized genres come last,
+/// and only when there are any.
+fn group_by_category(
+    categories: Vec<GenreCategoryDto>,
+    genres: Vec<CategoryGenreCountRow>,
+    totals: Vec<CategoryTotalRow>,
+) -> Vec<GenreCategoryCountDto> {
+    let totals: HashMap<Option<i64>, i64> = totals
+        .into_iter()
+        .map(|row| (row.category_id, row.count))
+        .collect();
+    let mut members: HashMap<Option<i64>, Vec<EntityCountDto>> = HashMap::new();
+    for genre in genres {
+        members
+            .entry(genre.category_id)
+            .or_default()
+            .push(EntityCountDto {
+                id: genre.id,
+                name: genre.name,
+                count: genre.count,
+            });
+    }
+
+    let mut grouped: Vec<GenreCategoryCountDto> = categories
+        .into_iter()
+        .map(|category| GenreCategoryCountDto {
+            count: totals.get(&Some(category.id)).copied().unwrap_or(0),
+            genres: members.remove(&Some(category.id)).unwrap_or_default(),
+            id: Some(category.id),
+            name: Some(category.name),
+        })
+        .collect();
+    grouped.sort_by(|a, b| b.count.cmp(&a.count).then(a.id.cmp(&b.id)));
+    if let Some(genres) = members.remove(&None) {
+        grouped.push(GenreCategoryCountDto {
+            id: None,
+            name: None,
+            count: totals.get(&None).copied().unwrap_or(0),
+            genres,
+        });
+    }
+    grouped
+}
+
+#[async_trait]
+impl GenreCategoryRepository for GenreRepo {
+    async fn find_categories(
+        &self,
+        db: &DatabaseConnection,
+    ) -> Result<Vec<GenreCategoryDto>, DbErr> {
+        let rows = GenreCategoryEntity::find()
+            .order_by_asc(genre_category::Column::Name)
+            .all(db)
+            .await?;
+        Ok(rows.into_iter().map(category_to_dto).collect())
+    }
+
+    async fn find_category(
+        &self,
+        db: &DatabaseConnection,
+        id: i64,
+    ) -> Result<Option<GenreCategoryDto>, DbErr> {
+        let row = GenreCategoryEntity::find_by_id(id).one(db).await?;
+        Ok(row.map(category_to_dto))
+    }
+
+    async fn create_category(
+        &self,
+        db: &DatabaseConnection,
+        dto: CreateGenreCategoryDto,
+    ) -> Result<i64, DbErr> {
+        let active = genre_category::ActiveModel {
+            name: Set(dto.name),
+            description: Set(dto.description),
+            create_time: Set(Utc::now().date_naive()),
+            ..Default::default()
+        };
+        let result = GenreCategoryEntity::insert(active).exec(db).await?;
+        Ok(result.last_insert_id)
+    }
+
+    async fn update_category(
+        &self,
+        db: &DatabaseConnection,
+        id: i64,
+        dto: CreateGenreCategoryDto,
+    ) -> Result<bool, DbErr> {
+        let result = GenreCategoryEntity::update_many()
+            .set(genre_category::ActiveModel {
+                name: Set(dto.name),
+                description: Set(dto.description),
+                ..Default::default()
+            })
+            .filter(genre_category::Column::Id.eq(id))
+            .exec(db)
+            .await?;
+        Ok(result.rows_affected > 0)
+    }
+
+    async fn delete_category(
+        &self,
+        txn: &DatabaseTransaction,
+        id: i64,
+    ) -> Result<Option<Vec<i64>>, DbErr> {
+        let genre_ids: Vec<i64> = GenreEntity::find()
+            .select_only()
+            .column(genre::Column::Id)
+            .filter(genre::Column::CategoryId.eq(id))
+            .into_tuple()
+            .all(txn)
+            .await?;
+        // The foreign key leaves the genres of the category uncategorized.
+        let result = GenreCategoryEntity::delete_by_id(id).exec(txn).await?;
+        Ok((result.rows_affected > 0).then_some(genre_ids))
+    }
+
+    async fn set_genre_category(
+        &self,
+        txn: &DatabaseTransaction,
+        id: i64,
+        category_id: Option<i64>,
+    ) -> Result<Option<Genre>, DbErr> {
+        let Some(existing) = GenreEntity::find_by_id(id).one(txn).await? else {
+            return Ok(None);
+        };
+        let mut active_model: genre::ActiveModel = existing.into();
+        active_model.category_id = Set(category_id);
+        let updated = active_model.update(txn).await?;
+        Ok(Some(Genre::from(updated)))
+    }
+
+    async fn category_record_counts(
+        &self,
+        db: &DatabaseConnection,
+    ) -> Result<Vec<GenreCategoryCountDto>, DbErr> {
+        let categories = self.find_categories(db).await?;
+        // Counted like `get_genre_record_counts`, so the genre counts match.
+        let genres = CategoryGenreCountRow::find_by_statement(Statement::from_string(
+            DatabaseBackend::Postgres,
+            "SELECT g.id, g.name, g.category_id, COUNT(rg.id) AS count \
+             FROM genre g LEFT JOIN record_genre rg ON rg.genre_id = g.id \
+             GROUP BY g.id, g.name, g.category_id \
+             ORDER BY count DESC, g.id ASC",
+        ))
+        .all(db)
+        .await?;
+        let totals = CategoryTotalRow::find_by_statement(Statement::from_string(
+            DatabaseBackend::Postgres,
+            "SELECT g.category_id, COUNT(DISTINCT rg.record_id) AS count \
+             FROM record_genre rg JOIN genre g ON g.id = rg.genre_id \
+             GROUP BY g.category_id",
+        ))
+        .all(db)
+        .await?;
+        Ok(group_by_category(categories, genres, totals))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{group_by_category, CategoryGenreCountRow, CategoryTotalRow, GenreCategoryDto};
+
+    fn category(id: i64, name: &str) -> GenreCategoryDto {
+        GenreCategoryDto {
+            id,
+            name: name.to_owned(),
+            description: None,
+        }
+    }
+
+    fn genre(id: i64, category_id: Option<i64>, count: i64) -> CategoryGenreCountRow {
+        CategoryGenreCountRow {
+            id,
+            name: format!("Genre {id}"),
+            category_id,
+            count,
+        }
+    }
+
+    #[test]
+    fn categories_rank_by_distinct_records_with_uncategorized_last() {
+        let grouped = group_by_category(
+            vec![
+                category(1, "Theme"),
+                category(2, "Format"),
+                category(3, "Empty"),
+            ],
+            vec![
+                genre(10, Some(1), 4),
+                genre(11, None, 3),
+                genre(12, Some(2), 3),
+                genre(13, Some(1), 2),
+            ],
+            vec![
+                CategoryTotalRow {
+                    category_id: Some(1),
+                    count: 5,
+                },
+                CategoryTotalRow {
+                    category_id: Some(2),
+                    count: 3,
+                },
+                CategoryTotalRow {
+                    category_id: None,
+                    count: 3,
+                },
+            ],
+        );
+
+        let summary: Vec<(Option<i64>, i64, Vec<i64>)> = grouped
+            .iter()
+            .map(|c| (c.id, c.count, c.genres.iter().map(|g| g.id).collect()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (Some(1), 5, vec![10, 13]),
+                (Some(2), 3, vec![12]),
+                (Some(3), 0, vec![]),
+                (None, 3, vec![11]),
+            ]
+        );
+        assert_eq!(grouped[0].name.as_deref(), Some("Theme"));
+        assert!(grouped[3].name.is_none());
+    }
+
+    #[test]
+    fn uncategorized_group_is_left_out_when_every_genre_has_a_category() {
+        let grouped = group_by_category(
+            vec![category(1, "Theme")],
+            vec![genre(10, Some(1), 0)],
+            Vec::new(),
+        );
+        assert_eq!(grouped.len(), 1);
+        assert_eq!(grouped[0].count, 0);
+        assert_eq!(grouped[0].genres.len(), 1);
+    }
+}
@@ -1,15 +1,21 @@
+use super::entity_repo_macro::affinity_order_by;
+use super::record::escape_like_pattern;
 use crate::domains::luna::{
-    domain::{Idol, IdolAffinityRepository, IdolRepository},
+    domain::{Idol, IdolAffinityRepository, IdolProfileRepository, IdolRepository},
     dto::{
-        CreateIdolDto, EntityCountDto, PaginatedResponse, PaginationQuery, SearchIdolDto,
-        UpdateIdolDto,
+        CreateIdolDto, EntityCountDto, IdolProfileDto, PaginatedResponse, PaginationQuery,
+        SearchIdolDto, UpdateIdolDto,
     },
 };
-use crate::entities::{idol, idol_participation, IdolEntity, IdolParticipationEntity};
+use crate::entities::{
+    idol, idol_alias, idol_participation, IdolAliasEntity, IdolEntity, IdolParticipationEntity,
+};
 use async_trait::async_trait;
+use chrono::NaiveDate;
 use sea_orm::{
-    ActiveModelTrait as _, ColumnTrait as _, DatabaseBackend, DatabaseConnection, DbErr,
-    EntityTrait as _, FromQueryResult, PaginatorTrait as _, QueryFilter as _, Statement, Value,
+    ActiveModelTrait as _, ActiveValue::Set, ColumnTrait as _, DatabaseBackend, DatabaseConnection,
+    DatabaseTransaction, DbErr, EntityTrait as _, FromQueryResult, PaginatorTrait as _,
+    QueryFilter as _, QueryOrder as _, QuerySelect as _, Statement, Value,
 };
 
 impl_named_entity_repo!(
@@ -28,6 +34,47 @@ impl_named_entity_repo!(
     IdolId
 );
 
+impl_entity_merge_repo!(
+    junction;
+    IdolRepo, IdolEntity, IdolParticipationEntity, idol_participation, IdolId
+);
+
+impl IdolRepo {
+    /// Find-or-create for the idols named in record bodies. A name no idol
+    /// has, but exactly one idol is known by as an alias, resolves to that
+    /// idol; any other name goes through [`IdolRepository::create`]. Returns
+    /// the idol ID and, when an alias was resolved, the idol's name.
+    pub(super) async fn create_or_resolve_alias(
+        &self,
+        txn: &DatabaseTransaction,
+        dto: CreateIdolDto,
+    ) -> Result<(i64, Option<String>), DbErr> {
+        let named = IdolEntity::find()
+            .filter(idol::Column::Name.eq(&dto.name))
+            .count(txn)
+            .await?;
+        if named == 0 {
+            // Aliases are unique per idol only; a name shared by several
+            // idols is ambiguous and left alone.
+            let known_as: Vec<i64> = IdolAliasEntity::find()
+                .select_only()
+                .column(idol_alias::Column::IdolId)
+                .filter(idol_alias::Column::Alias.eq(&dto.name))
+                .limit(2)
+                .into_tuple()
+                .all(txn)
+                .await?;
+            if let [id] = known_as[..] {
+                if let Some(idol) = IdolEntity::find_by_id(id).one(txn).await? {
+                    return Ok((id, Some(idol.name)));
+                }
+            }
+        }
+        let (id, _) = self.create(txn, dto).await?;
+        Ok((id, None))
+    }
+}
+
 // Tunable hyper-parameters for the affinity score. These are fixed constants
 // (not user input), interpolated into the SQL as literals. Two main knobs:
 // `AFFINITY_M_V` controls how hard small samples are pulled toward the prior
@@ -63,6 +110,12 @@ struct AffinityIdolRow {
     name: String,
     link: String,
     manual: bool,
+    birthday: Option<NaiveDate>,
+    debut_date: Option<NaiveDate>,
+    height_cm: Option<i32>,
+    bust_cm: Option<i32>,
+    waist_cm: Option<i32>,
+    hips_cm: Option<i32>,
 }
 
 impl From<AffinityIdolRow> for Idol {
@@ -72,6 +125,12 @@ impl From<AffinityIdolRow> for Idol {
             name: row.name,
             link: row.link,
             manual: row.manual,
+            birthday: row.birthday,
+            debut_date: row.debut_date,
+            height_cm: row.height_cm,
+            bust_cm: row.bust_cm,
+            waist_cm: row.waist_cm,
+            hips_cm: row.hips_cm,
         }
     }
 }
@@ -84,7 +143,8 @@ impl From<AffinityIdolRow> for Idol {
 /// parameter (never interpolated) to avoid SQL injection. Returns the clause
 /// (empty string when no filter applies) and the ordered bind values; the
 /// `$user_id` bind is prepended by the caller as `$1`, so placeholders here
-/// start at `next_param`.
+/// start at `next_param`. The idol-only `search` term is a substring match
+/// against `name`, `link` or any alias of the idol.
 fn build_affinity_filter(search_dto: &SearchIdolDto, next_param: usize) -> (String, Vec<Value>) {
     let mut clauses: Vec<String> = Vec::new();
     let mut binds: Vec<Value> = Vec::new();
@@ -103,6 +163,19 @@ fn build_affinity_filter(search_dto: &SearchIdolDto, next_param: usize) -> (Stri
     if let Some(link) = search_dto.link.as_deref().filter(|s| !s.trim().is_empty()) {
         clauses.push(format!("i.link LIKE '%' || ${p} || '%'"));
         binds.push(link.into());
+        p += 1;
+    }
+    if let Some(term) = search_dto.search.as_deref().map(str::trim) {
+        if !term.is_empty() {
+            // Free-text search matches either column or an alias, with LIKE
+            // wildcards in the term escaped so they match literally.
+            clauses.push(format!(
+                "(i.name LIKE '%' || ${p} || '%' OR i.link LIKE '%' || ${p} || '%' \
+                 OR EXISTS (SELECT 1 FROM idol_alias a \
+                            WHERE a.idol_id = i.id AND a.alias LIKE '%' || ${p} || '%'))"
+            ));
+            binds.push(escape_like_pattern(term).into());
+        }
     }
 
     let clause = if clauses.is_empty() {
@@ -159,7 +232,8 @@ impl IdolAffinityRepository for IdolRepo {
         // The outer CASE guards total=0; both rate denominators are always
         // positive (M_V, M_L > 0) so there is no division by zero.
         let select_sql = format!(
-            "SELECT i.id, i.name, i.link, i.manual, \
+            "SELECT i.id, i.name, i.link, i.manual, i.birthday, i.debut_date, \
+             i.height_cm, i.bust_cm, i.waist_cm, i.hips_cm, \
              COALESCE( \
                CASE WHEN agg.total > 0 \
                     THEN ( {W_V} * ((agg.viewed::float8 + {M_V} * {C_V}) / (agg.total::float8 + {M_V})) \
@@ -177,10 +251,11 @@ impl IdolAffinityRepository for IdolRepo {
                FROM idol_participation ip \
                LEFT JOIN user_record_interaction uri \
                       ON uri.record_id = ip.record_id AND uri.user_id = $1 \
+               JOIN record r ON r.id = ip.record_id AND r.deleted_at IS NULL \
                GROUP BY ip.idol_id \
              ) agg ON agg.idol_id = i.id\
              {where_clause} \
-             ORDER BY score DESC, i.id ASC \
+             ORDER BY {order_by} \
              LIMIT ${limit_param} OFFSET ${offset_param}",
             W_V = AFFINITY_W_V,
             W_L = AFFINITY_W_L,
@@ -193,6 +268,7 @@ impl IdolAffinityRepository for IdolRepo {
             N0 = AFFINITY_N0,
             limit_param = 2 + filter_binds.len(),
             offset_param = 3 + filter_binds.len(),
+            order_by = affinity_order_by(&pagination, "i"),
         );
 
         let mut select_binds: Vec<Value> = Vec::with_capacity(filter_binds.len() + 3);
@@ -220,9 +296,14 @@ impl IdolAffinityRepository for IdolRepo {
         let total_items = count_row.map_or(0, |r| r.cnt);
         let total_pages = (total_items as u64).div_ceil(page_size);
 
+        let ordering_param = pagination
+            .ordering
+            .as_deref()
+            .map(|o| format!("&ordering={o}"))
+            .unwrap_or_default();
         let next = if page_num + 1 < total_pages {
             Some(format!(
-                "?limit={page_size}&offset={}",
+                "?limit={page_size}&offset={}{ordering_param}",
                 (page_num + 1) * page_size
             ))
         } else {
@@ -230,7 +311,7 @@ impl IdolAffinityRepository for IdolRepo {
         };
         let previous = if page_num > 0 {
             Some(format!(
-                "?limit={page_size}&offset={}",
+                "?limit={page_size}&offset={}{ordering_param}",
                 (page_num - 1) * page_size
             ))
         } else {
@@ -241,6 +322,7 @@ impl IdolAffinityRepository for IdolRepo {
             count: total_items,
             next,
             previous,
+            next_cursor: None,
             results,
         })
     }
@@ -251,3 +333,52 @@ impl IdolAffinityRepository for IdolRepo {
 struct CountRow {
     cnt: i64,
 }
+
+#[async_trait]
+impl IdolProfileRepository for IdolRepo {
+    async fn find_aliases(&self, db: &DatabaseConnection, id: i64) -> Result<Vec<String>, DbErr> {
+        IdolAliasEntity::find()
+            .select_only()
+            .column(idol_alias::Column::Alias)
+            .filter(idol_alias::Column::IdolId.eq(id))
+            .order_by_asc(idol_alias::Column::Alias)
+            .into_tuple()
+            .all(db)
+            .await
+    }
+
+    async fn replace_profile(
+        &self,
+        txn: &DatabaseTransaction,
+        id: i64,
+        profile: &IdolProfileDto,
+    ) -> Result<Option<Idol>, DbErr> {
+        let Some(existing) = IdolEntity::find_by_id(id).one(txn).await? else {
+            return Ok(None);
+        };
+
+        let mut active_model: idol::ActiveModel = existing.into();
+        active_model.birthday = Set(profile.birthday);
+        active_model.debut_date = Set(profile.debut_date);
+        active_model.height_cm = Set(profile.height_cm);
+        active_model.bust_cm = Set(profile.bust_cm);
+        active_model.waist_cm = Set(profile.waist_cm);
+        active_model.hips_cm = Set(profile.hips_cm);
+        let updated = active_model.update(txn).await?;
+
+        IdolAliasEntity::delete_many()
+            .filter(idol_alias::Column::IdolId.eq(id))
+            .exec(txn)
+            .await?;
+        if !profile.aliases.is_empty() {
+            let aliases = profile.aliases.iter().map(|alias| idol_alias::ActiveModel {
+                idol_id: Set(id),
+                alias: Set(alias.clone()),
+                ..Default::default()
+            });
+            IdolAliasEntity::insert_many(aliases).exec(txn).await?;
+        }
+
+        Ok(Some(Idol::from(updated)))
+    }
+}
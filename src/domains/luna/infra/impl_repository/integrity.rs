@@ -0,0 +1,184 @@
+use crate::domains::luna::{
+    domain::IntegrityRepository,
+    dto::{OrphanedJunctionRowsDto, OrphanedRowsDto, PlaceholderReferencesDto, RecordIssueDto},
+};
+use crate::entities::{idol, record, IdolEntity, RecordEntity};
+use async_trait::async_trait;
+use sea_orm::{
+    ColumnTrait as _, ConnectionTrait as _, DatabaseBackend, DatabaseConnection, DbErr,
+    EntityTrait as _, FromQueryResult, QueryFilter as _, QueryOrder as _, QuerySelect as _,
+    Statement,
+};
+
+#[derive(FromQueryResult)]
+struct RecordIssueRow {
+    count: i64,
+    sample_ids: Vec<String>,
+}
+
+#[derive(FromQueryResult)]
+struct OrphanedRowsRow {
+    count: i64,
+    sample_ids: Vec<i64>,
+}
+
+/// Live records matching the SQL `condition`.
+async fn record_issue(
+    db: &DatabaseConnection,
+    condition: &str,
+    sample: u64,
+) -> Result<RecordIssueDto, DbErr> {
+    let row = RecordIssueRow::find_by_statement(Statement::from_sql_and_values(
+        DatabaseBackend::Postgres,
+        format!(
+            "SELECT COUNT(*) AS count, \
+                    COALESCE((array_agg(r.id ORDER BY r.id))[1:$1::INT], '{{}}'::TEXT[]) \
+                        AS sample_ids \
+             FROM record r \
+             WHERE r.deleted_at IS NULL AND ({condition})"
+        ),
+        [(sample as i64).into()],
+    ))
+    .one(db)
+    .await?;
+    Ok(row
+        .map(|row| RecordIssueDto {
+            count: row.count as u64,
+            sample_ids: row.sample_ids,
+        })
+        .unwrap_or_default())
+}
+
+/// Rows of `table` whose `record_id` points at a missing record or, when
+/// `entity` names a `(column, table)`, whose column points at a missing entity.
+async fn orphaned_rows_in(
+    db: &DatabaseConnection,
+    table: &str,
+    entity: Option<(&str, &str)>,
+    sample: u64,
+) -> Result<OrphanedRowsDto, DbErr> {
+    let entity_missing = entity.map_or_else(String::new, |(column, entity_table)| {
+        format!(" OR NOT EXISTS (SELECT 1 FROM {entity_table} e WHERE e.id = t.{column})")
+    });
+    let row = OrphanedRowsRow::find_by_statement(Statement::from_sql_and_values(
+        DatabaseBackend::Postgres,
+        format!(
+            "SELECT COUNT(*) AS count, \
+                    COALESCE((array_agg(t.id ORDER BY t.id))[1:$1::INT], '{{}}'::BIGINT[]) \
+                        AS sample_ids \
+             FROM {table} t \
+             WHERE NOT EXISTS (SELECT 1 FROM record r WHERE r.id = t.record_id){entity_missing}"
+        ),
+        [(sample as i64).into()],
+    ))
+    .one(db)
+    .await?;
+    Ok(row
+        .map(|row| OrphanedRowsDto {
+            count: row.count as u64,
+            sample_ids: row.sample_ids,
+        })
+        .unwrap_or_default())
+}
+
+pub struct IntegrityRepo;
+
+#[async_trait]
+impl IntegrityRepository for IntegrityRepo {
+    async fn placeholder_references(
+        &self,
+        db: &DatabaseConnection,
+        sample: u64,
+    ) -> Result<PlaceholderReferencesDto, DbErr> {
+        Ok(PlaceholderReferencesDto {
+            director: record_issue(db, "r.director_id = 0", sample).await?,
+            studio: record_issue(db, "r.studio_id = 0", sample).await?,
+            label: record_issue(db, "r.label_id = 0", sample).await?,
+            series: record_issue(db, "r.series_id = 0", sample).await?,
+        })
+    }
+
+    async fn has_links_without_links(
+        &self,
+        db: &DatabaseConnection,
+        sample: u64,
+    ) -> Result<RecordIssueDto, DbErr> {
+        record_issue(
+            db,
+            "r.has_links AND NOT EXISTS (SELECT 1 FROM links l WHERE l.record_id = r.id)",
+            sample,
+        )
+        .await
+    }
+
+    async fn orphaned_rows(
+        &self,
+        db: &DatabaseConnection,
+        sample: u64,
+    ) -> Result<OrphanedJunctionRowsDto, DbErr> {
+        Ok(OrphanedJunctionRowsDto {
+            record_genre: orphaned_rows_in(db, "record_genre", Some(("genre_id", "genre")), sample)
+                .await?,
+            idol_participation: orphaned_rows_in(
+                db,
+                "idol_participation",
+                Some(("idol_id", "idol")),
+                sample,
+            )
+            .await?,
+            record_tag: orphaned_rows_in(db, "record_tag", Some(("tag_id", "tag")), sample).await?,
+            links: orphaned_rows_in(db, "links", None, sample).await?,
+        })
+    }
+
+    async fn image_counts(&self, db: &DatabaseConnection) -> Result<Vec<(String, i32)>, DbErr> {
+        RecordEntity::find()
+            .select_only()
+            .column(record::Column::Id)
+            .column(record::Column::LocalImgCount)
+            .filter(record::Column::DeletedAt.is_null())
+            .order_by_asc(record::Column::Id)
+            .into_tuple()
+            .all(db)
+            .await
+    }
+
+    async fn record_ids(&self, db: &DatabaseConnection) -> Result<Vec<String>, DbErr> {
+        RecordEntity::find()
+            .select_only()
+            .column(record::Column::Id)
+            .into_tuple()
+            .all(db)
+            .await
+    }
+
+    async fn idol_names(&self, db: &DatabaseConnection) -> Result<Vec<String>, DbErr> {
+        IdolEntity::find()
+            .select_only()
+            .column(idol::Column::Name)
+            .into_tuple()
+            .all(db)
+            .await
+    }
+
+    async fn update_image_counts(
+        &self,
+        db: &DatabaseConnection,
+        counts: Vec<(String, i32)>,
+    ) -> Result<u64, DbErr> {
+        if counts.is_empty() {
+            return Ok(0);
+        }
+        let (ids, counts): (Vec<String>, Vec<i32>) = counts.into_iter().unzip();
+        let result = db
+            .execute(Statement::from_sql_and_values(
+                DatabaseBackend::Postgres,
+                "UPDATE record r SET local_img_count = v.count \
+                 FROM unnest($1::TEXT[], $2::INT[]) AS v(id, count) \
+                 WHERE r.id = v.id AND r.local_img_count <> v.count",
+                [ids.into(), counts.into()],
+            ))
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
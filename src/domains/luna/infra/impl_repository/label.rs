@@ -1,3 +1,4 @@
+use super::entity_repo_macro::affinity_order_by;
 use crate::domains::luna::{
     domain::{Label, LabelAffinityRepository, LabelRepository},
     dto::{
@@ -19,6 +20,11 @@ impl_named_entity_repo!(
     get_label_record_counts, RecordEntity, record, LabelId
 );
 
+impl_entity_merge_repo!(
+    record_fk;
+    LabelRepo, LabelEntity, LabelId
+);
+
 // Tunable hyper-parameters for the affinity score. These are fixed constants
 // (not user input), interpolated into the SQL as literals. Two main knobs:
 // `AFFINITY_M_V` controls how hard small samples are pulled toward the prior
@@ -54,6 +60,7 @@ struct AffinityLabelRow {
     name: String,
     link: String,
     manual: bool,
+    studio_id: Option<i64>,
 }
 
 impl From<AffinityLabelRow> for Label {
@@ -63,6 +70,7 @@ impl From<AffinityLabelRow> for Label {
             name: row.name,
             link: row.link,
             manual: row.manual,
+            studio_id: row.studio_id,
         }
     }
 }
@@ -152,7 +160,7 @@ impl LabelAffinityRepository for LabelRepo {
         // Labels relate to records via record.label_id (foreign key), so the
         // aggregate groups records directly (no junction table).
         let select_sql = format!(
-            "SELECT l.id, l.name, l.link, l.manual, \
+            "SELECT l.id, l.name, l.link, l.manual, l.studio_id, \
              COALESCE( \
                CASE WHEN agg.total > 0 \
                     THEN ( {W_V} * ((agg.viewed::float8 + {M_V} * {C_V}) / (agg.total::float8 + {M_V})) \
@@ -170,10 +178,11 @@ impl LabelAffinityRepository for LabelRepo {
                FROM record r \
                LEFT JOIN user_record_interaction uri \
                       ON uri.record_id = r.id AND uri.user_id = $1 \
+               WHERE r.deleted_at IS NULL \
                GROUP BY r.label_id \
              ) agg ON agg.entity_id = l.id\
              {where_clause} \
-             ORDER BY score DESC, l.id ASC \
+             ORDER BY {order_by} \
              LIMIT ${limit_param} OFFSET ${offset_param}",
             W_V = AFFINITY_W_V,
             W_L = AFFINITY_W_L,
@@ -186,6 +195,7 @@ impl LabelAffinityRepository for LabelRepo {
             N0 = AFFINITY_N0,
             limit_param = 2 + filter_binds.len(),
             offset_param = 3 + filter_binds.len(),
+            order_by = affinity_order_by(&pagination, "l"),
         );
 
         let mut select_binds: Vec<Value> = Vec::with_capacity(filter_binds.len() + 3);
@@ -213,9 +223,14 @@ impl LabelAffinityRepository for LabelRepo {
         let total_items = count_row.map_or(0, |r| r.cnt);
         let total_pages = (total_items as u64).div_ceil(page_size);
 
+        let ordering_param = pagination
+            .ordering
+            .as_deref()
+            .map(|o| format!("&ordering={o}"))
+            .unwrap_or_default();
         let next = if page_num + 1 < total_pages {
             Some(format!(
-                "?limit={page_size}&offset={}",
+                "?limit={page_size}&offset={}{ordering_param}",
                 (page_num + 1) * page_size
             ))
         } else {
@@ -223,7 +238,7 @@ impl LabelAffinityRepository for LabelRepo {
         };
         let previous = if page_num > 0 {
             Some(format!(
-                "?limit={page_size}&offset={}",
+                "?limit={page_size}&offset={}{ordering_param}",
                 (page_num - 1) * page_size
             ))
         } else {
@@ -234,6 +249,7 @@ impl LabelAffinityRepository for LabelRepo {
             count: total_items,
             next,
             previous,
+            next_cursor: None,
             results,
         })
     }
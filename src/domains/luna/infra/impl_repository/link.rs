@@ -0,0 +1,195 @@
+use super::record::sync_has_links;
+use crate::domains::luna::{
+    domain::{Link, LinkRepository},
+    dto::{normalize_link_url, LinkSearchQuery, PatchLinkDto},
+};
+use crate::entities::{links, record, LinksEntity};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sea_orm::sea_query::{JoinType, NullOrdering};
+use sea_orm::{
+    ActiveModelTrait as _, ColumnTrait as _, Condition, DatabaseConnection, DatabaseTransaction,
+    DbErr, EntityTrait as _, Order, PaginatorTrait as _, QueryFilter as _, QueryOrder as _,
+    QuerySelect as _, RelationTrait as _, Set,
+};
+
+/// Links whose record is not in the trash.
+fn live_links() -> sea_orm::Select<LinksEntity> {
+    LinksEntity::find()
+        .join(JoinType::InnerJoin, links::Relation::Record.def())
+        .filter(record::Column::DeletedAt.is_null())
+}
+
+pub struct LinkRepo;
+
+#[async_trait]
+impl LinkRepository for LinkRepo {
+    async fn find_by_id(&self, db: &DatabaseConnection, id: i64) -> Result<Option<Link>, DbErr> {
+        Ok(LinksEntity::find_by_id(id).one(db).await?.map(Link::from))
+    }
+
+    async fn find_by_record(
+        &self,
+        db: &DatabaseConnection,
+        record_id: &str,
+    ) -> Result<Vec<Link>, DbErr> {
+        let rows = LinksEntity::find()
+            .filter(links::Column::RecordId.eq(record_id))
+            .order_by_asc(links::Column::Id)
+            .all(db)
+            .await?;
+        Ok(rows.into_iter().map(Link::from).collect())
+    }
+
+    async fn find_dead_paginated(
+        &self,
+        db: &DatabaseConnection,
+        limit: u64,
+        offset: u64,
+    ) -> Result<(Vec<Link>, u64), DbErr> {
+        let query = live_links().filter(links::Column::Dead.eq(true));
+        let total = query.clone().count(db).await?;
+        let rows = query
+            .order_by_desc(links::Column::LastCheckedAt)
+            .order_by_asc(links::Column::Id)
+            .offset(offset)
+            .limit(limit)
+            .all(db)
+            .await?;
+        Ok((rows.into_iter().map(Link::from).collect(), total))
+    }
+
+    async fn search(
+        &self,
+        db: &DatabaseConnection,
+        query: &LinkSearchQuery,
+        max_permission: i32,
+        limit: u64,
+        offset: u64,
+    ) -> Result<(Vec<Link>, u64), DbErr> {
+        let mut select = live_links().filter(record::Column::Permission.lte(max_permission));
+        if let Some(name) = query.name.as_deref().filter(|s| !s.trim().is_empty()) {
+            select = select.filter(links::Column::Name.contains(name));
+        }
+        if let Some(min_size) = query.min_size {
+            select = select.filter(links::Column::Size.gte(min_size));
+        }
+        if let Some(star) = query.star {
+            select = select.filter(links::Column::Star.eq(star));
+        }
+        if let Some(date_from) = query.date_from {
+            select = select.filter(links::Column::Date.gte(date_from));
+        }
+        let total = select.clone().count(db).await?;
+        let rows = select
+            .order_by_desc(links::Column::Id)
+            .offset(offset)
+            .limit(limit)
+            .all(db)
+            .await?;
+        Ok((rows.into_iter().map(Link::from).collect(), total))
+    }
+
+    async fn find_least_recently_checked(
+        &self,
+        db: &DatabaseConnection,
+        limit: u64,
+    ) -> Result<Vec<Link>, DbErr> {
+        let rows = live_links()
+            .filter(
+                Condition::any()
+                    .add(links::Column::Link.starts_with("http://"))
+                    .add(links::Column::Link.starts_with("https://")),
+            )
+            .order_by_with_nulls(
+                links::Column::LastCheckedAt,
+                Order::Asc,
+                NullOrdering::First,
+            )
+            .order_by_asc(links::Column::Id)
+            .limit(limit)
+            .all(db)
+            .await?;
+        Ok(rows.into_iter().map(Link::from).collect())
+    }
+
+    async fn record_check(
+        &self,
+        db: &DatabaseConnection,
+        id: i64,
+        status: Option<i32>,
+        dead: bool,
+        checked_at: DateTime<Utc>,
+    ) -> Result<(), DbErr> {
+        // A link deleted while it was being checked is no error.
+        LinksEntity::update_many()
+            .col_expr(links::Column::Dead, dead.into())
+            .col_expr(links::Column::LastStatus, status.into())
+            .col_expr(links::Column::LastCheckedAt, Some(checked_at).into())
+            .filter(links::Column::Id.eq(id))
+            .exec(db)
+            .await?;
+        Ok(())
+    }
+
+    async fn update(
+        &self,
+        txn: &DatabaseTransaction,
+        record_id: &str,
+        id: i64,
+        patch: PatchLinkDto,
+    ) -> Result<Option<Link>, DbErr> {
+        let Some(existing) = LinksEntity::find_by_id(id)
+            .filter(links::Column::RecordId.eq(record_id))
+            .one(txn)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        let mut active: links::ActiveModel = existing.clone().into();
+        if let Some(name) = patch.name {
+            active.name = Set(name);
+        }
+        if let Some(size) = patch.size {
+            active.size = Set(size);
+        }
+        if let Some(date) = patch.date {
+            active.date = Set(date);
+        }
+        if let Some(star) = patch.star {
+            active.star = Set(star);
+        }
+        if let Some(priority) = patch.priority {
+            active.priority = Set(priority);
+        }
+        // The health of the old URL says nothing about the new one
+        if let Some(url) = patch.link.as_deref().map(normalize_link_url) {
+            if url != existing.link {
+                active.link = Set(url);
+                active.dead = Set(false);
+                active.last_status = Set(None);
+                active.last_checked_at = Set(None);
+            }
+        }
+        Ok(Some(Link::from(active.update(txn).await?)))
+    }
+
+    async fn delete(
+        &self,
+        txn: &DatabaseTransaction,
+        record_id: &str,
+        id: i64,
+    ) -> Result<bool, DbErr> {
+        let result = LinksEntity::delete_many()
+            .filter(links::Column::Id.eq(id))
+            .filter(links::Column::RecordId.eq(record_id))
+            .exec(txn)
+            .await?;
+        if result.rows_affected == 0 {
+            return Ok(false);
+        }
+        sync_has_links(txn, record_id).await?;
+        Ok(true)
+    }
+}
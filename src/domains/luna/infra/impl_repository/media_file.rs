@@ -0,0 +1,175 @@
+use crate::domains::luna::{
+    domain::{MediaFileRepository, StoredMediaFile},
+    dto::{DuplicateImageFileDto, DuplicateImageGroupDto},
+};
+use crate::entities::{media_files, MediaFilesEntity};
+use async_trait::async_trait;
+use sea_orm::{
+    sea_query::{Expr, OnConflict},
+    ActiveValue::Set,
+    ColumnTrait as _, ConnectionTrait as _, DatabaseBackend, DatabaseConnection, DbErr,
+    EntityTrait as _, QueryFilter as _, QueryOrder as _, QuerySelect as _, Statement,
+};
+
+fn to_stored(model: media_files::Model) -> StoredMediaFile {
+    StoredMediaFile {
+        key: model.key,
+        owner_type: model.owner_type,
+        owner_id: model.owner_id,
+        filename: model.filename,
+        sha256: model.sha256,
+        size: u64::try_from(model.size).unwrap_or(0),
+    }
+}
+
+pub struct MediaFileRepo;
+
+#[async_trait]
+impl MediaFileRepository for MediaFileRepo {
+    async fn find_by_sha256(
+        &self,
+        db: &DatabaseConnection,
+        sha256: &str,
+    ) -> Result<Vec<StoredMediaFile>, DbErr> {
+        let rows = MediaFilesEntity::find()
+            .filter(media_files::Column::Sha256.eq(sha256))
+            .order_by_asc(media_files::Column::Key)
+            .all(db)
+            .await?;
+        Ok(rows.into_iter().map(to_stored).collect())
+    }
+
+    async fn upsert(&self, db: &DatabaseConnection, file: &StoredMediaFile) -> Result<(), DbErr> {
+        let row = media_files::ActiveModel {
+            key: Set(file.key.clone()),
+            owner_type: Set(file.owner_type.clone()),
+            owner_id: Set(file.owner_id.clone()),
+            filename: Set(file.filename.clone()),
+            sha256: Set(file.sha256.clone()),
+            size: Set(i64::try_from(file.size).unwrap_or(i64::MAX)),
+            created_at: Set(chrono::Utc::now()),
+        };
+        MediaFilesEntity::insert(row)
+            .on_conflict(
+                OnConflict::column(media_files::Column::Key)
+                    .update_columns([
+                        media_files::Column::Sha256,
+                        media_files::Column::Size,
+                        media_files::Column::CreatedAt,
+                    ])
+                    .to_owned(),
+            )
+            .exec(db)
+            .await?;
+        Ok(())
+    }
+
+    async fn keys(&self, db: &DatabaseConnection) -> Result<Vec<String>, DbErr> {
+        MediaFilesEntity::find()
+            .select_only()
+            .column(media_files::Column::Key)
+            .order_by_asc(media_files::Column::Key)
+            .into_tuple()
+            .all(db)
+            .await
+    }
+
+    async fn delete_keys(&self, db: &DatabaseConnection, keys: &[String]) -> Result<u64, DbErr> {
+        if keys.is_empty() {
+            return Ok(0);
+        }
+        let result = MediaFilesEntity::delete_many()
+            .filter(media_files::Column::Key.is_in(keys.iter().cloned()))
+            .exec(db)
+            .await?;
+        Ok(result.rows_affected)
+    }
+
+    async fn rename_key(
+        &self,
+        db: &DatabaseConnection,
+        key: &str,
+        new_key: &str,
+        owner_id: &str,
+        filename: &str,
+    ) -> Result<(), DbErr> {
+        // A stale row for the destination would break the unique key
+        MediaFilesEntity::delete_many()
+            .filter(media_files::Column::Key.eq(new_key))
+            .exec(db)
+            .await?;
+        MediaFilesEntity::update_many()
+            .col_expr(media_files::Column::Key, Expr::value(new_key))
+            .col_expr(media_files::Column::OwnerId, Expr::value(owner_id))
+            .col_expr(media_files::Column::Filename, Expr::value(filename))
+            .filter(media_files::Column::Key.eq(key))
+            .exec(db)
+            .await?;
+        Ok(())
+    }
+
+    async fn delete_by_owners(
+        &self,
+        db: &DatabaseConnection,
+        owner_type: &str,
+        owner_ids: &[String],
+    ) -> Result<u64, DbErr> {
+        if owner_ids.is_empty() {
+            return Ok(0);
+        }
+        let result = MediaFilesEntity::delete_many()
+            .filter(media_files::Column::OwnerType.eq(owner_type))
+            .filter(media_files::Column::OwnerId.is_in(owner_ids.iter().cloned()))
+            .exec(db)
+            .await?;
+        Ok(result.rows_affected)
+    }
+
+    async fn duplicate_groups(
+        &self,
+        db: &DatabaseConnection,
+        limit: u64,
+    ) -> Result<Vec<DuplicateImageGroupDto>, DbErr> {
+        let hashes: Vec<String> = db
+            .query_all(Statement::from_sql_and_values(
+                DatabaseBackend::Postgres,
+                "SELECT sha256 FROM media_files GROUP BY sha256 HAVING COUNT(*) > 1 \
+                 ORDER BY COUNT(*) DESC, sha256 LIMIT $1",
+                [i64::try_from(limit).unwrap_or(i64::MAX).into()],
+            ))
+            .await?
+            .iter()
+            .map(|row| row.try_get::<String>("", "sha256"))
+            .collect::<Result<_, _>>()?;
+        if hashes.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let rows = MediaFilesEntity::find()
+            .filter(media_files::Column::Sha256.is_in(hashes.iter().cloned()))
+            .order_by_asc(media_files::Column::OwnerType)
+            .order_by_asc(media_files::Column::OwnerId)
+            .order_by_asc(media_files::Column::Filename)
+            .all(db)
+            .await?;
+        let mut groups: Vec<DuplicateImageGroupDto> = hashes
+            .into_iter()
+            .map(|sha256| DuplicateImageGroupDto {
+                sha256,
+                size: 0,
+                files: Vec::new(),
+            })
+            .collect();
+        for row in rows {
+            if let Some(group) = groups.iter_mut().find(|group| group.sha256 == row.sha256) {
+                group.size = u64::try_from(row.size).unwrap_or(0);
+                group.files.push(DuplicateImageFileDto {
+                    owner_type: row.owner_type,
+                    owner_id: row.owner_id,
+                    filename: row.filename,
+                });
+            }
+        }
+        Ok(groups)
+    }
+}
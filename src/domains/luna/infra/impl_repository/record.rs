@@ -1,31 +1,271 @@
-use super::record_loader::{load_record_with_relations, load_records_batch, load_records_slim};
+use super::record_loader::{
+    load_record_with_relations, load_records_batch, load_records_batch_with, load_records_slim,
+    load_relations_batch,
+};
+use crate::common::{config::SimilarityWeights, pagination::parse_ordering};
 use crate::domains::luna::{
     domain::{
-        CreatedNestedEntities, DirectorRepository as _, GenreRepository as _, IdolRepository as _,
-        LabelRepository as _, Record, RecordRepository, SeriesRepository as _,
-        StudioRepository as _,
+        CreatedNestedEntities, DeletedRecordRows, DirectorRepository as _, GenreRepository as _,
+        IdolRepository as _, LabelRepository as _, Record, RecordChanges, RecordRelationRows,
+        RecordRepository, SeriesRepository as _, StudioRepository as _,
     },
     dto::{
-        CreateLinkDto, CreateRecordDto, PaginatedResponse, PaginationQuery, SearchRecordDto,
-        UpdateRecordDto, UserFilter,
+        normalize_link_url, CreateDirectorDto, CreateGenreDto, CreateIdolDto, CreateLabelDto,
+        CreateLinkDto, CreateRecordDto, CreateSeriesDto, CreateStudioDto, FetchedMetadata,
+        LinkSkipReason, LinkUpdateResultDto, MatchMode, PaginatedResponse, PaginationQuery,
+        PatchRecordDto, RecordCursor, RecordRelations, ResolvedIdolDto, SearchRecordDto,
+        SeriesNeighborDto, SkippedLinkDto, UpdateRecordDto, UserFilter, RECORD_ORDERING_FIELDS,
     },
     infra::{DirectorRepo, GenreRepo, IdolRepo, LabelRepo, SeriesRepo, StudioRepo},
 };
 use crate::entities::{
-    idol_participation, links, record, record_genre, user_record_interaction, LinksEntity,
-    RecordEntity,
+    director, genre, idol, idol_alias, idol_participation, label, links, record, record_deletion,
+    record_genre, record_rating, record_tag, series, studio, user_record_favorites,
+    user_record_interaction, LinksEntity, RecordDeletionEntity, RecordEntity,
 };
 use async_trait::async_trait;
 use sea_orm::prelude::Decimal;
-use sea_orm::sea_query::JoinType;
+use sea_orm::sea_query::{
+    Expr, Func, IntoTableRef, JoinType, NullOrdering, Query, SelectStatement,
+};
 use sea_orm::{
-    ActiveModelTrait as _, ColumnTrait as _, DatabaseConnection, DatabaseTransaction, DbErr,
-    EntityTrait as _, FromQueryResult, Order, PaginatorTrait as _, QueryFilter as _,
-    QueryOrder as _, QuerySelect as _, RelationTrait as _, Set,
+    ActiveModelTrait as _, ColumnTrait as _, Condition, ConnectionTrait as _, DatabaseBackend,
+    DatabaseConnection, DatabaseTransaction, DbErr, EntityTrait as _, FromQueryResult, Order,
+    PaginatorTrait as _, QueryFilter as _, QueryOrder as _, QuerySelect as _, QueryTrait as _,
+    RelationTrait as _, Set, Statement,
 };
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+
+/// Tables holding at most one row per record and key column. Merging
+/// records moves such a row to the target unless the target has its key.
+const RECORD_MERGE_TABLES: [(&str, &str); 7] = [
+    ("record_genre", "genre_id"),
+    ("idol_participation", "idol_id"),
+    ("record_tag", "tag_id"),
+    ("links", "link"),
+    ("user_record_favorites", "user_id"),
+    ("record_rating", "user_id"),
+    ("user_record_interaction", "user_id"),
+];
+
+/// Records that are not in the trash. Every listing and lookup starts here;
+/// only the trash, restore and purge paths see soft-deleted rows.
+fn live_records() -> sea_orm::Select<RecordEntity> {
+    RecordEntity::find().filter(record::Column::DeletedAt.is_null())
+}
+
+/// Apply the `SearchRecordDto` column filters as SQL `WHERE` clauses.
+fn apply_search_filters(
+    mut query: sea_orm::Select<RecordEntity>,
+    search_dto: &SearchRecordDto,
+) -> sea_orm::Select<RecordEntity> {
+    if let Some(id) = search_dto.id.as_deref() {
+        query = query.filter(record::Column::Id.like(format!("%{id}%")));
+    }
+    if let Some(title) = search_dto.title.as_deref() {
+        query = query.filter(record::Column::Title.like(format!("%{title}%")));
+    }
+    if let Some(director_id) = search_dto.director_id {
+        query = query.filter(record::Column::DirectorId.eq(director_id));
+    }
+    if let Some(studio_id) = search_dto.studio_id {
+        query = query.filter(record::Column::StudioId.eq(studio_id));
+    }
+    if let Some(label_id) = search_dto.label_id {
+        query = query.filter(record::Column::LabelId.eq(label_id));
+    }
+    if let Some(series_id) = search_dto.series_id {
+        query = query.filter(record::Column::SeriesId.eq(series_id));
+    }
+    if let Some(term) = search_dto.search.as_deref().map(str::trim) {
+        if !term.is_empty() {
+            query = query.filter(search_term_condition(&escape_like_pattern(term)));
+        }
+    }
+    if let Some(date_from) = search_dto.date_from {
+        query = query.filter(record::Column::Date.gte(date_from));
+    }
+    if let Some(date_to) = search_dto.date_to {
+        query = query.filter(record::Column::Date.lte(date_to));
+    }
+    if let Some(modified_since) = search_dto.modified_since {
+        query = query.filter(record::Column::UpdateTime.gte(modified_since));
+    }
+    if let Some(min_rating) = search_dto.min_rating {
+        query = query.filter(
+            record::Column::Id.in_subquery(
+                Query::select()
+                    .column(record_rating::Column::RecordId)
+                    .from(record_rating::Entity)
+                    .group_by_col(record_rating::Column::RecordId)
+                    .and_having(
+                        Expr::expr(Func::avg(Expr::col(record_rating::Column::Score)))
+                            .gte(min_rating),
+                    )
+                    .to_owned(),
+            ),
+        );
+    }
+    // Junction filters use `id IN (SELECT record_id ...)` rather than a JOIN so
+    // they compose with each other and with the user filter without producing
+    // duplicate rows (and therefore inflated counts).
+    if let Some(genre_id) = search_dto.genre_id {
+        query = query.filter(
+            record::Column::Id.in_subquery(
+                Query::select()
+                    .column(record_genre::Column::RecordId)
+                    .from(record_genre::Entity)
+                    .and_where(record_genre::Column::GenreId.eq(genre_id))
+                    .to_owned(),
+            ),
+        );
+    }
+    if let Some(category_id) = search_dto.genre_category_id {
+        query = query.filter(
+            record::Column::Id.in_subquery(
+                Query::select()
+                    .column(record_genre::Column::RecordId)
+                    .from(record_genre::Entity)
+                    .and_where(
+                        record_genre::Column::GenreId.in_subquery(
+                            Query::select()
+                                .column(genre::Column::Id)
+                                .from(genre::Entity)
+                                .and_where(genre::Column::CategoryId.eq(category_id))
+                                .to_owned(),
+                        ),
+                    )
+                    .to_owned(),
+            ),
+        );
+    }
+    if let Some(idol_id) = search_dto.idol_id {
+        query = query.filter(
+            record::Column::Id.in_subquery(
+                Query::select()
+                    .column(idol_participation::Column::RecordId)
+                    .from(idol_participation::Entity)
+                    .and_where(idol_participation::Column::IdolId.eq(idol_id))
+                    .to_owned(),
+            ),
+        );
+    }
+    let match_mode = search_dto.match_mode.unwrap_or_default();
+    let genre_ids = search_dto.genre_id_list().unwrap_or_default();
+    if !genre_ids.is_empty() {
+        query = query.filter(record::Column::Id.in_subquery(junction_record_ids(
+            record_genre::Entity,
+            record_genre::Column::RecordId,
+            record_genre::Column::GenreId,
+            &genre_ids,
+            match_mode,
+        )));
+    }
+    let idol_ids = search_dto.idol_id_list().unwrap_or_default();
+    if !idol_ids.is_empty() {
+        query = query.filter(record::Column::Id.in_subquery(junction_record_ids(
+            idol_participation::Entity,
+            idol_participation::Column::RecordId,
+            idol_participation::Column::IdolId,
+            &idol_ids,
+            match_mode,
+        )));
+    }
+    let tag_ids = search_dto.tag_id_list().unwrap_or_default();
+    if !tag_ids.is_empty() {
+        query = query.filter(record::Column::Id.in_subquery(junction_record_ids(
+            record_tag::Entity,
+            record_tag::Column::RecordId,
+            record_tag::Column::TagId,
+            &tag_ids,
+            match_mode,
+        )));
+    }
+    query
+}
+
+/// Select the record IDs linked through a junction table to any (or, with
+/// `MatchMode::All`, every) one of `ids`. `ids` must be free of duplicates so
+/// the `HAVING COUNT(DISTINCT ...)` check can compare against its length.
+fn junction_record_ids<C: sea_orm::ColumnTrait>(
+    table: impl IntoTableRef,
+    record_col: C,
+    key_col: C,
+    ids: &[i64],
+    match_mode: MatchMode,
+) -> SelectStatement {
+    let mut select = Query::select();
+    select
+        .column(record_col)
+        .from(table)
+        .and_where(key_col.is_in(ids.iter().copied()));
+    if match_mode == MatchMode::All {
+        select
+            .group_by_col(record_col)
+            .and_having(Expr::expr(Func::count_distinct(Expr::col(key_col))).eq(ids.len() as i64));
+    }
+    select.to_owned()
+}
+
+/// Escape SQL `LIKE` wildcards so user input is matched literally.
+/// `SeaORM`'s `contains()` adds the surrounding `%`s itself.
+pub(super) fn escape_like_pattern(term: &str) -> String {
+    term.replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+/// Select the IDs of a named entity whose `name` contains `$pattern`.
+macro_rules! name_match_ids {
+    ($entity_mod:ident, $pattern:expr) => {
+        $entity_mod::Entity::find()
+            .select_only()
+            .column($entity_mod::Column::Id)
+            .filter($entity_mod::Column::Name.contains($pattern))
+            .into_query()
+    };
+}
+
+/// Build the free-text `search` condition: a record matches when the term
+/// appears in its ID or title, or in the name of its director, studio, label,
+/// series, any of its genres, or any of its idols, under their name or an
+/// alias. Related names are matched through `IN (SELECT ...)` subqueries so
+/// the whole filter stays in SQL.
+fn search_term_condition(pattern: &str) -> Condition {
+    let genre_record_ids = record_genre::Entity::find()
+        .select_only()
+        .column(record_genre::Column::RecordId)
+        .filter(record_genre::Column::GenreId.in_subquery(name_match_ids!(genre, pattern)))
+        .into_query();
+    let alias_idol_ids = idol_alias::Entity::find()
+        .select_only()
+        .column(idol_alias::Column::IdolId)
+        .filter(idol_alias::Column::Alias.contains(pattern))
+        .into_query();
+    let idol_record_ids = idol_participation::Entity::find()
+        .select_only()
+        .column(idol_participation::Column::RecordId)
+        .filter(
+            Condition::any()
+                .add(idol_participation::Column::IdolId.in_subquery(name_match_ids!(idol, pattern)))
+                .add(idol_participation::Column::IdolId.in_subquery(alias_idol_ids)),
+        )
+        .into_query();
+
+    Condition::any()
+        .add(record::Column::Id.contains(pattern))
+        .add(record::Column::Title.contains(pattern))
+        .add(record::Column::DirectorId.in_subquery(name_match_ids!(director, pattern)))
+        .add(record::Column::StudioId.in_subquery(name_match_ids!(studio, pattern)))
+        .add(record::Column::LabelId.in_subquery(name_match_ids!(label, pattern)))
+        .add(record::Column::SeriesId.in_subquery(name_match_ids!(series, pattern)))
+        .add(record::Column::Id.in_subquery(genre_record_ids))
+        .add(record::Column::Id.in_subquery(idol_record_ids))
+}
 
-/// Apply user interaction filter as INNER JOIN on `user_record_interaction`.
+/// Hide records above the caller's clearance, then apply the favorites and
+/// interaction filters as INNER JOINs on `user_record_favorites` and
+/// `user_record_interaction`.
 fn apply_user_filter(
     query: sea_orm::Select<RecordEntity>,
     user_filter: &Option<UserFilter>,
@@ -33,6 +273,31 @@ fn apply_user_filter(
     let Some(ref filter) = user_filter else {
         return query;
     };
+    let query = query.filter(record::Column::Permission.lte(filter.max_permission));
+    let query = if filter.favorites_only {
+        query
+            .join_rev(
+                JoinType::InnerJoin,
+                user_record_favorites::Relation::Record.def(),
+            )
+            .filter(user_record_favorites::Column::UserId.eq(&filter.user_id))
+    } else {
+        query
+    };
+    let query = if filter.unseen_only {
+        query.filter(
+            record::Column::Id.not_in_subquery(
+                Query::select()
+                    .column(user_record_interaction::Column::RecordId)
+                    .from(user_record_interaction::Entity)
+                    .and_where(user_record_interaction::Column::UserId.eq(&filter.user_id))
+                    .and_where(user_record_interaction::Column::Viewed.eq(true))
+                    .to_owned(),
+            ),
+        )
+    } else {
+        query
+    };
     if !filter.liked_only && !filter.viewed_only {
         return query;
     }
@@ -51,12 +316,68 @@ fn apply_user_filter(
     q
 }
 
+/// Estimated rows below which random picks sort the whole table rather than
+/// a `TABLESAMPLE`.
+const RANDOM_SAMPLE_MIN_ROWS: f64 = 10_000.0;
+
+/// How many times more rows a `TABLESAMPLE` aims to read than requested,
+/// leaving room for trashed and hidden records.
+const RANDOM_SAMPLE_OVERSAMPLING: f64 = 20.0;
+
+/// Draw up to `count` visible live record IDs from a block sample of the
+/// table. `None` when the table is too small for sampling to pay off or the
+/// sample came back short, so the caller sorts the whole table instead.
+async fn sample_record_ids(
+    db: &DatabaseConnection,
+    count: u64,
+    max_permission: i32,
+) -> Result<Option<Vec<String>>, DbErr> {
+    #[derive(FromQueryResult)]
+    struct EstimateRow {
+        estimate: f64,
+    }
+
+    #[derive(FromQueryResult)]
+    struct IdRow {
+        id: String,
+    }
+
+    // The planner's row estimate costs nothing to read, unlike `COUNT(*)`.
+    let estimate = EstimateRow::find_by_statement(Statement::from_string(
+        DatabaseBackend::Postgres,
+        "SELECT reltuples::DOUBLE PRECISION AS estimate \
+         FROM pg_class WHERE oid = 'record'::regclass",
+    ))
+    .one(db)
+    .await?
+    .map_or(0.0, |row| row.estimate);
+    if estimate < RANDOM_SAMPLE_MIN_ROWS {
+        return Ok(None);
+    }
+
+    let percent = (count as f64 * RANDOM_SAMPLE_OVERSAMPLING * 100.0 / estimate).min(100.0);
+    let rows = IdRow::find_by_statement(Statement::from_sql_and_values(
+        DatabaseBackend::Postgres,
+        "SELECT id FROM record TABLESAMPLE SYSTEM ($1::REAL) \
+         WHERE deleted_at IS NULL AND permission <= $2 \
+         ORDER BY RANDOM() LIMIT $3",
+        [percent.into(), max_permission.into(), (count as i64).into()],
+    ))
+    .all(db)
+    .await?;
+    if (rows.len() as u64) < count {
+        return Ok(None);
+    }
+    Ok(Some(rows.into_iter().map(|row| row.id).collect()))
+}
+
 /// Build a pagination link string, appending active filter params.
 fn build_page_link(
     limit: u64,
     offset: u64,
     liked_param: Option<&str>,
     viewed_param: Option<&str>,
+    ordering: Option<&str>,
 ) -> String {
     let mut parts = vec![format!("limit={limit}"), format!("offset={offset}")];
     if let Some(val) = liked_param {
@@ -65,9 +386,70 @@ fn build_page_link(
     if let Some(val) = viewed_param {
         parts.push(format!("viewed_only={val}"));
     }
+    if let Some(val) = ordering {
+        parts.push(format!("ordering={val}"));
+    }
+    format!("?{}", parts.join("&"))
+}
+
+/// Build a keyset pagination link string, appending active filter params
+/// and the already encoded `filters`.
+fn build_cursor_link(
+    limit: u64,
+    cursor: &str,
+    liked_param: Option<&str>,
+    viewed_param: Option<&str>,
+    filters: &[String],
+) -> String {
+    let mut parts = vec![format!("limit={limit}"), format!("cursor={cursor}")];
+    if let Some(val) = liked_param {
+        parts.push(format!("liked_only={val}"));
+    }
+    if let Some(val) = viewed_param {
+        parts.push(format!("viewed_only={val}"));
+    }
+    parts.extend_from_slice(filters);
     format!("?{}", parts.join("&"))
 }
 
+/// `name=value` query pairs of the filters set in `search_dto`, so a page
+/// link keeps filtering like the request it came from.
+fn search_params(search_dto: &SearchRecordDto) -> Vec<String> {
+    let Ok(serde_json::Value::Object(fields)) = serde_json::to_value(search_dto) else {
+        return Vec::new();
+    };
+    fields
+        .into_iter()
+        .filter_map(|(name, value)| {
+            let value = match value {
+                serde_json::Value::Null => return None,
+                serde_json::Value::String(value) => value,
+                value => value.to_string(),
+            };
+            Some(format!(
+                "{name}={}",
+                percent_encoding::utf8_percent_encode(&value, percent_encoding::NON_ALPHANUMERIC)
+            ))
+        })
+        .collect()
+}
+
+/// Restrict `query` to records after `cursor` in `(date DESC, id ASC)` order.
+fn apply_cursor(
+    query: sea_orm::Select<RecordEntity>,
+    cursor: &RecordCursor,
+) -> sea_orm::Select<RecordEntity> {
+    query.filter(
+        Condition::any()
+            .add(record::Column::Date.lt(cursor.date))
+            .add(
+                Condition::all()
+                    .add(record::Column::Date.eq(cursor.date))
+                    .add(record::Column::Id.gt(cursor.id.as_str())),
+            ),
+    )
+}
+
 /// Extract (`page_size`, `current_offset`) from pagination query.
 fn resolve_pagination(pagination: &PaginationQuery) -> (u64, u64) {
     let page_size = pagination
@@ -78,6 +460,73 @@ fn resolve_pagination(pagination: &PaginationQuery) -> (u64, u64) {
     (page_size, current_offset)
 }
 
+/// Map an allowlisted `ordering` field to its record column.
+fn record_sort_column(field: &str) -> Option<record::Column> {
+    match field {
+        "id" => Some(record::Column::Id),
+        "title" => Some(record::Column::Title),
+        "date" => Some(record::Column::Date),
+        "duration" => Some(record::Column::Duration),
+        "create_time" => Some(record::Column::CreateTime),
+        "update_time" => Some(record::Column::UpdateTime),
+        _ => None,
+    }
+}
+
+/// Apply the requested `ordering`, falling back to newest-first.
+///
+/// The service validates `ordering` before calling the repository, so an
+/// invalid value here just falls back to the default order. `id` is always
+/// appended as a tiebreaker so page boundaries are stable.
+fn apply_ordering(
+    mut query: sea_orm::Select<RecordEntity>,
+    pagination: &PaginationQuery,
+) -> sea_orm::Select<RecordEntity> {
+    let keys = pagination
+        .ordering
+        .as_deref()
+        .and_then(|raw| parse_ordering(raw, RECORD_ORDERING_FIELDS).ok())
+        .unwrap_or_default();
+    if keys.is_empty() {
+        return query
+            .order_by(record::Column::Date, Order::Desc)
+            .order_by(record::Column::Id, Order::Asc);
+    }
+    for key in &keys {
+        let order = if key.descending {
+            Order::Desc
+        } else {
+            Order::Asc
+        };
+        if key.field == "rating" {
+            // Unrated records sort after rated ones in either direction.
+            query = query.order_by_with_nulls(
+                Expr::cust(
+                    "(SELECT AVG(record_rating.score) FROM record_rating \
+                     WHERE record_rating.record_id = record.id)",
+                ),
+                order,
+                NullOrdering::Last,
+            );
+        } else if key.field == "series_order" {
+            // Unnumbered records follow the numbered ones by date.
+            query = query
+                .order_by_with_nulls(
+                    record::Column::OrderInSeries,
+                    order.clone(),
+                    NullOrdering::Last,
+                )
+                .order_by(record::Column::Date, order);
+        } else if let Some(column) = record_sort_column(&key.field) {
+            query = query.order_by(column, order);
+        }
+    }
+    if !keys.iter().any(|k| k.field == "id") {
+        query = query.order_by(record::Column::Id, Order::Asc);
+    }
+    query
+}
+
 /// Build a `PaginatedResponse` from results, pagination params, and filter state.
 fn build_paginated_response<T>(
     results: Vec<T>,
@@ -86,6 +535,7 @@ fn build_paginated_response<T>(
     current_offset: u64,
     liked_param: Option<&str>,
     viewed_param: Option<&str>,
+    ordering: Option<&str>,
 ) -> PaginatedResponse<T> {
     let next_offset = current_offset + page_size;
     let next = if next_offset < total_items {
@@ -94,6 +544,7 @@ fn build_paginated_response<T>(
             next_offset,
             liked_param,
             viewed_param,
+            ordering,
         ))
     } else {
         None
@@ -104,6 +555,7 @@ fn build_paginated_response<T>(
             current_offset.saturating_sub(page_size),
             liked_param,
             viewed_param,
+            ordering,
         ))
     } else {
         None
@@ -112,6 +564,7 @@ fn build_paginated_response<T>(
         count: total_items as i64,
         next,
         previous,
+        next_cursor: None,
         results,
     }
 }
@@ -129,6 +582,216 @@ fn filter_params(user_filter: &Option<UserFilter>) -> (Option<&str>, Option<&str
         .unwrap_or((None, None))
 }
 
+/// Find or create the director, studio, label and series of a record,
+/// recording each in `nested`. A missing reference maps to the `0`
+/// ("unknown") row.
+async fn resolve_record_refs(
+    txn: &DatabaseTransaction,
+    nested: &mut CreatedNestedEntities,
+    director: Option<CreateDirectorDto>,
+    studio: Option<CreateStudioDto>,
+    label: Option<CreateLabelDto>,
+    series: Option<CreateSeriesDto>,
+) -> Result<(i64, i64, i64, i64), DbErr> {
+    // Handle director creation or use default
+    let director_id = if let Some(director_dto) = director {
+        let name = director_dto.name.clone();
+        let director_repo = DirectorRepo;
+        let (id, _) = director_repo.create(txn, director_dto).await?;
+        nested.director = Some((id, name));
+        id
+    } else {
+        0 // Default unknown director
+    };
+
+    // Handle studio creation or use default
+    let studio_id = if let Some(studio_dto) = studio {
+        let name = studio_dto.name.clone();
+        let studio_repo = StudioRepo;
+        let (id, _) = studio_repo.create(txn, studio_dto).await?;
+        nested.studio = Some((id, name));
+        id
+    } else {
+        0 // Default unknown studio
+    };
+
+    // Handle label creation or use default
+    let label_id = if let Some(label_dto) = label {
+        let name = label_dto.name.clone();
+        let label_repo = LabelRepo;
+        let (id, _) = label_repo.create(txn, label_dto).await?;
+        nested.label = Some((id, name));
+        id
+    } else {
+        0 // Default unknown label
+    };
+
+    // Handle series creation or use default
+    let series_id = if let Some(series_dto) = series {
+        let name = series_dto.name.clone();
+        let series_repo = SeriesRepo;
+        let (id, _) = series_repo.create(txn, series_dto).await?;
+        nested.series = Some((id, name));
+        id
+    } else {
+        0 // Default unknown series
+    };
+
+    Ok((director_id, studio_id, label_id, series_id))
+}
+
+/// The lowest-ID genre named `name`, created when there is none. Returns
+/// its ID and whether it was created.
+async fn genre_by_name(txn: &DatabaseTransaction, name: &str) -> Result<(i64, bool), DbErr> {
+    let existing = genre::Entity::find()
+        .filter(genre::Column::Name.eq(name))
+        .order_by_asc(genre::Column::Id)
+        .one(txn)
+        .await?;
+    if let Some(existing) = existing {
+        return Ok((existing.id, false));
+    }
+    GenreRepo
+        .create(
+            txn,
+            CreateGenreDto {
+                name: name.to_owned(),
+                link: None,
+                manual: None,
+            },
+        )
+        .await
+}
+
+/// The lowest-ID idol named `name`, created when there is none. Returns its
+/// ID and whether it was created.
+async fn idol_by_name(txn: &DatabaseTransaction, name: &str) -> Result<(i64, bool), DbErr> {
+    let existing = idol::Entity::find()
+        .filter(idol::Column::Name.eq(name))
+        .order_by_asc(idol::Column::Id)
+        .one(txn)
+        .await?;
+    if let Some(existing) = existing {
+        return Ok((existing.id, false));
+    }
+    IdolRepo
+        .create(
+            txn,
+            CreateIdolDto {
+                name: name.to_owned(),
+                link: None,
+                manual: None,
+            },
+        )
+        .await
+}
+
+/// Finds or creates an idol named in a record body, resolving aliases of
+/// known idols. Returns the idol's ID and name; a resolved alias is noted in
+/// `nested`.
+async fn resolve_record_idol(
+    txn: &DatabaseTransaction,
+    nested: &mut CreatedNestedEntities,
+    idol_dto: CreateIdolDto,
+) -> Result<(i64, String), DbErr> {
+    let name = idol_dto.name.clone();
+    match IdolRepo.create_or_resolve_alias(txn, idol_dto).await? {
+        (idol_id, Some(idol_name)) => {
+            nested.resolved_idols.push(ResolvedIdolDto {
+                name,
+                idol_id,
+                idol_name: idol_name.clone(),
+            });
+            Ok((idol_id, idol_name))
+        }
+        (idol_id, None) => Ok((idol_id, name)),
+    }
+}
+
+/// Makes the genre rows of `record_id` match `wanted`. Rows marked manual
+/// are kept unless `override_manual` is set. Returns whether any row changed.
+async fn sync_record_genres(
+    txn: &DatabaseTransaction,
+    record_id: &str,
+    wanted: &HashSet<i64>,
+    override_manual: bool,
+) -> Result<bool, DbErr> {
+    let existing = record_genre::Entity::find()
+        .filter(record_genre::Column::RecordId.eq(record_id))
+        .all(txn)
+        .await?;
+    let stale: Vec<i64> = existing
+        .iter()
+        .filter(|row| (override_manual || !row.manual) && !wanted.contains(&row.genre_id))
+        .map(|row| row.id)
+        .collect();
+    let mut changed = !stale.is_empty();
+    if changed {
+        record_genre::Entity::delete_many()
+            .filter(record_genre::Column::Id.is_in(stale))
+            .exec(txn)
+            .await?;
+    }
+    let present: HashSet<i64> = existing.iter().map(|row| row.genre_id).collect();
+    for genre_id in wanted.difference(&present) {
+        record_genre::ActiveModel {
+            id: sea_orm::ActiveValue::NotSet,
+            record_id: Set(record_id.to_owned()),
+            genre_id: Set(*genre_id),
+            manual: Set(false),
+        }
+        .insert(txn)
+        .await?;
+        changed = true;
+    }
+    Ok(changed)
+}
+
+/// Makes the idol rows of `record_id` match `wanted`, treating manual rows
+/// as [`sync_record_genres`] does. A record left without idols gets the `0`
+/// placeholder row, as in `create`. Returns whether any row changed.
+async fn sync_record_idols(
+    txn: &DatabaseTransaction,
+    record_id: &str,
+    mut wanted: HashSet<i64>,
+    override_manual: bool,
+) -> Result<bool, DbErr> {
+    let existing = idol_participation::Entity::find()
+        .filter(idol_participation::Column::RecordId.eq(record_id))
+        .all(txn)
+        .await?;
+    let keeps_manual =
+        !override_manual && existing.iter().any(|row| row.manual && row.idol_id != 0);
+    if wanted.is_empty() && !keeps_manual {
+        wanted.insert(0);
+    }
+    let stale: Vec<i64> = existing
+        .iter()
+        .filter(|row| (override_manual || !row.manual) && !wanted.contains(&row.idol_id))
+        .map(|row| row.id)
+        .collect();
+    let mut changed = !stale.is_empty();
+    if changed {
+        idol_participation::Entity::delete_many()
+            .filter(idol_participation::Column::Id.is_in(stale))
+            .exec(txn)
+            .await?;
+    }
+    let present: HashSet<i64> = existing.iter().map(|row| row.idol_id).collect();
+    for idol_id in wanted.difference(&present) {
+        idol_participation::ActiveModel {
+            id: sea_orm::ActiveValue::NotSet,
+            idol_id: Set(*idol_id),
+            record_id: Set(record_id.to_owned()),
+            manual: Set(false),
+        }
+        .insert(txn)
+        .await?;
+        changed = true;
+    }
+    Ok(changed)
+}
+
 // These helpers centralize the placeholder contract shared by manual link
 // writes and crawler-driven incremental backfill.
 fn default_link_date() -> chrono::NaiveDate {
@@ -159,6 +822,145 @@ fn resolve_link_defaults(link: &CreateLinkDto) -> (String, Decimal, chrono::Naiv
     )
 }
 
+/// Duplicate-detection key of a link with a real name and size: the
+/// lowercased name and the size. Placeholder links have none.
+fn name_size_key(name: &str, size: Decimal) -> Option<(String, Decimal)> {
+    (!name.eq_ignore_ascii_case(&default_link_name()) && size != default_link_size())
+        .then(|| (name.to_lowercase(), size))
+}
+
+/// Whether stored URL `stored` is the same link as normalized URL `url`,
+/// ignoring case.
+fn same_link_url(stored: &str, url: &str) -> bool {
+    normalize_link_url(stored).to_lowercase() == url.to_lowercase()
+}
+
+/// Normalizes the URLs of `links` and drops blank ones and those repeating
+/// an earlier link's URL (ignoring case) or name and size. Returns the kept
+/// links with their normalized URLs, and the dropped ones.
+fn dedupe_links(links: Vec<CreateLinkDto>) -> (Vec<(String, CreateLinkDto)>, Vec<SkippedLinkDto>) {
+    let mut kept = Vec::with_capacity(links.len());
+    let mut skipped = Vec::new();
+    let mut urls: HashSet<String> = HashSet::new();
+    let mut names: HashSet<(String, Decimal)> = HashSet::new();
+    for link in links {
+        let url = normalize_link_url(&link.link);
+        let (name, size, _) = resolve_link_defaults(&link);
+        let reason = if url.is_empty() {
+            Some(LinkSkipReason::Blank)
+        } else if !urls.insert(url.to_lowercase()) {
+            Some(LinkSkipReason::DuplicateUrl)
+        } else if name_size_key(&name, size).is_some_and(|key| !names.insert(key)) {
+            Some(LinkSkipReason::DuplicateNameSize)
+        } else {
+            None
+        };
+        match reason {
+            Some(reason) => skipped.push(SkippedLinkDto {
+                link: link.link,
+                reason,
+            }),
+            None => kept.push((url, link)),
+        }
+    }
+    (kept, skipped)
+}
+
+/// Makes the links of `record_id` exactly `wanted`, keyed by URL: unknown
+/// URLs are removed, matching URLs are updated in place and new URLs are
+/// inserted. Links [`dedupe_links`] drops are skipped.
+async fn sync_record_links(
+    txn: &DatabaseTransaction,
+    record_id: &str,
+    wanted: Vec<CreateLinkDto>,
+) -> Result<LinkUpdateResultDto, DbErr> {
+    let existing_links = LinksEntity::find()
+        .filter(links::Column::RecordId.eq(record_id))
+        .all(txn)
+        .await?;
+    let (wanted, skipped) = dedupe_links(wanted);
+    let mut result = LinkUpdateResultDto {
+        skipped,
+        ..LinkUpdateResultDto::default()
+    };
+    let mut matched: HashSet<i64> = HashSet::new();
+    for (url, link_dto) in wanted {
+        let (name, size, date) = resolve_link_defaults(&link_dto);
+        let star = link_dto.star.unwrap_or(false);
+        let current = existing_links
+            .iter()
+            .find(|l| !matched.contains(&l.id) && same_link_url(&l.link, &url));
+        match current {
+            Some(current) => {
+                matched.insert(current.id);
+                if current.name != name
+                    || current.size != size
+                    || current.date != date
+                    || current.star != star
+                {
+                    let mut active: links::ActiveModel = current.clone().into();
+                    active.name = Set(name);
+                    active.size = Set(size);
+                    active.date = Set(date);
+                    active.star = Set(star);
+                    active.update(txn).await?;
+                    result.updated.push(url);
+                }
+            }
+            None => {
+                links::ActiveModel {
+                    id: sea_orm::ActiveValue::NotSet,
+                    record_id: Set(record_id.to_owned()),
+                    name: Set(name),
+                    size: Set(size),
+                    date: Set(date),
+                    link: Set(url.clone()),
+                    star: Set(star),
+                    priority: Set(0),
+                    dead: Set(false),
+                    last_status: Set(None),
+                    last_checked_at: Set(None),
+                    create_time: sea_orm::ActiveValue::NotSet,
+                }
+                .insert(txn)
+                .await?;
+                result.inserted.push(url);
+            }
+        }
+    }
+    let (stale_ids, stale_urls): (Vec<i64>, Vec<String>) = existing_links
+        .into_iter()
+        .filter(|l| !matched.contains(&l.id))
+        .map(|l| (l.id, l.link))
+        .unzip();
+    if !stale_ids.is_empty() {
+        LinksEntity::delete_many()
+            .filter(links::Column::Id.is_in(stale_ids))
+            .exec(txn)
+            .await?;
+        result.removed = stale_urls;
+    }
+    Ok(result)
+}
+
+/// Sets `has_links` of `record_id` to whether it has any link rows.
+pub(super) async fn sync_has_links(
+    txn: &DatabaseTransaction,
+    record_id: &str,
+) -> Result<(), DbErr> {
+    let has_links = LinksEntity::find()
+        .filter(links::Column::RecordId.eq(record_id))
+        .count(txn)
+        .await?
+        > 0;
+    RecordEntity::update_many()
+        .col_expr(record::Column::HasLinks, Expr::value(has_links))
+        .filter(record::Column::Id.eq(record_id))
+        .exec(txn)
+        .await?;
+    Ok(())
+}
+
 // Record Repository Implementation
 pub struct RecordRepo;
 
@@ -166,7 +968,7 @@ pub struct RecordRepo;
 #[async_trait]
 impl RecordRepository for RecordRepo {
     async fn find_all(&self, db: &DatabaseConnection) -> Result<Vec<Record>, DbErr> {
-        let record_models = RecordEntity::find()
+        let record_models = live_records()
             .order_by(record::Column::Date, Order::Desc)
             .order_by(record::Column::Id, Order::Asc)
             .all(db)
@@ -179,7 +981,11 @@ impl RecordRepository for RecordRepo {
         db: &DatabaseConnection,
         id: String,
     ) -> Result<Option<Record>, DbErr> {
-        if let Some(record_model) = RecordEntity::find_by_id(id).one(db).await? {
+        if let Some(record_model) = live_records()
+            .filter(record::Column::Id.eq(id))
+            .one(db)
+            .await?
+        {
             let record = load_record_with_relations(db, record_model).await?;
             Ok(Some(record))
         } else {
@@ -192,28 +998,7 @@ impl RecordRepository for RecordRepo {
         db: &DatabaseConnection,
         search_dto: SearchRecordDto,
     ) -> Result<Vec<Record>, DbErr> {
-        let mut query = RecordEntity::find();
-
-        if let Some(id) = search_dto.id {
-            query = query.filter(record::Column::Id.like(format!("%{id}%")));
-        }
-        if let Some(title) = search_dto.title {
-            query = query.filter(record::Column::Title.like(format!("%{title}%")));
-        }
-        if let Some(director_id) = search_dto.director_id {
-            query = query.filter(record::Column::DirectorId.eq(director_id));
-        }
-        if let Some(studio_id) = search_dto.studio_id {
-            query = query.filter(record::Column::StudioId.eq(studio_id));
-        }
-        if let Some(label_id) = search_dto.label_id {
-            query = query.filter(record::Column::LabelId.eq(label_id));
-        }
-        if let Some(series_id) = search_dto.series_id {
-            query = query.filter(record::Column::SeriesId.eq(series_id));
-        }
-
-        let record_models = query
+        let record_models = apply_search_filters(live_records(), &search_dto)
             .order_by(record::Column::Date, Order::Desc)
             .order_by(record::Column::Id, Order::Asc)
             .all(db)
@@ -227,42 +1012,66 @@ impl RecordRepository for RecordRepo {
         search_dto: SearchRecordDto,
         pagination: PaginationQuery,
         user_filter: Option<UserFilter>,
+        relations: RecordRelations,
     ) -> Result<PaginatedResponse<Record>, DbErr> {
-        let mut query = RecordEntity::find();
-
-        if let Some(id) = search_dto.id {
-            query = query.filter(record::Column::Id.like(format!("%{id}%")));
-        }
-        if let Some(title) = search_dto.title {
-            query = query.filter(record::Column::Title.like(format!("%{title}%")));
-        }
-        if let Some(director_id) = search_dto.director_id {
-            query = query.filter(record::Column::DirectorId.eq(director_id));
-        }
-        if let Some(studio_id) = search_dto.studio_id {
-            query = query.filter(record::Column::StudioId.eq(studio_id));
-        }
-        if let Some(label_id) = search_dto.label_id {
-            query = query.filter(record::Column::LabelId.eq(label_id));
-        }
-        if let Some(series_id) = search_dto.series_id {
-            query = query.filter(record::Column::SeriesId.eq(series_id));
-        }
-
-        query = apply_user_filter(query, &user_filter);
+        let query = apply_search_filters(live_records(), &search_dto);
+        let query = apply_user_filter(query, &user_filter);
 
         let (page_size, current_offset) = resolve_pagination(&pagination);
         let (liked_param, viewed_param) = filter_params(&user_filter);
 
+        if let Some(raw) = pagination.cursor.as_deref() {
+            // Keyset mode: seek past the cursor instead of counting rows with
+            // OFFSET, and fetch one extra row to learn whether a next page
+            // exists. An empty or unreadable cursor starts from the top; only
+            // that first page pays for the total count.
+            let (query, count) = match RecordCursor::decode(raw) {
+                Some(cursor) => (apply_cursor(query, &cursor), -1),
+                None => {
+                    let count = query.clone().count(db).await? as i64;
+                    (query, count)
+                }
+            };
+            let mut record_models = query
+                .order_by(record::Column::Date, Order::Desc)
+                .order_by(record::Column::Id, Order::Asc)
+                .limit(page_size + 1)
+                .all(db)
+                .await?;
+            let has_more = record_models.len() as u64 > page_size;
+            record_models.truncate(page_size as usize);
+            let next_cursor = record_models.last().filter(|_| has_more).map(|m| {
+                RecordCursor {
+                    date: m.date,
+                    id: m.id.clone(),
+                }
+                .encode()
+            });
+            let records = load_records_batch_with(db, record_models, relations).await?;
+            let mut filters = search_params(&search_dto);
+            if user_filter.as_ref().is_some_and(|f| f.unseen_only) {
+                filters.push("seen=false".to_owned());
+            }
+            let next = next_cursor
+                .as_deref()
+                .map(|c| build_cursor_link(page_size, c, liked_param, viewed_param, &filters));
+
+            return Ok(PaginatedResponse {
+                count,
+                next,
+                previous: None,
+                next_cursor,
+                results: records,
+            });
+        }
+
         let total_items = query.clone().count(db).await?;
-        let record_models = query
-            .order_by(record::Column::Date, Order::Desc)
-            .order_by(record::Column::Id, Order::Asc)
+        let record_models = apply_ordering(query, &pagination)
             .offset(current_offset)
             .limit(page_size)
             .all(db)
             .await?;
-        let records = load_records_batch(db, record_models).await?;
+        let records = load_records_batch_with(db, record_models, relations).await?;
 
         Ok(build_paginated_response(
             records,
@@ -271,6 +1080,7 @@ impl RecordRepository for RecordRepo {
             current_offset,
             liked_param,
             viewed_param,
+            pagination.ordering.as_deref(),
         ))
     }
 
@@ -278,6 +1088,7 @@ impl RecordRepository for RecordRepo {
         &self,
         txn: &DatabaseTransaction,
         record: CreateRecordDto,
+        actor: &str,
     ) -> Result<(String, CreatedNestedEntities), DbErr> {
         use crate::domains::luna::domain::CreatedNestedEntities;
         use chrono::Utc;
@@ -290,49 +1101,18 @@ impl RecordRepository for RecordRepo {
             return Ok((record.id, nested));
         }
 
-        // Handle director creation or use default
-        let director_id = if let Some(director_dto) = record.director {
-            let name = director_dto.name.clone();
-            let director_repo = DirectorRepo;
-            let (id, _) = director_repo.create(txn, director_dto).await?;
-            nested.director = Some((id, name));
-            id
-        } else {
-            0 // Default unknown director
-        };
-
-        // Handle studio creation or use default
-        let studio_id = if let Some(studio_dto) = record.studio {
-            let name = studio_dto.name.clone();
-            let studio_repo = StudioRepo;
-            let (id, _) = studio_repo.create(txn, studio_dto).await?;
-            nested.studio = Some((id, name));
-            id
-        } else {
-            0 // Default unknown studio
-        };
-
-        // Handle label creation or use default
-        let label_id = if let Some(label_dto) = record.label {
-            let name = label_dto.name.clone();
-            let label_repo = LabelRepo;
-            let (id, _) = label_repo.create(txn, label_dto).await?;
-            nested.label = Some((id, name));
-            id
-        } else {
-            0 // Default unknown label
-        };
+        let (director_id, studio_id, label_id, series_id) = resolve_record_refs(
+            txn,
+            &mut nested,
+            record.director,
+            record.studio,
+            record.label,
+            record.series,
+        )
+        .await?;
 
-        // Handle series creation or use default
-        let series_id = if let Some(series_dto) = record.series {
-            let name = series_dto.name.clone();
-            let series_repo = SeriesRepo;
-            let (id, _) = series_repo.create(txn, series_dto).await?;
-            nested.series = Some((id, name));
-            id
-        } else {
-            0 // Default unknown series
-        };
+        // `has_links` follows the links actually stored
+        let has_links = record.links.iter().any(|l| !l.link.trim().is_empty());
 
         // Create the main record
         let record_active_model = record::ActiveModel {
@@ -344,13 +1124,19 @@ impl RecordRepository for RecordRepo {
             studio_id: Set(studio_id),
             label_id: Set(label_id),
             series_id: Set(series_id),
-            has_links: Set(record.has_links),
+            has_links: Set(has_links),
             permission: Set(record.permission),
             local_img_count: Set(record.local_img_count),
             create_time: Set(now),
             update_time: Set(now),
-            creator: Set(record.creator),
-            modified_by: Set(record.modified_by),
+            creator: Set(actor.to_owned()),
+            modified_by: Set(actor.to_owned()),
+            deleted_at: Set(None),
+            version: Set(1),
+            sync_seq: sea_orm::ActiveValue::NotSet,
+            trailer: Set(None),
+            cover_index: Set(None),
+            order_in_series: Set(None),
         };
 
         let inserted = record_active_model.insert(txn).await?;
@@ -382,9 +1168,7 @@ impl RecordRepository for RecordRepo {
             idol_participation.insert(txn).await?;
         } else {
             for idol_dto in record.idols {
-                let name = idol_dto.name.clone();
-                let idol_repo = IdolRepo;
-                let (idol_id, _) = idol_repo.create(txn, idol_dto).await?;
+                let (idol_id, name) = resolve_record_idol(txn, &mut nested, idol_dto).await?;
                 nested.idols.push((idol_id, name));
 
                 let idol_participation = idol_participation::ActiveModel {
@@ -397,13 +1181,9 @@ impl RecordRepository for RecordRepo {
             }
         }
 
-        // Handle links (skip empty/whitespace URLs, deduplicate by URL)
-        let mut seen_links: HashSet<String> = HashSet::new();
-        for link_dto in record.links {
-            let trimmed = link_dto.link.trim().to_owned();
-            if trimmed.is_empty() || !seen_links.insert(trimmed) {
-                continue;
-            }
+        // Handle links (normalized, blank and duplicate ones dropped)
+        let (record_links, _) = dedupe_links(record.links);
+        for (url, link_dto) in record_links {
             let (name, size, date) = resolve_link_defaults(&link_dto);
             let link_active_model = links::ActiveModel {
                 id: sea_orm::ActiveValue::NotSet,
@@ -411,8 +1191,13 @@ impl RecordRepository for RecordRepo {
                 name: Set(name),
                 size: Set(size),
                 date: Set(date),
-                link: Set(link_dto.link),
+                link: Set(url),
                 star: Set(link_dto.star.unwrap_or(false)),
+                priority: Set(0),
+                dead: Set(false),
+                last_status: Set(None),
+                last_checked_at: Set(None),
+                create_time: sea_orm::ActiveValue::NotSet,
             };
             link_active_model.insert(txn).await?;
         }
@@ -425,8 +1210,13 @@ impl RecordRepository for RecordRepo {
         txn: &DatabaseTransaction,
         id: String,
         record: UpdateRecordDto,
+        actor: &str,
     ) -> Result<Option<Record>, DbErr> {
-        if let Some(existing) = RecordEntity::find_by_id(&id).one(txn).await? {
+        if let Some(existing) = live_records()
+            .filter(record::Column::Id.eq(&id))
+            .one(txn)
+            .await?
+        {
             use chrono::Utc;
             let now = Utc::now().date_naive();
 
@@ -438,11 +1228,10 @@ impl RecordRepository for RecordRepo {
             active_record.studio_id = Set(record.studio_id);
             active_record.label_id = Set(record.label_id);
             active_record.series_id = Set(record.series_id);
-            active_record.has_links = Set(record.has_links);
             active_record.permission = Set(record.permission);
             active_record.local_img_count = Set(record.local_img_count);
             active_record.update_time = Set(now);
-            active_record.modified_by = Set(record.modified_by);
+            active_record.modified_by = Set(actor.to_owned());
 
             let updated = active_record.update(txn).await?;
             let rec = load_record_with_relations(txn, updated).await?;
@@ -452,99 +1241,683 @@ impl RecordRepository for RecordRepo {
         }
     }
 
-    async fn update_record_links(
+    async fn replace(
         &self,
         txn: &DatabaseTransaction,
-        record_id: String,
-        new_links: Vec<CreateLinkDto>,
-    ) -> Result<i32, DbErr> {
-        // Get existing links for the record
-        let existing_links = LinksEntity::find()
-            .filter(links::Column::RecordId.eq(&record_id))
-            .all(txn)
-            .await?;
-
-        // Track all known URLs (DB existing + newly inserted in this batch)
-        let mut known_urls: HashSet<String> =
-            existing_links.iter().map(|l| l.link.clone()).collect();
-
-        let mut changed_count = 0;
-
-        for new_link in new_links {
-            if new_link.link.trim().is_empty() {
-                continue;
-            }
-
-            if let Some(existing) = existing_links.iter().find(|l| l.link == new_link.link) {
-                // Backfill placeholder fields on existing link
-                let default_name = default_link_name();
-                let default_size = default_link_size();
-                let default_date = default_link_date();
-
-                let name_changed = existing.name == default_name
-                    && !new_link.name.trim().is_empty()
-                    && new_link.name != default_name;
-                let size_changed = existing.size == default_size && new_link.size.is_some();
-                let date_changed = existing.date == default_date && new_link.date.is_some();
+        record: CreateRecordDto,
+        override_manual: bool,
+        actor: &str,
+    ) -> Result<Option<(bool, CreatedNestedEntities)>, DbErr> {
+        let Some(existing) = RecordEntity::find_by_id(&record.id).one(txn).await? else {
+            let (_, nested) = self.create(txn, record, actor).await?;
+            return Ok(Some((true, nested)));
+        };
+        if existing.deleted_at.is_some() {
+            return Ok(None);
+        }
 
-                if name_changed || size_changed || date_changed {
-                    let mut active: links::ActiveModel = existing.clone().into();
-                    if name_changed {
-                        active.name = Set(new_link.name.clone());
-                    }
-                    if size_changed {
-                        active.size = Set(new_link
-                            .size
-                            .expect("size must be present when size_changed"));
-                    }
-                    if date_changed {
-                        active.date = Set(new_link
-                            .date
-                            .expect("date must be present when date_changed"));
-                    }
-                    active.update(txn).await?;
-                    changed_count += 1;
-                }
-            } else if known_urls.insert(new_link.link.clone()) {
-                let (name, size, date) = resolve_link_defaults(&new_link);
-                let link_active_model = links::ActiveModel {
-                    record_id: Set(record_id.clone()),
-                    name: Set(name),
-                    size: Set(size),
-                    date: Set(date),
-                    link: Set(new_link.link),
-                    star: Set(new_link.star.unwrap_or(false)),
-                    ..Default::default()
-                };
-                link_active_model.insert(txn).await?;
-                changed_count += 1;
+        let mut nested = CreatedNestedEntities::default();
+        let record_id = record.id.clone();
+        let (director_id, studio_id, label_id, series_id) = resolve_record_refs(
+            txn,
+            &mut nested,
+            record.director,
+            record.studio,
+            record.label,
+            record.series,
+        )
+        .await?;
+
+        // Scalars are replaced wholesale; `create_time` and `creator` keep
+        // their original values, and `has_links` follows the links.
+        let has_links = record.links.iter().any(|l| !l.link.trim().is_empty());
+        let mut active_record: record::ActiveModel = existing.into();
+        active_record.title = Set(record.title);
+        active_record.date = Set(record.date);
+        active_record.duration = Set(record.duration);
+        active_record.director_id = Set(director_id);
+        active_record.studio_id = Set(studio_id);
+        active_record.label_id = Set(label_id);
+        active_record.series_id = Set(series_id);
+        active_record.has_links = Set(has_links);
+        active_record.permission = Set(record.permission);
+        active_record.local_img_count = Set(record.local_img_count);
+        active_record.update_time = Set(chrono::Utc::now().date_naive());
+        active_record.modified_by = Set(actor.to_owned());
+        active_record.update(txn).await?;
+
+        // Genres and idols: resolve the desired sets, then drop and add only
+        // the difference.
+        let mut genre_ids: HashSet<i64> = HashSet::new();
+        for genre_dto in record.genres {
+            let name = genre_dto.name.clone();
+            let (genre_id, _) = GenreRepo.create(txn, genre_dto).await?;
+            if genre_ids.insert(genre_id) {
+                nested.genres.push((genre_id, name));
             }
         }
+        sync_record_genres(txn, &record_id, &genre_ids, override_manual).await?;
 
-        // Update has_links flag if any links were changed
-        if changed_count > 0 {
-            let rec = RecordEntity::find_by_id(&record_id).one(txn).await?;
-            if let Some(rec) = rec {
-                let mut active_record: record::ActiveModel = rec.into();
-                active_record.has_links = Set(true);
-                active_record.update(txn).await?;
+        let mut idol_ids: HashSet<i64> = HashSet::new();
+        for idol_dto in record.idols {
+            let (idol_id, name) = resolve_record_idol(txn, &mut nested, idol_dto).await?;
+            if idol_ids.insert(idol_id) {
+                nested.idols.push((idol_id, name));
             }
         }
+        sync_record_idols(txn, &record_id, idol_ids, override_manual).await?;
 
-        Ok(changed_count)
-    }
+        sync_record_links(txn, &record_id, record.links).await?;
 
-    async fn delete(&self, txn: &DatabaseTransaction, id: String) -> Result<bool, DbErr> {
-        let result = RecordEntity::delete_by_id(id).exec(txn).await?;
-        Ok(result.rows_affected > 0)
+        Ok(Some((false, nested)))
     }
 
-    async fn find_all_slim(
+    async fn patch(
+        &self,
+        txn: &DatabaseTransaction,
+        id: String,
+        patch: PatchRecordDto,
+        actor: &str,
+    ) -> Result<Option<Record>, DbErr> {
+        let Some(existing) = live_records()
+            .filter(record::Column::Id.eq(&id))
+            .one(txn)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        // Omitted fields stay `Unchanged`, so the UPDATE only writes what the
+        // client sent (plus `update_time` and `modified_by`).
+        let mut active_record: record::ActiveModel = existing.into();
+        if let Some(title) = patch.title {
+            active_record.title = Set(title);
+        }
+        if let Some(date) = patch.date {
+            active_record.date = Set(date);
+        }
+        if let Some(duration) = patch.duration {
+            active_record.duration = Set(duration);
+        }
+        if let Some(director_id) = patch.director_id {
+            active_record.director_id = Set(director_id);
+        }
+        if let Some(studio_id) = patch.studio_id {
+            active_record.studio_id = Set(studio_id);
+        }
+        if let Some(label_id) = patch.label_id {
+            active_record.label_id = Set(label_id);
+        }
+        if let Some(series_id) = patch.series_id {
+            active_record.series_id = Set(series_id);
+        }
+        if let Some(permission) = patch.permission {
+            active_record.permission = Set(permission);
+        }
+        if let Some(local_img_count) = patch.local_img_count {
+            active_record.local_img_count = Set(local_img_count);
+        }
+        active_record.modified_by = Set(actor.to_owned());
+        active_record.update_time = Set(chrono::Utc::now().date_naive());
+
+        let updated = active_record.update(txn).await?;
+        let rec = load_record_with_relations(txn, updated).await?;
+        Ok(Some(rec))
+    }
+
+    async fn merge_metadata(
+        &self,
+        txn: &DatabaseTransaction,
+        id: String,
+        metadata: &FetchedMetadata,
+        overwrite: bool,
+        actor: &str,
+    ) -> Result<Option<(Vec<String>, CreatedNestedEntities)>, DbErr> {
+        let Some(existing) = live_records()
+            .filter(record::Column::Id.eq(&id))
+            .one(txn)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        let mut updated = Vec::new();
+        let mut nested = CreatedNestedEntities::default();
+        let mut active_record: record::ActiveModel = existing.clone().into();
+        if let Some(title) = metadata.title.as_deref().map(str::trim) {
+            if !title.is_empty()
+                && title != existing.title
+                && (overwrite || existing.title.trim().is_empty())
+            {
+                active_record.title = Set(title.to_owned());
+                updated.push("title".to_owned());
+            }
+        }
+        if let Some(date) = metadata.date {
+            if overwrite && date != existing.date {
+                active_record.date = Set(date);
+                updated.push("date".to_owned());
+            }
+        }
+        if let Some(duration) = metadata.duration {
+            if duration > 0
+                && duration != existing.duration
+                && (overwrite || existing.duration == 0)
+            {
+                active_record.duration = Set(duration);
+                updated.push("duration".to_owned());
+            }
+        }
+
+        let mut genre_ids: HashSet<i64> = HashSet::new();
+        for name in metadata.genres.iter().map(|name| name.trim()) {
+            if name.is_empty() {
+                continue;
+            }
+            let (genre_id, created) = genre_by_name(txn, name).await?;
+            if genre_ids.insert(genre_id) && created {
+                nested.genres.push((genre_id, name.to_owned()));
+            }
+        }
+        if !genre_ids.is_empty() && sync_record_genres(txn, &id, &genre_ids, false).await? {
+            updated.push("genres".to_owned());
+        }
+
+        let mut idol_ids: HashSet<i64> = HashSet::new();
+        for name in metadata.idols.iter().map(|name| name.trim()) {
+            if name.is_empty() {
+                continue;
+            }
+            let (idol_id, created) = idol_by_name(txn, name).await?;
+            if idol_ids.insert(idol_id) && created {
+                nested.idols.push((idol_id, name.to_owned()));
+            }
+        }
+        if !idol_ids.is_empty() && sync_record_idols(txn, &id, idol_ids, false).await? {
+            updated.push("idols".to_owned());
+        }
+
+        if !updated.is_empty() {
+            active_record.modified_by = Set(actor.to_owned());
+            active_record.update_time = Set(chrono::Utc::now().date_naive());
+            active_record.update(txn).await?;
+        }
+        Ok(Some((updated, nested)))
+    }
+
+    async fn update_record_links(
+        &self,
+        txn: &DatabaseTransaction,
+        record_id: String,
+        new_links: Vec<CreateLinkDto>,
+    ) -> Result<LinkUpdateResultDto, DbErr> {
+        // Get existing links for the record
+        let existing_links = LinksEntity::find()
+            .filter(links::Column::RecordId.eq(&record_id))
+            .all(txn)
+            .await?;
+        let existing_names: HashSet<(String, Decimal)> = existing_links
+            .iter()
+            .filter_map(|l| name_size_key(&l.name, l.size))
+            .collect();
+
+        let (new_links, skipped) = dedupe_links(new_links);
+        let mut result = LinkUpdateResultDto {
+            skipped,
+            ..LinkUpdateResultDto::default()
+        };
+
+        for (url, new_link) in new_links {
+            if let Some(existing) = existing_links.iter().find(|l| same_link_url(&l.link, &url)) {
+                // Backfill placeholder fields on existing link
+                let default_name = default_link_name();
+                let default_size = default_link_size();
+                let default_date = default_link_date();
+
+                let name_changed = existing.name == default_name
+                    && !new_link.name.trim().is_empty()
+                    && new_link.name != default_name;
+                let size_changed = existing.size == default_size && new_link.size.is_some();
+                let date_changed = existing.date == default_date && new_link.date.is_some();
+
+                if name_changed || size_changed || date_changed {
+                    let mut active: links::ActiveModel = existing.clone().into();
+                    if name_changed {
+                        active.name = Set(new_link.name.clone());
+                    }
+                    if size_changed {
+                        active.size = Set(new_link
+                            .size
+                            .expect("size must be present when size_changed"));
+                    }
+                    if date_changed {
+                        active.date = Set(new_link
+                            .date
+                            .expect("date must be present when date_changed"));
+                    }
+                    active.update(txn).await?;
+                    result.updated.push(url);
+                } else {
+                    result.skipped.push(SkippedLinkDto {
+                        link: new_link.link,
+                        reason: LinkSkipReason::DuplicateUrl,
+                    });
+                }
+                continue;
+            }
+
+            let (name, size, date) = resolve_link_defaults(&new_link);
+            if name_size_key(&name, size).is_some_and(|key| existing_names.contains(&key)) {
+                result.skipped.push(SkippedLinkDto {
+                    link: new_link.link,
+                    reason: LinkSkipReason::DuplicateNameSize,
+                });
+                continue;
+            }
+            let link_active_model = links::ActiveModel {
+                record_id: Set(record_id.clone()),
+                name: Set(name),
+                size: Set(size),
+                date: Set(date),
+                link: Set(url.clone()),
+                star: Set(new_link.star.unwrap_or(false)),
+                ..Default::default()
+            };
+            link_active_model.insert(txn).await?;
+            result.inserted.push(url);
+        }
+
+        if result.changed() {
+            sync_has_links(txn, &record_id).await?;
+        }
+
+        Ok(result)
+    }
+
+    async fn replace_record_links(
+        &self,
+        txn: &DatabaseTransaction,
+        record_id: String,
+        links: Vec<CreateLinkDto>,
+    ) -> Result<LinkUpdateResultDto, DbErr> {
+        let result = sync_record_links(txn, &record_id, links).await?;
+        sync_has_links(txn, &record_id).await?;
+        Ok(result)
+    }
+
+    async fn delete(&self, txn: &DatabaseTransaction, id: String) -> Result<bool, DbErr> {
+        let result = RecordEntity::delete_by_id(id).exec(txn).await?;
+        Ok(result.rows_affected > 0)
+    }
+
+    async fn add_local_images(
+        &self,
+        txn: &DatabaseTransaction,
+        id: String,
+        added: i32,
+    ) -> Result<Option<i32>, DbErr> {
+        let updated = RecordEntity::update_many()
+            .col_expr(
+                record::Column::LocalImgCount,
+                Expr::col(record::Column::LocalImgCount).add(added),
+            )
+            .filter(record::Column::Id.eq(id))
+            .filter(record::Column::DeletedAt.is_null())
+            .exec_with_returning(txn)
+            .await?;
+        Ok(updated.first().map(|r| r.local_img_count))
+    }
+
+    async fn set_trailer(
+        &self,
+        txn: &DatabaseTransaction,
+        id: String,
+        trailer: Option<String>,
+    ) -> Result<bool, DbErr> {
+        let updated = RecordEntity::update_many()
+            .col_expr(record::Column::Trailer, Expr::value(trailer))
+            .filter(record::Column::Id.eq(id))
+            .filter(record::Column::DeletedAt.is_null())
+            .exec(txn)
+            .await?;
+        Ok(updated.rows_affected > 0)
+    }
+
+    async fn set_cover_index(
+        &self,
+        txn: &DatabaseTransaction,
+        id: String,
+        cover_index: Option<i32>,
+    ) -> Result<bool, DbErr> {
+        let updated = RecordEntity::update_many()
+            .col_expr(record::Column::CoverIndex, Expr::value(cover_index))
+            .filter(record::Column::Id.eq(id))
+            .filter(record::Column::DeletedAt.is_null())
+            .exec(txn)
+            .await?;
+        Ok(updated.rows_affected > 0)
+    }
+
+    async fn set_order_in_series(
+        &self,
+        txn: &DatabaseTransaction,
+        id: String,
+        order_in_series: Option<i32>,
+    ) -> Result<bool, DbErr> {
+        let updated = RecordEntity::update_many()
+            .col_expr(record::Column::OrderInSeries, Expr::value(order_in_series))
+            .filter(record::Column::Id.eq(id))
+            .filter(record::Column::DeletedAt.is_null())
+            .exec(txn)
+            .await?;
+        Ok(updated.rows_affected > 0)
+    }
+
+    async fn find_series_neighbors(
+        &self,
+        db: &DatabaseConnection,
+        id: &str,
+        max_permission: i32,
+    ) -> Result<(Option<SeriesNeighborDto>, Option<SeriesNeighborDto>), DbErr> {
+        #[derive(FromQueryResult)]
+        struct NeighborRow {
+            previous_id: Option<String>,
+            previous_title: Option<String>,
+            next_id: Option<String>,
+            next_title: Option<String>,
+        }
+
+        // The record itself is always part of the window so it has a
+        // position even above `max_permission`.
+        let row = NeighborRow::find_by_statement(Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            "WITH series_records AS ( \
+                SELECT r.id, \
+                       LAG(r.id) OVER w AS previous_id, \
+                       LAG(r.title) OVER w AS previous_title, \
+                       LEAD(r.id) OVER w AS next_id, \
+                       LEAD(r.title) OVER w AS next_title \
+                FROM record r \
+                JOIN record t ON t.id = $1 AND r.series_id = t.series_id \
+                WHERE t.series_id <> 0 AND r.deleted_at IS NULL \
+                  AND (r.permission <= $2 OR r.id = $1) \
+                WINDOW w AS (ORDER BY r.order_in_series ASC NULLS LAST, r.date ASC, r.id ASC) \
+             ) \
+             SELECT previous_id, previous_title, next_id, next_title \
+             FROM series_records WHERE id = $1",
+            [id.into(), max_permission.into()],
+        ))
+        .one(db)
+        .await?;
+
+        let Some(row) = row else {
+            return Ok((None, None));
+        };
+        let neighbor = |id: Option<String>, title: Option<String>| {
+            id.map(|id| SeriesNeighborDto {
+                id,
+                title: title.unwrap_or_default(),
+            })
+        };
+        Ok((
+            neighbor(row.previous_id, row.previous_title),
+            neighbor(row.next_id, row.next_title),
+        ))
+    }
+
+    async fn bump_version(
+        &self,
+        txn: &DatabaseTransaction,
+        id: String,
+        expected: Option<i32>,
+    ) -> Result<Option<i32>, DbErr> {
+        let mut query = RecordEntity::update_many()
+            .col_expr(
+                record::Column::Version,
+                sea_orm::sea_query::Expr::col(record::Column::Version).add(1),
+            )
+            .filter(record::Column::Id.eq(id))
+            .filter(record::Column::DeletedAt.is_null());
+        if let Some(expected) = expected {
+            query = query.filter(record::Column::Version.eq(expected));
+        }
+        let updated = query.exec_with_returning(txn).await?;
+        Ok(updated.first().map(|r| r.version))
+    }
+
+    async fn find_version(
+        &self,
+        txn: &DatabaseTransaction,
+        id: String,
+    ) -> Result<Option<i32>, DbErr> {
+        Ok(RecordEntity::find_by_id(id)
+            .one(txn)
+            .await?
+            .map(|r| r.version))
+    }
+
+    async fn soft_delete(&self, txn: &DatabaseTransaction, id: String) -> Result<bool, DbErr> {
+        let result = RecordEntity::update_many()
+            .col_expr(
+                record::Column::DeletedAt,
+                sea_orm::sea_query::Expr::value(chrono::Utc::now()),
+            )
+            .filter(record::Column::Id.eq(id))
+            .filter(record::Column::DeletedAt.is_null())
+            .exec(txn)
+            .await?;
+        Ok(result.rows_affected > 0)
+    }
+
+    async fn restore(&self, txn: &DatabaseTransaction, id: String) -> Result<bool, DbErr> {
+        let result = RecordEntity::update_many()
+            .col_expr(
+                record::Column::DeletedAt,
+                sea_orm::sea_query::Expr::value(Option::<chrono::DateTime<chrono::Utc>>::None),
+            )
+            .filter(record::Column::Id.eq(id))
+            .filter(record::Column::DeletedAt.is_not_null())
+            .exec(txn)
+            .await?;
+        Ok(result.rows_affected > 0)
+    }
+
+    async fn purge(&self, txn: &DatabaseTransaction, id: String) -> Result<bool, DbErr> {
+        let result = RecordEntity::delete_many()
+            .filter(record::Column::Id.eq(id))
+            .filter(record::Column::DeletedAt.is_not_null())
+            .exec(txn)
+            .await?;
+        Ok(result.rows_affected > 0)
+    }
+
+    async fn find_trash_paginated(
+        &self,
+        db: &DatabaseConnection,
+        pagination: PaginationQuery,
+        max_permission: i32,
+    ) -> Result<PaginatedResponse<Record>, DbErr> {
+        let query = RecordEntity::find()
+            .filter(record::Column::DeletedAt.is_not_null())
+            .filter(record::Column::Permission.lte(max_permission));
+        let (page_size, current_offset) = resolve_pagination(&pagination);
+
+        let total_items = query.clone().count(db).await?;
+        let record_models = query
+            .order_by(record::Column::DeletedAt, Order::Desc)
+            .order_by(record::Column::Id, Order::Asc)
+            .offset(current_offset)
+            .limit(page_size)
+            .all(db)
+            .await?;
+        let records = load_records_batch(db, record_models).await?;
+
+        Ok(build_paginated_response(
+            records,
+            total_items,
+            page_size,
+            current_offset,
+            None,
+            None,
+            None,
+        ))
+    }
+
+    async fn soft_delete_many(
+        &self,
+        txn: &DatabaseTransaction,
+        ids: Vec<String>,
+    ) -> Result<Vec<String>, DbErr> {
+        let record_ids: Vec<String> = live_records()
+            .select_only()
+            .column(record::Column::Id)
+            .filter(record::Column::Id.is_in(ids))
+            .into_tuple()
+            .all(txn)
+            .await?;
+        if record_ids.is_empty() {
+            return Ok(record_ids);
+        }
+
+        RecordEntity::update_many()
+            .col_expr(
+                record::Column::DeletedAt,
+                sea_orm::sea_query::Expr::value(chrono::Utc::now()),
+            )
+            .filter(record::Column::Id.is_in(record_ids.clone()))
+            .exec(txn)
+            .await?;
+        Ok(record_ids)
+    }
+
+    async fn delete_many(
+        &self,
+        txn: &DatabaseTransaction,
+        ids: Vec<String>,
+    ) -> Result<DeletedRecordRows, DbErr> {
+        let record_ids: Vec<String> = RecordEntity::find()
+            .select_only()
+            .column(record::Column::Id)
+            .filter(record::Column::Id.is_in(ids))
+            .into_tuple()
+            .all(txn)
+            .await?;
+        if record_ids.is_empty() {
+            return Ok(DeletedRecordRows::default());
+        }
+
+        // The junction and link tables cascade on delete, but removing them
+        // explicitly lets the caller report how many rows went with each record.
+        let record_genres = record_genre::Entity::delete_many()
+            .filter(record_genre::Column::RecordId.is_in(record_ids.clone()))
+            .exec(txn)
+            .await?
+            .rows_affected;
+        let idol_participations = idol_participation::Entity::delete_many()
+            .filter(idol_participation::Column::RecordId.is_in(record_ids.clone()))
+            .exec(txn)
+            .await?
+            .rows_affected;
+        let links = LinksEntity::delete_many()
+            .filter(links::Column::RecordId.is_in(record_ids.clone()))
+            .exec(txn)
+            .await?
+            .rows_affected;
+        RecordEntity::delete_many()
+            .filter(record::Column::Id.is_in(record_ids.clone()))
+            .exec(txn)
+            .await?;
+
+        Ok(DeletedRecordRows {
+            record_ids,
+            record_genres,
+            idol_participations,
+            links,
+        })
+    }
+
+    async fn merge_into(
+        &self,
+        txn: &DatabaseTransaction,
+        target_id: String,
+        source_id: String,
+        actor: &str,
+    ) -> Result<Option<Record>, DbErr> {
+        let Some(target) = live_records()
+            .filter(record::Column::Id.eq(&target_id))
+            .one(txn)
+            .await?
+        else {
+            return Ok(None);
+        };
+        let Some(source) = live_records()
+            .filter(record::Column::Id.eq(&source_id))
+            .one(txn)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        // Rows the target already has a match for stay with the source and
+        // go when it is deleted; moving them would violate the unique indexes.
+        for (table, key) in RECORD_MERGE_TABLES {
+            txn.execute(Statement::from_sql_and_values(
+                DatabaseBackend::Postgres,
+                format!(
+                    "UPDATE {table} SET record_id = $1 \
+                     WHERE record_id = $2 \
+                       AND {key} NOT IN (SELECT {key} FROM {table} WHERE record_id = $1)"
+                ),
+                [target_id.clone().into(), source_id.clone().into()],
+            ))
+            .await?;
+        }
+        for table in ["record_comments", "crawl_code_result"] {
+            txn.execute(Statement::from_sql_and_values(
+                DatabaseBackend::Postgres,
+                format!("UPDATE {table} SET record_id = $1 WHERE record_id = $2"),
+                [target_id.clone().into(), source_id.clone().into()],
+            ))
+            .await?;
+        }
+
+        let has_links = LinksEntity::find()
+            .filter(links::Column::RecordId.eq(&target_id))
+            .count(txn)
+            .await?
+            > 0;
+        let local_img_count = target.local_img_count + source.local_img_count;
+        let source_is_newer = source.update_time > target.update_time;
+
+        let mut active_record: record::ActiveModel = target.into();
+        if source_is_newer {
+            active_record.title = Set(source.title);
+            active_record.date = Set(source.date);
+            active_record.duration = Set(source.duration);
+            active_record.director_id = Set(source.director_id);
+            active_record.studio_id = Set(source.studio_id);
+            active_record.label_id = Set(source.label_id);
+            active_record.series_id = Set(source.series_id);
+            active_record.permission = Set(source.permission);
+        }
+        active_record.has_links = Set(has_links);
+        active_record.local_img_count = Set(local_img_count);
+        active_record.modified_by = Set(actor.to_owned());
+        active_record.update_time = Set(chrono::Utc::now().date_naive());
+        let updated = active_record.update(txn).await?;
+
+        RecordEntity::delete_by_id(source_id).exec(txn).await?;
+
+        let rec = load_record_with_relations(txn, updated).await?;
+        Ok(Some(rec))
+    }
+
+    async fn find_all_slim(
         &self,
         db: &DatabaseConnection,
         user_filter: Option<UserFilter>,
     ) -> Result<Vec<Record>, DbErr> {
-        let query = apply_user_filter(RecordEntity::find(), &user_filter);
+        let query = apply_user_filter(live_records(), &user_filter);
         let record_models = query
             .order_by(record::Column::Date, Order::Desc)
             .order_by(record::Column::Id, Order::Asc)
@@ -563,7 +1936,7 @@ impl RecordRepository for RecordRepo {
             id: String,
         }
 
-        let query = apply_user_filter(RecordEntity::find(), &user_filter);
+        let query = apply_user_filter(live_records(), &user_filter);
         let records: Vec<IdOnly> = query
             .order_by(record::Column::Date, Order::Desc)
             .order_by(record::Column::Id, Order::Asc)
@@ -576,6 +1949,48 @@ impl RecordRepository for RecordRepo {
         Ok(records.into_iter().map(|r| r.id).collect())
     }
 
+    async fn find_existing_ids(
+        &self,
+        db: &DatabaseConnection,
+        ids: Vec<String>,
+        max_permission: i32,
+    ) -> Result<Vec<String>, DbErr> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let found: HashSet<String> = live_records()
+            .select_only()
+            .column(record::Column::Id)
+            .filter(record::Column::Id.is_in(ids.clone()))
+            .filter(record::Column::Permission.lte(max_permission))
+            .into_tuple::<String>()
+            .all(db)
+            .await?
+            .into_iter()
+            .collect();
+
+        let mut seen = HashSet::new();
+        Ok(ids
+            .into_iter()
+            .filter(|id| found.contains(id) && seen.insert(id.clone()))
+            .collect())
+    }
+
+    async fn find_permission(
+        &self,
+        db: &DatabaseConnection,
+        id: &str,
+    ) -> Result<Option<i32>, DbErr> {
+        live_records()
+            .select_only()
+            .column(record::Column::Permission)
+            .filter(record::Column::Id.eq(id))
+            .into_tuple()
+            .one(db)
+            .await
+    }
+
     async fn find_ids_paginated(
         &self,
         db: &DatabaseConnection,
@@ -587,16 +2002,14 @@ impl RecordRepository for RecordRepo {
             id: String,
         }
 
-        let query = apply_user_filter(RecordEntity::find(), &user_filter);
+        let query = apply_user_filter(live_records(), &user_filter);
         let (page_size, current_offset) = resolve_pagination(&pagination);
         let (liked_param, viewed_param) = filter_params(&user_filter);
 
         let total_items = query.clone().count(db).await?;
-        let records: Vec<IdOnly> = query
+        let records: Vec<IdOnly> = apply_ordering(query, &pagination)
             .select_only()
             .column(record::Column::Id)
-            .order_by(record::Column::Date, Order::Desc)
-            .order_by(record::Column::Id, Order::Asc)
             .offset(current_offset)
             .limit(page_size)
             .into_model::<IdOnly>()
@@ -612,23 +2025,24 @@ impl RecordRepository for RecordRepo {
             current_offset,
             liked_param,
             viewed_param,
+            pagination.ordering.as_deref(),
         ))
     }
 
     async fn find_all_slim_paginated(
         &self,
         db: &DatabaseConnection,
+        search_dto: SearchRecordDto,
         pagination: PaginationQuery,
         user_filter: Option<UserFilter>,
     ) -> Result<PaginatedResponse<Record>, DbErr> {
-        let query = apply_user_filter(RecordEntity::find(), &user_filter);
+        let query = apply_search_filters(live_records(), &search_dto);
+        let query = apply_user_filter(query, &user_filter);
         let (page_size, current_offset) = resolve_pagination(&pagination);
         let (liked_param, viewed_param) = filter_params(&user_filter);
 
         let total_items = query.clone().count(db).await?;
-        let record_models = query
-            .order_by(record::Column::Date, Order::Desc)
-            .order_by(record::Column::Id, Order::Asc)
+        let record_models = apply_ordering(query, &pagination)
             .offset(current_offset)
             .limit(page_size)
             .all(db)
@@ -642,6 +2056,7 @@ impl RecordRepository for RecordRepo {
             current_offset,
             liked_param,
             viewed_param,
+            pagination.ordering.as_deref(),
         ))
     }
 
@@ -650,9 +2065,11 @@ impl RecordRepository for RecordRepo {
         db: &DatabaseConnection,
         genre_id: i64,
     ) -> Result<Vec<Record>, DbErr> {
-        let record_models = RecordEntity::find()
-            .join_rev(JoinType::InnerJoin, record_genre::Relation::Record.def())
-            .filter(record_genre::Column::GenreId.eq(genre_id))
+        let search_dto = SearchRecordDto {
+            genre_id: Some(genre_id),
+            ..Default::default()
+        };
+        let record_models = apply_search_filters(live_records(), &search_dto)
             .order_by(record::Column::Date, Order::Desc)
             .order_by(record::Column::Id, Order::Asc)
             .all(db)
@@ -660,96 +2077,279 @@ impl RecordRepository for RecordRepo {
         load_records_batch(db, record_models).await
     }
 
-    async fn find_by_genre_id_paginated(
+    async fn find_by_idol_id(
         &self,
         db: &DatabaseConnection,
-        genre_id: i64,
-        pagination: PaginationQuery,
-        user_filter: Option<UserFilter>,
-    ) -> Result<PaginatedResponse<Record>, DbErr> {
-        let query = RecordEntity::find()
-            .join_rev(JoinType::InnerJoin, record_genre::Relation::Record.def())
-            .filter(record_genre::Column::GenreId.eq(genre_id));
-        let query = apply_user_filter(query, &user_filter);
-
-        let (page_size, current_offset) = resolve_pagination(&pagination);
-        let (liked_param, viewed_param) = filter_params(&user_filter);
-
-        let total_items = query.clone().count(db).await?;
-        let record_models = query
+        idol_id: i64,
+    ) -> Result<Vec<Record>, DbErr> {
+        let search_dto = SearchRecordDto {
+            idol_id: Some(idol_id),
+            ..Default::default()
+        };
+        let record_models = apply_search_filters(live_records(), &search_dto)
             .order_by(record::Column::Date, Order::Desc)
             .order_by(record::Column::Id, Order::Asc)
-            .offset(current_offset)
-            .limit(page_size)
             .all(db)
             .await?;
-        let records = load_records_batch(db, record_models).await?;
+        load_records_batch(db, record_models).await
+    }
 
-        Ok(build_paginated_response(
-            records,
-            total_items,
-            page_size,
-            current_offset,
-            liked_param,
-            viewed_param,
-        ))
+    async fn find_changes_since(
+        &self,
+        db: &DatabaseConnection,
+        since: i64,
+        limit: u64,
+        max_permission: i32,
+    ) -> Result<RecordChanges, DbErr> {
+        // Trashed rows are included on purpose: they surface as deletions.
+        // Positions are drawn at commit, so none can appear below `since`
+        // after a client has synced past it.
+        let written = RecordEntity::find()
+            .filter(record::Column::SyncSeq.gt(since))
+            .order_by(record::Column::SyncSeq, Order::Asc)
+            .limit(limit + 1)
+            .all(db)
+            .await?;
+        let removed = RecordDeletionEntity::find()
+            .filter(record_deletion::Column::Seq.gt(since))
+            .order_by(record_deletion::Column::Seq, Order::Asc)
+            .limit(limit + 1)
+            .all(db)
+            .await?;
+
+        // Merge both streams in sequence order and cut the page at `limit`
+        let mut changes: Vec<(i64, String, Option<record::Model>)> = written
+            .into_iter()
+            .map(|m| (m.sync_seq, m.id.clone(), Some(m)))
+            .chain(removed.into_iter().map(|d| (d.seq, d.record_id, None)))
+            .collect();
+        changes.sort_by_key(|(seq, _, _)| *seq);
+        let has_more = changes.len() as u64 > limit;
+        changes.truncate(limit as usize);
+        let next_since = changes.last().map_or(since, |(seq, _, _)| *seq);
+
+        // A record can change more than once within a page; only the last
+        // change matters to the client.
+        let mut latest: HashMap<String, i64> = HashMap::new();
+        for (seq, id, _) in &changes {
+            latest.insert(id.clone(), *seq);
+        }
+        let mut models = Vec::new();
+        let mut deleted = Vec::new();
+        for (seq, id, model) in changes {
+            if latest.get(&id) != Some(&seq) {
+                continue;
+            }
+            match model {
+                Some(m) if m.deleted_at.is_none() && m.permission <= max_permission => {
+                    models.push(m);
+                }
+                _ => deleted.push(id),
+            }
+        }
+
+        Ok(RecordChanges {
+            records: load_records_batch(db, models).await?,
+            deleted,
+            next_since,
+            has_more,
+        })
     }
 
-    async fn find_by_idol_id(
+    async fn find_recent(
         &self,
         db: &DatabaseConnection,
-        idol_id: i64,
+        limit: u64,
+        max_permission: i32,
     ) -> Result<Vec<Record>, DbErr> {
+        // Every insert and update restamps `sync_seq`, so it orders by recency
+        // more finely than the date-only `update_time`.
         let record_models = RecordEntity::find()
-            .join_rev(
-                JoinType::InnerJoin,
-                idol_participation::Relation::Record.def(),
-            )
-            .filter(idol_participation::Column::IdolId.eq(idol_id))
-            .order_by(record::Column::Date, Order::Desc)
-            .order_by(record::Column::Id, Order::Asc)
+            .filter(record::Column::DeletedAt.is_null())
+            .filter(record::Column::Permission.lte(max_permission))
+            .order_by(record::Column::SyncSeq, Order::Desc)
+            .limit(limit)
             .all(db)
             .await?;
         load_records_batch(db, record_models).await
     }
 
-    async fn find_by_idol_id_paginated(
+    async fn find_similar(
         &self,
         db: &DatabaseConnection,
-        idol_id: i64,
+        id: &str,
+        weights: SimilarityWeights,
+        limit: u64,
+        max_permission: i32,
+    ) -> Result<Vec<(Record, f64)>, DbErr> {
+        #[derive(FromQueryResult)]
+        struct ScoreRow {
+            id: String,
+            score: f64,
+        }
+
+        // Each shared idol or genre, and a shared series or studio, adds one
+        // weighted row per candidate; placeholder entities (ID 0) are not
+        // shared metadata.
+        let rows = ScoreRow::find_by_statement(Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            "WITH target AS (SELECT id, series_id, studio_id FROM record WHERE id = $1), \
+             shared AS ( \
+                 SELECT ip.record_id, $2::DOUBLE PRECISION AS weight \
+                 FROM idol_participation tp \
+                 JOIN idol_participation ip ON ip.idol_id = tp.idol_id \
+                 WHERE tp.record_id = $1 AND ip.record_id <> $1 AND tp.idol_id <> 0 \
+                 UNION ALL \
+                 SELECT rg.record_id, $3::DOUBLE PRECISION \
+                 FROM record_genre tg \
+                 JOIN record_genre rg ON rg.genre_id = tg.genre_id \
+                 WHERE tg.record_id = $1 AND rg.record_id <> $1 AND tg.genre_id <> 0 \
+                 UNION ALL \
+                 SELECT r.id, $4::DOUBLE PRECISION \
+                 FROM target t JOIN record r ON r.series_id = t.series_id \
+                 WHERE r.id <> t.id AND t.series_id <> 0 \
+                 UNION ALL \
+                 SELECT r.id, $5::DOUBLE PRECISION \
+                 FROM target t JOIN record r ON r.studio_id = t.studio_id \
+                 WHERE r.id <> t.id AND t.studio_id <> 0 \
+             ) \
+             SELECT s.record_id AS id, SUM(s.weight) AS score \
+             FROM shared s JOIN record r ON r.id = s.record_id \
+             WHERE r.deleted_at IS NULL AND r.permission <= $6 \
+             GROUP BY s.record_id \
+             HAVING SUM(s.weight) > 0 \
+             ORDER BY score DESC, s.record_id \
+             LIMIT $7",
+            [
+                id.into(),
+                weights.idol.into(),
+                weights.genre.into(),
+                weights.series.into(),
+                weights.studio.into(),
+                max_permission.into(),
+                (limit as i64).into(),
+            ],
+        ))
+        .all(db)
+        .await?;
+
+        let ids: Vec<String> = rows.iter().map(|row| row.id.clone()).collect();
+        let scores: HashMap<String, f64> =
+            rows.into_iter().map(|row| (row.id, row.score)).collect();
+        let mut record_models = RecordEntity::find()
+            .filter(record::Column::Id.is_in(ids.clone()))
+            .all(db)
+            .await?;
+        record_models.sort_by_key(|m| ids.iter().position(|id| *id == m.id));
+        let records = load_records_slim(db, record_models).await?;
+        Ok(records
+            .into_iter()
+            .map(|record| {
+                let score = scores.get(&record.id).copied().unwrap_or_default();
+                (record, score)
+            })
+            .collect())
+    }
+
+    async fn find_random(
+        &self,
+        db: &DatabaseConnection,
+        count: u64,
+        genre_id: Option<i64>,
+        idol_id: Option<i64>,
+        max_permission: i32,
+    ) -> Result<Vec<Record>, DbErr> {
+        // Unfiltered picks from a large table read a small block sample
+        // instead of sorting every row by `RANDOM()`.
+        if genre_id.is_none() && idol_id.is_none() {
+            if let Some(ids) = sample_record_ids(db, count, max_permission).await? {
+                let mut record_models = live_records()
+                    .filter(record::Column::Id.is_in(ids.clone()))
+                    .all(db)
+                    .await?;
+                record_models.sort_by_key(|m| ids.iter().position(|id| *id == m.id));
+                return load_records_batch(db, record_models).await;
+            }
+        }
+
+        let search_dto = SearchRecordDto {
+            genre_id,
+            idol_id,
+            ..Default::default()
+        };
+        let record_models = apply_search_filters(live_records(), &search_dto)
+            .filter(record::Column::Permission.lte(max_permission))
+            .order_by(Expr::cust("RANDOM()"), Order::Asc)
+            .limit(count)
+            .all(db)
+            .await?;
+        load_records_batch(db, record_models).await
+    }
+
+    async fn find_seen_paginated(
+        &self,
+        db: &DatabaseConnection,
+        user_id: &str,
         pagination: PaginationQuery,
-        user_filter: Option<UserFilter>,
-    ) -> Result<PaginatedResponse<Record>, DbErr> {
-        let query = RecordEntity::find()
+        max_permission: i32,
+    ) -> Result<PaginatedResponse<(Record, chrono::DateTime<chrono::Utc>)>, DbErr> {
+        let query = live_records()
+            .filter(record::Column::Permission.lte(max_permission))
             .join_rev(
                 JoinType::InnerJoin,
-                idol_participation::Relation::Record.def(),
+                user_record_interaction::Relation::Record.def(),
             )
-            .filter(idol_participation::Column::IdolId.eq(idol_id));
-        let query = apply_user_filter(query, &user_filter);
-
+            .filter(user_record_interaction::Column::UserId.eq(user_id))
+            .filter(user_record_interaction::Column::Viewed.eq(true))
+            .filter(user_record_interaction::Column::ViewedAt.is_not_null());
         let (page_size, current_offset) = resolve_pagination(&pagination);
-        let (liked_param, viewed_param) = filter_params(&user_filter);
 
         let total_items = query.clone().count(db).await?;
         let record_models = query
-            .order_by(record::Column::Date, Order::Desc)
+            .order_by(user_record_interaction::Column::ViewedAt, Order::Desc)
             .order_by(record::Column::Id, Order::Asc)
             .offset(current_offset)
             .limit(page_size)
             .all(db)
             .await?;
-        let records = load_records_batch(db, record_models).await?;
+        let record_ids: Vec<String> = record_models.iter().map(|m| m.id.clone()).collect();
+        let seen_at: HashMap<String, chrono::DateTime<chrono::Utc>> =
+            user_record_interaction::Entity::find()
+                .filter(user_record_interaction::Column::UserId.eq(user_id))
+                .filter(user_record_interaction::Column::RecordId.is_in(record_ids))
+                .all(db)
+                .await?
+                .into_iter()
+                .filter_map(|row| Some((row.record_id, row.viewed_at?)))
+                .collect();
+        let records = load_records_batch(db, record_models)
+            .await?
+            .into_iter()
+            .filter_map(|record| {
+                let seen_at = *seen_at.get(&record.id)?;
+                Some((record, seen_at))
+            })
+            .collect();
 
         Ok(build_paginated_response(
             records,
             total_items,
             page_size,
             current_offset,
-            liked_param,
-            viewed_param,
+            None,
+            None,
+            None,
         ))
     }
+
+    async fn find_relations(
+        &self,
+        db: &DatabaseConnection,
+        record_ids: Vec<String>,
+        relations: RecordRelations,
+    ) -> Result<HashMap<String, RecordRelationRows>, DbErr> {
+        load_relations_batch(db, record_ids, relations).await
+    }
 }
 
 #[cfg(test)]
@@ -779,6 +2379,51 @@ mod tests {
         );
     }
 
+    #[test]
+    fn dedupe_links_drops_blank_and_repeated_links() {
+        let link = |name: &str, size: Option<&str>, url: &str| CreateLinkDto {
+            name: name.to_owned(),
+            size: size.map(|s| Decimal::from_str_exact(s).expect("valid decimal")),
+            date: None,
+            link: url.to_owned(),
+            star: None,
+        };
+        let (kept, skipped) = dedupe_links(vec![
+            link("One", Some("1.5"), " https://example.com/a?utm_campaign=x "),
+            link("Other", None, "https://EXAMPLE.com/A"),
+            link("one", Some("1.5"), "https://example.com/b"),
+            link("None", None, "https://example.com/c"),
+            link("None", None, "https://example.com/d"),
+            link("Blank", None, " "),
+        ]);
+
+        let urls: Vec<&str> = kept.iter().map(|(url, _)| url.as_str()).collect();
+        assert_eq!(
+            urls,
+            [
+                "https://example.com/a",
+                "https://example.com/c",
+                "https://example.com/d"
+            ],
+            "placeholder names and sizes never make links duplicates"
+        );
+        let reasons: Vec<LinkSkipReason> = skipped.iter().map(|s| s.reason).collect();
+        assert_eq!(
+            reasons,
+            [
+                LinkSkipReason::DuplicateUrl,
+                LinkSkipReason::DuplicateNameSize,
+                LinkSkipReason::Blank
+            ]
+        );
+    }
+
+    #[test]
+    fn escape_like_pattern_treats_wildcards_literally() {
+        assert_eq!(escape_like_pattern("100%_off\\"), "100\\%\\_off\\\\");
+        assert_eq!(escape_like_pattern("plain"), "plain");
+    }
+
     #[test]
     fn resolve_link_defaults_preserves_explicit_name_size_and_date() {
         // Real metadata must survive normalization unchanged; only placeholder
@@ -4,16 +4,34 @@
 //! avoid N+1 query patterns when assembling records with their relations.
 
 use crate::domains::luna::domain::{
-    Director, Genre, Idol, IdolParticipation, Label, Link, Record, RecordGenre, Series, Studio,
+    Director, Genre, Idol, IdolParticipation, Label, Link, Record, RecordGenre, RecordRelationRows,
+    Series, Studio,
 };
+use crate::domains::luna::dto::RecordRelations;
 use crate::entities::{
-    director, idol_participation, label, links, record, record_genre, series, studio,
-    DirectorEntity, GenreEntity, IdolEntity, IdolParticipationEntity, LabelEntity, LinksEntity,
-    RecordGenreEntity, SeriesEntity, StudioEntity,
+    director, idol_participation, label, links, record, record_comments, record_genre,
+    record_rating, series, studio, DirectorEntity, GenreEntity, IdolEntity,
+    IdolParticipationEntity, LabelEntity, LinksEntity, RecordCommentsEntity, RecordEntity,
+    RecordGenreEntity, RecordRatingEntity, SeriesEntity, StudioEntity,
+};
+use sea_orm::prelude::Date;
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ColumnTrait as _, ConnectionTrait, DbErr, EntityTrait as _, FromQueryResult, QueryFilter as _,
+    QueryOrder as _, QuerySelect as _,
 };
-use sea_orm::{ColumnTrait as _, ConnectionTrait, DbErr, EntityTrait as _, QueryFilter as _};
 use std::collections::HashMap;
 
+/// Links in the order records list them: starred first, then by priority
+/// and size, largest first.
+fn ranked_links() -> sea_orm::Select<LinksEntity> {
+    LinksEntity::find()
+        .order_by_desc(links::Column::Star)
+        .order_by_desc(links::Column::Priority)
+        .order_by_desc(links::Column::Size)
+        .order_by_asc(links::Column::Id)
+}
+
 /// Load a single record with all related data using any connection-like type.
 pub(super) async fn load_record_with_relations<C: ConnectionTrait>(
     db: &C,
@@ -67,21 +85,34 @@ pub(super) async fn load_record_with_relations<C: ConnectionTrait>(
     let idols = idol_participations
         .into_iter()
         .filter_map(|(ip, idol_opt)| {
-            idol_opt.map(|idol| IdolParticipation {
-                idol: Idol::from(idol),
-                manual: ip.manual,
+            idol_opt.map(|idol| {
+                let idol = Idol::from(idol);
+                IdolParticipation {
+                    age_at_record: idol.age_on(record_model.date),
+                    idol,
+                    manual: ip.manual,
+                }
             })
         })
         .collect();
 
     // Load links
-    let links_models = LinksEntity::find()
+    let links_models = ranked_links()
         .filter(links::Column::RecordId.eq(&record_model.id))
         .all(db)
         .await?;
 
     let links = links_models.into_iter().map(Link::from).collect();
 
+    let (rating_average, rating_count) = load_ratings_batch(db, vec![record_model.id.clone()])
+        .await?
+        .remove(&record_model.id)
+        .unwrap_or_default();
+    let comment_count = load_comment_counts_batch(db, vec![record_model.id.clone()])
+        .await?
+        .remove(&record_model.id)
+        .unwrap_or_default();
+
     Ok(Record {
         id: record_model.id,
         title: record_model.title,
@@ -101,15 +132,31 @@ pub(super) async fn load_record_with_relations<C: ConnectionTrait>(
         update_time: record_model.update_time,
         creator: record_model.creator,
         modified_by: record_model.modified_by,
+        version: record_model.version,
+        trailer: record_model.trailer,
+        cover_index: record_model.cover_index,
+        order_in_series: record_model.order_in_series,
+        rating_average,
+        rating_count,
+        comment_count,
     })
 }
 
-/// Batch-load multiple records with all related data using only ~8 queries total
+/// Batch-load multiple records with all related data using only ~10 queries total
 /// instead of 7 queries per record (N+1 fix).
-#[expect(clippy::too_many_lines)]
 pub(super) async fn load_records_batch<C: ConnectionTrait>(
     db: &C,
     record_models: Vec<record::Model>,
+) -> Result<Vec<Record>, DbErr> {
+    load_records_batch_with(db, record_models, RecordRelations::ALL).await
+}
+
+/// Like [`load_records_batch`], but only queries the junction relations
+/// selected in `relations`; the others are left empty.
+pub(super) async fn load_records_batch_with<C: ConnectionTrait>(
+    db: &C,
+    record_models: Vec<record::Model>,
+    relations: RecordRelations,
 ) -> Result<Vec<Record>, DbErr> {
     if record_models.is_empty() {
         return Ok(Vec::new());
@@ -159,68 +206,14 @@ pub(super) async fn load_records_batch<C: ConnectionTrait>(
         .map(|s| (s.id, s))
         .collect();
 
-    // Batch load genres (query 5)
-    let all_record_genres = RecordGenreEntity::find()
-        .filter(record_genre::Column::RecordId.is_in(record_ids.clone()))
-        .find_also_related(GenreEntity)
-        .all(db)
-        .await?;
-
-    let genres_by_record: HashMap<String, Vec<RecordGenre>> = {
-        let mut map = HashMap::new();
-        for (rg, genre_opt) in all_record_genres {
-            if let Some(genre) = genre_opt {
-                let entry = RecordGenre {
-                    genre: Genre::from(genre),
-                    manual: rg.manual,
-                };
-                map.entry(rg.record_id.clone())
-                    .or_insert_with(Vec::new)
-                    .push(entry);
-            }
-        }
-        map
-    };
-
-    // Batch load idols (query 6)
-    let all_idol_participations = IdolParticipationEntity::find()
-        .filter(idol_participation::Column::RecordId.is_in(record_ids.clone()))
-        .find_also_related(IdolEntity)
-        .all(db)
-        .await?;
-
-    let idols_by_record: HashMap<String, Vec<IdolParticipation>> = {
-        let mut map = HashMap::new();
-        for (ip, idol_opt) in all_idol_participations {
-            if let Some(idol) = idol_opt {
-                let entry = IdolParticipation {
-                    idol: Idol::from(idol),
-                    manual: ip.manual,
-                };
-                map.entry(ip.record_id.clone())
-                    .or_insert_with(Vec::new)
-                    .push(entry);
-            }
-        }
-        map
-    };
+    // Batch load rating aggregates (query 5)
+    let mut ratings = load_ratings_batch(db, record_ids.clone()).await?;
 
-    // Batch load links (query 7)
-    let all_links = LinksEntity::find()
-        .filter(links::Column::RecordId.is_in(record_ids))
-        .all(db)
-        .await?;
+    // Batch load comment counts (query 6)
+    let mut comment_counts = load_comment_counts_batch(db, record_ids.clone()).await?;
 
-    let links_by_record: HashMap<String, Vec<Link>> = {
-        let mut map = HashMap::new();
-        for link_model in all_links {
-            let link = Link::from(link_model);
-            map.entry(link.record_id.clone())
-                .or_insert_with(Vec::new)
-                .push(link);
-        }
-        map
-    };
+    // Batch load genres, idols and links (queries 7-9)
+    let mut relation_rows = load_relations_batch(db, record_ids, relations).await?;
 
     // Assemble records
     let mut records = Vec::with_capacity(record_models.len());
@@ -238,18 +231,13 @@ pub(super) async fn load_records_batch<C: ConnectionTrait>(
             .get(&record_model.series_id)
             .ok_or_else(|| DbErr::RecordNotFound("Series not found".to_owned()))?;
 
-        let genres = genres_by_record
-            .get(&record_model.id)
-            .cloned()
-            .unwrap_or_default();
-        let idols = idols_by_record
-            .get(&record_model.id)
-            .cloned()
-            .unwrap_or_default();
-        let links = links_by_record
-            .get(&record_model.id)
-            .cloned()
-            .unwrap_or_default();
+        let RecordRelationRows {
+            genres,
+            idols,
+            links,
+        } = relation_rows.remove(&record_model.id).unwrap_or_default();
+        let (rating_average, rating_count) = ratings.remove(&record_model.id).unwrap_or_default();
+        let comment_count = comment_counts.remove(&record_model.id).unwrap_or_default();
 
         records.push(Record {
             id: record_model.id,
@@ -270,12 +258,168 @@ pub(super) async fn load_records_batch<C: ConnectionTrait>(
             update_time: record_model.update_time,
             creator: record_model.creator,
             modified_by: record_model.modified_by,
+            version: record_model.version,
+            trailer: record_model.trailer,
+            cover_index: record_model.cover_index,
+            order_in_series: record_model.order_in_series,
+            rating_average,
+            rating_count,
+            comment_count,
         });
     }
 
     Ok(records)
 }
 
+#[derive(FromQueryResult)]
+struct RatingRow {
+    record_id: String,
+    average: f64,
+    count: i64,
+}
+
+/// Batch-load the rating average and count of `record_ids`. Unrated records
+/// are absent from the map.
+async fn load_ratings_batch<C: ConnectionTrait>(
+    db: &C,
+    record_ids: Vec<String>,
+) -> Result<HashMap<String, (Option<f64>, i64)>, DbErr> {
+    let rows = RecordRatingEntity::find()
+        .select_only()
+        .column(record_rating::Column::RecordId)
+        .column_as(
+            Expr::cust("AVG(record_rating.score)::DOUBLE PRECISION"),
+            "average",
+        )
+        .column_as(record_rating::Column::Id.count(), "count")
+        .filter(record_rating::Column::RecordId.is_in(record_ids))
+        .group_by(record_rating::Column::RecordId)
+        .into_model::<RatingRow>()
+        .all(db)
+        .await?;
+    Ok(rows
+        .into_iter()
+        .map(|row| (row.record_id, (Some(row.average), row.count)))
+        .collect())
+}
+
+#[derive(FromQueryResult)]
+struct CommentCountRow {
+    record_id: String,
+    count: i64,
+}
+
+/// Batch-load the number of comments on `record_ids`. Records without
+/// comments are absent from the map.
+async fn load_comment_counts_batch<C: ConnectionTrait>(
+    db: &C,
+    record_ids: Vec<String>,
+) -> Result<HashMap<String, i64>, DbErr> {
+    let rows = RecordCommentsEntity::find()
+        .select_only()
+        .column(record_comments::Column::RecordId)
+        .column_as(record_comments::Column::Id.count(), "count")
+        .filter(record_comments::Column::RecordId.is_in(record_ids))
+        .group_by(record_comments::Column::RecordId)
+        .into_model::<CommentCountRow>()
+        .all(db)
+        .await?;
+    Ok(rows
+        .into_iter()
+        .map(|row| (row.record_id, row.count))
+        .collect())
+}
+
+/// Batch-load the genres, idols and links of `record_ids` selected in
+/// `relations`, with one query per selected relation. Records without any
+/// rows are absent from the map.
+pub(super) async fn load_relations_batch<C: ConnectionTrait>(
+    db: &C,
+    record_ids: Vec<String>,
+    relations: RecordRelations,
+) -> Result<HashMap<String, RecordRelationRows>, DbErr> {
+    let mut rows: HashMap<String, RecordRelationRows> = HashMap::new();
+    if record_ids.is_empty() {
+        return Ok(rows);
+    }
+
+    if relations.genres {
+        let record_genres = RecordGenreEntity::find()
+            .filter(record_genre::Column::RecordId.is_in(record_ids.clone()))
+            .find_also_related(GenreEntity)
+            .all(db)
+            .await?;
+        for (rg, genre_opt) in record_genres {
+            if let Some(genre) = genre_opt {
+                rows.entry(rg.record_id)
+                    .or_default()
+                    .genres
+                    .push(RecordGenre {
+                        genre: Genre::from(genre),
+                        manual: rg.manual,
+                    });
+            }
+        }
+    }
+
+    if relations.idols {
+        let idol_participations = IdolParticipationEntity::find()
+            .filter(idol_participation::Column::RecordId.is_in(record_ids.clone()))
+            .find_also_related(IdolEntity)
+            .all(db)
+            .await?;
+        // Ages need the release dates, which only matter for idols with a
+        // known birthday.
+        let dates: HashMap<String, Date> = if idol_participations
+            .iter()
+            .any(|(_, idol)| idol.as_ref().is_some_and(|idol| idol.birthday.is_some()))
+        {
+            RecordEntity::find()
+                .select_only()
+                .column(record::Column::Id)
+                .column(record::Column::Date)
+                .filter(record::Column::Id.is_in(record_ids.clone()))
+                .into_tuple::<(String, Date)>()
+                .all(db)
+                .await?
+                .into_iter()
+                .collect()
+        } else {
+            HashMap::new()
+        };
+        for (ip, idol_opt) in idol_participations {
+            if let Some(idol) = idol_opt {
+                let idol = Idol::from(idol);
+                let age_at_record = dates.get(&ip.record_id).and_then(|&date| idol.age_on(date));
+                rows.entry(ip.record_id)
+                    .or_default()
+                    .idols
+                    .push(IdolParticipation {
+                        idol,
+                        manual: ip.manual,
+                        age_at_record,
+                    });
+            }
+        }
+    }
+
+    if relations.links {
+        let link_models = ranked_links()
+            .filter(links::Column::RecordId.is_in(record_ids))
+            .all(db)
+            .await?;
+        for link_model in link_models {
+            let link = Link::from(link_model);
+            rows.entry(link.record_id.clone())
+                .or_default()
+                .links
+                .push(link);
+        }
+    }
+
+    Ok(rows)
+}
+
 /// Batch-load records with only basic fields + direct FK relations
 /// (director/studio/label/series), skipping genres/idols/links.
 pub(super) async fn load_records_slim<C: ConnectionTrait>(
@@ -363,6 +507,13 @@ pub(super) async fn load_records_slim<C: ConnectionTrait>(
             update_time: record_model.update_time,
             creator: record_model.creator,
             modified_by: record_model.modified_by,
+            version: record_model.version,
+            trailer: record_model.trailer,
+            cover_index: record_model.cover_index,
+            order_in_series: record_model.order_in_series,
+            rating_average: None,
+            rating_count: 0,
+            comment_count: 0,
         });
     }
 
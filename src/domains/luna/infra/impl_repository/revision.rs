@@ -0,0 +1,101 @@
+use super::record_loader::load_record_with_relations;
+use crate::domains::luna::{
+    domain::RevisionRepository,
+    dto::{RecordDto, RecordRevisionDto},
+};
+use crate::entities::{record, record_revisions, RecordEntity, RecordRevisionsEntity};
+use async_trait::async_trait;
+use chrono::Utc;
+use sea_orm::{
+    ActiveValue::Set, ColumnTrait as _, DatabaseConnection, DatabaseTransaction, DbErr,
+    EntityTrait as _, PaginatorTrait as _, QueryFilter as _, QueryOrder as _, QuerySelect as _,
+};
+
+fn to_dto(model: record_revisions::Model) -> Result<RecordRevisionDto, DbErr> {
+    // Snapshots were written from a `RecordDto`; keys added since then read
+    // back with their defaults.
+    let snapshot = serde_json::from_value(model.snapshot).map_err(|err| {
+        DbErr::Custom(format!(
+            "Record revision {} has an unreadable snapshot: {err}",
+            model.id
+        ))
+    })?;
+    Ok(RecordRevisionDto {
+        id: model.id,
+        record_id: model.record_id,
+        version: model.version,
+        actor: model.actor,
+        created_at: model.created_at,
+        snapshot,
+    })
+}
+
+pub struct RevisionRepo;
+
+#[async_trait]
+impl RevisionRepository for RevisionRepo {
+    async fn snapshot(
+        &self,
+        txn: &DatabaseTransaction,
+        record_id: &str,
+        actor: &str,
+    ) -> Result<bool, DbErr> {
+        let Some(model) = RecordEntity::find_by_id(record_id)
+            .filter(record::Column::DeletedAt.is_null())
+            .lock_exclusive()
+            .one(txn)
+            .await?
+        else {
+            return Ok(false);
+        };
+        let version = model.version;
+        let record = RecordDto::from(load_record_with_relations(txn, model).await?);
+        let snapshot = serde_json::to_value(&record)
+            .map_err(|err| DbErr::Custom(format!("Failed to encode record snapshot: {err}")))?;
+
+        let active = record_revisions::ActiveModel {
+            record_id: Set(record_id.to_owned()),
+            version: Set(version),
+            actor: Set(actor.to_owned()),
+            snapshot: Set(snapshot),
+            created_at: Set(Utc::now()),
+            ..Default::default()
+        };
+        RecordRevisionsEntity::insert(active).exec(txn).await?;
+        Ok(true)
+    }
+
+    async fn find_by_record_paginated(
+        &self,
+        db: &DatabaseConnection,
+        record_id: &str,
+        limit: u64,
+        offset: u64,
+    ) -> Result<(Vec<RecordRevisionDto>, u64), DbErr> {
+        let query =
+            RecordRevisionsEntity::find().filter(record_revisions::Column::RecordId.eq(record_id));
+        let total = query.clone().count(db).await?;
+        let rows = query
+            .order_by_desc(record_revisions::Column::Id)
+            .offset(offset)
+            .limit(limit)
+            .all(db)
+            .await?;
+        let revisions = rows.into_iter().map(to_dto).collect::<Result<_, _>>()?;
+        Ok((revisions, total))
+    }
+
+    async fn find_by_id(
+        &self,
+        db: &DatabaseConnection,
+        record_id: &str,
+        id: i64,
+    ) -> Result<Option<RecordRevisionDto>, DbErr> {
+        RecordRevisionsEntity::find_by_id(id)
+            .filter(record_revisions::Column::RecordId.eq(record_id))
+            .one(db)
+            .await?
+            .map(to_dto)
+            .transpose()
+    }
+}
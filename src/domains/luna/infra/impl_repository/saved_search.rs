@@ -0,0 +1,138 @@
+use crate::domains::luna::{
+    domain::SavedSearchRepository,
+    dto::{CreateSavedSearchDto, SavedSearchDto, SearchRecordDto},
+};
+use crate::entities::{saved_searches, SavedSearchesEntity};
+use async_trait::async_trait;
+use chrono::Utc;
+use sea_orm::{
+    ActiveValue::Set, ColumnTrait as _, DatabaseConnection, DbErr, EntityTrait as _,
+    PaginatorTrait as _, QueryFilter as _, QueryOrder as _, QuerySelect as _, Select,
+};
+
+fn filters_to_json(filters: &SearchRecordDto) -> Result<serde_json::Value, DbErr> {
+    serde_json::to_value(filters)
+        .map_err(|err| DbErr::Custom(format!("Failed to encode saved search filters: {err}")))
+}
+
+fn to_dto(model: saved_searches::Model) -> Result<SavedSearchDto, DbErr> {
+    // Filters were written from a `SearchRecordDto`; keys added since then
+    // read back as absent.
+    let filters = serde_json::from_value(model.filters).map_err(|err| {
+        DbErr::Custom(format!(
+            "Saved search {} has unreadable filters: {err}",
+            model.id
+        ))
+    })?;
+    Ok(SavedSearchDto {
+        id: model.id,
+        owner_id: model.user_id,
+        name: model.name,
+        filters,
+        ordering: model.ordering,
+        is_public: model.is_public,
+        created_at: model.created_at,
+        updated_at: model.updated_at,
+    })
+}
+
+/// One page of `query` and the total it pages through.
+async fn fetch_page(
+    db: &DatabaseConnection,
+    query: Select<SavedSearchesEntity>,
+    limit: u64,
+    offset: u64,
+) -> Result<(Vec<SavedSearchDto>, u64), DbErr> {
+    let total = query.clone().count(db).await?;
+    let rows = query.offset(offset).limit(limit).all(db).await?;
+    let searches = rows.into_iter().map(to_dto).collect::<Result<_, _>>()?;
+    Ok((searches, total))
+}
+
+pub struct SavedSearchRepo;
+
+#[async_trait]
+impl SavedSearchRepository for SavedSearchRepo {
+    async fn find_by_owner_paginated(
+        &self,
+        db: &DatabaseConnection,
+        user_id: &str,
+        limit: u64,
+        offset: u64,
+    ) -> Result<(Vec<SavedSearchDto>, u64), DbErr> {
+        let query = SavedSearchesEntity::find()
+            .filter(saved_searches::Column::UserId.eq(user_id))
+            .order_by_asc(saved_searches::Column::Name);
+        fetch_page(db, query, limit, offset).await
+    }
+
+    async fn find_public_paginated(
+        &self,
+        db: &DatabaseConnection,
+        limit: u64,
+        offset: u64,
+    ) -> Result<(Vec<SavedSearchDto>, u64), DbErr> {
+        let query = SavedSearchesEntity::find()
+            .filter(saved_searches::Column::IsPublic.eq(true))
+            .order_by_desc(saved_searches::Column::CreatedAt)
+            .order_by_desc(saved_searches::Column::Id);
+        fetch_page(db, query, limit, offset).await
+    }
+
+    async fn find_by_id(
+        &self,
+        db: &DatabaseConnection,
+        id: i64,
+    ) -> Result<Option<SavedSearchDto>, DbErr> {
+        SavedSearchesEntity::find_by_id(id)
+            .one(db)
+            .await?
+            .map(to_dto)
+            .transpose()
+    }
+
+    async fn create(
+        &self,
+        db: &DatabaseConnection,
+        user_id: &str,
+        dto: CreateSavedSearchDto,
+    ) -> Result<i64, DbErr> {
+        let now = Utc::now();
+        let active = saved_searches::ActiveModel {
+            user_id: Set(user_id.to_owned()),
+            name: Set(dto.name),
+            filters: Set(filters_to_json(&dto.filters)?),
+            ordering: Set(dto.ordering),
+            is_public: Set(dto.is_public),
+            created_at: Set(now),
+            updated_at: Set(now),
+            ..Default::default()
+        };
+        let inserted = SavedSearchesEntity::insert(active).exec(db).await?;
+        Ok(inserted.last_insert_id)
+    }
+
+    async fn update(
+        &self,
+        db: &DatabaseConnection,
+        id: i64,
+        dto: CreateSavedSearchDto,
+    ) -> Result<(), DbErr> {
+        let active = saved_searches::ActiveModel {
+            id: Set(id),
+            name: Set(dto.name),
+            filters: Set(filters_to_json(&dto.filters)?),
+            ordering: Set(dto.ordering),
+            is_public: Set(dto.is_public),
+            updated_at: Set(Utc::now()),
+            ..Default::default()
+        };
+        SavedSearchesEntity::update(active).exec(db).await?;
+        Ok(())
+    }
+
+    async fn delete(&self, db: &DatabaseConnection, id: i64) -> Result<(), DbErr> {
+        SavedSearchesEntity::delete_by_id(id).exec(db).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,317 @@
+use crate::domains::luna::{
+    domain::StatisticsRepository,
+    dto::{CatalogTotalsDto, DateGranularity, EntityCountDto, ExportEntity},
+};
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use sea_orm::{
+    prelude::Decimal, DatabaseBackend, DatabaseConnection, DbErr, FromQueryResult, Statement,
+};
+
+#[derive(FromQueryResult)]
+struct TotalsRow {
+    records: i64,
+    idols: i64,
+    directors: i64,
+    studios: i64,
+    links: i64,
+    local_images: i64,
+}
+
+/// Count of records in the date bucket starting at `period`.
+#[derive(FromQueryResult)]
+struct PeriodRow {
+    period: NaiveDate,
+    count: i64,
+}
+
+/// Count and combined size of links.
+#[derive(FromQueryResult)]
+struct LinkSizeRow {
+    count: i64,
+    size: Decimal,
+}
+
+/// Count and combined size of the links added in the month starting at
+/// `period`.
+#[derive(FromQueryResult)]
+struct LinkPeriodRow {
+    period: NaiveDate,
+    count: i64,
+    size: Decimal,
+}
+
+#[derive(FromQueryResult)]
+struct CountRow {
+    id: i64,
+    name: String,
+    count: i64,
+}
+
+#[derive(FromQueryResult)]
+struct ExistsRow {
+    found: bool,
+}
+
+#[derive(FromQueryResult)]
+struct AverageRow {
+    average: Option<f64>,
+}
+
+/// `FROM` clause reaching live records `r` from `entity`, and the column
+/// holding the entity ID.
+fn record_source(entity: ExportEntity) -> (&'static str, &'static str) {
+    match entity {
+        ExportEntity::Genres => (
+            "record_genre j JOIN record r ON r.id = j.record_id",
+            "j.genre_id",
+        ),
+        ExportEntity::Idols => (
+            "idol_participation j JOIN record r ON r.id = j.record_id",
+            "j.idol_id",
+        ),
+        ExportEntity::Directors => ("record r", "r.director_id"),
+        ExportEntity::Labels => ("record r", "r.label_id"),
+        ExportEntity::Studios => ("record r", "r.studio_id"),
+        ExportEntity::Series => ("record r", "r.series_id"),
+    }
+}
+
+/// Junction table linking records to `entity` and its entity column, for the
+/// entities a record can have several of.
+fn junction(entity: ExportEntity) -> Option<(&'static str, &'static str)> {
+    match entity {
+        ExportEntity::Genres => Some(("record_genre", "genre_id")),
+        ExportEntity::Idols => Some(("idol_participation", "idol_id")),
+        _ => None,
+    }
+}
+
+pub struct StatisticsRepo;
+
+#[async_trait]
+impl StatisticsRepository for StatisticsRepo {
+    async fn catalog_totals(&self, db: &DatabaseConnection) -> Result<CatalogTotalsDto, DbErr> {
+        let row = TotalsRow::find_by_statement(Statement::from_string(
+            DatabaseBackend::Postgres,
+            "SELECT \
+                (SELECT COUNT(*) FROM record WHERE deleted_at IS NULL) AS records, \
+                (SELECT COUNT(*) FROM idol WHERE id <> 0) AS idols, \
+                (SELECT COUNT(*) FROM director WHERE id <> 0) AS directors, \
+                (SELECT COUNT(*) FROM studio WHERE id <> 0) AS studios, \
+                (SELECT COUNT(*) FROM links l JOIN record r ON r.id = l.record_id \
+                    WHERE r.deleted_at IS NULL) AS links, \
+                (SELECT COALESCE(SUM(local_img_count), 0)::BIGINT FROM record \
+                    WHERE deleted_at IS NULL) AS local_images",
+        ))
+        .one(db)
+        .await?
+        .ok_or_else(|| DbErr::RecordNotFound("catalog totals".to_owned()))?;
+
+        Ok(CatalogTotalsDto {
+            records: row.records,
+            idols: row.idols,
+            directors: row.directors,
+            studios: row.studios,
+            links: row.links,
+            local_images: row.local_images,
+        })
+    }
+
+    async fn records_added_per_month(
+        &self,
+        db: &DatabaseConnection,
+        since: NaiveDate,
+    ) -> Result<Vec<(NaiveDate, i64)>, DbErr> {
+        let rows = PeriodRow::find_by_statement(Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            "SELECT date_trunc('month', create_time)::DATE AS period, COUNT(*) AS count \
+             FROM record \
+             WHERE deleted_at IS NULL AND create_time >= $1 \
+             GROUP BY 1 ORDER BY 1",
+            [since.into()],
+        ))
+        .all(db)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.period, row.count))
+            .collect())
+    }
+
+    async fn link_totals(&self, db: &DatabaseConnection) -> Result<(i64, Decimal), DbErr> {
+        let row = LinkSizeRow::find_by_statement(Statement::from_string(
+            DatabaseBackend::Postgres,
+            "SELECT COUNT(*) AS count, \
+                COALESCE(SUM(l.size) FILTER (WHERE l.size >= 0), 0) AS size \
+             FROM links l JOIN record r ON r.id = l.record_id \
+             WHERE r.deleted_at IS NULL",
+        ))
+        .one(db)
+        .await?
+        .ok_or_else(|| DbErr::RecordNotFound("link totals".to_owned()))?;
+        Ok((row.count, row.size))
+    }
+
+    async fn links_added_per_month(
+        &self,
+        db: &DatabaseConnection,
+        since: NaiveDate,
+    ) -> Result<Vec<(NaiveDate, i64, Decimal)>, DbErr> {
+        let rows = LinkPeriodRow::find_by_statement(Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            "SELECT date_trunc('month', l.create_time)::DATE AS period, COUNT(*) AS count, \
+                COALESCE(SUM(l.size) FILTER (WHERE l.size >= 0), 0) AS size \
+             FROM links l JOIN record r ON r.id = l.record_id \
+             WHERE r.deleted_at IS NULL AND l.create_time >= $1 \
+             GROUP BY 1 ORDER BY 1",
+            [since.into()],
+        ))
+        .all(db)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.period, row.count, row.size))
+            .collect())
+    }
+
+    async fn records_by_release_date(
+        &self,
+        db: &DatabaseConnection,
+        granularity: DateGranularity,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> Result<Vec<(NaiveDate, i64)>, DbErr> {
+        // NULL bounds leave that side of the range open.
+        let sql = format!(
+            "SELECT date_trunc('{field}', date)::DATE AS period, COUNT(*) AS count \
+             FROM record \
+             WHERE deleted_at IS NULL \
+               AND ($1::DATE IS NULL OR date >= $1) \
+               AND ($2::DATE IS NULL OR date <= $2) \
+             GROUP BY 1 ORDER BY 1",
+            field = granularity.trunc_field()
+        );
+        let rows = PeriodRow::find_by_statement(Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            &sql,
+            [from.into(), to.into()],
+        ))
+        .all(db)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.period, row.count))
+            .collect())
+    }
+
+    async fn top_entities(
+        &self,
+        db: &DatabaseConnection,
+        entity: ExportEntity,
+        limit: u64,
+    ) -> Result<Vec<EntityCountDto>, DbErr> {
+        // Table and column names come from fixed lists, never from user input.
+        let (source, entity_column) = record_source(entity);
+        let sql = format!(
+            "SELECT e.id, e.name, COUNT(*) AS count \
+             FROM {source} JOIN {table} e ON e.id = {entity_column} \
+             WHERE r.deleted_at IS NULL AND e.id <> 0 \
+             GROUP BY e.id, e.name \
+             ORDER BY count DESC, e.id \
+             LIMIT $1",
+            table = entity.table()
+        );
+        let rows = CountRow::find_by_statement(Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            &sql,
+            [(limit as i64).into()],
+        ))
+        .all(db)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| EntityCountDto {
+                id: row.id,
+                name: row.name,
+                count: row.count,
+            })
+            .collect())
+    }
+
+    async fn entity_exists(
+        &self,
+        db: &DatabaseConnection,
+        entity: ExportEntity,
+        id: i64,
+    ) -> Result<bool, DbErr> {
+        let sql = format!(
+            "SELECT EXISTS (SELECT 1 FROM {table} WHERE id = $1) AS found",
+            table = entity.table()
+        );
+        let row = ExistsRow::find_by_statement(Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            &sql,
+            [id.into()],
+        ))
+        .one(db)
+        .await?;
+        Ok(row.is_some_and(|row| row.found))
+    }
+
+    async fn co_occurring(
+        &self,
+        db: &DatabaseConnection,
+        entity: ExportEntity,
+        id: i64,
+        limit: u64,
+        max_permission: i32,
+    ) -> Result<Vec<EntityCountDto>, DbErr> {
+        let Some((junction, column)) = junction(entity) else {
+            return Err(DbErr::Custom(format!(
+                "{} have no junction table",
+                entity.name()
+            )));
+        };
+        // Table and column names come from fixed lists, never from user input.
+        let sql = format!(
+            "SELECT e.id, e.name, COUNT(*) AS count \
+             FROM {junction} a \
+             JOIN {junction} b ON b.record_id = a.record_id AND b.{column} <> a.{column} \
+             JOIN record r ON r.id = a.record_id \
+             JOIN {table} e ON e.id = b.{column} \
+             WHERE a.{column} = $1 AND r.deleted_at IS NULL AND r.permission <= $2 \
+                AND e.id <> 0 \
+             GROUP BY e.id, e.name \
+             ORDER BY count DESC, e.id \
+             LIMIT $3",
+            table = entity.table()
+        );
+        let rows = CountRow::find_by_statement(Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            &sql,
+            [id.into(), max_permission.into(), (limit as i64).into()],
+        ))
+        .all(db)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| EntityCountDto {
+                id: row.id,
+                name: row.name,
+                count: row.count,
+            })
+            .collect())
+    }
+
+    async fn average_duration(&self, db: &DatabaseConnection) -> Result<Option<f64>, DbErr> {
+        let row = AverageRow::find_by_statement(Statement::from_string(
+            DatabaseBackend::Postgres,
+            "SELECT AVG(duration)::DOUBLE PRECISION AS average \
+             FROM record WHERE deleted_at IS NULL AND duration > 0",
+        ))
+        .one(db)
+        .await?;
+        Ok(row.and_then(|row| row.average))
+    }
+}
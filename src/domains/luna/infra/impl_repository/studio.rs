@@ -1,16 +1,21 @@
+use super::entity_repo_macro::affinity_order_by;
 use crate::domains::luna::{
-    domain::{Studio, StudioAffinityRepository, StudioRepository},
+    domain::{
+        Label, Studio, StudioAffinityRepository, StudioHierarchyRepository, StudioRepository,
+    },
     dto::{
         CreateStudioDto, EntityCountDto, PaginatedResponse, PaginationQuery, SearchStudioDto,
-        UpdateStudioDto,
+        StudioRollupDto, UpdateStudioDto,
     },
 };
-use crate::entities::{record, studio, RecordEntity, StudioEntity};
+use crate::entities::{label, record, studio, LabelEntity, RecordEntity, StudioEntity};
 use async_trait::async_trait;
 use sea_orm::{
-    ActiveModelTrait as _, ColumnTrait as _, DatabaseBackend, DatabaseConnection, DbErr,
-    EntityTrait as _, FromQueryResult, PaginatorTrait as _, QueryFilter as _, Statement, Value,
+    ActiveModelTrait as _, ColumnTrait as _, ConnectionTrait as _, DatabaseBackend,
+    DatabaseConnection, DatabaseTransaction, DbErr, EntityTrait as _, FromQueryResult,
+    PaginatorTrait as _, QueryFilter as _, QueryOrder as _, Set, Statement, Value,
 };
+use std::collections::HashMap;
 
 impl_named_entity_repo!(
     paginated;
@@ -28,6 +33,11 @@ impl_named_entity_repo!(
     StudioId
 );
 
+impl_entity_merge_repo!(
+    record_fk;
+    StudioRepo, StudioEntity, StudioId
+);
+
 // Tunable hyper-parameters for the affinity score. These are fixed constants
 // (not user input), interpolated into the SQL as literals. Two main knobs:
 // `AFFINITY_M_V` controls how hard small samples are pulled toward the prior
@@ -63,6 +73,7 @@ struct AffinityStudioRow {
     name: String,
     link: String,
     manual: bool,
+    parent_id: Option<i64>,
 }
 
 impl From<AffinityStudioRow> for Studio {
@@ -72,6 +83,7 @@ impl From<AffinityStudioRow> for Studio {
             name: row.name,
             link: row.link,
             manual: row.manual,
+            parent_id: row.parent_id,
         }
     }
 }
@@ -161,7 +173,7 @@ impl StudioAffinityRepository for StudioRepo {
         // Studios relate to records via record.studio_id (foreign key), so the
         // aggregate groups records directly (no junction table).
         let select_sql = format!(
-            "SELECT t.id, t.name, t.link, t.manual, \
+            "SELECT t.id, t.name, t.link, t.manual, t.parent_id, \
              COALESCE( \
                CASE WHEN agg.total > 0 \
                     THEN ( {W_V} * ((agg.viewed::float8 + {M_V} * {C_V}) / (agg.total::float8 + {M_V})) \
@@ -179,10 +191,11 @@ impl StudioAffinityRepository for StudioRepo {
                FROM record r \
                LEFT JOIN user_record_interaction uri \
                       ON uri.record_id = r.id AND uri.user_id = $1 \
+               WHERE r.deleted_at IS NULL \
                GROUP BY r.studio_id \
              ) agg ON agg.entity_id = t.id\
              {where_clause} \
-             ORDER BY score DESC, t.id ASC \
+             ORDER BY {order_by} \
              LIMIT ${limit_param} OFFSET ${offset_param}",
             W_V = AFFINITY_W_V,
             W_L = AFFINITY_W_L,
@@ -195,6 +208,7 @@ impl StudioAffinityRepository for StudioRepo {
             N0 = AFFINITY_N0,
             limit_param = 2 + filter_binds.len(),
             offset_param = 3 + filter_binds.len(),
+            order_by = affinity_order_by(&pagination, "t"),
         );
 
         let mut select_binds: Vec<Value> = Vec::with_capacity(filter_binds.len() + 3);
@@ -222,9 +236,14 @@ impl StudioAffinityRepository for StudioRepo {
         let total_items = count_row.map_or(0, |r| r.cnt);
         let total_pages = (total_items as u64).div_ceil(page_size);
 
+        let ordering_param = pagination
+            .ordering
+            .as_deref()
+            .map(|o| format!("&ordering={o}"))
+            .unwrap_or_default();
         let next = if page_num + 1 < total_pages {
             Some(format!(
-                "?limit={page_size}&offset={}",
+                "?limit={page_size}&offset={}{ordering_param}",
                 (page_num + 1) * page_size
             ))
         } else {
@@ -232,7 +251,7 @@ impl StudioAffinityRepository for StudioRepo {
         };
         let previous = if page_num > 0 {
             Some(format!(
-                "?limit={page_size}&offset={}",
+                "?limit={page_size}&offset={}{ordering_param}",
                 (page_num - 1) * page_size
             ))
         } else {
@@ -243,6 +262,7 @@ impl StudioAffinityRepository for StudioRepo {
             count: total_items,
             next,
             previous,
+            next_cursor: None,
             results,
         })
     }
@@ -253,3 +273,286 @@ impl StudioAffinityRepository for StudioRepo {
 struct CountRow {
     cnt: i64,
 }
+
+/// Studio of a roll-up with its own and its subtree's live record counts.
+#[derive(Debug, FromQueryResult)]
+struct RollupStudioRow {
+    id: i64,
+    name: String,
+    parent_id: Option<i64>,
+    record_count: i64,
+    total_record_count: i64,
+}
+
+/// Label of a studio in a roll-up with its live record count.
+#[derive(Debug, FromQueryResult)]
+struct RollupLabelRow {
+    id: i64,
+    name: String,
+    studio_id: i64,
+    count: i64,
+}
+
+/// Studio `id` and every studio below it. `UNION` rather than `UNION ALL`,
+/// so a cycle ends the recursion instead of looping.
+const SUBTREE_CTE: &str = "WITH RECURSIVE subtree AS ( \
+       SELECT id FROM studio WHERE id = $1 \
+       UNION \
+       SELECT s.id FROM studio s JOIN subtree t ON s.parent_id = t.id \
+     )";
+
+/// Assembles the roll-up of studio `id` from the rows of its subtree;
+/// `None` when `id` is not among them.
+fn build_rollup(
+    id: i64,
+    studios: Vec<RollupStudioRow>,
+    labels: Vec<RollupLabelRow>,
+) -> Option<StudioRollupDto> {
+    let mut by_id: HashMap<i64, RollupStudioRow> = HashMap::new();
+    let mut children: HashMap<i64, Vec<i64>> = HashMap::new();
+    for row in studios {
+        if let Some(parent_id) = row.parent_id.filter(|_| row.id != id) {
+            children.entry(parent_id).or_default().push(row.id);
+        }
+        by_id.insert(row.id, row);
+    }
+    let mut labels_of: HashMap<i64, Vec<EntityCountDto>> = HashMap::new();
+    for row in labels {
+        labels_of
+            .entry(row.studio_id)
+            .or_default()
+            .push(EntityCountDto {
+                id: row.id,
+                name: row.name,
+                count: row.count,
+            });
+    }
+
+    // Rows are taken out of `by_id` as they are placed, so each studio
+    // appears once however the rows link up.
+    fn node(
+        id: i64,
+        by_id: &mut HashMap<i64, RollupStudioRow>,
+        children: &HashMap<i64, Vec<i64>>,
+        labels_of: &mut HashMap<i64, Vec<EntityCountDto>>,
+    ) -> Option<StudioRollupDto> {
+        let row = by_id.remove(&id)?;
+        let mut labels = labels_of.remove(&id).unwrap_or_default();
+        labels.sort_by(|a, b| a.name.cmp(&b.name).then(a.id.cmp(&b.id)));
+        let mut nested: Vec<StudioRollupDto> = children
+            .get(&id)
+            .into_iter()
+            .flatten()
+            .filter_map(|&child| node(child, by_id, children, labels_of))
+            .collect();
+        nested.sort_by(|a, b| a.name.cmp(&b.name).then(a.id.cmp(&b.id)));
+        Some(StudioRollupDto {
+            id: row.id,
+            name: row.name,
+            record_count: row.record_count,
+            total_record_count: row.total_record_count,
+            labels,
+            children: nested,
+        })
+    }
+    node(id, &mut by_id, &children, &mut labels_of)
+}
+
+#[async_trait]
+impl StudioHierarchyRepository for StudioRepo {
+    async fn find_lineage(&self, txn: &DatabaseTransaction, id: i64) -> Result<Vec<i64>, DbErr> {
+        #[derive(FromQueryResult)]
+        struct LineageRow {
+            id: i64,
+        }
+
+        txn.execute(Statement::from_string(
+            DatabaseBackend::Postgres,
+            "SELECT pg_advisory_xact_lock(hashtext('studio_hierarchy'))",
+        ))
+        .await?;
+        let rows = LineageRow::find_by_statement(Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            "WITH RECURSIVE lineage AS ( \
+               SELECT id, parent_id, 0 AS depth FROM studio WHERE id = $1 \
+               UNION ALL \
+               SELECT s.id, s.parent_id, l.depth + 1 \
+               FROM studio s JOIN lineage l ON s.id = l.parent_id \
+             ) \
+             SELECT id FROM lineage ORDER BY depth",
+            [id.into()],
+        ))
+        .all(txn)
+        .await?;
+        Ok(rows.into_iter().map(|row| row.id).collect())
+    }
+
+    async fn set_parent(
+        &self,
+        txn: &DatabaseTransaction,
+        id: i64,
+        parent_id: Option<i64>,
+    ) -> Result<Option<Studio>, DbErr> {
+        let Some(existing) = StudioEntity::find_by_id(id).one(txn).await? else {
+            return Ok(None);
+        };
+        let mut active_model: studio::ActiveModel = existing.into();
+        active_model.parent_id = Set(parent_id);
+        let updated = active_model.update(txn).await?;
+        Ok(Some(Studio::from(updated)))
+    }
+
+    async fn set_label_studio(
+        &self,
+        txn: &DatabaseTransaction,
+        id: i64,
+        studio_id: Option<i64>,
+    ) -> Result<Option<Label>, DbErr> {
+        let Some(existing) = LabelEntity::find_by_id(id).one(txn).await? else {
+            return Ok(None);
+        };
+        let mut active_model: label::ActiveModel = existing.into();
+        active_model.studio_id = Set(studio_id);
+        let updated = active_model.update(txn).await?;
+        Ok(Some(Label::from(updated)))
+    }
+
+    async fn find_children(
+        &self,
+        db: &DatabaseConnection,
+        id: i64,
+    ) -> Result<(Vec<Studio>, Vec<Label>), DbErr> {
+        let studios = StudioEntity::find()
+            .filter(studio::Column::ParentId.eq(id))
+            .order_by_asc(studio::Column::Name)
+            .order_by_asc(studio::Column::Id)
+            .all(db)
+            .await?;
+        let labels = LabelEntity::find()
+            .filter(label::Column::StudioId.eq(id))
+            .order_by_asc(label::Column::Name)
+            .order_by_asc(label::Column::Id)
+            .all(db)
+            .await?;
+        Ok((
+            studios.into_iter().map(Studio::from).collect(),
+            labels.into_iter().map(Label::from).collect(),
+        ))
+    }
+
+    async fn rollup(
+        &self,
+        db: &DatabaseConnection,
+        id: i64,
+    ) -> Result<Option<StudioRollupDto>, DbErr> {
+        // `closure` pairs every studio of the subtree with itself and each
+        // studio below it, so a studio's total counts the records of all of
+        // them and of their labels; `COUNT(*)` over `record` counts a record
+        // with both a studio and a label in the subtree once.
+        let studios_sql = format!(
+            "{SUBTREE_CTE}, closure AS ( \
+               SELECT id AS ancestor, id AS descendant FROM subtree \
+               UNION \
+               SELECT c.ancestor, s.id FROM closure c JOIN studio s ON s.parent_id = c.descendant \
+             ) \
+             SELECT s.id, s.name, s.parent_id, \
+               (SELECT COUNT(*) FROM record r \
+                WHERE r.studio_id = s.id AND r.deleted_at IS NULL) AS record_count, \
+               (SELECT COUNT(*) FROM record r \
+                WHERE r.deleted_at IS NULL \
+                  AND (r.studio_id IN (SELECT c.descendant FROM closure c WHERE c.ancestor = s.id) \
+                       OR r.label_id IN (SELECT l.id FROM label l \
+                                         JOIN closure c ON l.studio_id = c.descendant \
+                                         WHERE c.ancestor = s.id))) AS total_record_count \
+             FROM studio s JOIN subtree t ON t.id = s.id"
+        );
+        let studios = RollupStudioRow::find_by_statement(Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            &studios_sql,
+            [id.into()],
+        ))
+        .all(db)
+        .await?;
+        if studios.is_empty() {
+            return Ok(None);
+        }
+
+        let labels_sql = format!(
+            "{SUBTREE_CTE} \
+             SELECT l.id, l.name, l.studio_id, COUNT(r.id) AS count \
+             FROM label l JOIN subtree t ON l.studio_id = t.id \
+             LEFT JOIN record r ON r.label_id = l.id AND r.deleted_at IS NULL \
+             GROUP BY l.id, l.name, l.studio_id"
+        );
+        let labels = RollupLabelRow::find_by_statement(Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            &labels_sql,
+            [id.into()],
+        ))
+        .all(db)
+        .await?;
+
+        Ok(build_rollup(id, studios, labels))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build_rollup, RollupLabelRow, RollupStudioRow};
+
+    fn studio(id: i64, name: &str, parent_id: Option<i64>, records: i64) -> RollupStudioRow {
+        RollupStudioRow {
+            id,
+            name: name.to_owned(),
+            parent_id,
+            record_count: records,
+            total_record_count: records,
+        }
+    }
+
+    #[test]
+    fn rollup_nests_studios_and_labels_by_name() {
+        let studios = vec![
+            studio(1, "Group", Some(9), 1),
+            studio(3, "Beta", Some(1), 2),
+            studio(2, "Alpha", Some(1), 3),
+            studio(4, "Alpha Sub", Some(2), 4),
+        ];
+        let labels = vec![
+            RollupLabelRow {
+                id: 11,
+                name: "Zeta Label".to_owned(),
+                studio_id: 2,
+                count: 5,
+            },
+            RollupLabelRow {
+                id: 10,
+                name: "Eta Label".to_owned(),
+                studio_id: 2,
+                count: 6,
+            },
+        ];
+
+        let rollup = build_rollup(1, studios, labels).expect("root is present");
+        assert_eq!(rollup.id, 1);
+        assert!(rollup.labels.is_empty());
+        let names: Vec<&str> = rollup.children.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["Alpha", "Beta"]);
+        let alpha = &rollup.children[0];
+        let labels: Vec<i64> = alpha.labels.iter().map(|l| l.id).collect();
+        assert_eq!(labels, [10, 11]);
+        assert_eq!(alpha.children.len(), 1);
+        assert_eq!(alpha.children[0].id, 4);
+        assert!(rollup.children[1].children.is_empty());
+    }
+
+    #[test]
+    fn rollup_survives_a_cycle_and_a_missing_root() {
+        let studios = vec![studio(1, "One", Some(2), 0), studio(2, "Two", Some(1), 0)];
+        let rollup = build_rollup(1, studios, vec![]).expect("root is present");
+        assert_eq!(rollup.children.len(), 1);
+        assert!(rollup.children[0].children.is_empty());
+
+        assert!(build_rollup(5, vec![studio(1, "One", None, 0)], vec![]).is_none());
+    }
+}
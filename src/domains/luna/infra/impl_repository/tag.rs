@@ -0,0 +1,234 @@
+use crate::domains::luna::{
+    domain::TagRepository,
+    dto::{CreateTagDto, SearchTagDto, TagCategoryDto, TagCountDto, TagDto},
+};
+use crate::entities::{
+    record_tag, tag, tag_category, RecordTagEntity, TagCategoryEntity, TagEntity,
+};
+use async_trait::async_trait;
+use chrono::Utc;
+use sea_orm::{
+    sea_query::OnConflict, ActiveValue::Set, ColumnTrait as _, DatabaseBackend, DatabaseConnection,
+    DbErr, EntityTrait as _, FromQueryResult, QueryFilter as _, QueryOrder as _, QuerySelect as _,
+    Statement,
+};
+
+use super::record::escape_like_pattern;
+
+#[derive(FromQueryResult)]
+struct TagCountRow {
+    id: i64,
+    name: String,
+    category_id: i64,
+    count: i64,
+}
+
+fn category_to_dto(category: tag_category::Model) -> TagCategoryDto {
+    TagCategoryDto {
+        id: category.id,
+        name: category.name,
+        description: category.description,
+    }
+}
+
+/// Pair a tag row with its category. Categories are never missing, as the
+/// foreign key rejects tags pointing elsewhere.
+fn to_dto((tag, category): (tag::Model, Option<tag_category::Model>)) -> TagDto {
+    let category = category.map(category_to_dto).unwrap_or(TagCategoryDto {
+        id: tag.category_id,
+        name: String::new(),
+        description: None,
+    });
+    TagDto {
+        id: tag.id,
+        name: tag.name,
+        description: tag.description,
+        category,
+    }
+}
+
+pub struct TagRepo;
+
+#[async_trait]
+impl TagRepository for TagRepo {
+    async fn find_all(
+        &self,
+        db: &DatabaseConnection,
+        search: SearchTagDto,
+    ) -> Result<Vec<TagDto>, DbErr> {
+        let mut query = TagEntity::find();
+        if let Some(category_id) = search.category_id {
+            query = query.filter(tag::Column::CategoryId.eq(category_id));
+        }
+        if let Some(name) = search.name.filter(|name| !name.is_empty()) {
+            query = query.filter(tag::Column::Name.contains(escape_like_pattern(&name)));
+        }
+        let rows = query
+            .find_also_related(TagCategoryEntity)
+            .order_by_asc(tag::Column::CategoryId)
+            .order_by_asc(tag::Column::Name)
+            .all(db)
+            .await?;
+        Ok(rows.into_iter().map(to_dto).collect())
+    }
+
+    async fn find_by_id(&self, db: &DatabaseConnection, id: i64) -> Result<Option<TagDto>, DbErr> {
+        let row = TagEntity::find_by_id(id)
+            .find_also_related(TagCategoryEntity)
+            .one(db)
+            .await?;
+        Ok(row.map(to_dto))
+    }
+
+    async fn find_categories(&self, db: &DatabaseConnection) -> Result<Vec<TagCategoryDto>, DbErr> {
+        let rows = TagCategoryEntity::find()
+            .order_by_asc(tag_category::Column::Id)
+            .all(db)
+            .await?;
+        Ok(rows.into_iter().map(category_to_dto).collect())
+    }
+
+    async fn category_exists(&self, db: &DatabaseConnection, id: i64) -> Result<bool, DbErr> {
+        Ok(TagCategoryEntity::find_by_id(id).one(db).await?.is_some())
+    }
+
+    async fn find_existing_ids(
+        &self,
+        db: &DatabaseConnection,
+        ids: &[i64],
+    ) -> Result<Vec<i64>, DbErr> {
+        TagEntity::find()
+            .select_only()
+            .column(tag::Column::Id)
+            .filter(tag::Column::Id.is_in(ids.iter().copied()))
+            .into_tuple()
+            .all(db)
+            .await
+    }
+
+    async fn create(&self, db: &DatabaseConnection, dto: CreateTagDto) -> Result<i64, DbErr> {
+        let active = tag::ActiveModel {
+            category_id: Set(dto.category_id),
+            name: Set(dto.name),
+            description: Set(dto.description),
+            create_time: Set(Utc::now().date_naive()),
+            ..Default::default()
+        };
+        let result = TagEntity::insert(active).exec(db).await?;
+        Ok(result.last_insert_id)
+    }
+
+    async fn update(
+        &self,
+        db: &DatabaseConnection,
+        id: i64,
+        dto: CreateTagDto,
+    ) -> Result<bool, DbErr> {
+        let result = TagEntity::update_many()
+            .set(tag::ActiveModel {
+                category_id: Set(dto.category_id),
+                name: Set(dto.name),
+                description: Set(dto.description),
+                ..Default::default()
+            })
+            .filter(tag::Column::Id.eq(id))
+            .exec(db)
+            .await?;
+        Ok(result.rows_affected > 0)
+    }
+
+    async fn delete(&self, db: &DatabaseConnection, id: i64) -> Result<bool, DbErr> {
+        // `record_tag` rows go with the tag through the cascading foreign key.
+        let result = TagEntity::delete_by_id(id).exec(db).await?;
+        Ok(result.rows_affected > 0)
+    }
+
+    async fn find_by_record(
+        &self,
+        db: &DatabaseConnection,
+        record_id: &str,
+    ) -> Result<Vec<TagDto>, DbErr> {
+        let rows = TagEntity::find()
+            .inner_join(RecordTagEntity)
+            .filter(record_tag::Column::RecordId.eq(record_id))
+            .find_also_related(TagCategoryEntity)
+            .order_by_asc(tag::Column::CategoryId)
+            .order_by_asc(tag::Column::Name)
+            .all(db)
+            .await?;
+        Ok(rows.into_iter().map(to_dto).collect())
+    }
+
+    async fn attach(
+        &self,
+        db: &DatabaseConnection,
+        record_id: &str,
+        tag_ids: &[i64],
+    ) -> Result<(), DbErr> {
+        let today = Utc::now().date_naive();
+        let rows = tag_ids.iter().map(|&tag_id| record_tag::ActiveModel {
+            record_id: Set(record_id.to_owned()),
+            tag_id: Set(tag_id),
+            manual: Set(true),
+            create_time: Set(today),
+            ..Default::default()
+        });
+        let on_conflict =
+            OnConflict::columns([record_tag::Column::RecordId, record_tag::Column::TagId])
+                .do_nothing()
+                .to_owned();
+
+        RecordTagEntity::insert_many(rows)
+            .on_conflict(on_conflict)
+            .exec_without_returning(db)
+            .await?;
+        Ok(())
+    }
+
+    async fn detach(
+        &self,
+        db: &DatabaseConnection,
+        record_id: &str,
+        tag_id: i64,
+    ) -> Result<bool, DbErr> {
+        let result = RecordTagEntity::delete_many()
+            .filter(record_tag::Column::RecordId.eq(record_id))
+            .filter(record_tag::Column::TagId.eq(tag_id))
+            .exec(db)
+            .await?;
+        Ok(result.rows_affected > 0)
+    }
+
+    async fn usage_counts(
+        &self,
+        db: &DatabaseConnection,
+        limit: u64,
+        max_permission: i32,
+    ) -> Result<Vec<TagCountDto>, DbErr> {
+        // The record filter sits in the join so tags on no visible record
+        // still come back, counted as 0.
+        let rows = TagCountRow::find_by_statement(Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            "SELECT t.id, t.name, t.category_id, COUNT(r.id) AS count \
+             FROM tag t \
+             LEFT JOIN record_tag j ON j.tag_id = t.id \
+             LEFT JOIN record r ON r.id = j.record_id \
+                AND r.deleted_at IS NULL AND r.permission <= $1 \
+             GROUP BY t.id, t.name, t.category_id \
+             ORDER BY count DESC, t.id \
+             LIMIT $2",
+            [max_permission.into(), (limit as i64).into()],
+        ))
+        .all(db)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| TagCountDto {
+                id: row.id,
+                name: row.name,
+                category_id: row.category_id,
+                count: row.count,
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,152 @@
+use crate::domains::luna::{
+    domain::TranslationRepository,
+    dto::{Language, TranslatedEntity, TranslatedNames, TranslationDto},
+};
+use crate::entities::{entity_translation, EntityTranslationEntity};
+use async_trait::async_trait;
+use sea_orm::{
+    sea_query::OnConflict, ActiveValue::Set, ColumnTrait as _, Condition, DatabaseBackend,
+    DatabaseConnection, DbErr, EntityTrait as _, FromQueryResult, QueryFilter as _,
+    QueryOrder as _, Statement,
+};
+use std::collections::HashMap;
+
+#[derive(FromQueryResult)]
+struct ExistsRow {
+    exists: bool,
+}
+
+pub struct TranslationRepo;
+
+#[async_trait]
+impl TranslationRepository for TranslationRepo {
+    async fn find_names(
+        &self,
+        db: &DatabaseConnection,
+        language: Language,
+        keys: &[(TranslatedEntity, i64)],
+    ) -> Result<TranslatedNames, DbErr> {
+        let mut ids: HashMap<TranslatedEntity, Vec<i64>> = HashMap::new();
+        for &(entity, id) in keys {
+            ids.entry(entity).or_default().push(id);
+        }
+        if ids.is_empty() {
+            return Ok(TranslatedNames::new());
+        }
+
+        let entities = ids
+            .iter()
+            .fold(Condition::any(), |condition, (entity, ids)| {
+                condition.add(
+                    Condition::all()
+                        .add(entity_translation::Column::EntityType.eq(entity.as_str()))
+                        .add(entity_translation::Column::EntityId.is_in(ids.iter().copied())),
+                )
+            });
+        let rows = EntityTranslationEntity::find()
+            .filter(entity_translation::Column::Language.eq(language.as_str()))
+            .filter(entities)
+            .all(db)
+            .await?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| {
+                let entity = ids
+                    .keys()
+                    .copied()
+                    .find(|entity| entity.as_str() == row.entity_type)?;
+                Some(((entity, row.entity_id), row.name))
+            })
+            .collect())
+    }
+
+    async fn find_by_entity(
+        &self,
+        db: &DatabaseConnection,
+        entity: TranslatedEntity,
+        id: i64,
+    ) -> Result<Vec<TranslationDto>, DbErr> {
+        let rows = EntityTranslationEntity::find()
+            .filter(entity_translation::Column::EntityType.eq(entity.as_str()))
+            .filter(entity_translation::Column::EntityId.eq(id))
+            .order_by_asc(entity_translation::Column::Language)
+            .all(db)
+            .await?;
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| {
+                Some(TranslationDto {
+                    language: Language::from_tag(&row.language)?,
+                    name: row.name,
+                })
+            })
+            .collect())
+    }
+
+    async fn upsert(
+        &self,
+        db: &DatabaseConnection,
+        entity: TranslatedEntity,
+        id: i64,
+        language: Language,
+        name: &str,
+    ) -> Result<(), DbErr> {
+        let active = entity_translation::ActiveModel {
+            entity_type: Set(entity.as_str().to_owned()),
+            entity_id: Set(id),
+            language: Set(language.as_str().to_owned()),
+            name: Set(name.to_owned()),
+            ..Default::default()
+        };
+        let on_conflict = OnConflict::columns([
+            entity_translation::Column::EntityType,
+            entity_translation::Column::EntityId,
+            entity_translation::Column::Language,
+        ])
+        .update_column(entity_translation::Column::Name)
+        .to_owned();
+
+        EntityTranslationEntity::insert(active)
+            .on_conflict(on_conflict)
+            .exec_without_returning(db)
+            .await?;
+        Ok(())
+    }
+
+    async fn delete(
+        &self,
+        db: &DatabaseConnection,
+        entity: TranslatedEntity,
+        id: i64,
+        language: Language,
+    ) -> Result<bool, DbErr> {
+        let result = EntityTranslationEntity::delete_many()
+            .filter(entity_translation::Column::EntityType.eq(entity.as_str()))
+            .filter(entity_translation::Column::EntityId.eq(id))
+            .filter(entity_translation::Column::Language.eq(language.as_str()))
+            .exec(db)
+            .await?;
+        Ok(result.rows_affected > 0)
+    }
+
+    async fn entity_exists(
+        &self,
+        db: &DatabaseConnection,
+        entity: TranslatedEntity,
+        id: i64,
+    ) -> Result<bool, DbErr> {
+        // The entity type names its table, so it is safe to interpolate.
+        let row = ExistsRow::find_by_statement(Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            format!(
+                "SELECT EXISTS (SELECT 1 FROM {} WHERE id = $1) AS exists",
+                entity.as_str()
+            ),
+            [id.into()],
+        ))
+        .one(db)
+        .await?;
+        Ok(row.is_some_and(|row| row.exists))
+    }
+}
@@ -1,20 +1,38 @@
-use crate::common::config::Config;
+use crate::common::{cache::CacheService, config::Config};
 use crate::domains::luna::domain::{
-    DirectorServiceTrait, FileServiceTrait, GenreServiceTrait, IdolServiceTrait, LabelServiceTrait,
-    LunaServiceTrait, RecordServiceTrait, SeriesServiceTrait, StudioServiceTrait,
+    CommentServiceTrait, DirectorServiceTrait, DuplicateServiceTrait, ExportServiceTrait,
+    FileServiceTrait, GenreServiceTrait, IdolServiceTrait, IntegrityServiceTrait,
+    LabelServiceTrait, LinkServiceTrait, LunaServiceTrait, RecordServiceTrait,
+    SavedSearchServiceTrait, SeriesServiceTrait, StatisticsServiceTrait, StudioServiceTrait,
+    TagServiceTrait, TranslationServiceTrait,
 };
+use crate::domains::luna::infra::catalog_cache::CatalogCache;
+use crate::domains::luna::infra::catalog_events::{CatalogEvents, CATALOG_EVENT_BACKLOG};
 use async_trait::async_trait;
 use sea_orm::DatabaseConnection;
 use std::sync::Arc;
 
+mod archive;
+mod comment;
 mod director;
+mod duplicate;
+mod export;
 pub mod file;
 mod genre;
 mod idol;
+mod integrity;
 mod label;
+mod link;
+mod merge;
 mod record;
+mod saved_search;
 mod series;
+mod statistics;
 mod studio;
+mod tag;
+#[cfg(feature = "thumbnails")]
+mod thumbnail;
+mod translation;
 
 /// Combined Luna service that includes all domain services.
 #[derive(Clone)]
@@ -27,21 +45,85 @@ pub struct LunaService {
     pub idol_service: Arc<dyn IdolServiceTrait>,
     pub record_service: Arc<dyn RecordServiceTrait>,
     pub file_service: Arc<dyn FileServiceTrait>,
+    pub export_service: Arc<dyn ExportServiceTrait>,
+    pub statistics_service: Arc<dyn StatisticsServiceTrait>,
+    pub comment_service: Arc<dyn CommentServiceTrait>,
+    pub tag_service: Arc<dyn TagServiceTrait>,
+    pub saved_search_service: Arc<dyn SavedSearchServiceTrait>,
+    pub duplicate_service: Arc<dyn DuplicateServiceTrait>,
+    pub integrity_service: Arc<dyn IntegrityServiceTrait>,
+    pub link_service: Arc<dyn LinkServiceTrait>,
+    pub translation_service: Arc<dyn TranslationServiceTrait>,
+    pub catalog_events: Arc<CatalogEvents>,
 }
 
 #[async_trait]
 impl LunaServiceTrait for LunaService {
     /// Constructor for the service.
     fn create_service(config: Config, db: DatabaseConnection) -> Arc<dyn LunaServiceTrait> {
+        let events = Arc::new(CatalogEvents::new(CATALOG_EVENT_BACKLOG));
+        let cache = Arc::new(CatalogCache::new(CacheService::new(&config)));
         Arc::new(Self {
-            director_service: director::DirectorService::create_service(db.clone()),
-            genre_service: genre::GenreService::create_service(db.clone()),
-            label_service: label::LabelService::create_service(db.clone()),
-            studio_service: studio::StudioService::create_service(db.clone()),
-            series_service: series::SeriesService::create_service(db.clone()),
-            idol_service: idol::IdolService::create_service(db.clone(), config.clone()),
-            record_service: record::RecordService::create_service(db),
-            file_service: Arc::new(file::FileService::new(config)),
+            director_service: director::DirectorService::create_service(
+                db.clone(),
+                Arc::clone(&events),
+                Arc::clone(&cache),
+            ),
+            genre_service: genre::GenreService::create_service(
+                db.clone(),
+                Arc::clone(&events),
+                Arc::clone(&cache),
+            ),
+            label_service: label::LabelService::create_service(
+                db.clone(),
+                Arc::clone(&events),
+                Arc::clone(&cache),
+            ),
+            studio_service: studio::StudioService::create_service(
+                db.clone(),
+                Arc::clone(&events),
+                Arc::clone(&cache),
+            ),
+            series_service: series::SeriesService::create_service(
+                db.clone(),
+                Arc::clone(&events),
+                Arc::clone(&cache),
+            ),
+            idol_service: idol::IdolService::create_service(
+                db.clone(),
+                config.clone(),
+                Arc::clone(&events),
+                Arc::clone(&cache),
+            ),
+            record_service: record::RecordService::create_service(
+                db.clone(),
+                config.clone(),
+                Arc::clone(&events),
+                Arc::clone(&cache),
+            ),
+            statistics_service: statistics::StatisticsService::create_service(
+                db.clone(),
+                Arc::clone(&cache),
+            ),
+            comment_service: comment::CommentService::create_service(db.clone()),
+            tag_service: tag::TagService::create_service(db.clone()),
+            saved_search_service: saved_search::SavedSearchService::create_service(db.clone()),
+            duplicate_service: duplicate::DuplicateService::create_service(db.clone()),
+            integrity_service: integrity::IntegrityService::create_service(
+                db.clone(),
+                config.clone(),
+                Arc::clone(&cache),
+            ),
+            link_service: link::LinkService::create_service(
+                db.clone(),
+                &config,
+                Arc::clone(&events),
+                Arc::clone(&cache),
+            ),
+            translation_service: translation::TranslationService::create_service(db.clone()),
+            export_service: export::ExportService::create_service(db.clone()),
+            file_service: Arc::new(file::FileService::new(config, db)),
+            catalog_events: events,
         })
     }
 
@@ -84,4 +166,54 @@ impl LunaServiceTrait for LunaService {
     fn file_service(&self) -> &dyn FileServiceTrait {
         &*self.file_service
     }
+
+    /// Get export service
+    fn export_service(&self) -> &dyn ExportServiceTrait {
+        &*self.export_service
+    }
+
+    /// Get statistics service
+    fn statistics_service(&self) -> &dyn StatisticsServiceTrait {
+        &*self.statistics_service
+    }
+
+    /// Get comment service
+    fn comment_service(&self) -> &dyn CommentServiceTrait {
+        &*self.comment_service
+    }
+
+    /// Get tag service
+    fn tag_service(&self) -> &dyn TagServiceTrait {
+        &*self.tag_service
+    }
+
+    /// Get saved search service
+    fn saved_search_service(&self) -> &dyn SavedSearchServiceTrait {
+        &*self.saved_search_service
+    }
+
+    /// Get duplicate report service
+    fn duplicate_service(&self) -> &dyn DuplicateServiceTrait {
+        &*self.duplicate_service
+    }
+
+    /// Get data-quality report service
+    fn integrity_service(&self) -> &dyn IntegrityServiceTrait {
+        &*self.integrity_service
+    }
+
+    /// Get link health service
+    fn link_service(&self) -> &dyn LinkServiceTrait {
+        &*self.link_service
+    }
+
+    /// Get entity name translation service
+    fn translation_service(&self) -> &dyn TranslationServiceTrait {
+        &*self.translation_service
+    }
+
+    /// Get the catalog change event channel
+    fn catalog_events(&self) -> &CatalogEvents {
+        &self.catalog_events
+    }
 }
@@ -0,0 +1,62 @@
+//! ZIP archives of a media directory, written while they are sent.
+//!
+//! Files are read from the storage backend in fixed-size chunks and written
+//! as stored (uncompressed) entries, since images are compressed already.
+//! The archive goes through an in-memory pipe of bounded size, so memory use
+//! does not grow with the number or size of the files.
+
+use crate::common::range::ByteRange;
+use crate::domains::luna::infra::media_storage::MediaStorage;
+use async_zip::{base::write::ZipFileWriter, Compression, ZipEntryBuilder};
+use futures::AsyncWriteExt as _;
+use std::io;
+use std::sync::Arc;
+use tokio::io::DuplexStream;
+
+/// Bytes read from storage at a time.
+const CHUNK_SIZE: u64 = 256 * 1024;
+
+/// Capacity of the pipe between the archive writer and the response body.
+const PIPE_CAPACITY: usize = 64 * 1024;
+
+/// Starts writing a ZIP of `files`, each a `(name in the archive, key, len)`,
+/// in the background and returns the end the archive is read from. A failure
+/// midway is logged and ends the archive early.
+pub(super) fn zip_media(
+    storage: Arc<dyn MediaStorage>,
+    files: Vec<(String, String, u64)>,
+) -> DuplexStream {
+    let (reader, writer) = tokio::io::duplex(PIPE_CAPACITY);
+    tokio::spawn(async move {
+        if let Err(err) = write_zip(&*storage, files, writer).await {
+            tracing::warn!("Failed to write media archive: {err}");
+        }
+    });
+    reader
+}
+
+async fn write_zip(
+    storage: &dyn MediaStorage,
+    files: Vec<(String, String, u64)>,
+    writer: DuplexStream,
+) -> io::Result<()> {
+    let mut zip = ZipFileWriter::with_tokio(writer);
+    for (name, key, len) in files {
+        let entry = ZipEntryBuilder::new(name.into(), Compression::Stored);
+        let mut entry_writer = zip.write_entry_stream(entry).await.map_err(zip_error)?;
+        let mut start = 0;
+        while start < len {
+            let end = (start + CHUNK_SIZE).min(len) - 1;
+            let chunk = storage.read(&key, Some(ByteRange { start, end })).await?;
+            entry_writer.write_all(&chunk).await?;
+            start = end + 1;
+        }
+        entry_writer.close().await.map_err(zip_error)?;
+    }
+    zip.close().await.map_err(zip_error)?;
+    Ok(())
+}
+
+fn zip_error(err: async_zip::error::ZipError) -> io::Error {
+    io::Error::other(err)
+}
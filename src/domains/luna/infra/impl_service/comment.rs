@@ -0,0 +1,126 @@
+use crate::{
+    common::{config::DEFAULT_PAGE_SIZE, error::AppError},
+    domains::luna::{
+        domain::{CommentRepository, CommentServiceTrait},
+        dto::{CommentDto, CreateCommentDto, PaginatedResponse, PaginationQuery, UpdateCommentDto},
+        infra::CommentRepo,
+    },
+};
+use async_trait::async_trait;
+use sea_orm::DatabaseConnection;
+use std::sync::Arc;
+
+/// Service struct for record comments.
+#[derive(Clone)]
+pub struct CommentService {
+    db: DatabaseConnection,
+    repo: Arc<dyn CommentRepository>,
+}
+
+impl CommentService {
+    pub fn create_service(db: DatabaseConnection) -> Arc<dyn CommentServiceTrait> {
+        Arc::new(Self {
+            db,
+            repo: Arc::new(CommentRepo),
+        })
+    }
+
+    /// Comment `id` on `record_id`.
+    async fn load_comment(&self, record_id: &str, id: i64) -> Result<CommentDto, AppError> {
+        self.repo
+            .find_by_id(&self.db, record_id, id)
+            .await
+            .map_err(AppError::DatabaseError)?
+            .ok_or_else(|| AppError::NotFound("Comment not found".into()))
+    }
+
+    /// Reject edits by anyone but the author of comment `id`.
+    async fn ensure_author(&self, record_id: &str, id: i64, user_id: &str) -> Result<(), AppError> {
+        let comment = self.load_comment(record_id, id).await?;
+        if comment.author.id != user_id {
+            return Err(AppError::Forbidden);
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl CommentServiceTrait for CommentService {
+    async fn list_comments(
+        &self,
+        record_id: &str,
+        pagination: PaginationQuery,
+    ) -> Result<PaginatedResponse<CommentDto>, AppError> {
+        let page_size = pagination
+            .limit
+            .filter(|&l| l > 0)
+            .map_or(DEFAULT_PAGE_SIZE, |l| l as u64);
+        let current_offset = pagination.offset.unwrap_or(0).max(0) as u64;
+
+        let (comments, total) = self
+            .repo
+            .find_by_record_paginated(&self.db, record_id, page_size, current_offset)
+            .await
+            .map_err(AppError::DatabaseError)?;
+
+        let next_offset = current_offset + page_size;
+        let next =
+            (next_offset < total).then(|| format!("?limit={page_size}&offset={next_offset}"));
+        let previous = (current_offset > 0).then(|| {
+            format!(
+                "?limit={page_size}&offset={}",
+                current_offset.saturating_sub(page_size)
+            )
+        });
+
+        Ok(PaginatedResponse {
+            count: total as i64,
+            next,
+            previous,
+            next_cursor: None,
+            results: comments,
+        })
+    }
+
+    async fn create_comment(
+        &self,
+        record_id: &str,
+        user_id: &str,
+        dto: CreateCommentDto,
+    ) -> Result<CommentDto, AppError> {
+        let id = self
+            .repo
+            .create(&self.db, record_id, user_id, &dto.body)
+            .await
+            .map_err(AppError::DatabaseError)?;
+        self.load_comment(record_id, id).await
+    }
+
+    async fn update_comment(
+        &self,
+        record_id: &str,
+        id: i64,
+        user_id: &str,
+        dto: UpdateCommentDto,
+    ) -> Result<CommentDto, AppError> {
+        self.ensure_author(record_id, id, user_id).await?;
+        self.repo
+            .update_body(&self.db, id, &dto.body)
+            .await
+            .map_err(AppError::DatabaseError)?;
+        self.load_comment(record_id, id).await
+    }
+
+    async fn delete_comment(
+        &self,
+        record_id: &str,
+        id: i64,
+        user_id: &str,
+    ) -> Result<(), AppError> {
+        self.ensure_author(record_id, id, user_id).await?;
+        self.repo
+            .delete(&self.db, id)
+            .await
+            .map_err(AppError::DatabaseError)
+    }
+}
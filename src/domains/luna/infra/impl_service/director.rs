@@ -1,13 +1,20 @@
+use super::merge::merge_named_entity;
 use crate::domains::search::SearchEntityType;
 use crate::{
-    common::error::AppError,
+    common::{error::AppError, pagination::resolve_ordering},
     domains::luna::{
-        domain::{DirectorAffinityRepository, DirectorRepository, DirectorServiceTrait},
+        domain::{
+            DirectorAffinityRepository, DirectorRepository, DirectorServiceTrait,
+            NamedEntityMergeRepository,
+        },
         dto::{
-            CreateDirectorDto, DirectorDto, EntityCountDto, PaginatedResponse, PaginationQuery,
-            SearchDirectorDto, UpdateDirectorDto,
+            AutocompleteQuery, CatalogAction, CreateDirectorDto, DirectorDto, EntityCountDto,
+            MergeEntityResponse, NameSuggestionDto, PaginatedResponse, PaginationQuery,
+            RecordCountQuery, SearchDirectorDto, UpdateDirectorDto, ENTITY_ORDERING_FIELDS,
+        },
+        infra::{
+            catalog_cache::CatalogCache, catalog_events::CatalogEvents, search_outbox, DirectorRepo,
         },
-        infra::{search_outbox, DirectorRepo},
     },
 };
 use async_trait::async_trait;
@@ -23,25 +30,67 @@ pub struct DirectorService {
     /// object cannot expose `DirectorAffinityRepository` methods, so the
     /// affinity trait needs its own trait object (both wrap `DirectorRepo`).
     affinity_repo: Arc<dyn DirectorAffinityRepository + Send + Sync>,
+    /// Merge handle; also wraps `DirectorRepo`.
+    merge_repo: Arc<dyn NamedEntityMergeRepository + Send + Sync>,
+    events: Arc<CatalogEvents>,
+    cache: Arc<CatalogCache>,
 }
 
 #[async_trait]
 impl DirectorServiceTrait for DirectorService {
-    fn create_service(db: DatabaseConnection) -> Arc<dyn DirectorServiceTrait> {
+    fn create_service(
+        db: DatabaseConnection,
+        events: Arc<CatalogEvents>,
+        cache: Arc<CatalogCache>,
+    ) -> Arc<dyn DirectorServiceTrait> {
         Arc::new(Self {
             db,
             repo: Arc::new(DirectorRepo {}),
             affinity_repo: Arc::new(DirectorRepo {}),
+            merge_repo: Arc::new(DirectorRepo {}),
+            events,
+            cache,
         })
     }
 
     async fn get_director_by_id(&self, id: i64) -> Result<DirectorDto, AppError> {
-        self.repo
-            .find_by_id(&self.db, id)
+        self.cache
+            .entity(SearchEntityType::Director, id, || async {
+                self.repo
+                    .find_by_id(&self.db, id)
+                    .await
+                    .map_err(AppError::DatabaseError)?
+                    .map(DirectorDto::from)
+                    .ok_or_else(|| AppError::NotFound("Director not found".into()))
+            })
+            .await
+    }
+
+    async fn get_directors_by_ids(&self, ids: &[i64]) -> Result<Vec<DirectorDto>, AppError> {
+        let directors = self
+            .repo
+            .find_by_ids(&self.db, ids)
+            .await
+            .map_err(AppError::DatabaseError)?;
+        Ok(directors.into_iter().map(DirectorDto::from).collect())
+    }
+
+    async fn autocomplete_directors(
+        &self,
+        query: AutocompleteQuery,
+    ) -> Result<Vec<NameSuggestionDto>, AppError> {
+        let term = query
+            .term()
+            .ok_or_else(|| AppError::ValidationError("q must not be blank".into()))?;
+        let limit = query.limit();
+        self.cache
+            .suggestions(SearchEntityType::Director, &term, limit, || async {
+                self.repo
+                    .autocomplete(&self.db, &term, limit)
+                    .await
+                    .map_err(AppError::DatabaseError)
+            })
             .await
-            .map_err(AppError::DatabaseError)?
-            .map(DirectorDto::from)
-            .ok_or_else(|| AppError::NotFound("Director not found".into()))
     }
 
     async fn get_director_list(
@@ -57,6 +106,7 @@ impl DirectorServiceTrait for DirectorService {
         search_dto: SearchDirectorDto,
         pagination: PaginationQuery,
     ) -> Result<PaginatedResponse<DirectorDto>, AppError> {
+        resolve_ordering(&pagination, ENTITY_ORDERING_FIELDS)?;
         let paginated = self
             .repo
             .find_list_paginated(&self.db, search_dto, pagination)
@@ -65,13 +115,21 @@ impl DirectorServiceTrait for DirectorService {
             count: paginated.count,
             next: paginated.next,
             previous: paginated.previous,
+            next_cursor: paginated.next_cursor,
             results: paginated.results.into_iter().map(Into::into).collect(),
         })
     }
 
     async fn get_directors(&self) -> Result<Vec<DirectorDto>, AppError> {
-        let directors = self.repo.find_all(&self.db).await?;
-        Ok(directors.into_iter().map(Into::into).collect())
+        self.cache
+            .entity_list(SearchEntityType::Director, || async {
+                self.repo
+                    .find_all(&self.db)
+                    .await
+                    .map(|directors| directors.into_iter().map(DirectorDto::from).collect())
+                    .map_err(AppError::DatabaseError)
+            })
+            .await
     }
 
     async fn get_director_list_by_affinity(
@@ -80,6 +138,7 @@ impl DirectorServiceTrait for DirectorService {
         pagination: PaginationQuery,
         user_id: String,
     ) -> Result<PaginatedResponse<DirectorDto>, AppError> {
+        resolve_ordering(&pagination, ENTITY_ORDERING_FIELDS)?;
         let paginated = self
             .affinity_repo
             .find_list_paginated_by_affinity(&self.db, search_dto, pagination, &user_id)
@@ -89,6 +148,7 @@ impl DirectorServiceTrait for DirectorService {
             count: paginated.count,
             next: paginated.next,
             previous: paginated.previous,
+            next_cursor: paginated.next_cursor,
             results: paginated.results.into_iter().map(Into::into).collect(),
         })
     }
@@ -120,6 +180,16 @@ impl DirectorServiceTrait for DirectorService {
         }
 
         txn.commit().await?;
+        if was_created {
+            self.events.publish_entity(
+                SearchEntityType::Director,
+                director_id,
+                CatalogAction::Created,
+            );
+            self.cache
+                .invalidate_entities(SearchEntityType::Director, &[director_id])
+                .await;
+        }
         self.get_director_by_id(director_id).await
     }
 
@@ -199,10 +269,36 @@ impl DirectorServiceTrait for DirectorService {
         }
 
         txn.commit().await?;
+        if surviving_id != id {
+            self.events
+                .publish_entity(SearchEntityType::Director, id, CatalogAction::Deleted);
+        }
+        self.events.publish_entity(
+            SearchEntityType::Director,
+            surviving_id,
+            CatalogAction::Updated,
+        );
+        self.cache
+            .invalidate_entities(SearchEntityType::Director, &[id, surviving_id])
+            .await;
         Ok(DirectorDto::from(director))
     }
 
-    async fn delete_director(&self, id: i64) -> Result<String, AppError> {
+    async fn delete_director(&self, id: i64, reassign_to: Option<i64>) -> Result<String, AppError> {
+        if let Some(target_id) = reassign_to {
+            merge_named_entity(
+                &self.db,
+                &*self.merge_repo,
+                &self.events,
+                &self.cache,
+                SearchEntityType::Director,
+                target_id,
+                id,
+            )
+            .await?;
+            return Ok("Director deleted".into());
+        }
+
         let txn = self.db.begin().await?;
 
         // Pre-delete snapshot: find affected records BEFORE delete
@@ -211,6 +307,17 @@ impl DirectorServiceTrait for DirectorService {
                 .await
                 .map_err(AppError::DatabaseError)?;
 
+        if !affected.is_empty() {
+            txn.rollback().await?;
+            return Err(AppError::ConflictWithRecords(
+                format!(
+                    "Director {id} is still referenced by {} record(s)",
+                    affected.len()
+                ),
+                affected,
+            ));
+        }
+
         match self.repo.delete(&txn, id).await {
             Ok(true) => {}
             Ok(false) => {
@@ -231,13 +338,47 @@ impl DirectorServiceTrait for DirectorService {
             .map_err(AppError::DatabaseError)?;
 
         txn.commit().await?;
+        self.events
+            .publish_entity(SearchEntityType::Director, id, CatalogAction::Deleted);
+        self.cache
+            .invalidate_entities(SearchEntityType::Director, &[id])
+            .await;
         Ok("Director deleted".into())
     }
 
-    async fn get_director_record_counts(&self) -> Result<Vec<EntityCountDto>, AppError> {
-        self.repo
-            .get_director_record_counts(&self.db)
-            .await
-            .map_err(AppError::DatabaseError)
+    async fn merge_director(
+        &self,
+        id: i64,
+        source_id: i64,
+    ) -> Result<MergeEntityResponse, AppError> {
+        merge_named_entity(
+            &self.db,
+            &*self.merge_repo,
+            &self.events,
+            &self.cache,
+            SearchEntityType::Director,
+            id,
+            source_id,
+        )
+        .await
+    }
+
+    async fn get_director_record_counts(
+        &self,
+        query: RecordCountQuery,
+    ) -> Result<PaginatedResponse<EntityCountDto>, AppError> {
+        let load = || async {
+            self.repo
+                .get_director_record_counts(&self.db, &query)
+                .await
+                .map_err(AppError::DatabaseError)
+        };
+        if query.is_first_page() {
+            self.cache
+                .record_counts(SearchEntityType::Director, load)
+                .await
+        } else {
+            load().await
+        }
     }
 }
@@ -0,0 +1,191 @@
+use crate::{
+    common::error::AppError,
+    domains::luna::{
+        domain::{DuplicateRepository, DuplicateServiceTrait, MediaFileRepository},
+        dto::{
+            DuplicateCandidateDto, DuplicateGroupDto, DuplicateImageGroupDto, DuplicateImageQuery,
+            DuplicateQuery, DuplicateReason, DEFAULT_DUPLICATE_GROUPS,
+            DEFAULT_DUPLICATE_SIMILARITY, MAX_DUPLICATE_GROUPS,
+        },
+        infra::{DuplicateRepo, MediaFileRepo},
+    },
+};
+use async_trait::async_trait;
+use sea_orm::DatabaseConnection;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Most similar-title pairs read per report, bounding the work on catalogs
+/// with many near-identical titles.
+const MAX_SIMILAR_PAIRS: u64 = 5000;
+
+/// Service struct for the duplicate-record report.
+#[derive(Clone)]
+pub struct DuplicateService {
+    db: DatabaseConnection,
+    repo: Arc<dyn DuplicateRepository>,
+    media_repo: Arc<dyn MediaFileRepository>,
+}
+
+impl DuplicateService {
+    pub fn create_service(db: DatabaseConnection) -> Arc<dyn DuplicateServiceTrait> {
+        Arc::new(Self {
+            db,
+            repo: Arc::new(DuplicateRepo),
+            media_repo: Arc::new(MediaFileRepo),
+        })
+    }
+}
+
+/// Merge `pairs` into connected groups of record IDs, each with the lowest
+/// score among its pairs.
+fn group_pairs(pairs: Vec<(String, String, f64)>) -> Vec<(Vec<String>, f64)> {
+    fn root(parent: &mut HashMap<String, String>, id: &str) -> String {
+        let mut current = id.to_owned();
+        while let Some(next) = parent.get(&current).filter(|next| **next != current) {
+            current = next.clone();
+        }
+        parent.insert(id.to_owned(), current.clone());
+        current
+    }
+
+    let mut parent: HashMap<String, String> = HashMap::new();
+    for (left, right, _) in &pairs {
+        parent.entry(left.clone()).or_insert_with(|| left.clone());
+        parent.entry(right.clone()).or_insert_with(|| right.clone());
+        let (left_root, right_root) = (root(&mut parent, left), root(&mut parent, right));
+        if left_root != right_root {
+            parent.insert(left_root, right_root);
+        }
+    }
+
+    let mut groups: HashMap<String, (Vec<String>, f64)> = HashMap::new();
+    let ids: Vec<String> = parent.keys().cloned().collect();
+    for id in ids {
+        let group_root = root(&mut parent, &id);
+        groups
+            .entry(group_root)
+            .or_insert((Vec::new(), 1.0))
+            .0
+            .push(id);
+    }
+    for (left, _, score) in &pairs {
+        let group_root = root(&mut parent, left);
+        if let Some(group) = groups.get_mut(&group_root) {
+            group.1 = group.1.min(*score);
+        }
+    }
+    groups
+        .into_values()
+        .map(|(mut ids, score)| {
+            ids.sort_unstable();
+            (ids, score)
+        })
+        .collect()
+}
+
+#[async_trait]
+impl DuplicateServiceTrait for DuplicateService {
+    async fn find_duplicates(
+        &self,
+        query: DuplicateQuery,
+    ) -> Result<Vec<DuplicateGroupDto>, AppError> {
+        let min_similarity = query.min_similarity.unwrap_or(DEFAULT_DUPLICATE_SIMILARITY);
+        if !(min_similarity > 0.0 && min_similarity <= 1.0) {
+            return Err(AppError::ValidationError(
+                "min_similarity must be in (0, 1]".into(),
+            ));
+        }
+        let limit = query.limit.unwrap_or(DEFAULT_DUPLICATE_GROUPS);
+        if limit == 0 {
+            return Err(AppError::ValidationError("limit must be > 0".into()));
+        }
+        let limit = limit.min(MAX_DUPLICATE_GROUPS);
+
+        let exact = self
+            .repo
+            .same_title_and_date(&self.db, limit)
+            .await
+            .map_err(AppError::DatabaseError)?;
+        let pairs = self
+            .repo
+            .similar_titles_with_same_idols(&self.db, min_similarity, MAX_SIMILAR_PAIRS)
+            .await
+            .map_err(AppError::DatabaseError)?;
+        let mut similar = group_pairs(pairs);
+        similar.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+        let groups: Vec<(DuplicateReason, Vec<String>, f64)> = exact
+            .into_iter()
+            .map(|ids| (DuplicateReason::SameTitleAndDate, ids, 1.0))
+            .chain(
+                similar
+                    .into_iter()
+                    .map(|(ids, score)| (DuplicateReason::SameIdolsSimilarTitle, ids, score)),
+            )
+            .take(limit as usize)
+            .collect();
+
+        let ids: Vec<String> = groups
+            .iter()
+            .flat_map(|(_, ids, _)| ids.iter().cloned())
+            .collect();
+        let candidates: HashMap<String, DuplicateCandidateDto> = self
+            .repo
+            .find_candidates(&self.db, &ids)
+            .await
+            .map_err(AppError::DatabaseError)?
+            .into_iter()
+            .map(|candidate| (candidate.id.clone(), candidate))
+            .collect();
+
+        Ok(groups
+            .into_iter()
+            .map(|(reason, ids, confidence)| DuplicateGroupDto {
+                reason,
+                confidence,
+                records: ids
+                    .iter()
+                    .filter_map(|id| candidates.get(id).cloned())
+                    .collect(),
+            })
+            .collect())
+    }
+
+    async fn find_duplicate_images(
+        &self,
+        query: DuplicateImageQuery,
+    ) -> Result<Vec<DuplicateImageGroupDto>, AppError> {
+        let limit = query.limit.unwrap_or(DEFAULT_DUPLICATE_GROUPS);
+        if limit == 0 {
+            return Err(AppError::ValidationError("limit must be > 0".into()));
+        }
+        self.media_repo
+            .duplicate_groups(&self.db, limit.min(MAX_DUPLICATE_GROUPS))
+            .await
+            .map_err(AppError::DatabaseError)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::group_pairs;
+
+    #[test]
+    fn pairs_merge_into_groups_with_their_weakest_score() {
+        let pair = |a: &str, b: &str, score| (a.to_owned(), b.to_owned(), score);
+        let mut groups = group_pairs(vec![
+            pair("a", "b", 0.9),
+            pair("b", "c", 0.7),
+            pair("x", "y", 0.8),
+        ]);
+        groups.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            groups,
+            vec![
+                (vec!["a".to_owned(), "b".to_owned(), "c".to_owned()], 0.7),
+                (vec!["x".to_owned(), "y".to_owned()], 0.8),
+            ]
+        );
+    }
+}
@@ -0,0 +1,165 @@
+use crate::{
+    common::error::AppError,
+    domains::luna::{
+        domain::{ExportRepository, ExportServiceTrait, ExportStream, NamedEntityRow},
+        dto::{
+            csv_line, ExportEntity, ExportFormat, RecordDto, ENTITY_EXPORT_COLUMNS,
+            RECORD_EXPORT_COLUMNS,
+        },
+        infra::ExportRepo,
+    },
+};
+use sea_orm::DatabaseConnection;
+use std::sync::Arc;
+
+/// Rows fetched per query; one batch is rendered into one body chunk.
+const EXPORT_BATCH_SIZE: u64 = 500;
+
+/// Service struct for streaming catalog exports.
+#[derive(Clone)]
+pub struct ExportService {
+    db: DatabaseConnection,
+    repo: Arc<dyn ExportRepository>,
+}
+
+impl ExportService {
+    pub fn create_service(db: DatabaseConnection) -> Arc<dyn ExportServiceTrait> {
+        Arc::new(Self {
+            db,
+            repo: Arc::new(ExportRepo),
+        })
+    }
+}
+
+impl ExportServiceTrait for ExportService {
+    fn export_records(&self, format: ExportFormat, max_permission: i32) -> ExportStream {
+        let db = self.db.clone();
+        let repo = self.repo.clone();
+        Box::pin(async_stream::try_stream! {
+            if format == ExportFormat::Csv {
+                yield csv_line(RECORD_EXPORT_COLUMNS);
+            }
+            let mut after = None;
+            loop {
+                let batch = repo
+                    .record_batch(&db, after.take(), EXPORT_BATCH_SIZE, max_permission)
+                    .await?;
+                let Some(last) = batch.last() else {
+                    break;
+                };
+                after = Some(last.id.clone());
+                let done = (batch.len() as u64) < EXPORT_BATCH_SIZE;
+
+                let mut chunk = String::new();
+                for record in batch {
+                    chunk.push_str(&record_line(&RecordDto::from(record), format)?);
+                }
+                yield chunk;
+                if done {
+                    break;
+                }
+            }
+        })
+    }
+
+    fn export_entities(&self, entity: ExportEntity, format: ExportFormat) -> ExportStream {
+        let db = self.db.clone();
+        let repo = self.repo.clone();
+        Box::pin(async_stream::try_stream! {
+            if format == ExportFormat::Csv {
+                yield csv_line(ENTITY_EXPORT_COLUMNS);
+            }
+            // IDs start at 0, the "unknown" placeholder row
+            let mut after = -1;
+            loop {
+                let batch = repo
+                    .entity_batch(&db, entity, after, EXPORT_BATCH_SIZE)
+                    .await?;
+                let Some(last) = batch.last() else {
+                    break;
+                };
+                after = last.id;
+                let done = (batch.len() as u64) < EXPORT_BATCH_SIZE;
+
+                let mut chunk = String::new();
+                for row in &batch {
+                    chunk.push_str(&entity_line(row, format)?);
+                }
+                yield chunk;
+                if done {
+                    break;
+                }
+            }
+        })
+    }
+}
+
+/// Render one record in `format`, in [`RECORD_EXPORT_COLUMNS`] order for CSV.
+fn record_line(record: &RecordDto, format: ExportFormat) -> Result<String, AppError> {
+    match format {
+        ExportFormat::Csv => {
+            let join = |names: Vec<&str>| names.join("|");
+            Ok(csv_line([
+                record.id.clone(),
+                record.title.clone(),
+                record.date.to_string(),
+                record.duration.to_string(),
+                record.director.name.clone(),
+                record.studio.name.clone(),
+                record.label.name.clone(),
+                record.series.name.clone(),
+                join(
+                    record
+                        .genres
+                        .iter()
+                        .map(|g| g.genre.name.as_str())
+                        .collect(),
+                ),
+                join(record.idols.iter().map(|i| i.idol.name.as_str()).collect()),
+                join(record.links.iter().map(|l| l.link.as_str()).collect()),
+                record.permission.to_string(),
+                record.local_img_count.to_string(),
+                record.create_time.to_string(),
+                record.update_time.to_string(),
+                record.creator.clone(),
+                record.modified_by.clone(),
+                record.version.to_string(),
+            ]))
+        }
+        ExportFormat::Jsonl => {
+            let mut value = serde_json::to_value(record).map_err(json_error)?;
+            // Per-user interaction flags are not part of the catalog
+            if let Some(object) = value.as_object_mut() {
+                object.remove("liked");
+                object.remove("viewed");
+            }
+            Ok(format!("{value}\n"))
+        }
+    }
+}
+
+/// Render one named entity row in `format`, in [`ENTITY_EXPORT_COLUMNS`] order for CSV.
+fn entity_line(row: &NamedEntityRow, format: ExportFormat) -> Result<String, AppError> {
+    match format {
+        ExportFormat::Csv => Ok(csv_line([
+            row.id.to_string(),
+            row.name.clone(),
+            row.link.clone(),
+            row.manual.to_string(),
+        ])),
+        ExportFormat::Jsonl => {
+            let value = serde_json::json!({
+                "id": row.id,
+                "name": row.name,
+                "link": row.link,
+                "manual": row.manual,
+            });
+            Ok(format!("{value}\n"))
+        }
+    }
+}
+
+fn json_error(err: serde_json::Error) -> AppError {
+    tracing::error!("Failed to serialize export row: {err}");
+    AppError::InternalError
+}
@@ -1,116 +1,300 @@
-use crate::common::{config::Config, error::AppError};
-use crate::domains::luna::domain::FileServiceTrait;
-use crate::domains::luna::dto::{MediaAccessDto, MediaType, UploadImageDto};
+use super::archive;
+#[cfg(feature = "thumbnails")]
+use super::thumbnail;
+use crate::common::{
+    config::Config,
+    error::AppError,
+    etag::{file_etag, file_not_modified, http_date, if_range},
+    range::{parse_range, RangeRequest},
+};
+use crate::domains::luna::domain::{
+    is_safe_media_id, FileServiceTrait, MediaFileRepository, StoredMediaFile,
+};
+use crate::domains::luna::dto::{
+    ImageData, ImageKind, MediaAccessDto, MediaFileDto, MediaType, StagedFile, UploadImageDto,
+    VideoKind,
+};
+#[cfg(feature = "thumbnails")]
+use crate::domains::luna::dto::{ThumbnailFit, ThumbnailSpec};
+#[cfg(feature = "thumbnails")]
+use crate::domains::luna::infra::media_storage::LocalStorage;
+use crate::domains::luna::infra::media_storage::{
+    self, media_dir_key, media_key, MediaStorage, StoredObject,
+};
+use crate::domains::luna::infra::MediaFileRepo;
 use async_trait::async_trait;
 use axum::{
     body::Body,
-    http::{header, StatusCode},
+    extract::multipart::Field,
+    http::{header, HeaderMap, StatusCode},
     response::Response,
 };
+use ring::digest;
+use sea_orm::DatabaseConnection;
+use std::collections::BTreeMap;
 use std::path::Path;
+#[cfg(feature = "thumbnails")]
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
 use tokio::fs;
+use tokio::io::{AsyncReadExt as _, AsyncWriteExt as _};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio_util::io::ReaderStream;
+
+/// Directory under the private assets path where uploads are streamed before
+/// being moved into place.
+const UPLOAD_STAGING_DIR: &str = ".uploads";
+
+/// Bytes of a staged upload hashed at a time.
+const HASH_CHUNK_SIZE: usize = 64 * 1024;
 
 /// Implementation of the file service for luna domain
 #[derive(Clone)]
 pub struct FileService {
     config: Config,
+    db: DatabaseConnection,
+    storage: Arc<dyn MediaStorage>,
+    /// Content hashes of stored images, used to skip duplicate uploads.
+    media_files: Arc<dyn MediaFileRepository>,
+    /// Thumbnails are cached on the local disk whatever the media backend.
+    #[cfg(feature = "thumbnails")]
+    thumbnail_cache: Arc<LocalStorage>,
+    upload_slots: Arc<Semaphore>,
 }
 
 impl FileService {
-    /// Creates a new `FileService` instance
-    pub fn new(config: Config) -> Self {
-        Self { config }
+    /// Creates a new `FileService` instance over the storage backend
+    /// `config` selects, keeping content hashes of uploads in `db`
+    pub fn new(config: Config, db: DatabaseConnection) -> Self {
+        let storage = media_storage::from_config(&config);
+        let upload_slots = Arc::new(Semaphore::new(config.upload_max_concurrent.max(1)));
+        Self {
+            #[cfg(feature = "thumbnails")]
+            thumbnail_cache: Arc::new(LocalStorage::new(&config.assets_private_path)),
+            config,
+            db,
+            storage,
+            media_files: Arc::new(MediaFileRepo),
+            upload_slots,
+        }
     }
 }
 
 #[async_trait]
 impl FileServiceTrait for FileService {
     /// Serves a media file based on the provided media access parameters
-    async fn serve_media_file(&self, media_dto: MediaAccessDto) -> Result<Response, AppError> {
+    async fn serve_media_file(
+        &self,
+        media_dto: MediaAccessDto,
+        headers: &HeaderMap,
+    ) -> Result<Response, AppError> {
         // Block path traversal characters in the ID parameter
-        if media_dto.id.contains("..")
-            || media_dto.id.contains('/')
-            || media_dto.id.contains('\\')
-            || media_dto.id.contains('\0')
-        {
+        if !is_safe_media_id(&media_dto.id) {
             return Err(AppError::NotFound("Media not found".into()));
         }
-
-        // Build the file directory path: assets_private_path/records/images/id/
-        let base_dir = Path::new(&self.config.assets_private_path)
-            .join("images")
-            .join(media_dto.media_type.get_sub_dir_name());
-        let file_dir = base_dir.join(&media_dto.id);
-        let filename_base = media_dto.get_filename();
-
-        // Verify the resolved path stays within the base directory (path traversal check)
-        if file_dir.exists() {
-            if let (Ok(canonical_base), Ok(canonical_file_dir)) =
-                (base_dir.canonicalize(), file_dir.canonicalize())
-            {
-                if !canonical_file_dir.starts_with(&canonical_base) {
-                    tracing::error!("Path traversal detected: {}", file_dir.display());
-                    return Err(AppError::NotFound("Media not found".into()));
-                }
+        if let Some(spec) = media_dto.thumbnail {
+            if spec.width == Some(0) {
+                return Err(AppError::ValidationError("w must be > 0".into()));
+            }
+            if spec.height == Some(0) {
+                return Err(AppError::ValidationError("h must be > 0".into()));
             }
         }
 
-        // Check if the directory exists
-        if !file_dir.exists() {
-            tracing::error!("Directory not found: {}", file_dir.display());
-            return Err(AppError::NotFound(format!(
-                "Media directory for id '{}' not found",
-                media_dto.id
-            )));
-        }
-
-        // Try to find the file with configured extensions
-        let mut file_path = None;
-        let mut found_extension = None;
+        // Files live at {images,videos}/{type}/{id}/{filename_base}.{extension}
+        let filename_base = media_dto.get_filename();
 
-        for extension in &self.config.asset_allowed_extensions {
-            let candidate_path = file_dir.join(format!("{filename_base}.{extension}"));
-            if candidate_path.exists() {
-                file_path = Some(candidate_path);
-                found_extension = Some(extension);
+        // Try to find the file with the extensions allowed for its type
+        let mut found = None;
+        for extension in self.allowed_extensions(&media_dto.media_type) {
+            let key = media_key(
+                &media_dto.media_type,
+                &media_dto.id,
+                &format!("{filename_base}.{extension}"),
+            );
+            if let Some(object) = self.storage.stat(&key).await.map_err(storage_error)? {
+                found = Some((key, extension, object));
                 break;
             }
         }
 
-        let file_path = file_path.ok_or_else(|| {
+        let (key, extension, object) = found.ok_or_else(|| {
             tracing::error!("No supported image file found for: {}", filename_base);
             AppError::NotFound(format!(
                 "Media file '{filename_base}' not found with any supported extension"
             ))
         })?;
 
-        // Read the file content
-        let file_content = fs::read(&file_path).await.map_err(|err| {
-            tracing::error!("Error reading file {}: {}", file_path.display(), err);
-            AppError::InternalError
-        })?;
-
         // Determine content type based on the found extension
-        let content_type =
-            Self::get_content_type_from_filename(found_extension.map(|s| s.as_str()).unwrap_or(""));
+        let content_type = Self::get_content_type_from_filename(extension);
+
+        // Serve a cached thumbnail when one was asked for; without the
+        // `thumbnails` feature the original is served
+        #[cfg(feature = "thumbnails")]
+        if let Some(spec) = media_dto.thumbnail {
+            if thumbnail::is_supported(extension) {
+                if let Some((cache_key, cached)) = self
+                    .thumbnail_for(&media_dto, &filename_base, &key, object, spec)
+                    .await
+                {
+                    return self
+                        .respond(
+                            &*self.thumbnail_cache,
+                            &cache_key,
+                            cached,
+                            "image/jpeg",
+                            headers,
+                        )
+                        .await;
+                }
+            }
+        }
 
-        // Create the response with cache headers
-        let response = Response::builder()
+        if self.config.media_storage.presigned_urls {
+            let expires_in = Duration::from_secs(self.config.media_storage.presign_expiry_secs);
+            if let Some(url) = self
+                .storage
+                .presigned_url(&key, expires_in)
+                .await
+                .map_err(storage_error)?
+            {
+                return Response::builder()
+                    .status(StatusCode::TEMPORARY_REDIRECT)
+                    .header(header::LOCATION, url)
+                    .header(header::CACHE_CONTROL, "no-store")
+                    .body(Body::empty())
+                    .map_err(|err| {
+                        tracing::error!("Error building response: {}", err);
+                        AppError::InternalError
+                    });
+            }
+        }
+
+        self.respond(&*self.storage, &key, object, content_type, headers)
+            .await
+    }
+
+    async fn list_media(
+        &self,
+        media_type: MediaType,
+        id: &str,
+        url: &str,
+    ) -> Result<Vec<MediaFileDto>, AppError> {
+        if !is_safe_media_id(id) {
+            return Err(AppError::NotFound("Media not found".into()));
+        }
+        let extensions = self.allowed_extensions(&media_type);
+        let entries = self
+            .storage
+            .list(&media_dir_key(&media_type, id))
+            .await
+            .map_err(storage_error)?;
+
+        // The media route tries extensions in configured order, so only the
+        // first of several files with the same stem is reachable
+        let mut found: BTreeMap<Option<u32>, (usize, String, u64)> = BTreeMap::new();
+        for (filename, object) in entries {
+            let Some((stem, extension)) = filename.rsplit_once('.') else {
+                continue;
+            };
+            let Some(rank) = extensions.iter().position(|&allowed| allowed == extension) else {
+                continue;
+            };
+            let n = match stem.strip_prefix(id) {
+                Some("") => None,
+                Some(suffix) => match suffix.strip_prefix('_').map(str::parse::<u32>) {
+                    Some(Ok(n)) => Some(n),
+                    _ => continue,
+                },
+                None => continue,
+            };
+            if found.get(&n).is_none_or(|(best, _, _)| rank < *best) {
+                found.insert(n, (rank, filename, object.len));
+            }
+        }
+
+        let mut files = Vec::with_capacity(found.len());
+        for (n, (_, filename, size)) in found {
+            // Dimensions are only read from files on the local disk
+            #[cfg(feature = "thumbnails")]
+            let (width, height) = match (
+                filename.rsplit_once('.'),
+                self.storage
+                    .local_path(&media_key(&media_type, id, &filename)),
+            ) {
+                (Some((_, extension)), Some(path)) if thumbnail::is_supported(extension) => {
+                    thumbnail::dimensions(path).await.unzip()
+                }
+                _ => (None, None),
+            };
+            #[cfg(not(feature = "thumbnails"))]
+            let (width, height) = (None, None);
+            files.push(MediaFileDto {
+                url: n.map_or_else(|| url.to_owned(), |n| format!("{url}?n={n}")),
+                filename,
+                n,
+                size,
+                width,
+                height,
+            });
+        }
+        Ok(files)
+    }
+
+    async fn archive_media(&self, media_type: MediaType, id: &str) -> Result<Response, AppError> {
+        if !is_safe_media_id(id) {
+            return Err(AppError::NotFound("Media not found".into()));
+        }
+        let extensions = self.allowed_extensions(&media_type);
+        let mut files: Vec<(String, String, u64)> = self
+            .storage
+            .list(&media_dir_key(&media_type, id))
+            .await
+            .map_err(storage_error)?
+            .into_iter()
+            .filter(|(filename, _)| {
+                filename
+                    .rsplit_once('.')
+                    .is_some_and(|(_, extension)| extensions.contains(&extension))
+            })
+            .map(|(filename, object)| {
+                let key = media_key(&media_type, id, &filename);
+                (filename, key, object.len)
+            })
+            .collect();
+        if files.is_empty() {
+            return Err(AppError::NotFound(format!("No media found for '{id}'")));
+        }
+        files.sort();
+
+        let archive = archive::zip_media(Arc::clone(&self.storage), files);
+        Response::builder()
             .status(StatusCode::OK)
-            .header(header::CONTENT_TYPE, content_type)
-            .header(header::CONTENT_LENGTH, file_content.len())
-            .header(header::CACHE_CONTROL, "public, max-age=3600, immutable")
+            .header(header::CONTENT_TYPE, "application/zip")
             .header(
-                header::ETAG,
-                format!("\"{:x}\"", md5::compute(&file_content)),
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}.zip\"", id.replace('"', "_")),
             )
-            .body(Body::from(file_content))
+            .body(Body::from_stream(ReaderStream::new(archive)))
             .map_err(|err| {
                 tracing::error!("Error building response: {}", err);
                 AppError::InternalError
-            })?;
+            })
+    }
 
-        Ok(response)
+    fn try_acquire_upload_slot(&self) -> Result<OwnedSemaphorePermit, AppError> {
+        Arc::clone(&self.upload_slots)
+            .try_acquire_owned()
+            .map_err(|_| AppError::TooManyRequests)
+    }
+
+    async fn stage_upload(&self, field: Field<'_>) -> Result<StagedFile, AppError> {
+        self.stage(field, self.config.asset_max_size).await
+    }
+
+    async fn stage_video_upload(&self, field: Field<'_>) -> Result<StagedFile, AppError> {
+        self.stage(field, self.config.video_max_size).await
     }
 
     async fn upload_images(
@@ -118,52 +302,350 @@ impl FileServiceTrait for FileService {
         ty: MediaType,
         upload_dto: UploadImageDto,
     ) -> Result<usize, AppError> {
-        // Build the target directory path: assets_private_path/records/images/id/
-        let target_dir = Path::new(&self.config.assets_private_path)
-            .join("images")
-            .join(ty.get_sub_dir_name())
-            .join(&upload_dto.id);
-
-        // Check if directory exists, create if not
-        if !target_dir.exists() {
-            fs::create_dir_all(&target_dir).await.map_err(|err| {
-                tracing::error!("Error creating directory {}: {}", target_dir.display(), err);
-                AppError::InternalError
-            })?;
+        // Check every file before any is moved into place
+        let mut files = Vec::with_capacity(upload_dto.files.len());
+        for image_data in upload_dto.files {
+            let kind = sniff_upload(&image_data).await?;
+            #[cfg(feature = "thumbnails")]
+            if self.config.upload_strip_metadata {
+                strip_upload_metadata(&image_data, kind).await?;
+            }
+            let (sha256, size) = hash_file(image_data.staged.path())
+                .await
+                .map_err(staging_error)?;
+            files.push((image_data, kind, sha256, size));
         }
 
         let mut uploaded_count = 0;
+        #[cfg(feature = "thumbnails")]
+        let mut uploaded = Vec::new();
 
-        for image_data in upload_dto.files {
-            // Generate filename based on name and mime type
-            let extension = Self::get_extension_from_mime(&image_data.mime);
+        for (image_data, kind, sha256, size) in files {
+            // Generate filename based on name and the sniffed format
+            let extension = kind.extension();
             let filename = format!("{}.{}", image_data.name, extension);
-            let file_path = target_dir.join(&filename);
-
-            // Check if file already exists, skip if it does (don't overwrite)
-            if file_path.exists() {
-                tracing::info!("File {} already exists, skipping", file_path.display());
-                continue;
-            }
+            let key = media_key(&ty, &upload_dto.id, &filename);
+            let file = StoredMediaFile {
+                key: key.clone(),
+                owner_type: ty.get_sub_dir_name().to_owned(),
+                owner_id: upload_dto.id.clone(),
+                filename,
+                sha256,
+                size,
+            };
 
-            // Write the file
-            match fs::write(&file_path, &image_data.bytes).await {
-                Ok(_) => {
-                    tracing::info!("Successfully uploaded file: {}", file_path.display());
+            // Move the staged file into place, unless one is already there
+            // (no overwriting)
+            match self
+                .store_image(&file, image_data.staged, kind.mime())
+                .await
+            {
+                Ok(true) => {
+                    tracing::info!("Successfully uploaded file: {}", key);
                     uploaded_count += 1;
+                    #[cfg(feature = "thumbnails")]
+                    if thumbnail::is_supported(extension) {
+                        uploaded.push((image_data.name, key));
+                    }
+                }
+                Ok(false) => {
+                    tracing::info!("File {} already stored, skipping", key);
                 }
                 Err(err) => {
-                    tracing::error!("Error writing file {}: {}", file_path.display(), err);
+                    tracing::error!("Error writing file {}: {}", key, err);
                     // Continue with other files instead of failing completely
                 }
             }
         }
 
+        #[cfg(feature = "thumbnails")]
+        self.pregenerate_thumbnails(&ty, &upload_dto.id, uploaded);
+
         Ok(uploaded_count)
     }
+
+    async fn upload_video(
+        &self,
+        ty: MediaType,
+        id: &str,
+        staged: StagedFile,
+        mime: &str,
+    ) -> Result<(), AppError> {
+        if !is_safe_media_id(id) {
+            return Err(AppError::ValidationError(format!(
+                "Invalid media ID '{id}'"
+            )));
+        }
+        let head = read_head(staged.path()).await.map_err(staging_error)?;
+        let kind = VideoKind::sniff(&head).ok_or_else(|| {
+            AppError::ValidationError("File is not an MP4 or WebM video".to_owned())
+        })?;
+        if !kind.matches_mime(mime) {
+            return Err(AppError::ValidationError(format!(
+                "File is {} but was sent as {mime}",
+                kind.mime()
+            )));
+        }
+
+        // One video per ID, whatever its format
+        for extension in VideoKind::EXTENSIONS {
+            let key = media_key(&ty, id, &format!("{id}.{extension}"));
+            if self
+                .storage
+                .stat(&key)
+                .await
+                .map_err(storage_error)?
+                .is_some()
+            {
+                return Err(AppError::Conflict(format!(
+                    "A video for '{id}' already exists"
+                )));
+            }
+        }
+
+        let key = media_key(&ty, id, &format!("{id}.{}", kind.extension()));
+        if !self
+            .storage
+            .put_new(&key, staged, kind.mime())
+            .await
+            .map_err(storage_error)?
+        {
+            return Err(AppError::Conflict(format!(
+                "A video for '{id}' already exists"
+            )));
+        }
+        tracing::info!("Successfully uploaded video: {}", key);
+        Ok(())
+    }
 }
 
 impl FileService {
+    /// Streams a multipart file field to a temporary file next to the media
+    /// directories, failing with `PayloadTooLarge` past `max_size` bytes.
+    async fn stage(&self, mut field: Field<'_>, max_size: usize) -> Result<StagedFile, AppError> {
+        let staging_dir = Path::new(&self.config.assets_private_path).join(UPLOAD_STAGING_DIR);
+        fs::create_dir_all(&staging_dir)
+            .await
+            .map_err(staging_error)?;
+
+        // Declared before the file handle so the handle closes before the
+        // staged file is removed on error
+        let staged = StagedFile::new(staging_dir.join(format!("{}.part", uuid::Uuid::new_v4())));
+        let mut file = fs::File::create(staged.path())
+            .await
+            .map_err(staging_error)?;
+        let mut written = 0;
+        while let Some(chunk) = field
+            .chunk()
+            .await
+            .map_err(|e| AppError::ValidationError(format!("Failed to read file data: {e}")))?
+        {
+            written += chunk.len();
+            if written > max_size {
+                return Err(AppError::PayloadTooLarge);
+            }
+            file.write_all(&chunk).await.map_err(staging_error)?;
+        }
+        file.flush().await.map_err(staging_error)?;
+        Ok(staged)
+    }
+
+    /// Stores `staged`, the content `file` describes, at its key. Content
+    /// already stored under another key is linked instead of written again
+    /// when the backend can share it. Returns whether the file was stored.
+    async fn store_image(
+        &self,
+        file: &StoredMediaFile,
+        staged: StagedFile,
+        mime: &str,
+    ) -> std::io::Result<bool> {
+        let copies = match self
+            .media_files
+            .find_by_sha256(&self.db, &file.sha256)
+            .await
+        {
+            Ok(copies) => copies,
+            Err(err) => {
+                tracing::warn!("Failed to look up media hash {}: {err}", file.sha256);
+                Vec::new()
+            }
+        };
+        let mut source = None;
+        for copy in copies {
+            if copy.key != file.key && self.storage.stat(&copy.key).await?.is_some() {
+                source = Some(copy.key);
+                break;
+            }
+        }
+
+        let stored = match source {
+            Some(source) if self.storage.link_existing(&source, &file.key).await? => {
+                tracing::info!("Linked {} to identical {}", file.key, source);
+                true
+            }
+            _ => self.storage.put_new(&file.key, staged, mime).await?,
+        };
+        if stored {
+            if let Err(err) = self.media_files.upsert(&self.db, file).await {
+                tracing::warn!("Failed to record hash of {}: {err}", file.key);
+            }
+        }
+        Ok(stored)
+    }
+
+    /// Extensions files of `media_type` are looked up with, in order.
+    fn allowed_extensions(&self, media_type: &MediaType) -> Vec<&str> {
+        match media_type {
+            MediaType::RecordVideo => VideoKind::EXTENSIONS.to_vec(),
+            MediaType::RecordImage | MediaType::IdolImage => self
+                .config
+                .asset_allowed_extensions
+                .iter()
+                .map(String::as_str)
+                .collect(),
+        }
+    }
+
+    /// Answers a request for `key` in `storage`, described by `object`, with
+    /// validators and caching headers, honouring conditional and `Range`
+    /// requests in `headers`.
+    async fn respond(
+        &self,
+        storage: &dyn MediaStorage,
+        key: &str,
+        object: StoredObject,
+        content_type: &str,
+        headers: &HeaderMap,
+    ) -> Result<Response, AppError> {
+        let len = object.len;
+        let modified = object.modified;
+        let etag = file_etag(len, modified);
+
+        // Validators and caching headers go on every answer, 304 included
+        let mut builder = Response::builder()
+            .header(
+                header::CACHE_CONTROL,
+                format!("public, max-age={}", self.config.media_cache_max_age_secs),
+            )
+            .header(header::ETAG, &etag)
+            .header(header::ACCEPT_RANGES, "bytes");
+        if let Some(modified) = modified {
+            builder = builder.header(header::LAST_MODIFIED, http_date(modified));
+        }
+
+        let range = headers
+            .get(header::RANGE)
+            .and_then(|v| v.to_str().ok())
+            .filter(|_| if_range(headers, &etag, modified))
+            .map_or(RangeRequest::Full, |v| parse_range(v, len));
+
+        let response = if file_not_modified(headers, &etag, modified) {
+            builder.status(StatusCode::NOT_MODIFIED).body(Body::empty())
+        } else {
+            match range {
+                RangeRequest::Unsatisfiable => builder
+                    .status(StatusCode::RANGE_NOT_SATISFIABLE)
+                    .header(header::CONTENT_RANGE, format!("bytes */{len}"))
+                    .body(Body::empty()),
+                RangeRequest::Partial(range) => {
+                    let content = storage
+                        .read(key, Some(range))
+                        .await
+                        .map_err(storage_error)?;
+                    #[cfg(feature = "metrics")]
+                    crate::common::metrics::media_served(content.len());
+                    builder
+                        .status(StatusCode::PARTIAL_CONTENT)
+                        .header(header::CONTENT_TYPE, content_type)
+                        .header(header::CONTENT_LENGTH, content.len())
+                        .header(header::CONTENT_RANGE, range.content_range(len))
+                        .body(Body::from(content))
+                }
+                RangeRequest::Full => {
+                    let content = storage.read(key, None).await.map_err(storage_error)?;
+                    #[cfg(feature = "metrics")]
+                    crate::common::metrics::media_served(content.len());
+                    builder
+                        .status(StatusCode::OK)
+                        .header(header::CONTENT_TYPE, content_type)
+                        .header(header::CONTENT_LENGTH, content.len())
+                        .body(Body::from(content))
+                }
+            }
+        }
+        .map_err(|err| {
+            tracing::error!("Error building response: {}", err);
+            AppError::InternalError
+        })?;
+
+        Ok(response)
+    }
+
+    /// Key and metadata of the cached thumbnail of `source` under `spec`,
+    /// generated on first use. `None` when it cannot be generated, so the
+    /// original is served instead.
+    #[cfg(feature = "thumbnails")]
+    async fn thumbnail_for(
+        &self,
+        media_dto: &MediaAccessDto,
+        filename_base: &str,
+        source: &str,
+        object: StoredObject,
+        spec: ThumbnailSpec,
+    ) -> Option<(String, StoredObject)> {
+        let spec = thumbnail::snap_spec(spec, &self.config.thumbnail_sizes);
+        let cache_key =
+            thumbnail::cache_key(&media_dto.media_type, &media_dto.id, filename_base, spec);
+        let target = self.thumbnail_cache.path(&cache_key);
+        if let Err(err) =
+            thumbnail::ensure_thumbnail(&*self.storage, source, object.modified, &target, spec)
+                .await
+        {
+            tracing::warn!("Failed to generate thumbnail of {source}: {err}");
+            return None;
+        }
+        match self.thumbnail_cache.stat(&cache_key).await {
+            Ok(Some(cached)) => Some((cache_key, cached)),
+            _ => None,
+        }
+    }
+
+    /// Generates the list-view thumbnail of each `(name, key)` just
+    /// uploaded in the background, so list views never wait on a resize.
+    #[cfg(feature = "thumbnails")]
+    fn pregenerate_thumbnails(&self, ty: &MediaType, id: &str, uploaded: Vec<(String, String)>) {
+        if uploaded.is_empty() {
+            return;
+        }
+        let spec = thumbnail::snap_spec(
+            ThumbnailSpec {
+                width: Some(self.config.thumbnail_list_size),
+                height: None,
+                fit: ThumbnailFit::Contain,
+            },
+            &self.config.thumbnail_sizes,
+        );
+        let jobs: Vec<(String, PathBuf)> = uploaded
+            .into_iter()
+            .map(|(name, source)| {
+                let target = self
+                    .thumbnail_cache
+                    .path(&thumbnail::cache_key(ty, id, &name, spec));
+                (source, target)
+            })
+            .collect();
+        let storage = Arc::clone(&self.storage);
+        tokio::spawn(async move {
+            for (source, target) in jobs {
+                if let Err(err) =
+                    thumbnail::ensure_thumbnail(&*storage, &source, None, &target, spec).await
+                {
+                    tracing::warn!("Failed to generate thumbnail of {source}: {err}");
+                }
+            }
+        });
+    }
+
     /// Get content type based on file extension
     fn get_content_type_from_filename(ext: &str) -> &'static str {
         match ext {
@@ -173,20 +655,116 @@ impl FileService {
             "webp" => "image/webp",
             "bmp" => "image/bmp",
             "svg" => "image/svg+xml",
+            "mp4" => "video/mp4",
+            "webm" => "video/webm",
             _ => "application/octet-stream",
         }
     }
+}
+
+/// Logs a failure of the media storage backend.
+fn storage_error(err: std::io::Error) -> AppError {
+    tracing::error!("Media storage error: {}", err);
+    AppError::InternalError
+}
+
+/// Recognizes the format of an upload from its magic bytes, rejecting
+/// anything but JPEG, PNG and WebP, and declared types that disagree.
+async fn sniff_upload(image_data: &ImageData) -> Result<ImageKind, AppError> {
+    let head = read_head(image_data.staged.path())
+        .await
+        .map_err(staging_error)?;
+    let kind = ImageKind::sniff(&head).ok_or_else(|| {
+        AppError::ValidationError(format!(
+            "File '{}' is not a JPEG, PNG or WebP image",
+            image_data.name
+        ))
+    })?;
+    if !kind.matches_mime(&image_data.mime) {
+        return Err(AppError::ValidationError(format!(
+            "File '{}' is {} but was sent as {}",
+            image_data.name,
+            kind.mime(),
+            image_data.mime
+        )));
+    }
+    Ok(kind)
+}
+
+/// Logs a failure to stage or read back an upload.
+fn staging_error(err: std::io::Error) -> AppError {
+    tracing::error!("Error staging upload: {}", err);
+    AppError::InternalError
+}
+
+/// Hex SHA-256 and size of the file at `path`, read in chunks.
+async fn hash_file(path: &Path) -> std::io::Result<(String, u64)> {
+    let mut file = fs::File::open(path).await?;
+    let mut context = digest::Context::new(&digest::SHA256);
+    let mut chunk = vec![0; HASH_CHUNK_SIZE];
+    loop {
+        let read = file.read(&mut chunk).await?;
+        if read == 0 {
+            break;
+        }
+        context.update(&chunk[..read]);
+    }
+    let size = file.metadata().await?.len();
+    let sha256 = context
+        .finish()
+        .as_ref()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect();
+    Ok((sha256, size))
+}
+
+/// Reads the first bytes of the file at `path`, enough to sniff its format.
+async fn read_head(path: &Path) -> std::io::Result<Vec<u8>> {
+    let mut head = Vec::with_capacity(ImageKind::SNIFF_LEN);
+    fs::File::open(path)
+        .await?
+        .take(ImageKind::SNIFF_LEN as u64)
+        .read_to_end(&mut head)
+        .await?;
+    Ok(head)
+}
+
+/// Re-encodes a staged upload of format `kind` in place without its
+/// metadata. Images that fail to decode are rejected.
+#[cfg(feature = "thumbnails")]
+async fn strip_upload_metadata(image_data: &ImageData, kind: ImageKind) -> Result<(), AppError> {
+    let path = image_data.staged.path();
+    let bytes = fs::read(path).await.map_err(staging_error)?;
+    let stripped = tokio::task::spawn_blocking(move || thumbnail::strip_metadata(&bytes, kind))
+        .await
+        .map_err(|err| {
+            tracing::error!("Metadata stripping task failed: {}", err);
+            AppError::InternalError
+        })?
+        .map_err(|err| {
+            AppError::ValidationError(format!(
+                "File '{}' could not be decoded: {err}",
+                image_data.name
+            ))
+        })?;
+    fs::write(path, stripped).await.map_err(staging_error)?;
+    Ok(())
+}
 
-    /// Get file extension from MIME type
-    fn get_extension_from_mime(mime: &str) -> &'static str {
-        match mime {
-            "image/jpeg" => "jpg",
-            "image/png" => "png",
-            "image/gif" => "gif",
-            "image/webp" => "webp",
-            "image/bmp" => "bmp",
-            "image/svg+xml" => "svg",
-            _ => "bin",
+/// Number of regular files in the media directory `dir`; 0 when it does not
+/// exist.
+pub(super) async fn count_media_files(dir: &Path) -> std::io::Result<i32> {
+    let mut entries = match fs::read_dir(dir).await {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(e),
+    };
+    let mut count = 0;
+    while let Some(entry) = entries.next_entry().await? {
+        if entry.file_type().await?.is_file() {
+            count += 1;
         }
     }
+    Ok(count)
 }
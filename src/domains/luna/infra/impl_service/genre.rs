@@ -1,13 +1,21 @@
+use super::merge::merge_named_entity;
 use crate::domains::search::SearchEntityType;
 use crate::{
-    common::error::AppError,
+    common::{error::AppError, pagination::resolve_ordering},
     domains::luna::{
-        domain::{GenreAffinityRepository, GenreRepository, GenreServiceTrait},
+        domain::{
+            GenreAffinityRepository, GenreCategoryRepository, GenreRepository, GenreServiceTrait,
+            NamedEntityMergeRepository,
+        },
         dto::{
-            CreateGenreDto, EntityCountDto, GenreDto, PaginatedResponse, PaginationQuery,
-            SearchGenreDto, UpdateGenreDto,
+            AutocompleteQuery, CatalogAction, CreateGenreCategoryDto, CreateGenreDto,
+            EntityCountDto, GenreCategoryCountDto, GenreCategoryDto, GenreDto, MergeEntityResponse,
+            NameSuggestionDto, PaginatedResponse, PaginationQuery, RecordCountQuery,
+            SearchGenreDto, UpdateGenreDto, ENTITY_ORDERING_FIELDS,
+        },
+        infra::{
+            catalog_cache::CatalogCache, catalog_events::CatalogEvents, search_outbox, GenreRepo,
         },
-        infra::{search_outbox, GenreRepo},
     },
 };
 use async_trait::async_trait;
@@ -23,25 +31,70 @@ pub struct GenreService {
     /// object cannot expose `GenreAffinityRepository` methods, so the affinity
     /// trait needs its own trait object (both wrap `GenreRepo`).
     affinity_repo: Arc<dyn GenreAffinityRepository + Send + Sync>,
+    /// Merge handle; also wraps `GenreRepo`.
+    merge_repo: Arc<dyn NamedEntityMergeRepository + Send + Sync>,
+    /// Genre category handle; also wraps `GenreRepo`.
+    category_repo: Arc<dyn GenreCategoryRepository + Send + Sync>,
+    events: Arc<CatalogEvents>,
+    cache: Arc<CatalogCache>,
 }
 
 #[async_trait]
 impl GenreServiceTrait for GenreService {
-    fn create_service(db: DatabaseConnection) -> Arc<dyn GenreServiceTrait> {
+    fn create_service(
+        db: DatabaseConnection,
+        events: Arc<CatalogEvents>,
+        cache: Arc<CatalogCache>,
+    ) -> Arc<dyn GenreServiceTrait> {
         Arc::new(Self {
             db,
             repo: Arc::new(GenreRepo {}),
             affinity_repo: Arc::new(GenreRepo {}),
+            merge_repo: Arc::new(GenreRepo {}),
+            category_repo: Arc::new(GenreRepo {}),
+            events,
+            cache,
         })
     }
 
     async fn get_genre_by_id(&self, id: i64) -> Result<GenreDto, AppError> {
-        self.repo
-            .find_by_id(&self.db, id)
+        self.cache
+            .entity(SearchEntityType::Genre, id, || async {
+                self.repo
+                    .find_by_id(&self.db, id)
+                    .await
+                    .map_err(AppError::DatabaseError)?
+                    .map(GenreDto::from)
+                    .ok_or_else(|| AppError::NotFound("Genre not found".into()))
+            })
+            .await
+    }
+
+    async fn get_genres_by_ids(&self, ids: &[i64]) -> Result<Vec<GenreDto>, AppError> {
+        let genres = self
+            .repo
+            .find_by_ids(&self.db, ids)
+            .await
+            .map_err(AppError::DatabaseError)?;
+        Ok(genres.into_iter().map(GenreDto::from).collect())
+    }
+
+    async fn autocomplete_genres(
+        &self,
+        query: AutocompleteQuery,
+    ) -> Result<Vec<NameSuggestionDto>, AppError> {
+        let term = query
+            .term()
+            .ok_or_else(|| AppError::ValidationError("q must not be blank".into()))?;
+        let limit = query.limit();
+        self.cache
+            .suggestions(SearchEntityType::Genre, &term, limit, || async {
+                self.repo
+                    .autocomplete(&self.db, &term, limit)
+                    .await
+                    .map_err(AppError::DatabaseError)
+            })
             .await
-            .map_err(AppError::DatabaseError)?
-            .map(GenreDto::from)
-            .ok_or_else(|| AppError::NotFound("Genre not found".into()))
     }
 
     async fn get_genre_list(&self, search_dto: SearchGenreDto) -> Result<Vec<GenreDto>, AppError> {
@@ -54,6 +107,7 @@ impl GenreServiceTrait for GenreService {
         search_dto: SearchGenreDto,
         pagination: PaginationQuery,
     ) -> Result<PaginatedResponse<GenreDto>, AppError> {
+        resolve_ordering(&pagination, ENTITY_ORDERING_FIELDS)?;
         let paginated = self
             .repo
             .find_list_paginated(&self.db, search_dto, pagination)
@@ -62,13 +116,21 @@ impl GenreServiceTrait for GenreService {
             count: paginated.count,
             next: paginated.next,
             previous: paginated.previous,
+            next_cursor: paginated.next_cursor,
             results: paginated.results.into_iter().map(Into::into).collect(),
         })
     }
 
     async fn get_genres(&self) -> Result<Vec<GenreDto>, AppError> {
-        let genres = self.repo.find_all(&self.db).await?;
-        Ok(genres.into_iter().map(Into::into).collect())
+        self.cache
+            .entity_list(SearchEntityType::Genre, || async {
+                self.repo
+                    .find_all(&self.db)
+                    .await
+                    .map(|genres| genres.into_iter().map(GenreDto::from).collect())
+                    .map_err(AppError::DatabaseError)
+            })
+            .await
     }
 
     async fn get_genre_list_by_affinity(
@@ -77,6 +139,7 @@ impl GenreServiceTrait for GenreService {
         pagination: PaginationQuery,
         user_id: String,
     ) -> Result<PaginatedResponse<GenreDto>, AppError> {
+        resolve_ordering(&pagination, ENTITY_ORDERING_FIELDS)?;
         let paginated = self
             .affinity_repo
             .find_list_paginated_by_affinity(&self.db, search_dto, pagination, &user_id)
@@ -86,6 +149,7 @@ impl GenreServiceTrait for GenreService {
             count: paginated.count,
             next: paginated.next,
             previous: paginated.previous,
+            next_cursor: paginated.next_cursor,
             results: paginated.results.into_iter().map(Into::into).collect(),
         })
     }
@@ -114,6 +178,13 @@ impl GenreServiceTrait for GenreService {
         }
 
         txn.commit().await?;
+        if was_created {
+            self.events
+                .publish_entity(SearchEntityType::Genre, genre_id, CatalogAction::Created);
+            self.cache
+                .invalidate_entities(SearchEntityType::Genre, &[genre_id])
+                .await;
+        }
         self.get_genre_by_id(genre_id).await
     }
 
@@ -185,6 +256,18 @@ impl GenreServiceTrait for GenreService {
         }
 
         txn.commit().await?;
+        if surviving_id != id {
+            self.events
+                .publish_entity(SearchEntityType::Genre, id, CatalogAction::Deleted);
+        }
+        self.events.publish_entity(
+            SearchEntityType::Genre,
+            surviving_id,
+            CatalogAction::Updated,
+        );
+        self.cache
+            .invalidate_entities(SearchEntityType::Genre, &[id, surviving_id])
+            .await;
         Ok(GenreDto::from(genre))
     }
 
@@ -215,13 +298,171 @@ impl GenreServiceTrait for GenreService {
             .map_err(AppError::DatabaseError)?;
 
         txn.commit().await?;
+        self.events
+            .publish_entity(SearchEntityType::Genre, id, CatalogAction::Deleted);
+        self.cache
+            .invalidate_entities(SearchEntityType::Genre, &[id])
+            .await;
         Ok("Genre deleted successfully".to_owned())
     }
 
-    async fn get_genre_record_counts(&self) -> Result<Vec<EntityCountDto>, AppError> {
-        self.repo
-            .get_genre_record_counts(&self.db)
+    async fn merge_genre(&self, id: i64, source_id: i64) -> Result<MergeEntityResponse, AppError> {
+        merge_named_entity(
+            &self.db,
+            &*self.merge_repo,
+            &self.events,
+            &self.cache,
+            SearchEntityType::Genre,
+            id,
+            source_id,
+        )
+        .await
+    }
+
+    async fn get_genre_record_counts(
+        &self,
+        query: RecordCountQuery,
+    ) -> Result<PaginatedResponse<EntityCountDto>, AppError> {
+        let load = || async {
+            self.repo
+                .get_genre_record_counts(&self.db, &query)
+                .await
+                .map_err(AppError::DatabaseError)
+        };
+        if query.is_first_page() {
+            self.cache
+                .record_counts(SearchEntityType::Genre, load)
+                .await
+        } else {
+            load().await
+        }
+    }
+
+    async fn get_genre_category_record_counts(
+        &self,
+    ) -> Result<Vec<GenreCategoryCountDto>, AppError> {
+        self.category_repo
+            .category_record_counts(&self.db)
+            .await
+            .map_err(AppError::DatabaseError)
+    }
+
+    async fn list_genre_categories(&self) -> Result<Vec<GenreCategoryDto>, AppError> {
+        self.category_repo
+            .find_categories(&self.db)
             .await
             .map_err(AppError::DatabaseError)
     }
+
+    async fn get_genre_category(&self, id: i64) -> Result<GenreCategoryDto, AppError> {
+        self.category_repo
+            .find_category(&self.db, id)
+            .await
+            .map_err(AppError::DatabaseError)?
+            .ok_or_else(|| AppError::NotFound("Genre category not found".into()))
+    }
+
+    async fn create_genre_category(
+        &self,
+        dto: CreateGenreCategoryDto,
+    ) -> Result<GenreCategoryDto, AppError> {
+        let id = self
+            .category_repo
+            .create_category(&self.db, dto)
+            .await
+            .map_err(AppError::DatabaseError)?;
+        self.get_genre_category(id).await
+    }
+
+    async fn update_genre_category(
+        &self,
+        id: i64,
+        dto: CreateGenreCategoryDto,
+    ) -> Result<GenreCategoryDto, AppError> {
+        let updated = self
+            .category_repo
+            .update_category(&self.db, id, dto)
+            .await
+            .map_err(AppError::DatabaseError)?;
+        if !updated {
+            return Err(AppError::NotFound("Genre category not found".into()));
+        }
+        self.get_genre_category(id).await
+    }
+
+    async fn delete_genre_category(&self, id: i64) -> Result<(), AppError> {
+        let txn = self.db.begin().await?;
+        let genre_ids = match self.category_repo.delete_category(&txn, id).await {
+            Ok(Some(genre_ids)) => genre_ids,
+            Ok(None) => {
+                txn.rollback().await?;
+                return Err(AppError::NotFound("Genre category not found".into()));
+            }
+            Err(e) => {
+                txn.rollback().await.ok();
+                return Err(AppError::DatabaseError(e));
+            }
+        };
+        txn.commit().await?;
+
+        for &genre_id in &genre_ids {
+            self.events
+                .publish_entity(SearchEntityType::Genre, genre_id, CatalogAction::Updated);
+        }
+        if !genre_ids.is_empty() {
+            // Hydrated records carry their genres
+            self.cache
+                .invalidate_entities(SearchEntityType::Genre, &genre_ids)
+                .await;
+            self.cache.invalidate_records().await;
+        }
+        Ok(())
+    }
+
+    async fn set_genre_category(
+        &self,
+        id: i64,
+        category_id: Option<i64>,
+    ) -> Result<GenreDto, AppError> {
+        if let Some(category_id) = category_id {
+            let exists = self
+                .category_repo
+                .find_category(&self.db, category_id)
+                .await
+                .map_err(AppError::DatabaseError)?
+                .is_some();
+            if !exists {
+                return Err(AppError::ValidationError(format!(
+                    "Genre category {category_id} does not exist"
+                )));
+            }
+        }
+
+        let txn = self.db.begin().await?;
+        let genre = match self
+            .category_repo
+            .set_genre_category(&txn, id, category_id)
+            .await
+        {
+            Ok(Some(genre)) => genre,
+            Ok(None) => {
+                txn.rollback().await?;
+                return Err(AppError::NotFound("Genre not found".into()));
+            }
+            Err(e) => {
+                txn.rollback().await.ok();
+                return Err(AppError::DatabaseError(e));
+            }
+        };
+        txn.commit().await?;
+
+        self.events
+            .publish_entity(SearchEntityType::Genre, id, CatalogAction::Updated);
+        // Hydrated records carry their genres
+        self.cache
+            .invalidate_entities(SearchEntityType::Genre, &[id])
+            .await;
+        self.cache.invalidate_records().await;
+        Ok(GenreDto::from(genre))
+    }
 }
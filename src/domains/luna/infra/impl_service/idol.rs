@@ -1,21 +1,46 @@
+use super::merge::merge_named_entity;
 use crate::domains::search::SearchEntityType;
 use crate::{
-    common::{config::Config, error::AppError},
+    common::{config::Config, error::AppError, pagination::resolve_ordering},
     domains::luna::{
-        domain::{IdolAffinityRepository, IdolRepository, IdolServiceTrait},
+        domain::{
+            is_safe_media_id, Idol, IdolAffinityRepository, IdolProfileRepository, IdolRepository,
+            IdolServiceTrait, MediaFileRepository, NamedEntityMergeRepository,
+        },
         dto::{
-            CreateIdolDto, EntityCountDto, IdolDto, IdolWithoutImageDto, PaginatedResponse,
-            PaginationQuery, SearchIdolDto, UpdateIdolDto,
+            AutocompleteQuery, CatalogAction, CreateIdolDto, EntityCountDto, IdolDto,
+            IdolProfileDto, IdolWithoutImageDto, MediaType, MergeEntityResponse, NameSuggestionDto,
+            PaginatedResponse, PaginationQuery, RecordCountQuery, SearchIdolDto, UpdateIdolDto,
+            ENTITY_ORDERING_FIELDS,
+        },
+        infra::{
+            catalog_cache::CatalogCache,
+            catalog_events::CatalogEvents,
+            media_storage::{self, media_dir_key, media_key, MediaStorage},
+            search_outbox, IdolRepo, MediaFileRepo,
         },
-        infra::{search_outbox, IdolRepo},
     },
 };
 use async_trait::async_trait;
 use sea_orm::{DatabaseConnection, TransactionTrait as _};
+use std::collections::HashSet;
 use std::path::Path;
 use std::sync::Arc;
 use tokio::fs;
 
+/// Profile of `idol` with its `aliases`.
+fn profile_of(idol: Idol, aliases: Vec<String>) -> IdolProfileDto {
+    IdolProfileDto {
+        birthday: idol.birthday,
+        debut_date: idol.debut_date,
+        height_cm: idol.height_cm,
+        bust_cm: idol.bust_cm,
+        waist_cm: idol.waist_cm,
+        hips_cm: idol.hips_cm,
+        aliases,
+    }
+}
+
 /// Service struct for handling idol-related operations.
 #[derive(Clone)]
 pub struct IdolService {
@@ -25,17 +50,37 @@ pub struct IdolService {
     /// object cannot expose `IdolAffinityRepository` methods, so the affinity
     /// trait needs its own trait object (both wrap `IdolRepo`).
     affinity_repo: Arc<dyn IdolAffinityRepository + Send + Sync>,
+    /// Merge handle; also wraps `IdolRepo`.
+    merge_repo: Arc<dyn NamedEntityMergeRepository + Send + Sync>,
+    /// Profile and alias handle; also wraps `IdolRepo`.
+    profile_repo: Arc<dyn IdolProfileRepository + Send + Sync>,
+    events: Arc<CatalogEvents>,
     config: Config,
+    cache: Arc<CatalogCache>,
+    /// Idol images are keyed by idol name, so they move on rename and merge.
+    storage: Arc<dyn MediaStorage>,
+    media_files: Arc<dyn MediaFileRepository>,
 }
 
 #[async_trait]
 impl IdolServiceTrait for IdolService {
-    fn create_service(db: DatabaseConnection, config: Config) -> Arc<dyn IdolServiceTrait> {
+    fn create_service(
+        db: DatabaseConnection,
+        config: Config,
+        events: Arc<CatalogEvents>,
+        cache: Arc<CatalogCache>,
+    ) -> Arc<dyn IdolServiceTrait> {
         Arc::new(Self {
             db: db.clone(),
             repo: Arc::new(IdolRepo),
             affinity_repo: Arc::new(IdolRepo),
+            merge_repo: Arc::new(IdolRepo),
+            profile_repo: Arc::new(IdolRepo),
+            events,
+            storage: media_storage::from_config(&config),
+            media_files: Arc::new(MediaFileRepo),
             config,
+            cache,
         })
     }
 
@@ -50,6 +95,33 @@ impl IdolServiceTrait for IdolService {
             .ok_or_else(|| AppError::NotFound("Idol not found".into()))
     }
 
+    async fn get_idols_by_ids(&self, ids: &[i64]) -> Result<Vec<IdolDto>, AppError> {
+        let idols = self
+            .repo
+            .find_by_ids(&self.db, ids)
+            .await
+            .map_err(AppError::DatabaseError)?;
+        Ok(idols.into_iter().map(IdolDto::from).collect())
+    }
+
+    async fn autocomplete_idols(
+        &self,
+        query: AutocompleteQuery,
+    ) -> Result<Vec<NameSuggestionDto>, AppError> {
+        let term = query
+            .term()
+            .ok_or_else(|| AppError::ValidationError("q must not be blank".into()))?;
+        let limit = query.limit();
+        self.cache
+            .suggestions(SearchEntityType::Idol, &term, limit, || async {
+                self.repo
+                    .autocomplete(&self.db, &term, limit)
+                    .await
+                    .map_err(AppError::DatabaseError)
+            })
+            .await
+    }
+
     async fn get_idol_list(&self, search_dto: SearchIdolDto) -> Result<Vec<IdolDto>, AppError> {
         let idols = self
             .repo
@@ -65,6 +137,7 @@ impl IdolServiceTrait for IdolService {
         search_dto: SearchIdolDto,
         pagination: PaginationQuery,
     ) -> Result<PaginatedResponse<IdolDto>, AppError> {
+        resolve_ordering(&pagination, ENTITY_ORDERING_FIELDS)?;
         let paginated = self
             .repo
             .find_list_paginated(&self.db, search_dto, pagination)
@@ -75,6 +148,7 @@ impl IdolServiceTrait for IdolService {
             count: paginated.count,
             next: paginated.next,
             previous: paginated.previous,
+            next_cursor: paginated.next_cursor,
             results: paginated.results.into_iter().map(IdolDto::from).collect(),
         })
     }
@@ -85,6 +159,7 @@ impl IdolServiceTrait for IdolService {
         pagination: PaginationQuery,
         user_id: String,
     ) -> Result<PaginatedResponse<IdolDto>, AppError> {
+        resolve_ordering(&pagination, ENTITY_ORDERING_FIELDS)?;
         let paginated = self
             .affinity_repo
             .find_list_paginated_by_affinity(&self.db, search_dto, pagination, &user_id)
@@ -95,6 +170,7 @@ impl IdolServiceTrait for IdolService {
             count: paginated.count,
             next: paginated.next,
             previous: paginated.previous,
+            next_cursor: paginated.next_cursor,
             results: paginated.results.into_iter().map(IdolDto::from).collect(),
         })
     }
@@ -133,10 +209,23 @@ impl IdolServiceTrait for IdolService {
 
         txn.commit().await.map_err(AppError::DatabaseError)?;
 
+        if was_created {
+            self.events
+                .publish_entity(SearchEntityType::Idol, id, CatalogAction::Created);
+            self.cache
+                .invalidate_entities(SearchEntityType::Idol, &[id])
+                .await;
+        }
         self.get_idol_by_id(id).await
     }
 
     async fn update_idol(&self, id: i64, update_dto: UpdateIdolDto) -> Result<IdolDto, AppError> {
+        let old_name = self
+            .repo
+            .find_by_id(&self.db, id)
+            .await
+            .map_err(AppError::DatabaseError)?
+            .map(|idol| idol.name);
         let txn = self.db.begin().await.map_err(AppError::DatabaseError)?;
 
         let pre_affected =
@@ -197,9 +286,86 @@ impl IdolServiceTrait for IdolService {
 
         txn.commit().await.map_err(AppError::DatabaseError)?;
 
+        if surviving_id != id {
+            self.events
+                .publish_entity(SearchEntityType::Idol, id, CatalogAction::Deleted);
+        }
+        self.events
+            .publish_entity(SearchEntityType::Idol, surviving_id, CatalogAction::Updated);
+        self.cache
+            .invalidate_entities(SearchEntityType::Idol, &[id, surviving_id])
+            .await;
+        if let Some(old_name) = old_name {
+            self.move_idol_media(&old_name, &idol.name).await;
+        }
         Ok(IdolDto::from(idol))
     }
 
+    async fn get_idol_profile(&self, id: i64) -> Result<IdolProfileDto, AppError> {
+        let idol = self
+            .repo
+            .find_by_id(&self.db, id)
+            .await
+            .map_err(AppError::DatabaseError)?
+            .ok_or_else(|| AppError::NotFound("Idol not found".into()))?;
+        let aliases = self
+            .profile_repo
+            .find_aliases(&self.db, id)
+            .await
+            .map_err(AppError::DatabaseError)?;
+        Ok(profile_of(idol, aliases))
+    }
+
+    async fn replace_idol_profile(
+        &self,
+        id: i64,
+        mut profile: IdolProfileDto,
+    ) -> Result<IdolProfileDto, AppError> {
+        if let (Some(birthday), Some(debut_date)) = (profile.birthday, profile.debut_date) {
+            if debut_date < birthday {
+                return Err(AppError::ValidationError(
+                    "Debut date cannot be before the birthday".into(),
+                ));
+            }
+        }
+        let mut aliases = Vec::with_capacity(profile.aliases.len());
+        for alias in &profile.aliases {
+            let alias = alias.trim();
+            if alias.is_empty() || alias.chars().count() > 255 {
+                return Err(AppError::ValidationError(
+                    "Aliases must be between 1 and 255 characters".into(),
+                ));
+            }
+            aliases.push(alias.to_owned());
+        }
+        aliases.sort();
+        aliases.dedup();
+        profile.aliases = aliases;
+
+        let txn = self.db.begin().await.map_err(AppError::DatabaseError)?;
+        let idol = match self.profile_repo.replace_profile(&txn, id, &profile).await {
+            Ok(Some(idol)) => idol,
+            Ok(None) => {
+                txn.rollback().await.ok();
+                return Err(AppError::NotFound("Idol not found".into()));
+            }
+            Err(e) => {
+                txn.rollback().await.ok();
+                return Err(AppError::DatabaseError(e));
+            }
+        };
+        txn.commit().await.map_err(AppError::DatabaseError)?;
+
+        self.events
+            .publish_entity(SearchEntityType::Idol, id, CatalogAction::Updated);
+        // Hydrated records carry the profiles of their idols
+        self.cache
+            .invalidate_entities(SearchEntityType::Idol, &[id])
+            .await;
+        self.cache.invalidate_records().await;
+        Ok(profile_of(idol, profile.aliases))
+    }
+
     async fn delete_idol(&self, id: i64) -> Result<String, AppError> {
         let txn = self.db.begin().await.map_err(AppError::DatabaseError)?;
 
@@ -226,15 +392,58 @@ impl IdolServiceTrait for IdolService {
             .map_err(AppError::DatabaseError)?;
 
         txn.commit().await.map_err(AppError::DatabaseError)?;
+        self.events
+            .publish_entity(SearchEntityType::Idol, id, CatalogAction::Deleted);
+        self.cache
+            .invalidate_entities(SearchEntityType::Idol, &[id])
+            .await;
         Ok("Idol deleted successfully".to_owned())
     }
 
     /// Gets record counts grouped by idols.
-    async fn get_idol_record_counts(&self) -> Result<Vec<EntityCountDto>, AppError> {
-        self.repo
-            .get_idol_record_counts(&self.db)
+    async fn merge_idol(&self, id: i64, source_id: i64) -> Result<MergeEntityResponse, AppError> {
+        let source_name = self
+            .repo
+            .find_by_id(&self.db, source_id)
             .await
-            .map_err(AppError::DatabaseError)
+            .map_err(AppError::DatabaseError)?
+            .map(|idol| idol.name);
+        let merged = merge_named_entity(
+            &self.db,
+            &*self.merge_repo,
+            &self.events,
+            &self.cache,
+            SearchEntityType::Idol,
+            id,
+            source_id,
+        )
+        .await?;
+
+        if let Some(source_name) = source_name {
+            match self.repo.find_by_id(&self.db, merged.id).await {
+                Ok(Some(target)) => self.move_idol_media(&source_name, &target.name).await,
+                Ok(None) => {}
+                Err(e) => tracing::warn!("Failed to load merged idol {}: {e}", merged.id),
+            }
+        }
+        Ok(merged)
+    }
+
+    async fn get_idol_record_counts(
+        &self,
+        query: RecordCountQuery,
+    ) -> Result<PaginatedResponse<EntityCountDto>, AppError> {
+        let load = || async {
+            self.repo
+                .get_idol_record_counts(&self.db, &query)
+                .await
+                .map_err(AppError::DatabaseError)
+        };
+        if query.is_first_page() {
+            self.cache.record_counts(SearchEntityType::Idol, load).await
+        } else {
+            load().await
+        }
     }
 
     /// Gets idols that don't have any images in the media directory.
@@ -304,3 +513,99 @@ impl IdolServiceTrait for IdolService {
         Ok(idols_without_images)
     }
 }
+
+impl IdolService {
+    /// Moves the images of the idol named `from` into the media directory of
+    /// the idol named `to`. Files named `{from}` or `{from}_{n}` are renamed
+    /// after `to`, and a name already taken there gets the next free sequence
+    /// number. Thumbnails cached for `from` are dropped. Failures are logged,
+    /// since the rename or merge is already committed.
+    async fn move_idol_media(&self, from: &str, to: &str) {
+        if from == to || !is_safe_media_id(from) || !is_safe_media_id(to) {
+            return;
+        }
+        let media_type = MediaType::IdolImage;
+        let source_dir = media_dir_key(&media_type, from);
+        let target_dir = media_dir_key(&media_type, to);
+        let files = match self.storage.list(&source_dir).await {
+            Ok(files) => files,
+            Err(e) => {
+                tracing::warn!("Failed to list media dir {source_dir}: {e}");
+                return;
+            }
+        };
+        let mut taken: HashSet<String> = match self.storage.list(&target_dir).await {
+            Ok(existing) => existing
+                .into_iter()
+                .map(|(name, _)| split_extension(&name).0.to_owned())
+                .collect(),
+            Err(e) => {
+                tracing::warn!("Failed to list media dir {target_dir}: {e}");
+                return;
+            }
+        };
+
+        for (filename, _) in files {
+            let (stem, extension) = split_extension(&filename);
+            let mut new_stem = match stem.strip_prefix(from) {
+                Some("") => to.to_owned(),
+                Some(rest) if rest.starts_with('_') => format!("{to}{rest}"),
+                _ => stem.to_owned(),
+            };
+            let mut seq = 1;
+            while taken.contains(&new_stem) {
+                new_stem = format!("{to}_{seq}");
+                seq += 1;
+            }
+            let new_name = match extension {
+                Some(extension) => format!("{new_stem}.{extension}"),
+                None => new_stem.clone(),
+            };
+            taken.insert(new_stem);
+
+            let key = media_key(&media_type, from, &filename);
+            let new_key = media_key(&media_type, to, &new_name);
+            match self.storage.rename(&key, &new_key).await {
+                Ok(true) => {
+                    if let Err(e) = self
+                        .media_files
+                        .rename_key(&self.db, &key, &new_key, to, &new_name)
+                        .await
+                    {
+                        tracing::warn!("Failed to re-key stored media file {key}: {e}");
+                    }
+                }
+                Ok(false) => tracing::warn!("Media file {new_key} already exists; kept {key}"),
+                Err(e) => tracing::warn!("Failed to move media file {key} to {new_key}: {e}"),
+            }
+        }
+
+        // Only removed once every file has moved.
+        match self.storage.list(&source_dir).await {
+            Ok(left) if left.is_empty() => {
+                if let Err(e) = self.storage.delete_dir(&source_dir).await {
+                    tracing::warn!("Failed to remove media dir {source_dir}: {e}");
+                }
+            }
+            Ok(_) => {}
+            Err(e) => tracing::warn!("Failed to list media dir {source_dir}: {e}"),
+        }
+        let thumbnails = Path::new(&self.config.assets_private_path)
+            .join("thumbnails")
+            .join(media_type.get_sub_dir_name())
+            .join(from);
+        if let Err(e) = fs::remove_dir_all(&thumbnails).await {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                tracing::warn!("Failed to remove thumbnails {}: {e}", thumbnails.display());
+            }
+        }
+    }
+}
+
+/// Splits `filename` into its stem and extension, if it has one.
+fn split_extension(filename: &str) -> (&str, Option<&str>) {
+    match filename.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() => (stem, Some(extension)),
+        _ => (filename, None),
+    }
+}
@@ -0,0 +1,328 @@
+use super::file::count_media_files;
+use crate::{
+    common::{config::Config, error::AppError},
+    domains::luna::{
+        domain::{
+            is_safe_media_id, IntegrityRepository, IntegrityServiceTrait, MediaFileRepository,
+        },
+        dto::{
+            IntegrityQuery, IntegrityReportDto, MediaGcReportDto, MediaType, OrphanedMediaDirDto,
+            ReconcileImagesResponse, RecordIssueDto, DEFAULT_INTEGRITY_SAMPLE,
+            MAX_INTEGRITY_SAMPLE,
+        },
+        infra::{
+            catalog_cache::CatalogCache,
+            media_storage::{self, media_dir_key, MediaStorage},
+            IntegrityRepo, MediaFileRepo,
+        },
+    },
+};
+use async_trait::async_trait;
+use sea_orm::DatabaseConnection;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::fs;
+
+/// Media directory whose owner is gone: its type, owner, path and contents.
+type OrphanedDir = (MediaType, String, PathBuf, OrphanedMediaDirDto);
+
+/// Service struct for the data-quality report.
+#[derive(Clone)]
+pub struct IntegrityService {
+    db: DatabaseConnection,
+    config: Config,
+    repo: Arc<dyn IntegrityRepository>,
+    cache: Arc<CatalogCache>,
+    storage: Arc<dyn MediaStorage>,
+    media_files: Arc<dyn MediaFileRepository>,
+}
+
+impl IntegrityService {
+    pub fn create_service(
+        db: DatabaseConnection,
+        config: Config,
+        cache: Arc<CatalogCache>,
+    ) -> Arc<dyn IntegrityServiceTrait> {
+        Arc::new(Self {
+            db,
+            storage: media_storage::from_config(&config),
+            config,
+            repo: Arc::new(IntegrityRepo),
+            cache,
+            media_files: Arc::new(MediaFileRepo),
+        })
+    }
+
+    /// Counts the media files of every live record. Returns how many records
+    /// were scanned and `(id, files)` of those whose `local_img_count`
+    /// differs, by ID. Directories that cannot be read are logged and skipped.
+    async fn scan_image_counts(&self) -> Result<(u64, Vec<(String, i32)>), AppError> {
+        let counts = self
+            .repo
+            .image_counts(&self.db)
+            .await
+            .map_err(AppError::DatabaseError)?;
+        let base_dir = Path::new(&self.config.assets_private_path)
+            .join("images")
+            .join(MediaType::RecordImage.get_sub_dir_name());
+
+        let mut scanned = 0;
+        let mut mismatched = Vec::new();
+        for (id, local_img_count) in counts {
+            if !is_safe_media_id(&id) {
+                continue;
+            }
+            let dir = base_dir.join(&id);
+            match count_media_files(&dir).await {
+                Ok(files) => {
+                    scanned += 1;
+                    if files != local_img_count {
+                        mismatched.push((id, files));
+                    }
+                }
+                Err(e) => tracing::warn!("Failed to read media dir {}: {e}", dir.display()),
+            }
+        }
+        Ok((scanned, mismatched))
+    }
+
+    /// Record video, record image and idol image directories on the local
+    /// disk with no record (trashed included) or idol of their name, by key.
+    /// Directories that cannot be read are logged and skipped.
+    async fn scan_orphaned_dirs(&self) -> Result<Vec<OrphanedDir>, AppError> {
+        let records: HashSet<String> = self
+            .repo
+            .record_ids(&self.db)
+            .await
+            .map_err(AppError::DatabaseError)?
+            .into_iter()
+            .collect();
+        let idols: HashSet<String> = self
+            .repo
+            .idol_names(&self.db)
+            .await
+            .map_err(AppError::DatabaseError)?
+            .into_iter()
+            .collect();
+
+        let mut orphans = Vec::new();
+        for media_type in [
+            MediaType::RecordImage,
+            MediaType::RecordVideo,
+            MediaType::IdolImage,
+        ] {
+            let owners = match media_type {
+                MediaType::IdolImage => &idols,
+                MediaType::RecordImage | MediaType::RecordVideo => &records,
+            };
+            let base_dir = Path::new(&self.config.assets_private_path)
+                .join(media_type.get_root_dir_name())
+                .join(media_type.get_sub_dir_name());
+            let owner_dirs = match list_dirs(&base_dir).await {
+                Ok(dirs) => dirs,
+                Err(e) => {
+                    tracing::warn!("Failed to read media dir {}: {e}", base_dir.display());
+                    continue;
+                }
+            };
+            for owner in owner_dirs.into_iter().filter(|dir| !owners.contains(dir)) {
+                let dir = base_dir.join(&owner);
+                let (files, bytes) = match dir_usage(&dir).await {
+                    Ok(usage) => usage,
+                    Err(e) => {
+                        tracing::warn!("Failed to read media dir {}: {e}", dir.display());
+                        continue;
+                    }
+                };
+                let report = OrphanedMediaDirDto {
+                    key: media_dir_key(&media_type, &owner),
+                    files,
+                    bytes,
+                };
+                orphans.push((media_type.clone(), owner, dir, report));
+            }
+        }
+        orphans.sort_by(|a, b| a.3.key.cmp(&b.3.key));
+        Ok(orphans)
+    }
+
+    /// Removes the orphaned directories, the thumbnails cached for their
+    /// images and the stored-file rows of both. Returns the number of
+    /// directories and rows removed; failed removals are logged and not
+    /// counted.
+    async fn delete_orphaned_media(
+        &self,
+        orphans: &[OrphanedDir],
+        missing_files: &[String],
+    ) -> Result<(u64, u64), AppError> {
+        let mut deleted_dirs = 0;
+        let mut owners_by_type: Vec<(String, Vec<String>)> = Vec::new();
+        for (media_type, owner, dir, _) in orphans {
+            match fs::remove_dir_all(dir).await {
+                Ok(()) => deleted_dirs += 1,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => {
+                    tracing::warn!("Failed to remove media dir {}: {e}", dir.display());
+                    continue;
+                }
+            }
+            if matches!(media_type, MediaType::RecordVideo) {
+                continue;
+            }
+            let thumbnails = Path::new(&self.config.assets_private_path)
+                .join("thumbnails")
+                .join(media_type.get_sub_dir_name())
+                .join(owner);
+            if let Err(e) = fs::remove_dir_all(&thumbnails).await {
+                if e.kind() != std::io::ErrorKind::NotFound {
+                    tracing::warn!("Failed to remove thumbnails {}: {e}", thumbnails.display());
+                }
+            }
+            let owner_type = media_type.get_sub_dir_name();
+            match owners_by_type.iter_mut().find(|(ty, _)| *ty == owner_type) {
+                Some((_, owners)) => owners.push(owner.clone()),
+                None => owners_by_type.push((owner_type, vec![owner.clone()])),
+            }
+        }
+
+        let mut deleted_rows = self
+            .media_files
+            .delete_keys(&self.db, missing_files)
+            .await
+            .map_err(AppError::DatabaseError)?;
+        for (owner_type, owners) in owners_by_type {
+            deleted_rows += self
+                .media_files
+                .delete_by_owners(&self.db, &owner_type, &owners)
+                .await
+                .map_err(AppError::DatabaseError)?;
+        }
+        Ok((deleted_dirs, deleted_rows))
+    }
+}
+
+#[async_trait]
+impl IntegrityServiceTrait for IntegrityService {
+    async fn integrity_report(
+        &self,
+        query: IntegrityQuery,
+    ) -> Result<IntegrityReportDto, AppError> {
+        let sample = query.sample.unwrap_or(DEFAULT_INTEGRITY_SAMPLE);
+        if sample == 0 {
+            return Err(AppError::ValidationError("sample must be > 0".into()));
+        }
+        let sample = sample.min(MAX_INTEGRITY_SAMPLE);
+
+        let placeholder_references = self
+            .repo
+            .placeholder_references(&self.db, sample)
+            .await
+            .map_err(AppError::DatabaseError)?;
+        let has_links_without_links = self
+            .repo
+            .has_links_without_links(&self.db, sample)
+            .await
+            .map_err(AppError::DatabaseError)?;
+        let orphaned_rows = self
+            .repo
+            .orphaned_rows(&self.db, sample)
+            .await
+            .map_err(AppError::DatabaseError)?;
+        let (_, mismatched) = self.scan_image_counts().await?;
+        let local_img_count_mismatch = RecordIssueDto {
+            count: mismatched.len() as u64,
+            sample_ids: mismatched
+                .into_iter()
+                .take(sample as usize)
+                .map(|(id, _)| id)
+                .collect(),
+        };
+
+        Ok(IntegrityReportDto {
+            placeholder_references,
+            has_links_without_links,
+            local_img_count_mismatch,
+            orphaned_rows,
+        })
+    }
+
+    async fn reconcile_image_counts(&self) -> Result<ReconcileImagesResponse, AppError> {
+        let (scanned, mismatched) = self.scan_image_counts().await?;
+        let updated = self
+            .repo
+            .update_image_counts(&self.db, mismatched)
+            .await
+            .map_err(AppError::DatabaseError)?;
+        if updated > 0 {
+            self.cache.invalidate_records().await;
+        }
+        Ok(ReconcileImagesResponse { scanned, updated })
+    }
+
+    async fn collect_orphaned_media(&self, dry_run: bool) -> Result<MediaGcReportDto, AppError> {
+        let orphans = self.scan_orphaned_dirs().await?;
+
+        let mut missing_files = Vec::new();
+        for key in self
+            .media_files
+            .keys(&self.db)
+            .await
+            .map_err(AppError::DatabaseError)?
+        {
+            let stored = self.storage.stat(&key).await.map_err(|e| {
+                tracing::error!("Media storage error: {e}");
+                AppError::InternalError
+            })?;
+            if stored.is_none() {
+                missing_files.push(key);
+            }
+        }
+
+        let (deleted_dirs, deleted_rows) = if dry_run {
+            (0, 0)
+        } else {
+            self.delete_orphaned_media(&orphans, &missing_files).await?
+        };
+        Ok(MediaGcReportDto {
+            dry_run,
+            orphaned_dirs: orphans.into_iter().map(|(_, _, _, dir)| dir).collect(),
+            missing_files,
+            deleted_dirs,
+            deleted_rows,
+        })
+    }
+}
+
+/// Names of the directories directly under `dir`; none when it does not
+/// exist.
+async fn list_dirs(dir: &Path) -> std::io::Result<Vec<String>> {
+    let mut entries = match fs::read_dir(dir).await {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let mut dirs = Vec::new();
+    while let Some(entry) = entries.next_entry().await? {
+        if entry.file_type().await?.is_dir() {
+            if let Ok(name) = entry.file_name().into_string() {
+                dirs.push(name);
+            }
+        }
+    }
+    Ok(dirs)
+}
+
+/// Number and total size of the regular files directly under `dir`.
+async fn dir_usage(dir: &Path) -> std::io::Result<(u64, u64)> {
+    let mut entries = fs::read_dir(dir).await?;
+    let (mut files, mut bytes) = (0, 0);
+    while let Some(entry) = entries.next_entry().await? {
+        let metadata = entry.metadata().await?;
+        if metadata.is_file() {
+            files += 1;
+            bytes += metadata.len();
+        }
+    }
+    Ok((files, bytes))
+}
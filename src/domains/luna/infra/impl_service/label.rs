@@ -1,13 +1,21 @@
+use super::merge::merge_named_entity;
 use crate::domains::search::SearchEntityType;
 use crate::{
-    common::error::AppError,
+    common::{error::AppError, pagination::resolve_ordering},
     domains::luna::{
-        domain::{LabelAffinityRepository, LabelRepository, LabelServiceTrait},
+        domain::{
+            LabelAffinityRepository, LabelRepository, LabelServiceTrait,
+            NamedEntityMergeRepository, StudioHierarchyRepository,
+        },
         dto::{
-            CreateLabelDto, EntityCountDto, LabelDto, PaginatedResponse, PaginationQuery,
-            SearchLabelDto, UpdateLabelDto,
+            AutocompleteQuery, CatalogAction, CreateLabelDto, EntityCountDto, LabelDto,
+            MergeEntityResponse, NameSuggestionDto, PaginatedResponse, PaginationQuery,
+            RecordCountQuery, SearchLabelDto, UpdateLabelDto, ENTITY_ORDERING_FIELDS,
+        },
+        infra::{
+            catalog_cache::CatalogCache, catalog_events::CatalogEvents, search_outbox, LabelRepo,
+            StudioRepo,
         },
-        infra::{search_outbox, LabelRepo},
     },
 };
 use async_trait::async_trait;
@@ -23,25 +31,70 @@ pub struct LabelService {
     /// object cannot expose `LabelAffinityRepository` methods, so the affinity
     /// trait needs its own trait object (both wrap `LabelRepo`).
     affinity_repo: Arc<dyn LabelAffinityRepository + Send + Sync>,
+    /// Merge handle; also wraps `LabelRepo`.
+    merge_repo: Arc<dyn NamedEntityMergeRepository + Send + Sync>,
+    /// Studio hierarchy handle, which also places labels; wraps `StudioRepo`.
+    hierarchy_repo: Arc<dyn StudioHierarchyRepository + Send + Sync>,
+    events: Arc<CatalogEvents>,
+    cache: Arc<CatalogCache>,
 }
 
 #[async_trait]
 impl LabelServiceTrait for LabelService {
-    fn create_service(db: DatabaseConnection) -> Arc<dyn LabelServiceTrait> {
+    fn create_service(
+        db: DatabaseConnection,
+        events: Arc<CatalogEvents>,
+        cache: Arc<CatalogCache>,
+    ) -> Arc<dyn LabelServiceTrait> {
         Arc::new(Self {
             db,
             repo: Arc::new(LabelRepo {}),
             affinity_repo: Arc::new(LabelRepo {}),
+            merge_repo: Arc::new(LabelRepo {}),
+            hierarchy_repo: Arc::new(StudioRepo),
+            events,
+            cache,
         })
     }
 
     async fn get_label_by_id(&self, id: i64) -> Result<LabelDto, AppError> {
-        self.repo
-            .find_by_id(&self.db, id)
+        self.cache
+            .entity(SearchEntityType::Label, id, || async {
+                self.repo
+                    .find_by_id(&self.db, id)
+                    .await
+                    .map_err(AppError::DatabaseError)?
+                    .map(LabelDto::from)
+                    .ok_or_else(|| AppError::NotFound("Label not found".into()))
+            })
+            .await
+    }
+
+    async fn get_labels_by_ids(&self, ids: &[i64]) -> Result<Vec<LabelDto>, AppError> {
+        let labels = self
+            .repo
+            .find_by_ids(&self.db, ids)
+            .await
+            .map_err(AppError::DatabaseError)?;
+        Ok(labels.into_iter().map(LabelDto::from).collect())
+    }
+
+    async fn autocomplete_labels(
+        &self,
+        query: AutocompleteQuery,
+    ) -> Result<Vec<NameSuggestionDto>, AppError> {
+        let term = query
+            .term()
+            .ok_or_else(|| AppError::ValidationError("q must not be blank".into()))?;
+        let limit = query.limit();
+        self.cache
+            .suggestions(SearchEntityType::Label, &term, limit, || async {
+                self.repo
+                    .autocomplete(&self.db, &term, limit)
+                    .await
+                    .map_err(AppError::DatabaseError)
+            })
             .await
-            .map_err(AppError::DatabaseError)?
-            .map(LabelDto::from)
-            .ok_or_else(|| AppError::NotFound("Label not found".into()))
     }
 
     async fn get_label_list(&self, search_dto: SearchLabelDto) -> Result<Vec<LabelDto>, AppError> {
@@ -54,6 +107,7 @@ impl LabelServiceTrait for LabelService {
         search_dto: SearchLabelDto,
         pagination: PaginationQuery,
     ) -> Result<PaginatedResponse<LabelDto>, AppError> {
+        resolve_ordering(&pagination, ENTITY_ORDERING_FIELDS)?;
         let paginated = self
             .repo
             .find_list_paginated(&self.db, search_dto, pagination)
@@ -62,13 +116,21 @@ impl LabelServiceTrait for LabelService {
             count: paginated.count,
             next: paginated.next,
             previous: paginated.previous,
+            next_cursor: paginated.next_cursor,
             results: paginated.results.into_iter().map(Into::into).collect(),
         })
     }
 
     async fn get_labels(&self) -> Result<Vec<LabelDto>, AppError> {
-        let labels = self.repo.find_all(&self.db).await?;
-        Ok(labels.into_iter().map(Into::into).collect())
+        self.cache
+            .entity_list(SearchEntityType::Label, || async {
+                self.repo
+                    .find_all(&self.db)
+                    .await
+                    .map(|labels| labels.into_iter().map(LabelDto::from).collect())
+                    .map_err(AppError::DatabaseError)
+            })
+            .await
     }
 
     async fn get_label_list_by_affinity(
@@ -77,6 +139,7 @@ impl LabelServiceTrait for LabelService {
         pagination: PaginationQuery,
         user_id: String,
     ) -> Result<PaginatedResponse<LabelDto>, AppError> {
+        resolve_ordering(&pagination, ENTITY_ORDERING_FIELDS)?;
         let paginated = self
             .affinity_repo
             .find_list_paginated_by_affinity(&self.db, search_dto, pagination, &user_id)
@@ -86,6 +149,7 @@ impl LabelServiceTrait for LabelService {
             count: paginated.count,
             next: paginated.next,
             previous: paginated.previous,
+            next_cursor: paginated.next_cursor,
             results: paginated.results.into_iter().map(Into::into).collect(),
         })
     }
@@ -114,6 +178,13 @@ impl LabelServiceTrait for LabelService {
         }
 
         txn.commit().await?;
+        if was_created {
+            self.events
+                .publish_entity(SearchEntityType::Label, label_id, CatalogAction::Created);
+            self.cache
+                .invalidate_entities(SearchEntityType::Label, &[label_id])
+                .await;
+        }
         self.get_label_by_id(label_id).await
     }
 
@@ -185,16 +256,106 @@ impl LabelServiceTrait for LabelService {
         }
 
         txn.commit().await?;
+        if surviving_id != id {
+            self.events
+                .publish_entity(SearchEntityType::Label, id, CatalogAction::Deleted);
+        }
+        self.events.publish_entity(
+            SearchEntityType::Label,
+            surviving_id,
+            CatalogAction::Updated,
+        );
+        self.cache
+            .invalidate_entities(SearchEntityType::Label, &[id, surviving_id])
+            .await;
         Ok(LabelDto::from(label))
     }
 
-    async fn delete_label(&self, id: i64) -> Result<String, AppError> {
+    async fn set_label_studio(
+        &self,
+        id: i64,
+        studio_id: Option<i64>,
+    ) -> Result<LabelDto, AppError> {
+        if id == 0 || studio_id == Some(0) {
+            return Err(AppError::ValidationError(
+                "Placeholder labels and studios cannot be part of a hierarchy".into(),
+            ));
+        }
+
+        let txn = self.db.begin().await?;
+        if let Some(studio_id) = studio_id {
+            // A studio without a lineage does not exist
+            match self.hierarchy_repo.find_lineage(&txn, studio_id).await {
+                Ok(lineage) if lineage.is_empty() => {
+                    txn.rollback().await?;
+                    return Err(AppError::NotFound("Studio not found".into()));
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    txn.rollback().await.ok();
+                    return Err(AppError::DatabaseError(e));
+                }
+            }
+        }
+        let label = match self
+            .hierarchy_repo
+            .set_label_studio(&txn, id, studio_id)
+            .await
+        {
+            Ok(Some(label)) => label,
+            Ok(None) => {
+                txn.rollback().await?;
+                return Err(AppError::NotFound("Label not found".into()));
+            }
+            Err(e) => {
+                txn.rollback().await.ok();
+                return Err(AppError::DatabaseError(e));
+            }
+        };
+        txn.commit().await?;
+
+        self.events
+            .publish_entity(SearchEntityType::Label, id, CatalogAction::Updated);
+        // Hydrated records carry their label
+        self.cache
+            .invalidate_entities(SearchEntityType::Label, &[id])
+            .await;
+        self.cache.invalidate_records().await;
+        Ok(LabelDto::from(label))
+    }
+
+    async fn delete_label(&self, id: i64, reassign_to: Option<i64>) -> Result<String, AppError> {
+        if let Some(target_id) = reassign_to {
+            merge_named_entity(
+                &self.db,
+                &*self.merge_repo,
+                &self.events,
+                &self.cache,
+                SearchEntityType::Label,
+                target_id,
+                id,
+            )
+            .await?;
+            return Ok("Label deleted successfully".into());
+        }
+
         let txn = self.db.begin().await?;
 
         let affected = search_outbox::find_affected_record_ids(&txn, SearchEntityType::Label, id)
             .await
             .map_err(AppError::DatabaseError)?;
 
+        if !affected.is_empty() {
+            txn.rollback().await?;
+            return Err(AppError::ConflictWithRecords(
+                format!(
+                    "Label {id} is still referenced by {} record(s)",
+                    affected.len()
+                ),
+                affected,
+            ));
+        }
+
         match self.repo.delete(&txn, id).await {
             Ok(true) => {}
             Ok(false) => {
@@ -215,13 +376,43 @@ impl LabelServiceTrait for LabelService {
             .map_err(AppError::DatabaseError)?;
 
         txn.commit().await?;
+        self.events
+            .publish_entity(SearchEntityType::Label, id, CatalogAction::Deleted);
+        self.cache
+            .invalidate_entities(SearchEntityType::Label, &[id])
+            .await;
         Ok("Label deleted successfully".to_owned())
     }
 
-    async fn get_label_record_counts(&self) -> Result<Vec<EntityCountDto>, AppError> {
-        self.repo
-            .get_label_record_counts(&self.db)
-            .await
-            .map_err(AppError::DatabaseError)
+    async fn merge_label(&self, id: i64, source_id: i64) -> Result<MergeEntityResponse, AppError> {
+        merge_named_entity(
+            &self.db,
+            &*self.merge_repo,
+            &self.events,
+            &self.cache,
+            SearchEntityType::Label,
+            id,
+            source_id,
+        )
+        .await
+    }
+
+    async fn get_label_record_counts(
+        &self,
+        query: RecordCountQuery,
+    ) -> Result<PaginatedResponse<EntityCountDto>, AppError> {
+        let load = || async {
+            self.repo
+                .get_label_record_counts(&self.db, &query)
+                .await
+                .map_err(AppError::DatabaseError)
+        };
+        if query.is_first_page() {
+            self.cache
+                .record_counts(SearchEntityType::Label, load)
+                .await
+        } else {
+            load().await
+        }
     }
 }
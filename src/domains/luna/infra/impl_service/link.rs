@@ -0,0 +1,372 @@
+use crate::{
+    common::{
+        config::{Config, DEFAULT_PAGE_SIZE},
+        error::AppError,
+    },
+    domains::luna::{
+        domain::{Link, LinkRepository, LinkServiceTrait, RecordRepository, RevisionRepository},
+        dto::{
+            normalize_link_url, CatalogAction, LinkCheckReportDto, LinkDto, LinkSearchQuery,
+            PaginatedResponse, PaginationQuery, PatchLinkDto,
+        },
+        infra::{
+            catalog_cache::CatalogCache, catalog_events::CatalogEvents, LinkRepo, RecordRepo,
+            RevisionRepo,
+        },
+    },
+    domains::search::SearchEntityType,
+};
+use async_trait::async_trait;
+use chrono::Utc;
+use futures::{stream, StreamExt as _};
+use reqwest::StatusCode;
+use sea_orm::{DatabaseConnection, TransactionTrait as _};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// What requesting a link found: the status it answered with, unset when
+/// the request failed, and whether that makes the link dead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Probe {
+    status: Option<i32>,
+    dead: bool,
+}
+
+/// Service struct for record links and their health checks.
+#[derive(Clone)]
+pub struct LinkService {
+    db: DatabaseConnection,
+    repo: Arc<dyn LinkRepository>,
+    records: Arc<dyn RecordRepository>,
+    revisions: Arc<dyn RevisionRepository>,
+    events: Arc<CatalogEvents>,
+    cache: Arc<CatalogCache>,
+    client: reqwest::Client,
+    concurrency: usize,
+    batch_size: u64,
+}
+
+impl LinkService {
+    pub fn create_service(
+        db: DatabaseConnection,
+        config: &Config,
+        events: Arc<CatalogEvents>,
+        cache: Arc<CatalogCache>,
+    ) -> Arc<dyn LinkServiceTrait> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.link_check_timeout_secs))
+            .build()
+            .unwrap_or_default();
+        Arc::new(Self {
+            db,
+            repo: Arc::new(LinkRepo),
+            records: Arc::new(RecordRepo),
+            revisions: Arc::new(RevisionRepo),
+            events,
+            cache,
+            client,
+            concurrency: config.link_check_concurrency.max(1),
+            batch_size: config.link_check_batch_size,
+        })
+    }
+
+    /// Requests `links`, `concurrency` at a time, and stores the outcomes.
+    /// Returns the links as updated, in the order their checks finished.
+    async fn check(&self, links: Vec<Link>) -> Result<(Vec<Link>, LinkCheckReportDto), AppError> {
+        let probes: Vec<(Link, Probe)> = stream::iter(links)
+            .map(|link| async move {
+                let probe = probe(&self.client, &link.link).await;
+                (link, probe)
+            })
+            .buffer_unordered(self.concurrency)
+            .collect()
+            .await;
+
+        let mut report = LinkCheckReportDto::default();
+        let mut checked = Vec::with_capacity(probes.len());
+        for (mut link, probe) in probes {
+            let checked_at = Utc::now();
+            self.repo
+                .record_check(&self.db, link.id, probe.status, probe.dead, checked_at)
+                .await
+                .map_err(AppError::DatabaseError)?;
+            report.checked += 1;
+            if probe.dead {
+                report.dead += 1;
+            } else if link.dead {
+                report.revived += 1;
+            }
+            link.dead = probe.dead;
+            link.last_status = probe.status;
+            link.last_checked_at = Some(checked_at);
+            checked.push(link);
+        }
+        // Hydrated records carry the health of their links
+        if report.checked > 0 {
+            self.cache.invalidate_records().await;
+        }
+        Ok((checked, report))
+    }
+
+    /// Announces that the links of `record_id` changed and drops cached
+    /// records.
+    async fn links_changed(&self, record_id: &str) {
+        // An unknown permission is treated as the most restrictive level.
+        let permission = self
+            .records
+            .find_permission(&self.db, record_id)
+            .await
+            .ok()
+            .flatten()
+            .unwrap_or(i32::MAX);
+        self.events.publish(
+            SearchEntityType::Record,
+            record_id,
+            CatalogAction::Updated,
+            Some(permission),
+        );
+        self.cache.invalidate_records().await;
+    }
+}
+
+/// Page of `links` starting at `offset` out of `total` matches.
+fn link_page(
+    links: Vec<Link>,
+    total: u64,
+    page_size: u64,
+    offset: u64,
+) -> PaginatedResponse<LinkDto> {
+    let next_offset = offset + page_size;
+    let next = (next_offset < total).then(|| format!("?limit={page_size}&offset={next_offset}"));
+    let previous = (offset > 0).then(|| {
+        format!(
+            "?limit={page_size}&offset={}",
+            offset.saturating_sub(page_size)
+        )
+    });
+
+    PaginatedResponse {
+        count: total as i64,
+        next,
+        previous,
+        next_cursor: None,
+        results: links.into_iter().map(LinkDto::from).collect(),
+    }
+}
+
+/// Whether `url` is a link the checker can request.
+fn is_http(url: &str) -> bool {
+    url.starts_with("http://") || url.starts_with("https://")
+}
+
+/// Whether a link answering `status` is dead. Rate limiting says nothing
+/// about the link, so `429` is not.
+fn is_dead(status: StatusCode) -> bool {
+    (status.is_client_error() || status.is_server_error())
+        && status != StatusCode::TOO_MANY_REQUESTS
+}
+
+/// Requests `url` with `HEAD`, or with `GET` when the server does not
+/// allow `HEAD`. Only the status is read.
+async fn probe(client: &reqwest::Client, url: &str) -> Probe {
+    let mut response = client.head(url).send().await;
+    if let Ok(head) = &response {
+        if matches!(
+            head.status(),
+            StatusCode::METHOD_NOT_ALLOWED | StatusCode::NOT_IMPLEMENTED
+        ) {
+            response = client.get(url).send().await;
+        }
+    }
+    match response {
+        Ok(response) => Probe {
+            status: Some(i32::from(response.status().as_u16())),
+            dead: is_dead(response.status()),
+        },
+        Err(err) => {
+            tracing::debug!("Link check of {url} failed: {err}");
+            Probe {
+                status: None,
+                dead: true,
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl LinkServiceTrait for LinkService {
+    async fn dead_links(
+        &self,
+        pagination: PaginationQuery,
+    ) -> Result<PaginatedResponse<LinkDto>, AppError> {
+        let page_size = pagination
+            .limit
+            .filter(|&l| l > 0)
+            .map_or(DEFAULT_PAGE_SIZE, |l| l as u64);
+        let current_offset = pagination.offset.unwrap_or(0).max(0) as u64;
+
+        let (links, total) = self
+            .repo
+            .find_dead_paginated(&self.db, page_size, current_offset)
+            .await
+            .map_err(AppError::DatabaseError)?;
+        Ok(link_page(links, total, page_size, current_offset))
+    }
+
+    async fn search_links(
+        &self,
+        query: LinkSearchQuery,
+        max_permission: i32,
+    ) -> Result<PaginatedResponse<LinkDto>, AppError> {
+        let page_size = query
+            .limit
+            .filter(|&limit| limit > 0)
+            .unwrap_or(DEFAULT_PAGE_SIZE);
+        let offset = query.offset.unwrap_or(0);
+
+        let (links, total) = self
+            .repo
+            .search(&self.db, &query, max_permission, page_size, offset)
+            .await
+            .map_err(AppError::DatabaseError)?;
+        Ok(link_page(links, total, page_size, offset))
+    }
+
+    async fn check_link(&self, id: i64) -> Result<LinkDto, AppError> {
+        let link = self
+            .repo
+            .find_by_id(&self.db, id)
+            .await
+            .map_err(AppError::DatabaseError)?
+            .ok_or_else(|| AppError::NotFound("Link not found".into()))?;
+        if !is_http(&link.link) {
+            return Err(AppError::ValidationError(
+                "Only http and https links can be checked".into(),
+            ));
+        }
+        let (mut checked, _) = self.check(vec![link]).await?;
+        let link = checked.pop().expect("one link was checked");
+        Ok(LinkDto::from(link))
+    }
+
+    async fn check_record_links(&self, record_id: &str) -> Result<Vec<LinkDto>, AppError> {
+        let links = self
+            .repo
+            .find_by_record(&self.db, record_id)
+            .await
+            .map_err(AppError::DatabaseError)?;
+        let (http, other): (Vec<Link>, Vec<Link>) =
+            links.into_iter().partition(|link| is_http(&link.link));
+        let (mut links, _) = self.check(http).await?;
+        links.extend(other);
+        links.sort_by_key(|link| link.id);
+        Ok(links.into_iter().map(LinkDto::from).collect())
+    }
+
+    async fn check_stale_links(&self) -> Result<LinkCheckReportDto, AppError> {
+        let links = self
+            .repo
+            .find_least_recently_checked(&self.db, self.batch_size)
+            .await
+            .map_err(AppError::DatabaseError)?;
+        let (_, report) = self.check(links).await?;
+        Ok(report)
+    }
+
+    async fn update_link(
+        &self,
+        record_id: &str,
+        id: i64,
+        mut patch: PatchLinkDto,
+        actor: &str,
+    ) -> Result<LinkDto, AppError> {
+        if let Some(url) = patch.link.as_mut() {
+            *url = normalize_link_url(url);
+            if url.is_empty() {
+                return Err(AppError::ValidationError("Link URL cannot be empty".into()));
+            }
+            let taken = self
+                .repo
+                .find_by_record(&self.db, record_id)
+                .await
+                .map_err(AppError::DatabaseError)?
+                .iter()
+                .any(|link| {
+                    link.id != id && normalize_link_url(&link.link).eq_ignore_ascii_case(url)
+                });
+            if taken {
+                return Err(AppError::Conflict(
+                    "The record already has a link with this URL".into(),
+                ));
+            }
+        }
+
+        let txn = self.db.begin().await.map_err(AppError::DatabaseError)?;
+        if let Err(e) = self.revisions.snapshot(&txn, record_id, actor).await {
+            txn.rollback().await.ok();
+            return Err(AppError::DatabaseError(e));
+        }
+        let link = match self.repo.update(&txn, record_id, id, patch).await {
+            Ok(Some(link)) => link,
+            Ok(None) => {
+                txn.rollback().await.ok();
+                return Err(AppError::NotFound("Link not found".into()));
+            }
+            Err(e) => {
+                txn.rollback().await.ok();
+                return Err(AppError::DatabaseError(e));
+            }
+        };
+        txn.commit().await.map_err(AppError::DatabaseError)?;
+
+        self.links_changed(record_id).await;
+        Ok(LinkDto::from(link))
+    }
+
+    async fn delete_link(&self, record_id: &str, id: i64, actor: &str) -> Result<(), AppError> {
+        let txn = self.db.begin().await.map_err(AppError::DatabaseError)?;
+        if let Err(e) = self.revisions.snapshot(&txn, record_id, actor).await {
+            txn.rollback().await.ok();
+            return Err(AppError::DatabaseError(e));
+        }
+        let deleted = match self.repo.delete(&txn, record_id, id).await {
+            Ok(deleted) => deleted,
+            Err(e) => {
+                txn.rollback().await.ok();
+                return Err(AppError::DatabaseError(e));
+            }
+        };
+        if !deleted {
+            txn.rollback().await.map_err(AppError::DatabaseError)?;
+            return Err(AppError::NotFound("Link not found".into()));
+        }
+        txn.commit().await.map_err(AppError::DatabaseError)?;
+
+        self.links_changed(record_id).await;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_dead, is_http};
+    use reqwest::StatusCode;
+
+    #[test]
+    fn only_http_links_are_checked() {
+        assert!(is_http("https://example.com/file"));
+        assert!(is_http("http://example.com/file"));
+        assert!(!is_http("magnet:?xt=urn:btih:abc"));
+        assert!(!is_http("ftp://example.com/file"));
+    }
+
+    #[test]
+    fn error_statuses_other_than_rate_limiting_are_dead() {
+        assert!(!is_dead(StatusCode::OK));
+        assert!(!is_dead(StatusCode::FOUND));
+        assert!(!is_dead(StatusCode::TOO_MANY_REQUESTS));
+        assert!(is_dead(StatusCode::NOT_FOUND));
+        assert!(is_dead(StatusCode::GONE));
+        assert!(is_dead(StatusCode::BAD_GATEWAY));
+    }
+}
@@ -0,0 +1,85 @@
+use crate::{
+    common::error::AppError,
+    domains::luna::{
+        domain::NamedEntityMergeRepository,
+        dto::{CatalogAction, MergeEntityResponse},
+        infra::{catalog_cache::CatalogCache, catalog_events::CatalogEvents, search_outbox},
+    },
+    domains::search::SearchEntityType,
+};
+use sea_orm::{DatabaseConnection, TransactionTrait as _};
+
+/// Fold `source_id` into `target_id` in one transaction and enqueue the search
+/// events: a delete for the duplicate, an upsert for the survivor, and a
+/// reindex for every record that pointed at the duplicate. Once committed, the
+/// merge is published as a delete of the duplicate and an update of the survivor.
+pub(super) async fn merge_named_entity(
+    db: &DatabaseConnection,
+    repo: &dyn NamedEntityMergeRepository,
+    events: &CatalogEvents,
+    cache: &CatalogCache,
+    entity_type: SearchEntityType,
+    target_id: i64,
+    source_id: i64,
+) -> Result<MergeEntityResponse, AppError> {
+    if target_id == source_id {
+        return Err(AppError::ValidationError(
+            "Cannot merge an entity into itself".into(),
+        ));
+    }
+    if source_id == 0 {
+        return Err(AppError::ValidationError(
+            "The placeholder entity cannot be merged away".into(),
+        ));
+    }
+
+    let txn = db.begin().await?;
+
+    // Snapshot before the merge; afterwards these records point at the target.
+    let affected = search_outbox::find_affected_record_ids(&txn, entity_type, source_id)
+        .await
+        .map_err(AppError::DatabaseError)?;
+
+    let (records, target_name) = match repo.merge(&txn, target_id, source_id).await {
+        Ok(Some(merged)) => merged,
+        Ok(None) => {
+            txn.rollback().await?;
+            return Err(AppError::NotFound(format!(
+                "{} to merge not found",
+                entity_type.as_str()
+            )));
+        }
+        Err(e) => {
+            txn.rollback().await.ok();
+            return Err(AppError::DatabaseError(e));
+        }
+    };
+
+    search_outbox::outbox_entity_delete(&txn, entity_type, source_id, vec![])
+        .await
+        .map_err(AppError::DatabaseError)?;
+    search_outbox::outbox_entity_upsert(
+        &txn,
+        entity_type,
+        target_id,
+        &target_name,
+        affected.clone(),
+    )
+    .await
+    .map_err(AppError::DatabaseError)?;
+    search_outbox::outbox_fanout_records(&txn, &affected)
+        .await
+        .map_err(AppError::DatabaseError)?;
+
+    txn.commit().await?;
+    events.publish_entity(entity_type, source_id, CatalogAction::Deleted);
+    events.publish_entity(entity_type, target_id, CatalogAction::Updated);
+    cache
+        .invalidate_entities(entity_type, &[target_id, source_id])
+        .await;
+    Ok(MergeEntityResponse {
+        id: target_id,
+        merged_id: source_id,
+        records,
+    })
+}
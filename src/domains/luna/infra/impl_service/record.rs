@@ -1,12 +1,40 @@
+#[cfg(feature = "metadata")]
+use crate::common::jwt::Role;
+#[cfg(feature = "metadata")]
+use crate::domains::luna::{
+    domain::RecordPermission,
+    dto::{RefreshMetadataQuery, RefreshMetadataResponse},
+    infra::metadata::{self, MetadataProvider},
+};
 use crate::{
-    common::error::AppError,
+    common::{
+        config::{Config, DEFAULT_PAGE_SIZE},
+        error::AppError,
+        pagination::resolve_ordering,
+    },
     domains::luna::{
-        domain::{RecordRepository, RecordServiceTrait},
+        domain::{
+            is_safe_media_id, CreatedNestedEntities, ExportRepository as _,
+            MediaFileRepository as _, RecordRepository, RecordServiceTrait, RevisionRepository,
+        },
         dto::{
-            CreateLinkDto, CreateRecordDto, PaginatedResponse, PaginationQuery, RecordDto,
-            RecordSlimDto, SearchRecordDto, UpdateRecordDto, UserFilter,
+            BulkCreateMode, BulkCreateResponse, BulkDeleteRecordsDto, BulkDeleteRecordsResponse,
+            BulkItemResult, CatalogAction, CreateLinkDto, CreateRecordDto, ExportEntity,
+            ImportConflictMode, ImportResponse, ImportRow, ImportRowResult, ImportRowStatus,
+            LinkUpdateMode, LinkUpdateResultDto, MediaType, PaginatedResponse, PaginationQuery,
+            PatchRecordDto, RandomRecordsQuery, RecordCursor, RecordDto, RecordRelations,
+            RecordRelationsDto, RecordRevisionDto, RecordSlimDto, RecordSyncResponse,
+            ResolvedIdolDto, SearchRecordDto, SeenRecordDto, SeriesNeighborDto, SimilarRecordDto,
+            UpdateRecordDto, UserFilter, DEFAULT_FEED_LIMIT, DEFAULT_RANDOM_RECORDS,
+            DEFAULT_SIMILAR_RECORDS, DEFAULT_SYNC_LIMIT, MAX_BULK_RECORDS, MAX_FEED_LIMIT,
+            MAX_RANDOM_RECORDS, MAX_SIMILAR_RECORDS, MAX_SYNC_LIMIT, RECORD_ORDERING_FIELDS,
+        },
+        infra::{
+            catalog_cache::CatalogCache,
+            catalog_events::CatalogEvents,
+            media_storage::{self, media_dir_key, MediaStorage},
+            ExportRepo, MediaFileRepo, RecordRepo, RevisionRepo,
         },
-        infra::RecordRepo,
     },
     domains::search::{
         OutboxRepo, OutboxRepository as _, SearchEntityType, TombstoneRepo,
@@ -15,22 +43,48 @@ use crate::{
 };
 use async_trait::async_trait;
 use chrono::Utc;
-use sea_orm::{DatabaseConnection, TransactionTrait as _};
+use sea_orm::{DatabaseConnection, DatabaseTransaction, DbErr, TransactionTrait as _};
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use validator::Validate as _;
+
+/// `(link, manual)` of the entity each name resolved to during one import,
+/// or `None` when no entity has that name yet.
+type ImportNameCache = HashMap<(ExportEntity, String), Option<(String, bool)>>;
 
 /// Service struct for handling record-related operations.
 #[derive(Clone)]
 pub struct RecordService {
     db: DatabaseConnection,
     repo: Arc<dyn RecordRepository + Send + Sync>,
+    revisions: Arc<dyn RevisionRepository>,
+    config: Config,
+    events: Arc<CatalogEvents>,
+    cache: Arc<CatalogCache>,
+    storage: Arc<dyn MediaStorage>,
+    /// Sources asked by `refresh_metadata`, in order.
+    #[cfg(feature = "metadata")]
+    metadata_providers: Vec<Arc<dyn MetadataProvider>>,
 }
 
 #[async_trait]
 impl RecordServiceTrait for RecordService {
-    fn create_service(db: DatabaseConnection) -> Arc<dyn RecordServiceTrait> {
+    fn create_service(
+        db: DatabaseConnection,
+        config: Config,
+        events: Arc<CatalogEvents>,
+        cache: Arc<CatalogCache>,
+    ) -> Arc<dyn RecordServiceTrait> {
         Arc::new(Self {
             db: db.clone(),
             repo: Arc::new(RecordRepo),
+            revisions: Arc::new(RevisionRepo),
+            storage: media_storage::from_config(&config),
+            #[cfg(feature = "metadata")]
+            metadata_providers: metadata::from_config(&config.metadata),
+            config,
+            events,
+            cache,
         })
     }
 
@@ -64,10 +118,12 @@ impl RecordServiceTrait for RecordService {
         search_dto: SearchRecordDto,
         pagination: PaginationQuery,
         user_filter: Option<UserFilter>,
+        relations: RecordRelations,
     ) -> Result<PaginatedResponse<RecordDto>, AppError> {
+        validate_list_pagination(&pagination)?;
         let paginated = self
             .repo
-            .find_list_paginated(&self.db, search_dto, pagination, user_filter)
+            .find_list_paginated(&self.db, search_dto, pagination, user_filter, relations)
             .await
             .map_err(AppError::DatabaseError)?;
 
@@ -75,6 +131,7 @@ impl RecordServiceTrait for RecordService {
             count: paginated.count,
             next: paginated.next,
             previous: paginated.previous,
+            next_cursor: paginated.next_cursor,
             results: paginated.results.into_iter().map(RecordDto::from).collect(),
         })
     }
@@ -102,6 +159,31 @@ impl RecordServiceTrait for RecordService {
         Ok(ids)
     }
 
+    async fn record_exists(&self, id: &str, max_permission: i32) -> Result<bool, AppError> {
+        let found = self
+            .find_existing_record_ids(vec![id.to_owned()], max_permission)
+            .await?;
+        Ok(!found.is_empty())
+    }
+
+    async fn find_existing_record_ids(
+        &self,
+        ids: Vec<String>,
+        max_permission: i32,
+    ) -> Result<Vec<String>, AppError> {
+        self.repo
+            .find_existing_ids(&self.db, ids, max_permission)
+            .await
+            .map_err(AppError::DatabaseError)
+    }
+
+    async fn get_record_permission(&self, id: &str) -> Result<Option<i32>, AppError> {
+        self.repo
+            .find_permission(&self.db, id)
+            .await
+            .map_err(AppError::DatabaseError)
+    }
+
     async fn get_all_record_slim(
         &self,
         user_filter: Option<UserFilter>,
@@ -120,6 +202,7 @@ impl RecordServiceTrait for RecordService {
         pagination: PaginationQuery,
         user_filter: Option<UserFilter>,
     ) -> Result<PaginatedResponse<String>, AppError> {
+        resolve_ordering(&pagination, RECORD_ORDERING_FIELDS)?;
         self.repo
             .find_ids_paginated(&self.db, pagination, user_filter)
             .await
@@ -128,12 +211,14 @@ impl RecordServiceTrait for RecordService {
 
     async fn get_record_slim_paginated(
         &self,
+        search_dto: SearchRecordDto,
         pagination: PaginationQuery,
         user_filter: Option<UserFilter>,
     ) -> Result<PaginatedResponse<RecordSlimDto>, AppError> {
+        resolve_ordering(&pagination, RECORD_ORDERING_FIELDS)?;
         let paginated = self
             .repo
-            .find_all_slim_paginated(&self.db, pagination, user_filter)
+            .find_all_slim_paginated(&self.db, search_dto, pagination, user_filter)
             .await
             .map_err(AppError::DatabaseError)?;
 
@@ -141,6 +226,7 @@ impl RecordServiceTrait for RecordService {
             count: paginated.count,
             next: paginated.next,
             previous: paginated.previous,
+            next_cursor: paginated.next_cursor,
             results: paginated
                 .results
                 .into_iter()
@@ -149,164 +235,502 @@ impl RecordServiceTrait for RecordService {
         })
     }
 
-    async fn create_record(&self, create_dto: CreateRecordDto) -> Result<RecordDto, AppError> {
+    async fn create_record(
+        &self,
+        create_dto: CreateRecordDto,
+        actor: &str,
+    ) -> Result<RecordDto, AppError> {
         let txn = self.db.begin().await.map_err(AppError::DatabaseError)?;
+        let permission = create_dto.permission;
 
-        let (id, nested) = match self.repo.create(&txn, create_dto).await {
-            Ok(result) => result,
+        let id = match self.create_record_in_txn(&txn, create_dto, actor).await {
+            Ok((id, _)) => id,
             Err(e) => {
                 txn.rollback().await.ok();
                 return Err(AppError::DatabaseError(e));
             }
         };
 
-        // Insert outbox events for nested named entities (version=0 for fan-out semantics)
-        for (entity_type, entity_info) in [
-            (SearchEntityType::Director, &nested.director),
-            (SearchEntityType::Studio, &nested.studio),
-            (SearchEntityType::Label, &nested.label),
-            (SearchEntityType::Series, &nested.series),
-        ] {
-            if let Some((entity_id, entity_name)) = entity_info {
-                if let Err(e) = crate::domains::luna::infra::search_outbox::outbox_entity_upsert(
-                    &txn,
-                    entity_type,
-                    *entity_id,
-                    entity_name,
-                    vec![],
-                )
-                .await
-                {
-                    txn.rollback().await.ok();
-                    return Err(AppError::DatabaseError(e));
+        txn.commit().await.map_err(AppError::DatabaseError)?;
+        self.publish_record(&id, CatalogAction::Created, Some(permission));
+        self.cache.invalidate_records().await;
+
+        self.get_record_by_id(&id).await
+    }
+
+    async fn create_records_bulk(
+        &self,
+        items: Vec<CreateRecordDto>,
+        mode: BulkCreateMode,
+        actor: &str,
+    ) -> Result<BulkCreateResponse, AppError> {
+        if items.is_empty() {
+            return Err(AppError::ValidationError(
+                "Bulk request must contain at least one record".to_string(),
+            ));
+        }
+        if items.len() > MAX_BULK_RECORDS {
+            return Err(AppError::ValidationError(format!(
+                "Bulk request cannot exceed {MAX_BULK_RECORDS} records"
+            )));
+        }
+
+        let txn = self.db.begin().await.map_err(AppError::DatabaseError)?;
+        let mut results = Vec::with_capacity(items.len());
+        let mut created = Vec::new();
+        let mut aborted = false;
+
+        for (index, item) in items.into_iter().enumerate() {
+            let id = item.id.clone();
+            let permission = item.permission;
+            if aborted {
+                results.push(BulkItemResult {
+                    index,
+                    id,
+                    success: false,
+                    error: Some("Not attempted: batch aborted".to_string()),
+                    resolved_idols: Vec::new(),
+                });
+                continue;
+            }
+
+            let outcome = match item.validate() {
+                Err(err) => Err(format!("Invalid input: {err}")),
+                Ok(()) => match mode {
+                    BulkCreateMode::Atomic => self
+                        .create_record_in_txn(&txn, item, actor)
+                        .await
+                        .map(|(_, resolved)| resolved)
+                        .map_err(|e| e.to_string()),
+                    // A savepoint per item keeps one failure from poisoning
+                    // the enclosing transaction.
+                    BulkCreateMode::Partial => {
+                        let savepoint = txn.begin().await.map_err(AppError::DatabaseError)?;
+                        match self.create_record_in_txn(&savepoint, item, actor).await {
+                            Ok((_, resolved)) => savepoint
+                                .commit()
+                                .await
+                                .map(|()| resolved)
+                                .map_err(|e| e.to_string()),
+                            Err(e) => {
+                                savepoint.rollback().await.ok();
+                                Err(e.to_string())
+                            }
+                        }
+                    }
+                },
+            };
+
+            match outcome {
+                Ok(resolved_idols) => {
+                    created.push((id.clone(), permission));
+                    results.push(BulkItemResult {
+                        index,
+                        id,
+                        success: true,
+                        error: None,
+                        resolved_idols,
+                    });
+                }
+                Err(error) => {
+                    aborted = mode == BulkCreateMode::Atomic;
+                    results.push(BulkItemResult {
+                        index,
+                        id,
+                        success: false,
+                        error: Some(error),
+                        resolved_idols: Vec::new(),
+                    });
                 }
             }
         }
 
-        for (genre_id, genre_name) in &nested.genres {
-            if let Err(e) = crate::domains::luna::infra::search_outbox::outbox_entity_upsert(
-                &txn,
-                SearchEntityType::Genre,
-                *genre_id,
-                genre_name,
-                vec![],
-            )
+        if aborted {
+            txn.rollback().await.ok();
+            // Nothing from an aborted atomic batch was persisted.
+            for result in results.iter_mut().filter(|r| r.success) {
+                result.success = false;
+                result.error = Some("Rolled back: batch aborted".to_string());
+                result.resolved_idols.clear();
+            }
+        } else {
+            txn.commit().await.map_err(AppError::DatabaseError)?;
+            for (id, permission) in &created {
+                self.publish_record(id, CatalogAction::Created, Some(*permission));
+            }
+            self.cache.invalidate_records().await;
+        }
+
+        let created = results.iter().filter(|r| r.success).count();
+        Ok(BulkCreateResponse {
+            mode,
+            created,
+            failed: results.len() - created,
+            results,
+        })
+    }
+
+    async fn import_records(
+        &self,
+        rows: Vec<ImportRow>,
+        on_conflict: ImportConflictMode,
+        actor: &str,
+    ) -> Result<ImportResponse, AppError> {
+        let txn = self.db.begin().await.map_err(AppError::DatabaseError)?;
+        let mut names = ImportNameCache::new();
+        let mut seen = HashSet::new();
+        let mut results = Vec::with_capacity(rows.len());
+        let mut written = Vec::new();
+
+        for ImportRow { row, record } in rows {
+            let record = match record {
+                Ok(record) => record,
+                Err(message) => {
+                    results.push(ImportRowResult {
+                        row,
+                        id: None,
+                        status: ImportRowStatus::Error,
+                        message: Some(message),
+                    });
+                    continue;
+                }
+            };
+            let id = record.id.clone();
+            let permission = record.permission;
+            if !seen.insert(id.clone()) {
+                results.push(ImportRowResult {
+                    row,
+                    id: Some(id),
+                    status: ImportRowStatus::Skipped,
+                    message: Some("Duplicate of an earlier row".to_owned()),
+                });
+                continue;
+            }
+
+            let outcome = match record.validate() {
+                Err(err) => Err(format!("Invalid input: {err}")),
+                Ok(()) => {
+                    let savepoint = txn.begin().await.map_err(AppError::DatabaseError)?;
+                    match self
+                        .import_record_in_txn(&savepoint, record, on_conflict, &mut names, actor)
+                        .await
+                    {
+                        Ok(status) => savepoint
+                            .commit()
+                            .await
+                            .map(|()| status)
+                            .map_err(|e| e.to_string()),
+                        Err(e) => {
+                            savepoint.rollback().await.ok();
+                            Err(e.to_string())
+                        }
+                    }
+                }
+            };
+
+            let action = match outcome {
+                Ok(ImportRowStatus::Created) => Some(CatalogAction::Created),
+                Ok(ImportRowStatus::Updated) => Some(CatalogAction::Updated),
+                _ => None,
+            };
+            if let Some(action) = action {
+                written.push((id.clone(), action, permission));
+            }
+            results.push(match outcome {
+                Ok(status) => ImportRowResult {
+                    row,
+                    id: Some(id),
+                    status,
+                    message: (status == ImportRowStatus::Skipped)
+                        .then(|| "Record already exists".to_owned()),
+                },
+                Err(message) => ImportRowResult {
+                    row,
+                    id: Some(id),
+                    status: ImportRowStatus::Error,
+                    message: Some(message),
+                },
+            });
+        }
+
+        txn.commit().await.map_err(AppError::DatabaseError)?;
+        for (id, action, permission) in &written {
+            self.publish_record(id, *action, Some(*permission));
+        }
+        self.cache.invalidate_records().await;
+
+        let count = |status| results.iter().filter(|r| r.status == status).count();
+        Ok(ImportResponse {
+            created: count(ImportRowStatus::Created),
+            updated: count(ImportRowStatus::Updated),
+            skipped: count(ImportRowStatus::Skipped),
+            failed: count(ImportRowStatus::Error),
+            rows: results,
+        })
+    }
+
+    async fn update_record(
+        &self,
+        id: &str,
+        update_dto: UpdateRecordDto,
+        expected_version: Option<i32>,
+        actor: &str,
+    ) -> Result<RecordDto, AppError> {
+        let txn = self.db.begin().await.map_err(AppError::DatabaseError)?;
+
+        let claimed = match self.claim_version(&txn, id, expected_version, actor).await {
+            Ok(claimed) => claimed,
+            Err(e) => {
+                txn.rollback().await.ok();
+                return Err(e);
+            }
+        };
+        if !claimed {
+            txn.rollback().await.map_err(AppError::DatabaseError)?;
+            return Err(AppError::NotFound("Record not found".into()));
+        }
+
+        let updated_record = match self
+            .repo
+            .update(&txn, id.to_owned(), update_dto, actor)
             .await
-            {
+        {
+            Ok(r) => r,
+            Err(e) => {
                 txn.rollback().await.ok();
                 return Err(AppError::DatabaseError(e));
             }
+        };
+
+        let Some(record) = updated_record else {
+            txn.rollback().await.map_err(AppError::DatabaseError)?;
+            return Err(AppError::NotFound("Record not found".into()));
+        };
+
+        // Insert outbox event + tombstone within same transaction
+        if let Err(e) = enqueue_record_upsert(&txn, id).await {
+            txn.rollback().await.ok();
+            return Err(AppError::DatabaseError(e));
         }
 
-        for (idol_id, idol_name) in &nested.idols {
-            if let Err(e) = crate::domains::luna::infra::search_outbox::outbox_entity_upsert(
-                &txn,
-                SearchEntityType::Idol,
-                *idol_id,
-                idol_name,
-                vec![],
-            )
-            .await
-            {
+        txn.commit().await.map_err(AppError::DatabaseError)?;
+        self.publish_record(id, CatalogAction::Updated, Some(record.permission));
+        self.cache.invalidate_records().await;
+
+        Ok(RecordDto::from(record))
+    }
+
+    async fn patch_record(
+        &self,
+        id: &str,
+        patch_dto: PatchRecordDto,
+        expected_version: Option<i32>,
+        actor: &str,
+    ) -> Result<RecordDto, AppError> {
+        let txn = self.db.begin().await.map_err(AppError::DatabaseError)?;
+
+        let claimed = match self.claim_version(&txn, id, expected_version, actor).await {
+            Ok(claimed) => claimed,
+            Err(e) => {
+                txn.rollback().await.ok();
+                return Err(e);
+            }
+        };
+        if !claimed {
+            txn.rollback().await.map_err(AppError::DatabaseError)?;
+            return Err(AppError::NotFound("Record not found".into()));
+        }
+
+        let patched_record = match self.repo.patch(&txn, id.to_owned(), patch_dto, actor).await {
+            Ok(r) => r,
+            Err(e) => {
                 txn.rollback().await.ok();
                 return Err(AppError::DatabaseError(e));
             }
+        };
+
+        let Some(record) = patched_record else {
+            txn.rollback().await.map_err(AppError::DatabaseError)?;
+            return Err(AppError::NotFound("Record not found".into()));
+        };
+
+        if let Err(e) = enqueue_record_upsert(&txn, id).await {
+            txn.rollback().await.ok();
+            return Err(AppError::DatabaseError(e));
+        }
+
+        txn.commit().await.map_err(AppError::DatabaseError)?;
+        self.publish_record(id, CatalogAction::Updated, Some(record.permission));
+        self.cache.invalidate_records().await;
+
+        Ok(RecordDto::from(record))
+    }
+
+    async fn replace_record(
+        &self,
+        id: &str,
+        replace_dto: CreateRecordDto,
+        override_manual: bool,
+        expected_version: Option<i32>,
+        actor: &str,
+    ) -> Result<RecordDto, AppError> {
+        if replace_dto.id != id {
+            return Err(AppError::ValidationError(format!(
+                "Body id '{}' does not match path id '{id}'",
+                replace_dto.id
+            )));
+        }
+
+        let txn = self.db.begin().await.map_err(AppError::DatabaseError)?;
+
+        let claimed = match self.claim_version(&txn, id, expected_version, actor).await {
+            Ok(claimed) => claimed,
+            Err(e) => {
+                txn.rollback().await.ok();
+                return Err(e);
+            }
+        };
+        // A missing record is created, unless the client expected an existing one
+        if !claimed && expected_version.is_some() {
+            txn.rollback().await.map_err(AppError::DatabaseError)?;
+            return Err(AppError::NotFound("Record not found".into()));
         }
 
-        // Insert outbox event + tombstone for the record itself
-        let version = Utc::now()
-            .timestamp_nanos_opt()
-            .unwrap_or_else(|| Utc::now().timestamp_millis() * 1_000_000);
-        if let Err(e) = OutboxRepo::insert_event(
-            &txn,
-            SearchEntityType::Record.as_str(),
-            &id,
-            "upsert",
-            version,
-            None,
-            None,
-        )
-        .await
+        let permission = replace_dto.permission;
+        let nested = match self
+            .repo
+            .replace(&txn, replace_dto, override_manual, actor)
+            .await
         {
+            Ok(Some((_, nested))) => nested,
+            // Trashed records are restored before they are edited
+            Ok(None) => {
+                txn.rollback().await.map_err(AppError::DatabaseError)?;
+                return Err(AppError::NotFound("Record not found".into()));
+            }
+            Err(e) => {
+                txn.rollback().await.ok();
+                return Err(AppError::DatabaseError(e));
+            }
+        };
+
+        if let Err(e) = enqueue_nested_upserts(&txn, &nested).await {
             txn.rollback().await.ok();
             return Err(AppError::DatabaseError(e));
         }
-        if let Err(e) =
-            TombstoneRepo::upsert_version(&txn, SearchEntityType::Record.as_str(), &id, version)
-                .await
-        {
+        if let Err(e) = enqueue_record_upsert(&txn, id).await {
             txn.rollback().await.ok();
             return Err(AppError::DatabaseError(e));
         }
 
         txn.commit().await.map_err(AppError::DatabaseError)?;
+        let action = if claimed {
+            CatalogAction::Updated
+        } else {
+            CatalogAction::Created
+        };
+        self.publish_record(id, action, Some(permission));
+        self.cache.invalidate_records().await;
 
-        self.get_record_by_id(&id).await
+        self.get_record_by_id(id).await
     }
 
-    async fn update_record(
+    #[cfg(feature = "metadata")]
+    async fn refresh_metadata(
         &self,
         id: &str,
-        update_dto: UpdateRecordDto,
-    ) -> Result<RecordDto, AppError> {
+        query: RefreshMetadataQuery,
+        expected_version: Option<i32>,
+        actor: &str,
+    ) -> Result<RefreshMetadataResponse, AppError> {
+        if !self
+            .record_exists(id, RecordPermission::clearance(Role::Admin))
+            .await?
+        {
+            return Err(AppError::NotFound("Record not found".into()));
+        }
+        // Providers are asked before the transaction, so no row stays
+        // locked while waiting on them.
+        let (fetched, sources) = metadata::fetch_metadata(&self.metadata_providers, id).await?;
+
         let txn = self.db.begin().await.map_err(AppError::DatabaseError)?;
 
-        let updated_record = match self.repo.update(&txn, id.to_owned(), update_dto).await {
-            Ok(r) => r,
+        let claimed = match self.claim_version(&txn, id, expected_version, actor).await {
+            Ok(claimed) => claimed,
             Err(e) => {
                 txn.rollback().await.ok();
-                return Err(AppError::DatabaseError(e));
+                return Err(e);
             }
         };
+        if !claimed {
+            txn.rollback().await.map_err(AppError::DatabaseError)?;
+            return Err(AppError::NotFound("Record not found".into()));
+        }
 
-        let Some(record) = updated_record else {
+        let merged = match self
+            .repo
+            .merge_metadata(&txn, id.to_owned(), &fetched, query.overwrite, actor)
+            .await
+        {
+            Ok(merged) => merged,
+            Err(e) => {
+                txn.rollback().await.ok();
+                return Err(AppError::DatabaseError(e));
+            }
+        };
+        let Some((updated, nested)) = merged else {
             txn.rollback().await.map_err(AppError::DatabaseError)?;
             return Err(AppError::NotFound("Record not found".into()));
         };
 
-        // Insert outbox event + tombstone within same transaction
-        let version = Utc::now()
-            .timestamp_nanos_opt()
-            .unwrap_or_else(|| Utc::now().timestamp_millis() * 1_000_000);
-        if let Err(e) = OutboxRepo::insert_event(
-            &txn,
-            SearchEntityType::Record.as_str(),
-            id,
-            "upsert",
-            version,
-            None,
-            None,
-        )
-        .await
-        {
+        if let Err(e) = enqueue_nested_upserts(&txn, &nested).await {
             txn.rollback().await.ok();
             return Err(AppError::DatabaseError(e));
         }
-        if let Err(e) =
-            TombstoneRepo::upsert_version(&txn, SearchEntityType::Record.as_str(), id, version)
-                .await
-        {
+        if let Err(e) = enqueue_record_upsert(&txn, id).await {
             txn.rollback().await.ok();
             return Err(AppError::DatabaseError(e));
         }
 
         txn.commit().await.map_err(AppError::DatabaseError)?;
+        let record = self.get_record_by_id(id).await?;
+        self.publish_record(id, CatalogAction::Updated, Some(record.permission));
+        self.cache.invalidate_records().await;
 
-        Ok(RecordDto::from(record))
+        Ok(RefreshMetadataResponse {
+            record,
+            sources,
+            fetched,
+            updated,
+        })
     }
 
     async fn update_record_links(
         &self,
         id: &str,
         new_links: Vec<CreateLinkDto>,
-    ) -> Result<i32, AppError> {
+        mode: LinkUpdateMode,
+        actor: &str,
+    ) -> Result<LinkUpdateResultDto, AppError> {
         let txn = self.db.begin().await.map_err(AppError::DatabaseError)?;
+        if let Err(e) = self.revisions.snapshot(&txn, id, actor).await {
+            txn.rollback().await.ok();
+            return Err(AppError::DatabaseError(e));
+        }
 
-        let result = match self
-            .repo
-            .update_record_links(&txn, id.to_owned(), new_links)
-            .await
-        {
+        let result = match mode {
+            LinkUpdateMode::Append => {
+                self.repo
+                    .update_record_links(&txn, id.to_owned(), new_links)
+                    .await
+            }
+            LinkUpdateMode::Replace => {
+                self.repo
+                    .replace_record_links(&txn, id.to_owned(), new_links)
+                    .await
+            }
+        };
+        let result = match result {
             Ok(r) => r,
             Err(e) => {
                 txn.rollback().await.ok();
@@ -315,13 +739,80 @@ impl RecordServiceTrait for RecordService {
         };
 
         txn.commit().await.map_err(AppError::DatabaseError)?;
+        // An unknown permission is treated as the most restrictive level.
+        let permission = self
+            .get_record_permission(id)
+            .await
+            .ok()
+            .flatten()
+            .unwrap_or(i32::MAX);
+        self.publish_record(id, CatalogAction::Updated, Some(permission));
+        self.cache.invalidate_records().await;
         Ok(result)
     }
 
+    async fn get_record_history(
+        &self,
+        id: &str,
+        pagination: PaginationQuery,
+    ) -> Result<PaginatedResponse<RecordRevisionDto>, AppError> {
+        let page_size = pagination
+            .limit
+            .filter(|&l| l > 0)
+            .map_or(DEFAULT_PAGE_SIZE, |l| l as u64);
+        let current_offset = pagination.offset.unwrap_or(0).max(0) as u64;
+
+        let (revisions, total) = self
+            .revisions
+            .find_by_record_paginated(&self.db, id, page_size, current_offset)
+            .await
+            .map_err(AppError::DatabaseError)?;
+
+        let next_offset = current_offset + page_size;
+        let next =
+            (next_offset < total).then(|| format!("?limit={page_size}&offset={next_offset}"));
+        let previous = (current_offset > 0).then(|| {
+            format!(
+                "?limit={page_size}&offset={}",
+                current_offset.saturating_sub(page_size)
+            )
+        });
+
+        Ok(PaginatedResponse {
+            count: total as i64,
+            next,
+            previous,
+            next_cursor: None,
+            results: revisions,
+        })
+    }
+
+    async fn revert_record(
+        &self,
+        id: &str,
+        revision: i64,
+        expected_version: Option<i32>,
+        actor: &str,
+    ) -> Result<RecordDto, AppError> {
+        let revision = self
+            .revisions
+            .find_by_id(&self.db, id, revision)
+            .await
+            .map_err(AppError::DatabaseError)?
+            .ok_or_else(|| AppError::NotFound("Revision not found".into()))?;
+        let current = self.get_record_by_id(id).await?;
+
+        let mut restored = CreateRecordDto::from(revision.snapshot);
+        // Uploaded images are not edits; the images on disk stay counted.
+        restored.local_img_count = current.local_img_count;
+        self.replace_record(id, restored, true, expected_version, actor)
+            .await
+    }
+
     async fn delete_record(&self, id: &str) -> Result<String, AppError> {
         let txn = self.db.begin().await.map_err(AppError::DatabaseError)?;
 
-        let deleted = match self.repo.delete(&txn, id.to_owned()).await {
+        let deleted = match self.repo.soft_delete(&txn, id.to_owned()).await {
             Ok(d) => d,
             Err(e) => {
                 txn.rollback().await.ok();
@@ -335,52 +826,405 @@ impl RecordServiceTrait for RecordService {
         }
 
         // Insert outbox delete event + mark tombstone within same transaction
-        let version = Utc::now()
-            .timestamp_nanos_opt()
-            .unwrap_or_else(|| Utc::now().timestamp_millis() * 1_000_000);
-        if let Err(e) = OutboxRepo::insert_event(
-            &txn,
-            SearchEntityType::Record.as_str(),
-            id,
-            "delete",
-            version,
-            None,
-            None,
-        )
-        .await
-        {
-            txn.rollback().await.ok();
-            return Err(AppError::DatabaseError(e));
-        }
-        if let Err(e) =
-            TombstoneRepo::mark_deleted(&txn, SearchEntityType::Record.as_str(), id, version).await
-        {
+        if let Err(e) = enqueue_record_delete(&txn, id).await {
             txn.rollback().await.ok();
             return Err(AppError::DatabaseError(e));
         }
 
         txn.commit().await.map_err(AppError::DatabaseError)?;
-        Ok("Record deleted successfully".to_owned())
+        self.publish_record(id, CatalogAction::Deleted, None);
+        self.cache.invalidate_records().await;
+        Ok("Record moved to trash".to_owned())
     }
 
-    async fn get_records_by_director(
+    async fn get_trash_paginated(
         &self,
-        director_id: i64,
         pagination: PaginationQuery,
-        user_filter: Option<UserFilter>,
+        max_permission: i32,
     ) -> Result<PaginatedResponse<RecordDto>, AppError> {
-        self.query_by_search_dto(
-            SearchRecordDto {
-                director_id: Some(director_id),
-                ..Default::default()
-            },
-            pagination,
-            user_filter,
-        )
-        .await
-    }
+        let paginated = self
+            .repo
+            .find_trash_paginated(&self.db, pagination, max_permission)
+            .await
+            .map_err(AppError::DatabaseError)?;
 
-    async fn get_records_by_studio(
+        Ok(PaginatedResponse {
+            count: paginated.count,
+            next: paginated.next,
+            previous: paginated.previous,
+            next_cursor: paginated.next_cursor,
+            results: paginated.results.into_iter().map(RecordDto::from).collect(),
+        })
+    }
+
+    async fn restore_record(&self, id: &str) -> Result<RecordDto, AppError> {
+        let txn = self.db.begin().await.map_err(AppError::DatabaseError)?;
+
+        let restored = match self.repo.restore(&txn, id.to_owned()).await {
+            Ok(r) => r,
+            Err(e) => {
+                txn.rollback().await.ok();
+                return Err(AppError::DatabaseError(e));
+            }
+        };
+
+        if !restored {
+            txn.rollback().await.map_err(AppError::DatabaseError)?;
+            return Err(AppError::NotFound("Record not found in trash".into()));
+        }
+
+        // A fresh version outranks the delete tombstone, so the record is
+        // indexed again.
+        if let Err(e) = enqueue_record_upsert(&txn, id).await {
+            txn.rollback().await.ok();
+            return Err(AppError::DatabaseError(e));
+        }
+
+        txn.commit().await.map_err(AppError::DatabaseError)?;
+
+        let record = self.get_record_by_id(id).await?;
+        self.publish_record(id, CatalogAction::Created, Some(record.permission));
+        self.cache.invalidate_records().await;
+        Ok(record)
+    }
+
+    async fn purge_record(&self, id: &str) -> Result<String, AppError> {
+        let txn = self.db.begin().await.map_err(AppError::DatabaseError)?;
+
+        // The search document was already removed when the record was trashed.
+        let purged = match self.repo.purge(&txn, id.to_owned()).await {
+            Ok(p) => p,
+            Err(e) => {
+                txn.rollback().await.ok();
+                return Err(AppError::DatabaseError(e));
+            }
+        };
+
+        if !purged {
+            txn.rollback().await.map_err(AppError::DatabaseError)?;
+            return Err(AppError::NotFound("Record not found in trash".into()));
+        }
+
+        // The deletion was published when the record was trashed.
+        txn.commit().await.map_err(AppError::DatabaseError)?;
+        Ok("Record permanently deleted".to_owned())
+    }
+
+    async fn delete_records_bulk(
+        &self,
+        delete_dto: BulkDeleteRecordsDto,
+    ) -> Result<BulkDeleteRecordsResponse, AppError> {
+        let BulkDeleteRecordsDto {
+            ids,
+            purge,
+            delete_media,
+        } = delete_dto;
+        if delete_media && !purge {
+            return Err(AppError::ValidationError(
+                "delete_media requires purge; trashed records keep their media".into(),
+            ));
+        }
+        if !purge {
+            return self.trash_records_bulk(ids).await;
+        }
+        let txn = self.db.begin().await.map_err(AppError::DatabaseError)?;
+
+        let deleted = match self.repo.delete_many(&txn, ids.clone()).await {
+            Ok(d) => d,
+            Err(e) => {
+                txn.rollback().await.ok();
+                return Err(AppError::DatabaseError(e));
+            }
+        };
+
+        // Outbox delete event + tombstone per record, as in `delete_record`
+        let version = Utc::now()
+            .timestamp_nanos_opt()
+            .unwrap_or_else(|| Utc::now().timestamp_millis() * 1_000_000);
+        for id in &deleted.record_ids {
+            if let Err(e) = OutboxRepo::insert_event(
+                &txn,
+                SearchEntityType::Record.as_str(),
+                id,
+                "delete",
+                version,
+                None,
+                None,
+            )
+            .await
+            {
+                txn.rollback().await.ok();
+                return Err(AppError::DatabaseError(e));
+            }
+            if let Err(e) =
+                TombstoneRepo::mark_deleted(&txn, SearchEntityType::Record.as_str(), id, version)
+                    .await
+            {
+                txn.rollback().await.ok();
+                return Err(AppError::DatabaseError(e));
+            }
+        }
+
+        txn.commit().await.map_err(AppError::DatabaseError)?;
+        for id in &deleted.record_ids {
+            self.publish_record(id, CatalogAction::Deleted, None);
+        }
+        self.cache.invalidate_records().await;
+
+        // Media lives outside the database, so it is removed only after the
+        // rows are gone.
+        let media_dirs = if delete_media {
+            self.delete_record_media(&deleted.record_ids).await
+        } else {
+            0
+        };
+
+        let not_found = ids
+            .into_iter()
+            .filter(|id| !deleted.record_ids.contains(id))
+            .collect();
+        Ok(BulkDeleteRecordsResponse {
+            records: deleted.record_ids.len() as u64,
+            record_genres: deleted.record_genres,
+            idol_participations: deleted.idol_participations,
+            links: deleted.links,
+            media_dirs,
+            not_found,
+        })
+    }
+
+    async fn add_local_images(&self, id: &str, added: i32) -> Result<i32, AppError> {
+        let txn = self.db.begin().await.map_err(AppError::DatabaseError)?;
+
+        let count = match self.repo.add_local_images(&txn, id.to_owned(), added).await {
+            Ok(c) => c,
+            Err(e) => {
+                txn.rollback().await.ok();
+                return Err(AppError::DatabaseError(e));
+            }
+        };
+
+        let Some(count) = count else {
+            txn.rollback().await.map_err(AppError::DatabaseError)?;
+            return Err(AppError::NotFound("Record not found".into()));
+        };
+
+        txn.commit().await.map_err(AppError::DatabaseError)?;
+        // An unknown permission is treated as the most restrictive level.
+        let permission = self
+            .get_record_permission(id)
+            .await
+            .ok()
+            .flatten()
+            .unwrap_or(i32::MAX);
+        self.publish_record(id, CatalogAction::Updated, Some(permission));
+        self.cache.invalidate_records().await;
+        Ok(count)
+    }
+
+    async fn set_trailer(&self, id: &str, trailer: Option<String>) -> Result<(), AppError> {
+        let txn = self.db.begin().await.map_err(AppError::DatabaseError)?;
+
+        let updated = match self.repo.set_trailer(&txn, id.to_owned(), trailer).await {
+            Ok(updated) => updated,
+            Err(e) => {
+                txn.rollback().await.ok();
+                return Err(AppError::DatabaseError(e));
+            }
+        };
+        if !updated {
+            txn.rollback().await.map_err(AppError::DatabaseError)?;
+            return Err(AppError::NotFound("Record not found".into()));
+        }
+
+        txn.commit().await.map_err(AppError::DatabaseError)?;
+        // An unknown permission is treated as the most restrictive level.
+        let permission = self
+            .get_record_permission(id)
+            .await
+            .ok()
+            .flatten()
+            .unwrap_or(i32::MAX);
+        self.publish_record(id, CatalogAction::Updated, Some(permission));
+        self.cache.invalidate_records().await;
+        Ok(())
+    }
+
+    async fn set_cover_index(&self, id: &str, cover_index: Option<i32>) -> Result<(), AppError> {
+        let txn = self.db.begin().await.map_err(AppError::DatabaseError)?;
+
+        let updated = match self
+            .repo
+            .set_cover_index(&txn, id.to_owned(), cover_index)
+            .await
+        {
+            Ok(updated) => updated,
+            Err(e) => {
+                txn.rollback().await.ok();
+                return Err(AppError::DatabaseError(e));
+            }
+        };
+        if !updated {
+            txn.rollback().await.map_err(AppError::DatabaseError)?;
+            return Err(AppError::NotFound("Record not found".into()));
+        }
+
+        txn.commit().await.map_err(AppError::DatabaseError)?;
+        // An unknown permission is treated as the most restrictive level.
+        let permission = self
+            .get_record_permission(id)
+            .await
+            .ok()
+            .flatten()
+            .unwrap_or(i32::MAX);
+        self.publish_record(id, CatalogAction::Updated, Some(permission));
+        self.cache.invalidate_records().await;
+        Ok(())
+    }
+
+    async fn set_order_in_series(
+        &self,
+        id: &str,
+        order_in_series: Option<i32>,
+    ) -> Result<(), AppError> {
+        let txn = self.db.begin().await.map_err(AppError::DatabaseError)?;
+
+        let updated = match self
+            .repo
+            .set_order_in_series(&txn, id.to_owned(), order_in_series)
+            .await
+        {
+            Ok(updated) => updated,
+            Err(e) => {
+                txn.rollback().await.ok();
+                return Err(AppError::DatabaseError(e));
+            }
+        };
+        if !updated {
+            txn.rollback().await.map_err(AppError::DatabaseError)?;
+            return Err(AppError::NotFound("Record not found".into()));
+        }
+
+        txn.commit().await.map_err(AppError::DatabaseError)?;
+        // An unknown permission is treated as the most restrictive level.
+        let permission = self
+            .get_record_permission(id)
+            .await
+            .ok()
+            .flatten()
+            .unwrap_or(i32::MAX);
+        self.publish_record(id, CatalogAction::Updated, Some(permission));
+        self.cache.invalidate_records().await;
+        Ok(())
+    }
+
+    async fn get_series_neighbors(
+        &self,
+        id: &str,
+        max_permission: i32,
+    ) -> Result<(Option<SeriesNeighborDto>, Option<SeriesNeighborDto>), AppError> {
+        self.repo
+            .find_series_neighbors(&self.db, id, max_permission)
+            .await
+            .map_err(AppError::DatabaseError)
+    }
+
+    async fn merge_records(
+        &self,
+        id: &str,
+        source_id: &str,
+        expected_version: Option<i32>,
+        actor: &str,
+    ) -> Result<RecordDto, AppError> {
+        if id == source_id {
+            return Err(AppError::ValidationError(
+                "Cannot merge a record into itself".into(),
+            ));
+        }
+
+        let txn = self.db.begin().await.map_err(AppError::DatabaseError)?;
+
+        let claimed = match self.claim_version(&txn, id, expected_version, actor).await {
+            Ok(claimed) => claimed,
+            Err(e) => {
+                txn.rollback().await.ok();
+                return Err(e);
+            }
+        };
+        if !claimed {
+            txn.rollback().await.map_err(AppError::DatabaseError)?;
+            return Err(AppError::NotFound("Record not found".into()));
+        }
+        // Locks the source too, so no edit to it lands mid-merge.
+        let source_claimed = match self.claim_version(&txn, source_id, None, actor).await {
+            Ok(claimed) => claimed,
+            Err(e) => {
+                txn.rollback().await.ok();
+                return Err(e);
+            }
+        };
+        if !source_claimed {
+            txn.rollback().await.map_err(AppError::DatabaseError)?;
+            return Err(AppError::NotFound("Record to merge not found".into()));
+        }
+
+        let merged = match self
+            .repo
+            .merge_into(&txn, id.to_owned(), source_id.to_owned(), actor)
+            .await
+        {
+            Ok(m) => m,
+            Err(e) => {
+                txn.rollback().await.ok();
+                return Err(AppError::DatabaseError(e));
+            }
+        };
+
+        // Both rows exist, so one of them is in the trash.
+        let Some(record) = merged else {
+            txn.rollback().await.map_err(AppError::DatabaseError)?;
+            return Err(AppError::NotFound("Record not found".into()));
+        };
+
+        if let Err(e) = enqueue_record_delete(&txn, source_id).await {
+            txn.rollback().await.ok();
+            return Err(AppError::DatabaseError(e));
+        }
+        if let Err(e) = enqueue_record_upsert(&txn, id).await {
+            txn.rollback().await.ok();
+            return Err(AppError::DatabaseError(e));
+        }
+
+        txn.commit().await.map_err(AppError::DatabaseError)?;
+        self.publish_record(source_id, CatalogAction::Deleted, None);
+        self.publish_record(id, CatalogAction::Updated, Some(record.permission));
+        self.cache.invalidate_records().await;
+
+        // Media lives outside the database, so it is moved only after the
+        // rows are committed.
+        self.move_record_media(source_id, id).await;
+
+        Ok(RecordDto::from(record))
+    }
+
+    async fn get_records_by_director(
+        &self,
+        director_id: i64,
+        pagination: PaginationQuery,
+        user_filter: Option<UserFilter>,
+    ) -> Result<PaginatedResponse<RecordDto>, AppError> {
+        self.query_by_search_dto(
+            SearchRecordDto {
+                director_id: Some(director_id),
+                ..Default::default()
+            },
+            pagination,
+            user_filter,
+        )
+        .await
+    }
+
+    async fn get_records_by_studio(
         &self,
         studio_id: i64,
         pagination: PaginationQuery,
@@ -437,12 +1281,15 @@ impl RecordServiceTrait for RecordService {
         pagination: PaginationQuery,
         user_filter: Option<UserFilter>,
     ) -> Result<PaginatedResponse<RecordDto>, AppError> {
-        let paginated = self
-            .repo
-            .find_by_genre_id_paginated(&self.db, genre_id, pagination, user_filter)
-            .await
-            .map_err(AppError::DatabaseError)?;
-        Ok(Self::to_paginated_response(paginated))
+        self.query_by_search_dto(
+            SearchRecordDto {
+                genre_id: Some(genre_id),
+                ..Default::default()
+            },
+            pagination,
+            user_filter,
+        )
+        .await
     }
 
     async fn get_records_by_idol(
@@ -451,16 +1298,578 @@ impl RecordServiceTrait for RecordService {
         pagination: PaginationQuery,
         user_filter: Option<UserFilter>,
     ) -> Result<PaginatedResponse<RecordDto>, AppError> {
+        self.query_by_search_dto(
+            SearchRecordDto {
+                idol_id: Some(idol_id),
+                ..Default::default()
+            },
+            pagination,
+            user_filter,
+        )
+        .await
+    }
+
+    async fn sync_records(
+        &self,
+        since: i64,
+        limit: Option<u64>,
+        max_permission: i32,
+    ) -> Result<RecordSyncResponse, AppError> {
+        let limit = limit.unwrap_or(DEFAULT_SYNC_LIMIT);
+        if limit == 0 {
+            return Err(AppError::ValidationError("limit must be > 0".into()));
+        }
+        let changes = self
+            .repo
+            .find_changes_since(
+                &self.db,
+                since.max(0),
+                limit.min(MAX_SYNC_LIMIT),
+                max_permission,
+            )
+            .await
+            .map_err(AppError::DatabaseError)?;
+
+        Ok(RecordSyncResponse {
+            records: changes.records.into_iter().map(RecordDto::from).collect(),
+            deleted: changes.deleted,
+            next_since: changes.next_since,
+            has_more: changes.has_more,
+        })
+    }
+
+    async fn get_recent_records(
+        &self,
+        limit: Option<u64>,
+        max_permission: i32,
+    ) -> Result<Vec<RecordDto>, AppError> {
+        let limit = limit.unwrap_or(DEFAULT_FEED_LIMIT);
+        if limit == 0 {
+            return Err(AppError::ValidationError("limit must be > 0".into()));
+        }
+        let records = self
+            .repo
+            .find_recent(&self.db, limit.min(MAX_FEED_LIMIT), max_permission)
+            .await
+            .map_err(AppError::DatabaseError)?;
+        Ok(records.into_iter().map(RecordDto::from).collect())
+    }
+
+    async fn get_similar_records(
+        &self,
+        id: &str,
+        limit: Option<u64>,
+        max_permission: i32,
+    ) -> Result<Vec<SimilarRecordDto>, AppError> {
+        let limit = limit.unwrap_or(DEFAULT_SIMILAR_RECORDS);
+        if limit == 0 {
+            return Err(AppError::ValidationError("limit must be > 0".into()));
+        }
+        let permission = self
+            .repo
+            .find_permission(&self.db, id)
+            .await
+            .map_err(AppError::DatabaseError)?
+            .ok_or_else(|| AppError::NotFound("Record not found".into()))?;
+        if permission > max_permission {
+            return Err(AppError::Forbidden);
+        }
+        let similar = self
+            .repo
+            .find_similar(
+                &self.db,
+                id,
+                self.config.similarity_weights,
+                limit.min(MAX_SIMILAR_RECORDS),
+                max_permission,
+            )
+            .await
+            .map_err(AppError::DatabaseError)?;
+        Ok(similar
+            .into_iter()
+            .map(|(record, score)| SimilarRecordDto {
+                record: RecordSlimDto::from(record),
+                score,
+            })
+            .collect())
+    }
+
+    async fn get_random_records(
+        &self,
+        query: RandomRecordsQuery,
+        max_permission: i32,
+    ) -> Result<Vec<RecordDto>, AppError> {
+        let count = query.count.unwrap_or(DEFAULT_RANDOM_RECORDS);
+        if count == 0 {
+            return Err(AppError::ValidationError("count must be > 0".into()));
+        }
+        let records = self
+            .repo
+            .find_random(
+                &self.db,
+                count.min(MAX_RANDOM_RECORDS),
+                query.genre_id,
+                query.idol_id,
+                max_permission,
+            )
+            .await
+            .map_err(AppError::DatabaseError)?;
+        Ok(records.into_iter().map(RecordDto::from).collect())
+    }
+
+    async fn get_seen_history(
+        &self,
+        user_id: &str,
+        pagination: PaginationQuery,
+        max_permission: i32,
+    ) -> Result<PaginatedResponse<SeenRecordDto>, AppError> {
         let paginated = self
             .repo
-            .find_by_idol_id_paginated(&self.db, idol_id, pagination, user_filter)
+            .find_seen_paginated(&self.db, user_id, pagination, max_permission)
             .await
             .map_err(AppError::DatabaseError)?;
-        Ok(Self::to_paginated_response(paginated))
+
+        Ok(PaginatedResponse {
+            count: paginated.count,
+            next: paginated.next,
+            previous: paginated.previous,
+            next_cursor: paginated.next_cursor,
+            results: paginated
+                .results
+                .into_iter()
+                .map(|(record, seen_at)| SeenRecordDto {
+                    record: RecordDto::from(record),
+                    seen_at,
+                })
+                .collect(),
+        })
+    }
+
+    async fn get_record_relations(
+        &self,
+        record_ids: &[String],
+        relations: RecordRelations,
+    ) -> Result<HashMap<String, RecordRelationsDto>, AppError> {
+        let mut rows = self
+            .repo
+            .find_relations(&self.db, record_ids.to_vec(), relations)
+            .await
+            .map_err(AppError::DatabaseError)?;
+        Ok(record_ids
+            .iter()
+            .map(|id| {
+                let dto = rows.remove(id).map(RecordRelationsDto::from);
+                (id.clone(), dto.unwrap_or_default())
+            })
+            .collect())
+    }
+}
+
+/// Insert the record's search outbox `upsert` event and bump its tombstone
+/// version inside `txn`.
+async fn enqueue_record_upsert(txn: &DatabaseTransaction, id: &str) -> Result<(), DbErr> {
+    let version = Utc::now()
+        .timestamp_nanos_opt()
+        .unwrap_or_else(|| Utc::now().timestamp_millis() * 1_000_000);
+    OutboxRepo::insert_event(
+        txn,
+        SearchEntityType::Record.as_str(),
+        id,
+        "upsert",
+        version,
+        None,
+        None,
+    )
+    .await?;
+    TombstoneRepo::upsert_version(txn, SearchEntityType::Record.as_str(), id, version).await
+}
+
+/// Insert the record's search outbox `delete` event and mark its tombstone
+/// inside `txn`.
+async fn enqueue_record_delete(txn: &DatabaseTransaction, id: &str) -> Result<(), DbErr> {
+    let version = Utc::now()
+        .timestamp_nanos_opt()
+        .unwrap_or_else(|| Utc::now().timestamp_millis() * 1_000_000);
+    OutboxRepo::insert_event(
+        txn,
+        SearchEntityType::Record.as_str(),
+        id,
+        "delete",
+        version,
+        None,
+        None,
+    )
+    .await?;
+    TombstoneRepo::mark_deleted(txn, SearchEntityType::Record.as_str(), id, version).await
+}
+
+/// Insert search outbox events for named entities created or resolved while
+/// writing a record (version=0 for fan-out semantics).
+async fn enqueue_nested_upserts(
+    txn: &DatabaseTransaction,
+    nested: &CreatedNestedEntities,
+) -> Result<(), DbErr> {
+    for (entity_type, entity_info) in [
+        (SearchEntityType::Director, &nested.director),
+        (SearchEntityType::Studio, &nested.studio),
+        (SearchEntityType::Label, &nested.label),
+        (SearchEntityType::Series, &nested.series),
+    ] {
+        if let Some((entity_id, entity_name)) = entity_info {
+            crate::domains::luna::infra::search_outbox::outbox_entity_upsert(
+                txn,
+                entity_type,
+                *entity_id,
+                entity_name,
+                vec![],
+            )
+            .await?;
+        }
+    }
+
+    for (genre_id, genre_name) in &nested.genres {
+        crate::domains::luna::infra::search_outbox::outbox_entity_upsert(
+            txn,
+            SearchEntityType::Genre,
+            *genre_id,
+            genre_name,
+            vec![],
+        )
+        .await?;
+    }
+
+    for (idol_id, idol_name) in &nested.idols {
+        crate::domains::luna::infra::search_outbox::outbox_entity_upsert(
+            txn,
+            SearchEntityType::Idol,
+            *idol_id,
+            idol_name,
+            vec![],
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Validate `ordering` and `cursor` for the record list queries.
+///
+/// Keyset pagination has a fixed `(date, id)` order, so combining `cursor`
+/// with `ordering` is rejected rather than silently ignoring one of them.
+fn validate_list_pagination(pagination: &PaginationQuery) -> Result<(), AppError> {
+    resolve_ordering(pagination, RECORD_ORDERING_FIELDS)?;
+    if let Some(cursor) = pagination.cursor.as_deref() {
+        if pagination.ordering.is_some() {
+            return Err(AppError::ValidationError(
+                "`cursor` cannot be combined with `ordering`".to_string(),
+            ));
+        }
+        if !cursor.is_empty() && RecordCursor::decode(cursor).is_none() {
+            return Err(AppError::ValidationError("Invalid cursor".to_string()));
+        }
     }
+    Ok(())
 }
 
 impl RecordService {
+    /// Bumps the version of record `id` inside `txn`, locking the row for the
+    /// rest of the edit. Returns `false` when the record does not exist or is
+    /// in the trash, and `PreconditionFailed` when it exists at a version
+    /// other than `expected`.
+    ///
+    /// The state the edit replaces is first stored as a revision by `actor`;
+    /// it is rolled back with the edit when the claim fails.
+    async fn claim_version(
+        &self,
+        txn: &DatabaseTransaction,
+        id: &str,
+        expected: Option<i32>,
+        actor: &str,
+    ) -> Result<bool, AppError> {
+        let live = self
+            .revisions
+            .snapshot(txn, id, actor)
+            .await
+            .map_err(AppError::DatabaseError)?;
+        if !live {
+            return Ok(false);
+        }
+        if self
+            .repo
+            .bump_version(txn, id.to_owned(), expected)
+            .await
+            .map_err(AppError::DatabaseError)?
+            .is_some()
+        {
+            return Ok(true);
+        }
+
+        match self
+            .repo
+            .find_version(txn, id.to_owned())
+            .await
+            .map_err(AppError::DatabaseError)?
+        {
+            Some(current) => Err(AppError::PreconditionFailed(current)),
+            None => Ok(false),
+        }
+    }
+
+    /// Moves the live records among `ids` to the trash in one transaction,
+    /// as `delete_record` does for one. Their rows and media stay until purged.
+    async fn trash_records_bulk(
+        &self,
+        ids: Vec<String>,
+    ) -> Result<BulkDeleteRecordsResponse, AppError> {
+        let txn = self.db.begin().await.map_err(AppError::DatabaseError)?;
+
+        let trashed = match self.repo.soft_delete_many(&txn, ids.clone()).await {
+            Ok(t) => t,
+            Err(e) => {
+                txn.rollback().await.ok();
+                return Err(AppError::DatabaseError(e));
+            }
+        };
+        for id in &trashed {
+            if let Err(e) = enqueue_record_delete(&txn, id).await {
+                txn.rollback().await.ok();
+                return Err(AppError::DatabaseError(e));
+            }
+        }
+
+        txn.commit().await.map_err(AppError::DatabaseError)?;
+        for id in &trashed {
+            self.publish_record(id, CatalogAction::Deleted, None);
+        }
+        self.cache.invalidate_records().await;
+
+        let not_found = ids.into_iter().filter(|id| !trashed.contains(id)).collect();
+        Ok(BulkDeleteRecordsResponse {
+            records: trashed.len() as u64,
+            record_genres: 0,
+            idol_participations: 0,
+            links: 0,
+            media_dirs: 0,
+            not_found,
+        })
+    }
+
+    /// Removes the images, trailer and cached thumbnails of each record in
+    /// `ids`, as the media collector does for orphans, and forgets their
+    /// stored-file rows. Returns the number of media directories removed;
+    /// failed removals are logged and not counted.
+    async fn delete_record_media(&self, ids: &[String]) -> u64 {
+        let mut removed = 0;
+        for id in ids {
+            if !is_safe_media_id(id) {
+                continue;
+            }
+            for media_type in [MediaType::RecordImage, MediaType::RecordVideo] {
+                let dir = media_dir_key(&media_type, id);
+                match self.storage.delete_dir(&dir).await {
+                    Ok(true) => removed += 1,
+                    Ok(false) => {}
+                    Err(e) => tracing::warn!("Failed to remove media dir {dir}: {e}"),
+                }
+            }
+            // Thumbnails are cached on the local disk whatever the backend
+            let thumbnails = std::path::Path::new(&self.config.assets_private_path)
+                .join("thumbnails")
+                .join(MediaType::RecordImage.get_sub_dir_name())
+                .join(id);
+            if let Err(e) = tokio::fs::remove_dir_all(&thumbnails).await {
+                if e.kind() != std::io::ErrorKind::NotFound {
+                    tracing::warn!("Failed to remove thumbnails {}: {e}", thumbnails.display());
+                }
+            }
+        }
+        if let Err(e) = MediaFileRepo
+            .delete_by_owners(&self.db, &MediaType::RecordImage.get_sub_dir_name(), ids)
+            .await
+        {
+            tracing::warn!("Failed to forget hashes of removed media: {e}");
+        }
+        removed
+    }
+
+    /// Moves the images of record `source_id` into the directory of record
+    /// `target_id`. A file whose name is taken there gets `-{source_id}`
+    /// appended to its stem. Failures are logged, since the merge is already
+    /// committed.
+    async fn move_record_media(&self, source_id: &str, target_id: &str) {
+        if [source_id, target_id]
+            .iter()
+            .any(|id| !is_safe_media_id(id))
+        {
+            return;
+        }
+        let base_dir = std::path::Path::new(&self.config.assets_private_path)
+            .join("images")
+            .join(MediaType::RecordImage.get_sub_dir_name());
+        let source_dir = base_dir.join(source_id);
+        let target_dir = base_dir.join(target_id);
+
+        let mut entries = match tokio::fs::read_dir(&source_dir).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return,
+            Err(e) => {
+                tracing::warn!("Failed to read media dir {}: {e}", source_dir.display());
+                return;
+            }
+        };
+        if let Err(e) = tokio::fs::create_dir_all(&target_dir).await {
+            tracing::warn!("Failed to create media dir {}: {e}", target_dir.display());
+            return;
+        }
+
+        loop {
+            let entry = match entries.next_entry().await {
+                Ok(Some(entry)) => entry,
+                Ok(None) => break,
+                Err(e) => {
+                    tracing::warn!("Failed to read media dir {}: {e}", source_dir.display());
+                    break;
+                }
+            };
+            let file_name = std::path::PathBuf::from(entry.file_name());
+            let mut destination = target_dir.join(&file_name);
+            if tokio::fs::try_exists(&destination).await.unwrap_or(true) {
+                let stem = file_name.file_stem().unwrap_or_default().to_string_lossy();
+                let renamed = match file_name.extension() {
+                    Some(ext) => format!("{stem}-{source_id}.{}", ext.to_string_lossy()),
+                    None => format!("{stem}-{source_id}"),
+                };
+                destination = target_dir.join(renamed);
+            }
+            if let Err(e) = tokio::fs::rename(entry.path(), &destination).await {
+                tracing::warn!(
+                    "Failed to move media file {} to {}: {e}",
+                    entry.path().display(),
+                    destination.display()
+                );
+            }
+        }
+
+        // Only succeeds once every file has moved.
+        if let Err(e) = tokio::fs::remove_dir(&source_dir).await {
+            tracing::warn!("Failed to remove media dir {}: {e}", source_dir.display());
+        }
+    }
+
+    /// Publish a committed change to record `id` on the catalog event channel.
+    fn publish_record(&self, id: &str, action: CatalogAction, permission: Option<i32>) {
+        #[cfg(feature = "metrics")]
+        if action == CatalogAction::Created {
+            crate::common::metrics::record_created();
+        }
+        self.events
+            .publish(SearchEntityType::Record, id, action, permission);
+    }
+
+    /// Create a record and enqueue its search outbox events inside `txn`.
+    /// Returns the record ID and the idol names that resolved as aliases.
+    ///
+    /// The caller owns the transaction and is responsible for committing or
+    /// rolling it back; `txn` may be a savepoint.
+    async fn create_record_in_txn(
+        &self,
+        txn: &DatabaseTransaction,
+        create_dto: CreateRecordDto,
+        actor: &str,
+    ) -> Result<(String, Vec<ResolvedIdolDto>), DbErr> {
+        let (id, nested) = self.repo.create(txn, create_dto, actor).await?;
+
+        enqueue_nested_upserts(txn, &nested).await?;
+
+        // Insert outbox event + tombstone for the record itself
+        enqueue_record_upsert(txn, &id).await?;
+
+        Ok((id, nested.resolved_idols))
+    }
+
+    /// Create or replace one imported record inside `txn`, which may be a
+    /// savepoint.
+    async fn import_record_in_txn(
+        &self,
+        txn: &DatabaseTransaction,
+        mut record: CreateRecordDto,
+        on_conflict: ImportConflictMode,
+        names: &mut ImportNameCache,
+        actor: &str,
+    ) -> Result<ImportRowStatus, DbErr> {
+        let id = record.id.clone();
+        let exists = self.repo.find_version(txn, id.clone()).await?.is_some();
+        if exists && on_conflict == ImportConflictMode::Skip {
+            return Ok(ImportRowStatus::Skipped);
+        }
+
+        self.resolve_import_names(&mut record, names).await?;
+
+        if !exists {
+            self.create_record_in_txn(txn, record, actor).await?;
+            return Ok(ImportRowStatus::Created);
+        }
+        self.revisions.snapshot(txn, &id, actor).await?;
+        self.repo.bump_version(txn, id.clone(), None).await?;
+        // Imports never override curated associations.
+        let Some((_, nested)) = self.repo.replace(txn, record, false, actor).await? else {
+            return Err(DbErr::Custom("Record is in the trash".to_owned()));
+        };
+        enqueue_nested_upserts(txn, &nested).await?;
+        enqueue_record_upsert(txn, &id).await?;
+        Ok(ImportRowStatus::Updated)
+    }
+
+    /// Point each name-only reference of `record` (one without a `link`) at
+    /// the existing entity of that name, so importing a CSV dump reuses it
+    /// instead of creating a near-duplicate with an empty link.
+    async fn resolve_import_names(
+        &self,
+        record: &mut CreateRecordDto,
+        names: &mut ImportNameCache,
+    ) -> Result<(), DbErr> {
+        let mut refs: Vec<(ExportEntity, &str, &mut Option<String>, &mut Option<bool>)> =
+            Vec::new();
+        if let Some(d) = record.director.as_mut() {
+            refs.push((ExportEntity::Directors, &d.name, &mut d.link, &mut d.manual));
+        }
+        if let Some(s) = record.studio.as_mut() {
+            refs.push((ExportEntity::Studios, &s.name, &mut s.link, &mut s.manual));
+        }
+        if let Some(l) = record.label.as_mut() {
+            refs.push((ExportEntity::Labels, &l.name, &mut l.link, &mut l.manual));
+        }
+        if let Some(s) = record.series.as_mut() {
+            refs.push((ExportEntity::Series, &s.name, &mut s.link, &mut s.manual));
+        }
+        for g in &mut record.genres {
+            refs.push((ExportEntity::Genres, &g.name, &mut g.link, &mut g.manual));
+        }
+        for i in &mut record.idols {
+            refs.push((ExportEntity::Idols, &i.name, &mut i.link, &mut i.manual));
+        }
+
+        for (entity, name, link, manual) in refs.into_iter().filter(|r| r.2.is_none()) {
+            let key = (entity, name.to_owned());
+            let resolved = match names.get(&key) {
+                Some(resolved) => resolved.clone(),
+                None => {
+                    let resolved = ExportRepo
+                        .entity_by_name(&self.db, entity, name)
+                        .await?
+                        .map(|row| (row.link, row.manual));
+                    names.insert(key, resolved.clone());
+                    resolved
+                }
+            };
+            if let Some((existing_link, existing_manual)) = resolved {
+                *link = Some(existing_link);
+                *manual = Some(existing_manual);
+            }
+        }
+        Ok(())
+    }
+
     /// Query records using a `SearchRecordDto` filter with pagination.
     async fn query_by_search_dto(
         &self,
@@ -468,9 +1877,16 @@ impl RecordService {
         pagination: PaginationQuery,
         user_filter: Option<UserFilter>,
     ) -> Result<PaginatedResponse<RecordDto>, AppError> {
+        validate_list_pagination(&pagination)?;
         let paginated = self
             .repo
-            .find_list_paginated(&self.db, search_dto, pagination, user_filter)
+            .find_list_paginated(
+                &self.db,
+                search_dto,
+                pagination,
+                user_filter,
+                RecordRelations::ALL,
+            )
             .await
             .map_err(AppError::DatabaseError)?;
         Ok(Self::to_paginated_response(paginated))
@@ -484,6 +1900,7 @@ impl RecordService {
             count: paginated.count,
             next: paginated.next,
             previous: paginated.previous,
+            next_cursor: paginated.next_cursor,
             results: paginated.results.into_iter().map(RecordDto::from).collect(),
         }
     }
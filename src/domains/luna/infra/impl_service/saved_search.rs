@@ -0,0 +1,170 @@
+use crate::{
+    common::{config::DEFAULT_PAGE_SIZE, error::AppError, pagination::parse_ordering},
+    domains::luna::{
+        domain::{SavedSearchRepository, SavedSearchServiceTrait},
+        dto::{
+            CreateSavedSearchDto, PaginatedResponse, PaginationQuery, SavedSearchDto,
+            RECORD_ORDERING_FIELDS,
+        },
+        infra::SavedSearchRepo,
+    },
+};
+use async_trait::async_trait;
+use sea_orm::DatabaseConnection;
+use std::sync::Arc;
+
+/// Service struct for saved record searches.
+#[derive(Clone)]
+pub struct SavedSearchService {
+    db: DatabaseConnection,
+    repo: Arc<dyn SavedSearchRepository>,
+}
+
+/// `limit` and `offset` of `pagination`, with the usual defaults.
+fn page_bounds(pagination: &PaginationQuery) -> (u64, u64) {
+    let page_size = pagination
+        .limit
+        .filter(|&l| l > 0)
+        .map_or(DEFAULT_PAGE_SIZE, |l| l as u64);
+    let offset = pagination.offset.unwrap_or(0).max(0) as u64;
+    (page_size, offset)
+}
+
+fn to_page(
+    (searches, total): (Vec<SavedSearchDto>, u64),
+    page_size: u64,
+    current_offset: u64,
+) -> PaginatedResponse<SavedSearchDto> {
+    let next_offset = current_offset + page_size;
+    let next = (next_offset < total).then(|| format!("?limit={page_size}&offset={next_offset}"));
+    let previous = (current_offset > 0).then(|| {
+        format!(
+            "?limit={page_size}&offset={}",
+            current_offset.saturating_sub(page_size)
+        )
+    });
+    PaginatedResponse {
+        count: total as i64,
+        next,
+        previous,
+        next_cursor: None,
+        results: searches,
+    }
+}
+
+/// Reject definitions the record listing would refuse to run.
+fn validate_definition(dto: &CreateSavedSearchDto) -> Result<(), AppError> {
+    dto.filters
+        .validate_date_range()
+        .map_err(AppError::ValidationError)?;
+    dto.filters
+        .validate_id_lists()
+        .map_err(AppError::ValidationError)?;
+    if let Some(ordering) = dto.ordering.as_deref() {
+        parse_ordering(ordering, RECORD_ORDERING_FIELDS)?;
+    }
+    Ok(())
+}
+
+impl SavedSearchService {
+    pub fn create_service(db: DatabaseConnection) -> Arc<dyn SavedSearchServiceTrait> {
+        Arc::new(Self {
+            db,
+            repo: Arc::new(SavedSearchRepo),
+        })
+    }
+
+    /// Search `id`, whoever owns it.
+    async fn load(&self, id: i64) -> Result<SavedSearchDto, AppError> {
+        self.repo
+            .find_by_id(&self.db, id)
+            .await
+            .map_err(AppError::DatabaseError)?
+            .ok_or_else(|| AppError::NotFound("Saved search not found".into()))
+    }
+
+    /// Reject changes by anyone but the owner of search `id`. Private
+    /// searches of other users are reported as missing.
+    async fn ensure_owner(&self, id: i64, user_id: &str) -> Result<(), AppError> {
+        let search = self.get_saved_search(id, user_id).await?;
+        if search.owner_id != user_id {
+            return Err(AppError::Forbidden);
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl SavedSearchServiceTrait for SavedSearchService {
+    async fn list_own(
+        &self,
+        user_id: &str,
+        pagination: PaginationQuery,
+    ) -> Result<PaginatedResponse<SavedSearchDto>, AppError> {
+        let (page_size, offset) = page_bounds(&pagination);
+        let page = self
+            .repo
+            .find_by_owner_paginated(&self.db, user_id, page_size, offset)
+            .await
+            .map_err(AppError::DatabaseError)?;
+        Ok(to_page(page, page_size, offset))
+    }
+
+    async fn list_public(
+        &self,
+        pagination: PaginationQuery,
+    ) -> Result<PaginatedResponse<SavedSearchDto>, AppError> {
+        let (page_size, offset) = page_bounds(&pagination);
+        let page = self
+            .repo
+            .find_public_paginated(&self.db, page_size, offset)
+            .await
+            .map_err(AppError::DatabaseError)?;
+        Ok(to_page(page, page_size, offset))
+    }
+
+    async fn get_saved_search(&self, id: i64, user_id: &str) -> Result<SavedSearchDto, AppError> {
+        let search = self.load(id).await?;
+        if search.owner_id != user_id && !search.is_public {
+            return Err(AppError::NotFound("Saved search not found".into()));
+        }
+        Ok(search)
+    }
+
+    async fn create_saved_search(
+        &self,
+        user_id: &str,
+        dto: CreateSavedSearchDto,
+    ) -> Result<SavedSearchDto, AppError> {
+        validate_definition(&dto)?;
+        let id = self
+            .repo
+            .create(&self.db, user_id, dto)
+            .await
+            .map_err(AppError::DatabaseError)?;
+        self.load(id).await
+    }
+
+    async fn update_saved_search(
+        &self,
+        id: i64,
+        user_id: &str,
+        dto: CreateSavedSearchDto,
+    ) -> Result<SavedSearchDto, AppError> {
+        validate_definition(&dto)?;
+        self.ensure_owner(id, user_id).await?;
+        self.repo
+            .update(&self.db, id, dto)
+            .await
+            .map_err(AppError::DatabaseError)?;
+        self.load(id).await
+    }
+
+    async fn delete_saved_search(&self, id: i64, user_id: &str) -> Result<(), AppError> {
+        self.ensure_owner(id, user_id).await?;
+        self.repo
+            .delete(&self.db, id)
+            .await
+            .map_err(AppError::DatabaseError)
+    }
+}
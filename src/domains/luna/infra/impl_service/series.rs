@@ -1,13 +1,20 @@
+use super::merge::merge_named_entity;
 use crate::domains::search::SearchEntityType;
 use crate::{
-    common::error::AppError,
+    common::{error::AppError, pagination::resolve_ordering},
     domains::luna::{
-        domain::{SeriesAffinityRepository, SeriesRepository, SeriesServiceTrait},
+        domain::{
+            NamedEntityMergeRepository, SeriesAffinityRepository, SeriesRepository,
+            SeriesServiceTrait,
+        },
         dto::{
-            CreateSeriesDto, EntityCountDto, PaginatedResponse, PaginationQuery, SearchSeriesDto,
-            SeriesDto, UpdateSeriesDto,
+            AutocompleteQuery, CatalogAction, CreateSeriesDto, EntityCountDto, MergeEntityResponse,
+            NameSuggestionDto, PaginatedResponse, PaginationQuery, RecordCountQuery,
+            SearchSeriesDto, SeriesDto, UpdateSeriesDto, ENTITY_ORDERING_FIELDS,
+        },
+        infra::{
+            catalog_cache::CatalogCache, catalog_events::CatalogEvents, search_outbox, SeriesRepo,
         },
-        infra::{search_outbox, SeriesRepo},
     },
 };
 use async_trait::async_trait;
@@ -23,28 +30,67 @@ pub struct SeriesService {
     /// object cannot expose `SeriesAffinityRepository` methods, so the affinity
     /// trait needs its own trait object (both wrap `SeriesRepo`).
     affinity_repo: Arc<dyn SeriesAffinityRepository + Send + Sync>,
+    /// Merge handle; also wraps `SeriesRepo`.
+    merge_repo: Arc<dyn NamedEntityMergeRepository + Send + Sync>,
+    events: Arc<CatalogEvents>,
+    cache: Arc<CatalogCache>,
 }
 
 #[async_trait]
 impl SeriesServiceTrait for SeriesService {
-    fn create_service(db: DatabaseConnection) -> Arc<dyn SeriesServiceTrait> {
+    fn create_service(
+        db: DatabaseConnection,
+        events: Arc<CatalogEvents>,
+        cache: Arc<CatalogCache>,
+    ) -> Arc<dyn SeriesServiceTrait> {
         Arc::new(Self {
             db: db.clone(),
             repo: Arc::new(SeriesRepo),
             affinity_repo: Arc::new(SeriesRepo),
+            merge_repo: Arc::new(SeriesRepo),
+            events,
+            cache,
         })
     }
 
     async fn get_series_by_id(&self, id: i64) -> Result<SeriesDto, AppError> {
+        self.cache
+            .entity(SearchEntityType::Series, id, || async {
+                self.repo
+                    .find_by_id(&self.db, id)
+                    .await
+                    .map_err(AppError::DatabaseError)?
+                    .map(SeriesDto::from)
+                    .ok_or_else(|| AppError::NotFound("Series not found".into()))
+            })
+            .await
+    }
+
+    async fn get_series_by_ids(&self, ids: &[i64]) -> Result<Vec<SeriesDto>, AppError> {
         let series = self
             .repo
-            .find_by_id(&self.db, id)
+            .find_by_ids(&self.db, ids)
             .await
             .map_err(AppError::DatabaseError)?;
+        Ok(series.into_iter().map(SeriesDto::from).collect())
+    }
 
-        series
-            .map(SeriesDto::from)
-            .ok_or_else(|| AppError::NotFound("Series not found".into()))
+    async fn autocomplete_series(
+        &self,
+        query: AutocompleteQuery,
+    ) -> Result<Vec<NameSuggestionDto>, AppError> {
+        let term = query
+            .term()
+            .ok_or_else(|| AppError::ValidationError("q must not be blank".into()))?;
+        let limit = query.limit();
+        self.cache
+            .suggestions(SearchEntityType::Series, &term, limit, || async {
+                self.repo
+                    .autocomplete(&self.db, &term, limit)
+                    .await
+                    .map_err(AppError::DatabaseError)
+            })
+            .await
     }
 
     async fn get_series_list(
@@ -65,6 +111,7 @@ impl SeriesServiceTrait for SeriesService {
         search_dto: SearchSeriesDto,
         pagination: PaginationQuery,
     ) -> Result<PaginatedResponse<SeriesDto>, AppError> {
+        resolve_ordering(&pagination, ENTITY_ORDERING_FIELDS)?;
         let paginated = self
             .repo
             .find_list_paginated(&self.db, search_dto, pagination)
@@ -75,6 +122,7 @@ impl SeriesServiceTrait for SeriesService {
             count: paginated.count,
             next: paginated.next,
             previous: paginated.previous,
+            next_cursor: paginated.next_cursor,
             results: paginated.results.into_iter().map(SeriesDto::from).collect(),
         })
     }
@@ -85,6 +133,7 @@ impl SeriesServiceTrait for SeriesService {
         pagination: PaginationQuery,
         user_id: String,
     ) -> Result<PaginatedResponse<SeriesDto>, AppError> {
+        resolve_ordering(&pagination, ENTITY_ORDERING_FIELDS)?;
         let paginated = self
             .affinity_repo
             .find_list_paginated_by_affinity(&self.db, search_dto, pagination, &user_id)
@@ -95,18 +144,21 @@ impl SeriesServiceTrait for SeriesService {
             count: paginated.count,
             next: paginated.next,
             previous: paginated.previous,
+            next_cursor: paginated.next_cursor,
             results: paginated.results.into_iter().map(SeriesDto::from).collect(),
         })
     }
 
     async fn get_series(&self) -> Result<Vec<SeriesDto>, AppError> {
-        let series = self
-            .repo
-            .find_all(&self.db)
+        self.cache
+            .entity_list(SearchEntityType::Series, || async {
+                self.repo
+                    .find_all(&self.db)
+                    .await
+                    .map(|series| series.into_iter().map(SeriesDto::from).collect())
+                    .map_err(AppError::DatabaseError)
+            })
             .await
-            .map_err(AppError::DatabaseError)?;
-
-        Ok(series.into_iter().map(SeriesDto::from).collect())
     }
 
     async fn create_series(&self, create_dto: CreateSeriesDto) -> Result<SeriesDto, AppError> {
@@ -133,6 +185,13 @@ impl SeriesServiceTrait for SeriesService {
 
         txn.commit().await.map_err(AppError::DatabaseError)?;
 
+        if was_created {
+            self.events
+                .publish_entity(SearchEntityType::Series, id, CatalogAction::Created);
+            self.cache
+                .invalidate_entities(SearchEntityType::Series, &[id])
+                .await;
+        }
         self.get_series_by_id(id).await
     }
 
@@ -204,16 +263,53 @@ impl SeriesServiceTrait for SeriesService {
 
         txn.commit().await.map_err(AppError::DatabaseError)?;
 
+        if surviving_id != id {
+            self.events
+                .publish_entity(SearchEntityType::Series, id, CatalogAction::Deleted);
+        }
+        self.events.publish_entity(
+            SearchEntityType::Series,
+            surviving_id,
+            CatalogAction::Updated,
+        );
+        self.cache
+            .invalidate_entities(SearchEntityType::Series, &[id, surviving_id])
+            .await;
         Ok(SeriesDto::from(series))
     }
 
-    async fn delete_series(&self, id: i64) -> Result<String, AppError> {
+    async fn delete_series(&self, id: i64, reassign_to: Option<i64>) -> Result<String, AppError> {
+        if let Some(target_id) = reassign_to {
+            merge_named_entity(
+                &self.db,
+                &*self.merge_repo,
+                &self.events,
+                &self.cache,
+                SearchEntityType::Series,
+                target_id,
+                id,
+            )
+            .await?;
+            return Ok("Series deleted successfully".into());
+        }
+
         let txn = self.db.begin().await.map_err(AppError::DatabaseError)?;
 
         let affected = search_outbox::find_affected_record_ids(&txn, SearchEntityType::Series, id)
             .await
             .map_err(AppError::DatabaseError)?;
 
+        if !affected.is_empty() {
+            txn.rollback().await?;
+            return Err(AppError::ConflictWithRecords(
+                format!(
+                    "Series {id} is still referenced by {} record(s)",
+                    affected.len()
+                ),
+                affected,
+            ));
+        }
+
         let deleted = self
             .repo
             .delete(&txn, id)
@@ -233,14 +329,44 @@ impl SeriesServiceTrait for SeriesService {
             .map_err(AppError::DatabaseError)?;
 
         txn.commit().await.map_err(AppError::DatabaseError)?;
+        self.events
+            .publish_entity(SearchEntityType::Series, id, CatalogAction::Deleted);
+        self.cache
+            .invalidate_entities(SearchEntityType::Series, &[id])
+            .await;
         Ok("Series deleted successfully".to_owned())
     }
 
     /// Gets record counts grouped by series.
-    async fn get_series_record_counts(&self) -> Result<Vec<EntityCountDto>, AppError> {
-        self.repo
-            .get_series_record_counts(&self.db)
-            .await
-            .map_err(AppError::DatabaseError)
+    async fn merge_series(&self, id: i64, source_id: i64) -> Result<MergeEntityResponse, AppError> {
+        merge_named_entity(
+            &self.db,
+            &*self.merge_repo,
+            &self.events,
+            &self.cache,
+            SearchEntityType::Series,
+            id,
+            source_id,
+        )
+        .await
+    }
+
+    async fn get_series_record_counts(
+        &self,
+        query: RecordCountQuery,
+    ) -> Result<PaginatedResponse<EntityCountDto>, AppError> {
+        let load = || async {
+            self.repo
+                .get_series_record_counts(&self.db, &query)
+                .await
+                .map_err(AppError::DatabaseError)
+        };
+        if query.is_first_page() {
+            self.cache
+                .record_counts(SearchEntityType::Series, load)
+                .await
+        } else {
+            load().await
+        }
     }
 }
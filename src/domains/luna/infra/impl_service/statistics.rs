@@ -0,0 +1,299 @@
+use crate::{
+    common::{config::DEFAULT_PAGE_SIZE, error::AppError},
+    domains::luna::{
+        domain::{StatisticsRepository, StatisticsServiceTrait},
+        dto::{
+            CoOccurrenceQuery, DateCountDto, DateGranularity, EntityCountDto, ExportEntity,
+            LinkMonthDto, LinkStatisticsDto, MonthCountDto, RecordsByDateQuery,
+            StatisticsOverviewDto,
+        },
+        infra::{catalog_cache::CatalogCache, StatisticsRepo},
+    },
+};
+use async_trait::async_trait;
+use chrono::{Datelike as _, NaiveDate, Utc};
+use sea_orm::{prelude::Decimal, DatabaseConnection};
+use std::sync::Arc;
+
+/// Months of growth shown by the overview, including the current one.
+const OVERVIEW_MONTHS: i32 = 24;
+
+/// Entities listed in each top list of the overview.
+const OVERVIEW_TOP_LIMIT: u64 = 10;
+
+/// Service struct for catalog-wide statistics.
+#[derive(Clone)]
+pub struct StatisticsService {
+    db: DatabaseConnection,
+    repo: Arc<dyn StatisticsRepository>,
+    cache: Arc<CatalogCache>,
+}
+
+impl StatisticsService {
+    pub fn create_service(
+        db: DatabaseConnection,
+        cache: Arc<CatalogCache>,
+    ) -> Arc<dyn StatisticsServiceTrait> {
+        Arc::new(Self {
+            db,
+            repo: Arc::new(StatisticsRepo),
+            cache,
+        })
+    }
+
+    async fn load_overview(&self) -> Result<StatisticsOverviewDto, AppError> {
+        let (first, current, since) = overview_window()?;
+        let added = self
+            .repo
+            .records_added_per_month(&self.db, since)
+            .await
+            .map_err(AppError::DatabaseError)?;
+        let mut records_per_month = Vec::new();
+        for index in first..=current {
+            let Some(month) = month_start(index) else {
+                continue;
+            };
+            let count = added
+                .iter()
+                .find(|(added_month, _)| *added_month == month)
+                .map_or(0, |(_, count)| *count);
+            records_per_month.push(MonthCountDto {
+                month: month.format("%Y-%m").to_string(),
+                count,
+            });
+        }
+
+        Ok(StatisticsOverviewDto {
+            totals: self
+                .repo
+                .catalog_totals(&self.db)
+                .await
+                .map_err(AppError::DatabaseError)?,
+            records_per_month,
+            top_genres: self.top(ExportEntity::Genres).await?,
+            top_idols: self.top(ExportEntity::Idols).await?,
+            top_studios: self.top(ExportEntity::Studios).await?,
+            average_duration: self
+                .repo
+                .average_duration(&self.db)
+                .await
+                .map_err(AppError::DatabaseError)?,
+        })
+    }
+
+    async fn top(&self, entity: ExportEntity) -> Result<Vec<EntityCountDto>, AppError> {
+        self.repo
+            .top_entities(&self.db, entity, OVERVIEW_TOP_LIMIT)
+            .await
+            .map_err(AppError::DatabaseError)
+    }
+
+    /// Entities of type `entity` sharing records visible at `max_permission`
+    /// with row `id`, or `not_found` when that row does not exist.
+    async fn co_occurring(
+        &self,
+        entity: ExportEntity,
+        id: i64,
+        query: CoOccurrenceQuery,
+        max_permission: i32,
+        not_found: &str,
+    ) -> Result<Vec<EntityCountDto>, AppError> {
+        let exists = self
+            .repo
+            .entity_exists(&self.db, entity, id)
+            .await
+            .map_err(AppError::DatabaseError)?;
+        if !exists {
+            return Err(AppError::NotFound(not_found.to_owned()));
+        }
+        let limit = query
+            .limit
+            .filter(|&limit| limit > 0)
+            .unwrap_or(DEFAULT_PAGE_SIZE);
+        self.repo
+            .co_occurring(&self.db, entity, id, limit, max_permission)
+            .await
+            .map_err(AppError::DatabaseError)
+    }
+}
+
+/// First and current month index of the overview, and the first day of the
+/// first month.
+fn overview_window() -> Result<(i32, i32, NaiveDate), AppError> {
+    let current = month_index(Utc::now().date_naive());
+    let first = current - (OVERVIEW_MONTHS - 1);
+    let since = month_start(first)
+        .ok_or_else(|| AppError::InternalErrorWithMessage("Month out of range".to_owned()))?;
+    Ok((first, current, since))
+}
+
+/// Months since year 0, so month arithmetic is plain integer arithmetic.
+fn month_index(date: NaiveDate) -> i32 {
+    date.year() * 12 + date.month0() as i32
+}
+
+/// First day of the month `index` counts.
+fn month_start(index: i32) -> Option<NaiveDate> {
+    NaiveDate::from_ymd_opt(index.div_euclid(12), index.rem_euclid(12) as u32 + 1, 1)
+}
+
+/// Position of `date`'s bucket, consecutive buckets differing by one.
+fn bucket_index(date: NaiveDate, granularity: DateGranularity) -> i32 {
+    match granularity {
+        DateGranularity::Month => month_index(date),
+        DateGranularity::Year => date.year(),
+    }
+}
+
+/// First day of the bucket `index` counts.
+fn bucket_start(index: i32, granularity: DateGranularity) -> Option<NaiveDate> {
+    match granularity {
+        DateGranularity::Month => month_start(index),
+        DateGranularity::Year => NaiveDate::from_ymd_opt(index, 1, 1),
+    }
+}
+
+#[async_trait]
+impl StatisticsServiceTrait for StatisticsService {
+    async fn overview(&self) -> Result<StatisticsOverviewDto, AppError> {
+        self.cache.overview(|| self.load_overview()).await
+    }
+
+    async fn link_statistics(&self) -> Result<LinkStatisticsDto, AppError> {
+        let (first, current, since) = overview_window()?;
+        let (total_count, total_size) = self
+            .repo
+            .link_totals(&self.db)
+            .await
+            .map_err(AppError::DatabaseError)?;
+        let added = self
+            .repo
+            .links_added_per_month(&self.db, since)
+            .await
+            .map_err(AppError::DatabaseError)?;
+        let mut added = added.into_iter().peekable();
+        let mut links_per_month = Vec::new();
+        for index in first..=current {
+            let Some(month) = month_start(index) else {
+                continue;
+            };
+            let (count, size) = added
+                .next_if(|(added_month, _, _)| *added_month == month)
+                .map_or((0, Decimal::ZERO), |(_, count, size)| (count, size));
+            links_per_month.push(LinkMonthDto {
+                month: month.format("%Y-%m").to_string(),
+                count,
+                size,
+            });
+        }
+
+        Ok(LinkStatisticsDto {
+            total_count,
+            total_size,
+            links_per_month,
+        })
+    }
+
+    async fn records_by_date(
+        &self,
+        query: RecordsByDateQuery,
+    ) -> Result<Vec<DateCountDto>, AppError> {
+        if let (Some(from), Some(to)) = (query.from, query.to) {
+            if from > to {
+                return Err(AppError::ValidationError(
+                    "`from` must not be after `to`".into(),
+                ));
+            }
+        }
+        let granularity = query.granularity.unwrap_or_default();
+        let buckets = self
+            .repo
+            .records_by_release_date(&self.db, granularity, query.from, query.to)
+            .await
+            .map_err(AppError::DatabaseError)?;
+
+        let (Some(&(first, _)), Some(&(last, _))) = (buckets.first(), buckets.last()) else {
+            return Ok(Vec::new());
+        };
+        let mut counts = buckets.into_iter().peekable();
+        let mut series = Vec::new();
+        for index in bucket_index(first, granularity)..=bucket_index(last, granularity) {
+            let Some(start) = bucket_start(index, granularity) else {
+                continue;
+            };
+            let count = counts
+                .next_if(|(period, _)| *period == start)
+                .map_or(0, |(_, count)| count);
+            series.push(DateCountDto {
+                period: start.format(granularity.label_format()).to_string(),
+                count,
+            });
+        }
+        Ok(series)
+    }
+
+    async fn co_stars(
+        &self,
+        id: i64,
+        query: CoOccurrenceQuery,
+        max_permission: i32,
+    ) -> Result<Vec<EntityCountDto>, AppError> {
+        self.co_occurring(
+            ExportEntity::Idols,
+            id,
+            query,
+            max_permission,
+            "Idol not found",
+        )
+        .await
+    }
+
+    async fn related_genres(
+        &self,
+        id: i64,
+        query: CoOccurrenceQuery,
+        max_permission: i32,
+    ) -> Result<Vec<EntityCountDto>, AppError> {
+        self.co_occurring(
+            ExportEntity::Genres,
+            id,
+            query,
+            max_permission,
+            "Genre not found",
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{bucket_index, bucket_start, month_index, month_start};
+    use crate::domains::luna::dto::DateGranularity;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn month_arithmetic_crosses_years() {
+        let date = NaiveDate::from_ymd_opt(2026, 2, 17).expect("valid date");
+        let index = month_index(date);
+        assert_eq!(
+            month_start(index),
+            NaiveDate::from_ymd_opt(2026, 2, 1),
+            "same month"
+        );
+        assert_eq!(
+            month_start(index - 23),
+            NaiveDate::from_ymd_opt(2024, 3, 1),
+            "23 months earlier"
+        );
+    }
+
+    #[test]
+    fn year_buckets_start_on_new_year() {
+        let date = NaiveDate::from_ymd_opt(2019, 8, 30).expect("valid date");
+        let index = bucket_index(date, DateGranularity::Year);
+        assert_eq!(
+            bucket_start(index + 1, DateGranularity::Year),
+            NaiveDate::from_ymd_opt(2020, 1, 1)
+        );
+    }
+}
@@ -1,13 +1,21 @@
+use super::merge::merge_named_entity;
 use crate::domains::search::SearchEntityType;
 use crate::{
-    common::error::AppError,
+    common::{error::AppError, pagination::resolve_ordering},
     domains::luna::{
-        domain::{StudioAffinityRepository, StudioRepository, StudioServiceTrait},
+        domain::{
+            NamedEntityMergeRepository, StudioAffinityRepository, StudioHierarchyRepository,
+            StudioRepository, StudioServiceTrait,
+        },
         dto::{
-            CreateStudioDto, EntityCountDto, PaginatedResponse, PaginationQuery, SearchStudioDto,
-            StudioDto, UpdateStudioDto,
+            AutocompleteQuery, CatalogAction, CreateStudioDto, EntityCountDto, LabelDto,
+            MergeEntityResponse, NameSuggestionDto, PaginatedResponse, PaginationQuery,
+            RecordCountQuery, SearchStudioDto, StudioChildrenDto, StudioDto, StudioRollupDto,
+            UpdateStudioDto, ENTITY_ORDERING_FIELDS,
+        },
+        infra::{
+            catalog_cache::CatalogCache, catalog_events::CatalogEvents, search_outbox, StudioRepo,
         },
-        infra::{search_outbox, StudioRepo},
     },
 };
 use async_trait::async_trait;
@@ -23,25 +31,70 @@ pub struct StudioService {
     /// object cannot expose `StudioAffinityRepository` methods, so the affinity
     /// trait needs its own trait object (both wrap `StudioRepo`).
     affinity_repo: Arc<dyn StudioAffinityRepository + Send + Sync>,
+    /// Merge handle; also wraps `StudioRepo`.
+    merge_repo: Arc<dyn NamedEntityMergeRepository + Send + Sync>,
+    /// Hierarchy handle; also wraps `StudioRepo`.
+    hierarchy_repo: Arc<dyn StudioHierarchyRepository + Send + Sync>,
+    events: Arc<CatalogEvents>,
+    cache: Arc<CatalogCache>,
 }
 
 #[async_trait]
 impl StudioServiceTrait for StudioService {
-    fn create_service(db: DatabaseConnection) -> Arc<dyn StudioServiceTrait> {
+    fn create_service(
+        db: DatabaseConnection,
+        events: Arc<CatalogEvents>,
+        cache: Arc<CatalogCache>,
+    ) -> Arc<dyn StudioServiceTrait> {
         Arc::new(Self {
             db: db.clone(),
             repo: Arc::new(StudioRepo),
             affinity_repo: Arc::new(StudioRepo),
+            merge_repo: Arc::new(StudioRepo),
+            hierarchy_repo: Arc::new(StudioRepo),
+            events,
+            cache,
         })
     }
 
     async fn get_studio_by_id(&self, id: i64) -> Result<StudioDto, AppError> {
-        self.repo
-            .find_by_id(&self.db, id)
+        self.cache
+            .entity(SearchEntityType::Studio, id, || async {
+                self.repo
+                    .find_by_id(&self.db, id)
+                    .await
+                    .map_err(AppError::DatabaseError)?
+                    .map(StudioDto::from)
+                    .ok_or_else(|| AppError::NotFound("Studio not found".into()))
+            })
+            .await
+    }
+
+    async fn get_studios_by_ids(&self, ids: &[i64]) -> Result<Vec<StudioDto>, AppError> {
+        let studios = self
+            .repo
+            .find_by_ids(&self.db, ids)
+            .await
+            .map_err(AppError::DatabaseError)?;
+        Ok(studios.into_iter().map(StudioDto::from).collect())
+    }
+
+    async fn autocomplete_studios(
+        &self,
+        query: AutocompleteQuery,
+    ) -> Result<Vec<NameSuggestionDto>, AppError> {
+        let term = query
+            .term()
+            .ok_or_else(|| AppError::ValidationError("q must not be blank".into()))?;
+        let limit = query.limit();
+        self.cache
+            .suggestions(SearchEntityType::Studio, &term, limit, || async {
+                self.repo
+                    .autocomplete(&self.db, &term, limit)
+                    .await
+                    .map_err(AppError::DatabaseError)
+            })
             .await
-            .map_err(AppError::DatabaseError)?
-            .map(StudioDto::from)
-            .ok_or_else(|| AppError::NotFound("Studio not found".into()))
     }
 
     async fn get_studio_list(
@@ -57,6 +110,7 @@ impl StudioServiceTrait for StudioService {
         search_dto: SearchStudioDto,
         pagination: PaginationQuery,
     ) -> Result<PaginatedResponse<StudioDto>, AppError> {
+        resolve_ordering(&pagination, ENTITY_ORDERING_FIELDS)?;
         let paginated = self
             .repo
             .find_list_paginated(&self.db, search_dto, pagination)
@@ -65,13 +119,21 @@ impl StudioServiceTrait for StudioService {
             count: paginated.count,
             next: paginated.next,
             previous: paginated.previous,
+            next_cursor: paginated.next_cursor,
             results: paginated.results.into_iter().map(StudioDto::from).collect(),
         })
     }
 
     async fn get_studios(&self) -> Result<Vec<StudioDto>, AppError> {
-        let studios = self.repo.find_all(&self.db).await?;
-        Ok(studios.into_iter().map(StudioDto::from).collect())
+        self.cache
+            .entity_list(SearchEntityType::Studio, || async {
+                self.repo
+                    .find_all(&self.db)
+                    .await
+                    .map(|studios| studios.into_iter().map(StudioDto::from).collect())
+                    .map_err(AppError::DatabaseError)
+            })
+            .await
     }
 
     async fn get_studio_list_by_affinity(
@@ -80,6 +142,7 @@ impl StudioServiceTrait for StudioService {
         pagination: PaginationQuery,
         user_id: String,
     ) -> Result<PaginatedResponse<StudioDto>, AppError> {
+        resolve_ordering(&pagination, ENTITY_ORDERING_FIELDS)?;
         let paginated = self
             .affinity_repo
             .find_list_paginated_by_affinity(&self.db, search_dto, pagination, &user_id)
@@ -89,6 +152,7 @@ impl StudioServiceTrait for StudioService {
             count: paginated.count,
             next: paginated.next,
             previous: paginated.previous,
+            next_cursor: paginated.next_cursor,
             results: paginated.results.into_iter().map(StudioDto::from).collect(),
         })
     }
@@ -117,6 +181,13 @@ impl StudioServiceTrait for StudioService {
         }
 
         txn.commit().await?;
+        if was_created {
+            self.events
+                .publish_entity(SearchEntityType::Studio, studio_id, CatalogAction::Created);
+            self.cache
+                .invalidate_entities(SearchEntityType::Studio, &[studio_id])
+                .await;
+        }
         self.get_studio_by_id(studio_id).await
     }
 
@@ -188,16 +259,128 @@ impl StudioServiceTrait for StudioService {
         }
 
         txn.commit().await?;
+        if surviving_id != id {
+            self.events
+                .publish_entity(SearchEntityType::Studio, id, CatalogAction::Deleted);
+        }
+        self.events.publish_entity(
+            SearchEntityType::Studio,
+            surviving_id,
+            CatalogAction::Updated,
+        );
+        self.cache
+            .invalidate_entities(SearchEntityType::Studio, &[id, surviving_id])
+            .await;
+        Ok(StudioDto::from(studio))
+    }
+
+    async fn set_studio_parent(
+        &self,
+        id: i64,
+        parent_id: Option<i64>,
+    ) -> Result<StudioDto, AppError> {
+        if id == 0 || parent_id == Some(0) {
+            return Err(AppError::ValidationError(
+                "The placeholder studio cannot be part of a hierarchy".into(),
+            ));
+        }
+
+        let txn = self.db.begin().await?;
+        if let Some(parent_id) = parent_id {
+            let lineage = match self.hierarchy_repo.find_lineage(&txn, parent_id).await {
+                Ok(lineage) => lineage,
+                Err(e) => {
+                    txn.rollback().await.ok();
+                    return Err(AppError::DatabaseError(e));
+                }
+            };
+            if lineage.is_empty() {
+                txn.rollback().await?;
+                return Err(AppError::NotFound("Parent studio not found".into()));
+            }
+            if lineage.contains(&id) {
+                txn.rollback().await?;
+                return Err(AppError::ValidationError(
+                    "A studio cannot be moved under itself or a studio below it".into(),
+                ));
+            }
+        }
+        let studio = match self.hierarchy_repo.set_parent(&txn, id, parent_id).await {
+            Ok(Some(studio)) => studio,
+            Ok(None) => {
+                txn.rollback().await?;
+                return Err(AppError::NotFound("Studio not found".into()));
+            }
+            Err(e) => {
+                txn.rollback().await.ok();
+                return Err(AppError::DatabaseError(e));
+            }
+        };
+        txn.commit().await?;
+
+        self.events
+            .publish_entity(SearchEntityType::Studio, id, CatalogAction::Updated);
+        // Hydrated records carry their studio
+        self.cache
+            .invalidate_entities(SearchEntityType::Studio, &[id])
+            .await;
+        self.cache.invalidate_records().await;
         Ok(StudioDto::from(studio))
     }
 
-    async fn delete_studio(&self, id: i64) -> Result<String, AppError> {
+    async fn get_studio_children(&self, id: i64) -> Result<StudioChildrenDto, AppError> {
+        self.get_studio_by_id(id).await?;
+        let (studios, labels) = self
+            .hierarchy_repo
+            .find_children(&self.db, id)
+            .await
+            .map_err(AppError::DatabaseError)?;
+        Ok(StudioChildrenDto {
+            studios: studios.into_iter().map(StudioDto::from).collect(),
+            labels: labels.into_iter().map(LabelDto::from).collect(),
+        })
+    }
+
+    async fn get_studio_rollup(&self, id: i64) -> Result<StudioRollupDto, AppError> {
+        self.hierarchy_repo
+            .rollup(&self.db, id)
+            .await
+            .map_err(AppError::DatabaseError)?
+            .ok_or_else(|| AppError::NotFound("Studio not found".into()))
+    }
+
+    async fn delete_studio(&self, id: i64, reassign_to: Option<i64>) -> Result<String, AppError> {
+        if let Some(target_id) = reassign_to {
+            merge_named_entity(
+                &self.db,
+                &*self.merge_repo,
+                &self.events,
+                &self.cache,
+                SearchEntityType::Studio,
+                target_id,
+                id,
+            )
+            .await?;
+            return Ok("Studio deleted successfully".into());
+        }
+
         let txn = self.db.begin().await?;
 
         let affected = search_outbox::find_affected_record_ids(&txn, SearchEntityType::Studio, id)
             .await
             .map_err(AppError::DatabaseError)?;
 
+        if !affected.is_empty() {
+            txn.rollback().await?;
+            return Err(AppError::ConflictWithRecords(
+                format!(
+                    "Studio {id} is still referenced by {} record(s)",
+                    affected.len()
+                ),
+                affected,
+            ));
+        }
+
         match self.repo.delete(&txn, id).await {
             Ok(true) => {}
             Ok(false) => {
@@ -218,13 +401,43 @@ impl StudioServiceTrait for StudioService {
             .map_err(AppError::DatabaseError)?;
 
         txn.commit().await?;
+        self.events
+            .publish_entity(SearchEntityType::Studio, id, CatalogAction::Deleted);
+        self.cache
+            .invalidate_entities(SearchEntityType::Studio, &[id])
+            .await;
         Ok("Studio deleted successfully".into())
     }
 
-    async fn get_studio_record_counts(&self) -> Result<Vec<EntityCountDto>, AppError> {
-        self.repo
-            .get_studio_record_counts(&self.db)
-            .await
-            .map_err(AppError::DatabaseError)
+    async fn merge_studio(&self, id: i64, source_id: i64) -> Result<MergeEntityResponse, AppError> {
+        merge_named_entity(
+            &self.db,
+            &*self.merge_repo,
+            &self.events,
+            &self.cache,
+            SearchEntityType::Studio,
+            id,
+            source_id,
+        )
+        .await
+    }
+
+    async fn get_studio_record_counts(
+        &self,
+        query: RecordCountQuery,
+    ) -> Result<PaginatedResponse<EntityCountDto>, AppError> {
+        let load = || async {
+            self.repo
+                .get_studio_record_counts(&self.db, &query)
+                .await
+                .map_err(AppError::DatabaseError)
+        };
+        if query.is_first_page() {
+            self.cache
+                .record_counts(SearchEntityType::Studio, load)
+                .await
+        } else {
+            load().await
+        }
     }
 }
@@ -0,0 +1,177 @@
+use crate::{
+    common::error::AppError,
+    domains::luna::{
+        domain::{TagRepository, TagServiceTrait},
+        dto::{
+            AttachTagsDto, CreateTagDto, SearchTagDto, TagCategoryDto, TagCountDto, TagDto,
+            DEFAULT_TAG_CLOUD_LIMIT, MAX_TAG_CLOUD_LIMIT,
+        },
+        infra::TagRepo,
+    },
+};
+use async_trait::async_trait;
+use sea_orm::DatabaseConnection;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// Service struct for user-defined tags.
+#[derive(Clone)]
+pub struct TagService {
+    db: DatabaseConnection,
+    repo: Arc<dyn TagRepository>,
+}
+
+impl TagService {
+    pub fn create_service(db: DatabaseConnection) -> Arc<dyn TagServiceTrait> {
+        Arc::new(Self {
+            db,
+            repo: Arc::new(TagRepo),
+        })
+    }
+
+    /// Reject tags pointing at a category that does not exist.
+    async fn ensure_category(&self, category_id: i64) -> Result<(), AppError> {
+        let exists = self
+            .repo
+            .category_exists(&self.db, category_id)
+            .await
+            .map_err(AppError::DatabaseError)?;
+        if !exists {
+            return Err(AppError::ValidationError(format!(
+                "Tag category {category_id} does not exist"
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl TagServiceTrait for TagService {
+    async fn list_tags(&self, search: SearchTagDto) -> Result<Vec<TagDto>, AppError> {
+        self.repo
+            .find_all(&self.db, search)
+            .await
+            .map_err(AppError::DatabaseError)
+    }
+
+    async fn get_tag(&self, id: i64) -> Result<TagDto, AppError> {
+        self.repo
+            .find_by_id(&self.db, id)
+            .await
+            .map_err(AppError::DatabaseError)?
+            .ok_or_else(|| AppError::NotFound("Tag not found".into()))
+    }
+
+    async fn list_categories(&self) -> Result<Vec<TagCategoryDto>, AppError> {
+        self.repo
+            .find_categories(&self.db)
+            .await
+            .map_err(AppError::DatabaseError)
+    }
+
+    async fn create_tag(&self, dto: CreateTagDto) -> Result<TagDto, AppError> {
+        self.ensure_category(dto.category_id).await?;
+        let id = self
+            .repo
+            .create(&self.db, dto)
+            .await
+            .map_err(AppError::DatabaseError)?;
+        self.get_tag(id).await
+    }
+
+    async fn update_tag(&self, id: i64, dto: CreateTagDto) -> Result<TagDto, AppError> {
+        self.ensure_category(dto.category_id).await?;
+        let updated = self
+            .repo
+            .update(&self.db, id, dto)
+            .await
+            .map_err(AppError::DatabaseError)?;
+        if !updated {
+            return Err(AppError::NotFound("Tag not found".into()));
+        }
+        self.get_tag(id).await
+    }
+
+    async fn delete_tag(&self, id: i64) -> Result<(), AppError> {
+        let deleted = self
+            .repo
+            .delete(&self.db, id)
+            .await
+            .map_err(AppError::DatabaseError)?;
+        if !deleted {
+            return Err(AppError::NotFound("Tag not found".into()));
+        }
+        Ok(())
+    }
+
+    async fn record_tags(&self, record_id: &str) -> Result<Vec<TagDto>, AppError> {
+        self.repo
+            .find_by_record(&self.db, record_id)
+            .await
+            .map_err(AppError::DatabaseError)
+    }
+
+    async fn attach_tags(
+        &self,
+        record_id: &str,
+        dto: AttachTagsDto,
+    ) -> Result<Vec<TagDto>, AppError> {
+        let mut tag_ids = dto.tag_ids;
+        tag_ids.sort_unstable();
+        tag_ids.dedup();
+
+        let existing: HashSet<i64> = self
+            .repo
+            .find_existing_ids(&self.db, &tag_ids)
+            .await
+            .map_err(AppError::DatabaseError)?
+            .into_iter()
+            .collect();
+        let missing: Vec<String> = tag_ids
+            .iter()
+            .filter(|id| !existing.contains(id))
+            .map(ToString::to_string)
+            .collect();
+        if !missing.is_empty() {
+            return Err(AppError::ValidationError(format!(
+                "Unknown tag IDs: {}",
+                missing.join(", ")
+            )));
+        }
+
+        self.repo
+            .attach(&self.db, record_id, &tag_ids)
+            .await
+            .map_err(AppError::DatabaseError)?;
+        self.record_tags(record_id).await
+    }
+
+    async fn detach_tag(&self, record_id: &str, tag_id: i64) -> Result<(), AppError> {
+        let detached = self
+            .repo
+            .detach(&self.db, record_id, tag_id)
+            .await
+            .map_err(AppError::DatabaseError)?;
+        if !detached {
+            return Err(AppError::NotFound(
+                "Tag is not attached to the record".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    async fn tag_cloud(
+        &self,
+        limit: Option<u64>,
+        max_permission: i32,
+    ) -> Result<Vec<TagCountDto>, AppError> {
+        let limit = limit.unwrap_or(DEFAULT_TAG_CLOUD_LIMIT);
+        if limit == 0 {
+            return Err(AppError::ValidationError("limit must be > 0".into()));
+        }
+        self.repo
+            .usage_counts(&self.db, limit.min(MAX_TAG_CLOUD_LIMIT), max_permission)
+            .await
+            .map_err(AppError::DatabaseError)
+    }
+}
@@ -0,0 +1,270 @@
+//! Image processing for the `thumbnails` feature.
+//!
+//! Thumbnails are JPEG files cached under
+//! `assets_private_path/thumbnails/{type}/{id}/` and regenerated when the
+//! original is newer than the cached copy, whichever storage backend keeps
+//! the original. Uploads can also be re-encoded here to drop their metadata.
+
+use crate::domains::luna::dto::{ImageKind, MediaType, ThumbnailFit, ThumbnailSpec};
+use crate::domains::luna::infra::media_storage::MediaStorage;
+use image::{
+    codecs::jpeg::JpegEncoder, imageops::FilterType, DynamicImage, ImageDecoder as _, ImageFormat,
+    ImageReader,
+};
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use thiserror::Error;
+use tokio::fs;
+
+/// JPEG quality of generated thumbnails.
+const THUMBNAIL_QUALITY: u8 = 82;
+
+/// JPEG quality of re-encoded uploads.
+const UPLOAD_QUALITY: u8 = 92;
+
+/// Extensions of the originals thumbnails can be generated from.
+const THUMBNAIL_SOURCE_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "webp", "gif", "bmp"];
+
+/// Error raised while generating a thumbnail.
+#[derive(Debug, Error)]
+pub(super) enum ThumbnailError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    #[error(transparent)]
+    Image(#[from] image::ImageError),
+
+    #[error("Thumbnail task failed: {0}")]
+    Task(#[from] tokio::task::JoinError),
+}
+
+/// Whether a thumbnail can be generated from a file with this extension.
+pub(super) fn is_supported(extension: &str) -> bool {
+    THUMBNAIL_SOURCE_EXTENSIONS.contains(&extension.to_ascii_lowercase().as_str())
+}
+
+/// Rounds `requested` up to the next of `sizes`, or down to the largest.
+pub(super) fn snap_size(requested: u32, sizes: &[u32]) -> u32 {
+    sizes
+        .iter()
+        .copied()
+        .find(|&size| size >= requested)
+        .or_else(|| sizes.last().copied())
+        .unwrap_or(requested)
+}
+
+/// `spec` with both sides rounded to one of `sizes`.
+pub(super) fn snap_spec(spec: ThumbnailSpec, sizes: &[u32]) -> ThumbnailSpec {
+    ThumbnailSpec {
+        width: spec.width.map(|w| snap_size(w, sizes)),
+        height: spec.height.map(|h| snap_size(h, sizes)),
+        fit: spec.fit,
+    }
+}
+
+/// Key of the cached thumbnail of `filename_base` under `spec`, relative to
+/// the private assets directory, named `{filename_base}_{w}x{h}_{fit}.jpg`
+/// with a missing side written as 0.
+pub(super) fn cache_key(
+    media_type: &MediaType,
+    id: &str,
+    filename_base: &str,
+    spec: ThumbnailSpec,
+) -> String {
+    format!(
+        "thumbnails/{}/{id}/{filename_base}_{}x{}_{}.jpg",
+        media_type.get_sub_dir_name(),
+        spec.width.unwrap_or(0),
+        spec.height.unwrap_or(0),
+        spec.fit.as_str()
+    )
+}
+
+/// Generates the thumbnail of `source` in `storage` under `spec` at
+/// `target`, unless a copy at least as new as `source_modified` is already
+/// there. The file is written under a temporary name and renamed, so readers
+/// never see a partial file.
+pub(super) async fn ensure_thumbnail(
+    storage: &dyn MediaStorage,
+    source: &str,
+    source_modified: Option<SystemTime>,
+    target: &Path,
+    spec: ThumbnailSpec,
+) -> Result<(), ThumbnailError> {
+    if is_fresh(source_modified, target).await {
+        return Ok(());
+    }
+
+    let bytes = storage.read(source, None).await?;
+    let thumbnail = tokio::task::spawn_blocking(move || render(&bytes, spec)).await??;
+
+    if let Some(dir) = target.parent() {
+        fs::create_dir_all(dir).await?;
+    }
+    let tmp = target.with_file_name(format!(".{}.tmp", uuid::Uuid::new_v4()));
+    fs::write(&tmp, thumbnail).await?;
+    if let Err(e) = fs::rename(&tmp, target).await {
+        drop(fs::remove_file(&tmp).await);
+        return Err(e.into());
+    }
+    Ok(())
+}
+
+/// Whether `target` exists and was written no earlier than `source_modified`.
+async fn is_fresh(source_modified: Option<SystemTime>, target: &Path) -> bool {
+    let Some(source_modified) = source_modified else {
+        return false;
+    };
+    fs::metadata(target)
+        .await
+        .and_then(|target| target.modified())
+        .is_ok_and(|target| target >= source_modified)
+}
+
+/// Decodes `bytes`, scales the image into `spec` and encodes it as JPEG.
+pub(super) fn render(bytes: &[u8], spec: ThumbnailSpec) -> Result<Vec<u8>, image::ImageError> {
+    let image = resize(&image::load_from_memory(bytes)?, spec);
+    let mut out = Vec::new();
+    JpegEncoder::new_with_quality(&mut out, THUMBNAIL_QUALITY).encode_image(&image.to_rgb8())?;
+    Ok(out)
+}
+
+/// Width and height of the image at `path`, read from its header.
+pub(super) async fn dimensions(path: PathBuf) -> Option<(u32, u32)> {
+    tokio::task::spawn_blocking(move || image::image_dimensions(path))
+        .await
+        .ok()?
+        .ok()
+}
+
+/// Decodes an upload of format `kind` and encodes it again in the same
+/// format, dropping EXIF and any other metadata. The EXIF orientation is
+/// applied to the pixels first so the image still displays upright. WebP is
+/// written lossless, the only WebP encoding available.
+pub(super) fn strip_metadata(bytes: &[u8], kind: ImageKind) -> Result<Vec<u8>, image::ImageError> {
+    let format = match kind {
+        ImageKind::Jpeg => ImageFormat::Jpeg,
+        ImageKind::Png => ImageFormat::Png,
+        ImageKind::Webp => ImageFormat::WebP,
+    };
+    let mut decoder = ImageReader::with_format(Cursor::new(bytes), format).into_decoder()?;
+    let orientation = decoder.orientation()?;
+    let mut image = DynamicImage::from_decoder(decoder)?;
+    image.apply_orientation(orientation);
+
+    let mut out = Vec::new();
+    match kind {
+        ImageKind::Jpeg => JpegEncoder::new_with_quality(&mut out, UPLOAD_QUALITY)
+            .encode_image(&image.to_rgb8())?,
+        ImageKind::Png | ImageKind::Webp => image.write_to(&mut Cursor::new(&mut out), format)?,
+    }
+    Ok(out)
+}
+
+/// Scales `image` into the box of `spec`. Images are never enlarged: a box
+/// bigger than the image is shrunk, keeping its aspect ratio, until it fits.
+fn resize(image: &DynamicImage, spec: ThumbnailSpec) -> DynamicImage {
+    let (width, height) = (image.width(), image.height());
+    match (spec.width, spec.height, spec.fit) {
+        (Some(w), Some(h), ThumbnailFit::Cover) => {
+            let (w, h) = shrink_box(w, h, width, height);
+            image.resize_to_fill(w, h, FilterType::Triangle)
+        }
+        (Some(w), Some(h), ThumbnailFit::Fill) => {
+            let (w, h) = shrink_box(w, h, width, height);
+            image.resize_exact(w, h, FilterType::Triangle)
+        }
+        (w, h, _) => {
+            let w = w.unwrap_or(width).min(width);
+            let h = h.unwrap_or(height).min(height);
+            if w == width && h == height {
+                image.clone()
+            } else {
+                image.resize(w, h, FilterType::Triangle)
+            }
+        }
+    }
+}
+
+/// Scales the `w`×`h` box down uniformly until it fits in `width`×`height`.
+fn shrink_box(w: u32, h: u32, width: u32, height: u32) -> (u32, u32) {
+    if w <= width && h <= height {
+        return (w, h);
+    }
+    let scale = f64::min(
+        f64::from(width) / f64::from(w),
+        f64::from(height) / f64::from(h),
+    );
+    let scaled = |side: u32| ((f64::from(side) * scale).round() as u32).max(1);
+    (scaled(w), scaled(h))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{render, snap_size, ThumbnailFit, ThumbnailSpec};
+    use image::{codecs::png::PngEncoder, ImageEncoder as _, RgbImage};
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let mut out = Vec::new();
+        PngEncoder::new(&mut out)
+            .write_image(
+                RgbImage::new(width, height).as_raw(),
+                width,
+                height,
+                image::ExtendedColorType::Rgb8,
+            )
+            .expect("encode PNG");
+        out
+    }
+
+    fn rendered_size(
+        bytes: &[u8],
+        width: Option<u32>,
+        height: Option<u32>,
+        fit: ThumbnailFit,
+    ) -> (u32, u32) {
+        let spec = ThumbnailSpec { width, height, fit };
+        let thumbnail = image::load_from_memory(&render(bytes, spec).expect("render"))
+            .expect("decode thumbnail");
+        (thumbnail.width(), thumbnail.height())
+    }
+
+    #[test]
+    fn sizes_round_up_then_cap_at_largest() {
+        let sizes = [160, 320, 640];
+        assert_eq!(snap_size(1, &sizes), 160);
+        assert_eq!(snap_size(320, &sizes), 320);
+        assert_eq!(snap_size(321, &sizes), 640);
+        assert_eq!(snap_size(5000, &sizes), 640);
+    }
+
+    #[test]
+    fn fits_keep_aspect_ratio_and_never_enlarge() {
+        let bytes = png(400, 200);
+        assert_eq!(
+            rendered_size(&bytes, Some(100), None, ThumbnailFit::Contain),
+            (100, 50)
+        );
+        assert_eq!(
+            rendered_size(&bytes, Some(100), Some(100), ThumbnailFit::Contain),
+            (100, 50)
+        );
+        assert_eq!(
+            rendered_size(&bytes, Some(100), Some(100), ThumbnailFit::Cover),
+            (100, 100)
+        );
+        assert_eq!(
+            rendered_size(&bytes, Some(100), Some(100), ThumbnailFit::Fill),
+            (100, 100)
+        );
+        assert_eq!(
+            rendered_size(&bytes, Some(800), None, ThumbnailFit::Contain),
+            (400, 200)
+        );
+        assert_eq!(
+            rendered_size(&bytes, Some(800), Some(800), ThumbnailFit::Cover),
+            (200, 200)
+        );
+    }
+}
@@ -0,0 +1,114 @@
+use crate::{
+    common::error::AppError,
+    domains::luna::{
+        domain::{TranslationRepository, TranslationServiceTrait},
+        dto::{Language, TranslatedEntity, TranslatedNames, TranslationDto},
+        infra::TranslationRepo,
+    },
+};
+use async_trait::async_trait;
+use sea_orm::DatabaseConnection;
+use std::sync::Arc;
+
+/// Service struct for translated entity names.
+#[derive(Clone)]
+pub struct TranslationService {
+    db: DatabaseConnection,
+    repo: Arc<dyn TranslationRepository>,
+}
+
+impl TranslationService {
+    pub fn create_service(db: DatabaseConnection) -> Arc<dyn TranslationServiceTrait> {
+        Arc::new(Self {
+            db,
+            repo: Arc::new(TranslationRepo),
+        })
+    }
+
+    async fn ensure_entity_exists(
+        &self,
+        entity: TranslatedEntity,
+        id: i64,
+    ) -> Result<(), AppError> {
+        let exists = self
+            .repo
+            .entity_exists(&self.db, entity, id)
+            .await
+            .map_err(AppError::DatabaseError)?;
+        if !exists {
+            return Err(AppError::NotFound(format!(
+                "No {} with ID {id}",
+                entity.as_str()
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl TranslationServiceTrait for TranslationService {
+    async fn names(
+        &self,
+        language: Language,
+        keys: &[(TranslatedEntity, i64)],
+    ) -> Result<TranslatedNames, AppError> {
+        if keys.is_empty() {
+            return Ok(TranslatedNames::new());
+        }
+        self.repo
+            .find_names(&self.db, language, keys)
+            .await
+            .map_err(AppError::DatabaseError)
+    }
+
+    async fn list_translations(
+        &self,
+        entity: TranslatedEntity,
+        id: i64,
+    ) -> Result<Vec<TranslationDto>, AppError> {
+        self.ensure_entity_exists(entity, id).await?;
+        self.repo
+            .find_by_entity(&self.db, entity, id)
+            .await
+            .map_err(AppError::DatabaseError)
+    }
+
+    async fn set_translation(
+        &self,
+        entity: TranslatedEntity,
+        id: i64,
+        language: Language,
+        name: &str,
+    ) -> Result<TranslationDto, AppError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(AppError::ValidationError("Name must not be blank".into()));
+        }
+        self.ensure_entity_exists(entity, id).await?;
+        self.repo
+            .upsert(&self.db, entity, id, language, name)
+            .await
+            .map_err(AppError::DatabaseError)?;
+        Ok(TranslationDto {
+            language,
+            name: name.to_owned(),
+        })
+    }
+
+    async fn delete_translation(
+        &self,
+        entity: TranslatedEntity,
+        id: i64,
+        language: Language,
+    ) -> Result<(), AppError> {
+        let deleted = self
+            .repo
+            .delete(&self.db, entity, id, language)
+            .await
+            .map_err(AppError::DatabaseError)?;
+        if !deleted {
+            return Err(AppError::NotFound("Translation not found".into()));
+        }
+        Ok(())
+    }
+}
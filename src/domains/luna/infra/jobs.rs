@@ -0,0 +1,89 @@
+//! Background jobs of the luna domain, run by the
+//! [`JobRunner`](crate::common::jobs::JobRunner).
+
+use crate::common::{config::Config, error::AppError, jobs::Job};
+use crate::domains::luna::domain::LunaServiceTrait;
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Collects orphaned media every `media_gc_interval_secs`, only reporting
+/// what it finds unless `media_gc_delete` is set.
+pub struct MediaGcJob {
+    luna_service: Arc<dyn LunaServiceTrait>,
+    interval: Option<Duration>,
+    delete: bool,
+}
+
+impl MediaGcJob {
+    pub fn new(luna_service: Arc<dyn LunaServiceTrait>, config: &Config) -> Self {
+        Self {
+            luna_service,
+            interval: (config.media_gc_interval_secs > 0)
+                .then(|| Duration::from_secs(config.media_gc_interval_secs)),
+            delete: config.media_gc_delete,
+        }
+    }
+}
+
+#[async_trait]
+impl Job for MediaGcJob {
+    fn name(&self) -> &'static str {
+        "media_gc"
+    }
+
+    fn interval(&self) -> Option<Duration> {
+        self.interval
+    }
+
+    async fn run(&self) -> Result<String, AppError> {
+        let report = self
+            .luna_service
+            .integrity_service()
+            .collect_orphaned_media(!self.delete)
+            .await?;
+        Ok(format!(
+            "{} orphaned dirs, {} missing files; {} dirs and {} rows deleted",
+            report.orphaned_dirs.len(),
+            report.missing_files.len(),
+            report.deleted_dirs,
+            report.deleted_rows
+        ))
+    }
+}
+
+/// Checks the health of up to `link_check_batch_size` record links every
+/// `link_check_interval_secs`, the least recently checked first.
+pub struct LinkCheckJob {
+    luna_service: Arc<dyn LunaServiceTrait>,
+    interval: Option<Duration>,
+}
+
+impl LinkCheckJob {
+    pub fn new(luna_service: Arc<dyn LunaServiceTrait>, config: &Config) -> Self {
+        Self {
+            luna_service,
+            interval: (config.link_check_interval_secs > 0)
+                .then(|| Duration::from_secs(config.link_check_interval_secs)),
+        }
+    }
+}
+
+#[async_trait]
+impl Job for LinkCheckJob {
+    fn name(&self) -> &'static str {
+        "link_check"
+    }
+
+    fn interval(&self) -> Option<Duration> {
+        self.interval
+    }
+
+    async fn run(&self) -> Result<String, AppError> {
+        let report = self.luna_service.link_service().check_stale_links().await?;
+        Ok(format!(
+            "{} links checked, {} dead, {} revived",
+            report.checked, report.dead, report.revived
+        ))
+    }
+}
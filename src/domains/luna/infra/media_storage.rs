@@ -0,0 +1,303 @@
+//! Where media files are kept.
+//!
+//! The file service reads and writes media through [`MediaStorage`], keyed
+//! by `/` separated paths such as `images/record/ABC-123/ABC-123_1.jpg`.
+//! [`LocalStorage`] keeps them under the private assets directory; with the
+//! `s3` feature and `MEDIA_STORAGE=s3`, [`S3Storage`] keeps them in an
+//! S3-compatible bucket such as MinIO and can hand out presigned URLs.
+//! Uploads are staged and thumbnails cached on the local disk either way.
+
+use crate::common::config::{Config, MediaStorageKind};
+use crate::common::range::ByteRange;
+use crate::domains::luna::dto::{MediaType, StagedFile};
+use async_trait::async_trait;
+use std::io;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::fs;
+use tokio::io::{AsyncReadExt as _, AsyncSeekExt as _};
+
+#[cfg(feature = "s3")]
+mod s3;
+#[cfg(feature = "s3")]
+pub use s3::S3Storage;
+
+/// Size and modification time of a stored file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StoredObject {
+    pub len: u64,
+    pub modified: Option<SystemTime>,
+}
+
+/// Backend holding the media files.
+#[async_trait]
+pub trait MediaStorage: Send + Sync {
+    /// Size and modification time of `key`, or `None` when it does not exist.
+    async fn stat(&self, key: &str) -> io::Result<Option<StoredObject>>;
+
+    /// Reads `key`, or only the bytes of `range` when given.
+    async fn read(&self, key: &str, range: Option<ByteRange>) -> io::Result<Vec<u8>>;
+
+    /// Files directly under the directory `dir`, by file name. A missing
+    /// directory lists nothing.
+    async fn list(&self, dir: &str) -> io::Result<Vec<(String, StoredObject)>>;
+
+    /// Moves `staged` to `key` unless a file is already there. Returns
+    /// whether it was stored.
+    async fn put_new(&self, key: &str, staged: StagedFile, content_type: &str) -> io::Result<bool>;
+
+    /// Makes `key` refer to the content already stored at `source` without
+    /// copying it, unless a file is already at `key`. Returns whether it
+    /// did; backends that cannot share content return `false`.
+    async fn link_existing(&self, source: &str, key: &str) -> io::Result<bool>;
+
+    /// Moves `from` to `key` unless a file is already at `key`. Returns
+    /// whether it moved.
+    async fn rename(&self, from: &str, key: &str) -> io::Result<bool>;
+
+    /// Removes the directory `dir` with everything in it. Returns whether
+    /// anything was there.
+    async fn delete_dir(&self, dir: &str) -> io::Result<bool>;
+
+    /// A URL clients can fetch `key` from directly for `expires_in`, or
+    /// `None` when the backend only serves through this server.
+    async fn presigned_url(&self, key: &str, expires_in: Duration) -> io::Result<Option<String>>;
+
+    /// Path of `key` on the local disk, for backends that keep files there.
+    fn local_path(&self, key: &str) -> Option<PathBuf>;
+}
+
+/// Builds the backend `config` selects.
+pub fn from_config(config: &Config) -> Arc<dyn MediaStorage> {
+    match config.media_storage.kind {
+        MediaStorageKind::Local => Arc::new(LocalStorage::new(&config.assets_private_path)),
+        #[cfg(feature = "s3")]
+        MediaStorageKind::S3 => Arc::new(S3Storage::new(&config.media_storage)),
+    }
+}
+
+/// Key of file `filename` in the `media_type` directory of `id`.
+pub fn media_key(media_type: &MediaType, id: &str, filename: &str) -> String {
+    format!("{}/{filename}", media_dir_key(media_type, id))
+}
+
+/// Key of the `media_type` directory of `id`, e.g. `videos/record/ABC-123`.
+pub fn media_dir_key(media_type: &MediaType, id: &str) -> String {
+    format!(
+        "{}/{}/{id}",
+        media_type.get_root_dir_name(),
+        media_type.get_sub_dir_name()
+    )
+}
+
+/// Media kept as files under a root directory.
+pub struct LocalStorage {
+    root: PathBuf,
+}
+
+impl LocalStorage {
+    /// Storage rooted at `root`.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Path of `key` under the root.
+    pub fn path(&self, key: &str) -> PathBuf {
+        key.split('/')
+            .fold(self.root.clone(), |path, part| path.join(part))
+    }
+}
+
+#[async_trait]
+impl MediaStorage for LocalStorage {
+    async fn stat(&self, key: &str) -> io::Result<Option<StoredObject>> {
+        match fs::metadata(self.path(key)).await {
+            Ok(metadata) if metadata.is_file() => Ok(Some(StoredObject {
+                len: metadata.len(),
+                modified: metadata.modified().ok(),
+            })),
+            Ok(_) => Ok(None),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    async fn read(&self, key: &str, range: Option<ByteRange>) -> io::Result<Vec<u8>> {
+        let path = self.path(key);
+        let Some(range) = range else {
+            return fs::read(path).await;
+        };
+        let mut file = fs::File::open(path).await?;
+        file.seek(io::SeekFrom::Start(range.start)).await?;
+        let mut content = Vec::with_capacity(usize::try_from(range.byte_count()).unwrap_or(0));
+        file.take(range.byte_count())
+            .read_to_end(&mut content)
+            .await?;
+        Ok(content)
+    }
+
+    async fn list(&self, dir: &str) -> io::Result<Vec<(String, StoredObject)>> {
+        let mut entries = match fs::read_dir(self.path(dir)).await {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err),
+        };
+        let mut files = Vec::new();
+        while let Some(entry) = entries.next_entry().await? {
+            let metadata = entry.metadata().await?;
+            if !metadata.is_file() {
+                continue;
+            }
+            if let Ok(name) = entry.file_name().into_string() {
+                files.push((
+                    name,
+                    StoredObject {
+                        len: metadata.len(),
+                        modified: metadata.modified().ok(),
+                    },
+                ));
+            }
+        }
+        Ok(files)
+    }
+
+    async fn put_new(
+        &self,
+        key: &str,
+        staged: StagedFile,
+        _content_type: &str,
+    ) -> io::Result<bool> {
+        let path = self.path(key);
+        if fs::try_exists(&path).await? {
+            return Ok(false);
+        }
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).await?;
+        }
+        staged.persist(&path).await?;
+        Ok(true)
+    }
+
+    async fn link_existing(&self, source: &str, key: &str) -> io::Result<bool> {
+        let path = self.path(key);
+        if fs::try_exists(&path).await? {
+            return Ok(false);
+        }
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).await?;
+        }
+        match fs::hard_link(self.path(source), &path).await {
+            Ok(()) => Ok(true),
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+
+    async fn rename(&self, from: &str, key: &str) -> io::Result<bool> {
+        let path = self.path(key);
+        if fs::try_exists(&path).await? {
+            return Ok(false);
+        }
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).await?;
+        }
+        fs::rename(self.path(from), &path).await?;
+        Ok(true)
+    }
+
+    async fn delete_dir(&self, dir: &str) -> io::Result<bool> {
+        match fs::remove_dir_all(self.path(dir)).await {
+            Ok(()) => Ok(true),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+
+    async fn presigned_url(&self, _key: &str, _expires_in: Duration) -> io::Result<Option<String>> {
+        Ok(None)
+    }
+
+    fn local_path(&self, key: &str) -> Option<PathBuf> {
+        Some(self.path(key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ByteRange, LocalStorage, MediaStorage as _, StagedFile};
+
+    #[tokio::test]
+    async fn local_storage_round_trip() {
+        let root = std::env::temp_dir().join(format!("media-storage-{}", uuid::Uuid::new_v4()));
+        let storage = LocalStorage::new(&root);
+        let staged_path = root.join("upload.part");
+        tokio::fs::create_dir_all(&root).await.expect("create root");
+        tokio::fs::write(&staged_path, b"hello media")
+            .await
+            .expect("write staged file");
+
+        let key = "images/records/ABC-1/ABC-1.jpg";
+        assert!(storage
+            .put_new(key, StagedFile::new(staged_path.clone()), "image/jpeg")
+            .await
+            .expect("store"));
+        let stored = storage.stat(key).await.expect("stat").expect("stored");
+        assert_eq!(stored.len, 11);
+        let range = ByteRange { start: 6, end: 10 };
+        assert_eq!(
+            storage.read(key, Some(range)).await.expect("read range"),
+            b"media"
+        );
+
+        tokio::fs::write(&staged_path, b"other")
+            .await
+            .expect("write staged file");
+        assert!(!storage
+            .put_new(key, StagedFile::new(staged_path), "image/jpeg")
+            .await
+            .expect("store again"));
+
+        let listed = storage.list("images/records/ABC-1").await.expect("list");
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].0, "ABC-1.jpg");
+        let linked = "images/records/ABC-2/ABC-2.jpg";
+        assert!(storage.link_existing(key, linked).await.expect("link"));
+        assert_eq!(
+            storage.read(linked, None).await.expect("read link"),
+            b"hello media"
+        );
+        assert!(!storage
+            .link_existing(key, linked)
+            .await
+            .expect("link again"));
+
+        assert!(storage
+            .list("images/records/missing")
+            .await
+            .expect("list missing")
+            .is_empty());
+
+        let renamed = "images/records/ABC-2/ABC-2_1.jpg";
+        assert!(!storage.rename(linked, key).await.expect("rename onto file"));
+        assert!(storage.rename(linked, renamed).await.expect("rename"));
+        assert!(storage.stat(linked).await.expect("stat renamed").is_none());
+        assert_eq!(
+            storage.read(renamed, None).await.expect("read renamed"),
+            b"hello media"
+        );
+
+        assert!(storage
+            .delete_dir("images/records/ABC-2")
+            .await
+            .expect("delete dir"));
+        assert!(storage.stat(renamed).await.expect("stat deleted").is_none());
+        assert!(storage.stat(key).await.expect("stat kept").is_some());
+        assert!(!storage
+            .delete_dir("images/records/ABC-2")
+            .await
+            .expect("delete dir again"));
+
+        drop(tokio::fs::remove_dir_all(&root).await);
+    }
+}
@@ -0,0 +1,252 @@
+use super::{MediaStorage, StoredObject};
+use crate::common::config::MediaStorageConfig;
+use crate::common::range::ByteRange;
+use crate::domains::luna::dto::StagedFile;
+use async_trait::async_trait;
+use aws_sdk_s3::{
+    config::{BehaviorVersion, Credentials, Region},
+    error::DisplayErrorContext,
+    presigning::PresigningConfig,
+    primitives::ByteStream,
+    types::{Delete, ObjectIdentifier},
+    Client,
+};
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use std::io;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+/// Media kept as objects in an S3-compatible bucket, under an optional key
+/// prefix.
+pub struct S3Storage {
+    client: Client,
+    bucket: String,
+    prefix: String,
+}
+
+impl S3Storage {
+    /// Client for the bucket `config` names. Nothing is sent until the
+    /// first request.
+    pub fn new(config: &MediaStorageConfig) -> Self {
+        let credentials = Credentials::new(
+            &config.s3_access_key_id,
+            &config.s3_secret_access_key,
+            None,
+            None,
+            "lunirelust",
+        );
+        let mut builder = aws_sdk_s3::Config::builder()
+            .behavior_version(BehaviorVersion::latest())
+            .region(Region::new(config.s3_region.clone()))
+            .credentials_provider(credentials)
+            .force_path_style(config.s3_force_path_style);
+        if let Some(endpoint) = &config.s3_endpoint {
+            builder = builder.endpoint_url(endpoint);
+        }
+        Self {
+            client: Client::from_conf(builder.build()),
+            bucket: config.s3_bucket.clone(),
+            prefix: config.s3_prefix.trim_matches('/').to_owned(),
+        }
+    }
+
+    fn object_key(&self, key: &str) -> String {
+        if self.prefix.is_empty() {
+            key.to_owned()
+        } else {
+            format!("{}/{key}", self.prefix)
+        }
+    }
+}
+
+/// Characters left alone when a key is URL-encoded as a copy source.
+const COPY_SOURCE: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'/')
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~');
+
+/// Wraps an SDK error, keeping its full cause chain in the message.
+fn sdk_error(err: impl std::error::Error) -> io::Error {
+    io::Error::other(DisplayErrorContext(err).to_string())
+}
+
+/// Metadata of an object as S3 reports it.
+fn stored_object(
+    len: Option<i64>,
+    modified: Option<&aws_sdk_s3::primitives::DateTime>,
+) -> StoredObject {
+    StoredObject {
+        len: len.and_then(|len| u64::try_from(len).ok()).unwrap_or(0),
+        modified: modified.and_then(|modified| SystemTime::try_from(*modified).ok()),
+    }
+}
+
+#[async_trait]
+impl MediaStorage for S3Storage {
+    async fn stat(&self, key: &str) -> io::Result<Option<StoredObject>> {
+        match self
+            .client
+            .head_object()
+            .bucket(&self.bucket)
+            .key(self.object_key(key))
+            .send()
+            .await
+        {
+            Ok(head) => Ok(Some(stored_object(
+                head.content_length(),
+                head.last_modified(),
+            ))),
+            Err(err) if err.as_service_error().is_some_and(|err| err.is_not_found()) => Ok(None),
+            Err(err) => Err(sdk_error(err)),
+        }
+    }
+
+    async fn read(&self, key: &str, range: Option<ByteRange>) -> io::Result<Vec<u8>> {
+        let object = self
+            .client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(self.object_key(key))
+            .set_range(range.map(|range| format!("bytes={}-{}", range.start, range.end)))
+            .send()
+            .await
+            .map_err(sdk_error)?;
+        let body = object.body.collect().await.map_err(sdk_error)?;
+        Ok(body.into_bytes().to_vec())
+    }
+
+    async fn list(&self, dir: &str) -> io::Result<Vec<(String, StoredObject)>> {
+        let prefix = format!("{}/", self.object_key(dir));
+        let mut files = Vec::new();
+        let mut pages = self
+            .client
+            .list_objects_v2()
+            .bucket(&self.bucket)
+            .prefix(&prefix)
+            .delimiter("/")
+            .into_paginator()
+            .send();
+        while let Some(page) = pages.next().await {
+            for object in page.map_err(sdk_error)?.contents() {
+                let Some(name) = object.key().and_then(|key| key.strip_prefix(&prefix)) else {
+                    continue;
+                };
+                if !name.is_empty() {
+                    files.push((
+                        name.to_owned(),
+                        stored_object(object.size(), object.last_modified()),
+                    ));
+                }
+            }
+        }
+        Ok(files)
+    }
+
+    async fn put_new(&self, key: &str, staged: StagedFile, content_type: &str) -> io::Result<bool> {
+        if self.stat(key).await?.is_some() {
+            return Ok(false);
+        }
+        let body = ByteStream::from_path(staged.path())
+            .await
+            .map_err(sdk_error)?;
+        self.client
+            .put_object()
+            .bucket(&self.bucket)
+            .key(self.object_key(key))
+            .content_type(content_type)
+            .body(body)
+            .send()
+            .await
+            .map_err(sdk_error)?;
+        Ok(true)
+    }
+
+    async fn link_existing(&self, _source: &str, _key: &str) -> io::Result<bool> {
+        Ok(false)
+    }
+
+    async fn rename(&self, from: &str, key: &str) -> io::Result<bool> {
+        if self.stat(key).await?.is_some() {
+            return Ok(false);
+        }
+        // S3 has no rename: copy the object, then delete the original
+        let source = self.object_key(from);
+        self.client
+            .copy_object()
+            .bucket(&self.bucket)
+            .copy_source(
+                utf8_percent_encode(&format!("{}/{source}", self.bucket), COPY_SOURCE).to_string(),
+            )
+            .key(self.object_key(key))
+            .send()
+            .await
+            .map_err(sdk_error)?;
+        self.client
+            .delete_object()
+            .bucket(&self.bucket)
+            .key(source)
+            .send()
+            .await
+            .map_err(sdk_error)?;
+        Ok(true)
+    }
+
+    async fn delete_dir(&self, dir: &str) -> io::Result<bool> {
+        let prefix = format!("{}/", self.object_key(dir));
+        let mut deleted = false;
+        let mut pages = self
+            .client
+            .list_objects_v2()
+            .bucket(&self.bucket)
+            .prefix(&prefix)
+            .into_paginator()
+            .send();
+        // A listing page holds at most 1000 keys, as many as one batch delete takes
+        while let Some(page) = pages.next().await {
+            let objects = page
+                .map_err(sdk_error)?
+                .contents()
+                .iter()
+                .filter_map(|object| object.key())
+                .map(|key| ObjectIdentifier::builder().key(key).build())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(sdk_error)?;
+            if objects.is_empty() {
+                continue;
+            }
+            let delete = Delete::builder()
+                .set_objects(Some(objects))
+                .quiet(true)
+                .build()
+                .map_err(sdk_error)?;
+            self.client
+                .delete_objects()
+                .bucket(&self.bucket)
+                .delete(delete)
+                .send()
+                .await
+                .map_err(sdk_error)?;
+            deleted = true;
+        }
+        Ok(deleted)
+    }
+
+    async fn presigned_url(&self, key: &str, expires_in: Duration) -> io::Result<Option<String>> {
+        let presigning = PresigningConfig::expires_in(expires_in).map_err(sdk_error)?;
+        let request = self
+            .client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(self.object_key(key))
+            .presigned(presigning)
+            .await
+            .map_err(sdk_error)?;
+        Ok(Some(request.uri().to_owned()))
+    }
+
+    fn local_path(&self, _key: &str) -> Option<PathBuf> {
+        None
+    }
+}
@@ -0,0 +1,309 @@
+//! Record metadata from external sources.
+//!
+//! A [`MetadataProvider`] looks a record up by its ID. [`HttpMetadataProvider`]
+//! fetches a JSON document shaped like [`FetchedMetadata`] from a configured
+//! URL; other sources plug in by implementing the trait. [`fetch_metadata`]
+//! asks every provider whose ID pattern matches, in order, and keeps the first
+//! value found for each field.
+
+use crate::common::config::{MetadataConfig, MetadataProviderConfig};
+use crate::common::error::AppError;
+use crate::domains::luna::dto::FetchedMetadata;
+use async_trait::async_trait;
+use regex::Regex;
+use reqwest::StatusCode;
+use std::fmt::Write as _;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Source of record metadata.
+#[async_trait]
+pub trait MetadataProvider: Send + Sync {
+    /// Name reported with the metadata it supplies.
+    fn name(&self) -> &str;
+
+    /// Whether record `id` may be known to this provider.
+    fn matches(&self, id: &str) -> bool;
+
+    /// Metadata of record `id`, or `None` when the provider does not know it.
+    async fn fetch(&self, id: &str) -> Result<Option<FetchedMetadata>, AppError>;
+}
+
+/// Builds the providers `config` lists, sharing one HTTP client.
+pub fn from_config(config: &MetadataConfig) -> Vec<Arc<dyn MetadataProvider>> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(config.timeout_secs))
+        .build()
+        .unwrap_or_default();
+    config
+        .providers
+        .iter()
+        .map(|provider| {
+            Arc::new(HttpMetadataProvider::new(provider, client.clone()))
+                as Arc<dyn MetadataProvider>
+        })
+        .collect()
+}
+
+/// Asks the providers matching `id` in order and combines what they know,
+/// earlier providers winning per field. Returns the metadata and the names
+/// of the providers that supplied any.
+///
+/// Fails with `NotFound` when no provider matches `id` or none knows it. A
+/// provider that fails is logged and skipped, unless all of them fail.
+pub async fn fetch_metadata(
+    providers: &[Arc<dyn MetadataProvider>],
+    id: &str,
+) -> Result<(FetchedMetadata, Vec<String>), AppError> {
+    let mut metadata = FetchedMetadata::default();
+    let mut sources = Vec::new();
+    let mut failures = Vec::new();
+    let mut asked = 0;
+    for provider in providers.iter().filter(|provider| provider.matches(id)) {
+        asked += 1;
+        match provider.fetch(id).await {
+            Ok(Some(found)) if !found.is_empty() => {
+                metadata.fill_from(found);
+                sources.push(provider.name().to_owned());
+            }
+            Ok(_) => {}
+            Err(err) => {
+                tracing::warn!(
+                    "Metadata provider {} failed for {id}: {err}",
+                    provider.name()
+                );
+                failures.push(format!("{}: {err}", provider.name()));
+            }
+        }
+    }
+    if asked == 0 {
+        return Err(AppError::NotFound(format!(
+            "No metadata provider handles record ID {id}"
+        )));
+    }
+    if sources.is_empty() {
+        if failures.len() == asked {
+            return Err(AppError::InternalErrorWithMessage(format!(
+                "Metadata providers failed: {}",
+                failures.join("; ")
+            )));
+        }
+        return Err(AppError::NotFound(format!(
+            "No metadata found for record {id}"
+        )));
+    }
+    Ok((metadata, sources))
+}
+
+/// Provider answering `GET <url>` with a [`FetchedMetadata`] JSON document,
+/// or `404` for unknown records.
+pub struct HttpMetadataProvider {
+    name: String,
+    url: String,
+    id_pattern: Regex,
+    client: reqwest::Client,
+}
+
+impl HttpMetadataProvider {
+    /// Provider described by `config`, sending its requests with `client`.
+    pub fn new(config: &MetadataProviderConfig, client: reqwest::Client) -> Self {
+        Self {
+            name: config.name.clone(),
+            url: config.url.clone(),
+            id_pattern: config.id_pattern.clone(),
+            client,
+        }
+    }
+
+    /// The configured URL with `{id}` replaced by the percent-encoded `id`.
+    fn url_for(&self, id: &str) -> String {
+        self.url.replace("{id}", &encode_id(id))
+    }
+}
+
+#[async_trait]
+impl MetadataProvider for HttpMetadataProvider {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn matches(&self, id: &str) -> bool {
+        self.id_pattern.is_match(id)
+    }
+
+    async fn fetch(&self, id: &str) -> Result<Option<FetchedMetadata>, AppError> {
+        let request_error =
+            |err: reqwest::Error| AppError::InternalErrorWithMessage(err.to_string());
+        let response = self
+            .client
+            .get(self.url_for(id))
+            .send()
+            .await
+            .map_err(request_error)?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let response = response.error_for_status().map_err(request_error)?;
+        response.json().await.map(Some).map_err(request_error)
+    }
+}
+
+/// Percent-encodes every byte of `id` outside the URL-safe unreserved set.
+fn encode_id(id: &str) -> String {
+    let mut encoded = String::with_capacity(id.len());
+    for byte in id.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            encoded.push(char::from(byte));
+        } else {
+            write!(encoded, "%{byte:02X}").expect("writing to a String cannot fail");
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        encode_id, fetch_metadata, from_config, AppError, FetchedMetadata, MetadataProvider,
+    };
+    use crate::common::config::{MetadataConfig, MetadataProviderConfig};
+    use async_trait::async_trait;
+    use axum::{extract::Path, http::StatusCode, routing::get, Json, Router};
+    use regex::Regex;
+    use std::sync::Arc;
+
+    struct FixedProvider {
+        name: &'static str,
+        prefix: &'static str,
+        result: Result<Option<FetchedMetadata>, ()>,
+    }
+
+    #[async_trait]
+    impl MetadataProvider for FixedProvider {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn matches(&self, id: &str) -> bool {
+            id.starts_with(self.prefix)
+        }
+
+        async fn fetch(&self, _id: &str) -> Result<Option<FetchedMetadata>, AppError> {
+            self.result
+                .clone()
+                .map_err(|()| AppError::InternalErrorWithMessage("offline".to_owned()))
+        }
+    }
+
+    fn provider(
+        name: &'static str,
+        prefix: &'static str,
+        result: Result<Option<FetchedMetadata>, ()>,
+    ) -> Arc<dyn MetadataProvider> {
+        Arc::new(FixedProvider {
+            name,
+            prefix,
+            result,
+        })
+    }
+
+    #[test]
+    fn ids_are_percent_encoded() {
+        assert_eq!(encode_id("ABC-123"), "ABC-123");
+        assert_eq!(encode_id("a b/c?"), "a%20b%2Fc%3F");
+    }
+
+    #[tokio::test]
+    async fn earlier_providers_win_per_field() {
+        let first = FetchedMetadata {
+            title: Some("First".to_owned()),
+            genres: vec!["Drama".to_owned()],
+            ..FetchedMetadata::default()
+        };
+        let second = FetchedMetadata {
+            title: Some("Second".to_owned()),
+            duration: Some(120),
+            genres: vec!["Comedy".to_owned()],
+            idols: vec!["Aoi".to_owned()],
+            ..FetchedMetadata::default()
+        };
+        let providers = [
+            provider("broken", "ABC", Err(())),
+            provider("first", "ABC", Ok(Some(first))),
+            provider("other", "XYZ", Ok(Some(FetchedMetadata::default()))),
+            provider("second", "ABC", Ok(Some(second))),
+        ];
+
+        let (metadata, sources) = fetch_metadata(&providers, "ABC-1").await.expect("found");
+        assert_eq!(sources, ["first", "second"]);
+        assert_eq!(metadata.title.as_deref(), Some("First"));
+        assert_eq!(metadata.duration, Some(120));
+        assert_eq!(metadata.genres, ["Drama"]);
+        assert_eq!(metadata.idols, ["Aoi"]);
+    }
+
+    #[tokio::test]
+    async fn unknown_and_failed_lookups_are_errors() {
+        let providers = [provider("empty", "ABC", Ok(None))];
+        assert!(matches!(
+            fetch_metadata(&providers, "XYZ-1").await,
+            Err(AppError::NotFound(_))
+        ));
+        assert!(matches!(
+            fetch_metadata(&providers, "ABC-1").await,
+            Err(AppError::NotFound(_))
+        ));
+
+        let providers = [provider("broken", "ABC", Err(()))];
+        assert!(matches!(
+            fetch_metadata(&providers, "ABC-1").await,
+            Err(AppError::InternalErrorWithMessage(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn http_provider_fetches_json() {
+        let app = Router::new().route(
+            "/records/{id}",
+            get(|Path(id): Path<String>| async move {
+                if id == "ABC 1" {
+                    Ok(Json(serde_json::json!({
+                        "title": "Fetched",
+                        "date": "2024-05-01",
+                        "genres": ["Drama"]
+                    })))
+                } else {
+                    Err(StatusCode::NOT_FOUND)
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind");
+        let addr = listener.local_addr().expect("address");
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let providers = from_config(&MetadataConfig {
+            providers: vec![MetadataProviderConfig {
+                name: "mock".to_owned(),
+                url: format!("http://{addr}/records/{{id}}"),
+                id_pattern: Regex::new("^ABC.*$").expect("regex"),
+            }],
+            timeout_secs: 5,
+        });
+        let found = providers[0]
+            .fetch("ABC 1")
+            .await
+            .expect("fetched")
+            .expect("known");
+        assert_eq!(found.title.as_deref(), Some("Fetched"));
+        assert_eq!(found.genres, ["Drama"]);
+        assert!(found.idols.is_empty());
+        assert!(providers[0]
+            .fetch("ABC 2")
+            .await
+            .expect("fetched")
+            .is_none());
+        assert!(!providers[0].matches("XYZ-1"));
+    }
+}
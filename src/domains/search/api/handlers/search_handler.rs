@@ -2,12 +2,14 @@
 
 use axum::{
     extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
     Extension,
 };
 use serde::Deserialize;
 
 use crate::common::app_state::AppState;
-use crate::common::dto::RestApiResponse;
+use crate::common::dto::{ApiResponse, RestApiResponse};
 use crate::common::error::AppError;
 use crate::common::jwt::Claims;
 use crate::domains::search::dto::{SearchQuery, SearchResponse};
@@ -27,9 +29,10 @@ use crate::domains::search::dto::{SearchQuery, SearchResponse};
         ("date_to" = Option<String>, Query, description = "Filter to date (inclusive)"),
         ("limit" = Option<i64>, Query, description = "Max results (default 20)"),
         ("offset" = Option<i64>, Query, description = "Results offset (default 0)"),
+        ("group" = Option<bool>, Query, description = "Group the page's hits by entity type"),
     ),
     responses(
-        (status = 200, description = "Search results", body = SearchResponse),
+        (status = 200, description = "Search results", body = ApiResponse<SearchResponse>),
         (status = 400, description = "Bad request - empty query"),
         (status = 401, description = "Unauthorized"),
     ),
@@ -54,15 +57,41 @@ pub async fn search(
         date_to: params.date_to,
         limit: params.limit,
         offset: params.offset,
+        group: params.group,
     };
 
-    let user_permission = state.search_service.get_user_permission(&claims.sub).await;
+    let user_permission = state
+        .search_service
+        .get_user_permission(&claims.sub, claims.role)
+        .await;
 
     let response = state.search_service.search(query, user_permission).await?;
 
     Ok(RestApiResponse::success(response))
 }
 
+/// Reindex endpoint: POST /cards/search/reindex
+///
+/// Rebuilds the search index from the database in the background. Admin only.
+#[utoipa::path(
+    post,
+    path = "/cards/search/reindex",
+    responses(
+        (status = 202, description = "Reindex started"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - admin role required"),
+        (status = 409, description = "A reindex is already running"),
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Search"
+)]
+pub async fn reindex(State(state): State<AppState>) -> Result<impl IntoResponse, AppError> {
+    state.search_service.trigger_reindex()?;
+    Ok((StatusCode::ACCEPTED, RestApiResponse::success(())))
+}
+
 /// Raw search params from query string.
 /// Maps directly from the HTTP query string and is then converted into `SearchQuery`.
 #[derive(Debug, Deserialize)]
@@ -87,4 +116,6 @@ pub struct SearchParams {
     pub limit: Option<i64>,
     /// Number of results to skip (default 0).
     pub offset: Option<i64>,
+    /// Return the hits grouped by entity type.
+    pub group: Option<bool>,
 }
@@ -1,18 +1,22 @@
 //! Search API routes.
 
-use axum::routing::get;
+use axum::routing::{get, post};
 use axum::Router;
 use utoipa::OpenApi;
 
 use crate::common::app_state::AppState;
-use crate::common::openapi::SecurityAddon;
-use crate::domains::search::api::handlers::search_handler::{__path_search, search};
+use crate::common::jwt::{with_role, Role};
+use crate::common::openapi::{ErrorResponsesAddon, SecurityAddon};
+use crate::domains::search::api::handlers::search_handler::{
+    __path_reindex, __path_search, reindex, search,
+};
 use crate::domains::search::dto::SearchResponse;
 
 #[derive(OpenApi)]
 #[openapi(
     paths(
         search,
+        reindex,
     ),
     components(schemas(
         SearchResponse,
@@ -23,10 +27,12 @@ use crate::domains::search::dto::SearchResponse;
     security(
         ("bearer_auth" = [])
     ),
-    modifiers(&SecurityAddon)
+    modifiers(&SecurityAddon, &ErrorResponsesAddon)
 )]
 pub struct SearchApiDoc;
 
 pub fn search_routes() -> Router<AppState> {
-    Router::new().route("/search", get(search))
+    Router::new()
+        .route("/search", get(search))
+        .route("/search/reindex", with_role(Role::Admin, post(reindex)))
 }
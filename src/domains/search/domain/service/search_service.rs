@@ -2,6 +2,7 @@
 
 use crate::common::config::Config;
 use crate::common::error::AppError;
+use crate::common::jwt::Role;
 use crate::domains::search::dto::{SearchQuery, SearchResponse};
 use async_trait::async_trait;
 use sea_orm::DatabaseConnection;
@@ -25,10 +26,18 @@ pub trait SearchServiceTrait: Send + Sync {
     /// Check if `MeiliSearch` is ready to serve queries.
     fn is_meili_ready(&self) -> bool;
 
-    /// Look up the caller's permission level from `user_ext`.
-    /// Falls back to 0 (no access to restricted records) on lookup failure.
-    async fn get_user_permission(&self, user_id: &str) -> i32;
+    /// Resolve the caller's record clearance from their role.
+    /// Falls back to 0 (public records only) when the user no longer exists.
+    async fn get_user_permission(&self, user_id: &str, role: Role) -> i32;
 
     /// Trigger startup full sync (runs in background).
     fn trigger_startup_sync(&self);
+
+    /// Rebuild the search index from the database (runs in background).
+    /// Fails with `Conflict` while a previous reindex is still running.
+    fn trigger_reindex(&self) -> Result<(), AppError>;
+
+    /// Rebuild the search index from the database and wait for it to finish.
+    /// Fails with `Conflict` while another reindex is running.
+    async fn reindex(&self) -> Result<(), AppError>;
 }
@@ -31,6 +31,9 @@ pub struct SearchQuery {
     /// Number of results to skip (default 0)
     #[serde(default)]
     pub offset: Option<i64>,
+    /// Return the page's hits grouped by entity type in `groups` instead of `results`
+    #[serde(default)]
+    pub group: Option<bool>,
 }
 
 /// A single search result item.
@@ -67,8 +70,40 @@ pub struct SearchResponse {
     pub limit: i64,
     /// Number of results skipped
     pub offset: i64,
-    /// Search result items
+    /// Search result items, best match first; empty when grouped
     pub results: Vec<SearchResultItem>,
+    /// The page's hits grouped by entity type, when requested with `group=true`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub groups: Option<Vec<SearchHitGroup>>,
+}
+
+/// Hits of one entity type, best match first.
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct SearchHitGroup {
+    pub entity_type: SearchEntityType,
+    pub results: Vec<SearchResultItem>,
+}
+
+impl SearchResponse {
+    /// Moves `results` into per-type `groups`. Groups are ordered by their best
+    /// hit, and hits keep their relevance order within a group.
+    pub fn into_grouped(mut self) -> Self {
+        let mut groups: Vec<SearchHitGroup> = Vec::new();
+        for item in std::mem::take(&mut self.results) {
+            match groups
+                .iter_mut()
+                .find(|g| g.entity_type == item.entity_type)
+            {
+                Some(group) => group.results.push(item),
+                None => groups.push(SearchHitGroup {
+                    entity_type: item.entity_type,
+                    results: vec![item],
+                }),
+            }
+        }
+        self.groups = Some(groups);
+        self
+    }
 }
 
 #[cfg(test)]
@@ -125,6 +160,7 @@ mod tests {
                 genre_names: None,
                 idol_names: None,
             }],
+            groups: None,
         };
         let json = serde_json::to_value(&response).expect("serializing SearchResponse");
         assert_eq!(json["search_mode"], "keyword_only");
@@ -138,6 +174,60 @@ mod tests {
         );
     }
 
+    fn item(id: &str, entity_type: SearchEntityType) -> SearchResultItem {
+        SearchResultItem {
+            id: id.to_owned(),
+            entity_type,
+            title: id.to_owned(),
+            score: None,
+            highlight: None,
+            date: None,
+            director_name: None,
+            studio_name: None,
+            label_name: None,
+            series_name: None,
+            genre_names: None,
+            idol_names: None,
+        }
+    }
+
+    #[test]
+    fn test_search_response_into_grouped() {
+        let response = SearchResponse {
+            search_mode: "sql_fallback".to_owned(),
+            total: 4,
+            limit: 20,
+            offset: 0,
+            results: vec![
+                item("d1", SearchEntityType::Director),
+                item("r1", SearchEntityType::Record),
+                item("d2", SearchEntityType::Director),
+                item("r2", SearchEntityType::Record),
+            ],
+            groups: None,
+        }
+        .into_grouped();
+
+        assert!(response.results.is_empty());
+        let groups = response.groups.expect("grouped response has groups");
+        let summary: Vec<(SearchEntityType, Vec<&str>)> = groups
+            .iter()
+            .map(|g| {
+                (
+                    g.entity_type,
+                    g.results.iter().map(|r| r.id.as_str()).collect(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (SearchEntityType::Director, vec!["d1", "d2"]),
+                (SearchEntityType::Record, vec!["r1", "r2"]),
+            ]
+        );
+    }
+
     #[test]
     fn test_search_result_item_optional_fields() {
         let item = SearchResultItem {
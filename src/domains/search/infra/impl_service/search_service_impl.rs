@@ -9,6 +9,8 @@ use sea_orm::{DatabaseConnection, EntityTrait as _};
 
 use crate::common::config::Config;
 use crate::common::error::AppError;
+use crate::common::jwt::Role;
+use crate::domains::luna::RecordPermission;
 use crate::domains::search::domain::repository::search_repo::SearchRepository as _;
 use crate::domains::search::domain::service::search_service::SearchServiceTrait;
 use crate::domains::search::dto::{SearchQuery, SearchResponse, SearchResultItem};
@@ -86,7 +88,7 @@ impl SearchServiceTrait for SearchService {
     }
 
     /// Execute a search query. Attempts `MeiliSearch` first (hybrid or keyword-only),
-    /// falls back to SQL LIKE queries if `MeiliSearch` is unavailable.
+    /// falls back to SQL full-text and LIKE queries if `MeiliSearch` is unavailable.
     async fn search(
         &self,
         query: SearchQuery,
@@ -140,6 +142,15 @@ impl SearchServiceTrait for SearchService {
 
         let filter_str = additional_filters.join(" AND ");
 
+        let grouped = query.group.unwrap_or(false);
+        let group = |response: SearchResponse| {
+            if grouped {
+                response.into_grouped()
+            } else {
+                response
+            }
+        };
+
         // Check MeiliSearch readiness
         if self.meili_ready.load(Ordering::Relaxed) {
             // Try MeiliSearch search
@@ -147,7 +158,7 @@ impl SearchServiceTrait for SearchService {
                 .search_meili(&query.q, &entity_types, &filter_str, limit, offset)
                 .await
             {
-                Ok(response) => return Ok(response),
+                Ok(response) => return Ok(group(response)),
                 Err(e) => {
                     tracing::warn!("MeiliSearch search failed, falling back to SQL: {}", e);
                     // Fall through to SQL fallback
@@ -166,6 +177,7 @@ impl SearchServiceTrait for SearchService {
             user_permission,
         )
         .await
+        .map(group)
     }
 
     /// Check if `MeiliSearch` is ready to serve queries.
@@ -173,7 +185,7 @@ impl SearchServiceTrait for SearchService {
         self.meili_ready.load(Ordering::Relaxed)
     }
 
-    async fn get_user_permission(&self, user_id: &str) -> i32 {
+    async fn get_user_permission(&self, user_id: &str, role: Role) -> i32 {
         use crate::entities::users::Entity as UsersEntity;
 
         // Verify the user exists — unauthenticated / invalid tokens get 0.
@@ -187,19 +199,29 @@ impl SearchServiceTrait for SearchService {
             return 0;
         }
 
-        // Per-user permission levels are not yet implemented.
-        // The user_ext table has a type mismatch (user_id: i64 vs
-        // users.id: String/UUID) and no migration creates it, so we
-        // cannot query it. Return the maximum permission level so that
-        // all authenticated users see every record they could previously
-        // access before the search feature was added.
-        i32::MAX
+        RecordPermission::clearance(role)
     }
 
     /// Trigger the background startup sync and indexer loop.
     fn trigger_startup_sync(&self) {
         self.indexer.trigger_startup_sync();
     }
+
+    /// Start a background rebuild of the whole index.
+    fn trigger_reindex(&self) -> Result<(), AppError> {
+        if self.indexer.trigger_reindex() {
+            Ok(())
+        } else {
+            Err(AppError::Conflict("A reindex is already running".into()))
+        }
+    }
+
+    async fn reindex(&self) -> Result<(), AppError> {
+        match self.indexer.reindex().await {
+            Some(result) => result.map_err(AppError::InternalErrorWithMessage),
+            None => Err(AppError::Conflict("A reindex is already running".into())),
+        }
+    }
 }
 
 impl SearchService {
@@ -326,6 +348,7 @@ impl SearchService {
             limit,
             offset,
             results,
+            groups: None,
         })
     }
 }
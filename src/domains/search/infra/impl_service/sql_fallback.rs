@@ -1,8 +1,13 @@
-//! SQL fallback search using `PostgreSQL` LIKE queries when `MeiliSearch` is unavailable.
+//! SQL fallback search when `MeiliSearch` is unavailable.
+//!
+//! Records, idols, directors and studios match on their `search_vector`
+//! full-text column (ranked with `ts_rank`) or by substring; the other named
+//! entities match by substring only.
 
+use sea_orm::sea_query::{Expr, SimpleExpr};
 use sea_orm::{
-    ColumnTrait as _, DatabaseConnection, EntityTrait as _, Order, PaginatorTrait as _,
-    QueryFilter as _, QueryOrder as _, QuerySelect as _,
+    ColumnTrait, Condition, DatabaseConnection, EntityTrait as _, FromQueryResult, Order,
+    PaginatorTrait as _, QueryFilter as _, QueryOrder as _, QuerySelect as _,
 };
 
 use crate::common::error::AppError;
@@ -11,7 +16,65 @@ use crate::domains::search::SearchEntityType;
 
 use super::filter_utils::parse_filters;
 
-/// Execute SQL fallback search using `PostgreSQL` LIKE queries.
+/// Text search configuration the `search_vector` columns are built with.
+const TS_CONFIG: &str = "simple";
+
+/// Matches rows whose `search_vector` matches `query`, or whose `column`
+/// contains the escaped LIKE `pattern`.
+fn text_match(table: &str, column: impl ColumnTrait, query: &str, pattern: &str) -> Condition {
+    Condition::any()
+        .add(Expr::cust_with_values(
+            format!("{table}.search_vector @@ plainto_tsquery('{TS_CONFIG}', $1)"),
+            [query.to_owned()],
+        ))
+        .add(column.contains(pattern))
+}
+
+/// Full-text relevance of a row of `table` for `query`; 0 for substring-only matches.
+fn text_rank(table: &str, query: &str) -> SimpleExpr {
+    Expr::cust_with_values(
+        format!("coalesce(ts_rank({table}.search_vector, plainto_tsquery('{TS_CONFIG}', $1)), 0)"),
+        [query.to_owned()],
+    )
+}
+
+/// A record hit with its full-text rank.
+#[derive(Debug, FromQueryResult)]
+struct RankedRecord {
+    id: String,
+    title: String,
+    date: chrono::NaiveDate,
+    rank: f32,
+}
+
+/// A named entity hit with its full-text rank.
+#[derive(Debug, FromQueryResult)]
+struct RankedName {
+    id: i64,
+    name: String,
+    rank: f32,
+}
+
+impl RankedName {
+    fn into_item(self, entity_type: SearchEntityType) -> SearchResultItem {
+        SearchResultItem {
+            id: self.id.to_string(),
+            entity_type,
+            title: self.name,
+            score: Some(f64::from(self.rank)),
+            highlight: None,
+            date: None,
+            director_name: None,
+            studio_name: None,
+            label_name: None,
+            series_name: None,
+            genre_names: None,
+            idol_names: None,
+        }
+    }
+}
+
+/// Execute SQL fallback search using `PostgreSQL` full-text and LIKE queries.
 #[expect(clippy::too_many_lines)]
 pub(super) async fn search_sql_fallback(
     db: &DatabaseConnection,
@@ -47,11 +110,16 @@ pub(super) async fn search_sql_fallback(
         // Match records by title, or by related entity names via subqueries.
         // This mirrors the MeiliSearch searchable attributes for SQL fallback.
         let id_match = record::Column::Id.contains(pattern.clone());
-        let title_match = record::Column::Title.contains(pattern.clone());
+        let title_match = text_match("record", record::Column::Title, query, &pattern);
 
         // Subquery: records whose director name matches
         let director_ids: Vec<i64> = director::Entity::find()
-            .filter(director::Column::Name.contains(&pattern))
+            .filter(text_match(
+                "director",
+                director::Column::Name,
+                query,
+                &pattern,
+            ))
             .all(db)
             .await
             .map_err(AppError::DatabaseError)?
@@ -60,7 +128,7 @@ pub(super) async fn search_sql_fallback(
             .collect();
 
         let studio_ids: Vec<i64> = studio::Entity::find()
-            .filter(studio::Column::Name.contains(&pattern))
+            .filter(text_match("studio", studio::Column::Name, query, &pattern))
             .all(db)
             .await
             .map_err(AppError::DatabaseError)?
@@ -115,7 +183,7 @@ pub(super) async fn search_sql_fallback(
         let idol_record_ids: Vec<String> =
             if !entity_types.is_empty() || wants(&SearchEntityType::Record) {
                 let idol_ids: Vec<i64> = idol::Entity::find()
-                    .filter(idol::Column::Name.contains(&pattern))
+                    .filter(text_match("idol", idol::Column::Name, query, &pattern))
                     .all(db)
                     .await
                     .map_err(AppError::DatabaseError)?
@@ -138,7 +206,6 @@ pub(super) async fn search_sql_fallback(
                 vec![]
             };
 
-        use sea_orm::Condition;
         let mut record_cond = Condition::any().add(id_match).add(title_match);
         if !director_ids.is_empty() {
             record_cond = record_cond.add(record::Column::DirectorId.is_in(director_ids));
@@ -160,6 +227,7 @@ pub(super) async fn search_sql_fallback(
         }
 
         let mut q = record::Entity::find()
+            .filter(record::Column::DeletedAt.is_null())
             .filter(record_cond)
             .filter(record::Column::Permission.lte(user_permission));
 
@@ -181,6 +249,7 @@ pub(super) async fn search_sql_fallback(
                     limit,
                     offset,
                     results: vec![],
+                    groups: None,
                 });
             }
         }
@@ -203,6 +272,7 @@ pub(super) async fn search_sql_fallback(
                     limit,
                     offset,
                     results: vec![],
+                    groups: None,
                 });
             }
         }
@@ -225,6 +295,7 @@ pub(super) async fn search_sql_fallback(
                     limit,
                     offset,
                     results: vec![],
+                    groups: None,
                 });
             }
         }
@@ -249,6 +320,7 @@ pub(super) async fn search_sql_fallback(
                     limit,
                     offset,
                     results: vec![],
+                    groups: None,
                 });
             }
         }
@@ -266,20 +338,27 @@ pub(super) async fn search_sql_fallback(
 
         record_total = q.clone().count(db).await.map_err(AppError::DatabaseError)? as i64;
         let found = q
+            .select_only()
+            .column(record::Column::Id)
+            .column(record::Column::Title)
+            .column(record::Column::Date)
+            .column_as(text_rank("record", query), "rank")
+            .order_by(text_rank("record", query), Order::Desc)
             .order_by(record::Column::Date, Order::Desc)
             .order_by(record::Column::Id, Order::Asc)
             .offset(offset as u64)
             .limit(limit as u64)
+            .into_model::<RankedRecord>()
             .all(db)
             .await
             .map_err(AppError::DatabaseError)?;
 
         for r in found {
             results.push(SearchResultItem {
-                id: r.id.clone(),
+                id: r.id,
                 entity_type: SearchEntityType::Record,
                 title: r.title,
-                score: None,
+                score: Some(f64::from(r.rank)),
                 highlight: None,
                 date: Some(r.date.to_string()),
                 director_name: None,
@@ -305,6 +384,7 @@ pub(super) async fn search_sql_fallback(
                 limit,
                 offset,
                 results,
+                groups: None,
             });
         }
     }
@@ -337,6 +417,7 @@ pub(super) async fn search_sql_fallback(
             limit,
             offset,
             results,
+            groups: None,
         });
     }
     if remaining == 0 && needs_entity_counts && !has_record_filters {
@@ -345,14 +426,19 @@ pub(super) async fn search_sql_fallback(
         // date, etc.) because named-entity docs don't have those fields.
         if wants(&SearchEntityType::Director) {
             total += director::Entity::find()
-                .filter(director::Column::Name.contains(&pattern))
+                .filter(text_match(
+                    "director",
+                    director::Column::Name,
+                    query,
+                    &pattern,
+                ))
                 .count(db)
                 .await
                 .map_err(AppError::DatabaseError)? as i64;
         }
         if wants(&SearchEntityType::Studio) {
             total += studio::Entity::find()
-                .filter(studio::Column::Name.contains(&pattern))
+                .filter(text_match("studio", studio::Column::Name, query, &pattern))
                 .count(db)
                 .await
                 .map_err(AppError::DatabaseError)? as i64;
@@ -380,7 +466,7 @@ pub(super) async fn search_sql_fallback(
         }
         if wants(&SearchEntityType::Idol) {
             total += idol::Entity::find()
-                .filter(idol::Column::Name.contains(&pattern))
+                .filter(text_match("idol", idol::Column::Name, query, &pattern))
                 .count(db)
                 .await
                 .map_err(AppError::DatabaseError)? as i64;
@@ -391,6 +477,7 @@ pub(super) async fn search_sql_fallback(
             limit,
             offset,
             results,
+            groups: None,
         });
     }
 
@@ -401,6 +488,7 @@ pub(super) async fn search_sql_fallback(
             limit,
             offset,
             results,
+            groups: None,
         });
     }
 
@@ -409,47 +497,51 @@ pub(super) async fn search_sql_fallback(
     let mut entity_results: Vec<SearchResultItem> = Vec::new();
 
     if wants(&SearchEntityType::Director) {
-        let q = director::Entity::find().filter(director::Column::Name.contains(&pattern));
+        let q = director::Entity::find().filter(text_match(
+            "director",
+            director::Column::Name,
+            query,
+            &pattern,
+        ));
         total += q.clone().count(db).await.map_err(AppError::DatabaseError)? as i64;
-        let found = q.all(db).await.map_err(AppError::DatabaseError)?;
-        for d in found {
-            entity_results.push(SearchResultItem {
-                id: d.id.to_string(),
-                entity_type: SearchEntityType::Director,
-                title: d.name,
-                score: None,
-                highlight: None,
-                date: None,
-                director_name: None,
-                studio_name: None,
-                label_name: None,
-                series_name: None,
-                genre_names: None,
-                idol_names: None,
-            });
-        }
+        let found = q
+            .select_only()
+            .column(director::Column::Id)
+            .column(director::Column::Name)
+            .column_as(text_rank("director", query), "rank")
+            .into_model::<RankedName>()
+            .all(db)
+            .await
+            .map_err(AppError::DatabaseError)?;
+        entity_results.extend(
+            found
+                .into_iter()
+                .map(|d| d.into_item(SearchEntityType::Director)),
+        );
     }
 
     if wants(&SearchEntityType::Studio) {
-        let q = studio::Entity::find().filter(studio::Column::Name.contains(&pattern));
+        let q = studio::Entity::find().filter(text_match(
+            "studio",
+            studio::Column::Name,
+            query,
+            &pattern,
+        ));
         total += q.clone().count(db).await.map_err(AppError::DatabaseError)? as i64;
-        let found = q.all(db).await.map_err(AppError::DatabaseError)?;
-        for s in found {
-            entity_results.push(SearchResultItem {
-                id: s.id.to_string(),
-                entity_type: SearchEntityType::Studio,
-                title: s.name,
-                score: None,
-                highlight: None,
-                date: None,
-                director_name: None,
-                studio_name: None,
-                label_name: None,
-                series_name: None,
-                genre_names: None,
-                idol_names: None,
-            });
-        }
+        let found = q
+            .select_only()
+            .column(studio::Column::Id)
+            .column(studio::Column::Name)
+            .column_as(text_rank("studio", query), "rank")
+            .into_model::<RankedName>()
+            .all(db)
+            .await
+            .map_err(AppError::DatabaseError)?;
+        entity_results.extend(
+            found
+                .into_iter()
+                .map(|s| s.into_item(SearchEntityType::Studio)),
+        );
     }
 
     if wants(&SearchEntityType::Label) {
@@ -519,27 +611,28 @@ pub(super) async fn search_sql_fallback(
     }
 
     if wants(&SearchEntityType::Idol) {
-        let q = idol::Entity::find().filter(idol::Column::Name.contains(&pattern));
+        let q =
+            idol::Entity::find().filter(text_match("idol", idol::Column::Name, query, &pattern));
         total += q.clone().count(db).await.map_err(AppError::DatabaseError)? as i64;
-        let found = q.all(db).await.map_err(AppError::DatabaseError)?;
-        for i in found {
-            entity_results.push(SearchResultItem {
-                id: i.id.to_string(),
-                entity_type: SearchEntityType::Idol,
-                title: i.name,
-                score: None,
-                highlight: None,
-                date: None,
-                director_name: None,
-                studio_name: None,
-                label_name: None,
-                series_name: None,
-                genre_names: None,
-                idol_names: None,
-            });
-        }
+        let found = q
+            .select_only()
+            .column(idol::Column::Id)
+            .column(idol::Column::Name)
+            .column_as(text_rank("idol", query), "rank")
+            .into_model::<RankedName>()
+            .all(db)
+            .await
+            .map_err(AppError::DatabaseError)?;
+        entity_results.extend(
+            found
+                .into_iter()
+                .map(|i| i.into_item(SearchEntityType::Idol)),
+        );
     }
 
+    // Best full-text matches first; the sort is stable, so ties keep type order.
+    entity_results.sort_by(|a, b| b.score.unwrap_or(0.0).total_cmp(&a.score.unwrap_or(0.0)));
+
     // Apply global offset to the merged entity list, then fill remaining.
     // If offset falls within records, entities start at 0.
     // If offset extends past records, entities start at the remainder.
@@ -562,6 +655,7 @@ pub(super) async fn search_sql_fallback(
         limit,
         offset,
         results,
+        groups: None,
     })
 }
 
@@ -660,6 +754,12 @@ mod tests {
             update_time: Set(today),
             creator: Set("sql_fallback_test".to_owned()),
             modified_by: Set("sql_fallback_test".to_owned()),
+            deleted_at: Set(None),
+            version: Set(1),
+            sync_seq: sea_orm::ActiveValue::NotSet,
+            trailer: Set(None),
+            cover_index: Set(None),
+            order_in_series: Set(None),
         };
 
         record_model
@@ -693,4 +793,87 @@ mod tests {
             "expected sql fallback search to match the record by id"
         );
     }
+
+    #[tokio::test]
+    async fn test_sql_fallback_ranks_full_text_matches() {
+        use crate::entities::director;
+
+        let db = setup_search_test_db().await;
+        let token = format!("fulltext{}", uuid::Uuid::new_v4().simple());
+        let record_id = format!("sql-fallback-rank-{}", uuid::Uuid::new_v4());
+        let today = chrono::Utc::now().date_naive();
+
+        let director = director::ActiveModel {
+            id: sea_orm::ActiveValue::NotSet,
+            name: Set(format!("Director {token}")),
+            link: Set(String::new()),
+            manual: Set(true),
+        }
+        .insert(&db)
+        .await
+        .expect("Failed to insert director");
+        record::ActiveModel {
+            id: Set(record_id.clone()),
+            title: Set(format!("{token} ranked title")),
+            date: Set(today),
+            duration: Set(60),
+            director_id: Set(0),
+            studio_id: Set(0),
+            label_id: Set(0),
+            series_id: Set(0),
+            has_links: Set(false),
+            permission: Set(1),
+            local_img_count: Set(0),
+            create_time: Set(today),
+            update_time: Set(today),
+            creator: Set("sql_fallback_test".to_owned()),
+            modified_by: Set("sql_fallback_test".to_owned()),
+            deleted_at: Set(None),
+            version: Set(1),
+            sync_seq: sea_orm::ActiveValue::NotSet,
+            trailer: Set(None),
+            cover_index: Set(None),
+            order_in_series: Set(None),
+        }
+        .insert(&db)
+        .await
+        .expect("Failed to insert record");
+
+        let response = search_sql_fallback(
+            &db,
+            &token,
+            &[SearchEntityType::Record, SearchEntityType::Director],
+            "",
+            20,
+            0,
+            i32::MAX,
+        )
+        .await;
+
+        record::Entity::delete_by_id(&record_id)
+            .exec(&db)
+            .await
+            .expect("Failed to clean up record");
+        director::Entity::delete_by_id(director.id)
+            .exec(&db)
+            .await
+            .expect("Failed to clean up director");
+
+        let response = response.expect("SQL fallback search should succeed");
+        let score_of = |entity_type: SearchEntityType, id: &str| {
+            response
+                .results
+                .iter()
+                .find(|item| item.entity_type == entity_type && item.id == id)
+                .and_then(|item| item.score)
+        };
+        assert!(
+            score_of(SearchEntityType::Record, &record_id).is_some_and(|s| s > 0.0),
+            "record title should match through its search vector"
+        );
+        assert!(
+            score_of(SearchEntityType::Director, &director.id.to_string()).is_some_and(|s| s > 0.0),
+            "director name should match through its search vector"
+        );
+    }
 }
@@ -274,8 +274,11 @@ pub(super) async fn run_full_sync(
         "Full sync: idols indexed"
     );
 
-    // Records
-    let records = record::Entity::find().all(db).await?;
+    // Records (trashed records stay out of the index)
+    let records = record::Entity::find()
+        .filter(record::Column::DeletedAt.is_null())
+        .all(db)
+        .await?;
 
     // Batch-load genre names per record
     let record_ids: Vec<String> = records.iter().map(|r| r.id.clone()).collect();
@@ -60,6 +60,8 @@ pub struct IndexerService {
     embedding_service: Arc<EmbeddingService>,
     /// Shared readiness flag — set to `true` once the index is populated and backlog is drained.
     meili_ready: Arc<AtomicBool>,
+    /// Set while an admin-requested reindex is running, so requests don't stack up.
+    reindexing: Arc<AtomicBool>,
 }
 
 impl IndexerService {
@@ -76,6 +78,7 @@ impl IndexerService {
             search_repo,
             embedding_service,
             meili_ready,
+            reindexing: Arc::new(AtomicBool::new(false)),
         }
     }
 
@@ -91,6 +94,78 @@ impl IndexerService {
             run_indexer_loop(&db, &config, &search_repo, &embedding_service, &meili_ready).await;
         });
     }
+
+    /// Rebuild the whole index from `PostgreSQL` in the background.
+    ///
+    /// Returns `false` without doing anything when a reindex is already
+    /// running. The existing documents keep serving queries meanwhile, since
+    /// `run_full_sync` overwrites them in place.
+    pub fn trigger_reindex(&self) -> bool {
+        if !self.begin_reindex() {
+            return false;
+        }
+
+        let db = self.db.clone();
+        let search_repo = self.search_repo.clone();
+        let embedding_service = self.embedding_service.clone();
+        let reindexing = self.reindexing.clone();
+
+        tokio::spawn(async move {
+            if let Err(e) = run_reindex(&db, &search_repo, &embedding_service).await {
+                tracing::error!("Reindex failed: {}", e);
+            }
+            reindexing.store(false, Ordering::Release);
+        });
+        true
+    }
+
+    /// Rebuild the whole index and wait for it to finish.
+    ///
+    /// Returns `None` without doing anything when a reindex is already
+    /// running. The embedding service is probed first so the rebuilt
+    /// documents carry vectors whenever vLLM is reachable.
+    pub async fn reindex(&self) -> Option<Result<(), String>> {
+        if !self.begin_reindex() {
+            return None;
+        }
+        self.embedding_service.check_health().await;
+        let result = run_reindex(&self.db, &self.search_repo, &self.embedding_service).await;
+        self.reindexing.store(false, Ordering::Release);
+        Some(result)
+    }
+
+    /// Claims the reindex flag; `false` if a reindex is already running.
+    fn begin_reindex(&self) -> bool {
+        self.reindexing
+            .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+            .is_ok()
+    }
+}
+
+/// Re-apply index settings and re-upload every document.
+async fn run_reindex(
+    db: &DatabaseConnection,
+    search_repo: &Arc<MeiliSearchRepo>,
+    embedding_service: &Arc<EmbeddingService>,
+) -> Result<(), String> {
+    tracing::info!("Reindex requested, rebuilding search index...");
+    let started = std::time::Instant::now();
+
+    if !search_repo.health_check().await {
+        return Err("MeiliSearch is not available".to_owned());
+    }
+    search_repo
+        .init_index()
+        .await
+        .map_err(|e| format!("failed to initialize MeiliSearch index: {e}"))?;
+    full_sync::run_full_sync(db, search_repo, embedding_service)
+        .await
+        .map_err(|e| e.to_string())?;
+    tracing::info!(
+        elapsed_ms = started.elapsed().as_millis() as u64,
+        "Reindex completed"
+    );
+    Ok(())
 }
 
 /// Run the one-time startup sync sequence:
@@ -5,7 +5,10 @@
 use std::str::FromStr as _;
 use std::sync::Arc;
 
-use sea_orm::{DatabaseConnection, EntityTrait as _, PaginatorTrait as _, QuerySelect as _};
+use sea_orm::{
+    ColumnTrait as _, DatabaseConnection, EntityTrait as _, PaginatorTrait as _, QueryFilter as _,
+    QuerySelect as _,
+};
 
 use crate::domains::search::domain::repository::search_repo::SearchRepository as _;
 use crate::domains::search::infra::embedding::embedding_service::EmbeddingService;
@@ -28,7 +31,11 @@ pub(super) async fn reconcile_counts(
     use crate::entities::{director, genre, idol, label, record, series, studio};
 
     // Reconcile records
-    let pg_record_count = record::Entity::find().count(db).await.unwrap_or(0);
+    let pg_record_count = record::Entity::find()
+        .filter(record::Column::DeletedAt.is_null())
+        .count(db)
+        .await
+        .unwrap_or(0);
     let meili_record_count = search_repo
         .get_document_count(SearchEntityType::Record)
         .await
@@ -203,6 +210,7 @@ async fn remove_ghost_documents(
 async fn fetch_pg_record_ids(db: &DatabaseConnection) -> std::collections::HashSet<String> {
     use crate::entities::record;
     record::Entity::find()
+        .filter(record::Column::DeletedAt.is_null())
         .all(db)
         .await
         .map(|rows| rows.iter().map(|r| r.id.clone()).collect())
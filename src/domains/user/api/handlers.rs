@@ -1,6 +1,9 @@
 use crate::{
     common::{
-        app_state::AppState, dto::RestApiResponse, error::AppError, jwt::Claims,
+        app_state::AppState,
+        dto::{ApiResponse, RestApiResponse},
+        error::AppError,
+        jwt::Claims,
         multipart_helper::parse_multipart_to_maps,
     },
     domains::{
@@ -20,7 +23,7 @@ use validator::Validate as _;
 #[utoipa::path(
     get,
     path = "/user/{id}",
-    responses((status = 200, description = "Get user by ID", body = UserDto)),
+    responses((status = 200, description = "Get user by ID", body = ApiResponse<UserDto>)),
     tag = "Users"
 )]
 pub async fn get_user_by_id(
@@ -35,7 +38,7 @@ pub async fn get_user_by_id(
     post,
     path = "/user/list",
     request_body = SearchUserDto,
-    responses((status = 200, description = "List users by condition", body = [UserDto])),
+    responses((status = 200, description = "List users by condition", body = ApiResponse<Vec<UserDto>>)),
     tag = "Users"
 )]
 pub async fn get_user_list(
@@ -49,7 +52,7 @@ pub async fn get_user_list(
 #[utoipa::path(
     get,
     path = "/user",
-    responses((status = 200, description = "List all users", body = [UserDto])),
+    responses((status = 200, description = "List all users", body = ApiResponse<Vec<UserDto>>)),
     tag = "Users"
 )]
 pub async fn get_users(State(state): State<AppState>) -> Result<impl IntoResponse, AppError> {
@@ -65,7 +68,7 @@ pub async fn get_users(State(state): State<AppState>) -> Result<impl IntoRespons
         content_type = "multipart/form-data",
         description = "User creation with optional profile picture upload"
     ),
-    responses((status = 200, description = "Create a new user", body = UserDto)),
+    responses((status = 200, description = "Create a new user", body = ApiResponse<UserDto>)),
     tag = "Users"
 )]
 pub async fn create_user(
@@ -97,7 +100,7 @@ pub async fn create_user(
     // Validate the CreateUser DTO.
     create_user
         .validate()
-        .map_err(|err| AppError::ValidationError(format!("Invalid input: {err}")))?;
+        .map_err(|err| AppError::InvalidInput(err))?;
 
     let mut upload_file_dto = None;
 
@@ -128,7 +131,7 @@ pub async fn create_user(
     put,
     path = "/user/{id}",
     request_body = UpdateUserDto,
-    responses((status = 200, description = "Update user", body = UserDto)),
+    responses((status = 200, description = "Update user", body = ApiResponse<UserDto>)),
     tag = "Users"
 )]
 pub async fn update_user(
@@ -139,7 +142,7 @@ pub async fn update_user(
 ) -> Result<impl IntoResponse, AppError> {
     payload.validate().map_err(|err| {
         tracing::error!("Validation error: {err}");
-        AppError::ValidationError(format!("Invalid input: {err}"))
+        AppError::InvalidInput(err)
     })?;
 
     // Set the modified_by field to the current user's ID.
@@ -164,10 +167,28 @@ pub async fn delete_user(
     Ok(RestApiResponse::success_with_message(message, ()))
 }
 
+/// Lifts a login lockout and clears the user's failed login count.
+#[utoipa::path(
+    post,
+    path = "/user/{id}/unlock",
+    responses(
+        (status = 200, description = "User unlocked"),
+        (status = 404, description = "User not found"),
+    ),
+    tag = "Users"
+)]
+pub async fn unlock_user(
+    State(state): State<AppState>,
+    axum::extract::Path(id): axum::extract::Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    state.auth_service.unlock_account(&id).await?;
+    Ok(RestApiResponse::success(()))
+}
+
 #[utoipa::path(
     get,
     path = "/user/me",
-    responses((status = 200, description = "Get current user info", body = UserDto)),
+    responses((status = 200, description = "Get current user info", body = ApiResponse<UserDto>)),
     security(
         ("bearer_auth" = [])
     ),
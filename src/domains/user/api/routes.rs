@@ -1,7 +1,8 @@
 use super::handlers::{
     __path_create_user, __path_delete_user, __path_get_current_user, __path_get_user_by_id,
-    __path_get_user_list, __path_get_users, __path_update_user, create_user, delete_user,
-    get_current_user, get_user_by_id, get_user_list, get_users, update_user,
+    __path_get_user_list, __path_get_users, __path_unlock_user, __path_update_user, create_user,
+    delete_user, get_current_user, get_user_by_id, get_user_list, get_users, unlock_user,
+    update_user,
 };
 
 use crate::{
@@ -10,13 +11,14 @@ use crate::{
 };
 
 use axum::{
-    routing::{delete, get, post, put},
+    routing::{delete, get, post, put, MethodRouter},
     Router,
 };
 
 use utoipa::OpenApi;
 
-use crate::common::openapi::SecurityAddon;
+use crate::common::jwt::{with_role, Role};
+use crate::common::openapi::{ErrorResponsesAddon, SecurityAddon};
 
 #[derive(OpenApi)]
 #[openapi(
@@ -27,6 +29,7 @@ use crate::common::openapi::SecurityAddon;
         create_user,
         update_user,
         delete_user,
+        unlock_user,
         get_current_user,
     ),
     components(schemas(
@@ -38,18 +41,24 @@ use crate::common::openapi::SecurityAddon;
     security(
         ("bearer_auth" = [])
     ),
-    modifiers(&SecurityAddon)
+    modifiers(&SecurityAddon, &ErrorResponsesAddon)
 )]
 /// This struct is used to generate `OpenAPI` documentation for the user routes.
 pub struct UserApiDoc;
 
+/// User management (create, update, delete, unlock) requires the admin role.
+fn admin(route: MethodRouter<AppState>) -> MethodRouter<AppState> {
+    with_role(Role::Admin, route)
+}
+
 pub fn user_routes() -> Router<AppState> {
     Router::new()
         .route("/", get(get_users))
-        .route("/", post(create_user))
+        .route("/", admin(post(create_user)))
         .route("/list", post(get_user_list))
         .route("/me", get(get_current_user))
         .route("/{id}", get(get_user_by_id))
-        .route("/{id}", put(update_user))
-        .route("/{id}", delete(delete_user))
+        .route("/{id}", admin(put(update_user)))
+        .route("/{id}", admin(delete(delete_user)))
+        .route("/{id}/unlock", admin(post(unlock_user)))
 }
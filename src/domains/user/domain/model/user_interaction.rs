@@ -18,6 +18,8 @@ pub struct UserInteraction {
 pub struct InteractionStatus {
     pub liked: bool,
     pub viewed: bool,
+    /// Whether the record is on the user's favorites list.
+    pub favorite: bool,
 }
 
 impl From<crate::entities::user_record_interaction::Model> for UserInteraction {
@@ -1,6 +1,7 @@
 use crate::domains::user::domain::model::user_interaction::InteractionStatus;
 
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use sea_orm::{DatabaseConnection, DbErr};
 
 #[async_trait]
@@ -26,6 +27,25 @@ pub trait InteractionRepository: Send + Sync {
         record_id: &str,
     ) -> Result<(), DbErr>;
 
+    /// Mark a record as seen by the user at `seen_at`, replacing any earlier
+    /// timestamp. Creates the row if it does not exist.
+    async fn mark_seen(
+        &self,
+        db: &DatabaseConnection,
+        user_id: &str,
+        record_id: &str,
+        seen_at: DateTime<Utc>,
+    ) -> Result<(), DbErr>;
+
+    /// Clear the user's seen status and timestamp for a record; a no-op when
+    /// unseen. The like status is kept.
+    async fn mark_unseen(
+        &self,
+        db: &DatabaseConnection,
+        user_id: &str,
+        record_id: &str,
+    ) -> Result<(), DbErr>;
+
     /// Idempotent mark a record as liked by the user.
     /// If already liked, preserves the original `liked_at` timestamp.
     /// Other columns (viewed, `viewed_at`) are never modified.
@@ -36,6 +56,39 @@ pub trait InteractionRepository: Send + Sync {
         record_id: &str,
     ) -> Result<(), DbErr>;
 
+    /// Idempotently add a record to the user's favorites.
+    async fn add_favorite(
+        &self,
+        db: &DatabaseConnection,
+        user_id: &str,
+        record_id: &str,
+    ) -> Result<(), DbErr>;
+
+    /// Remove a record from the user's favorites; a no-op when absent.
+    async fn remove_favorite(
+        &self,
+        db: &DatabaseConnection,
+        user_id: &str,
+        record_id: &str,
+    ) -> Result<(), DbErr>;
+
+    /// Set the user's 1-10 score for a record, replacing any earlier score.
+    async fn rate(
+        &self,
+        db: &DatabaseConnection,
+        user_id: &str,
+        record_id: &str,
+        score: i32,
+    ) -> Result<(), DbErr>;
+
+    /// Withdraw the user's score for a record; a no-op when absent.
+    async fn remove_rating(
+        &self,
+        db: &DatabaseConnection,
+        user_id: &str,
+        record_id: &str,
+    ) -> Result<(), DbErr>;
+
     /// Batch-fetch interaction status for multiple records.
     /// Returns a map of `record_id` -> `InteractionStatus`.
     async fn batch_get_status(
@@ -4,6 +4,7 @@ use crate::domains::luna::dto::{PaginatedResponse, PaginationQuery};
 use super::super::model::user_interaction::InteractionStatus;
 
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use sea_orm::DatabaseConnection;
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -22,6 +23,29 @@ pub trait InteractionServiceTrait: Send + Sync {
     /// Mark a record as viewed by the user.
     async fn mark_viewed(&self, user_id: &str, record_id: &str) -> Result<(), AppError>;
 
+    /// Mark a record as seen by the user at `seen_at`.
+    async fn mark_seen(
+        &self,
+        user_id: &str,
+        record_id: &str,
+        seen_at: DateTime<Utc>,
+    ) -> Result<(), AppError>;
+
+    /// Clear the user's seen status for a record.
+    async fn mark_unseen(&self, user_id: &str, record_id: &str) -> Result<(), AppError>;
+
+    /// Add a record to the user's favorites.
+    async fn add_favorite(&self, user_id: &str, record_id: &str) -> Result<(), AppError>;
+
+    /// Remove a record from the user's favorites.
+    async fn remove_favorite(&self, user_id: &str, record_id: &str) -> Result<(), AppError>;
+
+    /// Set the user's 1-10 score for a record.
+    async fn rate(&self, user_id: &str, record_id: &str, score: i32) -> Result<(), AppError>;
+
+    /// Withdraw the user's score for a record.
+    async fn remove_rating(&self, user_id: &str, record_id: &str) -> Result<(), AppError>;
+
     /// Batch-fetch interaction status for multiple records.
     async fn batch_get_status(
         &self,
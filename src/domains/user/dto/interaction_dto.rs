@@ -1,6 +1,8 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use utoipa::ToSchema;
+use validator::Validate;
 
 /// Response DTO for `toggle_like` endpoint.
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -14,11 +16,47 @@ pub struct MarkViewedResponse {
     pub viewed: bool,
 }
 
+/// Request DTO for the seen endpoint.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct MarkSeenDto {
+    /// When the record was seen; defaults to now. Must not be in the future.
+    #[serde(default)]
+    pub seen_at: Option<DateTime<Utc>>,
+}
+
+/// Response DTO for the seen and unseen endpoints.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SeenResponse {
+    pub seen: bool,
+    pub seen_at: Option<DateTime<Utc>>,
+}
+
+/// Response DTO for the favorite and unfavorite endpoints.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct FavoriteResponse {
+    pub is_favorite: bool,
+}
+
+/// Request DTO for the rate endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, Validate)]
+pub struct RateRecordDto {
+    #[validate(range(min = 1, max = 10, message = "Score must be between 1 and 10"))]
+    pub score: i32,
+}
+
+/// Response DTO for the rate and unrate endpoints: the caller's score, if any.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RatingResponse {
+    pub score: Option<i32>,
+}
+
 /// Response DTO for interaction status of a single record.
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct InteractionStatusDto {
     pub liked: bool,
     pub viewed: bool,
+    #[serde(default)]
+    pub is_favorite: bool,
 }
 
 /// Request DTO for batch interaction status query.
@@ -2,7 +2,7 @@ use crate::domains::user::{
     domain::model::user_interaction::InteractionStatus,
     domain::repository::interaction_repo::InteractionRepository,
 };
-use crate::entities::user_record_interaction;
+use crate::entities::{record_rating, user_record_favorites, user_record_interaction};
 use async_trait::async_trait;
 use chrono::Utc;
 use sea_orm::{
@@ -131,6 +131,154 @@ impl InteractionRepository for InteractionRepo {
         Ok(())
     }
 
+    /// Upsert the seen status with `ON CONFLICT (user_id, record_id) DO
+    /// UPDATE`, overwriting `viewed_at` with `seen_at`.
+    /// The existing `liked`/`liked_at` columns are preserved on conflict.
+    async fn mark_seen(
+        &self,
+        db: &DatabaseConnection,
+        user_id: &str,
+        record_id: &str,
+        seen_at: chrono::DateTime<Utc>,
+    ) -> Result<(), DbErr> {
+        let active = user_record_interaction::ActiveModel {
+            user_id: Set(user_id.to_owned()),
+            record_id: Set(record_id.to_owned()),
+            liked: Set(false),
+            liked_at: Set(None),
+            viewed: Set(true),
+            viewed_at: Set(Some(seen_at)),
+            ..Default::default()
+        };
+        let on_conflict = OnConflict::columns([
+            user_record_interaction::Column::UserId,
+            user_record_interaction::Column::RecordId,
+        ])
+        .update_columns([
+            user_record_interaction::Column::Viewed,
+            user_record_interaction::Column::ViewedAt,
+        ])
+        .to_owned();
+
+        user_record_interaction::Entity::insert(active)
+            .on_conflict(on_conflict)
+            .exec_without_returning(db)
+            .await?;
+        Ok(())
+    }
+
+    async fn mark_unseen(
+        &self,
+        db: &DatabaseConnection,
+        user_id: &str,
+        record_id: &str,
+    ) -> Result<(), DbErr> {
+        user_record_interaction::Entity::update_many()
+            .col_expr(user_record_interaction::Column::Viewed, Expr::value(false))
+            .col_expr(
+                user_record_interaction::Column::ViewedAt,
+                Expr::value(Option::<chrono::DateTime<Utc>>::None),
+            )
+            .filter(user_record_interaction::Column::UserId.eq(user_id))
+            .filter(user_record_interaction::Column::RecordId.eq(record_id))
+            .exec(db)
+            .await?;
+        Ok(())
+    }
+
+    /// Idempotently add a user/record pair to the favorites.
+    ///
+    /// Uses `ON CONFLICT (user_id, record_id) DO NOTHING` so favoriting an
+    /// already-favorite record keeps its original `created_at`.
+    async fn add_favorite(
+        &self,
+        db: &DatabaseConnection,
+        user_id: &str,
+        record_id: &str,
+    ) -> Result<(), DbErr> {
+        let active = user_record_favorites::ActiveModel {
+            user_id: Set(user_id.to_owned()),
+            record_id: Set(record_id.to_owned()),
+            created_at: Set(Utc::now()),
+            ..Default::default()
+        };
+        let on_conflict = OnConflict::columns([
+            user_record_favorites::Column::UserId,
+            user_record_favorites::Column::RecordId,
+        ])
+        .do_nothing()
+        .to_owned();
+
+        user_record_favorites::Entity::insert(active)
+            .on_conflict(on_conflict)
+            .exec_without_returning(db)
+            .await?;
+        Ok(())
+    }
+
+    async fn remove_favorite(
+        &self,
+        db: &DatabaseConnection,
+        user_id: &str,
+        record_id: &str,
+    ) -> Result<(), DbErr> {
+        user_record_favorites::Entity::delete_many()
+            .filter(user_record_favorites::Column::UserId.eq(user_id))
+            .filter(user_record_favorites::Column::RecordId.eq(record_id))
+            .exec(db)
+            .await?;
+        Ok(())
+    }
+
+    /// Upsert the user's score with `ON CONFLICT (user_id, record_id) DO
+    /// UPDATE`, keeping the original `created_at`.
+    async fn rate(
+        &self,
+        db: &DatabaseConnection,
+        user_id: &str,
+        record_id: &str,
+        score: i32,
+    ) -> Result<(), DbErr> {
+        let now = Utc::now();
+        let active = record_rating::ActiveModel {
+            user_id: Set(user_id.to_owned()),
+            record_id: Set(record_id.to_owned()),
+            score: Set(score),
+            created_at: Set(now),
+            updated_at: Set(now),
+            ..Default::default()
+        };
+        let on_conflict = OnConflict::columns([
+            record_rating::Column::UserId,
+            record_rating::Column::RecordId,
+        ])
+        .update_columns([
+            record_rating::Column::Score,
+            record_rating::Column::UpdatedAt,
+        ])
+        .to_owned();
+
+        record_rating::Entity::insert(active)
+            .on_conflict(on_conflict)
+            .exec_without_returning(db)
+            .await?;
+        Ok(())
+    }
+
+    async fn remove_rating(
+        &self,
+        db: &DatabaseConnection,
+        user_id: &str,
+        record_id: &str,
+    ) -> Result<(), DbErr> {
+        record_rating::Entity::delete_many()
+            .filter(record_rating::Column::UserId.eq(user_id))
+            .filter(record_rating::Column::RecordId.eq(record_id))
+            .exec(db)
+            .await?;
+        Ok(())
+    }
+
     async fn batch_get_status(
         &self,
         db: &DatabaseConnection,
@@ -147,19 +295,24 @@ impl InteractionRepository for InteractionRepo {
             .all(db)
             .await?;
 
+        let favorites = user_record_favorites::Entity::find()
+            .filter(user_record_favorites::Column::UserId.eq(user_id))
+            .filter(user_record_favorites::Column::RecordId.is_in(record_ids.to_vec()))
+            .all(db)
+            .await?;
+
         let mut status_map: std::collections::HashMap<String, InteractionStatus> = record_ids
             .iter()
             .map(|id| (id.clone(), InteractionStatus::default()))
             .collect();
 
         for interaction in interactions {
-            status_map.insert(
-                interaction.record_id.clone(),
-                InteractionStatus {
-                    liked: interaction.liked,
-                    viewed: interaction.viewed,
-                },
-            );
+            let status = status_map.entry(interaction.record_id).or_default();
+            status.liked = interaction.liked;
+            status.viewed = interaction.viewed;
+        }
+        for favorite in favorites {
+            status_map.entry(favorite.record_id).or_default().favorite = true;
         }
 
         Ok(status_map)
@@ -100,6 +100,7 @@ impl UserRepository for UserRepo {
             created_at: NotSet,
             modified_by: Set(Some(user.modified_by)),
             modified_at: NotSet,
+            role: NotSet,
         };
 
         user_active_model.insert(txn).await?;
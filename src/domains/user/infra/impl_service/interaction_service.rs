@@ -13,6 +13,7 @@ use crate::{
     },
 };
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use sea_orm::DatabaseConnection;
 use std::{collections::HashMap, sync::Arc};
 
@@ -46,6 +47,53 @@ impl InteractionServiceTrait for InteractionService {
             .map_err(AppError::DatabaseError)
     }
 
+    async fn mark_seen(
+        &self,
+        user_id: &str,
+        record_id: &str,
+        seen_at: DateTime<Utc>,
+    ) -> Result<(), AppError> {
+        self.repo
+            .mark_seen(&self.db, user_id, record_id, seen_at)
+            .await
+            .map_err(AppError::DatabaseError)
+    }
+
+    async fn mark_unseen(&self, user_id: &str, record_id: &str) -> Result<(), AppError> {
+        self.repo
+            .mark_unseen(&self.db, user_id, record_id)
+            .await
+            .map_err(AppError::DatabaseError)
+    }
+
+    async fn add_favorite(&self, user_id: &str, record_id: &str) -> Result<(), AppError> {
+        self.repo
+            .add_favorite(&self.db, user_id, record_id)
+            .await
+            .map_err(AppError::DatabaseError)
+    }
+
+    async fn remove_favorite(&self, user_id: &str, record_id: &str) -> Result<(), AppError> {
+        self.repo
+            .remove_favorite(&self.db, user_id, record_id)
+            .await
+            .map_err(AppError::DatabaseError)
+    }
+
+    async fn rate(&self, user_id: &str, record_id: &str, score: i32) -> Result<(), AppError> {
+        self.repo
+            .rate(&self.db, user_id, record_id, score)
+            .await
+            .map_err(AppError::DatabaseError)
+    }
+
+    async fn remove_rating(&self, user_id: &str, record_id: &str) -> Result<(), AppError> {
+        self.repo
+            .remove_rating(&self.db, user_id, record_id)
+            .await
+            .map_err(AppError::DatabaseError)
+    }
+
     async fn batch_get_status(
         &self,
         user_id: &str,
@@ -94,6 +142,7 @@ impl InteractionServiceTrait for InteractionService {
             count: total as i64,
             next,
             previous,
+            next_cursor: None,
             results: ids,
         })
     }
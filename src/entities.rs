@@ -2,48 +2,84 @@
 //!
 //! This module contains all database entities generated from the database schema.
 
+pub mod api_keys;
+pub mod audit_log;
 pub mod crawl_code_result;
 pub mod crawl_entity_progress;
 pub mod crawl_page_result;
 pub mod crawl_task;
 pub mod devices;
 pub mod director;
+pub mod entity_translation;
 pub mod genre;
+pub mod genre_category;
 pub mod idol;
+pub mod idol_alias;
 pub mod idol_participation;
+pub mod jobs;
 pub mod label;
 pub mod links;
+pub mod media_files;
+pub mod password_reset_tokens;
 pub mod record;
+pub mod record_comments;
+pub mod record_deletion;
 pub mod record_genre;
+pub mod record_rating;
+pub mod record_revisions;
+pub mod record_tag;
+pub mod roles;
+pub mod saved_searches;
 pub mod search_document_versions;
 pub mod search_sync_events;
 pub mod series;
 pub mod studio;
+pub mod tag;
+pub mod tag_category;
 pub mod uploaded_files;
 pub mod user_auth;
 pub mod user_ext;
+pub mod user_record_favorites;
 pub mod user_record_interaction;
 pub mod users;
 
+pub use api_keys::{ApiKeysEntity, ApiKeysModel};
+pub use audit_log::{AuditLogEntity, AuditLogModel};
 pub use crawl_code_result::{CrawlCodeResultEntity, CrawlCodeResultModel};
 pub use crawl_entity_progress::{CrawlEntityProgressEntity, CrawlEntityProgressModel};
 pub use crawl_page_result::{CrawlPageResultEntity, CrawlPageResultModel};
 pub use crawl_task::{CrawlTaskEntity, CrawlTaskModel};
 pub use devices::{DevicesEntity, DevicesModel};
 pub use director::{DirectorEntity, DirectorModel};
+pub use entity_translation::{EntityTranslationEntity, EntityTranslationModel};
 pub use genre::{GenreEntity, GenreModel};
+pub use genre_category::{GenreCategoryEntity, GenreCategoryModel};
 pub use idol::{IdolEntity, IdolModel};
+pub use idol_alias::{IdolAliasEntity, IdolAliasModel};
 pub use idol_participation::{IdolParticipationEntity, IdolParticipationModel};
+pub use jobs::{JobsEntity, JobsModel};
 pub use label::{LabelEntity, LabelModel};
 pub use links::{LinksEntity, LinksModel};
+pub use media_files::{MediaFilesEntity, MediaFilesModel};
+pub use password_reset_tokens::{PasswordResetTokensEntity, PasswordResetTokensModel};
 pub use record::{RecordEntity, RecordModel};
+pub use record_comments::{RecordCommentsEntity, RecordCommentsModel};
+pub use record_deletion::{RecordDeletionEntity, RecordDeletionModel};
 pub use record_genre::{RecordGenreEntity, RecordGenreModel};
+pub use record_rating::{RecordRatingEntity, RecordRatingModel};
+pub use record_revisions::{RecordRevisionsEntity, RecordRevisionsModel};
+pub use record_tag::{RecordTagEntity, RecordTagModel};
+pub use roles::{RolesEntity, RolesModel};
+pub use saved_searches::{SavedSearchesEntity, SavedSearchesModel};
 pub use search_document_versions::{SearchDocumentVersionsEntity, SearchDocumentVersionsModel};
 pub use search_sync_events::{SearchSyncEventsEntity, SearchSyncEventsModel};
 pub use series::{SeriesEntity, SeriesModel};
 pub use studio::{StudioEntity, StudioModel};
+pub use tag::{TagEntity, TagModel};
+pub use tag_category::{TagCategoryEntity, TagCategoryModel};
 pub use uploaded_files::{UploadedFilesEntity, UploadedFilesModel};
 pub use user_auth::{UserAuthEntity, UserAuthModel};
 pub use user_ext::{UserExtEntity, UserExtModel};
+pub use user_record_favorites::{UserRecordFavoritesEntity, UserRecordFavoritesModel};
 pub use user_record_interaction::{UserRecordInteractionEntity, UserRecordInteractionModel};
 pub use users::{UsersEntity, UsersModel};
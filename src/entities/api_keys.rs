@@ -0,0 +1,52 @@
+//! `ApiKeys` entity
+//!
+//! Long-lived credentials for machine clients, sent as the `X-Api-Key` header.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+pub use Entity as ApiKeysEntity;
+pub use Model as ApiKeysModel;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "api_keys")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    /// Key ID (UUID).
+    pub id: String,
+    /// User the key authenticates as.
+    pub user_id: String,
+    /// Label chosen by the issuer, e.g. the client's name.
+    pub name: String,
+    /// Non-secret lookup prefix embedded in the key.
+    #[sea_orm(unique)]
+    pub prefix: String,
+    /// Hex SHA-256 digest of the full key.
+    pub key_hash: String,
+    /// Role granted to the key; capped at the owner's role when used.
+    pub role: String,
+    /// Timestamp when the key was issued.
+    pub created_at: DateTimeWithTimeZone,
+    /// Timestamp of the last successful authentication with the key.
+    pub last_used_at: Option<DateTimeWithTimeZone>,
+    /// Timestamp when the key was revoked; revoked keys no longer authenticate.
+    pub revoked_at: Option<DateTimeWithTimeZone>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::users::Entity",
+        from = "Column::UserId",
+        to = "super::users::Column::Id"
+    )]
+    Users,
+}
+
+impl Related<super::users::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Users.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,34 @@
+//! `AuditLog` entity
+//!
+//! Append-only history of create/update/delete operations and who made them.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+pub use Entity as AuditLogEntity;
+pub use Model as AuditLogModel;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "audit_log")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    /// Auto-incrementing audit entry ID.
+    pub id: i64,
+    /// Entity type label: "record", "director", "genre", "label", "studio", "series", "idol" or "user".
+    pub entity_type: String,
+    /// Primary key of the affected entity; `NULL` for bulk operations without a single target.
+    pub entity_id: Option<String>,
+    /// Operation performed: "create", "update", "delete", "merge", "restore", "purge" or "unlock".
+    pub action: String,
+    /// User ID (JWT `sub`) of the caller that made the change.
+    pub actor: String,
+    /// JSON change payload: the submitted `changes` and the resulting entity state (`result`).
+    pub diff: Option<Json>,
+    /// Timestamp when the change was recorded.
+    pub created_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -20,6 +20,15 @@ pub struct Model {
     pub created_at: Option<chrono::DateTime<chrono::Utc>>,
     pub modified_by: Option<String>,
     pub modified_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Argon2 hash of the current refresh token, `NULL` when signed out.
+    pub refresh_token_hash: Option<String>,
+    pub refresh_token_expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Time of the last authenticated request made with the device's tokens.
+    pub last_seen_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// `User-Agent` of that request.
+    pub user_agent: Option<String>,
+    /// Client IP of that request.
+    pub ip_address: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
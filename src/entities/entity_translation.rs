@@ -0,0 +1,27 @@
+//! Entity translation entity
+//!
+//! Display names of named card entities in other languages
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+pub use Entity as EntityTranslationEntity;
+pub use Model as EntityTranslationModel;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "entity_translation")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    /// `director`, `genre`, `idol`, `label`, `series` or `studio`.
+    pub entity_type: String,
+    pub entity_id: i64,
+    /// `ja`, `en` or `zh`.
+    pub language: String,
+    pub name: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
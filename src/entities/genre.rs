@@ -16,12 +16,26 @@ pub struct Model {
     pub name: String,
     pub link: String,
     pub manual: bool,
+    /// Category the genre is grouped under.
+    pub category_id: Option<i64>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
     #[sea_orm(has_many = "super::record_genre::Entity")]
     RecordGenre,
+    #[sea_orm(
+        belongs_to = "super::genre_category::Entity",
+        from = "Column::CategoryId",
+        to = "super::genre_category::Column::Id"
+    )]
+    GenreCategory,
+}
+
+impl Related<super::genre_category::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::GenreCategory.def()
+    }
 }
 
 impl Related<super::record::Entity> for Entity {
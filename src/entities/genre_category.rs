@@ -0,0 +1,33 @@
+//! `GenreCategory` entity
+//!
+//! Groups genres, e.g. themes apart from formats
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+pub use Entity as GenreCategoryEntity;
+pub use Model as GenreCategoryModel;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "genre_category")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    pub create_time: Date,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::genre::Entity")]
+    Genre,
+}
+
+impl Related<super::genre::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Genre.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
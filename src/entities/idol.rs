@@ -16,12 +16,26 @@ pub struct Model {
     pub name: String,
     pub link: String,
     pub manual: bool,
+    pub birthday: Option<Date>,
+    pub debut_date: Option<Date>,
+    pub height_cm: Option<i32>,
+    pub bust_cm: Option<i32>,
+    pub waist_cm: Option<i32>,
+    pub hips_cm: Option<i32>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
     #[sea_orm(has_many = "super::idol_participation::Entity")]
     IdolParticipation,
+    #[sea_orm(has_many = "super::idol_alias::Entity")]
+    IdolAlias,
+}
+
+impl Related<super::idol_alias::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::IdolAlias.def()
+    }
 }
 
 impl Related<super::record::Entity> for Entity {
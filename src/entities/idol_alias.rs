@@ -0,0 +1,36 @@
+//! Idol alias entity
+//!
+//! Other names an idol is known by
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+pub use Entity as IdolAliasEntity;
+pub use Model as IdolAliasModel;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "idol_alias")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub idol_id: i64,
+    pub alias: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::idol::Entity",
+        from = "Column::IdolId",
+        to = "super::idol::Column::Id"
+    )]
+    Idol,
+}
+
+impl Related<super::idol::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Idol.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
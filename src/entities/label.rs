@@ -16,12 +16,26 @@ pub struct Model {
     pub name: String,
     pub link: String,
     pub manual: bool,
+    /// Studio that publishes the label.
+    pub studio_id: Option<i64>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
     #[sea_orm(has_many = "super::record::Entity")]
     Record,
+    #[sea_orm(
+        belongs_to = "super::studio::Entity",
+        from = "Column::StudioId",
+        to = "super::studio::Column::Id"
+    )]
+    Studio,
+}
+
+impl Related<super::studio::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Studio.def()
+    }
 }
 
 impl Related<super::record::Entity> for Entity {
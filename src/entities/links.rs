@@ -2,6 +2,7 @@
 //!
 //! Represents download links for records
 
+use chrono::{DateTime, Utc};
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
@@ -19,6 +20,16 @@ pub struct Model {
     pub date: Date,
     pub link: String,
     pub star: bool,
+    /// Rank among the links of the record; higher comes first.
+    pub priority: i32,
+    /// Whether the last health check found the link unreachable.
+    pub dead: bool,
+    /// HTTP status of the last health check; unset when the request failed.
+    pub last_status: Option<i32>,
+    /// When the link was last checked; unset until the first check.
+    pub last_checked_at: Option<DateTime<Utc>>,
+    /// Day the link was added to its record.
+    pub create_time: Date,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -0,0 +1,31 @@
+//! Media files entity for `SeaORM`
+//!
+//! Stored record and idol images with the SHA-256 of their content.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+pub use Entity as MediaFilesEntity;
+pub use Model as MediaFilesModel;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "media_files")]
+pub struct Model {
+    /// Storage key, e.g. `images/record/ABC-123/ABC-123_1.jpg`.
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub key: String,
+    /// `record` or `idol`.
+    pub owner_type: String,
+    /// Record ID or idol name the file belongs to.
+    pub owner_id: String,
+    pub filename: String,
+    /// Lowercase hex SHA-256 of the content.
+    pub sha256: String,
+    pub size: i64,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
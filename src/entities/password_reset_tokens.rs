@@ -0,0 +1,45 @@
+//! `PasswordResetTokens` entity
+//!
+//! Single-use tokens emailed to users who forgot their password.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+pub use Entity as PasswordResetTokensEntity;
+pub use Model as PasswordResetTokensModel;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "password_reset_tokens")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    /// Token ID (UUID), embedded in the token sent to the user.
+    pub id: String,
+    /// User whose password the token resets.
+    pub user_id: String,
+    /// Argon2 hash of the full token.
+    pub token_hash: String,
+    /// The token is rejected after this time.
+    pub expires_at: DateTimeWithTimeZone,
+    /// Timestamp when the token was redeemed; used tokens are rejected.
+    pub used_at: Option<DateTimeWithTimeZone>,
+    /// Timestamp when the token was issued.
+    pub created_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::users::Entity",
+        from = "Column::UserId",
+        to = "super::users::Column::Id"
+    )]
+    Users,
+}
+
+impl Related<super::users::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Users.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
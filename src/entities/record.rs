@@ -27,6 +27,18 @@ pub struct Model {
     pub update_time: Date,
     pub creator: String,
     pub modified_by: String,
+    /// Set when the record is in the trash; `None` for live records.
+    pub deleted_at: Option<DateTimeUtc>,
+    /// Bumped on every API edit; checked against `If-Match`.
+    pub version: i32,
+    /// Position in `record_sync_seq`, restamped by a trigger when each write commits.
+    pub sync_seq: i64,
+    /// URL of the trailer video; `None` until one is uploaded.
+    pub trailer: Option<String>,
+    /// Sequence number of the cover image; `None` for the main `{id}` image.
+    pub cover_index: Option<i32>,
+    /// Position within the series; `None` when not numbered.
+    pub order_in_series: Option<i32>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -0,0 +1,27 @@
+//! `RecordDeletion` entity
+//!
+//! Journal of hard-deleted records, written by a trigger, so sync clients can
+//! drop records that no longer exist.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+pub use Entity as RecordDeletionEntity;
+pub use Model as RecordDeletionModel;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "record_deletion")]
+pub struct Model {
+    /// Position in `record_sync_seq`, shared with `record.sync_seq`.
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub seq: i64,
+    /// ID of the deleted record.
+    pub record_id: String,
+    /// When the record was deleted.
+    pub deleted_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
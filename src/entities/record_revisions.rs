@@ -0,0 +1,42 @@
+//! Record revisions entity for `SeaORM`
+//!
+//! Snapshots of records taken before each edit, with who made the edit.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+pub use Entity as RecordRevisionsEntity;
+pub use Model as RecordRevisionsModel;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "record_revisions")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub record_id: String,
+    /// Version of the record the snapshot shows.
+    pub version: i32,
+    /// User ID (JWT `sub`) of the caller whose edit replaced this state.
+    pub actor: String,
+    /// The record as a serialized `RecordDto`.
+    pub snapshot: Json,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::record::Entity",
+        from = "Column::RecordId",
+        to = "super::record::Column::Id"
+    )]
+    Record,
+}
+
+impl Related<super::record::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Record.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,24 @@
+//! `Roles` entity
+//!
+//! Access roles assignable to users: "viewer", "editor" and "admin".
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+pub use Entity as RolesEntity;
+pub use Model as RolesModel;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "roles")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    /// Role name, referenced by `users.role`.
+    pub name: String,
+    /// Human-readable summary of what the role may do.
+    pub description: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
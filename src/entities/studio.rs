@@ -16,12 +16,24 @@ pub struct Model {
     pub name: String,
     pub link: String,
     pub manual: bool,
+    /// Studio group the studio belongs to.
+    pub parent_id: Option<i64>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
     #[sea_orm(has_many = "super::record::Entity")]
     Record,
+    #[sea_orm(has_many = "super::label::Entity")]
+    Label,
+    #[sea_orm(belongs_to = "Entity", from = "Column::ParentId", to = "Column::Id")]
+    Parent,
+}
+
+impl Related<super::label::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Label.def()
+    }
 }
 
 impl Related<super::record::Entity> for Entity {
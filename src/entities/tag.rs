@@ -0,0 +1,56 @@
+//! Tag entity
+//!
+//! User-defined labels attached to records, grouped into categories
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+pub use Entity as TagEntity;
+pub use Model as TagModel;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "tag")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub category_id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    pub create_time: Date,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::tag_category::Entity",
+        from = "Column::CategoryId",
+        to = "super::tag_category::Column::Id"
+    )]
+    TagCategory,
+    #[sea_orm(has_many = "super::record_tag::Entity")]
+    RecordTag,
+}
+
+impl Related<super::tag_category::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TagCategory.def()
+    }
+}
+
+impl Related<super::record_tag::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::RecordTag.def()
+    }
+}
+
+impl Related<super::record::Entity> for Entity {
+    fn to() -> RelationDef {
+        super::record_tag::Relation::Record.def()
+    }
+
+    fn via() -> Option<RelationDef> {
+        Some(super::record_tag::Relation::Tag.def().rev())
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
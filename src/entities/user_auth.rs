@@ -14,6 +14,21 @@ pub struct Model {
     pub password_hash: String,
     pub created_at: Option<chrono::DateTime<chrono::Utc>>,
     pub modified_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Argon2 hash of the current refresh token, `NULL` when signed out.
+    pub refresh_token_hash: Option<String>,
+    pub refresh_token_expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Consecutive failed logins since the last successful one.
+    pub failed_login_attempts: i32,
+    /// Logins are rejected until this time after too many failures.
+    pub locked_until: Option<chrono::DateTime<chrono::Utc>>,
+    /// Encrypted TOTP secret, set on enrollment.
+    pub totp_secret: Option<Vec<u8>>,
+    /// Whether logins require a TOTP code; set once enrollment is verified.
+    pub totp_enabled: bool,
+    /// Time step of the last accepted TOTP code, so codes can't be replayed.
+    pub totp_last_used_step: Option<i64>,
+    /// Access tokens issued before the last bump are rejected.
+    pub token_generation: i32,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
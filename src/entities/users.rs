@@ -18,6 +18,8 @@ pub struct Model {
     pub created_at: Option<chrono::DateTime<chrono::Utc>>,
     pub modified_by: Option<String>,
     pub modified_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Access role name (see `roles`); defaults to "viewer".
+    pub role: String,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -1,6 +1,7 @@
 //! Lunirelust — an Axum-based web API.
 
 pub mod app;
+pub mod cli;
 pub mod common;
 pub mod domains;
 pub mod entities;
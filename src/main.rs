@@ -1,8 +1,15 @@
+use clap::Parser as _;
 use common::{
     bootstrap::{build_app_state, shutdown_signal},
     config::{setup_database, Config},
 };
-use lunirelust::{app::create_router, common};
+use lunirelust::{
+    app::create_router,
+    cli::{self, Cli, CliResult, Command},
+    common,
+    domains::auth::dto::auth_dto::RegisterDto,
+};
+use std::net::SocketAddr;
 use tracing::info;
 
 #[cfg(not(feature = "opentelemetry"))]
@@ -11,16 +18,18 @@ use common::bootstrap::setup_tracing;
 #[cfg(feature = "opentelemetry")]
 use common::opentelemetry::{setup_tracing_opentelemetry, shutdown_opentelemetry};
 
+#[cfg(feature = "grpc")]
+use lunirelust::domains::grpc::serve_grpc;
+
 /// Main entry point for the application.
-/// It sets up the database connection, initializes the server, and starts listening for requests.
-/// It also sets up the Swagger UI for API documentation.
+/// It parses the command line and runs the server (the default) or an admin command.
 ///
 /// # Errors
-/// Returns an error if the database connection fails or if the server fails to start.
-/// # Panics
-/// Panics if the environment variables are not set correctly or if the server fails to start.
+/// Returns an error if the configuration is invalid or the command fails.
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+async fn main() -> CliResult {
+    let cli = Cli::parse();
+
     #[cfg(not(feature = "opentelemetry"))]
     setup_tracing();
 
@@ -33,7 +42,42 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         provider
     };
 
-    let config = Config::from_env()?;
+    let config = Config::load(cli.config.as_deref())?;
+    let result = match cli.into_command() {
+        Command::Serve => serve(config).await,
+        Command::Migrate { action } => cli::migrate(&config, action).await,
+        Command::CreateAdmin {
+            username,
+            email,
+            password,
+        } => {
+            let dto = RegisterDto {
+                username,
+                email,
+                password,
+            };
+            cli::create_admin(config, dto).await
+        }
+        Command::Export {
+            format,
+            entity,
+            output,
+        } => cli::export(config, format, entity, output).await,
+        Command::ReindexSearch => cli::reindex_search(config).await,
+    };
+
+    #[cfg(feature = "opentelemetry")]
+    shutdown_opentelemetry(&opentelemetry_tracer_provider)?;
+
+    result
+}
+
+/// Sets up the database connection, initializes the server, and starts listening for requests.
+/// It also sets up the Swagger UI for API documentation.
+///
+/// # Errors
+/// Returns an error if the database connection fails or if the server fails to start.
+async fn serve(config: Config) -> CliResult {
     let pool = setup_database(&config).await?;
     let state = build_app_state(&pool, config.clone());
 
@@ -43,6 +87,18 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     // Reconcile stale crawl tasks from previous run.
     state.crawl_service.reconcile_startup().await;
 
+    // Run the background jobs on their schedules.
+    state.job_runner.start();
+
+    // The gRPC API runs on its own port and shares the services with the REST API.
+    #[cfg(feature = "grpc")]
+    let grpc_server = {
+        let grpc_addr = format!("{}:{}", config.service_host, config.grpc_port);
+        info!("gRPC server running at {grpc_addr}");
+        let grpc_listener = tokio::net::TcpListener::bind(&grpc_addr).await?;
+        tokio::spawn(serve_grpc(state.clone(), grpc_listener, shutdown_signal()))
+    };
+
     let app = create_router(state);
 
     let addr = format!("{}:{}", config.service_host, config.service_port);
@@ -51,12 +107,16 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
 
     let listener = tokio::net::TcpListener::bind(&addr).await?;
 
-    axum::serve(listener, app)
-        .with_graceful_shutdown(shutdown_signal())
-        .await?;
+    // Peer addresses are needed for per-IP rate limiting on the auth routes.
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(shutdown_signal())
+    .await?;
 
-    #[cfg(feature = "opentelemetry")]
-    shutdown_opentelemetry(&opentelemetry_tracer_provider)?;
+    #[cfg(feature = "grpc")]
+    grpc_server.await??;
 
     Ok(())
 }
@@ -1,7 +1,10 @@
 use axum::http::{Method, StatusCode};
 use lunirelust::{
-    common::dto::RestApiResponse,
-    domains::luna::dto::{DirectorDto, PaginatedResponse},
+    common::{
+        dto::{ErrorResponse, RestApiResponse, ValidationErrorDetail},
+        error::ErrorCode,
+    },
+    domains::luna::dto::{DirectorDto, MergeEntityResponse, PaginatedResponse},
 };
 
 use super::test_helpers::{deserialize_json_body, request_with_auth, request_with_auth_and_body};
@@ -230,3 +233,132 @@ async fn test_director_deduplication() {
 
     println!("Successfully verified director deduplication works");
 }
+
+/// Test that the `name` query parameter narrows the director list
+#[tokio::test]
+async fn test_director_name_filter_is_applied() {
+    let unique_name = format!("Filter Director {}", uuid::Uuid::new_v4().simple());
+    let create_payload = serde_json::json!({
+        "name": unique_name,
+        "link": "https://example.com/filter-director",
+        "manual": true
+    });
+    let response =
+        request_with_auth_and_body(Method::POST, "/cards/directors", &create_payload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let url = format!("/cards/directors?name={}", unique_name.replace(' ', "%20"));
+    let response = request_with_auth(Method::GET, &url).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let (_parts, body) = response.into_parts();
+    let directors: RestApiResponse<PaginatedResponse<DirectorDto>> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize filtered directors response");
+    let directors_data = directors.0.data.expect("No directors data");
+
+    assert_eq!(directors_data.count, 1, "Only the created director matches");
+    assert_eq!(directors_data.results[0].name, unique_name);
+}
+
+/// Test that PATCH accepts a partial body without an `id` and keeps the name
+#[tokio::test]
+async fn test_patch_director_keeps_omitted_fields() {
+    let unique_name = format!("Patch Director {}", uuid::Uuid::new_v4().simple());
+    let create_payload = serde_json::json!({
+        "name": unique_name,
+        "link": "https://example.com/patch-director",
+        "manual": true
+    });
+    let response =
+        request_with_auth_and_body(Method::POST, "/cards/directors", &create_payload).await;
+    let (_parts, body) = response.into_parts();
+    let created: RestApiResponse<DirectorDto> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize created director response");
+    let created = created.0.data.expect("No created director data");
+
+    let patch_payload = serde_json::json!({ "link": "https://example.com/patched" });
+    let url = format!("/cards/directors/{}", created.id);
+    let response = request_with_auth_and_body(Method::PATCH, &url, &patch_payload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let (_parts, body) = response.into_parts();
+    let patched: RestApiResponse<DirectorDto> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize patched director response");
+    let patched = patched.0.data.expect("No patched director data");
+    assert_eq!(
+        patched.name, unique_name,
+        "Omitted name should be preserved"
+    );
+    assert_eq!(patched.link, "https://example.com/patched");
+}
+
+/// Create a director with a unique name and return it
+async fn create_unique_director(prefix: &str) -> DirectorDto {
+    let payload = serde_json::json!({
+        "name": format!("{prefix} {}", uuid::Uuid::new_v4().simple()),
+        "link": "",
+        "manual": true
+    });
+    let response = request_with_auth_and_body(Method::POST, "/cards/directors", &payload).await;
+    let (_parts, body) = response.into_parts();
+    let created: RestApiResponse<DirectorDto> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize created director response");
+    created.0.data.expect("No created director data")
+}
+
+/// Test that merging deletes the duplicate and rejects self-merges
+#[tokio::test]
+async fn test_merge_director() {
+    let target = create_unique_director("Merge Target").await;
+    let source = create_unique_director("Merge Source").await;
+
+    let url = format!("/cards/directors/{}/merge", target.id);
+    let payload = serde_json::json!({ "source_id": source.id });
+    let response = request_with_auth_and_body(Method::POST, &url, &payload).await;
+    let (parts, body) = response.into_parts();
+    assert_eq!(parts.status, StatusCode::OK);
+    let merged: RestApiResponse<MergeEntityResponse> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize merge response");
+    let merged = merged.0.data.expect("No merge data");
+    assert_eq!(merged.id, target.id);
+    assert_eq!(merged.merged_id, source.id);
+    assert_eq!(merged.records, 0);
+
+    let response = request_with_auth(Method::GET, &format!("/cards/directors/{}", source.id)).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let payload = serde_json::json!({ "source_id": target.id });
+    let response = request_with_auth_and_body(Method::POST, &url, &payload).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+/// Test that a body failing validation reports each failed rule
+#[tokio::test]
+async fn test_create_director_validation_details() {
+    let payload = serde_json::json!({ "name": "" });
+    let response = request_with_auth_and_body(Method::POST, "/cards/directors", &payload).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let (_parts, body) = response.into_parts();
+    let error: ErrorResponse = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize validation error");
+    assert_eq!(error.status, 400);
+    assert_eq!(error.code, ErrorCode::ValidationFailed);
+    let details: Vec<ValidationErrorDetail> =
+        serde_json::from_value(error.details.expect("No validation details"))
+            .expect("Failed to deserialize validation details");
+    assert_eq!(
+        details,
+        [ValidationErrorDetail {
+            field: "name".to_owned(),
+            code: "length".to_owned(),
+            message: Some("Name cannot be empty".to_owned()),
+        }]
+    );
+}
@@ -1,10 +1,16 @@
-use axum::http::{Method, StatusCode};
+use axum::http::{header::ACCEPT_LANGUAGE, Method, StatusCode};
 use lunirelust::{
     common::dto::RestApiResponse,
-    domains::luna::dto::{GenreDto, PaginatedResponse},
+    domains::luna::dto::{
+        GenreCategoryCountDto, GenreCategoryDto, GenreDto, Language, NameSuggestionDto,
+        PaginatedResponse, RecordDto, TranslationDto,
+    },
 };
 
-use super::test_helpers::{deserialize_json_body, request_with_auth, request_with_auth_and_body};
+use super::test_helpers::{
+    deserialize_json_body, request_with_auth, request_with_auth_and_body,
+    request_with_auth_and_header,
+};
 
 /// Test getting all genres
 #[tokio::test]
@@ -117,3 +123,314 @@ async fn test_genre_deduplication() {
     );
     println!("Successfully verified genre deduplication works");
 }
+
+/// Test grouping genres into categories, filtering records by category and
+/// the grouped record counts
+#[tokio::test]
+async fn test_genre_categories() {
+    let marker = uuid::Uuid::new_v4();
+    let category = serde_json::json!({
+        "name": format!("Format {marker}"),
+        "description": "How the record is made"
+    });
+    let response =
+        request_with_auth_and_body(Method::POST, "/cards/genres/categories", &category).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let (_parts, body) = response.into_parts();
+    let created: RestApiResponse<GenreCategoryDto> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize genre category");
+    let category_id = created.0.data.expect("No genre category data").id;
+
+    let response =
+        request_with_auth_and_body(Method::POST, "/cards/genres/categories", &category).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let record_id = format!("test-genre-category-{marker}");
+    let payload = serde_json::json!({
+        "id": record_id,
+        "title": "Genre Category Record",
+        "date": "2025-08-11",
+        "duration": 60,
+        "director": null,
+        "studio": null,
+        "label": null,
+        "series": null,
+        "genres": [{
+            "name": format!("Category Genre {marker}"),
+            "link": format!("https://example.com/genre/{marker}"),
+            "manual": true
+        }],
+        "idols": [],
+        "has_links": false,
+        "links": [],
+        "permission": 1,
+        "local_img_count": 0
+    });
+    let response = request_with_auth_and_body(Method::POST, "/cards/records", &payload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let (_parts, body) = response.into_parts();
+    let record: RestApiResponse<RecordDto> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize created record");
+    let genre_id = record.0.data.expect("No record data").genres[0].genre.id;
+
+    let url = format!("/cards/genres/{genre_id}/category");
+    let response = request_with_auth_and_body(
+        Method::PUT,
+        &url,
+        &serde_json::json!({ "category_id": 999_999_999 }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = request_with_auth_and_body(
+        Method::PUT,
+        &url,
+        &serde_json::json!({ "category_id": category_id }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let (_parts, body) = response.into_parts();
+    let genre: RestApiResponse<GenreDto> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize genre");
+    assert_eq!(
+        genre.0.data.expect("No genre data").category_id,
+        Some(category_id)
+    );
+
+    let url = format!("/cards/records?genre_category_id={category_id}");
+    let response = request_with_auth(Method::GET, &url).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let (_parts, body) = response.into_parts();
+    let records: RestApiResponse<PaginatedResponse<RecordDto>> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize records");
+    let records = records.0.data.expect("No records data");
+    assert_eq!(records.count, 1);
+    assert_eq!(records.results[0].id, record_id);
+
+    let response = request_with_auth(Method::GET, "/cards/genre-records-count/by-category").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let (_parts, body) = response.into_parts();
+    let groups: RestApiResponse<Vec<GenreCategoryCountDto>> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize grouped counts");
+    let groups = groups.0.data.expect("No grouped counts data");
+    let group = groups
+        .iter()
+        .find(|group| group.id == Some(category_id))
+        .expect("The category should be grouped");
+    assert_eq!(group.count, 1);
+    assert_eq!(group.genres.len(), 1);
+    assert_eq!(group.genres[0].id, genre_id);
+
+    let url = format!("/cards/genres/categories/{category_id}");
+    let response = request_with_auth(Method::DELETE, &url).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = request_with_auth(Method::GET, &url).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = request_with_auth(Method::GET, &format!("/cards/genres/{genre_id}")).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let (_parts, body) = response.into_parts();
+    let genre: RestApiResponse<GenreDto> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize genre");
+    assert_eq!(
+        genre.0.data.expect("No genre data").category_id,
+        None,
+        "Deleting the category leaves its genres uncategorized"
+    );
+}
+
+/// Test looking up many genres at once, in the order asked for
+#[tokio::test]
+async fn test_get_genres_by_ids() {
+    let marker = uuid::Uuid::new_v4();
+    let mut ids = Vec::new();
+    for name in ["First", "Second"] {
+        let genre = serde_json::json!({
+            "name": format!("Batch {name} {marker}"),
+            "link": format!("https://example.com/genre/{name}/{marker}"),
+            "manual": true
+        });
+        let response = request_with_auth_and_body(Method::POST, "/cards/genres", &genre).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let (_parts, body) = response.into_parts();
+        let created: RestApiResponse<GenreDto> = deserialize_json_body(body)
+            .await
+            .expect("Failed to deserialize genre");
+        ids.push(created.0.data.expect("No genre data").id);
+    }
+
+    let payload = serde_json::json!({ "ids": [ids[1], 999_999_999, ids[0], ids[1]] });
+    let response = request_with_auth_and_body(Method::POST, "/cards/genres/batch", &payload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let (_parts, body) = response.into_parts();
+    let found: RestApiResponse<Vec<GenreDto>> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize genres");
+    let found: Vec<i64> = found
+        .0
+        .data
+        .expect("No genres data")
+        .iter()
+        .map(|genre| genre.id)
+        .collect();
+    assert_eq!(
+        found,
+        [ids[1], ids[0]],
+        "Unknown and repeated IDs are dropped"
+    );
+
+    let empty = serde_json::json!({ "ids": [] });
+    let response = request_with_auth_and_body(Method::POST, "/cards/genres/batch", &empty).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+/// Test that autocomplete ranks prefix matches first, then by record count
+#[tokio::test]
+async fn test_autocomplete_genres() {
+    let marker = uuid::Uuid::new_v4().simple().to_string();
+    let genre = |name: String| {
+        serde_json::json!({
+            "name": name,
+            "link": format!("https://example.com/genre/{}", uuid::Uuid::new_v4()),
+            "manual": true
+        })
+    };
+    for name in [format!("{marker} alpha"), format!("Other {marker}")] {
+        let response =
+            request_with_auth_and_body(Method::POST, "/cards/genres", &genre(name)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+    // The only genre with a record outranks the other prefix match.
+    let payload = serde_json::json!({
+        "id": format!("test-autocomplete-{marker}"),
+        "title": "Autocomplete Record",
+        "date": "2025-08-11",
+        "duration": 60,
+        "director": null,
+        "studio": null,
+        "label": null,
+        "series": null,
+        "genres": [genre(format!("{marker} beta"))],
+        "idols": [],
+        "has_links": false,
+        "links": [],
+        "permission": 1,
+        "local_img_count": 0
+    });
+    let response = request_with_auth_and_body(Method::POST, "/cards/records", &payload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let url = format!("/cards/genres/autocomplete?q={}", marker.to_uppercase());
+    let response = request_with_auth(Method::GET, &url).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let (_parts, body) = response.into_parts();
+    let suggestions: RestApiResponse<Vec<NameSuggestionDto>> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize suggestions");
+    let names: Vec<String> = suggestions
+        .0
+        .data
+        .expect("No suggestions data")
+        .into_iter()
+        .map(|suggestion| suggestion.name)
+        .collect();
+    assert_eq!(
+        names,
+        [
+            format!("{marker} beta"),
+            format!("{marker} alpha"),
+            format!("Other {marker}")
+        ]
+    );
+
+    let response = request_with_auth(Method::GET, &format!("{url}&limit=1")).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let (_parts, body) = response.into_parts();
+    let suggestions: RestApiResponse<Vec<NameSuggestionDto>> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize suggestions");
+    assert_eq!(suggestions.0.data.expect("No suggestions data").len(), 1);
+
+    let response = request_with_auth(Method::GET, "/cards/genres/autocomplete?q=%20").await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+/// Test that genre names follow `Accept-Language` once translated
+#[tokio::test]
+async fn test_genre_name_translations() {
+    let marker = uuid::Uuid::new_v4().simple().to_string();
+    let payload = serde_json::json!({
+        "name": format!("Translated {marker}"),
+        "link": format!("https://example.com/genre/{marker}"),
+        "manual": true
+    });
+    let response = request_with_auth_and_body(Method::POST, "/cards/genres", &payload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let (_parts, body) = response.into_parts();
+    let genre: RestApiResponse<GenreDto> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize genre");
+    let id = genre.0.data.expect("No genre data").id;
+
+    let url = format!("/cards/admin/translations/genre/{id}/ja");
+    let translation = serde_json::json!({ "name": format!("翻訳 {marker}") });
+    let response = request_with_auth_and_body(Method::PUT, &url, &translation).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let genre_url = format!("/cards/genres/{id}");
+    let name_for = |accept_language: &'static str| {
+        let genre_url = genre_url.clone();
+        async move {
+            let response = request_with_auth_and_header(
+                Method::GET,
+                &genre_url,
+                ACCEPT_LANGUAGE,
+                accept_language,
+            )
+            .await;
+            assert_eq!(response.status(), StatusCode::OK);
+            let (_parts, body) = response.into_parts();
+            let genre: RestApiResponse<GenreDto> = deserialize_json_body(body)
+                .await
+                .expect("Failed to deserialize genre");
+            genre.0.data.expect("No genre data").name
+        }
+    };
+    assert_eq!(name_for("ja-JP, en;q=0.5").await, format!("翻訳 {marker}"));
+    // No English translation, so the stored name is kept
+    assert_eq!(name_for("en").await, format!("Translated {marker}"));
+
+    let response = request_with_auth(
+        Method::GET,
+        &format!("/cards/admin/translations/genre/{id}"),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let (_parts, body) = response.into_parts();
+    let translations: RestApiResponse<Vec<TranslationDto>> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize translations");
+    let translations = translations.0.data.expect("No translations data");
+    assert_eq!(translations.len(), 1);
+    assert_eq!(translations[0].language, Language::Ja);
+
+    let response = request_with_auth(Method::DELETE, &url).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(name_for("ja").await, format!("Translated {marker}"));
+    let response = request_with_auth(Method::DELETE, &url).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = request_with_auth_and_body(
+        Method::PUT,
+        "/cards/admin/translations/genre/-1/ja",
+        &translation,
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
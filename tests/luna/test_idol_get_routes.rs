@@ -1,10 +1,15 @@
 use axum::http::{Method, StatusCode};
 use lunirelust::{
     common::dto::RestApiResponse,
-    domains::luna::dto::{IdolDto, PaginatedResponse},
+    domains::luna::dto::{
+        BulkCreateResponse, IdolDto, IdolProfileDto, PaginatedResponse, RecordDto, ResolvedIdolDto,
+    },
 };
 
-use super::test_helpers::{deserialize_json_body, request_with_auth, request_with_auth_and_body};
+use super::test_helpers::{
+    deserialize_json_body, register_viewer_token, request_with_auth, request_with_auth_and_body,
+    request_with_token_and_body,
+};
 
 /// Test getting all idols
 #[tokio::test]
@@ -192,3 +197,297 @@ async fn test_idol_deduplication() {
     );
     println!("Successfully verified idol deduplication works");
 }
+
+/// Test that idol profiles are replaced as a whole, that aliases find the idol
+/// and its records, and that records report the idol's age on their date
+#[tokio::test]
+async fn test_idol_profile_and_aliases() {
+    let suffix = uuid::Uuid::new_v4();
+    let name = format!("Profile Idol {suffix}");
+    let alias = format!("alias-{suffix}");
+    let response = request_with_auth_and_body(
+        Method::POST,
+        "/cards/idols",
+        &serde_json::json!({ "name": name }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let created: RestApiResponse<IdolDto> = deserialize_json_body(response.into_body())
+        .await
+        .expect("Failed to deserialize created idol");
+    let idol_id = created.0.data.expect("No created idol data").id;
+
+    let url = format!("/cards/idols/{idol_id}/profile");
+    let profile = serde_json::json!({
+        "birthday": "2000-06-15",
+        "debut_date": "2019-04-01",
+        "height_cm": 158,
+        "bust_cm": 82,
+        "waist_cm": 58,
+        "hips_cm": 85,
+        "aliases": [format!(" {alias} "), alias, "Another Name"]
+    });
+    let response = request_with_auth_and_body(Method::PUT, &url, &profile).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: RestApiResponse<IdolProfileDto> = deserialize_json_body(response.into_body())
+        .await
+        .expect("Failed to deserialize profile");
+    let saved = body.0.data.expect("No profile data");
+    assert_eq!(
+        saved.aliases,
+        ["Another Name".to_owned(), alias.clone()],
+        "Aliases are trimmed, deduplicated and sorted"
+    );
+    assert_eq!(saved.height_cm, Some(158));
+
+    let response = request_with_auth(Method::GET, &url).await;
+    let body: RestApiResponse<IdolProfileDto> = deserialize_json_body(response.into_body())
+        .await
+        .expect("Failed to deserialize profile");
+    let fetched = body.0.data.expect("No profile data");
+    assert_eq!(fetched.aliases, saved.aliases);
+    assert_eq!(
+        fetched.birthday.map(|d| d.to_string()).as_deref(),
+        Some("2000-06-15")
+    );
+
+    let response = request_with_auth(Method::GET, &format!("/cards/idols?search={alias}")).await;
+    let body: RestApiResponse<PaginatedResponse<IdolDto>> =
+        deserialize_json_body(response.into_body())
+            .await
+            .expect("Failed to deserialize idols");
+    let found = body.0.data.expect("No idols data").results;
+    assert_eq!(found.len(), 1, "Searching an alias finds the idol");
+    assert_eq!(found[0].id, idol_id);
+    assert_eq!(
+        found[0].debut_date.map(|d| d.to_string()).as_deref(),
+        Some("2019-04-01")
+    );
+
+    let record_id = format!("profile-{suffix}");
+    let payload = serde_json::json!([{
+        "id": record_id,
+        "title": "Profile Record",
+        "date": "2020-06-14",
+        "duration": 60,
+        "director": null,
+        "studio": null,
+        "label": null,
+        "series": null,
+        "genres": [],
+        "idols": [{ "name": name, "link": null, "manual": null }],
+        "has_links": false,
+        "links": [],
+        "permission": 1,
+        "local_img_count": 0
+    }]);
+    let response = request_with_auth_and_body(Method::POST, "/cards/records/bulk", &payload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = request_with_auth(Method::GET, &format!("/cards/records/{record_id}")).await;
+    let body: RestApiResponse<RecordDto> = deserialize_json_body(response.into_body())
+        .await
+        .expect("Failed to deserialize record");
+    let record = body.0.data.expect("No record data");
+    assert_eq!(record.idols.len(), 1);
+    assert_eq!(record.idols[0].idol.id, idol_id);
+    assert_eq!(
+        record.idols[0].age_at_record,
+        Some(19),
+        "The day before turning 20"
+    );
+
+    let response = request_with_auth(Method::GET, &format!("/cards/records?search={alias}")).await;
+    let body: RestApiResponse<PaginatedResponse<RecordDto>> =
+        deserialize_json_body(response.into_body())
+            .await
+            .expect("Failed to deserialize records");
+    let records = body.0.data.expect("No records data").results;
+    assert!(
+        records.iter().any(|r| r.id == record_id),
+        "Record search matches aliases"
+    );
+
+    // A replace without aliases or measurements clears them
+    let response = request_with_auth_and_body(
+        Method::PUT,
+        &url,
+        &serde_json::json!({ "birthday": "2000-06-15" }),
+    )
+    .await;
+    let body: RestApiResponse<IdolProfileDto> = deserialize_json_body(response.into_body())
+        .await
+        .expect("Failed to deserialize profile");
+    let cleared = body.0.data.expect("No profile data");
+    assert!(cleared.aliases.is_empty());
+    assert_eq!(cleared.height_cm, None);
+}
+
+/// Test that a record naming an idol by alias is linked to that idol
+#[tokio::test]
+async fn test_record_idol_alias_resolution() {
+    let suffix = uuid::Uuid::new_v4();
+    let name = format!("Canonical Idol {suffix}");
+    let alias = format!("stage-name-{suffix}");
+    let response = request_with_auth_and_body(
+        Method::POST,
+        "/cards/idols",
+        &serde_json::json!({ "name": name }),
+    )
+    .await;
+    let created: RestApiResponse<IdolDto> = deserialize_json_body(response.into_body())
+        .await
+        .expect("Failed to deserialize created idol");
+    let idol_id = created.0.data.expect("No created idol data").id;
+    let response = request_with_auth_and_body(
+        Method::PUT,
+        &format!("/cards/idols/{idol_id}/profile"),
+        &serde_json::json!({ "aliases": [alias] }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let record_id = format!("alias-{suffix}");
+    let payload = serde_json::json!([{
+        "id": record_id,
+        "title": "Alias Record",
+        "date": "2020-06-14",
+        "duration": 60,
+        "director": null,
+        "studio": null,
+        "label": null,
+        "series": null,
+        "genres": [],
+        "idols": [{ "name": alias, "link": null, "manual": null }],
+        "has_links": false,
+        "links": [],
+        "permission": 1,
+        "local_img_count": 0
+    }]);
+    let response = request_with_auth_and_body(Method::POST, "/cards/records/bulk", &payload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: RestApiResponse<BulkCreateResponse> = deserialize_json_body(response.into_body())
+        .await
+        .expect("Failed to deserialize bulk response");
+    let results = body.0.data.expect("No bulk data").results;
+    assert!(results[0].success);
+    assert_eq!(
+        results[0].resolved_idols,
+        [ResolvedIdolDto {
+            name: alias.clone(),
+            idol_id,
+            idol_name: name.clone(),
+        }]
+    );
+
+    let response = request_with_auth(Method::GET, &format!("/cards/records/{record_id}")).await;
+    let body: RestApiResponse<RecordDto> = deserialize_json_body(response.into_body())
+        .await
+        .expect("Failed to deserialize record");
+    let record = body.0.data.expect("No record data");
+    assert_eq!(record.idols.len(), 1);
+    assert_eq!(
+        record.idols[0].idol.id, idol_id,
+        "No idol named after the alias"
+    );
+    assert_eq!(record.idols[0].idol.name, name);
+}
+
+/// Test that invalid profiles, unknown idols and viewers are rejected
+#[tokio::test]
+async fn test_idol_profile_errors() {
+    let response = request_with_auth_and_body(
+        Method::POST,
+        "/cards/idols",
+        &serde_json::json!({ "name": format!("Invalid Profile Idol {}", uuid::Uuid::new_v4()) }),
+    )
+    .await;
+    let created: RestApiResponse<IdolDto> = deserialize_json_body(response.into_body())
+        .await
+        .expect("Failed to deserialize created idol");
+    let url = format!(
+        "/cards/idols/{}/profile",
+        created.0.data.expect("No created idol data").id
+    );
+
+    for invalid in [
+        serde_json::json!({ "birthday": "2000-06-15", "debut_date": "1999-01-01" }),
+        serde_json::json!({ "height_cm": 10 }),
+        serde_json::json!({ "aliases": ["  "] }),
+    ] {
+        let response = request_with_auth_and_body(Method::PUT, &url, &invalid).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{invalid}");
+    }
+
+    let response = request_with_auth(Method::GET, "/cards/idols/999999999/profile").await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let token = register_viewer_token().await;
+    let response = request_with_token_and_body(
+        Method::PUT,
+        &url,
+        &token,
+        &serde_json::json!({ "height_cm": 160 }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+/// Test looking up many idols at once: request order, each once, unknown IDs
+/// left out, and the ID count bounded
+#[tokio::test]
+async fn test_get_idols_by_ids() {
+    let marker = uuid::Uuid::new_v4();
+    let mut ids = Vec::new();
+    for name in ["First", "Second", "Third"] {
+        let idol = serde_json::json!({
+            "name": format!("Batch {name} {marker}"),
+            "link": format!("https://example.com/idol/{name}/{marker}"),
+            "manual": true
+        });
+        let response = request_with_auth_and_body(Method::POST, "/cards/idols", &idol).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let (_parts, body) = response.into_parts();
+        let created: RestApiResponse<IdolDto> = deserialize_json_body(body)
+            .await
+            .expect("Failed to deserialize idol");
+        ids.push(created.0.data.expect("No idol data").id);
+    }
+
+    let payload = serde_json::json!({
+        "ids": [ids[2], 999_999_999, ids[0], ids[2], -1, ids[1], ids[0]]
+    });
+    let response = request_with_auth_and_body(Method::POST, "/cards/idols/batch", &payload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let (_parts, body) = response.into_parts();
+    let found: RestApiResponse<Vec<IdolDto>> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize idols");
+    let found: Vec<i64> = found
+        .0
+        .data
+        .expect("No idols data")
+        .iter()
+        .map(|idol| idol.id)
+        .collect();
+    assert_eq!(
+        found,
+        [ids[2], ids[0], ids[1]],
+        "Found idols follow the request order; unknown and repeated IDs are dropped"
+    );
+
+    let missing = serde_json::json!({ "ids": [999_999_999] });
+    let response = request_with_auth_and_body(Method::POST, "/cards/idols/batch", &missing).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let (_parts, body) = response.into_parts();
+    let found: RestApiResponse<Vec<IdolDto>> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize idols");
+    assert!(found.0.data.expect("No idols data").is_empty());
+
+    for invalid in [Vec::new(), vec![ids[0]; 201]] {
+        let payload = serde_json::json!({ "ids": invalid });
+        let response =
+            request_with_auth_and_body(Method::POST, "/cards/idols/batch", &payload).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+}
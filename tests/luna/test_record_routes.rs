@@ -1,10 +1,39 @@
-use axum::http::{Method, StatusCode};
+use axum::http::{
+    header::{
+        ACCEPT_ENCODING, ACCEPT_RANGES, AUTHORIZATION, CONTENT_ENCODING, CONTENT_RANGE,
+        CONTENT_TYPE, ETAG, IF_MATCH, IF_NONE_MATCH, LAST_MODIFIED, RANGE,
+    },
+    Method, StatusCode,
+};
+use http_body_util::BodyExt as _;
 use lunirelust::{
-    common::dto::RestApiResponse,
-    domains::luna::dto::{PaginatedResponse, RecordDto},
+    common::{
+        config::DEFAULT_JSON_BODY_LIMIT,
+        dto::{ErrorResponse, RestApiResponse},
+        error::ErrorCode,
+    },
+    domains::luna::dto::{
+        csv_line, BulkCreateResponse, BulkDeleteRecordsResponse, CommentDto, DuplicateGroupDto,
+        DuplicateImageGroupDto, DuplicateReason, IntegrityReportDto, LinkDto, LinkSkipReason,
+        LinkUpdateResultDto, MediaFileDto, MediaGcReportDto, PaginatedResponse, RecordDto,
+        RecordExistsResponse, RecordRevisionDto, SavedSearchDto, SeenRecordDto, SimilarRecordDto,
+        TagCountDto, TagDto, RECORD_EXPORT_COLUMNS,
+    },
+    domains::user::dto::user_dto::UserDto,
+    entities::{genre, idol_participation, record, record_genre, record_revisions},
+};
+use sea_orm::{
+    sea_query::Expr, ColumnTrait as _, EntityTrait as _, PaginatorTrait as _, QueryFilter as _,
+    TransactionTrait as _,
 };
+use tower::ServiceExt as _;
 
-use super::test_helpers::{deserialize_json_body, request_with_auth, request_with_auth_and_body};
+use super::test_helpers::{
+    create_test_router_with, deserialize_json_body, get_authentication_token,
+    register_viewer_token, request_with_auth, request_with_auth_and_body,
+    request_with_auth_and_header, request_with_auth_and_multipart,
+    request_with_auth_header_and_body, request_with_token_and_body, setup_test_db,
+};
 
 /// Test creating a new record with a simple payload to verify basic functionality
 #[tokio::test]
@@ -52,9 +81,7 @@ async fn test_create_record_simple() {
         "has_links": false,
         "links": [],
         "permission": 1,
-        "local_img_count": 0,
-        "creator": "test_creator",
-        "modified_by": "test_modifier"
+        "local_img_count": 0
     });
 
     // Act - Send POST request to create record
@@ -166,165 +193,3662 @@ async fn test_get_records_with_pagination() {
     );
 }
 
-/// Test getting a specific record by ID - this will test the endpoint structure
+/// Test that list filters from the query string are applied to the results
 #[tokio::test]
-async fn test_get_record_by_id_endpoint() {
-    // Arrange - Use a test ID
-    let test_id = "test-record-endpoint-check";
+async fn test_get_records_with_filters() {
+    let response = request_with_auth(
+        Method::GET,
+        "/cards/records?director_id=0&date_from=2000-01-01&date_to=2099-12-31&limit=10",
+    )
+    .await;
 
-    // Act - Try to get record by ID
-    let url = format!("/cards/records/{test_id}");
+    let (parts, body) = response.into_parts();
+    assert_eq!(
+        parts.status,
+        StatusCode::OK,
+        "Expected filtered records endpoint to return OK, got: {}",
+        parts.status
+    );
+
+    let response_body: RestApiResponse<PaginatedResponse<RecordDto>> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize filtered records response");
+    let paginated_data = response_body.0.data.expect("Should have data in response");
+
+    for record in &paginated_data.results {
+        assert_eq!(record.director.id, 0, "Director filter should be applied");
+        assert!(
+            record.date.to_string().as_str() >= "2000-01-01"
+                && record.date.to_string().as_str() <= "2099-12-31",
+            "Date range filter should be applied, got {}",
+            record.date
+        );
+    }
+}
+
+/// Test that an inverted date range is rejected
+#[tokio::test]
+async fn test_get_records_with_inverted_date_range() {
+    let response = request_with_auth(
+        Method::GET,
+        "/cards/records?date_from=2025-01-01&date_to=2024-01-01",
+    )
+    .await;
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+/// Test that `ordering` sorts the record list
+#[tokio::test]
+async fn test_get_records_with_ordering() {
+    let response = request_with_auth(Method::GET, "/cards/records?ordering=date,id&limit=20").await;
+
+    let (parts, body) = response.into_parts();
+    assert_eq!(parts.status, StatusCode::OK);
+
+    let response_body: RestApiResponse<PaginatedResponse<RecordDto>> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize ordered records response");
+    let paginated_data = response_body.0.data.expect("Should have data in response");
+
+    for pair in paginated_data.results.windows(2) {
+        assert!(
+            pair[0].date <= pair[1].date,
+            "Records should be sorted by date ascending"
+        );
+    }
+}
+
+/// Test that an ordering field outside the allowlist is rejected
+#[tokio::test]
+async fn test_get_records_with_unknown_ordering() {
+    let response = request_with_auth(Method::GET, "/cards/records?ordering=-creator").await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+/// Test that keyset pagination returns consecutive, non-overlapping pages
+#[tokio::test]
+async fn test_get_records_with_cursor() {
+    let response = request_with_auth(Method::GET, "/cards/records?cursor=&limit=2").await;
+    let (parts, body) = response.into_parts();
+    assert_eq!(parts.status, StatusCode::OK);
+
+    let first: RestApiResponse<PaginatedResponse<RecordDto>> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize first cursor page");
+    let first = first.0.data.expect("Should have data in response");
+    assert!(first.previous.is_none(), "Keyset mode has no previous link");
+
+    let Some(next_cursor) = first.next_cursor else {
+        assert!(first.count <= 2, "Missing next_cursor with more rows left");
+        return;
+    };
+
+    let url = format!("/cards/records?cursor={next_cursor}&limit=2");
     let response = request_with_auth(Method::GET, &url).await;
+    let (parts, body) = response.into_parts();
+    assert_eq!(parts.status, StatusCode::OK);
 
-    // Assert - Verify the endpoint is accessible
-    let (parts, _body) = response.into_parts();
+    let second: RestApiResponse<PaginatedResponse<RecordDto>> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize second cursor page");
+    let second = second.0.data.expect("Should have data in response");
+    assert!(!second.results.is_empty());
+    for record in &second.results {
+        assert!(
+            first.results.iter().all(|r| r.id != record.id),
+            "Pages should not overlap"
+        );
+    }
+}
 
-    // The endpoint should be accessible, even if the record doesn't exist
+/// Test that cursor pages keep their filters and skip recounting
+#[tokio::test]
+async fn test_get_records_with_cursor_keeps_filters() {
+    let title = format!("Cursor Filter {}", uuid::Uuid::new_v4());
+    let payload: Vec<serde_json::Value> = (0..3)
+        .map(|_| {
+            let mut item = bulk_record_payload(&format!("cursor-{}", uuid::Uuid::new_v4()));
+            item["title"] = serde_json::json!(title);
+            item
+        })
+        .collect();
+    let response = request_with_auth_and_body(
+        Method::POST,
+        "/cards/records/bulk",
+        &serde_json::json!(payload),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let encoded_title = title.replace(' ', "%20").replace('-', "%2D");
+    let url = format!("/cards/records?cursor=&limit=2&title={encoded_title}");
+    let response = request_with_auth(Method::GET, &url).await;
+    let (parts, body) = response.into_parts();
+    assert_eq!(parts.status, StatusCode::OK);
+
+    let first: RestApiResponse<PaginatedResponse<RecordDto>> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize first cursor page");
+    let first = first.0.data.expect("Should have data in response");
+    assert_eq!(first.count, 3);
+    assert_eq!(first.results.len(), 2);
+    let next = first.next.expect("Should have a next link");
     assert!(
-        parts.status == StatusCode::OK || parts.status == StatusCode::NOT_FOUND,
-        "Expected record by ID endpoint to be accessible, got: {}",
-        parts.status
+        next.contains(&format!("title={encoded_title}")),
+        "Next link should keep the title filter: {next}"
     );
 
-    if parts.status == StatusCode::NOT_FOUND {
-        println!("Record by ID endpoint correctly returns 404 for non-existent record");
-    } else {
-        println!("Record by ID endpoint is accessible");
-    }
+    let response = request_with_auth(Method::GET, &format!("/cards/records{next}")).await;
+    let (parts, body) = response.into_parts();
+    assert_eq!(parts.status, StatusCode::OK);
+
+    let second: RestApiResponse<PaginatedResponse<RecordDto>> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize second cursor page");
+    let second = second.0.data.expect("Should have data in response");
+    assert_eq!(second.count, -1, "Later keyset pages skip the count");
+    assert_eq!(second.results.len(), 1);
+    assert!(second.results.iter().all(|r| r.title == title));
+    assert!(second.next.is_none());
 }
 
-/// Test record creation with invalid data to verify validation
+/// Test that an unreadable cursor is rejected
 #[tokio::test]
-async fn test_create_record_validation() {
-    // Arrange - Create payload with invalid data
-    let invalid_payload = serde_json::json!({
-        "id": "", // Empty ID should fail validation
-        "title": "",
-        "date": "invalid-date",
-        "duration": -1, // Negative duration should fail
-        "director_id": "invalid",
-        "studio_id": "invalid",
-        "label_id": "invalid",
-        "series_id": "invalid",
+async fn test_get_records_with_invalid_cursor() {
+    let response = request_with_auth(Method::GET, "/cards/records?cursor=not-a-cursor").await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+/// Build a minimal bulk item payload
+fn bulk_record_payload(id: &str) -> serde_json::Value {
+    serde_json::json!({
+        "id": id,
+        "title": "Bulk Test Record",
+        "date": "2025-08-11",
+        "duration": 60,
+        "director": null,
+        "studio": null,
+        "label": null,
+        "series": null,
         "genres": [],
         "idols": [],
         "has_links": false,
         "links": [],
         "permission": 1,
-        "local_img_count": 0,
-        "creator": "",
-        "modified_by": ""
-    });
+        "local_img_count": 0
+    })
+}
+
+/// Test that partial mode commits valid items and reports invalid ones
+#[tokio::test]
+async fn test_bulk_create_records_partial() {
+    let payload = serde_json::json!([
+        bulk_record_payload(&format!("bulk-{}", uuid::Uuid::new_v4())),
+        bulk_record_payload(""),
+        bulk_record_payload(&format!("bulk-{}", uuid::Uuid::new_v4())),
+    ]);
 
-    // Act - Send POST request with invalid data
     let response =
-        request_with_auth_and_body(Method::POST, "/cards/records", &invalid_payload).await;
+        request_with_auth_and_body(Method::POST, "/cards/records/bulk?mode=partial", &payload)
+            .await;
+    let (parts, body) = response.into_parts();
+    assert_eq!(parts.status, StatusCode::OK);
 
-    // Assert - Should get validation error
-    let (parts, _body) = response.into_parts();
+    let response_body: RestApiResponse<BulkCreateResponse> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize bulk response");
+    let data = response_body.0.data.expect("Should have data in response");
+    assert_eq!(data.created, 2);
+    assert_eq!(data.failed, 1);
+    assert!(!data.results[1].success, "Empty ID should fail validation");
+}
 
-    assert!(
-        parts.status == StatusCode::BAD_REQUEST
-            || parts.status == StatusCode::UNPROCESSABLE_ENTITY
-            || parts.status == StatusCode::INTERNAL_SERVER_ERROR,
-        "Expected validation error for invalid record data, got: {}",
-        parts.status
+/// Test that atomic mode persists nothing when one item fails
+#[tokio::test]
+async fn test_bulk_create_records_atomic_rolls_back() {
+    let good_id = format!("bulk-{}", uuid::Uuid::new_v4());
+    let payload = serde_json::json!([bulk_record_payload(&good_id), bulk_record_payload("")]);
+
+    let response = request_with_auth_and_body(Method::POST, "/cards/records/bulk", &payload).await;
+    let (parts, body) = response.into_parts();
+    assert_eq!(parts.status, StatusCode::OK);
+
+    let response_body: RestApiResponse<BulkCreateResponse> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize bulk response");
+    let data = response_body.0.data.expect("Should have data in response");
+    assert_eq!(data.created, 0);
+
+    let response = request_with_auth(Method::GET, &format!("/cards/records/{good_id}")).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+/// Test that bulk delete trashes existing records, reports missing IDs and
+/// only removes rows permanently on `purge`
+#[tokio::test]
+async fn test_bulk_delete_records() {
+    let ids: Vec<String> = (0..2)
+        .map(|_| format!("bulk-del-{}", uuid::Uuid::new_v4()))
+        .collect();
+    let payload = serde_json::json!([bulk_record_payload(&ids[0]), bulk_record_payload(&ids[1])]);
+    let response = request_with_auth_and_body(Method::POST, "/cards/records/bulk", &payload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let missing = format!("bulk-del-missing-{}", uuid::Uuid::new_v4());
+    let delete_payload = serde_json::json!({ "ids": [ids[0], ids[1], missing] });
+    let response =
+        request_with_auth_and_body(Method::DELETE, "/cards/records", &delete_payload).await;
+    let (parts, body) = response.into_parts();
+    assert_eq!(parts.status, StatusCode::OK);
+
+    let response_body: RestApiResponse<BulkDeleteRecordsResponse> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize bulk delete response");
+    let data = response_body.0.data.expect("Should have data in response");
+    assert_eq!(data.records, 2);
+    assert_eq!(data.media_dirs, 0);
+    assert_eq!(data.not_found, vec![missing]);
+
+    let response = request_with_auth(Method::GET, &format!("/cards/records/{}", ids[0])).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let response =
+        request_with_auth(Method::POST, &format!("/cards/records/{}/restore", ids[0])).await;
+    assert_eq!(
+        response.status(),
+        StatusCode::OK,
+        "Bulk delete only trashes"
     );
 
-    println!(
-        "Record validation correctly rejected invalid data with status: {}",
-        parts.status
+    let purge_payload = serde_json::json!({ "ids": [ids[0], ids[1]], "delete_media": true });
+    let response =
+        request_with_auth_and_body(Method::DELETE, "/cards/records", &purge_payload).await;
+    assert_eq!(
+        response.status(),
+        StatusCode::BAD_REQUEST,
+        "Media is only removed with purge"
     );
+
+    let purge_payload = serde_json::json!({ "ids": [ids[0], ids[1]], "purge": true });
+    let response =
+        request_with_auth_and_body(Method::DELETE, "/cards/records", &purge_payload).await;
+    let (parts, body) = response.into_parts();
+    assert_eq!(parts.status, StatusCode::OK);
+    let response_body: RestApiResponse<BulkDeleteRecordsResponse> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize bulk purge response");
+    let data = response_body.0.data.expect("Should have data in response");
+    assert_eq!(data.records, 2);
+    assert!(data.not_found.is_empty());
+    let response =
+        request_with_auth(Method::POST, &format!("/cards/records/{}/restore", ids[1])).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
 }
 
-/// Test well-formatted JSON payload creation
+/// Test that bulk delete rejects an empty ID list
 #[tokio::test]
-async fn test_json_payload_formatting() {
-    // This test demonstrates the improved JSON formatting approach with nested objects
-    let payload = serde_json::json!({
-        "id": format!("test-record-{}", uuid::Uuid::new_v4()),
-        "title": "Well Formatted Test Record",
-        "date": "2025-08-11",
-        "duration": 7200,
-        "director": {
-            "name": "Famous Director",
-            "link": "https://example.com/director",
-            "manual": true
-        },
-        "studio": {
-            "name": "Big Studio",
-            "link": "https://example.com/studio",
-            "manual": true
-        },
-        "label": {
-            "name": "Premium Label",
-            "link": "https://example.com/label",
-            "manual": true
-        },
-        "series": {
-            "name": "Popular Series",
-            "link": "https://example.com/series",
-            "manual": true
-        },
-        "genres": [
-            {
-                "name": "Action",
-                "link": "https://example.com/genre/action",
-                "manual": true
-            },
-            {
-                "name": "Drama",
-                "link": "https://example.com/genre/drama",
-                "manual": false
-            }
-        ],
-        "idols": [
-            {
-                "name": "Popular Idol",
-                "link": "https://example.com/idol",
-                "manual": true
-            }
-        ],
-        "has_links": true,
-        "links": [
-            {
-                "name": "Test Link",
-                "size": "1.5",
-                "date": "2025-08-11",
-                "link": "https://example.com/test",
-                "star": true
-            }
-        ],
-        "permission": 1,
-        "local_img_count": 5,
-        "creator": "test_creator",
-        "modified_by": "test_modifier"
-    });
+async fn test_bulk_delete_records_requires_ids() {
+    let payload = serde_json::json!({ "ids": [] });
+    let response = request_with_auth_and_body(Method::DELETE, "/cards/records", &payload).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
 
-    // Verify the payload can be serialized properly
-    let json_string =
-        serde_json::to_string_pretty(&payload).expect("Should be able to serialize payload");
+/// Test that PATCH only changes the fields present in the body
+#[tokio::test]
+async fn test_patch_record_updates_only_given_fields() {
+    let id = format!("patch-{}", uuid::Uuid::new_v4());
+    let payload = serde_json::json!([bulk_record_payload(&id)]);
+    let response = request_with_auth_and_body(Method::POST, "/cards/records/bulk", &payload).await;
+    assert_eq!(response.status(), StatusCode::OK);
 
-    assert!(!json_string.is_empty(), "JSON string should not be empty");
-    assert!(
-        json_string.contains("Well Formatted Test Record"),
-        "Should contain the title"
+    let patch = serde_json::json!({ "title": "Patched Title" });
+    let url = format!("/cards/records/{id}");
+    let response = request_with_auth_and_body(Method::PATCH, &url, &patch).await;
+    let (parts, body) = response.into_parts();
+    assert_eq!(parts.status, StatusCode::OK);
+
+    let response_body: RestApiResponse<RecordDto> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize patched record");
+    let record = response_body.0.data.expect("Should have data in response");
+    assert_eq!(record.title, "Patched Title");
+    assert_eq!(record.duration, 60, "Omitted fields keep their values");
+    assert_eq!(record.permission, 1, "Omitted fields keep their values");
+}
+
+/// Test that record GETs carry an ETag and honor `If-None-Match` until the record changes
+#[tokio::test]
+async fn test_get_record_etag_not_modified() {
+    let id = format!("etag-{}", uuid::Uuid::new_v4());
+    let payload = serde_json::json!([bulk_record_payload(&id)]);
+    let response = request_with_auth_and_body(Method::POST, "/cards/records/bulk", &payload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let url = format!("/cards/records/{id}");
+    let response = request_with_auth(Method::GET, &url).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let etag = response
+        .headers()
+        .get(ETAG)
+        .and_then(|v| v.to_str().ok())
+        .expect("Response should carry an ETag")
+        .to_owned();
+    assert!(etag.starts_with("W/"), "ETag should be weak: {etag}");
+
+    let response = request_with_auth_and_header(Method::GET, &url, IF_NONE_MATCH, &etag).await;
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+    assert_eq!(
+        response.headers().get(ETAG).and_then(|v| v.to_str().ok()),
+        Some(etag.as_str())
     );
-    assert!(
-        json_string.contains("genres"),
-        "Should contain genres field"
+    let body = response
+        .into_body()
+        .collect()
+        .await
+        .expect("Failed to read body")
+        .to_bytes();
+    assert!(body.is_empty(), "304 responses have no body");
+
+    let patch = serde_json::json!({ "title": "Retitled" });
+    let response = request_with_auth_and_body(Method::PATCH, &url, &patch).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = request_with_auth_and_header(Method::GET, &url, IF_NONE_MATCH, &etag).await;
+    assert_eq!(
+        response.status(),
+        StatusCode::OK,
+        "Changed records are resent"
     );
-    assert!(json_string.contains("idols"), "Should contain idols field");
-    assert!(json_string.contains("links"), "Should contain links field");
-    assert!(
-        json_string.contains("director"),
-        "Should contain director field"
+    assert_ne!(
+        response.headers().get(ETAG).and_then(|v| v.to_str().ok()),
+        Some(etag.as_str())
+    );
+}
+
+/// Test that JSON bodies over the configured limit are rejected with 413
+#[tokio::test]
+async fn test_oversized_json_body_rejected() {
+    let payload = serde_json::json!({ "title": "x".repeat(DEFAULT_JSON_BODY_LIMIT + 1) });
+    let response = request_with_auth_and_body(Method::POST, "/cards/records", &payload).await;
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+}
+
+/// Test that JSON list responses are gzip-compressed when the client accepts it
+#[tokio::test]
+async fn test_get_records_compressed() {
+    let response =
+        request_with_auth_and_header(Method::GET, "/cards/records", ACCEPT_ENCODING, "gzip").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response
+            .headers()
+            .get(CONTENT_ENCODING)
+            .and_then(|v| v.to_str().ok()),
+        Some("gzip")
     );
+
+    let response = request_with_auth(Method::GET, "/cards/records").await;
     assert!(
-        json_string.contains("studio"),
-        "Should contain studio field"
+        response.headers().get(CONTENT_ENCODING).is_none(),
+        "Clients that do not ask for compression get plain JSON"
     );
+}
 
-    println!("Successfully demonstrated well-formatted JSON payload creation");
-    println!("Sample JSON payload:\n{json_string}");
+/// Test that edits based on a stale `If-Match` version are rejected with 412
+#[tokio::test]
+async fn test_patch_record_rejects_stale_version() {
+    let id = format!("version-{}", uuid::Uuid::new_v4());
+    let payload = serde_json::json!([bulk_record_payload(&id)]);
+    let response = request_with_auth_and_body(Method::POST, "/cards/records/bulk", &payload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let url = format!("/cards/records/{id}");
+    let response = request_with_auth(Method::GET, &url).await;
+    let response_body: RestApiResponse<RecordDto> = deserialize_json_body(response.into_body())
+        .await
+        .expect("Failed to deserialize record");
+    let version = response_body
+        .0
+        .data
+        .expect("Should have record data")
+        .version;
+
+    // First editor saves against the version they loaded
+    let patch = serde_json::json!({ "title": "First Editor" });
+    let response = request_with_auth_header_and_body(
+        Method::PATCH,
+        &url,
+        IF_MATCH,
+        &version.to_string(),
+        &patch,
+    )
+    .await;
+    let (parts, body) = response.into_parts();
+    assert_eq!(parts.status, StatusCode::OK);
+    let response_body: RestApiResponse<RecordDto> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize patched record");
+    let record = response_body.0.data.expect("Should have record data");
+    assert_eq!(record.version, version + 1, "Edits bump the version");
+
+    // Second editor still holds the old version
+    let patch = serde_json::json!({ "title": "Second Editor" });
+    let response = request_with_auth_header_and_body(
+        Method::PATCH,
+        &url,
+        IF_MATCH,
+        &format!("\"{version}\""),
+        &patch,
+    )
+    .await;
+    let (parts, body) = response.into_parts();
+    assert_eq!(parts.status, StatusCode::PRECONDITION_FAILED);
+    let response_body: ErrorResponse = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize 412 body");
+    assert_eq!(response_body.code, ErrorCode::VersionMismatch);
+    let details = response_body
+        .details
+        .expect("412 carries the current version");
+    assert_eq!(details["version"], version + 1);
+
+    let response = request_with_auth(Method::GET, &url).await;
+    let response_body: RestApiResponse<RecordDto> = deserialize_json_body(response.into_body())
+        .await
+        .expect("Failed to deserialize record");
+    assert_eq!(
+        response_body.0.data.expect("Should have record data").title,
+        "First Editor",
+        "The stale edit must not overwrite the newer one"
+    );
+}
+
+/// Test that the `ETag` of a record GET can be sent back in `If-Match`
+#[tokio::test]
+async fn test_record_etag_round_trips_through_if_match() {
+    let id = format!("etag-{}", uuid::Uuid::new_v4());
+    let payload = serde_json::json!([bulk_record_payload(&id)]);
+    let response = request_with_auth_and_body(Method::POST, "/cards/records/bulk", &payload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = request_with_auth(Method::GET, &format!("/cards/records/{id}")).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let etag = response.headers()[ETAG]
+        .to_str()
+        .expect("ETag is ASCII")
+        .to_owned();
+
+    let url = format!("/cards/records/{id}/full");
+    let mut replacement = bulk_record_payload(&id);
+    replacement["title"] = serde_json::json!("Round Trip");
+    let response =
+        request_with_auth_header_and_body(Method::PUT, &url, IF_MATCH, &etag, &replacement).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response =
+        request_with_auth_header_and_body(Method::PUT, &url, IF_MATCH, &etag, &replacement).await;
+    assert_eq!(
+        response.status(),
+        StatusCode::PRECONDITION_FAILED,
+        "The tag names the version before the edit"
+    );
+}
+
+/// Test that edits of a trashed record answer 404 without bumping its version
+/// or storing a revision
+#[tokio::test]
+async fn test_edit_trashed_record_leaves_no_trace() {
+    let id = format!("trashed-edit-{}", uuid::Uuid::new_v4());
+    let payload = serde_json::json!([bulk_record_payload(&id)]);
+    let response = request_with_auth_and_body(Method::POST, "/cards/records/bulk", &payload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = request_with_auth(Method::DELETE, &format!("/cards/records/{id}")).await;
+    assert!(response.status().is_success());
+
+    /// Version and revision count of record `id`
+    async fn edit_state(db: &sea_orm::DatabaseConnection, id: &str) -> (i32, u64) {
+        let version = record::Entity::find_by_id(id)
+            .one(db)
+            .await
+            .expect("Failed to load record")
+            .expect("Trashed record is kept")
+            .version;
+        let revisions = record_revisions::Entity::find()
+            .filter(record_revisions::Column::RecordId.eq(id))
+            .count(db)
+            .await
+            .expect("Failed to count revisions");
+        (version, revisions)
+    }
+    let db = setup_test_db().await.expect("Failed to setup test db");
+    let before = edit_state(&db, &id).await;
+
+    let url = format!("/cards/records/{id}");
+    let patch = serde_json::json!({ "title": "Edited In Trash" });
+    let response = request_with_auth_and_body(Method::PATCH, &url, &patch).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let mut replacement = bulk_record_payload(&id);
+    replacement["title"] = serde_json::json!("Replaced In Trash");
+    let response =
+        request_with_auth_and_body(Method::PUT, &format!("{url}/full"), &replacement).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    assert_eq!(edit_state(&db, &id).await, before);
+}
+
+/// Test that edits leave revisions behind and that reverting to one restores
+/// the record and can itself be undone
+#[tokio::test]
+async fn test_record_history_and_revert() {
+    let id = format!("history-{}", uuid::Uuid::new_v4());
+    let payload = serde_json::json!([bulk_record_payload(&id)]);
+    let response = request_with_auth_and_body(Method::POST, "/cards/records/bulk", &payload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let url = format!("/cards/records/{id}");
+    let patch = serde_json::json!({ "title": "Edited Title", "duration": 90 });
+    let response = request_with_auth_and_body(Method::PATCH, &url, &patch).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let history_url = format!("/cards/records/{id}/history");
+    let response = request_with_auth(Method::GET, &history_url).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: RestApiResponse<PaginatedResponse<RecordRevisionDto>> =
+        deserialize_json_body(response.into_body())
+            .await
+            .expect("Failed to deserialize history");
+    let history = body.0.data.expect("Should have history data");
+    assert_eq!(history.count, 1, "One edit leaves one revision");
+    let revision = &history.results[0];
+    assert_eq!(revision.record_id, id);
+    assert!(!revision.actor.is_empty());
+    assert_eq!(revision.snapshot.title, "Bulk Test Record");
+    assert_eq!(revision.snapshot.duration, 60);
+
+    let edited = fetch_record(&id).await;
+    let response = request_with_auth_header_and_body(
+        Method::POST,
+        &format!("/cards/records/{id}/revert/{}", revision.id),
+        IF_MATCH,
+        &edited.version.to_string(),
+        &serde_json::json!({}),
+    )
+    .await;
+    let (parts, body) = response.into_parts();
+    assert_eq!(parts.status, StatusCode::OK);
+    let body: RestApiResponse<RecordDto> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize reverted record");
+    let reverted = body.0.data.expect("Should have record data");
+    assert_eq!(reverted.title, "Bulk Test Record");
+    assert_eq!(reverted.duration, 60);
+    assert_eq!(reverted.version, edited.version + 1, "Reverts are edits");
+
+    let response = request_with_auth(Method::GET, &history_url).await;
+    let body: RestApiResponse<PaginatedResponse<RecordRevisionDto>> =
+        deserialize_json_body(response.into_body())
+            .await
+            .expect("Failed to deserialize history");
+    let history = body.0.data.expect("Should have history data");
+    assert_eq!(history.count, 2);
+    assert_eq!(
+        history.results[0].snapshot.title, "Edited Title",
+        "The revert keeps the state it replaced, newest first"
+    );
+
+    let response = request_with_auth(
+        Method::POST,
+        &format!("/cards/records/{id}/revert/999999999"),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let token = register_viewer_token().await;
+    let response = request_with_token_and_body(
+        Method::POST,
+        &format!("/cards/records/{id}/revert/{}", revision.id),
+        &token,
+        &serde_json::json!({}),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+/// Test that PUT /full replaces the genre set and links of an existing record
+#[tokio::test]
+async fn test_replace_record_full_replaces_relations() {
+    let id = format!("full-{}", uuid::Uuid::new_v4());
+    let mut initial = bulk_record_payload(&id);
+    initial["genres"] =
+        serde_json::json!([{ "name": "full-old-genre", "link": null, "manual": null }]);
+    initial["has_links"] = serde_json::json!(true);
+    initial["links"] = serde_json::json!([
+        { "name": "kept", "size": "1.0", "date": null, "link": "https://example.com/kept", "star": false },
+        { "name": "dropped", "size": "1.0", "date": null, "link": "https://example.com/dropped", "star": false }
+    ]);
+    let payload = serde_json::json!([initial]);
+    let response = request_with_auth_and_body(Method::POST, "/cards/records/bulk", &payload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let mut replacement = bulk_record_payload(&id);
+    replacement["title"] = serde_json::json!("Replaced Title");
+    replacement["genres"] =
+        serde_json::json!([{ "name": "full-new-genre", "link": null, "manual": null }]);
+    replacement["has_links"] = serde_json::json!(true);
+    replacement["links"] = serde_json::json!([
+        { "name": "kept", "size": "2.0", "date": null, "link": "https://example.com/kept", "star": true },
+        { "name": "added", "size": "1.0", "date": null, "link": "https://example.com/added", "star": false }
+    ]);
+    let url = format!("/cards/records/{id}/full");
+    let response = request_with_auth_and_body(Method::PUT, &url, &replacement).await;
+    let (parts, body) = response.into_parts();
+    assert_eq!(parts.status, StatusCode::OK);
+
+    let response_body: RestApiResponse<RecordDto> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize replaced record");
+    let record = response_body.0.data.expect("Should have data in response");
+    assert_eq!(record.title, "Replaced Title");
+    let genres: Vec<&str> = record
+        .genres
+        .iter()
+        .map(|g| g.genre.name.as_str())
+        .collect();
+    assert_eq!(genres, vec!["full-new-genre"]);
+    let mut links: Vec<&str> = record.links.iter().map(|l| l.link.as_str()).collect();
+    links.sort_unstable();
+    assert_eq!(
+        links,
+        vec!["https://example.com/added", "https://example.com/kept"]
+    );
+    let kept = record
+        .links
+        .iter()
+        .find(|l| l.link == "https://example.com/kept")
+        .expect("kept link present");
+    assert!(kept.star, "Kept link is updated in place");
+}
+
+async fn fetch_record(id: &str) -> RecordDto {
+    let response = request_with_auth(Method::GET, &format!("/cards/records/{id}")).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: RestApiResponse<RecordDto> = deserialize_json_body(response.into_body())
+        .await
+        .expect("Failed to deserialize record");
+    body.0.data.expect("Should have record data")
+}
+
+/// Test editing, deleting and bulk replacing links keeps `has_links` in step
+#[tokio::test]
+async fn test_record_link_crud() {
+    let id = format!("links-{}", uuid::Uuid::new_v4());
+    let mut initial = bulk_record_payload(&id);
+    initial["has_links"] = serde_json::json!(false);
+    initial["links"] = serde_json::json!([
+        { "name": "first", "size": "1.0", "date": null, "link": "https://example.com/first", "star": false },
+        { "name": "second", "size": "1.0", "date": null, "link": "https://example.com/second", "star": false }
+    ]);
+    let response = request_with_auth_and_body(
+        Method::POST,
+        "/cards/records/bulk",
+        &serde_json::json!([initial]),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let record = fetch_record(&id).await;
+    assert!(record.has_links, "has_links follows the links sent");
+    let first = record
+        .links
+        .iter()
+        .find(|l| l.link == "https://example.com/first")
+        .expect("first link")
+        .id;
+    let second = record
+        .links
+        .iter()
+        .find(|l| l.link == "https://example.com/second")
+        .expect("second link")
+        .id;
+
+    let url = format!("/cards/records/{id}/links/{first}");
+    let patch = serde_json::json!({ "name": "renamed", "star": true, "link": " https://example.com/moved " });
+    let response = request_with_auth_and_body(Method::PATCH, &url, &patch).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: RestApiResponse<LinkDto> = deserialize_json_body(response.into_body())
+        .await
+        .expect("Failed to deserialize link");
+    let link = body.0.data.expect("Should have link data");
+    assert_eq!(link.name, "renamed");
+    assert!(link.star);
+    assert_eq!(link.link, "https://example.com/moved");
+
+    let taken = serde_json::json!({ "link": "https://example.com/second" });
+    let response = request_with_auth_and_body(Method::PATCH, &url, &taken).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    let response = request_with_auth_and_body(
+        Method::PATCH,
+        &format!("/cards/records/{id}/links/999999999"),
+        &patch,
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    for link_id in [first, second] {
+        let response = request_with_auth(
+            Method::DELETE,
+            &format!("/cards/records/{id}/links/{link_id}"),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+    let record = fetch_record(&id).await;
+    assert!(record.links.is_empty());
+    assert!(!record.has_links, "deleting the last link clears has_links");
+    let response = request_with_auth(Method::DELETE, &url).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let links_url = format!("/cards/records/links/{id}");
+    let response = request_with_auth_and_body(
+        Method::PATCH,
+        &links_url,
+        &serde_json::json!([
+            { "name": "a", "size": "1.0", "date": null, "link": "https://example.com/a", "star": false },
+            { "name": "b", "size": "1.0", "date": null, "link": "https://example.com/b", "star": false }
+        ]),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(fetch_record(&id).await.has_links);
+
+    let response = request_with_auth_and_body(
+        Method::PATCH,
+        &format!("{links_url}?mode=replace"),
+        &serde_json::json!([
+            { "name": "b", "size": "2.0", "date": null, "link": "https://example.com/b", "star": true },
+            { "name": "c", "size": "1.0", "date": null, "link": "https://example.com/c", "star": false }
+        ]),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: RestApiResponse<LinkUpdateResultDto> = deserialize_json_body(response.into_body())
+        .await
+        .expect("Failed to deserialize link update result");
+    let result = body.0.data.expect("Should have link update result");
+    assert_eq!(result.inserted, ["https://example.com/c"]);
+    assert_eq!(result.updated, ["https://example.com/b"]);
+    assert_eq!(result.removed, ["https://example.com/a"]);
+    assert!(result.skipped.is_empty());
+    let record = fetch_record(&id).await;
+    let mut links: Vec<&str> = record.links.iter().map(|l| l.link.as_str()).collect();
+    links.sort_unstable();
+    assert_eq!(links, ["https://example.com/b", "https://example.com/c"]);
+
+    let response = request_with_auth_and_body(
+        Method::PATCH,
+        &format!("{links_url}?mode=replace"),
+        &serde_json::json!([]),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let record = fetch_record(&id).await;
+    assert!(record.links.is_empty());
+    assert!(!record.has_links);
+}
+
+/// Test that appended links are normalized and duplicates by URL or by name
+/// and size are skipped and reported
+#[tokio::test]
+async fn test_update_record_links_dedupes() {
+    let id = format!("dedupe-{}", uuid::Uuid::new_v4());
+    let mut initial = bulk_record_payload(&id);
+    initial["links"] = serde_json::json!([
+        { "name": "Disc One", "size": "1.5", "date": null, "link": "https://example.com/one", "star": false }
+    ]);
+    let response = request_with_auth_and_body(
+        Method::POST,
+        "/cards/records/bulk",
+        &serde_json::json!([initial]),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = request_with_auth_and_body(
+        Method::PATCH,
+        &format!("/cards/records/links/{id}"),
+        &serde_json::json!([
+            { "name": "copy", "size": "2.0", "date": null, "link": "HTTPS://EXAMPLE.COM/ONE?utm_source=feed", "star": false },
+            { "name": "disc one", "size": "1.5", "date": null, "link": "https://mirror.example.com/one", "star": false },
+            { "name": "two", "size": "2.0", "date": null, "link": " https://example.com/two?id=2&fbclid=abc ", "star": false },
+            { "name": "two again", "size": "3.0", "date": null, "link": "https://example.com/two?id=2", "star": false },
+            { "name": "blank", "size": "1.0", "date": null, "link": "   ", "star": false }
+        ]),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: RestApiResponse<LinkUpdateResultDto> = deserialize_json_body(response.into_body())
+        .await
+        .expect("Failed to deserialize link update result");
+    let result = body.0.data.expect("Should have link update result");
+    assert_eq!(result.inserted, ["https://example.com/two?id=2"]);
+    assert!(result.updated.is_empty() && result.removed.is_empty());
+    // Repeats within the request are reported before those of stored links
+    let skipped: Vec<(&str, LinkSkipReason)> = result
+        .skipped
+        .iter()
+        .map(|s| (s.link.as_str(), s.reason))
+        .collect();
+    assert_eq!(
+        skipped,
+        [
+            ("https://example.com/two?id=2", LinkSkipReason::DuplicateUrl),
+            ("   ", LinkSkipReason::Blank),
+            (
+                "HTTPS://EXAMPLE.COM/ONE?utm_source=feed",
+                LinkSkipReason::DuplicateUrl
+            ),
+            (
+                "https://mirror.example.com/one",
+                LinkSkipReason::DuplicateNameSize
+            ),
+        ]
+    );
+
+    let record = fetch_record(&id).await;
+    let mut links: Vec<&str> = record.links.iter().map(|l| l.link.as_str()).collect();
+    links.sort_unstable();
+    assert_eq!(
+        links,
+        ["https://example.com/one", "https://example.com/two?id=2"]
+    );
+}
+
+/// Test that records list links starred first, then by priority and size
+#[tokio::test]
+async fn test_record_links_ranked() {
+    let id = format!("ranked-{}", uuid::Uuid::new_v4());
+    let mut initial = bulk_record_payload(&id);
+    initial["links"] = serde_json::json!([
+        { "name": "small", "size": "1.0", "date": null, "link": "https://example.com/small", "star": false },
+        { "name": "large", "size": "3.0", "date": null, "link": "https://example.com/large", "star": false },
+        { "name": "medium", "size": "2.0", "date": null, "link": "https://example.com/medium", "star": false }
+    ]);
+    let response = request_with_auth_and_body(
+        Method::POST,
+        "/cards/records/bulk",
+        &serde_json::json!([initial]),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let names = |record: &RecordDto| -> Vec<String> {
+        record.links.iter().map(|l| l.name.clone()).collect()
+    };
+    let record = fetch_record(&id).await;
+    assert_eq!(names(&record), ["large", "medium", "small"]);
+    let link_id = |name: &str| {
+        record
+            .links
+            .iter()
+            .find(|l| l.name == name)
+            .expect("link present")
+            .id
+    };
+
+    let star_url = format!("/cards/records/{id}/links/{}/star", link_id("small"));
+    let response = request_with_auth_and_body(
+        Method::POST,
+        &star_url,
+        &serde_json::json!({ "star": true }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: RestApiResponse<LinkDto> = deserialize_json_body(response.into_body())
+        .await
+        .expect("Failed to deserialize link");
+    assert!(body.0.data.expect("Should have link data").star);
+    let response = request_with_auth_and_body(
+        Method::PATCH,
+        &format!("/cards/records/{id}/links/{}", link_id("medium")),
+        &serde_json::json!({ "priority": 5 }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        names(&fetch_record(&id).await),
+        ["small", "medium", "large"]
+    );
+
+    let response = request_with_auth_and_body(
+        Method::POST,
+        &star_url,
+        &serde_json::json!({ "star": false }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        names(&fetch_record(&id).await),
+        ["medium", "large", "small"]
+    );
+
+    let token = register_viewer_token().await;
+    let response = request_with_token_and_body(
+        Method::POST,
+        &star_url,
+        &token,
+        &serde_json::json!({ "star": true }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+/// Test that link search filters across records and hides records above the
+/// caller's permission level
+#[tokio::test]
+async fn test_search_links() {
+    let id = format!("search-links-{}", uuid::Uuid::new_v4());
+    let mut public = bulk_record_payload(&format!("{id}-public"));
+    public["links"] = serde_json::json!([
+        { "name": format!("{id}-a"), "size": "1.0", "date": "2024-01-01", "link": "https://example.com/a", "star": false },
+        { "name": format!("{id}-b"), "size": "5.0", "date": "2025-06-01", "link": "https://example.com/b", "star": true }
+    ]);
+    let mut hidden = bulk_record_payload(&format!("{id}-hidden"));
+    hidden["permission"] = serde_json::json!(2);
+    hidden["links"] = serde_json::json!([
+        { "name": format!("{id}-c"), "size": "9.0", "date": "2025-02-01", "link": "https://example.com/c", "star": false }
+    ]);
+    let response = request_with_auth_and_body(
+        Method::POST,
+        "/cards/records/bulk",
+        &serde_json::json!([public, hidden]),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let names = |links: PaginatedResponse<LinkDto>| -> Vec<String> {
+        let mut names: Vec<String> = links.results.into_iter().map(|l| l.name).collect();
+        names.sort();
+        names
+    };
+    let search = |filters: &'static str| {
+        let uri = format!("/cards/links?name={id}{filters}");
+        async move {
+            let response = request_with_auth(Method::GET, &uri).await;
+            assert_eq!(response.status(), StatusCode::OK, "{uri}");
+            let body: RestApiResponse<PaginatedResponse<LinkDto>> =
+                deserialize_json_body(response.into_body())
+                    .await
+                    .expect("Failed to deserialize links");
+            body.0.data.expect("Should have links")
+        }
+    };
+    let all = search("").await;
+    assert_eq!(all.count, 3);
+    assert_eq!(
+        names(all),
+        [format!("{id}-a"), format!("{id}-b"), format!("{id}-c")]
+    );
+    assert_eq!(
+        names(search("&min_size=2").await),
+        [format!("{id}-b"), format!("{id}-c")]
+    );
+    assert_eq!(names(search("&star=true").await), [format!("{id}-b")]);
+    assert_eq!(
+        names(search("&date_from=2025-01-01&min_size=6").await),
+        [format!("{id}-c")]
+    );
+    let page = search("&limit=2").await;
+    assert_eq!(page.results.len(), 2);
+    assert!(page.next.is_some());
+
+    let token = register_viewer_token().await;
+    let response = request_with_token_and_body(
+        Method::GET,
+        &format!("/cards/links?name={id}"),
+        &token,
+        &serde_json::json!({}),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: RestApiResponse<PaginatedResponse<LinkDto>> =
+        deserialize_json_body(response.into_body())
+            .await
+            .expect("Failed to deserialize links");
+    assert_eq!(
+        names(body.0.data.expect("Should have links")),
+        [format!("{id}-a"), format!("{id}-b")]
+    );
+
+    let response = request_with_auth(Method::GET, "/cards/links?min_size=big").await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+/// Test that PUT /full keeps manual genres and idols unless told to override them
+#[tokio::test]
+async fn test_replace_record_full_keeps_manual_associations() {
+    let id = format!("manual-{}", uuid::Uuid::new_v4());
+    let curated = format!("{id}-curated");
+    let mut initial = bulk_record_payload(&id);
+    initial["genres"] = serde_json::json!([
+        { "name": curated, "link": null, "manual": null },
+        { "name": format!("{id}-scraped"), "link": null, "manual": null }
+    ]);
+    initial["idols"] = serde_json::json!([{ "name": curated, "link": null, "manual": null }]);
+    let payload = serde_json::json!([initial]);
+    let response = request_with_auth_and_body(Method::POST, "/cards/records/bulk", &payload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // Curators marked the idol and one genre by hand
+    let db = setup_test_db().await.expect("Failed to setup test db");
+    let curated_genre = genre::Entity::find()
+        .filter(genre::Column::Name.eq(&curated))
+        .one(&db)
+        .await
+        .expect("Failed to load genre")
+        .expect("curated genre");
+    record_genre::Entity::update_many()
+        .col_expr(record_genre::Column::Manual, Expr::value(true))
+        .filter(record_genre::Column::RecordId.eq(&id))
+        .filter(record_genre::Column::GenreId.eq(curated_genre.id))
+        .exec(&db)
+        .await
+        .expect("Failed to mark genre manual");
+    idol_participation::Entity::update_many()
+        .col_expr(idol_participation::Column::Manual, Expr::value(true))
+        .filter(idol_participation::Column::RecordId.eq(&id))
+        .exec(&db)
+        .await
+        .expect("Failed to mark idol manual");
+
+    let url = format!("/cards/records/{id}/full");
+    let response = request_with_auth_and_body(Method::PUT, &url, &bulk_record_payload(&id)).await;
+    let (parts, body) = response.into_parts();
+    assert_eq!(parts.status, StatusCode::OK);
+    let response_body: RestApiResponse<RecordDto> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize replaced record");
+    let record = response_body.0.data.expect("Should have data in response");
+    let genres: Vec<(&str, bool)> = record
+        .genres
+        .iter()
+        .map(|g| (g.genre.name.as_str(), g.manual))
+        .collect();
+    assert_eq!(genres, vec![(curated.as_str(), true)]);
+    let idols: Vec<(&str, bool)> = record
+        .idols
+        .iter()
+        .map(|i| (i.idol.name.as_str(), i.manual))
+        .collect();
+    assert_eq!(idols, vec![(curated.as_str(), true)]);
+
+    let url = format!("/cards/records/{id}/full?override_manual=true");
+    let response = request_with_auth_and_body(Method::PUT, &url, &bulk_record_payload(&id)).await;
+    let (parts, body) = response.into_parts();
+    assert_eq!(parts.status, StatusCode::OK);
+    let response_body: RestApiResponse<RecordDto> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize replaced record");
+    let record = response_body.0.data.expect("Should have data in response");
+    assert!(record.genres.is_empty());
+    assert!(record.idols.iter().all(|i| !i.manual && i.idol.id == 0));
+}
+
+/// Test that PUT /full rejects a body whose id differs from the path
+#[tokio::test]
+async fn test_replace_record_full_id_mismatch() {
+    let payload = bulk_record_payload("full-body-id");
+    let response =
+        request_with_auth_and_body(Method::PUT, "/cards/records/full-path-id/full", &payload).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+/// Test HEAD and batch existence checks for records
+#[tokio::test]
+async fn test_record_exists_endpoints() {
+    let id = format!("exists-{}", uuid::Uuid::new_v4());
+    let missing = format!("missing-{}", uuid::Uuid::new_v4());
+    let payload = serde_json::json!([bulk_record_payload(&id)]);
+    let response = request_with_auth_and_body(Method::POST, "/cards/records/bulk", &payload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = request_with_auth(Method::HEAD, &format!("/cards/records/{id}")).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = request_with_auth(Method::HEAD, &format!("/cards/records/{missing}")).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let body = serde_json::json!({ "ids": [missing, id] });
+    let response = request_with_auth_and_body(Method::POST, "/cards/records/exists", &body).await;
+    let (parts, body) = response.into_parts();
+    assert_eq!(parts.status, StatusCode::OK);
+    let response_body: RestApiResponse<RecordExistsResponse> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize exists response");
+    let data = response_body.0.data.expect("Should have data in response");
+    assert_eq!(data.existing, vec![id]);
+}
+
+/// Test that deleting a referenced director conflicts unless records are reassigned
+#[tokio::test]
+async fn test_delete_referenced_director_conflicts() {
+    let id = format!("ri-{}", uuid::Uuid::new_v4());
+    let mut payload = bulk_record_payload(&id);
+    payload["director"] = serde_json::json!({
+        "name": format!("RI Director {}", uuid::Uuid::new_v4().simple()),
+        "link": null,
+        "manual": null
+    });
+    let response = request_with_auth_and_body(
+        Method::POST,
+        "/cards/records/bulk",
+        &serde_json::json!([payload]),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = request_with_auth(Method::GET, &format!("/cards/records/{id}")).await;
+    let (_parts, body) = response.into_parts();
+    let record: RestApiResponse<RecordDto> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize record");
+    let director_id = record.0.data.expect("Should have data").director.id;
+
+    let url = format!("/cards/directors/{director_id}");
+    let response = request_with_auth(Method::DELETE, &url).await;
+    let (parts, body) = response.into_parts();
+    assert_eq!(parts.status, StatusCode::CONFLICT);
+    let conflict: ErrorResponse = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize conflict response");
+    assert_eq!(conflict.code, ErrorCode::StillReferenced);
+    assert_eq!(conflict.details, Some(serde_json::json!([id])));
+
+    let response = request_with_auth(Method::DELETE, &format!("{url}?reassign_to=0")).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = request_with_auth(Method::GET, &format!("/cards/records/{id}")).await;
+    let (_parts, body) = response.into_parts();
+    let record: RestApiResponse<RecordDto> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize record");
+    assert_eq!(record.0.data.expect("Should have data").director.id, 0);
+}
+
+/// Test the trash lifecycle: soft delete, restore, then purge
+#[tokio::test]
+async fn test_record_trash_restore_and_purge() {
+    let id = format!("trash-{}", uuid::Uuid::new_v4());
+    let payload = serde_json::json!([bulk_record_payload(&id)]);
+    let response = request_with_auth_and_body(Method::POST, "/cards/records/bulk", &payload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let url = format!("/cards/records/{id}");
+
+    let response = request_with_auth(Method::DELETE, &url).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = request_with_auth(Method::GET, &url).await;
+    assert_eq!(
+        response.status(),
+        StatusCode::NOT_FOUND,
+        "Trashed records are hidden"
+    );
+
+    let response = request_with_auth(Method::GET, "/cards/records/trash?limit=100").await;
+    let (parts, body) = response.into_parts();
+    assert_eq!(parts.status, StatusCode::OK);
+    let trash: RestApiResponse<PaginatedResponse<RecordDto>> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize trash");
+    let trash = trash.0.data.expect("Should have data in response");
+    assert!(trash.results.iter().any(|r| r.id == id));
+
+    let response = request_with_auth(Method::POST, &format!("{url}/restore")).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = request_with_auth(Method::GET, &url).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = request_with_auth(Method::DELETE, &format!("{url}/purge")).await;
+    assert_eq!(
+        response.status(),
+        StatusCode::NOT_FOUND,
+        "Only trashed records can be purged"
+    );
+    let response = request_with_auth(Method::DELETE, &url).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = request_with_auth(Method::DELETE, &format!("{url}/purge")).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = request_with_auth(Method::POST, &format!("{url}/restore")).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+/// Test merging a duplicate record: links are unioned by URL and the
+/// duplicate is deleted
+#[tokio::test]
+async fn test_merge_records() {
+    let target_id = format!("merge-{}", uuid::Uuid::new_v4());
+    let source_id = format!("merge-{}", uuid::Uuid::new_v4());
+    let link = |url: &str| serde_json::json!({ "name": "part", "size": null, "link": url });
+    let mut target = bulk_record_payload(&target_id);
+    target["links"] = serde_json::json!([
+        link("https://example.com/shared"),
+        link("https://example.com/target")
+    ]);
+    let mut source = bulk_record_payload(&source_id);
+    source["links"] = serde_json::json!([
+        link("https://example.com/shared"),
+        link("https://example.com/source")
+    ]);
+    let payload = serde_json::json!([target, source]);
+    let response = request_with_auth_and_body(Method::POST, "/cards/records/bulk", &payload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let url = format!("/cards/records/{target_id}/merge");
+
+    let response = request_with_auth_and_body(
+        Method::POST,
+        &url,
+        &serde_json::json!({ "source_id": target_id }),
+    )
+    .await;
+    assert_eq!(
+        response.status(),
+        StatusCode::BAD_REQUEST,
+        "A record cannot absorb itself"
+    );
+
+    let response = request_with_auth_and_body(
+        Method::POST,
+        &url,
+        &serde_json::json!({ "source_id": source_id }),
+    )
+    .await;
+    let (parts, body) = response.into_parts();
+    assert_eq!(parts.status, StatusCode::OK);
+    let merged: RestApiResponse<RecordDto> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize merged record");
+    let merged = merged.0.data.expect("Should have data in response");
+    assert_eq!(merged.id, target_id);
+    let mut urls: Vec<&str> = merged.links.iter().map(|l| l.link.as_str()).collect();
+    urls.sort_unstable();
+    assert_eq!(
+        urls,
+        [
+            "https://example.com/shared",
+            "https://example.com/source",
+            "https://example.com/target"
+        ]
+    );
+
+    let response = request_with_auth(Method::GET, &format!("/cards/records/{source_id}")).await;
+    assert_eq!(
+        response.status(),
+        StatusCode::NOT_FOUND,
+        "The duplicate is deleted"
+    );
+    let response = request_with_auth_and_body(
+        Method::POST,
+        &url,
+        &serde_json::json!({ "source_id": source_id }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+/// Test getting a specific record by ID - this will test the endpoint structure
+#[tokio::test]
+async fn test_get_record_by_id_endpoint() {
+    // Arrange - Use a test ID
+    let test_id = "test-record-endpoint-check";
+
+    // Act - Try to get record by ID
+    let url = format!("/cards/records/{test_id}");
+    let response = request_with_auth(Method::GET, &url).await;
+
+    // Assert - Verify the endpoint is accessible
+    let (parts, _body) = response.into_parts();
+
+    // The endpoint should be accessible, even if the record doesn't exist
+    assert!(
+        parts.status == StatusCode::OK || parts.status == StatusCode::NOT_FOUND,
+        "Expected record by ID endpoint to be accessible, got: {}",
+        parts.status
+    );
+
+    if parts.status == StatusCode::NOT_FOUND {
+        println!("Record by ID endpoint correctly returns 404 for non-existent record");
+    } else {
+        println!("Record by ID endpoint is accessible");
+    }
+}
+
+/// Test record creation with invalid data to verify validation
+#[tokio::test]
+async fn test_create_record_validation() {
+    // Arrange - Create payload with invalid data
+    let invalid_payload = serde_json::json!({
+        "id": "", // Empty ID should fail validation
+        "title": "",
+        "date": "invalid-date",
+        "duration": -1, // Negative duration should fail
+        "director_id": "invalid",
+        "studio_id": "invalid",
+        "label_id": "invalid",
+        "series_id": "invalid",
+        "genres": [],
+        "idols": [],
+        "has_links": false,
+        "links": [],
+        "permission": 1,
+        "local_img_count": 0
+    });
+
+    // Act - Send POST request with invalid data
+    let response =
+        request_with_auth_and_body(Method::POST, "/cards/records", &invalid_payload).await;
+
+    // Assert - Should get validation error
+    let (parts, _body) = response.into_parts();
+
+    assert!(
+        parts.status == StatusCode::BAD_REQUEST
+            || parts.status == StatusCode::UNPROCESSABLE_ENTITY
+            || parts.status == StatusCode::INTERNAL_SERVER_ERROR,
+        "Expected validation error for invalid record data, got: {}",
+        parts.status
+    );
+
+    println!(
+        "Record validation correctly rejected invalid data with status: {}",
+        parts.status
+    );
+}
+
+/// Test that IDs which cannot name a media directory are rejected, so
+/// deleting their media cannot reach another record's files
+#[tokio::test]
+async fn test_create_record_rejects_path_ids() {
+    for id in [".", "..", "a/b", "a\\b"] {
+        let payload = bulk_record_payload(id);
+        let response = request_with_auth_and_body(Method::POST, "/cards/records", &payload).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{id:?}");
+    }
+}
+
+/// Test well-formatted JSON payload creation
+#[tokio::test]
+async fn test_json_payload_formatting() {
+    // This test demonstrates the improved JSON formatting approach with nested objects
+    let payload = serde_json::json!({
+        "id": format!("test-record-{}", uuid::Uuid::new_v4()),
+        "title": "Well Formatted Test Record",
+        "date": "2025-08-11",
+        "duration": 7200,
+        "director": {
+            "name": "Famous Director",
+            "link": "https://example.com/director",
+            "manual": true
+        },
+        "studio": {
+            "name": "Big Studio",
+            "link": "https://example.com/studio",
+            "manual": true
+        },
+        "label": {
+            "name": "Premium Label",
+            "link": "https://example.com/label",
+            "manual": true
+        },
+        "series": {
+            "name": "Popular Series",
+            "link": "https://example.com/series",
+            "manual": true
+        },
+        "genres": [
+            {
+                "name": "Action",
+                "link": "https://example.com/genre/action",
+                "manual": true
+            },
+            {
+                "name": "Drama",
+                "link": "https://example.com/genre/drama",
+                "manual": false
+            }
+        ],
+        "idols": [
+            {
+                "name": "Popular Idol",
+                "link": "https://example.com/idol",
+                "manual": true
+            }
+        ],
+        "has_links": true,
+        "links": [
+            {
+                "name": "Test Link",
+                "size": "1.5",
+                "date": "2025-08-11",
+                "link": "https://example.com/test",
+                "star": true
+            }
+        ],
+        "permission": 1,
+        "local_img_count": 5
+    });
+
+    // Verify the payload can be serialized properly
+    let json_string =
+        serde_json::to_string_pretty(&payload).expect("Should be able to serialize payload");
+
+    assert!(!json_string.is_empty(), "JSON string should not be empty");
+    assert!(
+        json_string.contains("Well Formatted Test Record"),
+        "Should contain the title"
+    );
+    assert!(
+        json_string.contains("genres"),
+        "Should contain genres field"
+    );
+    assert!(json_string.contains("idols"), "Should contain idols field");
+    assert!(json_string.contains("links"), "Should contain links field");
+    assert!(
+        json_string.contains("director"),
+        "Should contain director field"
+    );
+    assert!(
+        json_string.contains("studio"),
+        "Should contain studio field"
+    );
+
+    println!("Successfully demonstrated well-formatted JSON payload creation");
+    println!("Sample JSON payload:\n{json_string}");
+}
+
+/// Test that `creator`/`modified_by` come from the caller's token, not the body
+#[tokio::test]
+async fn test_record_creator_from_claims() {
+    let mut payload = bulk_record_payload(&format!("test-claims-{}", uuid::Uuid::new_v4()));
+    payload["creator"] = serde_json::json!("spoofed");
+    payload["modified_by"] = serde_json::json!("spoofed");
+
+    let response = request_with_auth_and_body(Method::POST, "/cards/records", &payload).await;
+    let (parts, body) = response.into_parts();
+    assert_eq!(parts.status, StatusCode::OK);
+    let created: RestApiResponse<RecordDto> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize created record");
+    let record = created.0.data.expect("No created record data");
+
+    let response = request_with_auth(Method::GET, "/user/me").await;
+    let me: RestApiResponse<UserDto> = deserialize_json_body(response.into_body())
+        .await
+        .expect("Failed to deserialize current user");
+    let me = me.0.data.expect("No current user data");
+
+    assert_eq!(record.creator, me.id);
+    assert_eq!(record.modified_by, me.id);
+}
+
+/// Test that an admin-only record is readable by an admin but hidden from a viewer
+#[tokio::test]
+async fn test_admin_only_record_forbidden_for_viewer() {
+    let record_id = format!("test-perm-{}", uuid::Uuid::new_v4());
+    let mut payload = bulk_record_payload(&record_id);
+    payload["permission"] = serde_json::json!(2);
+
+    let response = request_with_auth_and_body(Method::POST, "/cards/records", &payload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let url = format!("/cards/records/{record_id}");
+    let response = request_with_auth(Method::GET, &url).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let token = register_viewer_token().await;
+    let empty = serde_json::json!({});
+    let response = request_with_token_and_body(Method::GET, &url, &token, &empty).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+/// Test that existence checks and the trash do not reveal records above the caller's level
+#[tokio::test]
+async fn test_admin_only_record_hidden_from_existence_checks() {
+    let record_id = format!("test-perm-{}", uuid::Uuid::new_v4());
+    let mut payload = bulk_record_payload(&record_id);
+    payload["permission"] = serde_json::json!(2);
+    let response = request_with_auth_and_body(Method::POST, "/cards/records", &payload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let url = format!("/cards/records/{record_id}");
+    let token = register_viewer_token().await;
+    let empty = serde_json::json!({});
+    let response = request_with_token_and_body(Method::HEAD, &url, &token, &empty).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let ids = serde_json::json!({ "ids": [record_id] });
+    let response =
+        request_with_token_and_body(Method::POST, "/cards/records/exists", &token, &ids).await;
+    let (parts, body) = response.into_parts();
+    assert_eq!(parts.status, StatusCode::OK);
+    let exists: RestApiResponse<RecordExistsResponse> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize exists response");
+    assert!(exists.0.data.expect("Should have data").existing.is_empty());
+
+    let response = request_with_auth(Method::DELETE, &url).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response =
+        request_with_token_and_body(Method::GET, "/cards/records/trash", &token, &empty).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let response = request_with_auth(Method::GET, "/cards/records/trash?limit=100").await;
+    let (parts, body) = response.into_parts();
+    assert_eq!(parts.status, StatusCode::OK);
+    let trash: RestApiResponse<PaginatedResponse<RecordDto>> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize trash");
+    let trash = trash.0.data.expect("Should have data in response");
+    assert!(trash.results.iter().any(|r| r.id == record_id));
+}
+
+/// Test that random records honour the genre filter and the caller's permission
+#[tokio::test]
+async fn test_random_records() {
+    let marker = uuid::Uuid::new_v4();
+    let genre = serde_json::json!({
+        "name": format!("Random Genre {marker}"),
+        "link": format!("https://example.com/genre/{marker}"),
+        "manual": true
+    });
+    let mut genre_id = None;
+    for permission in [1, 2] {
+        let mut payload = bulk_record_payload(&format!("test-random-{permission}-{marker}"));
+        payload["permission"] = serde_json::json!(permission);
+        payload["genres"] = serde_json::json!([genre.clone()]);
+        let response = request_with_auth_and_body(Method::POST, "/cards/records", &payload).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let (_parts, body) = response.into_parts();
+        let created: RestApiResponse<RecordDto> = deserialize_json_body(body)
+            .await
+            .expect("Failed to deserialize created record");
+        let created = created.0.data.expect("No created record data");
+        genre_id = created.genres.first().map(|g| g.genre.id);
+    }
+    let genre_id = genre_id.expect("Records should carry the genre");
+
+    let url = format!("/cards/records/random?count=10&genre_id={genre_id}");
+    let response = request_with_auth(Method::GET, &url).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let (_parts, body) = response.into_parts();
+    let picked: RestApiResponse<Vec<RecordDto>> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize random records");
+    let picked = picked.0.data.expect("Should have data in response");
+    assert_eq!(picked.len(), 2, "Admin sees both records of the genre");
+
+    let token = register_viewer_token().await;
+    let empty = serde_json::json!({});
+    let response = request_with_token_and_body(Method::GET, &url, &token, &empty).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let (_parts, body) = response.into_parts();
+    let picked: RestApiResponse<Vec<RecordDto>> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize random records");
+    let picked = picked.0.data.expect("Should have data in response");
+    assert_eq!(picked.len(), 1, "Viewer only sees the public record");
+    assert_eq!(picked[0].permission, 1);
+
+    let response = request_with_auth(Method::GET, "/cards/records/random?count=0").await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+/// Test that similar records are ranked by the weights of what they share
+#[tokio::test]
+async fn test_similar_records() {
+    let marker = uuid::Uuid::new_v4();
+    let named = |kind: &str| {
+        serde_json::json!({
+            "name": format!("Similar {kind} {marker}"),
+            "link": format!("https://example.com/{kind}/{marker}"),
+            "manual": true
+        })
+    };
+    let target_id = format!("test-similar-target-{marker}");
+    let idol_match_id = format!("test-similar-idol-{marker}");
+    let genre_match_id = format!("test-similar-genre-{marker}");
+    for (id, idols, genres) in [
+        (&target_id, vec![named("idol")], vec![named("genre")]),
+        (&idol_match_id, vec![named("idol")], vec![]),
+        (&genre_match_id, vec![], vec![named("genre")]),
+    ] {
+        let mut payload = bulk_record_payload(id);
+        payload["idols"] = serde_json::json!(idols);
+        payload["genres"] = serde_json::json!(genres);
+        let response = request_with_auth_and_body(Method::POST, "/cards/records", &payload).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    let response =
+        request_with_auth(Method::GET, &format!("/cards/records/{target_id}/similar")).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let (_parts, body) = response.into_parts();
+    let similar: RestApiResponse<Vec<SimilarRecordDto>> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize similar records");
+    let similar = similar.0.data.expect("Should have data in response");
+
+    let ids: Vec<&str> = similar.iter().map(|s| s.record.id.as_str()).collect();
+    assert_eq!(ids, [idol_match_id.as_str(), genre_match_id.as_str()]);
+    assert!(similar[0].score > similar[1].score);
+
+    let response = request_with_auth(Method::GET, "/cards/records/no-such-record/similar").await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+/// Test favoriting, listing and unfavoriting records
+#[tokio::test]
+async fn test_favorite_records() {
+    let marker = uuid::Uuid::new_v4();
+    let favorite_id = format!("test-favorite-{marker}");
+    let other_id = format!("test-favorite-other-{marker}");
+    for id in [&favorite_id, &other_id] {
+        let payload = bulk_record_payload(id);
+        let response = request_with_auth_and_body(Method::POST, "/cards/records", &payload).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    let token = register_viewer_token().await;
+    let empty = serde_json::json!({});
+    let favorite_url = format!("/cards/records/user/{favorite_id}/favorite");
+    let response = request_with_token_and_body(Method::PUT, &favorite_url, &token, &empty).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    // Favoriting twice is a no-op
+    let response = request_with_token_and_body(Method::PUT, &favorite_url, &token, &empty).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = request_with_token_and_body(
+        Method::GET,
+        &format!("/cards/records/{favorite_id}"),
+        &token,
+        &empty,
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let (_parts, body) = response.into_parts();
+    let record: RestApiResponse<RecordDto> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize record");
+    assert!(record.0.data.expect("No record data").is_favorite);
+
+    let response =
+        request_with_token_and_body(Method::GET, "/cards/records/user/favorites", &token, &empty)
+            .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let (_parts, body) = response.into_parts();
+    let favorites: RestApiResponse<PaginatedResponse<RecordDto>> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize favorites");
+    let favorites = favorites.0.data.expect("Should have data in response");
+    let ids: Vec<&str> = favorites.results.iter().map(|r| r.id.as_str()).collect();
+    assert_eq!(ids, [favorite_id.as_str()]);
+    assert!(favorites.results[0].is_favorite);
+
+    let response = request_with_token_and_body(Method::DELETE, &favorite_url, &token, &empty).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response =
+        request_with_token_and_body(Method::GET, "/cards/records/user/favorites", &token, &empty)
+            .await;
+    let (_parts, body) = response.into_parts();
+    let favorites: RestApiResponse<PaginatedResponse<RecordDto>> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize favorites");
+    assert_eq!(favorites.0.data.expect("Should have data").count, 0);
+
+    let response = request_with_token_and_body(
+        Method::PUT,
+        "/cards/records/user/no-such-record/favorite",
+        &token,
+        &empty,
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+/// Test rating records, the rating aggregates and rating filter and ordering
+#[tokio::test]
+async fn test_record_ratings() {
+    let marker = uuid::Uuid::new_v4();
+    let high_id = format!("test-rating-high-{marker}");
+    let low_id = format!("test-rating-low-{marker}");
+    for id in [&high_id, &low_id] {
+        let payload = bulk_record_payload(id);
+        let response = request_with_auth_and_body(Method::POST, "/cards/records", &payload).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    let token = register_viewer_token().await;
+    for (id, admin_score, viewer_score) in [(&high_id, 8, Some(4)), (&low_id, 3, None)] {
+        let url = format!("/cards/records/user/{id}/rating");
+        let score = serde_json::json!({ "score": admin_score });
+        let response = request_with_auth_and_body(Method::PUT, &url, &score).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        if let Some(viewer_score) = viewer_score {
+            let score = serde_json::json!({ "score": viewer_score });
+            let response = request_with_token_and_body(Method::PUT, &url, &token, &score).await;
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+    }
+
+    let response = request_with_auth(Method::GET, &format!("/cards/records/{high_id}")).await;
+    let (_parts, body) = response.into_parts();
+    let record: RestApiResponse<RecordDto> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize record");
+    let record = record.0.data.expect("No record data");
+    assert_eq!(record.rating_average, Some(6.0));
+    assert_eq!(record.rating_count, 2);
+
+    let list_ids = |url: String| async move {
+        let response = request_with_auth(Method::GET, &url).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let (_parts, body) = response.into_parts();
+        let page: RestApiResponse<PaginatedResponse<RecordDto>> = deserialize_json_body(body)
+            .await
+            .expect("Failed to deserialize records");
+        let page = page.0.data.expect("Should have data in response");
+        page.results.into_iter().map(|r| r.id).collect::<Vec<_>>()
+    };
+    let ids = list_ids(format!("/cards/records?id={marker}&ordering=rating")).await;
+    assert_eq!(ids, [low_id.clone(), high_id.clone()]);
+    let ids = list_ids(format!("/cards/records?id={marker}&min_rating=5")).await;
+    assert_eq!(ids, [high_id.clone()]);
+
+    let url = format!("/cards/records/user/{high_id}/rating");
+    let response = request_with_auth_and_body(Method::DELETE, &url, &serde_json::json!({})).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let ids = list_ids(format!("/cards/records?id={marker}&min_rating=5")).await;
+    assert!(ids.is_empty(), "Only the viewer's 4 remains");
+
+    let out_of_range = serde_json::json!({ "score": 11 });
+    let response = request_with_auth_and_body(Method::PUT, &url, &out_of_range).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+/// Test adding, listing, editing and deleting record comments
+#[tokio::test]
+async fn test_record_comments() {
+    let record_id = format!("test-comments-{}", uuid::Uuid::new_v4());
+    let payload = bulk_record_payload(&record_id);
+    let response = request_with_auth_and_body(Method::POST, "/cards/records", &payload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let url = format!("/cards/records/{record_id}/comments");
+    let response =
+        request_with_auth_and_body(Method::POST, &url, &serde_json::json!({ "body": "first" }))
+            .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let (_parts, body) = response.into_parts();
+    let comment: RestApiResponse<CommentDto> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize comment");
+    let comment = comment.0.data.expect("No comment data");
+    assert_eq!(comment.body, "first");
+
+    let token = register_viewer_token().await;
+    let second = serde_json::json!({ "body": "second" });
+    let response = request_with_token_and_body(Method::POST, &url, &token, &second).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = request_with_auth(Method::GET, &format!("{url}?limit=1")).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let (_parts, body) = response.into_parts();
+    let page: RestApiResponse<PaginatedResponse<CommentDto>> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize comments");
+    let page = page.0.data.expect("Should have data in response");
+    assert_eq!(page.count, 2);
+    assert_eq!(page.results.len(), 1);
+    assert_eq!(page.results[0].id, comment.id, "Oldest first");
+    assert!(page.next.is_some());
+
+    let response = request_with_auth(Method::GET, &format!("/cards/records/{record_id}")).await;
+    let (_parts, body) = response.into_parts();
+    let record: RestApiResponse<RecordDto> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize record");
+    assert_eq!(record.0.data.expect("No record data").comment_count, 2);
+
+    // Only the author may edit or delete a comment.
+    let comment_url = format!("{url}/{}", comment.id);
+    let edit = serde_json::json!({ "body": "edited" });
+    let response = request_with_token_and_body(Method::PUT, &comment_url, &token, &edit).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let response = request_with_auth_and_body(Method::PUT, &comment_url, &edit).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let (_parts, body) = response.into_parts();
+    let edited: RestApiResponse<CommentDto> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize comment");
+    assert_eq!(edited.0.data.expect("No comment data").body, "edited");
+
+    let empty = serde_json::json!({ "body": "" });
+    let response = request_with_auth_and_body(Method::PUT, &comment_url, &empty).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response =
+        request_with_auth_and_body(Method::DELETE, &comment_url, &serde_json::json!({})).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response =
+        request_with_auth_and_body(Method::DELETE, &comment_url, &serde_json::json!({})).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = request_with_auth_and_body(
+        Method::POST,
+        "/cards/records/no-such-record/comments",
+        &serde_json::json!({ "body": "orphan" }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+/// Test marking records seen and unseen, the `seen` filter and the history
+#[tokio::test]
+async fn test_record_seen_history() {
+    let marker = uuid::Uuid::new_v4();
+    let older_id = format!("test-seen-older-{marker}");
+    let newer_id = format!("test-seen-newer-{marker}");
+    let unseen_id = format!("test-seen-unseen-{marker}");
+    for id in [&older_id, &newer_id, &unseen_id] {
+        let payload = bulk_record_payload(id);
+        let response = request_with_auth_and_body(Method::POST, "/cards/records", &payload).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    let token = register_viewer_token().await;
+    for (id, seen_at) in [
+        (
+            &older_id,
+            serde_json::json!({ "seen_at": "2024-01-01T00:00:00Z" }),
+        ),
+        (&newer_id, serde_json::json!({})),
+    ] {
+        let url = format!("/cards/records/user/{id}/seen");
+        let response = request_with_token_and_body(Method::PUT, &url, &token, &seen_at).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    let list_ids = |url: String| {
+        let token = token.clone();
+        async move {
+            let empty = serde_json::json!({});
+            let response = request_with_token_and_body(Method::GET, &url, &token, &empty).await;
+            assert_eq!(response.status(), StatusCode::OK);
+            let (_parts, body) = response.into_parts();
+            let page: RestApiResponse<PaginatedResponse<RecordDto>> = deserialize_json_body(body)
+                .await
+                .expect("Failed to deserialize records");
+            let page = page.0.data.expect("Should have data in response");
+            let mut ids: Vec<String> = page.results.into_iter().map(|r| r.id).collect();
+            ids.sort_unstable();
+            ids
+        }
+    };
+    let mut seen = vec![newer_id.clone(), older_id.clone()];
+    seen.sort_unstable();
+    let ids = list_ids(format!("/cards/records?id={marker}&seen=true")).await;
+    assert_eq!(ids, seen);
+    let ids = list_ids(format!("/cards/records?id={marker}&seen=false")).await;
+    assert_eq!(ids, [unseen_id.clone()]);
+
+    let empty = serde_json::json!({});
+    let response =
+        request_with_token_and_body(Method::GET, "/cards/users/me/history", &token, &empty).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let (_parts, body) = response.into_parts();
+    let page: RestApiResponse<PaginatedResponse<SeenRecordDto>> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize history");
+    let page = page.0.data.expect("Should have data in response");
+    let history: Vec<&str> = page.results.iter().map(|r| r.record.id.as_str()).collect();
+    assert_eq!(
+        history,
+        [newer_id.as_str(), older_id.as_str()],
+        "Newest first"
+    );
+    assert!(page.results.iter().all(|r| r.record.viewed));
+
+    let url = format!("/cards/records/user/{older_id}/seen");
+    let response = request_with_token_and_body(Method::DELETE, &url, &token, &empty).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let ids = list_ids(format!("/cards/records?id={marker}&seen=true")).await;
+    assert_eq!(ids, [newer_id.clone()]);
+
+    let future = serde_json::json!({ "seen_at": "2999-01-01T00:00:00Z" });
+    let response = request_with_token_and_body(Method::PUT, &url, &token, &future).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+/// Test tag CRUD, record tagging, the `tag_ids` filter and the tag cloud
+#[tokio::test]
+async fn test_tags() {
+    let marker = uuid::Uuid::new_v4();
+    let create_tag = |name: String| async move {
+        let payload = serde_json::json!({ "name": name, "category_id": 1 });
+        let response = request_with_auth_and_body(Method::POST, "/cards/tags", &payload).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let (_parts, body) = response.into_parts();
+        let tag: RestApiResponse<TagDto> = deserialize_json_body(body)
+            .await
+            .expect("Failed to deserialize tag");
+        tag.0.data.expect("No tag data")
+    };
+    let first = create_tag(format!("first-{marker}")).await;
+    let second = create_tag(format!("second-{marker}")).await;
+    assert_eq!(first.category.id, 1);
+
+    let duplicate = serde_json::json!({ "name": first.name, "category_id": 1 });
+    let response = request_with_auth_and_body(Method::POST, "/cards/tags", &duplicate).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    let orphan = serde_json::json!({ "name": format!("orphan-{marker}"), "category_id": 999_999 });
+    let response = request_with_auth_and_body(Method::POST, "/cards/tags", &orphan).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let token = register_viewer_token().await;
+    let viewer = serde_json::json!({ "name": format!("viewer-{marker}"), "category_id": 1 });
+    let response = request_with_token_and_body(Method::POST, "/cards/tags", &token, &viewer).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let both_id = format!("test-tags-both-{marker}");
+    let one_id = format!("test-tags-one-{marker}");
+    for id in [&both_id, &one_id] {
+        let payload = bulk_record_payload(id);
+        let response = request_with_auth_and_body(Method::POST, "/cards/records", &payload).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+    let attach = |record_id: String, tag_ids: Vec<i64>| async move {
+        let url = format!("/cards/records/{record_id}/tags");
+        let payload = serde_json::json!({ "tag_ids": tag_ids });
+        request_with_auth_and_body(Method::POST, &url, &payload).await
+    };
+    let response = attach(both_id.clone(), vec![first.id, second.id, first.id]).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let (_parts, body) = response.into_parts();
+    let tags: RestApiResponse<Vec<TagDto>> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize record tags");
+    assert_eq!(tags.0.data.expect("No tag data").len(), 2);
+    let response = attach(one_id.clone(), vec![first.id]).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = attach(one_id.clone(), vec![-1]).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let list_ids = |url: String| async move {
+        let response = request_with_auth(Method::GET, &url).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let (_parts, body) = response.into_parts();
+        let page: RestApiResponse<PaginatedResponse<RecordDto>> = deserialize_json_body(body)
+            .await
+            .expect("Failed to deserialize records");
+        let page = page.0.data.expect("Should have data in response");
+        let mut ids: Vec<String> = page.results.into_iter().map(|r| r.id).collect();
+        ids.sort_unstable();
+        ids
+    };
+    let ids = list_ids(format!("/cards/records?id={marker}&tag_ids={}", first.id)).await;
+    let mut expected = vec![both_id.clone(), one_id.clone()];
+    expected.sort_unstable();
+    assert_eq!(ids, expected);
+    let url = format!(
+        "/cards/records?id={marker}&tag_ids={},{}&match=all",
+        first.id, second.id
+    );
+    assert_eq!(list_ids(url).await, [both_id.clone()]);
+
+    let response = request_with_auth(Method::GET, "/cards/tags/cloud?limit=1000").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let (_parts, body) = response.into_parts();
+    let cloud: RestApiResponse<Vec<TagCountDto>> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize tag cloud");
+    let cloud = cloud.0.data.expect("No cloud data");
+    let count_of = |id: i64| cloud.iter().find(|t| t.id == id).map(|t| t.count);
+    assert_eq!(count_of(first.id), Some(2));
+    assert_eq!(count_of(second.id), Some(1));
+    let response = request_with_auth(Method::GET, "/cards/tags/cloud?limit=0").await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let detach_url = format!("/cards/records/{both_id}/tags/{}", second.id);
+    let response =
+        request_with_auth_and_body(Method::DELETE, &detach_url, &serde_json::json!({})).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response =
+        request_with_auth_and_body(Method::DELETE, &detach_url, &serde_json::json!({})).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let tag_url = format!("/cards/tags/{}", first.id);
+    let rename = serde_json::json!({ "name": format!("renamed-{marker}"), "category_id": 1 });
+    let response = request_with_auth_and_body(Method::PUT, &tag_url, &rename).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response =
+        request_with_auth_and_body(Method::DELETE, &tag_url, &serde_json::json!({})).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = request_with_auth(Method::GET, &tag_url).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = request_with_auth(Method::GET, &format!("/cards/records/{one_id}/tags")).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let (_parts, body) = response.into_parts();
+    let tags: RestApiResponse<Vec<TagDto>> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize record tags");
+    assert!(
+        tags.0.data.expect("No tag data").is_empty(),
+        "Deleting a tag detaches it"
+    );
+}
+
+/// Test saving, sharing, running and editing saved searches
+#[tokio::test]
+async fn test_saved_searches() {
+    let marker = uuid::Uuid::new_v4();
+    let first_id = format!("test-saved-a-{marker}");
+    let second_id = format!("test-saved-b-{marker}");
+    for id in [&first_id, &second_id] {
+        let payload = bulk_record_payload(id);
+        let response = request_with_auth_and_body(Method::POST, "/cards/records", &payload).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    let definition = serde_json::json!({
+        "name": format!("search-{marker}"),
+        "filters": { "id": marker.to_string() },
+        "ordering": "-id",
+    });
+    let response =
+        request_with_auth_and_body(Method::POST, "/cards/saved-searches", &definition).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let (_parts, body) = response.into_parts();
+    let search: RestApiResponse<SavedSearchDto> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize saved search");
+    let search = search.0.data.expect("No saved search data");
+    assert_eq!(
+        search.filters.id.as_deref(),
+        Some(marker.to_string().as_str())
+    );
+    assert!(!search.is_public);
+
+    let response =
+        request_with_auth_and_body(Method::POST, "/cards/saved-searches", &definition).await;
+    assert_eq!(
+        response.status(),
+        StatusCode::CONFLICT,
+        "Names are unique per owner"
+    );
+    let bad_ordering = serde_json::json!({ "name": format!("bad-{marker}"), "ordering": "nope" });
+    let response =
+        request_with_auth_and_body(Method::POST, "/cards/saved-searches", &bad_ordering).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let bad_filters = serde_json::json!({
+        "name": format!("bad-{marker}"),
+        "filters": { "genre_ids": "1,two" },
+    });
+    let response =
+        request_with_auth_and_body(Method::POST, "/cards/saved-searches", &bad_filters).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let results_url = format!("/cards/saved-searches/{}/results", search.id);
+    let response = request_with_auth(Method::GET, &results_url).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let (_parts, body) = response.into_parts();
+    let page: RestApiResponse<PaginatedResponse<RecordDto>> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize records");
+    let ids: Vec<String> = page
+        .0
+        .data
+        .expect("Should have data in response")
+        .results
+        .into_iter()
+        .map(|r| r.id)
+        .collect();
+    assert_eq!(
+        ids,
+        [second_id.clone(), first_id.clone()],
+        "Saved ordering applies"
+    );
+
+    // Private searches are hidden from other users until shared.
+    let token = register_viewer_token().await;
+    let empty = serde_json::json!({});
+    let response = request_with_token_and_body(Method::GET, &results_url, &token, &empty).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let search_url = format!("/cards/saved-searches/{}", search.id);
+    let shared = serde_json::json!({
+        "name": format!("search-{marker}"),
+        "filters": { "id": marker.to_string() },
+        "is_public": true,
+    });
+    let response = request_with_auth_and_body(Method::PUT, &search_url, &shared).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = request_with_token_and_body(Method::GET, &results_url, &token, &empty).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = request_with_token_and_body(Method::PUT, &search_url, &token, &shared).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let response = request_with_token_and_body(
+        Method::GET,
+        "/cards/saved-searches/public?limit=1000",
+        &token,
+        &empty,
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let (_parts, body) = response.into_parts();
+    let page: RestApiResponse<PaginatedResponse<SavedSearchDto>> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize saved searches");
+    let page = page.0.data.expect("Should have data in response");
+    assert!(page.results.iter().any(|s| s.id == search.id));
+
+    let response = request_with_auth_and_body(Method::DELETE, &search_url, &empty).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = request_with_auth(Method::GET, &search_url).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+/// Test the duplicate report groups exact and similar-title records
+#[tokio::test]
+async fn test_duplicate_record_report() {
+    let marker = uuid::Uuid::new_v4();
+    let record = |id: String, title: String, date: &str, idol: Option<String>| {
+        let mut payload = bulk_record_payload(&id);
+        payload["title"] = serde_json::json!(title);
+        payload["date"] = serde_json::json!(date);
+        if let Some(name) = idol {
+            payload["idols"] = serde_json::json!([
+                { "name": name, "link": "https://example.com/idol", "manual": true }
+            ]);
+        }
+        payload
+    };
+    let exact = [
+        format!("test-dup-exact-a-{marker}"),
+        format!("test-dup-exact-b-{marker}"),
+    ];
+    let similar = [
+        format!("test-dup-similar-a-{marker}"),
+        format!("test-dup-similar-b-{marker}"),
+    ];
+    let idol = format!("Duplicate Idol {marker}");
+    let payloads = [
+        record(
+            exact[0].clone(),
+            format!("Exact {marker}"),
+            "2024-03-01",
+            None,
+        ),
+        record(
+            exact[1].clone(),
+            format!(" exact {marker}"),
+            "2024-03-01",
+            None,
+        ),
+        record(
+            similar[0].clone(),
+            format!("Midnight Harbor {marker} Part 1"),
+            "2024-04-01",
+            Some(idol.clone()),
+        ),
+        record(
+            similar[1].clone(),
+            format!("Midnight Harbor {marker} Part 2"),
+            "2024-05-01",
+            Some(idol),
+        ),
+    ];
+    for payload in &payloads {
+        let response = request_with_auth_and_body(Method::POST, "/cards/records", payload).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    let response = request_with_auth(Method::GET, "/cards/admin/duplicates?limit=500").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let (_parts, body) = response.into_parts();
+    let groups: RestApiResponse<Vec<DuplicateGroupDto>> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize duplicate groups");
+    let groups = groups.0.data.expect("No duplicate data");
+    let group_of = |id: &str| {
+        groups
+            .iter()
+            .find(|group| group.records.iter().any(|record| record.id == id))
+            .expect("Record should be reported")
+    };
+    let exact_group = group_of(&exact[0]);
+    assert_eq!(exact_group.reason, DuplicateReason::SameTitleAndDate);
+    let ids: Vec<&str> = exact_group.records.iter().map(|r| r.id.as_str()).collect();
+    assert_eq!(ids, [exact[0].as_str(), exact[1].as_str()]);
+    let similar_group = group_of(&similar[0]);
+    assert_eq!(similar_group.reason, DuplicateReason::SameIdolsSimilarTitle);
+    assert!(similar_group.records.iter().any(|r| r.id == similar[1]));
+    assert!(similar_group.confidence >= 0.6 && similar_group.confidence < 1.0);
+
+    let response = request_with_auth(Method::GET, "/cards/admin/duplicates?min_similarity=2").await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let token = register_viewer_token().await;
+    let response = request_with_token_and_body(
+        Method::GET,
+        "/cards/admin/duplicates",
+        &token,
+        &serde_json::json!({}),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+/// Test the data-quality report flags a record claiming links and images it
+/// does not have
+#[tokio::test]
+async fn test_integrity_report() {
+    let id = format!("integrity-{}", uuid::Uuid::new_v4());
+    let mut payload = bulk_record_payload(&id);
+    payload["local_img_count"] = serde_json::json!(3);
+    let response = request_with_auth_and_body(
+        Method::POST,
+        "/cards/records/bulk",
+        &serde_json::json!([payload]),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    // `has_links` follows the links, so only a direct write can break it
+    let db = setup_test_db().await.expect("Failed to setup test db");
+    record::Entity::update_many()
+        .col_expr(record::Column::HasLinks, Expr::value(true))
+        .filter(record::Column::Id.eq(&id))
+        .exec(&db)
+        .await
+        .expect("Failed to set has_links");
+
+    let response = request_with_auth(Method::GET, "/cards/admin/integrity?sample=5").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let (_parts, body) = response.into_parts();
+    let report: RestApiResponse<IntegrityReportDto> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize integrity report");
+    let report = report.0.data.expect("No integrity data");
+    for issue in [
+        &report.placeholder_references.director,
+        &report.has_links_without_links,
+        &report.local_img_count_mismatch,
+    ] {
+        assert!(issue.count >= 1);
+        assert!(!issue.sample_ids.is_empty() && issue.sample_ids.len() <= 5);
+    }
+
+    let response = request_with_auth(Method::GET, "/cards/admin/integrity?sample=0").await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let token = register_viewer_token().await;
+    let response = request_with_token_and_body(
+        Method::GET,
+        "/cards/admin/integrity",
+        &token,
+        &serde_json::json!({}),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+/// Test that a bulk purge with `delete_media` removes the record's media
+#[tokio::test]
+async fn test_bulk_purge_deletes_media() {
+    let id = format!("purge-media-{}", uuid::Uuid::new_v4());
+    let response = request_with_auth_and_body(
+        Method::POST,
+        "/cards/records/bulk",
+        &serde_json::json!([bulk_record_payload(&id)]),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let content = [png_bytes(), id.clone().into_bytes()].concat();
+    let upload = media_upload_body(&id, &format!("{id}.png"), "image/png", &content);
+    let response =
+        request_with_auth_and_multipart(Method::POST, "/cards/media/upload", upload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = request_with_auth_and_body(
+        Method::DELETE,
+        "/cards/records",
+        &serde_json::json!({ "ids": [id], "purge": true, "delete_media": true }),
+    )
+    .await;
+    let (parts, body) = response.into_parts();
+    assert_eq!(parts.status, StatusCode::OK);
+    let response_body: RestApiResponse<BulkDeleteRecordsResponse> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize bulk purge response");
+    assert_eq!(
+        response_body.0.data.expect("Should have data").media_dirs,
+        1
+    );
+
+    let response = request_with_auth(Method::POST, "/cards/admin/media-gc").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let report: RestApiResponse<MediaGcReportDto> = deserialize_json_body(response.into_body())
+        .await
+        .expect("Failed to deserialize media GC report");
+    let key = format!("images/record/{id}");
+    assert!(report
+        .0
+        .data
+        .expect("No media GC data")
+        .orphaned_dirs
+        .iter()
+        .all(|dir| dir.key != key));
+}
+
+/// Test that media left behind by a deleted record is reported on a dry run
+/// and removed otherwise
+#[tokio::test]
+async fn test_collect_orphaned_media() {
+    let id = format!("orphan-{}", uuid::Uuid::new_v4());
+    let response = request_with_auth_and_body(
+        Method::POST,
+        "/cards/records/bulk",
+        &serde_json::json!([bulk_record_payload(&id)]),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let content = [png_bytes(), id.clone().into_bytes()].concat();
+    let upload = media_upload_body(&id, &format!("{id}.png"), "image/png", &content);
+    let response =
+        request_with_auth_and_multipart(Method::POST, "/cards/media/upload", upload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = request_with_auth_and_body(
+        Method::DELETE,
+        "/cards/records",
+        &serde_json::json!({ "ids": [id], "purge": true }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let key = format!("images/record/{id}");
+    let collect = |uri: &'static str| async move {
+        let response = request_with_auth(Method::POST, uri).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let (_parts, body) = response.into_parts();
+        let report: RestApiResponse<MediaGcReportDto> = deserialize_json_body(body)
+            .await
+            .expect("Failed to deserialize media GC report");
+        report.0.data.expect("No media GC data")
+    };
+
+    let report = collect("/cards/admin/media-gc").await;
+    assert!(report.dry_run);
+    assert_eq!(report.deleted_dirs, 0);
+    let orphan = report
+        .orphaned_dirs
+        .iter()
+        .find(|dir| dir.key == key)
+        .expect("Orphaned directory should be reported");
+    assert_eq!(orphan.files, 1);
+    assert_eq!(orphan.bytes, content.len() as u64);
+
+    let report = collect("/cards/admin/media-gc?dry_run=false").await;
+    assert!(!report.dry_run);
+    assert!(report.orphaned_dirs.iter().any(|dir| dir.key == key));
+    assert!(report.deleted_dirs >= 1);
+
+    let report = collect("/cards/admin/media-gc").await;
+    assert!(report.orphaned_dirs.iter().all(|dir| dir.key != key));
+
+    let token = register_viewer_token().await;
+    let response = request_with_token_and_body(
+        Method::POST,
+        "/cards/admin/media-gc",
+        &token,
+        &serde_json::json!({}),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+/// Test that idol images follow their idol through a rename and a merge, so
+/// the media collector never sees them as orphans
+#[tokio::test]
+async fn test_idol_media_follows_rename_and_merge() {
+    let marker = uuid::Uuid::new_v4();
+    let create_idol = |name: String| async move {
+        let payload =
+            serde_json::json!({ "name": name, "link": "https://example.com/idol", "manual": true });
+        let response = request_with_auth_and_body(Method::POST, "/cards/idols", &payload).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: RestApiResponse<serde_json::Value> = deserialize_json_body(response.into_body())
+            .await
+            .expect("Failed to deserialize idol");
+        body.0.data.expect("No idol data")["id"]
+            .as_i64()
+            .expect("idol id")
+    };
+    let upload = |idol_id: i64| async move {
+        let body = media_upload_body("", "face.png", "image/png", &png_bytes());
+        let uri = format!("/cards/media/upload_idol_by_id/{idol_id}");
+        let response = request_with_auth_and_multipart(Method::POST, &uri, body).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    };
+    let old_name = format!("idol-media-{marker}");
+    let source_name = format!("idol-media-source-{marker}");
+    let new_name = format!("idol-media-renamed-{marker}");
+    let idol_id = create_idol(old_name.clone()).await;
+    let source_id = create_idol(source_name.clone()).await;
+    upload(idol_id).await;
+    upload(source_id).await;
+
+    let response = request_with_auth_and_body(
+        Method::PATCH,
+        &format!("/cards/idols/{idol_id}"),
+        &serde_json::json!({ "name": new_name }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = request_with_auth(Method::GET, &format!("/cards/media/idol/id/{idol_id}")).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = request_with_auth_and_body(
+        Method::POST,
+        &format!("/cards/idols/{idol_id}/merge"),
+        &serde_json::json!({ "source_id": source_id }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response =
+        request_with_auth(Method::GET, &format!("/cards/media/idol/id/{idol_id}/list")).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let files: RestApiResponse<Vec<MediaFileDto>> = deserialize_json_body(response.into_body())
+        .await
+        .expect("Failed to deserialize media list");
+    let mut names: Vec<String> = files
+        .0
+        .data
+        .expect("No media list data")
+        .into_iter()
+        .map(|file| file.filename)
+        .collect();
+    names.sort();
+    assert_eq!(
+        names,
+        vec![format!("{new_name}.png"), format!("{new_name}_1.png")]
+    );
+
+    let response = request_with_auth(Method::POST, "/cards/admin/media-gc").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let report: RestApiResponse<MediaGcReportDto> = deserialize_json_body(response.into_body())
+        .await
+        .expect("Failed to deserialize media GC report");
+    let report = report.0.data.expect("No media GC data");
+    for name in [old_name, source_name, new_name] {
+        let key = format!("images/idol/{name}");
+        assert!(report.orphaned_dirs.iter().all(|dir| dir.key != key));
+    }
+}
+
+/// Bytes that start with the PNG signature, enough for an upload to be
+/// recognized as PNG
+fn png_bytes() -> Vec<u8> {
+    [b"\x89PNG\r\n\x1a\n".as_slice(), b"png-bytes"].concat()
+}
+
+/// Multipart body uploading `content` as `filename` for record `id`
+fn media_upload_body(id: &str, filename: &str, content_type: &str, content: &[u8]) -> Vec<u8> {
+    let mut body = format!(
+        "------XYZ\r\nContent-Disposition: form-data; name=\"id\"\r\n\r\n{id}\r\n\
+         ------XYZ\r\nContent-Disposition: form-data; name=\"file1\"; filename=\"{filename}\"\r\n\
+         Content-Type: {content_type}\r\n\r\n"
+    )
+    .into_bytes();
+    body.extend_from_slice(content);
+    body.extend_from_slice(b"\r\n------XYZ--\r\n");
+    body
+}
+
+/// Test that uploads keep `local_img_count` in step with the media directory
+/// and that reconciling leaves a matching count alone
+#[tokio::test]
+async fn test_upload_counts_images_and_reconcile() {
+    let id = format!("images-{}", uuid::Uuid::new_v4());
+    let response = request_with_auth_and_body(
+        Method::POST,
+        "/cards/records/bulk",
+        &serde_json::json!([bulk_record_payload(&id)]),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let upload = media_upload_body(&id, "cover.png", "image/png", &png_bytes());
+    let response =
+        request_with_auth_and_multipart(Method::POST, "/cards/media/upload", upload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    async fn local_img_count(id: &str) -> i32 {
+        let response = request_with_auth(Method::GET, &format!("/cards/records/{id}")).await;
+        let body: RestApiResponse<RecordDto> = deserialize_json_body(response.into_body())
+            .await
+            .expect("Failed to deserialize record");
+        body.0
+            .data
+            .expect("Should have data in response")
+            .local_img_count
+    }
+    assert_eq!(local_img_count(&id).await, 1);
+
+    let response = request_with_auth(Method::POST, "/cards/admin/integrity/reconcile-images").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(local_img_count(&id).await, 1);
+
+    let token = register_viewer_token().await;
+    let response = request_with_token_and_body(
+        Method::POST,
+        "/cards/admin/integrity/reconcile-images",
+        &token,
+        &serde_json::json!({}),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+/// Router staging uploads under a fresh private assets directory, which is
+/// returned with it, with at most `upload_max_concurrent` uploads of up to
+/// `asset_max_size` bytes each
+async fn limited_upload_router(
+    asset_max_size: usize,
+    upload_max_concurrent: usize,
+) -> (axum::Router, std::path::PathBuf) {
+    let private_path = std::env::temp_dir().join(format!("upload-limits-{}", uuid::Uuid::new_v4()));
+    let assets_private_path = private_path.to_string_lossy().into_owned();
+    let router = create_test_router_with(|config| {
+        config.assets_private_path = assets_private_path;
+        config.asset_max_size = asset_max_size;
+        config.upload_max_concurrent = upload_max_concurrent;
+    })
+    .await;
+    (router, private_path)
+}
+
+/// Authenticated multipart image upload streaming `body`
+async fn image_upload_request(body: axum::body::Body) -> axum::http::Request<axum::body::Body> {
+    axum::http::Request::builder()
+        .method(Method::POST)
+        .uri("/cards/media/upload")
+        .header(CONTENT_TYPE, "multipart/form-data; boundary=----XYZ")
+        .header(AUTHORIZATION, get_authentication_token().await)
+        .body(body)
+        .expect("Failed to create request")
+}
+
+/// Files left in the upload staging directory under `private_path`
+fn staged_files(private_path: &std::path::Path) -> usize {
+    std::fs::read_dir(private_path.join(".uploads")).map_or(0, Iterator::count)
+}
+
+/// Test that a file over `ASSET_MAX_SIZE` is rejected with 413 and its
+/// staging file removed
+#[tokio::test]
+async fn test_upload_over_size_cap_leaves_no_staging_file() {
+    let (router, private_path) = limited_upload_router(64, 4).await;
+    let oversized = [png_bytes(), vec![0; 1024]].concat();
+    let upload = media_upload_body("over-cap", "cover.png", "image/png", &oversized);
+
+    let response = router
+        .oneshot(image_upload_request(upload.into()).await)
+        .await
+        .expect("Failed to send upload");
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    assert_eq!(staged_files(&private_path), 0);
+}
+
+/// Test that uploads past `UPLOAD_MAX_CONCURRENT` are rejected with 429
+/// while another one is still streaming
+#[tokio::test]
+async fn test_upload_over_concurrency_limit_is_rejected() {
+    let (router, private_path) = limited_upload_router(1024 * 1024, 1).await;
+
+    // The first upload sends the start of its file, then stalls holding the
+    // only slot
+    let (sender, receiver) =
+        futures::channel::mpsc::unbounded::<Result<axum::body::Bytes, std::io::Error>>();
+    let upload = media_upload_body("slow", "cover.png", "image/png", &png_bytes());
+    let head = upload[..upload.len() - 16].to_vec();
+    sender
+        .unbounded_send(Ok(head.into()))
+        .expect("Failed to send upload head");
+    let stalled = tokio::spawn(
+        router
+            .clone()
+            .oneshot(image_upload_request(axum::body::Body::from_stream(receiver)).await),
+    );
+    for _ in 0..100 {
+        if staged_files(&private_path) > 0 {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    }
+    assert_eq!(
+        staged_files(&private_path),
+        1,
+        "The first upload is staging"
+    );
+
+    let upload = media_upload_body("fast", "cover.png", "image/png", &png_bytes());
+    let response = router
+        .oneshot(image_upload_request(upload.into()).await)
+        .await
+        .expect("Failed to send upload");
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+
+    // Cut short, the first upload fails and removes its staging file
+    drop(sender);
+    let response = stalled
+        .await
+        .expect("Stalled upload panicked")
+        .expect("Router is infallible");
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(staged_files(&private_path), 0);
+}
+
+/// Test that media answers byte ranges and conditional requests
+#[tokio::test]
+async fn test_serve_media_range_and_conditional() {
+    let id = format!("range-{}", uuid::Uuid::new_v4());
+    let response = request_with_auth_and_body(
+        Method::POST,
+        "/cards/records/bulk",
+        &serde_json::json!([bulk_record_payload(&id)]),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let upload = media_upload_body(&id, &format!("{id}.png"), "image/png", &png_bytes());
+    let response =
+        request_with_auth_and_multipart(Method::POST, "/cards/media/upload", upload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let uri = format!("/cards/media/{id}");
+
+    let response = request_with_auth(Method::GET, &uri).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[ACCEPT_RANGES], "bytes");
+    assert!(response.headers().contains_key(LAST_MODIFIED));
+    let etag = response.headers()[ETAG]
+        .to_str()
+        .expect("ETag is ASCII")
+        .to_owned();
+
+    let response = request_with_auth_and_header(Method::GET, &uri, RANGE, "bytes=1-3").await;
+    assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+    assert_eq!(response.headers()[CONTENT_RANGE], "bytes 1-3/17");
+    let body = response
+        .into_body()
+        .collect()
+        .await
+        .expect("read range")
+        .to_bytes();
+    assert_eq!(&body[..], b"PNG");
+
+    let response = request_with_auth_and_header(Method::GET, &uri, RANGE, "bytes=100-").await;
+    assert_eq!(response.status(), StatusCode::RANGE_NOT_SATISFIABLE);
+    assert_eq!(response.headers()[CONTENT_RANGE], "bytes */17");
+
+    let response = request_with_auth_and_header(Method::GET, &uri, IF_NONE_MATCH, &etag).await;
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+}
+
+/// Test that record media of a restricted record cannot be fetched from the
+/// private assets mount, bypassing the media permission check
+#[tokio::test]
+async fn test_private_assets_hide_record_media() {
+    let id = format!("hidden-{}", uuid::Uuid::new_v4());
+    let mut payload = bulk_record_payload(&id);
+    payload["permission"] = serde_json::json!(2);
+    let response = request_with_auth_and_body(
+        Method::POST,
+        "/cards/records/bulk",
+        &serde_json::json!([payload]),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let upload = media_upload_body(&id, &format!("{id}.png"), "image/png", &png_bytes());
+    let response =
+        request_with_auth_and_multipart(Method::POST, "/cards/media/upload", upload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let token = register_viewer_token().await;
+    let empty = serde_json::json!({});
+    let response =
+        request_with_token_and_body(Method::GET, &format!("/cards/media/{id}"), &token, &empty)
+            .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    for uri in [
+        format!("/assets/private/images/record/{id}/{id}.png"),
+        format!("/assets/private/%69mages/record/{id}/{id}.png"),
+        format!("/assets/private/./images//record/{id}/{id}.png"),
+        "/assets/private/.uploads/".to_owned(),
+    ] {
+        let response = request_with_token_and_body(Method::GET, &uri, &token, &empty).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND, "{uri}");
+    }
+}
+
+/// Test that uploads are checked against their magic bytes and stored with
+/// the extension of the sniffed format
+#[tokio::test]
+async fn test_upload_sniffs_image_format() {
+    let id = format!("sniff-{}", uuid::Uuid::new_v4());
+    let response = request_with_auth_and_body(
+        Method::POST,
+        "/cards/records/bulk",
+        &serde_json::json!([bulk_record_payload(&id)]),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let upload = media_upload_body(&id, &format!("{id}.jpg"), "image/jpeg", b"not-an-image");
+    let response =
+        request_with_auth_and_multipart(Method::POST, "/cards/media/upload", upload).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let upload = media_upload_body(&id, &format!("{id}.jpg"), "image/jpeg", &png_bytes());
+    let response =
+        request_with_auth_and_multipart(Method::POST, "/cards/media/upload", upload).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let upload = media_upload_body(
+        &id,
+        &format!("{id}.jpg"),
+        "application/octet-stream",
+        &png_bytes(),
+    );
+    let response =
+        request_with_auth_and_multipart(Method::POST, "/cards/media/upload", upload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = request_with_auth(Method::GET, &format!("/cards/media/{id}")).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[CONTENT_TYPE], "image/png");
+}
+
+/// Test that the media list reports the main and numbered images of a record
+#[tokio::test]
+async fn test_list_media() {
+    let id = format!("list-{}", uuid::Uuid::new_v4());
+    let response = request_with_auth_and_body(
+        Method::POST,
+        "/cards/records/bulk",
+        &serde_json::json!([bulk_record_payload(&id)]),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let uri = format!("/cards/media/{id}/list");
+    let response = request_with_auth(Method::GET, &uri).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let listed: RestApiResponse<Vec<MediaFileDto>> = deserialize_json_body(response.into_body())
+        .await
+        .expect("Failed to deserialize media list");
+    assert!(listed.0.data.expect("media list").is_empty());
+
+    for filename in [format!("{id}_1.png"), format!("{id}.png")] {
+        let upload = media_upload_body(&id, &filename, "image/png", &png_bytes());
+        let response =
+            request_with_auth_and_multipart(Method::POST, "/cards/media/upload", upload).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    let response = request_with_auth(Method::GET, &uri).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let listed: RestApiResponse<Vec<MediaFileDto>> = deserialize_json_body(response.into_body())
+        .await
+        .expect("Failed to deserialize media list");
+    let files = listed.0.data.expect("media list");
+    assert_eq!(files.len(), 2);
+    assert_eq!(files[0].filename, format!("{id}.png"));
+    assert_eq!(files[0].n, None);
+    assert_eq!(files[0].url, format!("/cards/media/{id}"));
+    assert_eq!(files[1].n, Some(1));
+    assert_eq!(files[1].url, format!("/cards/media/{id}?n=1"));
+    assert!(files.iter().all(|file| file.size == 17));
+}
+
+/// Test that the media archive holds every uploaded image and that a record
+/// without images has no archive
+#[tokio::test]
+async fn test_archive_media() {
+    let id = format!("archive-{}", uuid::Uuid::new_v4());
+    let response = request_with_auth_and_body(
+        Method::POST,
+        "/cards/records/bulk",
+        &serde_json::json!([bulk_record_payload(&id)]),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let uri = format!("/cards/media/{id}/archive");
+    let response = request_with_auth(Method::GET, &uri).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    for filename in [format!("{id}.png"), format!("{id}_1.png")] {
+        let upload = media_upload_body(&id, &filename, "image/png", &png_bytes());
+        let response =
+            request_with_auth_and_multipart(Method::POST, "/cards/media/upload", upload).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    let response = request_with_auth(Method::GET, &uri).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[CONTENT_TYPE], "application/zip");
+    let body = response
+        .into_body()
+        .collect()
+        .await
+        .expect("read archive")
+        .to_bytes();
+    assert!(body.starts_with(b"PK\x03\x04"), "archive is a ZIP file");
+    for filename in [format!("{id}.png"), format!("{id}_1.png")] {
+        assert!(
+            body.windows(filename.len())
+                .any(|window| window == filename.as_bytes()),
+            "archive holds {filename}"
+        );
+    }
+    let png = png_bytes();
+    assert_eq!(
+        body.windows(png.len())
+            .filter(|window| *window == png.as_slice())
+            .count(),
+        2
+    );
+}
+
+/// Test that the same image uploaded for two records is reported as a
+/// duplicate and still served for both
+#[tokio::test]
+async fn test_duplicate_images_report() {
+    let ids = [
+        format!("dupimg-a-{}", uuid::Uuid::new_v4()),
+        format!("dupimg-b-{}", uuid::Uuid::new_v4()),
+    ];
+    let payloads: Vec<_> = ids.iter().map(|id| bulk_record_payload(id)).collect();
+    let response = request_with_auth_and_body(
+        Method::POST,
+        "/cards/records/bulk",
+        &serde_json::json!(payloads),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // Content unique to this test, so no other upload shares its hash
+    let content = [png_bytes(), ids[0].clone().into_bytes()].concat();
+    for id in &ids {
+        let upload = media_upload_body(id, &format!("{id}.png"), "image/png", &content);
+        let response =
+            request_with_auth_and_multipart(Method::POST, "/cards/media/upload", upload).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+    for id in &ids {
+        let response = request_with_auth(Method::GET, &format!("/cards/media/{id}")).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response
+            .into_body()
+            .collect()
+            .await
+            .expect("read image")
+            .to_bytes();
+        assert_eq!(body.as_ref(), content.as_slice());
+    }
+
+    let response = request_with_auth(Method::GET, "/cards/admin/duplicate-images?limit=500").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let (_parts, body) = response.into_parts();
+    let groups: RestApiResponse<Vec<DuplicateImageGroupDto>> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize duplicate image groups");
+    let groups = groups.0.data.expect("No duplicate image data");
+    let group = groups
+        .iter()
+        .find(|group| group.files.iter().any(|file| file.owner_id == ids[0]))
+        .expect("Upload should be reported");
+    assert_eq!(group.size, content.len() as u64);
+    let owners: Vec<&str> = group.files.iter().map(|f| f.owner_id.as_str()).collect();
+    assert_eq!(owners, [ids[0].as_str(), ids[1].as_str()]);
+    assert!(group.files.iter().all(|file| file.owner_type == "record"));
+
+    let response = request_with_auth(Method::GET, "/cards/admin/duplicate-images?limit=0").await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let token = register_viewer_token().await;
+    let response = request_with_token_and_body(
+        Method::GET,
+        "/cards/admin/duplicate-images",
+        &token,
+        &serde_json::json!({}),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+/// Test that an uploaded image can be chosen as cover and that images the
+/// record lacks are refused
+#[tokio::test]
+async fn test_set_record_cover() {
+    let id = format!("cover-{}", uuid::Uuid::new_v4());
+    let response = request_with_auth_and_body(
+        Method::POST,
+        "/cards/records/bulk",
+        &serde_json::json!([bulk_record_payload(&id)]),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let upload = media_upload_body(&id, &format!("{id}_2.png"), "image/png", &png_bytes());
+    let response =
+        request_with_auth_and_multipart(Method::POST, "/cards/media/upload", upload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let uri = format!("/cards/records/{id}/cover");
+    let response =
+        request_with_auth_and_body(Method::PUT, &uri, &serde_json::json!({ "n": 3 })).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response =
+        request_with_auth_and_body(Method::PUT, &uri, &serde_json::json!({ "n": 2 })).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let record: RestApiResponse<RecordDto> = deserialize_json_body(response.into_body())
+        .await
+        .expect("Failed to deserialize record");
+    let record = record.0.data.expect("record");
+    assert_eq!(record.cover_index, Some(2));
+    assert_eq!(record.cover_url, format!("/cards/media/{id}?n=2"));
+
+    let response = request_with_auth(Method::GET, &format!("/cards/records/{id}")).await;
+    let record: RestApiResponse<RecordDto> = deserialize_json_body(response.into_body())
+        .await
+        .expect("Failed to deserialize record");
+    assert_eq!(
+        record.0.data.expect("record").cover_url,
+        format!("/cards/media/{id}?n=2")
+    );
+}
+
+/// Test that records list in series order, numbered records first, and
+/// link to their neighbors in the series
+#[tokio::test]
+async fn test_series_order_and_neighbors() {
+    let suffix = uuid::Uuid::new_v4();
+    let series = format!("Ordered Series {suffix}");
+    let ids: Vec<String> = (0..3).map(|i| format!("series-{i}-{suffix}")).collect();
+    let mut payloads: Vec<serde_json::Value> = ids
+        .iter()
+        .zip(["2025-01-01", "2025-02-01", "2025-03-01"])
+        .map(|(id, date)| {
+            let mut payload = bulk_record_payload(id);
+            payload["date"] = serde_json::json!(date);
+            payload["series"] = serde_json::json!({
+                "name": series,
+                "link": "",
+                "manual": true
+            });
+            payload
+        })
+        .collect();
+    let loose = format!("series-loose-{suffix}");
+    payloads.push(bulk_record_payload(&loose));
+    let response = request_with_auth_and_body(
+        Method::POST,
+        "/cards/records/bulk",
+        &serde_json::json!(payloads),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // The latest record is the first of the series
+    let uri = format!("/cards/records/{}/series-order", ids[2]);
+    let response = request_with_auth_and_body(
+        Method::PUT,
+        &uri,
+        &serde_json::json!({ "order_in_series": 1 }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let record: RestApiResponse<RecordDto> = deserialize_json_body(response.into_body())
+        .await
+        .expect("Failed to deserialize record");
+    let record = record.0.data.expect("record");
+    assert_eq!(record.order_in_series, Some(1));
+    let series_id = record.series.id;
+
+    let response = request_with_auth(
+        Method::GET,
+        &format!("/cards/series/{series_id}/records?ordered=true"),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let page: RestApiResponse<PaginatedResponse<RecordDto>> =
+        deserialize_json_body(response.into_body())
+            .await
+            .expect("Failed to deserialize records");
+    let listed: Vec<String> = page
+        .0
+        .data
+        .expect("records")
+        .results
+        .into_iter()
+        .map(|r| r.id)
+        .collect();
+    assert_eq!(listed, [&ids[2], &ids[0], &ids[1]]);
+
+    let response = request_with_auth(Method::GET, &format!("/cards/records/{}", ids[0])).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let record: RestApiResponse<RecordDto> = deserialize_json_body(response.into_body())
+        .await
+        .expect("Failed to deserialize record");
+    let record = record.0.data.expect("record");
+    assert_eq!(
+        record.series_previous.map(|r| r.id).as_deref(),
+        Some(ids[2].as_str())
+    );
+    assert_eq!(
+        record.series_next.map(|r| r.id).as_deref(),
+        Some(ids[1].as_str())
+    );
+
+    let response = request_with_auth(Method::GET, &format!("/cards/records/{}", ids[2])).await;
+    let record: RestApiResponse<RecordDto> = deserialize_json_body(response.into_body())
+        .await
+        .expect("Failed to deserialize record");
+    assert!(record.0.data.expect("record").series_previous.is_none());
+
+    let response = request_with_auth(Method::GET, &format!("/cards/records/{loose}")).await;
+    let record: RestApiResponse<RecordDto> = deserialize_json_body(response.into_body())
+        .await
+        .expect("Failed to deserialize record");
+    let record = record.0.data.expect("record");
+    assert!(record.series_previous.is_none() && record.series_next.is_none());
+
+    // Only records in a series take an order, and only from 1
+    let response = request_with_auth_and_body(
+        Method::PUT,
+        &format!("/cards/records/{loose}/series-order"),
+        &serde_json::json!({ "order_in_series": 1 }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let response = request_with_auth_and_body(
+        Method::PUT,
+        &uri,
+        &serde_json::json!({ "order_in_series": 0 }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = request_with_auth(
+        Method::GET,
+        &format!("/cards/series/{series_id}/records?ordered=true&ordering=title"),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+/// Test that a trailer upload is sniffed, linked from the record, served
+/// with `Range` support, and not overwritten
+#[tokio::test]
+async fn test_upload_and_serve_record_video() {
+    let id = format!("video-{}", uuid::Uuid::new_v4());
+    let response = request_with_auth_and_body(
+        Method::POST,
+        "/cards/records/bulk",
+        &serde_json::json!([bulk_record_payload(&id)]),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let uri = format!("/cards/media/{id}/video");
+    let video_body = |content_type: &str, content: &[u8]| {
+        let mut body = format!(
+            "------XYZ\r\nContent-Disposition: form-data; name=\"file\"; filename=\"trailer\"\r\n\
+             Content-Type: {content_type}\r\n\r\n"
+        )
+        .into_bytes();
+        body.extend_from_slice(content);
+        body.extend_from_slice(b"\r\n------XYZ--\r\n");
+        body
+    };
+    let mp4 = b"\0\0\0\x18ftypisom\0\0\x02\0isomiso2mp41";
+
+    let response =
+        request_with_auth_and_multipart(Method::POST, &uri, video_body("video/mp4", &png_bytes()))
+            .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response =
+        request_with_auth_and_multipart(Method::POST, &uri, video_body("video/mp4", mp4)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = request_with_auth(Method::GET, &format!("/cards/records/{id}")).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let record: RestApiResponse<RecordDto> = deserialize_json_body(response.into_body())
+        .await
+        .expect("Failed to deserialize record");
+    assert_eq!(record.0.data.expect("record").trailer, Some(uri.clone()));
+
+    let response = request_with_auth_and_header(Method::GET, &uri, RANGE, "bytes=4-7").await;
+    assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+    assert_eq!(response.headers()[CONTENT_TYPE], "video/mp4");
+    assert_eq!(
+        response.headers()[CONTENT_RANGE],
+        format!("bytes 4-7/{}", mp4.len())
+    );
+    let body = response
+        .into_body()
+        .collect()
+        .await
+        .expect("read range")
+        .to_bytes();
+    assert_eq!(&body[..], b"ftyp");
+
+    let response =
+        request_with_auth_and_multipart(Method::POST, &uri, video_body("video/mp4", mp4)).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+}
+
+/// Test that a zero thumbnail side is rejected
+#[tokio::test]
+async fn test_serve_media_rejects_zero_thumbnail_size() {
+    let response = request_with_auth(Method::GET, "/cards/media/any-id?w=0").await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+/// Test that `w` serves a cached JPEG thumbnail rounded up to a configured
+/// size, and that the original is still served without it
+#[cfg(feature = "thumbnails")]
+#[tokio::test]
+async fn test_serve_media_thumbnail() {
+    use image::{codecs::png::PngEncoder, ImageEncoder as _, RgbImage};
+
+    let id = format!("thumb-{}", uuid::Uuid::new_v4());
+    let response = request_with_auth_and_body(
+        Method::POST,
+        "/cards/records/bulk",
+        &serde_json::json!([bulk_record_payload(&id)]),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let mut png = Vec::new();
+    PngEncoder::new(&mut png)
+        .write_image(
+            RgbImage::new(400, 200).as_raw(),
+            400,
+            200,
+            image::ExtendedColorType::Rgb8,
+        )
+        .expect("encode PNG");
+    let upload = media_upload_body(&id, &format!("{id}.png"), "image/png", &png);
+    let response =
+        request_with_auth_and_multipart(Method::POST, "/cards/media/upload", upload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = request_with_auth(Method::GET, &format!("/cards/media/{id}?w=100")).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[CONTENT_TYPE], "image/jpeg");
+    let body = response
+        .into_body()
+        .collect()
+        .await
+        .expect("read thumbnail")
+        .to_bytes();
+    let thumbnail = image::load_from_memory(&body).expect("decode thumbnail");
+    assert_eq!((thumbnail.width(), thumbnail.height()), (160, 80));
+
+    let response = request_with_auth(Method::GET, &format!("/cards/media/{id}")).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[CONTENT_TYPE], "image/png");
+}
+
+/// Test that rebuilding the search index is reserved for admins
+#[tokio::test]
+async fn test_search_reindex_requires_admin() {
+    let token = register_viewer_token().await;
+    let empty = serde_json::json!({});
+    let response =
+        request_with_token_and_body(Method::POST, "/cards/search/reindex", &token, &empty).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+/// Test that `genre_ids` honours `match=any` and `match=all`
+#[tokio::test]
+async fn test_get_records_multi_genre_match_modes() {
+    let suffix = uuid::Uuid::new_v4().simple().to_string();
+    let genre = |name: &str| serde_json::json!({ "name": format!("{name}-{suffix}"), "link": null, "manual": null });
+    let both_id = format!("multi-both-{suffix}");
+    let one_id = format!("multi-one-{suffix}");
+    let mut both = bulk_record_payload(&both_id);
+    both["genres"] = serde_json::json!([genre("multi-a"), genre("multi-b")]);
+    let mut one = bulk_record_payload(&one_id);
+    one["genres"] = serde_json::json!([genre("multi-a")]);
+    let payload = serde_json::json!([both, one]);
+    let response = request_with_auth_and_body(Method::POST, "/cards/records/bulk", &payload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = request_with_auth(Method::GET, &format!("/cards/records/{both_id}")).await;
+    let body: RestApiResponse<RecordDto> = deserialize_json_body(response.into_body())
+        .await
+        .expect("Failed to deserialize record");
+    let genre_ids: Vec<String> = body
+        .0
+        .data
+        .expect("Should have data in response")
+        .genres
+        .iter()
+        .map(|g| g.genre.id.to_string())
+        .collect();
+    assert_eq!(genre_ids.len(), 2);
+
+    let matching_ids = |mode: &'static str| {
+        let uri = format!(
+            "/cards/records?genre_ids={}&match={mode}&limit=50",
+            genre_ids.join(",")
+        );
+        async move {
+            let response = request_with_auth(Method::GET, &uri).await;
+            assert_eq!(response.status(), StatusCode::OK);
+            let body: RestApiResponse<PaginatedResponse<RecordDto>> =
+                deserialize_json_body(response.into_body())
+                    .await
+                    .expect("Failed to deserialize record list");
+            let mut ids: Vec<String> = body
+                .0
+                .data
+                .expect("Should have data in response")
+                .results
+                .into_iter()
+                .map(|r| r.id)
+                .collect();
+            ids.sort_unstable();
+            ids
+        }
+    };
+
+    let mut expected_any = vec![both_id.clone(), one_id.clone()];
+    expected_any.sort_unstable();
+    assert_eq!(matching_ids("any").await, expected_any);
+    assert_eq!(matching_ids("all").await, vec![both_id]);
+
+    let response = request_with_auth(Method::GET, "/cards/records?genre_ids=1,x").await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+/// Test that `fields` trims each listed record to the requested keys
+#[tokio::test]
+async fn test_get_records_fields_projection() {
+    let id = format!("fields-{}", uuid::Uuid::new_v4());
+    let payload = serde_json::json!([bulk_record_payload(&id)]);
+    let response = request_with_auth_and_body(Method::POST, "/cards/records/bulk", &payload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let uri = format!("/cards/records?id={id}&fields=title,date");
+    let response = request_with_auth(Method::GET, &uri).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: RestApiResponse<PaginatedResponse<serde_json::Value>> =
+        deserialize_json_body(response.into_body())
+            .await
+            .expect("Failed to deserialize projected list");
+    let results = body.0.data.expect("Should have data in response").results;
+    assert_eq!(results.len(), 1);
+    let mut keys: Vec<&str> = results[0]
+        .as_object()
+        .expect("record object")
+        .keys()
+        .map(String::as_str)
+        .collect();
+    keys.sort_unstable();
+    assert_eq!(keys, vec!["date", "id", "title"]);
+
+    let response = request_with_auth(Method::GET, "/cards/records?fields=bogus").await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+/// Test that the slim listing honours record filters and `modified_since`
+#[tokio::test]
+async fn test_get_record_slim_filters_and_modified_since() {
+    let id = format!("slim-{}", uuid::Uuid::new_v4());
+    let payload = serde_json::json!([bulk_record_payload(&id)]);
+    let response = request_with_auth_and_body(Method::POST, "/cards/records/bulk", &payload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let slim_ids = |uri: String| async move {
+        let response = request_with_auth(Method::GET, &uri).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: RestApiResponse<PaginatedResponse<serde_json::Value>> =
+            deserialize_json_body(response.into_body())
+                .await
+                .expect("Failed to deserialize slim list");
+        body.0
+            .data
+            .expect("Should have data in response")
+            .results
+            .into_iter()
+            .map(|r| r["id"].as_str().unwrap_or_default().to_owned())
+            .collect::<Vec<_>>()
+    };
+
+    let today = chrono::Utc::now().date_naive();
+    let tomorrow = today.succ_opt().expect("valid date");
+    assert_eq!(
+        slim_ids(format!(
+            "/cards/records/slim?id={id}&modified_since={today}"
+        ))
+        .await,
+        vec![id.clone()]
+    );
+    assert!(slim_ids(format!(
+        "/cards/records/slim?id={id}&modified_since={tomorrow}"
+    ))
+    .await
+    .is_empty());
+}
+
+/// Test that incremental sync reports new records and deletions after a position
+#[tokio::test]
+async fn test_sync_records_reports_changes_and_deletions() {
+    let sync_page = |since: i64| async move {
+        let uri = format!("/cards/sync/records?since={since}&limit=1000");
+        let response = request_with_auth(Method::GET, &uri).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: RestApiResponse<serde_json::Value> = deserialize_json_body(response.into_body())
+            .await
+            .expect("Failed to deserialize sync page");
+        body.0.data.expect("Should have data in response")
+    };
+
+    let doomed = format!("sync-doomed-{}", uuid::Uuid::new_v4());
+    let payload = serde_json::json!([bulk_record_payload(&doomed)]);
+    let response = request_with_auth_and_body(Method::POST, "/cards/records/bulk", &payload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // Catch up to the current head
+    let mut since = 0;
+    loop {
+        let page = sync_page(since).await;
+        since = page["next_since"].as_i64().expect("next_since");
+        if !page["has_more"].as_bool().expect("has_more") {
+            break;
+        }
+    }
+
+    let fresh = format!("sync-fresh-{}", uuid::Uuid::new_v4());
+    let payload = serde_json::json!([bulk_record_payload(&fresh)]);
+    let response = request_with_auth_and_body(Method::POST, "/cards/records/bulk", &payload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = request_with_auth(Method::DELETE, &format!("/cards/records/{doomed}")).await;
+    assert!(response.status().is_success());
+
+    let page = sync_page(since).await;
+    let ids: Vec<&str> = page["records"]
+        .as_array()
+        .expect("records array")
+        .iter()
+        .filter_map(|r| r["id"].as_str())
+        .collect();
+    let deleted: Vec<&str> = page["deleted"]
+        .as_array()
+        .expect("deleted array")
+        .iter()
+        .filter_map(serde_json::Value::as_str)
+        .collect();
+    assert!(ids.contains(&fresh.as_str()));
+    assert!(deleted.contains(&doomed.as_str()));
+    assert!(page["next_since"].as_i64().expect("next_since") > since);
+}
+
+/// Test that a write committing after a later one still syncs after it
+#[tokio::test]
+async fn test_sync_position_follows_commit_order() {
+    let slow = format!("sync-slow-{}", uuid::Uuid::new_v4());
+    let fast = format!("sync-fast-{}", uuid::Uuid::new_v4());
+    let payload = serde_json::json!([bulk_record_payload(&slow), bulk_record_payload(&fast)]);
+    let response = request_with_auth_and_body(Method::POST, "/cards/records/bulk", &payload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let db = setup_test_db().await.expect("Failed to setup test db");
+    let txn = db.begin().await.expect("Failed to begin transaction");
+    record::Entity::update_many()
+        .col_expr(record::Column::Title, Expr::value("Slow Writer"))
+        .filter(record::Column::Id.eq(&slow))
+        .exec(&txn)
+        .await
+        .expect("Failed to update record");
+
+    let patch = serde_json::json!({ "title": "Fast Writer" });
+    let response =
+        request_with_auth_and_body(Method::PATCH, &format!("/cards/records/{fast}"), &patch).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    txn.commit().await.expect("Failed to commit transaction");
+
+    let sync_seq = |id: String| {
+        let db = db.clone();
+        async move {
+            record::Entity::find_by_id(id)
+                .one(&db)
+                .await
+                .expect("Failed to load record")
+                .expect("Record exists")
+                .sync_seq
+        }
+    };
+    assert!(sync_seq(slow).await > sync_seq(fast).await);
+}
+
+/// Test that record and entity exports stream CSV and JSONL dumps
+#[tokio::test]
+async fn test_export_records_and_entities() {
+    let id = format!("export-{}", uuid::Uuid::new_v4());
+    let payload = serde_json::json!([bulk_record_payload(&id)]);
+    let response = request_with_auth_and_body(Method::POST, "/cards/records/bulk", &payload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = request_with_auth(Method::GET, "/cards/export/records?format=csv").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok()),
+        Some("text/csv; charset=utf-8")
+    );
+    let body = response
+        .into_body()
+        .collect()
+        .await
+        .expect("Failed to read export body")
+        .to_bytes();
+    let csv = String::from_utf8(body.to_vec()).expect("CSV should be UTF-8");
+    let mut lines = csv.lines();
+    assert_eq!(
+        lines.next(),
+        Some(csv_line(RECORD_EXPORT_COLUMNS).trim_end())
+    );
+    assert!(lines.any(|line| line.starts_with(&format!("{id},"))));
+
+    let response = request_with_auth(Method::GET, "/cards/export/genres?format=jsonl").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response
+        .into_body()
+        .collect()
+        .await
+        .expect("Failed to read export body")
+        .to_bytes();
+    let jsonl = String::from_utf8(body.to_vec()).expect("JSONL should be UTF-8");
+    for line in jsonl.lines() {
+        let row: serde_json::Value = serde_json::from_str(line).expect("Each line is JSON");
+        assert!(row["id"].is_i64() && row["name"].is_string());
+    }
+
+    let response = request_with_auth(Method::GET, "/cards/export/unknown").await;
+    assert!(response.status().is_client_error());
+}
+
+/// Test that a CSV import reports created, skipped, updated and invalid rows
+#[tokio::test]
+async fn test_import_records_reports_per_row_status() {
+    let id = format!("import-{}", uuid::Uuid::new_v4());
+    let mut csv = csv_line(["id", "title", "date", "director", "genres"]);
+    csv.push_str(&csv_line([
+        id.as_str(),
+        "Imported",
+        "2024-05-06",
+        "Import Director",
+        "a|b",
+    ]));
+    csv.push_str(&csv_line([id.as_str(), "Again", "2024-05-06", "", ""]));
+    csv.push_str(&csv_line(["import-bad", "Bad", "yesterday", "", ""]));
+    let multipart = |csv: &str| {
+        format!(
+            "------XYZ\r\nContent-Disposition: form-data; name=\"file\"; filename=\"records.csv\"\r\n\
+             Content-Type: text/csv\r\n\r\n{csv}\r\n------XYZ--\r\n"
+        )
+        .into_bytes()
+    };
+    let import = |uri: &'static str, body: Vec<u8>| async move {
+        let response = request_with_auth_and_multipart(Method::POST, uri, body).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: RestApiResponse<serde_json::Value> = deserialize_json_body(response.into_body())
+            .await
+            .expect("Failed to deserialize import report");
+        body.0.data.expect("Should have data in response")
+    };
+
+    let report = import("/cards/import", multipart(&csv)).await;
+    let statuses: Vec<&str> = report["rows"]
+        .as_array()
+        .expect("rows array")
+        .iter()
+        .filter_map(|r| r["status"].as_str())
+        .collect();
+    assert_eq!(statuses, ["created", "skipped", "error"]);
+    assert_eq!(report["created"], 1);
+    assert_eq!(report["failed"], 1);
+
+    let mut csv = csv_line(["id", "title", "date"]);
+    csv.push_str(&csv_line([id.as_str(), "Reimported", "2024-05-06"]));
+    let report = import("/cards/import?on_conflict=update", multipart(&csv)).await;
+    assert_eq!(report["updated"], 1);
+
+    let response = request_with_auth(Method::GET, &format!("/cards/records/{id}")).await;
+    let body: RestApiResponse<RecordDto> = deserialize_json_body(response.into_body())
+        .await
+        .expect("Failed to deserialize record");
+    let record = body.0.data.expect("Should have data in response");
+    assert_eq!(record.title, "Reimported");
+    assert!(record.version > 1);
+}
+
+/// Test that the recent-records feeds list a freshly created record
+#[tokio::test]
+async fn test_recent_record_feeds() {
+    let id = format!("feed-{}", uuid::Uuid::new_v4());
+    let payload = serde_json::json!([bulk_record_payload(&id)]);
+    let response = request_with_auth_and_body(Method::POST, "/cards/records/bulk", &payload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = request_with_auth(Method::GET, "/cards/feeds/recent.json").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok()),
+        Some("application/feed+json")
+    );
+    assert!(response.headers().contains_key(ETAG));
+    let feed: serde_json::Value = deserialize_json_body(response.into_body())
+        .await
+        .expect("Failed to deserialize JSON feed");
+    let items = feed["items"].as_array().expect("items array");
+    assert!(items.iter().any(|item| item["id"]
+        .as_str()
+        .is_some_and(|item_id| item_id.ends_with(&id))));
+
+    let response = request_with_auth(Method::GET, "/cards/feeds/recent.atom?limit=5").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("application/atom+xml")));
+    let body = response
+        .into_body()
+        .collect()
+        .await
+        .expect("Failed to read feed body")
+        .to_bytes();
+    let atom = String::from_utf8(body.to_vec()).expect("Atom should be UTF-8");
+    assert!(atom.contains("<feed"));
+
+    let response = request_with_auth(Method::GET, "/cards/feeds/recent.json?limit=0").await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+/// Read SSE frames from `body` until the accumulated text contains `needle`.
+async fn read_sse_until(body: &mut axum::body::Body, needle: &str) -> String {
+    let read = async {
+        let mut text = String::new();
+        while !text.contains(needle) {
+            let frame = body
+                .frame()
+                .await
+                .expect("Event stream ended")
+                .expect("Failed to read event stream");
+            if let Some(data) = frame.data_ref() {
+                text.push_str(&String::from_utf8_lossy(data));
+            }
+        }
+        text
+    };
+    tokio::time::timeout(std::time::Duration::from_secs(10), read)
+        .await
+        .expect("Timed out waiting for event")
+}
+
+/// Test that the catalog event stream delivers new records and asks clients
+/// with a stale `Last-Event-ID` to resync
+#[tokio::test]
+async fn test_catalog_event_stream() {
+    let response = request_with_auth(Method::GET, "/cards/events").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok()),
+        Some("text/event-stream")
+    );
+    let mut body = response.into_body();
+    let greeting = read_sse_until(&mut body, "retry:").await;
+    assert!(greeting.contains(": connected"));
+
+    let id = format!("events-{}", uuid::Uuid::new_v4());
+    let payload = serde_json::json!([bulk_record_payload(&id)]);
+    let response = request_with_auth_and_body(Method::POST, "/cards/records/bulk", &payload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    read_sse_until(
+        &mut body,
+        &format!(
+            "event: record.created\ndata: {{\"entity\":\"record\",\"id\":\"{id}\",\"action\":\"created\"}}"
+        ),
+    )
+    .await;
+
+    let response = request_with_auth_and_header(
+        Method::GET,
+        "/cards/events",
+        axum::http::HeaderName::from_static("last-event-id"),
+        "0-1",
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    read_sse_until(&mut response.into_body(), "event: reset").await;
 }
@@ -1,7 +1,16 @@
 use axum::http::{Method, StatusCode};
-use lunirelust::{common::dto::RestApiResponse, domains::luna::dto::EntityCountDto};
-
-use super::test_helpers::{deserialize_json_body, request_with_auth};
+use lunirelust::{
+    common::dto::RestApiResponse,
+    domains::luna::dto::{
+        DateCountDto, DirectorDto, EntityCountDto, LinkStatisticsDto, PaginatedResponse, RecordDto,
+        StatisticsOverviewDto,
+    },
+};
+
+use super::test_helpers::{
+    deserialize_json_body, register_viewer_token, request_with_auth, request_with_auth_and_body,
+    request_with_token_and_body,
+};
 
 /// Test getting director records count statistics
 #[tokio::test]
@@ -19,9 +28,10 @@ async fn test_get_director_records_count() {
         parts.status
     );
 
-    let response_body: RestApiResponse<Vec<EntityCountDto>> = deserialize_json_body(body)
-        .await
-        .expect("Failed to deserialize director records count response");
+    let response_body: RestApiResponse<PaginatedResponse<EntityCountDto>> =
+        deserialize_json_body(body)
+            .await
+            .expect("Failed to deserialize director records count response");
 
     assert_eq!(
         response_body.0.status,
@@ -32,7 +42,7 @@ async fn test_get_director_records_count() {
     let data = response_body.0.data.expect("Should have data in response");
 
     // Verify response structure - should be a list of EntityCountDto
-    for count_item in &data {
+    for count_item in &data.results {
         assert!(count_item.id >= 0, "ID should be non-negative");
         assert!(!count_item.name.is_empty(), "Name should not be empty");
         assert!(count_item.count >= 0, "Count should be non-negative");
@@ -40,7 +50,7 @@ async fn test_get_director_records_count() {
 
     println!(
         "Successfully retrieved director records count with {} entries",
-        data.len()
+        data.results.len()
     );
 }
 
@@ -60,9 +70,10 @@ async fn test_get_genre_records_count() {
         parts.status
     );
 
-    let response_body: RestApiResponse<Vec<EntityCountDto>> = deserialize_json_body(body)
-        .await
-        .expect("Failed to deserialize genre records count response");
+    let response_body: RestApiResponse<PaginatedResponse<EntityCountDto>> =
+        deserialize_json_body(body)
+            .await
+            .expect("Failed to deserialize genre records count response");
 
     assert_eq!(
         response_body.0.status,
@@ -73,7 +84,7 @@ async fn test_get_genre_records_count() {
     let data = response_body.0.data.expect("Should have data in response");
 
     // Verify response structure
-    for count_item in &data {
+    for count_item in &data.results {
         assert!(count_item.id >= 0, "ID should be non-negative");
         assert!(!count_item.name.is_empty(), "Name should not be empty");
         assert!(count_item.count >= 0, "Count should be non-negative");
@@ -81,7 +92,7 @@ async fn test_get_genre_records_count() {
 
     println!(
         "Successfully retrieved genre records count with {} entries",
-        data.len()
+        data.results.len()
     );
 }
 
@@ -101,9 +112,10 @@ async fn test_get_label_records_count() {
         parts.status
     );
 
-    let response_body: RestApiResponse<Vec<EntityCountDto>> = deserialize_json_body(body)
-        .await
-        .expect("Failed to deserialize label records count response");
+    let response_body: RestApiResponse<PaginatedResponse<EntityCountDto>> =
+        deserialize_json_body(body)
+            .await
+            .expect("Failed to deserialize label records count response");
 
     assert_eq!(
         response_body.0.status,
@@ -114,7 +126,7 @@ async fn test_get_label_records_count() {
     let data = response_body.0.data.expect("Should have data in response");
 
     // Verify response structure
-    for count_item in &data {
+    for count_item in &data.results {
         assert!(count_item.id >= 0, "ID should be non-negative");
         assert!(!count_item.name.is_empty(), "Name should not be empty");
         assert!(count_item.count >= 0, "Count should be non-negative");
@@ -122,7 +134,7 @@ async fn test_get_label_records_count() {
 
     println!(
         "Successfully retrieved label records count with {} entries",
-        data.len()
+        data.results.len()
     );
 }
 
@@ -142,9 +154,10 @@ async fn test_get_studio_records_count() {
         parts.status
     );
 
-    let response_body: RestApiResponse<Vec<EntityCountDto>> = deserialize_json_body(body)
-        .await
-        .expect("Failed to deserialize studio records count response");
+    let response_body: RestApiResponse<PaginatedResponse<EntityCountDto>> =
+        deserialize_json_body(body)
+            .await
+            .expect("Failed to deserialize studio records count response");
 
     assert_eq!(
         response_body.0.status,
@@ -155,7 +168,7 @@ async fn test_get_studio_records_count() {
     let data = response_body.0.data.expect("Should have data in response");
 
     // Verify response structure
-    for count_item in &data {
+    for count_item in &data.results {
         assert!(count_item.id >= 0, "ID should be non-negative");
         assert!(!count_item.name.is_empty(), "Name should not be empty");
         assert!(count_item.count >= 0, "Count should be non-negative");
@@ -163,7 +176,7 @@ async fn test_get_studio_records_count() {
 
     println!(
         "Successfully retrieved studio records count with {} entries",
-        data.len()
+        data.results.len()
     );
 }
 
@@ -183,9 +196,10 @@ async fn test_get_series_records_count() {
         parts.status
     );
 
-    let response_body: RestApiResponse<Vec<EntityCountDto>> = deserialize_json_body(body)
-        .await
-        .expect("Failed to deserialize series records count response");
+    let response_body: RestApiResponse<PaginatedResponse<EntityCountDto>> =
+        deserialize_json_body(body)
+            .await
+            .expect("Failed to deserialize series records count response");
 
     assert_eq!(
         response_body.0.status,
@@ -196,7 +210,7 @@ async fn test_get_series_records_count() {
     let data = response_body.0.data.expect("Should have data in response");
 
     // Verify response structure
-    for count_item in &data {
+    for count_item in &data.results {
         assert!(count_item.id >= 0, "ID should be non-negative");
         assert!(!count_item.name.is_empty(), "Name should not be empty");
         assert!(count_item.count >= 0, "Count should be non-negative");
@@ -204,7 +218,7 @@ async fn test_get_series_records_count() {
 
     println!(
         "Successfully retrieved series records count with {} entries",
-        data.len()
+        data.results.len()
     );
 }
 
@@ -224,9 +238,10 @@ async fn test_get_idol_records_count() {
         parts.status
     );
 
-    let response_body: RestApiResponse<Vec<EntityCountDto>> = deserialize_json_body(body)
-        .await
-        .expect("Failed to deserialize idol records count response");
+    let response_body: RestApiResponse<PaginatedResponse<EntityCountDto>> =
+        deserialize_json_body(body)
+            .await
+            .expect("Failed to deserialize idol records count response");
 
     assert_eq!(
         response_body.0.status,
@@ -237,7 +252,7 @@ async fn test_get_idol_records_count() {
     let data = response_body.0.data.expect("Should have data in response");
 
     // Verify response structure
-    for count_item in &data {
+    for count_item in &data.results {
         assert!(count_item.id >= 0, "ID should be non-negative");
         assert!(!count_item.name.is_empty(), "Name should not be empty");
         assert!(count_item.count >= 0, "Count should be non-negative");
@@ -245,7 +260,7 @@ async fn test_get_idol_records_count() {
 
     println!(
         "Successfully retrieved idol records count with {} entries",
-        data.len()
+        data.results.len()
     );
 }
 
@@ -275,7 +290,7 @@ async fn test_all_statistics_endpoints() {
             parts.status
         );
 
-        let response_body: Result<RestApiResponse<Vec<EntityCountDto>>, _> =
+        let response_body: Result<RestApiResponse<PaginatedResponse<EntityCountDto>>, _> =
             deserialize_json_body(body).await;
 
         assert!(
@@ -296,9 +311,352 @@ async fn test_all_statistics_endpoints() {
         println!(
             "Endpoint {} returned {} count entries",
             endpoint,
-            data.len()
+            data.results.len()
         );
     }
 
     println!("All statistics endpoints tested successfully!");
 }
+
+/// Test that directors without records are counted as zero and that counts
+/// come back in descending order
+#[tokio::test]
+async fn test_record_counts_include_unused_directors() {
+    let marker = uuid::Uuid::new_v4().simple().to_string();
+    let create_payload = serde_json::json!({
+        "name": format!("Unused Director {marker}"),
+        "link": "https://example.com/unused-director",
+        "manual": true
+    });
+    let response =
+        request_with_auth_and_body(Method::POST, "/cards/directors", &create_payload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let (_parts, body) = response.into_parts();
+    let created: RestApiResponse<DirectorDto> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize created director");
+    let created = created.0.data.expect("No created director data");
+
+    let response = request_with_auth(
+        Method::GET,
+        &format!("/cards/director-records-count?name={marker}"),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let (_parts, body) = response.into_parts();
+    let counts: RestApiResponse<PaginatedResponse<EntityCountDto>> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize director records count");
+    let counts = counts.0.data.expect("Should have data in response");
+    assert_eq!(
+        counts.count, 1,
+        "Only the new director matches the name filter"
+    );
+
+    let unused = counts
+        .results
+        .iter()
+        .find(|item| item.id == created.id)
+        .expect("New director should be listed");
+    assert_eq!(unused.count, 0);
+    assert_eq!(unused.name, created.name);
+}
+
+/// Test paging and `min_count` filtering of record counts
+#[tokio::test]
+async fn test_record_counts_paging_and_min_count() {
+    let response = request_with_auth(
+        Method::GET,
+        "/cards/genre-records-count?limit=2&offset=1&min_count=1",
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let (_parts, body) = response.into_parts();
+    let page: RestApiResponse<PaginatedResponse<EntityCountDto>> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize genre records count");
+    let page = page.0.data.expect("Should have data in response");
+
+    assert!(page.results.len() <= 2, "Page should honour the limit");
+    assert!(
+        page.results.iter().all(|item| item.count >= 1),
+        "Entities below min_count should be filtered out"
+    );
+    assert!(
+        page.results
+            .windows(2)
+            .all(|pair| pair[0].count >= pair[1].count),
+        "Counts should be sorted in descending order"
+    );
+    assert_eq!(page.previous.as_deref(), Some("?limit=2&offset=0"));
+    assert_eq!(page.next.is_some(), page.count > 3);
+
+    let response = request_with_auth(
+        Method::GET,
+        "/cards/genre-records-count?limit=2&offset=0&min_count=1",
+    )
+    .await;
+    let (_parts, body) = response.into_parts();
+    let first: RestApiResponse<PaginatedResponse<EntityCountDto>> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize genre records count");
+    let first = first.0.data.expect("Should have data in response");
+    assert_eq!(
+        first.count, page.count,
+        "Total should not depend on the page"
+    );
+    assert!(first.previous.is_none());
+}
+
+/// Test that the statistics overview covers 24 months and ranks top entities
+#[tokio::test]
+async fn test_statistics_overview() {
+    let response = request_with_auth(Method::GET, "/cards/statistics/overview").await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let (_parts, body) = response.into_parts();
+    let overview: RestApiResponse<StatisticsOverviewDto> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize statistics overview");
+    let overview = overview.0.data.expect("Should have data in response");
+
+    assert_eq!(overview.records_per_month.len(), 24);
+    assert!(
+        overview
+            .records_per_month
+            .windows(2)
+            .all(|pair| pair[0].month < pair[1].month),
+        "Months should be listed oldest first"
+    );
+    let recent: i64 = overview.records_per_month.iter().map(|m| m.count).sum();
+    assert!(recent <= overview.totals.records);
+
+    for top in [
+        &overview.top_genres,
+        &overview.top_idols,
+        &overview.top_studios,
+    ] {
+        assert!(top.len() <= 10);
+        assert!(top.iter().all(|item| item.id != 0 && item.count > 0));
+        assert!(top.windows(2).all(|pair| pair[0].count >= pair[1].count));
+    }
+    if let Some(average) = overview.average_duration {
+        assert!(average > 0.0);
+    }
+}
+
+/// Test that link statistics cover 24 months and count new links in the
+/// current month
+#[tokio::test]
+async fn test_link_statistics() {
+    let id = format!("link-stats-{}", uuid::Uuid::new_v4());
+    let payload = serde_json::json!([{
+        "id": id,
+        "title": "Link Statistics Record",
+        "date": "2025-08-11",
+        "duration": 60,
+        "director": null,
+        "studio": null,
+        "label": null,
+        "series": null,
+        "genres": [],
+        "idols": [],
+        "has_links": true,
+        "links": [
+            { "name": "sized", "size": "2.5", "date": null, "link": format!("https://example.com/{id}/sized"), "star": false },
+            { "name": "", "size": null, "date": null, "link": format!("https://example.com/{id}/unsized"), "star": false }
+        ],
+        "permission": 1,
+        "local_img_count": 0
+    }]);
+    let response = request_with_auth_and_body(Method::POST, "/cards/records/bulk", &payload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = request_with_auth(Method::GET, "/cards/statistics/links").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let (_parts, body) = response.into_parts();
+    let statistics: RestApiResponse<LinkStatisticsDto> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize link statistics");
+    let statistics = statistics.0.data.expect("Should have data in response");
+    assert_eq!(statistics.links_per_month.len(), 24);
+    assert!(
+        statistics
+            .links_per_month
+            .windows(2)
+            .all(|pair| pair[0].month < pair[1].month),
+        "Months should be listed oldest first"
+    );
+    let recent: i64 = statistics.links_per_month.iter().map(|m| m.count).sum();
+    assert!(recent <= statistics.total_count);
+    let this_month = statistics.links_per_month.last().expect("current month");
+    assert!(this_month.count >= 2);
+    assert!(this_month.size >= sea_orm::prelude::Decimal::new(25, 1));
+}
+
+/// Test that release-date buckets are contiguous, labelled by granularity and
+/// that an inverted range is rejected
+#[tokio::test]
+async fn test_records_by_date() {
+    let response = request_with_auth(
+        Method::GET,
+        "/cards/statistics/records-by-date?granularity=year&from=2000-01-01",
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let (_parts, body) = response.into_parts();
+    let buckets: RestApiResponse<Vec<DateCountDto>> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize records by date");
+    let buckets = buckets.0.data.expect("Should have data in response");
+    let years: Vec<i32> = buckets
+        .iter()
+        .map(|bucket| bucket.period.parse().expect("Year label should be numeric"))
+        .collect();
+    assert!(years.iter().all(|&year| year >= 2000));
+    assert!(years.windows(2).all(|pair| pair[1] == pair[0] + 1));
+
+    let response = request_with_auth(
+        Method::GET,
+        "/cards/statistics/records-by-date?from=2020-01-01&to=2019-01-01",
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = request_with_auth(
+        Method::GET,
+        "/cards/statistics/records-by-date?granularity=week",
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+/// Test that idols on the same record are co-stars and genres on the same
+/// record are related
+#[tokio::test]
+async fn test_co_occurrence() {
+    let marker = uuid::Uuid::new_v4().simple().to_string();
+    let named = |name: &str| {
+        serde_json::json!({
+            "name": format!("{name} {marker}"),
+            "link": format!("https://example.com/{name}/{marker}"),
+            "manual": true
+        })
+    };
+    let payload = serde_json::json!({
+        "id": format!("co-{marker}"),
+        "title": "Co-occurrence Record",
+        "date": "2025-08-11",
+        "duration": 7200,
+        "director": named("director"),
+        "studio": named("studio"),
+        "label": named("label"),
+        "series": named("series"),
+        "genres": [named("genre-a"), named("genre-b")],
+        "idols": [named("idol-a"), named("idol-b")],
+        "has_links": false,
+        "links": [],
+        "permission": 1,
+        "local_img_count": 0
+    });
+    let response = request_with_auth_and_body(Method::POST, "/cards/records", &payload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let (_parts, body) = response.into_parts();
+    let record: RestApiResponse<RecordDto> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize created record");
+    let record = record.0.data.expect("No created record data");
+
+    let idols: Vec<i64> = record.idols.iter().map(|p| p.idol.id).collect();
+    let genres: Vec<i64> = record.genres.iter().map(|g| g.genre.id).collect();
+    assert_eq!((idols.len(), genres.len()), (2, 2));
+
+    for (path, ids) in [
+        (format!("/cards/idols/{}/co-stars", idols[0]), &idols),
+        (format!("/cards/genres/{}/related", genres[0]), &genres),
+    ] {
+        let response = request_with_auth(Method::GET, &path).await;
+        assert_eq!(response.status(), StatusCode::OK, "GET {path}");
+        let (_parts, body) = response.into_parts();
+        let related: RestApiResponse<Vec<EntityCountDto>> = deserialize_json_body(body)
+            .await
+            .expect("Failed to deserialize co-occurrence response");
+        let related = related.0.data.expect("Should have data in response");
+
+        assert_eq!(related.len(), 1, "Only the other entity shares the record");
+        assert_eq!((related[0].id, related[0].count), (ids[1], 1));
+    }
+
+    let response = request_with_auth(Method::GET, "/cards/idols/999999999/co-stars").await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+/// Test that records above the caller's clearance do not make entities
+/// co-occur
+#[tokio::test]
+async fn test_co_occurrence_respects_permission() {
+    let marker = uuid::Uuid::new_v4().simple().to_string();
+    let named = |name: &str| {
+        serde_json::json!({
+            "name": format!("{name} {marker}"),
+            "link": format!("https://example.com/{name}/{marker}"),
+            "manual": true
+        })
+    };
+    let payload = serde_json::json!({
+        "id": format!("co-admin-{marker}"),
+        "title": "Admin-only Co-occurrence Record",
+        "date": "2025-08-12",
+        "duration": 7200,
+        "director": named("director"),
+        "studio": named("studio"),
+        "label": named("label"),
+        "series": named("series"),
+        "genres": [named("genre-a"), named("genre-b")],
+        "idols": [named("idol-a"), named("idol-b")],
+        "has_links": false,
+        "links": [],
+        "permission": 2,
+        "local_img_count": 0
+    });
+    let response = request_with_auth_and_body(Method::POST, "/cards/records", &payload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let (_parts, body) = response.into_parts();
+    let record: RestApiResponse<RecordDto> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize created record");
+    let record = record.0.data.expect("No created record data");
+
+    let token = register_viewer_token().await;
+    let empty = serde_json::json!({});
+    for path in [
+        format!("/cards/idols/{}/co-stars", record.idols[0].idol.id),
+        format!("/cards/genres/{}/related", record.genres[0].genre.id),
+    ] {
+        let response = request_with_token_and_body(Method::GET, &path, &token, &empty).await;
+        assert_eq!(response.status(), StatusCode::OK, "GET {path}");
+        let (_parts, body) = response.into_parts();
+        let related: RestApiResponse<Vec<EntityCountDto>> = deserialize_json_body(body)
+            .await
+            .expect("Failed to deserialize co-occurrence response");
+        assert!(
+            related
+                .0
+                .data
+                .expect("Should have data in response")
+                .is_empty(),
+            "A viewer does not see the admin-only record"
+        );
+
+        let response = request_with_auth(Method::GET, &path).await;
+        let (_parts, body) = response.into_parts();
+        let related: RestApiResponse<Vec<EntityCountDto>> = deserialize_json_body(body)
+            .await
+            .expect("Failed to deserialize co-occurrence response");
+        assert_eq!(
+            related.0.data.expect("Should have data in response").len(),
+            1
+        );
+    }
+}
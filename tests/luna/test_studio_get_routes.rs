@@ -1,10 +1,15 @@
 use axum::http::{Method, StatusCode};
 use lunirelust::{
     common::dto::RestApiResponse,
-    domains::luna::dto::{PaginatedResponse, StudioDto},
+    domains::luna::dto::{
+        LabelDto, PaginatedResponse, RecordDto, StudioChildrenDto, StudioDto, StudioRollupDto,
+    },
 };
 
-use super::test_helpers::{deserialize_json_body, request_with_auth, request_with_auth_and_body};
+use super::test_helpers::{
+    deserialize_json_body, register_viewer_token, request_with_auth, request_with_auth_and_body,
+    request_with_token_and_body,
+};
 
 /// Test getting all studios
 #[tokio::test]
@@ -227,3 +232,273 @@ async fn test_studio_deduplication() {
     );
     println!("Successfully verified studio deduplication works");
 }
+
+/// Response data of an editor request that must succeed
+async fn editor_data<T: serde::de::DeserializeOwned>(
+    method: Method,
+    uri: &str,
+    body: Option<serde_json::Value>,
+) -> T {
+    let response = match body {
+        Some(body) => request_with_auth_and_body(method, uri, &body).await,
+        None => request_with_auth(method, uri).await,
+    };
+    assert_eq!(response.status(), StatusCode::OK, "{uri}");
+    let body: RestApiResponse<T> = deserialize_json_body(response.into_body())
+        .await
+        .expect("Failed to deserialize response body");
+    body.0.data.expect("Response should have data")
+}
+
+fn hierarchy_record(id: &str, studio: Option<&str>, label: Option<&str>) -> serde_json::Value {
+    let named = |name: Option<&str>| name.map(|name| serde_json::json!({ "name": name }));
+    serde_json::json!({
+        "id": id,
+        "title": "Hierarchy Record",
+        "date": "2024-01-01",
+        "duration": 60,
+        "director": null,
+        "studio": named(studio),
+        "label": named(label),
+        "series": null,
+        "genres": [],
+        "idols": [],
+        "has_links": false,
+        "links": [],
+        "permission": 1,
+        "local_img_count": 0
+    })
+}
+
+/// Test that studios nest under studio groups, labels attach to studios, and
+/// the roll-up counts each record once across the hierarchy
+#[tokio::test]
+async fn test_studio_hierarchy_and_rollup() {
+    let suffix = uuid::Uuid::new_v4();
+    let studio_name = format!("Hierarchy Studio {suffix}");
+    let sub_name = format!("Hierarchy Sub Studio {suffix}");
+    let label_name = format!("Hierarchy Label {suffix}");
+    let payload = serde_json::json!([
+        hierarchy_record(
+            &format!("hier-a-{suffix}"),
+            Some(&studio_name),
+            Some(&label_name)
+        ),
+        hierarchy_record(&format!("hier-b-{suffix}"), Some(&sub_name), None),
+        hierarchy_record(&format!("hier-c-{suffix}"), None, Some(&label_name)),
+    ]);
+    let response = request_with_auth_and_body(Method::POST, "/cards/records/bulk", &payload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let first: RecordDto = editor_data(
+        Method::GET,
+        &format!("/cards/records/hier-a-{suffix}"),
+        None,
+    )
+    .await;
+    let second: RecordDto = editor_data(
+        Method::GET,
+        &format!("/cards/records/hier-b-{suffix}"),
+        None,
+    )
+    .await;
+    let (studio_id, label_id, sub_id) = (first.studio.id, first.label.id, second.studio.id);
+    let group: StudioDto = editor_data(
+        Method::POST,
+        "/cards/studios",
+        Some(serde_json::json!({ "name": format!("Hierarchy Group {suffix}") })),
+    )
+    .await;
+
+    let studio: StudioDto = editor_data(
+        Method::PUT,
+        &format!("/cards/studios/{studio_id}/parent"),
+        Some(serde_json::json!({ "parent_id": group.id })),
+    )
+    .await;
+    assert_eq!(studio.parent_id, Some(group.id));
+    let _: StudioDto = editor_data(
+        Method::PUT,
+        &format!("/cards/studios/{sub_id}/parent"),
+        Some(serde_json::json!({ "parent_id": studio_id })),
+    )
+    .await;
+    let label: LabelDto = editor_data(
+        Method::PUT,
+        &format!("/cards/labels/{label_id}/studio"),
+        Some(serde_json::json!({ "studio_id": studio_id })),
+    )
+    .await;
+    assert_eq!(label.studio_id, Some(studio_id));
+
+    let children: StudioChildrenDto = editor_data(
+        Method::GET,
+        &format!("/cards/studios/{studio_id}/children"),
+        None,
+    )
+    .await;
+    let studios: Vec<i64> = children.studios.iter().map(|s| s.id).collect();
+    let labels: Vec<i64> = children.labels.iter().map(|l| l.id).collect();
+    assert_eq!(studios, [sub_id]);
+    assert_eq!(labels, [label_id]);
+
+    let rollup: StudioRollupDto = editor_data(
+        Method::GET,
+        &format!("/cards/studios/{}/rollup", group.id),
+        None,
+    )
+    .await;
+    assert_eq!(rollup.record_count, 0);
+    assert_eq!(rollup.total_record_count, 3, "Each record is counted once");
+    assert_eq!(rollup.children.len(), 1);
+    let studio_rollup = &rollup.children[0];
+    assert_eq!(studio_rollup.id, studio_id);
+    assert_eq!(studio_rollup.record_count, 1);
+    assert_eq!(studio_rollup.total_record_count, 3);
+    assert_eq!(studio_rollup.labels.len(), 1);
+    assert_eq!(studio_rollup.labels[0].count, 2);
+    assert_eq!(studio_rollup.children[0].id, sub_id);
+    assert_eq!(studio_rollup.children[0].total_record_count, 1);
+
+    // Moving the studio to the top level takes its records out of the group
+    let _: StudioDto = editor_data(
+        Method::PUT,
+        &format!("/cards/studios/{studio_id}/parent"),
+        Some(serde_json::json!({ "parent_id": null })),
+    )
+    .await;
+    let rollup: StudioRollupDto = editor_data(
+        Method::GET,
+        &format!("/cards/studios/{}/rollup", group.id),
+        None,
+    )
+    .await;
+    assert_eq!(rollup.total_record_count, 0);
+    assert!(rollup.children.is_empty());
+}
+
+/// Test that cycles, placeholders, unknown studios and viewers are rejected
+#[tokio::test]
+async fn test_studio_hierarchy_errors() {
+    let suffix = uuid::Uuid::new_v4();
+    let parent: StudioDto = editor_data(
+        Method::POST,
+        "/cards/studios",
+        Some(serde_json::json!({ "name": format!("Cycle Parent {suffix}") })),
+    )
+    .await;
+    let child: StudioDto = editor_data(
+        Method::POST,
+        "/cards/studios",
+        Some(serde_json::json!({ "name": format!("Cycle Child {suffix}") })),
+    )
+    .await;
+    let _: StudioDto = editor_data(
+        Method::PUT,
+        &format!("/cards/studios/{}/parent", child.id),
+        Some(serde_json::json!({ "parent_id": parent.id })),
+    )
+    .await;
+
+    let parent_uri = format!("/cards/studios/{}/parent", parent.id);
+    for (body, status) in [
+        (
+            serde_json::json!({ "parent_id": child.id }),
+            StatusCode::BAD_REQUEST,
+        ),
+        (
+            serde_json::json!({ "parent_id": parent.id }),
+            StatusCode::BAD_REQUEST,
+        ),
+        (
+            serde_json::json!({ "parent_id": 0 }),
+            StatusCode::BAD_REQUEST,
+        ),
+        (
+            serde_json::json!({ "parent_id": 999_999_999 }),
+            StatusCode::NOT_FOUND,
+        ),
+    ] {
+        let response = request_with_auth_and_body(Method::PUT, &parent_uri, &body).await;
+        assert_eq!(response.status(), status, "{body}");
+    }
+
+    let response = request_with_auth_and_body(
+        Method::PUT,
+        "/cards/labels/999999999/studio",
+        &serde_json::json!({ "studio_id": parent.id }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let response = request_with_auth(Method::GET, "/cards/studios/999999999/rollup").await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let token = register_viewer_token().await;
+    let response = request_with_token_and_body(
+        Method::PUT,
+        &parent_uri,
+        &token,
+        &serde_json::json!({ "parent_id": null }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+/// Test looking up many studios at once: request order, each once, unknown IDs
+/// left out, and the ID count bounded
+#[tokio::test]
+async fn test_get_studios_by_ids() {
+    let marker = uuid::Uuid::new_v4();
+    let mut ids = Vec::new();
+    for name in ["First", "Second", "Third"] {
+        let studio = serde_json::json!({
+            "name": format!("Batch {name} {marker}"),
+            "link": format!("https://example.com/studio/{name}/{marker}"),
+            "manual": true
+        });
+        let response = request_with_auth_and_body(Method::POST, "/cards/studios", &studio).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let (_parts, body) = response.into_parts();
+        let created: RestApiResponse<StudioDto> = deserialize_json_body(body)
+            .await
+            .expect("Failed to deserialize studio");
+        ids.push(created.0.data.expect("No studio data").id);
+    }
+
+    let payload = serde_json::json!({
+        "ids": [ids[2], 999_999_999, ids[0], ids[2], -1, ids[1], ids[0]]
+    });
+    let response = request_with_auth_and_body(Method::POST, "/cards/studios/batch", &payload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let (_parts, body) = response.into_parts();
+    let found: RestApiResponse<Vec<StudioDto>> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize studios");
+    let found: Vec<i64> = found
+        .0
+        .data
+        .expect("No studios data")
+        .iter()
+        .map(|studio| studio.id)
+        .collect();
+    assert_eq!(
+        found,
+        [ids[2], ids[0], ids[1]],
+        "Found studios follow the request order; unknown and repeated IDs are dropped"
+    );
+
+    let missing = serde_json::json!({ "ids": [999_999_999] });
+    let response = request_with_auth_and_body(Method::POST, "/cards/studios/batch", &missing).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let (_parts, body) = response.into_parts();
+    let found: RestApiResponse<Vec<StudioDto>> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize studios");
+    assert!(found.0.data.expect("No studios data").is_empty());
+
+    for invalid in [Vec::new(), vec![ids[0]; 201]] {
+        let payload = serde_json::json!({ "ids": invalid });
+        let response =
+            request_with_auth_and_body(Method::POST, "/cards/studios/batch", &payload).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+}
@@ -0,0 +1,69 @@
+use axum::http::{Method, StatusCode};
+
+use lunirelust::common::dto::RestApiResponse;
+use lunirelust::domains::audit::dto::audit_dto::AuditLogDto;
+use lunirelust::domains::luna::dto::{DirectorDto, PaginatedResponse};
+mod test_helpers;
+use lunirelust::domains::user::dto::user_dto::UserDto;
+use test_helpers::{deserialize_json_body, request_with_auth, request_with_auth_and_body};
+
+async fn get_audit_logs(url: &str) -> PaginatedResponse<AuditLogDto> {
+    let response = request_with_auth(Method::GET, url).await;
+    let (parts, body) = response.into_parts();
+    assert_eq!(parts.status, StatusCode::OK);
+    let response_body: RestApiResponse<PaginatedResponse<AuditLogDto>> =
+        deserialize_json_body(body)
+            .await
+            .expect("Failed to deserialize audit response body");
+    response_body.0.data.expect("No audit data")
+}
+
+/// Test that create, update and delete of a director are each recorded with the caller as actor
+#[tokio::test]
+async fn test_audit_log_records_director_writes() {
+    let name = format!("Audit Director {}", uuid::Uuid::new_v4().simple());
+    let payload = serde_json::json!({ "name": name, "link": "", "manual": true });
+    let response = request_with_auth_and_body(Method::POST, "/cards/directors", &payload).await;
+    let (parts, body) = response.into_parts();
+    assert_eq!(parts.status, StatusCode::OK);
+    let created: RestApiResponse<DirectorDto> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize created director response");
+    let director = created.0.data.expect("No created director data");
+
+    let url = format!("/cards/directors/{}", director.id);
+    let patch = serde_json::json!({ "link": "https://example.com/audit" });
+    let response = request_with_auth_and_body(Method::PATCH, &url, &patch).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = request_with_auth(Method::DELETE, &url).await;
+    assert!(response.status().is_success());
+
+    let logs = get_audit_logs(&format!("/audit?entity=director&id={}", director.id)).await;
+    assert_eq!(logs.count, 3);
+    let actions: Vec<&str> = logs.results.iter().map(|l| l.action.as_str()).collect();
+    assert_eq!(actions, vec!["delete", "update", "create"]);
+
+    let response = request_with_auth(Method::GET, "/user/me").await;
+    let me: RestApiResponse<UserDto> = deserialize_json_body(response.into_body())
+        .await
+        .expect("Failed to deserialize current user");
+    let me = me.0.data.expect("No current user data");
+    assert!(logs.results.iter().all(|l| l.actor == me.id));
+
+    let update = &logs.results[1];
+    let diff = update.diff.as_ref().expect("update should carry a diff");
+    assert_eq!(diff["changes"]["link"], "https://example.com/audit");
+    assert_eq!(diff["result"]["link"], "https://example.com/audit");
+}
+
+/// Test that reads are not audited and `id` without `entity` is rejected
+#[tokio::test]
+async fn test_audit_log_query_validation() {
+    let response = request_with_auth(Method::GET, "/audit?id=1").await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = request_with_auth(Method::GET, "/cards/directors").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let logs = get_audit_logs("/audit?entity=director&id=-1").await;
+    assert_eq!(logs.count, 0);
+}
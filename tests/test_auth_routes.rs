@@ -1,10 +1,28 @@
 use axum::http::{Method, StatusCode};
 
-use lunirelust::common::{
-    dto::RestApiResponse,
-    jwt::{AuthBody, AuthPayload},
+use lunirelust::{
+    common::{
+        dto::RestApiResponse,
+        jwt::{AuthBody, AuthPayload},
+    },
+    domains::{
+        auth::{
+            dto::{
+                api_key_dto::{ApiKeyCreatedDto, ApiKeyDto},
+                totp_dto::{TotpChallengeDto, TotpEnrollmentDto},
+            },
+            totp,
+        },
+        device::dto::device_dto::DeviceDto,
+        user::dto::user_dto::UserDto,
+    },
+};
+use test_helpers::{
+    create_own_device, deserialize_json_body, login, register_test_user, register_viewer_token,
+    request_with_api_key, request_with_api_key_and_body, request_with_auth,
+    request_with_auth_and_body, request_with_body, request_with_token_and_body, TEST_CLIENT_ID,
+    TEST_CLIENT_SECRET, TEST_USER_ID,
 };
-use test_helpers::{deserialize_json_body, request_with_body, TEST_CLIENT_ID, TEST_CLIENT_SECRET};
 
 mod test_helpers;
 
@@ -78,3 +96,452 @@ async fn test_login_user_not_found() {
     println!("response_body.0.status: {:?}", response_body.0.status);
     println!("response_body.0.message: {:?}", response_body.0.message);
 }
+
+/// Test that a newly registered user is a viewer: reads succeed, card and user writes are forbidden
+#[tokio::test]
+async fn test_viewer_cannot_write() {
+    let token = register_viewer_token().await;
+
+    let empty = serde_json::json!({});
+    let response =
+        request_with_token_and_body(Method::GET, "/cards/directors", &token, &empty).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let director = serde_json::json!({ "name": "Viewer Director", "link": "", "manual": true });
+    let response =
+        request_with_token_and_body(Method::POST, "/cards/directors", &token, &director).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let url = format!("/user/{TEST_USER_ID}");
+    let response = request_with_token_and_body(Method::DELETE, &url, &token, &empty).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_api_key_authenticates_until_revoked() {
+    let payload = serde_json::json!({ "name": "scraper", "role": "viewer" });
+    let response = request_with_auth_and_body(Method::POST, "/api-keys", &payload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response_body: RestApiResponse<ApiKeyCreatedDto> =
+        deserialize_json_body(response.into_body())
+            .await
+            .expect("Failed to deserialize API key response body");
+    let created = response_body.0.data.expect("Failed to get API key data");
+    assert_eq!(created.key.role, "viewer");
+    assert!(created.api_key.contains(&created.key.prefix));
+
+    let response = request_with_api_key(Method::GET, "/user/me", &created.api_key).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // The key is scoped to `viewer` even though its owner is an admin.
+    let response =
+        request_with_api_key(Method::DELETE, "/cards/directors/1", &created.api_key).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let url = format!("/api-keys/{}", created.key.id);
+    let response = request_with_auth(Method::DELETE, &url).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response_body: RestApiResponse<ApiKeyDto> = deserialize_json_body(response.into_body())
+        .await
+        .expect("Failed to deserialize revoked API key");
+    let revoked = response_body.0.data.expect("Failed to get revoked API key");
+    assert!(revoked.revoked_at.is_some());
+
+    let response = request_with_api_key(Method::GET, "/user/me", &created.api_key).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_viewer_cannot_issue_elevated_api_key() {
+    let token = register_viewer_token().await;
+    let payload = serde_json::json!({ "name": "escalate", "role": "admin" });
+    let response = request_with_token_and_body(Method::POST, "/api-keys", &token, &payload).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_scoped_api_key_cannot_issue_broader_key() {
+    let payload = serde_json::json!({ "name": "scoped", "role": "viewer" });
+    let response = request_with_auth_and_body(Method::POST, "/api-keys", &payload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response_body: RestApiResponse<ApiKeyCreatedDto> =
+        deserialize_json_body(response.into_body())
+            .await
+            .expect("Failed to deserialize API key response body");
+    let scoped = response_body.0.data.expect("Failed to get API key data");
+
+    // The key's owner is an admin, but the key itself is only a viewer.
+    let payload = serde_json::json!({ "name": "escalate", "role": "admin" });
+    let response =
+        request_with_api_key_and_body(Method::POST, "/api-keys", &scoped.api_key, &payload).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let payload = serde_json::json!({ "name": "default" });
+    let response =
+        request_with_api_key_and_body(Method::POST, "/api-keys", &scoped.api_key, &payload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response_body: RestApiResponse<ApiKeyCreatedDto> =
+        deserialize_json_body(response.into_body())
+            .await
+            .expect("Failed to deserialize API key response body");
+    let minted = response_body.0.data.expect("Failed to get API key data");
+    assert_eq!(
+        minted.key.role, "viewer",
+        "The default role is the caller's"
+    );
+}
+
+#[tokio::test]
+async fn test_refresh_token_rotates() {
+    let credentials = register_test_user().await;
+    let first = login("/auth/login", &credentials).await;
+    assert!(!first.refresh_token.is_empty());
+
+    let payload = serde_json::json!({ "refresh_token": first.refresh_token });
+    let response = request_with_body(Method::POST, "/auth/refresh", &payload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response_body: RestApiResponse<AuthBody> = deserialize_json_body(response.into_body())
+        .await
+        .expect("Failed to deserialize refreshed tokens");
+    let second = response_body
+        .0
+        .data
+        .expect("Failed to get refreshed tokens");
+    assert_ne!(second.refresh_token, first.refresh_token);
+
+    let token = format!("{} {}", second.token_type, second.access_token);
+    let empty = serde_json::json!({});
+    let response = request_with_token_and_body(Method::GET, "/user/me", &token, &empty).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // The rotated-out refresh token can't be used again.
+    let response = request_with_body(Method::POST, "/auth/refresh", &payload).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let payload = serde_json::json!({ "refresh_token": second.refresh_token });
+    let response = request_with_body(Method::POST, "/auth/logout", &payload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = request_with_body(Method::POST, "/auth/refresh", &payload).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let response = request_with_token_and_body(Method::GET, "/user/me", &token, &empty).await;
+    assert_eq!(
+        response.status(),
+        StatusCode::UNAUTHORIZED,
+        "The access token is revoked with its session"
+    );
+}
+
+#[tokio::test]
+async fn test_logout_all_revokes_device_sessions() {
+    let credentials = register_test_user().await;
+    let account = login("/auth/login", &credentials).await;
+    let token = format!("{} {}", account.token_type, account.access_token);
+
+    let empty = serde_json::json!({});
+    let response = request_with_token_and_body(Method::GET, "/user/me", &token, &empty).await;
+    let me: RestApiResponse<UserDto> = deserialize_json_body(response.into_body())
+        .await
+        .expect("Failed to deserialize current user");
+    let me = me.0.data.expect("No current user data");
+
+    let device = serde_json::json!({
+        "name": "scraper-host",
+        "user_id": me.id,
+        "device_os": "Android",
+        "status": "active",
+        "registered_at": "2026-10-15T00:00:00Z",
+        "modified_by": me.id,
+    });
+    let response = request_with_token_and_body(Method::POST, "/device", &token, &device).await;
+    let device: RestApiResponse<DeviceDto> = deserialize_json_body(response.into_body())
+        .await
+        .expect("Failed to deserialize device");
+    let device = device.0.data.expect("No device data");
+
+    let on_device = login(
+        &format!("/auth/login?device_id={}", device.id),
+        &credentials,
+    )
+    .await;
+
+    let device_token = format!("{} {}", on_device.token_type, on_device.access_token);
+
+    let payload = serde_json::json!({ "refresh_token": account.refresh_token });
+    let response = request_with_body(Method::POST, "/auth/logout_all", &payload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    for access_token in [&token, &device_token] {
+        let response =
+            request_with_token_and_body(Method::GET, "/user/me", access_token, &empty).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    for refresh_token in [account.refresh_token, on_device.refresh_token] {
+        let payload = serde_json::json!({ "refresh_token": refresh_token });
+        let response = request_with_body(Method::POST, "/auth/refresh", &payload).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+}
+
+#[tokio::test]
+async fn test_failed_logins_lock_account_until_unlocked() {
+    let credentials = register_test_user().await;
+    let session = login("/auth/login", &credentials).await;
+    let token = format!("{} {}", session.token_type, session.access_token);
+    let empty = serde_json::json!({});
+    let response = request_with_token_and_body(Method::GET, "/user/me", &token, &empty).await;
+    let me: RestApiResponse<UserDto> = deserialize_json_body(response.into_body())
+        .await
+        .expect("Failed to deserialize current user");
+    let me = me.0.data.expect("No current user data");
+
+    let wrong = AuthPayload {
+        client_id: credentials.client_id.clone(),
+        client_secret: "not-the-password".to_owned(),
+    };
+    for _ in 0..5 {
+        let response = request_with_body(Method::POST, "/auth/login", &wrong).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    let response = request_with_body(Method::POST, "/auth/login", &credentials).await;
+    assert_eq!(response.status(), StatusCode::LOCKED);
+
+    let url = format!("/user/{}/unlock", me.id);
+    let response = request_with_auth(Method::POST, &url).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = request_with_body(Method::POST, "/auth/login", &credentials).await;
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_change_password_signs_out_other_sessions() {
+    let credentials = register_test_user().await;
+    let session = login("/auth/login", &credentials).await;
+    let token = format!("{} {}", session.token_type, session.access_token);
+    let other = login("/auth/login", &credentials).await;
+    let other_token = format!("{} {}", other.token_type, other.access_token);
+
+    let wrong = serde_json::json!({
+        "old_password": "not-the-password",
+        "new_password": "changed-password",
+    });
+    let response =
+        request_with_token_and_body(Method::POST, "/auth/password/change", &token, &wrong).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let change = serde_json::json!({
+        "old_password": credentials.client_secret,
+        "new_password": "changed-password",
+    });
+    let response =
+        request_with_token_and_body(Method::POST, "/auth/password/change", &token, &change).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response_body: RestApiResponse<AuthBody> = deserialize_json_body(response.into_body())
+        .await
+        .expect("Failed to deserialize new tokens");
+    let renewed = response_body.0.data.expect("Failed to get new tokens");
+
+    // Access tokens issued before the change stop working at once, including
+    // those of plain logins without a device.
+    let empty = serde_json::json!({});
+    for old in [&token, &other_token] {
+        let response = request_with_token_and_body(Method::GET, "/user/me", old, &empty).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+    let renewed_token = format!("{} {}", renewed.token_type, renewed.access_token);
+    let response =
+        request_with_token_and_body(Method::GET, "/user/me", &renewed_token, &empty).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let payload = serde_json::json!({ "refresh_token": session.refresh_token });
+    let response = request_with_body(Method::POST, "/auth/refresh", &payload).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let payload = serde_json::json!({ "refresh_token": renewed.refresh_token });
+    let response = request_with_body(Method::POST, "/auth/refresh", &payload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = request_with_body(Method::POST, "/auth/login", &credentials).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let changed = AuthPayload {
+        client_id: credentials.client_id,
+        client_secret: "changed-password".to_owned(),
+    };
+    let response = request_with_body(Method::POST, "/auth/login", &changed).await;
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_change_password_revokes_other_devices() {
+    let credentials = register_test_user().await;
+    let account = login("/auth/login", &credentials).await;
+    let token = format!("{} {}", account.token_type, account.access_token);
+
+    let mut devices = Vec::new();
+    let mut device_tokens = Vec::new();
+    for _ in 0..2 {
+        let device_id = create_own_device(&token).await;
+        let session = login(&format!("/auth/login?device_id={device_id}"), &credentials).await;
+        device_tokens.push(format!("{} {}", session.token_type, session.access_token));
+        devices.push(device_id);
+    }
+    let (current, other) = (&device_tokens[0], &device_tokens[1]);
+
+    let change = serde_json::json!({
+        "old_password": credentials.client_secret,
+        "new_password": "changed-password",
+    });
+    let response =
+        request_with_token_and_body(Method::POST, "/auth/password/change", current, &change).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let renewed: RestApiResponse<AuthBody> = deserialize_json_body(response.into_body())
+        .await
+        .expect("Failed to deserialize renewed tokens");
+    let renewed = renewed.0.data.expect("No renewed tokens");
+    assert_eq!(
+        renewed.refresh_token.split('.').nth(1),
+        Some(devices[0].as_str()),
+        "The renewed session stays on the caller's device"
+    );
+
+    let empty = serde_json::json!({});
+    let renewed_token = format!("{} {}", renewed.token_type, renewed.access_token);
+    let response =
+        request_with_token_and_body(Method::GET, "/user/me", &renewed_token, &empty).await;
+    assert_eq!(
+        response.status(),
+        StatusCode::OK,
+        "The device that changed the password stays signed in"
+    );
+    for old in [current, other] {
+        let response = request_with_token_and_body(Method::GET, "/user/me", old, &empty).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+}
+
+#[tokio::test]
+async fn test_password_reset_rejects_unknown_tokens() {
+    // Unknown addresses get the same answer as registered ones.
+    let payload = serde_json::json!({ "email": "nobody-registered@example.com" });
+    let response = request_with_body(Method::POST, "/auth/password/reset/request", &payload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    for token in ["not-a-token", "00000000-0000-0000-0000-000000000000.s3cret"] {
+        let payload = serde_json::json!({ "token": token, "new_password": "changed-password" });
+        let response =
+            request_with_body(Method::POST, "/auth/password/reset/confirm", &payload).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+}
+
+/// Decodes the unpadded base32 secret returned by TOTP enrollment.
+fn base32_decode(encoded: &str) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    let mut buffer = 0_u32;
+    let mut bits = 0;
+    for c in encoded.bytes() {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'2'..=b'7' => c - b'2' + 26,
+            _ => panic!("invalid base32 character {c}"),
+        };
+        buffer = (buffer << 5) | u32::from(value);
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push(((buffer >> bits) & 0xff) as u8);
+        }
+    }
+    bytes
+}
+
+/// Returns the TOTP code `steps` time steps from now.
+fn totp_code(enrollment: &TotpEnrollmentDto, steps: i64) -> String {
+    let secret = base32_decode(&enrollment.secret);
+    let step = totp::time_step(chrono::Utc::now()) + steps;
+    format!("{:06}", totp::code_at(&secret, step))
+}
+
+/// Registers a user, enrolls and enables TOTP, and returns the credentials,
+/// the access token and the enrollment. The current code is used up.
+async fn register_totp_user() -> (AuthPayload, String, TotpEnrollmentDto) {
+    let credentials = register_test_user().await;
+    let session = login("/auth/login", &credentials).await;
+    let token = format!("{} {}", session.token_type, session.access_token);
+
+    let empty = serde_json::json!({});
+    let response =
+        request_with_token_and_body(Method::POST, "/auth/totp/enroll", &token, &empty).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let enrollment: RestApiResponse<TotpEnrollmentDto> =
+        deserialize_json_body(response.into_body())
+            .await
+            .expect("Failed to deserialize TOTP enrollment");
+    let enrollment = enrollment.0.data.expect("No TOTP enrollment data");
+    assert!(enrollment.provisioning_uri.starts_with("otpauth://totp/"));
+
+    let payload = serde_json::json!({ "code": totp_code(&enrollment, 0) });
+    let response =
+        request_with_token_and_body(Method::POST, "/auth/totp/verify", &token, &payload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    (credentials, token, enrollment)
+}
+
+#[tokio::test]
+async fn test_totp_login_requires_code() {
+    let (credentials, _, enrollment) = register_totp_user().await;
+
+    let response = request_with_body(Method::POST, "/auth/login", &credentials).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let challenge: RestApiResponse<TotpChallengeDto> = deserialize_json_body(response.into_body())
+        .await
+        .expect("Failed to deserialize TOTP challenge");
+    let challenge = challenge.0.data.expect("No TOTP challenge data");
+    assert!(challenge.totp_required);
+
+    // The challenge token is not an access token.
+    let empty = serde_json::json!({});
+    let bearer = format!("Bearer {}", challenge.challenge_token);
+    let response = request_with_token_and_body(Method::GET, "/user/me", &bearer, &empty).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    // The code used to verify enrollment can't be replayed.
+    let replay = serde_json::json!({
+        "challenge_token": challenge.challenge_token,
+        "code": totp_code(&enrollment, 0),
+    });
+    let response = request_with_body(Method::POST, "/auth/login/totp", &replay).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let payload = serde_json::json!({
+        "challenge_token": challenge.challenge_token,
+        "code": totp_code(&enrollment, 1),
+    });
+    let response = request_with_body(Method::POST, "/auth/login/totp", &payload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response_body: RestApiResponse<AuthBody> = deserialize_json_body(response.into_body())
+        .await
+        .expect("Failed to deserialize auth response body");
+    let auth_body = response_body.0.data.expect("Failed to get auth body data");
+    assert!(!auth_body.access_token.is_empty());
+}
+
+#[tokio::test]
+async fn test_totp_disable_restores_password_login() {
+    let (credentials, token, enrollment) = register_totp_user().await;
+
+    let wrong = serde_json::json!({ "code": "12345" });
+    let response =
+        request_with_token_and_body(Method::POST, "/auth/totp/disable", &token, &wrong).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let payload = serde_json::json!({ "code": totp_code(&enrollment, 1) });
+    let response =
+        request_with_token_and_body(Method::POST, "/auth/totp/disable", &token, &payload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let auth_body = login("/auth/login", &credentials).await;
+    assert!(!auth_body.access_token.is_empty());
+}
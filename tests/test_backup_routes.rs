@@ -0,0 +1,64 @@
+use axum::http::{header::CONTENT_TYPE, Method, StatusCode};
+use http_body_util::BodyExt as _;
+
+mod test_helpers;
+use test_helpers::{
+    register_viewer_token, request_with_auth, request_with_auth_and_multipart,
+    request_with_token_and_body,
+};
+
+/// Test that backup and restore are admin-only
+#[tokio::test]
+async fn test_backup_routes_require_admin() {
+    let token = register_viewer_token().await;
+    let empty = serde_json::json!({});
+    for uri in ["/admin/backup", "/admin/restore"] {
+        let response = request_with_token_and_body(Method::POST, uri, &token, &empty).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN, "{uri}");
+    }
+}
+
+/// Test that a backup starts with a header and holds record rows, and that
+/// restoring it into a database that already has records is refused
+#[tokio::test]
+async fn test_backup_archive_and_restore_conflict() {
+    let response = request_with_auth(Method::POST, "/admin/backup").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok()),
+        Some("application/x-ndjson")
+    );
+    let body = response
+        .into_body()
+        .collect()
+        .await
+        .expect("Failed to read backup body")
+        .to_bytes();
+    let archive = String::from_utf8(body.to_vec()).expect("Archive should be UTF-8");
+
+    let mut lines = archive.lines();
+    let header: serde_json::Value =
+        serde_json::from_str(lines.next().expect("header line")).expect("header is JSON");
+    assert_eq!(header["kind"], "header");
+    assert_eq!(header["format"], "lunirelust-backup");
+    let tables: Vec<String> = lines
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).expect("line is JSON"))
+        .filter(|line| line["kind"] == "row")
+        .filter_map(|line| line["table"].as_str().map(str::to_owned))
+        .collect();
+    assert!(!tables.iter().any(|t| t == "seaql_migrations"));
+    let first = |table: &str| tables.iter().position(|t| t == table);
+    assert!(first("director") < first("record"));
+
+    let multipart = format!(
+        "------XYZ\r\nContent-Disposition: form-data; name=\"file\"; filename=\"backup.jsonl\"\r\n\
+         Content-Type: application/x-ndjson\r\n\r\n{archive}\r\n------XYZ--\r\n"
+    );
+    let response =
+        request_with_auth_and_multipart(Method::POST, "/admin/restore", multipart.into_bytes())
+            .await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+}
@@ -0,0 +1,110 @@
+use axum::{
+    body::Body,
+    http::{
+        header::{
+            ACCESS_CONTROL_ALLOW_CREDENTIALS, ACCESS_CONTROL_ALLOW_HEADERS,
+            ACCESS_CONTROL_ALLOW_METHODS, ACCESS_CONTROL_ALLOW_ORIGIN,
+            ACCESS_CONTROL_REQUEST_HEADERS, ACCESS_CONTROL_REQUEST_METHOD, ORIGIN,
+        },
+        HeaderMap, Method, Request, StatusCode,
+    },
+    Router,
+};
+use tower::ServiceExt as _;
+
+use lunirelust::{
+    app::create_router,
+    common::{
+        bootstrap::build_app_state,
+        config::{Config, CorsConfig},
+    },
+};
+
+mod test_helpers;
+use test_helpers::setup_test_db;
+
+const ALLOWED_ORIGIN: &str = "https://app.example.com";
+
+/// Builds a router with the given CORS settings on top of the test configuration.
+async fn cors_router(cors: CorsConfig) -> Router {
+    let pool = setup_test_db().await.expect("Failed to setup test db");
+    let mut config = Config::from_env().expect("Failed to load config");
+    config.cors = cors;
+    create_router(build_app_state(&pool, config))
+}
+
+fn cors_config(origins: &[&str], allow_credentials: bool) -> CorsConfig {
+    CorsConfig {
+        allowed_origins: origins.iter().map(|&o| o.to_owned()).collect(),
+        allowed_methods: vec!["GET".to_owned(), "post".to_owned(), "DELETE".to_owned()],
+        allowed_headers: vec!["authorization".to_owned(), "content-type".to_owned()],
+        allow_credentials,
+    }
+}
+
+/// Sends a preflight request and returns the status and response headers.
+async fn preflight(router: Router, origin: &str, method: &str) -> (StatusCode, HeaderMap) {
+    let request = Request::builder()
+        .method(Method::OPTIONS)
+        .uri("/user")
+        .header(ORIGIN, origin)
+        .header(ACCESS_CONTROL_REQUEST_METHOD, method)
+        .header(ACCESS_CONTROL_REQUEST_HEADERS, "authorization,content-type")
+        .body(Body::empty())
+        .expect("Failed to build preflight request");
+
+    let response = router.oneshot(request).await.expect("Preflight failed");
+    (response.status(), response.headers().clone())
+}
+
+fn header<'a>(headers: &'a HeaderMap, name: &axum::http::HeaderName) -> Option<&'a str> {
+    headers.get(name).and_then(|v| v.to_str().ok())
+}
+
+#[tokio::test]
+async fn test_preflight_allows_configured_origin() {
+    let router = cors_router(cors_config(&[ALLOWED_ORIGIN], true)).await;
+
+    let (status, headers) = preflight(router, ALLOWED_ORIGIN, "POST").await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        header(&headers, &ACCESS_CONTROL_ALLOW_ORIGIN),
+        Some(ALLOWED_ORIGIN)
+    );
+    assert_eq!(
+        header(&headers, &ACCESS_CONTROL_ALLOW_CREDENTIALS),
+        Some("true")
+    );
+
+    let methods = header(&headers, &ACCESS_CONTROL_ALLOW_METHODS).unwrap_or_default();
+    for method in ["GET", "POST", "DELETE"] {
+        assert!(methods.contains(method), "{method} missing from {methods}");
+    }
+    assert!(!methods.contains("PUT"), "PUT should not be allowed");
+
+    let allowed_headers = header(&headers, &ACCESS_CONTROL_ALLOW_HEADERS).unwrap_or_default();
+    assert!(allowed_headers.contains("authorization"));
+    assert!(allowed_headers.contains("content-type"));
+}
+
+#[tokio::test]
+async fn test_preflight_rejects_unknown_origin() {
+    let router = cors_router(cors_config(&[ALLOWED_ORIGIN], false)).await;
+
+    let (_, headers) = preflight(router, "https://evil.example.com", "POST").await;
+
+    assert!(headers.get(ACCESS_CONTROL_ALLOW_ORIGIN).is_none());
+    assert!(headers.get(ACCESS_CONTROL_ALLOW_CREDENTIALS).is_none());
+}
+
+#[tokio::test]
+async fn test_preflight_wildcard_origin_drops_credentials() {
+    let router = cors_router(cors_config(&["*"], true)).await;
+
+    let (status, headers) = preflight(router, "https://any.example.com", "GET").await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(header(&headers, &ACCESS_CONTROL_ALLOW_ORIGIN), Some("*"));
+    assert!(headers.get(ACCESS_CONTROL_ALLOW_CREDENTIALS).is_none());
+}
@@ -1,15 +1,20 @@
 use axum::http::{Method, StatusCode};
 
-use lunirelust::common::dto::RestApiResponse;
+use lunirelust::common::{
+    dto::{ErrorResponse, RestApiResponse},
+    error::ErrorCode,
+};
 use lunirelust::domains::device::dto::device_dto::{
-    CreateDeviceDto, DeviceDto, UpdateDeviceDto, UpdateDeviceDtoWithIdDto, UpdateManyDevicesDto,
+    CreateDeviceDto, DeviceDto, SessionDto, UpdateDeviceDto, UpdateDeviceDtoWithIdDto,
+    UpdateManyDevicesDto,
 };
 
 use lunirelust::domains::device::{DeviceOS, DeviceStatus};
 use uuid::Uuid;
 mod test_helpers;
 use test_helpers::{
-    deserialize_json_body, request_with_auth, request_with_auth_and_body, TEST_USER_ID,
+    create_own_device, deserialize_json_body, login, register_test_user, request_with_auth,
+    request_with_auth_and_body, request_with_body, request_with_token_and_body, TEST_USER_ID,
 };
 
 use chrono::{Duration, Utc};
@@ -177,13 +182,12 @@ async fn test_delete_device_not_found() {
 
     assert_eq!(parts.status, StatusCode::NOT_FOUND);
 
-    let response_body: RestApiResponse<()> = deserialize_json_body(body)
+    let response_body: ErrorResponse = deserialize_json_body(body)
         .await
         .expect("Failed to deserialize response body");
 
-    assert_eq!(response_body.0.status, StatusCode::NOT_FOUND);
-    // println!("response_body.0.status: {:?}", response_body.0.status);
-    // println!("response_body.0.message: {:?}", response_body.0.message);
+    assert_eq!(response_body.status, StatusCode::NOT_FOUND);
+    assert_eq!(response_body.code, ErrorCode::DeviceNotFound);
 }
 
 #[tokio::test]
@@ -248,3 +252,56 @@ async fn test_update_many_devices() {
     // println!("response_body.0.status: {:?}", response_body.0.status);
     // println!("response_body.0.message: {:?}", response_body.0.message);
 }
+
+#[tokio::test]
+async fn test_revoked_sessions_are_rejected() {
+    let credentials = register_test_user().await;
+    let account = login("/auth/login", &credentials).await;
+    let account_token = format!("{} {}", account.token_type, account.access_token);
+
+    let first_id = create_own_device(&account_token).await;
+    let second_id = create_own_device(&account_token).await;
+    let first = login(&format!("/auth/login?device_id={first_id}"), &credentials).await;
+    let first_token = format!("{} {}", first.token_type, first.access_token);
+    let second = login(&format!("/auth/login?device_id={second_id}"), &credentials).await;
+    let second_token = format!("{} {}", second.token_type, second.access_token);
+
+    let empty = serde_json::json!({});
+    let response =
+        request_with_token_and_body(Method::GET, "/sessions", &first_token, &empty).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let sessions: RestApiResponse<Vec<SessionDto>> = deserialize_json_body(response.into_body())
+        .await
+        .expect("Failed to deserialize sessions");
+    let sessions = sessions.0.data.expect("No session data");
+    assert_eq!(sessions.len(), 2);
+    let current = sessions
+        .iter()
+        .find(|session| session.current)
+        .expect("Current session is listed");
+    assert_eq!(current.device.id, first_id);
+    assert!(current.device.last_seen_at.is_some());
+
+    let url = format!("/sessions/{second_id}");
+    let response = request_with_token_and_body(Method::DELETE, &url, &first_token, &empty).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response =
+        request_with_token_and_body(Method::GET, "/user/me", &second_token, &empty).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    // Revoking the others from a device also signs out the account session.
+    let response = request_with_token_and_body(
+        Method::POST,
+        "/sessions/revoke_others",
+        &first_token,
+        &empty,
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let payload = serde_json::json!({ "refresh_token": account.refresh_token });
+    let response = request_with_body(Method::POST, "/auth/refresh", &payload).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let response = request_with_token_and_body(Method::GET, "/user/me", &first_token, &empty).await;
+    assert_eq!(response.status(), StatusCode::OK);
+}
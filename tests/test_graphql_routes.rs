@@ -0,0 +1,132 @@
+#![cfg(feature = "graphql")]
+
+use axum::http::{Method, StatusCode};
+use serde_json::{json, Value};
+
+mod test_helpers;
+use test_helpers::{
+    deserialize_json_body, register_viewer_token, request_with_auth_and_body,
+    request_with_token_and_body,
+};
+
+async fn graphql(query: &str, variables: Value) -> Value {
+    let payload = json!({ "query": query, "variables": variables });
+    let response = request_with_auth_and_body(Method::POST, "/graphql", &payload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    deserialize_json_body(response.into_body())
+        .await
+        .expect("Failed to deserialize GraphQL response")
+}
+
+const CREATE_RECORD: &str = "mutation ($input: RecordInput!) {
+    createRecord(input: $input) { id version director { name } genres { genre { name } } }
+}";
+
+/// Test record CRUD through `/graphql`, with nested relations resolved on a listing
+#[tokio::test]
+async fn test_graphql_record_crud() {
+    let id = format!("GQL-{}", uuid::Uuid::new_v4().simple());
+    let input = json!({
+        "id": id,
+        "title": "GraphQL record",
+        "date": "2024-01-02",
+        "duration": 90,
+        "director": { "name": format!("GQL Director {id}") },
+        "genres": [{ "name": format!("GQL Genre {id}") }],
+        "idols": [],
+        "hasLinks": true,
+        "links": [{ "link": "https://example.com/gql", "size": "1.5", "date": "2024-01-02" }],
+        "permission": 0,
+        "localImgCount": 0
+    });
+    let created = graphql(CREATE_RECORD, json!({ "input": input })).await;
+    assert_eq!(created["errors"], Value::Null, "{created}");
+    let record = &created["data"]["createRecord"];
+    assert_eq!(record["id"], id.as_str());
+    assert_eq!(record["director"]["name"], format!("GQL Director {id}"));
+    assert_eq!(
+        record["genres"][0]["genre"]["name"],
+        format!("GQL Genre {id}")
+    );
+
+    let listed = graphql(
+        "query ($id: String) {
+            records(filter: { id: $id }, limit: 10) {
+                count
+                results { id genres { genre { name } } links { link star } liked }
+            }
+        }",
+        json!({ "id": id }),
+    )
+    .await;
+    assert_eq!(listed["errors"], Value::Null, "{listed}");
+    let page = &listed["data"]["records"];
+    assert_eq!(page["count"], 1);
+    let result = &page["results"][0];
+    assert_eq!(
+        result["genres"][0]["genre"]["name"],
+        format!("GQL Genre {id}")
+    );
+    assert_eq!(result["links"][0]["link"], "https://example.com/gql");
+    assert_eq!(result["liked"], false);
+
+    let patched = graphql(
+        "mutation ($id: String!, $version: Int) {
+            patchRecord(id: $id, input: { title: \"Patched\" }, expectedVersion: $version) {
+                title version
+            }
+        }",
+        json!({ "id": id, "version": record["version"] }),
+    )
+    .await;
+    assert_eq!(patched["errors"], Value::Null, "{patched}");
+    assert_eq!(patched["data"]["patchRecord"]["title"], "Patched");
+
+    let stale = graphql(
+        "mutation ($id: String!, $version: Int) {
+            patchRecord(id: $id, input: { title: \"Stale\" }, expectedVersion: $version) { id }
+        }",
+        json!({ "id": id, "version": record["version"] }),
+    )
+    .await;
+    assert_eq!(stale["errors"][0]["extensions"]["status"], 412);
+    assert_eq!(stale["errors"][0]["extensions"]["code"], "VERSION_MISMATCH");
+
+    let deleted = graphql(
+        "mutation ($id: String!) { deleteRecord(id: $id) }",
+        json!({ "id": id }),
+    )
+    .await;
+    assert_eq!(deleted["data"]["deleteRecord"], id.as_str());
+
+    let fetched = graphql(
+        "query ($id: String!) { record(id: $id) { id } }",
+        json!({ "id": id }),
+    )
+    .await;
+    assert_eq!(fetched["errors"], Value::Null, "{fetched}");
+    assert_eq!(fetched["data"]["record"], Value::Null);
+}
+
+/// Test that viewers can query but not mutate
+#[tokio::test]
+async fn test_graphql_mutations_require_editor() {
+    let token = register_viewer_token().await;
+    let payload = json!({
+        "query": "mutation { deleteRecord(id: \"GQL-NOPE\") }",
+    });
+    let response = request_with_token_and_body(Method::POST, "/graphql", &token, &payload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: Value = deserialize_json_body(response.into_body())
+        .await
+        .expect("Failed to deserialize GraphQL response");
+    assert_eq!(body["errors"][0]["extensions"]["status"], 403);
+
+    let payload = json!({ "query": "{ directors { id name } }" });
+    let response = request_with_token_and_body(Method::POST, "/graphql", &token, &payload).await;
+    let body: Value = deserialize_json_body(response.into_body())
+        .await
+        .expect("Failed to deserialize GraphQL response");
+    assert_eq!(body["errors"], Value::Null, "{body}");
+    assert!(body["data"]["directors"].is_array());
+}
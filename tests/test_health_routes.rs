@@ -0,0 +1,75 @@
+use axum::http::{Method, StatusCode};
+
+use lunirelust::{
+    common::dto::RestApiResponse,
+    domains::health::dto::health_dto::{
+        DbPoolStats, HealthStatus, LivenessReport, ReadinessReport,
+    },
+};
+
+mod test_helpers;
+use test_helpers::{
+    deserialize_json_body, register_viewer_token, request, request_with_auth,
+    request_with_token_and_body,
+};
+
+/// Test that the liveness probe answers without credentials
+#[tokio::test]
+async fn test_liveness_probe() {
+    let response = request(Method::GET, "/healthz").await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let (_parts, body) = response.into_parts();
+    let report: RestApiResponse<LivenessReport> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize liveness report");
+    assert_eq!(
+        report.0.data.expect("No liveness data").status,
+        HealthStatus::Up
+    );
+}
+
+/// Test that the readiness probe reports every component of a healthy setup
+#[tokio::test]
+async fn test_readiness_probe() {
+    let response = request(Method::GET, "/readyz").await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let (_parts, body) = response.into_parts();
+    let report: RestApiResponse<ReadinessReport> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize readiness report");
+    let report = report.0.data.expect("No readiness data");
+    assert_eq!(report.status, HealthStatus::Up);
+    assert_eq!(report.database.status, HealthStatus::Up);
+    assert_eq!(report.assets.status, HealthStatus::Up);
+    assert_eq!(report.migrations.status, HealthStatus::Up);
+    assert!(report.migrations.applied > 0);
+    assert!(report.migrations.pending.is_empty());
+}
+
+/// Test that pool stats are admin-only and report the configured limits
+#[tokio::test]
+async fn test_db_pool_stats() {
+    let token = register_viewer_token().await;
+    let response = request_with_token_and_body(
+        Method::GET,
+        "/admin/db/stats",
+        &token,
+        &serde_json::json!({}),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let response = request_with_auth(Method::GET, "/admin/db/stats").await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let (_parts, body) = response.into_parts();
+    let stats: RestApiResponse<DbPoolStats> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize pool stats");
+    let stats = stats.0.data.expect("No pool stats");
+    assert!(stats.size <= stats.max_connections);
+    assert_eq!(stats.in_use, stats.size - stats.idle);
+    assert!(stats.acquire_timeout_secs > 0);
+}
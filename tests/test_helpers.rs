@@ -9,7 +9,7 @@ use axum::{
     body::Body,
     http::{
         header::{ACCEPT, AUTHORIZATION, CONTENT_TYPE},
-        Method, Request, Response, StatusCode,
+        HeaderName, HeaderValue, Method, Request, Response, StatusCode,
     },
     Router,
 };
@@ -64,8 +64,14 @@ pub async fn setup_test_db() -> Result<DatabaseConnection, Box<dyn std::error::E
 
 /// Helper function to create a test router
 pub async fn create_test_router() -> Router {
+    create_test_router_with(|_| {}).await
+}
+
+/// Helper function to create a test router whose config is adjusted by `configure`
+pub async fn create_test_router_with(configure: impl FnOnce(&mut Config)) -> Router {
     let pool = setup_test_db().await.expect("Failed to setup test db");
-    let config = Config::from_env().expect("Failed to load config");
+    let mut config = Config::from_env().expect("Failed to load config");
+    configure(&mut config);
     let state = build_app_state(&pool, config);
     create_router(state)
 }
@@ -81,7 +87,7 @@ async fn get_test_router() -> &'static Router {
 /// Helper function gets the authentication token
 /// for the test client
 /// This function is used to authenticate the test client
-async fn get_authentication_token() -> String {
+pub async fn get_authentication_token() -> String {
     let payload = AuthPayload {
         client_id: TEST_CLIENT_ID.to_owned(),
         client_secret: TEST_CLIENT_SECRET.to_owned(),
@@ -166,6 +172,24 @@ pub async fn request_with_auth(method: Method, uri: &str) -> Response<Body> {
     app.oneshot(request.await).await.unwrap()
 }
 
+/// Helper function to create an authenticated request with an extra header
+pub async fn request_with_auth_and_header(
+    method: Method,
+    uri: &str,
+    name: HeaderName,
+    value: &str,
+) -> Response<Body> {
+    let token = get_authentication_token().await;
+    let mut request = get_request_with_auth(method, uri, &token).await;
+    request.headers_mut().insert(
+        name,
+        HeaderValue::from_str(value).expect("Invalid header value"),
+    );
+    let app = get_test_router().await.clone();
+
+    app.oneshot(request).await.unwrap()
+}
+
 /// Helper function to create a request with authentication and a body
 pub async fn request_with_auth_and_body<T: serde::Serialize>(
     method: Method,
@@ -180,6 +204,141 @@ pub async fn request_with_auth_and_body<T: serde::Serialize>(
     app.oneshot(request.await).await.unwrap()
 }
 
+/// Helper function to create an authenticated request with an extra header and a body
+pub async fn request_with_auth_header_and_body<T: serde::Serialize>(
+    method: Method,
+    uri: &str,
+    name: HeaderName,
+    value: &str,
+    payload: &T,
+) -> Response<Body> {
+    let json_payload = serde_json::to_string(payload).expect("Failed to serialize payload");
+    let token = get_authentication_token().await;
+    let mut request = get_request_with_auth_and_body(method, uri, &token, &json_payload).await;
+    request.headers_mut().insert(
+        name,
+        HeaderValue::from_str(value).expect("Invalid header value"),
+    );
+    let app = get_test_router().await.clone();
+
+    app.oneshot(request).await.unwrap()
+}
+
+/// Helper function to create a request with an explicit `Authorization` token and a body
+pub async fn request_with_token_and_body<T: serde::Serialize>(
+    method: Method,
+    uri: &str,
+    token: &str,
+    payload: &T,
+) -> Response<Body> {
+    let json_payload = serde_json::to_string(payload).expect("Failed to serialize payload");
+    let request = get_request_with_auth_and_body(method, uri, token, &json_payload);
+    let app = get_test_router().await.clone();
+
+    app.oneshot(request.await).await.unwrap()
+}
+
+/// Helper function to create a request authenticated with an `X-Api-Key` header
+pub async fn request_with_api_key(method: Method, uri: &str, api_key: &str) -> Response<Body> {
+    let request = Request::builder()
+        .method(method)
+        .uri(uri.to_owned())
+        .header(CONTENT_TYPE, "application/json")
+        .header("X-Api-Key", api_key)
+        .header(ACCEPT, "application/json")
+        .body(axum::body::Body::empty())
+        .expect("Failed to create request");
+    let app = get_test_router().await.clone();
+
+    app.oneshot(request).await.unwrap()
+}
+
+/// Helper function to create a request authenticated with an `X-Api-Key` header and a body
+pub async fn request_with_api_key_and_body<T: serde::Serialize>(
+    method: Method,
+    uri: &str,
+    api_key: &str,
+    payload: &T,
+) -> Response<Body> {
+    let json_payload = serde_json::to_string(payload).expect("Failed to serialize payload");
+    let request = Request::builder()
+        .method(method)
+        .uri(uri.to_owned())
+        .header(CONTENT_TYPE, "application/json")
+        .header("X-Api-Key", api_key)
+        .header(ACCEPT, "application/json")
+        .body(Body::from(json_payload))
+        .expect("Failed to create request");
+    let app = get_test_router().await.clone();
+
+    app.oneshot(request).await.unwrap()
+}
+
+/// Registers a fresh account (which gets the default `viewer` role) and returns its credentials
+pub async fn register_test_user() -> AuthPayload {
+    let username = format!("viewer-{}", uuid::Uuid::new_v4().simple());
+    let password = "viewer-password".to_owned();
+    let register = serde_json::json!({
+        "username": username,
+        "email": format!("{username}@example.com"),
+        "password": password,
+    });
+    let response = request_with_body(Method::POST, "/auth/register", &register).await;
+    assert!(response.status().is_success());
+
+    AuthPayload {
+        client_id: username,
+        client_secret: password,
+    }
+}
+
+/// Logs in at `uri` (`/auth/login`, optionally with a query string) and returns the tokens
+pub async fn login(uri: &str, payload: &AuthPayload) -> AuthBody {
+    let response = request_with_body(Method::POST, uri, payload).await;
+    let response_body: RestApiResponse<AuthBody> = deserialize_json_body(response.into_body())
+        .await
+        .expect("Failed to deserialize auth response body");
+    response_body.0.data.expect("Failed to get auth body data")
+}
+
+/// Registers a fresh account (which gets the default `viewer` role) and returns its
+/// `Authorization` header value
+pub async fn register_viewer_token() -> String {
+    let auth_body = login("/auth/login", &register_test_user().await).await;
+    format!("{} {}", auth_body.token_type, auth_body.access_token)
+}
+
+/// Creates an active device owned by the caller of `token` and returns its ID
+pub async fn create_own_device(token: &str) -> String {
+    let empty = serde_json::json!({});
+    let response = request_with_token_and_body(Method::GET, "/user/me", token, &empty).await;
+    let me: RestApiResponse<serde_json::Value> = deserialize_json_body(response.into_body())
+        .await
+        .expect("Failed to deserialize current user");
+    let user_id = me.0.data.expect("No current user data")["id"]
+        .as_str()
+        .expect("User ID is a string")
+        .to_owned();
+
+    let device = serde_json::json!({
+        "name": format!("device-{}", uuid::Uuid::new_v4().simple()),
+        "user_id": user_id,
+        "device_os": "Android",
+        "status": "active",
+        "registered_at": "2026-10-15T00:00:00Z",
+        "modified_by": user_id,
+    });
+    let response = request_with_token_and_body(Method::POST, "/device", token, &device).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let device: RestApiResponse<serde_json::Value> = deserialize_json_body(response.into_body())
+        .await
+        .expect("Failed to deserialize device");
+    device.0.data.expect("No device data")["id"]
+        .as_str()
+        .expect("Device ID is a string")
+        .to_owned()
+}
+
 /// Helper function to create a request with authentication and multipart data
 pub async fn request_with_auth_and_multipart(
     method: Method,
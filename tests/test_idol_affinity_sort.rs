@@ -148,6 +148,9 @@ fn pagination(limit: i64, offset: i64) -> PaginationQuery {
         offset: Some(offset),
         liked_only: None,
         viewed_only: None,
+        seen: None,
+        ordering: None,
+        cursor: None,
     }
 }
 
@@ -0,0 +1,86 @@
+use axum::http::{Method, StatusCode};
+use lunirelust::common::dto::RestApiResponse;
+use lunirelust::common::jobs::{JobDto, JobRunDto, JobRunStatus, JobTrigger};
+use std::time::Duration;
+
+mod test_helpers;
+use test_helpers::{
+    deserialize_json_body, register_viewer_token, request_with_auth, request_with_token_and_body,
+};
+
+/// Response data of an admin request that must succeed
+async fn admin_data<T: serde::de::DeserializeOwned>(method: Method, uri: &str) -> T {
+    let response = request_with_auth(method, uri).await;
+    assert_eq!(response.status(), StatusCode::OK, "{uri}");
+    let body: RestApiResponse<T> = deserialize_json_body(response.into_body())
+        .await
+        .expect("Failed to deserialize response body");
+    body.0.data.expect("Response should have data")
+}
+
+/// Test that a job can be listed, triggered and followed to the end of its run
+#[tokio::test]
+async fn test_trigger_job_and_inspect_run() {
+    let jobs: Vec<JobDto> = admin_data(Method::GET, "/admin/jobs").await;
+    assert!(jobs.iter().any(|job| job.name == "media_gc"));
+
+    // A scheduled or earlier run may still be going
+    let mut started = None;
+    for _ in 0..50 {
+        let response = request_with_auth(Method::POST, "/admin/jobs/media_gc/run").await;
+        if response.status() == StatusCode::CONFLICT {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            continue;
+        }
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: RestApiResponse<JobRunDto> = deserialize_json_body(response.into_body())
+            .await
+            .expect("Failed to deserialize job run");
+        started = body.0.data;
+        break;
+    }
+    let started = started.expect("Job should start");
+    assert_eq!(started.name, "media_gc");
+    assert_eq!(started.trigger, JobTrigger::Manual);
+
+    let uri = format!("/admin/job-runs/{}", started.id);
+    let mut run: JobRunDto = admin_data(Method::GET, &uri).await;
+    for _ in 0..100 {
+        if run.status != JobRunStatus::Running {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        run = admin_data(Method::GET, &uri).await;
+    }
+    assert_eq!(run.status, JobRunStatus::Succeeded, "{:?}", run.error);
+    assert!(run.finished_at.is_some());
+    assert!(run.summary.is_some());
+
+    let runs: Vec<JobRunDto> = admin_data(Method::GET, "/admin/jobs/media_gc/runs?limit=5").await;
+    assert!(runs.iter().any(|r| r.id == started.id));
+}
+
+/// Test that unknown jobs and runs are not found and that jobs are admin-only
+#[tokio::test]
+async fn test_job_routes_errors() {
+    let response = request_with_auth(Method::POST, "/admin/jobs/no_such_job/run").await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let response = request_with_auth(Method::GET, "/admin/jobs/no_such_job/runs").await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let response = request_with_auth(Method::GET, "/admin/job-runs/0").await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let response = request_with_auth(Method::GET, "/admin/jobs/media_gc/runs?limit=0").await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let token = register_viewer_token().await;
+    let empty = serde_json::json!({});
+    for (method, uri) in [
+        (Method::GET, "/admin/jobs"),
+        (Method::POST, "/admin/jobs/media_gc/run"),
+        (Method::GET, "/admin/jobs/media_gc/runs"),
+        (Method::GET, "/admin/job-runs/1"),
+    ] {
+        let response = request_with_token_and_body(method, uri, &token, &empty).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN, "{uri}");
+    }
+}
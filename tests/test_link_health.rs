@@ -0,0 +1,138 @@
+use axum::{
+    http::{Method, StatusCode},
+    routing::get,
+    Router,
+};
+use lunirelust::common::dto::RestApiResponse;
+use lunirelust::domains::luna::dto::{LinkDto, PaginatedResponse};
+use tokio::net::TcpListener;
+
+mod test_helpers;
+use test_helpers::{
+    deserialize_json_body, register_viewer_token, request_with_auth, request_with_auth_and_body,
+    request_with_token_and_body,
+};
+
+/// Serves `/ok`, `/get-only` (which refuses `HEAD`) and `404` for anything
+/// else; returns its base URL.
+async fn spawn_link_server() -> String {
+    let app = Router::new().route("/ok", get(|| async { "ok" })).route(
+        "/get-only",
+        get(|| async { "ok" }).head(|| async { StatusCode::METHOD_NOT_ALLOWED }),
+    );
+    let listener = TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("Failed to bind link server");
+    let addr = listener.local_addr().expect("Link server address");
+    tokio::spawn(async move { axum::serve(listener, app).await });
+    format!("http://{addr}")
+}
+
+/// Response data of an editor request that must succeed
+async fn editor_data<T: serde::de::DeserializeOwned>(method: Method, uri: &str) -> T {
+    let response = request_with_auth(method, uri).await;
+    assert_eq!(response.status(), StatusCode::OK, "{uri}");
+    let body: RestApiResponse<T> = deserialize_json_body(response.into_body())
+        .await
+        .expect("Failed to deserialize response body");
+    body.0.data.expect("Response should have data")
+}
+
+fn link(url: &str) -> serde_json::Value {
+    serde_json::json!({ "name": "link", "size": "1.0", "date": null, "link": url, "star": false })
+}
+
+/// Test that checking a record's links records their status, skips non-http
+/// links and lists the dead ones
+#[tokio::test]
+async fn test_check_record_links() {
+    let base = spawn_link_server().await;
+    let id = format!("links-{}", uuid::Uuid::new_v4());
+    let gone = format!("{base}/gone/{id}");
+    let payload = serde_json::json!([{
+        "id": id,
+        "title": "Link Health Record",
+        "date": "2025-08-11",
+        "duration": 60,
+        "director": null,
+        "studio": null,
+        "label": null,
+        "series": null,
+        "genres": [],
+        "idols": [],
+        "has_links": true,
+        "links": [
+            link(&format!("{base}/ok")),
+            link(&format!("{base}/get-only")),
+            link(&gone),
+            link("magnet:?xt=urn:btih:0123456789abcdef")
+        ],
+        "permission": 1,
+        "local_img_count": 0
+    }]);
+    let response = request_with_auth_and_body(Method::POST, "/cards/records/bulk", &payload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let links: Vec<LinkDto> =
+        editor_data(Method::POST, &format!("/cards/records/{id}/links/check")).await;
+    assert_eq!(links.len(), 4);
+    let find = |suffix: &str| {
+        links
+            .iter()
+            .find(|l| l.link.ends_with(suffix))
+            .expect("link present")
+    };
+    let ok = find("/ok");
+    assert!(!ok.dead);
+    assert_eq!(ok.last_status, Some(200));
+    assert!(ok.last_checked_at.is_some());
+    let get_only = find("/get-only");
+    assert!(!get_only.dead, "GET is tried when HEAD is not allowed");
+    assert_eq!(get_only.last_status, Some(200));
+    let dead = find(&id);
+    assert!(dead.dead);
+    assert_eq!(dead.last_status, Some(404));
+    let magnet = find("abcdef");
+    assert!(!magnet.dead);
+    assert!(magnet.last_checked_at.is_none(), "magnet links are skipped");
+
+    let dead_links: PaginatedResponse<LinkDto> =
+        editor_data(Method::GET, "/cards/links/dead?limit=1000").await;
+    assert!(dead_links.results.iter().any(|l| l.id == dead.id));
+    assert!(dead_links.results.iter().all(|l| l.dead));
+
+    let checked: LinkDto =
+        editor_data(Method::POST, &format!("/cards/links/{}/check", ok.id)).await;
+    assert_eq!(checked.id, ok.id);
+    assert!(!checked.dead);
+
+    let response =
+        request_with_auth(Method::POST, &format!("/cards/links/{}/check", magnet.id)).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+/// Test that link checks are for editors and report unknown links and records
+#[tokio::test]
+async fn test_link_check_errors() {
+    let response = request_with_auth(Method::POST, "/cards/links/999999999/check").await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let response = request_with_auth(
+        Method::POST,
+        &format!(
+            "/cards/records/missing-{}/links/check",
+            uuid::Uuid::new_v4()
+        ),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let token = register_viewer_token().await;
+    let response = request_with_token_and_body(
+        Method::GET,
+        "/cards/links/dead",
+        &token,
+        &serde_json::json!({}),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
@@ -0,0 +1,237 @@
+#![cfg(feature = "metadata")]
+
+use std::sync::Arc;
+
+use axum::{extract::Path, http::StatusCode, routing::get, Json, Router};
+use lunirelust::common::config::{Config, MetadataProviderConfig};
+use lunirelust::common::error::AppError;
+use lunirelust::domains::luna::dto::{CreateRecordDto, RecordDto, RefreshMetadataQuery};
+use lunirelust::domains::luna::{LunaService, LunaServiceTrait};
+use lunirelust::entities::record_genre;
+use regex::Regex;
+use sea_orm::{ActiveModelTrait as _, ColumnTrait as _, EntityTrait as _, QueryFilter as _, Set};
+use tokio::net::TcpListener;
+
+mod test_helpers;
+use test_helpers::{
+    register_viewer_token, request_with_auth, request_with_token_and_body, TEST_USER_ID,
+};
+
+/// Serves metadata for `meta-*` IDs, naming its genres and idol after the
+/// ID, and `404` for anything else.
+async fn spawn_fake_provider() -> String {
+    let app = Router::new().route(
+        "/records/{id}",
+        get(|Path(id): Path<String>| async move {
+            if !id.starts_with("meta-") {
+                return Err(StatusCode::NOT_FOUND);
+            }
+            Ok(Json(serde_json::json!({
+                "title": "Fetched Title",
+                "date": "2024-05-01",
+                "duration": 95,
+                "genres": [format!("{id}-drama"), format!("{id}-comedy")],
+                "idols": [format!("{id}-aoi")],
+                "cover": format!("https://covers.example/{id}.jpg")
+            })))
+        }),
+    );
+    let listener = TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("Failed to bind fake provider");
+    let addr = listener.local_addr().expect("Fake provider address");
+    tokio::spawn(async move { axum::serve(listener, app).await });
+    format!("http://{addr}/records/{{id}}")
+}
+
+/// Luna service asking only the fake provider, for IDs starting with
+/// `meta-` or `missing-`.
+async fn luna_with_fake_provider() -> Arc<dyn LunaServiceTrait> {
+    let url = spawn_fake_provider().await;
+    let db = test_helpers::setup_test_db()
+        .await
+        .expect("Failed to setup test db");
+    let mut config = Config::from_env().expect("Failed to load config");
+    config.metadata.providers = vec![MetadataProviderConfig {
+        name: "fake".to_owned(),
+        url,
+        id_pattern: Regex::new("^(meta|missing)-.*$").expect("regex"),
+    }];
+    config.metadata.timeout_secs = 5;
+    LunaService::create_service(config, db)
+}
+
+fn record_payload(id: &str, genres: &[String]) -> CreateRecordDto {
+    let genres: Vec<serde_json::Value> = genres
+        .iter()
+        .map(|name| serde_json::json!({ "name": name }))
+        .collect();
+    serde_json::from_value(serde_json::json!({
+        "id": id,
+        "title": "Curated Title",
+        "date": "2020-01-01",
+        "duration": 0,
+        "director": null,
+        "studio": null,
+        "label": null,
+        "series": null,
+        "genres": genres,
+        "idols": [],
+        "has_links": false,
+        "links": [],
+        "permission": 1,
+        "local_img_count": 0
+    }))
+    .expect("valid record payload")
+}
+
+fn genre_names(record: &RecordDto) -> Vec<String> {
+    let mut names: Vec<String> = record.genres.iter().map(|g| g.genre.name.clone()).collect();
+    names.sort();
+    names
+}
+
+/// Test that fetched metadata fills blank fields, replaces automatic
+/// associations and keeps manual ones, and overwrites only on request
+#[tokio::test]
+async fn test_refresh_metadata_merges_fetched_fields() {
+    let luna = luna_with_fake_provider().await;
+    let records = luna.record_service();
+    let id = format!("meta-{}", uuid::Uuid::new_v4());
+    let curated = format!("{id}-curated");
+    let stale = format!("{id}-stale");
+    records
+        .create_record(record_payload(&id, &[curated.clone(), stale]), TEST_USER_ID)
+        .await
+        .expect("Failed to create record");
+
+    // Curators marked one genre by hand
+    let db = test_helpers::setup_test_db()
+        .await
+        .expect("Failed to setup test db");
+    let record = records.get_record_by_id(&id).await.expect("record");
+    let curated_genre_id = record
+        .genres
+        .iter()
+        .find(|g| g.genre.name == curated)
+        .expect("curated genre")
+        .genre
+        .id;
+    let mut manual: record_genre::ActiveModel = record_genre::Entity::find()
+        .filter(record_genre::Column::RecordId.eq(&id))
+        .filter(record_genre::Column::GenreId.eq(curated_genre_id))
+        .one(&db)
+        .await
+        .expect("Failed to load curated genre row")
+        .expect("curated genre row")
+        .into();
+    manual.manual = Set(true);
+    manual
+        .update(&db)
+        .await
+        .expect("Failed to mark genre manual");
+
+    let refreshed = records
+        .refresh_metadata(&id, RefreshMetadataQuery::default(), None, TEST_USER_ID)
+        .await
+        .expect("Failed to refresh metadata");
+    assert_eq!(refreshed.sources, ["fake"]);
+    assert_eq!(refreshed.updated, ["duration", "genres", "idols"]);
+    assert_eq!(
+        refreshed.fetched.cover.as_deref(),
+        Some(format!("https://covers.example/{id}.jpg").as_str())
+    );
+    let record = &refreshed.record;
+    assert_eq!(record.title, "Curated Title", "titles are kept by default");
+    assert_eq!(record.date.to_string(), "2020-01-01");
+    assert_eq!(record.duration, 95, "a zero duration is filled in");
+    let mut expected = vec![
+        format!("{id}-comedy"),
+        curated.clone(),
+        format!("{id}-drama"),
+    ];
+    expected.sort();
+    assert_eq!(genre_names(record), expected, "the stale genre is replaced");
+    assert!(record
+        .genres
+        .iter()
+        .any(|g| g.genre.name == curated && g.manual));
+    let idols: Vec<&str> = record.idols.iter().map(|i| i.idol.name.as_str()).collect();
+    assert_eq!(idols, [format!("{id}-aoi").as_str()]);
+
+    let overwritten = records
+        .refresh_metadata(
+            &id,
+            RefreshMetadataQuery { overwrite: true },
+            Some(record.version),
+            TEST_USER_ID,
+        )
+        .await
+        .expect("Failed to overwrite metadata");
+    assert_eq!(overwritten.updated, ["title", "date"]);
+    assert_eq!(overwritten.record.title, "Fetched Title");
+    assert_eq!(overwritten.record.date.to_string(), "2024-05-01");
+    assert_eq!(genre_names(&overwritten.record), expected);
+
+    let stale_version = records
+        .refresh_metadata(
+            &id,
+            RefreshMetadataQuery::default(),
+            Some(record.version),
+            TEST_USER_ID,
+        )
+        .await;
+    assert!(matches!(
+        stale_version,
+        Err(AppError::PreconditionFailed(_))
+    ));
+}
+
+/// Test that records no provider handles or knows are not found
+#[tokio::test]
+async fn test_refresh_metadata_unknown_records() {
+    let luna = luna_with_fake_provider().await;
+    let records = luna.record_service();
+    for prefix in ["missing", "other"] {
+        let id = format!("{prefix}-{}", uuid::Uuid::new_v4());
+        records
+            .create_record(record_payload(&id, &[]), TEST_USER_ID)
+            .await
+            .expect("Failed to create record");
+        let result = records
+            .refresh_metadata(&id, RefreshMetadataQuery::default(), None, TEST_USER_ID)
+            .await;
+        assert!(
+            matches!(result, Err(AppError::NotFound(_))),
+            "{prefix} records have no metadata"
+        );
+    }
+    let result = records
+        .refresh_metadata(
+            "meta-does-not-exist",
+            RefreshMetadataQuery::default(),
+            None,
+            TEST_USER_ID,
+        )
+        .await;
+    assert!(matches!(result, Err(AppError::NotFound(_))));
+}
+
+/// Test that the route is for editors and finds nothing without providers
+#[tokio::test]
+async fn test_refresh_metadata_route() {
+    let id = format!("meta-{}", uuid::Uuid::new_v4());
+    let uri = format!("/cards/records/{id}/refresh-metadata");
+    let response = request_with_auth(axum::http::Method::POST, &uri).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let token = register_viewer_token().await;
+    let response = request_with_token_and_body(
+        axum::http::Method::POST,
+        &uri,
+        &token,
+        &serde_json::json!({}),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
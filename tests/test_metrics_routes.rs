@@ -0,0 +1,44 @@
+#![cfg(feature = "metrics")]
+
+use axum::http::{header::CONTENT_TYPE, Method, StatusCode};
+use http_body_util::BodyExt as _;
+
+mod test_helpers;
+use test_helpers::request;
+
+/// Test that the scrape endpoint answers without credentials and labels
+/// requests by their route template
+#[tokio::test]
+async fn test_metrics_endpoint() {
+    let response = request(Method::GET, "/healthz").await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = request(Method::GET, "/metrics").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let content_type = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default()
+        .to_owned();
+    assert!(
+        content_type.starts_with("text/plain"),
+        "unexpected content type {content_type}"
+    );
+
+    let body = response
+        .into_body()
+        .collect()
+        .await
+        .expect("Failed to read metrics body")
+        .to_bytes();
+    let body = String::from_utf8(body.to_vec()).expect("Metrics are not UTF-8");
+    assert!(
+        body.contains(r#"route="/healthz""#),
+        "probe request was not counted"
+    );
+    assert!(
+        body.contains("lunirelust_db_pool_connections"),
+        "pool statistics missing"
+    );
+}
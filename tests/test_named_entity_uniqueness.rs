@@ -0,0 +1,228 @@
+//! Integration tests for the unique `(name, link, manual)` index on named
+//! entities: the migration that collapses existing duplicates before adding
+//! it, and find-or-create under concurrent writers.
+
+use lunirelust::domains::luna::{dto::CreateIdolDto, IdolRepo, IdolRepository as _};
+use migration::{MigrationName as _, MigratorTrait as _, SchemaManager};
+use sea_orm::{
+    ConnectionTrait as _, DatabaseBackend, DatabaseTransaction, Statement, TransactionTrait as _,
+};
+
+mod test_helpers;
+use test_helpers::setup_test_db;
+
+const DEDUPE_MIGRATION: &str = "m20261015_000001_unique_named_entities";
+
+async fn exec(txn: &DatabaseTransaction, sql: &str) {
+    txn.execute_unprepared(sql)
+        .await
+        .unwrap_or_else(|err| panic!("Failed to run `{sql}`: {err}"));
+}
+
+/// Rows of `sql` as `(text, bigint)` pairs read from its first two columns.
+async fn pairs(txn: &DatabaseTransaction, sql: &str) -> Vec<(String, i64)> {
+    txn.query_all(Statement::from_string(DatabaseBackend::Postgres, sql))
+        .await
+        .expect("Failed to query")
+        .iter()
+        .map(|row| {
+            (
+                row.try_get_by_index(0).expect("text column"),
+                row.try_get_by_index(1).expect("bigint column"),
+            )
+        })
+        .collect()
+}
+
+/// Test that the migration re-points record and junction references at the
+/// surviving entity before deleting duplicates and adding the unique index.
+/// Runs against a throwaway schema inside a transaction that is rolled back.
+#[tokio::test]
+async fn test_dedupe_migration_repoints_references() {
+    let db = setup_test_db().await.expect("Failed to setup test db");
+    let txn = db.begin().await.expect("Failed to begin transaction");
+    let schema = format!("dedupe_test_{}", uuid::Uuid::new_v4().simple());
+    exec(&txn, &format!("CREATE SCHEMA {schema}")).await;
+    exec(&txn, &format!("SET LOCAL search_path TO {schema}")).await;
+    for table in ["director", "studio", "label", "series", "genre", "idol"] {
+        exec(
+            &txn,
+            &format!(
+                "CREATE TABLE {table} (
+                    id bigint PRIMARY KEY, name text NOT NULL,
+                    link text NOT NULL, manual boolean NOT NULL
+                )"
+            ),
+        )
+        .await;
+    }
+    exec(
+        &txn,
+        "CREATE TABLE record (
+            id text PRIMARY KEY, director_id bigint, studio_id bigint,
+            label_id bigint, series_id bigint
+        )",
+    )
+    .await;
+    for (junction, fk) in [
+        ("record_genre", "genre_id"),
+        ("idol_participation", "idol_id"),
+    ] {
+        exec(
+            &txn,
+            &format!(
+                "CREATE TABLE {junction} (
+                    record_id text NOT NULL, {fk} bigint NOT NULL, manual boolean NOT NULL,
+                    UNIQUE (record_id, {fk})
+                )"
+            ),
+        )
+        .await;
+    }
+
+    exec(
+        &txn,
+        "INSERT INTO director VALUES (1, 'dup', '', false), (2, 'dup', '', false),
+            (3, 'dup', '', true)",
+    )
+    .await;
+    exec(
+        &txn,
+        "INSERT INTO record (id, director_id) VALUES ('r1', 2), ('r2', 1), ('r3', 3)",
+    )
+    .await;
+    exec(
+        &txn,
+        "INSERT INTO genre VALUES (1, 'dup', '', false), (2, 'dup', '', false)",
+    )
+    .await;
+    exec(
+        &txn,
+        "INSERT INTO record_genre VALUES ('r1', 2, true), ('r2', 1, false), ('r2', 2, true)",
+    )
+    .await;
+    exec(
+        &txn,
+        "INSERT INTO idol VALUES (5, 'dup', 'https://example.com/idol', false),
+            (4, 'dup', 'https://example.com/idol', false)",
+    )
+    .await;
+    exec(
+        &txn,
+        "INSERT INTO idol_participation VALUES ('r1', 5, false)",
+    )
+    .await;
+
+    let migrations = migration::Migrator::migrations();
+    let dedupe = migrations
+        .iter()
+        .find(|m| m.name() == DEDUPE_MIGRATION)
+        .expect("Dedupe migration is registered");
+    dedupe
+        .up(&SchemaManager::new(&txn))
+        .await
+        .expect("Failed to run the dedupe migration");
+
+    assert_eq!(
+        pairs(&txn, "SELECT id, director_id FROM record ORDER BY id").await,
+        [
+            ("r1".to_owned(), 1),
+            ("r2".to_owned(), 1),
+            ("r3".to_owned(), 3)
+        ],
+        "Records point at the lowest id; manual rows are a separate group"
+    );
+    assert_eq!(
+        pairs(&txn, "SELECT name, id FROM director ORDER BY id").await,
+        [("dup".to_owned(), 1), ("dup".to_owned(), 3)]
+    );
+    assert_eq!(
+        pairs(
+            &txn,
+            "SELECT record_id || ':' || manual, genre_id FROM record_genre ORDER BY record_id"
+        )
+        .await,
+        [("r1:true".to_owned(), 1), ("r2:false".to_owned(), 1)],
+        "Junction rows move to the survivor without duplicating existing ones"
+    );
+    assert_eq!(
+        pairs(&txn, "SELECT record_id, idol_id FROM idol_participation").await,
+        [("r1".to_owned(), 4)]
+    );
+    assert_eq!(
+        pairs(
+            &txn,
+            &format!(
+                "SELECT tablename::text, count(*) FROM pg_indexes
+                WHERE schemaname = '{schema}' AND indexname LIKE '%_name_link_manual_unique'
+                GROUP BY tablename ORDER BY tablename"
+            )
+        )
+        .await
+        .len(),
+        6,
+        "Every entity table gets the unique index"
+    );
+
+    txn.rollback().await.expect("Failed to roll back");
+}
+
+/// Test that two writers creating the same idol at once both get the one row
+/// that was inserted
+#[tokio::test]
+async fn test_concurrent_idol_creates_return_one_row() {
+    let db = setup_test_db().await.expect("Failed to setup test db");
+    let name = format!("concurrent-idol-{}", uuid::Uuid::new_v4());
+    let link = format!("https://example.com/{name}");
+    let dto = || CreateIdolDto {
+        name: name.clone(),
+        link: Some(link.clone()),
+        manual: Some(false),
+    };
+
+    let first = db.begin().await.expect("Failed to begin transaction");
+    let (first_id, first_created) = IdolRepo
+        .create(&first, dto())
+        .await
+        .expect("Failed to create idol");
+    assert!(first_created);
+
+    // The second insert waits on the first writer's uncommitted row
+    let second = db.begin().await.expect("Failed to begin transaction");
+    let second_dto = dto();
+    let racing = tokio::spawn(async move {
+        let created = IdolRepo.create(&second, second_dto).await;
+        second.commit().await.expect("Failed to commit");
+        created
+    });
+    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+    first.commit().await.expect("Failed to commit");
+
+    let (second_id, second_created) = racing
+        .await
+        .expect("Racing create panicked")
+        .expect("Failed to create idol");
+    assert_eq!(second_id, first_id);
+    assert!(!second_created);
+
+    let count = db
+        .query_one(Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            "SELECT count(*) AS count FROM idol WHERE name = $1 AND link = $2",
+            [name.clone().into(), link.clone().into()],
+        ))
+        .await
+        .expect("Failed to count idols")
+        .expect("Count row")
+        .try_get::<i64>("", "count")
+        .expect("Count column");
+    assert_eq!(count, 1);
+
+    db.execute(Statement::from_sql_and_values(
+        DatabaseBackend::Postgres,
+        "DELETE FROM idol WHERE id = $1",
+        [first_id.into()],
+    ))
+    .await
+    .expect("Failed to clean up idol");
+}
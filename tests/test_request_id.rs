@@ -0,0 +1,68 @@
+use axum::http::{Method, StatusCode};
+
+use lunirelust::common::{
+    dto::{ErrorResponse, RestApiResponse},
+    request_id::REQUEST_ID_HEADER,
+};
+
+mod test_helpers;
+use test_helpers::{deserialize_json_body, request_with_auth, request_with_auth_and_header};
+
+/// Test that a request without an ID gets a generated one in the header and body
+#[tokio::test]
+async fn test_request_id_generated() {
+    let response = request_with_auth(Method::GET, "/user").await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let (parts, body) = response.into_parts();
+    let header = parts
+        .headers
+        .get(REQUEST_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .expect("Response should carry a request ID")
+        .to_owned();
+    assert!(uuid::Uuid::parse_str(&header).is_ok());
+
+    let body: RestApiResponse<serde_json::Value> = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize response body");
+    assert_eq!(body.0.request_id.as_deref(), Some(header.as_str()));
+}
+
+/// Test that a client-supplied ID is echoed, including in error bodies
+#[tokio::test]
+async fn test_request_id_echoed_in_errors() {
+    let response = request_with_auth_and_header(
+        Method::GET,
+        "/cards/records/does-not-exist",
+        REQUEST_ID_HEADER,
+        "client-trace-42",
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let (parts, body) = response.into_parts();
+    assert_eq!(
+        parts.headers.get(REQUEST_ID_HEADER).map(|v| v.as_bytes()),
+        Some(b"client-trace-42".as_slice())
+    );
+    let body: ErrorResponse = deserialize_json_body(body)
+        .await
+        .expect("Failed to deserialize error body");
+    assert_eq!(body.request_id.as_deref(), Some("client-trace-42"));
+}
+
+/// Test that an unusable client-supplied ID is replaced
+#[tokio::test]
+async fn test_request_id_replaces_unusable_value() {
+    let response =
+        request_with_auth_and_header(Method::GET, "/user", REQUEST_ID_HEADER, "not acceptable")
+            .await;
+
+    let header = response
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .expect("Response should carry a request ID");
+    assert!(uuid::Uuid::parse_str(header).is_ok());
+}
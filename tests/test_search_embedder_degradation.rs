@@ -117,6 +117,7 @@ async fn test_search_keeps_keyword_only_mode_when_embedder_setup_fails() {
                 date_to: None,
                 limit: Some(20),
                 offset: Some(0),
+                group: None,
             },
             i32::MAX,
         )